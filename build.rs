@@ -35,4 +35,35 @@ fn main() {
             );
         }
     }
+
+    // embed_initramfs feature가 활성화되면 cpio 아카이브를 OUT_DIR로 복사
+    #[cfg(feature = "embed_initramfs")]
+    {
+        let out_dir = env::var("OUT_DIR").unwrap();
+        println!("cargo:rerun-if-env-changed=KERNERS_INITRAMFS");
+
+        let archive_src =
+            env::var("KERNERS_INITRAMFS").unwrap_or_else(|_| String::from("target/initramfs.cpio"));
+        let archive_dst = Path::new(&out_dir).join("initramfs.cpio");
+
+        if Path::new(&archive_src).exists() {
+            std::fs::copy(&archive_src, &archive_dst).expect("Failed to copy initramfs");
+            println!("cargo:rerun-if-changed={}", archive_src);
+            println!("cargo:warning=Embedded initramfs from {}", archive_src);
+        } else {
+            panic!(
+                "initramfs not found: {}. Create it with: (cd <dir> && find . | cpio -o -H newc) > {}",
+                archive_src, archive_src
+            );
+        }
+    }
+
+    // riscv_smode feature: OpenSBI가 점프하는 페이로드 주소(0x80200000)에 커널을 링크
+    #[cfg(feature = "riscv_smode")]
+    {
+        let target = env::var("TARGET").unwrap();
+        if target.contains("riscv64") {
+            println!("cargo:rustc-link-arg=--defsym=__kernel_load_addr=0x80200000");
+        }
+    }
 }
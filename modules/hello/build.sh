@@ -59,8 +59,9 @@ rustc --edition 2024 \
     src/lib.rs
 
 if [[ -f "$OUTPUT_DIR/${MODULE_NAME}.o" ]]; then
-    # .o 파일을 .ko로 복사 (relocatable object 그대로 사용)
+    # .o 파일을 .ko로 복사 (relocatable object 그대로 사용) 후 체크섬 트레일러 추가
     cp "$OUTPUT_DIR/${MODULE_NAME}.o" "$OUTPUT_DIR/${MODULE_NAME}.ko"
+    "$PROJECT_ROOT/scripts/sign_module.sh" "$OUTPUT_DIR/${MODULE_NAME}.ko"
     
     print_info "Module built: $OUTPUT_DIR/${MODULE_NAME}.ko"
     
@@ -1,9 +1,12 @@
-//! 메시지 큐 테스트 모듈
+//! IPC 테스트 모듈 (메시지 큐, 공유 메모리)
 //!
 //! 테스트 항목:
 //! 1. MQ 생성/열기
 //! 2. 메시지 전송/수신 (FIFO)
 //! 3. 빈 큐에서 수신 실패 확인
+//! 4. 공유 메모리 생성/크기 지정/매핑
+//! 5. 두 매핑이 같은 메모리를 보는지 확인
+//! 6. 매핑 중 크기 변경 실패, unlink 후 열기 실패 확인
 
 #![no_std]
 #![no_main]
@@ -15,6 +18,11 @@ unsafe extern "C" {
     fn kernel_mq_open(name: *const u8, name_len: usize, create: bool) -> i32;
     fn kernel_mq_send(name: *const u8, name_len: usize, data: *const u8, data_len: usize) -> i32;
     fn kernel_mq_receive(name: *const u8, name_len: usize, buf: *mut u8, buf_len: usize) -> i32;
+    fn kernel_shm_open(name: *const u8, name_len: usize, create: bool) -> i32;
+    fn kernel_shm_resize(name: *const u8, name_len: usize, size: usize) -> i32;
+    fn kernel_shm_map(name: *const u8, name_len: usize, offset: usize, len: usize) -> usize;
+    fn kernel_shm_unmap(addr: usize) -> i32;
+    fn kernel_shm_unlink(name: *const u8, name_len: usize) -> i32;
 }
 
 fn print(s: &str) {
@@ -77,6 +85,68 @@ pub extern "C" fn module_init() -> i32 {
     }
     print("PASS\n");
 
+    print("[test_ipc] === Shared Memory Tests ===\n");
+
+    let sname = b"test_shm";
+    let size = 8192;
+
+    // 테스트 5: 세그먼트 생성 + 크기 지정
+    print("[test_ipc] test: shm create/resize ... ");
+    let ret = unsafe { kernel_shm_open(sname.as_ptr(), sname.len(), true) };
+    if ret != 0 {
+        print("FAIL (open)\n");
+        return -6;
+    }
+    let ret = unsafe { kernel_shm_resize(sname.as_ptr(), sname.len(), size) };
+    if ret != 0 {
+        print("FAIL (resize)\n");
+        return -7;
+    }
+    print("PASS\n");
+
+    // 테스트 6: 두 번 매핑 → 한쪽에 쓴 값이 다른 쪽에 보임
+    print("[test_ipc] test: shm map shared ... ");
+    let a = unsafe { kernel_shm_map(sname.as_ptr(), sname.len(), 0, size) };
+    let b = unsafe { kernel_shm_map(sname.as_ptr(), sname.len(), 4096, 4096) };
+    if a == 0 || b == 0 {
+        print("FAIL (map)\n");
+        return -8;
+    }
+    unsafe {
+        core::ptr::write_volatile((a + 4096) as *mut u32, 0xC0FFEE);
+        if core::ptr::read_volatile(b as *const u32) != 0xC0FFEE {
+            print("FAIL (not shared)\n");
+            return -9;
+        }
+    }
+    print("PASS\n");
+
+    // 테스트 7: 매핑 중 크기 변경 → 실패
+    print("[test_ipc] test: shm resize while mapped ... ");
+    let ret = unsafe { kernel_shm_resize(sname.as_ptr(), sname.len(), size * 2) };
+    if ret != -1 {
+        print("FAIL (should return -1)\n");
+        return -10;
+    }
+    print("PASS\n");
+
+    // 테스트 8: unlink 후 매핑은 유지, 이름으로는 열 수 없음
+    print("[test_ipc] test: shm unlink ... ");
+    let ret = unsafe { kernel_shm_unlink(sname.as_ptr(), sname.len()) };
+    if ret != 0 || unsafe { kernel_shm_open(sname.as_ptr(), sname.len(), false) } != -1 {
+        print("FAIL\n");
+        return -11;
+    }
+    if unsafe { core::ptr::read_volatile(b as *const u32) } != 0xC0FFEE {
+        print("FAIL (mapping lost)\n");
+        return -12;
+    }
+    if unsafe { kernel_shm_unmap(a) } != 0 || unsafe { kernel_shm_unmap(b) } != 0 {
+        print("FAIL (unmap)\n");
+        return -13;
+    }
+    print("PASS\n");
+
     print("[test_ipc] All tests passed\n");
     0
 }
@@ -0,0 +1,129 @@
+//! 타이머 테스트 모듈
+//!
+//! 테스트 항목:
+//! 1. 일회성 타이머가 지연 뒤 한 번 실행 (kernel_timer_create)
+//! 2. 주기 타이머 반복 실행
+//! 3. 취소 후 더 이상 실행되지 않음 (kernel_timer_cancel)
+//! 4. 이미 만료된 일회성 타이머 취소는 실패
+
+#![no_std]
+#![no_main]
+
+use core::panic::PanicInfo;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+unsafe extern "C" {
+    fn kernel_print(s: *const u8, len: usize);
+    fn kernel_sleep_ticks(ticks: u32);
+    fn kernel_timer_create(
+        delay_ms: u64,
+        period_ms: u64,
+        callback: extern "C" fn(usize),
+        arg: usize,
+        name: *const u8,
+        name_len: usize,
+    ) -> i32;
+    fn kernel_timer_cancel(id: i32) -> i32;
+}
+
+fn print(s: &str) {
+    unsafe { kernel_print(s.as_ptr(), s.len()); }
+}
+
+/// 일회성 타이머 콜백이 더한 값
+static ONESHOT_SUM: AtomicU32 = AtomicU32::new(0);
+/// 주기 타이머 콜백 호출 수
+static PERIODIC_COUNT: AtomicU32 = AtomicU32::new(0);
+
+/// 타이머 콜백 (타이머 인터럽트에서 실행되므로 원자 변수만 갱신)
+extern "C" fn oneshot_cb(arg: usize) {
+    ONESHOT_SUM.fetch_add(arg as u32, Ordering::SeqCst);
+}
+
+extern "C" fn periodic_cb(_arg: usize) {
+    PERIODIC_COUNT.fetch_add(1, Ordering::SeqCst);
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn module_init() -> i32 {
+    print("[test_timer] === Timer Tests ===\n");
+
+    // 테스트 1: 일회성 타이머
+    print("[test_timer] test: one-shot ... ");
+    ONESHOT_SUM.store(0, Ordering::SeqCst);
+    let name = b"test_oneshot";
+    let once = unsafe { kernel_timer_create(20, 0, oneshot_cb, 5, name.as_ptr(), name.len()) };
+    if once <= 0 {
+        print("FAIL (create returned <= 0)\n");
+        return -1;
+    }
+    unsafe { kernel_sleep_ticks(6); }
+    if ONESHOT_SUM.load(Ordering::SeqCst) != 5 {
+        print("FAIL (callback not run once with arg)\n");
+        return -2;
+    }
+    print("PASS\n");
+
+    // 테스트 2: 주기 타이머
+    print("[test_timer] test: periodic ... ");
+    PERIODIC_COUNT.store(0, Ordering::SeqCst);
+    let name = b"test_periodic";
+    let tick = unsafe { kernel_timer_create(10, 10, periodic_cb, 0, name.as_ptr(), name.len()) };
+    if tick <= 0 {
+        print("FAIL (create returned <= 0)\n");
+        return -3;
+    }
+    unsafe { kernel_sleep_ticks(10); }
+    if PERIODIC_COUNT.load(Ordering::SeqCst) < 3 {
+        unsafe { kernel_timer_cancel(tick); }
+        print("FAIL (fewer than 3 calls)\n");
+        return -4;
+    }
+    print("PASS\n");
+
+    // 테스트 3: 취소
+    print("[test_timer] test: cancel ... ");
+    if unsafe { kernel_timer_cancel(tick) } != 0 {
+        print("FAIL (cancel returned != 0)\n");
+        return -5;
+    }
+    let after = PERIODIC_COUNT.load(Ordering::SeqCst);
+    unsafe { kernel_sleep_ticks(5); }
+    if PERIODIC_COUNT.load(Ordering::SeqCst) != after {
+        print("FAIL (callback ran after cancel)\n");
+        return -6;
+    }
+    print("PASS\n");
+
+    // 테스트 4: 만료된 일회성 타이머 취소
+    print("[test_timer] test: cancel expired ... ");
+    if unsafe { kernel_timer_cancel(once) } != -1 {
+        print("FAIL (expected -1)\n");
+        return -7;
+    }
+    print("PASS\n");
+
+    print("[test_timer] All tests passed\n");
+    0
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn module_exit() {
+    print("[test_timer] Module unloaded\n");
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn module_name() -> *const u8 {
+    b"test_timer\0".as_ptr()
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn module_version() -> *const u8 {
+    b"0.1.0\0".as_ptr()
+}
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    print("[test_timer] PANIC!\n");
+    loop {}
+}
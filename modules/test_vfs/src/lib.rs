@@ -5,6 +5,7 @@
 //! 2. 파일 생성
 //! 3. 파일 쓰기/읽기 정합성
 //! 4. 파일 삭제
+//! 5. FAT32 디렉토리 클러스터 확장 (/mnt에 FAT32가 마운트된 경우)
 
 #![no_std]
 #![no_main]
@@ -104,10 +105,78 @@ pub extern "C" fn module_init() -> i32 {
     }
     print("PASS\n");
 
+    // 테스트 6: FAT32 디렉토리 확장
+    print("[test_vfs] test: fat32 dir growth ... ");
+    match test_fat32_dir_growth() {
+        Ok(true) => print("PASS\n"),
+        Ok(false) => print("SKIP (no FAT32 at /mnt)\n"),
+        Err(code) => {
+            print("FAIL\n");
+            return code;
+        }
+    }
+
     print("[test_vfs] All tests passed\n");
     0
 }
 
+/// 확장 테스트에서 만들 파일 수 (LFN 포함 엔트리 2개씩, 512B 클러스터 5개 이상)
+const GROW_FILES: usize = 40;
+
+/// "/mnt/grow/growth_NN.txt" 경로
+fn grow_path(buf: &mut [u8; 32], n: usize) -> &[u8] {
+    let prefix = b"/mnt/grow/growth_";
+    let len = prefix.len();
+    buf[..len].copy_from_slice(prefix);
+    buf[len] = b'0' + (n / 10) as u8;
+    buf[len + 1] = b'0' + (n % 10) as u8;
+    buf[len + 2..len + 6].copy_from_slice(b".txt");
+    &buf[..len + 6]
+}
+
+/// 한 클러스터에 다 들어가지 않는 수의 파일을 만들어 디렉토리 체인 확장 확인
+///
+/// FAT32가 마운트되지 않았으면 Ok(false)
+fn test_fat32_dir_growth() -> Result<bool, i32> {
+    let dir = b"/mnt/grow";
+    let mut buf = [0u8; 32];
+    if unsafe { kernel_vfs_mkdir(dir.as_ptr(), dir.len()) } != 0 {
+        let first = grow_path(&mut buf, 0);
+        if unsafe { kernel_vfs_create_file(first.as_ptr(), first.len()) } != 0 {
+            return Ok(false);
+        }
+    }
+
+    for n in 0..GROW_FILES {
+        let path = grow_path(&mut buf, n);
+        let mut probe = [0u8; 1];
+        let exists = unsafe { kernel_vfs_read(path.as_ptr(), path.len(), 0, probe.as_mut_ptr(), 1) } >= 0;
+        if !exists && unsafe { kernel_vfs_create_file(path.as_ptr(), path.len()) } != 0 {
+            return Err(-8);
+        }
+    }
+
+    // 마지막 파일은 새로 붙은 클러스터의 엔트리
+    let data = b"grown";
+    let path = grow_path(&mut buf, GROW_FILES - 1);
+    if unsafe { kernel_vfs_write(path.as_ptr(), path.len(), 0, data.as_ptr(), data.len()) } != data.len() as i32 {
+        return Err(-9);
+    }
+    let mut out = [0u8; 8];
+    let read = unsafe { kernel_vfs_read(path.as_ptr(), path.len(), 0, out.as_mut_ptr(), out.len()) };
+    if read != data.len() as i32 || &out[..data.len()] != data {
+        return Err(-10);
+    }
+
+    for n in 0..GROW_FILES {
+        let path = grow_path(&mut buf, n);
+        if unsafe { kernel_vfs_unlink(path.as_ptr(), path.len()) } != 0 {
+            return Err(-11);
+        }
+    }
+    Ok(true)
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn module_exit() {
     print("[test_vfs] Module unloaded\n");
@@ -39,12 +39,19 @@ usage() {
     echo "  MEMORY_MB   Memory size in MB (default: 512)"
     echo "  SMP         Number of CPUs/Harts (default: 1)"
     echo ""
+    echo "Environment:"
+    echo "  KERNERS_APPEND  Kernel command line (QEMU -append, e.g. \"loglevel=debug root=vda\")"
+    echo "  KERNERS_GIC     aarch64 GIC version: 2 (default) or 3 (-machine virt,gic-version=3)"
+    echo "  KERNERS_SMODE   riscv64: 1 = S-mode kernel under OpenSBI (--features riscv_smode, -bios default)"
+    echo "  KERNERS_VIRTIO_MODERN  1 = modern (v2) virtio-mmio devices (-global virtio-mmio.force-legacy=false)"
+    echo ""
     echo "Examples:"
     echo "  $0                      # Run aarch64 with 512MB, 1 CPU"
     echo "  $0 aarch64 256          # Run aarch64 with 256MB, 1 CPU"
     echo "  $0 aarch64 512 4        # Run aarch64 with 512MB, 4 CPUs"
     echo "  $0 riscv64              # Run riscv64 with 512MB, 1 Hart"
     echo "  $0 riscv64 1024 4       # Run riscv64 with 1GB, 4 Harts"
+    echo "  KERNERS_SMODE=1 $0 riscv64   # Run riscv64 in S-mode under OpenSBI"
     exit 1
 }
 
@@ -53,6 +60,9 @@ if [[ "$1" == "-h" || "$1" == "--help" ]]; then
     usage
 fi
 
+# 추가 cargo feature
+CARGO_FEATURES=()
+
 # 아키텍처별 설정
 case "$ARCH" in
     aarch64)
@@ -60,6 +70,9 @@ case "$ARCH" in
         QEMU="qemu-system-aarch64"
         QEMU_MACHINE="virt"
         QEMU_CPU="-cpu cortex-a57"
+        if [[ "${KERNERS_GIC:-2}" == "3" ]]; then
+            QEMU_MACHINE="virt,gic-version=3"
+        fi
         ;;
     riscv64)
         TARGET="riscv64gc-unknown-none-elf"
@@ -67,6 +80,13 @@ case "$ARCH" in
         QEMU_MACHINE="virt"
         QEMU_CPU=""
         EXTRA_OPTS="-bios none"
+        MODE="M-mode"
+        if [[ "${KERNERS_SMODE:-0}" == "1" ]]; then
+            # OpenSBI가 M-mode에서 0x80200000의 커널로 점프
+            CARGO_FEATURES=(--features riscv_smode)
+            EXTRA_OPTS="-bios default"
+            MODE="S-mode (OpenSBI)"
+        fi
         ;;
     *)
         print_error "Unknown architecture: $ARCH"
@@ -80,7 +100,7 @@ KERNEL_BIN="target/${TARGET}/release/kerners.bin"
 # 커널 빌드
 build_kernel() {
     print_info "Building kernel for $ARCH..."
-    cargo build --release --target "$TARGET"
+    cargo build --release --target "$TARGET" "${CARGO_FEATURES[@]}"
 
     # ELF를 raw binary로 변환 (Linux 부트 헤더가 인식되도록)
     if [[ "$ARCH" == "aarch64" ]]; then
@@ -118,7 +138,7 @@ build_modules() {
 # 커널 빌드 (모듈 임베드 포함)
 build_kernel_with_modules() {
     print_info "Building kernel with embedded test module for $ARCH..."
-    cargo build --release --target "$TARGET" --features embed_test_module
+    cargo build --release --target "$TARGET" --features embed_test_module "${CARGO_FEATURES[@]}"
 
     # ELF를 raw binary로 변환 (Linux 부트 헤더가 인식되도록)
     if [[ "$ARCH" == "aarch64" ]]; then
@@ -166,12 +186,30 @@ run_qemu() {
     print_info "  CPUs/Harts: $SMP"
     print_info "  Kernel: $KERNEL"
     print_info "  DTB: auto-generated by QEMU (passed via register)"
+    print_info "  Machine: $QEMU_MACHINE"
+    if [[ -n "${MODE:-}" ]]; then
+        print_info "  Mode: $MODE"
+    fi
 
     # VirtIO 블록 디바이스 옵션
     VIRTIO_BLK=""
     if [[ -f "disk.img" ]]; then
         print_info "  Disk: disk.img (VirtIO)"
-        VIRTIO_BLK="-drive file=disk.img,format=raw,if=none,id=hd0 -device virtio-blk-device,drive=hd0"
+        VIRTIO_BLK="-drive file=disk.img,format=raw,if=none,id=hd0,discard=unmap -device virtio-blk-device,drive=hd0"
+    fi
+
+    # virtio-mmio는 QEMU 기본이 legacy (v1)
+    VIRTIO_MODE=""
+    if [[ "${KERNERS_VIRTIO_MODERN:-0}" == "1" ]]; then
+        print_info "  VirtIO: modern (v2)"
+        VIRTIO_MODE="-global virtio-mmio.force-legacy=false"
+    fi
+
+    # 커널 명령줄 (DTB /chosen bootargs)
+    APPEND_OPTS=()
+    if [[ -n "${KERNERS_APPEND:-}" ]]; then
+        print_info "  Cmdline: $KERNERS_APPEND"
+        APPEND_OPTS=(-append "$KERNERS_APPEND")
     fi
 
     echo ""
@@ -185,7 +223,9 @@ run_qemu() {
         -m ${MEMORY}M \
         -nographic \
         ${EXTRA_OPTS:-} \
+        $VIRTIO_MODE \
         $VIRTIO_BLK \
+        "${APPEND_OPTS[@]}" \
         -kernel "$KERNEL"
 }
 
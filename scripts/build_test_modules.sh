@@ -55,6 +55,7 @@ for module_dir in "$PROJECT_ROOT"/modules/test_*/; do
         src/lib.rs 2>&1; then
 
         cp "$OUTPUT_DIR/${module_name}.o" "$OUTPUT_DIR/${module_name}.ko"
+        "$SCRIPT_DIR/sign_module.sh" "$OUTPUT_DIR/${module_name}.ko"
         print_info "  → $OUTPUT_DIR/${module_name}.ko"
         BUILT=$((BUILT + 1))
     else
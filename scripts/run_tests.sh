@@ -1,9 +1,10 @@
 #!/bin/bash
 # 전체 테스트 오케스트레이션 스크립트
 #
-# Usage: ./scripts/run_tests.sh [ARCH] [TIMEOUT]
+# Usage: ./scripts/run_tests.sh [ARCH] [TIMEOUT] [FILTER...]
 #   ARCH:    aarch64 (default) or riscv64
 #   TIMEOUT: 초 단위 (default: 30)
+#   FILTER:  실행할 테스트 패턴, 커널 명령줄 `-t <패턴>`으로 전달 (예: 'kernel.*' test_mm)
 #
 # 과정:
 # 1. 테스트 모듈 빌드
@@ -18,6 +19,17 @@ PROJECT_ROOT="$(cd "$SCRIPT_DIR/.." && pwd)"
 
 ARCH="${1:-aarch64}"
 TIMEOUT="${2:-30}"
+shift $(( $# < 2 ? $# : 2 ))
+
+# 테스트 필터 → 커널 명령줄
+APPEND=""
+for pattern in "$@"; do
+    APPEND="$APPEND -t $pattern"
+done
+APPEND_OPTS=()
+if [ -n "$APPEND" ]; then
+    APPEND_OPTS=(-append "${APPEND# }")
+fi
 
 RED='\033[0;31m'
 GREEN='\033[0;32m'
@@ -104,11 +116,11 @@ else
 fi
 
 if [ -n "$TIMEOUT_CMD" ]; then
-    OUTPUT=$($TIMEOUT_CMD "$TIMEOUT" $QEMU $QEMU_ARGS -m 512M -nographic $VIRTIO_BLK -kernel "$KERNEL" 2>&1)
+    OUTPUT=$($TIMEOUT_CMD "$TIMEOUT" $QEMU $QEMU_ARGS -m 512M -nographic $VIRTIO_BLK -kernel "$KERNEL" "${APPEND_OPTS[@]}" 2>&1)
     QEMU_EXIT=$?
 else
     # timeout 명령 없으면 background + wait
-    $QEMU $QEMU_ARGS -m 512M -nographic $VIRTIO_BLK -kernel "$KERNEL" > /tmp/kerners_test_output.txt 2>&1 &
+    $QEMU $QEMU_ARGS -m 512M -nographic $VIRTIO_BLK -kernel "$KERNEL" "${APPEND_OPTS[@]}" > /tmp/kerners_test_output.txt 2>&1 &
     QEMU_PID=$!
     sleep "$TIMEOUT"
     if kill -0 "$QEMU_PID" 2>/dev/null; then
@@ -124,6 +136,14 @@ echo "$OUTPUT"
 echo ""
 
 # ---- Step 5: 결과 파싱 ----
+# 실패/시간 초과한 테스트 (TEST_RESULT name=<이름> status=<상태> ...)
+FAILED_TESTS=$(echo "$OUTPUT" | grep -E '^TEST_RESULT .*status=(FAIL|TIMEOUT)' || true)
+if [ -n "$FAILED_TESTS" ]; then
+    print_error "Failed tests:"
+    echo "$FAILED_TESTS" | sed 's/^TEST_RESULT /  /'
+fi
+echo "$OUTPUT" | grep '^TEST_SUMMARY' || true
+
 if echo "$OUTPUT" | grep -q "TEST_STATUS: PASS"; then
     print_pass
     exit 0
@@ -0,0 +1,52 @@
+#!/bin/bash
+# 모듈(.ko) 끝에 체크섬 트레일러를 붙이는 스크립트
+#
+# Usage: ./scripts/sign_module.sh <module.ko> [sha256|crc32]
+#
+# 트레일러 (48 바이트, src/module/verify.rs):
+#   digest [32]  SHA-256, CRC32면 앞 4바이트(LE)에 값, 나머지 0
+#   algo   u32   1 = CRC32, 2 = SHA-256 (LE)
+#   magic  [12]  "~KRNMODSUM~\n"
+# 이미 트레일러가 있으면 떼고 다시 붙입니다.
+
+set -e
+
+FILE="$1"
+ALGO="${2:-sha256}"
+MAGIC='~KRNMODSUM~'
+TRAILER_SIZE=48
+
+if [[ -z "$FILE" || ! -f "$FILE" ]]; then
+    echo "Usage: $0 <module.ko> [sha256|crc32]" >&2
+    exit 1
+fi
+
+# 기존 트레일러 제거
+if [[ "$(tail -c 12 "$FILE" | head -c 11)" == "$MAGIC" ]]; then
+    size=$(stat -c %s "$FILE")
+    truncate -s $((size - TRAILER_SIZE)) "$FILE"
+fi
+
+# 16진 문자열을 바이트로
+hex_bytes() {
+    printf "$(echo -n "$1" | sed 's/../\\x&/g')"
+}
+
+case "$ALGO" in
+    sha256)
+        digest=$(sha256sum "$FILE" | cut -d' ' -f1)
+        { hex_bytes "$digest"; printf '\x02\x00\x00\x00'; } > "$FILE.trailer"
+        ;;
+    crc32)
+        # gzip 트레일러의 앞 4바이트가 CRC32 (LE)
+        gzip -c "$FILE" | tail -c 8 | head -c 4 > "$FILE.trailer"
+        { head -c 28 /dev/zero; printf '\x01\x00\x00\x00'; } >> "$FILE.trailer"
+        ;;
+    *)
+        echo "Unknown checksum: $ALGO (use sha256 or crc32)" >&2
+        exit 1
+        ;;
+esac
+printf '%s\n' "$MAGIC" >> "$FILE.trailer"
+cat "$FILE.trailer" >> "$FILE"
+rm -f "$FILE.trailer"
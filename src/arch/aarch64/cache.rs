@@ -0,0 +1,52 @@
+//! aarch64 데이터 캐시 유지보수
+//!
+//! 캐시 일관성이 없는 DMA 버퍼(`mm::dma`)와 MMU를 끈 채 메모리를 읽는 kexec 트램펄린이
+//! 씁니다. 범위는 PoC(Point of Coherency)까지 처리하며 끝에 `dsb sy`로 완료를 기다립니다.
+
+use core::arch::asm;
+
+/// 가장 작은 D-cache 라인 크기 (바이트)
+pub fn dcache_line_size() -> usize {
+    let ctr: u64;
+    unsafe {
+        asm!("mrs {}, CTR_EL0", out(reg) ctr);
+    }
+    // DminLine: log2 워드 수
+    4 << ((ctr >> 16) & 0xF)
+}
+
+/// `[start, start + len)`의 D-cache 라인을 PoC까지 clean (dirty 라인을 메모리에 기록)
+///
+/// CPU가 쓴 데이터를 디바이스가 읽기 전에 부릅니다.
+pub fn clean_range(start: usize, len: usize) {
+    let line = dcache_line_size();
+    let mut addr = start & !(line - 1);
+    while addr < start + len {
+        unsafe {
+            asm!("dc cvac, {}", in(reg) addr);
+        }
+        addr += line;
+    }
+    unsafe {
+        asm!("dsb sy");
+    }
+}
+
+/// `[start, start + len)`의 D-cache 라인을 PoC까지 clean & invalidate
+///
+/// 디바이스가 쓴 데이터를 CPU가 읽기 전, 또는 캐시를 거치지 않고 메모리를 읽을 코드에
+/// 넘기기 전에 부릅니다. 범위 양 끝 라인을 다른 데이터와 나눠 쓸 수 있으므로 invalidate만
+/// 하지 않고 항상 clean을 함께 합니다.
+pub fn flush_range(start: usize, len: usize) {
+    let line = dcache_line_size();
+    let mut addr = start & !(line - 1);
+    while addr < start + len {
+        unsafe {
+            asm!("dc civac, {}", in(reg) addr);
+        }
+        addr += line;
+    }
+    unsafe {
+        asm!("dsb sy");
+    }
+}
@@ -0,0 +1,94 @@
+//! CPU 식별 및 기능 (ID 레지스터)
+//!
+//! MIDR_EL1로 코어 종류를, ID_AA64PFR0_EL1 / ID_AA64ISAR0_EL1로 지원 기능을
+//! 읽어 부팅 하드웨어 요약(`hwinfo`)에 사용합니다.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+macro_rules! read_sysreg {
+    ($reg:literal) => {{
+        let value: u64;
+        unsafe {
+            core::arch::asm!(concat!("mrs {}, ", $reg), out(reg) value, options(nomem, nostack));
+        }
+        value
+    }};
+}
+
+/// 4비트 ID 필드 추출
+fn field(reg: u64, shift: u32) -> u64 {
+    (reg >> shift) & 0xF
+}
+
+/// 코어 이름 (MIDR_EL1)
+///
+/// 알려진 Arm 코어는 이름으로, 그 외는 implementer/part 번호로 표시합니다.
+pub fn model() -> String {
+    let midr = read_sysreg!("midr_el1");
+    let implementer = (midr >> 24) & 0xFF;
+    let variant = (midr >> 20) & 0xF;
+    let part = (midr >> 4) & 0xFFF;
+    let revision = midr & 0xF;
+
+    let name = match (implementer, part) {
+        (0x41, 0xd03) => Some("Cortex-A53"),
+        (0x41, 0xd07) => Some("Cortex-A57"),
+        (0x41, 0xd08) => Some("Cortex-A72"),
+        (0x41, 0xd0b) => Some("Cortex-A76"),
+        (0x41, 0xd0c) => Some("Neoverse-N1"),
+        (0x41, 0xd40) => Some("Neoverse-V1"),
+        (0x41, 0xd49) => Some("Neoverse-N2"),
+        _ => None,
+    };
+
+    match name {
+        Some(name) => format!("{} r{}p{}", name, variant, revision),
+        None => format!("impl {:#x} part {:#x} r{}p{}", implementer, part, variant, revision),
+    }
+}
+
+/// 지원 기능 목록 (Linux /proc/cpuinfo features 이름)
+pub fn features() -> Vec<&'static str> {
+    let pfr0 = read_sysreg!("id_aa64pfr0_el1");
+    let isar0 = read_sysreg!("id_aa64isar0_el1");
+    let mut features = Vec::new();
+
+    // FP/AdvSIMD: 0xF = 미구현
+    if field(pfr0, 16) != 0xF {
+        features.push("fp");
+    }
+    if field(pfr0, 20) != 0xF {
+        features.push("asimd");
+    }
+    if field(pfr0, 32) != 0 {
+        features.push("sve");
+    }
+    if field(pfr0, 24) != 0 {
+        features.push("gic-sysreg");
+    }
+
+    match field(isar0, 4) {
+        0 => {}
+        1 => features.push("aes"),
+        _ => features.extend_from_slice(&["aes", "pmull"]),
+    }
+    if field(isar0, 8) != 0 {
+        features.push("sha1");
+    }
+    if field(isar0, 12) != 0 {
+        features.push("sha2");
+    }
+    if field(isar0, 16) != 0 {
+        features.push("crc32");
+    }
+    if field(isar0, 20) >= 2 {
+        features.push("atomics");
+    }
+    if field(isar0, 28) != 0 {
+        features.push("asimdrdm");
+    }
+
+    features
+}
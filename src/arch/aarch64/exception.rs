@@ -72,6 +72,63 @@ fn print_exception_context(ctx: &ExceptionContext) {
 
 /// Exception Class 코드
 const EC_SVC_AARCH64: u64 = 0b010101;  // SVC from AArch64 (syscall)
+const EC_IABT_LOWER: u64 = 0b100000;   // Instruction Abort (lower EL)
+const EC_IABT_SAME: u64 = 0b100001;    // Instruction Abort (same EL)
+const EC_DABT_LOWER: u64 = 0b100100;   // Data Abort (lower EL)
+const EC_DABT_SAME: u64 = 0b100101;    // Data Abort (same EL)
+const EC_SOFTSTEP_SAME: u64 = 0b110011; // Software Step (same EL)
+const EC_BRK_AARCH64: u64 = 0b111100;  // BRK from AArch64
+
+/// Fault Status Code (ISS[5:0]) 상위 4비트 - 하위 2비트는 레벨
+const FSC_TRANSLATION: u64 = 0b000100; // Translation fault
+const FSC_PERMISSION: u64 = 0b001100;  // Permission fault
+
+/// ISS WnR 비트 (Data Abort: 1 = 쓰기)
+const ISS_WNR: u64 = 1 << 6;
+
+/// SPSR I 비트 (예외 전에 IRQ가 꺼져 있었음)
+const SPSR_I: u64 = 1 << 7;
+
+/// Instruction/Data Abort 처리 (demand paging)
+///
+/// Translation fault는 mm::vma에 위임하고, 처리할 수 없는 접근은
+/// 커널 패닉 대신 해당 스레드를 종료합니다. 예외 프레임 안에서 끝내지 않고 SIGSEGV를 건 뒤,
+/// 커널 모드면 복귀 주소를 `vma::fault_exit`로 바꾸고 EL0면 EL0로 돌아가는 대신 바로
+/// `vma::fault_exit`를 부릅니다 (SIGSEGV를 무시해도 폴트를 되풀이하지 않음).
+///
+/// # Returns
+/// 처리했으면 true, mm에서 다룰 수 없는 폴트 종류이면 false
+fn handle_abort(ctx: &mut ExceptionContext, ec: u64, from_el0: bool) -> bool {
+    use crate::mm::vma::{self, FaultAccess, FaultError};
+
+    let fsc = ctx.esr & 0x3C;
+    let access = if ec == EC_IABT_LOWER || ec == EC_IABT_SAME {
+        FaultAccess::Execute
+    } else if ctx.esr & ISS_WNR != 0 {
+        FaultAccess::Write
+    } else {
+        FaultAccess::Read
+    };
+    let addr = ctx.far as usize;
+
+    let result = match fsc {
+        FSC_TRANSLATION => vma::handle_page_fault(addr, access),
+        FSC_PERMISSION => Err(FaultError::AccessViolation),
+        _ => return false,
+    };
+
+    if let Err(err) = result {
+        print_exception_context(ctx);
+        let irqs_enabled = ctx.spsr & SPSR_I == 0;
+        vma::kill_faulting_thread(addr, ctx.elr as usize, access, err, irqs_enabled);
+        if from_el0 {
+            vma::fault_exit();
+        } else {
+            ctx.elr = vma::fault_exit as *const () as usize as u64;
+        }
+    }
+    true
+}
 
 /// 기본 예외 핸들러
 #[unsafe(no_mangle)]
@@ -81,6 +138,12 @@ pub extern "C" fn exception_handler(ctx: &mut ExceptionContext, exception_type:
     // IRQ 처리 (exception_type % 4 == 1)
     if exception_type % 4 == 1 {
         super::gic::handle_irq();
+        // EOI 뒤 하반부 (인터럽트를 켜고 태스크릿 실행)
+        crate::softirq::run_pending();
+        // EL0로 복귀하는 경우에만 시그널 전달 (커널 코드는 락을 잡고 있을 수 있음)
+        if exception_type >= 8 {
+            crate::proc::signal::deliver_pending();
+        }
         return;
     }
 
@@ -100,6 +163,35 @@ pub extern "C" fn exception_handler(ctx: &mut ExceptionContext, exception_type:
         let ret = crate::syscall::syscall_handler(syscall_num, args);
         ctx.gpr[0] = ret as u64;  // 반환값을 x0에 저장
         // elr은 이미 svc 다음 명령어를 가리킴 (자동)
+        crate::proc::signal::deliver_pending();
+        return;
+    }
+
+    // 커널 모드 BRK/Software Step: kprobe, GDB 스텁
+    if exception_type == 0 || exception_type == 4 {
+        if ec == EC_BRK_AARCH64
+            && ctx.esr & 0xFFFF == super::kprobe::KPROBE_BRK_IMM
+            && crate::trace::kprobe::on_breakpoint(ctx)
+        {
+            return;
+        }
+        if ec == EC_BRK_AARCH64
+            && matches!(ctx.esr & 0xFFFF, super::gdb::GDB_BRK_IMM | super::gdb::GDB_COMPILED_BRK_IMM)
+            && crate::debug::gdbstub::handle_trap(ctx)
+        {
+            return;
+        }
+        if ec == EC_SOFTSTEP_SAME && (crate::debug::gdbstub::handle_step(ctx) || super::kprobe::handle_step(ctx)) {
+            return;
+        }
+    }
+
+    // Synchronous 예외 중 Abort: 페이지 폴트 처리
+    if exception_type % 4 == 0
+        && exception_type < 12
+        && matches!(ec, EC_IABT_LOWER | EC_IABT_SAME | EC_DABT_LOWER | EC_DABT_SAME)
+        && handle_abort(ctx, ec, exception_type >= 8)
+    {
         return;
     }
 
@@ -126,6 +218,8 @@ pub extern "C" fn exception_handler(ctx: &mut ExceptionContext, exception_type:
 
     kprintln!("\n[EXCEPTION] {}", type_str);
     print_exception_context(ctx);
+    // 백트레이스를 예외가 난 곳에서 시작 (x29 = fp)
+    crate::panic::set_exception_frame(ctx.elr as usize, ctx.gpr[29] as usize);
     panic!("Unhandled exception");
 }
 
@@ -0,0 +1,242 @@
+//! aarch64 GDB 스텁 지원 (BRK + 하드웨어 단일 스텝)
+//!
+//! 브레이크포인트는 kprobe와 다른 즉치값의 BRK로 심고(ESR_EL1.ISS로 구분), 단일 스텝은
+//! kprobe와 같은 MDSCR_EL1.SS + SPSR.SS 하드웨어 스텝을 씁니다. 레지스터 번호는 GDB
+//! `org.gnu.gdb.aarch64.core` 순서(x0-x30, sp, pc, cpsr)입니다.
+
+use alloc::string::String;
+use core::arch::asm;
+use core::fmt::Write;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use super::exception::ExceptionContext;
+use crate::proc::percpu::{self, MAX_CPUS};
+
+/// 정지 시 레지스터 컨텍스트
+pub type Regs = ExceptionContext;
+
+/// GDB가 심는 브레이크포인트의 BRK 즉치값 (Linux KGDB와 같은 값)
+pub const GDB_BRK_IMM: u64 = 0x400;
+/// `breakpoint()`가 실행하는 BRK 즉치값
+pub const GDB_COMPILED_BRK_IMM: u64 = 0x401;
+
+/// `brk #GDB_BRK_IMM`
+const BRK_INSN: u32 = 0xD420_0000 | ((GDB_BRK_IMM as u32) << 5);
+
+/// ESR_EL1 EC: BRK (AArch64), Software Step (같은 EL)
+const EC_BRK: u64 = 0b111100;
+const EC_SOFTSTEP: u64 = 0b110011;
+
+/// GDB 레지스터 수와 번호
+pub const NUM_REGS: usize = 34;
+const REG_SP: usize = 31;
+const REG_PC: usize = 32;
+const REG_CPSR: usize = 33;
+
+/// 예외 벡터가 스택에 만드는 프레임 크기 (`sub sp, sp, #288`)
+const FRAME_SIZE: u64 = 288;
+
+/// MDSCR_EL1 비트
+const MDSCR_SS: u64 = 1 << 0;
+const MDSCR_KDE: u64 = 1 << 13;
+
+/// SPSR 비트
+const SPSR_SS: u64 = 1 << 21;
+const SPSR_D: u64 = 1 << 9;
+const SPSR_I: u64 = 1 << 7;
+
+/// CPU별 GDB 단일 스텝 중인지
+static STEPPING: [AtomicBool; MAX_CPUS] = [const { AtomicBool::new(false) }; MAX_CPUS];
+/// 스텝 전 SPSR의 D/I 비트
+static STEP_SPSR: [AtomicU64; MAX_CPUS] = [const { AtomicU64::new(0) }; MAX_CPUS];
+
+pub fn zeroed_regs() -> Regs {
+    ExceptionContext {
+        gpr: [0; 31],
+        elr: 0,
+        spsr: 0,
+        esr: 0,
+        far: 0,
+    }
+}
+
+/// 예외 진입 직전의 sp (컨텍스트는 커널 스택에 저장됨)
+pub fn frame_sp(regs: &Regs) -> u64 {
+    regs as *const Regs as u64 + FRAME_SIZE
+}
+
+pub fn pc(regs: &Regs) -> usize {
+    regs.elr as usize
+}
+
+pub fn set_pc(regs: &mut Regs, pc: usize) {
+    regs.elr = pc as u64;
+}
+
+/// 레지스터 크기 (바이트)
+pub fn reg_size(n: usize) -> Option<usize> {
+    match n {
+        0..REG_CPSR => Some(8),
+        REG_CPSR => Some(4),
+        _ => None,
+    }
+}
+
+pub fn read_reg(regs: &Regs, sp: u64, n: usize) -> Option<u64> {
+    match n {
+        0..REG_SP => Some(regs.gpr[n]),
+        REG_SP => Some(sp),
+        REG_PC => Some(regs.elr),
+        REG_CPSR => Some(regs.spsr & 0xFFFF_FFFF),
+        _ => None,
+    }
+}
+
+/// 레지스터 쓰기 (sp는 예외 프레임 밖이라 바꿀 수 없음)
+pub fn write_reg(regs: &mut Regs, n: usize, value: u64) -> bool {
+    match n {
+        0..REG_SP => regs.gpr[n] = value,
+        REG_PC => regs.elr = value,
+        REG_CPSR => regs.spsr = (regs.spsr & !0xFFFF_FFFF) | (value & 0xFFFF_FFFF),
+        REG_SP => return false,
+        _ => return false,
+    }
+    true
+}
+
+/// `qXfer:features:read:target.xml` 응답
+pub fn target_xml() -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\"?><!DOCTYPE target SYSTEM \"gdb-target.dtd\"><target version=\"1.0\">\
+         <architecture>aarch64</architecture><feature name=\"org.gnu.gdb.aarch64.core\">",
+    );
+    for n in 0..REG_SP {
+        let _ = write!(xml, "<reg name=\"x{}\" bitsize=\"64\" type=\"uint64\"/>", n);
+    }
+    xml.push_str(
+        "<reg name=\"sp\" bitsize=\"64\" type=\"data_ptr\"/><reg name=\"pc\" bitsize=\"64\" type=\"code_ptr\"/>\
+         <reg name=\"cpsr\" bitsize=\"32\" type=\"uint32\"/></feature></target>",
+    );
+    xml
+}
+
+/// 스텁이 읽고 쓸 수 있는 주소인지 (현재 페이지 테이블에 매핑됨)
+pub fn is_mapped(addr: usize) -> bool {
+    super::mmu::translate(addr).is_some()
+}
+
+/// 브레이크포인트를 심을 수 있는 주소인지
+pub fn check_break(addr: usize) -> Result<(), &'static str> {
+    if addr % 4 != 0 {
+        return Err("unaligned address");
+    }
+    if super::kprobe::is_break(addr) {
+        return Err("kprobe installed");
+    }
+    Ok(())
+}
+
+/// 브레이크포인트 심기 (원래 명령어 반환)
+pub fn arm(addr: usize) -> u32 {
+    let orig = super::kprobe::read_insn(addr);
+    super::kprobe::patch(addr, BRK_INSN);
+    orig
+}
+
+pub fn disarm(addr: usize, orig: u32) {
+    super::kprobe::patch(addr, orig);
+}
+
+/// `breakpoint()`로 들어온 예외인지
+pub fn is_compiled_break(regs: &Regs) -> bool {
+    (regs.esr >> 26) & 0x3F == EC_BRK && regs.esr & 0xFFFF == GDB_COMPILED_BRK_IMM
+}
+
+/// `breakpoint()`의 BRK 다음 명령어로 (BRK는 ELR이 자기 자신을 가리킴)
+pub fn skip_compiled_break(regs: &mut Regs) {
+    regs.elr += 4;
+}
+
+/// GDB 스텁으로 들어가는 브레이크포인트 실행
+#[inline(never)]
+pub fn breakpoint() {
+    unsafe {
+        asm!("brk #{imm}", imm = const GDB_COMPILED_BRK_IMM, options(nostack));
+    }
+}
+
+/// 예외 복귀 뒤 명령어 하나만 실행하도록 스텝 설정
+pub fn begin_step(regs: &mut Regs, _sp: u64) {
+    let cpu = percpu::get_cpu_id() as usize % MAX_CPUS;
+    STEPPING[cpu].store(true, Ordering::Relaxed);
+    STEP_SPSR[cpu].store(regs.spsr & (SPSR_D | SPSR_I), Ordering::Relaxed);
+    // 복귀 후 디버그 예외 허용(D=0), IRQ 차단, 스텝 활성
+    regs.spsr = (regs.spsr & !SPSR_D) | SPSR_I | SPSR_SS;
+
+    unsafe {
+        asm!(
+            "msr oslar_el1, xzr",
+            "mrs {t}, mdscr_el1",
+            "orr {t}, {t}, {bits}",
+            "msr mdscr_el1, {t}",
+            "isb",
+            t = out(reg) _,
+            bits = in(reg) MDSCR_SS | MDSCR_KDE,
+            options(nostack)
+        );
+    }
+}
+
+/// Software Step 예외가 이 CPU의 GDB 스텝이면 스텝을 끄고 true
+pub fn end_step(regs: &mut Regs) -> bool {
+    let cpu = percpu::get_cpu_id() as usize % MAX_CPUS;
+    if (regs.esr >> 26) & 0x3F != EC_SOFTSTEP || !STEPPING[cpu].swap(false, Ordering::Relaxed) {
+        return false;
+    }
+
+    unsafe {
+        asm!(
+            "mrs {t}, mdscr_el1",
+            "bic {t}, {t}, {bits}",
+            "msr mdscr_el1, {t}",
+            "isb",
+            t = out(reg) _,
+            bits = in(reg) MDSCR_SS | MDSCR_KDE,
+            options(nostack)
+        );
+    }
+    let saved = STEP_SPSR[cpu].load(Ordering::Relaxed);
+    regs.spsr = (regs.spsr & !(SPSR_SS | SPSR_D | SPSR_I)) | saved;
+    true
+}
+
+/// 호출한 곳의 callee-saved 레지스터, fp, lr, pc를 `regs`에 담고 sp 반환 (패닉 진입용)
+#[inline(always)]
+pub fn capture(regs: &mut Regs) -> u64 {
+    let sp: u64;
+    let pc: u64;
+    let flags: u64;
+    // Safety: gpr[19..31]의 12개 슬롯에 씀
+    unsafe {
+        asm!(
+            "stp x19, x20, [{0}]",
+            "stp x21, x22, [{0}, #16]",
+            "stp x23, x24, [{0}, #32]",
+            "stp x25, x26, [{0}, #48]",
+            "stp x27, x28, [{0}, #64]",
+            "stp x29, x30, [{0}, #80]",
+            "mov {1}, sp",
+            "adr {2}, .",
+            "mrs {3}, nzcv",
+            in(reg) regs.gpr.as_mut_ptr().add(19),
+            out(reg) sp,
+            out(reg) pc,
+            out(reg) flags,
+            options(nostack),
+        );
+    }
+    regs.elr = pc;
+    // EL1h + 현재 NZCV, 패닉 경로는 인터럽트가 꺼져 있음
+    regs.spsr = flags | SPSR_D | SPSR_I | 0b0101;
+    sp
+}
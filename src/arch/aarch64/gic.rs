@@ -1,17 +1,34 @@
-//! ARM Generic Interrupt Controller (GICv2) 드라이버
+//! ARM Generic Interrupt Controller (GICv2/GICv3) 드라이버
 //! 
-//! QEMU virt 머신은 GICv2를 사용합니다.
+//! QEMU virt 머신은 기본으로 GICv2를 사용하고, `-machine virt,gic-version=3`이면 GICv3를
+//! 사용합니다. `init()`이 플랫폼 설정(DTB)의 `GicVersion`을 보고 드라이버를 고르며,
+//! 이 모듈의 함수들은 버전에 맞게 GICv2 코드나 `gicv3` 모듈로 분기합니다.
 //! 
-//! 주요 구성 요소:
+//! 주요 구성 요소 (GICv2):
 //! - Distributor (GICD): 인터럽트 라우팅 및 우선순위 관리
 //! - CPU Interface (GICC): CPU별 인터럽트 처리
 //! 
 //! QEMU virt 머신의 GIC 주소:
 //! - GICD: 0x0800_0000
-//! - GICC: 0x0801_0000
+//! - GICC: 0x0801_0000 (GICv2)
+//! - GICR: 0x080A_0000 (GICv3, `gicv3` 모듈 참고)
 
 use core::ptr::{read_volatile, write_volatile};
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use crate::kprintln;
+use super::gicv3;
+
+/// GICv3 드라이버를 쓰는지 (`init()`에서 결정)
+static V3: AtomicBool = AtomicBool::new(false);
+
+/// SGI별 수신 횟수 (`selftest gic`)
+static SGI_COUNTS: [AtomicU64; 16] = [const { AtomicU64::new(0) }; 16];
+
+/// GICv3 드라이버를 쓰는지
+#[inline]
+pub fn is_v3() -> bool {
+    V3.load(Ordering::Relaxed)
+}
 
 /// GIC Distributor 베이스 주소 얻기
 #[inline]
@@ -37,9 +54,11 @@ fn gicc_base() -> usize {
 const GICD_CTLR: usize = 0x000;        // Distributor Control
 const GICD_TYPER: usize = 0x004;       // Interrupt Controller Type
 const GICD_ISENABLER: usize = 0x100;   // Interrupt Set-Enable
+const GICD_ICENABLER: usize = 0x180;   // Interrupt Clear-Enable
 const GICD_IPRIORITYR: usize = 0x400;  // Interrupt Priority
 const GICD_ITARGETSR: usize = 0x800;   // Interrupt Processor Targets
 const GICD_ICFGR: usize = 0xC00;       // Interrupt Configuration
+const GICD_PIDR2: usize = 0xFE8;       // Peripheral ID2 (ArchRev)
 
 /// CPU Interface 레지스터 오프셋
 const GICC_CTLR: usize = 0x000;        // CPU Interface Control
@@ -50,9 +69,6 @@ const GICC_EOIR: usize = 0x010;        // End of Interrupt
 /// Physical Timer IRQ 번호 (QEMU virt)
 pub const IRQ_PHYS_TIMER: u32 = 30;
 
-/// UART IRQ 번호 (QEMU virt)
-pub const IRQ_UART: u32 = 33;
-
 /// GIC 레지스터 읽기
 #[inline]
 unsafe fn gicd_read(offset: usize) -> u32 {
@@ -78,7 +94,13 @@ unsafe fn gicc_write(offset: usize, value: u32) {
 }
 
 /// 특정 인터럽트 활성화
+///
+/// GICv3에서 SGI/PPI(0-31)는 현재 CPU의 재분배기에서 켭니다.
 pub unsafe fn enable_irq(irq: u32) {
+    if is_v3() && irq < 32 {
+        gicv3::enable_private_irq(irq);
+        return;
+    }
     let reg_idx = (irq / 32) as usize;
     let bit_idx = irq % 32;
     let offset = GICD_ISENABLER + reg_idx * 4;
@@ -86,8 +108,24 @@ pub unsafe fn enable_irq(irq: u32) {
     gicd_write(offset, 1 << bit_idx);
 }
 
+/// 특정 인터럽트 비활성화
+///
+/// GICv3에서 SGI/PPI(0-31)는 현재 CPU의 재분배기에서 끕니다.
+pub unsafe fn disable_irq(irq: u32) {
+    if is_v3() && irq < 32 {
+        gicv3::disable_private_irq(irq);
+        return;
+    }
+    let offset = GICD_ICENABLER + (irq / 32) as usize * 4;
+    unsafe { gicd_write(offset, 1 << (irq % 32)) };
+}
+
 /// 인터럽트 우선순위 설정 (0 = 최고 우선순위)
 pub unsafe fn set_priority(irq: u32, priority: u8) {
+    if is_v3() && irq < 32 {
+        gicv3::set_private_priority(irq, priority);
+        return;
+    }
     let reg_idx = (irq / 4) as usize;
     let byte_idx = (irq % 4) as usize;
     let offset = GICD_IPRIORITYR + reg_idx * 4;
@@ -100,7 +138,15 @@ pub unsafe fn set_priority(irq: u32, priority: u8) {
 }
 
 /// 인터럽트 타겟 CPU 설정
+///
+/// GICv3는 SPI 하나를 CPU 하나로만 라우팅하므로 `cpu_mask`의 가장 낮은 CPU를 씁니다.
 pub unsafe fn set_target(irq: u32, cpu_mask: u8) {
+    if is_v3() {
+        if irq >= 32 && cpu_mask != 0 {
+            gicv3::route_spi(irq, cpu_mask.trailing_zeros());
+        }
+        return;
+    }
     let reg_idx = (irq / 4) as usize;
     let byte_idx = (irq % 4) as usize;
     let offset = GICD_ITARGETSR + reg_idx * 4;
@@ -112,20 +158,52 @@ pub unsafe fn set_target(irq: u32, cpu_mask: u8) {
     gicd_write(offset, val);
 }
 
+/// 인터럽트가 활성화되어 있는지 (SGI/PPI는 현재 CPU 기준)
+pub fn is_enabled(irq: u32) -> bool {
+    if is_v3() && irq < 32 {
+        return gicv3::private_irq_enabled(irq);
+    }
+    let offset = GICD_ISENABLER + (irq / 32) as usize * 4;
+    unsafe { gicd_read(offset) & (1 << (irq % 32)) != 0 }
+}
+
 /// 인터럽트 acknowledge
 pub unsafe fn ack_irq() -> u32 {
+    if is_v3() {
+        return gicv3::ack_irq();
+    }
     gicc_read(GICC_IAR)
 }
 
 /// 인터럽트 처리 완료
 pub unsafe fn end_irq(irq: u32) {
+    if is_v3() {
+        gicv3::end_irq(irq);
+        return;
+    }
     gicc_write(GICC_EOIR, irq);
 }
 
-/// GIC 초기화
+/// GIC 초기화 (플랫폼 설정의 버전에 따라 GICv2 또는 GICv3)
 pub fn init() -> Result<(), &'static str> {
-    kprintln!("\n[GIC] Initializing GICv2...");
-    
+    let version = crate::drivers::config::gic_config().map(|c| c.version);
+    if version == Some(crate::drivers::config::GicVersion::V3) {
+        kprintln!("\n[GIC] Initializing GICv3...");
+        V3.store(true, Ordering::Relaxed);
+        if let Err(e) = gicv3::init() {
+            V3.store(false, Ordering::Relaxed);
+            return Err(e);
+        }
+    } else {
+        kprintln!("\n[GIC] Initializing GICv2...");
+        init_v2();
+    }
+    kprintln!("[GIC] GICv{} initialized", if is_v3() { 3 } else { 2 });
+    Ok(())
+}
+
+/// GICv2 Distributor와 부팅 CPU의 CPU Interface 활성화
+fn init_v2() {
     unsafe {
         // 1. Distributor 정보 확인
         let typer = gicd_read(GICD_TYPER);
@@ -141,31 +219,46 @@ pub fn init() -> Result<(), &'static str> {
         
         // 4. Priority Mask 설정 (모든 우선순위 허용)
         gicc_write(GICC_PMR, 0xFF);
-        
-        // 5. Physical Timer IRQ 설정
-        set_priority(IRQ_PHYS_TIMER, 0xA0); // 중간 우선순위
-        set_target(IRQ_PHYS_TIMER, 1);      // CPU 0에 전달
-        enable_irq(IRQ_PHYS_TIMER);
-        
-        kprintln!("[GIC] Physical Timer IRQ {} enabled", IRQ_PHYS_TIMER);
-        
-        // 6. UART IRQ 설정
-        set_priority(IRQ_UART, 0x80);       // 높은 우선순위
-        set_target(IRQ_UART, 1);            // CPU 0에 전달
-        enable_irq(IRQ_UART);
-        
-        kprintln!("[GIC] UART IRQ {} enabled", IRQ_UART);
     }
-    
-    kprintln!("[GIC] GICv2 initialized");
-    Ok(())
+}
+
+/// GIC 아키텍처 버전 (GICD_PIDR2.ArchRev, 1 = GICv1 ... 4 = GICv4)
+pub fn arch_version() -> u32 {
+    if is_v3() {
+        return gicv3::arch_version();
+    }
+    unsafe { (gicd_read(GICD_PIDR2) >> 4) & 0xF }
+}
+
+/// Distributor가 지원하는 최대 IRQ 수 (GICD_TYPER.ITLinesNumber)
+pub fn max_irqs() -> u32 {
+    unsafe { ((gicd_read(GICD_TYPER) & 0x1F) + 1) * 32 }
+}
+
+/// GIC 비활성화 (kexec 전 인터럽트 전달 중지)
+///
+/// 새 커널이 `init()`에서 다시 활성화합니다.
+pub fn disable() {
+    if is_v3() {
+        gicv3::disable();
+        return;
+    }
+    unsafe {
+        gicc_write(GICC_CTLR, 0);
+        gicd_write(GICD_CTLR, 0);
+    }
 }
 
 /// Secondary CPU의 GIC CPU Interface 초기화
 ///
 /// GICD(Distributor)는 primary CPU에서 이미 초기화되었으므로,
 /// 각 secondary CPU는 자신의 GICC(CPU Interface)만 초기화합니다.
+/// GICv3에서는 자기 재분배기와 시스템 레지스터 CPU 인터페이스를 초기화합니다.
 pub fn init_secondary() {
+    if is_v3() {
+        gicv3::init_secondary();
+        return;
+    }
     unsafe {
         // CPU Interface 활성화
         gicc_write(GICC_CTLR, 1);
@@ -180,8 +273,11 @@ pub fn init_secondary() {
 /// # Arguments
 /// * `target_cpu` - 대상 CPU 번호 (0-7)
 /// * `sgi_id` - SGI 번호 (0-15)
-#[allow(dead_code)]
 pub fn send_sgi(target_cpu: u32, sgi_id: u32) {
+    if is_v3() {
+        gicv3::send_sgi(target_cpu, sgi_id);
+        return;
+    }
     const GICD_SGIR: usize = 0xF00;
     unsafe {
         // TargetListFilter=0 (use target list), CPU target list, SGI ID
@@ -193,37 +289,54 @@ pub fn send_sgi(target_cpu: u32, sgi_id: u32) {
 /// IPI용 SGI 번호 (reschedule 요청)
 pub const SGI_RESCHEDULE: u32 = 0;
 
+/// SGI 수신 횟수 (현재까지 모든 CPU 합계)
+pub fn sgi_count(sgi_id: u32) -> u64 {
+    SGI_COUNTS.get(sgi_id as usize).map_or(0, |c| c.load(Ordering::Relaxed))
+}
+
 /// IRQ 핸들러에서 호출
 pub fn handle_irq() {
     unsafe {
         let irq = ack_irq();
-        let irq_num = irq & 0x3FF; // 하위 10비트가 IRQ 번호
+        // GICv2는 하위 10비트가 IRQ 번호, GICv3는 INTID 전체 (LPI는 8192부터)
+        let irq_num = if is_v3() { irq } else { irq & 0x3FF };
 
-        if irq_num >= 1020 {
+        if (1020..1024).contains(&irq_num) {
             return; // spurious interrupt
         }
+        crate::trace_event!(irq_entry, irq = irq_num);
 
         // SGI (IPI) 처리 (IRQ 0-15)
         if irq_num < 16 {
+            SGI_COUNTS[irq_num as usize].fetch_add(1, Ordering::Relaxed);
+            crate::irq::count_ipi();
             if irq_num == SGI_RESCHEDULE {
+                // kexec 진행 중이면 이 CPU를 펌웨어로 반환
+                if crate::kexec::stop_requested() {
+                    end_irq(irq);
+                    crate::kexec::stop_this_cpu();
+                }
+
+                // 다른 CPU가 GDB 스텁 세션 중이면 끝날 때까지 대기
+                if crate::debug::gdbstub::hold_requested() {
+                    crate::debug::gdbstub::hold_this_cpu();
+                }
+
                 // Reschedule IPI: 스케줄러가 타이머 틱에서 자동 호출되므로
                 // 여기서는 추가 처리 없이 EOI만 수행
                 crate::proc::scheduler::schedule();
             }
         }
-        // 타이머 인터럽트인 경우
-        else if irq_num == IRQ_PHYS_TIMER {
-            super::timer::handle_irq();
-        }
-        // UART 인터럽트인 경우
-        else if irq_num == IRQ_UART {
-            super::uart::handle_irq();
+        // LPI (GICv3 ITS를 거친 메시지 인터럽트)
+        else if irq_num >= gicv3::LPI_BASE {
+            gicv3::handle_lpi(irq_num);
         }
-        // VirtIO 디바이스 인터럽트
-        else if crate::virtio::irq::handle_virtio_irq(irq_num) {
-            // VirtIO 디바이스가 처리함
+        // PPI/SPI: 등록된 핸들러 (타이머, UART, VirtIO 등)
+        else {
+            crate::irq::dispatch(irq_num);
         }
 
+        crate::trace_event!(irq_exit, irq = irq_num);
         // EOI (End of Interrupt)
         end_irq(irq);
     }
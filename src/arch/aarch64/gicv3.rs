@@ -0,0 +1,716 @@
+//! GICv3 드라이버 (재분배기, 시스템 레지스터 CPU 인터페이스, LPI/ITS)
+//!
+//! `gic.rs`가 부팅 시 플랫폼 설정의 `GicVersion`을 보고 이 모듈로 분기합니다.
+//! QEMU virt는 `-machine virt,gic-version=3`일 때 GICv3를 씁니다.
+//!
+//! GICv2와 다른 점:
+//! - 어피니티 라우팅(ARE): SPI는 `GICD_IROUTER`로 대상 CPU의 MPIDR 어피니티를 지정
+//! - SGI/PPI(0-31)는 CPU마다 있는 재분배기(GICR)의 SGI 프레임에서 설정
+//! - CPU 인터페이스는 MMIO(GICC)가 아니라 `ICC_*_EL1` 시스템 레지스터
+//! - SGI는 `ICC_SGI1R_EL1`로 보냄
+//! - LPI(INTID 8192~): 메모리의 설정/펜딩 테이블과 ITS(Interrupt Translation Service)가
+//!   (DeviceID, EventID)를 LPI로 변환
+//!
+//! QEMU virt 주소: GICD 0x0800_0000, ITS 0x0808_0000, GICR 0x080A_0000 (CPU마다 128KB)
+
+use core::arch::asm;
+use core::ptr::{read_volatile, write_bytes, write_volatile};
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use crate::kprintln;
+use crate::proc::percpu::MAX_CPUS;
+use crate::sync::Spinlock;
+
+/// Distributor 레지스터 오프셋 (GICv3 추가분)
+const GICD_CTLR: usize = 0x0000;
+const GICD_TYPER: usize = 0x0004;
+const GICD_IGROUPR: usize = 0x0080;
+const GICD_IROUTER: usize = 0x6000;
+const GICD_PIDR2: usize = 0xFFE8;
+
+/// GICD_CTLR 비트
+const GICD_CTLR_ENABLE_G1: u32 = 1 << 0;
+const GICD_CTLR_ENABLE_G1A: u32 = 1 << 1;
+const GICD_CTLR_ARE: u32 = 1 << 4;
+const GICD_CTLR_RWP: u32 = 1 << 31;
+
+/// GICD_TYPER: LPI 지원
+const GICD_TYPER_LPIS: u32 = 1 << 17;
+
+/// 재분배기 RD 프레임 레지스터
+const GICR_CTLR: usize = 0x0000;
+const GICR_TYPER: usize = 0x0008;
+const GICR_WAKER: usize = 0x0014;
+const GICR_PROPBASER: usize = 0x0070;
+const GICR_PENDBASER: usize = 0x0078;
+
+/// SGI 프레임 (RD 프레임 + 64KB) 레지스터
+const GICR_SGI_OFFSET: usize = 0x1_0000;
+const GICR_IGROUPR0: usize = 0x0080;
+const GICR_ISENABLER0: usize = 0x0100;
+const GICR_ICENABLER0: usize = 0x0180;
+const GICR_IPRIORITYR: usize = 0x0400;
+
+/// 재분배기 하나의 크기 (RD + SGI 프레임), VLPI 지원 시 두 배
+const GICR_FRAME_SIZE: usize = 0x2_0000;
+
+const GICR_CTLR_ENABLE_LPIS: u32 = 1 << 0;
+const GICR_TYPER_PLPIS: u64 = 1 << 0;
+const GICR_TYPER_VLPIS: u64 = 1 << 1;
+const GICR_TYPER_LAST: u64 = 1 << 4;
+const GICR_WAKER_PROCESSOR_SLEEP: u32 = 1 << 1;
+const GICR_WAKER_CHILDREN_ASLEEP: u32 = 1 << 2;
+
+/// 첫 LPI INTID
+pub const LPI_BASE: u32 = 8192;
+/// LPI INTID 비트 수 (INTID < 2^14, 설정 테이블 8KB)
+const LPI_ID_BITS: u32 = 14;
+/// 핸들러를 붙일 수 있는 LPI 수 (INTID `LPI_BASE`..`LPI_BASE + MAX_LPIS`)
+pub const MAX_LPIS: usize = 64;
+/// LPI 설정 바이트: 우선순위 0xA0, RES1, 활성화
+const LPI_PROP_ENABLED: u8 = 0xA0 | 0b10 | 1;
+const LPI_PROP_DISABLED: u8 = 0xA0 | 0b10;
+
+/// CPU별 재분배기 베이스 (0 = 아직 초기화 안 됨)
+static RD_BASES: [AtomicUsize; MAX_CPUS] = [const { AtomicUsize::new(0) }; MAX_CPUS];
+/// CPU별 MPIDR 어피니티 (Aff3..Aff0, GICR_TYPER 형식)
+static AFFINITIES: [AtomicU64; MAX_CPUS] = [const { AtomicU64::new(u64::MAX) }; MAX_CPUS];
+
+/// LPI 설정 테이블 주소 (0 = LPI 없음)
+static PROP_TABLE: AtomicUsize = AtomicUsize::new(0);
+/// LPI별 핸들러 (`fn(u32)` 주소, 0 = 빈 슬롯)
+static LPI_HANDLERS: [AtomicUsize; MAX_LPIS] = [const { AtomicUsize::new(0) }; MAX_LPIS];
+
+#[inline]
+fn gicd_base() -> usize {
+    crate::drivers::config::gicd_base()
+}
+
+#[inline]
+unsafe fn read32(addr: usize) -> u32 {
+    unsafe { read_volatile(addr as *const u32) }
+}
+
+#[inline]
+unsafe fn write32(addr: usize, value: u32) {
+    unsafe { write_volatile(addr as *mut u32, value) };
+}
+
+#[inline]
+unsafe fn read64(addr: usize) -> u64 {
+    unsafe { read_volatile(addr as *const u64) }
+}
+
+#[inline]
+unsafe fn write64(addr: usize, value: u64) {
+    unsafe { write_volatile(addr as *mut u64, value) };
+}
+
+/// 레지스터 비트가 풀릴 때까지 대기 (최대 ~1M번 확인)
+fn wait_clear(addr: usize, bit: u32) -> bool {
+    for _ in 0..1_000_000 {
+        if unsafe { read32(addr) } & bit == 0 {
+            return true;
+        }
+        core::hint::spin_loop();
+    }
+    false
+}
+
+/// 현재 CPU의 MPIDR 어피니티 (Aff3[31:24] Aff2[23:16] Aff1[15:8] Aff0[7:0])
+fn current_affinity() -> u64 {
+    let mpidr: u64;
+    unsafe { asm!("mrs {}, MPIDR_EL1", out(reg) mpidr) };
+    (mpidr & 0xFF_FFFF) | ((mpidr >> 8) & 0xFF00_0000)
+}
+
+/// CPU 번호의 어피니티 (재분배기를 초기화하기 전이면 Aff0 = CPU 번호로 가정)
+pub fn cpu_affinity(cpu: u32) -> u64 {
+    match AFFINITIES.get(cpu as usize).map(|a| a.load(Ordering::Relaxed)) {
+        Some(aff) if aff != u64::MAX => aff,
+        _ => cpu as u64,
+    }
+}
+
+/// 현재 CPU의 재분배기 RD 프레임
+fn current_rd() -> usize {
+    let cpu = crate::proc::percpu::get_cpu_id() as usize;
+    RD_BASES.get(cpu).map_or(0, |b| b.load(Ordering::Relaxed))
+}
+
+// ---------------------------------------------------------------------------
+// CPU 인터페이스 (시스템 레지스터)
+// ---------------------------------------------------------------------------
+
+/// ICC_IAR1_EL1 읽기 (그룹 1 인터럽트 acknowledge)
+#[inline]
+pub fn ack_irq() -> u32 {
+    let iar: u64;
+    unsafe { asm!("mrs {}, S3_0_C12_C12_0", out(reg) iar) }; // ICC_IAR1_EL1
+    (iar & 0xFF_FFFF) as u32
+}
+
+/// ICC_EOIR1_EL1 쓰기 (인터럽트 처리 완료)
+#[inline]
+pub fn end_irq(irq: u32) {
+    unsafe { asm!("msr S3_0_C12_C12_1, {}", in(reg) irq as u64) }; // ICC_EOIR1_EL1
+}
+
+/// 시스템 레지스터 인터페이스 켜기, 모든 우선순위 허용, 그룹 1 활성화
+fn init_cpu_interface() {
+    unsafe {
+        // ICC_SRE_EL1: SRE | DFB | DIB
+        let mut sre: u64;
+        asm!("mrs {}, S3_0_C12_C12_5", out(reg) sre);
+        sre |= 0b111;
+        asm!("msr S3_0_C12_C12_5, {}", "isb", in(reg) sre);
+
+        // ICC_PMR_EL1: 모든 우선순위 허용
+        asm!("msr S3_0_C4_C6_0, {}", in(reg) 0xFFu64);
+        // ICC_BPR1_EL1: 선점 그룹 분할 없음
+        asm!("msr S3_0_C12_C12_3, {}", in(reg) 0u64);
+        // ICC_IGRPEN1_EL1: 그룹 1 활성화
+        asm!("msr S3_0_C12_C12_7, {}", "isb", in(reg) 1u64);
+    }
+}
+
+/// SGI 전송 (ICC_SGI1R_EL1)
+pub fn send_sgi(target_cpu: u32, sgi_id: u32) {
+    let aff = cpu_affinity(target_cpu);
+    let aff0 = aff & 0xFF;
+    let aff1 = (aff >> 8) & 0xFF;
+    let aff2 = (aff >> 16) & 0xFF;
+    let aff3 = (aff >> 24) & 0xFF;
+    // TargetList는 Aff0 16개 단위, RS가 그 범위 선택
+    let value = (1u64 << (aff0 % 16))
+        | (aff1 << 16)
+        | (((sgi_id & 0xF) as u64) << 24)
+        | (aff2 << 32)
+        | ((aff0 / 16) << 44)
+        | (aff3 << 48);
+    unsafe { asm!("dsb ishst", "msr S3_0_C12_C11_5, {}", "isb", in(reg) value) }; // ICC_SGI1R_EL1
+}
+
+// ---------------------------------------------------------------------------
+// Distributor
+// ---------------------------------------------------------------------------
+
+/// Distributor 초기화 (어피니티 라우팅, 모든 SPI를 그룹 1로)
+fn init_distributor() -> Result<(), &'static str> {
+    let base = gicd_base();
+    unsafe {
+        write32(base + GICD_CTLR, 0);
+        if !wait_clear(base + GICD_CTLR, GICD_CTLR_RWP) {
+            return Err("GICD_CTLR.RWP timeout");
+        }
+
+        let lines = (read32(base + GICD_TYPER) & 0x1F) as usize + 1;
+        for i in 1..lines {
+            write32(base + GICD_IGROUPR + i * 4, 0xFFFF_FFFF);
+        }
+
+        write32(base + GICD_CTLR, GICD_CTLR_ARE);
+        write32(base + GICD_CTLR, GICD_CTLR_ARE | GICD_CTLR_ENABLE_G1A | GICD_CTLR_ENABLE_G1);
+        if !wait_clear(base + GICD_CTLR, GICD_CTLR_RWP) {
+            return Err("GICD_CTLR.RWP timeout");
+        }
+    }
+    Ok(())
+}
+
+/// SPI 라우팅 (IRM = 0, 지정한 CPU 하나로)
+pub fn route_spi(irq: u32, cpu: u32) {
+    let aff = cpu_affinity(cpu);
+    // GICD_IROUTER: Aff3[39:32], Aff2..Aff0[23:0]
+    let value = (aff & 0xFF_FFFF) | ((aff & 0xFF00_0000) << 8);
+    unsafe { write64(gicd_base() + GICD_IROUTER + irq as usize * 8, value) };
+}
+
+/// Distributor 비활성화 (kexec)
+pub fn disable() {
+    unsafe {
+        asm!("msr S3_0_C12_C12_7, {}", "isb", in(reg) 0u64); // ICC_IGRPEN1_EL1
+        write32(gicd_base() + GICD_CTLR, 0);
+    }
+    let _ = wait_clear(gicd_base() + GICD_CTLR, GICD_CTLR_RWP);
+}
+
+/// 아키텍처 버전 (GICD_PIDR2.ArchRev)
+pub fn arch_version() -> u32 {
+    unsafe { (read32(gicd_base() + GICD_PIDR2) >> 4) & 0xF }
+}
+
+// ---------------------------------------------------------------------------
+// 재분배기
+// ---------------------------------------------------------------------------
+
+/// 현재 CPU의 재분배기 찾기 (GICR_TYPER 어피니티가 MPIDR과 같은 프레임)
+fn find_redistributor() -> Option<usize> {
+    let config = crate::drivers::config::gic_config()?;
+    let start = config.redistributor_base?;
+    let end = start + config.redistributor_size.max(GICR_FRAME_SIZE);
+    let affinity = current_affinity();
+
+    let mut rd = start;
+    while rd < end {
+        let typer = unsafe { read64(rd + GICR_TYPER) };
+        if typer >> 32 == affinity {
+            return Some(rd);
+        }
+        if typer & GICR_TYPER_LAST != 0 {
+            break;
+        }
+        rd += if typer & GICR_TYPER_VLPIS != 0 { GICR_FRAME_SIZE * 2 } else { GICR_FRAME_SIZE };
+    }
+    None
+}
+
+/// 현재 CPU의 재분배기 초기화 (깨우기, SGI/PPI 그룹 1, SGI 활성화)
+fn init_redistributor() -> Result<usize, &'static str> {
+    let rd = find_redistributor().ok_or("no redistributor for this CPU")?;
+    let cpu = crate::proc::percpu::get_cpu_id() as usize;
+    if cpu >= MAX_CPUS {
+        return Err("CPU number out of range");
+    }
+
+    unsafe {
+        let waker = read32(rd + GICR_WAKER);
+        write32(rd + GICR_WAKER, waker & !GICR_WAKER_PROCESSOR_SLEEP);
+        if !wait_clear(rd + GICR_WAKER, GICR_WAKER_CHILDREN_ASLEEP) {
+            return Err("GICR_WAKER.ChildrenAsleep timeout");
+        }
+
+        let sgi = rd + GICR_SGI_OFFSET;
+        write32(sgi + GICR_IGROUPR0, 0xFFFF_FFFF);
+        // PPI는 필요한 것만 켜고, SGI(IPI)는 모두 켬
+        write32(sgi + GICR_ICENABLER0, 0xFFFF_0000);
+        write32(sgi + GICR_ISENABLER0, 0x0000_FFFF);
+    }
+
+    RD_BASES[cpu].store(rd, Ordering::Relaxed);
+    AFFINITIES[cpu].store(current_affinity(), Ordering::Relaxed);
+    Ok(rd)
+}
+
+/// SGI/PPI 활성화 (현재 CPU의 재분배기)
+pub fn enable_private_irq(irq: u32) {
+    let rd = current_rd();
+    if rd != 0 {
+        unsafe { write32(rd + GICR_SGI_OFFSET + GICR_ISENABLER0, 1 << irq) };
+    }
+}
+
+/// SGI/PPI 비활성화 (현재 CPU의 재분배기)
+pub fn disable_private_irq(irq: u32) {
+    let rd = current_rd();
+    if rd != 0 {
+        unsafe { write32(rd + GICR_SGI_OFFSET + GICR_ICENABLER0, 1 << irq) };
+    }
+}
+
+/// SGI/PPI가 켜져 있는지 (현재 CPU의 재분배기)
+pub fn private_irq_enabled(irq: u32) -> bool {
+    let rd = current_rd();
+    rd != 0 && unsafe { read32(rd + GICR_SGI_OFFSET + GICR_ISENABLER0) } & (1 << irq) != 0
+}
+
+/// SGI/PPI 우선순위 (현재 CPU의 재분배기)
+pub fn set_private_priority(irq: u32, priority: u8) {
+    let rd = current_rd();
+    if rd != 0 {
+        unsafe { write_volatile((rd + GICR_SGI_OFFSET + GICR_IPRIORITYR + irq as usize) as *mut u8, priority) };
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 초기화
+// ---------------------------------------------------------------------------
+
+/// 부팅 CPU 초기화 (Distributor, 재분배기, CPU 인터페이스, LPI/ITS)
+pub fn init() -> Result<(), &'static str> {
+    init_distributor()?;
+    let rd = init_redistributor()?;
+    kprintln!("[GIC] Redistributor for CPU {} at {:#x}", crate::proc::percpu::get_cpu_id(), rd);
+    init_cpu_interface();
+
+    match init_lpis(rd) {
+        Ok(()) => match its::init() {
+            Ok(()) => kprintln!("[GIC] LPIs enabled ({} INTIDs from {}), ITS ready", MAX_LPIS, LPI_BASE),
+            Err(e) => kprintln!("[GIC] LPIs enabled, ITS unavailable: {}", e),
+        },
+        Err(e) => kprintln!("[GIC] LPIs unavailable: {}", e),
+    }
+    Ok(())
+}
+
+/// 보조 CPU 초기화 (자기 재분배기와 CPU 인터페이스, 타이머 PPI)
+pub fn init_secondary() {
+    let rd = match init_redistributor() {
+        Ok(rd) => rd,
+        Err(e) => {
+            kprintln!("[GIC] CPU {}: {}", crate::proc::percpu::get_cpu_id(), e);
+            return;
+        }
+    };
+    init_cpu_interface();
+    set_private_priority(super::gic::IRQ_PHYS_TIMER, 0xA0);
+    enable_private_irq(super::gic::IRQ_PHYS_TIMER);
+
+    if PROP_TABLE.load(Ordering::Acquire) != 0 && enable_lpis_on(rd).is_ok() {
+        let _ = its::map_collection(crate::proc::percpu::get_cpu_id(), rd);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// LPI
+// ---------------------------------------------------------------------------
+
+/// 0으로 채운 2^order 페이지 (물리 = 가상, identity 매핑)
+fn alloc_zeroed(order: usize) -> Option<usize> {
+    let addr = crate::mm::page::alloc_frames_order(order)?;
+    unsafe { write_bytes(addr as *mut u8, 0, crate::mm::page::PAGE_SIZE << order) };
+    Some(addr)
+}
+
+/// LPI 설정 테이블 만들고 부팅 CPU의 재분배기에서 LPI 켜기
+fn init_lpis(rd: usize) -> Result<(), &'static str> {
+    let typer = unsafe { read32(gicd_base() + GICD_TYPER) };
+    if typer & GICD_TYPER_LPIS == 0 {
+        return Err("not supported by distributor");
+    }
+    if ((typer >> 19) & 0x1F) + 1 < LPI_ID_BITS {
+        return Err("INTID space too small");
+    }
+
+    // 설정 테이블: LPI마다 1바이트 (모두 비활성)
+    let table_size = (1usize << LPI_ID_BITS) - LPI_BASE as usize;
+    let order = (table_size / crate::mm::page::PAGE_SIZE).trailing_zeros() as usize;
+    let prop = alloc_zeroed(order).ok_or("out of memory")?;
+    for i in 0..table_size {
+        unsafe { write_volatile((prop + i) as *mut u8, LPI_PROP_DISABLED) };
+    }
+    PROP_TABLE.store(prop, Ordering::Release);
+
+    enable_lpis_on(rd)
+}
+
+/// 재분배기 하나에서 LPI 켜기 (펜딩 테이블은 CPU마다 따로, 64KB 정렬)
+fn enable_lpis_on(rd: usize) -> Result<(), &'static str> {
+    let prop = PROP_TABLE.load(Ordering::Acquire);
+    unsafe {
+        if read64(rd + GICR_TYPER) & GICR_TYPER_PLPIS == 0 {
+            return Err("not supported by redistributor");
+        }
+        if read32(rd + GICR_CTLR) & GICR_CTLR_ENABLE_LPIS != 0 {
+            // kexec 전 커널이 켠 LPI는 끌 수 없고 테이블도 바꿀 수 없음
+            return Err("already enabled (previous kernel)");
+        }
+
+        // 펜딩 테이블: INTID마다 1비트
+        let pend = alloc_zeroed(4).ok_or("out of memory")?;
+        if pend % 0x1_0000 != 0 {
+            crate::mm::page::free_frames_order(pend, 4);
+            return Err("pending table not 64KB aligned");
+        }
+
+        write64(rd + GICR_PROPBASER, prop as u64 | (LPI_ID_BITS - 1) as u64);
+        write64(rd + GICR_PENDBASER, pend as u64);
+        asm!("dsb sy");
+        write32(rd + GICR_CTLR, read32(rd + GICR_CTLR) | GICR_CTLR_ENABLE_LPIS);
+    }
+    Ok(())
+}
+
+/// LPI를 쓸 수 있는지
+pub fn lpis_enabled() -> bool {
+    PROP_TABLE.load(Ordering::Acquire) != 0
+}
+
+/// 설정 테이블의 활성화 비트 변경 (ITS `invalidate()`로 재분배기에 알려야 적용)
+pub fn set_lpi_enabled(intid: u32, enabled: bool) {
+    let prop = PROP_TABLE.load(Ordering::Acquire);
+    if prop == 0 || intid < LPI_BASE {
+        return;
+    }
+    let value = if enabled { LPI_PROP_ENABLED } else { LPI_PROP_DISABLED };
+    unsafe {
+        write_volatile((prop + (intid - LPI_BASE) as usize) as *mut u8, value);
+        asm!("dsb sy");
+    }
+}
+
+/// LPI 할당 (핸들러 등록, 설정 테이블에서 활성화)
+///
+/// 장치 이벤트와 연결하려면 `its::map_event()`를 부릅니다.
+pub fn alloc_lpi(handler: fn(u32)) -> Option<u32> {
+    if !lpis_enabled() {
+        return None;
+    }
+    for (i, slot) in LPI_HANDLERS.iter().enumerate() {
+        if slot
+            .compare_exchange(0, handler as usize, Ordering::AcqRel, Ordering::Relaxed)
+            .is_ok()
+        {
+            let intid = LPI_BASE + i as u32;
+            set_lpi_enabled(intid, true);
+            return Some(intid);
+        }
+    }
+    None
+}
+
+/// LPI 해제 (먼저 `its::unmap_event()`로 연결을 끊어야 함)
+pub fn free_lpi(intid: u32) {
+    let Some(slot) = intid.checked_sub(LPI_BASE).and_then(|i| LPI_HANDLERS.get(i as usize)) else {
+        return;
+    };
+    set_lpi_enabled(intid, false);
+    slot.store(0, Ordering::Release);
+}
+
+/// LPI 디스패치 (IRQ 핸들러에서 호출, 핸들러가 없으면 false)
+pub fn handle_lpi(intid: u32) -> bool {
+    let handler = intid
+        .checked_sub(LPI_BASE)
+        .and_then(|i| LPI_HANDLERS.get(i as usize))
+        .map_or(0, |slot| slot.load(Ordering::Acquire));
+    if handler == 0 {
+        return false;
+    }
+    let handler: fn(u32) = unsafe { core::mem::transmute(handler) };
+    handler(intid);
+    true
+}
+
+// ---------------------------------------------------------------------------
+// ITS
+// ---------------------------------------------------------------------------
+
+/// ITS (Interrupt Translation Service)
+///
+/// 명령 큐에 `MAPD`/`MAPC`/`MAPTI` 등을 넣어 (DeviceID, EventID) → LPI 변환표를 만듭니다.
+/// 장치 테이블과 컬렉션 테이블은 한 페이지씩(ID 512개까지), 장치마다 ITT 한 페이지
+/// (EventID `ITT_EVENT_BITS`비트)를 씁니다. 컬렉션 번호는 CPU 번호와 같습니다.
+pub mod its {
+    use alloc::vec::Vec;
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+
+    const GITS_CTLR: usize = 0x0000;
+    const GITS_TYPER: usize = 0x0008;
+    const GITS_CBASER: usize = 0x0080;
+    const GITS_CWRITER: usize = 0x0088;
+    const GITS_CREADR: usize = 0x0090;
+    const GITS_BASER: usize = 0x0100;
+
+    const GITS_CTLR_ENABLED: u32 = 1 << 0;
+    const GITS_CTLR_QUIESCENT: u32 = 1 << 31;
+    /// GITS_TYPER.PTA: 컬렉션 대상이 재분배기 물리 주소 (0이면 프로세서 번호)
+    const GITS_TYPER_PTA: u64 = 1 << 19;
+    const GITS_BASER_VALID: u64 = 1 << 63;
+    const GITS_BASER_TYPE_DEVICE: u64 = 1;
+    const GITS_BASER_TYPE_COLLECTION: u64 = 4;
+
+    const CMD_INT: u64 = 0x03;
+    const CMD_SYNC: u64 = 0x05;
+    const CMD_MAPD: u64 = 0x08;
+    const CMD_MAPC: u64 = 0x09;
+    const CMD_MAPTI: u64 = 0x0A;
+    const CMD_INV: u64 = 0x0C;
+    const CMD_DISCARD: u64 = 0x0F;
+
+    /// 명령 하나의 크기
+    const CMD_SIZE: usize = 32;
+    /// 명령 큐 크기 (한 페이지, 명령 128개)
+    const QUEUE_SIZE: usize = crate::mm::page::PAGE_SIZE;
+    /// 장치 테이블이 담는 DeviceID 수 (한 페이지)
+    pub const MAX_DEVICE_ID: u32 = 512;
+    /// 장치마다 EventID 비트 수
+    pub const ITT_EVENT_BITS: u32 = 5;
+
+    struct Its {
+        base: usize,
+        queue: usize,
+        write_offset: usize,
+        /// 컬렉션 대상이 물리 주소인지 (GITS_TYPER.PTA)
+        pta: bool,
+        /// MAPD로 등록한 장치 (DeviceID, ITT 주소)
+        devices: Vec<(u32, usize)>,
+    }
+
+    static ITS: Spinlock<Option<Its>> = Spinlock::new(None);
+    static READY: AtomicBool = AtomicBool::new(false);
+
+    impl Its {
+        /// 명령 하나를 큐에 넣고 ITS가 처리할 때까지 대기
+        fn command(&mut self, cmd: [u64; 4]) -> Result<(), &'static str> {
+            unsafe {
+                let slot = self.queue + self.write_offset;
+                for (i, dw) in cmd.iter().enumerate() {
+                    write64(slot + i * 8, *dw);
+                }
+                self.write_offset = (self.write_offset + CMD_SIZE) % QUEUE_SIZE;
+                asm!("dsb sy");
+                write64(self.base + GITS_CWRITER, self.write_offset as u64);
+
+                for _ in 0..1_000_000 {
+                    if read64(self.base + GITS_CREADR) as usize & 0xF_FFE0 == self.write_offset {
+                        return Ok(());
+                    }
+                    core::hint::spin_loop();
+                }
+            }
+            Err("command queue timeout")
+        }
+
+        /// 재분배기 지정 필드 (MAPC/SYNC의 RDbase)
+        fn rd_target(&self, rd: usize) -> u64 {
+            if self.pta {
+                rd as u64 & !0xFFFF
+            } else {
+                // GICR_TYPER.Processor_Number
+                ((unsafe { read64(rd + GICR_TYPER) } >> 8) & 0xFFFF) << 16
+            }
+        }
+
+        fn sync(&mut self, cpu: u32) -> Result<(), &'static str> {
+            let rd = RD_BASES[cpu as usize].load(Ordering::Relaxed);
+            let target = self.rd_target(rd);
+            self.command([CMD_SYNC, 0, target, 0])
+        }
+
+        /// 장치 등록 (처음 한 번 MAPD, ITT 할당)
+        fn ensure_device(&mut self, device_id: u32) -> Result<(), &'static str> {
+            if self.devices.iter().any(|(id, _)| *id == device_id) {
+                return Ok(());
+            }
+            let itt = alloc_zeroed(0).ok_or("out of memory")?;
+            let cmd = [
+                CMD_MAPD | ((device_id as u64) << 32),
+                (ITT_EVENT_BITS - 1) as u64,
+                GITS_BASER_VALID | (itt as u64 & 0xF_FFFF_FFFF_FF00),
+                0,
+            ];
+            if let Err(e) = self.command(cmd) {
+                unsafe { crate::mm::page::free_frames_order(itt, 0) };
+                return Err(e);
+            }
+            self.devices.push((device_id, itt));
+            Ok(())
+        }
+    }
+
+    /// ITS 초기화 (명령 큐, 장치/컬렉션 테이블, 부팅 CPU 컬렉션)
+    pub(super) fn init() -> Result<(), &'static str> {
+        let base = crate::drivers::config::gic_config()
+            .and_then(|c| c.its_base)
+            .ok_or("not in DTB")?;
+
+        unsafe {
+            write32(base + GITS_CTLR, 0);
+            for _ in 0..1_000_000 {
+                if read32(base + GITS_CTLR) & GITS_CTLR_QUIESCENT != 0 {
+                    break;
+                }
+                core::hint::spin_loop();
+            }
+
+            let pta = read64(base + GITS_TYPER) & GITS_TYPER_PTA != 0;
+
+            // 장치/컬렉션 테이블 (한 페이지씩, 4KB 페이지, 단일 단계)
+            for n in 0..8 {
+                let reg = base + GITS_BASER + n * 8;
+                let baser = read64(reg);
+                let kind = (baser >> 56) & 0x7;
+                if kind != GITS_BASER_TYPE_DEVICE && kind != GITS_BASER_TYPE_COLLECTION {
+                    continue;
+                }
+                let table = alloc_zeroed(0).ok_or("out of memory")?;
+                let entry_size = baser & (0x1F << 48);
+                write64(reg, GITS_BASER_VALID | (kind << 56) | entry_size | table as u64);
+                if read64(reg) & GITS_BASER_VALID == 0 {
+                    return Err("table not accepted");
+                }
+            }
+
+            // 명령 큐 (한 페이지)
+            let queue = alloc_zeroed(0).ok_or("out of memory")?;
+            write64(base + GITS_CBASER, GITS_BASER_VALID | queue as u64);
+            write64(base + GITS_CWRITER, 0);
+            write32(base + GITS_CTLR, GITS_CTLR_ENABLED);
+
+            *ITS.lock() = Some(Its {
+                base,
+                queue,
+                write_offset: 0,
+                pta,
+                devices: Vec::new(),
+            });
+        }
+
+        let cpu = crate::proc::percpu::get_cpu_id();
+        map_collection(cpu, RD_BASES[cpu as usize].load(Ordering::Relaxed))?;
+        READY.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    /// ITS를 쓸 수 있는지
+    pub fn is_ready() -> bool {
+        READY.load(Ordering::Acquire)
+    }
+
+    fn with_its<T>(f: impl FnOnce(&mut Its) -> Result<T, &'static str>) -> Result<T, &'static str> {
+        match ITS.lock().as_mut() {
+            Some(its) => f(its),
+            None => Err("ITS not initialized"),
+        }
+    }
+
+    /// CPU의 컬렉션 등록 (컬렉션 번호 = CPU 번호)
+    pub(super) fn map_collection(cpu: u32, rd: usize) -> Result<(), &'static str> {
+        with_its(|its| {
+            let target = its.rd_target(rd);
+            its.command([CMD_MAPC, 0, GITS_BASER_VALID | target | cpu as u64, 0])?;
+            its.sync(cpu)
+        })
+    }
+
+    /// (DeviceID, EventID)를 LPI에 연결 (`cpu`의 컬렉션으로 전달)
+    pub fn map_event(device_id: u32, event_id: u32, intid: u32, cpu: u32) -> Result<(), &'static str> {
+        if device_id >= MAX_DEVICE_ID || event_id >= 1 << ITT_EVENT_BITS || intid < LPI_BASE {
+            return Err("id out of range");
+        }
+        with_its(|its| {
+            its.ensure_device(device_id)?;
+            let dev = (device_id as u64) << 32;
+            its.command([CMD_MAPTI | dev, event_id as u64 | ((intid as u64) << 32), cpu as u64, 0])?;
+            its.command([CMD_INV | dev, event_id as u64, 0, 0])?;
+            its.sync(cpu)
+        })
+    }
+
+    /// 연결 끊기 (펜딩 상태도 버림)
+    pub fn unmap_event(device_id: u32, event_id: u32) -> Result<(), &'static str> {
+        with_its(|its| {
+            its.command([CMD_DISCARD | ((device_id as u64) << 32), event_id as u64, 0, 0])?;
+            its.sync(0)
+        })
+    }
+
+    /// LPI 설정 변경(활성화/비활성화)을 재분배기에 반영
+    pub fn invalidate(device_id: u32, event_id: u32) -> Result<(), &'static str> {
+        with_its(|its| {
+            its.command([CMD_INV | ((device_id as u64) << 32), event_id as u64, 0, 0])?;
+            its.sync(0)
+        })
+    }
+
+    /// 장치가 이벤트를 보낸 것처럼 LPI 발생 (`INT` 명령, 테스트용)
+    pub fn trigger(device_id: u32, event_id: u32) -> Result<(), &'static str> {
+        with_its(|its| {
+            its.command([CMD_INT | ((device_id as u64) << 32), event_id as u64, 0, 0])?;
+            its.sync(0)
+        })
+    }
+}
@@ -0,0 +1,105 @@
+//! aarch64 kexec 트램펄린
+//!
+//! 새 커널 이미지는 현재 커널과 같은 주소(0x40080000)에 링크되어 있으므로
+//! 커널 코드 자신이 복사를 수행할 수 없습니다. 위치 독립적인 트램펄린을
+//! 별도 페이지로 옮긴 뒤 그곳에서 MMU/캐시를 끄고 세그먼트를 복사합니다.
+//!
+//! 트램펄린 입력 (Linux arm64 부트 프로토콜과 같은 레지스터로 점프):
+//! - x0: 복사 목록 주소 (`[dest, src, len]` u64 3개씩)
+//! - x1: 복사 목록 항목 수
+//! - x2: 새 커널 진입점
+//! - x3: DTB 주소 (점프 시 x0으로 전달)
+
+use core::arch::{asm, global_asm};
+
+global_asm!(
+    r#"
+    .section .text
+    .balign 8
+    .global kexec_trampoline_start
+    .global kexec_trampoline_end
+kexec_trampoline_start:
+    // MMU, D-cache, I-cache 비활성화 (identity mapping이므로 PC는 그대로 유효)
+    mrs     x4, sctlr_el1
+    bic     x4, x4, #(1 << 0)
+    bic     x4, x4, #(1 << 2)
+    bic     x4, x4, #(1 << 12)
+    msr     sctlr_el1, x4
+    isb
+
+1:  cbz     x1, 4f
+    ldp     x5, x6, [x0], #16       // dest, src
+    ldr     x7, [x0], #8            // len
+2:  cbz     x7, 3f
+    ldrb    w8, [x6], #1
+    strb    w8, [x5], #1
+    sub     x7, x7, #1
+    b       2b
+3:  sub     x1, x1, #1
+    b       1b
+
+4:  ic      iallu
+    dsb     sy
+    isb
+    mov     x9, x2
+    mov     x0, x3                  // x0 = DTB
+    mov     x1, xzr
+    mov     x2, xzr
+    mov     x3, xzr
+    br      x9
+kexec_trampoline_end:
+"#
+);
+
+unsafe extern "C" {
+    static kexec_trampoline_start: u8;
+    static kexec_trampoline_end: u8;
+}
+
+/// 트램펄린 코드 크기 (바이트)
+pub fn trampoline_size() -> usize {
+    unsafe {
+        &kexec_trampoline_end as *const u8 as usize - &kexec_trampoline_start as *const u8 as usize
+    }
+}
+
+/// 현재 CPU의 IRQ/FIQ 마스킹
+pub fn disable_interrupts() {
+    unsafe {
+        asm!("msr DAIFSet, #0xf");
+    }
+}
+
+/// 트램펄린을 `page`에 복사하고 실행 (반환하지 않음)
+///
+/// # Safety
+/// 인터럽트와 다른 CPU가 모두 정지된 상태여야 하며, `page`/`list`/스테이징
+/// 버퍼는 복사 대상 영역과 겹치지 않아야 함
+pub unsafe fn boot(page: usize, list: usize, count: usize, entry: usize, dtb: usize) -> ! {
+    let size = trampoline_size();
+    unsafe {
+        core::ptr::copy_nonoverlapping(
+            &kexec_trampoline_start as *const u8,
+            page as *mut u8,
+            size,
+        );
+    }
+    // 프레임은 identity 매핑에서 실행 불가이므로 트램펄린 페이지만 읽기+실행으로
+    let _ = super::mmu::protect_page(page, false, true);
+    super::cache::flush_range(page, size);
+
+    unsafe {
+        asm!(
+            "ic iallu",
+            "dsb sy",
+            "isb",
+            "br {tramp}",
+            tramp = in(reg) page,
+            in("x0") list,
+            in("x1") count,
+            in("x2") entry,
+            in("x3") dtb,
+            options(noreturn)
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! aarch64 kprobe 지원 (BRK + 하드웨어 단일 스텝)
+//!
+//! probe 주소의 명령어를 `brk #KPROBE_BRK_IMM`으로 바꿉니다. 적중하면 원래 명령어를
+//! 되돌리고 MDSCR_EL1.SS + SPSR.SS로 그 명령어 하나만 실행한 뒤, Software Step
+//! 예외에서 BRK를 다시 심습니다. 스텝 동안은 IRQ를 막아 인터럽트 핸들러로 스텝이
+//! 새지 않게 합니다.
+
+use core::arch::asm;
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use super::exception::ExceptionContext;
+use crate::proc::percpu::{self, MAX_CPUS};
+
+/// 적중 시 레지스터 컨텍스트
+pub type Regs = ExceptionContext;
+
+/// kprobe용 BRK 즉치값 (ESR_EL1.ISS로 구분, Linux와 같은 값)
+pub const KPROBE_BRK_IMM: u64 = 0x004;
+
+/// `brk #KPROBE_BRK_IMM`
+const BRK_INSN: u32 = 0xD420_0000 | ((KPROBE_BRK_IMM as u32) << 5);
+
+/// MDSCR_EL1 비트
+const MDSCR_SS: u64 = 1 << 0;
+const MDSCR_KDE: u64 = 1 << 13;
+
+/// SPSR 비트
+const SPSR_SS: u64 = 1 << 21;
+const SPSR_D: u64 = 1 << 9;
+const SPSR_I: u64 = 1 << 7;
+
+/// CPU별 스텝 중인 probe (ID + 1, 0 = 없음)
+static STEP_ID: [AtomicUsize; MAX_CPUS] = [const { AtomicUsize::new(0) }; MAX_CPUS];
+/// 스텝 전 SPSR의 D/I 비트
+static STEP_SPSR: [AtomicU64; MAX_CPUS] = [const { AtomicU64::new(0) }; MAX_CPUS];
+
+pub fn pc(regs: &Regs) -> usize {
+    regs.elr as usize
+}
+
+/// n번째 인자 레지스터 (x0~)
+pub fn arg(regs: &Regs, n: usize) -> u64 {
+    regs.gpr[n]
+}
+
+/// 복귀 주소 (x30)
+pub fn ret_addr(regs: &Regs) -> u64 {
+    regs.gpr[30]
+}
+
+pub fn read_insn(addr: usize) -> u32 {
+    unsafe { core::ptr::read_volatile(addr as *const u32) }
+}
+
+/// 명령어를 쓰고 I-cache와 동기화 (`gdb`도 사용)
+pub(super) fn patch(addr: usize, insn: u32) {
+    super::mmu::with_text_writable(addr, 4, || unsafe {
+        core::ptr::write_volatile(addr as *mut u32, insn);
+        asm!(
+            "dc cvau, {0}",
+            "dsb ish",
+            "ic ivau, {0}",
+            "dsb ish",
+            "isb",
+            in(reg) addr,
+            options(nostack)
+        );
+    });
+}
+
+pub fn arm(addr: usize) {
+    patch(addr, BRK_INSN);
+}
+
+pub fn disarm(addr: usize, orig: u32) {
+    patch(addr, orig);
+}
+
+pub fn is_break(addr: usize) -> bool {
+    read_insn(addr) == BRK_INSN
+}
+
+/// aarch64는 임시 브레이크포인트를 쓰지 않음
+pub fn is_step_break(_addr: usize) -> bool {
+    false
+}
+
+/// probe를 심을 수 있는 명령어인지 확인
+pub fn check_probe(addr: usize) -> Result<(), &'static str> {
+    if addr % 4 != 0 {
+        return Err("unaligned address");
+    }
+    let insn = read_insn(addr);
+    if insn & 0xFF00_0000 == 0xD400_0000 {
+        return Err("exception-generating instruction (svc/hvc/brk)");
+    }
+    if insn == 0xD69F_03E0 {
+        return Err("eret");
+    }
+    if insn & 0x3F00_0000 == 0x0800_0000 {
+        return Err("load/store exclusive (single-step breaks the monitor)");
+    }
+    // 시스템 레지스터/배리어는 스텝 중 바뀐 DAIF를 읽을 수 있음 (hint는 허용)
+    if insn & 0xFFC0_0000 == 0xD500_0000 && insn & 0xFFFF_F01F != 0xD503_201F {
+        return Err("system instruction");
+    }
+    Ok(())
+}
+
+/// 원래 명령어를 되돌리고 그 명령어 하나만 실행하도록 스텝 설정
+pub fn begin_step(id: usize, addr: usize, orig: u32, regs: &mut Regs) {
+    let cpu = percpu::get_cpu_id() as usize % MAX_CPUS;
+    disarm(addr, orig);
+
+    STEP_ID[cpu].store(id + 1, Ordering::Relaxed);
+    STEP_SPSR[cpu].store(regs.spsr & (SPSR_D | SPSR_I), Ordering::Relaxed);
+    // 복귀 후 디버그 예외 허용(D=0), IRQ 차단, 스텝 활성
+    regs.spsr = (regs.spsr & !SPSR_D) | SPSR_I | SPSR_SS;
+
+    unsafe {
+        asm!(
+            "msr oslar_el1, xzr",
+            "mrs {t}, mdscr_el1",
+            "orr {t}, {t}, {bits}",
+            "msr mdscr_el1, {t}",
+            "isb",
+            t = out(reg) _,
+            bits = in(reg) MDSCR_SS | MDSCR_KDE,
+            options(nostack)
+        );
+    }
+}
+
+/// Software Step 예외 처리 (같은 EL)
+///
+/// 이 CPU가 kprobe 스텝 중이었으면 스텝을 끄고 브레이크포인트를 되살린 뒤 true
+pub fn handle_step(regs: &mut Regs) -> bool {
+    let cpu = percpu::get_cpu_id() as usize % MAX_CPUS;
+    let id = STEP_ID[cpu].swap(0, Ordering::Relaxed);
+
+    unsafe {
+        asm!(
+            "mrs {t}, mdscr_el1",
+            "bic {t}, {t}, {bits}",
+            "msr mdscr_el1, {t}",
+            "isb",
+            t = out(reg) _,
+            bits = in(reg) MDSCR_SS | MDSCR_KDE,
+            options(nostack)
+        );
+    }
+    if id == 0 {
+        return false;
+    }
+
+    let saved = STEP_SPSR[cpu].load(Ordering::Relaxed);
+    regs.spsr = (regs.spsr & !(SPSR_SS | SPSR_D | SPSR_I)) | saved;
+    crate::trace::kprobe::end_step(id - 1);
+    true
+}
@@ -2,10 +2,16 @@
 //!
 //! 4-level 페이지 테이블을 사용한 가상 메모리 관리
 //! Higher-half kernel: 커널은 0xFFFF_0000_0000_0000 이상에 매핑
+//!
+//! RAM identity 매핑은 구간별 권한으로 만듭니다: 커널 `.text`는 읽기+실행, `.rodata`/`.ksymtab`은
+//! 읽기 전용, 나머지 RAM(`.data`/`.bss`, 스택, 힙, 프레임)은 읽기/쓰기 실행 불가, MMIO는 디바이스
+//! 메모리입니다.
 
 use crate::kprintln;
 use crate::mm;
+use crate::sync::IrqSpinlock;
 use core::arch::asm;
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 /// Higher-half 커널 베이스 주소
 pub const KERNEL_VIRT_BASE: usize = 0xFFFF_0000_0000_0000;
@@ -21,6 +27,9 @@ impl PageTableEntry {
     const BLOCK: u64 = 0 << 1; // Level 1-2: 블록 매핑 (bit 1 = 0)
     const PAGE: u64 = 1 << 1; // Level 3: 실제 페이지
     const AF: u64 = 1 << 10; // Access Flag
+    const AP_RO: u64 = 1 << 7; // AP[2]: 읽기 전용
+    const UXN: u64 = 1 << 53;
+    const PXN: u64 = 1 << 54;
     const ATTR_IDX_SHIFT: u64 = 2;
     const SH_INNER: u64 = 3 << 8; // Inner shareable
 
@@ -56,9 +65,47 @@ impl PageTableEntry {
     pub fn addr(&self) -> usize {
         (self.0 & 0x0000_FFFF_FFFF_F000) as usize
     }
+
+    /// 권한 비트만 바꾼 엔트리 (주소와 메모리 속성 유지)
+    fn with_permissions(self, write: bool, execute: bool) -> Self {
+        let flags = PageFlags::kernel_data(write, execute);
+        Self(self.0 & !(Self::AP_RO | Self::UXN | Self::PXN) | flags.to_bits())
+    }
+
+    /// 메모리 속성(MAIR 인덱스)만 바꾼 엔트리
+    fn with_attr_idx(self, attr_idx: u8) -> Self {
+        Self(self.0 & !(0x7 << Self::ATTR_IDX_SHIFT) | (attr_idx as u64) << Self::ATTR_IDX_SHIFT)
+    }
+
+    /// (쓰기 가능, EL1 실행 가능)
+    fn permissions(&self) -> (bool, bool) {
+        (self.0 & Self::AP_RO == 0, self.0 & Self::PXN == 0)
+    }
+}
+
+/// 활성화된 루트 페이지 테이블 주소 (런타임 매핑용, 0 = MMU 미활성)
+static ROOT_TABLE: AtomicUsize = AtomicUsize::new(0);
+
+/// 런타임 범위 매핑과 권한 변경 직렬화 (같은 2MB 블록을 두 번 나누지 않도록)
+///
+/// kprobe가 예외 핸들러에서 권한을 바꾸므로 인터럽트를 끄고 잡습니다.
+static MAP_LOCK: IrqSpinlock<()> = IrqSpinlock::new(());
+
+/// 매핑 종류 (`map_range`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapKind {
+    /// 읽기+실행 (커널 `.text`)
+    Text,
+    /// 읽기 전용 (`.rodata`)
+    ReadOnly,
+    /// 읽기/쓰기, 실행 불가
+    Data,
+    /// 디바이스 메모리 (MMIO, 실행 불가)
+    Device,
 }
 
 /// 페이지 속성
+#[derive(Clone, Copy)]
 pub struct PageFlags {
     pub attr_idx: u8, // MAIR 인덱스
     pub write: bool,
@@ -66,11 +113,20 @@ pub struct PageFlags {
 }
 
 impl PageFlags {
-    pub fn kernel_rwx() -> Self {
+    pub fn of(kind: MapKind) -> Self {
+        match kind {
+            MapKind::Text => Self::kernel_data(false, true),
+            MapKind::ReadOnly => Self::kernel_data(false, false),
+            MapKind::Data => Self::kernel_data(true, false),
+            MapKind::Device => Self::device(),
+        }
+    }
+
+    pub fn kernel_data(write: bool, execute: bool) -> Self {
         Self {
             attr_idx: 1, // Normal memory
-            write: true,
-            execute: true,
+            write,
+            execute,
         }
     }
 
@@ -133,8 +189,6 @@ pub fn alloc_page_table() -> Option<&'static mut PageTable> {
     // 페이지 프레임 할당자에서 4KB 메모리 할당
     let frame = mm::page::alloc_frame()?;
 
-    kprintln!("[MMU] Allocated page table at {:#x}", frame);
-
     // 물리 주소를 PageTable 구조체로 변환
     let page_table = unsafe { &mut *(frame as *mut PageTable) };
 
@@ -177,6 +231,12 @@ impl PageTableManager {
         let l1_table = unsafe { Self::get_or_create_next_level_raw(l0_ptr, l0_idx)? };
         let l2_table = unsafe { Self::get_or_create_next_level_raw(l1_table, l1_idx)? };
 
+        // 이미 4KB 페이지로 매핑된 구간을 블록으로 덮지 않음
+        let old = unsafe { (*l2_table).entry(l2_idx) };
+        if old.is_valid() && old.0 & PageTableEntry::TABLE != 0 {
+            return Err("Range already mapped with pages");
+        }
+
         // Level 2에 블록 엔트리 생성
         let entry = PageTableEntry::new_block(phys_addr, flags);
         unsafe { (*l2_table).set_entry(l2_idx, entry) };
@@ -208,6 +268,161 @@ impl PageTableManager {
         Ok(())
     }
 
+    /// `[virt_addr, virt_addr + size)` → `[phys_addr, phys_addr + size)` 매핑
+    ///
+    /// 둘 다 2MB 정렬된 구간은 블록으로, 나머지는 4KB 페이지로 매핑합니다.
+    pub fn map_range(
+        &mut self,
+        virt_addr: usize,
+        phys_addr: usize,
+        size: usize,
+        flags: PageFlags,
+    ) -> Result<(), &'static str> {
+        if (virt_addr | phys_addr) & 0xFFF != 0 {
+            return Err("Address must be 4KB aligned");
+        }
+        let end = virt_addr + size.next_multiple_of(4096);
+        let mut virt = virt_addr;
+        while virt < end {
+            let phys = phys_addr + (virt - virt_addr);
+            if (virt | phys) & 0x1F_FFFF == 0 && end - virt >= 0x20_0000 {
+                self.map_2mb_block(virt, phys, flags)?;
+                virt += 0x20_0000;
+            } else {
+                self.map_page(virt, phys, flags)?;
+                virt += 4096;
+            }
+        }
+        Ok(())
+    }
+
+    /// 4KB 페이지 매핑 해제
+    ///
+    /// # Returns
+    /// 매핑되어 있던 물리 주소
+    pub fn unmap_page(&mut self, virt_addr: usize) -> Option<usize> {
+        let l3_table = self.walk_to_l3(virt_addr)?;
+        let l3_idx = (virt_addr >> 12) & 0x1FF;
+
+        let entry = unsafe { (*l3_table).entry(l3_idx) };
+        if !entry.is_valid() {
+            return None;
+        }
+        unsafe { (*l3_table).set_entry(l3_idx, PageTableEntry::empty()) };
+        Some(entry.addr())
+    }
+
+    /// 가상 주소 → 물리 주소 변환 (블록 매핑 포함)
+    pub fn translate(&self, virt_addr: usize) -> Option<usize> {
+        let mut table = self.l0_table as *const PageTable;
+        for level in 0..4 {
+            let shift = 39 - level * 9;
+            let idx = (virt_addr >> shift) & 0x1FF;
+            let entry = unsafe { (*table).entry(idx) };
+            if !entry.is_valid() {
+                return None;
+            }
+            // Level 1/2의 bit 1 = 0 이면 블록, Level 3는 항상 페이지
+            let is_block = level < 3 && entry.0 & PageTableEntry::TABLE == 0;
+            if level == 3 || is_block {
+                let offset_mask = (1usize << shift) - 1;
+                let base = entry.addr() & !offset_mask;
+                return Some(base | (virt_addr & offset_mask));
+            }
+            table = entry.addr() as *const PageTable;
+        }
+        None
+    }
+
+    /// Level 3 테이블까지 탐색 (테이블 생성 없음)
+    fn walk_to_l3(&mut self, virt_addr: usize) -> Option<*mut PageTable> {
+        let mut table = self.l0_table as *mut PageTable;
+        for level in 0..3 {
+            let idx = (virt_addr >> (39 - level * 9)) & 0x1FF;
+            let entry = unsafe { (*table).entry(idx) };
+            if !entry.is_valid() || entry.0 & PageTableEntry::TABLE == 0 {
+                return None;
+            }
+            table = entry.addr() as *mut PageTable;
+        }
+        Some(table)
+    }
+
+    /// `[virt_addr, virt_addr + size)` → `[phys_addr, phys_addr + size)`를 블록 없이 4KB 페이지로 매핑
+    pub fn map_pages(
+        &mut self,
+        virt_addr: usize,
+        phys_addr: usize,
+        size: usize,
+        flags: PageFlags,
+    ) -> Result<(), &'static str> {
+        if (virt_addr | phys_addr) & 0xFFF != 0 {
+            return Err("Address must be 4KB aligned");
+        }
+        for offset in (0..size).step_by(4096) {
+            self.map_page(virt_addr + offset, phys_addr + offset, flags)?;
+        }
+        Ok(())
+    }
+
+    /// 4KB 페이지의 Level 3 엔트리 (2MB 블록이면 같은 속성의 페이지 512개로 나눔)
+    ///
+    /// 블록을 테이블로 바꾸는 것은 매핑 크기 변경이라 break-before-make가 필요합니다: 블록을
+    /// 무효화하고 블록 구간의 TLB를 비운 뒤 같은 속성으로 채운 L3 테이블을 넣습니다. 그 사이에
+    /// 블록에 접근하면 폴트가 나므로 다른 CPU가 켜져 있으면 나누지 않습니다. RAM은 부팅 때
+    /// 4KB 페이지로 매핑하므로(`map_ram`) 커널 코드, 스택, 힙, 프레임이 든 블록은 없고,
+    /// 나눌 수 있는 것은 런타임 `map_range`가 만든 블록뿐입니다.
+    fn split_to_l3(&mut self, virt_addr: usize) -> Result<*mut PageTableEntry, &'static str> {
+        let mut table = self.l0_table as *mut PageTable;
+        for level in 0..3 {
+            let idx = (virt_addr >> (39 - level * 9)) & 0x1FF;
+            let entry = unsafe { (*table).entry(idx) };
+            if !entry.is_valid() {
+                return Err("Address not mapped");
+            }
+            if entry.0 & PageTableEntry::TABLE == 0 {
+                if level != 2 {
+                    return Err("Cannot split 1GB block");
+                }
+                if crate::proc::percpu::online_count() > 1 {
+                    return Err("Cannot split a live 2MB block with other CPUs online");
+                }
+                let l3 = alloc_page_table().ok_or("Failed to allocate page table")?;
+                let attrs = entry.0 & !0x0000_FFFF_FFFF_F000;
+                for i in 0..512 {
+                    let page = PageTableEntry(attrs | PageTableEntry::PAGE | (entry.addr() + i * 4096) as u64);
+                    l3.set_entry(i, page);
+                }
+                let l3_addr = l3 as *mut PageTable as usize;
+                // break-before-make: 무효화 → 블록 구간 TLB 비움 → 테이블
+                unsafe { (*table).set_entry(idx, PageTableEntry::empty()) };
+                flush_tlb_range(virt_addr & !0x1F_FFFF, 0x20_0000);
+                unsafe { (*table).set_entry(idx, PageTableEntry::new_table(l3_addr)) };
+                unsafe { asm!("dsb ishst", "isb") };
+            }
+            table = unsafe { (*table).entry(idx) }.addr() as *mut PageTable;
+        }
+        let idx = (virt_addr >> 12) & 0x1FF;
+        Ok(unsafe { &mut (*table).entries[idx] as *mut PageTableEntry })
+    }
+
+    /// 매핑된 주소의 (쓰기 가능, 실행 가능)
+    fn permissions(&self, virt_addr: usize) -> Option<(bool, bool)> {
+        let mut table = self.l0_table as *const PageTable;
+        for level in 0..4 {
+            let idx = (virt_addr >> (39 - level * 9)) & 0x1FF;
+            let entry = unsafe { (*table).entry(idx) };
+            if !entry.is_valid() {
+                return None;
+            }
+            if level == 3 || entry.0 & PageTableEntry::TABLE == 0 {
+                return Some(entry.permissions());
+            }
+            table = entry.addr() as *const PageTable;
+        }
+        None
+    }
+
     /// 다음 레벨 테이블 가져오기 또는 생성 (raw 포인터 버전)
     unsafe fn get_or_create_next_level_raw(
         table: *mut PageTable,
@@ -216,6 +431,9 @@ impl PageTableManager {
         let entry = unsafe { (*table).entry(index) };
 
         if entry.is_valid() {
+            if entry.0 & PageTableEntry::TABLE == 0 {
+                return Err("Entry is already a block mapping");
+            }
             // 이미 존재하는 테이블
             let addr = entry.addr();
             Ok(addr as *mut PageTable)
@@ -235,9 +453,192 @@ impl PageTableManager {
     pub fn root_table_addr(&self) -> usize {
         self.l0_table as *const PageTable as usize
     }
+
+    /// 현재 활성화된 루트 테이블로 매니저 생성
+    fn active() -> Option<Self> {
+        let root = ROOT_TABLE.load(Ordering::Acquire);
+        if root == 0 {
+            return None;
+        }
+        Some(Self {
+            l0_table: unsafe { &mut *(root as *mut PageTable) },
+        })
+    }
+}
+
+/// 단일 페이지 TLB 무효화 (모든 CPU)
+fn flush_tlb_page(virt_addr: usize) {
+    unsafe {
+        asm!(
+            "dsb ishst",
+            "tlbi vaae1is, {}",
+            "dsb ish",
+            "isb",
+            in(reg) (virt_addr >> 12) as u64,
+        );
+    }
 }
 
-/// Identity mapping 생성 (커널 영역)
+/// `[virt_addr, virt_addr + size)`의 TLB 무효화 (모든 CPU)
+fn flush_tlb_range(virt_addr: usize, size: usize) {
+    unsafe {
+        asm!("dsb ishst");
+        for page in (virt_addr..virt_addr + size).step_by(4096) {
+            asm!("tlbi vaae1is, {}", in(reg) (page >> 12) as u64);
+        }
+        asm!("dsb ish", "isb");
+    }
+}
+
+/// 전체 TLB 무효화 (모든 CPU)
+fn flush_tlb_all() {
+    unsafe {
+        asm!("dsb ishst", "tlbi vmalle1is", "dsb ish", "isb");
+    }
+}
+
+/// 런타임 4KB 페이지 매핑 (demand paging용)
+///
+/// MMU 활성화 이후 현재 페이지 테이블에 매핑을 추가합니다.
+pub fn map_page(virt_addr: usize, phys_addr: usize, write: bool, execute: bool) -> Result<(), &'static str> {
+    let mut pt_mgr = PageTableManager::active().ok_or("MMU not enabled")?;
+    pt_mgr.map_page(virt_addr, phys_addr, PageFlags::kernel_data(write, execute))?;
+    flush_tlb_page(virt_addr);
+    Ok(())
+}
+
+/// 런타임 4KB 페이지 매핑 해제
+///
+/// # Returns
+/// 매핑되어 있던 물리 주소
+pub fn unmap_page(virt_addr: usize) -> Option<usize> {
+    let mut pt_mgr = PageTableManager::active()?;
+    let phys = pt_mgr.unmap_page(virt_addr)?;
+    flush_tlb_page(virt_addr);
+    Some(phys)
+}
+
+/// 현재 페이지 테이블 기준 가상 → 물리 주소 변환
+pub fn translate(virt_addr: usize) -> Option<usize> {
+    PageTableManager::active()?.translate(virt_addr)
+}
+
+/// 런타임 범위 매핑 (주소는 4KB 정렬, 이미 매핑된 블록과 겹치면 실패)
+pub fn map_range(virt_addr: usize, phys_addr: usize, size: usize, kind: MapKind) -> Result<(), &'static str> {
+    let mut pt_mgr = PageTableManager::active().ok_or("MMU not enabled")?;
+    let _guard = MAP_LOCK.lock();
+    pt_mgr.map_range(virt_addr, phys_addr, size, PageFlags::of(kind))?;
+    flush_tlb_all();
+    Ok(())
+}
+
+/// `[virt_addr, virt_addr + size)`에 걸친 매핑된 4KB 페이지들의 권한 변경
+///
+/// RAM은 부팅 때부터 4KB 페이지입니다. 런타임 `map_range`가 만든 2MB 블록 안의 페이지면
+/// 블록을 먼저 같은 속성의 4KB 페이지로 나누며(다른 CPU가 켜지기 전만), 다시 합치지 않습니다.
+pub fn protect_range(virt_addr: usize, size: usize, write: bool, execute: bool) -> Result<(), &'static str> {
+    let mut pt_mgr = PageTableManager::active().ok_or("MMU not enabled")?;
+    let _guard = MAP_LOCK.lock();
+    for page in (virt_addr & !0xFFF..virt_addr + size).step_by(4096) {
+        let entry = pt_mgr.split_to_l3(page)?;
+        unsafe {
+            *entry = (*entry).with_permissions(write, execute);
+        }
+        flush_tlb_page(page);
+    }
+    Ok(())
+}
+
+/// `[virt_addr, virt_addr + size)`에 걸친 매핑된 4KB 페이지들을 Normal Write-Back(`true`)
+/// 또는 Normal Non-cacheable(`false`)로 변경 (캐시 일관성 없는 DMA 버퍼용)
+///
+/// 메모리 종류를 바꾸므로 각 엔트리를 무효화하고 TLB를 비운 뒤 새 엔트리를 씁니다
+/// (break-before-make). 그 사이 다른 CPU가 접근하면 폴트가 나므로 아직 다른 곳에 넘기지
+/// 않은 페이지에만 씁니다. 캐시에 남은 라인 정리는 호출자 몫입니다.
+pub fn set_cacheable(virt_addr: usize, size: usize, cacheable: bool) -> Result<(), &'static str> {
+    let mut pt_mgr = PageTableManager::active().ok_or("MMU not enabled")?;
+    let attr_idx = if cacheable { 1 } else { 2 }; // MAIR Attr1 / Attr2
+    let _guard = MAP_LOCK.lock();
+    for page in (virt_addr & !0xFFF..virt_addr + size).step_by(4096) {
+        let entry = pt_mgr.split_to_l3(page)?;
+        unsafe {
+            let new = (*entry).with_attr_idx(attr_idx);
+            *entry = PageTableEntry::empty();
+            flush_tlb_page(page);
+            *entry = new;
+        }
+        flush_tlb_page(page);
+    }
+    Ok(())
+}
+
+/// 매핑된 4KB 페이지의 권한 변경 (모듈 W^X용)
+pub fn protect_page(virt_addr: usize, write: bool, execute: bool) -> Result<(), &'static str> {
+    protect_range(virt_addr, 1, write, execute)
+}
+
+/// 현재 페이지 테이블 기준 (쓰기 가능, 실행 가능), 매핑되지 않았으면 None
+pub fn page_permissions(virt_addr: usize) -> Option<(bool, bool)> {
+    PageTableManager::active()?.permissions(virt_addr)
+}
+
+/// `[addr, addr + len)`의 읽기 전용 페이지(커널 `.text`, W^X로 보호한 모듈 `.text`)를 잠깐
+/// 쓰기 가능하게 바꿔 `f` 실행 (kprobe 명령어 패치용)
+pub fn with_text_writable<R>(addr: usize, len: usize, f: impl FnOnce() -> R) -> R {
+    let mut restore = [None; 2];
+    for (slot, page) in restore.iter_mut().zip((addr & !0xFFF..addr + len).step_by(4096)) {
+        if let Some((false, execute)) = page_permissions(page) {
+            if protect_page(page, true, execute).is_ok() {
+                *slot = Some((page, execute));
+            }
+        }
+    }
+    let result = f();
+    for (page, execute) in restore.into_iter().flatten() {
+        let _ = protect_page(page, false, execute);
+    }
+    result
+}
+
+/// 커널 이미지의 (`.text` 시작, `.text` 끝 = 읽기 전용 시작, 읽기 전용 끝), 링커 스크립트가 4KB 정렬
+fn kernel_image() -> (usize, usize, usize) {
+    unsafe extern "C" {
+        static _text: u8;
+        static _etext: u8;
+        static _erodata: u8;
+    }
+    unsafe {
+        (
+            &_text as *const u8 as usize,
+            &_etext as *const u8 as usize,
+            &_erodata as *const u8 as usize,
+        )
+    }
+}
+
+/// RAM `[start, end)` identity 매핑 (커널 `.text`는 읽기+실행, `.rodata`는 읽기 전용, 나머지는 데이터)
+///
+/// 모듈 W^X, DMA 버퍼 같은 런타임 권한/속성 변경이 살아 있는 블록을 나누지 않도록 2MB 정렬
+/// 구간도 블록 없이 4KB 페이지로 매핑합니다 (MMU를 켜기 전에 만들므로 break-before-make가 필요 없음).
+fn map_ram(pt_mgr: &mut PageTableManager, start: usize, end: usize) -> Result<(), &'static str> {
+    let (start, end) = (start & !0xFFF, end.next_multiple_of(4096));
+    let (text, etext, erodata) = kernel_image();
+    let pieces = [
+        (start, text, MapKind::Data),
+        (text, etext, MapKind::Text),
+        (etext, erodata, MapKind::ReadOnly),
+        (erodata, end, MapKind::Data),
+    ];
+    for (from, to, kind) in pieces {
+        let (from, to) = (from.max(start), to.min(end));
+        if from < to {
+            pt_mgr.map_pages(from, from, to - from, PageFlags::of(kind))?;
+        }
+    }
+    Ok(())
+}
+
+/// Identity mapping 생성 (RAM은 구간별 권한, MMIO는 디바이스 메모리)
 pub fn create_identity_mapping(
     ram_start: usize,
     ram_size: usize,
@@ -246,32 +647,56 @@ pub fn create_identity_mapping(
 
     kprintln!("[MMU] Creating identity mapping...");
 
-    // 커널 영역을 2MB 블록으로 매핑
-    let block_size = 2 * 1024 * 1024; // 2MB
-
     // Identity mapping (물리 주소 = 가상 주소)
+    let (text, etext, erodata) = kernel_image();
     kprintln!("[MMU] Identity mapping RAM...");
-    for offset in (0..ram_size).step_by(block_size) {
-        let addr = ram_start + offset;
-        let aligned_addr = addr & !0x1F_FFFF;
-        pt_mgr.map_2mb_block(aligned_addr, aligned_addr, PageFlags::kernel_rwx())?;
+    kprintln!("      .text   {:#x} - {:#x} (RX)", text, etext);
+    kprintln!("      .rodata {:#x} - {:#x} (RO)", etext, erodata);
+    map_ram(&mut pt_mgr, ram_start, ram_start + ram_size)?;
+
+    // 추가 RAM 뱅크 (DTB에 memory 영역이 여러 개인 경우, 0번은 위에서 매핑함)
+    for bank in crate::mm::memory_banks().as_slice().iter().skip(1) {
+        map_ram(&mut pt_mgr, bank.base, bank.end())?;
     }
 
-    // MMIO 영역: UART (0x09000000)
+    // MMIO는 디바이스 메모리 (Device-nGnRnE, 실행 불가)
+    let device = PageFlags::of(MapKind::Device);
+
+    // MMIO 영역: UART (0x09000000, 시리얼 포트가 더 있으면 모두)
     kprintln!("[MMU] Mapping UART MMIO...");
-    pt_mgr.map_page(0x0900_0000, 0x0900_0000, PageFlags::device())?;
+    pt_mgr.map_page(0x0900_0000, 0x0900_0000, device)?;
+    for uart in crate::drivers::config::uarts() {
+        if uart.base != 0x0900_0000 {
+            pt_mgr.map_page(uart.base, uart.base, device)?;
+        }
+    }
+
+    // MMIO 영역: PL031 RTC (0x09010000)
+    kprintln!("[MMU] Mapping RTC MMIO...");
+    pt_mgr.map_page(0x0901_0000, 0x0901_0000, device)?;
 
     // MMIO 영역: GIC (0x08000000 - 0x08020000)
     kprintln!("[MMU] Mapping GIC MMIO...");
-    pt_mgr.map_page(0x0800_0000, 0x0800_0000, PageFlags::device())?; // GICD
-    pt_mgr.map_page(0x0801_0000, 0x0801_0000, PageFlags::device())?; // GICC
+    pt_mgr.map_page(0x0800_0000, 0x0800_0000, device)?; // GICD
+    pt_mgr.map_page(0x0801_0000, 0x0801_0000, device)?; // GICC
+    if let Some(gic) = crate::drivers::config::gic_config()
+        .filter(|g| g.version == crate::drivers::config::GicVersion::V3)
+    {
+        // GICv3: GICD 64KB, CPU마다 재분배기 128KB(VLPI면 256KB), ITS 128KB
+        let gicr_size = gic.redistributor_size.min(crate::proc::percpu::MAX_CPUS * 0x4_0000);
+        let regions = [
+            (gic.distributor_base, 0x1_0000),
+            (gic.redistributor_base.unwrap_or(0), gicr_size),
+            (gic.its_base.unwrap_or(0), 0x2_0000),
+        ];
+        for (base, size) in regions.into_iter().filter(|&(base, _)| base != 0) {
+            pt_mgr.map_range(base, base, size, device)?;
+        }
+    }
 
     // MMIO 영역: VirtIO (0x0a000000 - 0x0a004000, 32개 슬롯)
     kprintln!("[MMU] Mapping VirtIO MMIO...");
-    for i in 0..4 {
-        let addr = 0x0a00_0000 + i * 0x1000;
-        pt_mgr.map_page(addr, addr, PageFlags::device())?;
-    }
+    pt_mgr.map_range(0x0a00_0000, 0x0a00_0000, 4 * 0x1000, device)?;
 
     kprintln!("[MMU] Identity mapping created");
     kprintln!("      Root table at: {:#x}", pt_mgr.root_table_addr());
@@ -289,7 +714,8 @@ pub unsafe fn enable_mmu(pt_addr: usize) {
     unsafe {
         // 1. MAIR_EL1 설정 (Memory Attribute Indirection Register)
         let mair_value: u64 = (0x00 << 0) |  // Attr0: Device-nGnRnE
-            (0xFF << 8); // Attr1: Normal, Inner/Outer WB
+            (0xFF << 8) |  // Attr1: Normal, Inner/Outer WB
+            (0x44 << 16); // Attr2: Normal, Inner/Outer Non-cacheable (DMA)
 
         asm!("msr MAIR_EL1, {}", in(reg) mair_value);
 
@@ -335,6 +761,17 @@ pub unsafe fn enable_mmu(pt_addr: usize) {
     kprintln!("[MMU] MMU enabled!");
 }
 
+/// Secondary CPU MMU 활성화
+///
+/// Primary CPU가 만든 페이지 테이블을 공유하여 demand paging된
+/// 매핑이 모든 CPU에서 보이도록 합니다.
+pub fn init_secondary() {
+    let root = ROOT_TABLE.load(Ordering::Acquire);
+    if root != 0 {
+        unsafe { enable_mmu(root) };
+    }
+}
+
 /// MMU 초기화
 pub fn init(ram_start: usize, ram_size: usize) -> Result<(), &'static str> {
     kprintln!("\n[MMU] Initializing...");
@@ -346,16 +783,19 @@ pub fn init(ram_start: usize, ram_size: usize) -> Result<(), &'static str> {
     unsafe {
         enable_mmu(pt_mgr.root_table_addr());
     }
+    ROOT_TABLE.store(pt_mgr.root_table_addr(), Ordering::Release);
 
-    // 3. 테스트: 메모리 접근
-    let test_addr = (ram_start + 0x87000) as *mut u32;
+    // 3. 테스트: 메모리 접근 (스택은 데이터 매핑, 커널 `.text`는 읽기+실행)
+    let mut test_val = 0u32;
     unsafe {
-        *test_addr = 0xDEADBEEF;
-        let read_val = *test_addr;
-        if read_val != 0xDEADBEEF {
+        core::ptr::write_volatile(&mut test_val, 0xDEADBEEF);
+        if core::ptr::read_volatile(&test_val) != 0xDEADBEEF {
             return Err("MMU test failed: memory access incorrect");
         }
     }
+    if page_permissions(kernel_image().0) != Some((false, true)) {
+        return Err("MMU test failed: kernel text is not read+execute");
+    }
 
     kprintln!("[MMU] Test passed: Memory access works!");
 
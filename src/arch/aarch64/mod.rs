@@ -1,6 +1,13 @@
+pub mod cache;
+pub mod cpu;
 pub mod exception;
+pub mod gdb;
 pub mod gic;
+pub mod gicv3;
+pub mod kexec;
+pub mod kprobe;
 pub mod mmu;
+pub mod smp;
 pub mod timer;
 pub mod uart;
 
@@ -0,0 +1,118 @@
+//! 보조 CPU 부팅 (AArch64)
+//!
+//! DTB `/cpus`의 CPU 노드마다 PSCI `CPU_ON`으로 보조 CPU를 켭니다. 보드마다 다른 코드가
+//! 없으므로 PSCI를 지원하는 플랫폼이면 그대로 동작합니다.
+//!
+//! - `CPU_ON`의 대상은 노드의 `reg`(MPIDR 어피니티)이고, 논리 CPU 번호는 `get_cpu_id()`와
+//!   같은 Aff0입니다. 번호가 `cpu_count` 이상(`maxcpus=`)이거나 부팅 CPU와 Aff0이 겹치는
+//!   CPU(다른 클러스터)는 켜지 않습니다.
+//! - `enable-method`가 `psci`가 아닌 CPU(`spin-table` 등)는 지원하지 않아 건너뜁니다.
+//! - DTB에 CPU 노드가 없으면 MPIDR = CPU 번호로 가정합니다 (QEMU virt 배치).
+
+use crate::drivers::psci;
+
+/// 보조 CPU 시작 진입점
+///
+/// `psci::cpu_on`으로 시작된 보조 CPU가 처음 실행하는 코드입니다.
+/// context_id(논리 CPU 번호)를 x0으로 받습니다.
+#[unsafe(no_mangle)]
+pub extern "C" fn secondary_cpu_entry(context_id: usize) -> ! {
+    let cpu_id = context_id as u32;
+
+    // 1. 스택 설정 (primary CPU가 미리 할당해둔 스택 사용)
+    let stack_top = crate::proc::percpu::stacks::get_stack_top(cpu_id);
+    if stack_top != 0 {
+        unsafe {
+            core::arch::asm!(
+                "mov sp, {0}",
+                in(reg) stack_top,
+            );
+        }
+    }
+
+    // 2. 예외 벡터 설정 및 MMU 활성화 (primary 페이지 테이블 공유)
+    crate::arch::exception::init();
+    crate::arch::mmu::init_secondary();
+
+    // 3. GIC CPU Interface 초기화 (GICD는 primary가 이미 완료)
+    crate::arch::gic::init_secondary();
+
+    // 4. 타이머 초기화
+    crate::arch::timer::init_secondary();
+
+    // 5. Per-CPU 데이터 초기화 및 온라인 표시
+    crate::proc::percpu::init_secondary(cpu_id);
+
+    // 6. 이 CPU의 idle 스레드 생성 (스케줄러에서 사용)
+    crate::proc::init_on_secondary_cpu(cpu_id);
+
+    crate::kprintln!("[smp] CPU {} online", cpu_id);
+
+    // 7. 인터럽트 활성화
+    unsafe {
+        core::arch::asm!("msr DAIFClr, #2"); // IRQ unmask
+    }
+
+    // 8. idle 루프 (할 일이 없으면 다음 기한까지 틱을 끄고 대기, 오프라인으로 내리면 올릴 때까지 대기)
+    loop {
+        crate::proc::hotplug::park_if_offline();
+        crate::time::nohz::idle();
+    }
+}
+
+/// 현재 CPU의 MPIDR 어피니티 (Aff3[39:32] Aff2 Aff1 Aff0, DTB `reg` 형식)
+pub fn current_mpidr() -> u64 {
+    let mpidr: u64;
+    unsafe { core::arch::asm!("mrs {}, MPIDR_EL1", out(reg) mpidr) };
+    mpidr & 0xFF_00FF_FFFF
+}
+
+/// 논리 번호가 `cpu_count`보다 작은 보조 CPU 모두 시작
+pub fn start_secondary_cpus(cpu_count: usize) {
+    let entry = secondary_cpu_entry as *const () as usize;
+    let boot_mpidr = current_mpidr();
+
+    // (논리 번호, MPIDR)
+    let mut targets: alloc::vec::Vec<(usize, u64)> = alloc::vec::Vec::new();
+    let cpus = crate::dtb::get().map(|dt| dt.find_cpus()).unwrap_or_default();
+    if cpus.is_empty() {
+        targets.extend((1..cpu_count).map(|id| (id, id as u64)));
+    }
+    for cpu in cpus {
+        let id = (cpu.mpidr & 0xFF) as usize;
+        if cpu.mpidr == boot_mpidr {
+            continue;
+        }
+        if let Some(method) = cpu.enable_method.as_deref().filter(|m| *m != "psci") {
+            crate::kprintln!("[smp] {}: enable-method {} not supported, skipped", cpu.name, method);
+            continue;
+        }
+        if id == (boot_mpidr & 0xFF) as usize || targets.iter().any(|&(t, _)| t == id) {
+            crate::kprintln!("[smp] {}: MPIDR {:#x} aliases CPU {}, skipped", cpu.name, cpu.mpidr, id);
+            continue;
+        }
+        if id < cpu_count {
+            targets.push((id, cpu.mpidr));
+        }
+    }
+
+    crate::kprintln!("[smp] Starting {} secondary CPUs via PSCI", targets.len());
+    for (cpu_id, mpidr) in targets {
+        match psci::cpu_on(mpidr, entry, cpu_id) {
+            psci::error::SUCCESS => {
+                crate::kprintln!("[smp] CPU {} started", cpu_id);
+            }
+            psci::error::ALREADY_ON => {
+                crate::kprintln!("[smp] CPU {} already on", cpu_id);
+            }
+            err => {
+                crate::kprintln!(
+                    "[smp] Failed to start CPU {} (MPIDR {:#x}): {}",
+                    cpu_id,
+                    mpidr,
+                    psci::error::name(err)
+                );
+            }
+        }
+    }
+}
@@ -14,7 +14,7 @@ use core::sync::atomic::{AtomicU64, Ordering};
 use crate::kprintln;
 
 /// 타이머 틱 간격 (밀리초)
-const TIMER_TICK_MS: u64 = 10;
+pub const TIMER_TICK_MS: u64 = 10;
 
 /// 전역 타이머 틱 카운터 (SMP-safe)
 static TIMER_TICKS: AtomicU64 = AtomicU64::new(0);
@@ -103,6 +103,20 @@ pub fn set_next_tick() {
     let freq = get_frequency();
     let ticks = (freq * TIMER_TICK_MS) / 1000;
     set_tval(ticks as u32);
+    crate::time::tick_armed(TIMER_TICK_MS);
+}
+
+/// 주기 틱 대신 `delay_us` 뒤 한 번만 인터럽트 (틱 없는 idle, `time::nohz`)
+///
+/// 인터럽트 핸들러가 `set_next_tick()`으로 주기 틱을 다시 설정합니다.
+pub fn set_oneshot(delay_us: u64) {
+    let ticks = get_frequency().saturating_mul(delay_us) / 1_000_000;
+    set_cval(get_counter().saturating_add(ticks));
+}
+
+/// 틱 없는 idle 동안 건너뛴 틱을 전역 틱 카운터에 더함
+pub fn add_ticks(ticks: u64) {
+    TIMER_TICKS.fetch_add(ticks, Ordering::Relaxed);
 }
 
 /// 타이머 초기화
@@ -125,8 +139,11 @@ pub fn init() -> Result<(), &'static str> {
     
     // 타이머 활성화
     enable();
+
+    // Physical Timer PPI 핸들러 등록 (GIC에서 IRQ 활성화)
+    crate::irq::request_irq(super::gic::IRQ_PHYS_TIMER, timer_irq, "arch_timer").map_err(|e| e.as_str())?;
     
-    kprintln!("[Timer] Timer enabled");
+    kprintln!("[Timer] Timer enabled (IRQ {})", super::gic::IRQ_PHYS_TIMER);
     
     Ok(())
 }
@@ -141,6 +158,12 @@ pub fn init_secondary() {
     enable();
 }
 
+/// `crate::irq` 디스패치용 핸들러
+fn timer_irq(_irq: u32) -> bool {
+    handle_irq();
+    true
+}
+
 /// 타이머 인터럽트 핸들러
 pub fn handle_irq() {
     TIMER_TICKS.fetch_add(1, Ordering::Relaxed);
@@ -151,8 +174,11 @@ pub fn handle_irq() {
     // Per-CPU 틱 카운터 업데이트
     crate::proc::percpu::current().tick_count.fetch_add(1, Ordering::Relaxed);
 
-    // 선점 스케줄링: 타이머 틱마다 스케줄러 호출
-    crate::proc::scheduler::schedule();
+    // 만료된 콜백 타이머 실행
+    crate::time::timer::run_expired();
+
+    // 선점 스케줄링: 타임 슬라이스(`sched.quantum` 틱)가 끝났으면 스케줄러 호출
+    crate::proc::scheduler::tick();
 }
 
 /// 현재 틱 수 반환
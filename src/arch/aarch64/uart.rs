@@ -1,7 +1,10 @@
 //! ARM PL011 UART 드라이버
 //! 
 //! QEMU virt 보드의 UART는 PL011을 사용합니다.
-//! 베이스 주소: 0x0900_0000
+//! 베이스 주소: 0x0900_0000 (두 번째 UART가 있으면 0x0904_0000)
+//!
+//! `putc`/`getc`는 콘솔 UART, `*_at(base)`는 지정한 UART를 다룹니다
+//! (여러 UART는 `drivers::serial`이 관리).
 //! 
 //! 주요 레지스터:
 //! - UARTDR (0x000): Data Register
@@ -12,8 +15,6 @@
 //! - UARTICR (0x044): Interrupt Clear
 
 use core::ptr::{read_volatile, write_volatile};
-use crate::sync::Mutex;
-use crate::kprintln;
 
 /// UART 기본 주소 얻기
 ///
@@ -44,152 +45,94 @@ const FR_RXFE: u32 = 1 << 4;    // Receive FIFO empty
 const INT_RX: u32 = 1 << 4;     // Receive interrupt
 const INT_TX: u32 = 1 << 5;     // Transmit interrupt
 
-/// 입력 버퍼 크기
-const INPUT_BUFFER_SIZE: usize = 256;
-
-/// 입력 버퍼
-static INPUT_BUFFER: Mutex<InputBuffer> = Mutex::new(InputBuffer::new());
-
-/// 순환 버퍼
-struct InputBuffer {
-    buffer: [u8; INPUT_BUFFER_SIZE],
-    read_pos: usize,
-    write_pos: usize,
-    count: usize,
-}
-
-impl InputBuffer {
-    const fn new() -> Self {
-        Self {
-            buffer: [0; INPUT_BUFFER_SIZE],
-            read_pos: 0,
-            write_pos: 0,
-            count: 0,
-        }
-    }
-
-    fn push(&mut self, byte: u8) -> bool {
-        if self.count >= INPUT_BUFFER_SIZE {
-            return false; // 버퍼 가득 찼음
-        }
-
-        self.buffer[self.write_pos] = byte;
-        self.write_pos = (self.write_pos + 1) % INPUT_BUFFER_SIZE;
-        self.count += 1;
-        true
-    }
-
-    fn pop(&mut self) -> Option<u8> {
-        if self.count == 0 {
-            return None;
-        }
-
-        let byte = self.buffer[self.read_pos];
-        self.read_pos = (self.read_pos + 1) % INPUT_BUFFER_SIZE;
-        self.count -= 1;
-        Some(byte)
-    }
-
-    fn is_empty(&self) -> bool {
-        self.count == 0
-    }
-}
-
 /// UART 레지스터 읽기
 #[inline]
-unsafe fn read_reg(offset: usize) -> u32 {
-    read_volatile((uart_base() + offset) as *const u32)
+unsafe fn read_reg(base: usize, offset: usize) -> u32 {
+    read_volatile((base + offset) as *const u32)
 }
 
 /// UART 레지스터 쓰기
 #[inline]
-unsafe fn write_reg(offset: usize, value: u32) {
-    write_volatile((uart_base() + offset) as *mut u32, value);
+unsafe fn write_reg(base: usize, offset: usize, value: u32) {
+    write_volatile((base + offset) as *mut u32, value);
 }
 
-/// 문자 출력 (폴링 방식)
+/// 문자 출력 (콘솔 UART, 폴링 방식)
 #[inline(always)]
 pub fn putc(c: u8) {
+    putc_at(uart_base(), c);
+}
+
+/// 문자 입력 (콘솔 UART, 폴링 방식)
+pub fn getc() -> Option<u8> {
+    getc_at(uart_base())
+}
+
+/// `base`의 UART로 문자 출력 (폴링 방식)
+#[inline(always)]
+pub fn putc_at(base: usize, c: u8) {
     unsafe {
         // TX FIFO가 가득 찰 때까지 대기
-        while read_reg(UARTFR) & FR_TXFF != 0 {
+        while read_reg(base, UARTFR) & FR_TXFF != 0 {
             core::hint::spin_loop();
         }
-        write_reg(UARTDR, c as u32);
+        write_reg(base, UARTDR, c as u32);
     }
 }
 
-/// 문자 입력 (폴링 방식)
-pub fn getc() -> Option<u8> {
+/// `base`의 UART에서 문자 입력 (폴링 방식)
+pub fn getc_at(base: usize) -> Option<u8> {
     unsafe {
-        if read_reg(UARTFR) & FR_RXFE != 0 {
+        if read_reg(base, UARTFR) & FR_RXFE != 0 {
             None // RX FIFO 비어있음
         } else {
-            Some((read_reg(UARTDR) & 0xFF) as u8)
+            Some((read_reg(base, UARTDR) & 0xFF) as u8)
         }
     }
 }
 
-/// 버퍼에서 문자 읽기
-pub fn read_char() -> Option<u8> {
-    INPUT_BUFFER.lock().pop()
-}
-
-/// 버퍼가 비어있는지 확인
-pub fn is_input_empty() -> bool {
-    INPUT_BUFFER.lock().is_empty()
+/// 콘솔이 아닌 UART 초기화 (인터럽트 끔, pending 인터럽트 클리어)
+pub fn init_port(base: usize) {
+    unsafe {
+        write_reg(base, UARTIMSC, 0);
+        write_reg(base, UARTICR, 0x7FF);
+    }
 }
 
-/// UART 초기화 (인터럽트 활성화)
-pub fn init() -> Result<(), &'static str> {
-    kprintln!("\n[UART] Initializing with interrupt support...");
-    
+/// RX 인터럽트 활성화 (GIC 초기화 후 호출)
+pub fn enable_rx_irq(base: usize) {
     unsafe {
         // 모든 pending 인터럽트 클리어
-        write_reg(UARTICR, 0x7FF);  // 모든 인터럽트 클리어
+        write_reg(base, UARTICR, 0x7FF);  // 모든 인터럽트 클리어
         
         // FIFO 비우기
-        while read_reg(UARTFR) & FR_RXFE == 0 {
-            let _ = read_reg(UARTDR);  // 데이터 읽어서 버림
+        while read_reg(base, UARTFR) & FR_RXFE == 0 {
+            let _ = read_reg(base, UARTDR);  // 데이터 읽어서 버림
         }
         
         // RX 인터럽트 활성화
-        let mut imsc = read_reg(UARTIMSC);
+        let mut imsc = read_reg(base, UARTIMSC);
         imsc |= INT_RX;
-        write_reg(UARTIMSC, imsc);
+        write_reg(base, UARTIMSC, imsc);
     }
-    
-    kprintln!("[UART] RX interrupt enabled");
-    Ok(())
 }
 
 /// UART 인터럽트 핸들러
-pub fn handle_irq() {
+///
+/// RX FIFO를 모두 비워 `receive`로 넘깁니다 (시리얼 포트의 수신 링).
+pub fn handle_irq(base: usize, mut receive: impl FnMut(u8)) {
     unsafe {
-        let mis = read_reg(UARTMIS);
+        let mis = read_reg(base, UARTMIS);
         
         // RX 인터럽트 처리
         if mis & INT_RX != 0 {
             // FIFO에서 모든 문자 읽기
-            while read_reg(UARTFR) & FR_RXFE == 0 {
-                let ch = (read_reg(UARTDR) & 0xFF) as u8;
-                
-                // 에코백
-                putc(ch);
-                if ch == b'\r' {
-                    putc(b'\n');
-                }
-                
-                // 버퍼에 저장
-                if let Some(mut buffer) = INPUT_BUFFER.try_lock() {
-                    if !buffer.push(ch) {
-                        // 버퍼 가득 참 - 조용히 무시
-                    }
-                }
+            while read_reg(base, UARTFR) & FR_RXFE == 0 {
+                receive((read_reg(base, UARTDR) & 0xFF) as u8);
             }
             
             // 인터럽트 클리어 (반드시 해야 함)
-            write_reg(UARTICR, INT_RX);
+            write_reg(base, UARTICR, INT_RX);
         }
     }
 }
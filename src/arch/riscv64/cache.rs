@@ -0,0 +1,21 @@
+//! riscv64 데이터 캐시 유지보수
+//!
+//! RISC-V는 기본적으로 hart와 디바이스 사이 캐시 일관성을 하드웨어가 보장하고
+//! (DTB의 `dma-noncoherent`가 없으면), 캐시 라인 명령(Zicbom)은 선택 확장입니다. QEMU virt를
+//! 포함해 지금 지원하는 보드는 모두 일관적이므로 두 함수 모두 메모리 순서만 맞춥니다.
+
+use core::arch::asm;
+
+/// `[start, start + len)`을 디바이스가 읽기 전 (CPU 쓰기를 디바이스보다 먼저 보이게)
+pub fn clean_range(_start: usize, _len: usize) {
+    unsafe {
+        asm!("fence rw, rw");
+    }
+}
+
+/// `[start, start + len)`을 디바이스가 쓴 뒤 CPU가 읽기 전
+pub fn flush_range(_start: usize, _len: usize) {
+    unsafe {
+        asm!("fence rw, rw");
+    }
+}
@@ -0,0 +1,79 @@
+//! CPU 식별 및 기능 (misa)
+//!
+//! misa CSR의 MXL과 확장 비트로 ISA 문자열(`rv64imafdc...`)을 만들어 부팅
+//! 하드웨어 요약(`hwinfo`)에 사용합니다. M-mode 커널은 직접 읽고, misa가 M-mode 전용인
+//! S-mode 커널은 DTB `/cpus/cpu@0`의 `riscv,isa` 문자열로 같은 형식의 값을 만듭니다.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "riscv_smode"))]
+fn read_misa() -> u64 {
+    let misa: u64;
+    unsafe {
+        core::arch::asm!("csrr {}, misa", out(reg) misa, options(nomem, nostack));
+    }
+    misa
+}
+
+/// DTB `riscv,isa`("rv64imafdc_zicsr...")의 단일 문자 확장으로 misa 값 구성
+#[cfg(feature = "riscv_smode")]
+fn read_misa() -> u64 {
+    let Some(isa) = crate::dtb::get().and_then(|dt| dt.get_cpu_isa()) else {
+        return 0;
+    };
+    let Some(exts) = isa.strip_prefix("rv64") else {
+        return 0;
+    };
+    // MXL = 2 (64비트), 밑줄 뒤의 다중 문자 확장(zicsr 등)은 제외
+    let mut misa = 2u64 << 62;
+    for c in exts.split('_').next().unwrap_or("").bytes() {
+        if c.is_ascii_lowercase() {
+            misa |= 1 << (c - b'a');
+        }
+    }
+    misa
+}
+
+/// ISA 문자열 (예: "rv64imafdcsu")
+pub fn model() -> String {
+    let misa = read_misa();
+    let xlen = match misa >> 62 {
+        1 => 32,
+        2 => 64,
+        3 => 128,
+        _ => return String::from("rv64 (misa not implemented)"),
+    };
+
+    let mut isa = format!("rv{}", xlen);
+    for bit in 0..26 {
+        if misa & (1 << bit) != 0 {
+            isa.push((b'a' + bit as u8) as char);
+        }
+    }
+    isa
+}
+
+/// 지원 확장 목록 (주요 단일 문자 확장의 이름)
+pub fn features() -> Vec<&'static str> {
+    const NAMES: &[(u8, &str)] = &[
+        (b'i', "base-int"),
+        (b'm', "mul-div"),
+        (b'a', "atomics"),
+        (b'f', "fp32"),
+        (b'd', "fp64"),
+        (b'c', "compressed"),
+        (b'v', "vector"),
+        (b'h', "hypervisor"),
+        (b's', "supervisor"),
+        (b'u', "user"),
+    ];
+
+    let misa = read_misa();
+    NAMES
+        .iter()
+        .filter(|(c, _)| misa & (1 << (c - b'a')) != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
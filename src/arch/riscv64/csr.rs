@@ -0,0 +1,169 @@
+//! 권한 모드별 CSR
+//!
+//! 기본 빌드는 M-mode(QEMU `-bios none`)에서 실행되고, `riscv_smode` feature로 빌드하면
+//! Linux처럼 OpenSBI 아래 S-mode 페이로드로 실행됩니다. trap, 타이머, PLIC, 스핀락,
+//! 유저 모드 진입 코드는 이 모듈의 CSR 번호와 비트를 써서 두 모드를 같은 코드로 다룹니다.
+//!
+//! | 용도 | M-mode | S-mode |
+//! |------|--------|--------|
+//! | 상태 / 인터럽트 켜기 | `mstatus.MIE` | `sstatus.SIE` |
+//! | trap 벡터, 복귀 주소, 원인, 값 | `mtvec`, `mepc`, `mcause`, `mtval` | `stvec`, `sepc`, `scause`, `stval` |
+//! | 개별 인터럽트 켜기 / 대기 | `mie`, `mip` | `sie`, `sip` |
+//! | trap 복귀 | `mret` | `sret` |
+//! | hart ID | `mhartid` | `sscratch` (부팅 때 a0에서 저장) |
+//! | 타이머 / IPI | CLINT `mtimecmp` / `msip` | SBI TIME / IPI 확장 (`arch::sbi`) |
+//! | PLIC 컨텍스트 | hart × 2 | hart × 2 + 1 |
+//!
+//! CSR 번호는 `asm!`의 `const` 피연산자로 넘깁니다 (`csrr {0}, {csr}`).
+
+use core::arch::asm;
+
+#[cfg(not(feature = "riscv_smode"))]
+mod regs {
+    /// 커널이 실행되는 권한 모드
+    pub const MODE_NAME: &str = "M-mode";
+
+    pub const STATUS: usize = 0x300; // mstatus
+    pub const IE: usize = 0x304; // mie
+    pub const TVEC: usize = 0x305; // mtvec
+    pub const EPC: usize = 0x341; // mepc
+    pub const CAUSE: usize = 0x342; // mcause
+    pub const TVAL: usize = 0x343; // mtval
+    pub const IP: usize = 0x344; // mip
+    /// hart ID를 읽는 CSR
+    pub const HARTID: usize = 0xF14; // mhartid
+
+    /// 상태 레지스터: 인터럽트 켜기 (MIE)
+    pub const STATUS_IE: usize = 1 << 3;
+    /// 상태 레지스터: trap 이전 인터럽트 상태 (MPIE, 복귀 시 IE로)
+    pub const STATUS_PIE: usize = 1 << 7;
+    /// 상태 레지스터: trap 이전 권한 모드 (MPP, 0 = U-mode)
+    pub const STATUS_PP: usize = 0b11 << 11;
+
+    /// 소프트웨어(IPI) / 타이머 / 외부 인터럽트 비트 (IE, IP 공통)
+    pub const IE_SOFT: usize = 1 << 3;
+    pub const IE_TIMER: usize = 1 << 7;
+    pub const IE_EXT: usize = 1 << 11;
+
+    /// 이 모드의 PLIC 컨텍스트 (hart × 2 + 이 값)
+    pub const PLIC_CONTEXT: usize = 0;
+}
+
+#[cfg(feature = "riscv_smode")]
+mod regs {
+    /// 커널이 실행되는 권한 모드
+    pub const MODE_NAME: &str = "S-mode";
+
+    pub const STATUS: usize = 0x100; // sstatus
+    pub const IE: usize = 0x104; // sie
+    pub const TVEC: usize = 0x105; // stvec
+    pub const EPC: usize = 0x141; // sepc
+    pub const CAUSE: usize = 0x142; // scause
+    pub const TVAL: usize = 0x143; // stval
+    pub const IP: usize = 0x144; // sip
+    /// hart ID를 읽는 CSR (S-mode에는 mhartid가 없어 부팅 때 sscratch에 저장)
+    pub const HARTID: usize = 0x140; // sscratch
+
+    /// 상태 레지스터: 인터럽트 켜기 (SIE)
+    pub const STATUS_IE: usize = 1 << 1;
+    /// 상태 레지스터: trap 이전 인터럽트 상태 (SPIE, 복귀 시 IE로)
+    pub const STATUS_PIE: usize = 1 << 5;
+    /// 상태 레지스터: trap 이전 권한 모드 (SPP, 0 = U-mode)
+    pub const STATUS_PP: usize = 1 << 8;
+    /// 상태 레지스터: 커널이 U 페이지에 접근 허용 (SUM, 시스템 콜 버퍼 복사)
+    pub const STATUS_SUM: usize = 1 << 18;
+
+    /// 소프트웨어(IPI) / 타이머 / 외부 인터럽트 비트 (IE, IP 공통)
+    pub const IE_SOFT: usize = 1 << 1;
+    pub const IE_TIMER: usize = 1 << 5;
+    pub const IE_EXT: usize = 1 << 9;
+
+    /// 이 모드의 PLIC 컨텍스트 (hart × 2 + 이 값)
+    pub const PLIC_CONTEXT: usize = 1;
+}
+
+pub use regs::*;
+
+/// trap 복귀 명령어 (`asm!` 템플릿 조각)
+#[cfg(not(feature = "riscv_smode"))]
+macro_rules! xret {
+    () => {
+        "mret"
+    };
+}
+
+/// trap 복귀 명령어 (`asm!` 템플릿 조각)
+#[cfg(feature = "riscv_smode")]
+macro_rules! xret {
+    () => {
+        "sret"
+    };
+}
+
+pub(crate) use xret;
+
+/// 상태 레지스터 읽기
+#[inline]
+pub fn read_status() -> usize {
+    let value: usize;
+    unsafe { asm!("csrr {0}, {csr}", out(reg) value, csr = const STATUS, options(nomem, nostack)) };
+    value
+}
+
+/// 상태 레지스터 비트 켜기
+#[inline]
+pub fn set_status(bits: usize) {
+    unsafe { asm!("csrs {csr}, {0}", in(reg) bits, csr = const STATUS, options(nostack)) };
+}
+
+/// 상태 레지스터 비트 끄기
+#[inline]
+pub fn clear_status(bits: usize) {
+    unsafe { asm!("csrc {csr}, {0}", in(reg) bits, csr = const STATUS, options(nostack)) };
+}
+
+/// 개별 인터럽트 켜기 (`IE_*`)
+#[inline]
+pub fn enable_interrupts(bits: usize) {
+    unsafe { asm!("csrs {csr}, {0}", in(reg) bits, csr = const IE, options(nomem, nostack)) };
+}
+
+/// 개별 인터럽트 끄기 (`IE_*`)
+#[inline]
+pub fn disable_interrupts(bits: usize) {
+    unsafe { asm!("csrc {csr}, {0}", in(reg) bits, csr = const IE, options(nomem, nostack)) };
+}
+
+/// 대기 중인 인터럽트 비트 (`IE_*`와 같은 위치)
+#[inline]
+pub fn pending_interrupts() -> usize {
+    let value: usize;
+    unsafe { asm!("csrr {0}, {csr}", out(reg) value, csr = const IP, options(nomem, nostack)) };
+    value
+}
+
+/// 대기 중인 인터럽트 비트 지우기 (`IE_*`와 같은 위치)
+#[cfg(feature = "riscv_smode")]
+#[inline]
+pub fn clear_pending(bits: usize) {
+    unsafe { asm!("csrc {csr}, {0}", in(reg) bits, csr = const IP, options(nomem, nostack)) };
+}
+
+/// 현재 hart ID
+#[inline]
+pub fn hartid() -> u32 {
+    let id: usize;
+    unsafe { asm!("csrr {0}, {csr}", out(reg) id, csr = const HARTID, options(nomem, nostack)) };
+    id as u32
+}
+
+/// hart ID 기록 (S-mode, 부팅/보조 hart 진입 직후 한 번)
+///
+/// M-mode는 `mhartid`를 직접 읽으므로 아무것도 하지 않습니다.
+#[inline]
+pub fn set_hartid(_id: u32) {
+    #[cfg(feature = "riscv_smode")]
+    unsafe {
+        asm!("csrw {csr}, {0}", in(reg) _id as usize, csr = const HARTID, options(nomem, nostack))
+    };
+}
@@ -0,0 +1,321 @@
+//! riscv64 GDB 스텁 지원 (ebreak + 소프트웨어 단일 스텝)
+//!
+//! 브레이크포인트는 kprobe와 같은 `ebreak`/`c.ebreak`이고 주소로 구분합니다. 하드웨어 단일
+//! 스텝이 없으므로 현재 명령어를 해석해 다음 pc를 구하고(분기, `jal`/`jalr`, 압축 분기 포함)
+//! 그 자리에 임시 `ebreak`를 심습니다. 레지스터 번호는 GDB `org.gnu.gdb.riscv.cpu` 순서
+//! (x0-x31, pc)입니다.
+
+use alloc::string::String;
+use core::fmt::Write;
+use core::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+
+use super::csr;
+use super::kprobe::{break_for, insn_len, patch, read_insn};
+use super::trap::TrapContext;
+use crate::proc::percpu::{self, MAX_CPUS};
+
+/// 정지 시 레지스터 컨텍스트
+pub type Regs = TrapContext;
+
+/// GDB 레지스터 수와 번호
+pub const NUM_REGS: usize = 33;
+const REG_SP: usize = 2;
+const REG_PC: usize = 32;
+
+/// trap 벡터가 스택에 만드는 프레임 크기 (`addi sp, sp, -288`)
+const FRAME_SIZE: u64 = 288;
+
+/// status.MPIE / SPIE
+const STATUS_PIE: u64 = csr::STATUS_PIE as u64;
+
+/// CPU별 스텝용 임시 ebreak 주소(0 = 없음)와 그 자리의 원래 명령어
+static STEP_ADDR: [AtomicUsize; MAX_CPUS] = [const { AtomicUsize::new(0) }; MAX_CPUS];
+static STEP_ORIG: [AtomicU32; MAX_CPUS] = [const { AtomicU32::new(0) }; MAX_CPUS];
+/// 스텝 전 status.PIE
+static STEP_STATUS: [AtomicU64; MAX_CPUS] = [const { AtomicU64::new(0) }; MAX_CPUS];
+
+core::arch::global_asm!(
+    r#"
+.section .text.gdb_breakpoint, "ax"
+.balign 4
+.global gdb_breakpoint
+gdb_breakpoint:
+.option push
+.option norvc
+    ebreak
+.option pop
+    ret
+"#
+);
+
+unsafe extern "C" {
+    fn gdb_breakpoint();
+}
+
+pub fn zeroed_regs() -> Regs {
+    TrapContext {
+        gpr: [0; 32],
+        status: 0,
+        epc: 0,
+        cause: 0,
+        tval: 0,
+    }
+}
+
+/// trap 직전의 sp (저장된 x2는 프레임을 만든 뒤의 값)
+pub fn frame_sp(regs: &Regs) -> u64 {
+    regs as *const Regs as u64 + FRAME_SIZE
+}
+
+pub fn pc(regs: &Regs) -> usize {
+    regs.epc as usize
+}
+
+pub fn set_pc(regs: &mut Regs, pc: usize) {
+    regs.epc = pc as u64;
+}
+
+/// 레지스터 크기 (바이트)
+pub fn reg_size(n: usize) -> Option<usize> {
+    (n < NUM_REGS).then_some(8)
+}
+
+pub fn read_reg(regs: &Regs, sp: u64, n: usize) -> Option<u64> {
+    match n {
+        0 => Some(0),
+        REG_SP => Some(sp),
+        1..REG_PC => Some(regs.gpr[n]),
+        REG_PC => Some(regs.epc),
+        _ => None,
+    }
+}
+
+/// 레지스터 쓰기 (x0은 무시, sp는 trap 프레임 밖이라 바꿀 수 없음)
+pub fn write_reg(regs: &mut Regs, n: usize, value: u64) -> bool {
+    match n {
+        0 => {}
+        REG_SP => return false,
+        1..REG_PC => regs.gpr[n] = value,
+        REG_PC => regs.epc = value,
+        _ => return false,
+    }
+    true
+}
+
+/// `qXfer:features:read:target.xml` 응답
+pub fn target_xml() -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\"?><!DOCTYPE target SYSTEM \"gdb-target.dtd\"><target version=\"1.0\">\
+         <architecture>riscv:rv64</architecture><feature name=\"org.gnu.gdb.riscv.cpu\">",
+    );
+    for n in 0..REG_PC {
+        let ty = match n {
+            REG_SP => "data_ptr",
+            1 => "code_ptr",
+            _ => "int",
+        };
+        let _ = write!(xml, "<reg name=\"x{}\" bitsize=\"64\" type=\"{}\"/>", n, ty);
+    }
+    xml.push_str("<reg name=\"pc\" bitsize=\"64\" type=\"code_ptr\"/></feature></target>");
+    xml
+}
+
+/// 스텁이 읽고 쓸 수 있는 주소인지
+///
+/// M-mode 커널(기본 빌드)의 접근은 변환을 거치지 않으므로 RAM이면 페이지 테이블과 관계없이 허용
+pub fn is_mapped(addr: usize) -> bool {
+    if super::mmu::translate(addr).is_some() {
+        return true;
+    }
+    #[cfg(not(feature = "riscv_smode"))]
+    {
+        let base = crate::boards::ram_base();
+        if (base..base + crate::boards::ram_size()).contains(&addr) {
+            return true;
+        }
+    }
+    false
+}
+
+/// 브레이크포인트를 심을 수 있는 주소인지
+pub fn check_break(addr: usize) -> Result<(), &'static str> {
+    if addr % 2 != 0 {
+        return Err("unaligned address");
+    }
+    if super::kprobe::is_break(addr) {
+        return Err("breakpoint already installed (kprobe)");
+    }
+    Ok(())
+}
+
+/// 브레이크포인트 심기 (원래 명령어 반환)
+pub fn arm(addr: usize) -> u32 {
+    let orig = read_insn(addr);
+    patch(addr, break_for(orig));
+    orig
+}
+
+pub fn disarm(addr: usize, orig: u32) {
+    patch(addr, orig);
+}
+
+/// `breakpoint()`로 들어온 trap인지
+pub fn is_compiled_break(regs: &Regs) -> bool {
+    regs.epc as usize == gdb_breakpoint as unsafe extern "C" fn() as usize
+}
+
+/// `breakpoint()`의 ebreak 다음 명령어로 (항상 4바이트)
+pub fn skip_compiled_break(regs: &mut Regs) {
+    regs.epc += 4;
+}
+
+/// GDB 스텁으로 들어가는 브레이크포인트 실행
+pub fn breakpoint() {
+    unsafe { gdb_breakpoint() }
+}
+
+/// 부호 확장 (`bits`비트 값)
+fn sext(value: u64, bits: u32) -> u64 {
+    (((value << (64 - bits)) as i64) >> (64 - bits)) as u64
+}
+
+/// 현재 명령어를 실행한 뒤의 pc
+fn next_pc(regs: &Regs, sp: u64) -> u64 {
+    let pc = regs.epc;
+    let insn = read_insn(pc as usize) as u64;
+    let reg = |n: u64| read_reg(regs, sp, n as usize).unwrap_or(0);
+
+    if insn_len(insn as u32) == 4 {
+        let rs1 = reg((insn >> 15) & 0x1F);
+        let rs2 = reg((insn >> 20) & 0x1F);
+        match insn & 0x7F {
+            // jal
+            0x6F => {
+                let imm = (insn >> 31) << 20
+                    | ((insn >> 21) & 0x3FF) << 1
+                    | ((insn >> 20) & 1) << 11
+                    | ((insn >> 12) & 0xFF) << 12;
+                pc.wrapping_add(sext(imm, 21))
+            }
+            // jalr
+            0x67 => rs1.wrapping_add(sext(insn >> 20, 12)) & !1,
+            // 조건 분기
+            0x63 => {
+                let taken = match (insn >> 12) & 0b111 {
+                    0b000 => rs1 == rs2,
+                    0b001 => rs1 != rs2,
+                    0b100 => (rs1 as i64) < rs2 as i64,
+                    0b101 => (rs1 as i64) >= rs2 as i64,
+                    0b110 => rs1 < rs2,
+                    0b111 => rs1 >= rs2,
+                    _ => false,
+                };
+                if taken {
+                    let imm = (insn >> 31) << 12
+                        | ((insn >> 25) & 0x3F) << 5
+                        | ((insn >> 8) & 0xF) << 1
+                        | ((insn >> 7) & 1) << 11;
+                    pc.wrapping_add(sext(imm, 13))
+                } else {
+                    pc + 4
+                }
+            }
+            _ => pc + 4,
+        }
+    } else {
+        let funct3 = (insn >> 13) & 0b111;
+        match (insn & 0b11, funct3) {
+            // c.j
+            (0b01, 0b101) => {
+                let imm = ((insn >> 12) & 1) << 11
+                    | ((insn >> 11) & 1) << 4
+                    | ((insn >> 9) & 0b11) << 8
+                    | ((insn >> 8) & 1) << 10
+                    | ((insn >> 7) & 1) << 6
+                    | ((insn >> 6) & 1) << 7
+                    | ((insn >> 3) & 0b111) << 1
+                    | ((insn >> 2) & 1) << 5;
+                pc.wrapping_add(sext(imm, 12))
+            }
+            // c.beqz, c.bnez
+            (0b01, 0b110 | 0b111) => {
+                let rs1 = reg(8 + ((insn >> 7) & 0b111));
+                if (rs1 == 0) == (funct3 == 0b110) {
+                    let imm = ((insn >> 12) & 1) << 8
+                        | ((insn >> 10) & 0b11) << 3
+                        | ((insn >> 5) & 0b11) << 6
+                        | ((insn >> 3) & 0b11) << 1
+                        | ((insn >> 2) & 1) << 5;
+                    pc.wrapping_add(sext(imm, 9))
+                } else {
+                    pc + 2
+                }
+            }
+            // c.jr, c.jalr (rs2 = 0, rs1 != 0)
+            (0b10, 0b100) if (insn >> 2) & 0x1F == 0 && (insn >> 7) & 0x1F != 0 => {
+                reg((insn >> 7) & 0x1F) & !1
+            }
+            _ => pc + 2,
+        }
+    }
+}
+
+/// 다음 명령어에 임시 ebreak를 심어 명령어 하나만 실행하도록 설정
+pub fn begin_step(regs: &mut Regs, sp: u64) {
+    let cpu = percpu::get_cpu_id() as usize % MAX_CPUS;
+    let next = next_pc(regs, sp) as usize;
+    let orig = read_insn(next);
+
+    STEP_ORIG[cpu].store(orig, Ordering::Relaxed);
+    STEP_ADDR[cpu].store(next, Ordering::Release);
+    STEP_STATUS[cpu].store(regs.status & STATUS_PIE, Ordering::Relaxed);
+    // 스텝 동안 인터럽트 차단
+    regs.status &= !STATUS_PIE;
+    patch(next, break_for(orig));
+}
+
+/// 이 CPU가 스텝용으로 심은 임시 ebreak면 원래 명령어를 되돌리고 true
+pub fn end_step(regs: &mut Regs) -> bool {
+    let cpu = percpu::get_cpu_id() as usize % MAX_CPUS;
+    let addr = STEP_ADDR[cpu].load(Ordering::Acquire);
+    if addr == 0 || addr != regs.epc as usize {
+        return false;
+    }
+    STEP_ADDR[cpu].store(0, Ordering::Release);
+    patch(addr, STEP_ORIG[cpu].load(Ordering::Relaxed));
+    regs.status |= STEP_STATUS[cpu].load(Ordering::Relaxed);
+    true
+}
+
+/// 호출한 곳의 ra, sp, gp, tp, s0-s11, pc를 `regs`에 담고 sp 반환 (패닉 진입용)
+#[inline(always)]
+pub fn capture(regs: &mut Regs) -> u64 {
+    let pc: u64;
+    // Safety: gpr[0..32] 안의 슬롯에만 씀
+    unsafe {
+        core::arch::asm!(
+            "sd ra, 8({0})",
+            "sd sp, 16({0})",
+            "sd gp, 24({0})",
+            "sd tp, 32({0})",
+            "sd s0, 64({0})",
+            "sd s1, 72({0})",
+            "sd s2, 144({0})",
+            "sd s3, 152({0})",
+            "sd s4, 160({0})",
+            "sd s5, 168({0})",
+            "sd s6, 176({0})",
+            "sd s7, 184({0})",
+            "sd s8, 192({0})",
+            "sd s9, 200({0})",
+            "sd s10, 208({0})",
+            "sd s11, 216({0})",
+            "auipc {1}, 0",
+            in(reg) regs.gpr.as_mut_ptr(),
+            out(reg) pc,
+            options(nostack),
+        );
+    }
+    regs.epc = pc;
+    regs.gpr[REG_SP]
+}
@@ -0,0 +1,97 @@
+//! riscv64 kexec 트램펄린
+//!
+//! 새 커널 이미지는 현재 커널과 같은 주소에 링크되어 있으므로 위치 독립적인
+//! 트램펄린을 별도 페이지로 옮긴 뒤 그곳에서 세그먼트를 복사합니다.
+//! M-mode 커널은 주소 변환이 없고, S-mode 커널(`riscv_smode`)은 identity 매핑된 RAM에서
+//! 트램펄린을 실행한 뒤 점프 직전에 `satp`를 꺼서 새 커널이 Bare 모드로 시작하게 합니다.
+//!
+//! 트램펄린 입력:
+//! - a0: 복사 목록 주소 (`[dest, src, len]` u64 3개씩)
+//! - a1: 복사 목록 항목 수
+//! - a2: 새 커널 진입점
+//! - a3: DTB 주소 (점프 시 a0 = hartid, a1 = DTB)
+
+use core::arch::{asm, global_asm};
+
+use super::csr;
+
+global_asm!(
+    r#"
+    .section .text
+    .balign 8
+    .global kexec_trampoline_start
+    .global kexec_trampoline_end
+kexec_trampoline_start:
+1:  beqz    a1, 4f
+    ld      t0, 0(a0)           // dest
+    ld      t1, 8(a0)           // src
+    ld      t2, 16(a0)          // len
+    addi    a0, a0, 24
+2:  beqz    t2, 3f
+    lb      t3, 0(t1)
+    sb      t3, 0(t0)
+    addi    t0, t0, 1
+    addi    t1, t1, 1
+    addi    t2, t2, -1
+    j       2b
+3:  addi    a1, a1, -1
+    j       1b
+
+4:  fence.i
+    csrr    a0, {hartid}
+    mv      a1, a3
+    // 주소 변환 끄기 (S-mode; M-mode는 satp를 쓰지 않으므로 영향 없음)
+    csrw    satp, zero
+    sfence.vma
+    jr      a2
+kexec_trampoline_end:
+"#,
+    hartid = const csr::HARTID,
+);
+
+unsafe extern "C" {
+    static kexec_trampoline_start: u8;
+    static kexec_trampoline_end: u8;
+}
+
+/// 트램펄린 코드 크기 (바이트)
+pub fn trampoline_size() -> usize {
+    unsafe {
+        &kexec_trampoline_end as *const u8 as usize - &kexec_trampoline_start as *const u8 as usize
+    }
+}
+
+/// 현재 hart의 인터럽트 비활성화 (mstatus.MIE / sstatus.SIE 클리어)
+pub fn disable_interrupts() {
+    csr::clear_status(csr::STATUS_IE);
+}
+
+/// 트램펄린을 `page`에 복사하고 실행 (반환하지 않음)
+///
+/// # Safety
+/// 인터럽트와 다른 hart가 모두 정지된 상태여야 하며, `page`/`list`/스테이징
+/// 버퍼는 복사 대상 영역과 겹치지 않아야 함
+pub unsafe fn boot(page: usize, list: usize, count: usize, entry: usize, dtb: usize) -> ! {
+    unsafe {
+        core::ptr::copy_nonoverlapping(
+            &kexec_trampoline_start as *const u8,
+            page as *mut u8,
+            trampoline_size(),
+        );
+    }
+    // 프레임은 identity 매핑에서 실행 불가이므로 트램펄린 페이지만 읽기+실행으로
+    let _ = super::mmu::protect_page(page, false, true);
+
+    unsafe {
+        asm!(
+            "fence.i",
+            "jr {tramp}",
+            tramp = in(reg) page,
+            in("a0") list,
+            in("a1") count,
+            in("a2") entry,
+            in("a3") dtb,
+            options(noreturn)
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! riscv64 kprobe 지원 (ebreak + 소프트웨어 단일 스텝)
+//!
+//! probe 주소의 명령어를 `ebreak`(압축 명령어면 `c.ebreak`)로 바꿉니다. M-mode와 S-mode
+//! 모두 하드웨어 단일 스텝이 없으므로, 적중하면 원래 명령어를 되돌리고 바로 다음
+//! 명령어에 임시 `ebreak`를 심어 한 명령어만 실행시킨 뒤 그 trap에서 둘 다 원래대로
+//! 돌립니다. 다음 주소를 알 수 없는 분기/점프 명령어에는 probe를 심지 않습니다.
+//!
+//! 명령어를 고친 뒤 `fence.i`는 현재 hart에서만 실행합니다. QEMU는 코드 페이지
+//! 쓰기를 감지해 모든 hart의 번역 캐시를 버리므로 충분하지만, 실제 하드웨어에서는
+//! 다른 hart에도 `fence.i`가 필요합니다.
+
+use core::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+
+use super::csr;
+use super::trap::TrapContext;
+use crate::proc::percpu::{self, MAX_CPUS};
+
+/// 적중 시 레지스터 컨텍스트
+pub type Regs = TrapContext;
+
+/// `ebreak` / `c.ebreak`
+const EBREAK: u32 = 0x0010_0073;
+const C_EBREAK: u32 = 0x9002;
+
+/// status.MPIE / SPIE (trap 복귀 후 인터럽트 켜기)
+const STATUS_PIE: u64 = csr::STATUS_PIE as u64;
+
+/// CPU별 스텝 중인 probe (ID + 1, 0 = 없음)
+static STEP_ID: [AtomicUsize; MAX_CPUS] = [const { AtomicUsize::new(0) }; MAX_CPUS];
+/// 임시 ebreak 주소와 그 자리의 원래 명령어
+static STEP_ADDR: [AtomicUsize; MAX_CPUS] = [const { AtomicUsize::new(0) }; MAX_CPUS];
+static STEP_ORIG: [AtomicU32; MAX_CPUS] = [const { AtomicU32::new(0) }; MAX_CPUS];
+/// 스텝 전 status.PIE
+static STEP_STATUS: [AtomicU64; MAX_CPUS] = [const { AtomicU64::new(0) }; MAX_CPUS];
+
+pub fn pc(regs: &Regs) -> usize {
+    regs.epc as usize
+}
+
+/// n번째 인자 레지스터 (a0~ = x10~)
+pub fn arg(regs: &Regs, n: usize) -> u64 {
+    regs.gpr[10 + n]
+}
+
+/// 복귀 주소 (ra = x1)
+pub fn ret_addr(regs: &Regs) -> u64 {
+    regs.gpr[1]
+}
+
+/// 명령어 길이 (하위 2비트가 11이면 4바이트, 아니면 압축 2바이트)
+pub(super) fn insn_len(insn: u32) -> usize {
+    if insn & 0b11 == 0b11 { 4 } else { 2 }
+}
+
+/// 명령어 읽기 (2바이트 정렬만 보장되므로 반씩 읽음)
+pub fn read_insn(addr: usize) -> u32 {
+    unsafe {
+        let lo = core::ptr::read_volatile(addr as *const u16) as u32;
+        if insn_len(lo) == 2 {
+            return lo;
+        }
+        let hi = core::ptr::read_volatile((addr + 2) as *const u16) as u32;
+        lo | hi << 16
+    }
+}
+
+/// 명령어 쓰기 후 I-cache 동기화 (`gdb`도 사용)
+pub(super) fn patch(addr: usize, insn: u32) {
+    super::mmu::with_text_writable(addr, insn_len(insn), || unsafe {
+        core::ptr::write_volatile(addr as *mut u16, insn as u16);
+        if insn_len(insn) == 4 {
+            core::ptr::write_volatile((addr + 2) as *mut u16, (insn >> 16) as u16);
+        }
+        core::arch::asm!("fence.i", options(nostack));
+    });
+}
+
+/// `insn` 자리에 들어갈 같은 길이의 ebreak
+pub(super) fn break_for(insn: u32) -> u32 {
+    if insn_len(insn) == 4 { EBREAK } else { C_EBREAK }
+}
+
+pub fn arm(addr: usize) {
+    patch(addr, break_for(read_insn(addr)));
+}
+
+pub fn disarm(addr: usize, orig: u32) {
+    patch(addr, orig);
+}
+
+pub fn is_break(addr: usize) -> bool {
+    matches!(read_insn(addr), EBREAK | C_EBREAK)
+}
+
+/// 어느 CPU가 스텝용으로 잠깐 심어 둔 ebreak인지
+pub fn is_step_break(addr: usize) -> bool {
+    STEP_ADDR.iter().any(|a| a.load(Ordering::Acquire) == addr)
+}
+
+/// probe를 심을 수 있는 명령어인지 확인
+///
+/// 다음 명령어 위치로 스텝을 끝내므로 PC를 바꾸는 명령어는 거부합니다.
+pub fn check_probe(addr: usize) -> Result<(), &'static str> {
+    if addr % 2 != 0 {
+        return Err("unaligned address");
+    }
+    let insn = read_insn(addr);
+    if insn_len(insn) == 4 {
+        match insn & 0x7F {
+            0x63 | 0x6F | 0x67 => return Err("branch/jump"),
+            0x73 => return Err("system instruction"),
+            // lr/sc: trap이 예약을 끊음
+            0x2F if matches!(insn >> 27, 0b00010 | 0b00011) => {
+                return Err("lr/sc");
+            }
+            _ => {}
+        }
+    } else {
+        let funct3 = (insn >> 13) & 0b111;
+        match (insn & 0b11, funct3) {
+            // c.j, c.beqz, c.bnez
+            (0b01, 0b101 | 0b110 | 0b111) => return Err("branch/jump"),
+            // c.jr, c.jalr, c.ebreak (rs2 = 0)
+            (0b10, 0b100) if (insn >> 2) & 0x1F == 0 => return Err("branch/jump"),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// 원래 명령어를 되돌리고 다음 명령어에 임시 ebreak 심기
+pub fn begin_step(id: usize, addr: usize, orig: u32, regs: &mut Regs) {
+    let cpu = percpu::get_cpu_id() as usize % MAX_CPUS;
+    let next = addr + insn_len(orig);
+    // 다른 CPU가 같은 자리에 임시 ebreak를 심어 두었으면 그 CPU가 보관한 원래 명령어
+    let next_orig = (0..MAX_CPUS)
+        .find(|&c| STEP_ADDR[c].load(Ordering::Acquire) == next)
+        .map_or_else(|| read_insn(next), |c| STEP_ORIG[c].load(Ordering::Relaxed));
+
+    STEP_ID[cpu].store(id + 1, Ordering::Relaxed);
+    STEP_ORIG[cpu].store(next_orig, Ordering::Relaxed);
+    STEP_ADDR[cpu].store(next, Ordering::Release);
+    STEP_STATUS[cpu].store(regs.status & STATUS_PIE, Ordering::Relaxed);
+    // 스텝 동안 인터럽트 차단
+    regs.status &= !STATUS_PIE;
+
+    patch(next, break_for(next_orig));
+    disarm(addr, orig);
+}
+
+/// 스텝용 임시 ebreak 처리
+///
+/// 이 CPU가 심은 임시 ebreak면 다음 명령어를 복원하고 probe를 다시 심은 뒤 true
+pub fn handle_step(regs: &mut Regs) -> bool {
+    let cpu = percpu::get_cpu_id() as usize % MAX_CPUS;
+    let id = STEP_ID[cpu].load(Ordering::Relaxed);
+    if id == 0 || STEP_ADDR[cpu].load(Ordering::Acquire) != regs.epc as usize {
+        return false;
+    }
+
+    STEP_ADDR[cpu].store(0, Ordering::Release);
+    STEP_ID[cpu].store(0, Ordering::Relaxed);
+    // 같은 자리에서 스텝 중인 다른 CPU가 있으면 마지막 CPU가 복원
+    if !is_step_break(regs.epc as usize) {
+        patch(regs.epc as usize, STEP_ORIG[cpu].load(Ordering::Relaxed));
+    }
+    regs.status |= STEP_STATUS[cpu].load(Ordering::Relaxed);
+
+    crate::trace::kprobe::end_step(id - 1);
+    true
+}
@@ -12,10 +12,17 @@
 //! [27:19] PPN[1]
 //! [18:10] PPN[0]
 //! [9:0] Flags (D/A/G/U/X/W/R/V)
+//!
+//! RAM identity 매핑은 구간별 권한으로 만듭니다: 커널 `.text`는 읽기+실행, `.rodata`/`.ksymtab`은
+//! 읽기 전용, 나머지 RAM과 higher-half 별칭은 읽기/쓰기 실행 불가, MMIO는 읽기/쓰기 실행 불가입니다
+//! (Sv39에는 메모리 타입 비트가 없어 디바이스 속성은 플랫폼 PMA가 정함).
+//! M-mode 커널(기본 빌드)의 접근은 변환을 거치지 않으므로 `riscv_smode` 빌드에서만 적용됩니다.
 
 use crate::kprintln;
 use crate::mm::page::alloc_frame;
+use crate::sync::IrqSpinlock;
 use core::ptr::write_bytes;
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 /// 페이지 크기 (4KB)
 const PAGE_SIZE: usize = 4096;
@@ -23,6 +30,27 @@ const PAGE_SIZE: usize = 4096;
 /// Higher-half 커널 베이스 주소
 pub const KERNEL_VIRT_BASE: usize = 0xFFFF_FFFF_8000_0000;
 
+/// 활성화된 루트 페이지 테이블 PPN (런타임 매핑용, 0 = MMU 미활성)
+static ROOT_PPN: AtomicUsize = AtomicUsize::new(0);
+
+/// 런타임 범위 매핑과 권한 변경 직렬화 (같은 메가페이지를 두 번 나누지 않도록)
+///
+/// kprobe가 트랩 핸들러에서 권한을 바꾸므로 인터럽트를 끄고 잡습니다.
+static MAP_LOCK: IrqSpinlock<()> = IrqSpinlock::new(());
+
+/// 매핑 종류 (`map_range`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapKind {
+    /// 읽기+실행 (커널 `.text`)
+    Text,
+    /// 읽기 전용 (`.rodata`)
+    ReadOnly,
+    /// 읽기/쓰기, 실행 불가
+    Data,
+    /// 디바이스 메모리 (MMIO, 실행 불가)
+    Device,
+}
+
 /// 페이지 테이블 엔트리 (PTE)
 #[repr(transparent)]
 #[derive(Clone, Copy)]
@@ -77,6 +105,19 @@ impl PageTableEntry {
     fn addr(&self) -> usize {
         self.ppn() << 12
     }
+
+    /// W/X 비트만 바꾼 엔트리 (R, PPN, 나머지 플래그 유지)
+    fn with_permissions(self, write: bool, exec: bool) -> Self {
+        let mut bits = self.0 & !(Self::W | Self::X);
+        if write { bits |= Self::W; }
+        if exec { bits |= Self::X; }
+        Self(bits)
+    }
+
+    /// (쓰기 가능, 실행 가능)
+    fn permissions(&self) -> (bool, bool) {
+        (self.0 & Self::W != 0, self.0 & Self::X != 0)
+    }
 }
 
 /// 페이지 플래그
@@ -90,21 +131,19 @@ struct PageFlags {
 }
 
 impl PageFlags {
-    fn kernel_rwx() -> Self {
-        Self {
-            read: true,
-            write: true,
-            exec: true,
-            user: false,
-            global: true,
+    fn of(kind: MapKind) -> Self {
+        match kind {
+            MapKind::Text => Self::kernel_data(false, true),
+            MapKind::ReadOnly => Self::kernel_data(false, false),
+            MapKind::Data | MapKind::Device => Self::kernel_data(true, false),
         }
     }
 
-    fn kernel_rw() -> Self {
+    fn kernel_data(write: bool, exec: bool) -> Self {
         Self {
             read: true,
-            write: true,
-            exec: false,
+            write,
+            exec,
             user: false,
             global: true,
         }
@@ -197,6 +236,12 @@ impl PageTableManager {
             self.get_or_create_next_level(entry_ptr)?
         };
 
+        // 이미 4KB 페이지로 매핑된 구간을 메가페이지로 덮지 않음
+        let old = l1_table.entries[vpn1];
+        if old.is_valid() && !old.is_leaf() {
+            return Err("Range already mapped with pages");
+        }
+
         // Level 1 (리프 - 메가페이지)
         let ppn = phys >> 12;
         l1_table.entries[vpn1] = PageTableEntry::new_megapage(ppn, flags);
@@ -204,6 +249,28 @@ impl PageTableManager {
         Ok(())
     }
 
+    /// `[virt, virt + size)` → `[phys, phys + size)` 매핑
+    ///
+    /// 둘 다 2MB 정렬된 구간은 메가페이지로, 나머지는 4KB 페이지로 매핑합니다.
+    fn map_range(&mut self, virt: usize, phys: usize, size: usize, flags: PageFlags) -> Result<(), &'static str> {
+        if (virt | phys) & (PAGE_SIZE - 1) != 0 {
+            return Err("Address must be 4KB aligned");
+        }
+        let end = virt + size.next_multiple_of(PAGE_SIZE);
+        let mut addr = virt;
+        while addr < end {
+            let pa = phys + (addr - virt);
+            if (addr | pa) & 0x1F_FFFF == 0 && end - addr >= 0x20_0000 {
+                self.map_megapage(addr, pa, flags)?;
+                addr += 0x20_0000;
+            } else {
+                self.map_page(addr, pa, flags)?;
+                addr += PAGE_SIZE;
+            }
+        }
+        Ok(())
+    }
+
     unsafe fn get_or_create_next_level(
         &mut self,
         entry: *mut PageTableEntry,
@@ -222,9 +289,241 @@ impl PageTableManager {
         Ok(unsafe { &mut *(addr as *mut PageTable) })
     }
 
+    /// 4KB 페이지 매핑 해제, 매핑되어 있던 물리 주소 반환
+    fn unmap_page(&mut self, virt: usize) -> Option<usize> {
+        let vpn = [(virt >> 12) & 0x1FF, (virt >> 21) & 0x1FF, (virt >> 30) & 0x1FF];
+
+        let mut table = self.root_table as *mut PageTable;
+        for level in (1..3).rev() {
+            let entry = unsafe { (*table).entries[vpn[level]] };
+            if !entry.is_valid() || entry.is_leaf() {
+                return None;
+            }
+            table = entry.addr() as *mut PageTable;
+        }
+
+        let entry = unsafe { &mut (*table).entries[vpn[0]] };
+        if !entry.is_valid() {
+            return None;
+        }
+        let phys = entry.addr();
+        *entry = PageTableEntry::empty();
+        Some(phys)
+    }
+
+    /// 가상 주소 → 물리 주소 변환 (메가페이지 포함)
+    fn translate(&self, virt: usize) -> Option<usize> {
+        let mut table = self.root_table as *const PageTable;
+        for level in (0..3).rev() {
+            let shift = 12 + level * 9;
+            let entry = unsafe { (*table).entries[(virt >> shift) & 0x1FF] };
+            if !entry.is_valid() {
+                return None;
+            }
+            if entry.is_leaf() {
+                let offset_mask = (1usize << shift) - 1;
+                return Some((entry.addr() & !offset_mask) | (virt & offset_mask));
+            }
+            table = entry.addr() as *const PageTable;
+        }
+        None
+    }
+
+    /// 4KB 페이지의 Level 0 엔트리 (메가페이지면 같은 플래그의 페이지 512개로 나눔)
+    fn split_to_l0(&mut self, virt: usize) -> Result<*mut PageTableEntry, &'static str> {
+        let mut table = self.root_table as *mut PageTable;
+        for level in (1..3).rev() {
+            let entry = unsafe { &mut (*table).entries[(virt >> (12 + level * 9)) & 0x1FF] };
+            if !entry.is_valid() {
+                return Err("Address not mapped");
+            }
+            if entry.is_leaf() {
+                if level != 1 {
+                    return Err("Cannot split gigapage");
+                }
+                let l0 = alloc_page_table().ok_or("Failed to allocate page table")?;
+                let flags = entry.0 & 0x3FF;
+                for (i, page) in l0.entries.iter_mut().enumerate() {
+                    *page = PageTableEntry((((entry.ppn() + i) << 10) as u64) | flags);
+                }
+                *entry = PageTableEntry::new_table((l0 as *const PageTable as usize) >> 12);
+                // 메가페이지 TLB 항목 제거
+                unsafe { core::arch::asm!("sfence.vma") };
+                #[cfg(feature = "riscv_smode")]
+                super::sbi::remote_sfence_vma(0, usize::MAX);
+            }
+            table = entry.addr() as *mut PageTable;
+        }
+        Ok(unsafe { &mut (*table).entries[(virt >> 12) & 0x1FF] as *mut PageTableEntry })
+    }
+
+    /// 매핑된 주소의 (쓰기 가능, 실행 가능)
+    fn permissions(&self, virt: usize) -> Option<(bool, bool)> {
+        let mut table = self.root_table as *const PageTable;
+        for level in (0..3).rev() {
+            let entry = unsafe { (*table).entries[(virt >> (12 + level * 9)) & 0x1FF] };
+            if !entry.is_valid() {
+                return None;
+            }
+            if entry.is_leaf() {
+                return Some(entry.permissions());
+            }
+            table = entry.addr() as *const PageTable;
+        }
+        None
+    }
+
     fn root_ppn(&self) -> usize {
         (self.root_table as *const PageTable as usize) >> 12
     }
+
+    /// 현재 활성화된 루트 테이블로 매니저 생성
+    fn active() -> Option<Self> {
+        let ppn = ROOT_PPN.load(Ordering::Acquire);
+        if ppn == 0 {
+            return None;
+        }
+        Some(Self {
+            root_table: unsafe { &mut *((ppn << 12) as *mut PageTable) },
+        })
+    }
+}
+
+/// 단일 페이지 TLB 무효화
+fn flush_tlb_page(virt: usize) {
+    unsafe {
+        core::arch::asm!("sfence.vma {}, zero", in(reg) virt);
+    }
+}
+
+/// 런타임 4KB 페이지 매핑 (demand paging용)
+///
+/// MMU 활성화 이후 현재 페이지 테이블에 매핑을 추가합니다.
+pub fn map_page(virt: usize, phys: usize, write: bool, exec: bool) -> Result<(), &'static str> {
+    let mut pt_mgr = PageTableManager::active().ok_or("MMU not enabled")?;
+    pt_mgr.map_page(virt, phys, PageFlags::kernel_data(write, exec))?;
+    flush_tlb_page(virt);
+    Ok(())
+}
+
+/// 런타임 4KB 페이지 매핑 해제
+///
+/// # Returns
+/// 매핑되어 있던 물리 주소
+pub fn unmap_page(virt: usize) -> Option<usize> {
+    let mut pt_mgr = PageTableManager::active()?;
+    let phys = pt_mgr.unmap_page(virt)?;
+    flush_tlb_page(virt);
+    Some(phys)
+}
+
+/// 현재 페이지 테이블 기준 가상 → 물리 주소 변환
+pub fn translate(virt: usize) -> Option<usize> {
+    PageTableManager::active()?.translate(virt)
+}
+
+/// 런타임 범위 매핑 (주소는 4KB 정렬, 이미 매핑된 메가페이지와 겹치면 실패)
+pub fn map_range(virt: usize, phys: usize, size: usize, kind: MapKind) -> Result<(), &'static str> {
+    let mut pt_mgr = PageTableManager::active().ok_or("MMU not enabled")?;
+    let _guard = MAP_LOCK.lock();
+    pt_mgr.map_range(virt, phys, size, PageFlags::of(kind))?;
+    unsafe { core::arch::asm!("sfence.vma") };
+    #[cfg(feature = "riscv_smode")]
+    super::sbi::remote_sfence_vma(virt, size);
+    Ok(())
+}
+
+/// `[virt, virt + size)`에 걸친 매핑된 4KB 페이지들의 권한 변경
+///
+/// 메가페이지 안의 페이지면 먼저 같은 플래그의 4KB 페이지로 나눕니다. identity 매핑만
+/// 바꾸며 higher-half 별칭(읽기/쓰기, 실행 불가)은 그대로입니다. M-mode 커널(기본 빌드)의
+/// 접근은 변환을 거치지 않으므로 `riscv_smode` 빌드에서만 커널에 적용됩니다.
+pub fn protect_range(virt: usize, size: usize, write: bool, exec: bool) -> Result<(), &'static str> {
+    let mut pt_mgr = PageTableManager::active().ok_or("MMU not enabled")?;
+    let start = virt & !(PAGE_SIZE - 1);
+    let _guard = MAP_LOCK.lock();
+    for page in (start..virt + size).step_by(PAGE_SIZE) {
+        let entry = pt_mgr.split_to_l0(page)?;
+        unsafe {
+            *entry = (*entry).with_permissions(write, exec);
+        }
+        flush_tlb_page(page);
+    }
+    // 다른 hart에 남은 이전 권한 (M-mode 커널은 변환을 거치지 않으므로 로컬만)
+    #[cfg(feature = "riscv_smode")]
+    super::sbi::remote_sfence_vma(start, virt + size - start);
+    Ok(())
+}
+
+/// 캐시 속성 변경 (캐시 일관성 없는 DMA 버퍼용)
+///
+/// 페이지 단위 메모리 종류는 Svpbmt 확장이 있어야 하고 M-mode 커널은 변환을 거치지도 않으므로
+/// 지원하지 않습니다. 지원하는 보드는 DMA가 캐시 일관적이라 `mm::dma`가 부르지 않습니다.
+pub fn set_cacheable(_virt: usize, _size: usize, _cacheable: bool) -> Result<(), &'static str> {
+    Err("page memory types (Svpbmt) not supported")
+}
+
+/// 매핑된 4KB 페이지의 권한 변경 (모듈 W^X용)
+pub fn protect_page(virt: usize, write: bool, exec: bool) -> Result<(), &'static str> {
+    protect_range(virt, 1, write, exec)
+}
+
+/// 현재 페이지 테이블 기준 (쓰기 가능, 실행 가능), 매핑되지 않았으면 None
+pub fn page_permissions(virt: usize) -> Option<(bool, bool)> {
+    PageTableManager::active()?.permissions(virt)
+}
+
+/// `[addr, addr + len)`의 읽기 전용 페이지(커널 `.text`, W^X로 보호한 모듈 `.text`)를 잠깐
+/// 쓰기 가능하게 바꿔 `f` 실행 (kprobe 명령어 패치용)
+pub fn with_text_writable<R>(addr: usize, len: usize, f: impl FnOnce() -> R) -> R {
+    let mut restore = [None; 2];
+    for (slot, page) in restore.iter_mut().zip((addr & !(PAGE_SIZE - 1)..addr + len).step_by(PAGE_SIZE)) {
+        if let Some((false, exec)) = page_permissions(page) {
+            if protect_page(page, true, exec).is_ok() {
+                *slot = Some((page, exec));
+            }
+        }
+    }
+    let result = f();
+    for (page, exec) in restore.into_iter().flatten() {
+        let _ = protect_page(page, false, exec);
+    }
+    result
+}
+
+/// 커널 이미지의 (`.text` 시작, `.text` 끝 = 읽기 전용 시작, 읽기 전용 끝), 링커 스크립트가 4KB 정렬
+fn kernel_image() -> (usize, usize, usize) {
+    unsafe extern "C" {
+        static _text: u8;
+        static _etext: u8;
+        static _erodata: u8;
+    }
+    unsafe {
+        (
+            &_text as *const u8 as usize,
+            &_etext as *const u8 as usize,
+            &_erodata as *const u8 as usize,
+        )
+    }
+}
+
+/// RAM `[start, end)` identity 매핑 (커널 `.text`는 읽기+실행, `.rodata`는 읽기 전용, 나머지는 데이터)
+fn map_ram(pt_mgr: &mut PageTableManager, start: usize, end: usize) -> Result<(), &'static str> {
+    let (start, end) = (start & !(PAGE_SIZE - 1), end.next_multiple_of(PAGE_SIZE));
+    let (text, etext, erodata) = kernel_image();
+    let pieces = [
+        (start, text, MapKind::Data),
+        (text, etext, MapKind::Text),
+        (etext, erodata, MapKind::ReadOnly),
+        (erodata, end, MapKind::Data),
+    ];
+    for (from, to, kind) in pieces {
+        let (from, to) = (from.max(start), to.min(end));
+        if from < to {
+            pt_mgr.map_range(from, from, to - from, PageFlags::of(kind))?;
+        }
+    }
+    Ok(())
 }
 
 /// Identity mapping + Higher-half kernel mapping 생성
@@ -236,39 +535,56 @@ pub fn create_mapping(
 
     kprintln!("[MMU] Creating identity + higher-half mapping...");
 
-    let megapage_size = 2 * 1024 * 1024; // 2MB
-
     // 1. Identity mapping (물리 주소 = 가상 주소)
+    let (text, etext, erodata) = kernel_image();
     kprintln!("[MMU] Identity mapping RAM...");
-    for offset in (0..ram_size).step_by(megapage_size) {
-        let addr = ram_start + offset;
-        let aligned_addr = addr & !0x1F_FFFF;
-        pt_mgr.map_megapage(aligned_addr, aligned_addr, PageFlags::kernel_rwx())?;
+    kprintln!("      .text   {:#x} - {:#x} (RX)", text, etext);
+    kprintln!("      .rodata {:#x} - {:#x} (RO)", etext, erodata);
+    map_ram(&mut pt_mgr, ram_start, ram_start + ram_size)?;
+
+    // 추가 RAM 뱅크 (DTB에 memory 영역이 여러 개인 경우, 0번은 위에서 매핑함)
+    for bank in crate::mm::memory_banks().as_slice().iter().skip(1) {
+        map_ram(&mut pt_mgr, bank.base, bank.end())?;
     }
 
-    // 2. Higher-half mapping (가상: 0xFFFF_FFFF_8000_0000 -> 물리: ram_start)
+    // 2. Higher-half mapping (가상: 0xFFFF_FFFF_8000_0000 -> 물리: ram_start), 실행하지 않는 별칭
     kprintln!("[MMU] Higher-half kernel mapping...");
-    for offset in (0..ram_size).step_by(megapage_size) {
-        let phys_addr = ram_start + offset;
-        let virt_addr = KERNEL_VIRT_BASE + offset;
-        let aligned_phys = phys_addr & !0x1F_FFFF;
-        let aligned_virt = virt_addr & !0x1F_FFFF;
-        pt_mgr.map_megapage(aligned_virt, aligned_phys, PageFlags::kernel_rwx())?;
-    }
+    let ram_base = ram_start & !0x1F_FFFF;
+    pt_mgr.map_range(KERNEL_VIRT_BASE, ram_base, ram_start + ram_size - ram_base, PageFlags::of(MapKind::Data))?;
 
     // 3. MMIO 영역 매핑
-    // UART: 0x1000_0000
+    let device = PageFlags::of(MapKind::Device);
+    // UART: 0x1000_0000 (시리얼 포트가 더 있으면 모두)
     kprintln!("[MMU] Mapping UART MMIO...");
-    pt_mgr.map_page(0x1000_0000, 0x1000_0000, PageFlags::kernel_rw())?;
+    pt_mgr.map_page(0x1000_0000, 0x1000_0000, device)?;
+    for uart in crate::drivers::config::uarts() {
+        if uart.base != 0x1000_0000 {
+            pt_mgr.map_page(uart.base, uart.base, device)?;
+        }
+    }
 
     // CLINT: 0x0200_0000
     kprintln!("[MMU] Mapping CLINT MMIO...");
-    pt_mgr.map_page(0x0200_0000, 0x0200_0000, PageFlags::kernel_rw())?;
+    pt_mgr.map_page(0x0200_0000, 0x0200_0000, device)?;
 
-    // PLIC: 0x0C00_0000 - 0x0C20_0000 (여러 페이지)
+    // PLIC: 0x0C00_0000 - 0x0C20_0000 (여러 페이지) + 컨텍스트별 threshold/claim (hart당 M, S 2개)
     kprintln!("[MMU] Mapping PLIC MMIO...");
-    for offset in (0..0x20_0000).step_by(PAGE_SIZE) {
-        pt_mgr.map_page(0x0C00_0000 + offset, 0x0C00_0000 + offset, PageFlags::kernel_rw())?;
+    let plic_size = 0x20_0000 + crate::proc::percpu::MAX_CPUS * 2 * 0x1000;
+    pt_mgr.map_range(0x0C00_0000, 0x0C00_0000, plic_size, device)?;
+
+    // DTB의 기타 디바이스 (VirtIO, Goldfish RTC, sifive_test): S-mode 커널은 satp 변환을 거침
+    if let Some(dt) = crate::dtb::get() {
+        for compatible in ["virtio,mmio", "google,goldfish-rtc", "sifive,test0"] {
+            for dev in dt.find_compatible(compatible) {
+                let start = dev.reg_base as usize & !(PAGE_SIZE - 1);
+                let end = (dev.reg_base + dev.reg_size.max(1)) as usize;
+                for page in (start..end).step_by(PAGE_SIZE) {
+                    if pt_mgr.translate(page).is_none() {
+                        pt_mgr.map_page(page, page, device)?;
+                    }
+                }
+            }
+        }
     }
 
     kprintln!("[MMU] Mapping created, root PPN: {:#x}", pt_mgr.root_ppn());
@@ -296,6 +612,22 @@ pub unsafe fn enable_mmu(root_ppn: usize) {
     kprintln!("[MMU] MMU enabled!");
 }
 
+/// Secondary hart MMU 활성화 (boot hart가 만든 페이지 테이블 공유)
+pub fn init_secondary() {
+    let root_ppn = ROOT_PPN.load(Ordering::Acquire);
+    if root_ppn == 0 {
+        return;
+    }
+    let satp = (8u64 << 60) | (root_ppn as u64);
+    unsafe {
+        core::arch::asm!(
+            "csrw satp, {}",
+            "sfence.vma",
+            in(reg) satp
+        );
+    }
+}
+
 /// MMU 초기화
 pub fn init(ram_start: usize, ram_size: usize) -> Result<(), &'static str> {
     kprintln!("\n[MMU] Initializing Sv39 MMU...");
@@ -305,16 +637,19 @@ pub fn init(ram_start: usize, ram_size: usize) -> Result<(), &'static str> {
     unsafe {
         enable_mmu(pt_mgr.root_ppn());
     }
+    ROOT_PPN.store(pt_mgr.root_ppn(), Ordering::Release);
 
-    // 테스트: 메모리 접근
-    let test_addr = (ram_start + 0x87000) as *mut u32;
+    // 테스트: 메모리 접근 (스택은 데이터 매핑, 커널 `.text`는 읽기+실행)
+    let mut test_val = 0u32;
     unsafe {
-        *test_addr = 0xDEADBEEF;
-        let read_val = *test_addr;
-        if read_val != 0xDEADBEEF {
+        core::ptr::write_volatile(&mut test_val, 0xDEADBEEF);
+        if core::ptr::read_volatile(&test_val) != 0xDEADBEEF {
             return Err("MMU test failed: memory access incorrect");
         }
     }
+    if page_permissions(kernel_image().0) != Some((false, true)) {
+        return Err("MMU test failed: kernel text is not read+execute");
+    }
 
     kprintln!("[MMU] Test passed: Memory access works!");
 
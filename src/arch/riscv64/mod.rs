@@ -1,5 +1,12 @@
+pub mod cache;
+pub mod cpu;
+pub mod csr;
+pub mod gdb;
+pub mod kexec;
+pub mod kprobe;
 pub mod mmu;
 pub mod plic;
+pub mod sbi;
 pub mod timer;
 pub mod trap;
 pub mod uart;
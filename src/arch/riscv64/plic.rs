@@ -8,8 +8,13 @@
 //! - Enable (0x0C00_2000): 인터럽트 활성화
 //! - Threshold (0x0C20_0000): 우선순위 임계값
 //! - Claim/Complete (0x0C20_0004): 인터럽트 클레임/완료
+//!
+//! Enable/Threshold/Claim은 컨텍스트(hart × 권한 모드)마다 따로 있습니다. QEMU virt는
+//! hart마다 M-mode 컨텍스트(hart × 2)와 S-mode 컨텍스트(hart × 2 + 1)를 두며, 커널이 실행되는
+//! 모드의 컨텍스트(`csr::PLIC_CONTEXT`)를 씁니다.
 
 use core::ptr::{read_volatile, write_volatile};
+use super::csr;
 use crate::kprintln;
 
 /// PLIC 베이스 주소 얻기
@@ -25,17 +30,28 @@ fn plic_base() -> usize {
 /// Priority 레지스터 베이스 오프셋
 const PLIC_PRIORITY_OFFSET: usize = 0x0;
 
-/// Enable 레지스터 베이스 오프셋 (Context 0 = Machine mode, Hart 0)
+/// Enable 레지스터 베이스 오프셋 (Context 0, 컨텍스트마다 0x80)
 const PLIC_ENABLE_OFFSET: usize = 0x2000;
+const PLIC_ENABLE_STRIDE: usize = 0x80;
 
-/// Threshold 레지스터 오프셋 (Context 0)
+/// Threshold 레지스터 오프셋 (Context 0, 컨텍스트마다 0x1000)
 const PLIC_THRESHOLD_OFFSET: usize = 0x20_0000;
+const PLIC_CONTEXT_STRIDE: usize = 0x1000;
 
 /// Claim/Complete 레지스터 오프셋 (Context 0)
 const PLIC_CLAIM_OFFSET: usize = 0x20_0004;
 
-/// UART IRQ 번호 (QEMU virt)
-pub const IRQ_UART: u32 = 10;
+/// hart의 PLIC 컨텍스트 번호 (커널 권한 모드 기준)
+#[inline]
+fn context(hart_id: u32) -> usize {
+    (hart_id as usize) * 2 + csr::PLIC_CONTEXT
+}
+
+/// 현재 hart의 PLIC 컨텍스트 번호
+#[inline]
+fn current_context() -> usize {
+    context(crate::proc::percpu::get_cpu_id())
+}
 
 /// 인터럽트 우선순위 설정
 pub unsafe fn set_priority(irq: u32, priority: u32) {
@@ -43,11 +59,16 @@ pub unsafe fn set_priority(irq: u32, priority: u32) {
     write_volatile(addr as *mut u32, priority);
 }
 
-/// 인터럽트 활성화
+/// 인터럽트 활성화 (boot hart 컨텍스트)
 pub unsafe fn enable_irq(irq: u32) {
+    unsafe { enable_irq_context(context(0), irq) };
+}
+
+/// 컨텍스트 하나에서 인터럽트 활성화
+unsafe fn enable_irq_context(context: usize, irq: u32) {
     let reg_idx = (irq / 32) as usize;
     let bit_idx = irq % 32;
-    let addr = plic_base() + PLIC_ENABLE_OFFSET + reg_idx * 4;
+    let addr = plic_base() + PLIC_ENABLE_OFFSET + context * PLIC_ENABLE_STRIDE + reg_idx * 4;
 
     let mut val = read_volatile(addr as *const u32);
     val |= 1 << bit_idx;
@@ -55,20 +76,20 @@ pub unsafe fn enable_irq(irq: u32) {
 }
 
 /// Threshold 설정
-unsafe fn set_threshold(threshold: u32) {
-    let addr = plic_base() + PLIC_THRESHOLD_OFFSET;
+unsafe fn set_threshold(context: usize, threshold: u32) {
+    let addr = plic_base() + PLIC_THRESHOLD_OFFSET + context * PLIC_CONTEXT_STRIDE;
     write_volatile(addr as *mut u32, threshold);
 }
 
-/// 인터럽트 클레임
+/// 인터럽트 클레임 (현재 hart 컨텍스트)
 unsafe fn claim_irq() -> u32 {
-    let addr = plic_base() + PLIC_CLAIM_OFFSET;
+    let addr = plic_base() + PLIC_CLAIM_OFFSET + current_context() * PLIC_CONTEXT_STRIDE;
     read_volatile(addr as *const u32)
 }
 
-/// 인터럽트 완료
+/// 인터럽트 완료 (현재 hart 컨텍스트)
 unsafe fn complete_irq(irq: u32) {
-    let addr = plic_base() + PLIC_CLAIM_OFFSET;
+    let addr = plic_base() + PLIC_CLAIM_OFFSET + current_context() * PLIC_CONTEXT_STRIDE;
     write_volatile(addr as *mut u32, irq);
 }
 
@@ -76,24 +97,14 @@ unsafe fn complete_irq(irq: u32) {
 pub fn init() -> Result<(), &'static str> {
     kprintln!("\n[PLIC] Initializing PLIC...");
     
-    unsafe {
-        // Threshold 설정 (0 = 모든 우선순위 허용)
-        set_threshold(0);
-        
-        // UART 인터럽트 설정
-        set_priority(IRQ_UART, 1);
-        enable_irq(IRQ_UART);
-        
-        kprintln!("[PLIC] UART IRQ {} enabled", IRQ_UART);
-        
-        // MIE에서 외부 인터럽트 + 소프트웨어 인터럽트(IPI) 활성화
-        core::arch::asm!(
-            "li t0, 0x808",     // MEIE (0x800) | MSIE (0x8)
-            "csrs mie, t0"
-        );
-    }
+    // Threshold 설정 (0 = 모든 우선순위 허용)
+    // 각 IRQ는 드라이버가 `irq::request_irq()`로 핸들러를 걸 때 켜짐
+    unsafe { set_threshold(context(0), 0) };
+
+    // 외부 인터럽트 + 소프트웨어 인터럽트(IPI) 활성화 (MEIE | MSIE 또는 SEIE | SSIE)
+    csr::enable_interrupts(csr::IE_EXT | csr::IE_SOFT);
     
-    kprintln!("[PLIC] PLIC initialized");
+    kprintln!("[PLIC] PLIC initialized (context {})", context(0));
     Ok(())
 }
 
@@ -102,46 +113,36 @@ pub fn init() -> Result<(), &'static str> {
 /// 각 hart는 자신만의 PLIC context를 가집니다.
 /// M-mode context = hart_id * 2, S-mode context = hart_id * 2 + 1
 pub fn init_secondary(hart_id: u32) {
-    let context = (hart_id as usize) * 2; // M-mode context
+    let context = context(hart_id);
 
     unsafe {
         // Per-context threshold 설정
-        let threshold_addr = plic_base() + 0x20_0000 + context * 0x1000;
-        write_volatile(threshold_addr as *mut u32, 0);
-
-        // Per-context enable 레지스터에서 UART IRQ 활성화
-        let enable_base = plic_base() + 0x2000 + context * 0x80;
-        let reg_idx = (IRQ_UART / 32) as usize;
-        let bit_idx = IRQ_UART % 32;
-        let enable_addr = enable_base + reg_idx * 4;
-        let mut val = read_volatile(enable_addr as *const u32);
-        val |= 1 << bit_idx;
-        write_volatile(enable_addr as *mut u32, val);
-
-        // MIE에서 외부 인터럽트 + 소프트웨어 인터럽트(IPI) 활성화
-        core::arch::asm!(
-            "li t0, 0x808",     // MEIE (0x800) | MSIE (0x8)
-            "csrs mie, t0"
-        );
+        set_threshold(context, 0);
+
+        // Per-context enable 레지스터에서 핸들러가 걸린 IRQ 활성화
+        for irq in crate::irq::requested() {
+            enable_irq_context(context, irq);
+        }
     }
+
+    // 외부 인터럽트 + 소프트웨어 인터럽트(IPI) 활성화
+    csr::enable_interrupts(csr::IE_EXT | csr::IE_SOFT);
 }
 
 /// 외부 인터럽트 핸들러
+///
+/// claim이 0(대기 중인 소스 없음)을 돌려줄 때까지 `crate::irq::dispatch()`로 넘기므로 한 번의
+/// 트랩에서 동시에 들어온 인터럽트를 모두 비웁니다. 다른 hart가 먼저 claim한 경우 첫 claim이 0입니다.
 pub fn handle_irq() {
-    unsafe {
-        let irq = claim_irq();
-
+    loop {
+        let irq = unsafe { claim_irq() };
         if irq == 0 {
-            return; // spurious interrupt
-        }
-
-        if irq == IRQ_UART {
-            super::uart::handle_irq();
-        } else if crate::virtio::irq::handle_virtio_irq(irq) {
-            // VirtIO 디바이스가 처리함
+            return;
         }
-
-        complete_irq(irq);
+        crate::trace_event!(irq_entry, irq = irq);
+        crate::irq::dispatch(irq);
+        crate::trace_event!(irq_exit, irq = irq);
+        unsafe { complete_irq(irq) };
     }
 }
 
@@ -149,6 +150,7 @@ pub fn handle_irq() {
 ///
 /// CLINT의 MSIP 레지스터를 1로 설정하면 해당 hart에
 /// Machine Software Interrupt가 발생합니다.
+#[cfg(not(feature = "riscv_smode"))]
 #[allow(dead_code)]
 pub fn send_ipi(target_hartid: u32) {
     let clint_base = if crate::drivers::config::is_initialized() {
@@ -162,6 +164,16 @@ pub fn send_ipi(target_hartid: u32) {
     }
 }
 
+/// SBI IPI 확장으로 다른 hart에 소프트웨어 인터럽트(IPI) 전송
+///
+/// 펌웨어가 CLINT MSIP를 대신 쓰고 대상 hart의 sip.SSIP를 세워
+/// Supervisor Software Interrupt가 발생합니다.
+#[cfg(feature = "riscv_smode")]
+#[allow(dead_code)]
+pub fn send_ipi(target_hartid: u32) {
+    super::sbi::send_ipi(target_hartid);
+}
+
 /// 다른 hart에 reschedule IPI 전송
 #[allow(dead_code)]
 pub fn send_reschedule_ipi(target_hartid: u32) {
@@ -0,0 +1,163 @@
+//! SBI (Supervisor Binary Interface) 호출
+//!
+//! S-mode 커널(`riscv_smode` feature)은 M-mode 펌웨어(OpenSBI)에 `ecall`로 타이머, IPI,
+//! hart 시작/정지, 디버그 콘솔을 요청합니다. 호출 규약은 a7 = 확장 ID, a6 = 함수 ID,
+//! a0-a2 = 인자이고, a0 = 에러 코드, a1 = 값으로 돌아옵니다.
+//!
+//! M-mode 빌드에서는 `ecall`이 자기 trap 핸들러로 들어가므로 쓰지 않습니다 (HSM 호출은
+//! 보드 모듈 `qemu_virt_riscv64_smp`가 이 모듈을 통해 합니다).
+
+use core::arch::asm;
+
+/// 확장 ID
+#[allow(dead_code)]
+pub mod ext {
+    /// Base (스펙 버전, 구현 ID, 확장 탐색)
+    pub const BASE: usize = 0x10;
+    /// Timer ("TIME")
+    pub const TIME: usize = 0x5449_4D45;
+    /// IPI ("sPI")
+    pub const IPI: usize = 0x73_5049;
+    /// Hart State Management ("HSM")
+    pub const HSM: usize = 0x48_534D;
+    /// Remote fence ("RFNC")
+    pub const RFENCE: usize = 0x5246_4E43;
+    /// Debug Console ("DBCN")
+    pub const DBCN: usize = 0x4442_434E;
+    /// System Reset ("SRST")
+    pub const SRST: usize = 0x5352_5354;
+    /// 레거시 console_putchar / console_getchar (SBI v0.1)
+    pub const LEGACY_PUTCHAR: usize = 0x01;
+    pub const LEGACY_GETCHAR: usize = 0x02;
+}
+
+/// Base 확장 함수 ID
+const BASE_GET_SPEC_VERSION: usize = 0;
+const BASE_GET_IMPL_ID: usize = 1;
+const BASE_PROBE_EXTENSION: usize = 3;
+
+/// SBI 호출 결과
+#[derive(Debug, Clone, Copy)]
+pub struct SbiResult {
+    pub error: isize,
+    pub value: usize,
+}
+
+/// SBI ecall 호출
+#[inline(always)]
+pub fn call(ext: usize, func: usize, arg0: usize, arg1: usize, arg2: usize) -> SbiResult {
+    let error: isize;
+    let value: usize;
+    unsafe {
+        asm!(
+            "ecall",
+            in("a7") ext,
+            in("a6") func,
+            inout("a0") arg0 => error,
+            inout("a1") arg1 => value,
+            in("a2") arg2,
+            options(nostack)
+        );
+    }
+    SbiResult { error, value }
+}
+
+/// SBI 스펙 버전 (major, minor)
+pub fn spec_version() -> (usize, usize) {
+    let version = call(ext::BASE, BASE_GET_SPEC_VERSION, 0, 0, 0).value;
+    ((version >> 24) & 0x7F, version & 0xFF_FFFF)
+}
+
+/// SBI 구현 이름 (구현 ID 기준)
+pub fn impl_name() -> &'static str {
+    match call(ext::BASE, BASE_GET_IMPL_ID, 0, 0, 0).value {
+        0 => "BBL",
+        1 => "OpenSBI",
+        2 => "Xvisor",
+        3 => "KVM",
+        4 => "RustSBI",
+        5 => "Diosix",
+        6 => "Coffer",
+        _ => "unknown",
+    }
+}
+
+/// 확장 지원 여부
+pub fn probe_extension(ext_id: usize) -> bool {
+    call(ext::BASE, BASE_PROBE_EXTENSION, ext_id, 0, 0).value != 0
+}
+
+/// 현재 hart의 다음 타이머 인터럽트 시각 설정 (`time` CSR 기준, 대기 중인 STIP도 지움)
+#[cfg(feature = "riscv_smode")]
+pub fn set_timer(stime: u64) {
+    call(ext::TIME, 0, stime as usize, 0, 0);
+}
+
+/// hart 하나에 IPI (S-mode 소프트웨어 인터럽트) 전송
+#[cfg(feature = "riscv_smode")]
+pub fn send_ipi(hartid: u32) -> isize {
+    // hart_mask = 1 << (hartid - hart_mask_base)
+    call(ext::IPI, 0, 1, hartid as usize, 0).error
+}
+
+/// 모든 hart에서 `[start, start + size)`의 TLB 항목 무효화 (`size == usize::MAX`면 전체)
+#[cfg(feature = "riscv_smode")]
+pub fn remote_sfence_vma(start: usize, size: usize) -> isize {
+    // hart_mask_base = -1: 모든 hart
+    let error: isize;
+    unsafe {
+        asm!(
+            "ecall",
+            in("a7") ext::RFENCE,
+            in("a6") 1, // REMOTE_SFENCE_VMA
+            inout("a0") 0usize => error,
+            inout("a1") usize::MAX => _,
+            in("a2") start,
+            in("a3") size,
+            options(nostack)
+        );
+    }
+    error
+}
+
+/// 시스템 콜드 리부트 (SRST, 성공하면 돌아오지 않음)
+#[cfg(feature = "riscv_smode")]
+pub fn system_reset() -> SbiResult {
+    // reset_type 1 = cold reboot, reason 0 = 이유 없음
+    call(ext::SRST, 0, 1, 0, 0)
+}
+
+/// 시스템 종료 (SRST, 성공하면 돌아오지 않음)
+#[cfg(feature = "riscv_smode")]
+pub fn system_shutdown() -> SbiResult {
+    // reset_type 0 = shutdown, reason 0 = 이유 없음
+    call(ext::SRST, 0, 0, 0, 0)
+}
+
+/// 디버그 콘솔을 쓸 수 있는지 (DBCN 또는 레거시 putchar)
+#[cfg(feature = "riscv_smode")]
+pub fn console_available() -> bool {
+    probe_extension(ext::DBCN) || probe_extension(ext::LEGACY_PUTCHAR)
+}
+
+/// 디버그 콘솔로 한 바이트 출력
+#[cfg(feature = "riscv_smode")]
+pub fn console_putchar(byte: u8) {
+    if call(ext::DBCN, 2, byte as usize, 0, 0).error != 0 {
+        call(ext::LEGACY_PUTCHAR, 0, byte as usize, 0, 0);
+    }
+}
+
+/// 디버그 콘솔 입력 한 바이트 (non-blocking)
+#[cfg(feature = "riscv_smode")]
+pub fn console_getchar() -> Option<u8> {
+    let mut byte = 0u8;
+    // DBCN read: (바이트 수, 버퍼 물리 주소 하위, 상위), identity 매핑이라 가상 = 물리
+    let result = call(ext::DBCN, 1, 1, &mut byte as *mut u8 as usize, 0);
+    if result.error == 0 {
+        return (result.value == 1).then_some(byte);
+    }
+    // 레거시 getchar는 입력이 없으면 -1을 a0으로 돌려줌
+    let legacy = call(ext::LEGACY_GETCHAR, 0, 0, 0, 0).error;
+    (legacy >= 0).then_some(legacy as u8)
+}
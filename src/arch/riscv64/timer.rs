@@ -5,12 +5,18 @@
 //! 레지스터:
 //! - mtime (0x0200_BFF8): 현재 시간 카운터
 //! - mtimecmp (0x0200_4000 + hartid*8): 비교 값
+//!
+//! S-mode(`riscv_smode`)에서는 CLINT가 펌웨어 몫이므로 `time` CSR(`rdtime`)로 시간을 읽고
+//! SBI TIME 확장(`sbi::set_timer`)으로 다음 인터럽트를 요청합니다.
 
+#[cfg(not(feature = "riscv_smode"))]
 use core::ptr::{read_volatile, write_volatile};
 use core::sync::atomic::{AtomicU64, Ordering};
+use super::csr;
 use crate::kprintln;
 
 /// CLINT 베이스 주소 얻기
+#[cfg(not(feature = "riscv_smode"))]
 #[inline]
 fn clint_base() -> usize {
     if crate::drivers::config::is_initialized() {
@@ -31,39 +37,83 @@ fn timer_freq() -> u64 {
 }
 
 /// mtime 레지스터 오프셋 (64-bit)
+#[cfg(not(feature = "riscv_smode"))]
 const MTIME_OFFSET: usize = 0xBFF8;
 
 /// mtimecmp 레지스터 오프셋 (64-bit, hartid=0)
+#[cfg(not(feature = "riscv_smode"))]
 const MTIMECMP_OFFSET: usize = 0x4000;
 
 /// 타이머 틱 간격 (밀리초)
-const TIMER_TICK_MS: u64 = 10;
+pub const TIMER_TICK_MS: u64 = 10;
 
 /// 전역 타이머 틱 카운터 (SMP-safe)
 static TIMER_TICKS: AtomicU64 = AtomicU64::new(0);
 
 /// mtime 읽기
+#[cfg(not(feature = "riscv_smode"))]
 #[inline]
 fn read_mtime() -> u64 {
     unsafe { read_volatile((clint_base() + MTIME_OFFSET) as *const u64) }
 }
 
-/// mtimecmp 쓰기
+/// time CSR 읽기 (S-mode, 펌웨어가 mtime을 보여줌)
+#[cfg(feature = "riscv_smode")]
+#[inline]
+fn read_mtime() -> u64 {
+    let time: u64;
+    unsafe { core::arch::asm!("rdtime {0}", out(reg) time, options(nomem, nostack)) };
+    time
+}
+
+/// 현재 hart의 mtimecmp 쓰기
+#[cfg(not(feature = "riscv_smode"))]
+#[inline]
+fn write_mtimecmp(value: u64) {
+    let hartid = crate::proc::percpu::get_cpu_id();
+    let offset = MTIMECMP_OFFSET + (hartid as usize) * 8;
+    unsafe { write_volatile((clint_base() + offset) as *mut u64, value); }
+}
+
+/// 현재 hart의 다음 타이머 인터럽트 요청 (S-mode, SBI)
+#[cfg(feature = "riscv_smode")]
 #[inline]
 fn write_mtimecmp(value: u64) {
-    unsafe { write_volatile((clint_base() + MTIMECMP_OFFSET) as *mut u64, value); }
+    super::sbi::set_timer(value);
 }
 
-/// 다음 타이머 인터럽트 설정
+/// 다음 타이머 인터럽트 설정 (현재 hart)
 pub fn set_next_tick() {
     let current = read_mtime();
     let ticks = (timer_freq() * TIMER_TICK_MS) / 1000;
     write_mtimecmp(current + ticks);
+    crate::time::tick_armed(TIMER_TICK_MS);
+}
+
+/// 주기 틱 대신 `delay_us` 뒤 한 번만 인터럽트 (틱 없는 idle, `time::nohz`)
+///
+/// 인터럽트 핸들러가 `set_next_tick()`으로 주기 틱을 다시 설정합니다.
+pub fn set_oneshot(delay_us: u64) {
+    let ticks = timer_freq().saturating_mul(delay_us) / 1_000_000;
+    write_mtimecmp(read_mtime().saturating_add(ticks));
+}
+
+/// 현재 hart의 타이머 인터럽트가 대기 중인지
+pub fn is_pending() -> bool {
+    csr::pending_interrupts() & csr::IE_TIMER != 0
+}
+
+/// 틱 없는 idle 동안 건너뛴 틱을 전역 틱 카운터에 더함
+pub fn add_ticks(ticks: u64) {
+    TIMER_TICKS.fetch_add(ticks, Ordering::Relaxed);
 }
 
 /// 타이머 초기화
 pub fn init() -> Result<(), &'static str> {
+    #[cfg(not(feature = "riscv_smode"))]
     kprintln!("\n[Timer] Initializing CLINT timer...");
+    #[cfg(feature = "riscv_smode")]
+    kprintln!("\n[Timer] Initializing SBI timer...");
 
     kprintln!("[Timer] Frequency: {} Hz", timer_freq());
     kprintln!("[Timer] Tick interval: {} ms", TIMER_TICK_MS);
@@ -71,40 +121,26 @@ pub fn init() -> Result<(), &'static str> {
     // 첫 타이머 인터럽트 설정
     set_next_tick();
     
-    // MIE (Machine Interrupt Enable)에서 타이머 인터럽트 활성화
-    unsafe {
-        core::arch::asm!(
-            "li t0, 0x80",      // MTIE (Machine Timer Interrupt Enable)
-            "csrs mie, t0"
-        );
-    }
+    // mie.MTIE / sie.STIE: 타이머 인터럽트 활성화
+    csr::enable_interrupts(csr::IE_TIMER);
     
     kprintln!("[Timer] Timer enabled");
     
     Ok(())
 }
 
-/// Secondary hart의 mtimecmp 쓰기
-#[inline]
-fn write_mtimecmp_hart(hartid: u32, value: u64) {
-    let offset = MTIMECMP_OFFSET + (hartid as usize) * 8;
-    unsafe { write_volatile((clint_base() + offset) as *mut u64, value); }
-}
-
 /// Secondary hart 타이머 초기화
 pub fn init_secondary() {
-    let hartid = crate::proc::percpu::get_cpu_id();
-    let current = read_mtime();
-    let ticks = (timer_freq() * TIMER_TICK_MS) / 1000;
-    write_mtimecmp_hart(hartid, current + ticks);
-
-    // MIE에서 타이머 인터럽트 활성화
-    unsafe {
-        core::arch::asm!(
-            "li t0, 0x80",      // MTIE
-            "csrs mie, t0"
-        );
-    }
+    set_next_tick();
+
+    // 타이머 인터럽트 활성화
+    csr::enable_interrupts(csr::IE_TIMER);
+}
+
+/// 현재 hart의 타이머 인터럽트 끄기 (오프라인 hart, `init_secondary()`로 다시 켬)
+pub fn disable() {
+    csr::disable_interrupts(csr::IE_TIMER);
+    write_mtimecmp(u64::MAX);
 }
 
 /// 타이머 인터럽트 핸들러
@@ -123,8 +159,11 @@ pub fn handle_irq() {
         kprintln!("[Timer] {} seconds elapsed", seconds);
     }
 
-    // 선점 스케줄링: 타이머 틱마다 스케줄러 호출
-    crate::proc::scheduler::schedule();
+    // 만료된 콜백 타이머 실행
+    crate::time::timer::run_expired();
+
+    // 선점 스케줄링: 타임 슬라이스(`sched.quantum` 틱)가 끝났으면 스케줄러 호출
+    crate::proc::scheduler::tick();
 }
 
 /// 현재 틱 수 반환
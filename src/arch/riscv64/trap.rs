@@ -5,22 +5,25 @@
 //! RISC-V에서 trap은 다음을 포함:
 //! - Exceptions: 동기적 이벤트 (illegal instruction, page fault 등)
 //! - Interrupts: 비동기적 이벤트 (timer, external 등)
+//!
+//! 커널 권한 모드(M-mode 또는 `riscv_smode`의 S-mode)에 맞는 CSR은 `csr` 모듈이 고릅니다.
 
+use super::csr::{self, xret};
 use crate::kprintln;
 
-/// Trap 발생 시 저장되는 CPU 컨텍스트 (M-mode)
+/// Trap 발생 시 저장되는 CPU 컨텍스트
 #[repr(C)]
 pub struct TrapContext {
     /// General purpose registers x0-x31 (x0은 항상 0이지만 정렬을 위해 포함)
     pub gpr: [u64; 32],
-    /// Machine Status Register
-    pub mstatus: u64,
-    /// Machine Exception Program Counter (복귀 주소)
-    pub mepc: u64,
-    /// Machine Cause Register
-    pub mcause: u64,
-    /// Machine Trap Value (추가 정보, 예: fault 주소)
-    pub mtval: u64,
+    /// Status Register (mstatus / sstatus)
+    pub status: u64,
+    /// Exception Program Counter (복귀 주소, mepc / sepc)
+    pub epc: u64,
+    /// Cause Register (mcause / scause)
+    pub cause: u64,
+    /// Trap Value (추가 정보, 예: fault 주소, mtval / stval)
+    pub tval: u64,
 }
 
 /// scause 레지스터의 예외 코드 해석 (인터럽트가 아닌 경우)
@@ -60,18 +63,18 @@ fn interrupt_cause_to_str(cause: u64) -> &'static str {
 
 /// Trap 컨텍스트 출력
 fn print_trap_context(ctx: &TrapContext) {
-    let is_interrupt = (ctx.mcause >> 63) & 1 == 1;
-    let cause_code = ctx.mcause & 0x7FFFFFFF_FFFFFFFF;
+    let is_interrupt = (ctx.cause >> 63) & 1 == 1;
+    let cause_code = ctx.cause & 0x7FFFFFFF_FFFFFFFF;
     
-    kprintln!("Trap Context:");
-    kprintln!("  mcause:  {:#018x} ({})", ctx.mcause, 
+    kprintln!("Trap Context ({}):", csr::MODE_NAME);
+    kprintln!("  cause:   {:#018x} ({})", ctx.cause, 
         if is_interrupt { "Interrupt" } else { "Exception" });
     kprintln!("  Cause:   {} (code={})", 
         if is_interrupt { interrupt_cause_to_str(cause_code) } else { exception_cause_to_str(cause_code) },
         cause_code);
-    kprintln!("  mepc:    {:#018x}", ctx.mepc);
-    kprintln!("  mstatus: {:#018x}", ctx.mstatus);
-    kprintln!("  mtval:   {:#018x}", ctx.mtval);
+    kprintln!("  epc:     {:#018x}", ctx.epc);
+    kprintln!("  status:  {:#018x}", ctx.status);
+    kprintln!("  tval:    {:#018x}", ctx.tval);
     kprintln!();
     kprintln!("General Purpose Registers:");
     for i in 0..32 {
@@ -83,56 +86,99 @@ fn print_trap_context(ctx: &TrapContext) {
     kprintln!();
 }
 
+/// 처리하지 못한 예외로 패닉하기 전에 백트레이스 시작점 기록 (x8 = s0 = fp)
+fn set_fatal_frame(ctx: &TrapContext) {
+    crate::panic::set_exception_frame(ctx.epc as usize, ctx.gpr[8] as usize);
+}
+
 /// 메인 trap 핸들러 (Rust)
 /// 어셈블리 트램폴린에서 호출됨
 #[unsafe(no_mangle)]
 pub extern "C" fn trap_handler(ctx: &mut TrapContext) {
-    let is_interrupt = (ctx.mcause >> 63) & 1 == 1;
-    let cause_code = ctx.mcause & 0x7FFFFFFF_FFFFFFFF;
+    let is_interrupt = (ctx.cause >> 63) & 1 == 1;
+    let cause_code = ctx.cause & 0x7FFFFFFF_FFFFFFFF;
 
     if is_interrupt {
         handle_interrupt(ctx, cause_code);
+        // PLIC complete 뒤 하반부 (인터럽트를 켜고 태스크릿 실행)
+        crate::softirq::run_pending();
     } else {
         handle_exception(ctx, cause_code);
     }
+
+    // U-mode로 복귀하는 경우에만 시그널 전달 (커널 코드는 락을 잡고 있을 수 있음)
+    if ctx.status as usize & csr::STATUS_PP == 0 {
+        crate::proc::signal::deliver_pending();
+    }
 }
 
 /// 인터럽트 처리
 fn handle_interrupt(_ctx: &mut TrapContext, cause: u64) {
+    // 외부 인터럽트는 PLIC이 IRQ 번호로 기록, 로컬(타이머/IPI)은 cause 코드로 기록
+    let local = !matches!(cause, 9 | 11);
+    if local {
+        crate::trace_event!(irq_entry, cause = cause);
+    }
+
     match cause {
         1 => {
-            // Supervisor Software Interrupt
+            // Supervisor Software Interrupt (S-mode: SBI IPI)
             handle_software_interrupt();
         }
         3 => {
-            // Machine Software Interrupt (IPI via CLINT MSIP)
+            // Machine Software Interrupt (M-mode: CLINT MSIP)
             handle_software_interrupt();
         }
         5 => {
-            // Supervisor Timer Interrupt - Machine 모드에서는 7
+            // Supervisor Timer Interrupt (S-mode: SBI set_timer)
+            crate::irq::count_local_timer();
             super::timer::handle_irq();
         }
         7 => {
-            // Machine Timer Interrupt
+            // Machine Timer Interrupt (M-mode: CLINT mtimecmp)
+            crate::irq::count_local_timer();
             super::timer::handle_irq();
         }
         9 => {
-            // Supervisor External Interrupt - Machine 모드에서는 11
+            // Supervisor External Interrupt (S-mode PLIC 컨텍스트)
             super::plic::handle_irq();
         }
         11 => {
-            // Machine External Interrupt
+            // Machine External Interrupt (M-mode PLIC 컨텍스트)
             super::plic::handle_irq();
         }
         _ => {
             kprintln!("\n[INTERRUPT] Unhandled interrupt: cause={}", cause);
         }
     }
+
+    if local {
+        crate::trace_event!(irq_exit, cause = cause);
+    }
 }
 
 /// 소프트웨어 인터럽트 (IPI) 처리
 fn handle_software_interrupt() {
-    // CLINT MSIP 클리어 (자신의 hart)
+    clear_software_interrupt();
+    crate::irq::count_ipi();
+
+    // kexec 진행 중이면 이 hart를 펌웨어로 반환
+    if crate::kexec::stop_requested() {
+        crate::kexec::stop_this_cpu();
+    }
+
+    // 다른 hart가 GDB 스텁 세션 중이면 끝날 때까지 대기
+    if crate::debug::gdbstub::hold_requested() {
+        crate::debug::gdbstub::hold_this_cpu();
+    }
+
+    // Reschedule IPI: 스케줄러 호출
+    crate::proc::scheduler::schedule();
+}
+
+/// 자신의 hart에 대기 중인 IPI 지우기 (M-mode: CLINT MSIP)
+#[cfg(not(feature = "riscv_smode"))]
+fn clear_software_interrupt() {
     let hartid = crate::proc::percpu::get_cpu_id();
     let clint_base = if crate::drivers::config::is_initialized() {
         crate::drivers::config::clint_base()
@@ -143,9 +189,12 @@ fn handle_software_interrupt() {
         let msip_addr = clint_base + (hartid as usize) * 4;
         core::ptr::write_volatile(msip_addr as *mut u32, 0);
     }
+}
 
-    // Reschedule IPI: 스케줄러 호출
-    crate::proc::scheduler::schedule();
+/// 자신의 hart에 대기 중인 IPI 지우기 (S-mode: SBI가 세운 sip.SSIP)
+#[cfg(feature = "riscv_smode")]
+fn clear_software_interrupt() {
+    csr::clear_pending(csr::IE_SOFT);
 }
 
 /// 예외 처리
@@ -155,27 +204,36 @@ fn handle_exception(ctx: &mut TrapContext, cause: u64) {
             // Illegal instruction
             kprintln!("\n[EXCEPTION] Illegal instruction");
             print_trap_context(ctx);
-            panic!("Illegal instruction at {:#x}", ctx.mepc);
+            set_fatal_frame(ctx);
+            panic!("Illegal instruction at {:#x}", ctx.epc);
         }
         3 => {
-            // Breakpoint (ebreak)
-            kprintln!("\n[EXCEPTION] Breakpoint at {:#x}", ctx.mepc);
+            // Breakpoint (ebreak): kprobe 스텝, GDB 스텁(브레이크포인트, 스텝), probe 적중
+            if super::kprobe::handle_step(ctx)
+                || crate::debug::gdbstub::handle_trap(ctx)
+                || crate::trace::kprobe::on_breakpoint(ctx)
+            {
+                return;
+            }
+            kprintln!("\n[EXCEPTION] Breakpoint at {:#x}", ctx.epc);
             print_trap_context(ctx);
             // ebreak 이후 다음 명령으로 진행 (ebreak는 2바이트 또는 4바이트)
             // 압축 명령어가 아닌 경우 4바이트
-            // ctx.mepc += 4; // 필요시 mepc 증가
+            // ctx.epc += 4; // 필요시 epc 증가
         }
         5 => {
             // Load access fault
             kprintln!("\n[EXCEPTION] Load access fault");
             print_trap_context(ctx);
-            panic!("Load access fault at {:#x}, address: {:#x}", ctx.mepc, ctx.mtval);
+            set_fatal_frame(ctx);
+            panic!("Load access fault at {:#x}, address: {:#x}", ctx.epc, ctx.tval);
         }
         7 => {
             // Store access fault
             kprintln!("\n[EXCEPTION] Store access fault");
             print_trap_context(ctx);
-            panic!("Store access fault at {:#x}, address: {:#x}", ctx.mepc, ctx.mtval);
+            set_fatal_frame(ctx);
+            panic!("Store access fault at {:#x}, address: {:#x}", ctx.epc, ctx.tval);
         }
         8 | 9 | 11 => {
             // Environment call (U-mode: 8, S-mode: 9, M-mode: 11)
@@ -192,34 +250,49 @@ fn handle_exception(ctx: &mut TrapContext, cause: u64) {
             
             let ret = crate::syscall::syscall_handler(syscall_num, args);
             ctx.gpr[10] = ret as u64;  // 반환값을 a0에 저장
-            ctx.mepc += 4;  // ecall 다음 명령어로 (ecall은 4바이트)
+            ctx.epc += 4;  // ecall 다음 명령어로 (ecall은 4바이트)
         }
-        12 => {
-            // Instruction page fault
-            kprintln!("\n[EXCEPTION] Instruction page fault");
-            print_trap_context(ctx);
-            panic!("Instruction page fault at {:#x}, address: {:#x}", ctx.mepc, ctx.mtval);
-        }
-        13 => {
-            // Load page fault
-            kprintln!("\n[EXCEPTION] Load page fault");
-            print_trap_context(ctx);
-            panic!("Load page fault at {:#x}, address: {:#x}", ctx.mepc, ctx.mtval);
-        }
-        15 => {
-            // Store page fault
-            kprintln!("\n[EXCEPTION] Store page fault");
-            print_trap_context(ctx);
-            panic!("Store page fault at {:#x}, address: {:#x}", ctx.mepc, ctx.mtval);
+        12 | 13 | 15 => {
+            // Instruction/Load/Store page fault: demand paging
+            handle_page_fault(ctx, cause);
         }
         _ => {
             kprintln!("\n[EXCEPTION] Unhandled exception");
             print_trap_context(ctx);
+            set_fatal_frame(ctx);
             panic!("Unhandled exception: cause={}", cause);
         }
     }
 }
 
+/// 페이지 폴트 처리 (demand paging)
+///
+/// mm::vma에 위임하고, 처리할 수 없는 접근은 커널 패닉 대신
+/// 해당 스레드를 종료합니다. 트랩 프레임 안에서 끝내지 않고 SIGSEGV를 건 뒤, 커널 모드면
+/// 복귀 주소를 `vma::fault_exit`로 바꾸고 U-mode면 U-mode로 돌아가는 대신 바로
+/// `vma::fault_exit`를 부릅니다 (SIGSEGV를 무시해도 폴트를 되풀이하지 않음).
+fn handle_page_fault(ctx: &mut TrapContext, cause: u64) {
+    use crate::mm::vma::{self, FaultAccess};
+
+    let access = match cause {
+        12 => FaultAccess::Execute,
+        15 => FaultAccess::Write,
+        _ => FaultAccess::Read,
+    };
+    let addr = ctx.tval as usize;
+
+    if let Err(err) = vma::handle_page_fault(addr, access) {
+        print_trap_context(ctx);
+        let irqs_enabled = ctx.status as usize & csr::STATUS_PIE != 0;
+        vma::kill_faulting_thread(addr, ctx.epc as usize, access, err, irqs_enabled);
+        if ctx.status as usize & csr::STATUS_PP != 0 {
+            ctx.epc = vma::fault_exit as *const () as usize as u64;
+        } else {
+            vma::fault_exit();
+        }
+    }
+}
+
 // ============================================================================
 // Trap Vector (어셈블리)
 // ============================================================================
@@ -231,7 +304,7 @@ core::arch::global_asm!(
 .global trap_vector
 trap_vector:
     // 컨텍스트 저장을 위한 스택 공간 확보
-    // 32 GPRs + mstatus + mepc + mcause + mtval = 36 * 8 = 288 bytes
+    // 32 GPRs + status + epc + cause + tval = 36 * 8 = 288 bytes
     addi sp, sp, -288
 
     // x1-x31 저장 (x0은 항상 0)
@@ -267,15 +340,15 @@ trap_vector:
     sd x30, 240(sp)
     sd x31, 248(sp)
 
-    // CSR 레지스터 저장 (M-mode 레지스터 사용)
-    csrr t0, mstatus
-    csrr t1, mepc
-    csrr t2, mcause
-    csrr t3, mtval
-    sd t0, 256(sp)   // mstatus
-    sd t1, 264(sp)   // mepc
-    sd t2, 272(sp)   // mcause
-    sd t3, 280(sp)   // mtval
+    // CSR 레지스터 저장 (커널 모드의 status/epc/cause/tval)
+    csrr t0, {status}
+    csrr t1, {epc}
+    csrr t2, {cause}
+    csrr t3, {tval}
+    sd t0, 256(sp)   // status
+    sd t1, 264(sp)   // epc
+    sd t2, 272(sp)   // cause
+    sd t3, 280(sp)   // tval
 
     // 핸들러 호출 (a0 = sp = TrapContext 포인터)
     mv a0, sp
@@ -284,8 +357,8 @@ trap_vector:
     // CSR 레지스터 복원
     ld t0, 256(sp)
     ld t1, 264(sp)
-    csrw mstatus, t0
-    csrw mepc, t1
+    csrw {status}, t0
+    csrw {epc}, t1
 
     // x1-x31 복원
     ld x1, 8(sp)
@@ -323,30 +396,43 @@ trap_vector:
     // 스택 복원
     addi sp, sp, 288
 
-    // trap에서 복귀 (M-mode)
-    mret
-"#
+    // trap에서 복귀 (mret / sret)
+"#,
+    xret!(),
+    status = const csr::STATUS,
+    epc = const csr::EPC,
+    cause = const csr::CAUSE,
+    tval = const csr::TVAL,
 );
 
 /// Trap 벡터 초기화
-/// mtvec 레지스터에 trap 핸들러 주소 설정 (M-mode)
+/// mtvec / stvec 레지스터에 trap 핸들러 주소 설정
 pub fn init() {
     unsafe extern "C" {
         fn trap_vector();
     }
 
+    let vector = trap_vector as usize;
     unsafe {
-        let vector = trap_vector as usize;
-        // mtvec의 MODE 비트: 0 = Direct, 1 = Vectored
+        // tvec의 MODE 비트: 0 = Direct, 1 = Vectored
         // Direct 모드 사용 (모든 trap이 같은 주소로)
         core::arch::asm!(
-            "csrw mtvec, {0}",
+            "csrw {tvec}, {0}",
             in(reg) vector,
+            tvec = const csr::TVEC,
             options(nomem, nostack)
         );
-
-        crate::kprintln!("[riscv64] Trap vector initialized at {:#x} (M-mode)", vector);
     }
+
+    // S-mode는 sstatus.SUM 없이 U 페이지(시스템 콜 버퍼)에 접근할 수 없음
+    #[cfg(feature = "riscv_smode")]
+    csr::set_status(csr::STATUS_SUM);
+
+    crate::kprintln!(
+        "[riscv64] Trap vector initialized at {:#x} ({})",
+        vector,
+        csr::MODE_NAME
+    );
 }
 
 /// 테스트용: 브레이크포인트 발생
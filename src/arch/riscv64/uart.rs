@@ -1,6 +1,9 @@
 //! RISC-V NS16550A UART 드라이버
 //!
 //! QEMU virt 보드는 16550A 호환 UART를 사용합니다.
+//!
+//! `putc`/`getc`는 콘솔 UART, `*_at(base)`는 지정한 UART를 다룹니다
+//! (여러 UART는 `drivers::serial`이 관리).
 
 use core::ptr::{read_volatile, write_volatile};
 
@@ -22,14 +25,22 @@ const FCR: usize = 0x02;  // FIFO Control Register (쓰기)
 const LCR: usize = 0x03;  // Line Control Register
 const LSR: usize = 0x05;  // Line Status Register
 
+// IER 비트
+const IER_RX_AVAILABLE: u8 = 0x01;  // 수신 데이터 인터럽트
+
 // LSR 비트
 const LSR_RX_READY: u8 = 0x01;  // 데이터 수신 가능
 const LSR_TX_EMPTY: u8 = 0x20;  // TX 버퍼 비어있음
 
-/// UART 초기화
+/// UART 초기화 (콘솔 UART, 부팅 직후)
 pub fn init() {
+    init_port(uart_base());
+}
+
+/// `base`의 UART 초기화 (인터럽트 끔, FIFO, 8N1)
+pub fn init_port(base: usize) {
     unsafe {
-        let base = uart_base() as *mut u8;
+        let base = base as *mut u8;
 
         // 인터럽트 비활성화
         write_volatile(base.add(IER), 0x00);
@@ -42,20 +53,32 @@ pub fn init() {
     }
 }
 
+/// 문자 출력 (콘솔 UART, 폴링 방식)
 #[inline(always)]
 pub fn putc(c: u8) {
+    putc_at(uart_base(), c);
+}
+
+/// 문자 입력 (콘솔 UART, 폴링 방식)
+pub fn getc() -> Option<u8> {
+    getc_at(uart_base())
+}
+
+/// `base`의 UART로 문자 출력 (폴링 방식)
+#[inline(always)]
+pub fn putc_at(base: usize, c: u8) {
     unsafe {
-        let base = uart_base() as *mut u8;
+        let base = base as *mut u8;
         // TX 버퍼가 비어있을 때까지 대기
         while read_volatile(base.add(LSR)) & LSR_TX_EMPTY == 0 {}
         write_volatile(base.add(THR), c);
     }
 }
 
-/// 문자 입력 (폴링 방식)
-pub fn getc() -> Option<u8> {
+/// `base`의 UART에서 문자 입력 (폴링 방식)
+pub fn getc_at(base: usize) -> Option<u8> {
     unsafe {
-        let base = uart_base() as *mut u8;
+        let base = base as *mut u8;
 
         // 데이터가 수신되었는지 확인
         if read_volatile(base.add(LSR)) & LSR_RX_READY != 0 {
@@ -66,7 +89,18 @@ pub fn getc() -> Option<u8> {
     }
 }
 
-/// UART 인터럽트 핸들러 (현재는 폴링만 사용)
-pub fn handle_irq() {
-    // UART 인터럽트 처리 (필요시 구현)
+/// RX 인터럽트 활성화 (PLIC 초기화 후 호출)
+pub fn enable_rx_irq(base: usize) {
+    unsafe {
+        write_volatile((base as *mut u8).add(IER), IER_RX_AVAILABLE);
+    }
+}
+
+/// UART 인터럽트 핸들러
+///
+/// RX FIFO를 모두 비워 `receive`로 넘깁니다 (RBR을 읽으면 인터럽트가 내려감).
+pub fn handle_irq(base: usize, mut receive: impl FnMut(u8)) {
+    while let Some(ch) = getc_at(base) {
+        receive(ch);
+    }
 }
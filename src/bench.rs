@@ -0,0 +1,343 @@
+//! 커널 마이크로벤치마크 (`bench` 명령)
+//!
+//! 리팩터링 전후의 성능 차이를 숫자로 비교하기 위한 벤치마크 모음입니다. 연산 하나마다
+//! 단조 시계(`time::clock::monotonic_ns`)로 시간을 재서 초당 연산 수, 지연 백분위수
+//! (p50/p90/p99/max), 바이트 단위 연산이면 처리량을 출력합니다.
+//!
+//! | 이름 | 측정 |
+//! |------|------|
+//! | `heap` | 64B, 4KB 할당+해제 (`mm::heap`, slab) |
+//! | `ctxsw` | 같은 CPU에 고정한 두 스레드의 park/unpark 핑퐁 (스위칭 한 번) |
+//! | `ipc` | 메시지 큐 송신+수신 (`ipc::MessageQueue`) |
+//! | `vfs` | RamFS 파일 4KB 쓰기/읽기 |
+//! | `block` | RAM 디스크 4KB 쓰기/읽기 (`BlockDevice`) |
+//!
+//! 모듈은 `kernel_bench_run`으로 자기 함수를 같은 방식으로 잴 수 있습니다.
+//!
+//! 시계 해상도는 타이머 주파수를 따르므로(QEMU aarch64 16ns, riscv64 100ns) 그보다 짧은
+//! 연산은 0 또는 한 눈금으로 보입니다. 측정 중에 들어온 인터럽트는 p99/max에 나타납니다.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::alloc::Layout;
+use core::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+use crate::block::BlockDevice;
+use crate::fs::{self, FileMode, VNodeType};
+use crate::kprintln;
+use crate::proc::{self, percpu};
+use crate::sync::Mutex;
+use crate::time::clock::{monotonic_ns, Nanos};
+
+/// 기본 반복 횟수
+pub const DEFAULT_ITERATIONS: usize = 10_000;
+
+/// 최대 반복 횟수 (샘플 배열 크기 제한)
+pub const MAX_ITERATIONS: usize = 1_000_000;
+
+/// 본 측정 전에 버리는 반복 비율 (1/n)
+const WARMUP_DIVISOR: usize = 10;
+
+/// vfs/block 벤치마크의 연산 하나 크기
+const IO_SIZE: usize = 4096;
+
+/// vfs/block 벤치마크가 돌아가며 쓰는 영역 크기
+const IO_SPAN: usize = 1024 * 1024;
+
+/// 벤치마크 이름 (`bench` 인자)
+pub const BENCHES: [&str; 5] = ["heap", "ctxsw", "ipc", "vfs", "block"];
+
+/// 벤치마크 결과
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct BenchStats {
+    /// 측정한 연산 수
+    pub ops: u64,
+    /// 전체 시간 (나노초)
+    pub total_ns: u64,
+    pub p50_ns: u64,
+    pub p90_ns: u64,
+    pub p99_ns: u64,
+    pub max_ns: u64,
+}
+
+impl BenchStats {
+    /// 연산별 시간에서 통계 계산 (`samples`를 정렬함)
+    pub fn from_samples(samples: &mut [u64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+        // nearest-rank 백분위수
+        let rank = |p: usize| samples[(samples.len() * p).div_ceil(100).max(1) - 1];
+        Self {
+            ops: samples.len() as u64,
+            total_ns: samples.iter().sum(),
+            p50_ns: rank(50),
+            p90_ns: rank(90),
+            p99_ns: rank(99),
+            max_ns: samples[samples.len() - 1],
+        }
+    }
+
+    /// 초당 연산 수
+    pub fn ops_per_sec(&self) -> u64 {
+        if self.total_ns == 0 {
+            return 0;
+        }
+        (self.ops as u128 * 1_000_000_000 / self.total_ns as u128) as u64
+    }
+}
+
+/// `op(i)`를 `iterations`번 실행하며 연산별 시간 측정 (앞 1/10은 워밍업으로 버림)
+pub fn measure(iterations: usize, mut op: impl FnMut(usize)) -> BenchStats {
+    let iterations = iterations.clamp(1, MAX_ITERATIONS);
+    for i in 0..iterations / WARMUP_DIVISOR {
+        op(i);
+    }
+    let mut samples = Vec::with_capacity(iterations);
+    for i in 0..iterations {
+        let start = monotonic_ns();
+        op(i);
+        samples.push(monotonic_ns() - start);
+    }
+    BenchStats::from_samples(&mut samples)
+}
+
+/// 결과 표 머리글
+pub fn print_header() {
+    kprintln!(
+        "  {:<14} {:>8} {:>10} {:>8} {:>8} {:>8} {:>8} {:>10}",
+        "NAME",
+        "OPS",
+        "OPS/S",
+        "P50",
+        "P90",
+        "P99",
+        "MAX",
+        "MB/S"
+    );
+}
+
+/// 결과 한 줄 (`bytes_per_op`가 0이 아니면 처리량도)
+pub fn print_result(name: &str, stats: &BenchStats, bytes_per_op: usize) {
+    let throughput = if bytes_per_op != 0 && stats.total_ns != 0 {
+        let mb_per_sec = stats.ops as u128 * bytes_per_op as u128 * 1_000_000_000 / stats.total_ns as u128 / (1024 * 1024);
+        alloc::format!("{}", mb_per_sec)
+    } else {
+        String::from("-")
+    };
+    kprintln!(
+        "  {:<14} {:>8} {:>10} {:>8} {:>8} {:>8} {:>8} {:>10}",
+        name,
+        stats.ops,
+        stats.ops_per_sec(),
+        Nanos(stats.p50_ns),
+        Nanos(stats.p90_ns),
+        Nanos(stats.p99_ns),
+        Nanos(stats.max_ns),
+        throughput
+    );
+}
+
+/// 이름으로 벤치마크 실행 후 결과 출력
+///
+/// # Returns
+/// 알 수 없는 이름이거나 준비(파일/디스크 생성)에 실패하면 Err
+pub fn run(name: &str, iterations: usize) -> Result<(), &'static str> {
+    match name {
+        "heap" => {
+            for (label, size) in [("heap.64", 64), ("heap.4k", 4096)] {
+                let stats = bench_heap(size, iterations);
+                print_result(label, &stats, 0);
+            }
+        }
+        "ctxsw" => {
+            let stats = bench_ctxsw(iterations);
+            print_result("ctxsw", &stats, 0);
+        }
+        "ipc" => {
+            let stats = bench_ipc(iterations);
+            print_result("ipc.mq", &stats, 0);
+        }
+        "vfs" => {
+            let (write, read) = bench_vfs(iterations)?;
+            print_result("vfs.write4k", &write, IO_SIZE);
+            print_result("vfs.read4k", &read, IO_SIZE);
+        }
+        "block" => {
+            let (write, read) = bench_block(iterations)?;
+            print_result("block.write4k", &write, IO_SIZE);
+            print_result("block.read4k", &read, IO_SIZE);
+        }
+        _ => return Err("unknown benchmark"),
+    }
+    Ok(())
+}
+
+/// `size` 바이트 할당+해제
+fn bench_heap(size: usize, iterations: usize) -> BenchStats {
+    let layout = Layout::from_size_align(size, 8).unwrap();
+    measure(iterations, |_| {
+        // Safety: 0이 아닌 크기, 할당한 레이아웃 그대로 해제
+        unsafe {
+            let ptr = alloc::alloc::alloc(layout);
+            if !ptr.is_null() {
+                core::ptr::write_volatile(ptr, 1);
+                alloc::alloc::dealloc(ptr, layout);
+            }
+        }
+    })
+}
+
+/// 메시지 큐에 보내고 바로 받기 (같은 스레드, 대기 없음)
+fn bench_ipc(iterations: usize) -> BenchStats {
+    let queue = crate::ipc::MessageQueue::<u64>::new();
+    measure(iterations, |i| {
+        let _ = queue.send(i as u64);
+        let _ = queue.try_receive();
+    })
+}
+
+/// RamFS 파일에 4KB씩 쓰고 읽기 (1MB 영역을 돌아가며)
+fn bench_vfs(iterations: usize) -> Result<(BenchStats, BenchStats), &'static str> {
+    const NAME: &str = ".bench";
+    let root = fs::lookup_path("/").map_err(|_| "no root filesystem")?;
+    let file = root
+        .create(NAME, VNodeType::File, FileMode::default_file())
+        .map_err(|_| "cannot create /.bench")?;
+
+    let mut buf = alloc::vec![0x5Au8; IO_SIZE];
+    let offset = |i: usize| (i * IO_SIZE) % IO_SPAN;
+    let write = measure(iterations, |i| {
+        let _ = file.write(offset(i), &buf);
+    });
+    let read = measure(iterations, |i| {
+        let _ = file.read(offset(i), &mut buf);
+    });
+
+    drop(file);
+    let _ = root.unlink(NAME);
+    Ok((write, read))
+}
+
+/// 등록하지 않은 1MB RAM 디스크에 4KB씩 쓰고 읽기
+fn bench_block(iterations: usize) -> Result<(BenchStats, BenchStats), &'static str> {
+    let disk = crate::block::ramdisk::RamDisk::new_default("bench", IO_SPAN);
+    let blocks_per_op = (IO_SIZE / disk.block_size()) as u64;
+    let total_ops = (IO_SPAN / IO_SIZE) as u64;
+    if blocks_per_op == 0 {
+        return Err("block size larger than 4KB");
+    }
+
+    let mut buf = alloc::vec![0xA5u8; IO_SIZE];
+    let block = |i: usize| (i as u64 % total_ops) * blocks_per_op;
+    let write = measure(iterations, |i| {
+        let _ = disk.write_blocks(block(i), &buf);
+    });
+    let read = measure(iterations, |i| {
+        let _ = disk.read_blocks(block(i), &mut buf);
+    });
+    Ok((write, read))
+}
+
+// ---- ctxsw: park/unpark 핑퐁 ----
+
+/// 핑 스레드 (측정)
+static PING: AtomicU64 = AtomicU64::new(0);
+/// 퐁 스레드 (응답)
+static PONG: AtomicU64 = AtomicU64::new(0);
+/// 측정할 왕복 수
+static ROUNDS: AtomicUsize = AtomicUsize::new(0);
+/// 퐁 종료 요청
+static STOP: AtomicBool = AtomicBool::new(false);
+/// 핑이 남긴 통계
+static RESULT: Mutex<Option<BenchStats>> = Mutex::new(None);
+
+/// 같은 CPU의 두 스레드가 서로 깨우고 잠드는 데 걸린 시간 (왕복의 절반 = 스위칭 한 번)
+fn bench_ctxsw(iterations: usize) -> BenchStats {
+    let cpu = percpu::get_cpu_id();
+    ROUNDS.store(iterations, Ordering::Relaxed);
+    STOP.store(false, Ordering::Relaxed);
+    *RESULT.lock() = None;
+
+    let pong = proc::spawn_on_cpu("bench-pong", pong_thread, cpu);
+    PONG.store(pong, Ordering::Release);
+    let ping = proc::spawn_on_cpu("bench-ping", ping_thread, cpu);
+    let _ = proc::join(ping);
+    let _ = proc::join(pong);
+
+    RESULT.lock().take().unwrap_or_default()
+}
+
+fn ping_thread() -> ! {
+    let Some(me) = proc::current_tid() else {
+        proc::exit();
+    };
+    PING.store(me, Ordering::Release);
+    let pong = PONG.load(Ordering::Acquire);
+
+    let stats = measure(ROUNDS.load(Ordering::Relaxed), |_| {
+        proc::unpark(pong);
+        proc::park();
+    });
+    // 왕복에 스위칭 두 번
+    let half = BenchStats {
+        p50_ns: stats.p50_ns / 2,
+        p90_ns: stats.p90_ns / 2,
+        p99_ns: stats.p99_ns / 2,
+        max_ns: stats.max_ns / 2,
+        ops: stats.ops * 2,
+        total_ns: stats.total_ns,
+    };
+    *RESULT.lock() = Some(half);
+
+    STOP.store(true, Ordering::Release);
+    proc::unpark(pong);
+    proc::exit();
+}
+
+fn pong_thread() -> ! {
+    loop {
+        proc::park();
+        if STOP.load(Ordering::Acquire) {
+            proc::exit();
+        }
+        proc::unpark(PING.load(Ordering::Acquire));
+    }
+}
+
+// ---- 모듈 API ----
+
+/// 모듈 함수 `func(arg)`를 `iterations`번 측정하고 결과 출력
+///
+/// `out`이 null이 아니면 통계를 채웁니다.
+/// 반환: 0 = 성공, -1 = 잘못된 인자
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_bench_run(
+    name: *const u8,
+    name_len: usize,
+    iterations: usize,
+    func: extern "C" fn(usize),
+    arg: usize,
+    out: *mut BenchStats,
+) -> i32 {
+    if name.is_null() || iterations == 0 {
+        return -1;
+    }
+    // Safety: 모듈이 넘긴 (포인터, 길이)
+    let name = unsafe { core::slice::from_raw_parts(name, name_len) };
+    let Ok(name) = core::str::from_utf8(name) else {
+        return -1;
+    };
+
+    let stats = measure(iterations, |_| func(arg));
+    print_header();
+    print_result(name, &stats, 0);
+    if !out.is_null() {
+        // Safety: 모듈이 넘긴 BenchStats 포인터
+        unsafe { out.write(stats) };
+    }
+    0
+}
+crate::export_symbol!(kernel_bench_run);
@@ -10,7 +10,10 @@ use core::fmt;
 
 use crate::sync::RwLock;
 
+pub mod elevator;
+pub mod partition;
 pub mod ramdisk;
+pub mod request;
 pub mod virtio_blk;
 
 /// 블록 디바이스 에러
@@ -28,6 +31,8 @@ pub enum BlockError {
     ReadOnly,
     /// 디바이스가 준비되지 않음
     NotReady,
+    /// 디바이스가 지원하지 않는 요청
+    NotSupported,
 }
 
 impl fmt::Display for BlockError {
@@ -39,6 +44,7 @@ impl fmt::Display for BlockError {
             BlockError::BufferSizeMismatch => write!(f, "buffer size mismatch"),
             BlockError::ReadOnly => write!(f, "read-only device"),
             BlockError::NotReady => write!(f, "device not ready"),
+            BlockError::NotSupported => write!(f, "operation not supported"),
         }
     }
 }
@@ -46,6 +52,18 @@ impl fmt::Display for BlockError {
 /// 블록 디바이스 결과 타입
 pub type BlockResult<T> = Result<T, BlockError>;
 
+/// 블록 디바이스 ioctl 명령 (Linux `<linux/fs.h>`, `/dev/vdX` 노드)
+pub mod ioctl {
+    /// 읽기 전용 여부 (`int`, 1 = 읽기 전용)
+    pub const BLKROGET: u32 = 0x125E;
+    /// 버퍼 비우기 (디바이스 `sync`)
+    pub const BLKFLSBUF: u32 = 0x1261;
+    /// 논리 섹터(블록) 크기 (`int`)
+    pub const BLKSSZGET: u32 = 0x1268;
+    /// 디바이스 크기 (`u64` 바이트)
+    pub const BLKGETSIZE64: u32 = 0x8008_1272;
+}
+
 /// 블록 디바이스 trait
 ///
 /// 모든 블록 디바이스는 이 trait을 구현해야 합니다.
@@ -107,6 +125,22 @@ pub trait BlockDevice: Send + Sync {
         Ok(())
     }
 
+    /// 블록 버리기 (discard/TRIM)
+    ///
+    /// 더 이상 쓰지 않는 블록을 디바이스에 알려 공간을 돌려주게 합니다. 버린 블록을 읽은
+    /// 값은 정해져 있지 않습니다. 기본 구현은 지원하지 않는 디바이스로 `NotSupported`입니다.
+    fn discard(&self, _start_block: u64, _count: u64) -> BlockResult<()> {
+        Err(BlockError::NotSupported)
+    }
+
+    /// 블록을 0으로 채움
+    ///
+    /// 기본 구현은 0 버퍼를 `write_blocks`로 씁니다. 디바이스 명령이 있으면 데이터를 보내지
+    /// 않도록 재정의합니다.
+    fn write_zeroes(&self, start_block: u64, count: u64) -> BlockResult<()> {
+        zero_fill(self, start_block, count)
+    }
+
     /// 읽기 전용 여부
     fn is_read_only(&self) -> bool {
         false
@@ -116,6 +150,44 @@ pub trait BlockDevice: Send + Sync {
     fn capacity(&self) -> u64 {
         self.block_count() * self.block_size() as u64
     }
+
+    /// I/O 스케줄러 통계 (스케줄러가 없는 디바이스는 None)
+    fn io_stats(&self) -> Option<elevator::IoStats> {
+        None
+    }
+
+    /// 패닉 경로용 쓰기 (힙, 스케줄러, 인터럽트 없이 끝날 때까지 폴링)
+    ///
+    /// 크래시 덤프(`debug::crashdump`)가 씁니다. 락은 `try_*`로만 잡아 이미 잡혀 있으면
+    /// `NotReady`이며, 기본 구현은 지원하지 않는 디바이스로 `NotReady`입니다.
+    fn panic_write(&self, _start_block: u64, _buf: &[u8]) -> BlockResult<()> {
+        Err(BlockError::NotReady)
+    }
+}
+
+/// `write_zeroes` 기본 구현: 0 버퍼를 최대 64KB씩 씀
+///
+/// 디바이스 명령을 쓰는 구현이 지원하지 않는 디바이스에서 되돌아올 때도 부릅니다.
+pub fn zero_fill<D: BlockDevice + ?Sized>(device: &D, start_block: u64, count: u64) -> BlockResult<()> {
+    const CHUNK_BYTES: usize = 64 * 1024;
+
+    let block_size = device.block_size();
+    match start_block.checked_add(count) {
+        Some(end) if end <= device.block_count() => {}
+        _ => return Err(BlockError::InvalidBlock),
+    }
+    let per_chunk = (CHUNK_BYTES / block_size).max(1) as u64;
+    let zero = alloc::vec![0u8; per_chunk.min(count) as usize * block_size];
+
+    let mut block = start_block;
+    let mut left = count;
+    while left > 0 {
+        let n = left.min(per_chunk);
+        device.write_blocks(block, &zero[..n as usize * block_size])?;
+        block += n;
+        left -= n;
+    }
+    Ok(())
 }
 
 /// 등록된 블록 디바이스
@@ -176,6 +248,7 @@ pub struct BlockDeviceInfo {
     pub block_count: u64,
     pub capacity: u64,
     pub read_only: bool,
+    pub stats: Option<elevator::IoStats>,
 }
 
 /// 블록 디바이스 정보 조회
@@ -189,6 +262,7 @@ pub fn device_info(name: &str) -> Option<BlockDeviceInfo> {
             block_count: d.device.block_count(),
             capacity: d.device.capacity(),
             read_only: d.device.is_read_only(),
+            stats: d.device.io_stats(),
         })
 }
 
@@ -201,6 +275,12 @@ pub fn init() {
         register_device("vda", vda);
     }
 
+    // 실제 보드의 SD 카드 (DTB SDHCI 노드가 있으면 mmcblk0, ...)
+    crate::drivers::sdhci::init();
+
+    // 파티션 테이블이 있으면 vda1, mmcblk0p1, ... 등록
+    partition::scan_all();
+
     let devices = list_devices();
     if devices.is_empty() {
         crate::kprintln!("[block] No block devices found");
@@ -208,3 +288,33 @@ pub fn init() {
         crate::kprintln!("[block] {} device(s) available: {:?}", devices.len(), devices);
     }
 }
+
+/// 명령줄 `root=<dev>` 마운트 위치 (루트는 RamFS이므로 FAT32는 여기에 붙임)
+const ROOT_MOUNT_PATH: &str = "/mnt";
+
+/// bootargs의 `root=<dev>` 처리 (DevFS에 블록 디바이스 등록 후 호출)
+///
+/// 지정한 디바이스의 FAT32를 `/mnt`에 마운트합니다. 셸의 `mount <dev>`와 같습니다.
+pub fn mount_root_from_cmdline() {
+    let Some(name) = crate::boot::cmdline::root() else {
+        return;
+    };
+    let Some(device) = get_device(&name) else {
+        crate::kprintln!("[block] root={}: device not found", name);
+        return;
+    };
+
+    // 마운트 포인트 디렉토리 생성 (ls에서 보이도록)
+    if let Ok(root) = crate::fs::lookup_path("/") {
+        let _ = root.create(
+            crate::fs::path::basename(ROOT_MOUNT_PATH),
+            crate::fs::VNodeType::Directory,
+            crate::fs::FileMode::default_dir(),
+        );
+    }
+
+    match crate::fs::fat32::mount_fat32(device).and_then(|fs| crate::fs::mount(ROOT_MOUNT_PATH, fs)) {
+        Ok(()) => crate::kprintln!("[block] root=/dev/{} mounted at {}", name, ROOT_MOUNT_PATH),
+        Err(e) => crate::kprintln!("[block] root=/dev/{}: mount failed: {:?}", name, e),
+    }
+}
@@ -0,0 +1,325 @@
+//! 파티션 테이블 (MBR/GPT)
+//!
+//! 등록된 블록 디바이스의 파티션 테이블을 읽어 파티션마다 자식 디바이스(`vda1`, `vda2`, ...)를
+//! 등록합니다. 자식 디바이스는 블록 번호에 시작 LBA를 더해 부모에게 넘기는 래퍼입니다.
+//!
+//! ## 지원 범위
+//! - MBR: 주 파티션 4개 (확장 파티션 안의 논리 파티션은 아직 없음)
+//! - GPT: 보호 MBR(0xEE) 뒤 LBA 1의 헤더와 파티션 엔트리 배열 (CRC는 확인하지 않음)
+//!
+//! 파티션 테이블 없이 통째로 포맷된 디스크(FAT32 부트 섹터도 0x55AA로 끝남)는
+//! 부트 섹터의 파일시스템 시그니처로 구분해 건너뜁니다.
+//!
+//! ## 사용 예시
+//! ```rust
+//! partition::scan_all();                      // block::init()에서 자동 호출
+//! let part = block::get_device("vda1").unwrap();
+//! let fs = fs::fat32::mount_fat32(part)?;
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::sync::RwLock;
+
+use super::{BlockDevice, BlockError, BlockResult};
+
+/// MBR 파티션 엔트리 시작 오프셋
+const MBR_TABLE_OFFSET: usize = 0x1BE;
+/// MBR 파티션 엔트리 크기
+const MBR_ENTRY_SIZE: usize = 16;
+/// GPT 보호 MBR 파티션 타입
+const MBR_TYPE_GPT_PROTECTIVE: u8 = 0xEE;
+/// MBR 확장 파티션 타입 (CHS, LBA, Linux)
+const MBR_TYPES_EXTENDED: [u8; 3] = [0x05, 0x0F, 0x85];
+
+/// GPT 헤더 시그니처
+const GPT_SIGNATURE: &[u8; 8] = b"EFI PART";
+/// GPT 엔트리 수 상한 (손상된 헤더가 과도한 읽기를 일으키지 않도록)
+const GPT_MAX_ENTRIES: u32 = 128;
+
+/// 등록한 파티션 디바이스 이름
+static PARTITIONS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// 파티션 테이블 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableKind {
+    Mbr,
+    Gpt,
+}
+
+/// 파티션 위치 (부모 디바이스 블록 단위)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartitionEntry {
+    /// 1부터 시작하는 파티션 번호
+    pub index: u32,
+    /// 시작 LBA
+    pub start: u64,
+    /// 블록 수
+    pub count: u64,
+}
+
+/// 파티션 블록 디바이스
+///
+/// 부모 디바이스의 `[start, start + count)` 범위만 보이며, 범위 밖 접근은 `InvalidBlock`입니다.
+pub struct Partition {
+    /// 디바이스 이름 (`vda1` 등)
+    name: String,
+    /// 부모 디바이스
+    parent: Arc<dyn BlockDevice>,
+    /// 시작 LBA
+    start: u64,
+    /// 블록 수
+    count: u64,
+}
+
+impl Partition {
+    /// 새 파티션 디바이스 생성
+    pub fn new(name: &str, parent: Arc<dyn BlockDevice>, start: u64, count: u64) -> Self {
+        Self {
+            name: String::from(name),
+            parent,
+            start,
+            count,
+        }
+    }
+
+    /// `block_num`부터 `buf`만큼의 블록을 부모 블록 번호로 변환
+    fn translate(&self, block_num: u64, len: usize) -> BlockResult<u64> {
+        let block_size = self.block_size();
+        if len % block_size != 0 {
+            return Err(BlockError::BufferSizeMismatch);
+        }
+        self.translate_blocks(block_num, (len / block_size) as u64)
+    }
+
+    /// 파티션 안 `blocks`개 범위의 시작 블록을 부모 디바이스 블록 번호로 변환
+    fn translate_blocks(&self, block_num: u64, blocks: u64) -> BlockResult<u64> {
+        match block_num.checked_add(blocks) {
+            Some(end) if end <= self.count => Ok(self.start + block_num),
+            _ => Err(BlockError::InvalidBlock),
+        }
+    }
+}
+
+impl BlockDevice for Partition {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn block_size(&self) -> usize {
+        self.parent.block_size()
+    }
+
+    fn block_count(&self) -> u64 {
+        self.count
+    }
+
+    fn read_block(&self, block_num: u64, buf: &mut [u8]) -> BlockResult<()> {
+        if buf.len() != self.block_size() {
+            return Err(BlockError::BufferSizeMismatch);
+        }
+        let lba = self.translate(block_num, buf.len())?;
+        self.parent.read_block(lba, buf)
+    }
+
+    fn write_block(&self, block_num: u64, buf: &[u8]) -> BlockResult<()> {
+        if buf.len() != self.block_size() {
+            return Err(BlockError::BufferSizeMismatch);
+        }
+        let lba = self.translate(block_num, buf.len())?;
+        self.parent.write_block(lba, buf)
+    }
+
+    fn read_blocks(&self, start_block: u64, buf: &mut [u8]) -> BlockResult<()> {
+        let lba = self.translate(start_block, buf.len())?;
+        self.parent.read_blocks(lba, buf)
+    }
+
+    fn write_blocks(&self, start_block: u64, buf: &[u8]) -> BlockResult<()> {
+        let lba = self.translate(start_block, buf.len())?;
+        self.parent.write_blocks(lba, buf)
+    }
+
+    fn discard(&self, start_block: u64, count: u64) -> BlockResult<()> {
+        let lba = self.translate_blocks(start_block, count)?;
+        self.parent.discard(lba, count)
+    }
+
+    fn write_zeroes(&self, start_block: u64, count: u64) -> BlockResult<()> {
+        let lba = self.translate_blocks(start_block, count)?;
+        self.parent.write_zeroes(lba, count)
+    }
+
+    fn sync(&self) -> BlockResult<()> {
+        self.parent.sync()
+    }
+
+    fn panic_write(&self, start_block: u64, buf: &[u8]) -> BlockResult<()> {
+        let lba = self.translate(start_block, buf.len())?;
+        self.parent.panic_write(lba, buf)
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.parent.is_read_only()
+    }
+}
+
+fn le_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([buf[offset], buf[offset + 1], buf[offset + 2], buf[offset + 3]])
+}
+
+fn le_u64(buf: &[u8], offset: usize) -> u64 {
+    (le_u32(buf, offset) as u64) | ((le_u32(buf, offset + 4) as u64) << 32)
+}
+
+/// 파티션 테이블이 아닌 파일시스템 부트 섹터인지 (FAT12/16/32)
+fn is_volume_boot_sector(sector: &[u8]) -> bool {
+    sector[0x36..0x39] == *b"FAT" || sector[0x52..0x57] == *b"FAT32"
+}
+
+/// 파티션 테이블 읽기
+///
+/// # Returns
+/// 테이블 종류와 비어 있지 않은 파티션 목록 (테이블이 없으면 `None`)
+pub fn read_table(device: &dyn BlockDevice) -> BlockResult<Option<(TableKind, Vec<PartitionEntry>)>> {
+    let block_size = device.block_size();
+    if block_size < 512 {
+        return Ok(None);
+    }
+    let mut sector = vec![0u8; block_size];
+    device.read_block(0, &mut sector)?;
+
+    if sector[510] != 0x55 || sector[511] != 0xAA || is_volume_boot_sector(&sector) {
+        return Ok(None);
+    }
+
+    let mut entries = Vec::new();
+    for i in 0..4 {
+        let entry = &sector[MBR_TABLE_OFFSET + i * MBR_ENTRY_SIZE..][..MBR_ENTRY_SIZE];
+        // 부트 플래그는 0x00 또는 0x80뿐 (그 외 값이면 MBR이 아님)
+        if entry[0] != 0x00 && entry[0] != 0x80 {
+            return Ok(None);
+        }
+        let kind = entry[4];
+        if kind == MBR_TYPE_GPT_PROTECTIVE {
+            return read_gpt(device).map(|parts| parts.map(|p| (TableKind::Gpt, p)));
+        }
+        let start = le_u32(entry, 8) as u64;
+        let count = le_u32(entry, 12) as u64;
+        if kind == 0 || count == 0 || MBR_TYPES_EXTENDED.contains(&kind) {
+            continue;
+        }
+        entries.push(PartitionEntry {
+            index: i as u32 + 1,
+            start,
+            count,
+        });
+    }
+    Ok(Some((TableKind::Mbr, entries)))
+}
+
+/// GPT 헤더(LBA 1)와 파티션 엔트리 배열 읽기
+fn read_gpt(device: &dyn BlockDevice) -> BlockResult<Option<Vec<PartitionEntry>>> {
+    let block_size = device.block_size();
+    let mut header = vec![0u8; block_size];
+    device.read_block(1, &mut header)?;
+    if header[0..8] != *GPT_SIGNATURE {
+        return Ok(None);
+    }
+
+    let entries_lba = le_u64(&header, 72);
+    let entry_count = le_u32(&header, 80).min(GPT_MAX_ENTRIES) as usize;
+    let entry_size = le_u32(&header, 84) as usize;
+    if entry_size < 128 || entry_size > block_size || block_size % entry_size != 0 {
+        return Ok(None);
+    }
+
+    let per_block = block_size / entry_size;
+    let mut entries = Vec::new();
+    let mut block = vec![0u8; block_size];
+    for i in 0..entry_count {
+        if i % per_block == 0 {
+            device.read_block(entries_lba + (i / per_block) as u64, &mut block)?;
+        }
+        let entry = &block[(i % per_block) * entry_size..][..entry_size];
+        // 타입 GUID가 0이면 빈 엔트리
+        if entry[0..16].iter().all(|&b| b == 0) {
+            continue;
+        }
+        let first = le_u64(entry, 32);
+        let last = le_u64(entry, 40);
+        if last < first {
+            continue;
+        }
+        entries.push(PartitionEntry {
+            index: i as u32 + 1,
+            start: first,
+            count: last - first + 1,
+        });
+    }
+    Ok(Some(entries))
+}
+
+/// 파티션 디바이스 이름 (`vda` → `vda1`, `mmcblk0` → `mmcblk0p1`)
+pub fn partition_name(disk: &str, index: u32) -> String {
+    if disk.ends_with(|c: char| c.is_ascii_digit()) {
+        format!("{}p{}", disk, index)
+    } else {
+        format!("{}{}", disk, index)
+    }
+}
+
+/// 디바이스의 파티션을 찾아 자식 디바이스로 등록
+///
+/// 이미 등록된 파티션 이름은 건너뜁니다.
+///
+/// # Returns
+/// 새로 등록한 파티션 수
+pub fn scan_device(name: &str) -> BlockResult<usize> {
+    let device = super::get_device(name).ok_or(BlockError::DeviceNotFound)?;
+    let Some((kind, entries)) = read_table(&*device)? else {
+        return Ok(0);
+    };
+    crate::kprintln!("[block] {}: {:?} partition table, {} partition(s)", name, kind, entries.len());
+
+    let mut registered = 0;
+    for entry in entries {
+        let part_name = partition_name(name, entry.index);
+        let in_range = entry.start > 0
+            && entry.start.checked_add(entry.count).is_some_and(|end| end <= device.block_count());
+        if !in_range {
+            crate::kprintln!("[block] {}: partition out of range (start {}, {} blocks), skipped",
+                part_name,
+                entry.start,
+                entry.count
+            );
+            continue;
+        }
+        if super::get_device(&part_name).is_some() {
+            continue;
+        }
+        let part = Partition::new(&part_name, device.clone(), entry.start, entry.count);
+        super::register_device(&part_name, Arc::new(part));
+        PARTITIONS.write().push(part_name);
+        registered += 1;
+    }
+    Ok(registered)
+}
+
+/// 등록된 모든 디바이스의 파티션 스캔
+///
+/// 파티션 디바이스 자신은 다시 스캔하지 않습니다 (중첩 파티션 테이블 미지원).
+pub fn scan_all() {
+    for name in super::list_devices() {
+        if PARTITIONS.read().contains(&name) {
+            continue;
+        }
+        if let Err(e) = scan_device(&name) {
+            crate::kprintln!("[block] {}: partition scan failed: {}", name, e);
+        }
+    }
+}
@@ -7,6 +7,7 @@ use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
 
+use crate::debug::failinject::{self, Point};
 use crate::sync::RwLock;
 
 use super::{BlockDevice, BlockError, BlockResult};
@@ -107,6 +108,9 @@ impl BlockDevice for RamDisk {
     }
 
     fn read_block(&self, block_num: u64, buf: &mut [u8]) -> BlockResult<()> {
+        if failinject::should_fail(Point::BlockRead) {
+            return Err(BlockError::IoError);
+        }
         if buf.len() != self.block_size {
             return Err(BlockError::BufferSizeMismatch);
         }
@@ -144,9 +148,45 @@ impl BlockDevice for RamDisk {
         Ok(())
     }
 
+    /// 버린 블록은 0으로 읽힘
+    fn discard(&self, start_block: u64, count: u64) -> BlockResult<()> {
+        self.write_zeroes(start_block, count)
+    }
+
+    fn write_zeroes(&self, start_block: u64, count: u64) -> BlockResult<()> {
+        if self.read_only {
+            return Err(BlockError::ReadOnly);
+        }
+        let mut data = self.data.write();
+        let offset = start_block as usize * self.block_size;
+        let end = match (count as usize).checked_mul(self.block_size).and_then(|len| offset.checked_add(len)) {
+            Some(end) if end <= data.len() => end,
+            _ => return Err(BlockError::InvalidBlock),
+        };
+        data[offset..end].fill(0);
+        Ok(())
+    }
+
     fn is_read_only(&self) -> bool {
         self.read_only
     }
+
+    fn panic_write(&self, start_block: u64, buf: &[u8]) -> BlockResult<()> {
+        if self.read_only {
+            return Err(BlockError::ReadOnly);
+        }
+        if buf.len() % self.block_size != 0 {
+            return Err(BlockError::BufferSizeMismatch);
+        }
+        let mut data = self.data.try_write().ok_or(BlockError::NotReady)?;
+        let offset = start_block as usize * self.block_size;
+        let end = offset + buf.len();
+        if end > data.len() {
+            return Err(BlockError::InvalidBlock);
+        }
+        data[offset..end].copy_from_slice(buf);
+        Ok(())
+    }
 }
 
 /// RAM 디스크 생성 및 등록 헬퍼
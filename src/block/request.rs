@@ -0,0 +1,104 @@
+//! 블록 I/O 요청과 완료 대기
+//!
+//! 드라이버는 요청을 디바이스 큐에 넣고 바로 `Completion`을 돌려주며, 완료는
+//! 인터럽트 핸들러(`virtio::irq`)가 `complete()`로 알립니다. 호출자는 `wait()`에서
+//! 완료될 때까지 다른 스레드에 CPU를 양보하므로, 여러 스레드의 요청이 동시에
+//! 디바이스에 올라가 있을 수 있습니다.
+//!
+//! 인터럽트 컨텍스트에서는 `THREADS` 락을 잡을 수 없으므로 대기자를 Blocked로
+//! 재우지 않고 양보하며 완료 플래그를 확인합니다. 실행할 다른 스레드가 없으면
+//! `wfi`로 다음 인터럽트까지 CPU를 쉬게 합니다.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::{BlockError, BlockResult};
+
+/// 요청 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestOp {
+    Read,
+    Write,
+    Flush,
+    /// 블록 버리기 (데이터 없음)
+    Discard,
+    /// 블록을 0으로 채움 (데이터 없음)
+    WriteZeroes,
+}
+
+/// 요청 완료 대기 객체
+///
+/// 요청 하나당 하나씩 만들어 드라이버(완료 쪽)와 호출자(대기 쪽)가 공유합니다.
+pub struct Completion {
+    done: AtomicBool,
+    /// 디바이스가 보고한 성공 여부
+    ok: AtomicBool,
+}
+
+impl Completion {
+    pub const fn new() -> Self {
+        Self {
+            done: AtomicBool::new(false),
+            ok: AtomicBool::new(false),
+        }
+    }
+
+    /// 완료 알림 (인터럽트 컨텍스트에서 호출 가능)
+    pub fn complete(&self, result: BlockResult<()>) {
+        self.ok.store(result.is_ok(), Ordering::Relaxed);
+        self.done.store(true, Ordering::Release);
+    }
+
+    /// 완료됐는지
+    pub fn is_done(&self) -> bool {
+        self.done.load(Ordering::Acquire)
+    }
+
+    fn result(&self) -> BlockResult<()> {
+        if self.ok.load(Ordering::Relaxed) {
+            Ok(())
+        } else {
+            Err(BlockError::IoError)
+        }
+    }
+
+    /// 완료될 때까지 대기
+    ///
+    /// `poll`은 매 반복마다 호출되어 인터럽트를 놓친 경우에도 드라이버가 완료된
+    /// 요청을 거둘 수 있게 합니다.
+    pub fn wait(&self, mut poll: impl FnMut()) -> BlockResult<()> {
+        loop {
+            if self.is_done() {
+                return self.result();
+            }
+            poll();
+            if self.is_done() {
+                return self.result();
+            }
+            relax();
+        }
+    }
+}
+
+impl Default for Completion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 완료나 큐 공간을 기다리는 동안 CPU 양보
+///
+/// 시그널 전달(`yield_now`)은 하지 않습니다. 디바이스가 아직 호출자 버퍼에 DMA 중일
+/// 수 있으므로 대기 도중 스레드가 끝나면 안 됩니다.
+pub fn relax() {
+    if crate::proc::current_tid().is_some() && crate::proc::scheduler::ready_count() > 0 {
+        crate::proc::scheduler::schedule();
+        return;
+    }
+
+    // 다음 인터럽트(I/O 완료 또는 타이머)까지 대기
+    #[cfg(target_arch = "aarch64")]
+    unsafe { core::arch::asm!("wfi"); }
+
+    #[cfg(target_arch = "riscv64")]
+    unsafe { core::arch::asm!("wfi"); }
+}
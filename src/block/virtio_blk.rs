@@ -1,6 +1,25 @@
 //! VirtIO 블록 드라이버
 //!
 //! VirtIO MMIO 기반 블록 디바이스 드라이버
+//!
+//! 읽기/쓰기는 요청 헤더·데이터·상태 descriptor 체인을 큐에 넣고 `Completion`으로
+//! 완료를 기다립니다. 완료는 IRQ 핸들러가 used 링을 비우며 알리고, 인터럽트를
+//! 놓친 경우를 대비해 대기자도 used 링을 확인합니다. 큐에 descriptor가 남아 있는 한
+//! 여러 스레드의 요청이 동시에 디바이스에 올라갈 수 있습니다.
+//!
+//! 요청은 먼저 엘리베이터(`block::elevator`)에 들어가고, 디바이스가 비어 있을 때
+//! 블록 번호순으로 정렬·병합되어 나갑니다. 병합된 요청은 데이터 descriptor를 여러 개
+//! 가진 하나의 디바이스 요청이 됩니다.
+//!
+//! 패닉 경로(`panic_write`)는 엘리베이터와 완료 대기를 거치지 않고 요청 하나를 직접
+//! 올린 뒤 used 링을 폴링합니다.
+//!
+//! 디바이스가 지원하면 다음 기능을 협상합니다.
+//! - `FLUSH`: `sync()`가 디바이스 쓰기 캐시를 비움
+//! - `DISCARD`, `WRITE_ZEROES`: `discard()`/`write_zeroes()`를 데이터 없는 요청 하나로 처리
+//!   (파일 시스템이 해제한 클러스터를 알리거나 새 클러스터를 0으로 채울 때)
+//! - `MQ`: SMP 구성에서 CPU마다 virtqueue를 하나씩 두고 현재 CPU의 큐에 요청을 넣어
+//!   큐 락 경합을 줄임. 완료는 모든 큐를 훑어 거둠 (MMIO 디바이스는 IRQ가 하나)
 
 extern crate alloc;
 
@@ -8,13 +27,20 @@ use alloc::boxed::Box;
 use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec;
-use core::sync::atomic::{AtomicBool, Ordering};
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use crate::block::elevator::{Elevator, IoRequest, IoStats};
+use crate::block::request::{self, Completion, RequestOp};
 use crate::block::{BlockDevice, BlockError, BlockResult};
-use crate::sync::Mutex;
+use crate::debug::failinject::{self, Point};
+use crate::sync::IrqSpinlock;
 use crate::virtio::mmio::{self, VirtIOMMIO};
 use crate::virtio::queue::Virtqueue;
 use crate::virtio::{DeviceType, VirtIODeviceInfo, VirtIOError, VirtIOResult};
 
+/// 패닉 경로 요청 하나의 완료 대기 한도
+const PANIC_IO_TIMEOUT_NS: u64 = 5_000_000_000;
+
 /// VirtIO 블록 디바이스 Feature 비트
 #[allow(dead_code)]
 mod features {
@@ -32,14 +58,27 @@ mod features {
     pub const FLUSH: u64 = 1 << 9;
     /// 토폴로지 정보
     pub const TOPOLOGY: u64 = 1 << 10;
+    /// 여러 virtqueue (`num_queues`)
+    pub const MQ: u64 = 1 << 12;
+    /// discard 지원
+    pub const DISCARD: u64 = 1 << 13;
     /// 쓰기 제로 지원
     pub const WRITE_ZEROES: u64 = 1 << 14;
+}
 
-    // VirtIO 공통 Feature 비트 (selector 1)
-    /// VirtIO 1.0+ 현대적 디바이스
-    pub const VIRTIO_F_VERSION_1: u32 = 1 << 0; // 비트 32 -> selector 1의 비트 0
+/// 디바이스 설정 공간 오프셋 (`virtio_blk_config`)
+mod config {
+    pub const CAPACITY: usize = 0;
+    pub const BLK_SIZE: usize = 20;
+    pub const NUM_QUEUES: usize = 34;
+    pub const MAX_DISCARD_SECTORS: usize = 36;
+    pub const MAX_WRITE_ZEROES_SECTORS: usize = 48;
+    pub const WRITE_ZEROES_MAY_UNMAP: usize = 56;
 }
 
+/// write zeroes 요청 플래그: 0으로 읽히기만 하면 블록 할당을 풀어도 됨
+const WRITE_ZEROES_F_UNMAP: u32 = 1 << 0;
+
 /// VirtIO 블록 요청 타입
 #[repr(u32)]
 #[derive(Debug, Clone, Copy)]
@@ -48,6 +87,20 @@ pub enum RequestType {
     Out = 1,     // 쓰기
     Flush = 4,   // 플러시
     GetId = 8,   // 디바이스 ID 조회
+    Discard = 11,     // 블록 버리기
+    WriteZeroes = 13, // 0으로 채우기
+}
+
+impl From<RequestOp> for RequestType {
+    fn from(op: RequestOp) -> Self {
+        match op {
+            RequestOp::Read => Self::In,
+            RequestOp::Write => Self::Out,
+            RequestOp::Flush => Self::Flush,
+            RequestOp::Discard => Self::Discard,
+            RequestOp::WriteZeroes => Self::WriteZeroes,
+        }
+    }
 }
 
 /// VirtIO 블록 요청 헤더
@@ -62,6 +115,18 @@ pub struct VirtIOBlkReqHeader {
     pub sector: u64,
 }
 
+/// discard / write zeroes 요청의 데이터 세그먼트 (범위 하나)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VirtIOBlkDiscardWriteZeroes {
+    /// 시작 섹터
+    pub sector: u64,
+    /// 섹터 수
+    pub num_sectors: u32,
+    /// `WRITE_ZEROES_F_UNMAP` (discard는 0)
+    pub flags: u32,
+}
+
 /// VirtIO 블록 응답 상태
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -96,27 +161,56 @@ pub struct VirtIOBlkGeometry {
     pub sectors: u8,
 }
 
+/// 디바이스에 올라간 요청
+///
+/// 헤더와 상태 바이트는 디바이스가 완료할 때까지 주소가 고정되어야 하므로 Box에 둡니다.
+struct InFlight {
+    header: VirtIOBlkReqHeader,
+    status: u8,
+    /// 병합된 요청마다 하나씩
+    completions: Vec<Arc<Completion>>,
+}
+
+/// Virtqueue와 진행 중인 요청 (인터럽트 핸들러에서도 접근)
+struct RequestQueue {
+    queue: Virtqueue,
+    /// head descriptor 번호 → 요청 (완료 후에도 다음 요청이 자리를 쓸 때까지 남음)
+    inflight: Vec<Option<Box<InFlight>>>,
+}
+
 /// VirtIO 블록 디바이스
 pub struct VirtIOBlock {
     /// 디바이스 이름
     name: String,
     /// MMIO 핸들
     mmio: VirtIOMMIO,
-    /// Virtqueue
-    queue: Mutex<Virtqueue>,
+    /// 요청 큐 (MQ면 CPU마다 하나, `local_queue()`로 고름)
+    queues: Vec<IrqSpinlock<RequestQueue>>,
+    /// 디바이스에 올라간 요청 수
+    in_flight: AtomicUsize,
+    /// 디바이스가 바쁜 동안 요청을 모으는 I/O 스케줄러
+    elevator: Elevator,
     /// 용량 (섹터 수)
     capacity: u64,
     /// 블록 크기 (바이트)
     block_size: usize,
     /// 읽기 전용 여부
     read_only: bool,
+    /// FLUSH 요청 지원 여부
+    flush: bool,
+    /// discard 요청 하나의 최대 섹터 수 (0 = 지원 안 함)
+    max_discard_sectors: u32,
+    /// write zeroes 요청 하나의 최대 섹터 수 (0 = 지원 안 함)
+    max_write_zeroes_sectors: u32,
+    /// write zeroes가 블록 할당을 풀어도 되는지
+    write_zeroes_unmap: bool,
     /// IRQ 번호
     irq: u32,
     /// 인터럽트 플래그
     interrupt_flag: AtomicBool,
 }
 
-// Safety: VirtIOBlock은 Mutex로 보호됨
+// Safety: 요청 큐는 IrqSpinlock으로 보호됨
 unsafe impl Send for VirtIOBlock {}
 unsafe impl Sync for VirtIOBlock {}
 
@@ -129,46 +223,30 @@ impl VirtIOBlock {
 
         let mmio = VirtIOMMIO::new(info.mmio_base);
 
-        // VirtIO 버전 확인
-        let version = mmio.version();
-        crate::kprintln!("[VirtIO-blk] MMIO version: {}", version);
-
-        // 디바이스 초기화
-        mmio.init_device()?;
-
-        // Feature 읽기 (selector 0: 디바이스별 기능, selector 1: VirtIO 공통 기능)
-        let device_features_lo = mmio.device_features(0);
-        let device_features_hi = mmio.device_features(1);
-        let device_features = device_features_lo as u64 | ((device_features_hi as u64) << 32);
+        // 디바이스 초기화, Feature 협상
+        // RO/BLK_SIZE는 설정 공간을 해석하려고, FLUSH는 sync()에서 쓰기 캐시를 비우려고,
+        // DISCARD/WRITE_ZEROES는 데이터 없는 범위 요청, MQ는 CPU별 큐를 위해 받아들임
+        let accepted = mmio.negotiate(
+            features::RO
+                | features::BLK_SIZE
+                | features::FLUSH
+                | features::MQ
+                | features::DISCARD
+                | features::WRITE_ZEROES,
+        )?;
         crate::kprintln!(
-            "[VirtIO-blk] Device features: {:#x} (lo={:#x}, hi={:#x})",
-            device_features, device_features_lo, device_features_hi
+            "[VirtIO-blk] MMIO version {} ({}), features: device {:#x}, accepted {:#x}",
+            mmio.version(), mmio.version_name(), mmio.device_features64(), accepted
         );
 
-        // Feature 협상
-        // VirtIO 현대적 디바이스 (v2)는 VIRTIO_F_VERSION_1 필수
-        let driver_features_lo = 0u32; // 기본 기능만 사용
-        let driver_features_hi = if version >= 2 && (device_features_hi & features::VIRTIO_F_VERSION_1) != 0 {
-            crate::kprintln!("[VirtIO-blk] Negotiating VIRTIO_F_VERSION_1");
-            features::VIRTIO_F_VERSION_1
-        } else {
-            0u32
-        };
-
-        mmio.set_driver_features(0, driver_features_lo);
-        mmio.set_driver_features(1, driver_features_hi);
-
-        // Feature 협상 완료
-        mmio.finish_features()?;
-
         // 용량 읽기
-        let capacity = mmio.read_config64(0);
-        let block_size = if device_features & features::BLK_SIZE != 0 {
-            mmio.read_config32(20) as usize
+        let capacity = mmio.read_config64(config::CAPACITY);
+        let block_size = if accepted & features::BLK_SIZE != 0 {
+            mmio.read_config32(config::BLK_SIZE) as usize
         } else {
             512 // 기본 섹터 크기
         };
-        let read_only = device_features & features::RO != 0;
+        let read_only = accepted & features::RO != 0;
 
         crate::kprintln!(
             "[VirtIO-blk] Capacity: {} sectors ({} MB), block_size: {}, read_only: {}",
@@ -178,11 +256,46 @@ impl VirtIOBlock {
             read_only
         );
 
-        // Virtqueue 설정
-        let queue = Virtqueue::new(&mmio, 0)?;
+        let max_discard_sectors = if accepted & features::DISCARD != 0 {
+            mmio.read_config32(config::MAX_DISCARD_SECTORS)
+        } else {
+            0
+        };
+        let (max_write_zeroes_sectors, write_zeroes_unmap) = if accepted & features::WRITE_ZEROES != 0 {
+            (
+                mmio.read_config32(config::MAX_WRITE_ZEROES_SECTORS),
+                mmio.read_config8(config::WRITE_ZEROES_MAY_UNMAP) != 0,
+            )
+        } else {
+            (0, false)
+        };
+        if max_discard_sectors != 0 || max_write_zeroes_sectors != 0 {
+            crate::kprintln!(
+                "[VirtIO-blk] Discard: max {} sectors, write zeroes: max {} sectors{}",
+                max_discard_sectors,
+                max_write_zeroes_sectors,
+                if write_zeroes_unmap { " (may unmap)" } else { "" }
+            );
+        }
+
+        // Virtqueue 설정 (MQ면 디바이스 큐 수와 CPU 수 중 작은 만큼)
+        let num_queues = if accepted & features::MQ != 0 {
+            (mmio.read_config16(config::NUM_QUEUES) as usize).clamp(1, expected_cpus())
+        } else {
+            1
+        };
+        let mut queues = Vec::with_capacity(num_queues);
+        for idx in 0..num_queues {
+            let queue = Virtqueue::new(&mmio, idx as u32)?;
+            queues.push(IrqSpinlock::new(RequestQueue {
+                inflight: (0..queue.size()).map(|_| None).collect(),
+                queue,
+            }));
+        }
         crate::kprintln!(
-            "[VirtIO-blk] Queue setup: size={}",
-            queue.size()
+            "[VirtIO-blk] Queue setup: {} queue(s), size={}",
+            num_queues,
+            queues[0].lock().queue.size()
         );
 
         // 드라이버 준비 완료
@@ -191,197 +304,306 @@ impl VirtIOBlock {
         Ok(Self {
             name: String::from(name),
             mmio,
-            queue: Mutex::new(queue),
+            queues,
+            in_flight: AtomicUsize::new(0),
+            elevator: Elevator::new(block_size),
             capacity,
             block_size,
             read_only,
+            flush: accepted & features::FLUSH != 0,
+            max_discard_sectors,
+            max_write_zeroes_sectors,
+            write_zeroes_unmap,
             irq: info.irq,
             interrupt_flag: AtomicBool::new(false),
         })
     }
 
-    /// 블록 읽기 (내부)
-    fn read_block_internal(&self, block_num: u64, buf: &mut [u8]) -> VirtIOResult<()> {
-        if buf.len() < self.block_size {
-            return Err(VirtIOError::BufferTooSmall);
-        }
-        if block_num >= self.capacity {
-            return Err(VirtIOError::IoError);
-        }
-
-        // 요청 헤더
-        let header = VirtIOBlkReqHeader {
-            req_type: RequestType::In as u32,
-            reserved: 0,
-            sector: block_num,
-        };
-
-        // 상태 바이트
-        let mut status: u8 = 0xFF;
-
-        // Virtqueue에 요청 추가
-        let header_buf = unsafe {
-            core::slice::from_raw_parts(
-                &header as *const _ as *const u8,
-                core::mem::size_of::<VirtIOBlkReqHeader>(),
-            )
-        };
-        let status_buf = unsafe {
-            core::slice::from_raw_parts_mut(&mut status as *mut u8, 1)
-        };
-
-        {
-            let mut queue = self.queue.lock();
-
-            queue.add_buffer_chain(
-                &[header_buf],
-                &[buf, status_buf],
-            )?;
+    /// 블록 하나가 차지하는 512바이트 섹터 수
+    fn sectors_per_block(&self) -> u64 {
+        (self.block_size / 512).max(1) as u64
+    }
 
-            // 메모리 배리어 - 디바이스가 descriptor를 볼 수 있도록
-            core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+    /// 현재 CPU가 요청을 넣을 큐
+    fn local_queue(&self) -> &IrqSpinlock<RequestQueue> {
+        &self.queues[crate::proc::percpu::get_cpu_id() as usize % self.queues.len()]
+    }
 
-            // 디바이스에 알림
-            self.mmio.notify_queue(0);
+    /// 요청을 큐에 넣음 (완료를 기다리지 않음)
+    ///
+    /// 데이터 세그먼트는 descriptor 체인에 순서대로 들어가 하나의 디바이스 요청이 됩니다.
+    /// 큐에 descriptor가 모자라면 다른 요청이 끝날 때까지 기다립니다.
+    ///
+    /// # Safety
+    /// 세그먼트 버퍼는 `completions`가 모두 완료될 때까지 유효해야 합니다.
+    unsafe fn submit(
+        &self,
+        op: RequestOp,
+        sector: u64,
+        segments: &[(*mut u8, usize)],
+        completions: Vec<Arc<Completion>>,
+    ) -> VirtIOResult<()> {
+        let mut req = Box::new(InFlight {
+            header: VirtIOBlkReqHeader {
+                req_type: RequestType::from(op) as u32,
+                reserved: 0,
+                sector,
+            },
+            status: 0xFF,
+            completions,
+        });
+        let queue = self.local_queue();
+        let needed = (segments.len() + 2) as u16;
+        if needed > queue.lock().queue.size() {
+            return Err(VirtIOError::BufferTooSmall);
         }
 
-        // 완료 대기 (폴링)
-        self.wait_for_completion()?;
-
-        // 상태 확인
-        if status != VirtIOBlkStatus::Ok as u8 {
-            crate::kprintln!("[VirtIO-blk] Read error: status={}", status);
-            return Err(VirtIOError::IoError);
+        loop {
+            {
+                let mut rq = queue.lock();
+                if rq.queue.available_descs() >= needed {
+                    let header_buf = unsafe {
+                        core::slice::from_raw_parts(
+                            &req.header as *const _ as *const u8,
+                            core::mem::size_of::<VirtIOBlkReqHeader>(),
+                        )
+                    };
+                    let status_buf = unsafe { core::slice::from_raw_parts_mut(&mut req.status as *mut u8, 1) };
+
+                    let head = if op == RequestOp::Read {
+                        let mut bufs: Vec<&mut [u8]> = segments
+                            .iter()
+                            .map(|&(ptr, len)| unsafe { core::slice::from_raw_parts_mut(ptr, len) })
+                            .collect();
+                        bufs.push(status_buf);
+                        rq.queue.add_buffer_chain(&[header_buf], &bufs)?
+                    } else {
+                        let mut bufs: Vec<&[u8]> = Vec::with_capacity(segments.len() + 1);
+                        bufs.push(header_buf);
+                        bufs.extend(
+                            segments
+                                .iter()
+                                .map(|&(ptr, len)| unsafe { core::slice::from_raw_parts(ptr as *const u8, len) }),
+                        );
+                        rq.queue.add_buffer_chain(&bufs, &[status_buf])?
+                    };
+                    // 이전에 이 head를 쓴 (완료된) 요청은 여기서 해제
+                    rq.inflight[head as usize] = Some(req);
+                    self.in_flight.fetch_add(1, Ordering::AcqRel);
+                    crate::trace_event!(block_issue, head = head, op = op, sector = sector, segs = segments.len());
+
+                    // 디바이스가 descriptor를 볼 수 있도록 배리어 후 알림
+                    rq.queue.kick(&self.mmio);
+                    return Ok(());
+                }
+            }
+            // 큐가 가득 참: 끝난 요청을 거두고 양보
+            self.poll();
+            request::relax();
         }
-
-        Ok(())
     }
 
-    /// 블록 쓰기 (내부)
-    fn write_block_internal(&self, block_num: u64, buf: &[u8]) -> VirtIOResult<()> {
-        if self.read_only {
-            return Err(VirtIOError::IoError);
-        }
-        if buf.len() < self.block_size {
-            return Err(VirtIOError::BufferTooSmall);
+    /// 완료된 요청을 거둬 대기자에게 알림 (인터럽트 컨텍스트에서도 호출)
+    ///
+    /// 인터럽트 컨텍스트에서 heap을 해제하지 않도록 요청은 슬롯에 남겨 두고,
+    /// 같은 head로 다음 요청을 넣을 때(`submit`) 해제합니다.
+    fn reap(&self) {
+        for queue in &self.queues {
+            let mut rq = queue.lock();
+            while let Some((head, _len)) = rq.queue.poll_used() {
+                self.complete_head(&rq, head);
+            }
         }
-        if block_num >= self.capacity {
-            return Err(VirtIOError::IoError);
+    }
+
+    /// used 링에서 나온 `head` 요청의 대기자에게 완료 알림
+    fn complete_head(&self, rq: &RequestQueue, head: u16) {
+        let Some(req) = rq.inflight.get(head as usize).and_then(|slot| slot.as_ref()) else {
+            return;
+        };
+        // Safety: 디바이스가 DMA로 쓴 상태 바이트
+        let status = unsafe { core::ptr::read_volatile(&req.status) };
+        crate::trace_event!(block_complete, head = head, status = status);
+        for completion in &req.completions {
+            completion.complete(if status == VirtIOBlkStatus::Ok as u8 {
+                Ok(())
+            } else {
+                Err(BlockError::IoError)
+            });
         }
+        self.in_flight.fetch_sub(1, Ordering::AcqRel);
+        self.elevator.note_completed();
+    }
 
-        // 요청 헤더
+    /// 패닉 경로: 요청 하나를 바로 큐에 넣고 완료될 때까지 폴링
+    ///
+    /// 힙, 엘리베이터, 인터럽트를 쓰지 않고 큐 락은 `try_lock`으로만 잡습니다. 그 사이
+    /// 끝난 다른 요청은 `reap`처럼 대기자에게 알립니다. 시간 안에 끝나지 않으면 디바이스가
+    /// 나중에 이 스택의 상태 바이트에 쓸 수 있지만, 패닉 뒤에는 이 CPU가 돌아가지 않습니다.
+    fn panic_request(&self, op: RequestOp, sector: u64, data: &[u8]) -> BlockResult<()> {
+        let mut rq = self.local_queue().try_lock().ok_or(BlockError::NotReady)?;
         let header = VirtIOBlkReqHeader {
-            req_type: RequestType::Out as u32,
+            req_type: RequestType::from(op) as u32,
             reserved: 0,
-            sector: block_num,
+            sector,
         };
+        let mut status = 0xFFu8;
+        let deadline = crate::time::clock::monotonic_ns() + PANIC_IO_TIMEOUT_NS;
+        let timed_out = || crate::time::clock::monotonic_ns() > deadline;
+
+        let needed = if data.is_empty() { 2 } else { 3 };
+        while rq.queue.available_descs() < needed {
+            match rq.queue.poll_used() {
+                Some((head, _len)) => self.complete_head(&rq, head),
+                None if timed_out() => return Err(BlockError::IoError),
+                None => core::hint::spin_loop(),
+            }
+        }
 
-        // 상태 바이트
-        let mut status: u8 = 0xFF;
-
-        // Virtqueue에 요청 추가
+        // Safety: 완료될 때까지 이 함수 안에서 기다리므로 헤더와 상태 바이트가 살아 있음
         let header_buf = unsafe {
             core::slice::from_raw_parts(
                 &header as *const _ as *const u8,
                 core::mem::size_of::<VirtIOBlkReqHeader>(),
             )
         };
-        let status_buf = unsafe {
-            core::slice::from_raw_parts_mut(&mut status as *mut u8, 1)
-        };
-
-        {
-            let mut queue = self.queue.lock();
-            queue.add_buffer_chain(
-                &[header_buf, buf],
-                &[status_buf],
-            )?;
-
-            // 디바이스에 알림
-            self.mmio.notify_queue(0);
+        let status_buf = core::slice::from_mut(&mut status);
+        let head = if data.is_empty() {
+            rq.queue.add_buffer_chain(&[header_buf], &[status_buf])
+        } else {
+            rq.queue.add_buffer_chain(&[header_buf, data], &[status_buf])
         }
+        .map_err(|_| BlockError::IoError)?;
+        rq.queue.kick(&self.mmio);
 
-        // 완료 대기 (폴링)
-        self.wait_for_completion()?;
-
-        // 상태 확인
-        if status != VirtIOBlkStatus::Ok as u8 {
-            crate::kprintln!("[VirtIO-blk] Write error: status={}", status);
-            return Err(VirtIOError::IoError);
+        loop {
+            match rq.queue.poll_used() {
+                Some((done, _len)) if done == head => break,
+                Some((done, _len)) => self.complete_head(&rq, done),
+                None if timed_out() => return Err(BlockError::IoError),
+                None => core::hint::spin_loop(),
+            }
+        }
+        let irq_status = self.mmio.interrupt_status();
+        if irq_status != 0 {
+            self.mmio.ack_interrupt(irq_status);
         }
 
-        Ok(())
+        // Safety: 디바이스가 DMA로 쓴 상태 바이트
+        if unsafe { core::ptr::read_volatile(&status) } == VirtIOBlkStatus::Ok as u8 {
+            Ok(())
+        } else {
+            Err(BlockError::IoError)
+        }
     }
 
-    /// 완료 대기 (인터럽트 + WFI 기반, 폴링 fallback)
-    fn wait_for_completion(&self) -> VirtIOResult<()> {
-        // Phase 1: 인터럽트 기반 대기 (WFI)
-        for _ in 0..1000u32 {
-            // 인터럽트 플래그 확인
-            if self.interrupt_flag.swap(false, Ordering::SeqCst) {
-                let mut queue = self.queue.lock();
-                if queue.poll_used().is_some() {
-                    return Ok(());
-                }
-            }
+    /// 인터럽트를 놓친 경우 대비: 완료된 요청을 거두고 인터럽트 상태 확인
+    fn poll(&self) {
+        self.reap();
+        let status = self.mmio.interrupt_status();
+        if status != 0 {
+            self.mmio.ack_interrupt(status);
+        }
+    }
 
-            // Used 링 직접 확인 (인터럽트 놓친 경우 대비)
-            {
-                let mut queue = self.queue.lock();
-                if queue.poll_used().is_some() {
-                    let status = self.mmio.interrupt_status();
-                    if status != 0 {
-                        self.mmio.ack_interrupt(status);
-                    }
-                    self.interrupt_flag.store(false, Ordering::SeqCst);
-                    return Ok(());
+    /// 디바이스가 비어 있으면 엘리베이터에 모인 요청을 정렬·병합해 내보냄
+    fn kick(&self) {
+        if self.in_flight.load(Ordering::Acquire) != 0 {
+            return;
+        }
+        for batch in self.elevator.take_batches() {
+            let sector = batch.block * self.sectors_per_block();
+            let completions = batch.completions.clone();
+            // Safety: 각 세그먼트의 주인은 자기 completion을 기다리고 있음
+            if let Err(e) = unsafe { self.submit(batch.op, sector, &batch.segments, batch.completions) } {
+                crate::kprintln!("[VirtIO-blk] Submit failed at sector {}: {:?}", sector, e);
+                for completion in completions {
+                    completion.complete(Err(BlockError::IoError));
                 }
             }
-
-            // WFI로 저전력 대기 (다음 인터럽트까지)
-            #[cfg(target_arch = "aarch64")]
-            unsafe { core::arch::asm!("wfi"); }
-
-            #[cfg(target_arch = "riscv64")]
-            unsafe { core::arch::asm!("wfi"); }
         }
+    }
 
-        // Phase 2: 폴링 fallback (인터럽트가 동작하지 않는 경우 대비)
-        let mut timeout = 100_000u32;
-        loop {
-            {
-                let mut queue = self.queue.lock();
-                if queue.poll_used().is_some() {
-                    let status = self.mmio.interrupt_status();
-                    if status != 0 {
-                        self.mmio.ack_interrupt(status);
-                    }
-                    self.interrupt_flag.store(false, Ordering::SeqCst);
-                    return Ok(());
-                }
-            }
+    /// 블록 범위 요청(discard, write zeroes)을 `max_sectors`씩 나눠 보내고 완료를 기다림
+    ///
+    /// 데이터 세그먼트는 범위 하나짜리 `VirtIOBlkDiscardWriteZeroes`입니다. `sync()`처럼
+    /// 엘리베이터를 거치지 않으므로, 이미 완료된 쓰기보다 뒤에 처리되는 것만 보장합니다.
+    fn range_request(&self, op: RequestOp, start_block: u64, count: u64, max_sectors: u32, flags: u32) -> BlockResult<()> {
+        match start_block.checked_add(count) {
+            Some(end) if end <= self.block_count() => {}
+            _ => return Err(BlockError::InvalidBlock),
+        }
+        if self.read_only {
+            return Err(BlockError::ReadOnly);
+        }
 
-            timeout -= 1;
-            if timeout == 0 {
-                let queue = self.queue.lock();
-                crate::kprintln!("[VirtIO-blk] Timeout! Queue state:");
-                crate::kprintln!("  avail_idx={}, used_idx={}, last_used={}",
-                    queue.avail_idx(), queue.used_idx(), queue.last_used_idx());
-                crate::kprintln!("  ISR status: {:#x}", self.mmio.interrupt_status());
-                return Err(VirtIOError::Timeout);
+        let per_block = self.sectors_per_block();
+        let max_blocks = (max_sectors as u64 / per_block).max(1);
+        let mut block = start_block;
+        let mut left = count;
+        while left > 0 {
+            let blocks = left.min(max_blocks);
+            let mut range = VirtIOBlkDiscardWriteZeroes {
+                sector: block * per_block,
+                num_sectors: (blocks * per_block) as u32,
+                flags,
+            };
+            let segment = (
+                &mut range as *mut VirtIOBlkDiscardWriteZeroes as *mut u8,
+                core::mem::size_of::<VirtIOBlkDiscardWriteZeroes>(),
+            );
+            let completion = Arc::new(Completion::new());
+            // Safety: 완료를 기다리는 동안 range는 이 스택에 있음
+            unsafe { self.submit(op, 0, &[segment], vec![completion.clone()]) }
+                .map_err(|_| BlockError::IoError)?;
+            self.elevator.note_dispatched();
+            let result = completion.wait(|| {
+                self.poll();
+                self.kick();
+            });
+            if result.is_err() {
+                crate::kprintln!("[VirtIO-blk] {:?} error at block {} (+{})", op, block, blocks);
+                return result;
             }
-
-            core::hint::spin_loop();
+            block += blocks;
+            left -= blocks;
         }
+        Ok(())
     }
 
-    /// 인터럽트 핸들러
-    pub fn handle_irq(&self) {
-        let status = self.mmio.interrupt_status();
-        if status != 0 {
-            self.mmio.ack_interrupt(status);
-            self.interrupt_flag.store(true, Ordering::SeqCst);
+    /// 요청을 엘리베이터에 넣고 완료될 때까지 대기
+    fn do_request(&self, op: RequestOp, block_num: u64, data: *mut u8, len: usize) -> BlockResult<()> {
+        if len == 0 || len % self.block_size != 0 {
+            return Err(BlockError::BufferSizeMismatch);
+        }
+        let blocks = (len / self.block_size) as u64;
+        match block_num.checked_add(blocks) {
+            Some(end) if end <= self.block_count() => {}
+            _ => return Err(BlockError::InvalidBlock),
         }
+        if op == RequestOp::Write && self.read_only {
+            return Err(BlockError::ReadOnly);
+        }
+
+        let completion = Arc::new(Completion::new());
+        self.elevator.add(IoRequest {
+            op,
+            block: block_num,
+            data,
+            len,
+            completion: completion.clone(),
+        });
+        self.kick();
+
+        // 대기 중에도 디바이스가 비면 다른 스레드가 모아 둔 요청을 대신 내보냄
+        let result = completion.wait(|| {
+            self.poll();
+            self.kick();
+        });
+        if result.is_err() {
+            crate::kprintln!("[VirtIO-blk] {:?} error at block {}", op, block_num);
+        }
+        result
     }
 
     /// 인터럽트 컨트롤러에 IRQ 등록 및 디스패치 테이블에 등록
@@ -390,26 +612,14 @@ impl VirtIOBlock {
             return;
         }
 
-        // 인터럽트 컨트롤러에 IRQ 활성화
-        #[cfg(target_arch = "aarch64")]
-        unsafe {
-            crate::arch::gic::set_priority(self.irq, 0x90);
-            crate::arch::gic::set_target(self.irq, 1); // CPU 0
-            crate::arch::gic::enable_irq(self.irq);
-        }
-
-        #[cfg(target_arch = "riscv64")]
-        unsafe {
-            crate::arch::plic::set_priority(self.irq, 1);
-            crate::arch::plic::enable_irq(self.irq);
-        }
-
-        // VirtIO IRQ 디스패치 테이블에 등록
+        // VirtIO IRQ 디스패치 테이블에 등록 (완료 처리는 handle_irq에서)
         crate::virtio::irq::register_irq(
             self.irq,
             self.mmio.base(),
             &self.interrupt_flag,
+            "virtio-blk",
         );
+        crate::virtio::irq::set_irq_handler(self.irq, handle_irq);
     }
 }
 
@@ -423,27 +633,121 @@ impl BlockDevice for VirtIOBlock {
     }
 
     fn block_count(&self) -> u64 {
-        self.capacity
+        self.capacity / self.sectors_per_block()
     }
 
     fn read_block(&self, block_num: u64, buf: &mut [u8]) -> BlockResult<()> {
-        self.read_block_internal(block_num, buf)
-            .map_err(|_| BlockError::IoError)
+        if failinject::should_fail(Point::BlockRead) {
+            return Err(BlockError::IoError);
+        }
+        if buf.len() != self.block_size {
+            return Err(BlockError::BufferSizeMismatch);
+        }
+        self.do_request(RequestOp::Read, block_num, buf.as_mut_ptr(), buf.len())
     }
 
     fn write_block(&self, block_num: u64, buf: &[u8]) -> BlockResult<()> {
-        self.write_block_internal(block_num, buf)
-            .map_err(|_| BlockError::IoError)
+        if buf.len() != self.block_size {
+            return Err(BlockError::BufferSizeMismatch);
+        }
+        // 디바이스는 쓰기 요청의 데이터 버퍼를 읽기만 함
+        self.do_request(RequestOp::Write, block_num, buf.as_ptr() as *mut u8, buf.len())
+    }
+
+    /// 여러 블록을 요청 하나로 읽기
+    fn read_blocks(&self, start_block: u64, buf: &mut [u8]) -> BlockResult<()> {
+        if failinject::should_fail(Point::BlockRead) {
+            return Err(BlockError::IoError);
+        }
+        self.do_request(RequestOp::Read, start_block, buf.as_mut_ptr(), buf.len())
+    }
+
+    /// 여러 블록을 요청 하나로 쓰기
+    fn write_blocks(&self, start_block: u64, buf: &[u8]) -> BlockResult<()> {
+        self.do_request(RequestOp::Write, start_block, buf.as_ptr() as *mut u8, buf.len())
+    }
+
+    fn discard(&self, start_block: u64, count: u64) -> BlockResult<()> {
+        if self.max_discard_sectors == 0 {
+            return Err(BlockError::NotSupported);
+        }
+        self.range_request(RequestOp::Discard, start_block, count, self.max_discard_sectors, 0)
+    }
+
+    /// 지원하면 WRITE_ZEROES 요청으로, 아니면 0 버퍼를 씀
+    fn write_zeroes(&self, start_block: u64, count: u64) -> BlockResult<()> {
+        if self.max_write_zeroes_sectors == 0 {
+            return crate::block::zero_fill(self, start_block, count);
+        }
+        let flags = if self.write_zeroes_unmap { WRITE_ZEROES_F_UNMAP } else { 0 };
+        self.range_request(RequestOp::WriteZeroes, start_block, count, self.max_write_zeroes_sectors, flags)
     }
 
     fn is_read_only(&self) -> bool {
         self.read_only
     }
 
-    fn sync(&self) -> BlockResult<()> {
-        // TODO: FLUSH 요청 구현
+    fn io_stats(&self) -> Option<IoStats> {
+        Some(self.elevator.stats())
+    }
+
+    /// 데이터를 쓰고 (지원하면) 쓰기 캐시까지 비움
+    fn panic_write(&self, start_block: u64, buf: &[u8]) -> BlockResult<()> {
+        if buf.is_empty() || buf.len() % self.block_size != 0 {
+            return Err(BlockError::BufferSizeMismatch);
+        }
+        match start_block.checked_add((buf.len() / self.block_size) as u64) {
+            Some(end) if end <= self.block_count() => {}
+            _ => return Err(BlockError::InvalidBlock),
+        }
+        if self.read_only {
+            return Err(BlockError::ReadOnly);
+        }
+        self.panic_request(RequestOp::Write, start_block * self.sectors_per_block(), buf)?;
+        if self.flush {
+            self.panic_request(RequestOp::Flush, 0, &[])?;
+        }
         Ok(())
     }
+
+    /// 디바이스 쓰기 캐시 비우기
+    ///
+    /// 쓰기는 완료를 기다린 뒤 반환하므로, 이미 끝난 쓰기는 모두 플러시보다 앞섭니다.
+    /// 플러시는 순서가 중요하므로 엘리베이터를 거치지 않고 바로 보냅니다.
+    fn sync(&self) -> BlockResult<()> {
+        if !self.flush {
+            return Ok(());
+        }
+        let completion = Arc::new(Completion::new());
+        // Safety: 데이터 세그먼트 없음
+        unsafe { self.submit(RequestOp::Flush, 0, &[], vec![completion.clone()]) }
+            .map_err(|_| BlockError::IoError)?;
+        self.elevator.note_dispatched();
+        completion.wait(|| {
+            self.poll();
+            self.kick();
+        })
+    }
+}
+
+/// SMP 부팅에서 켜질 CPU 수 (`block::init()`은 보조 CPU를 켜기 전에 불림)
+fn expected_cpus() -> usize {
+    let mut cpus = crate::drivers::config::cpu_count();
+    if let Some(max) = crate::boot::cmdline::max_cpus() {
+        cpus = cpus.min(max.max(1));
+    }
+    cpus.clamp(1, crate::proc::percpu::MAX_CPUS)
+}
+
+/// 초기화된 블록 디바이스 (인터럽트 핸들러가 완료 처리에 사용)
+static DEVICES: IrqSpinlock<Vec<Arc<VirtIOBlock>>> = IrqSpinlock::new(Vec::new());
+
+/// VirtIO IRQ 하반부(태스크릿)에서 호출되는 핸들러
+fn handle_irq(irq: u32) {
+    let devices = DEVICES.lock();
+    for dev in devices.iter().filter(|d| d.irq == irq) {
+        dev.reap();
+    }
 }
 
 /// VirtIO 블록 디바이스 초기화
@@ -460,6 +764,7 @@ pub fn init() -> Option<Arc<VirtIOBlock>> {
                         info.mmio_base
                     );
                     let dev = Arc::new(dev);
+                    DEVICES.lock().push(dev.clone());
                     // 인터럽트 등록 (Arc 생성 후, flag 포인터가 안정적)
                     dev.register_interrupt();
                     return Some(dev);
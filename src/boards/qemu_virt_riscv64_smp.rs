@@ -7,6 +7,7 @@
 
 use super::board_module::{uart_quirks, BoardModuleInfo};
 use super::BoardConfig;
+use crate::arch::sbi;
 
 /// QEMU virt 보드 (RISC-V64 SMP)
 pub struct QemuVirtRiscv64Smp;
@@ -38,7 +39,7 @@ impl BoardConfig for QemuVirtRiscv64Smp {
 /// OpenSBI가 이를 구현합니다.
 pub mod sbi_hsm {
     /// SBI 확장 ID
-    pub const EXT_HSM: usize = 0x48534D; // "HSM"
+    pub const EXT_HSM: usize = crate::arch::sbi::ext::HSM; // "HSM"
 
     /// HSM 함수 ID
     pub const HART_START: usize = 0;
@@ -71,33 +72,16 @@ pub mod sbi_hsm {
     }
 }
 
-/// SBI Base 확장
-pub mod sbi_base {
-    /// SBI 확장 ID
-    pub const EXT_BASE: usize = 0x10;
-
-    /// Base 함수 ID
-    pub const GET_SPEC_VERSION: usize = 0;
-    pub const GET_IMPL_ID: usize = 1;
-    pub const GET_IMPL_VERSION: usize = 2;
-    pub const PROBE_EXTENSION: usize = 3;
-    pub const GET_MVENDORID: usize = 4;
-    pub const GET_MARCHID: usize = 5;
-    pub const GET_MIMPID: usize = 6;
-}
-
 /// 보드 초기화 함수
 fn board_init() -> Result<(), i32> {
     crate::kprintln!("[board] QEMU virt RISC-V64 SMP initialized");
 
     // SBI 버전 확인
-    let version = sbi_get_spec_version();
-    let major = (version >> 24) & 0x7F;
-    let minor = version & 0xFFFFFF;
-    crate::kprintln!("[board] SBI spec version: {}.{}", major, minor);
+    let (major, minor) = sbi::spec_version();
+    crate::kprintln!("[board] SBI spec version: {}.{} ({})", major, minor, sbi::impl_name());
 
     // HSM 확장 지원 여부 확인
-    if sbi_probe_extension(sbi_hsm::EXT_HSM) != 0 {
+    if sbi::probe_extension(sbi_hsm::EXT_HSM) {
         crate::kprintln!("[board] SBI HSM extension available");
     } else {
         crate::kprintln!("[board] SBI HSM extension NOT available");
@@ -119,46 +103,6 @@ pub static BOARD_INFO: BoardModuleInfo = BoardModuleInfo {
     smp_capable: true,
 };
 
-// ============================================================================
-// SBI 인터페이스
-// ============================================================================
-
-/// SBI 호출 결과
-#[derive(Debug, Clone, Copy)]
-pub struct SbiResult {
-    pub error: isize,
-    pub value: usize,
-}
-
-/// SBI ecall 호출
-#[inline(always)]
-fn sbi_call(ext: usize, func: usize, arg0: usize, arg1: usize, arg2: usize) -> SbiResult {
-    let error: isize;
-    let value: usize;
-    unsafe {
-        core::arch::asm!(
-            "ecall",
-            in("a7") ext,
-            in("a6") func,
-            inout("a0") arg0 => error,
-            inout("a1") arg1 => value,
-            in("a2") arg2,
-            options(nomem, nostack)
-        );
-    }
-    SbiResult { error, value }
-}
-
-/// SBI 스펙 버전 조회
-pub fn sbi_get_spec_version() -> usize {
-    sbi_call(sbi_base::EXT_BASE, sbi_base::GET_SPEC_VERSION, 0, 0, 0).value
-}
-
-/// SBI 확장 지원 여부 확인
-pub fn sbi_probe_extension(ext_id: usize) -> usize {
-    sbi_call(sbi_base::EXT_BASE, sbi_base::PROBE_EXTENSION, ext_id, 0, 0).value
-}
-
 // ============================================================================
 // HSM (Hart State Management) 인터페이스
 // ============================================================================
@@ -173,14 +117,14 @@ pub fn sbi_probe_extension(ext_id: usize) -> usize {
 /// # Returns
 /// SBI 에러 코드 (0 = 성공)
 pub fn hart_start(hartid: usize, start_addr: usize, opaque: usize) -> isize {
-    sbi_call(sbi_hsm::EXT_HSM, sbi_hsm::HART_START, hartid, start_addr, opaque).error
+    sbi::call(sbi_hsm::EXT_HSM, sbi_hsm::HART_START, hartid, start_addr, opaque).error
 }
 
 /// 현재 hart 종료
 ///
 /// 이 함수는 반환하지 않습니다.
 pub fn hart_stop() -> ! {
-    sbi_call(sbi_hsm::EXT_HSM, sbi_hsm::HART_STOP, 0, 0, 0);
+    sbi::call(sbi_hsm::EXT_HSM, sbi_hsm::HART_STOP, 0, 0, 0);
     // 반환하지 않아야 하지만, 안전을 위해 무한 루프
     loop {
         unsafe {
@@ -197,7 +141,7 @@ pub fn hart_stop() -> ! {
 /// # Returns
 /// hart 상태 (sbi_hsm::state 참조)
 pub fn hart_get_status(hartid: usize) -> isize {
-    let result = sbi_call(sbi_hsm::EXT_HSM, sbi_hsm::HART_GET_STATUS, hartid, 0, 0);
+    let result = sbi::call(sbi_hsm::EXT_HSM, sbi_hsm::HART_GET_STATUS, hartid, 0, 0);
     if result.error == 0 {
         result.value as isize
     } else {
@@ -212,7 +156,7 @@ pub fn hart_get_status(hartid: usize) -> isize {
 /// * `resume_addr` - 재개 시 시작할 주소 (non-retentive의 경우)
 /// * `opaque` - 재개 시 전달할 값
 pub fn hart_suspend(suspend_type: u32, resume_addr: usize, opaque: usize) -> isize {
-    sbi_call(
+    sbi::call(
         sbi_hsm::EXT_HSM,
         sbi_hsm::HART_SUSPEND,
         suspend_type as usize,
@@ -222,28 +166,81 @@ pub fn hart_suspend(suspend_type: u32, resume_addr: usize, opaque: usize) -> isi
     .error
 }
 
+/// sifive_test 장치 (QEMU virt 리셋/종료 레지스터)
+#[cfg(not(feature = "riscv_smode"))]
+const SIFIVE_TEST_BASE: usize = 0x10_0000;
+/// sifive_test: 리셋
+#[cfg(not(feature = "riscv_smode"))]
+const FINISHER_RESET: u32 = 0x7777;
+/// sifive_test: 정상 종료 (QEMU 종료 코드 0)
+#[cfg(not(feature = "riscv_smode"))]
+const FINISHER_PASS: u32 = 0x5555;
+
+/// 시스템 리셋
+///
+/// S-mode는 SBI SRST 확장으로, M-mode는 펌웨어가 없으므로 sifive_test 장치로 리셋합니다.
+pub fn system_reset() -> ! {
+    #[cfg(feature = "riscv_smode")]
+    sbi::system_reset();
+    #[cfg(not(feature = "riscv_smode"))]
+    unsafe {
+        core::ptr::write_volatile(SIFIVE_TEST_BASE as *mut u32, FINISHER_RESET);
+    }
+    loop {
+        unsafe {
+            core::arch::asm!("wfi");
+        }
+    }
+}
+
+/// 시스템 종료
+///
+/// S-mode는 SBI SRST 확장으로, M-mode는 sifive_test 장치로 QEMU를 끝냅니다.
+pub fn system_off() -> ! {
+    #[cfg(feature = "riscv_smode")]
+    sbi::system_shutdown();
+    #[cfg(not(feature = "riscv_smode"))]
+    unsafe {
+        core::ptr::write_volatile(SIFIVE_TEST_BASE as *mut u32, FINISHER_PASS);
+    }
+    loop {
+        unsafe {
+            core::arch::asm!("wfi");
+        }
+    }
+}
+
 // ============================================================================
 // 보조 hart 부팅
 // ============================================================================
 
-/// 보조 hart 시작 진입점
-///
-/// 이 함수는 hart_start로 시작된 보조 hart가 처음 실행하는 코드입니다.
-/// opaque 값을 a1으로 받습니다.
+// hart_start로 시작된 보조 hart가 처음 실행하는 코드 (a0 = hartid, a1 = opaque = 스택 top)
+//
+// SBI는 sp를 정해 주지 않으므로 Rust 코드에 들어가기 전에 primary hart가 할당해 둔 스택으로 바꿉니다.
+core::arch::global_asm!(
+    r#"
+    .section .text
+    .balign 4
+    .global secondary_hart_start
+secondary_hart_start:
+    mv sp, a1
+    tail secondary_hart_entry
+"#
+);
+
+unsafe extern "C" {
+    /// 보조 hart 시작 주소 (`hart_start`의 start_addr)
+    pub fn secondary_hart_start() -> !;
+}
+
+/// 보조 hart 진입점 (`secondary_hart_start`가 스택을 설정한 뒤 호출)
 #[unsafe(no_mangle)]
-pub extern "C" fn secondary_hart_entry(hartid: usize, _opaque: usize) -> ! {
+pub extern "C" fn secondary_hart_entry(hartid: usize, _stack_top: usize) -> ! {
     let cpu_id = hartid as u32;
 
-    // 1. 스택 설정 (primary hart가 미리 할당해둔 스택 사용)
-    let stack_top = crate::proc::percpu::stacks::get_stack_top(cpu_id);
-    if stack_top != 0 {
-        unsafe {
-            core::arch::asm!(
-                "mv sp, {0}",
-                in(reg) stack_top,
-            );
-        }
-    }
+    // 1. hart ID 보관 (S-mode) 및 boot hart의 페이지 테이블 사용
+    crate::arch::csr::set_hartid(cpu_id);
+    crate::arch::mmu::init_secondary();
 
     // 2. 트랩 핸들러 설정
     crate::arch::trap::init();
@@ -262,19 +259,13 @@ pub extern "C" fn secondary_hart_entry(hartid: usize, _opaque: usize) -> ! {
 
     crate::kprintln!("[smp] Hart {} online", cpu_id);
 
-    // 7. 인터럽트 활성화
-    unsafe {
-        core::arch::asm!(
-            "li t0, 0x8",      // MIE (Machine Interrupt Enable)
-            "csrs mstatus, t0"
-        );
-    }
+    // 7. 인터럽트 활성화 (mstatus.MIE / sstatus.SIE)
+    crate::arch::csr::set_status(crate::arch::csr::STATUS_IE);
 
-    // 8. idle 루프 (타이머 인터럽트가 스케줄러를 호출)
+    // 8. idle 루프 (할 일이 없으면 다음 기한까지 틱을 끄고 대기, 오프라인으로 내리면 올릴 때까지 대기)
     loop {
-        unsafe {
-            core::arch::asm!("wfi");
-        }
+        crate::proc::hotplug::park_if_offline();
+        crate::time::nohz::idle();
     }
 }
 
@@ -287,7 +278,12 @@ pub fn start_secondary_harts(hart_count: usize, entry_point: usize) {
 
     // hart 0은 부트 hart이므로 건너뜀
     for hartid in 1..hart_count {
-        match hart_start(hartid, entry_point, hartid) {
+        let stack_top = crate::proc::percpu::stacks::get_stack_top(hartid as u32);
+        if stack_top == 0 {
+            crate::kprintln!("[smp] No stack for hart {}, skipping", hartid);
+            continue;
+        }
+        match hart_start(hartid, entry_point, stack_top) {
             0 => {
                 crate::kprintln!("[smp] Hart {} started", hartid);
             }
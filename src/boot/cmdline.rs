@@ -0,0 +1,259 @@
+//! 커널 명령줄 (DTB /chosen의 bootargs)
+//!
+//! QEMU `-append "..."`로 전달된 명령줄을 부팅 초기에 한 번 읽어 `key=value`
+//! 옵션으로 나눠 둡니다. 각 서브시스템은 DTB를 다시 뒤지지 않고 여기서 조회합니다.
+//!
+//! 형식은 Linux와 같습니다:
+//! - 공백으로 구분, `key=value` 또는 값 없는 플래그(`quiet`)
+//! - 값에 공백이 있으면 큰따옴표로 감쌈 (`key="a b"`)
+//! - 같은 키가 여러 번 나오면 마지막 값이 우선
+//!
+//! | 옵션 | 사용처 |
+//! |------|--------|
+//! | `loglevel=<0-4\|name>`, `quiet` | 콘솔 로그 레벨 (`log`) |
+//! | `console=<name>` | 주 콘솔 (`console`) |
+//! | `console.color=<on\|off>` | 로그 레벨과 셸 프롬프트의 ANSI 색 (`console`, 기본 off) |
+//! | `root=<dev>` | 부팅 시 FAT32 자동 마운트 (`block`) |
+//! | `smp=<n>`, `maxcpus=<n>`, `nosmp` | 시작할 CPU 수 (`start_smp`) |
+//! | `panic=<reboot\|halt\|초>`, `panic_log=<n>` | 패닉 후 동작, 출력할 최근 로그 줄 수 (`panic`) |
+//! | `watchdog=<초>` | 부팅 때 워치독을 켜고 커널이 pet (`watchdog`) |
+//! | `gdb=<ttyS<n>\|hvc0>` | GDB 원격 스텁 포트, 패닉 때 디버거로 진입 (`debug::gdbstub`) |
+//! | `crashdump=<dev>[@<블록>]` | 패닉 때 크래시 덤프를 쓸 디스크 영역 (`debug::crashdump`) |
+//! | `nohz=<on\|off>` | 보조 CPU의 틱 없는 idle (`time::nohz`, 기본 on) |
+//! | `module.verify=<off\|warn\|enforce>` | 모듈 체크섬 트레일러 검증 (`module::verify`, 기본 warn) |
+//! | `module.timeout=<초>` | 모듈 init/exit 제한 시간 (`module::sandbox`, 기본 5, 0 = 무제한) |
+//! | `-t <패턴>` | 실행할 테스트 선택, 여러 번 가능 (`test_runner`) |
+//! | `test.timeout=<초>` | 테스트 하나의 기본 제한 시간 (`test_runner`, 기본 10, 0 = 무제한) |
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::log::LogLevel;
+use crate::module::verify::VerifyMode;
+use crate::panic::PanicAction;
+use crate::sync::RwLock;
+
+/// 파싱한 옵션 하나
+struct Param {
+    key: String,
+    value: Option<String>,
+}
+
+/// 원본 명령줄과 파싱 결과
+struct Cmdline {
+    raw: String,
+    params: Vec<Param>,
+}
+
+static CMDLINE: RwLock<Cmdline> = RwLock::new(Cmdline {
+    raw: String::new(),
+    params: Vec::new(),
+});
+
+/// 명령줄을 공백 단위로 나눔 (큰따옴표 안의 공백은 유지, 따옴표는 제거)
+fn split_args(s: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut cur = String::new();
+    let mut in_quote = false;
+    let mut has_token = false;
+
+    for c in s.chars() {
+        match c {
+            '"' => {
+                in_quote = !in_quote;
+                has_token = true;
+            }
+            c if c.is_whitespace() && !in_quote => {
+                if has_token {
+                    args.push(core::mem::take(&mut cur));
+                    has_token = false;
+                }
+            }
+            c => {
+                cur.push(c);
+                has_token = true;
+            }
+        }
+    }
+    if has_token {
+        args.push(cur);
+    }
+    args
+}
+
+/// 명령줄 파싱
+fn parse(raw: &str) -> Vec<Param> {
+    split_args(raw)
+        .into_iter()
+        .map(|arg| match arg.split_once('=') {
+            Some((key, value)) => Param {
+                key: String::from(key),
+                value: Some(String::from(value)),
+            },
+            None => Param { key: arg, value: None },
+        })
+        .collect()
+}
+
+/// DTB bootargs를 읽어 파싱 (힙 초기화 후, 다른 서브시스템보다 먼저 호출)
+pub fn init() {
+    let raw = crate::dtb::get()
+        .and_then(|dt| dt.get_bootargs())
+        .unwrap_or_default();
+    let params = parse(&raw);
+
+    if !raw.is_empty() {
+        crate::kprintln!("[boot] Command line: {}", raw);
+    }
+    *CMDLINE.write() = Cmdline { raw, params };
+}
+
+/// 원본 명령줄
+pub fn raw() -> String {
+    CMDLINE.read().raw.clone()
+}
+
+/// `key=value`의 값 (여러 번 나오면 마지막 값)
+pub fn get(key: &str) -> Option<String> {
+    CMDLINE
+        .read()
+        .params
+        .iter()
+        .rev()
+        .find(|p| p.key == key)
+        .and_then(|p| p.value.clone())
+}
+
+/// 정수 값 (10진수 또는 `0x` 16진수)
+pub fn get_usize(key: &str) -> Option<usize> {
+    let value = get(key)?;
+    match value.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// 키가 있는지 (값 유무와 무관, `quiet` 같은 플래그용)
+pub fn has(key: &str) -> bool {
+    CMDLINE.read().params.iter().any(|p| p.key == key)
+}
+
+/// 모든 옵션 (`key`, `value`) 목록
+pub fn params() -> Vec<(String, Option<String>)> {
+    CMDLINE
+        .read()
+        .params
+        .iter()
+        .map(|p| (p.key.clone(), p.value.clone()))
+        .collect()
+}
+
+/// 콘솔 로그 레벨 (`loglevel=`, 없으면 `quiet`이면 Warn)
+pub fn loglevel() -> Option<LogLevel> {
+    match get("loglevel") {
+        Some(level) => LogLevel::from_str(&level),
+        None if has("quiet") => Some(LogLevel::Warn),
+        None => None,
+    }
+}
+
+/// 주 콘솔 이름 (`console=`, 여러 개면 마지막 것 - Linux와 같음)
+pub fn console() -> Option<String> {
+    get("console")
+}
+
+/// 루트 디바이스 (`root=`, `/dev/` 접두사 제거)
+pub fn root() -> Option<String> {
+    let root = get("root")?;
+    Some(String::from(root.strip_prefix("/dev/").unwrap_or(&root)))
+}
+
+/// 시작할 최대 CPU 수 (`nosmp` = 1, `maxcpus=`, `smp=`)
+pub fn max_cpus() -> Option<usize> {
+    if has("nosmp") {
+        return Some(1);
+    }
+    get_usize("maxcpus").or_else(|| get_usize("smp"))
+}
+
+/// 패닉 후 동작 (`panic=`)
+pub fn panic_action() -> Option<PanicAction> {
+    PanicAction::from_str(&get("panic")?)
+}
+
+/// 패닉 때 출력할 최근 로그 줄 수 (`panic_log=`)
+pub fn panic_log_lines() -> Option<usize> {
+    get_usize("panic_log")
+}
+
+/// 콘솔 ANSI 색 사용 여부 (`console.color=on|off`, 없거나 알 수 없는 값이면 None)
+pub fn console_color() -> Option<bool> {
+    match get("console.color")?.as_str() {
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// 틱 없는 idle 사용 여부 (`nohz=on|off`, 없거나 알 수 없는 값이면 None)
+pub fn nohz() -> Option<bool> {
+    match get("nohz")?.as_str() {
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// 모듈 체크섬 검증 정책 (`module.verify=`)
+pub fn module_verify() -> Option<VerifyMode> {
+    VerifyMode::from_str(&get("module.verify")?)
+}
+
+/// 모듈 init/exit 제한 시간 (`module.timeout=<초>`)
+pub fn module_timeout() -> Option<u32> {
+    get_usize("module.timeout").and_then(|s| u32::try_from(s).ok())
+}
+
+/// 테스트 선택 패턴 (`-t <패턴>`, 나온 순서대로)
+///
+/// `-t`는 값 없는 플래그로, 바로 다음 인자를 패턴으로 봅니다.
+#[cfg(feature = "test_runner")]
+pub fn test_filters() -> Vec<String> {
+    let cmdline = CMDLINE.read();
+    let mut filters = Vec::new();
+    let mut params = cmdline.params.iter();
+    while let Some(param) = params.next() {
+        if param.key != "-t" || param.value.is_some() {
+            continue;
+        }
+        if let Some(next) = params.next() {
+            filters.push(match &next.value {
+                Some(value) => alloc::format!("{}={}", next.key, value),
+                None => next.key.clone(),
+            });
+        }
+    }
+    filters
+}
+
+/// 테스트 하나의 기본 제한 시간 (`test.timeout=<초>`)
+#[cfg(feature = "test_runner")]
+pub fn test_timeout() -> Option<u32> {
+    get_usize("test.timeout").and_then(|s| u32::try_from(s).ok())
+}
+
+/// GDB 스텁 포트 (`gdb=<ttyS<n>|hvc0>`)
+pub fn gdb() -> Option<String> {
+    get("gdb")
+}
+
+/// 크래시 덤프 영역 (`crashdump=<dev>[@<블록>]`, `/dev/` 접두사 제거)
+pub fn crashdump() -> Option<String> {
+    let spec = get("crashdump")?;
+    Some(String::from(spec.strip_prefix("/dev/").unwrap_or(&spec)))
+}
+
+/// 부팅 때 켤 워치독 제한 시간 (`watchdog=<초>`)
+pub fn watchdog_timeout() -> Option<u32> {
+    get_usize("watchdog").and_then(|s| u32::try_from(s).ok())
+}
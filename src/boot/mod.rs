@@ -0,0 +1,5 @@
+//! 부팅 파라미터
+//!
+//! - cmdline: 커널 명령줄 (DTB /chosen의 bootargs) 파싱
+
+pub mod cmdline;
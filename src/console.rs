@@ -1,18 +1,618 @@
+//! 커널 콘솔
+//!
+//! 출력 장치를 콘솔 레지스트리에 등록하고, `kprint!`/`kprintln!`을 비롯한 모든 콘솔 출력을
+//! 켜져 있는 콘솔 모두에 우선순위 순으로 보냅니다.
+//!
+//! | 콘솔 | 장치 | 등록 |
+//! |------|------|------|
+//! | `uart` | 아키텍처 UART (PL011, NS16550) | 처음부터 (켜짐) |
+//! | `hvc0` | VirtIO 콘솔 (`virtio::console`) | `virtio::console::init()` (`console=hvc0`이면 켜짐) |
+//! | `fb0` | 프레임버퍼 콘솔 (`fbcon`) | `fbcon::init()` (켜짐) |
+//! | `sbi` | SBI 디버그 콘솔 (S-mode riscv64) | `init_from_bootargs()` (`console=sbi`이거나 UART가 없으면 켜짐) |
+//! | `log` | 메모리 링 (장치 출력 없음) | 처음부터 (꺼짐) |
+//!
+//! 켜진 콘솔 중 우선순위가 가장 높은 것이 주 콘솔입니다. 켜진 콘솔이 모두 출력에 실패하면
+//! UART로 대신 출력하고, 입력은 켜진 콘솔과 UART 모두에서 받습니다 (QEMU 설정 실수로 셸을
+//! 잃지 않도록).
+//!
+//! 레지스트리는 고정 크기 슬롯과 원자 변수뿐이라 IRQ 문맥과 패닉 경로에서도 락 없이 씁니다.
+//!
+//! `console.color`를 켜면 로그 레벨(에러 빨강, 경고 노랑)과 셸 프롬프트에 ANSI 색을 입힙니다.
+//! 색은 콘솔로 내보낼 때만 붙으므로 로그 링 버퍼(dmesg, `/dev/kmsg`)에는 일반 텍스트가 남습니다.
+//!
+//! 여러 UART 중 어느 것이 콘솔인지는 bootargs의 `console=ttyS<n>`으로 고릅니다
+//! (`drivers::serial`). UART 수신은 인터럽트 방식입니다. IRQ 핸들러가 RX FIFO를 비워 `receive()`로 수신 링에
+//! 넣고, `getc()`는 이 링에서 꺼냅니다. 셸과 `/dev/console`은 그 위의 TTY 줄 규칙
+//! (`drivers::tty`)을 거쳐 읽습니다.
+
+use alloc::vec::Vec;
 use core::fmt::{self, Write};
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicU8, Ordering};
 
-/// UART로 문자열을 출력하는 함수
-pub fn puts(s: &str) {
-    for &b in s.as_bytes() {
-        putc_arch(b);
+use crate::sync::IrqSpinlock;
+use crate::sysctl::{self, Kind, Tunable, Value};
+
+/// 콘솔 장치
+pub struct ConsoleDriver {
+    /// 레지스트리 이름 (`console enable <이름>`)
+    pub name: &'static str,
+    /// `console list`에 보일 설명
+    pub desc: &'static str,
+    /// 바이트열 출력 (장치가 받지 못하면 false)
+    pub write: fn(&[u8]) -> bool,
+    /// 입력 한 바이트 (non-blocking, 입력이 없는 콘솔은 `None`)
+    pub getc: Option<fn() -> Option<u8>>,
+}
+
+/// 콘솔 레지스트리 오류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleError {
+    /// 없는 이름
+    NotFound,
+    /// 같은 이름이 이미 등록됨
+    Exists,
+    /// 빈 슬롯 없음
+    Full,
+    /// 켜진 콘솔이 하나뿐이라 끌 수 없음
+    LastConsole,
+}
+
+impl fmt::Display for ConsoleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "no such console"),
+            Self::Exists => write!(f, "console already registered"),
+            Self::Full => write!(f, "console table full"),
+            Self::LastConsole => write!(f, "last enabled console"),
+        }
+    }
+}
+
+/// 등록할 수 있는 콘솔 수
+pub const MAX_CONSOLES: usize = 8;
+
+/// 기본 우선순위 (`uart`, `hvc0`, `sbi`)
+pub const PRIORITY_DEFAULT: u8 = 10;
+/// `console=`로 고른 주 콘솔의 우선순위
+pub const PRIORITY_PRIMARY: u8 = 20;
+
+/// 레지스트리 슬롯 (`driver`가 null이면 빈 슬롯)
+struct Slot {
+    driver: AtomicPtr<ConsoleDriver>,
+    priority: AtomicU8,
+    enabled: AtomicBool,
+}
+
+impl Slot {
+    const EMPTY: Slot = Slot::new(ptr::null_mut(), 0, false);
+
+    const fn new(driver: *mut ConsoleDriver, priority: u8, enabled: bool) -> Self {
+        Self {
+            driver: AtomicPtr::new(driver),
+            priority: AtomicU8::new(priority),
+            enabled: AtomicBool::new(enabled),
+        }
+    }
+
+    fn driver(&self) -> Option<&'static ConsoleDriver> {
+        // SAFETY: `register()`만 `&'static ConsoleDriver`를 넣고 지우지 않음
+        unsafe { self.driver.load(Ordering::Acquire).as_ref() }
+    }
+}
+
+static UART_CONSOLE: ConsoleDriver = ConsoleDriver {
+    name: "uart",
+    desc: "architecture UART (PL011/NS16550)",
+    write: |bytes| {
+        bytes.iter().for_each(|&b| putc_arch(b));
+        true
+    },
+    getc: Some(uart_getc),
+};
+
+static LOG_CONSOLE: ConsoleDriver = ConsoleDriver {
+    name: "log",
+    desc: "in-memory capture (console log)",
+    write: |bytes| {
+        // 패닉 경로에서 같은 CPU가 락을 쥔 채 다시 들어올 수 있으므로 기다리지 않음
+        if let Some(mut capture) = CAPTURE.try_lock() {
+            capture.push(bytes);
+        }
+        true
+    },
+    getc: None,
+};
+
+#[cfg(all(target_arch = "riscv64", feature = "riscv_smode"))]
+static SBI_CONSOLE: ConsoleDriver = ConsoleDriver {
+    name: "sbi",
+    desc: "SBI debug console",
+    write: |bytes| {
+        bytes.iter().for_each(|&b| crate::arch::sbi::console_putchar(b));
+        true
+    },
+    getc: Some(crate::arch::sbi::console_getchar),
+};
+
+/// 콘솔 레지스트리 (`uart`와 `log`는 부팅 첫 출력부터 있도록 미리 채움)
+static CONSOLES: [Slot; MAX_CONSOLES] = [
+    Slot::new(&UART_CONSOLE as *const ConsoleDriver as *mut ConsoleDriver, PRIORITY_DEFAULT, true),
+    Slot::new(&LOG_CONSOLE as *const ConsoleDriver as *mut ConsoleDriver, 0, false),
+    Slot::EMPTY,
+    Slot::EMPTY,
+    Slot::EMPTY,
+    Slot::EMPTY,
+    Slot::EMPTY,
+    Slot::EMPTY,
+];
+
+fn find(name: &str) -> Option<&'static Slot> {
+    CONSOLES.iter().find(|slot| slot.driver().is_some_and(|d| d.name == name))
+}
+
+/// 등록된 슬롯 번호를 우선순위 높은 순으로 (같으면 등록 순)
+fn ordered() -> ([usize; MAX_CONSOLES], usize) {
+    let mut order = [0usize; MAX_CONSOLES];
+    let mut count = 0;
+    for (index, slot) in CONSOLES.iter().enumerate() {
+        if slot.driver().is_none() {
+            continue;
+        }
+        let priority = slot.priority.load(Ordering::Relaxed);
+        let mut pos = count;
+        while pos > 0 && CONSOLES[order[pos - 1]].priority.load(Ordering::Relaxed) < priority {
+            order[pos] = order[pos - 1];
+            pos -= 1;
+        }
+        order[pos] = index;
+        count += 1;
+    }
+    (order, count)
+}
+
+/// 켜진 콘솔을 우선순위 순으로 방문
+fn for_each_enabled(mut f: impl FnMut(&'static ConsoleDriver)) {
+    let (order, count) = ordered();
+    for &index in &order[..count] {
+        let slot = &CONSOLES[index];
+        if slot.enabled.load(Ordering::Relaxed) {
+            if let Some(driver) = slot.driver() {
+                f(driver);
+            }
+        }
     }
 }
 
-/// UART로 단일 문자 출력
+/// 콘솔 등록
+pub fn register(driver: &'static ConsoleDriver, priority: u8, enabled: bool) -> Result<(), ConsoleError> {
+    if find(driver.name).is_some() {
+        return Err(ConsoleError::Exists);
+    }
+    let new = driver as *const ConsoleDriver as *mut ConsoleDriver;
+    for slot in &CONSOLES {
+        if slot.driver.load(Ordering::Acquire).is_null() {
+            slot.priority.store(priority, Ordering::Relaxed);
+            slot.enabled.store(enabled, Ordering::Relaxed);
+            if slot
+                .driver
+                .compare_exchange(ptr::null_mut(), new, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                return Ok(());
+            }
+        }
+    }
+    Err(ConsoleError::Full)
+}
+
+/// 콘솔 등록 해제 (셀프테스트용)
+pub fn unregister(name: &str) -> Result<(), ConsoleError> {
+    let slot = find(name).ok_or(ConsoleError::NotFound)?;
+    slot.enabled.store(false, Ordering::Relaxed);
+    slot.driver.store(ptr::null_mut(), Ordering::Release);
+    Ok(())
+}
+
+/// 콘솔 켜기
+pub fn enable(name: &str) -> Result<(), ConsoleError> {
+    let slot = find(name).ok_or(ConsoleError::NotFound)?;
+    slot.enabled.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// 콘솔 끄기 (마지막으로 켜진 콘솔은 끌 수 없음)
+pub fn disable(name: &str) -> Result<(), ConsoleError> {
+    let slot = find(name).ok_or(ConsoleError::NotFound)?;
+    let enabled = CONSOLES.iter().filter(|s| s.driver().is_some() && s.enabled.load(Ordering::Relaxed)).count();
+    if slot.enabled.load(Ordering::Relaxed) && enabled == 1 {
+        return Err(ConsoleError::LastConsole);
+    }
+    slot.enabled.store(false, Ordering::Relaxed);
+    Ok(())
+}
+
+/// 콘솔 우선순위 변경
+pub fn set_priority(name: &str, priority: u8) -> Result<(), ConsoleError> {
+    let slot = find(name).ok_or(ConsoleError::NotFound)?;
+    slot.priority.store(priority, Ordering::Relaxed);
+    Ok(())
+}
+
+/// 콘솔이 등록되어 켜져 있는지
+pub fn is_enabled(name: &str) -> bool {
+    find(name).is_some_and(|slot| slot.enabled.load(Ordering::Relaxed))
+}
+
+/// 주 콘솔 (켜진 콘솔 중 우선순위가 가장 높은 것)
+pub fn primary() -> Option<&'static str> {
+    let mut primary = None;
+    for_each_enabled(|driver| {
+        primary.get_or_insert(driver.name);
+    });
+    primary
+}
+
+/// `console list`에 보일 콘솔 정보
+#[derive(Debug, Clone, Copy)]
+pub struct ConsoleInfo {
+    pub name: &'static str,
+    pub desc: &'static str,
+    pub priority: u8,
+    pub enabled: bool,
+    pub has_input: bool,
+}
+
+/// 등록된 콘솔 (우선순위 순)
+pub fn list() -> Vec<ConsoleInfo> {
+    let (order, count) = ordered();
+    order[..count]
+        .iter()
+        .filter_map(|&index| {
+            let slot = &CONSOLES[index];
+            slot.driver().map(|driver| ConsoleInfo {
+                name: driver.name,
+                desc: driver.desc,
+                priority: slot.priority.load(Ordering::Relaxed),
+                enabled: slot.enabled.load(Ordering::Relaxed),
+                has_input: driver.getc.is_some(),
+            })
+        })
+        .collect()
+}
+
+/// 켜진 콘솔 모두로 바이트열 출력
+///
+/// 하나도 받지 못했으면 (hvc0 실패 등) UART로 대신 출력합니다.
+pub fn write(bytes: &[u8]) {
+    let mut delivered = false;
+    for_each_enabled(|driver| delivered |= (driver.write)(bytes));
+    if !delivered {
+        for &b in bytes {
+            putc_arch(b);
+        }
+    }
+}
+
+/// 콘솔로 문자열을 출력하는 함수
+pub fn puts(s: &str) {
+    write(s.as_bytes());
+}
+
+/// 콘솔로 단일 문자 출력
 pub fn putc(c: u8) {
-    putc_arch(c);
+    write(&[c]);
+}
+
+/// 콘솔 입력 한 바이트 (non-blocking)
+///
+/// 켜진 콘솔을 우선순위 순으로 확인합니다. UART는 꺼져 있어도 마지막에 확인합니다.
+pub fn getc() -> Option<u8> {
+    let mut byte = None;
+    for_each_enabled(|driver| {
+        if byte.is_none() {
+            byte = driver.getc.and_then(|getc| getc());
+        }
+    });
+    if byte.is_none() && !is_enabled("uart") {
+        byte = uart_getc();
+    }
+    byte
+}
+
+/// UART 입력 한 바이트
+///
+/// 수신 링을 먼저 보고, RX 인터럽트가 켜지기 전이나 링이 비었을 때는 UART를 직접 폴링합니다.
+fn uart_getc() -> Option<u8> {
+    let queued = RX_RING.lock().pop();
+    queued.or_else(crate::arch::uart::getc)
+}
+
+/// ANSI 글자색
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    /// 에러 로그
+    Red,
+    /// 경고 로그
+    Yellow,
+    /// 셸 프롬프트 (굵게)
+    BoldGreen,
+}
+
+impl Color {
+    /// SGR 이스케이프 시퀀스
+    const fn sgr(self) -> &'static str {
+        match self {
+            Color::Red => "\x1b[31m",
+            Color::Yellow => "\x1b[33m",
+            Color::BoldGreen => "\x1b[1;32m",
+        }
+    }
+}
+
+/// 색 되돌리기
+const SGR_RESET: &str = "\x1b[0m";
+
+/// ANSI 색 사용 여부 (색을 모르는 터미널을 위해 기본은 꺼짐)
+static COLOR: AtomicBool = AtomicBool::new(false);
+
+/// ANSI 색을 쓰는지
+pub fn color_enabled() -> bool {
+    COLOR.load(Ordering::Relaxed)
+}
+
+/// ANSI 색 켜기/끄기
+pub fn set_color(on: bool) {
+    COLOR.store(on, Ordering::Relaxed);
+}
+
+/// `parts`를 `color`로 칠해 출력 (색이 꺼져 있거나 `None`이면 그대로)
+///
+/// 색 여부를 한 번만 읽으므로 출력 도중 꺼져도 색을 되돌리는 시퀀스는 짝을 맞춰 나갑니다.
+pub fn puts_colored(color: Option<Color>, parts: &[&str]) {
+    let color = color.filter(|_| color_enabled());
+    if let Some(color) = color {
+        puts(color.sgr());
+    }
+    for part in parts {
+        puts(part);
+    }
+    if color.is_some() {
+        puts(SGR_RESET);
+    }
+}
+
+static SYSCTL: [Tunable; 1] = [Tunable {
+    name: "console.color",
+    desc: "ANSI colors for log levels and the shell prompt",
+    kind: Kind::Bool,
+    get: || Value::Bool(color_enabled()),
+    set: Some(|v| {
+        set_color(sysctl::boolean(&v));
+        Ok(())
+    }),
+}];
+
+/// bootargs의 `console.color=` 처리 (`boot::cmdline::init()` 후 호출)
+pub fn init_from_cmdline() {
+    if let Some(on) = crate::boot::cmdline::console_color() {
+        set_color(on);
+    }
+    sysctl::register_all(&SYSCTL);
+}
+
+/// `log` 콘솔이 보관하는 출력 크기
+const CAPTURE_SIZE: usize = 4096;
+
+/// `log` 콘솔의 메모리 링 (가득 차면 오래된 바이트부터 덮어씀)
+struct Capture {
+    buffer: [u8; CAPTURE_SIZE],
+    head: usize,
+    len: usize,
+}
+
+impl Capture {
+    fn push(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.buffer[self.head] = b;
+            self.head = (self.head + 1) % CAPTURE_SIZE;
+            self.len = (self.len + 1).min(CAPTURE_SIZE);
+        }
+    }
+}
+
+static CAPTURE: IrqSpinlock<Capture> = IrqSpinlock::new(Capture {
+    buffer: [0; CAPTURE_SIZE],
+    head: 0,
+    len: 0,
+});
+
+/// `log` 콘솔이 보관한 출력 (오래된 것부터)
+pub fn captured() -> Vec<u8> {
+    let capture = CAPTURE.lock();
+    let start = (capture.head + CAPTURE_SIZE - capture.len) % CAPTURE_SIZE;
+    (0..capture.len).map(|i| capture.buffer[(start + i) % CAPTURE_SIZE]).collect()
+}
+
+/// `log` 콘솔 보관 내용 비우기
+pub fn clear_captured() {
+    let mut capture = CAPTURE.lock();
+    capture.head = 0;
+    capture.len = 0;
+}
+
+/// 수신 링 크기
+const RX_RING_SIZE: usize = 256;
+
+/// UART 수신 링 (IRQ 핸들러가 채우고 `getc()`가 비움)
+///
+/// 콘솔이 아닌 시리얼 포트(`drivers::serial`)도 포트마다 하나씩 씁니다.
+pub struct RxRing {
+    buffer: [u8; RX_RING_SIZE],
+    read_pos: usize,
+    count: usize,
+}
+
+impl RxRing {
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; RX_RING_SIZE],
+            read_pos: 0,
+            count: 0,
+        }
+    }
+
+    /// 바이트 추가 (가득 차면 false)
+    pub fn push(&mut self, byte: u8) -> bool {
+        if self.count >= RX_RING_SIZE {
+            return false;
+        }
+        self.buffer[(self.read_pos + self.count) % RX_RING_SIZE] = byte;
+        self.count += 1;
+        true
+    }
+
+    pub fn pop(&mut self) -> Option<u8> {
+        if self.count == 0 {
+            return None;
+        }
+        let byte = self.buffer[self.read_pos];
+        self.read_pos = (self.read_pos + 1) % RX_RING_SIZE;
+        self.count -= 1;
+        Some(byte)
+    }
+}
+
+static RX_RING: IrqSpinlock<RxRing> = IrqSpinlock::new(RxRing::new());
+
+/// 수신 바이트를 링에 추가 (UART IRQ 핸들러에서 호출, 링이 가득 차면 버림)
+///
+/// IRQ 문맥이므로 에코는 하지 않습니다. 에코는 입력을 소비하는 TTY 줄 규칙이 합니다.
+pub fn receive(byte: u8) {
+    let _ = RX_RING.lock().push(byte);
+}
+
+/// `name`을 주 콘솔로 (켜고 우선순위를 올리며, 같은 장치에 겹쳐 쓰지 않도록 UART는 끔)
+fn make_primary(name: &str) -> bool {
+    if enable(name).is_err() {
+        return false;
+    }
+    let _ = set_priority(name, PRIORITY_PRIMARY);
+    let _ = disable("uart");
+    true
+}
+
+/// bootargs의 `console=<name>` 처리 (VirtIO 콘솔 초기화 후 호출)
+///
+/// `hvc0`과 (S-mode riscv64의) `sbi`를 인식합니다. `ttyS<n>`/`ttyAMA<n>`은 플랫폼 탐색이
+/// 이미 콘솔 UART를 고를 때 반영했습니다 (`drivers::serial`).
+pub fn init_from_bootargs() {
+    #[cfg(all(target_arch = "riscv64", feature = "riscv_smode"))]
+    if crate::arch::sbi::console_available() {
+        let _ = register(&SBI_CONSOLE, PRIORITY_DEFAULT, false);
+        // S-mode: DTB에 UART가 없으면 SBI 디버그 콘솔로
+        if crate::drivers::config::uarts().is_empty() && make_primary("sbi") {
+            crate::kprintln!("[console] No UART, using SBI debug console");
+        }
+    }
+
+    // 여러 개면 마지막 것이 주 콘솔 (Linux와 같음)
+    let Some(name) = crate::boot::cmdline::console() else {
+        return;
+    };
+
+    if name == "hvc0" {
+        if make_primary("hvc0") {
+            crate::kprintln!("[console] Switched console to hvc0");
+        } else {
+            crate::kprintln!("[console] hvc0 not found, staying on UART");
+        }
+    }
+
+    #[cfg(all(target_arch = "riscv64", feature = "riscv_smode"))]
+    if name == "sbi" {
+        if make_primary("sbi") {
+            crate::kprintln!("[console] Switched console to SBI debug console");
+        } else {
+            crate::kprintln!("[console] SBI debug console not available, staying on UART");
+        }
+    }
+}
+
+/// `console` 명령: 인자가 없거나 `list`면 목록, `enable`/`disable`/`priority`로 설정 변경,
+/// `log`면 `log` 콘솔이 보관한 출력, `color`면 ANSI 색 켜기/끄기
+pub fn command(args: &[&str]) {
+    let result = match args {
+        [] | ["list"] => {
+            print_list();
+            return;
+        }
+        ["enable", name] => enable(name),
+        ["disable", name] => disable(name),
+        ["priority", name, value] => match value.parse() {
+            Ok(priority) => set_priority(name, priority),
+            Err(_) => {
+                crate::kprintln!("console: priority must be 0-255");
+                return;
+            }
+        },
+        ["log"] => {
+            // 출력이 다시 링에 쌓이므로 먼저 복사해 둠
+            let text = captured();
+            puts(&alloc::string::String::from_utf8_lossy(&text));
+            return;
+        }
+        ["log", "clear"] => {
+            clear_captured();
+            return;
+        }
+        ["color", rest @ ..] => {
+            match rest {
+                [] => {}
+                ["on"] => set_color(true),
+                ["off"] => set_color(false),
+                _ => {
+                    crate::kprintln!("Usage: console color [on|off]");
+                    return;
+                }
+            }
+            crate::kprintln!("console: color {}", if color_enabled() { "on" } else { "off" });
+            return;
+        }
+        _ => {
+            crate::kprintln!(
+                "Usage: console [list|enable <name>|disable <name>|priority <name> <n>|log [clear]|color [on|off]]"
+            );
+            return;
+        }
+    };
+    match result {
+        Ok(()) => print_list(),
+        Err(e) => crate::kprintln!("console: {}: {}", args[1], e),
+    }
+}
+
+fn print_list() {
+    let primary = primary();
+    crate::kprintln!("NAME   PRI  STATE     INPUT  DESCRIPTION");
+    for info in list() {
+        let state = match (info.enabled, Some(info.name) == primary) {
+            (true, true) => "primary",
+            (true, false) => "enabled",
+            _ => "disabled",
+        };
+        crate::kprintln!(
+            "{:<6} {:>3}  {:<8}  {:<5}  {}",
+            info.name,
+            info.priority,
+            state,
+            if info.has_input { "yes" } else { "no" },
+            info.desc
+        );
+    }
 }
 
-/// UART로 포맷팅된 문자열을 출력하는 구조체
+/// 콘솔로 포맷팅된 문자열을 출력하는 구조체
 pub struct Console;
 
 impl Write for Console {
@@ -22,12 +622,12 @@ impl Write for Console {
     }
 }
 
-/// 포맷팅된 문자열을 UART로 출력하는 함수
+/// 포맷팅된 문자열을 콘솔로 출력하는 함수
 pub fn kprint(args: fmt::Arguments) {
     let _ = Console.write_fmt(args);
 }
 
-/// 개행을 포함하여 포맷팅된 문자열을 UART로 출력하는 함수
+/// 개행을 포함하여 포맷팅된 문자열을 콘솔로 출력하는 함수
 pub fn kprintln(args: fmt::Arguments) {
     kprint(args);
     puts("\n");
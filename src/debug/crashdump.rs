@@ -0,0 +1,633 @@
+//! 크래시 덤프 (`crashdump` 명령, `crashdump=` bootargs)
+//!
+//! 패닉 때 메시지, 레지스터, 백트레이스, 스레드 목록, 스택, 최근 로그를 정해 둔 디스크
+//! 영역(`vda2` 같은 전용 파티션이나 `vda@<블록>`부터 64KB)에 남기고, 재부팅한 뒤
+//! `crashdump show`로 읽어 봅니다 (Linux pstore/kdump와 비슷한 용도).
+//!
+//! 패닉 경로(`save`)는 힙을 쓰지 않습니다. 정적 버퍼에 덤프를 만들고 블록 디바이스의
+//! `panic_write`(폴링, 락은 `try_*`)로 씁니다. 락을 잡지 못한 부분(스레드 목록, 로그)은
+//! 빠진 채로 남습니다.
+//!
+//! ```text
+//! 0   magic "KRSCRASH"   8  version   12 전체 길이   16 crc32([20..길이])
+//! 20  CPU   24 TID (0 = 없음)   32 가동 시간 ns   40 Unix 시각 ns   48 예외 pc (0 = 없음)
+//! 64  섹션: 종류(u32) 길이(u32) 데이터, 8바이트 정렬
+//! ```
+//!
+//! | 섹션 | 데이터 |
+//! |------|--------|
+//! | 메시지 | 패닉 메시지와 위치 (텍스트) |
+//! | 레지스터 | 이름(8바이트) + 값(u64) 쌍, 예외로 난 패닉은 없음 (예외 핸들러가 출력) |
+//! | 백트레이스 | 패닉 때 해석한 `[<주소>] 심볼+오프셋` 줄 (다시 빌드한 커널로 읽어도 맞음) |
+//! | 스레드 | TID(u64), 실행 중인 CPU(u32, 없으면 `u32::MAX`), 런큐 CPU(u32), 상태(u32), 이름(28바이트) |
+//! | 스택 | 시작 주소(u64) + 그 뒤 최대 2KB (매핑된 페이지까지) |
+//! | 로그 | 로그 버퍼의 마지막 32KB 이하 (줄 단위) |
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Write;
+use core::panic::Location;
+use core::sync::atomic::Ordering;
+
+use crate::block::{self, BlockDevice, BlockError};
+use crate::panic::{CallerRegs, Symbolized};
+use crate::proc::{percpu, ThreadState};
+use crate::sync::Spinlock;
+use crate::trace::backtrace;
+
+/// 덤프 영역 크기
+pub const DUMP_SIZE: usize = 64 * 1024;
+
+const MAGIC: [u8; 8] = *b"KRSCRASH";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 64;
+
+/// 섹션 헤더 (종류 + 길이)
+const SECTION_HEADER_LEN: usize = 8;
+/// 스택 섹션 최대 크기
+const STACK_BYTES: usize = 2048;
+/// 로그 섹션 최대 크기
+const LOG_BYTES: usize = 32 * 1024;
+/// 백트레이스 최대 프레임 수
+const MAX_FRAMES: usize = 32;
+/// 스레드 레코드 크기와 이름 길이
+const THREAD_RECORD_LEN: usize = 48;
+const THREAD_NAME_LEN: usize = 28;
+/// 레지스터 이름 길이
+const REG_NAME_LEN: usize = 8;
+
+/// 섹션 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+enum Section {
+    Message = 1,
+    Registers = 2,
+    Backtrace = 3,
+    Threads = 4,
+    Stack = 5,
+    Log = 6,
+}
+
+impl Section {
+    fn from_u32(kind: u32) -> Option<Self> {
+        match kind {
+            1 => Some(Self::Message),
+            2 => Some(Self::Registers),
+            3 => Some(Self::Backtrace),
+            4 => Some(Self::Threads),
+            5 => Some(Self::Stack),
+            6 => Some(Self::Log),
+            _ => None,
+        }
+    }
+}
+
+/// 덤프를 쓸 디스크 영역
+struct Target {
+    name: String,
+    device: Arc<dyn BlockDevice>,
+    /// 시작 블록
+    start: u64,
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}@{}", self.name, self.start)
+    }
+}
+
+static TARGET: Spinlock<Option<Target>> = Spinlock::new(None);
+
+/// 패닉 때 덤프를 만드는 버퍼 (패닉 경로에서 할당하지 않도록 정적)
+static BUFFER: Spinlock<[u8; DUMP_SIZE]> = Spinlock::new([0; DUMP_SIZE]);
+
+/// `<dev>[@<블록>]` 파싱
+pub fn parse_spec(spec: &str) -> Option<(&str, u64)> {
+    let spec = spec.strip_prefix("/dev/").unwrap_or(spec);
+    let (name, start) = match spec.split_once('@') {
+        Some((name, start)) => (name, start.parse().ok()?),
+        None => (spec, 0),
+    };
+    (!name.is_empty()).then_some((name, start))
+}
+
+/// 덤프 영역 설정 (None = 끄기)
+pub fn set_target(spec: Option<&str>) -> Result<(), &'static str> {
+    let target = match spec {
+        None => None,
+        Some(spec) => {
+            let (name, start) = parse_spec(spec).ok_or("expected <dev>[@<block>]")?;
+            let device = block::get_device(name).ok_or("device not found")?;
+            check_region(device.as_ref(), start)?;
+            Some(Target {
+                name: String::from(name),
+                device,
+                start,
+            })
+        }
+    };
+    *TARGET.lock() = target;
+    Ok(())
+}
+
+/// `start`부터 덤프 영역이 들어가는지
+fn check_region(device: &dyn BlockDevice, start: u64) -> Result<(), &'static str> {
+    let block_size = device.block_size();
+    if block_size == 0 || DUMP_SIZE % block_size != 0 {
+        return Err("unsupported block size");
+    }
+    if device.is_read_only() {
+        return Err("read-only device");
+    }
+    match start.checked_add((DUMP_SIZE / block_size) as u64) {
+        Some(end) if end <= device.block_count() => Ok(()),
+        _ => Err("region past end of device"),
+    }
+}
+
+/// 현재 덤프 영역 (`vda@2048`)
+pub fn target() -> Option<String> {
+    TARGET.lock().as_ref().map(|t| alloc::format!("{}", t))
+}
+
+/// bootargs의 `crashdump=<dev>[@<블록>]` 처리 (블록 디바이스 등록 후 호출)
+///
+/// 영역에 이전 부팅의 덤프가 있으면 알려 줍니다.
+pub fn init_from_cmdline() {
+    let Some(spec) = crate::boot::cmdline::crashdump() else {
+        return;
+    };
+    if let Err(e) = set_target(Some(&spec)) {
+        crate::kprintln!("[crashdump] crashdump={}: {}", spec, e);
+        return;
+    }
+    crate::kprintln!("[crashdump] Dumps go to {} ({} KB)", spec, DUMP_SIZE / 1024);
+    match read() {
+        Ok(Some(dump)) => crate::kprintln!(
+            "[crashdump] Found a crash dump from a previous boot: {} (crashdump show)",
+            dump.message.lines().next().unwrap_or("")
+        ),
+        Ok(None) => {}
+        Err(e) => crate::kprintln!("[crashdump] {}: {}", spec, e),
+    }
+}
+
+/// 패닉 때 덤프 저장 (`panic::handle`에서 호출, 영역이 없으면 아무것도 하지 않음)
+///
+/// `exception`은 처리하지 못한 예외의 pc이고, 백트레이스는 `fp`부터 따라갑니다.
+pub(crate) fn save(
+    message: &dyn fmt::Display,
+    location: Option<&Location>,
+    cpu: usize,
+    exception: Option<usize>,
+    fp: usize,
+    regs: &CallerRegs,
+) {
+    let Some(target) = TARGET.try_lock() else {
+        crate::console::puts("Crash dump: target locked\n");
+        return;
+    };
+    let Some(target) = target.as_ref() else {
+        return;
+    };
+    let Some(mut buf) = BUFFER.try_lock() else {
+        crate::console::puts("Crash dump: buffer busy\n");
+        return;
+    };
+
+    let len = build(&mut buf[..], message, location, cpu, exception, fp, regs);
+    let block_size = target.device.block_size();
+    let padded = len.div_ceil(block_size) * block_size;
+    match target.device.panic_write(target.start, &buf[..padded]) {
+        Ok(()) => crate::console::kprintln(format_args!("Crash dump: {} bytes written to {}", len, target)),
+        Err(e) => crate::console::kprintln(format_args!("Crash dump to {} failed: {}", target, e)),
+    }
+}
+
+/// 덤프를 `buf`에 만들고 길이 반환 (힙을 쓰지 않음)
+pub(crate) fn build(
+    buf: &mut [u8],
+    message: &dyn fmt::Display,
+    location: Option<&Location>,
+    cpu: usize,
+    exception: Option<usize>,
+    fp: usize,
+    regs: &CallerRegs,
+) -> usize {
+    buf.fill(0);
+    let mut w = DumpWriter { buf, len: HEADER_LEN };
+
+    w.section(Section::Message, |w| {
+        let _ = write!(w, "{}", message);
+        if let Some(location) = location {
+            let _ = write!(w, "\n  at {}:{}:{}", location.file(), location.line(), location.column());
+        }
+    });
+    if exception.is_none() {
+        w.section(Section::Registers, |w| {
+            for (name, value) in regs.iter() {
+                let mut padded = [0u8; REG_NAME_LEN];
+                let n = name.len().min(REG_NAME_LEN);
+                padded[..n].copy_from_slice(&name.as_bytes()[..n]);
+                w.put(&padded);
+                w.put(&(value as u64).to_le_bytes());
+            }
+        });
+    }
+    w.section(Section::Backtrace, |w| {
+        let mut frames = MAX_FRAMES;
+        if let Some(pc) = exception {
+            let _ = writeln!(w, "{}", Symbolized(pc));
+            frames -= 1;
+        }
+        backtrace::walk(fp, frames, |ret| {
+            let _ = writeln!(w, "{}", Symbolized(ret));
+        });
+    });
+    if let Some(threads) = crate::proc::THREADS.try_lock() {
+        w.section(Section::Threads, |w| {
+            for &thread in threads.iter() {
+                let t = thread.get();
+                let running = (0..percpu::total_count())
+                    .find(|&cpu| percpu::get(cpu).current_tid.load(Ordering::Relaxed) == t.tid)
+                    .unwrap_or(u32::MAX);
+                // 런큐 락 없이 읽으므로 다른 CPU가 바꾸는 중이면 어긋날 수 있음
+                let state = t.state as u32;
+                let mut name = [0u8; THREAD_NAME_LEN];
+                let n = t.name.len().min(THREAD_NAME_LEN);
+                name[..n].copy_from_slice(&t.name.as_bytes()[..n]);
+                w.put(&t.tid.to_le_bytes());
+                w.put(&running.to_le_bytes());
+                w.put(&t.cpu.load(Ordering::Relaxed).to_le_bytes());
+                w.put(&state.to_le_bytes());
+                w.put(&name);
+            }
+        });
+    }
+    w.section(Section::Stack, |w| {
+        let base = if exception.is_some() { fp } else { regs.sp() };
+        w.put(&(base as u64).to_le_bytes());
+        let mut addr = base;
+        while addr < base + STACK_BYTES {
+            let page_end = (addr | (crate::mm::page::PAGE_SIZE - 1)) + 1;
+            let end = page_end.min(base + STACK_BYTES);
+            if !crate::arch::gdb::is_mapped(addr) {
+                break;
+            }
+            // Safety: 매핑된 페이지 안
+            w.put(unsafe { core::slice::from_raw_parts(addr as *const u8, end - addr) });
+            addr = end;
+        }
+    });
+    w.section(Section::Log, |w| w.put_log_tail());
+
+    let len = w.len;
+    let tid = crate::proc::current_tid().unwrap_or(0);
+    let header = &mut buf[..HEADER_LEN];
+    header[0..8].copy_from_slice(&MAGIC);
+    header[8..12].copy_from_slice(&VERSION.to_le_bytes());
+    header[12..16].copy_from_slice(&(len as u32).to_le_bytes());
+    header[20..24].copy_from_slice(&(cpu as u32).to_le_bytes());
+    header[24..32].copy_from_slice(&tid.to_le_bytes());
+    header[32..40].copy_from_slice(&crate::time::clock::monotonic_ns().to_le_bytes());
+    header[40..48].copy_from_slice(&crate::time::clock::realtime_ns().to_le_bytes());
+    header[48..56].copy_from_slice(&(exception.unwrap_or(0) as u64).to_le_bytes());
+    let crc = crate::module::verify::crc32(&buf[20..len]);
+    buf[16..20].copy_from_slice(&crc.to_le_bytes());
+    len
+}
+
+/// 고정 버퍼에 덤프를 채우는 쓰기 도구 (넘치면 잘림)
+struct DumpWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl DumpWriter<'_> {
+    fn put(&mut self, bytes: &[u8]) {
+        let n = bytes.len().min(self.buf.len() - self.len);
+        self.buf[self.len..self.len + n].copy_from_slice(&bytes[..n]);
+        self.len += n;
+    }
+
+    /// 섹션 헤더를 쓰고 `f`가 채운 만큼 길이를 기록한 뒤 8바이트로 정렬
+    fn section(&mut self, kind: Section, f: impl FnOnce(&mut Self)) {
+        if self.buf.len() - self.len < SECTION_HEADER_LEN {
+            return;
+        }
+        let header = self.len;
+        self.len += SECTION_HEADER_LEN;
+        f(self);
+        let data_len = (self.len - header - SECTION_HEADER_LEN) as u32;
+        self.buf[header..header + 4].copy_from_slice(&(kind as u32).to_le_bytes());
+        self.buf[header + 4..header + 8].copy_from_slice(&data_len.to_le_bytes());
+        self.len = self.len.next_multiple_of(8).min(self.buf.len());
+    }
+
+    /// 로그 버퍼의 마지막 `LOG_BYTES` 이하를 줄 단위로
+    ///
+    /// 전체 로그를 원형으로 덮어쓴 뒤 돌려 놓고, 잘린 첫 줄은 버립니다.
+    fn put_log_tail(&mut self) {
+        let cap = LOG_BYTES.min(self.buf.len() - self.len);
+        if cap == 0 {
+            return;
+        }
+        let area = &mut self.buf[self.len..self.len + cap];
+        let mut pos = 0;
+        let mut wrapped = false;
+        let ok = crate::log::tail(usize::MAX, |line| {
+            for &b in line {
+                area[pos] = b;
+                pos += 1;
+                if pos == cap {
+                    pos = 0;
+                    wrapped = true;
+                }
+            }
+        });
+        if !ok {
+            return;
+        }
+        let len = if wrapped {
+            area.rotate_left(pos);
+            let skip = area.iter().position(|&b| b == b'\n').map_or(cap, |i| i + 1);
+            area.copy_within(skip.., 0);
+            cap - skip
+        } else {
+            pos
+        };
+        self.len += len;
+    }
+}
+
+impl fmt::Write for DumpWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.put(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// 덤프 속 스레드 하나
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpThread {
+    pub tid: u64,
+    /// 실행 중이던 CPU
+    pub running: Option<u32>,
+    /// 속한 런큐
+    pub cpu: u32,
+    pub state: Option<ThreadState>,
+    pub name: String,
+}
+
+/// 읽어 들인 덤프
+#[derive(Debug, Clone)]
+pub struct CrashDump {
+    pub len: usize,
+    pub cpu: u32,
+    pub tid: Option<u64>,
+    pub uptime_ns: u64,
+    /// Unix 시각 (RTC가 없었으면 부팅 후 시간과 같음)
+    pub realtime_ns: u64,
+    pub exception_pc: Option<u64>,
+    pub message: String,
+    pub registers: Vec<(String, u64)>,
+    pub backtrace: Vec<String>,
+    /// 스레드 목록 락을 잡지 못했으면 None
+    pub threads: Option<Vec<DumpThread>>,
+    /// (시작 주소, 내용)
+    pub stack: Option<(u64, Vec<u8>)>,
+    /// 로그 버퍼를 잡지 못했으면 None
+    pub log: Option<String>,
+}
+
+fn le_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([buf[offset], buf[offset + 1], buf[offset + 2], buf[offset + 3]])
+}
+
+fn le_u64(buf: &[u8], offset: usize) -> u64 {
+    (le_u32(buf, offset) as u64) | ((le_u32(buf, offset + 4) as u64) << 32)
+}
+
+/// NUL로 채운 고정 길이 이름
+fn fixed_str(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// 덤프 영역 내용 해석 (덤프가 없으면 None)
+pub fn parse(data: &[u8]) -> Result<Option<CrashDump>, &'static str> {
+    if data.len() < HEADER_LEN || data[..8] != MAGIC {
+        return Ok(None);
+    }
+    if le_u32(data, 8) != VERSION {
+        return Err("unsupported dump version");
+    }
+    let len = le_u32(data, 12) as usize;
+    if !(HEADER_LEN..=data.len()).contains(&len) {
+        return Err("bad dump length");
+    }
+    if crate::module::verify::crc32(&data[20..len]) != le_u32(data, 16) {
+        return Err("checksum mismatch");
+    }
+
+    let tid = le_u64(data, 24);
+    let exception_pc = le_u64(data, 48);
+    let mut dump = CrashDump {
+        len,
+        cpu: le_u32(data, 20),
+        tid: (tid != 0).then_some(tid),
+        uptime_ns: le_u64(data, 32),
+        realtime_ns: le_u64(data, 40),
+        exception_pc: (exception_pc != 0).then_some(exception_pc),
+        message: String::new(),
+        registers: Vec::new(),
+        backtrace: Vec::new(),
+        threads: None,
+        stack: None,
+        log: None,
+    };
+
+    let mut offset = HEADER_LEN;
+    while offset + SECTION_HEADER_LEN <= len {
+        let kind = le_u32(data, offset);
+        let data_len = le_u32(data, offset + 4) as usize;
+        let start = offset + SECTION_HEADER_LEN;
+        let Some(body) = data.get(start..start + data_len).filter(|_| start + data_len <= len) else {
+            return Err("bad section length");
+        };
+        match Section::from_u32(kind) {
+            Some(Section::Message) => dump.message = String::from_utf8_lossy(body).into_owned(),
+            Some(Section::Registers) => {
+                dump.registers = body
+                    .chunks_exact(REG_NAME_LEN + 8)
+                    .map(|r| (fixed_str(&r[..REG_NAME_LEN]), le_u64(r, REG_NAME_LEN)))
+                    .collect();
+            }
+            Some(Section::Backtrace) => {
+                dump.backtrace = String::from_utf8_lossy(body).lines().map(String::from).collect();
+            }
+            Some(Section::Threads) => {
+                let threads = body.chunks_exact(THREAD_RECORD_LEN).map(|r| DumpThread {
+                    tid: le_u64(r, 0),
+                    running: Some(le_u32(r, 8)).filter(|&cpu| cpu != u32::MAX),
+                    cpu: le_u32(r, 12),
+                    state: match le_u32(r, 16) {
+                        0 => Some(ThreadState::Ready),
+                        1 => Some(ThreadState::Running),
+                        2 => Some(ThreadState::Blocked),
+                        3 => Some(ThreadState::Terminated),
+                        _ => None,
+                    },
+                    name: fixed_str(&r[20..20 + THREAD_NAME_LEN]),
+                });
+                dump.threads = Some(threads.collect());
+            }
+            Some(Section::Stack) if body.len() >= 8 => dump.stack = Some((le_u64(body, 0), body[8..].to_vec())),
+            Some(Section::Log) => dump.log = Some(String::from_utf8_lossy(body).into_owned()),
+            // 새 버전의 섹션은 건너뜀
+            _ => {}
+        }
+        offset = (start + data_len).next_multiple_of(8);
+    }
+    Ok(Some(dump))
+}
+
+/// `device`의 `start`부터 덤프 읽기
+pub fn read_from(device: &dyn BlockDevice, start: u64) -> Result<Option<CrashDump>, &'static str> {
+    check_region(device, start)?;
+    let mut data = vec![0u8; DUMP_SIZE];
+    device.read_blocks(start, &mut data).map_err(block_error)?;
+    parse(&data)
+}
+
+/// 덤프 영역에서 덤프 읽기
+pub fn read() -> Result<Option<CrashDump>, &'static str> {
+    let (device, start) = current()?;
+    read_from(device.as_ref(), start)
+}
+
+/// 덤프 영역의 헤더를 지워 덤프 없음으로
+pub fn clear() -> Result<(), &'static str> {
+    let (device, start) = current()?;
+    let zero = vec![0u8; device.block_size()];
+    device.write_block(start, &zero).map_err(block_error)?;
+    device.sync().map_err(block_error)
+}
+
+fn current() -> Result<(Arc<dyn BlockDevice>, u64), &'static str> {
+    let target = TARGET.lock();
+    let target = target.as_ref().ok_or("no dump region (crashdump dev <dev>[@<block>])")?;
+    Ok((target.device.clone(), target.start))
+}
+
+fn block_error(e: BlockError) -> &'static str {
+    match e {
+        BlockError::InvalidBlock => "region past end of device",
+        BlockError::ReadOnly => "read-only device",
+        _ => "I/O error",
+    }
+}
+
+/// `crashdump` 출력
+pub fn report() {
+    match target() {
+        Some(target) => crate::kprintln!("  region: {} ({} KB)", target, DUMP_SIZE / 1024),
+        None => {
+            crate::kprintln!("  region: none (crashdump dev <dev>[@<block>])");
+            return;
+        }
+    }
+    match read() {
+        Ok(Some(dump)) => crate::kprintln!(
+            "  dump: {} bytes, uptime {}.{:06}s, {}",
+            dump.len,
+            dump.uptime_ns / 1_000_000_000,
+            dump.uptime_ns % 1_000_000_000 / 1000,
+            dump.message.lines().next().unwrap_or("")
+        ),
+        Ok(None) => crate::kprintln!("  dump: none"),
+        Err(e) => crate::kprintln!("  dump: {}", e),
+    }
+}
+
+/// `crashdump show` 출력
+pub fn show(dump: &CrashDump) {
+    use crate::time::clock::{DateTime, NSEC_PER_SEC};
+
+    let mut lines = dump.message.lines();
+    crate::kprintln!("Kernel panic - not syncing: {}", lines.next().unwrap_or(""));
+    for line in lines {
+        crate::kprintln!("{}", line);
+    }
+    match dump.tid {
+        Some(tid) => crate::kprintln!("CPU: {} TID: {}", dump.cpu, tid),
+        None => crate::kprintln!("CPU: {}", dump.cpu),
+    }
+    let secs = dump.realtime_ns / NSEC_PER_SEC;
+    crate::kprintln!(
+        "Uptime: {}.{:06}s, time: {} UTC",
+        dump.uptime_ns / NSEC_PER_SEC,
+        dump.uptime_ns % NSEC_PER_SEC / 1000,
+        DateTime::from_unix(secs)
+    );
+
+    if let Some(pc) = dump.exception_pc {
+        crate::kprintln!("Exception at {:#018x}", pc);
+    }
+    if !dump.registers.is_empty() {
+        crate::kprintln!("Caller registers:");
+        for regs in dump.registers.chunks(3) {
+            let mut line = String::new();
+            for (name, value) in regs {
+                let _ = write!(line, "  {:>3}: {:016x}", name, value);
+            }
+            crate::kprintln!("{}", line);
+        }
+    }
+    crate::kprintln!("Call trace:");
+    for frame in &dump.backtrace {
+        crate::kprintln!(" {}", frame);
+    }
+
+    match &dump.threads {
+        Some(threads) => {
+            crate::kprintln!("Threads ({}):", threads.len());
+            for t in threads {
+                let state = match t.state {
+                    Some(state) => alloc::format!("{:?}", state),
+                    None => String::from("?"),
+                };
+                match t.running {
+                    Some(cpu) => crate::kprintln!("  tid={}, name='{}', state={} [CPU {}]", t.tid, t.name, state, cpu),
+                    None => crate::kprintln!("  tid={}, name='{}', state={} (rq {})", t.tid, t.name, state, t.cpu),
+                }
+            }
+        }
+        None => crate::kprintln!("Threads: (thread list was locked)"),
+    }
+
+    if let Some((base, bytes)) = &dump.stack {
+        crate::kprintln!("Stack ({} bytes from {:#x}):", bytes.len(), base);
+        for (i, chunk) in bytes.chunks(16).enumerate() {
+            let mut line = alloc::format!("  {:016x}:", base + (i * 16) as u64);
+            for word in chunk.chunks(8) {
+                let mut value = [0u8; 8];
+                value[..word.len()].copy_from_slice(word);
+                let _ = write!(line, " {:016x}", u64::from_le_bytes(value));
+            }
+            crate::kprintln!("{}", line);
+        }
+    }
+
+    match &dump.log {
+        Some(log) => {
+            // dmesg처럼 콘솔로 바로 (지금 로그 버퍼에 이전 부팅의 로그를 다시 쌓지 않게)
+            crate::kprintln!("Log ({} lines):", log.lines().count());
+            crate::console::write(log.as_bytes());
+        }
+        None => crate::kprintln!("Log: (log buffer was locked)"),
+    }
+}
@@ -0,0 +1,281 @@
+//! 결함 주입 (`failinject` 명령)
+//!
+//! 페이지 프레임 할당, 힙 할당, 블록 읽기를 정해진 규칙으로 실패시켜 FAT32, 모듈 로더,
+//! VFS의 오류 처리 경로를 실제로 지나가게 합니다 (Linux `fail_page_alloc`/`failslab`/
+//! `fail_make_request`와 비슷한 구성).
+//!
+//! | 지점 | 위치 | 실패 모양 |
+//! |------|------|----------|
+//! | `frame` | `mm::page::alloc_frame`, `alloc_frames`, `alloc_frames_order` | `None` |
+//! | `heap` | 전역 할당자 (`mm::heap`) | null |
+//! | `block` | `RamDisk`/`VirtIOBlock`의 `read_block`, `read_blocks` | `BlockError::IoError` |
+//!
+//! 지점마다 규칙을 따로 둡니다. 조건을 모두 만족하는 호출만 실패합니다.
+//! - `every <n>`: 대상 호출 n번째마다 (기본 1 = 매번)
+//! - `prob <퍼센트>`: 그 중 확률로 (기본 100)
+//! - `times <n>`: 최대 실패 횟수 (기본 무제한, 다 쓰면 더 실패하지 않음)
+//! - `tid <n>`: 이 스레드의 호출만 (기본 모든 스레드, 인터럽트 핸들러 포함)
+//!
+//! 힙 할당 실패는 `Box`/`Vec` 같은 컬렉션에서 `handle_alloc_error` 패닉이 되므로, `heap`은
+//! null을 직접 확인하는 코드(모듈의 `kernel_alloc`, `alloc_zeroed` 호출부)를 노리는 스레드로
+//! `tid`를 좁혀 씁니다.
+//!
+//! 할당자 안에서 불리므로 판정은 원자 변수만 쓰고 힙과 락을 쓰지 않습니다. 꺼진 지점은 원자
+//! 변수 하나만 읽습니다.
+
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+
+use crate::kprintln;
+use crate::proc::{self, Tid};
+
+/// 결함 주입 지점
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Point {
+    /// 페이지 프레임 할당
+    Frame,
+    /// 힙 할당
+    Heap,
+    /// 블록 디바이스 읽기
+    BlockRead,
+}
+
+/// 모든 지점 (`ATTRS` 순서)
+pub const POINTS: [Point; 3] = [Point::Frame, Point::Heap, Point::BlockRead];
+
+impl Point {
+    /// 셸 이름
+    pub fn name(self) -> &'static str {
+        match self {
+            Point::Frame => "frame",
+            Point::Heap => "heap",
+            Point::BlockRead => "block",
+        }
+    }
+
+    /// 셸 이름으로 찾기
+    pub fn from_name(name: &str) -> Option<Self> {
+        POINTS.iter().copied().find(|p| p.name() == name)
+    }
+
+    fn attr(self) -> &'static Attr {
+        &ATTRS[self as usize]
+    }
+}
+
+/// 실패 규칙
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// 대상 호출 n번째마다 (0, 1 = 매번)
+    pub interval: usize,
+    /// 실패 확률 (퍼센트, 100 이상 = 항상)
+    pub probability: u32,
+    /// 최대 실패 횟수 (None = 무제한)
+    pub times: Option<usize>,
+    /// 이 스레드의 호출만 (None = 모든 호출)
+    pub tid: Option<Tid>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            interval: 1,
+            probability: 100,
+            times: None,
+            tid: None,
+        }
+    }
+}
+
+/// 지점 통계
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// 규칙을 확인한 호출 수 (`tid`가 다른 호출 제외)
+    pub calls: usize,
+    /// 실패시킨 호출 수
+    pub injected: usize,
+}
+
+/// `times` 무제한
+const UNLIMITED: usize = usize::MAX;
+
+/// `tid` 없음
+const ANY_THREAD: u64 = u64::MAX;
+
+/// 지점 하나의 규칙과 통계
+struct Attr {
+    enabled: AtomicBool,
+    interval: AtomicUsize,
+    probability: AtomicU32,
+    /// 남은 실패 횟수
+    times: AtomicUsize,
+    tid: AtomicU64,
+    calls: AtomicUsize,
+    injected: AtomicUsize,
+}
+
+impl Attr {
+    const fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            interval: AtomicUsize::new(1),
+            probability: AtomicU32::new(100),
+            times: AtomicUsize::new(UNLIMITED),
+            tid: AtomicU64::new(ANY_THREAD),
+            calls: AtomicUsize::new(0),
+            injected: AtomicUsize::new(0),
+        }
+    }
+}
+
+static ATTRS: [Attr; 3] = [Attr::new(), Attr::new(), Attr::new()];
+
+/// 확률 판정용 xorshift64 상태
+static RANDOM: AtomicU64 = AtomicU64::new(0x9E37_79B9_7F4A_7C15);
+
+/// 이번 호출을 실패시킬지 판정
+///
+/// 주입 지점에서 호출합니다. true를 받으면 해당 지점의 실패 값을 돌려줍니다.
+#[inline]
+pub fn should_fail(point: Point) -> bool {
+    let attr = point.attr();
+    if !attr.enabled.load(Ordering::Acquire) {
+        return false;
+    }
+    should_fail_slow(attr)
+}
+
+#[inline(never)]
+fn should_fail_slow(attr: &Attr) -> bool {
+    let tid = attr.tid.load(Ordering::Relaxed);
+    if tid != ANY_THREAD && proc::current_tid() != Some(tid) {
+        return false;
+    }
+
+    let call = attr.calls.fetch_add(1, Ordering::Relaxed) + 1;
+    let interval = attr.interval.load(Ordering::Relaxed).max(1);
+    if call % interval != 0 {
+        return false;
+    }
+    let probability = attr.probability.load(Ordering::Relaxed);
+    if probability < 100 && next_random() % 100 >= probability as u64 {
+        return false;
+    }
+
+    // 남은 횟수를 먼저 줄여 여러 CPU가 동시에 와도 times를 넘지 않음
+    let taken = attr.times.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| match left {
+        0 => None,
+        UNLIMITED => Some(UNLIMITED),
+        n => Some(n - 1),
+    });
+    if taken.is_err() {
+        return false;
+    }
+    attr.injected.fetch_add(1, Ordering::Relaxed);
+    true
+}
+
+fn next_random() -> u64 {
+    let mut x = RANDOM.load(Ordering::Relaxed);
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    // 경쟁으로 같은 값을 두 번 써도 판정에는 문제없음
+    RANDOM.store(x, Ordering::Relaxed);
+    x
+}
+
+/// 확률 판정 시드 (같은 시드면 같은 순서로 실패)
+pub fn set_seed(seed: u64) {
+    // xorshift는 0에서 멈춤
+    RANDOM.store(if seed == 0 { 1 } else { seed }, Ordering::Relaxed);
+}
+
+/// 지점에 규칙을 정하고 켬 (통계는 0부터 다시)
+pub fn configure(point: Point, config: Config) {
+    let attr = point.attr();
+    attr.enabled.store(false, Ordering::Release);
+    attr.interval.store(config.interval.max(1), Ordering::Relaxed);
+    attr.probability.store(config.probability.min(100), Ordering::Relaxed);
+    attr.times.store(config.times.unwrap_or(UNLIMITED), Ordering::Relaxed);
+    attr.tid.store(config.tid.unwrap_or(ANY_THREAD), Ordering::Relaxed);
+    attr.calls.store(0, Ordering::Relaxed);
+    attr.injected.store(0, Ordering::Relaxed);
+    attr.enabled.store(true, Ordering::Release);
+}
+
+/// 지점 끄기 (통계는 남김)
+pub fn disable(point: Point) {
+    point.attr().enabled.store(false, Ordering::Release);
+}
+
+/// 모든 지점 끄기
+pub fn disable_all() {
+    for point in POINTS {
+        disable(point);
+    }
+}
+
+/// 지점이 켜져 있는지
+pub fn is_enabled(point: Point) -> bool {
+    point.attr().enabled.load(Ordering::Acquire)
+}
+
+/// 지점의 현재 규칙 (남은 실패 횟수 기준)
+pub fn config(point: Point) -> Config {
+    let attr = point.attr();
+    let times = attr.times.load(Ordering::Relaxed);
+    let tid = attr.tid.load(Ordering::Relaxed);
+    Config {
+        interval: attr.interval.load(Ordering::Relaxed),
+        probability: attr.probability.load(Ordering::Relaxed),
+        times: (times != UNLIMITED).then_some(times),
+        tid: (tid != ANY_THREAD).then_some(tid),
+    }
+}
+
+/// 지점 통계
+pub fn stats(point: Point) -> Stats {
+    let attr = point.attr();
+    Stats {
+        calls: attr.calls.load(Ordering::Relaxed),
+        injected: attr.injected.load(Ordering::Relaxed),
+    }
+}
+
+/// `failinject` 출력
+pub fn report() {
+    kprintln!(
+        "  {:<6} {:<4} {:>6} {:>5} {:>10} {:>6} {:>10} {:>10}",
+        "POINT",
+        "ON",
+        "EVERY",
+        "PROB",
+        "TIMES LEFT",
+        "TID",
+        "CALLS",
+        "INJECTED"
+    );
+    for point in POINTS {
+        let config = config(point);
+        let stats = stats(point);
+        let times = match config.times {
+            Some(n) => alloc::format!("{}", n),
+            None => alloc::string::String::from("-"),
+        };
+        let tid = match config.tid {
+            Some(tid) => alloc::format!("{}", tid),
+            None => alloc::string::String::from("any"),
+        };
+        kprintln!(
+            "  {:<6} {:<4} {:>6} {:>4}% {:>10} {:>6} {:>10} {:>10}",
+            point.name(),
+            if is_enabled(point) { "yes" } else { "no" },
+            config.interval,
+            config.probability,
+            times,
+            tid,
+            stats.calls,
+            stats.injected
+        );
+    }
+}
@@ -0,0 +1,749 @@
+//! GDB 원격 스텁 (`gdb` 명령, `gdb=` bootargs)
+//!
+//! 보조 UART(`ttyS<n>`)나 VirtIO 콘솔(`hvc0`)에서 GDB 원격 시리얼 프로토콜(RSP)을 처리해,
+//! QEMU를 `-s -S`로 다시 띄우지 않고도 실행 중인 커널을 GDB로 멈춰 봅니다 (Linux KGDB와
+//! 비슷한 구성). 아키텍처별 레지스터 배치, 브레이크포인트 명령어, 단일 스텝은 `arch::gdb`가
+//! 맡습니다.
+//!
+//! - 들어오는 길: 셸 `gdb break`(`arch::gdb::breakpoint()`), GDB가 심은 브레이크포인트 적중,
+//!   단일 스텝 완료, 포트를 정해 둔 상태의 패닉 (`enter_panic`)
+//! - 멈춘 동안: 들어온 CPU는 인터럽트를 끈 채 포트를 폴링하고, 다른 온라인 CPU는 reschedule
+//!   IPI를 받아 IPI 핸들러 안에서 세션이 끝날 때까지 돕니다 (`hold_this_cpu`).
+//! - 패킷: `?`, `g`/`G`, `p`/`P`, `m`/`M`, `c`, `s`, `Z0`/`z0`, `D`, `k`, `H`, `T`,
+//!   `qSupported`, `qXfer:features:read:target.xml`, `qAttached`, `qC`, `qfThreadInfo`.
+//!   스레드는 하나(멈춘 CPU)로 보이고 그 밖의 패킷은 빈 응답(미지원)입니다.
+//!
+//! 멈춘 CPU는 예외 컨텍스트이고 다른 CPU도 멈춰 있으므로, 그 CPU들이 잡고 있던 잠들지 않는
+//! 락(힙, 페이지 테이블)을 기다리면 세션이 멈출 수 있습니다.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Write;
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+
+use crate::arch::gdb::{self, Regs};
+use crate::drivers::serial;
+use crate::proc::percpu;
+
+/// 스텁이 쓰는 포트
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Port {
+    /// `ttyS<n>` (`drivers::serial` 포트 번호)
+    Serial(usize),
+    /// VirtIO 콘솔 `hvc0`
+    Hvc,
+}
+
+impl Port {
+    /// `ttyS<n>`, `ttyAMA<n>`, `hvc0`
+    pub fn parse(name: &str) -> Option<Self> {
+        if name == "hvc0" {
+            return Some(Port::Hvc);
+        }
+        serial::parse_port_name(name).map(Port::Serial)
+    }
+
+    /// 스텁이 쓸 수 있는 포트인지 (콘솔과 같은 포트는 거부)
+    fn check(self) -> Result<(), &'static str> {
+        match self {
+            Port::Serial(index) => {
+                if serial::port(index).is_none() {
+                    return Err("no such serial port");
+                }
+                if index == serial::console_index() && crate::console::is_enabled("uart") {
+                    return Err("port is the console");
+                }
+            }
+            Port::Hvc => {
+                if !crate::virtio::console::is_present() {
+                    return Err("no virtio console");
+                }
+                if crate::console::is_enabled("hvc0") {
+                    return Err("port is the console");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn getc(self) -> Option<u8> {
+        match self {
+            Port::Serial(index) => serial::getc(index),
+            Port::Hvc => crate::virtio::console::getc(),
+        }
+    }
+
+    fn write(self, bytes: &[u8]) {
+        match self {
+            Port::Serial(index) => serial::write(index, bytes),
+            Port::Hvc => {
+                crate::virtio::console::write(bytes);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Port {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Port::Serial(index) => write!(f, "ttyS{}", index),
+            Port::Hvc => write!(f, "hvc0"),
+        }
+    }
+}
+
+/// `PORT` 값: 꺼짐 / hvc0 (그 밖은 시리얼 포트 번호)
+const NO_PORT: usize = usize::MAX;
+const HVC_PORT: usize = usize::MAX - 1;
+
+/// `MASTER` 값: 세션 없음
+const NO_CPU: u32 = u32::MAX;
+
+/// 동시에 심을 수 있는 브레이크포인트 수
+pub const MAX_BREAKPOINTS: usize = 16;
+
+/// 받을 수 있는 패킷 크기 (`qSupported`의 `PacketSize`)
+const MAX_PACKET: usize = 4096;
+
+/// 정지 응답 (SIGTRAP)
+const STOP_REPLY: &[u8] = b"S05";
+
+/// 보낸 패킷의 응답(`+`/`-`) 대기 시간
+const ACK_TIMEOUT_NS: u64 = 1_000_000_000;
+
+/// 스텁 포트
+static PORT: AtomicUsize = AtomicUsize::new(NO_PORT);
+/// 세션 중인 CPU
+static MASTER: AtomicU32 = AtomicU32::new(NO_CPU);
+/// 세션 동안 IPI 핸들러에서 대기 중인 CPU 수
+static HELD: AtomicU32 = AtomicU32::new(0);
+/// GDB가 붙어 있음 (`c`/`s` 뒤 정지 응답을 기다림)
+static ATTACHED: AtomicBool = AtomicBool::new(false);
+/// 멈춘 횟수
+static STOPS: AtomicUsize = AtomicUsize::new(0);
+
+/// GDB가 심은 브레이크포인트 (주소 0 = 빈 칸)
+struct Breakpoint {
+    addr: AtomicUsize,
+    orig: AtomicU32,
+}
+
+static BREAKPOINTS: [Breakpoint; MAX_BREAKPOINTS] = [const {
+    Breakpoint {
+        addr: AtomicUsize::new(0),
+        orig: AtomicU32::new(0),
+    }
+}; MAX_BREAKPOINTS];
+
+/// 스텁 포트 (None = 꺼짐)
+pub fn port() -> Option<Port> {
+    match PORT.load(Ordering::Acquire) {
+        NO_PORT => None,
+        HVC_PORT => Some(Port::Hvc),
+        index => Some(Port::Serial(index)),
+    }
+}
+
+/// 스텁 포트 설정 (None = 끄기, 심어 둔 브레이크포인트도 모두 제거)
+pub fn set_port(port: Option<Port>) -> Result<(), &'static str> {
+    let value = match port {
+        None => {
+            remove_all_breakpoints();
+            ATTACHED.store(false, Ordering::Relaxed);
+            NO_PORT
+        }
+        Some(port) => {
+            port.check()?;
+            match port {
+                Port::Serial(index) => index,
+                Port::Hvc => HVC_PORT,
+            }
+        }
+    };
+    PORT.store(value, Ordering::Release);
+    Ok(())
+}
+
+/// bootargs의 `gdb=<ttyS<n>|hvc0>` 처리 (시리얼 포트와 VirtIO 콘솔 초기화 후 호출)
+pub fn init_from_cmdline() {
+    let Some(name) = crate::boot::cmdline::gdb() else {
+        return;
+    };
+    match Port::parse(&name).ok_or("unknown port").and_then(|port| set_port(Some(port))) {
+        Ok(()) => crate::kprintln!("[gdb] stub on {}", name),
+        Err(e) => crate::kprintln!("[gdb] gdb={}: {}", name, e),
+    }
+}
+
+/// 셸 `gdb break`: 이 CPU를 멈추고 GDB를 기다림
+pub fn breakpoint() -> Result<(), &'static str> {
+    let port = port().ok_or("gdb stub disabled (gdb port <ttyS<n>|hvc0>)")?;
+    if !ATTACHED.load(Ordering::Relaxed) {
+        crate::kprintln!("[gdb] waiting for GDB on {}", port);
+    }
+    gdb::breakpoint();
+    Ok(())
+}
+
+/// 브레이크포인트/스텝 예외 처리 (아키텍처 예외 핸들러에서 호출)
+///
+/// 스텁의 브레이크포인트, `breakpoint()`, 스텁의 단일 스텝이면 세션을 연 뒤 true
+pub fn handle_trap(regs: &mut Regs) -> bool {
+    if !gdb::end_step(regs) {
+        if gdb::is_compiled_break(regs) {
+            gdb::skip_compiled_break(regs);
+        } else if find_breakpoint(gdb::pc(regs)).is_none() {
+            return false;
+        }
+    }
+    enter(regs);
+    true
+}
+
+/// aarch64 Software Step 예외 처리: 스텁의 단일 스텝이면 세션을 연 뒤 true
+#[cfg(target_arch = "aarch64")]
+pub fn handle_step(regs: &mut Regs) -> bool {
+    if !gdb::end_step(regs) {
+        return false;
+    }
+    enter(regs);
+    true
+}
+
+fn enter(regs: &mut Regs) {
+    // 포트를 끈 뒤에 실행된 `breakpoint()`는 그냥 지나감
+    let Some(port) = port() else {
+        return;
+    };
+    let sp = gdb::frame_sp(regs);
+    session(port, &mut Frame { regs, sp }, false);
+}
+
+/// 패닉 처리 중 호출: 포트가 있으면 GDB가 떨어질 때까지(`D`/`k`) 디버거에서 대기
+///
+/// 패닉 뒤에는 실행을 이어갈 수 없으므로 `c`/`s`에는 바로 다시 정지 응답을 보냅니다.
+#[inline(never)]
+pub fn enter_panic() {
+    let Some(port) = port() else {
+        return;
+    };
+    let mut regs = gdb::zeroed_regs();
+    let sp = gdb::capture(&mut regs);
+    crate::console::kprintln(format_args!("[gdb] panic: waiting for GDB on {} (detach to continue)", port));
+    session(port, &mut Frame { regs: &mut regs, sp }, true);
+}
+
+/// 멈춘 CPU의 레지스터
+pub struct Frame<'a> {
+    pub regs: &'a mut Regs,
+    /// 멈추기 직전의 sp (예외 프레임에 없으므로 따로 둠)
+    pub sp: u64,
+}
+
+/// 패킷 처리 뒤 할 일
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resume {
+    /// 다음 패킷을 기다림
+    Stay,
+    /// 실행 계속
+    Continue,
+    /// 명령어 하나 실행 후 다시 정지
+    Step,
+    /// GDB가 떨어짐 (브레이크포인트 제거 후 실행 계속)
+    Detach,
+}
+
+/// 세션: 다른 CPU를 멈추고 `c`/`s`/`D`/`k`가 올 때까지 패킷 처리
+fn session(port: Port, frame: &mut Frame, panicked: bool) {
+    let cpu = percpu::get_cpu_id();
+    // 다른 CPU가 세션 중이면 끝날 때까지 기다렸다가 이어서 멈춤
+    while MASTER.compare_exchange(NO_CPU, cpu, Ordering::AcqRel, Ordering::Acquire).is_err() {
+        core::hint::spin_loop();
+    }
+    STOPS.fetch_add(1, Ordering::Relaxed);
+    round_up_cpus(cpu);
+
+    if ATTACHED.load(Ordering::Relaxed) {
+        send_packet(port, STOP_REPLY);
+    }
+
+    let mut reply = Vec::new();
+    loop {
+        let packet = recv_packet(port);
+        ATTACHED.store(true, Ordering::Relaxed);
+        reply.clear();
+        let resume = handle_packet(frame, &packet, &mut reply);
+        if packet.first() != Some(&b'k') && !matches!(resume, Resume::Continue | Resume::Step) {
+            send_packet(port, &reply);
+        }
+        match resume {
+            Resume::Stay => {}
+            Resume::Continue | Resume::Step if panicked => send_packet(port, STOP_REPLY),
+            Resume::Continue => break,
+            Resume::Step => {
+                gdb::begin_step(frame.regs, frame.sp);
+                break;
+            }
+            Resume::Detach => {
+                ATTACHED.store(false, Ordering::Relaxed);
+                break;
+            }
+        }
+    }
+
+    MASTER.store(NO_CPU, Ordering::Release);
+}
+
+/// 다른 온라인 CPU를 IPI로 불러 세우고 최대 ~100ms 기다림
+fn round_up_cpus(me: u32) {
+    let mut expected = 0;
+    for cpu in percpu::online_mask().iter().filter(|&cpu| cpu != me) {
+        expected += 1;
+
+        #[cfg(target_arch = "aarch64")]
+        crate::arch::gic::send_reschedule_ipi(cpu);
+
+        #[cfg(target_arch = "riscv64")]
+        crate::arch::plic::send_reschedule_ipi(cpu);
+    }
+    for _ in 0..1000u32 {
+        if HELD.load(Ordering::Acquire) >= expected {
+            break;
+        }
+        for _ in 0..10000u32 {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// 다른 CPU가 세션 중이라 이 CPU가 멈춰야 하는지 (IPI 핸들러에서 확인)
+pub fn hold_requested() -> bool {
+    let master = MASTER.load(Ordering::Acquire);
+    master != NO_CPU && master != percpu::get_cpu_id()
+}
+
+/// 세션이 끝날 때까지 이 CPU를 붙잡음 (인터럽트가 꺼진 IPI 핸들러 안)
+pub fn hold_this_cpu() {
+    let master = MASTER.load(Ordering::Acquire);
+    HELD.fetch_add(1, Ordering::AcqRel);
+    while MASTER.load(Ordering::Acquire) == master {
+        core::hint::spin_loop();
+    }
+    HELD.fetch_sub(1, Ordering::AcqRel);
+}
+
+// ============================================================================
+// 패킷
+// ============================================================================
+
+/// RSP 체크섬 (바이트 합의 하위 8비트)
+pub fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |sum, &b| sum.wrapping_add(b))
+}
+
+/// `$<data>#<체크섬>`
+pub fn encode_packet(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + 4);
+    out.push(b'$');
+    out.extend_from_slice(data);
+    out.push(b'#');
+    push_hex_byte(&mut out, checksum(data));
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReadState {
+    /// `$`를 기다림 (그 밖의 바이트는 버림)
+    Idle,
+    Data,
+    /// 체크섬 두 자리 중 받은 자리 수
+    Checksum(u8),
+}
+
+/// 바이트 단위로 패킷을 조립
+pub struct PacketReader {
+    state: ReadState,
+    data: Vec<u8>,
+    expected: u8,
+}
+
+impl Default for PacketReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PacketReader {
+    pub const fn new() -> Self {
+        Self {
+            state: ReadState::Idle,
+            data: Vec::new(),
+            expected: 0,
+        }
+    }
+
+    /// 바이트 하나 입력, 패킷이 끝나면 `Some(Ok(데이터))`, 체크섬이 틀리거나 너무 길면 `Some(Err)`
+    pub fn feed(&mut self, byte: u8) -> Option<Result<Vec<u8>, ()>> {
+        match self.state {
+            ReadState::Idle => {
+                if byte == b'$' {
+                    self.data.clear();
+                    self.state = ReadState::Data;
+                }
+            }
+            ReadState::Data => match byte {
+                b'#' => {
+                    self.expected = 0;
+                    self.state = ReadState::Checksum(0);
+                }
+                // 앞 패킷이 끊긴 채 새 패킷 시작
+                b'$' => self.data.clear(),
+                _ if self.data.len() >= MAX_PACKET => {
+                    self.state = ReadState::Idle;
+                    return Some(Err(()));
+                }
+                _ => self.data.push(byte),
+            },
+            ReadState::Checksum(digits) => {
+                let Some(nibble) = hex_value(byte) else {
+                    self.state = ReadState::Idle;
+                    return Some(Err(()));
+                };
+                self.expected = self.expected << 4 | nibble;
+                if digits == 0 {
+                    self.state = ReadState::Checksum(1);
+                } else {
+                    self.state = ReadState::Idle;
+                    let data = core::mem::take(&mut self.data);
+                    return Some(if checksum(&data) == self.expected { Ok(data) } else { Err(()) });
+                }
+            }
+        }
+        None
+    }
+}
+
+/// 패킷 하나 받기 (체크섬이 맞으면 `+`, 틀리면 `-`로 다시 요청)
+fn recv_packet(port: Port) -> Vec<u8> {
+    let mut reader = PacketReader::new();
+    loop {
+        let Some(byte) = port.getc() else {
+            core::hint::spin_loop();
+            continue;
+        };
+        match reader.feed(byte) {
+            Some(Ok(packet)) => {
+                port.write(b"+");
+                return packet;
+            }
+            Some(Err(())) => port.write(b"-"),
+            None => {}
+        }
+    }
+}
+
+/// 패킷 보내기 (`-`면 다시 보내고, GDB가 응답하지 않으면 제한 시간 뒤 포기)
+fn send_packet(port: Port, data: &[u8]) {
+    let packet = encode_packet(data);
+    'retry: for _ in 0..3 {
+        port.write(&packet);
+        let start = crate::time::clock::monotonic_ns();
+        while crate::time::clock::monotonic_ns() - start < ACK_TIMEOUT_NS {
+            match port.getc() {
+                Some(b'+') => return,
+                Some(b'-') => continue 'retry,
+                _ => core::hint::spin_loop(),
+            }
+        }
+        return;
+    }
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|d| d as u8)
+}
+
+fn push_hex_byte(out: &mut Vec<u8>, byte: u8) {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    out.push(DIGITS[(byte >> 4) as usize]);
+    out.push(DIGITS[(byte & 0xF) as usize]);
+}
+
+/// 16진수 정수 (빅엔디언 표기)
+fn parse_hex(s: &[u8]) -> Option<usize> {
+    if s.is_empty() || s.len() > 16 {
+        return None;
+    }
+    s.iter().try_fold(0usize, |acc, &b| Some(acc << 4 | hex_value(b)? as usize))
+}
+
+/// 16진수 바이트열
+fn parse_hex_bytes(s: &[u8]) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    s.chunks(2).map(|pair| Some(hex_value(pair[0])? << 4 | hex_value(pair[1])?)).collect()
+}
+
+/// `addr,len`
+fn parse_addr_len(s: &[u8]) -> Option<(usize, usize)> {
+    let comma = s.iter().position(|&b| b == b',')?;
+    Some((parse_hex(&s[..comma])?, parse_hex(&s[comma + 1..])?))
+}
+
+/// 레지스터 값을 대상 바이트 순서(리틀 엔디언)로
+fn push_reg(out: &mut Vec<u8>, value: u64, size: usize) {
+    for byte in &value.to_le_bytes()[..size] {
+        push_hex_byte(out, *byte);
+    }
+}
+
+/// 리틀 엔디언 바이트열을 레지스터 값으로
+fn reg_from_le(bytes: &[u8]) -> u64 {
+    bytes.iter().rev().fold(0u64, |acc, &b| acc << 8 | b as u64)
+}
+
+/// 패킷 하나 처리 (`reply`에 응답 데이터, 빈 응답 = 미지원)
+pub fn handle_packet(frame: &mut Frame, packet: &[u8], reply: &mut Vec<u8>) -> Resume {
+    let Some((&cmd, args)) = packet.split_first() else {
+        return Resume::Stay;
+    };
+    match cmd {
+        b'?' => reply.extend_from_slice(STOP_REPLY),
+        b'g' => {
+            for n in 0..gdb::NUM_REGS {
+                let size = gdb::reg_size(n).unwrap_or(8);
+                push_reg(reply, gdb::read_reg(frame.regs, frame.sp, n).unwrap_or(0), size);
+            }
+        }
+        b'G' => {
+            let Some(bytes) = parse_hex_bytes(args) else {
+                push_result(reply, false);
+                return Resume::Stay;
+            };
+            let mut offset = 0;
+            for n in 0..gdb::NUM_REGS {
+                let size = gdb::reg_size(n).unwrap_or(8);
+                let Some(value) = bytes.get(offset..offset + size) else {
+                    break;
+                };
+                // sp처럼 바꿀 수 없는 레지스터는 건너뜀
+                gdb::write_reg(frame.regs, n, reg_from_le(value));
+                offset += size;
+            }
+            push_result(reply, true);
+        }
+        b'p' => {
+            let reg = parse_hex(args).and_then(|n| Some((gdb::read_reg(frame.regs, frame.sp, n)?, gdb::reg_size(n)?)));
+            match reg {
+                Some((value, size)) => push_reg(reply, value, size),
+                None => push_result(reply, false),
+            }
+        }
+        b'P' => {
+            let result = args.iter().position(|&b| b == b'=').and_then(|eq| {
+                let n = parse_hex(&args[..eq])?;
+                let bytes = parse_hex_bytes(&args[eq + 1..])?;
+                (bytes.len() == gdb::reg_size(n)?).then(|| gdb::write_reg(frame.regs, n, reg_from_le(&bytes)))
+            });
+            push_result(reply, result == Some(true));
+        }
+        b'm' => match parse_addr_len(args) {
+            Some((addr, len)) => {
+                let len = len.min(MAX_PACKET / 2);
+                if is_accessible(addr, len) {
+                    for i in 0..len {
+                        // Safety: 매핑된 페이지만 읽음
+                        push_hex_byte(reply, unsafe { core::ptr::read_volatile((addr + i) as *const u8) });
+                    }
+                } else {
+                    reply.extend_from_slice(b"E14");
+                }
+            }
+            None => push_result(reply, false),
+        },
+        b'M' => {
+            let parsed = args.iter().position(|&b| b == b':').and_then(|colon| {
+                let (addr, len) = parse_addr_len(&args[..colon])?;
+                let bytes = parse_hex_bytes(&args[colon + 1..])?;
+                (bytes.len() == len).then_some((addr, bytes))
+            });
+            match parsed {
+                Some((addr, bytes)) if is_accessible(addr, bytes.len()) => {
+                    write_memory(addr, &bytes);
+                    push_result(reply, true);
+                }
+                Some(_) => reply.extend_from_slice(b"E14"),
+                None => push_result(reply, false),
+            }
+        }
+        b'c' | b's' => {
+            if !args.is_empty() {
+                match parse_hex(args) {
+                    Some(addr) => gdb::set_pc(frame.regs, addr),
+                    None => {
+                        push_result(reply, false);
+                        return Resume::Stay;
+                    }
+                }
+            }
+            return if cmd == b'c' { Resume::Continue } else { Resume::Step };
+        }
+        b'Z' | b'z' => {
+            // 소프트웨어 브레이크포인트(0)만 지원: `Z0,addr,kind`
+            let mut fields = args.split(|&b| b == b',');
+            if fields.next() != Some(b"0".as_slice()) {
+                return Resume::Stay;
+            }
+            let result = match fields.next().and_then(parse_hex) {
+                Some(addr) if cmd == b'Z' => insert_breakpoint(addr).is_ok(),
+                Some(addr) => remove_breakpoint(addr).is_ok(),
+                None => false,
+            };
+            push_result(reply, result);
+        }
+        b'D' => {
+            remove_all_breakpoints();
+            push_result(reply, true);
+            return Resume::Detach;
+        }
+        b'k' => {
+            remove_all_breakpoints();
+            return Resume::Detach;
+        }
+        b'H' | b'T' => push_result(reply, true),
+        b'q' => handle_query(args, reply),
+        _ => {}
+    }
+    Resume::Stay
+}
+
+/// `OK` 또는 `E01`
+fn push_result(reply: &mut Vec<u8>, ok: bool) {
+    reply.extend_from_slice(if ok { b"OK" } else { b"E01" });
+}
+
+/// `q` 패킷
+fn handle_query(args: &[u8], reply: &mut Vec<u8>) {
+    if args.starts_with(b"Supported") {
+        let _ = write!(VecWriter(reply), "PacketSize={:x};qXfer:features:read+", MAX_PACKET);
+    } else if let Some(range) = args.strip_prefix(b"Xfer:features:read:target.xml:") {
+        let Some((offset, len)) = parse_addr_len(range) else {
+            reply.extend_from_slice(b"E01");
+            return;
+        };
+        let xml = gdb::target_xml();
+        let xml = xml.as_bytes();
+        let start = offset.min(xml.len());
+        let end = start.saturating_add(len.min(MAX_PACKET - 1)).min(xml.len());
+        reply.push(if end == xml.len() { b'l' } else { b'm' });
+        reply.extend_from_slice(&xml[start..end]);
+    } else if args == b"Attached" {
+        reply.push(b'1');
+    } else if args == b"C" {
+        reply.extend_from_slice(b"QC1");
+    } else if args == b"fThreadInfo" {
+        reply.extend_from_slice(b"m1");
+    } else if args == b"sThreadInfo" {
+        reply.push(b'l');
+    }
+}
+
+/// `write!`로 `Vec<u8>`에 쓰기
+struct VecWriter<'a>(&'a mut Vec<u8>);
+
+impl fmt::Write for VecWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// `[addr, addr + len)`의 모든 페이지가 매핑되어 있는지
+fn is_accessible(addr: usize, len: usize) -> bool {
+    let Some(end) = addr.checked_add(len) else {
+        return false;
+    };
+    (addr & !0xFFF..end).step_by(4096).all(gdb::is_mapped)
+}
+
+/// 메모리 쓰기 (읽기 전용 `.text`도 잠깐 쓰기 가능하게 바꿈)
+fn write_memory(addr: usize, bytes: &[u8]) {
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let at = addr + offset;
+        let chunk = (4096 - at % 4096).min(bytes.len() - offset);
+        crate::arch::mmu::with_text_writable(at, chunk, || {
+            for (i, &b) in bytes[offset..offset + chunk].iter().enumerate() {
+                // Safety: 매핑을 확인한 주소
+                unsafe { core::ptr::write_volatile((at + i) as *mut u8, b) };
+            }
+        });
+        offset += chunk;
+    }
+}
+
+// ============================================================================
+// 브레이크포인트
+// ============================================================================
+
+fn find_breakpoint(addr: usize) -> Option<&'static Breakpoint> {
+    if addr == 0 {
+        return None;
+    }
+    BREAKPOINTS.iter().find(|bp| bp.addr.load(Ordering::Acquire) == addr)
+}
+
+/// 브레이크포인트 심기 (이미 있으면 그대로)
+pub fn insert_breakpoint(addr: usize) -> Result<(), &'static str> {
+    if find_breakpoint(addr).is_some() {
+        return Ok(());
+    }
+    gdb::check_break(addr)?;
+    if !is_accessible(addr, 4) {
+        return Err("address not mapped");
+    }
+    let slot = BREAKPOINTS
+        .iter()
+        .find(|bp| bp.addr.compare_exchange(0, addr, Ordering::AcqRel, Ordering::Acquire).is_ok())
+        .ok_or("too many breakpoints")?;
+    // 주소를 먼저 올려 두어 심자마자 다른 CPU가 적중해도 스텁 것으로 알아봄
+    slot.orig.store(gdb::arm(addr), Ordering::Release);
+    Ok(())
+}
+
+/// 브레이크포인트 제거 (원래 명령어 복원)
+pub fn remove_breakpoint(addr: usize) -> Result<(), &'static str> {
+    let bp = find_breakpoint(addr).ok_or("no breakpoint")?;
+    gdb::disarm(addr, bp.orig.load(Ordering::Acquire));
+    bp.addr.store(0, Ordering::Release);
+    Ok(())
+}
+
+/// 모든 브레이크포인트 제거
+pub fn remove_all_breakpoints() {
+    for addr in breakpoints() {
+        let _ = remove_breakpoint(addr);
+    }
+}
+
+/// 심어 둔 브레이크포인트 주소
+pub fn breakpoints() -> Vec<usize> {
+    BREAKPOINTS.iter().map(|bp| bp.addr.load(Ordering::Acquire)).filter(|&addr| addr != 0).collect()
+}
+
+/// `gdb` 출력
+pub fn report() {
+    match port() {
+        Some(port) => crate::kprintln!(
+            "  port: {}, gdb {}, stops: {}",
+            port,
+            if ATTACHED.load(Ordering::Relaxed) { "attached" } else { "not attached" },
+            STOPS.load(Ordering::Relaxed)
+        ),
+        None => crate::kprintln!("  port: none (gdb port <ttyS<n>|hvc0>)"),
+    }
+    for addr in breakpoints() {
+        crate::kprintln!("  breakpoint {:#x}", addr);
+    }
+}
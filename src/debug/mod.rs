@@ -0,0 +1,10 @@
+//! 커널 디버깅 도구
+//!
+//! 정상 실행에서는 드물게만 지나가는 경로를 일부러 지나가게 하는 도구들
+//! - failinject: 페이지/힙 할당, 블록 읽기를 정해진 규칙으로 실패시키는 결함 주입
+//! - gdbstub: 보조 시리얼 포트나 hvc0으로 GDB를 붙여 실행 중인 커널을 멈춰 보는 원격 스텁
+//! - crashdump: 패닉 때 레지스터, 스택, 스레드 목록, 최근 로그를 디스크에 남기고 재부팅 후 읽기
+
+pub mod crashdump;
+pub mod failinject;
+pub mod gdbstub;
@@ -4,11 +4,14 @@
 //! 드라이버들은 이 모듈을 통해 하드웨어 설정에 접근합니다.
 
 use crate::sync::RwLock;
+use alloc::vec::Vec;
 use core::sync::atomic::{AtomicBool, Ordering};
 
 /// UART 설정
 #[derive(Debug, Clone)]
 pub struct UartConfig {
+    /// UART 종류 (DTB compatible, 예: "arm,pl011")
+    pub compatible: &'static str,
     pub base: usize,
     pub size: usize,
     pub irq: u32,
@@ -21,6 +24,8 @@ pub struct GicConfig {
     pub distributor_base: usize,
     pub cpu_interface_base: usize,
     pub redistributor_base: Option<usize>, // GICv3용
+    pub redistributor_size: usize,         // GICv3용 (CPU마다 128KB 프레임)
+    pub its_base: Option<usize>,           // GICv3 ITS (LPI)
     pub version: GicVersion,
 }
 
@@ -73,7 +78,10 @@ pub enum InterruptControllerConfig {
 /// 플랫폼 전체 설정
 #[derive(Debug, Clone)]
 pub struct PlatformConfig {
+    /// 콘솔 UART (`uarts` 중 하나)
     pub uart: UartConfig,
+    /// 탐색된 모든 UART (인덱스가 `/dev/ttyS<n>`의 n)
+    pub uarts: Vec<UartConfig>,
     pub interrupt_controller: InterruptControllerConfig,
     pub timer: TimerConfig,
     pub clint: Option<ClintConfig>, // RISC-V only
@@ -85,33 +93,38 @@ static CONFIG_INITIALIZED: AtomicBool = AtomicBool::new(false);
 static PLATFORM_CONFIG: RwLock<Option<PlatformConfig>> = RwLock::new(None);
 
 /// 플랫폼 설정 초기화 (부팅 초기 1회)
+///
+/// 세부 내용은 debug 레벨로만 남기고, 부팅 요약은 `hwinfo`가 출력합니다.
 pub fn init_platform_config(config: PlatformConfig) {
     let mut guard = PLATFORM_CONFIG.write();
-    crate::kprintln!("[config] Initializing platform config:");
-    crate::kprintln!("  UART: base={:#x}, irq={}", config.uart.base, config.uart.irq);
+    crate::log_debug!(tag: "config", "Initializing platform config:");
+    crate::log_debug!(tag: "config", "  UART: base={:#x}, irq={}", config.uart.base, config.uart.irq);
+    crate::log_debug!(tag: "config", "  Serial ports: {}", config.uarts.len());
 
     match &config.interrupt_controller {
         InterruptControllerConfig::Gic(gic) => {
-            crate::kprintln!(
+            crate::log_debug!(
+                tag: "config",
                 "  GIC: GICD={:#x}, GICC={:#x}",
                 gic.distributor_base,
                 gic.cpu_interface_base
             );
         }
         InterruptControllerConfig::Plic(plic) => {
-            crate::kprintln!("  PLIC: base={:#x}, sources={}", plic.base, plic.num_sources);
+            crate::log_debug!(tag: "config", "  PLIC: base={:#x}, sources={}", plic.base, plic.num_sources);
         }
         InterruptControllerConfig::None => {
-            crate::kprintln!("  Interrupt Controller: None");
+            crate::log_debug!(tag: "config", "  Interrupt Controller: None");
         }
     }
 
-    crate::kprintln!(
+    crate::log_debug!(
+        tag: "config",
         "  Timer: type={:?}, freq={}Hz",
         config.timer.timer_type,
         config.timer.frequency
     );
-    crate::kprintln!("  CPUs: {}", config.cpu_count);
+    crate::log_debug!(tag: "config", "  CPUs: {}", config.cpu_count);
 
     *guard = Some(config);
     CONFIG_INITIALIZED.store(true, Ordering::Release);
@@ -136,6 +149,11 @@ pub fn uart_config() -> Option<UartConfig> {
     get_platform_config().map(|c| c.uart)
 }
 
+/// 모든 UART 설정 읽기 (`/dev/ttyS<n>` 순서)
+pub fn uarts() -> Vec<UartConfig> {
+    get_platform_config().map(|c| c.uarts).unwrap_or_default()
+}
+
 /// GIC 설정 읽기 (AArch64)
 #[cfg(target_arch = "aarch64")]
 pub fn gic_config() -> Option<GicConfig> {
@@ -4,6 +4,11 @@
 
 pub mod config;
 pub mod probe;
+#[cfg(target_arch = "aarch64")]
+pub mod psci;
+pub mod sdhci;
+pub mod serial;
+pub mod tty;
 
 extern crate alloc;
 
@@ -50,64 +55,110 @@ pub trait Driver: Send + Sync {
 
 /// 등록된 드라이버 정보
 struct RegisteredDriver {
+    /// 등록 ID (해제용, 1부터)
+    id: usize,
     driver: Arc<dyn Driver>,
-    probed: bool,
+    /// 이 드라이버가 맡은 디바이스 노드 이름들
+    devices: Vec<String>,
 }
 
 /// 드라이버 레지스트리
 struct DriverRegistry {
     drivers: Vec<RegisteredDriver>,
+    next_id: usize,
 }
 
 impl DriverRegistry {
     const fn new() -> Self {
         Self {
             drivers: Vec::new(),
+            next_id: 1,
         }
     }
+
+    /// 이미 어떤 드라이버가 맡은 디바이스인지
+    fn is_bound(&self, device: &str) -> bool {
+        self.drivers
+            .iter()
+            .any(|d| d.devices.iter().any(|name| name == device))
+    }
 }
 
 /// 전역 드라이버 레지스트리
 static DRIVER_REGISTRY: RwLock<DriverRegistry> = RwLock::new(DriverRegistry::new());
 
 /// 드라이버 등록
-pub fn register_driver(driver: Arc<dyn Driver>) {
+///
+/// 등록만 하고 probe는 하지 않습니다. 부팅 후(모듈 등)에 등록했다면
+/// `probe_all()`을 다시 호출해 아직 주인이 없는 디바이스를 맡게 합니다.
+///
+/// # Returns
+/// 등록 ID (`unregister_driver()`에 사용)
+pub fn register_driver(driver: Arc<dyn Driver>) -> usize {
     let mut registry = DRIVER_REGISTRY.write();
     crate::kprintln!("[drivers] Registering driver: {}", driver.name());
+    let id = registry.next_id;
+    registry.next_id += 1;
     registry.drivers.push(RegisteredDriver {
+        id,
         driver,
-        probed: false,
+        devices: Vec::new(),
     });
+    id
+}
+
+/// 드라이버 등록 해제
+///
+/// 드라이버가 맡았던 디바이스는 다시 주인 없는 상태가 되어 다음 `probe_all()`에서
+/// 다른 드라이버가 맡을 수 있습니다. 디바이스 정지는 드라이버(모듈 exit) 몫입니다.
+pub fn unregister_driver(id: usize) -> bool {
+    let mut registry = DRIVER_REGISTRY.write();
+    let Some(pos) = registry.drivers.iter().position(|d| d.id == id) else {
+        return false;
+    };
+    let reg_driver = registry.drivers.remove(pos);
+    crate::kprintln!(
+        "[drivers] Unregistered driver: {} ({} devices released)",
+        reg_driver.driver.name(),
+        reg_driver.devices.len()
+    );
+    true
 }
 
 /// 모든 드라이버 probe 실행
 ///
-/// DTB를 순회하며 등록된 드라이버와 매칭되는 디바이스를 찾아 초기화
-pub fn probe_all() {
+/// DTB를 순회하며 등록된 드라이버와 매칭되는 디바이스를 찾아 초기화합니다.
+/// 여러 번 호출해도 되며, 이미 다른 드라이버가 맡은 디바이스는 건너뛰므로
+/// 모듈이 드라이버를 등록한 뒤 호출하면 새 드라이버만 디바이스를 얻습니다.
+///
+/// probe는 레지스트리 쓰기 락을 잡은 채 실행되므로 `Driver::probe()` 안에서
+/// 드라이버를 등록/해제하면 안 됩니다.
+///
+/// # Returns
+/// 이번 호출에서 새로 probe에 성공한 디바이스 수
+pub fn probe_all() -> usize {
     let dt = match crate::dtb::get() {
         Some(dt) => dt,
         None => {
             crate::kprintln!("[drivers] Warning: DTB not available, skipping probe");
-            return;
+            return 0;
         }
     };
 
     crate::kprintln!("[drivers] Starting device probe...");
 
     let mut registry = DRIVER_REGISTRY.write();
+    let mut bound = 0;
 
-    for reg_driver in registry.drivers.iter_mut() {
-        if reg_driver.probed {
-            continue;
-        }
-
-        let driver = &reg_driver.driver;
-        let compatible_list = driver.compatible();
+    for i in 0..registry.drivers.len() {
+        let driver = registry.drivers[i].driver.clone();
 
-        for compat in compatible_list {
-            let devices = dt.find_compatible(compat);
+        for compat in driver.compatible() {
+            for info in dt.find_compatible(compat) {
+                if registry.is_bound(&info.name) {
+                    continue;
+                }
 
-            for info in devices {
                 crate::kprintln!(
                     "[drivers] Found {} for driver {} (compatible: {})",
                     info.name,
@@ -122,7 +173,8 @@ pub fn probe_all() {
                             driver.name(),
                             info.reg_base
                         );
-                        reg_driver.probed = true;
+                        registry.drivers[i].devices.push(info.name);
+                        bound += 1;
                     }
                     Err(e) => {
                         crate::kprintln!(
@@ -136,16 +188,29 @@ pub fn probe_all() {
         }
     }
 
-    crate::kprintln!("[drivers] Device probe complete");
+    crate::kprintln!("[drivers] Device probe complete ({} new)", bound);
+    bound
 }
 
 /// 등록된 드라이버 목록 출력
 pub fn list_drivers() {
     let registry = DRIVER_REGISTRY.read();
+    if registry.drivers.is_empty() {
+        crate::kprintln!("[drivers] No drivers registered");
+        return;
+    }
     crate::kprintln!("[drivers] Registered drivers:");
     for reg_driver in registry.drivers.iter() {
-        let status = if reg_driver.probed { "probed" } else { "pending" };
-        crate::kprintln!("  - {} [{}]", reg_driver.driver.name(), status);
+        if reg_driver.devices.is_empty() {
+            crate::kprintln!("  {:>3} {} [pending]", reg_driver.id, reg_driver.driver.name());
+        } else {
+            crate::kprintln!(
+                "  {:>3} {} [{}]",
+                reg_driver.id,
+                reg_driver.driver.name(),
+                reg_driver.devices.join(", ")
+            );
+        }
     }
 }
 
@@ -2,6 +2,8 @@
 //!
 //! DTB에서 디바이스 정보를 읽고, 실패 시 BoardConfig 폴백을 사용합니다.
 
+use alloc::vec::Vec;
+
 use crate::boards::{self, BoardConfig, CurrentBoard};
 use crate::drivers::config::*;
 use crate::dtb::{self, DeviceTree};
@@ -25,8 +27,9 @@ pub fn probe_platform() -> PlatformConfig {
     #[cfg(not(any(target_arch = "aarch64", target_arch = "riscv64")))]
     let interrupt_controller = InterruptControllerConfig::None;
 
-    // UART 탐색
-    let uart = probe_uart(dt);
+    // UART 탐색 (bootargs console=로 콘솔 UART 선택)
+    let uarts = probe_uarts(dt);
+    let uart = uarts[console_uart_index(uarts.len())].clone();
 
     // 타이머 탐색
     let timer = probe_timer(dt);
@@ -45,6 +48,7 @@ pub fn probe_platform() -> PlatformConfig {
 
     PlatformConfig {
         uart,
+        uarts,
         interrupt_controller,
         timer,
         clint,
@@ -62,6 +66,14 @@ fn probe_gic(dt: Option<&DeviceTree>) -> InterruptControllerConfig {
             info.cpu_interface_base,
             info.version
         );
+        if let Some(gicr) = info.redistributor_base {
+            crate::kprintln!(
+                "[probe] GICv3: GICR={:#x} (size {:#x}), ITS={:?}",
+                gicr,
+                info.redistributor_size,
+                info.its_base
+            );
+        }
 
         let version = match info.version {
             dtb::GicVersion::V2 => GicVersion::V2,
@@ -72,6 +84,8 @@ fn probe_gic(dt: Option<&DeviceTree>) -> InterruptControllerConfig {
             distributor_base: info.distributor_base as usize,
             cpu_interface_base: info.cpu_interface_base as usize,
             redistributor_base: info.redistributor_base.map(|b| b as usize),
+            redistributor_size: info.redistributor_size as usize,
+            its_base: info.its_base.map(|b| b as usize),
             version,
         })
     } else {
@@ -86,6 +100,8 @@ fn probe_gic(dt: Option<&DeviceTree>) -> InterruptControllerConfig {
             distributor_base: CurrentBoard::GICD_BASE,
             cpu_interface_base: CurrentBoard::GICC_BASE,
             redistributor_base: None,
+            redistributor_size: 0,
+            its_base: None,
             version: GicVersion::V2,
         })
     }
@@ -151,43 +167,100 @@ fn probe_clint(dt: Option<&DeviceTree>) -> Option<ClintConfig> {
     }
 }
 
-/// UART 탐색
-fn probe_uart(dt: Option<&DeviceTree>) -> UartConfig {
-    if let Some(info) = dt.and_then(|d| d.find_uart()) {
+/// DTB에 UART가 없을 때 가정하는 UART 종류 (QEMU virt 기본)
+#[cfg(target_arch = "aarch64")]
+const DEFAULT_UART_COMPATIBLE: &str = "arm,pl011";
+#[cfg(target_arch = "riscv64")]
+const DEFAULT_UART_COMPATIBLE: &str = "ns16550a";
+
+/// DTB `interrupts` 셀에서 IRQ 번호 추출 (아키텍처별)
+#[cfg(target_arch = "aarch64")]
+fn uart_irq_from_dtb(interrupts: &[u32]) -> Option<u32> {
+    // GIC: <type num flags>, SPI(0)는 +32, PPI(1)는 +16
+    match interrupts {
+        [0, spi, ..] => Some(spi + 32),
+        [1, ppi, ..] => Some(ppi + 16),
+        _ => None,
+    }
+}
+
+#[cfg(target_arch = "riscv64")]
+fn uart_irq_from_dtb(interrupts: &[u32]) -> Option<u32> {
+    // PLIC: <num>
+    interrupts.first().copied().filter(|&irq| irq != 0)
+}
+
+/// UART 탐색 (모든 UART, 항상 하나 이상)
+///
+/// 순서가 `/dev/ttyS<n>` 번호가 됩니다. IRQ를 DTB에서 못 읽으면 첫 UART는 보드 기본값,
+/// 나머지는 0(RX 인터럽트 없이 폴링)을 씁니다.
+fn probe_uarts(dt: Option<&DeviceTree>) -> Vec<UartConfig> {
+    let found = dt.map(|d| d.find_uarts()).unwrap_or_default();
+    let mut uarts = Vec::with_capacity(found.len());
+
+    for (index, info) in found.into_iter().enumerate() {
+        let irq = uart_irq_from_dtb(&info.interrupts)
+            .unwrap_or(if index == 0 { boards::uart_irq() } else { 0 });
+        let clock_freq = if info.clock_freq != 0 {
+            info.clock_freq
+        } else {
+            boards::uart_clock_freq()
+        };
+
         crate::kprintln!(
-            "[probe] UART found via DTB: base={:#x}, irq={}, clock={}Hz",
+            "[probe] UART{} found via DTB: base={:#x}, irq={}, clock={}Hz",
+            index,
             info.base,
-            info.irq,
-            info.clock_freq
+            irq,
+            clock_freq
         );
 
-        UartConfig {
+        uarts.push(UartConfig {
+            compatible: info.compatible,
             base: info.base as usize,
             size: info.size as usize,
-            irq: if info.irq != 0 {
-                info.irq
-            } else {
-                boards::uart_irq()
-            },
-            clock_freq: if info.clock_freq != 0 {
-                info.clock_freq
-            } else {
-                boards::uart_clock_freq()
-            },
-        }
-    } else {
+            irq,
+            clock_freq,
+        });
+    }
+
+    if uarts.is_empty() {
         // BoardConfig 폴백
         crate::kprintln!(
             "[probe] UART not in DTB, using board default: base={:#x}",
             CurrentBoard::UART_BASE
         );
 
-        UartConfig {
+        uarts.push(UartConfig {
+            compatible: DEFAULT_UART_COMPATIBLE,
             base: CurrentBoard::UART_BASE,
             size: 0x1000, // 4KB (일반적인 UART 레지스터 영역)
             irq: CurrentBoard::UART_IRQ,
             clock_freq: CurrentBoard::UART_CLOCK_FREQ,
+        });
+    }
+
+    uarts
+}
+
+/// 콘솔 UART 인덱스 (bootargs `console=ttyS<n>` 또는 `ttyAMA<n>`, 기본 0)
+fn console_uart_index(count: usize) -> usize {
+    let Some(name) = crate::boot::cmdline::console() else {
+        return 0;
+    };
+    match crate::drivers::serial::parse_port_name(&name) {
+        Some(index) if index < count => {
+            if index != 0 {
+                crate::kprintln!("[probe] Console on UART{} ({})", index, name);
+            }
+            index
+        }
+        Some(_) => {
+            crate::kprintln!("[probe] console={}: no such UART, using UART0", name);
+            0
         }
+        // hvc0 등 UART가 아닌 콘솔은 console::init_from_bootargs가 처리
+        None => 0,
     }
 }
 
@@ -0,0 +1,469 @@
+//! PSCI (Power State Coordination Interface) 드라이버 - AArch64
+//!
+//! 펌웨어(EL2 하이퍼바이저 또는 EL3 TF-A)에 CPU 전원 관리를 요청하는 표준 인터페이스입니다.
+//! 호출 방식(conduit)은 DTB `/psci` 노드의 `method`로 정합니다 (QEMU virt는 `hvc`, TF-A를
+//! 쓰는 실제 보드는 보통 `smc`). 노드가 없으면 예전처럼 HVC로 가정합니다.
+//!
+//! - 보조 CPU 시작: `cpu_on()` (`arch::smp`가 DTB CPU 노드의 MPIDR로 호출)
+//! - CPU 정지 (kexec), 시스템 리셋/전원 끄기 (`power`, 패닉 재부팅)
+//! - idle 상태: DTB `arm,idle-state` 중 CPU 문맥과 로컬 타이머가 유지되는 상태(retention)를
+//!   `CPU_SUSPEND`로 씁니다. 틱 없는 idle이 예상 대기 시간이 상태의 최소 체류 시간과 복귀
+//!   지연보다 길면 `wfi` 대신 가장 깊은 상태로 들어갑니다 (`suspend_idle()`).
+//!   전원이 꺼지는 상태(power-down)는 복귀 때 문맥을 복원해야 하므로 쓰지 않습니다.
+//! - PSCI 0.1 (`arm,psci`)은 함수 ID를 DTB 속성(`cpu_on`, `cpu_off`, `cpu_suspend`)에서 읽음
+//!
+//! `psci` 셸 명령이 호출 방식, 버전, CPU 상태, idle 상태 사용 횟수를 보여 주고, sysctl
+//! `psci.idle`로 idle 상태 사용을 끌 수 있습니다.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering};
+
+use crate::sync::RwLock;
+use crate::sysctl::{self, Kind, Tunable, Value};
+
+/// PSCI 함수 ID (0.2 이상 표준 ID, SMC64 호출 규약)
+pub mod func {
+    /// PSCI 버전 조회
+    pub const VERSION: u32 = 0x8400_0000;
+    /// CPU 일시 중지 (64비트)
+    pub const CPU_SUSPEND_64: u32 = 0xC400_0001;
+    /// CPU 전원 끄기
+    pub const CPU_OFF: u32 = 0x8400_0002;
+    /// CPU 전원 켜기 (64비트)
+    pub const CPU_ON_64: u32 = 0xC400_0003;
+    /// CPU 상태 조회 (64비트)
+    pub const AFFINITY_INFO_64: u32 = 0xC400_0004;
+    /// 시스템 종료
+    pub const SYSTEM_OFF: u32 = 0x8400_0008;
+    /// 시스템 리셋
+    pub const SYSTEM_RESET: u32 = 0x8400_0009;
+    /// 함수 지원 여부 조회 (1.0)
+    pub const FEATURES: u32 = 0x8400_000A;
+}
+
+/// PSCI 반환 코드
+pub mod error {
+    pub const SUCCESS: i32 = 0;
+    pub const NOT_SUPPORTED: i32 = -1;
+    pub const INVALID_PARAMS: i32 = -2;
+    pub const DENIED: i32 = -3;
+    pub const ALREADY_ON: i32 = -4;
+    pub const ON_PENDING: i32 = -5;
+    pub const INTERNAL_FAILURE: i32 = -6;
+    pub const NOT_PRESENT: i32 = -7;
+    pub const DISABLED: i32 = -8;
+    pub const INVALID_ADDRESS: i32 = -9;
+
+    /// 반환 코드 이름
+    pub fn name(code: i32) -> &'static str {
+        match code {
+            SUCCESS => "SUCCESS",
+            NOT_SUPPORTED => "NOT_SUPPORTED",
+            INVALID_PARAMS => "INVALID_PARAMS",
+            DENIED => "DENIED",
+            ALREADY_ON => "ALREADY_ON",
+            ON_PENDING => "ON_PENDING",
+            INTERNAL_FAILURE => "INTERNAL_FAILURE",
+            NOT_PRESENT => "NOT_PRESENT",
+            DISABLED => "DISABLED",
+            INVALID_ADDRESS => "INVALID_ADDRESS",
+            _ => "unknown",
+        }
+    }
+}
+
+/// `CPU_SUSPEND` 기능 플래그 (`PSCI_FEATURES` 결과): 확장 power_state 형식
+const FEATURE_EXTENDED_STATE_ID: i32 = 1 << 1;
+/// 원래 power_state 형식의 StateType 비트 (1 = power-down)
+const STATE_TYPE_POWERDOWN: u32 = 1 << 16;
+/// 확장 power_state 형식의 StateType 비트
+const STATE_TYPE_POWERDOWN_EXTENDED: u32 = 1 << 30;
+
+/// 최대 idle 상태 수 (DTB에 더 있으면 얕은 것부터)
+const MAX_IDLE_STATES: usize = 4;
+
+/// 펌웨어 호출 방식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conduit {
+    /// EL2 하이퍼바이저 (`hvc #0`)
+    Hvc = 0,
+    /// EL3 보안 모니터 (`smc #0`)
+    Smc = 1,
+}
+
+impl Conduit {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Conduit::Hvc => "hvc",
+            Conduit::Smc => "smc",
+        }
+    }
+}
+
+/// 호출 방식 (`init()` 전에는 HVC: QEMU virt와 같음)
+static CONDUIT: AtomicU8 = AtomicU8::new(Conduit::Hvc as u8);
+/// DTB에 PSCI 노드가 있었는지
+static PRESENT: AtomicBool = AtomicBool::new(false);
+/// `PSCI_VERSION` 결과 (0.1이면 0x0000_0001로 표기)
+static VERSION: AtomicU32 = AtomicU32::new(0);
+/// 함수 ID (PSCI 0.1이면 DTB 값으로 바뀜)
+static CPU_ON_ID: AtomicU32 = AtomicU32::new(func::CPU_ON_64);
+static CPU_OFF_ID: AtomicU32 = AtomicU32::new(func::CPU_OFF);
+static CPU_SUSPEND_ID: AtomicU32 = AtomicU32::new(func::CPU_SUSPEND_64);
+
+/// idle 상태 사용 여부 (`psci.idle`)
+static IDLE_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// CPU_SUSPEND로 쓰는 idle 상태
+pub struct IdleState {
+    /// DTB 노드 이름
+    pub name: String,
+    /// CPU_SUSPEND power_state 인자
+    pub param: u32,
+    /// 이보다 오래 잘 때만 진입 (min-residency + exit-latency, 마이크로초)
+    pub target_residency_us: u64,
+    pub exit_latency_us: u32,
+    /// 진입 횟수
+    pub usage: AtomicU64,
+    /// 상태에 머문 시간 합 (마이크로초)
+    pub time_us: AtomicU64,
+    /// 펌웨어가 거부해 더 쓰지 않음
+    pub disabled: AtomicBool,
+}
+
+/// 쓸 수 있는 idle 상태 (얕은 것부터, 부팅 때 한 번 채움)
+static IDLE_STATES: RwLock<Vec<IdleState>> = RwLock::new(Vec::new());
+/// `IDLE_STATES`가 비어 있지 않은지 (idle 경로에서 락 없이 확인)
+static HAS_IDLE_STATES: AtomicBool = AtomicBool::new(false);
+
+static SYSCTL: [Tunable; 1] = [Tunable {
+    name: "psci.idle",
+    desc: "enter PSCI CPU_SUSPEND idle states when the CPU sleeps long enough",
+    kind: Kind::Bool,
+    get: || Value::Bool(IDLE_ENABLED.load(Ordering::Relaxed)),
+    set: Some(|v| {
+        IDLE_ENABLED.store(sysctl::boolean(&v), Ordering::Relaxed);
+        Ok(())
+    }),
+}];
+
+/// DTB에서 호출 방식, 함수 ID, idle 상태 읽기 (힙 초기화 뒤, 보조 CPU 시작 전)
+pub fn init() {
+    let Some(dt) = crate::dtb::get() else {
+        return;
+    };
+    match dt.find_psci() {
+        Some(info) => {
+            let conduit = match info.method.as_deref() {
+                Some("smc") => Conduit::Smc,
+                Some("hvc") => Conduit::Hvc,
+                other => {
+                    crate::kprintln!("[psci] Warning: unknown method {:?}, using hvc", other);
+                    Conduit::Hvc
+                }
+            };
+            CONDUIT.store(conduit as u8, Ordering::Relaxed);
+            PRESENT.store(true, Ordering::Relaxed);
+
+            // PSCI 0.1만 지원하면 함수 ID가 펌웨어마다 다름
+            let v0_2 = info.compatible.iter().any(|c| c != "arm,psci");
+            if !v0_2 {
+                if let Some(id) = info.cpu_on {
+                    CPU_ON_ID.store(id, Ordering::Relaxed);
+                }
+                if let Some(id) = info.cpu_off {
+                    CPU_OFF_ID.store(id, Ordering::Relaxed);
+                }
+                if let Some(id) = info.cpu_suspend {
+                    CPU_SUSPEND_ID.store(id, Ordering::Relaxed);
+                }
+            }
+            let version = if v0_2 { call(func::VERSION, 0, 0, 0) as u32 } else { 1 };
+            VERSION.store(version, Ordering::Relaxed);
+            crate::kprintln!(
+                "[psci] PSCI {}.{} via {}",
+                version >> 16,
+                version & 0xFFFF,
+                conduit.as_str()
+            );
+        }
+        None => {
+            crate::kprintln!("[psci] Warning: no PSCI node in DTB, assuming hvc");
+        }
+    }
+
+    init_idle_states(dt);
+    sysctl::register_all(&SYSCTL);
+}
+
+/// DTB idle 상태 중 `CPU_SUSPEND`로 들어갔다 그대로 돌아오는 상태만 고름
+fn init_idle_states(dt: &crate::dtb::DeviceTree) {
+    let mut states: Vec<IdleState> = Vec::new();
+    let extended = features(CPU_SUSPEND_ID.load(Ordering::Relaxed))
+        .is_some_and(|f| f & FEATURE_EXTENDED_STATE_ID != 0);
+    // CPU 노드의 cpu-idle-states가 가리키는 상태만 그 CPU에 유효
+    let referenced: Vec<u32> = dt.find_cpus().into_iter().flat_map(|c| c.idle_states).collect();
+
+    for s in dt.find_idle_states() {
+        if !referenced.contains(&s.phandle) {
+            continue;
+        }
+        let powerdown = if extended {
+            s.psci_param & STATE_TYPE_POWERDOWN_EXTENDED != 0
+        } else {
+            s.psci_param & STATE_TYPE_POWERDOWN != 0
+        };
+        // 문맥이 사라지거나 타이머가 멈추면 틱 없는 idle에서 깨어날 수 없음
+        if powerdown || s.local_timer_stop {
+            crate::kprintln!(
+                "[psci] idle state {} ({:#x}): {}, not used",
+                s.name,
+                s.psci_param,
+                if powerdown { "power-down" } else { "local timer stops" }
+            );
+            continue;
+        }
+        states.push(IdleState {
+            name: s.name,
+            param: s.psci_param,
+            target_residency_us: s.min_residency_us as u64 + s.exit_latency_us as u64,
+            exit_latency_us: s.exit_latency_us,
+            usage: AtomicU64::new(0),
+            time_us: AtomicU64::new(0),
+            disabled: AtomicBool::new(false),
+        });
+    }
+
+    states.sort_by_key(|s| s.target_residency_us);
+    states.truncate(MAX_IDLE_STATES);
+    for s in &states {
+        crate::kprintln!(
+            "[psci] idle state {} ({:#x}): residency >= {} us",
+            s.name,
+            s.param,
+            s.target_residency_us
+        );
+    }
+    HAS_IDLE_STATES.store(!states.is_empty(), Ordering::Relaxed);
+    *IDLE_STATES.write() = states;
+}
+
+/// 호출 방식
+pub fn conduit() -> Conduit {
+    if CONDUIT.load(Ordering::Relaxed) == Conduit::Smc as u8 {
+        Conduit::Smc
+    } else {
+        Conduit::Hvc
+    }
+}
+
+/// DTB에 PSCI 노드가 있었는지
+pub fn is_present() -> bool {
+    PRESENT.load(Ordering::Relaxed)
+}
+
+/// PSCI 버전 (상위 16비트 major, 하위 16비트 minor, `init()` 전이면 0)
+pub fn version() -> u32 {
+    VERSION.load(Ordering::Relaxed)
+}
+
+/// 함수 지원 여부 (`PSCI_FEATURES`, 1.0 이상)
+///
+/// # Returns
+/// 지원하면 기능 플래그, 지원하지 않거나 1.0 미만이면 None
+pub fn features(func_id: u32) -> Option<i32> {
+    if version() < 0x1_0000 {
+        return None;
+    }
+    let ret = call(func::FEATURES, func_id as u64, 0, 0) as i32;
+    if ret < 0 { None } else { Some(ret) }
+}
+
+/// 보조 CPU 시작
+///
+/// # Arguments
+/// * `mpidr` - 대상 CPU의 MPIDR 어피니티 (DTB `reg`)
+/// * `entry_point` - CPU가 시작할 물리 주소
+/// * `context_id` - CPU에 전달할 값 (x0 레지스터로 전달됨)
+///
+/// # Returns
+/// PSCI 에러 코드 (0 = 성공)
+pub fn cpu_on(mpidr: u64, entry_point: usize, context_id: usize) -> i32 {
+    call(CPU_ON_ID.load(Ordering::Relaxed), mpidr, entry_point as u64, context_id as u64) as i32
+}
+
+/// 현재 CPU 종료 (반환하지 않음, 다시 `cpu_on()`으로 시작할 수 있음)
+pub fn cpu_off() -> ! {
+    call(CPU_OFF_ID.load(Ordering::Relaxed), 0, 0, 0);
+    // 반환하지 않아야 하지만, 안전을 위해 무한 루프
+    halt()
+}
+
+/// CPU를 power_state 상태로 일시 중지
+///
+/// retention 상태는 깨어나면 그대로 반환하고, power-down 상태는 `entry_point`에서
+/// `context_id`를 x0으로 받아 다시 시작합니다.
+pub fn cpu_suspend(power_state: u32, entry_point: usize, context_id: usize) -> i32 {
+    call(
+        CPU_SUSPEND_ID.load(Ordering::Relaxed),
+        power_state as u64,
+        entry_point as u64,
+        context_id as u64,
+    ) as i32
+}
+
+/// CPU 상태 조회
+///
+/// # Returns
+/// * 0: CPU가 켜져 있음
+/// * 1: CPU가 꺼져 있음
+/// * 2: 전환 중
+/// * 음수: PSCI 에러 코드
+pub fn affinity_info(mpidr: u64, lowest_affinity_level: u64) -> i32 {
+    call(func::AFFINITY_INFO_64, mpidr, lowest_affinity_level, 0) as i32
+}
+
+/// 시스템 리셋
+pub fn system_reset() -> ! {
+    call(func::SYSTEM_RESET, 0, 0, 0);
+    halt()
+}
+
+/// 시스템 종료
+pub fn system_off() -> ! {
+    call(func::SYSTEM_OFF, 0, 0, 0);
+    halt()
+}
+
+fn halt() -> ! {
+    loop {
+        unsafe {
+            core::arch::asm!("wfi");
+        }
+    }
+}
+
+/// PSCI 호출 (SMC64 호출 규약: x0 함수 ID, x1-x3 인자, x0 반환)
+#[inline(always)]
+fn call(func_id: u32, arg0: u64, arg1: u64, arg2: u64) -> i64 {
+    let result: i64;
+    unsafe {
+        if conduit() == Conduit::Smc {
+            core::arch::asm!(
+                "smc #0",
+                inout("x0") func_id as u64 => result,
+                inout("x1") arg0 => _,
+                inout("x2") arg1 => _,
+                inout("x3") arg2 => _,
+                options(nomem, nostack)
+            );
+        } else {
+            core::arch::asm!(
+                "hvc #0",
+                inout("x0") func_id as u64 => result,
+                inout("x1") arg0 => _,
+                inout("x2") arg1 => _,
+                inout("x3") arg2 => _,
+                options(nomem, nostack)
+            );
+        }
+    }
+    result
+}
+
+/// idle에서 `expected_us` 동안 잘 때 CPU_SUSPEND 상태로 대기 (인터럽트를 끈 채 호출)
+///
+/// 깨어날 인터럽트가 오면 `wfi`처럼 반환합니다.
+///
+/// # Returns
+/// 상태에 들어갔다 나왔으면 true, 쓸 상태가 없으면 false (호출자가 `wfi`)
+pub fn suspend_idle(expected_us: u64) -> bool {
+    if !HAS_IDLE_STATES.load(Ordering::Relaxed) || !IDLE_ENABLED.load(Ordering::Relaxed) {
+        return false;
+    }
+    // 다른 CPU가 쓰는 중이면 (셸의 psci 명령 등) 이번에는 wfi
+    let Some(states) = IDLE_STATES.try_read() else {
+        return false;
+    };
+    let Some(state) = states
+        .iter()
+        .rev()
+        .find(|s| s.target_residency_us <= expected_us && !s.disabled.load(Ordering::Relaxed))
+    else {
+        return false;
+    };
+
+    let start = crate::time::now_us();
+    let ret = cpu_suspend(state.param, 0, 0);
+    if ret != error::SUCCESS {
+        state.disabled.store(true, Ordering::Relaxed);
+        crate::log_warn!(tag: "psci", "idle state {} rejected: {}, disabled", state.name, error::name(ret));
+        return false;
+    }
+    state.usage.fetch_add(1, Ordering::Relaxed);
+    state.time_us.fetch_add(crate::time::now_us().saturating_sub(start), Ordering::Relaxed);
+    true
+}
+
+/// `psci` 셸 명령: 호출 방식, 버전, CPU 상태, idle 상태
+pub fn print_status() {
+    let version = version();
+    crate::kprintln!(
+        "PSCI {}.{} via {}{}",
+        version >> 16,
+        version & 0xFFFF,
+        conduit().as_str(),
+        if is_present() { "" } else { " (no DTB node, assumed)" }
+    );
+    if version >= 0x1_0000 {
+        let supported = |id| if features(id).is_some() { "yes" } else { "no" };
+        crate::kprintln!(
+            "  features: CPU_SUSPEND {}, CPU_OFF {}, SYSTEM_RESET {}",
+            supported(func::CPU_SUSPEND_64),
+            supported(func::CPU_OFF),
+            supported(func::SYSTEM_RESET)
+        );
+    }
+
+    if let Some(dt) = crate::dtb::get() {
+        crate::kprintln!("  CPUs:");
+        for cpu in dt.find_cpus() {
+            let state = match affinity_info(cpu.mpidr, 0) {
+                0 => "on",
+                1 => "off",
+                2 => "on pending",
+                e => error::name(e),
+            };
+            crate::kprintln!(
+                "    {:<8} mpidr {:#06x}  {:<10} {}",
+                cpu.name,
+                cpu.mpidr,
+                cpu.enable_method.as_deref().unwrap_or("-"),
+                state
+            );
+        }
+    }
+
+    let states = IDLE_STATES.read();
+    if states.is_empty() {
+        crate::kprintln!("  idle states: none (wfi only)");
+        return;
+    }
+    crate::kprintln!(
+        "  idle states ({}):",
+        if IDLE_ENABLED.load(Ordering::Relaxed) { "enabled" } else { "disabled by psci.idle" }
+    );
+    for s in states.iter() {
+        crate::kprintln!(
+            "    {:<16} param {:#010x}  residency {:>6} us  exit {:>5} us  usage {:>8}  time {} ms{}",
+            s.name,
+            s.param,
+            s.target_residency_us,
+            s.exit_latency_us,
+            s.usage.load(Ordering::Relaxed),
+            s.time_us.load(Ordering::Relaxed) / 1000,
+            if s.disabled.load(Ordering::Relaxed) { "  (rejected)" } else { "" }
+        );
+    }
+}
@@ -0,0 +1,615 @@
+//! SDHCI SD 카드 드라이버
+//!
+//! SD Host Controller 표준(v2/v3) 레지스터를 가진 컨트롤러(라즈베리 파이 1-3의 Arasan
+//! `emmc`, 파이 4의 `emmc2` 등)에 꽂힌 SD 카드를 블록 디바이스 `mmcblk<n>`으로 등록합니다.
+//! `block::init()`이 `Driver`로 등록하고 DTB `compatible`로 probe하므로, 이후 파티션
+//! 스캔(`mmcblk0p1`, ...)과 DevFS 등록, `root=`/`mount`는 VirtIO 블록과 같습니다.
+//!
+//! - 데이터는 버퍼 데이터 포트로 CPU가 옮기고(PIO), 완료는 인터럽트 상태 레지스터를 폴링
+//!   (DMA, IRQ 없음). 명령 하나가 끝날 때까지 호스트 락을 잡고 있음
+//! - 카드 초기화: CMD0 → CMD8 → ACMD41 → CMD2 → CMD3 → CMD9(용량) → CMD7 → ACMD6(4비트)
+//! - SDHC/SDXC는 블록 주소, SDSC는 바이트 주소(CMD16으로 512바이트 블록)
+//! - 식별은 400kHz, 이후 25MHz(기본 속도). 기본 클럭은 capabilities, 없으면 DTB
+//!   `clock-frequency`, 둘 다 없으면 `FALLBACK_BASE_CLOCK`으로 가정
+//! - 레지스터는 모두 32비트로 접근 (BCM2835는 8/16비트 쓰기를 잃음)
+//! - MMC/eMMC, SDIO, UHS 모드, 카드 교체 감지, 쓰기 방지 스위치는 지원하지 않음
+
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::ptr::{read_volatile, write_volatile};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::block::{self, BlockDevice, BlockError, BlockResult};
+use crate::dtb::DeviceInfo;
+use crate::sync::Mutex;
+
+use super::{Driver, DriverError, DriverResult};
+
+/// SDHCI 레지스터 오프셋 (32비트 단위로 묶어 접근)
+mod regs {
+    /// 블록 크기(15:0) | 블록 수(31:16)
+    pub const BLOCK: usize = 0x04;
+    pub const ARGUMENT: usize = 0x08;
+    /// 전송 모드(15:0) | 명령(31:16), 쓰면 명령이 나감
+    pub const COMMAND: usize = 0x0C;
+    pub const RESPONSE: usize = 0x10;
+    pub const BUFFER: usize = 0x20;
+    pub const PRESENT_STATE: usize = 0x24;
+    /// 호스트 제어(7:0) | 전원 제어(15:8) | 블록 갭 | 웨이크업
+    pub const HOST_CONTROL: usize = 0x28;
+    /// 클럭 제어(15:0) | 타임아웃 제어(19:16) | 소프트웨어 리셋(26:24)
+    pub const CLOCK_CONTROL: usize = 0x2C;
+    /// 일반 인터럽트(15:0) | 에러 인터럽트(31:16), 1을 써서 지움
+    pub const INT_STATUS: usize = 0x30;
+    pub const INT_STATUS_ENABLE: usize = 0x34;
+    pub const INT_SIGNAL_ENABLE: usize = 0x38;
+    pub const CAPABILITIES: usize = 0x40;
+    /// 슬롯 인터럽트(15:0) | 호스트 버전(31:16)
+    pub const SLOT_INT_VERSION: usize = 0xFC;
+}
+
+/// `PRESENT_STATE` 비트
+mod present {
+    pub const CMD_INHIBIT: u32 = 1 << 0;
+    pub const DAT_INHIBIT: u32 = 1 << 1;
+    pub const CARD_INSERTED: u32 = 1 << 16;
+}
+
+/// `INT_STATUS` 비트
+mod int {
+    pub const CMD_COMPLETE: u32 = 1 << 0;
+    pub const TRANSFER_COMPLETE: u32 = 1 << 1;
+    pub const BUFFER_WRITE_READY: u32 = 1 << 4;
+    pub const BUFFER_READ_READY: u32 = 1 << 5;
+    pub const CARD_INTERRUPT: u32 = 1 << 8;
+    pub const ERROR: u32 = 1 << 15;
+    /// 명령 타임아웃 (에러 인터럽트 비트 0)
+    pub const CMD_TIMEOUT: u32 = 1 << 16;
+    pub const ERROR_MASK: u32 = 0xFFFF_0000;
+}
+
+/// `CLOCK_CONTROL` 워드 비트
+mod clock {
+    pub const INTERNAL_ENABLE: u32 = 1 << 0;
+    pub const INTERNAL_STABLE: u32 = 1 << 1;
+    pub const SD_ENABLE: u32 = 1 << 2;
+    /// 최대 데이터 타임아웃 (TMCLK * 2^27)
+    pub const TIMEOUT_MAX: u32 = 0xE << 16;
+    pub const RESET_ALL: u32 = 1 << 24;
+    pub const RESET_CMD: u32 = 1 << 25;
+    pub const RESET_DATA: u32 = 1 << 26;
+}
+
+/// `HOST_CONTROL` 워드 비트
+mod host {
+    pub const DATA_WIDTH_4BIT: u32 = 1 << 1;
+    /// 전원 제어: 버스 전원 켜기 + 3.3V
+    pub const POWER_33V: u32 = (1 | 0x7 << 1) << 8;
+}
+
+/// 명령 워드 (`COMMAND` 레지스터 상위 16비트) 응답 형식
+mod resp {
+    pub const NONE: u32 = 0;
+    pub const LONG: u32 = 1;
+    pub const SHORT: u32 = 2;
+    pub const SHORT_BUSY: u32 = 3;
+    pub const CRC_CHECK: u32 = 1 << 3;
+    pub const INDEX_CHECK: u32 = 1 << 4;
+    pub const DATA_PRESENT: u32 = 1 << 5;
+
+    pub const R1: u32 = SHORT | CRC_CHECK | INDEX_CHECK;
+    pub const R1B: u32 = SHORT_BUSY | CRC_CHECK | INDEX_CHECK;
+    pub const R2: u32 = LONG | CRC_CHECK;
+    pub const R3: u32 = SHORT;
+    pub const R6: u32 = R1;
+    pub const R7: u32 = R1;
+}
+
+/// 전송 모드 (`COMMAND` 레지스터 하위 16비트)
+mod mode {
+    pub const BLOCK_COUNT_ENABLE: u32 = 1 << 1;
+    pub const AUTO_CMD12: u32 = 1 << 2;
+    pub const READ: u32 = 1 << 4;
+    pub const MULTI_BLOCK: u32 = 1 << 5;
+}
+
+/// SD 명령 번호
+mod cmd {
+    pub const GO_IDLE_STATE: u32 = 0;
+    pub const ALL_SEND_CID: u32 = 2;
+    pub const SEND_RELATIVE_ADDR: u32 = 3;
+    /// ACMD6
+    pub const SET_BUS_WIDTH: u32 = 6;
+    pub const SELECT_CARD: u32 = 7;
+    pub const SEND_IF_COND: u32 = 8;
+    pub const SEND_CSD: u32 = 9;
+    pub const SET_BLOCKLEN: u32 = 16;
+    pub const READ_SINGLE_BLOCK: u32 = 17;
+    pub const READ_MULTIPLE_BLOCK: u32 = 18;
+    pub const WRITE_BLOCK: u32 = 24;
+    pub const WRITE_MULTIPLE_BLOCK: u32 = 25;
+    /// ACMD41
+    pub const SD_SEND_OP_COND: u32 = 41;
+    pub const APP_CMD: u32 = 55;
+}
+
+/// 섹터 크기
+const BLOCK_SIZE: usize = 512;
+/// 명령 하나로 옮기는 최대 블록 수 (블록 수 레지스터는 16비트)
+const MAX_BLOCKS_PER_CMD: usize = 128;
+
+/// 식별 단계 클럭
+const IDENT_CLOCK_HZ: u32 = 400_000;
+/// 기본 속도 모드 클럭
+const DEFAULT_SPEED_HZ: u32 = 25_000_000;
+/// 기본 클럭을 모를 때 가정하는 값 (실제보다 크게 잡으면 버스 클럭은 목표보다 느려질 뿐)
+const FALLBACK_BASE_CLOCK: u32 = 250_000_000;
+
+/// CMD8 인자: 2.7-3.6V, 확인 패턴 0xAA
+const IF_COND_CHECK: u32 = 0x1AA;
+/// ACMD41 인자: 지원 전압 범위 2.7-3.6V
+const OCR_VOLTAGE_WINDOW: u32 = 0x00FF_8000;
+/// OCR: 고용량 카드 (SDHC/SDXC, 블록 주소)
+const OCR_CCS: u32 = 1 << 30;
+/// OCR: 카드 전원 켜기 완료
+const OCR_BUSY_DONE: u32 = 1 << 31;
+
+/// 명령 하나의 완료 대기 한도
+const CMD_TIMEOUT_US: u64 = 100_000;
+/// 데이터 전송(쓰기 중 카드 busy 포함) 대기 한도
+const DATA_TIMEOUT_US: u64 = 1_000_000;
+/// ACMD41 전원 켜기 대기 한도
+const POWER_UP_TIMEOUT_US: u64 = 1_000_000;
+
+/// SD 호스트 에러
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SdError {
+    /// 레지스터 상태 대기 시간 초과
+    Timeout,
+    /// 카드가 명령에 응답하지 않음
+    NoResponse,
+    /// 에러 인터럽트 (상위 16비트 에러 상태)
+    Command(u32),
+    /// 지원하지 않는 카드 (전압 불일치, SDIO 등)
+    Unusable,
+}
+
+/// 호스트 컨트롤러 레지스터 (probe 때 한 번 초기화, 이후 `SdCard`의 락 안에서만 접근)
+struct Host {
+    base: usize,
+    /// SDHCI 사양 버전 (0 = 1.0, 1 = 2.0, 2 = 3.0 ...)
+    version: u8,
+    /// 기본 클럭 (Hz)
+    base_clock: u32,
+}
+
+impl Host {
+    fn read(&self, offset: usize) -> u32 {
+        unsafe { read_volatile((self.base + offset) as *const u32) }
+    }
+
+    fn write(&self, offset: usize, value: u32) {
+        unsafe { write_volatile((self.base + offset) as *mut u32, value) }
+    }
+
+    /// `cond`가 참이 될 때까지 폴링
+    fn wait(&self, timeout_us: u64, mut cond: impl FnMut(&Self) -> bool) -> Result<(), SdError> {
+        let deadline = crate::time::clock::monotonic_ns() + timeout_us * 1000;
+        while !cond(self) {
+            if crate::time::clock::monotonic_ns() > deadline {
+                return if cond(self) { Ok(()) } else { Err(SdError::Timeout) };
+            }
+            core::hint::spin_loop();
+        }
+        Ok(())
+    }
+
+    /// 소프트웨어 리셋 (`clock::RESET_*`)
+    fn reset(&self, bits: u32) -> Result<(), SdError> {
+        self.write(regs::CLOCK_CONTROL, self.read(regs::CLOCK_CONTROL) | bits);
+        self.wait(CMD_TIMEOUT_US, |h| h.read(regs::CLOCK_CONTROL) & bits == 0)
+    }
+
+    /// SD 버스 클럭을 `hz` 이하로 설정
+    fn set_clock(&self, hz: u32) -> Result<(), SdError> {
+        let timeout = self.read(regs::CLOCK_CONTROL) & 0x000F_0000;
+        self.write(regs::CLOCK_CONTROL, timeout);
+
+        let div = clock_divider(self.base_clock, hz, self.version >= 2);
+        let div_bits = (div & 0xFF) << 8 | (div >> 8 & 0x3) << 6;
+        self.write(regs::CLOCK_CONTROL, timeout | div_bits | clock::INTERNAL_ENABLE);
+        self.wait(CMD_TIMEOUT_US, |h| h.read(regs::CLOCK_CONTROL) & clock::INTERNAL_STABLE != 0)?;
+        self.write(regs::CLOCK_CONTROL, timeout | div_bits | clock::INTERNAL_ENABLE | clock::SD_ENABLE);
+        Ok(())
+    }
+
+    /// 컨트롤러 리셋, 전원, 식별 클럭, 폴링용 인터럽트 설정
+    fn init(&self) -> Result<(), SdError> {
+        self.reset(clock::RESET_ALL)?;
+        self.write(regs::HOST_CONTROL, host::POWER_33V);
+        self.set_clock(IDENT_CLOCK_HZ)?;
+        let clk = self.read(regs::CLOCK_CONTROL) & !0x000F_0000;
+        self.write(regs::CLOCK_CONTROL, clk | clock::TIMEOUT_MAX);
+        // 상태는 모두 보고하되(카드 인터럽트 제외) 인터럽트 신호는 보내지 않음
+        self.write(regs::INT_STATUS_ENABLE, !int::CARD_INTERRUPT);
+        self.write(regs::INT_SIGNAL_ENABLE, 0);
+        self.write(regs::INT_STATUS, 0xFFFF_FFFF);
+        Ok(())
+    }
+
+    /// 에러 뒤 명령/데이터 회로 리셋, 상태 지움
+    fn recover(&self) {
+        let _ = self.reset(clock::RESET_CMD | clock::RESET_DATA);
+        self.write(regs::INT_STATUS, 0xFFFF_FFFF);
+    }
+
+    /// 명령을 보내고 완료될 때까지 대기 (데이터 없음)
+    ///
+    /// # Returns
+    /// 응답 레지스터 4개 (짧은 응답은 [0]만)
+    fn command(&self, index: u32, arg: u32, flags: u32) -> Result<[u32; 4], SdError> {
+        self.issue(index, arg, flags, 0)?;
+        if flags & resp::SHORT_BUSY == resp::SHORT_BUSY {
+            // R1b: 카드가 busy를 풀 때까지
+            self.wait_int(int::TRANSFER_COMPLETE, DATA_TIMEOUT_US)?;
+        }
+        Ok(self.response())
+    }
+
+    /// 명령 레지스터에 쓰고 명령 완료까지 대기
+    fn issue(&self, index: u32, arg: u32, flags: u32, transfer_mode: u32) -> Result<(), SdError> {
+        let mut inhibit = present::CMD_INHIBIT;
+        if flags & resp::DATA_PRESENT != 0 || flags & resp::SHORT_BUSY == resp::SHORT_BUSY {
+            inhibit |= present::DAT_INHIBIT;
+        }
+        self.wait(CMD_TIMEOUT_US, |h| h.read(regs::PRESENT_STATE) & inhibit == 0)?;
+        self.write(regs::INT_STATUS, 0xFFFF_FFFF);
+        self.write(regs::ARGUMENT, arg);
+        self.write(regs::COMMAND, (index << 8 | flags) << 16 | transfer_mode);
+        self.wait_int(int::CMD_COMPLETE, CMD_TIMEOUT_US)
+    }
+
+    /// 인터럽트 상태 `bit`를 기다렸다 지움 (에러 비트가 서면 회로를 리셋하고 에러)
+    fn wait_int(&self, bit: u32, timeout_us: u64) -> Result<(), SdError> {
+        let mut status = 0;
+        let waited = self.wait(timeout_us, |h| {
+            status = h.read(regs::INT_STATUS);
+            status & (bit | int::ERROR) != 0
+        });
+        if status & int::ERROR != 0 {
+            self.recover();
+            return Err(if status & int::CMD_TIMEOUT != 0 {
+                SdError::NoResponse
+            } else {
+                SdError::Command((status & int::ERROR_MASK) >> 16)
+            });
+        }
+        if waited.is_err() {
+            self.recover();
+            return Err(SdError::Timeout);
+        }
+        self.write(regs::INT_STATUS, bit);
+        Ok(())
+    }
+
+    fn response(&self) -> [u32; 4] {
+        core::array::from_fn(|i| self.read(regs::RESPONSE + i * 4))
+    }
+
+    /// 앱 명령 (CMD55 + ACMDn)
+    fn app_command(&self, rca: u32, index: u32, arg: u32, flags: u32) -> Result<[u32; 4], SdError> {
+        self.command(cmd::APP_CMD, rca << 16, resp::R1)?;
+        self.command(index, arg, flags)
+    }
+
+    /// 카드 식별과 선택, 데이터 전송 준비
+    fn init_card(&self) -> Result<CardInfo, SdError> {
+        self.command(cmd::GO_IDLE_STATE, 0, resp::NONE)?;
+
+        // CMD8에 응답하면 v2.0 이상 (고용량일 수 있음), 응답이 없으면 v1.x
+        let v2 = match self.command(cmd::SEND_IF_COND, IF_COND_CHECK, resp::R7) {
+            Ok(r) if r[0] & 0xFFF == IF_COND_CHECK => true,
+            Ok(_) => return Err(SdError::Unusable),
+            Err(SdError::NoResponse) => false,
+            Err(e) => return Err(e),
+        };
+
+        let hcs = if v2 { OCR_CCS } else { 0 };
+        let mut ocr = 0;
+        self.wait(POWER_UP_TIMEOUT_US, |h| {
+            match h.app_command(0, cmd::SD_SEND_OP_COND, hcs | OCR_VOLTAGE_WINDOW, resp::R3) {
+                Ok(r) => ocr = r[0],
+                Err(_) => ocr = 0,
+            }
+            ocr & OCR_BUSY_DONE != 0
+        })
+        .map_err(|_| if ocr == 0 { SdError::Unusable } else { SdError::Timeout })?;
+        let high_capacity = ocr & OCR_CCS != 0;
+
+        let cid = long_response(self.command(cmd::ALL_SEND_CID, 0, resp::R2)?);
+        let rca = self.command(cmd::SEND_RELATIVE_ADDR, 0, resp::R6)?[0] >> 16;
+        let csd = long_response(self.command(cmd::SEND_CSD, rca << 16, resp::R2)?);
+        let blocks = csd_capacity(csd) / BLOCK_SIZE as u64;
+        self.command(cmd::SELECT_CARD, rca << 16, resp::R1B)?;
+
+        self.set_clock(DEFAULT_SPEED_HZ)?;
+        // 4비트 버스는 모든 SD 메모리 카드의 필수 기능
+        self.app_command(rca, cmd::SET_BUS_WIDTH, 2, resp::R1)?;
+        self.write(regs::HOST_CONTROL, self.read(regs::HOST_CONTROL) | host::DATA_WIDTH_4BIT);
+        if !high_capacity {
+            self.command(cmd::SET_BLOCKLEN, BLOCK_SIZE as u32, resp::R1)?;
+        }
+
+        Ok(CardInfo {
+            high_capacity,
+            blocks,
+            product: cid_product_name(cid),
+        })
+    }
+
+    /// 블록 전송 (`buf`는 블록 크기의 배수, 최대 `MAX_BLOCKS_PER_CMD`개)
+    ///
+    /// 여러 블록이면 CMD18/CMD25에 Auto CMD12로 끝냅니다. 쓰기는 카드가 프로그램을
+    /// 마칠 때(전송 완료)까지 기다립니다.
+    fn transfer(&self, card: &CardInfo, block: u64, buf: *mut u8, len: usize, write: bool) -> Result<(), SdError> {
+        let count = len / BLOCK_SIZE;
+        let multi = count > 1;
+        let index = match (write, multi) {
+            (false, false) => cmd::READ_SINGLE_BLOCK,
+            (false, true) => cmd::READ_MULTIPLE_BLOCK,
+            (true, false) => cmd::WRITE_BLOCK,
+            (true, true) => cmd::WRITE_MULTIPLE_BLOCK,
+        };
+        let arg = if card.high_capacity { block } else { block * BLOCK_SIZE as u64 } as u32;
+        let mut transfer_mode = if write { 0 } else { mode::READ };
+        if multi {
+            transfer_mode |= mode::MULTI_BLOCK | mode::BLOCK_COUNT_ENABLE | mode::AUTO_CMD12;
+        }
+
+        self.write(regs::BLOCK, (count as u32) << 16 | BLOCK_SIZE as u32);
+        self.issue(index, arg, resp::R1 | resp::DATA_PRESENT, transfer_mode)?;
+
+        let ready = if write { int::BUFFER_WRITE_READY } else { int::BUFFER_READ_READY };
+        for i in 0..count {
+            self.wait_int(ready, DATA_TIMEOUT_US)?;
+            let words = unsafe { buf.add(i * BLOCK_SIZE) } as *mut u32;
+            for w in 0..BLOCK_SIZE / 4 {
+                // 버퍼는 바이트 정렬일 수 있음
+                unsafe {
+                    if write {
+                        self.write(regs::BUFFER, words.add(w).read_unaligned());
+                    } else {
+                        words.add(w).write_unaligned(self.read(regs::BUFFER));
+                    }
+                }
+            }
+        }
+        self.wait_int(int::TRANSFER_COMPLETE, DATA_TIMEOUT_US)
+    }
+}
+
+/// 초기화한 카드 정보
+struct CardInfo {
+    /// SDHC/SDXC (블록 주소)
+    high_capacity: bool,
+    /// 512바이트 블록 수
+    blocks: u64,
+    /// CID 제품 이름
+    product: String,
+}
+
+/// `base`에서 `target` 이하 클럭을 만드는 분주 값 N (출력 = base / 2N, 0이면 base 그대로)
+///
+/// v3는 10비트 N, v2 이하는 2의 거듭제곱 N (1..=128)입니다.
+pub fn clock_divider(base: u32, target: u32, v3: bool) -> u32 {
+    if target == 0 || base <= target {
+        return 0;
+    }
+    if v3 {
+        base.div_ceil(2 * target).min(0x3FF)
+    } else {
+        let mut n = 1;
+        while n < 128 && base / (2 * n) > target {
+            n *= 2;
+        }
+        n
+    }
+}
+
+/// SDHCI의 136비트 응답 레지스터(CRC 제외, 8비트 밀림)를 CID/CSD 비트 배치로
+pub fn long_response(r: [u32; 4]) -> u128 {
+    let value = r[0] as u128 | (r[1] as u128) << 32 | (r[2] as u128) << 64 | (r[3] as u128) << 96;
+    value << 8
+}
+
+/// `reg`의 `hi..=lo` 비트
+fn bits(reg: u128, hi: u32, lo: u32) -> u64 {
+    ((reg >> lo) & ((1u128 << (hi - lo + 1)) - 1)) as u64
+}
+
+/// CSD에서 카드 용량 (바이트)
+pub fn csd_capacity(csd: u128) -> u64 {
+    match bits(csd, 127, 126) {
+        // CSD 2.0 (SDHC/SDXC): (C_SIZE + 1) * 512KB
+        1 => (bits(csd, 69, 48) + 1) * 512 * 1024,
+        // CSD 1.0 (SDSC): (C_SIZE + 1) * 2^(C_SIZE_MULT + 2) * 2^READ_BL_LEN
+        _ => (bits(csd, 73, 62) + 1) << (bits(csd, 49, 47) + 2) << bits(csd, 83, 80),
+    }
+}
+
+/// CID의 제품 이름 (5글자 ASCII)
+fn cid_product_name(cid: u128) -> String {
+    (0..5)
+        .map(|i| bits(cid, 103 - i * 8, 96 - i * 8) as u8)
+        .map(|b| if b.is_ascii_graphic() { b as char } else { '?' })
+        .collect()
+}
+
+/// SD 카드 블록 디바이스
+pub struct SdCard {
+    name: String,
+    host: Mutex<Host>,
+    card: CardInfo,
+}
+
+impl SdCard {
+    /// 범위 확인 뒤 `MAX_BLOCKS_PER_CMD`씩 전송 (`locked`: 잡아 둔 호스트)
+    fn transfer_blocks(&self, host: &Host, start_block: u64, buf: *mut u8, len: usize, write: bool) -> BlockResult<()> {
+        if len % BLOCK_SIZE != 0 {
+            return Err(BlockError::BufferSizeMismatch);
+        }
+        match start_block.checked_add((len / BLOCK_SIZE) as u64) {
+            Some(end) if end <= self.card.blocks => {}
+            _ => return Err(BlockError::InvalidBlock),
+        }
+        let mut done = 0;
+        while done < len {
+            let chunk = (len - done).min(MAX_BLOCKS_PER_CMD * BLOCK_SIZE);
+            let block = start_block + (done / BLOCK_SIZE) as u64;
+            // Safety: done + chunk <= len
+            let ptr = unsafe { buf.add(done) };
+            host.transfer(&self.card, block, ptr, chunk, write).map_err(|e| {
+                crate::log_warn!(tag: "sdhci", "{}: {} at block {}: {:?}",
+                    self.name, if write { "write" } else { "read" }, block, e);
+                BlockError::IoError
+            })?;
+            done += chunk;
+        }
+        Ok(())
+    }
+}
+
+impl BlockDevice for SdCard {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn block_size(&self) -> usize {
+        BLOCK_SIZE
+    }
+
+    fn block_count(&self) -> u64 {
+        self.card.blocks
+    }
+
+    fn read_block(&self, block_num: u64, buf: &mut [u8]) -> BlockResult<()> {
+        if buf.len() != BLOCK_SIZE {
+            return Err(BlockError::BufferSizeMismatch);
+        }
+        self.read_blocks(block_num, buf)
+    }
+
+    fn write_block(&self, block_num: u64, buf: &[u8]) -> BlockResult<()> {
+        if buf.len() != BLOCK_SIZE {
+            return Err(BlockError::BufferSizeMismatch);
+        }
+        self.write_blocks(block_num, buf)
+    }
+
+    fn read_blocks(&self, start_block: u64, buf: &mut [u8]) -> BlockResult<()> {
+        let host = self.host.lock();
+        self.transfer_blocks(&host, start_block, buf.as_mut_ptr(), buf.len(), false)
+    }
+
+    fn write_blocks(&self, start_block: u64, buf: &[u8]) -> BlockResult<()> {
+        let host = self.host.lock();
+        // 쓰기 전송은 버퍼를 읽기만 함
+        self.transfer_blocks(&host, start_block, buf.as_ptr() as *mut u8, buf.len(), true)
+    }
+
+    /// PIO 폴링이라 그대로 쓸 수 있음 (호스트 락은 `try_lock`)
+    fn panic_write(&self, start_block: u64, buf: &[u8]) -> BlockResult<()> {
+        let host = self.host.try_lock().ok_or(BlockError::NotReady)?;
+        self.transfer_blocks(&host, start_block, buf.as_ptr() as *mut u8, buf.len(), true)
+    }
+}
+
+/// 다음 `mmcblk<n>` 번호
+static NEXT_INDEX: AtomicUsize = AtomicUsize::new(0);
+
+/// DTB SDHCI 노드 드라이버
+pub struct SdhciDriver;
+
+impl Driver for SdhciDriver {
+    fn name(&self) -> &str {
+        "sdhci"
+    }
+
+    fn compatible(&self) -> &[&str] {
+        &[
+            "brcm,bcm2835-sdhci",
+            "brcm,bcm2835-mmc",
+            "brcm,bcm2711-emmc2",
+            "arasan,sdhci-8.9a",
+            "arasan,sdhci-5.1",
+        ]
+    }
+
+    fn probe(&self, info: &DeviceInfo) -> DriverResult<()> {
+        let base = info.reg_base as usize;
+        if base == 0 {
+            return Err(DriverError::DeviceNotFound);
+        }
+        map_registers(base, info.reg_size as usize)?;
+
+        let mut host = Host { base, version: 0, base_clock: 0 };
+        host.version = (host.read(regs::SLOT_INT_VERSION) >> 16) as u8;
+        let caps = host.read(regs::CAPABILITIES);
+        // v3는 8비트, 그 이전은 6비트 (MHz)
+        let caps_mhz = if host.version >= 2 { caps >> 8 & 0xFF } else { caps >> 8 & 0x3F };
+        host.base_clock = match (caps_mhz, info.clock_frequency) {
+            (0, Some(hz)) if hz != 0 => hz,
+            (0, _) => FALLBACK_BASE_CLOCK,
+            (mhz, _) => mhz * 1_000_000,
+        };
+        crate::kprintln!(
+            "[sdhci] {} @ {:#x}: SDHCI v{}.0, base clock {} MHz, card {}",
+            info.name,
+            base,
+            host.version as u32 + 1,
+            host.base_clock / 1_000_000,
+            if host.read(regs::PRESENT_STATE) & present::CARD_INSERTED != 0 { "present" } else { "not detected" }
+        );
+
+        // 카드 감지 핀이 없는 보드도 있으므로 감지 여부와 관계없이 초기화해 봄
+        let card = host.init().and_then(|()| host.init_card()).map_err(|e| {
+            crate::kprintln!("[sdhci] {}: card init failed: {:?}", info.name, e);
+            DriverError::InitFailed
+        })?;
+
+        let name = format!("mmcblk{}", NEXT_INDEX.fetch_add(1, Ordering::Relaxed));
+        crate::kprintln!(
+            "[sdhci] {}: {} card '{}', {} MB",
+            name,
+            if card.high_capacity { "SDHC/SDXC" } else { "SDSC" },
+            card.product,
+            card.blocks * BLOCK_SIZE as u64 / (1024 * 1024)
+        );
+        let device = SdCard { name: name.clone(), host: Mutex::new(host), card };
+        block::register_device(&name, Arc::new(device));
+        Ok(())
+    }
+}
+
+/// 컨트롤러 레지스터를 디바이스 메모리로 매핑 (이미 매핑돼 있으면 그대로)
+fn map_registers(base: usize, size: usize) -> DriverResult<()> {
+    use crate::arch::mmu::{self, MapKind};
+    use crate::mm::page::PAGE_SIZE;
+
+    if mmu::translate(base).is_some() {
+        return Ok(());
+    }
+    let start = base & !(PAGE_SIZE - 1);
+    let len = (base + size.max(0x100)).next_multiple_of(PAGE_SIZE) - start;
+    mmu::map_range(start, start, len, MapKind::Device).map_err(|e| {
+        crate::kprintln!("[sdhci] cannot map {:#x}: {}", base, e);
+        DriverError::OutOfResources
+    })
+}
+
+/// 드라이버 등록 후 DTB의 SDHCI 노드 probe (`block::init()`에서, 파티션 스캔 전)
+pub fn init() {
+    super::register_driver(Arc::new(SdhciDriver));
+    super::probe_all();
+}
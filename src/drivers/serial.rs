@@ -0,0 +1,238 @@
+//! 시리얼 포트 (여러 UART)
+//!
+//! 플랫폼 탐색(`drivers::probe`)이 찾은 UART마다 포트 번호를 붙여 `/dev/ttyS<n>` 문자
+//! 디바이스로 내보냅니다. 그중 하나가 콘솔 UART이며 bootargs의 `console=ttyS<n>`
+//! (또는 `ttyAMA<n>`)로 고릅니다 (기본 0).
+//!
+//! - 콘솔 포트의 입력은 콘솔 수신 링(`console::receive`)으로 가고, `/dev/ttyS<콘솔>`은
+//!   `/dev/console`과 같은 TTY를 씁니다.
+//! - 나머지 포트는 포트별 수신 링과 TTY(`drivers::tty::serial`)를 가집니다.
+//! - 포트마다 RX IRQ에 `handle_irq()`를 걸고(`crate::irq::request_irq`), 핸들러는 IRQ 번호에
+//!   맞는 포트를 찾습니다. 인터럽트 문맥에서 읽으므로 포트 표는 고정 크기 atomic 배열입니다.
+//! - 핸들러(상반부)는 UART FIFO를 포트의 임시 버퍼로 비우기만 하고, 수신 링으로 나누는 일은
+//!   포트의 태스크릿(하반부, `crate::softirq`)이 인터럽트를 켠 채로 합니다.
+
+use core::sync::atomic::{AtomicU32, AtomicU8, AtomicUsize, Ordering};
+
+use crate::console::{self, RxRing};
+use crate::softirq::Tasklet;
+use crate::sync::IrqSpinlock;
+
+/// 최대 포트 수 (넘는 UART는 무시)
+pub const MAX_PORTS: usize = 4;
+
+/// 상반부가 읽은 바이트를 하반부가 옮길 때까지 두는 임시 버퍼 크기 (UART FIFO보다 넉넉히)
+const RX_STAGE_SIZE: usize = 64;
+
+/// 상반부 → 하반부 수신 버퍼
+///
+/// 쓰는 쪽은 포트의 IRQ 핸들러(같은 IRQ는 한 번에 한 CPU만 처리), 읽는 쪽은 포트의
+/// 태스크릿(동시에 두 CPU에서 돌지 않음) 하나씩이므로 락 없이 위치 카운터만으로 충분합니다.
+struct RxStage {
+    buf: [AtomicU8; RX_STAGE_SIZE],
+    /// 지금까지 쓴 바이트 수 (상반부만 증가)
+    head: AtomicUsize,
+    /// 지금까지 읽은 바이트 수 (하반부만 증가)
+    tail: AtomicUsize,
+}
+
+impl RxStage {
+    const fn new() -> Self {
+        Self {
+            buf: [const { AtomicU8::new(0) }; RX_STAGE_SIZE],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// 바이트 추가 (가득 차면 버림)
+    fn push(&self, byte: u8) {
+        let head = self.head.load(Ordering::Relaxed);
+        if head.wrapping_sub(self.tail.load(Ordering::Acquire)) >= RX_STAGE_SIZE {
+            return;
+        }
+        self.buf[head % RX_STAGE_SIZE].store(byte, Ordering::Relaxed);
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+    }
+
+    fn pop(&self) -> Option<u8> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        if tail == self.head.load(Ordering::Acquire) {
+            return None;
+        }
+        let byte = self.buf[tail % RX_STAGE_SIZE].load(Ordering::Relaxed);
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Some(byte)
+    }
+}
+
+/// 포트 표 항목 (base 0 = 미사용)
+struct Port {
+    base: AtomicUsize,
+    /// RX IRQ 번호 (0 = 없음, 폴링)
+    irq: AtomicU32,
+    /// IRQ 핸들러가 FIFO에서 읽은 바이트
+    rx_stage: RxStage,
+    /// `rx_stage`를 수신 링으로 옮기는 하반부 (data = 포트 번호)
+    rx_tasklet: Tasklet,
+}
+
+impl Port {
+    const fn new(index: usize) -> Self {
+        Self {
+            base: AtomicUsize::new(0),
+            irq: AtomicU32::new(0),
+            rx_stage: RxStage::new(),
+            rx_tasklet: Tasklet::new(flush_rx, index),
+        }
+    }
+}
+
+static PORTS: [Port; MAX_PORTS] = [Port::new(0), Port::new(1), Port::new(2), Port::new(3)];
+static PORT_COUNT: AtomicUsize = AtomicUsize::new(0);
+static CONSOLE_PORT: AtomicUsize = AtomicUsize::new(0);
+
+/// 콘솔이 아닌 포트의 수신 링
+static RX_RINGS: [IrqSpinlock<RxRing>; MAX_PORTS] = [const { IrqSpinlock::new(RxRing::new()) }; MAX_PORTS];
+
+/// 포트 정보
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortInfo {
+    pub index: usize,
+    pub base: usize,
+    pub irq: u32,
+    pub is_console: bool,
+}
+
+/// `ttyS<n>` 또는 `ttyAMA<n>`에서 포트 번호 추출
+pub fn parse_port_name(name: &str) -> Option<usize> {
+    name.strip_prefix("ttyS")
+        .or_else(|| name.strip_prefix("ttyAMA"))
+        .and_then(|n| n.parse().ok())
+}
+
+/// 포트 표 채우기 (플랫폼 설정 후, 인터럽트 컨트롤러 초기화 전에 호출)
+///
+/// 콘솔이 아닌 UART는 여기서 초기화합니다 (콘솔 UART는 부팅 때 이미 사용 중).
+pub fn init() {
+    let uarts = crate::drivers::config::uarts();
+    let console_base = crate::drivers::config::uart_base();
+
+    let mut count = 0;
+    for uart in uarts.iter().take(MAX_PORTS) {
+        let port = &PORTS[count];
+        port.irq.store(uart.irq, Ordering::Relaxed);
+        port.base.store(uart.base, Ordering::Release);
+        if uart.base == console_base {
+            CONSOLE_PORT.store(count, Ordering::Relaxed);
+        } else {
+            crate::arch::uart::init_port(uart.base);
+        }
+        count += 1;
+    }
+    if uarts.len() > MAX_PORTS {
+        crate::kprintln!("[serial] {} UARTs found, using first {}", uarts.len(), MAX_PORTS);
+    }
+    PORT_COUNT.store(count, Ordering::Release);
+}
+
+/// 포트 수
+pub fn count() -> usize {
+    PORT_COUNT.load(Ordering::Acquire)
+}
+
+/// 콘솔 포트 번호
+pub fn console_index() -> usize {
+    CONSOLE_PORT.load(Ordering::Relaxed)
+}
+
+/// 포트 정보
+pub fn port(index: usize) -> Option<PortInfo> {
+    if index >= count() {
+        return None;
+    }
+    Some(PortInfo {
+        index,
+        base: PORTS[index].base.load(Ordering::Acquire),
+        irq: PORTS[index].irq.load(Ordering::Relaxed),
+        is_console: index == console_index(),
+    })
+}
+
+/// 모든 포트 정보
+pub fn ports() -> impl Iterator<Item = PortInfo> {
+    (0..count()).filter_map(port)
+}
+
+/// 모든 포트의 RX 인터럽트 활성화 (인터럽트 컨트롤러 초기화 후 호출)
+///
+/// IRQ를 공유하는 포트는 핸들러 하나가 모두 처리합니다.
+pub fn enable_rx_irqs() {
+    for port in ports().filter(|p| p.irq != 0) {
+        let name = alloc::format!("ttyS{}", port.index);
+        match crate::irq::request_irq(port.irq, handle_irq, &name) {
+            Ok(()) | Err(crate::irq::IrqError::AlreadyRegistered) => {}
+            Err(e) => {
+                crate::kprintln!("[serial] ttyS{}: irq {}: {}, polling", port.index, port.irq, e.as_str());
+                continue;
+            }
+        }
+        crate::arch::uart::enable_rx_irq(port.base);
+        crate::kprintln!("[serial] ttyS{}: RX interrupt enabled (irq {})", port.index, port.irq);
+    }
+}
+
+/// 수신 바이트를 포트의 수신 링에 추가 (하반부에서 호출, 가득 차면 버림)
+pub fn receive(index: usize, byte: u8) {
+    if index == console_index() {
+        console::receive(byte);
+    } else if index < MAX_PORTS {
+        let _ = RX_RINGS[index].lock().push(byte);
+    }
+}
+
+/// UART IRQ 처리 (이 IRQ를 쓰는 포트가 없으면 false)
+///
+/// FIFO를 비워 임시 버퍼에 넣고 하반부를 예약합니다.
+pub fn handle_irq(irq: u32) -> bool {
+    let mut handled = false;
+    for port in PORTS.iter().take(count()) {
+        if port.irq.load(Ordering::Relaxed) == irq {
+            let base = port.base.load(Ordering::Acquire);
+            crate::arch::uart::handle_irq(base, |byte| port.rx_stage.push(byte));
+            port.rx_tasklet.schedule();
+            handled = true;
+        }
+    }
+    handled
+}
+
+/// 하반부: 상반부가 읽어 둔 바이트를 포트의 수신 링으로 옮김 (`index` = 포트 번호)
+fn flush_rx(index: usize) {
+    while let Some(byte) = PORTS[index].rx_stage.pop() {
+        receive(index, byte);
+    }
+}
+
+/// 포트 입력 한 바이트 (non-blocking, 링이 비었으면 UART를 직접 폴링)
+pub fn getc(index: usize) -> Option<u8> {
+    if index == console_index() {
+        return console::getc();
+    }
+    let info = port(index)?;
+    let queued = RX_RINGS[index].lock().pop();
+    queued.or_else(|| crate::arch::uart::getc_at(info.base))
+}
+
+/// 포트로 바이트열 출력 (폴링)
+pub fn write(index: usize, bytes: &[u8]) {
+    if index == console_index() {
+        console::write(bytes);
+        return;
+    }
+    if let Some(info) = port(index) {
+        for &b in bytes {
+            crate::arch::uart::putc_at(info.base, b);
+        }
+    }
+}
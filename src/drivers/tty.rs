@@ -0,0 +1,361 @@
+//! TTY 줄 규칙 (line discipline)
+//!
+//! 입력 바이트를 termios 설정에 따라 처리해 읽는 쪽에 넘깁니다.
+//! 커널 셸과 `/dev/console`이 같은 콘솔 TTY(`console()`, 입력은 `console::getc()`)를 쓰고,
+//! 콘솔이 아닌 시리얼 포트는 포트마다 TTY(`serial()`, 입력은 `serial::getc()`)를 가집니다.
+//!
+//! - 정규 모드(`ICANON`): 한 줄(개행 또는 `VEOF`)이 완성되어야 읽을 수 있고,
+//!   `VERASE`/백스페이스(0x08)로 한 글자, `VKILL`로 편집 중인 줄을 지웁니다.
+//!   빈 줄에서 `VEOF`를 받으면 다음 읽기가 0(EOF)입니다.
+//! - 비정규 모드(raw): 받은 바이트를 편집 없이 넘기며, `VMIN` 바이트가 모일 때까지
+//!   기다립니다 (`VMIN` = 0이면 기다리지 않음, `VTIME`은 무시).
+//! - `ECHO`면 입력을 에코하고, `ECHOE`면 지우기를 `"\x08 \x08"`로 화면에서도 지웁니다.
+//! - `ICRNL`이면 CR을 LF로 바꿉니다.
+//!
+//! 읽기는 non-blocking이며(`Tty::read`가 `None`이면 아직 읽을 것이 없음), 기다리는 방법은
+//! 호출자가 정합니다 (`/dev/console`은 양보하며 다시 확인, 셸은 WFI).
+
+use core::mem::size_of;
+
+use crate::console;
+use crate::drivers::serial;
+use crate::fs::{VfsError, VfsResult};
+use crate::sync::Mutex;
+
+/// termios ioctl 명령 (Linux asm-generic)
+pub const TCGETS: u32 = 0x5401;
+pub const TCSETS: u32 = 0x5402;
+pub const TCSETSW: u32 = 0x5403;
+pub const TCSETSF: u32 = 0x5404;
+
+/// c_iflag: 입력 CR을 LF로 변환
+pub const ICRNL: u32 = 0o400;
+/// c_oflag: 출력 후처리 (보존만 함)
+pub const OPOST: u32 = 0o1;
+/// c_lflag: 정규 모드
+pub const ICANON: u32 = 0o2;
+/// c_lflag: 입력 에코
+pub const ECHO: u32 = 0o10;
+/// c_lflag: 지우기를 화면에서도 지움
+pub const ECHOE: u32 = 0o20;
+
+/// c_cc 인덱스
+pub const VERASE: usize = 2;
+pub const VKILL: usize = 3;
+pub const VEOF: usize = 4;
+pub const VTIME: usize = 5;
+pub const VMIN: usize = 6;
+
+/// c_cc 크기 (Linux 커널 termios)
+pub const NCCS: usize = 19;
+
+/// termios (Linux 커널 `struct termios` 레이아웃, 36바이트)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Termios {
+    pub c_iflag: u32,
+    pub c_oflag: u32,
+    pub c_cflag: u32,
+    pub c_lflag: u32,
+    pub c_line: u8,
+    pub c_cc: [u8; NCCS],
+}
+
+impl Termios {
+    /// 기본값 (정규 모드, 에코, CR → LF)
+    pub const fn cooked() -> Self {
+        let mut c_cc = [0u8; NCCS];
+        c_cc[VERASE] = 0x7F;
+        c_cc[VKILL] = 0x15; // Ctrl-U
+        c_cc[VEOF] = 0x04; // Ctrl-D
+        c_cc[VMIN] = 1;
+        Self {
+            c_iflag: ICRNL,
+            c_oflag: OPOST,
+            c_cflag: 0,
+            c_lflag: ICANON | ECHO | ECHOE,
+            c_line: 0,
+            c_cc,
+        }
+    }
+
+    /// 비정규 모드, 에코 없음, CR 그대로 (`cfmakeraw`와 같은 효과)
+    pub fn make_raw(&mut self) {
+        self.c_iflag &= !ICRNL;
+        self.c_oflag &= !OPOST;
+        self.c_lflag &= !(ICANON | ECHO | ECHOE);
+        self.c_cc[VMIN] = 1;
+        self.c_cc[VTIME] = 0;
+    }
+
+    pub fn is_canonical(&self) -> bool {
+        self.c_lflag & ICANON != 0
+    }
+}
+
+/// 입력 버퍼 크기 (정규 모드에서 개행 한 바이트 포함)
+const TTY_BUF_SIZE: usize = 256;
+
+/// TTY가 붙은 회선
+#[derive(Debug, Clone, Copy)]
+enum Line {
+    /// 콘솔 (UART 또는 hvc0)
+    Console,
+    /// 시리얼 포트 (`drivers::serial` 포트 번호)
+    Serial(usize),
+}
+
+impl Line {
+    fn getc(self) -> Option<u8> {
+        match self {
+            Line::Console => console::getc(),
+            Line::Serial(index) => serial::getc(index),
+        }
+    }
+
+    fn write(self, bytes: &[u8]) {
+        match self {
+            Line::Console => console::write(bytes),
+            Line::Serial(index) => serial::write(index, bytes),
+        }
+    }
+}
+
+/// TTY 상태
+///
+/// `buffer[..ready]`는 읽을 수 있는 바이트, `buffer[ready..len]`은 편집 중인 줄입니다
+/// (비정규 모드에서는 항상 `ready == len`).
+struct TtyState {
+    line: Line,
+    termios: Termios,
+    buffer: [u8; TTY_BUF_SIZE],
+    len: usize,
+    ready: usize,
+    /// 빈 줄에서 `VEOF`를 받음 (다음 읽기가 0을 반환)
+    eof: bool,
+}
+
+impl TtyState {
+    const fn new(line: Line) -> Self {
+        Self {
+            line,
+            termios: Termios::cooked(),
+            buffer: [0; TTY_BUF_SIZE],
+            len: 0,
+            ready: 0,
+            eof: false,
+        }
+    }
+
+    fn lflag(&self, flag: u32) -> bool {
+        self.termios.c_lflag & flag != 0
+    }
+
+    fn echo(&self, bytes: &[u8]) {
+        if self.lflag(ECHO) {
+            self.line.write(bytes);
+        }
+    }
+
+    /// 편집 중인 마지막 바이트 지우기
+    fn erase(&mut self) -> bool {
+        if self.len == self.ready {
+            return false;
+        }
+        self.len -= 1;
+        if self.lflag(ECHOE) {
+            self.echo(b"\x08 \x08");
+        }
+        true
+    }
+
+    /// 입력 한 바이트 처리
+    fn input(&mut self, mut c: u8) {
+        if c == b'\r' && self.termios.c_iflag & ICRNL != 0 {
+            c = b'\n';
+        }
+
+        if !self.termios.is_canonical() {
+            if self.len < TTY_BUF_SIZE {
+                self.buffer[self.len] = c;
+                self.len += 1;
+                self.ready = self.len;
+                self.echo(&[c]);
+            }
+            return;
+        }
+
+        let cc = self.termios.c_cc;
+        if c == b'\n' {
+            // 개행 자리는 항상 남겨 둠
+            self.buffer[self.len] = b'\n';
+            self.len += 1;
+            self.ready = self.len;
+            self.echo(b"\n");
+        } else if c == cc[VERASE] || c == 0x08 {
+            self.erase();
+        } else if c == cc[VKILL] {
+            while self.erase() {}
+        } else if c == cc[VEOF] {
+            // 빈 줄이면 EOF, 아니면 개행 없이 지금까지의 줄을 넘김
+            self.eof = self.len == 0;
+            self.ready = self.len;
+        } else if (c == b'\t' || (32..127).contains(&c)) && self.len < TTY_BUF_SIZE - 1 {
+            self.buffer[self.len] = c;
+            self.len += 1;
+            self.echo(&[c]);
+        }
+    }
+
+    /// 지금 읽을 수 있는지 (정규 모드는 완성된 줄, 비정규 모드는 `VMIN` 바이트)
+    fn readable(&self) -> bool {
+        if self.termios.is_canonical() {
+            self.ready > 0 || self.eof
+        } else {
+            self.ready >= self.termios.c_cc[VMIN] as usize
+        }
+    }
+
+    /// 읽을 수 있는 바이트를 `buf`로 꺼냄
+    fn take(&mut self, buf: &mut [u8]) -> usize {
+        let n = buf.len().min(self.ready);
+        buf[..n].copy_from_slice(&self.buffer[..n]);
+        self.buffer.copy_within(n..self.len, 0);
+        self.len -= n;
+        self.ready -= n;
+        if n == 0 {
+            self.eof = false;
+        }
+        n
+    }
+
+    /// 모드 변경
+    fn set_termios(&mut self, termios: Termios, flush: bool) {
+        if flush {
+            self.len = 0;
+            self.ready = 0;
+            self.eof = false;
+        }
+        if !termios.is_canonical() {
+            // 편집 중이던 줄은 그대로 읽을 수 있게 됨
+            self.ready = self.len;
+        }
+        self.termios = termios;
+    }
+}
+
+/// TTY (줄 규칙 + termios)
+pub struct Tty {
+    state: Mutex<TtyState>,
+}
+
+static CONSOLE_TTY: Tty = Tty::new(Line::Console);
+
+/// 콘솔이 아닌 시리얼 포트의 TTY (포트 번호 순)
+static SERIAL_TTYS: [Tty; serial::MAX_PORTS] = [
+    Tty::new(Line::Serial(0)),
+    Tty::new(Line::Serial(1)),
+    Tty::new(Line::Serial(2)),
+    Tty::new(Line::Serial(3)),
+];
+
+/// 콘솔 TTY (커널 셸, `/dev/console`)
+pub fn console() -> &'static Tty {
+    &CONSOLE_TTY
+}
+
+/// 시리얼 포트의 TTY (`/dev/ttyS<n>`, 콘솔 포트는 `console()`과 같음)
+pub fn serial(index: usize) -> Option<&'static Tty> {
+    if index >= serial::count() {
+        None
+    } else if index == serial::console_index() {
+        Some(&CONSOLE_TTY)
+    } else {
+        Some(&SERIAL_TTYS[index])
+    }
+}
+
+impl Tty {
+    const fn new(line: Line) -> Self {
+        Self {
+            state: Mutex::new(TtyState::new(line)),
+        }
+    }
+
+    /// 읽기 (non-blocking)
+    ///
+    /// 받은 입력을 줄 규칙으로 처리한 뒤 읽을 수 있으면 최대 `buf.len()` 바이트를 돌려줍니다.
+    /// 정규 모드에서 줄이 `buf`보다 길면 나머지는 다음 읽기로 넘어가고, 빈 줄의 `VEOF`는
+    /// `Some(0)`입니다. 아직 읽을 것이 없으면 `None`입니다.
+    pub fn read(&self, buf: &mut [u8]) -> Option<usize> {
+        let mut state = self.state.lock();
+        Self::fill(&mut state);
+        if state.readable() {
+            Some(state.take(buf))
+        } else {
+            None
+        }
+    }
+
+    /// 지금 읽으면 바로 돌아오는지 (`poll`의 `POLLIN`)
+    pub fn poll_readable(&self) -> bool {
+        let mut state = self.state.lock();
+        Self::fill(&mut state);
+        state.readable()
+    }
+
+    /// 받은 입력 처리 (정규 모드는 한 줄까지만, 비정규 모드는 버퍼가 찰 때까지)
+    fn fill(state: &mut TtyState) {
+        loop {
+            let done = if state.termios.is_canonical() {
+                state.readable()
+            } else {
+                state.len == TTY_BUF_SIZE
+            };
+            if done {
+                break;
+            }
+            match state.line.getc() {
+                Some(c) => state.input(c),
+                None => break,
+            }
+        }
+    }
+
+    /// 현재 termios
+    pub fn termios(&self) -> Termios {
+        self.state.lock().termios
+    }
+
+    /// termios 변경 (`flush`면 수신 링에 남은 것까지 아직 읽지 않은 입력을 버림)
+    pub fn set_termios(&self, termios: Termios, flush: bool) {
+        let mut state = self.state.lock();
+        if flush {
+            while state.line.getc().is_some() {}
+        }
+        state.set_termios(termios, flush);
+    }
+
+    /// termios ioctl (`arg`는 호출자 주소 공간의 `Termios` 포인터)
+    ///
+    /// # Errors
+    /// * `InvalidArgument` - `arg`가 널
+    /// * `NotATty` - termios 명령이 아님
+    pub fn ioctl(&self, cmd: u32, arg: usize) -> VfsResult<usize> {
+        if !matches!(cmd, TCGETS | TCSETS | TCSETSW | TCSETSF) {
+            return Err(VfsError::NotATty);
+        }
+        if arg == 0 {
+            return Err(VfsError::InvalidArgument);
+        }
+        let ptr = arg as *mut Termios;
+        match cmd {
+            TCGETS => unsafe { ptr.write_unaligned(self.termios()) },
+            // 출력은 동기식이라 TCSETSW(출력 비운 뒤 적용)는 TCSETS와 같음
+            _ => self.set_termios(unsafe { ptr.read_unaligned() }, cmd == TCSETSF),
+        }
+        Ok(0)
+    }
+}
+
+const _: () = assert!(size_of::<Termios>() == 36);
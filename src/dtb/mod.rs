@@ -53,6 +53,42 @@ pub struct MemoryRegion {
     pub size: u64,
 }
 
+/// 노드가 자식에게 주는 주소 형식 (`#address-cells`, `#size-cells`, `ranges`)
+#[derive(Debug, Clone)]
+struct Bus {
+    address_cells: u32,
+    size_cells: u32,
+    /// (자식 주소, 부모 주소, 크기). 비어 있으면 그대로 (`ranges;` 또는 속성 없음)
+    ranges: Vec<(u64, u64, u64)>,
+}
+
+impl Bus {
+    /// 루트 노드 기본값 (DTB 사양)
+    const ROOT_DEFAULT: Bus = Bus { address_cells: 2, size_cells: 1, ranges: Vec::new() };
+
+    /// 자식 노드의 기본값: 주소 형식은 물려받고 변환은 없음
+    fn inherit(&self) -> Bus {
+        Bus { address_cells: self.address_cells, size_cells: self.size_cells, ranges: Vec::new() }
+    }
+
+    /// 버스 주소를 조상 버스들의 `ranges`로 CPU 물리 주소로 변환 (`ancestors`는 루트부터)
+    ///
+    /// 라즈베리 파이의 `/soc`처럼 자식이 버스 주소(0x7e...)를 쓰는 경우입니다. 맞는 범위가
+    /// 없으면 그대로 둡니다.
+    fn translate(ancestors: &[Bus], mut addr: u64) -> u64 {
+        for bus in ancestors.iter().rev() {
+            if let Some(&(child, parent, _)) = bus
+                .ranges
+                .iter()
+                .find(|&&(child, _, size)| addr >= child && addr - child < size)
+            {
+                addr = addr - child + parent;
+            }
+        }
+        addr
+    }
+}
+
 /// 디바이스 정보 (드라이버에 전달)
 #[derive(Debug, Clone)]
 pub struct DeviceInfo {
@@ -70,6 +106,8 @@ pub struct DeviceInfo {
     pub compatible: alloc::string::String,
     /// clock-frequency (있는 경우)
     pub clock_frequency: Option<u32>,
+    /// DMA 캐시 일관성 (`dma-coherent`면 true, `dma-noncoherent`면 false, 둘 다 없으면 None)
+    pub dma_coherent: Option<bool>,
 }
 
 impl DeviceInfo {
@@ -83,6 +121,7 @@ impl DeviceInfo {
             interrupts: alloc::vec::Vec::new(),
             compatible: alloc::string::String::new(),
             clock_frequency: None,
+            dma_coherent: None,
         }
     }
 }
@@ -122,6 +161,16 @@ impl DeviceTree {
         })
     }
 
+    /// DTB 시작 주소
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    /// DTB 전체 크기 (바이트)
+    pub fn total_size(&self) -> usize {
+        self.header.totalsize as usize
+    }
+
     /// 헤더 읽기
     unsafe fn read_header(base: usize) -> Result<FdtHeader, DtbError> {
         let ptr = base as *const u32;
@@ -182,19 +231,31 @@ impl DeviceTree {
     }
 
     /// 메모리 영역 찾기 (/memory 노드의 reg 프로퍼티)
+    ///
+    /// RAM 뱅크가 여러 개이면 시작 주소가 가장 낮은 뱅크 (커널이 올라간 뱅크)를
+    /// 돌려줍니다. 전체 뱅크는 `for_each_memory()`로 순회합니다.
     pub fn get_memory(&self) -> Result<MemoryRegion, DtbError> {
-        unsafe { self.find_memory_region() }
+        let mut lowest: Option<MemoryRegion> = None;
+        self.for_each_memory(|region| {
+            if lowest.map_or(true, |l| region.base < l.base) {
+                lowest = Some(region);
+            }
+        });
+        lowest.ok_or(DtbError::NodeNotFound)
     }
 
-    /// /memory 노드에서 메모리 정보 추출
-    unsafe fn find_memory_region(&self) -> Result<MemoryRegion, DtbError> {
+    /// 모든 메모리 뱅크 순회
+    ///
+    /// `memory`/`memory@...` 노드가 여러 개이거나 `reg`에 (address, size) 쌍이
+    /// 여러 개 있으면 각각 넘깁니다. 힙 초기화 전에 호출되므로 할당하지 않습니다.
+    pub fn for_each_memory(&self, mut f: impl FnMut(MemoryRegion)) {
         unsafe {
-            let mut offset = 0usize;
             let struct_base = self.struct_base();
-            let mut in_memory_node = false;
-            let mut address_cells: u32 = 2; // 기본값
-            let mut size_cells: u32 = 1; // 기본값
+            let mut offset = 0usize;
             let mut depth = 0;
+            let mut in_memory_node = false;
+            let mut address_cells: u32 = 2;
+            let mut size_cells: u32 = 1;
 
             loop {
                 let token_ptr = (struct_base + offset) as *const u32;
@@ -203,13 +264,9 @@ impl DeviceTree {
 
                 match token {
                     FDT_BEGIN_NODE => {
-                        // 노드 이름 읽기
                         let name_ptr = (struct_base + offset) as *const u8;
                         let name = self.read_cstring(name_ptr);
-                        let name_len = name.len() + 1; // null terminator 포함
-                        offset = Self::align4(offset + name_len);
-
-                        // memory 또는 memory@xxxx 노드 확인
+                        offset = Self::align4(offset + name.len() + 1);
                         if depth == 1 && (name == "memory" || name.starts_with("memory@")) {
                             in_memory_node = true;
                         }
@@ -220,6 +277,9 @@ impl DeviceTree {
                         if depth == 1 {
                             in_memory_node = false;
                         }
+                        if depth == 0 {
+                            return;
+                        }
                     }
                     FDT_PROP => {
                         let len =
@@ -232,7 +292,6 @@ impl DeviceTree {
                         let prop_name = self.get_string(nameoff);
                         let prop_data = (struct_base + offset) as *const u8;
 
-                        // 루트 노드에서 #address-cells, #size-cells 읽기
                         if depth == 1 {
                             if prop_name == "#address-cells" && len == 4 {
                                 address_cells =
@@ -243,32 +302,148 @@ impl DeviceTree {
                             }
                         }
 
-                        // memory 노드의 reg 프로퍼티
-                        if in_memory_node && prop_name == "reg" {
-                            let base = self.read_cells(prop_data, address_cells);
-                            let size = self.read_cells(
-                                prop_data.add((address_cells * 4) as usize),
-                                size_cells,
-                            );
-                            return Ok(MemoryRegion { base, size });
+                        if in_memory_node && depth == 2 && prop_name == "reg" {
+                            let entry_len = ((address_cells + size_cells) * 4) as usize;
+                            if entry_len > 0 {
+                                for i in 0..len as usize / entry_len {
+                                    let entry = prop_data.add(i * entry_len);
+                                    let base = self.read_cells(entry, address_cells);
+                                    let size = self.read_cells(
+                                        entry.add((address_cells * 4) as usize),
+                                        size_cells,
+                                    );
+                                    if size > 0 {
+                                        f(MemoryRegion { base, size });
+                                    }
+                                }
+                            }
                         }
 
                         offset = Self::align4(offset + len as usize);
                     }
-                    FDT_NOP => {
-                        // 무시
+                    FDT_NOP => {}
+                    FDT_END => return,
+                    _ => return,
+                }
+            }
+        }
+    }
+
+    /// 예약 메모리 영역 순회
+    ///
+    /// 메모리 예약 블록(`/memreserve/`)과 /reserved-memory 자식 노드의 `reg`를
+    /// 차례로 넘깁니다. 힙 초기화 전에 호출되므로 할당하지 않습니다. `reg` 없이
+    /// `size`만 있는 동적 할당 요청 노드는 건너뜁니다.
+    ///
+    /// # Arguments
+    /// * `f` - (이름, 영역) 콜백. 예약 블록 항목의 이름은 `"memreserve"`
+    pub fn for_each_reserved(&self, mut f: impl FnMut(&str, MemoryRegion)) {
+        unsafe {
+            self.scan_mem_rsvmap(&mut f);
+            self.scan_reserved_memory(&mut f);
+        }
+    }
+
+    /// 메모리 예약 블록 스캔 ((address, size) u64 쌍, (0, 0)에서 끝)
+    unsafe fn scan_mem_rsvmap(&self, f: &mut impl FnMut(&str, MemoryRegion)) {
+        unsafe {
+            let mut ptr = (self.base + self.header.off_mem_rsvmap as usize) as *const u8;
+            loop {
+                let base = self.read_cells(ptr, 2);
+                let size = self.read_cells(ptr.add(8), 2);
+                if base == 0 && size == 0 {
+                    return;
+                }
+                f("memreserve", MemoryRegion { base, size });
+                ptr = ptr.add(16);
+            }
+        }
+    }
+
+    /// /reserved-memory 노드 스캔
+    unsafe fn scan_reserved_memory(&self, f: &mut impl FnMut(&str, MemoryRegion)) {
+        unsafe {
+            let struct_base = self.struct_base();
+            let mut offset = 0usize;
+            let mut depth = 0;
+            let mut in_reserved = false;
+            let mut node_name = "";
+            // 루트 기본값, /reserved-memory에 자체 값이 있으면 덮어씀
+            let mut address_cells: u32 = 2;
+            let mut size_cells: u32 = 1;
+
+            loop {
+                let token_ptr = (struct_base + offset) as *const u32;
+                let token = u32::from_be(token_ptr.read_volatile());
+                offset += 4;
+
+                match token {
+                    FDT_BEGIN_NODE => {
+                        let name_ptr = (struct_base + offset) as *const u8;
+                        let name = self.read_cstring(name_ptr);
+                        offset = Self::align4(offset + name.len() + 1);
+                        depth += 1;
+                        if depth == 2 && name == "reserved-memory" {
+                            in_reserved = true;
+                        }
+                        if depth == 3 {
+                            node_name = name;
+                        }
                     }
-                    FDT_END => {
-                        break;
+                    FDT_END_NODE => {
+                        if depth == 2 && in_reserved {
+                            return;
+                        }
+                        depth -= 1;
+                        if depth == 0 {
+                            return;
+                        }
                     }
-                    _ => {
-                        // 알 수 없는 토큰
-                        break;
+                    FDT_PROP => {
+                        let len =
+                            u32::from_be(((struct_base + offset) as *const u32).read_volatile());
+                        offset += 4;
+                        let nameoff =
+                            u32::from_be(((struct_base + offset) as *const u32).read_volatile());
+                        offset += 4;
+
+                        let prop_name = self.get_string(nameoff);
+                        let prop_data = (struct_base + offset) as *const u8;
+
+                        // 루트 또는 /reserved-memory의 셀 크기
+                        if depth == 1 || (depth == 2 && in_reserved) {
+                            if prop_name == "#address-cells" && len == 4 {
+                                address_cells =
+                                    u32::from_be((prop_data as *const u32).read_volatile());
+                            } else if prop_name == "#size-cells" && len == 4 {
+                                size_cells =
+                                    u32::from_be((prop_data as *const u32).read_volatile());
+                            }
+                        }
+
+                        // 자식 노드의 reg (여러 (address, size) 쌍일 수 있음)
+                        if depth == 3 && in_reserved && prop_name == "reg" {
+                            let entry_len = ((address_cells + size_cells) * 4) as usize;
+                            if entry_len > 0 {
+                                for i in 0..len as usize / entry_len {
+                                    let entry = prop_data.add(i * entry_len);
+                                    let base = self.read_cells(entry, address_cells);
+                                    let size = self.read_cells(
+                                        entry.add((address_cells * 4) as usize),
+                                        size_cells,
+                                    );
+                                    f(node_name, MemoryRegion { base, size });
+                                }
+                            }
+                        }
+
+                        offset = Self::align4(offset + len as usize);
                     }
+                    FDT_NOP => {}
+                    FDT_END => return,
+                    _ => return,
                 }
             }
-
-            Err(DtbError::NodeNotFound)
         }
     }
 
@@ -344,12 +519,10 @@ impl DeviceTree {
 
             // 현재 노드 스택 (이름, 시작 오프셋)
             let mut node_stack: Vec<(String, usize)> = Vec::new();
+            // 노드마다 자식 주소 형식 (node_stack과 같은 깊이)
+            let mut bus_stack: Vec<Bus> = Vec::new();
 
-            // 루트의 #address-cells, #size-cells
-            let mut root_address_cells: u32 = 2;
-            let mut root_size_cells: u32 = 1;
-
-            // 현재 파싱 중인 노드 정보
+            // 현재 파싱 중인 노드 정보 (reg는 부모의 #address-cells, #size-cells로 읽음)
             let mut current_info: Option<DeviceInfo> = None;
             let mut current_address_cells: u32 = 2;
             let mut current_size_cells: u32 = 1;
@@ -366,19 +539,29 @@ impl DeviceTree {
                         let name_len = name.len() + 1;
                         offset = Self::align4(offset + name_len);
 
+                        // 자식 주소 형식은 따로 적지 않으면 부모 것을 물려받음
+                        let parent = bus_stack.last().map_or(Bus::ROOT_DEFAULT, Bus::inherit);
+
                         // 루트 노드가 아닌 경우에만 처리
                         if depth > 0 {
                             current_info = Some(DeviceInfo::new(name));
-                            current_address_cells = root_address_cells;
-                            current_size_cells = root_size_cells;
+                            current_address_cells = parent.address_cells;
+                            current_size_cells = parent.size_cells;
                         }
 
+                        bus_stack.push(parent);
                         node_stack.push((String::from(name), offset));
                         depth += 1;
                     }
                     FDT_END_NODE => {
                         // 노드 종료 시 콜백 호출
-                        if let Some(info) = current_info.take() {
+                        if let Some(mut info) = current_info.take() {
+                            // 조상 버스의 ranges로 CPU 물리 주소로 변환
+                            let ancestors = &bus_stack[..bus_stack.len().saturating_sub(1)];
+                            info.reg_base = Bus::translate(ancestors, info.reg_base);
+                            for (base, _) in info.reg_extra.iter_mut() {
+                                *base = Bus::translate(ancestors, *base);
+                            }
                             // reg가 있는 디바이스 노드만 콜백
                             if info.reg_base != 0 || !info.compatible.is_empty() {
                                 callback(&info);
@@ -387,6 +570,7 @@ impl DeviceTree {
 
                         depth -= 1;
                         node_stack.pop();
+                        bus_stack.pop();
                     }
                     FDT_PROP => {
                         let len =
@@ -399,17 +583,36 @@ impl DeviceTree {
                         let prop_name = self.get_string(nameoff);
                         let prop_data = (struct_base + offset) as *const u8;
 
-                        // 루트 노드의 #address-cells, #size-cells
-                        if depth == 1 {
-                            if prop_name == "#address-cells" && len == 4 {
-                                root_address_cells =
+                        // 이 노드의 자식 주소 형식과 주소 변환
+                        let depth_bus = bus_stack.len();
+                        match prop_name {
+                            "#address-cells" if len == 4 => {
+                                bus_stack[depth_bus - 1].address_cells =
                                     u32::from_be((prop_data as *const u32).read_volatile());
-                                current_address_cells = root_address_cells;
-                            } else if prop_name == "#size-cells" && len == 4 {
-                                root_size_cells =
+                            }
+                            "#size-cells" if len == 4 => {
+                                bus_stack[depth_bus - 1].size_cells =
                                     u32::from_be((prop_data as *const u32).read_volatile());
-                                current_size_cells = root_size_cells;
                             }
+                            "ranges" if depth_bus >= 2 => {
+                                let parent_cells = bus_stack[depth_bus - 2].address_cells;
+                                let bus = &bus_stack[depth_bus - 1];
+                                let (child_cells, size_cells) = (bus.address_cells, bus.size_cells);
+                                let entry_size = (child_cells + parent_cells + size_cells) as usize * 4;
+                                let mut ranges = Vec::new();
+                                for i in 0..len as usize / entry_size.max(1) {
+                                    let entry = prop_data.add(i * entry_size);
+                                    let child = self.read_cells(entry, child_cells);
+                                    let parent = self.read_cells(entry.add(child_cells as usize * 4), parent_cells);
+                                    let size = self.read_cells(
+                                        entry.add((child_cells + parent_cells) as usize * 4),
+                                        size_cells,
+                                    );
+                                    ranges.push((child, parent, size));
+                                }
+                                bus_stack[depth_bus - 1].ranges = ranges;
+                            }
+                            _ => {}
                         }
 
                         // 현재 노드의 프로퍼티 파싱
@@ -463,18 +666,8 @@ impl DeviceTree {
                                         ));
                                     }
                                 }
-                                "#address-cells" => {
-                                    if len == 4 {
-                                        current_address_cells =
-                                            u32::from_be((prop_data as *const u32).read_volatile());
-                                    }
-                                }
-                                "#size-cells" => {
-                                    if len == 4 {
-                                        current_size_cells =
-                                            u32::from_be((prop_data as *const u32).read_volatile());
-                                    }
-                                }
+                                "dma-coherent" => info.dma_coherent = Some(true),
+                                "dma-noncoherent" => info.dma_coherent = Some(false),
                                 _ => {}
                             }
                         }
@@ -534,12 +727,23 @@ impl DeviceTree {
                     GicVersion::V2
                 };
 
-                // GIC는 여러 개의 reg 영역을 가짐
-                // GICv2: [0]=GICD, [1]=GICC
-                // GICv3: [0]=GICD, [1]=GICR
-                let cpu_interface_base = info.reg_extra.first().map(|(b, _)| *b).unwrap_or(0);
-                let redistributor_base = if version == GicVersion::V3 {
-                    info.reg_extra.get(1).map(|(b, _)| *b)
+                // GIC는 여러 개의 reg 영역을 가짐 (reg_base가 첫 번째, reg_extra가 나머지)
+                // GICv2: GICD, GICC
+                // GICv3: GICD, GICR, (GICC, GICH, GICV - 레거시/가상화, 선택)
+                let (cpu_interface_base, redistributor) = if version == GicVersion::V3 {
+                    (
+                        info.reg_extra.get(1).map(|(b, _)| *b).unwrap_or(0),
+                        info.reg_extra.first().copied(),
+                    )
+                } else {
+                    (info.reg_extra.first().map(|(b, _)| *b).unwrap_or(0), None)
+                };
+
+                // GICv3 ITS는 GIC 노드의 자식 노드
+                let its_base = if version == GicVersion::V3 {
+                    self.find_compatible("arm,gic-v3-its")
+                        .first()
+                        .map(|its| its.reg_base)
                 } else {
                     None
                 };
@@ -547,7 +751,9 @@ impl DeviceTree {
                 return Some(GicInfo {
                     distributor_base: info.reg_base,
                     cpu_interface_base,
-                    redistributor_base,
+                    redistributor_base: redistributor.map(|(b, _)| b),
+                    redistributor_size: redistributor.map_or(0, |(_, s)| s),
+                    its_base,
                     version,
                 });
             }
@@ -595,8 +801,9 @@ impl DeviceTree {
 
     /// UART 찾기
     ///
-    /// PL011 (ARM) 또는 NS16550A (일반) UART를 찾음
-    pub fn find_uart(&self) -> Option<UartInfo> {
+    /// PL011 (ARM) 또는 NS16550A (일반) UART를 모두 찾아 베이스 주소 순으로 반환
+    /// (먼저 일치한 compatible 하나만 사용, `arm,primecell`은 PL011이 없을 때만)
+    pub fn find_uarts(&self) -> Vec<UartInfo> {
         const UART_COMPATIBLES: &[&str] = &[
             "arm,pl011",
             "arm,primecell",
@@ -606,20 +813,23 @@ impl DeviceTree {
         ];
 
         for compat in UART_COMPATIBLES {
-            let devices = self.find_compatible(compat);
-            if let Some(info) = devices.into_iter().next() {
-                // 첫 번째 인터럽트가 IRQ 번호
-                let irq = info.interrupts.first().copied().unwrap_or(0);
-
-                return Some(UartInfo {
+            let mut uarts: Vec<UartInfo> = self
+                .find_compatible(compat)
+                .into_iter()
+                .map(|info| UartInfo {
+                    compatible: compat,
                     base: info.reg_base,
                     size: info.reg_size,
-                    irq,
+                    interrupts: info.interrupts,
                     clock_freq: info.clock_frequency.unwrap_or(0),
-                });
+                })
+                .collect();
+            if !uarts.is_empty() {
+                uarts.sort_by_key(|u| u.base);
+                return uarts;
             }
         }
-        None
+        Vec::new()
     }
 
     /// CPU 개수 세기
@@ -715,6 +925,279 @@ impl DeviceTree {
             }
         }
     }
+
+    /// /chosen 노드의 bootargs 읽기
+    ///
+    /// QEMU `-append` 옵션으로 전달된 커널 명령줄입니다.
+    pub fn get_bootargs(&self) -> Option<String> {
+        let data = unsafe { self.scan_chosen_property("bootargs")? };
+        let s = core::str::from_utf8(data).ok()?;
+        Some(String::from(s.trim_end_matches('\0')))
+    }
+
+    /// /chosen 노드의 initrd 영역 읽기 (`linux,initrd-start`/`linux,initrd-end`)
+    ///
+    /// QEMU `-initrd` 옵션이나 부트로더가 올려 둔 initramfs의 물리 주소 범위입니다.
+    /// 힙 초기화 전에도 호출되므로 할당하지 않습니다.
+    ///
+    /// # Returns
+    /// `(start, end)` 물리 주소 (end는 끝 다음 바이트)
+    pub fn get_initrd(&self) -> Option<(usize, usize)> {
+        let start = unsafe { Self::read_be_cell(self.scan_chosen_property("linux,initrd-start")?)? };
+        let end = unsafe { Self::read_be_cell(self.scan_chosen_property("linux,initrd-end")?)? };
+        if end <= start {
+            return None;
+        }
+        Some((start, end))
+    }
+
+    /// 4바이트 또는 8바이트 빅 엔디언 정수 속성 읽기
+    fn read_be_cell(data: &[u8]) -> Option<usize> {
+        match data.len() {
+            4 => Some(u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize),
+            8 => {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(data);
+                Some(u64::from_be_bytes(bytes) as usize)
+            }
+            _ => None,
+        }
+    }
+
+    /// 첫 번째 hart(`/cpus/cpu@0`)의 `riscv,isa` 속성 (예: "rv64imafdch_zicsr_zifencei")
+    ///
+    /// S-mode에서는 misa CSR을 읽을 수 없으므로 ISA를 DTB에서 얻습니다.
+    #[cfg_attr(not(feature = "riscv_smode"), allow(dead_code))]
+    pub fn get_cpu_isa(&self) -> Option<String> {
+        let data = unsafe { self.scan_node_property(3, "cpu@0", "riscv,isa")? };
+        let s = core::str::from_utf8(data).ok()?;
+        Some(String::from(s.trim_end_matches('\0')))
+    }
+
+    /// PSCI 노드 찾기 (`arm,psci`, `arm,psci-0.2`, `arm,psci-1.0`)
+    ///
+    /// 호출 방식(`method`)과 PSCI 0.1의 함수 ID 속성을 읽습니다.
+    #[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+    pub fn find_psci(&self) -> Option<PsciInfo> {
+        let mut result = None;
+        unsafe {
+            self.for_each_node(|_, props| {
+                let Some(compatible) = Self::prop(props, "compatible").map(Self::string_list) else {
+                    return;
+                };
+                if result.is_some() || !compatible.iter().any(|c| c.starts_with("arm,psci")) {
+                    return;
+                }
+                let id = |name| Self::prop(props, name).and_then(Self::read_be_cell).map(|v| v as u32);
+                result = Some(PsciInfo {
+                    compatible,
+                    method: Self::prop(props, "method").and_then(Self::string),
+                    cpu_on: id("cpu_on"),
+                    cpu_off: id("cpu_off"),
+                    cpu_suspend: id("cpu_suspend"),
+                });
+            });
+        }
+        result
+    }
+
+    /// `/cpus` 아래 CPU 노드들 (DTB 순서)
+    #[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+    pub fn find_cpus(&self) -> Vec<CpuInfo> {
+        let mut cpus = Vec::new();
+        unsafe {
+            self.for_each_node(|path, props| {
+                let is_cpu = Self::prop(props, "device_type").and_then(Self::string).as_deref() == Some("cpu");
+                if path.len() != 3 || path[1] != "cpus" || !is_cpu {
+                    return;
+                }
+                let Some(mpidr) = Self::prop(props, "reg").and_then(Self::read_be_cell) else {
+                    return;
+                };
+                let idle_states = Self::prop(props, "cpu-idle-states")
+                    .map(|d| d.chunks_exact(4).map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]])).collect())
+                    .unwrap_or_default();
+                cpus.push(CpuInfo {
+                    name: String::from(path[2]),
+                    mpidr: mpidr as u64,
+                    enable_method: Self::prop(props, "enable-method").and_then(Self::string),
+                    idle_states,
+                });
+            });
+        }
+        cpus
+    }
+
+    /// `arm,idle-state` 노드들 (`/cpus/idle-states` 아래, PSCI 진입 파라미터가 있는 것만)
+    #[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+    pub fn find_idle_states(&self) -> Vec<IdleStateInfo> {
+        let mut states = Vec::new();
+        unsafe {
+            self.for_each_node(|path, props| {
+                let Some(compatible) = Self::prop(props, "compatible").map(Self::string_list) else {
+                    return;
+                };
+                if !compatible.iter().any(|c| c == "arm,idle-state") {
+                    return;
+                }
+                let cell = |name| Self::prop(props, name).and_then(Self::read_be_cell).map(|v| v as u32);
+                let Some(psci_param) = cell("arm,psci-suspend-param") else {
+                    return;
+                };
+                states.push(IdleStateInfo {
+                    name: String::from(*path.last().unwrap_or(&"")),
+                    phandle: cell("phandle").unwrap_or(0),
+                    psci_param,
+                    exit_latency_us: cell("exit-latency-us").unwrap_or(0),
+                    min_residency_us: cell("min-residency-us").unwrap_or(0),
+                    local_timer_stop: Self::prop(props, "local-timer-stop").is_some(),
+                });
+            });
+        }
+        states
+    }
+
+    /// 속성 목록에서 값 찾기
+    #[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+    fn prop(props: &[(&str, &'static [u8])], name: &str) -> Option<&'static [u8]> {
+        props.iter().find(|(n, _)| *n == name).map(|(_, data)| *data)
+    }
+
+    /// 문자열 속성 (끝의 null 제외)
+    #[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+    fn string(data: &[u8]) -> Option<String> {
+        let s = core::str::from_utf8(data).ok()?;
+        Some(String::from(s.trim_end_matches('\0')))
+    }
+
+    /// null로 구분된 문자열 목록 속성 (`compatible` 등)
+    #[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+    fn string_list(data: &[u8]) -> Vec<String> {
+        core::str::from_utf8(data)
+            .map(|s| s.split('\0').filter(|c| !c.is_empty()).map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// 모든 노드를 끝날 때 (경로, 속성 목록)으로 콜백 (자식이 부모보다 먼저)
+    ///
+    /// 경로는 루트(`""`)부터 그 노드까지의 이름이고, 속성 값은 DTB 메모리를 그대로 가리킵니다.
+    #[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+    unsafe fn for_each_node<F>(&self, mut callback: F)
+    where
+        F: FnMut(&[&str], &[(&str, &'static [u8])]),
+    {
+        unsafe {
+            let struct_base = self.struct_base();
+            let mut offset = 0usize;
+            let mut path: Vec<&str> = Vec::new();
+            let mut props: Vec<Vec<(&str, &'static [u8])>> = Vec::new();
+
+            loop {
+                let token = u32::from_be(((struct_base + offset) as *const u32).read_volatile());
+                offset += 4;
+
+                match token {
+                    FDT_BEGIN_NODE => {
+                        let name = self.read_cstring((struct_base + offset) as *const u8);
+                        offset = Self::align4(offset + name.len() + 1);
+                        path.push(name);
+                        props.push(Vec::new());
+                    }
+                    FDT_END_NODE => {
+                        if let Some(node_props) = props.pop() {
+                            callback(&path, &node_props);
+                        }
+                        path.pop();
+                        if path.is_empty() {
+                            return;
+                        }
+                    }
+                    FDT_PROP => {
+                        let len = u32::from_be(((struct_base + offset) as *const u32).read_volatile());
+                        offset += 4;
+                        let nameoff = u32::from_be(((struct_base + offset) as *const u32).read_volatile());
+                        offset += 4;
+                        let data = core::slice::from_raw_parts((struct_base + offset) as *const u8, len as usize);
+                        if let Some(node_props) = props.last_mut() {
+                            node_props.push((self.get_string(nameoff), data));
+                        }
+                        offset = Self::align4(offset + len as usize);
+                    }
+                    FDT_NOP => {}
+                    _ => return,
+                }
+            }
+        }
+    }
+
+    /// /chosen 노드의 속성 스캔
+    ///
+    /// 속성 값은 DTB 메모리를 그대로 가리킵니다 (DTB는 부팅 내내 유지됨).
+    unsafe fn scan_chosen_property(&self, prop: &str) -> Option<&'static [u8]> {
+        // 루트의 직계 자식(depth==2)만 확인
+        unsafe { self.scan_node_property(2, "chosen", prop) }
+    }
+
+    /// `node_depth` 깊이(루트 = 1)의 `node_name` 노드에서 속성 스캔
+    unsafe fn scan_node_property(
+        &self,
+        node_depth: i32,
+        node_name: &str,
+        prop: &str,
+    ) -> Option<&'static [u8]> {
+        unsafe {
+            let struct_base = self.struct_base();
+            let mut offset = 0usize;
+            let mut depth = 0i32;
+            let mut in_node = false;
+
+            loop {
+                let token_ptr = (struct_base + offset) as *const u32;
+                let token = u32::from_be(token_ptr.read_volatile());
+                offset += 4;
+
+                match token {
+                    FDT_BEGIN_NODE => {
+                        let name_ptr = (struct_base + offset) as *const u8;
+                        let name = self.read_cstring(name_ptr);
+                        let name_len = name.len() + 1;
+                        offset = Self::align4(offset + name_len);
+                        depth += 1;
+                        if depth == node_depth {
+                            in_node = name == node_name;
+                        }
+                    }
+                    FDT_END_NODE => {
+                        if depth == node_depth && in_node {
+                            return None;
+                        }
+                        depth -= 1;
+                        if depth == 0 {
+                            return None;
+                        }
+                    }
+                    FDT_PROP => {
+                        let len =
+                            u32::from_be(((struct_base + offset) as *const u32).read_volatile());
+                        offset += 4;
+                        let nameoff =
+                            u32::from_be(((struct_base + offset) as *const u32).read_volatile());
+                        offset += 4;
+
+                        if depth == node_depth && in_node && self.get_string(nameoff) == prop {
+                            let prop_data = (struct_base + offset) as *const u8;
+                            return Some(core::slice::from_raw_parts(prop_data, len as usize));
+                        }
+
+                        offset = Self::align4(offset + len as usize);
+                    }
+                    FDT_NOP => {}
+                    FDT_END => return None,
+                    _ => return None,
+                }
+            }
+        }
+    }
 }
 
 // =========================================================================
@@ -727,6 +1210,10 @@ pub struct GicInfo {
     pub distributor_base: u64,
     pub cpu_interface_base: u64,
     pub redistributor_base: Option<u64>,
+    /// 재분배기(GICR) 영역 크기 (CPU마다 프레임 하나)
+    pub redistributor_size: u64,
+    /// GICv3 ITS (LPI 변환) 베이스
+    pub its_base: Option<u64>,
     pub version: GicVersion,
 }
 
@@ -753,12 +1240,58 @@ pub struct ClintInfo {
 /// UART 정보
 #[derive(Debug, Clone)]
 pub struct UartInfo {
+    /// 일치한 compatible 문자열
+    pub compatible: &'static str,
     pub base: u64,
     pub size: u64,
-    pub irq: u32,
+    /// DTB `interrupts` 셀 (IRQ 번호 해석은 인터럽트 컨트롤러마다 다름)
+    pub interrupts: Vec<u32>,
     pub clock_freq: u32,
 }
 
+/// PSCI 정보
+#[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+#[derive(Debug, Clone)]
+pub struct PsciInfo {
+    /// compatible 목록 (`arm,psci-1.0`, `arm,psci-0.2`, `arm,psci`)
+    pub compatible: Vec<String>,
+    /// 호출 방식 (`"smc"` 또는 `"hvc"`)
+    pub method: Option<String>,
+    /// PSCI 0.1 함수 ID (0.2부터는 표준 ID라 보통 없음)
+    pub cpu_on: Option<u32>,
+    pub cpu_off: Option<u32>,
+    pub cpu_suspend: Option<u32>,
+}
+
+/// CPU 노드 정보
+#[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+#[derive(Debug, Clone)]
+pub struct CpuInfo {
+    /// 노드 이름 (예: "cpu@1")
+    pub name: String,
+    /// `reg` (MPIDR 어피니티 필드)
+    pub mpidr: u64,
+    /// 보조 CPU를 켜는 방법 (`"psci"`, `"spin-table"` 등)
+    pub enable_method: Option<String>,
+    /// `cpu-idle-states` phandle 목록
+    pub idle_states: Vec<u32>,
+}
+
+/// CPU idle 상태 (`arm,idle-state`)
+#[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+#[derive(Debug, Clone)]
+pub struct IdleStateInfo {
+    pub name: String,
+    pub phandle: u32,
+    /// CPU_SUSPEND의 power_state 인자 (`arm,psci-suspend-param`)
+    pub psci_param: u32,
+    pub exit_latency_us: u32,
+    /// 들어갔다 나오는 비용을 메우는 최소 체류 시간 (진입/복귀 지연 포함)
+    pub min_residency_us: u32,
+    /// 이 상태에서는 CPU 로컬 타이머가 멈춤
+    pub local_timer_stop: bool,
+}
+
 /// 전역 DTB 저장소 (내부 가변성 사용)
 struct DtbHolder {
     inner: UnsafeCell<Option<DeviceTree>>,
@@ -0,0 +1,252 @@
+//! 프레임버퍼 콘솔
+//!
+//! 커널 콘솔 출력(`console::write`)을 VirtIO GPU 프레임버퍼에 글자로 그립니다.
+//! 콘솔 레지스트리에 `fb0`으로 등록되어 UART/hvc0과 함께 출력을 받습니다
+//! (`console disable fb0`으로 끌 수 있음).
+//!
+//! - 8x8 글꼴을 세로로 두 배 늘린 8x16 셀 (1280x800이면 160x50)
+//! - `\n`, `\r`, `\t`, 백스페이스를 처리하고 ANSI 이스케이프 시퀀스는 건너뜀
+//! - 마지막 줄을 넘으면 한 줄 위로 스크롤
+//! - 쓰기마다 바뀐 줄만 `virtio::gpu::flush()`로 반영 (스크롤하면 화면 전체)
+//!
+//! GPU는 부팅 후반에 초기화되므로, `init()`은 그 전까지 링 버퍼에 쌓인 부팅 로그를
+//! dmesg 형식으로 먼저 그립니다.
+
+mod font;
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::sync::IrqSpinlock;
+use crate::virtio::gpu::{self, FbInfo, Rect};
+
+/// 글꼴 세로 배율
+const SCALE_Y: usize = 2;
+/// 셀 크기 (픽셀)
+const CELL_WIDTH: usize = font::WIDTH;
+const CELL_HEIGHT: usize = font::HEIGHT * SCALE_Y;
+/// 탭 간격 (글자)
+const TAB_WIDTH: usize = 8;
+
+/// 글자색 (`0x00RRGGBB`)
+const FG_COLOR: u32 = 0x00AA_AAAA;
+/// 배경색
+const BG_COLOR: u32 = 0x0000_0000;
+
+/// 이스케이프 시퀀스 해석 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Escape {
+    None,
+    /// ESC를 받음
+    Esc,
+    /// `ESC [` 뒤 종료 바이트(0x40..=0x7E)를 기다리는 중
+    Csi,
+}
+
+/// 콘솔 상태
+struct FbCon {
+    fb: FbInfo,
+    cols: usize,
+    rows: usize,
+    col: usize,
+    row: usize,
+    escape: Escape,
+    /// 이번 쓰기에서 바뀐 줄 범위 `[first, last]`
+    dirty: Option<(usize, usize)>,
+    /// 스크롤로 화면 전체가 바뀜
+    scrolled: bool,
+}
+
+impl FbCon {
+    fn new(fb: FbInfo) -> Self {
+        Self {
+            cols: fb.width as usize / CELL_WIDTH,
+            rows: fb.height as usize / CELL_HEIGHT,
+            fb,
+            col: 0,
+            row: 0,
+            escape: Escape::None,
+            dirty: None,
+            scrolled: false,
+        }
+    }
+
+    fn pixel_ptr(&self, x: usize, y: usize) -> *mut u32 {
+        (self.fb.addr + y * self.fb.stride as usize + x * 4) as *mut u32
+    }
+
+    fn mark_dirty(&mut self, row: usize) {
+        self.dirty = Some(match self.dirty {
+            Some((first, last)) => (first.min(row), last.max(row)),
+            None => (row, row),
+        });
+    }
+
+    /// (col, row) 셀에 글자 그리기
+    fn draw_char(&mut self, col: usize, row: usize, c: u8) {
+        let glyph = font::glyph(c);
+        for (i, bits) in glyph.iter().enumerate() {
+            for dy in 0..SCALE_Y {
+                let y = row * CELL_HEIGHT + i * SCALE_Y + dy;
+                let line = self.pixel_ptr(col * CELL_WIDTH, y);
+                for x in 0..CELL_WIDTH {
+                    let color = if bits & (1 << x) != 0 { FG_COLOR } else { BG_COLOR };
+                    // Safety: 셀은 화면 안 (cols/rows는 해상도로 계산)
+                    unsafe { line.add(x).write(color) };
+                }
+            }
+        }
+        self.mark_dirty(row);
+    }
+
+    /// 한 줄(셀 높이) 지우기
+    fn clear_row(&mut self, row: usize) {
+        for y in row * CELL_HEIGHT..(row + 1) * CELL_HEIGHT {
+            let line = self.pixel_ptr(0, y);
+            for x in 0..self.fb.width as usize {
+                unsafe { line.add(x).write(BG_COLOR) };
+            }
+        }
+        self.mark_dirty(row);
+    }
+
+    /// 한 줄 위로 스크롤
+    fn scroll(&mut self) {
+        let row_bytes = CELL_HEIGHT * self.fb.stride as usize;
+        unsafe {
+            core::ptr::copy(
+                (self.fb.addr + row_bytes) as *const u8,
+                self.fb.addr as *mut u8,
+                (self.rows - 1) * row_bytes,
+            );
+        }
+        self.clear_row(self.rows - 1);
+        self.scrolled = true;
+    }
+
+    fn newline(&mut self) {
+        self.col = 0;
+        if self.row + 1 < self.rows {
+            self.row += 1;
+        } else {
+            self.scroll();
+        }
+    }
+
+    fn putc(&mut self, c: u8) {
+        match self.escape {
+            Escape::Esc => {
+                self.escape = if c == b'[' { Escape::Csi } else { Escape::None };
+                return;
+            }
+            Escape::Csi => {
+                if (0x40..=0x7E).contains(&c) {
+                    self.escape = Escape::None;
+                }
+                return;
+            }
+            Escape::None => {}
+        }
+
+        match c {
+            0x1B => self.escape = Escape::Esc,
+            b'\n' => self.newline(),
+            b'\r' => self.col = 0,
+            b'\t' => {
+                self.col = (self.col / TAB_WIDTH + 1) * TAB_WIDTH;
+                if self.col >= self.cols {
+                    self.newline();
+                }
+            }
+            0x08 | 0x7F => self.col = self.col.saturating_sub(1),
+            c if c < 0x20 => {}
+            c => {
+                if self.col >= self.cols {
+                    self.newline();
+                }
+                self.draw_char(self.col, self.row, c);
+                self.col += 1;
+            }
+        }
+    }
+
+    /// 바뀐 영역을 화면에 반영
+    fn flush(&mut self) {
+        let rect = if self.scrolled {
+            Some(self.fb.full_rect())
+        } else {
+            self.dirty.map(|(first, last)| Rect {
+                x: 0,
+                y: (first * CELL_HEIGHT) as u32,
+                width: self.fb.width,
+                height: ((last - first + 1) * CELL_HEIGHT) as u32,
+            })
+        };
+        self.dirty = None;
+        self.scrolled = false;
+        if let Some(rect) = rect {
+            gpu::flush(rect);
+        }
+    }
+}
+
+/// 콘솔 상태 (콘솔 출력은 인터럽트 컨텍스트에서도 호출됨)
+static FBCON: IrqSpinlock<Option<FbCon>> = IrqSpinlock::new(None);
+
+/// 활성화 여부 (GPU가 없을 때 락 없이 빠르게 건너뛰기 위함)
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// 프레임버퍼 콘솔이 켜져 있는지
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// 화면에 바이트열 출력
+pub fn write(bytes: &[u8]) {
+    if !is_enabled() {
+        return;
+    }
+    let mut fbcon = FBCON.lock();
+    if let Some(con) = fbcon.as_mut() {
+        for &c in bytes {
+            con.putc(c);
+        }
+        con.flush();
+    }
+}
+
+/// 콘솔 레지스트리 항목 (UART보다 낮은 우선순위, 입력 없음)
+static FB_CONSOLE: crate::console::ConsoleDriver = crate::console::ConsoleDriver {
+    name: "fb0",
+    desc: "framebuffer text console",
+    write: |bytes| {
+        write(bytes);
+        true
+    },
+    getc: None,
+};
+
+/// 프레임버퍼 콘솔 우선순위
+const PRIORITY: u8 = 5;
+
+/// 프레임버퍼 콘솔 초기화 (`virtio::gpu::init()` 이후 호출)
+///
+/// 링 버퍼의 부팅 로그를 먼저 그린 뒤 이후 콘솔 출력을 미러링합니다.
+///
+/// # Returns
+/// 프레임버퍼가 있어 콘솔을 켰으면 true
+pub fn init() -> bool {
+    let Some(fb) = gpu::framebuffer() else {
+        return false;
+    };
+    let con = FbCon::new(fb);
+    let (cols, rows) = (con.cols, con.rows);
+    *FBCON.lock() = Some(con);
+    ENABLED.store(true, Ordering::Relaxed);
+
+    // 활성화 전 부팅 로그 다시 보여주기
+    crate::log::replay(write);
+    let _ = crate::console::register(&FB_CONSOLE, PRIORITY, true);
+
+    crate::kprintln!("[fbcon] {}x{} text console on /dev/fb0", cols, rows);
+    true
+}
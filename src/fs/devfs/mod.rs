@@ -4,8 +4,18 @@
 //! - /dev/null: 모든 쓰기를 버림, 읽기 시 EOF
 //! - /dev/zero: 읽기 시 0 반환, 쓰기 무시
 //! - /dev/console: 콘솔 입출력
+//! - /dev/ttySN: 시리얼 포트 (`drivers::serial`)
+//! - /dev/kmsg: 커널 로그 링 버퍼 읽기/쓰기
+//! - /dev/input/eventN: 입력 이벤트 (input 서브시스템)
+//! - /dev/shm/<name>: 공유 메모리 세그먼트 (`ipc::shm`)
+//! - /dev/hvc0: VirtIO 콘솔 (`virtio::console`)
+//! - /dev/fb0: VirtIO GPU 프레임버퍼 (`virtio::gpu`)
+//! - /dev/watchdog: 소프트웨어 워치독 (`watchdog`)
+
+pub mod shm;
 
 use alloc::boxed::Box;
+use alloc::format;
 use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
@@ -21,6 +31,8 @@ use super::{
 pub struct DevFs {
     /// 루트 디렉토리
     root: Arc<DevFsRoot>,
+    /// 하위 디렉토리 (/dev/input 등)
+    subdirs: RwLock<Vec<(String, Arc<DevFsRoot>)>>,
 }
 
 impl DevFs {
@@ -32,8 +44,13 @@ impl DevFs {
         root.register("null", Arc::new(NullDevice));
         root.register("zero", Arc::new(ZeroDevice));
         root.register("console", Arc::new(ConsoleDevice));
+        root.register("kmsg", Arc::new(KmsgDevice));
+        root.register("shm", Arc::new(shm::ShmDir));
 
-        Arc::new(Self { root })
+        Arc::new(Self {
+            root,
+            subdirs: RwLock::new(Vec::new()),
+        })
     }
 
     /// 디바이스 등록
@@ -45,6 +62,25 @@ impl DevFs {
     pub fn unregister_device(&self, name: &str) {
         self.root.unregister(name);
     }
+
+    /// 하위 디렉토리에 디바이스 등록 (예: `input`/`event0`)
+    ///
+    /// 디렉토리가 없으면 생성합니다.
+    pub fn register_device_in(&self, dir: &str, name: &str, device: Arc<dyn VNode>) {
+        let subdir = {
+            let mut subdirs = self.subdirs.write();
+            match subdirs.iter().find(|(n, _)| n == dir) {
+                Some((_, d)) => d.clone(),
+                None => {
+                    let d = DevFsRoot::new();
+                    subdirs.push((String::from(dir), d.clone()));
+                    self.root.register(dir, d.clone());
+                    d
+                }
+            }
+        };
+        subdir.register(name, device);
+    }
 }
 
 impl FileSystem for DevFs {
@@ -68,7 +104,7 @@ impl FileSystem for DevFs {
     }
 }
 
-/// DevFS 루트 디렉토리
+/// DevFS 디렉토리 (루트 및 /dev/input 같은 하위 디렉토리)
 pub struct DevFsRoot {
     /// 디바이스 목록 - Vec으로 변경하여 BTreeMap 문제 회피
     devices: RwLock<Vec<(String, Arc<dyn VNode>)>>,
@@ -196,6 +232,9 @@ impl VNode for ZeroDevice {
 }
 
 /// /dev/console - 콘솔 입출력
+///
+/// 읽기는 콘솔 TTY 줄 규칙(`drivers::tty`)을 거칩니다. 기본인 정규 모드에서는 한 줄이
+/// 완성될 때까지 기다리며, termios ioctl(`TCGETS`/`TCSETS`)로 모드를 바꿀 수 있습니다.
 pub struct ConsoleDevice;
 
 impl VNode for ConsoleDevice {
@@ -204,9 +243,19 @@ impl VNode for ConsoleDevice {
     }
 
     fn read(&self, _offset: usize, buf: &mut [u8]) -> VfsResult<usize> {
-        // TODO: UART에서 읽기 구현
-        // 현재는 non-blocking으로 0 반환
-        Ok(0)
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            if let Some(n) = crate::drivers::tty::console().read(buf) {
+                return Ok(n);
+            }
+            if crate::proc::current_tid().is_some_and(crate::proc::signal::has_pending) {
+                return Err(VfsError::Interrupted);
+            }
+            // 입력은 UART IRQ가 수신 링에 넣으므로 양보하며 다시 확인
+            crate::proc::scheduler::schedule();
+        }
     }
 
     fn write(&self, _offset: usize, buf: &[u8]) -> VfsResult<usize> {
@@ -226,6 +275,243 @@ impl VNode for ConsoleDevice {
             ..Default::default()
         })
     }
+
+    fn ioctl(&self, cmd: u32, arg: usize) -> VfsResult<usize> {
+        crate::drivers::tty::console().ioctl(cmd, arg)
+    }
+
+    fn poll(&self, events: u16, _waiter: Option<crate::proc::Tid>) -> u16 {
+        use crate::fs::poll::{POLLIN, POLLOUT};
+
+        // 입력은 IRQ에서 들어와 대기자를 깨울 수 없으므로 등록하지 않음 (poll이 주기적으로 재확인)
+        let mut revents = events & POLLOUT;
+        if events & POLLIN != 0 && crate::drivers::tty::console().poll_readable() {
+            revents |= POLLIN;
+        }
+        revents
+    }
+}
+
+/// /dev/ttyS<n> - 시리얼 포트
+///
+/// 포트별 TTY(`drivers::tty::serial`)로 읽고 UART로 바로 씁니다. 콘솔 포트는
+/// `/dev/console`과 같은 TTY를 씁니다.
+pub struct SerialDevice {
+    index: usize,
+    tty: &'static crate::drivers::tty::Tty,
+}
+
+impl SerialDevice {
+    /// 포트가 없으면 None
+    pub fn new(index: usize) -> Option<Self> {
+        let tty = crate::drivers::tty::serial(index)?;
+        Some(Self { index, tty })
+    }
+}
+
+impl VNode for SerialDevice {
+    fn node_type(&self) -> VNodeType {
+        VNodeType::CharDevice
+    }
+
+    fn read(&self, _offset: usize, buf: &mut [u8]) -> VfsResult<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            if let Some(n) = self.tty.read(buf) {
+                return Ok(n);
+            }
+            if crate::proc::current_tid().is_some_and(crate::proc::signal::has_pending) {
+                return Err(VfsError::Interrupted);
+            }
+            crate::proc::scheduler::schedule();
+        }
+    }
+
+    fn write(&self, _offset: usize, buf: &[u8]) -> VfsResult<usize> {
+        crate::drivers::serial::write(self.index, buf);
+        Ok(buf.len())
+    }
+
+    fn stat(&self) -> VfsResult<Stat> {
+        Ok(Stat {
+            node_type: VNodeType::CharDevice,
+            mode: FileMode::new(0o660),
+            size: 0,
+            nlink: 1,
+            ..Default::default()
+        })
+    }
+
+    fn ioctl(&self, cmd: u32, arg: usize) -> VfsResult<usize> {
+        self.tty.ioctl(cmd, arg)
+    }
+
+    fn poll(&self, events: u16, _waiter: Option<crate::proc::Tid>) -> u16 {
+        use crate::fs::poll::{POLLIN, POLLOUT};
+
+        // /dev/console과 같이 IRQ 입력은 대기자를 깨울 수 없으므로 등록하지 않음
+        let mut revents = events & POLLOUT;
+        if events & POLLIN != 0 && self.tty.poll_readable() {
+            revents |= POLLIN;
+        }
+        revents
+    }
+}
+
+/// /dev/hvc0 - VirtIO 콘솔
+///
+/// 읽기는 non-blocking이며 받은 입력이 없으면 0을 반환합니다.
+pub struct HvcDevice;
+
+impl VNode for HvcDevice {
+    fn node_type(&self) -> VNodeType {
+        VNodeType::CharDevice
+    }
+
+    fn read(&self, _offset: usize, buf: &mut [u8]) -> VfsResult<usize> {
+        Ok(crate::virtio::console::read(buf))
+    }
+
+    fn write(&self, _offset: usize, buf: &[u8]) -> VfsResult<usize> {
+        if crate::virtio::console::write(buf) {
+            Ok(buf.len())
+        } else {
+            Err(VfsError::IoError)
+        }
+    }
+
+    fn stat(&self) -> VfsResult<Stat> {
+        Ok(Stat {
+            node_type: VNodeType::CharDevice,
+            mode: FileMode::new(0o620),
+            size: 0,
+            nlink: 1,
+            ..Default::default()
+        })
+    }
+}
+
+/// /dev/fb0 - VirtIO GPU 프레임버퍼
+///
+/// 오프셋은 프레임버퍼 바이트 위치입니다 (픽셀당 4바이트 `0x00RRGGBB`, 줄 간격 = 너비 × 4).
+/// `write`는 쓴 줄을 바로 화면에 반영하고, `mmap`으로 직접 그린 내용은 `sync` 또는
+/// `munmap` 때 화면 전체를 반영합니다.
+pub struct FbDevice;
+
+impl FbDevice {
+    fn info() -> VfsResult<crate::virtio::gpu::FbInfo> {
+        crate::virtio::gpu::framebuffer().ok_or(VfsError::IoError)
+    }
+}
+
+impl VNode for FbDevice {
+    fn node_type(&self) -> VNodeType {
+        VNodeType::CharDevice
+    }
+
+    fn read(&self, offset: usize, buf: &mut [u8]) -> VfsResult<usize> {
+        let fb = Self::info()?;
+        if offset >= fb.size {
+            return Ok(0);
+        }
+        let len = buf.len().min(fb.size - offset);
+        unsafe {
+            core::ptr::copy_nonoverlapping((fb.addr + offset) as *const u8, buf.as_mut_ptr(), len);
+        }
+        Ok(len)
+    }
+
+    fn write(&self, offset: usize, buf: &[u8]) -> VfsResult<usize> {
+        let fb = Self::info()?;
+        if offset >= fb.size {
+            return Err(VfsError::NoSpace);
+        }
+        let len = buf.len().min(fb.size - offset);
+        unsafe {
+            core::ptr::copy_nonoverlapping(buf.as_ptr(), (fb.addr + offset) as *mut u8, len);
+        }
+
+        // 쓴 바이트가 걸친 줄만 반영
+        let stride = fb.stride as usize;
+        let first = offset / stride;
+        let last = (offset + len - 1) / stride;
+        crate::virtio::gpu::flush(crate::virtio::gpu::Rect {
+            x: 0,
+            y: first as u32,
+            width: fb.width,
+            height: (last - first + 1) as u32,
+        });
+        Ok(len)
+    }
+
+    fn stat(&self) -> VfsResult<Stat> {
+        Ok(Stat {
+            node_type: VNodeType::CharDevice,
+            mode: FileMode::new(0o660),
+            size: Self::info().map(|fb| fb.size as u64).unwrap_or(0),
+            nlink: 1,
+            ..Default::default()
+        })
+    }
+
+    fn sync(&self) -> VfsResult<()> {
+        let fb = Self::info()?;
+        if crate::virtio::gpu::flush(fb.full_rect()) {
+            Ok(())
+        } else {
+            Err(VfsError::IoError)
+        }
+    }
+
+    fn mmap(&self, offset: usize, len: usize) -> VfsResult<usize> {
+        let fb = Self::info()?;
+        match offset.checked_add(len) {
+            Some(end) if end <= fb.size => Ok(fb.addr + offset),
+            _ => Err(VfsError::InvalidArgument),
+        }
+    }
+}
+
+/// /dev/kmsg - 커널 로그 링 버퍼
+///
+/// 읽기: `<level>,<seq>,<timestamp_us>,-;<message>` 형식의 레코드를 줄 단위로
+/// 스트리밍합니다. 오프셋은 레코드 텍스트 스트림 위치이며, 덮어써진 위치를
+/// 가리키면 가장 오래된 레코드로 건너뜁니다. 새 레코드가 없으면 0을 반환합니다.
+///
+/// 쓰기: 줄마다 로그 메시지로 기록합니다. `<N>` 접두사로 레벨(0~4)을 지정합니다.
+pub struct KmsgDevice;
+
+impl VNode for KmsgDevice {
+    fn node_type(&self) -> VNodeType {
+        VNodeType::CharDevice
+    }
+
+    fn read(&self, offset: usize, buf: &mut [u8]) -> VfsResult<usize> {
+        Ok(crate::log::kmsg_read(offset as u64, buf))
+    }
+
+    fn adjust_read_offset(&self, offset: usize) -> usize {
+        crate::log::kmsg_adjust_offset(offset as u64) as usize
+    }
+
+    fn write(&self, _offset: usize, buf: &[u8]) -> VfsResult<usize> {
+        let text = core::str::from_utf8(buf).map_err(|_| VfsError::InvalidArgument)?;
+        crate::log::kmsg_write(text);
+        Ok(buf.len())
+    }
+
+    fn stat(&self) -> VfsResult<Stat> {
+        // size = 스트림 끝 (SEEK_END로 새 레코드만 읽기)
+        Ok(Stat {
+            node_type: VNodeType::CharDevice,
+            mode: FileMode::new(0o644),
+            size: crate::log::kmsg_end(),
+            nlink: 1,
+            ..Default::default()
+        })
+    }
 }
 
 /// /dev/random - 난수 생성 (간단한 PRNG)
@@ -436,6 +722,63 @@ impl VNode for BlockDeviceNode {
             ..Default::default()
         })
     }
+
+    fn ioctl(&self, cmd: u32, arg: usize) -> VfsResult<usize> {
+        use crate::block::ioctl::*;
+
+        if cmd == BLKFLSBUF {
+            self.device.sync().map_err(|_| VfsError::IoError)?;
+            return Ok(0);
+        }
+        if !matches!(cmd, BLKROGET | BLKSSZGET | BLKGETSIZE64) {
+            return Err(VfsError::NotATty);
+        }
+        if arg == 0 {
+            return Err(VfsError::InvalidArgument);
+        }
+        // arg는 호출자 주소 공간의 결과 포인터
+        unsafe {
+            match cmd {
+                BLKROGET => (arg as *mut i32).write_unaligned(self.device.is_read_only() as i32),
+                BLKSSZGET => (arg as *mut i32).write_unaligned(self.device.block_size() as i32),
+                _ => (arg as *mut u64).write_unaligned(self.device.capacity()),
+            }
+        }
+        Ok(0)
+    }
+}
+
+/// /dev/watchdog - 소프트웨어 워치독
+///
+/// 쓰면 pet(처음 쓰면 켬), `V`를 쓰면 끕니다. `WDIOC_*` ioctl은 [`crate::watchdog`].
+pub struct WatchdogDevice;
+
+impl VNode for WatchdogDevice {
+    fn node_type(&self) -> VNodeType {
+        VNodeType::CharDevice
+    }
+
+    fn read(&self, _offset: usize, _buf: &mut [u8]) -> VfsResult<usize> {
+        Err(VfsError::InvalidArgument)
+    }
+
+    fn write(&self, _offset: usize, buf: &[u8]) -> VfsResult<usize> {
+        crate::watchdog::device_write(buf)
+    }
+
+    fn stat(&self) -> VfsResult<Stat> {
+        Ok(Stat {
+            node_type: VNodeType::CharDevice,
+            mode: FileMode::new(0o600),
+            size: 0,
+            nlink: 1,
+            ..Default::default()
+        })
+    }
+
+    fn ioctl(&self, cmd: u32, arg: usize) -> VfsResult<usize> {
+        crate::watchdog::device_ioctl(cmd, arg)
+    }
 }
 
 /// DevFS 생성 헬퍼
@@ -445,6 +788,14 @@ pub fn create_devfs() -> Arc<DevFs> {
     // 추가 디바이스 등록
     devfs.register_device("random", Arc::new(RandomDevice::new()));
     devfs.register_device("urandom", Arc::new(RandomDevice::new()));
+    devfs.register_device("watchdog", Arc::new(WatchdogDevice));
+
+    // 시리얼 포트 (/dev/ttyS0, /dev/ttyS1, ...)
+    for index in 0..crate::drivers::serial::count() {
+        if let Some(dev) = SerialDevice::new(index) {
+            devfs.register_device(&format!("ttyS{}", index), Arc::new(dev));
+        }
+    }
 
     // 전역 참조 설정 (나중에 블록 디바이스 등록 시 사용)
     set_devfs(devfs.clone());
@@ -0,0 +1,110 @@
+//! /dev/shm - 공유 메모리 세그먼트 디렉토리
+//!
+//! `ipc::shm`의 이름 있는 세그먼트를 파일로 보여 줍니다.
+//! - 파일 생성 = `shm_create`, 삭제 = `shm_unlink`
+//! - `truncate` = 크기 변경, 끝을 넘는 쓰기는 세그먼트를 늘림
+//! - `mmap` = 세그먼트 매핑 (`munmap`으로 해제)
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::ipc::shm::{self, ShmError, ShmSegment};
+
+use super::super::{DirEntry, FileMode, Stat, VfsError, VfsResult, VNode, VNodeType};
+
+fn shm_error(e: ShmError) -> VfsError {
+    match e {
+        ShmError::NotFound => VfsError::NotFound,
+        ShmError::AlreadyExists => VfsError::AlreadyExists,
+        ShmError::InvalidArgument => VfsError::InvalidArgument,
+        ShmError::NoMemory => VfsError::NoSpace,
+        ShmError::Busy => VfsError::FileBusy,
+    }
+}
+
+/// /dev/shm 디렉토리
+pub struct ShmDir;
+
+impl VNode for ShmDir {
+    fn node_type(&self) -> VNodeType {
+        VNodeType::Directory
+    }
+
+    fn lookup(&self, name: &str) -> VfsResult<Arc<dyn VNode>> {
+        let seg = shm::lookup(name).ok_or(VfsError::NotFound)?;
+        Ok(Arc::new(ShmNode { seg }))
+    }
+
+    fn create(&self, name: &str, node_type: VNodeType, _mode: FileMode) -> VfsResult<Arc<dyn VNode>> {
+        if node_type != VNodeType::File {
+            return Err(VfsError::NotSupported);
+        }
+        let seg = shm::shm_create(name).map_err(shm_error)?;
+        Ok(Arc::new(ShmNode { seg }))
+    }
+
+    fn unlink(&self, name: &str) -> VfsResult<()> {
+        shm::shm_unlink(name).map_err(shm_error)
+    }
+
+    fn readdir(&self) -> VfsResult<Vec<DirEntry>> {
+        Ok(shm::names()
+            .into_iter()
+            .map(|name| DirEntry {
+                name,
+                node_type: VNodeType::File,
+            })
+            .collect())
+    }
+
+    fn stat(&self) -> VfsResult<Stat> {
+        Ok(Stat {
+            node_type: VNodeType::Directory,
+            mode: FileMode::new(0o1777),
+            size: shm::names().len() as u64,
+            nlink: 2,
+            ..Default::default()
+        })
+    }
+}
+
+/// /dev/shm/<name> - 세그먼트 파일
+pub struct ShmNode {
+    seg: Arc<ShmSegment>,
+}
+
+impl VNode for ShmNode {
+    fn node_type(&self) -> VNodeType {
+        VNodeType::File
+    }
+
+    fn read(&self, offset: usize, buf: &mut [u8]) -> VfsResult<usize> {
+        Ok(self.seg.read(offset, buf))
+    }
+
+    fn write(&self, offset: usize, buf: &[u8]) -> VfsResult<usize> {
+        let end = offset.checked_add(buf.len()).ok_or(VfsError::InvalidArgument)?;
+        if end > self.seg.size() {
+            self.seg.resize(end).map_err(shm_error)?;
+        }
+        Ok(self.seg.write(offset, buf))
+    }
+
+    fn truncate(&self, size: u64) -> VfsResult<()> {
+        self.seg.resize(size as usize).map_err(shm_error)
+    }
+
+    fn stat(&self) -> VfsResult<Stat> {
+        Ok(Stat {
+            node_type: VNodeType::File,
+            mode: FileMode::new(0o600),
+            size: self.seg.size() as u64,
+            nlink: 1,
+            ..Default::default()
+        })
+    }
+
+    fn mmap(&self, offset: usize, len: usize) -> VfsResult<usize> {
+        shm::map(&self.seg, offset, len).map_err(shm_error)
+    }
+}
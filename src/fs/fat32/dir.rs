@@ -5,6 +5,8 @@
 use alloc::string::String;
 use alloc::vec::Vec;
 
+use crate::time::clock::{DateTime, NSEC_PER_SEC};
+
 /// 디렉토리 엔트리 (32 bytes)
 #[derive(Debug, Clone, Copy)]
 pub struct DirEntry {
@@ -36,6 +38,14 @@ pub struct DirEntry {
     pub file_size: u32,
 }
 
+/// 엔트리의 수정/접근 시각 (Unix 초, 기록 없으면 0)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timestamps {
+    pub modified: u64,
+    /// FAT는 접근 날짜만 기록하므로 그날 자정
+    pub accessed: u64,
+}
+
 /// 디렉토리 엔트리 속성
 pub mod attr {
     pub const READ_ONLY: u8 = 0x01;
@@ -173,6 +183,20 @@ impl DirEntry {
         }
     }
 
+    /// 디스크에 저장된 11바이트 8.3 이름 (이름 8 + 확장자 3, LFN 체크섬 계산용)
+    pub fn short_bytes(&self) -> [u8; 11] {
+        let mut raw = [0u8; 11];
+        raw[..8].copy_from_slice(&self.name);
+        raw[8..].copy_from_slice(&self.ext);
+        raw
+    }
+
+    /// 11바이트 8.3 이름 설정
+    pub fn set_short_bytes(&mut self, raw: &[u8; 11]) {
+        self.name.copy_from_slice(&raw[..8]);
+        self.ext.copy_from_slice(&raw[8..]);
+    }
+
     /// "." 엔트리인지 확인
     pub fn is_dot(&self) -> bool {
         self.name[0] == b'.' && self.name[1] == b' '
@@ -210,6 +234,51 @@ impl DirEntry {
         entry
     }
 
+    /// 생성 시각 기록 (Unix 나노초, 수정/접근 시각도 같이 설정)
+    pub fn set_created(&mut self, unix_ns: u64) {
+        let (date, time, tenth) = to_fat_time(unix_ns);
+        self.crt_date = date;
+        self.crt_time = time;
+        self.crt_time_tenth = tenth;
+        self.set_modified(unix_ns);
+    }
+
+    /// 수정 시각 기록 (Unix 나노초, 접근 날짜도 같이 설정)
+    pub fn set_modified(&mut self, unix_ns: u64) {
+        let (date, time, _) = to_fat_time(unix_ns);
+        self.wrt_date = date;
+        self.wrt_time = time;
+        self.lst_acc_date = date;
+    }
+
+    /// 접근 날짜 기록 (Unix 나노초)
+    pub fn set_accessed(&mut self, unix_ns: u64) {
+        self.lst_acc_date = to_fat_time(unix_ns).0;
+    }
+
+    /// 생성 시각 (Unix 초, 기록 없으면 0)
+    pub fn created(&self) -> u64 {
+        from_fat_time(self.crt_date, self.crt_time) + self.crt_time_tenth as u64 / 100
+    }
+
+    /// 수정 시각 (Unix 초, 기록 없으면 0)
+    pub fn modified(&self) -> u64 {
+        from_fat_time(self.wrt_date, self.wrt_time)
+    }
+
+    /// 마지막 접근 날짜 (Unix 초, 자정 기준)
+    pub fn accessed(&self) -> u64 {
+        from_fat_time(self.lst_acc_date, 0)
+    }
+
+    /// 수정/접근 시각
+    pub fn timestamps(&self) -> Timestamps {
+        Timestamps {
+            modified: self.modified(),
+            accessed: self.accessed(),
+        }
+    }
+
     /// 엔트리를 삭제됨으로 마킹
     pub fn mark_deleted(&mut self) {
         self.name[0] = 0xE5;
@@ -272,6 +341,43 @@ impl DirEntry {
     }
 }
 
+/// FAT 날짜/시각의 하한 (1980-01-01 00:00:00 UTC)
+const FAT_EPOCH: u64 = 315_532_800;
+
+/// Unix 나노초 → FAT (날짜, 시각, 10ms 단위 생성 시각 보정)
+///
+/// 날짜 = `(연도-1980)<<9 | 월<<5 | 일`, 시각 = `시<<11 | 분<<5 | 초/2`.
+/// 시각은 2초 단위이므로 홀수 초와 밀리초는 보정 값(0-199)에 담습니다.
+/// 1980년 이전은 1980-01-01, 2107년 이후는 2107-12-31로 맞춥니다.
+pub fn to_fat_time(unix_ns: u64) -> (u16, u16, u8) {
+    let secs = (unix_ns / NSEC_PER_SEC).max(FAT_EPOCH);
+    let dt = DateTime::from_unix(secs);
+    if dt.year > 2107 {
+        return ((127 << 9) | (12 << 5) | 31, (23 << 11) | (59 << 5) | 29, 199);
+    }
+    let date = (((dt.year - 1980) as u16) << 9) | ((dt.month as u16) << 5) | dt.day as u16;
+    let time = ((dt.hour as u16) << 11) | ((dt.minute as u16) << 5) | (dt.second / 2) as u16;
+    let millis = if unix_ns / NSEC_PER_SEC < FAT_EPOCH { 0 } else { unix_ns % NSEC_PER_SEC / 1_000_000 };
+    let tenth = (dt.second % 2) * 100 + (millis / 10) as u8;
+    (date, time, tenth)
+}
+
+/// FAT 날짜/시각 → Unix 초 (날짜가 0이면 기록 없음으로 보고 0)
+pub fn from_fat_time(date: u16, time: u16) -> u64 {
+    if date == 0 {
+        return 0;
+    }
+    let dt = DateTime {
+        year: 1980 + (date >> 9) as u32,
+        month: ((date >> 5) & 0xF).clamp(1, 12) as u8,
+        day: (date & 0x1F).max(1) as u8,
+        hour: (time >> 11) as u8,
+        minute: ((time >> 5) & 0x3F) as u8,
+        second: ((time & 0x1F) * 2) as u8,
+    };
+    dt.to_unix()
+}
+
 /// LFN (Long File Name) 엔트리 (32 bytes)
 #[derive(Debug, Clone, Copy)]
 pub struct LfnEntry {
@@ -334,6 +440,26 @@ impl LfnEntry {
         })
     }
 
+    /// 바이트 배열로 변환
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut buf = [0u8; 32];
+        buf[0] = self.order;
+        for (i, c) in self.name1.iter().enumerate() {
+            buf[1 + i * 2..3 + i * 2].copy_from_slice(&c.to_le_bytes());
+        }
+        buf[11] = self.attr;
+        buf[12] = self.entry_type;
+        buf[13] = self.checksum;
+        for (i, c) in self.name2.iter().enumerate() {
+            buf[14 + i * 2..16 + i * 2].copy_from_slice(&c.to_le_bytes());
+        }
+        buf[26..28].copy_from_slice(&self.fst_clus_lo.to_le_bytes());
+        for (i, c) in self.name3.iter().enumerate() {
+            buf[28 + i * 2..30 + i * 2].copy_from_slice(&c.to_le_bytes());
+        }
+        buf
+    }
+
     /// 8.3 이름의 체크섬 계산
     pub fn checksum(short_name: &[u8; 11]) -> u8 {
         let mut sum: u8 = 0;
@@ -401,3 +527,110 @@ pub fn extract_lfn_name(lfn_entries: &[LfnEntry]) -> String {
 
     result
 }
+
+/// LFN 엔트리 하나에 담기는 UCS-2 문자 수
+pub const LFN_CHARS_PER_ENTRY: usize = 13;
+
+/// 8.3 이름에 쓸 수 없는 문자 (`_`로 바꿈)
+fn short_char(c: char) -> u8 {
+    match c {
+        'a'..='z' => c.to_ascii_uppercase() as u8,
+        'A'..='Z' | '0'..='9' => c as u8,
+        '!' | '#' | '$' | '%' | '&' | '\'' | '(' | ')' | '-' | '@' | '^' | '_' | '`' | '{' | '}' | '~' => c as u8,
+        _ => b'_',
+    }
+}
+
+/// 이름이 LFN 없이 8.3 엔트리만으로 그대로 표현되는지
+///
+/// 대문자 8.3 형식(`README.TXT`)만 해당합니다. 소문자나 긴 이름은 LFN이 필요합니다.
+pub fn fits_short_name(name: &str) -> bool {
+    let (base, ext) = match name.rfind('.') {
+        Some(pos) => (&name[..pos], &name[pos + 1..]),
+        None => (name, ""),
+    };
+    let valid = |part: &str, max: usize| {
+        part.len() <= max && part.chars().all(|c| c.is_ascii() && c != '.' && short_char(c) == c as u8)
+    };
+    !base.is_empty() && valid(base, 8) && valid(ext, 3) && !name.ends_with('.')
+}
+
+/// 긴 이름에서 고유한 8.3 이름 생성 (`LONGFI~1.TXT` 형식)
+///
+/// `exists`는 같은 디렉토리에 이미 있는 11바이트 이름인지 확인합니다.
+/// 이름이 8.3에 그대로 맞고 겹치지 않으면 `~N` 꼬리 없이 사용합니다.
+pub fn generate_short_name(name: &str, exists: impl Fn(&[u8; 11]) -> bool) -> Option<[u8; 11]> {
+    let trimmed = name.trim_start_matches('.');
+    let (base, ext) = match trimmed.rfind('.') {
+        Some(pos) => (&trimmed[..pos], &trimmed[pos + 1..]),
+        None => (trimmed, ""),
+    };
+    let base: Vec<u8> = base.chars().filter(|c| *c != ' ' && *c != '.').map(short_char).collect();
+    let ext: Vec<u8> = ext.chars().filter(|c| *c != ' ').map(short_char).take(3).collect();
+
+    let mut raw = [b' '; 11];
+    raw[8..8 + ext.len()].copy_from_slice(&ext);
+
+    if fits_short_name(name) {
+        raw[..base.len()].copy_from_slice(&base);
+        if !exists(&raw) {
+            return Some(raw);
+        }
+    }
+
+    let base: &[u8] = if base.is_empty() { b"_" } else { &base };
+    for n in 1..=999_999u32 {
+        let tail = alloc::format!("~{}", n);
+        let keep = base.len().min(8 - tail.len());
+        raw[..8].fill(b' ');
+        raw[..keep].copy_from_slice(&base[..keep]);
+        raw[keep..keep + tail.len()].copy_from_slice(tail.as_bytes());
+        if !exists(&raw) {
+            return Some(raw);
+        }
+    }
+    None
+}
+
+/// 긴 이름의 LFN 엔트리 생성 (디스크 배치 순서: 마지막 조각이 먼저)
+///
+/// 이름 뒤에는 0x0000 종료 문자를, 남는 칸은 0xFFFF를 채웁니다.
+pub fn build_lfn_entries(name: &str, short: &[u8; 11]) -> Vec<LfnEntry> {
+    let chars: Vec<u16> = name.encode_utf16().collect();
+    let count = chars.len().div_ceil(LFN_CHARS_PER_ENTRY);
+    let checksum = LfnEntry::checksum(short);
+
+    let mut entries = Vec::with_capacity(count);
+    for seq in (1..=count).rev() {
+        let mut part = [0xFFFFu16; LFN_CHARS_PER_ENTRY];
+        let start = (seq - 1) * LFN_CHARS_PER_ENTRY;
+        for (i, slot) in part.iter_mut().enumerate() {
+            match chars.get(start + i) {
+                Some(&c) => *slot = c,
+                None => {
+                    *slot = 0;
+                    break;
+                }
+            }
+        }
+
+        let mut entry = LfnEntry {
+            order: seq as u8,
+            name1: [0; 5],
+            attr: attr::LONG_NAME,
+            entry_type: 0,
+            checksum,
+            name2: [0; 6],
+            fst_clus_lo: 0,
+            name3: [0; 2],
+        };
+        if seq == count {
+            entry.order |= LfnEntry::LAST_ENTRY;
+        }
+        entry.name1.copy_from_slice(&part[0..5]);
+        entry.name2.copy_from_slice(&part[5..11]);
+        entry.name3.copy_from_slice(&part[11..13]);
+        entries.push(entry);
+    }
+    entries
+}
@@ -1,13 +1,41 @@
 //! FAT 테이블 관리
 //!
 //! FAT32 파일 할당 테이블 읽기/쓰기
+//!
+//! 볼륨마다 `FatTable` 하나를 공유하며 빈 클러스터 수와 다음 빈 클러스터 힌트를
+//! 캐시합니다. 같은 블록 디바이스를 다시 열면(`/mnt`와 `ramfs save /dev/vda:/x` 등) 살아 있는
+//! 테이블을 재사용하므로, 두 인스턴스가 각자의 캐시로 같은 빈 클러스터를 할당하지 않습니다.
+//! 마운트하며 건 dirty 표시도 테이블에서 세어 마지막 마운트가 내려갈 때만 clean으로 되돌립니다. 캐시는 마운트 시 FSInfo 섹터에서 읽거나(정상 언마운트된 볼륨) 처음
+//! 필요할 때 FAT를 훑어 채우고, 할당/해제마다 갱신하며 `sync` 때 FSInfo에 다시 씁니다.
+//!
+//! FAT 섹터는 쓰기 지연(write-back) 캐시를 거칩니다. 엔트리 읽기/쓰기는 메모리의 섹터를
+//! 고치고 dirty로 표시만 하며, dirty 섹터는 다음 때 모든 FAT 사본에 기록됩니다.
+//!
+//! - `flush()` (`Fat32FileSystem::sync`, 언마운트)
+//! - dirty 섹터가 `fat32.dirty_limit`개 쌓였거나 가장 오래된 것이 `fat32.dirty_expire_ms`를
+//!   넘긴 뒤의 쓰기
+//! - 주기 기록: 섹터가 처음 dirty가 되면 `fat32-writeback` 지연 작업을 넣고, 작업은
+//!   `fat32.dirty_expire_ms`가 지난 테이블을 기록한 뒤 dirty가 남아 있으면 자신을 다시 넣음.
+//!   쓰기가 멈춰도 FAT가 디렉토리 엔트리(바로 기록)보다 오래 뒤처지지 않음
+//! - 캐시가 가득 차(`fat32.cache_sectors`) dirty 섹터를 내보낼 때
+//!
+//! 메모리가 부족하면 `mm::oom`의 압박 통지로 마운트된 모든 볼륨의 깨끗한 섹터를 버립니다.
+//!
+//! 체인을 해제할 때는 (`fat32.discard`가 켜져 있으면) 클러스터를 FAT에서 비우기 전에 데이터
+//! 영역을 블록 디바이스에 discard로 알립니다. 비운 뒤에 보내면 그 사이 다른 파일이 받은
+//! 클러스터의 새 데이터를 버릴 수 있기 때문입니다.
 
-use alloc::sync::Arc;
+use alloc::collections::BTreeMap;
+use alloc::sync::{Arc, Weak};
 use alloc::vec;
 use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 
-use crate::block::BlockDevice;
+use crate::block::{BlockDevice, BlockError, BlockResult};
+use crate::mm::oom::{self, Notifier};
+use crate::mm::page::PAGE_SIZE;
 use crate::sync::Mutex;
+use crate::sysctl::{self, Kind, Tunable, Value};
 
 use super::boot::Fat32BootSector;
 
@@ -18,6 +46,176 @@ pub const FAT_BAD_CLUSTER: u32 = 0x0FFFFFF7;
 pub const FAT_EOC_MIN: u32 = 0x0FFFFFF8; // End of Chain 최소값
 pub const FAT_EOC: u32 = 0x0FFFFFFF; // End of Chain
 
+/// FAT[1] 볼륨 상태 비트: 1 = 정상 언마운트됨 (0이면 dirty)
+pub const FAT_CLEAN_SHUTDOWN: u32 = 0x08000000;
+/// FAT[1] 볼륨 상태 비트: 1 = I/O 에러 없음 (0이면 디스크 에러 발생)
+pub const FAT_NO_HARD_ERROR: u32 = 0x04000000;
+
+/// FSInfo 시그니처 (오프셋 0, 484, 508)
+const FSINFO_LEAD_SIG: u32 = 0x41615252;
+const FSINFO_STRUC_SIG: u32 = 0x61417272;
+const FSINFO_TRAIL_SIG: u32 = 0xAA550000;
+/// FSInfo 빈 클러스터 수 / 다음 빈 클러스터 오프셋
+const FSINFO_FREE_COUNT: usize = 488;
+const FSINFO_NEXT_FREE: usize = 492;
+/// FSInfo 값 모름
+const FSINFO_UNKNOWN: u32 = 0xFFFFFFFF;
+
+/// 볼륨마다 캐시할 최대 FAT 섹터 수 (512B 섹터면 128KB, 클러스터 32K개)
+static CACHE_SECTORS: AtomicUsize = AtomicUsize::new(256);
+/// 이만큼 dirty 섹터가 쌓이면 기록
+static DIRTY_LIMIT: AtomicUsize = AtomicUsize::new(64);
+/// dirty 섹터를 메모리에 두는 최대 시간 (마이크로초)
+static DIRTY_EXPIRE_US: AtomicU64 = AtomicU64::new(5_000_000);
+/// 해제한 클러스터를 디바이스에 discard로 알림
+static DISCARD: AtomicBool = AtomicBool::new(true);
+
+static SYSCTL: [Tunable; 4] = [
+    Tunable {
+        name: "fat32.cache_sectors",
+        desc: "FAT sectors cached per volume",
+        kind: Kind::Int { min: 16, max: 65536 },
+        get: || Value::Int(CACHE_SECTORS.load(Ordering::Relaxed) as i64),
+        set: Some(|v| {
+            CACHE_SECTORS.store(sysctl::int(&v) as usize, Ordering::Relaxed);
+            Ok(())
+        }),
+    },
+    Tunable {
+        name: "fat32.dirty_limit",
+        desc: "dirty FAT sectors that trigger write-back",
+        kind: Kind::Int { min: 1, max: 65536 },
+        get: || Value::Int(DIRTY_LIMIT.load(Ordering::Relaxed) as i64),
+        set: Some(|v| {
+            DIRTY_LIMIT.store(sysctl::int(&v) as usize, Ordering::Relaxed);
+            Ok(())
+        }),
+    },
+    Tunable {
+        name: "fat32.dirty_expire_ms",
+        desc: "maximum age of a dirty FAT sector in milliseconds",
+        kind: Kind::Int { min: 0, max: 600_000 },
+        get: || Value::Int((DIRTY_EXPIRE_US.load(Ordering::Relaxed) / 1000) as i64),
+        set: Some(|v| {
+            DIRTY_EXPIRE_US.store(sysctl::int(&v) as u64 * 1000, Ordering::Relaxed);
+            Ok(())
+        }),
+    },
+    Tunable {
+        name: "fat32.discard",
+        desc: "discard freed clusters on the block device",
+        kind: Kind::Bool,
+        get: || Value::Bool(DISCARD.load(Ordering::Relaxed)),
+        set: Some(|v| {
+            DISCARD.store(sysctl::boolean(&v), Ordering::Relaxed);
+            Ok(())
+        }),
+    },
+];
+
+/// `fat32.*` 노브 등록 (`fs::init()`에서 호출)
+pub fn register_sysctl() {
+    sysctl::register_all(&SYSCTL);
+}
+
+/// 메모리 압박 통지 등록 (`fs::init()`에서 호출)
+pub fn register_oom_notifier() {
+    if let Err(e) = oom::register_notifier(&OOM_NOTIFIER) {
+        crate::log_warn!(tag: "fat32", "OOM notifier: {}", e);
+    }
+}
+
+/// 열린 볼륨의 FAT 테이블 (블록 디바이스마다 하나, 재사용/압박 회수/주기 기록용 약한 참조)
+static TABLES: Mutex<Vec<Weak<FatTable>>> = Mutex::new(Vec::new());
+
+/// `fat32-writeback` 작업이 큐에 있는지
+static WRITEBACK_QUEUED: AtomicBool = AtomicBool::new(false);
+
+/// 메모리 압박 통지: 깨끗한 FAT 섹터를 버림
+static OOM_NOTIFIER: Notifier = Notifier {
+    name: "fat32 sector cache",
+    reclaim: reclaim_clean,
+};
+
+/// 블록 디바이스의 FAT 테이블 (볼륨을 열 때)
+///
+/// 같은 디바이스, 같은 FAT 배치의 테이블이 살아 있으면 그것을 돌려주고, 없으면 새로 만들어
+/// 추적합니다 (메모리 압박 회수, 주기 기록).
+pub fn table_for(device: Arc<dyn BlockDevice>, boot: &Fat32BootSector) -> Arc<FatTable> {
+    let mut tables = TABLES.lock();
+    tables.retain(|t| t.strong_count() > 0);
+    let fresh = FatTable::new(device, boot);
+    if let Some(table) = tables.iter().filter_map(Weak::upgrade).find(|t| t.same_volume(&fresh)) {
+        return table;
+    }
+    let table = Arc::new(fresh);
+    tables.push(Arc::downgrade(&table));
+    table
+}
+
+/// 주기 기록 작업을 아직 넣지 않았으면 `fat32.dirty_expire_ms` 뒤로 넣음
+fn schedule_writeback() {
+    if WRITEBACK_QUEUED.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    let delay_ms = DIRTY_EXPIRE_US.load(Ordering::Relaxed) / 1000;
+    crate::proc::workqueue::delayed_work("fat32-writeback", delay_ms, writeback, 0);
+}
+
+/// 주기 기록 작업: 기한이 지난 dirty 섹터를 기록하고, 남은 dirty가 있으면 다시 넣음
+fn writeback(_: usize) {
+    WRITEBACK_QUEUED.store(false, Ordering::Release);
+    // 디스크 I/O 동안 목록 락을 잡지 않도록 강한 참조로 옮김
+    let tables: Vec<Arc<FatTable>> = TABLES.lock().iter().filter_map(Weak::upgrade).collect();
+    let mut pending = false;
+    for table in tables {
+        match table.flush_expired() {
+            Ok(dirty) => pending |= dirty,
+            Err(e) => crate::log_warn!(tag: "fat32", "periodic FAT write-back failed: {:?}", e),
+        }
+    }
+    if pending {
+        schedule_writeback();
+    }
+}
+
+/// 모든 볼륨의 깨끗한 섹터를 최대 `pages` 페이지만큼 버림 (버린 페이지 수)
+///
+/// 할당 실패 경로에서 불리므로 목록과 캐시 락은 기다리지 않습니다.
+fn reclaim_clean(pages: usize) -> usize {
+    let Some(tables) = TABLES.try_lock() else {
+        return 0;
+    };
+    let target = pages.saturating_mul(PAGE_SIZE);
+    let mut freed = 0;
+    for table in tables.iter().filter_map(Weak::upgrade) {
+        if freed >= target {
+            break;
+        }
+        freed += table.drop_clean(target - freed);
+    }
+    freed / PAGE_SIZE
+}
+
+/// 캐시된 FAT 섹터
+struct CachedSector {
+    data: Vec<u8>,
+    dirty: bool,
+    /// 마지막 사용 순번 (가장 작은 것을 내보냄)
+    last_use: u64,
+}
+
+/// FAT 섹터 캐시 (키: FAT 영역 안 섹터 번호)
+struct FatCache {
+    sectors: BTreeMap<u32, CachedSector>,
+    /// 사용 순번
+    tick: u64,
+    /// dirty 섹터 수
+    dirty: usize,
+    /// 가장 오래된 dirty 섹터가 생긴 시각 (부팅 후 마이크로초)
+    dirty_since: Option<u64>,
+}
+
 /// FAT 테이블 관리자
 pub struct FatTable {
     /// 블록 디바이스
@@ -32,10 +230,22 @@ pub struct FatTable {
     bytes_per_sector: u16,
     /// 총 클러스터 수
     total_clusters: u32,
-    /// 빈 클러스터 수 캐시
+    /// 데이터 영역 시작 섹터 (클러스터 2)
+    data_start: u32,
+    /// 클러스터 당 섹터 수
+    sectors_per_cluster: u32,
+    /// 디바이스가 discard를 받는지 (`NotSupported`를 한 번 받으면 false)
+    can_discard: AtomicBool,
+    /// FSInfo 섹터 (없으면 None)
+    fs_info: Option<u32>,
+    /// 빈 클러스터 수 캐시 (None = 아직 모름)
     free_count: Mutex<Option<u32>>,
     /// 다음 빈 클러스터 힌트
     next_free_hint: Mutex<u32>,
+    /// FAT 섹터 쓰기 지연 캐시
+    cache: Mutex<FatCache>,
+    /// 마운트하며 볼륨을 dirty로 표시해 둔 인스턴스 수 (마지막이 내려갈 때 clean 복원)
+    dirty_holders: Mutex<usize>,
 }
 
 impl FatTable {
@@ -48,81 +258,193 @@ impl FatTable {
             num_fats: boot.num_fats,
             bytes_per_sector: boot.bytes_per_sector,
             total_clusters: boot.total_clusters(),
+            data_start: boot.data_start_sector(),
+            sectors_per_cluster: boot.sectors_per_cluster as u32,
+            can_discard: AtomicBool::new(true),
+            // 0과 0xFFFF는 FSInfo 없음
+            fs_info: match boot.fs_info {
+                0 | 0xFFFF => None,
+                sector => Some(sector as u32),
+            },
             free_count: Mutex::new(None),
             next_free_hint: Mutex::new(2), // 클러스터는 2부터 시작
+            cache: Mutex::new(FatCache {
+                sectors: BTreeMap::new(),
+                tick: 0,
+                dirty: 0,
+                dirty_since: None,
+            }),
+            dirty_holders: Mutex::new(0),
         }
     }
 
-    /// 클러스터의 다음 클러스터 읽기
-    pub fn read_entry(&self, cluster: u32) -> Result<u32, FatError> {
-        if cluster < 2 || cluster >= self.total_clusters + 2 {
-            return Err(FatError::InvalidCluster);
+    /// 같은 디바이스의 같은 FAT 배치인지 (다시 포맷한 볼륨은 새 테이블)
+    fn same_volume(&self, other: &FatTable) -> bool {
+        core::ptr::addr_eq(Arc::as_ptr(&self.device), Arc::as_ptr(&other.device))
+            && self.fat_start == other.fat_start
+            && self.fat_size == other.fat_size
+            && self.num_fats == other.num_fats
+            && self.bytes_per_sector == other.bytes_per_sector
+            && self.total_clusters == other.total_clusters
+    }
+
+    /// FAT 섹터 하나를 캐시에 올려 `f` 실행 (`write`면 dirty로 표시)
+    fn with_sector<R>(&self, sector: u32, write: bool, f: impl FnOnce(&mut [u8]) -> R) -> Result<R, FatError> {
+        let mut cache = self.cache.lock();
+        cache.tick += 1;
+        let tick = cache.tick;
+
+        if !cache.sectors.contains_key(&sector) {
+            // 노브를 줄였으면 한도 아래로 내려갈 때까지 내보냄
+            while cache.sectors.len() >= CACHE_SECTORS.load(Ordering::Relaxed) {
+                self.evict(&mut cache)?;
+            }
+            let mut data = vec![0u8; self.bytes_per_sector as usize];
+            self.device
+                .read_block((self.fat_start + sector) as u64, &mut data)
+                .map_err(|_| FatError::IoError)?;
+            cache.sectors.insert(sector, CachedSector { data, dirty: false, last_use: tick });
+        }
+
+        let entry = cache.sectors.get_mut(&sector).ok_or(FatError::IoError)?;
+        entry.last_use = tick;
+        let result = f(&mut entry.data);
+        let newly_dirty = write && !entry.dirty;
+        entry.dirty |= write;
+
+        let mut first_dirty = false;
+        if newly_dirty {
+            cache.dirty += 1;
+            if cache.dirty_since.is_none() {
+                cache.dirty_since = Some(crate::time::now_us());
+                first_dirty = true;
+            }
+        }
+        if write {
+            let expired = cache
+                .dirty_since
+                .is_some_and(|since| {
+                    crate::time::now_us().saturating_sub(since) >= DIRTY_EXPIRE_US.load(Ordering::Relaxed)
+                });
+            if cache.dirty >= DIRTY_LIMIT.load(Ordering::Relaxed) || expired {
+                self.flush_locked(&mut cache)?;
+            }
+        }
+        drop(cache);
+
+        // 이후 쓰기가 없어도 기한 안에 기록되도록
+        if first_dirty {
+            schedule_writeback();
+        }
+        Ok(result)
+    }
+
+    /// 가장 오래 쓰지 않은 섹터를 캐시에서 내보냄 (dirty면 먼저 기록)
+    fn evict(&self, cache: &mut FatCache) -> Result<(), FatError> {
+        let Some(victim) = cache.sectors.iter().min_by_key(|(_, s)| s.last_use).map(|(&k, _)| k) else {
+            return Ok(());
+        };
+        if cache.sectors[&victim].dirty {
+            // 한 섹터만 내보내면 dirty 시각 추적이 복잡해지므로 전부 기록
+            self.flush_locked(cache)?;
+        }
+        cache.sectors.remove(&victim);
+        Ok(())
+    }
+
+    /// dirty 섹터를 모든 FAT 사본에 기록
+    fn flush_locked(&self, cache: &mut FatCache) -> Result<(), FatError> {
+        if cache.dirty == 0 {
+            return Ok(());
+        }
+        for (&sector, cached) in cache.sectors.iter_mut().filter(|(_, s)| s.dirty) {
+            for fat_num in 0..self.num_fats {
+                let target = self.fat_start + fat_num as u32 * self.fat_size + sector;
+                self.device
+                    .write_block(target as u64, &cached.data)
+                    .map_err(|_| FatError::IoError)?;
+            }
+            cached.dirty = false;
+        }
+        cache.dirty = 0;
+        cache.dirty_since = None;
+        Ok(())
+    }
+
+    /// 깨끗한 섹터를 최대 `bytes`만큼 캐시에서 버림 (버린 바이트, 캐시 락을 얻지 못하면 0)
+    fn drop_clean(&self, bytes: usize) -> usize {
+        let Some(mut cache) = self.cache.try_lock() else {
+            return 0;
+        };
+        let mut freed = 0;
+        cache.sectors.retain(|_, s| {
+            if s.dirty || freed >= bytes {
+                return true;
+            }
+            freed += s.data.len();
+            false
+        });
+        freed
+    }
+
+    /// dirty FAT 섹터를 디스크에 기록
+    pub fn flush(&self) -> Result<(), FatError> {
+        let mut cache = self.cache.lock();
+        self.flush_locked(&mut cache)
+    }
+
+    /// 가장 오래된 dirty 섹터가 `fat32.dirty_expire_ms`를 넘겼으면 기록 (아직 dirty가 남았는지)
+    fn flush_expired(&self) -> Result<bool, FatError> {
+        let mut cache = self.cache.lock();
+        let Some(since) = cache.dirty_since else {
+            return Ok(false);
+        };
+        if crate::time::now_us().saturating_sub(since) < DIRTY_EXPIRE_US.load(Ordering::Relaxed) {
+            return Ok(true);
         }
+        self.flush_locked(&mut cache)?;
+        Ok(false)
+    }
 
-        // FAT 엔트리 위치 계산
+    /// 클러스터의 FAT 엔트리 위치 (FAT 영역 안 섹터, 섹터 안 오프셋)
+    fn locate(&self, cluster: u32) -> (u32, usize) {
         let fat_offset = cluster * 4; // FAT32는 4바이트 엔트리
-        let fat_sector = self.fat_start + (fat_offset / self.bytes_per_sector as u32);
-        let entry_offset = (fat_offset % self.bytes_per_sector as u32) as usize;
+        (fat_offset / self.bytes_per_sector as u32, (fat_offset % self.bytes_per_sector as u32) as usize)
+    }
 
-        // 섹터 읽기
-        let mut buf = vec![0u8; self.bytes_per_sector as usize];
-        self.device
-            .read_block(fat_sector as u64, &mut buf)
-            .map_err(|_| FatError::IoError)?;
+    /// 블록 디바이스
+    pub fn device(&self) -> &Arc<dyn BlockDevice> {
+        &self.device
+    }
 
-        // 엔트리 읽기 (리틀 엔디안)
-        let entry = u32::from_le_bytes([
-            buf[entry_offset],
-            buf[entry_offset + 1],
-            buf[entry_offset + 2],
-            buf[entry_offset + 3],
-        ]);
+    /// 총 클러스터 수
+    pub fn total_clusters(&self) -> u32 {
+        self.total_clusters
+    }
+
+    /// 클러스터의 다음 클러스터 읽기
+    pub fn read_entry(&self, cluster: u32) -> Result<u32, FatError> {
+        if cluster < 2 || cluster >= self.total_clusters + 2 {
+            return Err(FatError::InvalidCluster);
+        }
 
+        let (sector, offset) = self.locate(cluster);
         // FAT32는 상위 4비트 무시
-        Ok(entry & 0x0FFFFFFF)
+        self.with_sector(sector, false, |buf| read_u32(buf, offset) & 0x0FFFFFFF)
     }
 
-    /// 클러스터의 다음 클러스터 쓰기
+    /// 클러스터의 다음 클러스터 쓰기 (캐시에만, 디스크 기록은 `flush`)
     pub fn write_entry(&self, cluster: u32, value: u32) -> Result<(), FatError> {
         if cluster < 2 || cluster >= self.total_clusters + 2 {
             return Err(FatError::InvalidCluster);
         }
 
-        // FAT 엔트리 위치 계산
-        let fat_offset = cluster * 4;
-        let fat_sector = self.fat_start + (fat_offset / self.bytes_per_sector as u32);
-        let entry_offset = (fat_offset % self.bytes_per_sector as u32) as usize;
-
-        // 모든 FAT 테이블에 쓰기
-        for fat_num in 0..self.num_fats {
-            let sector = fat_sector + (fat_num as u32 * self.fat_size);
-
-            // 섹터 읽기
-            let mut buf = vec![0u8; self.bytes_per_sector as usize];
-            self.device
-                .read_block(sector as u64, &mut buf)
-                .map_err(|_| FatError::IoError)?;
-
+        let (sector, offset) = self.locate(cluster);
+        self.with_sector(sector, true, |buf| {
             // 상위 4비트 보존
-            let old_entry = u32::from_le_bytes([
-                buf[entry_offset],
-                buf[entry_offset + 1],
-                buf[entry_offset + 2],
-                buf[entry_offset + 3],
-            ]);
-            let new_entry = (old_entry & 0xF0000000) | (value & 0x0FFFFFFF);
-
-            // 엔트리 쓰기
-            let bytes = new_entry.to_le_bytes();
-            buf[entry_offset..entry_offset + 4].copy_from_slice(&bytes);
-
-            // 섹터 쓰기
-            self.device
-                .write_block(sector as u64, &buf)
-                .map_err(|_| FatError::IoError)?;
-        }
-
-        Ok(())
+            let new_entry = (read_u32(buf, offset) & 0xF0000000) | (value & 0x0FFFFFFF);
+            buf[offset..offset + 4].copy_from_slice(&new_entry.to_le_bytes());
+        })
     }
 
     /// 클러스터 체인 읽기
@@ -237,9 +559,46 @@ impl FatTable {
         Ok(())
     }
 
+    /// 클러스터들의 데이터 영역을 연속 구간마다 `f(시작 섹터, 섹터 수)`로 처리
+    fn for_each_run(&self, clusters: &[u32], mut f: impl FnMut(u64, u64) -> BlockResult<()>) -> BlockResult<()> {
+        let mut i = 0;
+        while i < clusters.len() {
+            let start = clusters[i];
+            let mut len = 1;
+            while i + len < clusters.len() && clusters[i + len] == start + len as u32 {
+                len += 1;
+            }
+            let sector = self.data_start as u64 + (start - 2) as u64 * self.sectors_per_cluster as u64;
+            f(sector, len as u64 * self.sectors_per_cluster as u64)?;
+            i += len;
+        }
+        Ok(())
+    }
+
+    /// 해제할 클러스터를 디바이스에 discard로 알림 (FAT에서 비우기 전에, 실패는 무시)
+    ///
+    /// FAT 변경이 디스크에 닿기 전에 멈추면 이전 FAT가 버린 클러스터를 가리키지만,
+    /// 어차피 지우거나 잘라내던 데이터입니다.
+    fn discard_clusters(&self, clusters: &[u32]) {
+        if !DISCARD.load(Ordering::Relaxed) || !self.can_discard.load(Ordering::Relaxed) {
+            return;
+        }
+        let result = self.for_each_run(clusters, |sector, count| self.device.discard(sector, count));
+        if result == Err(BlockError::NotSupported) {
+            self.can_discard.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// 클러스터들의 데이터 영역을 0으로 채움 (디바이스가 지원하면 데이터 없이)
+    pub fn zero_clusters(&self, clusters: &[u32]) -> Result<(), FatError> {
+        self.for_each_run(clusters, |sector, count| self.device.write_zeroes(sector, count))
+            .map_err(|_| FatError::IoError)
+    }
+
     /// 클러스터 체인 해제
     pub fn free_chain(&self, start_cluster: u32) -> Result<(), FatError> {
         let chain = self.read_chain(start_cluster)?;
+        self.discard_clusters(&chain);
         for cluster in chain {
             self.free_cluster(cluster)?;
         }
@@ -271,6 +630,8 @@ impl FatTable {
             return self.free_chain(start_cluster);
         }
 
+        self.discard_clusters(&chain[keep_count..]);
+
         // 새 마지막 클러스터를 EOC로 마킹
         self.write_entry(chain[keep_count - 1], FAT_EOC)?;
 
@@ -282,22 +643,172 @@ impl FatTable {
         Ok(())
     }
 
+    /// 볼륨 상태 플래그 읽기 (FAT[1])
+    pub fn volume_flags(&self) -> Result<u32, FatError> {
+        // FAT[1]은 첫 FAT 섹터의 오프셋 4
+        self.with_sector(0, false, |buf| read_u32(buf, 4))
+    }
+
+    /// 볼륨 clean 비트 설정 (모든 FAT 사본의 FAT[1])
+    ///
+    /// 다른 dirty FAT 섹터와 함께 바로 기록합니다. clean 표시는 그 전의 FAT 변경이
+    /// 모두 디스크에 있어야 의미가 있습니다.
+    pub fn set_clean(&self, clean: bool) -> Result<(), FatError> {
+        self.with_sector(0, true, |buf| {
+            let mut entry = read_u32(buf, 4);
+            if clean {
+                entry |= FAT_CLEAN_SHUTDOWN;
+            } else {
+                entry &= !FAT_CLEAN_SHUTDOWN;
+            }
+            buf[4..8].copy_from_slice(&entry.to_le_bytes());
+        })?;
+        self.flush()
+    }
+
+    /// 다른 마운트가 이 볼륨을 dirty로 표시해 두었는지
+    pub fn dirty_held(&self) -> bool {
+        *self.dirty_holders.lock() > 0
+    }
+
+    /// 마운트 동안 볼륨을 dirty로 표시 (`release_dirty`와 짝)
+    ///
+    /// 첫 마운트만 디스크에 표시하고, 이미 다른 마운트가 표시해 두었으면 합류만 합니다.
+    pub fn hold_dirty(&self) -> Result<(), FatError> {
+        let mut holders = self.dirty_holders.lock();
+        if *holders == 0 {
+            self.set_clean(false)?;
+        }
+        *holders += 1;
+        Ok(())
+    }
+
+    /// `hold_dirty` 해제, 마지막 마운트면 clean 비트 복원
+    pub fn release_dirty(&self) -> Result<(), FatError> {
+        let mut holders = self.dirty_holders.lock();
+        *holders = holders.saturating_sub(1);
+        if *holders == 0 {
+            self.set_clean(true)?;
+        }
+        Ok(())
+    }
+
     /// End of Chain 확인
     pub fn is_eoc(value: u32) -> bool {
         value >= FAT_EOC_MIN
     }
 
-    /// 빈 클러스터 수 계산 (느림)
+    /// 빈 클러스터 수 계산 (FAT 전체를 섹터 단위로 읽음, 느림)
+    ///
+    /// 결과로 캐시를 채우고, 힌트를 첫 빈 클러스터로 옮깁니다. 섹터 캐시를 밀어내지
+    /// 않도록 dirty 섹터를 먼저 기록한 뒤 디스크에서 직접 읽습니다.
     pub fn count_free_clusters(&self) -> Result<u32, FatError> {
+        self.flush()?;
+        let per_sector = self.bytes_per_sector as u32 / 4;
+        let end = self.total_clusters + 2;
+        let mut buf = vec![0u8; self.bytes_per_sector as usize];
         let mut count = 0;
-        for cluster in 2..self.total_clusters + 2 {
-            if self.read_entry(cluster)? == FAT_FREE {
-                count += 1;
+        let mut first_free = None;
+
+        let mut sector = 0;
+        while sector * per_sector < end {
+            self.device
+                .read_block((self.fat_start + sector) as u64, &mut buf)
+                .map_err(|_| FatError::IoError)?;
+            for (i, raw) in buf.chunks_exact(4).enumerate() {
+                let cluster = sector * per_sector + i as u32;
+                if cluster < 2 || cluster >= end {
+                    continue;
+                }
+                if read_u32(raw, 0) & 0x0FFFFFFF == FAT_FREE {
+                    count += 1;
+                    first_free.get_or_insert(cluster);
+                }
             }
+            sector += 1;
         }
+
         *self.free_count.lock() = Some(count);
+        if let Some(cluster) = first_free {
+            *self.next_free_hint.lock() = cluster;
+        }
         Ok(count)
     }
+
+    /// 빈 클러스터 수 (캐시가 없으면 FAT를 훑어 채움)
+    pub fn free_clusters(&self) -> Result<u32, FatError> {
+        let cached = *self.free_count.lock();
+        match cached {
+            Some(count) => Ok(count),
+            None => self.count_free_clusters(),
+        }
+    }
+
+    /// FSInfo 섹터에서 빈 클러스터 수와 힌트 읽기
+    ///
+    /// 시그니처가 맞고 값이 범위 안이면 캐시를 채우고 true를 돌려줍니다. 정상
+    /// 언마운트된 볼륨에서만 부르며, dirty 볼륨은 `free_clusters()`가 FAT를 훑게 둡니다.
+    pub fn load_fsinfo(&self) -> Result<bool, FatError> {
+        let Some(sector) = self.fs_info else {
+            return Ok(false);
+        };
+        let mut buf = vec![0u8; self.bytes_per_sector as usize];
+        self.device
+            .read_block(sector as u64, &mut buf)
+            .map_err(|_| FatError::IoError)?;
+
+        let read = |offset: usize| u32::from_le_bytes([buf[offset], buf[offset + 1], buf[offset + 2], buf[offset + 3]]);
+        if read(0) != FSINFO_LEAD_SIG || read(484) != FSINFO_STRUC_SIG || read(508) != FSINFO_TRAIL_SIG {
+            return Ok(false);
+        }
+
+        let free = read(FSINFO_FREE_COUNT);
+        if free == FSINFO_UNKNOWN || free > self.total_clusters {
+            return Ok(false);
+        }
+        *self.free_count.lock() = Some(free);
+
+        let next = read(FSINFO_NEXT_FREE);
+        if (2..self.total_clusters + 2).contains(&next) {
+            *self.next_free_hint.lock() = next;
+        }
+        Ok(true)
+    }
+
+    /// 캐시된 빈 클러스터 수와 힌트를 FSInfo 섹터에 기록 (모르면 0xFFFFFFFF)
+    pub fn write_fsinfo(&self) -> Result<(), FatError> {
+        let Some(sector) = self.fs_info else {
+            return Ok(());
+        };
+        let mut buf = vec![0u8; self.bytes_per_sector as usize];
+        self.device
+            .read_block(sector as u64, &mut buf)
+            .map_err(|_| FatError::IoError)?;
+
+        let free = self.free_count.lock().unwrap_or(FSINFO_UNKNOWN);
+        let next = *self.next_free_hint.lock();
+        buf[0..4].copy_from_slice(&FSINFO_LEAD_SIG.to_le_bytes());
+        buf[484..488].copy_from_slice(&FSINFO_STRUC_SIG.to_le_bytes());
+        buf[FSINFO_FREE_COUNT..FSINFO_FREE_COUNT + 4].copy_from_slice(&free.to_le_bytes());
+        buf[FSINFO_NEXT_FREE..FSINFO_NEXT_FREE + 4].copy_from_slice(&next.to_le_bytes());
+        buf[508..512].copy_from_slice(&FSINFO_TRAIL_SIG.to_le_bytes());
+
+        self.device
+            .write_block(sector as u64, &buf)
+            .map_err(|_| FatError::IoError)
+    }
+}
+
+impl Drop for FatTable {
+    /// 마지막 참조가 사라질 때 남은 dirty 섹터 기록 (sync 없이 버려진 볼륨 대비)
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// 리틀 엔디안 u32 읽기
+fn read_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([buf[offset], buf[offset + 1], buf[offset + 2], buf[offset + 3]])
 }
 
 /// FAT 테이블 에러
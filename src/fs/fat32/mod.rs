@@ -5,11 +5,15 @@
 use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 use crate::block::BlockDevice;
 use crate::sync::RwLock;
+use crate::time::clock;
 
-use super::{DirEntry, FileMode, FileSystem, FsStats, Stat, VfsError, VfsResult, VNode, VNodeType};
+use super::{
+    DirEntry, FileMode, FileSystem, FsState, FsStats, Stat, VfsError, VfsResult, VNode, VNodeType,
+};
 
 pub mod boot;
 pub mod dir;
@@ -23,16 +27,23 @@ pub struct Fat32FileSystem {
     boot: boot::Fat32BootSector,
     /// 루트 클러스터 번호
     root_cluster: u32,
+    /// FAT 테이블 (같은 블록 디바이스를 연 모든 인스턴스가 공유)
+    fat: Arc<fat::FatTable>,
+    /// 이 인스턴스가 볼륨의 dirty 표시에 참여했는지 (정상 언마운트 시 해제)
+    marked_dirty: AtomicBool,
 }
 
 impl Fat32FileSystem {
     /// 새 FAT32 파일시스템 생성
     pub fn new(device: Arc<dyn BlockDevice>, boot: boot::Fat32BootSector) -> Arc<Self> {
         let root_cluster = boot.root_cluster;
+        let fat = fat::table_for(device.clone(), &boot);
         Arc::new(Self {
             device,
             boot,
             root_cluster,
+            fat,
+            marked_dirty: AtomicBool::new(false),
         })
     }
 
@@ -55,9 +66,9 @@ impl Fat32FileSystem {
         Ok(data)
     }
 
-    /// FAT 테이블 생성
-    pub fn fat_table(&self) -> fat::FatTable {
-        fat::FatTable::new(self.device.clone(), &self.boot)
+    /// FAT 테이블
+    pub fn fat_table(&self) -> Arc<fat::FatTable> {
+        self.fat.clone()
     }
 
     /// 클러스터 데이터 쓰기
@@ -96,58 +107,126 @@ impl FileSystem for Fat32FileSystem {
     }
 
     fn root(&self) -> Arc<dyn VNode> {
-        Arc::new(Fat32Dir::new_root(self.device.clone(), self.boot, self.root_cluster))
+        Arc::new(Fat32Dir::new_root(self.fat.clone(), self.boot, self.root_cluster))
     }
 
     fn sync(&self) -> VfsResult<()> {
+        // 캐시된 FAT를 기록하고 빈 클러스터 수를 FSInfo에 남겨 호스트 도구와 맞춤
+        if !self.device.is_read_only() {
+            self.fat.flush().map_err(|_| VfsError::IoError)?;
+            self.fat.write_fsinfo().map_err(|_| VfsError::IoError)?;
+        }
         self.device.sync().map_err(|_| VfsError::IoError)
     }
 
+    /// 블록 = 클러스터 (호스트 `df`와 같은 단위)
     fn statfs(&self) -> VfsResult<FsStats> {
+        let free = self.fat.free_clusters().map_err(|_| VfsError::IoError)?;
         Ok(FsStats {
             fs_type: String::from("fat32"),
-            block_size: self.boot.bytes_per_sector as u64,
-            total_blocks: self.boot.total_sectors_32 as u64,
-            free_blocks: 0, // TODO: FAT 테이블에서 계산
+            block_size: self.boot.sectors_per_cluster as u64 * self.boot.bytes_per_sector as u64,
+            total_blocks: self.fat.total_clusters() as u64,
+            free_blocks: free as u64,
             total_inodes: 0,
             free_inodes: 0,
         })
     }
+
+    fn mount_check(&self) -> VfsResult<FsState> {
+        let fat = self.fat_table();
+        let flags = fat.volume_flags().map_err(|_| VfsError::IoError)?;
+
+        // 이전 마운트 중 디스크 에러가 기록된 볼륨은 거부
+        if flags & fat::FAT_NO_HARD_ERROR == 0 {
+            crate::kprintln!("[FAT32] Volume has recorded disk errors");
+            return Err(VfsError::IoError);
+        }
+
+        // 같은 볼륨의 다른 마운트가 dirty로 표시해 둔 것이면 정상 볼륨
+        let shared = fat.dirty_held();
+        let state = if shared || flags & fat::FAT_CLEAN_SHUTDOWN != 0 {
+            FsState::Clean
+        } else {
+            FsState::Dirty
+        };
+
+        // 정상 언마운트된 볼륨만 FSInfo의 빈 클러스터 수를 믿음 (dirty면 statfs 때 FAT를 훑음).
+        // 공유 테이블의 캐시는 이미 디스크보다 새로움
+        if state == FsState::Clean && !shared {
+            fat.load_fsinfo().map_err(|_| VfsError::IoError)?;
+        }
+
+        // 마운트 중에는 dirty로 표시 (읽기 전용 디바이스는 건드리지 않음).
+        // 이미 dirty인 볼륨은 fsck 전까지 dirty로 남겨 둠
+        if state == FsState::Clean && !self.device.is_read_only() {
+            fat.hold_dirty().map_err(|_| VfsError::IoError)?;
+            self.sync()?;
+            self.marked_dirty.store(true, Ordering::SeqCst);
+        }
+
+        Ok(state)
+    }
+
+    fn unmount(&self) -> VfsResult<()> {
+        self.sync()?;
+
+        // 모든 쓰기가 끝난 뒤 clean 비트 복원 (같은 볼륨의 마지막 마운트만)
+        if self.marked_dirty.swap(false, Ordering::SeqCst) {
+            self.fat_table().release_dirty().map_err(|_| VfsError::IoError)?;
+            self.sync()?;
+        }
+
+        Ok(())
+    }
 }
 
 /// FAT32 디렉토리
 pub struct Fat32Dir {
     /// 블록 디바이스
     device: Arc<dyn BlockDevice>,
+    /// FAT 테이블
+    fat: Arc<fat::FatTable>,
     /// 부트 섹터 정보
     boot: boot::Fat32BootSector,
     /// 시작 클러스터
     cluster: u32,
     /// 디렉토리 이름
     name: String,
+    /// 수정/접근 시각 (루트는 0)
+    times: dir::Timestamps,
 }
 
 impl Fat32Dir {
     /// 루트 디렉토리 생성
-    pub fn new_root(device: Arc<dyn BlockDevice>, boot: boot::Fat32BootSector, cluster: u32) -> Self {
+    pub fn new_root(fat: Arc<fat::FatTable>, boot: boot::Fat32BootSector, cluster: u32) -> Self {
         Self {
-            device,
+            device: fat.device().clone(),
+            fat,
             boot,
             cluster,
             name: String::from("/"),
+            times: dir::Timestamps::default(),
         }
     }
 
     /// 서브디렉토리 생성
-    pub fn new(device: Arc<dyn BlockDevice>, boot: boot::Fat32BootSector, cluster: u32, name: String) -> Self {
+    pub fn new(fat: Arc<fat::FatTable>, boot: boot::Fat32BootSector, cluster: u32, name: String) -> Self {
         Self {
-            device,
+            device: fat.device().clone(),
+            fat,
             boot,
             cluster,
             name,
+            times: dir::Timestamps::default(),
         }
     }
 
+    /// 디렉토리 엔트리의 시각 지정
+    pub fn with_times(mut self, times: dir::Timestamps) -> Self {
+        self.times = times;
+        self
+    }
+
     /// 클러스터 데이터 읽기
     fn read_cluster_data(&self) -> VfsResult<Vec<u8>> {
         let cluster_size = self.boot.sectors_per_cluster as usize
@@ -169,7 +248,7 @@ impl Fat32Dir {
 
     /// 모든 클러스터 데이터 읽기 (FAT 체인 따라가기)
     fn read_all_cluster_data(&self) -> VfsResult<Vec<u8>> {
-        let fat = fat::FatTable::new(self.device.clone(), &self.boot);
+        let fat = &self.fat;
         let chain = fat.read_chain(self.cluster).map_err(|_| VfsError::IoError)?;
 
         let cluster_size = self.boot.sectors_per_cluster as usize
@@ -209,6 +288,7 @@ impl Fat32Dir {
                 break; // 엔트리 끝
             }
             if chunk[0] == 0xE5 {
+                lfn_parts.clear();
                 continue; // 삭제된 엔트리
             }
 
@@ -251,24 +331,81 @@ impl Fat32Dir {
         Ok(entries)
     }
 
-    /// 빈 디렉토리 엔트리 슬롯 찾기
-    fn find_free_entry_slot(&self) -> VfsResult<usize> {
+    /// 연속된 빈 디렉토리 엔트리 슬롯 찾기 (LFN + 8.3 엔트리를 한 번에 쓰기 위함)
+    ///
+    /// 자리가 없으면 디렉토리 끝의 빈 슬롯에 이어지도록 클러스터를 붙입니다.
+    fn find_free_run(&self, count: usize) -> VfsResult<usize> {
         let data = self.read_all_cluster_data()?;
+        let mut run = 0;
 
         for (idx, chunk) in data.chunks(32).enumerate() {
             // 빈 슬롯 (0x00) 또는 삭제된 슬롯 (0xE5)
             if chunk[0] == 0x00 || chunk[0] == 0xE5 {
-                return Ok(idx * 32);
+                run += 1;
+                if run == count {
+                    return Ok((idx + 1 - count) * 32);
+                }
+            } else {
+                run = 0;
             }
         }
 
-        // TODO: 디렉토리 클러스터 확장
-        Err(VfsError::NoSpace)
+        // 끝의 빈 슬롯 `run`개 + 새 클러스터로 채움
+        let cluster_size = self.boot.sectors_per_cluster as usize
+            * self.boot.bytes_per_sector as usize;
+        let needed = (count - run) * 32;
+        self.grow((needed + cluster_size - 1) / cluster_size, data.len())?;
+        Ok(data.len() - run * 32)
+    }
+
+    /// 디렉토리 클러스터 체인 확장
+    ///
+    /// 새 클러스터를 0으로 채운 뒤 체인에 연결하므로 연결 전에 끊겨도 디렉토리는 온전합니다.
+    /// FAT32 디렉토리는 최대 65536 엔트리(2MB)입니다.
+    fn grow(&self, clusters: usize, current_size: usize) -> VfsResult<()> {
+        const MAX_DIR_SIZE: usize = 65536 * 32;
+
+        let cluster_size = self.boot.sectors_per_cluster as usize
+            * self.boot.bytes_per_sector as usize;
+        if current_size + clusters * cluster_size > MAX_DIR_SIZE {
+            return Err(VfsError::NoSpace);
+        }
+
+        let fat = &self.fat;
+        let chain = fat.read_chain(self.cluster).map_err(|_| VfsError::IoError)?;
+        let last = *chain.last().ok_or(VfsError::IoError)?;
+
+        let new_clusters = fat.alloc_clusters(clusters).map_err(|_| VfsError::NoSpace)?;
+        if fat.zero_clusters(&new_clusters).is_err() {
+            let _ = fat.free_chain(new_clusters[0]);
+            return Err(VfsError::IoError);
+        }
+
+        fat.write_entry(last, new_clusters[0]).map_err(|_| VfsError::IoError)?;
+        Ok(())
+    }
+
+    /// 8.3 엔트리 바로 앞에 붙은 LFN 엔트리들의 시작 오프셋
+    fn lfn_run_start(data: &[u8], offset: usize) -> usize {
+        let mut start = offset;
+        while start >= 32 {
+            let chunk = &data[start - 32..start];
+            if chunk[0] == 0xE5 || chunk[0] == 0x00 || chunk[11] != dir::attr::LONG_NAME {
+                break;
+            }
+            start -= 32;
+        }
+        start
     }
 
     /// 디렉토리 엔트리 쓰기
     fn write_dir_entry(&self, offset: usize, entry: &dir::DirEntry) -> VfsResult<()> {
-        let fat = fat::FatTable::new(self.device.clone(), &self.boot);
+        self.write_raw_entry(offset, &entry.to_bytes())
+    }
+
+    /// 32바이트 엔트리 쓰기 (8.3 또는 LFN)
+    fn write_raw_entry(&self, offset: usize, entry_bytes: &[u8; 32]) -> VfsResult<()> {
+        let fat = &self.fat;
         let chain = fat.read_chain(self.cluster).map_err(|_| VfsError::IoError)?;
 
         let cluster_size = self.boot.sectors_per_cluster as usize
@@ -286,8 +423,7 @@ impl Fat32Dir {
         let mut data = self.read_cluster_data_for(cluster)?;
 
         // 엔트리 쓰기
-        let entry_bytes = entry.to_bytes();
-        data[offset_in_cluster..offset_in_cluster + 32].copy_from_slice(&entry_bytes);
+        data[offset_in_cluster..offset_in_cluster + 32].copy_from_slice(entry_bytes);
 
         // 클러스터 쓰기
         self.write_cluster_data(cluster, &data)?;
@@ -295,6 +431,60 @@ impl Fat32Dir {
         Ok(())
     }
 
+    /// `[start, end)` 범위의 엔트리 슬롯을 삭제됨으로 마킹
+    fn mark_deleted_range(&self, data: &[u8], start: usize, end: usize) -> VfsResult<()> {
+        for offset in (start..end).step_by(32) {
+            let mut raw = [0u8; 32];
+            raw.copy_from_slice(&data[offset..offset + 32]);
+            raw[0] = 0xE5;
+            self.write_raw_entry(offset, &raw)?;
+        }
+        Ok(())
+    }
+
+    /// 8.3 엔트리와 그 앞의 LFN 엔트리들을 삭제됨으로 마킹
+    fn delete_entry_run(&self, offset: usize) -> VfsResult<()> {
+        let data = self.read_all_cluster_data()?;
+        let start = Self::lfn_run_start(&data, offset);
+        self.mark_deleted_range(&data, start, offset + 32)
+    }
+
+    /// 디렉토리에 있는 8.3 이름 목록 (`skip` 오프셋의 엔트리는 제외)
+    fn short_names(&self, skip: Option<usize>) -> VfsResult<Vec<[u8; 11]>> {
+        Ok(self
+            .parse_entries_with_offsets()?
+            .into_iter()
+            .filter(|(_, _, offset)| Some(*offset) != skip)
+            .map(|(_, entry, _)| entry.short_bytes())
+            .collect())
+    }
+
+    /// `name`에 맞는 8.3 이름과 LFN 엔트리 (8.3으로 충분하면 LFN 없음)
+    fn name_entries(&self, name: &str, skip: Option<usize>) -> VfsResult<([u8; 11], Vec<dir::LfnEntry>)> {
+        let taken = self.short_names(skip)?;
+        let short = dir::generate_short_name(name, |raw| taken.contains(raw)).ok_or(VfsError::NoSpace)?;
+        let lfn = if dir::fits_short_name(name) {
+            Vec::new()
+        } else {
+            dir::build_lfn_entries(name, &short)
+        };
+        Ok((short, lfn))
+    }
+
+    /// 새 엔트리 추가 (필요하면 LFN 포함), 8.3 엔트리 오프셋 반환
+    fn insert_entry(&self, name: &str, mut entry: dir::DirEntry) -> VfsResult<usize> {
+        let (short, lfn) = self.name_entries(name, None)?;
+        entry.set_short_bytes(&short);
+
+        let start = self.find_free_run(lfn.len() + 1)?;
+        for (i, part) in lfn.iter().enumerate() {
+            self.write_raw_entry(start + i * 32, &part.to_bytes())?;
+        }
+        let offset = start + lfn.len() * 32;
+        self.write_dir_entry(offset, &entry)?;
+        Ok(offset)
+    }
+
     /// 특정 클러스터 데이터 읽기
     fn read_cluster_data_for(&self, cluster: u32) -> VfsResult<Vec<u8>> {
         let cluster_size = self.boot.sectors_per_cluster as usize
@@ -344,29 +534,29 @@ impl Fat32Dir {
     /// 빈 파일 생성
     fn create_file(&self, name: &str) -> VfsResult<Arc<dyn VNode>> {
         // 디렉토리 엔트리 생성 (클러스터 없음, 크기 0)
-        let entry = dir::DirEntry::new_file(name, 0, 0);
-
-        // 빈 슬롯 찾기
-        let offset = self.find_free_entry_slot()?;
+        let now = clock::realtime_ns();
+        let mut entry = dir::DirEntry::new_file(name, 0, 0);
+        entry.set_created(now);
 
-        // 엔트리 쓰기
-        self.write_dir_entry(offset, &entry)?;
+        // 빈 슬롯에 LFN + 8.3 엔트리 쓰기
+        let offset = self.insert_entry(name, entry)?;
 
         // Fat32File 반환
         Ok(Arc::new(Fat32File::new(
-            self.device.clone(),
+            self.fat.clone(),
             self.boot,
             0,
             0,
             String::from(name),
             self.cluster,
             offset,
-        )))
+        )
+        .with_times(entry.timestamps())))
     }
 
     /// 새 디렉토리 생성
     fn create_directory(&self, name: &str) -> VfsResult<Arc<dyn VNode>> {
-        let fat = fat::FatTable::new(self.device.clone(), &self.boot);
+        let fat = &self.fat;
 
         // 새 디렉토리를 위한 클러스터 할당
         let cluster = fat.alloc_cluster().map_err(|_| VfsError::NoSpace)?;
@@ -376,29 +566,31 @@ impl Fat32Dir {
             * self.boot.bytes_per_sector as usize;
         let mut data = alloc::vec![0u8; cluster_size];
 
+        let now = clock::realtime_ns();
+
         // "." 엔트리
-        let dot = dir::DirEntry::dot_entry(cluster);
+        let mut dot = dir::DirEntry::dot_entry(cluster);
+        dot.set_created(now);
         data[0..32].copy_from_slice(&dot.to_bytes());
 
         // ".." 엔트리
-        let dotdot = dir::DirEntry::dotdot_entry(self.cluster);
+        let mut dotdot = dir::DirEntry::dotdot_entry(self.cluster);
+        dotdot.set_created(now);
         data[32..64].copy_from_slice(&dotdot.to_bytes());
 
         // 클러스터에 쓰기
         self.write_cluster_data(cluster, &data)?;
 
         // 부모 디렉토리에 엔트리 추가
-        let entry = dir::DirEntry::new_dir(name, cluster);
-        let offset = self.find_free_entry_slot()?;
-        self.write_dir_entry(offset, &entry)?;
+        let mut entry = dir::DirEntry::new_dir(name, cluster);
+        entry.set_created(now);
+        self.insert_entry(name, entry)?;
 
         // Fat32Dir 반환
-        Ok(Arc::new(Fat32Dir::new(
-            self.device.clone(),
-            self.boot,
-            cluster,
-            String::from(name),
-        )))
+        Ok(Arc::new(
+            Fat32Dir::new(self.fat.clone(), self.boot, cluster, String::from(name))
+                .with_times(entry.timestamps()),
+        ))
     }
 }
 
@@ -414,22 +606,21 @@ impl VNode for Fat32Dir {
             // 대소문자 무시 비교
             if entry_name.eq_ignore_ascii_case(name) {
                 if entry.is_dir() {
-                    return Ok(Arc::new(Fat32Dir::new(
-                        self.device.clone(),
-                        self.boot,
-                        entry.cluster(),
-                        entry_name,
-                    )));
+                    return Ok(Arc::new(
+                        Fat32Dir::new(self.fat.clone(), self.boot, entry.cluster(), entry_name)
+                            .with_times(entry.timestamps()),
+                    ));
                 } else {
                     return Ok(Arc::new(Fat32File::new(
-                        self.device.clone(),
+                        self.fat.clone(),
                         self.boot,
                         entry.cluster(),
                         entry.file_size,
                         entry_name,
                         self.cluster,
                         offset,
-                    )));
+                    )
+                    .with_times(entry.timestamps())));
                 }
             }
         }
@@ -459,6 +650,9 @@ impl VNode for Fat32Dir {
             mode: FileMode::default_dir(),
             size: 0,
             nlink: 2,
+            atime: self.times.accessed,
+            mtime: self.times.modified,
+            ctime: self.times.modified,
             ..Default::default()
         })
     }
@@ -496,17 +690,15 @@ impl VNode for Fat32Dir {
             return Err(VfsError::IsADirectory);
         }
 
-        let fat = fat::FatTable::new(self.device.clone(), &self.boot);
+        let fat = &self.fat;
 
         // 클러스터 해제
         if entry.cluster() >= 2 {
             fat.free_chain(entry.cluster()).map_err(|_| VfsError::IoError)?;
         }
 
-        // 디렉토리 엔트리 삭제 마킹
-        let mut deleted_entry = entry;
-        deleted_entry.mark_deleted();
-        self.write_dir_entry(offset, &deleted_entry)?;
+        // 디렉토리 엔트리 삭제 마킹 (LFN 엔트리 포함)
+        self.delete_entry_run(offset)?;
 
         Ok(())
     }
@@ -528,7 +720,7 @@ impl VNode for Fat32Dir {
 
         // 디렉토리가 비어있는지 확인
         let subdir = Fat32Dir::new(
-            self.device.clone(),
+            self.fat.clone(),
             self.boot,
             entry.cluster(),
             String::from(name),
@@ -539,28 +731,101 @@ impl VNode for Fat32Dir {
             return Err(VfsError::DirectoryNotEmpty);
         }
 
-        let fat = fat::FatTable::new(self.device.clone(), &self.boot);
+        let fat = &self.fat;
 
         // 클러스터 해제
         fat.free_chain(entry.cluster()).map_err(|_| VfsError::IoError)?;
 
-        // 디렉토리 엔트리 삭제 마킹
-        let mut deleted_entry = entry;
-        deleted_entry.mark_deleted();
-        self.write_dir_entry(offset, &deleted_entry)?;
+        // 디렉토리 엔트리 삭제 마킹 (LFN 엔트리 포함)
+        self.delete_entry_run(offset)?;
+
+        Ok(())
+    }
+
+    fn rename(&self, old_name: &str, new_name: &str) -> VfsResult<()> {
+        // 이름 검증
+        if new_name.is_empty() || new_name.contains('/') || new_name.len() > 255 {
+            return Err(VfsError::InvalidArgument);
+        }
+
+        let entries = self.parse_entries_with_offsets()?;
+        let find = |name: &str| {
+            entries
+                .iter()
+                .find(|(entry_name, _, _)| entry_name.eq_ignore_ascii_case(name))
+                .map(|(_, entry, offset)| (*entry, *offset))
+        };
+
+        let (entry, offset) = find(old_name).ok_or(VfsError::NotFound)?;
+        if old_name == new_name {
+            return Ok(());
+        }
+
+        // 대상이 있으면 교체 (대소문자만 바뀌는 경우는 같은 엔트리)
+        if let Some((target, target_offset)) = find(new_name) {
+            if target_offset != offset {
+                match (entry.is_dir(), target.is_dir()) {
+                    (false, true) => return Err(VfsError::IsADirectory),
+                    (true, false) => return Err(VfsError::NotADirectory),
+                    (true, true) => self.rmdir(new_name)?,
+                    (false, false) => self.unlink(new_name)?,
+                }
+            }
+        }
+
+        let (short, lfn) = self.name_entries(new_name, Some(offset))?;
+        let mut new_entry = entry;
+        new_entry.set_short_bytes(&short);
+
+        let data = self.read_all_cluster_data()?;
+        let run_start = Self::lfn_run_start(&data, offset);
+        let needed = lfn.len() * 32;
+
+        // 8.3 엔트리 앞에서 재사용할 수 있는 슬롯 (기존 LFN + 삭제된 슬롯)
+        let mut free_start = run_start;
+        while free_start >= 32 && data[free_start - 32] == 0xE5 {
+            free_start -= 32;
+        }
+
+        if offset - free_start >= needed {
+            // 8.3 엔트리를 제자리에 두어 열린 Fat32File의 엔트리 오프셋이 유지되게 함
+            let start = offset - needed;
+            for (i, part) in lfn.iter().enumerate() {
+                self.write_raw_entry(start + i * 32, &part.to_bytes())?;
+            }
+            self.write_dir_entry(offset, &new_entry)?;
+            if run_start < start {
+                self.mark_deleted_range(&data, run_start, start)?;
+            }
+        } else {
+            // 앞에 자리가 없으면 새 위치에 쓰고 기존 엔트리 삭제
+            let start = self.find_free_run(lfn.len() + 1)?;
+            for (i, part) in lfn.iter().enumerate() {
+                self.write_raw_entry(start + i * 32, &part.to_bytes())?;
+            }
+            self.write_dir_entry(start + needed, &new_entry)?;
+            self.mark_deleted_range(&data, run_start, offset + 32)?;
+        }
 
         Ok(())
     }
 
     fn sync(&self) -> VfsResult<()> {
+        self.fat.flush().map_err(|_| VfsError::IoError)?;
         self.device.sync().map_err(|_| VfsError::IoError)
     }
+
+    fn lock_key(&self) -> Option<(usize, u64)> {
+        Some((Arc::as_ptr(&self.fat) as usize, self.cluster as u64))
+    }
 }
 
 /// FAT32 파일
 pub struct Fat32File {
     /// 블록 디바이스
     device: Arc<dyn BlockDevice>,
+    /// FAT 테이블
+    fat: Arc<fat::FatTable>,
     /// 부트 섹터 정보
     boot: boot::Fat32BootSector,
     /// 시작 클러스터 (가변)
@@ -573,12 +838,14 @@ pub struct Fat32File {
     parent_cluster: u32,
     /// 부모 디렉토리 내 엔트리 오프셋
     entry_offset: usize,
+    /// 수정/접근 시각 (쓰기/크기 변경/읽기 시 갱신)
+    times: RwLock<dir::Timestamps>,
 }
 
 impl Fat32File {
     /// 새 파일 생성
     pub fn new(
-        device: Arc<dyn BlockDevice>,
+        fat: Arc<fat::FatTable>,
         boot: boot::Fat32BootSector,
         start_cluster: u32,
         size: u32,
@@ -587,16 +854,24 @@ impl Fat32File {
         entry_offset: usize,
     ) -> Self {
         Self {
-            device,
+            device: fat.device().clone(),
+            fat,
             boot,
             start_cluster: RwLock::new(start_cluster),
             size: RwLock::new(size),
             name,
             parent_cluster,
             entry_offset,
+            times: RwLock::new(dir::Timestamps::default()),
         }
     }
 
+    /// 디렉토리 엔트리의 시각 지정
+    pub fn with_times(self, times: dir::Timestamps) -> Self {
+        *self.times.write() = times;
+        self
+    }
+
     /// 클러스터 데이터 읽기
     fn read_cluster(&self, cluster: u32) -> VfsResult<Vec<u8>> {
         let cluster_size = self.boot.sectors_per_cluster as usize
@@ -643,9 +918,33 @@ impl Fat32File {
         Ok(())
     }
 
-    /// 부모 디렉토리의 엔트리 업데이트
+    /// 부모 디렉토리의 엔트리 업데이트 (클러스터, 크기, 수정 시각)
     fn update_dir_entry(&self, new_cluster: u32, new_size: u32) -> VfsResult<()> {
-        let fat = fat::FatTable::new(self.device.clone(), &self.boot);
+        let now = clock::realtime_ns();
+        self.modify_dir_entry(|entry| {
+            entry.set_cluster(new_cluster);
+            entry.file_size = new_size;
+            entry.set_modified(now);
+        })
+    }
+
+    /// 읽기 후 접근 날짜 갱신
+    ///
+    /// FAT는 접근 날짜만 기록하므로 날짜가 바뀐 첫 읽기에서만 엔트리를 씁니다.
+    /// 벽시계가 저장된 날짜보다 이르면(RTC 없음 등) 되돌리지 않습니다.
+    fn touch_accessed(&self) {
+        let now = clock::realtime_ns();
+        let today = dir::from_fat_time(dir::to_fat_time(now).0, 0);
+        if today <= self.times.read().accessed {
+            return;
+        }
+        // 읽기 자체는 성공했으므로 기록 실패(읽기 전용 디바이스 등)는 무시
+        let _ = self.modify_dir_entry(|entry| entry.set_accessed(now));
+    }
+
+    /// 부모 디렉토리의 8.3 엔트리를 읽어 `f`로 고쳐 쓰고 캐시된 시각 갱신
+    fn modify_dir_entry(&self, f: impl FnOnce(&mut dir::DirEntry)) -> VfsResult<()> {
+        let fat = &self.fat;
         let chain = fat.read_chain(self.parent_cluster).map_err(|_| VfsError::IoError)?;
 
         let cluster_size = self.boot.sectors_per_cluster as usize
@@ -665,8 +964,8 @@ impl Fat32File {
 
         // 엔트리 수정
         if let Some(mut entry) = dir::DirEntry::from_bytes(&data[offset_in_cluster..]) {
-            entry.set_cluster(new_cluster);
-            entry.file_size = new_size;
+            f(&mut entry);
+            *self.times.write() = entry.timestamps();
             let entry_bytes = entry.to_bytes();
             data[offset_in_cluster..offset_in_cluster + 32].copy_from_slice(&entry_bytes);
 
@@ -696,7 +995,7 @@ impl VNode for Fat32File {
             return Ok(0);
         }
 
-        let fat = fat::FatTable::new(self.device.clone(), &self.boot);
+        let fat = &self.fat;
         let chain = fat.read_chain(start_cluster).map_err(|_| VfsError::IoError)?;
 
         let cluster_size = self.boot.sectors_per_cluster as usize
@@ -729,6 +1028,8 @@ impl VNode for Fat32File {
             cluster_offset = 0; // 첫 클러스터 이후는 0부터 시작
         }
 
+        self.touch_accessed();
+
         Ok(bytes_read)
     }
 
@@ -737,7 +1038,7 @@ impl VNode for Fat32File {
             return Ok(0);
         }
 
-        let fat = fat::FatTable::new(self.device.clone(), &self.boot);
+        let fat = &self.fat;
         let cluster_size = self.boot.sectors_per_cluster as usize
             * self.boot.bytes_per_sector as usize;
 
@@ -803,7 +1104,7 @@ impl VNode for Fat32File {
     }
 
     fn truncate(&self, new_size: u64) -> VfsResult<()> {
-        let fat = fat::FatTable::new(self.device.clone(), &self.boot);
+        let fat = &self.fat;
         let cluster_size = self.boot.sectors_per_cluster as usize
             * self.boot.bytes_per_sector as usize;
 
@@ -830,6 +1131,10 @@ impl VNode for Fat32File {
             // 확장 (0으로 채워진 클러스터 추가)
             if *start_cluster < 2 {
                 let new_cluster = fat.alloc_cluster().map_err(|_| VfsError::NoSpace)?;
+                if fat.zero_clusters(&[new_cluster]).is_err() {
+                    let _ = fat.free_cluster(new_cluster);
+                    return Err(VfsError::IoError);
+                }
                 *start_cluster = new_cluster;
             }
 
@@ -839,7 +1144,8 @@ impl VNode for Fat32File {
             if required_clusters > chain.len() {
                 let additional = required_clusters - chain.len();
                 let last = *chain.last().unwrap();
-                fat.extend_chain(last, additional).map_err(|_| VfsError::NoSpace)?;
+                let added = fat.extend_chain(last, additional).map_err(|_| VfsError::NoSpace)?;
+                fat.zero_clusters(&added).map_err(|_| VfsError::IoError)?;
             }
             *size = new_size;
         }
@@ -852,6 +1158,8 @@ impl VNode for Fat32File {
 
     fn stat(&self) -> VfsResult<Stat> {
         let size = *self.size.read();
+        // FAT에는 상태 변경 시각이 없으므로 수정 시각으로 채움
+        let times = *self.times.read();
         Ok(Stat {
             node_type: VNodeType::File,
             mode: FileMode::default_file(),
@@ -859,13 +1167,22 @@ impl VNode for Fat32File {
             nlink: 1,
             blksize: self.boot.bytes_per_sector as u32,
             blocks: ((size as u64 + 511) / 512),
+            atime: times.accessed,
+            mtime: times.modified,
+            ctime: times.modified,
             ..Default::default()
         })
     }
 
     fn sync(&self) -> VfsResult<()> {
+        self.fat.flush().map_err(|_| VfsError::IoError)?;
         self.device.sync().map_err(|_| VfsError::IoError)
     }
+
+    fn lock_key(&self) -> Option<(usize, u64)> {
+        // 디렉토리 엔트리 위치 (클러스터 번호만 쓰는 디렉토리 키와 겹치지 않음)
+        Some((Arc::as_ptr(&self.fat) as usize, (self.parent_cluster as u64) << 32 | self.entry_offset as u64))
+    }
 }
 
 /// FAT32 마운트
@@ -1,14 +1,24 @@
 //! 파일 디스크립터 관리
 //!
 //! 프로세스별 파일 디스크립터 테이블
-
-use alloc::boxed::Box;
+//!
+//! ## 오프셋 공유 규칙
+//! - `open()`할 때마다 새 `OpenFile`(열린 파일 설명)이 만들어지고 오프셋은 0에서 시작
+//! - `dup`/`dup2`로 만든 fd는 같은 `Arc<OpenFile>`을 가리키므로 오프셋과 플래그를 공유
+//! - 같은 파일을 두 번 `open()`하면 오프셋은 서로 독립
+//! - `O_APPEND` 쓰기는 오프셋 락을 잡은 채 파일 끝으로 이동 후 씀
+//! - `flock` 잠금도 `OpenFile`이 가지므로 `dup`한 fd끼리 공유하고 마지막 fd를 닫을 때 풀림
+
+use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU64, Ordering};
 
-use crate::sync::RwLock;
+use crate::sync::{RwLock, Spinlock};
 
-use super::{VfsError, VfsResult, VNode};
+use super::flock::{self, LockKey, LockKind};
+use super::pipe::PipeEnd;
+use super::{FileMode, VfsError, VfsResult, VNode, VNodeType};
 
 /// 표준 파일 디스크립터
 pub const STDIN_FD: i32 = 0;
@@ -34,13 +44,24 @@ impl OpenFlags {
     pub const O_TRUNC: u32 = 0o1000;
     /// 추가 모드
     pub const O_APPEND: u32 = 0o2000;
+    /// 논블로킹 (파이프/FIFO)
+    pub const O_NONBLOCK: u32 = 0o4000;
     /// 디렉토리만
     pub const O_DIRECTORY: u32 = 0o200000;
+    /// 새 fd에 `FD_CLOEXEC` 설정 (열린 파일 플래그에는 남지 않음)
+    pub const O_CLOEXEC: u32 = 0o2000000;
+    /// 접근 모드 마스크
+    pub const O_ACCMODE: u32 = 3;
 
     pub fn new(flags: u32) -> Self {
         Self(flags)
     }
 
+    /// 접근 모드가 올바른지 (`O_ACCMODE` 값 3은 잘못된 값)
+    pub fn is_valid(&self) -> bool {
+        self.0 & Self::O_ACCMODE != Self::O_ACCMODE
+    }
+
     pub fn is_readable(&self) -> bool {
         let access = self.0 & 3;
         access == Self::O_RDONLY || access == Self::O_RDWR
@@ -70,6 +91,14 @@ impl OpenFlags {
     pub fn is_directory(&self) -> bool {
         self.0 & Self::O_DIRECTORY != 0
     }
+
+    pub fn is_nonblock(&self) -> bool {
+        self.0 & Self::O_NONBLOCK != 0
+    }
+
+    pub fn is_cloexec(&self) -> bool {
+        self.0 & Self::O_CLOEXEC != 0
+    }
 }
 
 /// Seek 위치
@@ -83,6 +112,9 @@ pub enum SeekFrom {
     End(i64),
 }
 
+/// 다음 `OpenFile::id`
+static NEXT_OPEN_FILE_ID: AtomicU64 = AtomicU64::new(1);
+
 /// 열린 파일
 pub struct OpenFile {
     /// VNode 참조
@@ -91,18 +123,41 @@ pub struct OpenFile {
     pub flags: OpenFlags,
     /// 현재 오프셋
     pub offset: RwLock<usize>,
+    /// 연 경로 (정규화된 절대 경로, 파이프/콘솔은 빈 문자열)
+    pub path: String,
+    /// 열린 파일 설명 번호 (flock 소유자)
+    id: u64,
+    /// 가진 flock 잠금의 키 (잠근 뒤 FAT32 rename으로 키가 바뀌어도 해제할 수 있게 기억)
+    flock_key: Spinlock<Option<LockKey>>,
 }
 
 impl OpenFile {
     /// 새 OpenFile 생성
+    ///
+    /// FIFO 노드는 여는 모드에 맞는 파이프 끝으로, 열 때마다 상태를 따로 가지는 장치는
+    /// 그 장치가 만든 노드(`VNode::open_instance`)로 바꿔서 엽니다.
     pub fn new(vnode: Arc<dyn VNode>, flags: OpenFlags) -> Self {
+        let vnode: Arc<dyn VNode> = match vnode.fifo() {
+            Some(pipe) => PipeEnd::new(pipe, flags.is_readable(), flags.is_writable(), flags.is_nonblock()),
+            None => vnode.open_instance(flags).unwrap_or(vnode),
+        };
         Self {
             vnode,
             flags,
             offset: RwLock::new(0),
+            path: String::new(),
+            id: NEXT_OPEN_FILE_ID.fetch_add(1, Ordering::Relaxed),
+            flock_key: Spinlock::new(None),
         }
     }
 
+    /// 경로를 기억하는 OpenFile 생성 (`*at` 시스템 콜의 dirfd 기준 경로로 사용)
+    pub fn with_path(vnode: Arc<dyn VNode>, flags: OpenFlags, path: String) -> Self {
+        let mut file = Self::new(vnode, flags);
+        file.path = path;
+        file
+    }
+
     /// 읽기
     pub fn read(&self, buf: &mut [u8]) -> VfsResult<usize> {
         if !self.flags.is_readable() {
@@ -110,6 +165,7 @@ impl OpenFile {
         }
 
         let mut offset = self.offset.write();
+        *offset = self.vnode.adjust_read_offset(*offset);
         let n = self.vnode.read(*offset, buf)?;
         *offset += n;
         Ok(n)
@@ -160,12 +216,102 @@ impl OpenFile {
     pub fn tell(&self) -> u64 {
         *self.offset.read() as u64
     }
+
+    /// flock 잠금 획득 또는 종류 변경 (`fs::flock` 참고)
+    pub fn lock(&self, kind: LockKind, nonblock: bool) -> VfsResult<()> {
+        let key = (*self.flock_key.lock()).unwrap_or_else(|| flock::key_of(&self.vnode));
+        let result = flock::lock(key, self.id, kind, nonblock, &self.path);
+        match result {
+            Ok(()) => *self.flock_key.lock() = Some(key),
+            Err(VfsError::WouldBlock) => {}
+            // 종류를 바꾸려 기다리다 시그널을 받으면 기존 잠금도 풀려 있음
+            Err(_) => *self.flock_key.lock() = None,
+        }
+        result
+    }
+
+    /// flock 잠금 해제 (없으면 아무것도 안 함)
+    pub fn unlock(&self) {
+        if let Some(key) = self.flock_key.lock().take() {
+            flock::unlock(key, self.id);
+        }
+    }
+}
+
+impl Drop for OpenFile {
+    fn drop(&mut self) {
+        self.unlock();
+    }
+}
+
+/// 경로의 부모 디렉토리에 새 노드 생성 (마운트 경계를 따름)
+fn create_at(path: &str, node_type: VNodeType, mode: FileMode) -> VfsResult<Arc<dyn VNode>> {
+    let (parent_path, name) = super::path::split(path);
+    if name.is_empty() {
+        return Err(VfsError::InvalidPath);
+    }
+    let parent = super::lookup_path(parent_path)?;
+    parent.create(name, node_type, mode)
+}
+
+/// 파일 열기 (open(2) 의미)
+///
+/// - `O_CREAT`: 없으면 `mode`로 생성, `O_EXCL`도 있으면 이미 있을 때 `AlreadyExists`
+/// - `O_TRUNC`: 쓰기로 연 일반 파일만 크기 0으로 (장치/FIFO는 무시)
+/// - `O_DIRECTORY`: 디렉토리가 아니면 `NotADirectory`
+/// - 디렉토리를 쓰기로 열면 `IsADirectory`
+///
+/// 반환된 `OpenFile`은 오프셋 0에서 시작하며 `FdTable::insert`로 fd를 받습니다.
+pub fn open(path: &str, flags: OpenFlags, mode: FileMode) -> VfsResult<OpenFile> {
+    if !flags.is_valid() || (flags.is_directory() && flags.is_create()) {
+        return Err(VfsError::InvalidArgument);
+    }
+    let path = super::path::normalize(path)?;
+
+    let vnode = match super::lookup_path(&path) {
+        Ok(_) if flags.is_create() && flags.is_exclusive() => return Err(VfsError::AlreadyExists),
+        Ok(vnode) => vnode,
+        Err(VfsError::NotFound) if flags.is_create() => {
+            match create_at(&path, VNodeType::File, mode) {
+                Ok(vnode) => vnode,
+                // 그 사이 다른 스레드가 만들었으면 그것을 엶
+                Err(VfsError::AlreadyExists) if !flags.is_exclusive() => super::lookup_path(&path)?,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(e) => return Err(e),
+    };
+
+    match vnode.node_type() {
+        VNodeType::Directory if flags.is_writable() => return Err(VfsError::IsADirectory),
+        VNodeType::Directory => {}
+        _ if flags.is_directory() => return Err(VfsError::NotADirectory),
+        VNodeType::File if flags.is_truncate() && flags.is_writable() => vnode.truncate(0)?,
+        _ => {}
+    }
+
+    Ok(OpenFile::with_path(vnode, flags, path))
+}
+
+/// fd 플래그: exec 때 닫힘 (`fcntl(F_SETFD)`)
+pub const FD_CLOEXEC: u32 = 1;
+
+/// fd 테이블 슬롯
+#[derive(Clone)]
+struct FdEntry {
+    file: Arc<OpenFile>,
+    /// exec 때 닫힘
+    cloexec: bool,
 }
 
 /// 파일 디스크립터 테이블
+///
+/// 스레드(프로세스)마다 하나씩 가지며 (`proc::spawn_with_files`), 없으면 커널 FD 테이블을
+/// 씁니다. `fork()`로 복제한 테이블은 같은 `OpenFile`을 가리키므로 오프셋과 flock 잠금을
+/// 부모와 공유하고, 이후 fd를 열고 닫는 것은 서로 영향이 없습니다.
 pub struct FdTable {
     /// 파일 디스크립터 배열 (None = 미사용)
-    files: RwLock<Vec<Option<Arc<OpenFile>>>>,
+    files: RwLock<Vec<Option<FdEntry>>>,
     /// 최대 파일 디스크립터 수
     max_fds: usize,
 }
@@ -200,24 +346,31 @@ impl FdTable {
 
     /// 새 FD 할당
     pub fn insert(&self, file: Arc<OpenFile>) -> VfsResult<i32> {
-        let mut files = self.files.write();
+        self.insert_from(0, file, false)
+    }
 
-        // 빈 슬롯 찾기
-        for (i, slot) in files.iter_mut().enumerate() {
-            if slot.is_none() {
-                *slot = Some(file);
-                return Ok(i as i32);
-            }
+    /// `min_fd` 이상에서 가장 낮은 빈 번호로 FD 할당 (`F_DUPFD`)
+    pub fn insert_from(&self, min_fd: i32, file: Arc<OpenFile>, cloexec: bool) -> VfsResult<i32> {
+        if min_fd < 0 || min_fd as usize >= self.max_fds {
+            return Err(VfsError::InvalidArgument);
         }
+        let mut files = self.files.write();
+        let min = min_fd as usize;
 
+        // 빈 슬롯 찾기
+        let fd = match files.iter().skip(min).position(|slot| slot.is_none()) {
+            Some(pos) => min + pos,
+            None => files.len().max(min),
+        };
         // 새 슬롯 추가
-        if files.len() >= self.max_fds {
+        if fd >= self.max_fds {
             return Err(VfsError::NoSpace);
         }
-
-        let fd = files.len() as i32;
-        files.push(Some(file));
-        Ok(fd)
+        if files.len() <= fd {
+            files.resize(fd + 1, None);
+        }
+        files[fd] = Some(FdEntry { file, cloexec });
+        Ok(fd as i32)
     }
 
     /// FD로 파일 가져오기
@@ -228,7 +381,8 @@ impl FdTable {
 
         let files = self.files.read();
         files.get(fd as usize)
-            .and_then(|slot| slot.clone())
+            .and_then(|slot| slot.as_ref())
+            .map(|entry| entry.file.clone())
             .ok_or(VfsError::InvalidArgument)
     }
 
@@ -238,18 +392,15 @@ impl FdTable {
             return Err(VfsError::InvalidArgument);
         }
 
-        let mut files = self.files.write();
-        if let Some(slot) = files.get_mut(fd as usize) {
-            if slot.is_some() {
-                *slot = None;
-                return Ok(());
-            }
-        }
-
-        Err(VfsError::InvalidArgument)
+        // 마지막 참조면 파이프 끝 정리, flock 해제가 일어나므로 락 밖에서 놓음
+        let closed = {
+            let mut files = self.files.write();
+            files.get_mut(fd as usize).and_then(|slot| slot.take())
+        };
+        closed.map(|_| ()).ok_or(VfsError::InvalidArgument)
     }
 
-    /// FD 복제
+    /// FD 복제 (새 fd의 `FD_CLOEXEC`는 꺼짐)
     pub fn dup(&self, old_fd: i32) -> VfsResult<i32> {
         let file = self.get(old_fd)?;
         self.insert(file)
@@ -257,23 +408,67 @@ impl FdTable {
 
     /// FD를 특정 번호로 복제 (dup2)
     pub fn dup2(&self, old_fd: i32, new_fd: i32) -> VfsResult<i32> {
-        if new_fd < 0 || new_fd as usize >= self.max_fds {
-            return Err(VfsError::InvalidArgument);
+        if old_fd == new_fd {
+            self.get(old_fd)?;
+            return Ok(new_fd);
         }
+        self.dup3(old_fd, new_fd, false)
+    }
 
+    /// FD를 특정 번호로 복제하며 `FD_CLOEXEC` 지정 (dup3, 열려 있던 `new_fd`는 닫힘)
+    pub fn dup3(&self, old_fd: i32, new_fd: i32, cloexec: bool) -> VfsResult<i32> {
         let file = self.get(old_fd)?;
+        self.install(new_fd, file, cloexec)?;
+        Ok(new_fd)
+    }
 
-        let mut files = self.files.write();
-
-        // 필요하면 확장
-        while files.len() <= new_fd as usize {
-            files.push(None);
+    /// 파일을 특정 번호에 설치 (열려 있던 fd는 닫힘)
+    fn install(&self, fd: i32, file: Arc<OpenFile>, cloexec: bool) -> VfsResult<()> {
+        if fd < 0 || fd as usize >= self.max_fds {
+            return Err(VfsError::InvalidArgument);
         }
 
-        // 기존 파일 닫기
-        files[new_fd as usize] = Some(file);
+        let replaced = {
+            let mut files = self.files.write();
+            // 필요하면 확장
+            if files.len() <= fd as usize {
+                files.resize(fd as usize + 1, None);
+            }
+            files[fd as usize].replace(FdEntry { file, cloexec })
+        };
+        drop(replaced);
+        Ok(())
+    }
 
-        Ok(new_fd)
+    /// 경로를 열어 특정 fd로 연결 (셸 리다이렉션, 예: stdout → 파일)
+    ///
+    /// 열려 있던 fd는 닫히고, 새 fd의 `FD_CLOEXEC`는 꺼져 있어 exec 뒤에도 남습니다.
+    pub fn redirect(&self, fd: i32, path: &str, flags: OpenFlags, mode: FileMode) -> VfsResult<()> {
+        let file = open(path, flags, mode)?;
+        self.install(fd, Arc::new(file), false)
+    }
+
+    /// `FD_CLOEXEC` 여부
+    pub fn cloexec(&self, fd: i32) -> VfsResult<bool> {
+        let files = self.files.read();
+        usize::try_from(fd)
+            .ok()
+            .and_then(|fd| files.get(fd))
+            .and_then(|slot| slot.as_ref())
+            .map(|entry| entry.cloexec)
+            .ok_or(VfsError::InvalidArgument)
+    }
+
+    /// `FD_CLOEXEC` 설정
+    pub fn set_cloexec(&self, fd: i32, cloexec: bool) -> VfsResult<()> {
+        let mut files = self.files.write();
+        let entry = usize::try_from(fd)
+            .ok()
+            .and_then(|fd| files.get_mut(fd))
+            .and_then(|slot| slot.as_mut())
+            .ok_or(VfsError::InvalidArgument)?;
+        entry.cloexec = cloexec;
+        Ok(())
     }
 
     /// 열린 FD 수
@@ -284,8 +479,43 @@ impl FdTable {
 
     /// 모든 FD 닫기
     pub fn close_all(&self) {
-        let mut files = self.files.write();
-        files.clear();
+        let closed = core::mem::take(&mut *self.files.write());
+        drop(closed);
+    }
+
+    /// fork 의미로 복제 (같은 `OpenFile` 공유, `FD_CLOEXEC` 유지)
+    pub fn fork(&self) -> Self {
+        Self {
+            files: RwLock::new(self.files.read().clone()),
+            max_fds: self.max_fds,
+        }
+    }
+
+    /// exec 때 `FD_CLOEXEC` fd 닫기
+    ///
+    /// # Returns
+    /// 닫은 fd 수
+    pub fn close_on_exec(&self) -> usize {
+        let mut closed = Vec::new();
+        {
+            let mut files = self.files.write();
+            for slot in files.iter_mut() {
+                if slot.as_ref().is_some_and(|entry| entry.cloexec) {
+                    closed.push(slot.take());
+                }
+            }
+        }
+        closed.len()
+    }
+
+    /// 열린 fd 목록 (번호, 파일, `FD_CLOEXEC`)
+    pub fn list(&self) -> Vec<(i32, Arc<OpenFile>, bool)> {
+        let files = self.files.read();
+        files
+            .iter()
+            .enumerate()
+            .filter_map(|(fd, slot)| slot.as_ref().map(|e| (fd as i32, e.file.clone(), e.cloexec)))
+            .collect()
     }
 }
 
@@ -295,23 +525,26 @@ impl Default for FdTable {
     }
 }
 
-/// 커널 전역 FD 테이블 (단일 프로세스 환경용)
-static KERNEL_FD_TABLE: RwLock<Option<FdTable>> = RwLock::new(None);
+/// 커널 전역 FD 테이블 (자기 테이블이 없는 스레드가 사용)
+static KERNEL_FD_TABLE: RwLock<Option<Arc<FdTable>>> = RwLock::new(None);
 
 /// 커널 FD 테이블 초기화
 pub fn init_kernel_fd_table(console: Arc<dyn VNode>) {
     let mut table = KERNEL_FD_TABLE.write();
-    *table = Some(FdTable::with_stdio(console));
+    *table = Some(Arc::new(FdTable::with_stdio(console)));
 }
 
 /// 커널 FD 테이블 가져오기
-pub fn kernel_fd_table() -> VfsResult<&'static FdTable> {
-    // Safety: 초기화 후에는 변경되지 않음
-    let table = KERNEL_FD_TABLE.read();
-    if table.is_some() {
-        // 참조 유지를 위해 unsafe 사용
-        Ok(unsafe { &*(table.as_ref().unwrap() as *const FdTable) })
-    } else {
-        Err(VfsError::NotFound)
+pub fn kernel_fd_table() -> VfsResult<Arc<FdTable>> {
+    KERNEL_FD_TABLE.read().clone().ok_or(VfsError::NotFound)
+}
+
+/// 현재 스레드의 FD 테이블 (없으면 커널 FD 테이블)
+///
+/// 시스템 콜은 모두 이 테이블에서 fd를 찾습니다.
+pub fn current_fd_table() -> VfsResult<Arc<FdTable>> {
+    match crate::proc::current_tid().and_then(crate::proc::files) {
+        Some(table) => Ok(table),
+        None => kernel_fd_table(),
     }
 }
@@ -0,0 +1,150 @@
+//! 권고 파일 잠금 (flock)
+//!
+//! 파일 전체에 거는 공유/배타 잠금입니다. 잠금은 열린 파일 설명(`OpenFile`)이 가지므로
+//! `dup`으로 복제한 fd는 같은 잠금을 공유하고, 같은 파일을 따로 `open()`한 fd끼리는 충돌합니다.
+//! 마지막 참조가 닫힐 때(`OpenFile` drop) 자동으로 풀립니다.
+//!
+//! | 요청 | 다른 소유자의 공유 잠금 | 다른 소유자의 배타 잠금 |
+//! |------|-------------------------|-------------------------|
+//! | 공유 | 가능 | 대기 |
+//! | 배타 | 대기 | 대기 |
+//!
+//! - 잠금 키는 `VNode::lock_key()`(FAT32/9P처럼 조회마다 새 VNode를 만드는 경우)이고,
+//!   없으면 VNode 주소입니다. 그래서 같은 파일을 가리키는 모든 fd가 같은 잠금 표를 봅니다.
+//! - 권고 잠금이므로 `read`/`write`는 잠금을 확인하지 않습니다.
+//! - 잠금 종류 변경(공유 ↔ 배타)은 원자적이지 않습니다. 기다려야 하면 기존 잠금을 먼저
+//!   풀어서 두 공유 소유자가 동시에 배타로 올리려 할 때 교착되지 않게 합니다.
+//! - 블로킹 대기는 파이프처럼 양보하며 다시 확인하고, 시그널이 오면 `Interrupted`로 돌아옵니다.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::sync::Mutex;
+
+use super::{VfsError, VfsResult, VNode};
+
+/// flock(2) 연산 비트 (Linux 호환)
+pub const LOCK_SH: u32 = 1;
+pub const LOCK_EX: u32 = 2;
+pub const LOCK_NB: u32 = 4;
+pub const LOCK_UN: u32 = 8;
+
+/// 잠금 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockKind {
+    /// 여러 소유자가 함께 가질 수 있음
+    Shared,
+    /// 한 소유자만 가질 수 있음
+    Exclusive,
+}
+
+impl LockKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LockKind::Shared => "SHARED",
+            LockKind::Exclusive => "EXCLUSIVE",
+        }
+    }
+}
+
+/// 잠금 키 (볼륨, 볼륨 안의 노드)
+pub type LockKey = (usize, u64);
+
+/// VNode의 잠금 키
+pub fn key_of(vnode: &Arc<dyn VNode>) -> LockKey {
+    vnode.lock_key().unwrap_or((Arc::as_ptr(vnode) as *const () as usize, 0))
+}
+
+struct Holder {
+    /// 소유자 (`OpenFile`의 열린 파일 설명 번호)
+    owner: u64,
+    kind: LockKind,
+    /// 잠글 때의 경로 (`locks` 명령용)
+    path: String,
+}
+
+/// 키별 잠금 소유자 (소유자가 없으면 항목 제거)
+static LOCKS: Mutex<BTreeMap<LockKey, Vec<Holder>>> = Mutex::new(BTreeMap::new());
+
+/// 다른 소유자와 충돌하는지
+fn conflicts(holders: &[Holder], owner: u64, kind: LockKind) -> bool {
+    holders
+        .iter()
+        .any(|h| h.owner != owner && (kind == LockKind::Exclusive || h.kind == LockKind::Exclusive))
+}
+
+/// 키에서 소유자의 잠금 제거
+fn remove(locks: &mut BTreeMap<LockKey, Vec<Holder>>, key: LockKey, owner: u64) {
+    if let Some(holders) = locks.get_mut(&key) {
+        holders.retain(|h| h.owner != owner);
+        if holders.is_empty() {
+            locks.remove(&key);
+        }
+    }
+}
+
+/// 잠금 획득 (이미 가진 잠금은 `kind`로 바뀜)
+///
+/// # Errors
+/// * `WouldBlock` - `nonblock`인데 다른 소유자와 충돌 (기존 잠금은 유지)
+/// * `Interrupted` - 기다리는 중 시그널 도착 (종류를 바꾸던 중이면 기존 잠금은 풀린 상태)
+pub(super) fn lock(key: LockKey, owner: u64, kind: LockKind, nonblock: bool, path: &str) -> VfsResult<()> {
+    let mut released = false;
+    loop {
+        {
+            let mut locks = LOCKS.lock();
+            let holders = locks.entry(key).or_default();
+            if !conflicts(holders, owner, kind) {
+                match holders.iter_mut().find(|h| h.owner == owner) {
+                    Some(h) => h.kind = kind,
+                    None => holders.push(Holder { owner, kind, path: String::from(path) }),
+                }
+                return Ok(());
+            }
+            // 충돌했으면 다른 소유자가 있으므로 항목이 비지 않음
+            if nonblock {
+                return Err(VfsError::WouldBlock);
+            }
+            if !released {
+                remove(&mut locks, key, owner);
+                released = true;
+            }
+        }
+
+        if crate::proc::current_tid().is_some_and(crate::proc::signal::has_pending) {
+            return Err(VfsError::Interrupted);
+        }
+        // 시그널 처리는 호출자에게 맡기므로 yield_now 대신 전달 없이 양보
+        crate::proc::scheduler::schedule();
+    }
+}
+
+/// 잠금 해제 (없으면 아무것도 안 함)
+pub(super) fn unlock(key: LockKey, owner: u64) {
+    remove(&mut LOCKS.lock(), key, owner);
+}
+
+/// 잠금 목록 항목
+#[derive(Debug, Clone)]
+pub struct LockInfo {
+    pub owner: u64,
+    pub kind: LockKind,
+    pub path: String,
+}
+
+/// 현재 잠금 목록 (`locks` 명령)
+pub fn list() -> Vec<LockInfo> {
+    let locks = LOCKS.lock();
+    locks
+        .values()
+        .flat_map(|holders| {
+            holders.iter().map(|h| LockInfo {
+                owner: h.owner,
+                kind: h.kind,
+                path: h.path.clone(),
+            })
+        })
+        .collect()
+}
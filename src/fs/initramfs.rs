@@ -0,0 +1,298 @@
+//! initramfs (cpio newc) 풀기
+//!
+//! 부팅 시 cpio 아카이브를 루트 RamFS에 풀어, FAT32 디스크 없이도 `/modules`,
+//! `/bin` 등을 채울 수 있게 합니다. 아카이브는 두 곳에서 옵니다:
+//!
+//! - 커널에 임베드 (`embed_initramfs` feature, 빌드 시 `KERNERS_INITRAMFS` 또는
+//!   `target/initramfs.cpio`)
+//! - 부트로더가 올려 둔 영역 (QEMU `-initrd`, DTB /chosen의
+//!   `linux,initrd-start`/`linux,initrd-end`)
+//!
+//! 둘 다 있으면 임베드 아카이브를 먼저 풀고 부트로더 아카이브가 덮어씁니다.
+//!
+//! # 포맷 (SVR4 newc, `cpio -o -H newc`)
+//!
+//! ```text
+//! 헤더 (110 바이트, 숫자는 8자리 16진수 ASCII)
+//!   magic     "070701" (또는 CRC 포함 "070702")
+//!   ino mode uid gid nlink mtime filesize
+//!   devmajor devminor rdevmajor rdevminor namesize check
+//! 이름 (namesize 바이트, NUL 포함) + 4바이트 정렬 패딩
+//! 데이터 (filesize 바이트) + 4바이트 정렬 패딩
+//! ```
+//!
+//! 이름이 `TRAILER!!!`인 항목에서 끝납니다. 디렉토리, 일반 파일, 심볼릭 링크만
+//! 만들고 디바이스 노드/FIFO/소켓은 건너뜁니다 (디바이스는 /dev의 DevFS가 제공).
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::fs::{self, FileMode, VfsError, VfsResult, VNode, VNodeType};
+use crate::kprintln;
+
+/// 헤더 크기
+const HEADER_SIZE: usize = 110;
+
+/// newc 매직 (CRC 없음 / 있음)
+const MAGIC_NEWC: &[u8; 6] = b"070701";
+const MAGIC_CRC: &[u8; 6] = b"070702";
+
+/// 마지막 항목 이름
+const TRAILER: &str = "TRAILER!!!";
+
+/// 파일 종류 비트 (`c_mode`)
+const S_IFMT: u32 = 0o170000;
+const S_IFDIR: u32 = 0o040000;
+const S_IFREG: u32 = 0o100000;
+const S_IFLNK: u32 = 0o120000;
+
+/// 풀기 결과 요약
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnpackStats {
+    /// 디렉토리 수
+    pub dirs: usize,
+    /// 파일 수
+    pub files: usize,
+    /// 심볼릭 링크 수
+    pub symlinks: usize,
+    /// 건너뛴 항목 수 (디바이스 노드 등)
+    pub skipped: usize,
+    /// 파일 내용 총 바이트
+    pub bytes: usize,
+}
+
+/// newc 헤더
+struct Header {
+    ino: u32,
+    mode: u32,
+    nlink: u32,
+    filesize: usize,
+    namesize: usize,
+}
+
+impl Header {
+    fn parse(data: &[u8]) -> VfsResult<Self> {
+        if data.len() < HEADER_SIZE {
+            return Err(VfsError::InvalidFormat);
+        }
+        let magic = &data[..6];
+        if magic != MAGIC_NEWC && magic != MAGIC_CRC {
+            return Err(VfsError::InvalidFormat);
+        }
+        // 필드 i (0 = ino)의 8자리 16진수
+        let field = |i: usize| -> VfsResult<u32> {
+            let start = 6 + i * 8;
+            let s = core::str::from_utf8(&data[start..start + 8])
+                .map_err(|_| VfsError::InvalidFormat)?;
+            u32::from_str_radix(s, 16).map_err(|_| VfsError::InvalidFormat)
+        };
+        Ok(Self {
+            ino: field(0)?,
+            mode: field(1)?,
+            nlink: field(4)?,
+            filesize: field(6)? as usize,
+            namesize: field(11)? as usize,
+        })
+    }
+}
+
+/// 4바이트 정렬
+fn align4(n: usize) -> usize {
+    (n + 3) & !3
+}
+
+/// 하드 링크 묶음 (같은 ino, 내용은 묶음의 마지막 항목에만 있음)
+struct HardLinks {
+    ino: u32,
+    nodes: Vec<Arc<dyn VNode>>,
+}
+
+/// cpio newc 아카이브를 `dest` 디렉토리 아래에 풀기
+///
+/// 이미 있는 디렉토리는 권한만 바꾸고, 같은 이름의 파일은 내용을 덮어씁니다.
+/// 상위 디렉토리가 아카이브에 없으면 기본 권한으로 만듭니다.
+pub fn unpack(data: &[u8], dest: &Arc<dyn VNode>) -> VfsResult<UnpackStats> {
+    let mut stats = UnpackStats::default();
+    let mut links: Vec<HardLinks> = Vec::new();
+    let mut offset = 0;
+
+    loop {
+        let header = Header::parse(&data[offset..])?;
+        let name_start = offset + HEADER_SIZE;
+        let name_end = name_start + header.namesize;
+        let data_start = align4(name_end);
+        if header.namesize == 0
+            || name_end > data.len()
+            || data_start > data.len()
+            || header.filesize > data.len() - data_start
+        {
+            return Err(VfsError::InvalidFormat);
+        }
+        // 이름은 NUL 포함
+        let name = core::str::from_utf8(&data[name_start..name_end - 1])
+            .map_err(|_| VfsError::InvalidFormat)?;
+        let body = &data[data_start..data_start + header.filesize];
+        offset = align4(data_start + header.filesize);
+
+        if name == TRAILER {
+            break;
+        }
+        unpack_entry(dest, name, &header, body, &mut links, &mut stats)?;
+
+        if offset >= data.len() {
+            // 트레일러 없이 끝남 (잘린 아카이브)
+            return Err(VfsError::InvalidFormat);
+        }
+    }
+
+    Ok(stats)
+}
+
+/// 항목 하나 풀기
+fn unpack_entry(
+    dest: &Arc<dyn VNode>,
+    name: &str,
+    header: &Header,
+    body: &[u8],
+    links: &mut Vec<HardLinks>,
+    stats: &mut UnpackStats,
+) -> VfsResult<()> {
+    let rel = name
+        .trim_start_matches("./")
+        .trim_start_matches('/')
+        .trim_end_matches('/');
+    let mode = FileMode::new(header.mode & 0o7777);
+
+    // 아카이브 루트 (".")
+    if rel.is_empty() || rel == "." {
+        if header.mode & S_IFMT == S_IFDIR {
+            dest.chmod(mode)?;
+        }
+        return Ok(());
+    }
+
+    // ".."로 dest 밖을 가리키지 못하게 함
+    if rel.split('/').any(|c| c == "..") {
+        return Err(VfsError::InvalidPath);
+    }
+    let (parent_path, file_name) = fs::path::split(rel);
+    let parent = make_dirs(dest, parent_path)?;
+
+    match header.mode & S_IFMT {
+        S_IFDIR => {
+            let node = match parent.lookup(file_name) {
+                Ok(node) if node.node_type() == VNodeType::Directory => node,
+                Ok(_) => return Err(VfsError::NotADirectory),
+                Err(_) => parent.create(file_name, VNodeType::Directory, mode)?,
+            };
+            node.chmod(mode)?;
+            stats.dirs += 1;
+        }
+        S_IFREG => {
+            let node = match parent.lookup(file_name) {
+                Ok(node) if node.node_type() == VNodeType::File => node,
+                Ok(_) => return Err(VfsError::AlreadyExists),
+                Err(_) => parent.create(file_name, VNodeType::File, mode)?,
+            };
+            node.truncate(0)?;
+            write_all(&node, body)?;
+            node.chmod(mode)?;
+            stats.files += 1;
+            stats.bytes += body.len();
+
+            if header.nlink > 1 {
+                // 하드 링크는 각각 복사본으로 만들고, 뒤늦게 온 내용을 앞 항목에도 씀
+                let pos = links.iter().position(|l| l.ino == header.ino);
+                let group = match pos {
+                    Some(i) => &mut links[i],
+                    None => {
+                        links.push(HardLinks { ino: header.ino, nodes: Vec::new() });
+                        links.last_mut().unwrap()
+                    }
+                };
+                if !body.is_empty() {
+                    for prev in &group.nodes {
+                        prev.truncate(0)?;
+                        write_all(prev, body)?;
+                    }
+                }
+                group.nodes.push(node);
+            }
+        }
+        S_IFLNK => {
+            let target = core::str::from_utf8(body).map_err(|_| VfsError::InvalidFormat)?;
+            if let Ok(node) = parent.lookup(file_name) {
+                if node.node_type() != VNodeType::Symlink {
+                    return Err(VfsError::AlreadyExists);
+                }
+                parent.unlink(file_name)?;
+            }
+            parent.symlink(file_name, target)?;
+            stats.symlinks += 1;
+        }
+        _ => stats.skipped += 1,
+    }
+
+    Ok(())
+}
+
+/// `rel` 경로의 디렉토리들을 차례로 찾거나 만듦 (`mkdir -p`)
+fn make_dirs(dest: &Arc<dyn VNode>, rel: &str) -> VfsResult<Arc<dyn VNode>> {
+    let mut dir = dest.clone();
+    for comp in rel.split('/').filter(|c| !c.is_empty() && *c != ".") {
+        dir = match dir.lookup(comp) {
+            Ok(node) if node.node_type() == VNodeType::Directory => node,
+            Ok(_) => return Err(VfsError::NotADirectory),
+            Err(_) => dir.create(comp, VNodeType::Directory, FileMode::default_dir())?,
+        };
+    }
+    Ok(dir)
+}
+
+/// 전체 데이터 쓰기
+fn write_all(node: &Arc<dyn VNode>, data: &[u8]) -> VfsResult<()> {
+    let mut done = 0;
+    while done < data.len() {
+        let n = node.write(done, &data[done..])?;
+        if n == 0 {
+            return Err(VfsError::NoSpace);
+        }
+        done += n;
+    }
+    Ok(())
+}
+
+/// 아카이브 하나를 루트 RamFS에 풀고 결과 출력
+fn unpack_into_root(label: &str, data: &[u8]) {
+    // 마운트(/dev 등)를 따라가지 않도록 루트 RamFS 노드에서 직접 시작
+    let Some(root) = fs::root_fs().map(|fs| fs.root()) else {
+        return;
+    };
+    match unpack(data, &root) {
+        Ok(stats) => kprintln!(
+            "[initramfs] {} ({} KB): {} dirs, {} files, {} symlinks, {} skipped",
+            label,
+            data.len() / 1024,
+            stats.dirs,
+            stats.files,
+            stats.symlinks,
+            stats.skipped
+        ),
+        Err(e) => kprintln!("[initramfs] Failed to unpack {}: {:?}", label, e),
+    }
+}
+
+/// initramfs 풀기 (`init_vfs()`에서 루트 RamFS 생성 후 호출)
+pub fn init() {
+    #[cfg(feature = "embed_initramfs")]
+    {
+        static EMBEDDED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/initramfs.cpio"));
+        unpack_into_root("embedded", EMBEDDED);
+    }
+
+    if let Some(region) = crate::mm::initrd_region() {
+        // Safety: mm::init()이 힙/프레임 풀에서 제외해 둔 영역
+        let data = unsafe { core::slice::from_raw_parts(region.base as *const u8, region.size) };
+        unpack_into_root("initrd", data);
+    }
+}
@@ -18,7 +18,13 @@ pub mod path;
 pub mod ramfs;
 pub mod devfs;
 pub mod fat32;
+pub mod p9;
+pub mod initramfs;
 pub mod fd;
+pub mod flock;
+pub mod mount;
+pub mod pipe;
+pub mod poll;
 
 /// VFS 에러
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -55,6 +61,16 @@ pub enum VfsError {
     SymlinkLoop,
     /// 잘못된 파일시스템 포맷
     InvalidFormat,
+    /// 논블로킹 작업이 지금은 진행할 수 없음
+    WouldBlock,
+    /// 읽는 쪽이 없는 파이프에 쓰기
+    BrokenPipe,
+    /// 다른 디렉토리/파일시스템으로 옮길 수 없음 (rename)
+    CrossDevice,
+    /// 기다리는 중 시그널 도착
+    Interrupted,
+    /// 터미널이 아닌 노드에 터미널 ioctl
+    NotATty,
     /// 알 수 없는 에러
     Unknown,
 }
@@ -78,6 +94,11 @@ impl fmt::Display for VfsError {
             VfsError::NotMountPoint => write!(f, "not a mount point"),
             VfsError::SymlinkLoop => write!(f, "too many symbolic links"),
             VfsError::InvalidFormat => write!(f, "invalid filesystem format"),
+            VfsError::WouldBlock => write!(f, "operation would block"),
+            VfsError::BrokenPipe => write!(f, "broken pipe"),
+            VfsError::CrossDevice => write!(f, "cross-device link"),
+            VfsError::Interrupted => write!(f, "interrupted"),
+            VfsError::NotATty => write!(f, "not a tty"),
             VfsError::Unknown => write!(f, "unknown error"),
         }
     }
@@ -207,6 +228,15 @@ pub trait VNode: Send + Sync {
         Err(VfsError::NotSupported)
     }
 
+    /// 읽기 시작 위치 보정
+    ///
+    /// 링 버퍼처럼 오래된 데이터가 사라지는 스트림은 요청 오프셋이 더 이상
+    /// 유효하지 않을 때 읽을 수 있는 가장 이른 오프셋을 반환합니다.
+    /// `OpenFile::read`가 읽기 전에 호출하여 파일 오프셋을 맞춥니다.
+    fn adjust_read_offset(&self, offset: usize) -> usize {
+        offset
+    }
+
     /// 파일 쓰기
     ///
     /// `offset`: 쓰기 시작 위치
@@ -241,6 +271,28 @@ pub trait VNode: Send + Sync {
         Err(VfsError::NotADirectory)
     }
 
+    /// 같은 디렉토리 안에서 이름 변경
+    ///
+    /// `new_name`이 이미 있으면 교체합니다. 파일은 파일로, 디렉토리는 빈
+    /// 디렉토리로만 교체할 수 있습니다.
+    fn rename(&self, old_name: &str, new_name: &str) -> VfsResult<()> {
+        Err(VfsError::NotSupported)
+    }
+
+    /// 하드 링크 생성 - `target` 노드를 가리키는 새 엔트리 `name` 추가
+    ///
+    /// `target`은 같은 파일시스템의 디렉토리가 아닌 노드여야 합니다.
+    fn link(&self, name: &str, target: &Arc<dyn VNode>) -> VfsResult<()> {
+        Err(VfsError::NotSupported)
+    }
+
+    /// 하드 링크 수 조정 (디렉토리가 엔트리를 추가/삭제할 때 대상 노드에 호출)
+    ///
+    /// 링크 수를 직접 관리하는 노드만 구현하며, 바뀐 링크 수를 반환합니다.
+    fn adjust_nlink(&self, delta: i32) -> VfsResult<u32> {
+        Err(VfsError::NotSupported)
+    }
+
     /// 디렉토리 내용 읽기
     fn readdir(&self) -> VfsResult<Vec<DirEntry>> {
         Err(VfsError::NotADirectory)
@@ -268,6 +320,54 @@ pub trait VNode: Send + Sync {
     fn symlink(&self, name: &str, target: &str) -> VfsResult<Arc<dyn VNode>> {
         Err(VfsError::NotSupported)
     }
+
+    /// 이름 있는 FIFO의 파이프 버퍼
+    ///
+    /// FIFO 노드는 열릴 때 이 파이프의 읽기/쓰기 끝(`pipe::PipeEnd`)으로 바뀝니다.
+    fn fifo(&self) -> Option<Arc<crate::ipc::pipe::Pipe>> {
+        None
+    }
+
+    /// 열 때마다 따로 쓸 노드
+    ///
+    /// 열린 파일마다 상태(읽은 위치, `O_NONBLOCK`)를 따로 가지는 장치가 구현합니다. `Some`이면
+    /// `OpenFile`이 이 노드 대신 돌려준 노드를 씁니다 (`/dev/input/eventN`).
+    fn open_instance(&self, flags: fd::OpenFlags) -> Option<Arc<dyn VNode>> {
+        None
+    }
+
+    /// 메모리 매핑 (`mmap`)
+    ///
+    /// `offset`부터 `len` 바이트를 가리키는 커널 주소를 반환합니다. 매핑은
+    /// 노드 구현이 기록하며 `munmap`으로 해제합니다 (`/dev/shm` 세그먼트, `/dev/fb0`).
+    fn mmap(&self, offset: usize, len: usize) -> VfsResult<usize> {
+        Err(VfsError::NotSupported)
+    }
+
+    /// 파일 잠금(`flock`) 키 - 같은 파일을 가리키는 VNode끼리 같은 (볼륨, 노드) 값
+    ///
+    /// 조회할 때마다 새 VNode를 만드는 파일시스템(FAT32, 9P)만 구현합니다. `None`이면
+    /// VNode 주소를 키로 씁니다 (같은 `Arc`를 돌려주는 RamFS, DevFS).
+    fn lock_key(&self) -> Option<(usize, u64)> {
+        None
+    }
+
+    /// 장치 제어 (`ioctl`)
+    ///
+    /// `arg`는 명령에 따라 값 또는 호출자 주소 공간의 포인터입니다. 터미널이 아니면
+    /// `NotATty`(`ENOTTY`)입니다 (`/dev/console`의 termios, [`crate::drivers::tty`]).
+    fn ioctl(&self, cmd: u32, arg: usize) -> VfsResult<usize> {
+        Err(VfsError::NotATty)
+    }
+
+    /// 준비된 이벤트 (`poll`, [`poll`] 모듈의 `POLL*` 비트)
+    ///
+    /// `events` 중 지금 준비된 비트를 반환합니다 (`POLLERR`/`POLLHUP`은 항상 요청됨).
+    /// 준비되지 않은 상태가 바뀔 수 있는 노드는 `waiter`를 대기 목록에 등록하고, 상태가
+    /// 바뀌면 깨워야 합니다. 기본 구현은 항상 읽기/쓰기 가능입니다.
+    fn poll(&self, events: u16, waiter: Option<crate::proc::Tid>) -> u16 {
+        events & poll::DEFAULT_POLLMASK
+    }
 }
 
 /// FileSystem trait - 파일시스템 추상화
@@ -288,12 +388,31 @@ pub trait FileSystem: Send + Sync {
         Err(VfsError::NotSupported)
     }
 
+    /// 마운트 시 일관성 검사
+    ///
+    /// `fs::mount`가 마운트 테이블에 올리기 전에 호출합니다. 파일시스템은
+    /// 온디스크 상태(FAT32: FAT[1] 상태 비트, ext2: s_state)를 확인하고,
+    /// 마운트를 허용하면 dirty 플래그를 설정해 두었다가 정상 `unmount()`에서
+    /// 해제합니다. 마운트할 수 없는 상태면 에러를 반환합니다.
+    fn mount_check(&self) -> VfsResult<FsState> {
+        Ok(FsState::Clean)
+    }
+
     /// 언마운트 (정리 작업)
     fn unmount(&self) -> VfsResult<()> {
         Ok(())
     }
 }
 
+/// 마운트 시점의 파일시스템 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsState {
+    /// 이전에 정상 언마운트됨
+    Clean,
+    /// 정상 언마운트되지 않음 (크래시, 강제 리셋 등)
+    Dirty,
+}
+
 /// 파일시스템 통계
 #[derive(Debug, Clone)]
 pub struct FsStats {
@@ -337,7 +456,7 @@ pub fn set_root_fs(fs: Arc<dyn FileSystem>) {
         fs,
     });
 
-    crate::kprintln!("[vfs] Root filesystem mounted");
+    crate::log_info!(tag: "vfs", "Root filesystem mounted");
 }
 
 /// 루트 파일시스템 가져오기
@@ -346,9 +465,22 @@ pub fn root_fs() -> Option<Arc<dyn FileSystem>> {
 }
 
 /// 파일시스템 마운트
+///
+/// 부모 디렉토리가 있어야 하고, 마운트 포인트 경로에 이미 무언가 있으면 디렉토리여야
+/// 합니다. 마운트 포인트 디렉토리가 없어도 부모의 `readdir()`에 보입니다.
 pub fn mount(path: &str, fs: Arc<dyn FileSystem>) -> VfsResult<()> {
-    if path.is_empty() || !path.starts_with('/') {
-        return Err(VfsError::InvalidPath);
+    let path = path::normalize(path)?;
+    let path = path.as_str();
+
+    if path != "/" {
+        if lookup_path(path::dirname(path))?.node_type() != VNodeType::Directory {
+            return Err(VfsError::NotADirectory);
+        }
+        match lookup_path(path) {
+            Ok(node) if node.node_type() != VNodeType::Directory => return Err(VfsError::NotADirectory),
+            Ok(_) | Err(VfsError::NotFound) => {}
+            Err(e) => return Err(e),
+        }
     }
 
     let mut mounts = MOUNT_TABLE.write();
@@ -358,6 +490,21 @@ pub fn mount(path: &str, fs: Arc<dyn FileSystem>) -> VfsResult<()> {
         return Err(VfsError::AlreadyExists);
     }
 
+    // 파일시스템 일관성 검사 (실패 시 마운트 거부)
+    match fs.mount_check() {
+        Ok(FsState::Clean) => {}
+        Ok(FsState::Dirty) => crate::log_warn!(
+            tag: "vfs",
+            "{} at {} was not cleanly unmounted, run fsck",
+            fs.name(),
+            path
+        ),
+        Err(e) => {
+            crate::log_error!(tag: "vfs", "Refusing to mount {} at {}: {}", fs.name(), path, e);
+            return Err(e);
+        }
+    }
+
     mounts.push(MountPoint {
         path: String::from(path),
         fs,
@@ -366,21 +513,23 @@ pub fn mount(path: &str, fs: Arc<dyn FileSystem>) -> VfsResult<()> {
     // 경로 길이로 정렬 (긴 경로가 먼저 매칭되도록)
     mounts.sort_by(|a, b| b.path.len().cmp(&a.path.len()));
 
-    crate::kprintln!("[vfs] Mounted filesystem at {}", path);
+    crate::log_info!(tag: "vfs", "Mounted filesystem at {}", path);
 
     Ok(())
 }
 
 /// 파일시스템 언마운트
 pub fn unmount(path: &str) -> VfsResult<()> {
+    let path = path::normalize(path)?;
+    let path = path.as_str();
     let mut mounts = MOUNT_TABLE.write();
 
     let idx = mounts.iter()
         .position(|m| m.path == path)
         .ok_or(VfsError::NotMountPoint)?;
 
-    // 루트는 언마운트 불가
-    if path == "/" {
+    // 루트와 하위 마운트가 남은 마운트는 언마운트 불가
+    if path == "/" || mounts.iter().any(|m| m.path.starts_with(&format!("{}/", path))) {
         return Err(VfsError::FileBusy);
     }
 
@@ -389,11 +538,29 @@ pub fn unmount(path: &str) -> VfsResult<()> {
 
     mounts.remove(idx);
 
-    crate::kprintln!("[vfs] Unmounted filesystem at {}", path);
+    crate::log_info!(tag: "vfs", "Unmounted filesystem at {}", path);
 
     Ok(())
 }
 
+/// 루트를 제외한 모든 파일시스템 언마운트
+///
+/// 재부팅(kexec 등) 전에 호출하여 버퍼를 내보내고 dirty 플래그를 정리합니다.
+/// 하위 마운트가 먼저 해제되도록 긴 경로부터 처리합니다.
+pub fn unmount_all() {
+    let paths: Vec<String> = MOUNT_TABLE.read()
+        .iter()
+        .filter(|m| m.path != "/")
+        .map(|m| m.path.clone())
+        .collect();
+
+    for path in paths {
+        if let Err(e) = unmount(&path) {
+            crate::log_warn!(tag: "vfs", "Failed to unmount {}: {}", path, e);
+        }
+    }
+}
+
 /// 경로에 해당하는 파일시스템 찾기
 pub fn find_mount(path: &str) -> Option<(Arc<dyn FileSystem>, String)> {
     let mounts = MOUNT_TABLE.read();
@@ -417,13 +584,74 @@ pub fn find_mount(path: &str) -> Option<(Arc<dyn FileSystem>, String)> {
 }
 
 /// 경로로 VNode 검색
+///
+/// 루트 파일시스템에서 한 단계씩 내려가며 각 디렉토리에서 마운트를 확인합니다
+/// (`mount::MountedDir`). 돌려주는 디렉토리는 자기 경로를 기억하는 래퍼입니다.
 pub fn lookup_path(path: &str) -> VfsResult<Arc<dyn VNode>> {
     let path = path::normalize(path)?;
 
-    let (fs, relative_path) = find_mount(&path)
-        .ok_or(VfsError::NotFound)?;
+    let root = mount::mount_at("/").ok_or(VfsError::NotFound)?.root();
+
+    path::resolve(&mount::wrap(root, String::from("/")), &path)
+}
+
+/// 경로 이름 변경 (rename)
+///
+/// 같은 디렉토리 안의 이름 변경만 파일시스템에 맡기고, 부모 디렉토리가 다르면
+/// `CrossDevice`를 반환합니다 (호출자가 복사 + 삭제로 처리). 마운트 포인트는
+/// 옮길 수 없고, 디렉토리를 자기 하위로 옮기는 것은 `InvalidArgument`입니다.
+pub fn rename(old_path: &str, new_path: &str) -> VfsResult<()> {
+    let old_path = path::normalize(old_path)?;
+    let new_path = path::normalize(new_path)?;
+
+    if old_path == new_path {
+        return Ok(());
+    }
+    if new_path.starts_with(&format!("{}/", old_path)) {
+        return Err(VfsError::InvalidArgument);
+    }
+
+    let (old_dir, old_name) = path::split(&old_path);
+    let (new_dir, new_name) = path::split(&new_path);
+    if old_name.is_empty() || new_name.is_empty() {
+        return Err(VfsError::InvalidPath);
+    }
+    let old_prefix = format!("{}/", old_path);
+    if MOUNT_TABLE.read().iter().any(|m| m.path == old_path || m.path == new_path || m.path.starts_with(&old_prefix)) {
+        return Err(VfsError::FileBusy);
+    }
+    if old_dir != new_dir {
+        return Err(VfsError::CrossDevice);
+    }
+
+    lookup_path(old_dir)?.rename(old_name, new_name)
+}
+
+/// 하드 링크 생성 (`new_path`가 `old_path`의 노드를 가리키게 함)
+///
+/// 두 경로가 같은 파일시스템이어야 하고(`CrossDevice`), 디렉토리에는 만들 수
+/// 없습니다(`PermissionDenied`).
+pub fn link(old_path: &str, new_path: &str) -> VfsResult<()> {
+    let old_path = path::normalize(old_path)?;
+    let new_path = path::normalize(new_path)?;
+
+    let (new_dir, new_name) = path::split(&new_path);
+    if new_name.is_empty() {
+        return Err(VfsError::InvalidPath);
+    }
+
+    let (old_fs, _) = find_mount(&old_path).ok_or(VfsError::NotFound)?;
+    let (new_fs, _) = find_mount(new_dir).ok_or(VfsError::NotFound)?;
+    if !Arc::ptr_eq(&old_fs, &new_fs) {
+        return Err(VfsError::CrossDevice);
+    }
+
+    let target = lookup_path(&old_path)?;
+    if target.node_type() == VNodeType::Directory {
+        return Err(VfsError::PermissionDenied);
+    }
 
-    path::resolve(&fs.root(), &relative_path)
+    lookup_path(new_dir)?.link(new_name, &target)
 }
 
 /// 마운트 목록 반환
@@ -434,7 +662,21 @@ pub fn list_mounts() -> Vec<(String, String)> {
         .collect()
 }
 
+/// 마운트별 파일시스템 통계 (`df` 명령, 통계를 내지 않는 파일시스템은 에러)
+pub fn list_mount_stats() -> Vec<(String, VfsResult<FsStats>)> {
+    let mounts: Vec<(String, Arc<dyn FileSystem>)> = MOUNT_TABLE.read()
+        .iter()
+        .map(|m| (m.path.clone(), m.fs.clone()))
+        .collect();
+    // statfs는 디스크를 읽을 수 있으므로 마운트 테이블 락 밖에서 호출
+    let mut stats: Vec<_> = mounts.into_iter().map(|(path, fs)| (path, fs.statfs())).collect();
+    stats.sort_by(|a, b| a.0.cmp(&b.0));
+    stats
+}
+
 /// VFS 초기화
 pub fn init() {
-    crate::kprintln!("[vfs] Virtual File System initialized");
+    fat32::fat::register_sysctl();
+    fat32::fat::register_oom_notifier();
+    crate::log_info!(tag: "vfs", "Virtual File System initialized");
 }
@@ -0,0 +1,152 @@
+//! 마운트 포인트 순회
+//!
+//! `lookup_path()`가 돌려주는 디렉토리는 `MountedDir`로 감싸져 자기 경로를 기억합니다.
+//! 경로 문자열 접두사 대신 디렉토리 한 단계마다 마운트를 확인하므로:
+//! - `lookup(name)`: 그 자리에 마운트가 있으면 마운트된 파일시스템의 루트로 넘어감
+//! - `lookup("..")`: 마운트 경계를 넘어 상위 디렉토리로 돌아감
+//! - `readdir()`: 바로 아래 마운트 포인트를 디렉토리 엔트리로 보여 줌 (`ls /`의 dev, mnt)
+//! - 마운트 포인트 이름은 지우거나 바꿀 수 없음 (`FileBusy`)
+//!
+//! 마운트 포인트 디렉토리가 아래 파일시스템에 없어도 마운트할 수 있고, 있으면 마운트가
+//! 그 디렉토리를 가립니다. 중첩 마운트(`/mnt` 안의 `/mnt/usb`)도 같은 방식으로 동작합니다.
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use super::{path, DirEntry, FileMode, FileSystem, Stat, VfsError, VfsResult, VNode, VNodeType, MOUNT_TABLE};
+
+/// `path`에 정확히 마운트된 파일시스템
+pub fn mount_at(path: &str) -> Option<Arc<dyn FileSystem>> {
+    MOUNT_TABLE.read().iter().find(|m| m.path == path).map(|m| m.fs.clone())
+}
+
+/// `dir` 바로 아래의 마운트 포인트 이름 목록
+pub fn child_mounts(dir: &str) -> Vec<String> {
+    MOUNT_TABLE
+        .read()
+        .iter()
+        .filter(|m| m.path != "/")
+        .filter(|m| path::dirname(&m.path) == dir)
+        .map(|m| String::from(path::basename(&m.path)))
+        .collect()
+}
+
+/// 디렉토리면 경로를 기억하는 `MountedDir`로 감쌈
+pub fn wrap(node: Arc<dyn VNode>, path: String) -> Arc<dyn VNode> {
+    if node.node_type() == VNodeType::Directory {
+        Arc::new(MountedDir { inner: node, path })
+    } else {
+        node
+    }
+}
+
+/// 마운트를 인식하는 디렉토리
+pub struct MountedDir {
+    /// 실제 파일시스템 디렉토리
+    inner: Arc<dyn VNode>,
+    /// 정규화된 절대 경로
+    path: String,
+}
+
+impl MountedDir {
+    fn child_path(&self, name: &str) -> String {
+        path::join(&self.path, name)
+    }
+
+    /// 이 디렉토리의 `name`이 마운트 포인트인지
+    fn is_mount_point(&self, name: &str) -> bool {
+        mount_at(&self.child_path(name)).is_some()
+    }
+}
+
+impl VNode for MountedDir {
+    fn node_type(&self) -> VNodeType {
+        VNodeType::Directory
+    }
+
+    fn lookup(&self, name: &str) -> VfsResult<Arc<dyn VNode>> {
+        match name {
+            "" | "." => return Ok(wrap(self.inner.clone(), self.path.clone())),
+            ".." => return super::lookup_path(path::dirname(&self.path)),
+            _ => {}
+        }
+
+        let child = self.child_path(name);
+        let node = match mount_at(&child) {
+            Some(fs) => fs.root(),
+            None => self.inner.lookup(name)?,
+        };
+        Ok(wrap(node, child))
+    }
+
+    fn create(&self, name: &str, node_type: VNodeType, mode: FileMode) -> VfsResult<Arc<dyn VNode>> {
+        if self.is_mount_point(name) {
+            return Err(VfsError::AlreadyExists);
+        }
+        let node = self.inner.create(name, node_type, mode)?;
+        Ok(wrap(node, self.child_path(name)))
+    }
+
+    fn unlink(&self, name: &str) -> VfsResult<()> {
+        if self.is_mount_point(name) {
+            return Err(VfsError::FileBusy);
+        }
+        self.inner.unlink(name)
+    }
+
+    fn rmdir(&self, name: &str) -> VfsResult<()> {
+        if self.is_mount_point(name) {
+            return Err(VfsError::FileBusy);
+        }
+        self.inner.rmdir(name)
+    }
+
+    fn rename(&self, old_name: &str, new_name: &str) -> VfsResult<()> {
+        if self.is_mount_point(old_name) || self.is_mount_point(new_name) {
+            return Err(VfsError::FileBusy);
+        }
+        self.inner.rename(old_name, new_name)
+    }
+
+    fn link(&self, name: &str, target: &Arc<dyn VNode>) -> VfsResult<()> {
+        if self.is_mount_point(name) {
+            return Err(VfsError::AlreadyExists);
+        }
+        self.inner.link(name, target)
+    }
+
+    fn readdir(&self) -> VfsResult<Vec<DirEntry>> {
+        let mut entries = self.inner.readdir()?;
+        for name in child_mounts(&self.path) {
+            match entries.iter_mut().find(|e| e.name == name) {
+                // 마운트가 가린 디렉토리
+                Some(entry) => entry.node_type = VNodeType::Directory,
+                None => entries.push(DirEntry {
+                    name,
+                    node_type: VNodeType::Directory,
+                }),
+            }
+        }
+        Ok(entries)
+    }
+
+    fn stat(&self) -> VfsResult<Stat> {
+        self.inner.stat()
+    }
+
+    fn chmod(&self, mode: FileMode) -> VfsResult<()> {
+        self.inner.chmod(mode)
+    }
+
+    fn sync(&self) -> VfsResult<()> {
+        self.inner.sync()
+    }
+
+    fn symlink(&self, name: &str, target: &str) -> VfsResult<Arc<dyn VNode>> {
+        if self.is_mount_point(name) {
+            return Err(VfsError::AlreadyExists);
+        }
+        self.inner.symlink(name, target)
+    }
+}
@@ -0,0 +1,510 @@
+//! 9P 파일시스템 클라이언트 (9P2000.L over virtio-9p)
+//!
+//! QEMU가 공유한 호스트 디렉토리를 마운트합니다. 디스크 이미지를 다시 만들지 않고
+//! 모듈이나 테스트 파일을 넣고 뺄 때 씁니다.
+//!
+//! ```text
+//! kerners> mount -t 9p host /host
+//! kerners> ls /host
+//! kerners> insmod /host/hello.ko
+//! ```
+//!
+//! ## 구조
+//! - VNode마다 경로를 가리키는 fid 하나 (`Twalk`로 얻고 drop 때 `Tclunk`)
+//! - 읽기/쓰기는 처음 필요할 때 fid를 복제해 `Tlopen`한 I/O fid를 따로 둠
+//!   (`O_RDWR`을 먼저 시도하고 권한이 없으면 `O_RDONLY`)
+//! - 캐시 없음: `stat`, `readdir`, `read`는 매번 서버에 요청
+//! - 요청은 전송 계층에서 하나씩 처리하므로 태그는 항상 같은 값
+//!
+//! 하드 링크, FIFO/디바이스 노드 생성은 지원하지 않습니다.
+
+pub mod proto;
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::sync::Mutex;
+use crate::virtio::p9::{VirtIO9P, MAX_MSIZE};
+
+use super::{DirEntry, FileMode, FileSystem, FsStats, Stat, VfsError, VfsResult, VNode, VNodeType};
+use proto::{Message, Qid, Reader};
+
+/// 프로토콜 버전
+const VERSION: &str = "9P2000.L";
+/// 요청 태그 (요청이 직렬화되므로 하나만 사용)
+const TAG: u16 = 1;
+/// 루트 fid
+const ROOT_FID: u32 = 0;
+/// Tattach 사용자 이름
+const UNAME: &str = "root";
+
+/// 9P 클라이언트 (연결 하나)
+pub struct Client {
+    transport: Arc<VirtIO9P>,
+    /// 협상한 최대 메시지 크기
+    msize: usize,
+    /// 다음 새 fid
+    next_fid: AtomicU32,
+    /// clunk되어 다시 쓸 수 있는 fid
+    free_fids: Mutex<Vec<u32>>,
+}
+
+impl Client {
+    /// 버전 협상
+    fn connect(transport: Arc<VirtIO9P>) -> VfsResult<Self> {
+        let mut client = Self {
+            transport,
+            msize: MAX_MSIZE,
+            next_fid: AtomicU32::new(ROOT_FID + 1),
+            free_fids: Mutex::new(Vec::new()),
+        };
+
+        let mut msg = Message::new(proto::TVERSION, proto::NOTAG);
+        msg.u32(MAX_MSIZE as u32).str(VERSION);
+        let resp = client.rpc(msg, proto::TVERSION)?;
+        let mut r = Reader::new(&resp);
+        let msize = r.u32()? as usize;
+        let version = r.str()?;
+        if version != VERSION {
+            crate::kprintln!("[9p] server does not speak {} (got '{}')", VERSION, version);
+            return Err(VfsError::NotSupported);
+        }
+        client.msize = msize.min(MAX_MSIZE);
+        Ok(client)
+    }
+
+    /// 요청을 보내고 응답 본문 반환 (`kind`는 요청 종류, 응답은 `kind + 1`)
+    fn rpc(&self, msg: Message, kind: u8) -> VfsResult<Vec<u8>> {
+        let resp = self.transport.transact(&msg.finish()).map_err(|_| VfsError::IoError)?;
+
+        let mut r = Reader::new(&resp);
+        let size = r.u32()? as usize;
+        let resp_kind = r.u8()?;
+        let _tag = r.u16()?;
+        if size < proto::HEADER_SIZE || size > resp.len() {
+            return Err(VfsError::IoError);
+        }
+        let body = &resp[proto::HEADER_SIZE..size];
+
+        if resp_kind == proto::RLERROR {
+            let errno = Reader::new(body).u32()?;
+            return Err(proto::errno_to_vfs(errno));
+        }
+        if resp_kind != kind + 1 {
+            return Err(VfsError::IoError);
+        }
+        Ok(body.to_vec())
+    }
+
+    fn alloc_fid(&self) -> u32 {
+        self.free_fids
+            .lock()
+            .pop()
+            .unwrap_or_else(|| self.next_fid.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// fid 해제 (서버에서 clunk 실패해도 fid는 다시 씀: 9P에서 clunk는 항상 fid를 없앰)
+    fn clunk(&self, fid: u32) {
+        let mut msg = Message::new(proto::TCLUNK, TAG);
+        msg.u32(fid);
+        let _ = self.rpc(msg, proto::TCLUNK);
+        self.free_fids.lock().push(fid);
+    }
+
+    /// `fid`에서 `names`를 따라가 새 fid 반환 (이름이 없으면 fid 복제)
+    fn walk(&self, fid: u32, names: &[&str]) -> VfsResult<(u32, Qid)> {
+        let newfid = self.alloc_fid();
+        let mut msg = Message::new(proto::TWALK, TAG);
+        msg.u32(fid).u32(newfid).u16(names.len() as u16);
+        for name in names {
+            msg.str(name);
+        }
+
+        let result = self.rpc(msg, proto::TWALK).and_then(|resp| {
+            let mut r = Reader::new(&resp);
+            let nwqid = r.u16()? as usize;
+            let mut qid = Qid::default();
+            for _ in 0..nwqid {
+                qid = r.qid()?;
+            }
+            // 일부만 따라간 경우 newfid는 만들어지지 않음
+            if nwqid < names.len() {
+                return Err(VfsError::NotFound);
+            }
+            Ok(qid)
+        });
+
+        match result {
+            Ok(qid) => Ok((newfid, qid)),
+            Err(e) => {
+                self.free_fids.lock().push(newfid);
+                Err(e)
+            }
+        }
+    }
+
+    /// `fid`를 복제해 `flags`로 연 I/O fid 반환
+    fn open(&self, fid: u32, flags: u32) -> VfsResult<u32> {
+        let (iofid, _) = self.walk(fid, &[])?;
+        let mut msg = Message::new(proto::TLOPEN, TAG);
+        msg.u32(iofid).u32(flags);
+        match self.rpc(msg, proto::TLOPEN) {
+            Ok(_) => Ok(iofid),
+            Err(e) => {
+                self.clunk(iofid);
+                Err(e)
+            }
+        }
+    }
+
+    fn getattr(&self, fid: u32) -> VfsResult<Stat> {
+        let mut msg = Message::new(proto::TGETATTR, TAG);
+        msg.u32(fid).u64(proto::GETATTR_BASIC);
+        let resp = self.rpc(msg, proto::TGETATTR)?;
+
+        let mut r = Reader::new(&resp);
+        let _valid = r.u64()?;
+        let _qid = r.qid()?;
+        let mode = r.u32()?;
+        let uid = r.u32()?;
+        let gid = r.u32()?;
+        let nlink = r.u64()?;
+        let _rdev = r.u64()?;
+        let size = r.u64()?;
+        let blksize = r.u64()?;
+        let blocks = r.u64()?;
+        let atime = r.u64()?;
+        let _atime_nsec = r.u64()?;
+        let mtime = r.u64()?;
+        let _mtime_nsec = r.u64()?;
+        let ctime = r.u64()?;
+
+        Ok(Stat {
+            node_type: proto::mode_type(mode),
+            mode: FileMode::new(mode),
+            size,
+            nlink: nlink as u32,
+            uid,
+            gid,
+            blksize: blksize as u32,
+            blocks,
+            atime,
+            mtime,
+            ctime,
+        })
+    }
+
+    fn setattr(&self, fid: u32, valid: u32, mode: u32, size: u64) -> VfsResult<()> {
+        let mut msg = Message::new(proto::TSETATTR, TAG);
+        msg.u32(fid).u32(valid).u32(mode).u32(0).u32(0).u64(size);
+        msg.u64(0).u64(0).u64(0).u64(0); // atime, mtime
+        self.rpc(msg, proto::TSETATTR).map(|_| ())
+    }
+
+    /// 한 번의 Tread/Twrite로 옮길 수 있는 최대 바이트
+    fn io_unit(&self) -> usize {
+        self.msize - proto::IO_HEADER_SIZE
+    }
+}
+
+/// 9P 파일/디렉토리
+pub struct P9Node {
+    client: Arc<Client>,
+    /// 이 노드의 경로를 가리키는 fid
+    fid: u32,
+    qid: Qid,
+    /// 열린 I/O fid와 쓰기 가능 여부
+    io: Mutex<Option<(u32, bool)>>,
+}
+
+impl P9Node {
+    fn new(client: Arc<Client>, fid: u32, qid: Qid) -> Self {
+        Self {
+            client,
+            fid,
+            qid,
+            io: Mutex::new(None),
+        }
+    }
+
+    /// I/O fid (없으면 열기)
+    fn io_fid(&self, write: bool) -> VfsResult<u32> {
+        let mut io = self.io.lock();
+        if let Some((fid, writable)) = *io {
+            if write && !writable {
+                return Err(VfsError::PermissionDenied);
+            }
+            return Ok(fid);
+        }
+
+        let (fid, writable) = match self.client.open(self.fid, proto::O_RDWR) {
+            Ok(fid) => (fid, true),
+            Err(VfsError::PermissionDenied | VfsError::ReadOnly) if !write => {
+                (self.client.open(self.fid, proto::O_RDONLY)?, false)
+            }
+            Err(e) => return Err(e),
+        };
+        *io = Some((fid, writable));
+        Ok(fid)
+    }
+
+    /// 이 디렉토리의 자식 노드
+    fn child(&self, name: &str) -> VfsResult<Arc<P9Node>> {
+        let (fid, qid) = self.client.walk(self.fid, &[name])?;
+        Ok(Arc::new(P9Node::new(self.client.clone(), fid, qid)))
+    }
+
+    fn unlinkat(&self, name: &str, flags: u32) -> VfsResult<()> {
+        let mut msg = Message::new(proto::TUNLINKAT, TAG);
+        msg.u32(self.fid).str(name).u32(flags);
+        self.client.rpc(msg, proto::TUNLINKAT).map(|_| ())
+    }
+}
+
+impl Drop for P9Node {
+    fn drop(&mut self) {
+        if let Some((fid, _)) = self.io.lock().take() {
+            self.client.clunk(fid);
+        }
+        self.client.clunk(self.fid);
+    }
+}
+
+impl VNode for P9Node {
+    fn node_type(&self) -> VNodeType {
+        self.qid.node_type()
+    }
+
+    fn read(&self, offset: usize, buf: &mut [u8]) -> VfsResult<usize> {
+        if self.node_type() == VNodeType::Directory {
+            return Err(VfsError::IsADirectory);
+        }
+        let fid = self.io_fid(false)?;
+
+        let mut done = 0;
+        while done < buf.len() {
+            let count = (buf.len() - done).min(self.client.io_unit());
+            let mut msg = Message::new(proto::TREAD, TAG);
+            msg.u32(fid).u64((offset + done) as u64).u32(count as u32);
+            let resp = self.client.rpc(msg, proto::TREAD)?;
+
+            let mut r = Reader::new(&resp);
+            let n = (r.u32()? as usize).min(count);
+            buf[done..done + n].copy_from_slice(r.bytes(n)?);
+            done += n;
+            if n < count {
+                break;
+            }
+        }
+        Ok(done)
+    }
+
+    fn write(&self, offset: usize, buf: &[u8]) -> VfsResult<usize> {
+        if self.node_type() == VNodeType::Directory {
+            return Err(VfsError::IsADirectory);
+        }
+        let fid = self.io_fid(true)?;
+
+        let mut done = 0;
+        while done < buf.len() {
+            let chunk = &buf[done..(done + self.client.io_unit()).min(buf.len())];
+            let mut msg = Message::new(proto::TWRITE, TAG);
+            msg.u32(fid).u64((offset + done) as u64).u32(chunk.len() as u32).bytes(chunk);
+            let resp = self.client.rpc(msg, proto::TWRITE)?;
+
+            let n = Reader::new(&resp).u32()? as usize;
+            done += n;
+            if n < chunk.len() {
+                break;
+            }
+        }
+        Ok(done)
+    }
+
+    fn truncate(&self, size: u64) -> VfsResult<()> {
+        self.client.setattr(self.fid, proto::SETATTR_SIZE, 0, size)
+    }
+
+    fn lookup(&self, name: &str) -> VfsResult<Arc<dyn VNode>> {
+        if self.node_type() != VNodeType::Directory {
+            return Err(VfsError::NotADirectory);
+        }
+        Ok(self.child(name)?)
+    }
+
+    fn create(&self, name: &str, node_type: VNodeType, mode: FileMode) -> VfsResult<Arc<dyn VNode>> {
+        match node_type {
+            VNodeType::File => {
+                // Tlcreate는 디렉토리 fid를 새 파일의 열린 fid로 바꾸므로 복제본 사용
+                let (iofid, _) = self.client.walk(self.fid, &[])?;
+                let mut msg = Message::new(proto::TLCREATE, TAG);
+                msg.u32(iofid)
+                    .str(name)
+                    .u32(proto::O_RDWR | proto::O_CREAT | proto::O_EXCL)
+                    .u32(mode.0)
+                    .u32(0);
+                if let Err(e) = self.client.rpc(msg, proto::TLCREATE) {
+                    self.client.clunk(iofid);
+                    return Err(e);
+                }
+                let node = self.child(name)?;
+                *node.io.lock() = Some((iofid, true));
+                Ok(node)
+            }
+            VNodeType::Directory => {
+                let mut msg = Message::new(proto::TMKDIR, TAG);
+                msg.u32(self.fid).str(name).u32(mode.0).u32(0);
+                self.client.rpc(msg, proto::TMKDIR)?;
+                Ok(self.child(name)?)
+            }
+            _ => Err(VfsError::NotSupported),
+        }
+    }
+
+    fn unlink(&self, name: &str) -> VfsResult<()> {
+        self.unlinkat(name, 0)
+    }
+
+    fn rmdir(&self, name: &str) -> VfsResult<()> {
+        self.unlinkat(name, proto::AT_REMOVEDIR)
+    }
+
+    fn rename(&self, old_name: &str, new_name: &str) -> VfsResult<()> {
+        let mut msg = Message::new(proto::TRENAMEAT, TAG);
+        msg.u32(self.fid).str(old_name).u32(self.fid).str(new_name);
+        self.client.rpc(msg, proto::TRENAMEAT).map(|_| ())
+    }
+
+    fn readdir(&self) -> VfsResult<Vec<DirEntry>> {
+        if self.node_type() != VNodeType::Directory {
+            return Err(VfsError::NotADirectory);
+        }
+        let fid = self.client.open(self.fid, proto::O_RDONLY | proto::O_DIRECTORY)?;
+
+        let mut entries = Vec::new();
+        let result = (|| {
+            let mut offset = 0u64;
+            loop {
+                let mut msg = Message::new(proto::TREADDIR, TAG);
+                msg.u32(fid).u64(offset).u32(self.client.io_unit() as u32);
+                let resp = self.client.rpc(msg, proto::TREADDIR)?;
+
+                let mut r = Reader::new(&resp);
+                let count = r.u32()? as usize;
+                if count == 0 {
+                    return Ok(());
+                }
+                let mut r = Reader::new(r.bytes(count)?);
+                while r.remaining() > 0 {
+                    let _qid = r.qid()?;
+                    offset = r.u64()?;
+                    let d_type = r.u8()?;
+                    let name = r.str()?;
+                    if name != "." && name != ".." {
+                        entries.push(DirEntry {
+                            name,
+                            node_type: proto::dirent_type(d_type),
+                        });
+                    }
+                }
+            }
+        })();
+
+        self.client.clunk(fid);
+        result.map(|()| entries)
+    }
+
+    fn stat(&self) -> VfsResult<Stat> {
+        self.client.getattr(self.fid)
+    }
+
+    fn chmod(&self, mode: FileMode) -> VfsResult<()> {
+        self.client.setattr(self.fid, proto::SETATTR_MODE, mode.0, 0)
+    }
+
+    fn sync(&self) -> VfsResult<()> {
+        let Some((fid, _)) = *self.io.lock() else {
+            return Ok(());
+        };
+        let mut msg = Message::new(proto::TFSYNC, TAG);
+        msg.u32(fid).u32(0);
+        self.client.rpc(msg, proto::TFSYNC).map(|_| ())
+    }
+
+    fn readlink(&self) -> VfsResult<String> {
+        if self.node_type() != VNodeType::Symlink {
+            return Err(VfsError::InvalidArgument);
+        }
+        let mut msg = Message::new(proto::TREADLINK, TAG);
+        msg.u32(self.fid);
+        let resp = self.client.rpc(msg, proto::TREADLINK)?;
+        Reader::new(&resp).str()
+    }
+
+    fn symlink(&self, name: &str, target: &str) -> VfsResult<Arc<dyn VNode>> {
+        let mut msg = Message::new(proto::TSYMLINK, TAG);
+        msg.u32(self.fid).str(name).str(target).u32(0);
+        self.client.rpc(msg, proto::TSYMLINK)?;
+        Ok(self.child(name)?)
+    }
+
+    fn lock_key(&self) -> Option<(usize, u64)> {
+        Some((Arc::as_ptr(&self.client) as usize, self.qid.path))
+    }
+}
+
+/// 9P 파일시스템
+pub struct P9Fs {
+    client: Arc<Client>,
+    root: Arc<P9Node>,
+}
+
+impl FileSystem for P9Fs {
+    fn name(&self) -> &str {
+        "9p"
+    }
+
+    fn root(&self) -> Arc<dyn VNode> {
+        self.root.clone()
+    }
+
+    fn statfs(&self) -> VfsResult<FsStats> {
+        let mut msg = Message::new(proto::TSTATFS, TAG);
+        msg.u32(ROOT_FID);
+        let resp = self.client.rpc(msg, proto::TSTATFS)?;
+
+        let mut r = Reader::new(&resp);
+        let _fs_type = r.u32()?;
+        let bsize = r.u32()?;
+        let blocks = r.u64()?;
+        let bfree = r.u64()?;
+        let _bavail = r.u64()?;
+        let files = r.u64()?;
+        let ffree = r.u64()?;
+        Ok(FsStats {
+            fs_type: String::from("9p"),
+            block_size: bsize as u64,
+            total_blocks: blocks,
+            free_blocks: bfree,
+            total_inodes: files,
+            free_inodes: ffree,
+        })
+    }
+}
+
+/// 마운트 태그 `tag`의 호스트 디렉토리에 연결
+pub fn mount_9p(tag: &str) -> VfsResult<Arc<dyn FileSystem>> {
+    let transport = crate::virtio::p9::find(tag).ok_or(VfsError::NotFound)?;
+    let client = Arc::new(Client::connect(transport)?);
+
+    let mut msg = Message::new(proto::TATTACH, TAG);
+    msg.u32(ROOT_FID).u32(proto::NOFID).str(UNAME).str("").u32(0);
+    let resp = client.rpc(msg, proto::TATTACH)?;
+    let qid = Reader::new(&resp).qid()?;
+
+    crate::kprintln!("[9p] Attached to '{}' (msize {})", tag, client.msize);
+    let root = Arc::new(P9Node::new(client.clone(), ROOT_FID, qid));
+    Ok(Arc::new(P9Fs { client, root }))
+}
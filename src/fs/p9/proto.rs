@@ -0,0 +1,248 @@
+//! 9P2000.L 메시지 인코딩/디코딩
+//!
+//! 모든 메시지는 `size[4] type[1] tag[2]` 헤더로 시작하고 정수는 리틀 엔디언,
+//! 문자열은 `len[2]` + UTF-8 바이트입니다.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::fs::{VfsError, VfsResult, VNodeType};
+
+/// 메시지 헤더 크기
+pub const HEADER_SIZE: usize = 7;
+/// Tread/Twrite 헤더 크기 (msize에서 빼면 한 번에 옮길 수 있는 데이터 크기)
+pub const IO_HEADER_SIZE: usize = 24;
+
+/// 태그 없음 (Tversion 전용)
+pub const NOTAG: u16 = 0xFFFF;
+/// fid 없음 (Tattach의 afid)
+pub const NOFID: u32 = 0xFFFF_FFFF;
+
+// 메시지 종류 (T = 요청, R = 응답 = T + 1)
+pub const RLERROR: u8 = 7;
+pub const TSTATFS: u8 = 8;
+pub const TLOPEN: u8 = 12;
+pub const TLCREATE: u8 = 14;
+pub const TSYMLINK: u8 = 16;
+pub const TREADLINK: u8 = 22;
+pub const TGETATTR: u8 = 24;
+pub const TSETATTR: u8 = 26;
+pub const TREADDIR: u8 = 40;
+pub const TFSYNC: u8 = 50;
+pub const TMKDIR: u8 = 72;
+pub const TRENAMEAT: u8 = 74;
+pub const TUNLINKAT: u8 = 76;
+pub const TVERSION: u8 = 100;
+pub const TATTACH: u8 = 104;
+pub const TWALK: u8 = 110;
+pub const TREAD: u8 = 116;
+pub const TWRITE: u8 = 118;
+pub const TCLUNK: u8 = 120;
+
+/// qid 종류 비트
+pub const QTDIR: u8 = 0x80;
+pub const QTSYMLINK: u8 = 0x02;
+
+/// Linux open 플래그 (Tlopen/Tlcreate)
+pub const O_RDONLY: u32 = 0;
+pub const O_RDWR: u32 = 2;
+pub const O_CREAT: u32 = 0o100;
+pub const O_EXCL: u32 = 0o200;
+pub const O_DIRECTORY: u32 = 0o200000;
+
+/// Tunlinkat 플래그
+pub const AT_REMOVEDIR: u32 = 0x200;
+
+/// Tgetattr 요청 마스크 (기본 필드 전부)
+pub const GETATTR_BASIC: u64 = 0x7FF;
+
+/// Tsetattr 유효 비트
+pub const SETATTR_MODE: u32 = 0x1;
+pub const SETATTR_SIZE: u32 = 0x8;
+
+/// 파일 종류 비트 (`st_mode`)
+const S_IFMT: u32 = 0o170000;
+const S_IFDIR: u32 = 0o040000;
+const S_IFLNK: u32 = 0o120000;
+const S_IFCHR: u32 = 0o020000;
+const S_IFBLK: u32 = 0o060000;
+const S_IFIFO: u32 = 0o010000;
+const S_IFSOCK: u32 = 0o140000;
+
+/// 서버 파일 식별자
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Qid {
+    pub kind: u8,
+    pub version: u32,
+    pub path: u64,
+}
+
+impl Qid {
+    pub fn node_type(&self) -> VNodeType {
+        if self.kind & QTDIR != 0 {
+            VNodeType::Directory
+        } else if self.kind & QTSYMLINK != 0 {
+            VNodeType::Symlink
+        } else {
+            VNodeType::File
+        }
+    }
+}
+
+/// `st_mode`의 파일 종류
+pub fn mode_type(mode: u32) -> VNodeType {
+    match mode & S_IFMT {
+        S_IFDIR => VNodeType::Directory,
+        S_IFLNK => VNodeType::Symlink,
+        S_IFCHR => VNodeType::CharDevice,
+        S_IFBLK => VNodeType::BlockDevice,
+        S_IFIFO => VNodeType::Fifo,
+        S_IFSOCK => VNodeType::Socket,
+        _ => VNodeType::File,
+    }
+}
+
+/// Treaddir 엔트리의 `d_type`
+pub fn dirent_type(d_type: u8) -> VNodeType {
+    match d_type {
+        1 => VNodeType::Fifo,
+        2 => VNodeType::CharDevice,
+        4 => VNodeType::Directory,
+        6 => VNodeType::BlockDevice,
+        10 => VNodeType::Symlink,
+        12 => VNodeType::Socket,
+        _ => VNodeType::File,
+    }
+}
+
+/// Rlerror의 Linux errno를 VFS 에러로 변환
+pub fn errno_to_vfs(errno: u32) -> VfsError {
+    match errno {
+        1 | 13 => VfsError::PermissionDenied,
+        2 => VfsError::NotFound,
+        16 => VfsError::FileBusy,
+        17 => VfsError::AlreadyExists,
+        18 => VfsError::CrossDevice,
+        20 => VfsError::NotADirectory,
+        21 => VfsError::IsADirectory,
+        22 => VfsError::InvalidArgument,
+        28 => VfsError::NoSpace,
+        30 => VfsError::ReadOnly,
+        38 | 95 => VfsError::NotSupported,
+        39 => VfsError::DirectoryNotEmpty,
+        40 => VfsError::SymlinkLoop,
+        _ => VfsError::IoError,
+    }
+}
+
+/// 요청 메시지 작성
+pub struct Message {
+    buf: Vec<u8>,
+}
+
+impl Message {
+    pub fn new(kind: u8, tag: u16) -> Self {
+        let mut msg = Self {
+            buf: Vec::with_capacity(64),
+        };
+        msg.u32(0); // size는 finish()에서 채움
+        msg.u8(kind);
+        msg.u16(tag);
+        msg
+    }
+
+    pub fn u8(&mut self, v: u8) -> &mut Self {
+        self.buf.push(v);
+        self
+    }
+
+    pub fn u16(&mut self, v: u16) -> &mut Self {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+        self
+    }
+
+    pub fn u32(&mut self, v: u32) -> &mut Self {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+        self
+    }
+
+    pub fn u64(&mut self, v: u64) -> &mut Self {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+        self
+    }
+
+    pub fn str(&mut self, s: &str) -> &mut Self {
+        self.u16(s.len() as u16);
+        self.buf.extend_from_slice(s.as_bytes());
+        self
+    }
+
+    pub fn bytes(&mut self, data: &[u8]) -> &mut Self {
+        self.buf.extend_from_slice(data);
+        self
+    }
+
+    /// size 필드를 채운 메시지 바이트
+    pub fn finish(mut self) -> Vec<u8> {
+        let size = self.buf.len() as u32;
+        self.buf[..4].copy_from_slice(&size.to_le_bytes());
+        self.buf
+    }
+}
+
+/// 응답 본문 읽기 (범위를 넘으면 `IoError`)
+pub struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    pub fn bytes(&mut self, len: usize) -> VfsResult<&'a [u8]> {
+        if self.remaining() < len {
+            return Err(VfsError::IoError);
+        }
+        let data = &self.buf[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(data)
+    }
+
+    pub fn u8(&mut self) -> VfsResult<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> VfsResult<u16> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    pub fn u32(&mut self) -> VfsResult<u32> {
+        let b = self.bytes(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub fn u64(&mut self) -> VfsResult<u64> {
+        Ok(self.u32()? as u64 | (self.u32()? as u64) << 32)
+    }
+
+    pub fn str(&mut self) -> VfsResult<String> {
+        let len = self.u16()? as usize;
+        let bytes = self.bytes(len)?;
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    pub fn qid(&mut self) -> VfsResult<Qid> {
+        Ok(Qid {
+            kind: self.u8()?,
+            version: self.u32()?,
+            path: self.u64()?,
+        })
+    }
+}
@@ -0,0 +1,119 @@
+//! 파이프 끝 VNode
+//!
+//! `ipc::pipe::Pipe`의 읽기/쓰기 끝을 VNode로 감싸 fd 테이블에 넣습니다.
+//! 끝이 만들어질 때 파이프의 읽기/쓰기 끝 수를 올리고, 마지막 참조가 사라질 때
+//! (fd를 닫을 때) 내립니다.
+//!
+//! - 익명 파이프: `create_pipe()` (`pipe2` 시스템 콜)
+//! - 이름 있는 FIFO: RamFS FIFO 노드를 열면 `OpenFile::new`가 여는 모드에 맞는 끝으로 바꿈
+//!
+//! 스트림이므로 오프셋은 무시합니다.
+
+use alloc::sync::Arc;
+
+use crate::ipc::pipe::{Pipe, PipeError};
+
+use super::{FileMode, Stat, VfsError, VfsResult, VNode, VNodeType};
+
+/// 파이프 끝
+pub struct PipeEnd {
+    pipe: Arc<Pipe>,
+    readable: bool,
+    writable: bool,
+    nonblock: bool,
+}
+
+impl PipeEnd {
+    /// 파이프 끝 생성 (읽기/쓰기 끝 수 증가)
+    pub fn new(pipe: Arc<Pipe>, readable: bool, writable: bool, nonblock: bool) -> Arc<Self> {
+        if readable {
+            pipe.open_reader();
+        }
+        if writable {
+            pipe.open_writer();
+        }
+        Arc::new(Self {
+            pipe,
+            readable,
+            writable,
+            nonblock,
+        })
+    }
+}
+
+impl Drop for PipeEnd {
+    fn drop(&mut self) {
+        if self.readable {
+            self.pipe.close_reader();
+        }
+        if self.writable {
+            self.pipe.close_writer();
+        }
+    }
+}
+
+fn pipe_error(e: PipeError) -> VfsError {
+    match e {
+        PipeError::WouldBlock => VfsError::WouldBlock,
+        PipeError::BrokenPipe => VfsError::BrokenPipe,
+    }
+}
+
+impl VNode for PipeEnd {
+    fn node_type(&self) -> VNodeType {
+        VNodeType::Fifo
+    }
+
+    fn read(&self, _offset: usize, buf: &mut [u8]) -> VfsResult<usize> {
+        if !self.readable {
+            return Err(VfsError::PermissionDenied);
+        }
+        self.pipe.read(buf, self.nonblock).map_err(pipe_error)
+    }
+
+    fn write(&self, _offset: usize, buf: &[u8]) -> VfsResult<usize> {
+        if !self.writable {
+            return Err(VfsError::PermissionDenied);
+        }
+        self.pipe.write(buf, self.nonblock).map_err(pipe_error)
+    }
+
+    fn poll(&self, events: u16, waiter: Option<crate::proc::Tid>) -> u16 {
+        self.pipe.poll(self.readable, self.writable, events, waiter)
+    }
+
+    fn stat(&self) -> VfsResult<Stat> {
+        Ok(Stat {
+            node_type: VNodeType::Fifo,
+            mode: FileMode::new(0o600),
+            size: self.pipe.available() as u64,
+            ..Default::default()
+        })
+    }
+}
+
+/// 익명 파이프 생성
+///
+/// # Returns
+/// (읽기 끝, 쓰기 끝)
+pub fn create_pipe(nonblock: bool) -> (Arc<dyn VNode>, Arc<dyn VNode>) {
+    let pipe = Pipe::new();
+    let reader = PipeEnd::new(pipe.clone(), true, false, nonblock);
+    let writer = PipeEnd::new(pipe, false, true, nonblock);
+    (reader, writer)
+}
+
+/// 이름 있는 FIFO 생성 (`mkfifo`)
+///
+/// FIFO를 만들 수 있는 파일시스템은 RamFS뿐이며, 다른 곳에서는 `NotSupported`입니다.
+pub fn mkfifo(path: &str, mode: FileMode) -> VfsResult<()> {
+    let path = super::path::normalize(path)?;
+    let pos = path.rfind('/').ok_or(VfsError::InvalidPath)?;
+    let name = &path[pos + 1..];
+    if name.is_empty() {
+        return Err(VfsError::InvalidPath);
+    }
+    let parent = super::lookup_path(if pos == 0 { "/" } else { &path[..pos] })?;
+    parent.create(name, VNodeType::Fifo, mode)?;
+    Ok(())
+}
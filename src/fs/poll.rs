@@ -0,0 +1,102 @@
+//! fd 다중 대기 (poll)
+//!
+//! 여러 fd 중 하나라도 읽기/쓰기 가능해질 때까지 기다립니다 (`ppoll` 시스템 콜).
+//! 각 노드는 `VNode::poll(events, waiter)`로 지금 준비된 이벤트를 알려주고, 준비되지
+//! 않았으면 `waiter` 스레드를 자기 대기 목록(`proc::WaitList`)에 등록합니다. 상태가
+//! 바뀐 노드가 대기 목록을 깨우면 `poll()`은 모든 fd를 다시 확인합니다.
+//!
+//! | 노드 | 준비 조건 |
+//! |------|-----------|
+//! | 일반 파일, 대부분의 장치 (기본 구현) | 항상 `POLLIN`/`POLLOUT` |
+//! | 파이프 읽기 끝 | 데이터가 있으면 `POLLIN`, 쓰기 끝이 모두 닫혔으면 `POLLHUP` |
+//! | 파이프 쓰기 끝 | 공간이 있으면 `POLLOUT`, 읽기 끝이 모두 닫혔으면 `POLLERR` |
+//! | `/dev/console` | TTY에서 읽을 수 있으면 `POLLIN`, 쓰기는 항상 `POLLOUT` |
+//! | `/dev/input/eventN` | 그 파일이 읽지 않은 이벤트가 있으면 `POLLIN` |
+//!
+//! 콘솔 입력과 입력 이벤트는 IRQ에서 들어오는데 인터럽트 핸들러는 스레드를 깨울 수 없으므로,
+//! 기다리는 동안 `POLL_RECHECK_US`마다 깨어나 다시 확인합니다.
+
+use alloc::sync::Arc;
+
+use crate::proc::{self, Tid};
+
+use super::fd::FdTable;
+use super::{VfsError, VfsResult};
+
+/// 읽을 데이터가 있음
+pub const POLLIN: u16 = 0x001;
+/// 쓸 수 있음
+pub const POLLOUT: u16 = 0x004;
+/// 에러 (요청하지 않아도 보고)
+pub const POLLERR: u16 = 0x008;
+/// 상대가 끊김 (요청하지 않아도 보고)
+pub const POLLHUP: u16 = 0x010;
+/// 열리지 않은 fd (요청하지 않아도 보고)
+pub const POLLNVAL: u16 = 0x020;
+
+/// `VNode::poll` 기본 구현의 결과 (Linux `DEFAULT_POLLMASK`)
+pub const DEFAULT_POLLMASK: u16 = POLLIN | POLLOUT;
+
+/// 대기 중 다시 확인하는 간격 (IRQ로 깨울 수 없는 콘솔 입력용)
+pub const POLL_RECHECK_US: u64 = 10_000;
+
+/// `struct pollfd` (Linux 레이아웃, 8바이트)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PollFd {
+    pub fd: i32,
+    pub events: u16,
+    pub revents: u16,
+}
+
+/// fd들의 준비 상태를 채우고 준비된 fd 수를 반환
+fn scan(table: &Arc<FdTable>, fds: &mut [PollFd], waiter: Option<Tid>) -> usize {
+    let mut ready = 0;
+    for pfd in fds.iter_mut() {
+        pfd.revents = if pfd.fd < 0 {
+            // 음수 fd는 건너뜀 (Linux와 같음)
+            0
+        } else {
+            match table.get(pfd.fd) {
+                Ok(file) => {
+                    let wanted = pfd.events | POLLERR | POLLHUP;
+                    file.vnode.poll(wanted, waiter) & wanted
+                }
+                Err(_) => POLLNVAL,
+            }
+        };
+        if pfd.revents != 0 {
+            ready += 1;
+        }
+    }
+    ready
+}
+
+/// fd 중 하나라도 준비될 때까지 대기 (`revents` 채움)
+///
+/// `timeout_us`가 `None`이면 무기한, `Some(0)`이면 기다리지 않고 확인만 합니다.
+///
+/// # Returns
+/// 준비된 fd 수 (시간이 다 되면 0)
+///
+/// # Errors
+/// * `Interrupted` - 기다리는 중 시그널 도착
+pub fn poll(table: &Arc<FdTable>, fds: &mut [PollFd], timeout_us: Option<u64>) -> VfsResult<usize> {
+    let deadline = timeout_us.map(|us| crate::time::now_us().saturating_add(us));
+    let waiter = proc::current_tid();
+    loop {
+        let ready = scan(table, fds, waiter);
+        if ready > 0 || timeout_us == Some(0) {
+            return Ok(ready);
+        }
+        let now = crate::time::now_us();
+        if deadline.is_some_and(|d| now >= d) {
+            return Ok(0);
+        }
+        if waiter.is_some_and(proc::signal::has_pending) {
+            return Err(VfsError::Interrupted);
+        }
+        let slice = deadline.map_or(POLL_RECHECK_US, |d| (d - now).min(POLL_RECHECK_US));
+        proc::park_timeout(slice);
+    }
+}
@@ -0,0 +1,472 @@
+//! RamFS 스냅샷 아카이브
+//!
+//! RamFS 트리(또는 하위 트리)를 하나의 아카이브 파일로 직렬화해 영구 저장소에
+//! 저장하고, 다음 부팅 때 되살립니다. 개발 중 잦은 재부팅 사이에 작업 파일을
+//! 유지하기 위한 용도입니다.
+//!
+//! 저장 대상은 두 가지 형식을 받습니다:
+//! - `/dev/vda:/backup.img` - 블록 디바이스의 FAT32 볼륨 안 경로 (마운트 불필요)
+//! - `/mnt/backup.img` - 일반 VFS 경로
+//!
+//! 부팅 시 bootargs에 `ramfs.restore=/dev/vda:/backup.img`가 있으면 블록 디바이스
+//! 등록 직후 루트로 복원합니다.
+//!
+//! # 포맷 (little endian)
+//!
+//! ```text
+//! 헤더 (32 바이트)
+//!   magic     [u8; 8]  "KRFSARC1"
+//!   version   u32
+//!   count     u32      항목 수
+//!   body_len  u64      본문 길이
+//!   checksum  u32      본문 FNV-1a
+//!   reserved  u32
+//! 항목 (전위 순회, 부모 디렉토리가 항상 먼저 나옴)
+//!   kind      u8       1 = 디렉토리, 2 = 파일, 3 = 심볼릭 링크
+//!   mode      u16
+//!   path_len  u16
+//!   data_len  u64      파일 내용 또는 링크 대상 길이
+//!   path      [u8]     하위 트리 기준 상대 경로 ("" = 하위 트리 루트)
+//!   data      [u8]
+//! ```
+//!
+//! 마운트된 파일시스템(/dev, /mnt 등)은 RamFS 노드가 아니므로 포함되지 않습니다.
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::block;
+use crate::fs::{self, path, FileMode, FileSystem, VfsError, VfsResult, VNode, VNodeType};
+use crate::kprintln;
+
+/// 아카이브 매직
+const MAGIC: &[u8; 8] = b"KRFSARC1";
+
+/// 포맷 버전
+const VERSION: u32 = 1;
+
+/// 헤더 크기
+const HEADER_SIZE: usize = 32;
+
+/// 항목 고정부 크기 (kind + mode + path_len + data_len)
+const ENTRY_HEADER_SIZE: usize = 1 + 2 + 2 + 8;
+
+/// 디렉토리 중첩 한계 (심볼릭 링크는 따라가지 않으므로 순환은 없음)
+const MAX_DEPTH: usize = 32;
+
+/// bootargs 복원 옵션
+const RESTORE_ARG: &str = "ramfs.restore";
+
+const KIND_DIR: u8 = 1;
+const KIND_FILE: u8 = 2;
+const KIND_SYMLINK: u8 = 3;
+
+/// 저장/복원 결과 요약
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArchiveStats {
+    /// 디렉토리 수
+    pub dirs: usize,
+    /// 파일 수
+    pub files: usize,
+    /// 심볼릭 링크 수
+    pub symlinks: usize,
+    /// 파일 내용 총 바이트
+    pub data_bytes: usize,
+    /// 아카이브 크기
+    pub archive_size: usize,
+}
+
+/// RamFS 하위 트리를 아카이브로 저장
+///
+/// # Arguments
+/// * `target` - 저장 위치 (`<device>:<path>` 또는 VFS 경로)
+/// * `subtree` - 저장할 RamFS 경로 (`/` = 전체)
+pub fn save(target: &str, subtree: &str) -> VfsResult<ArchiveStats> {
+    let start = resolve_ramfs(subtree)?;
+    let mut stats = ArchiveStats::default();
+
+    let mut body = Vec::new();
+    let mut count = 0u32;
+    serialize(&start, "", &mut body, &mut count, &mut stats, 0)?;
+
+    let mut archive = Vec::with_capacity(HEADER_SIZE + body.len());
+    archive.extend_from_slice(MAGIC);
+    archive.extend_from_slice(&VERSION.to_le_bytes());
+    archive.extend_from_slice(&count.to_le_bytes());
+    archive.extend_from_slice(&(body.len() as u64).to_le_bytes());
+    archive.extend_from_slice(&fnv1a(&body).to_le_bytes());
+    archive.extend_from_slice(&0u32.to_le_bytes());
+    archive.extend_from_slice(&body);
+    stats.archive_size = archive.len();
+
+    let target = Target::open(target)?;
+    let node = target.create_file()?;
+    node.truncate(0)?;
+    write_all(&node, &archive)?;
+    node.sync()?;
+    target.close()?;
+
+    Ok(stats)
+}
+
+/// 아카이브를 RamFS로 복원
+///
+/// 기존 디렉토리는 유지하고, 같은 이름의 파일은 아카이브 내용으로 덮어씁니다.
+///
+/// # Arguments
+/// * `target` - 아카이브 위치 (`<device>:<path>` 또는 VFS 경로)
+/// * `dest` - 복원할 RamFS 디렉토리 (`/` = 루트)
+pub fn load(target: &str, dest: &str) -> VfsResult<ArchiveStats> {
+    let archive = {
+        let target = Target::open(target)?;
+        let node = target.lookup_file()?;
+        let data = read_all(&node)?;
+        target.close()?;
+        data
+    };
+
+    let (count, body) = parse_header(&archive)?;
+    let dest = resolve_ramfs(dest)?;
+    if dest.node_type() != VNodeType::Directory {
+        return Err(VfsError::NotADirectory);
+    }
+
+    let mut stats = ArchiveStats {
+        archive_size: archive.len(),
+        ..Default::default()
+    };
+    let mut offset = 0;
+    for _ in 0..count {
+        offset = restore_entry(&dest, body, offset, &mut stats)?;
+    }
+    if offset != body.len() {
+        return Err(VfsError::InvalidFormat);
+    }
+
+    Ok(stats)
+}
+
+/// bootargs의 `ramfs.restore=<target>` 처리 (부팅 시 호출)
+pub fn restore_from_bootargs() {
+    let Some(target) = crate::boot::cmdline::get(RESTORE_ARG) else {
+        return;
+    };
+
+    kprintln!("[ramfs] Restoring snapshot from {}...", target);
+    match load(&target, "/") {
+        Ok(stats) => kprintln!(
+            "[ramfs] Restored {} dirs, {} files, {} symlinks ({} bytes)",
+            stats.dirs,
+            stats.files,
+            stats.symlinks,
+            stats.data_bytes
+        ),
+        Err(e) => kprintln!("[ramfs] Restore failed: {}", e),
+    }
+}
+
+/// 루트 RamFS 안에서 경로 해석 (마운트 무시)
+fn resolve_ramfs(p: &str) -> VfsResult<Arc<dyn VNode>> {
+    let root_fs = fs::root_fs().ok_or(VfsError::NotFound)?;
+    if root_fs.name() != "ramfs" {
+        return Err(VfsError::NotSupported);
+    }
+    path::resolve(&root_fs.root(), p)
+}
+
+/// 노드와 그 하위를 전위 순회하며 직렬화
+fn serialize(
+    node: &Arc<dyn VNode>,
+    rel: &str,
+    out: &mut Vec<u8>,
+    count: &mut u32,
+    stats: &mut ArchiveStats,
+    depth: usize,
+) -> VfsResult<()> {
+    if depth > MAX_DEPTH {
+        return Err(VfsError::InvalidPath);
+    }
+
+    let stat = node.stat()?;
+    let mode = stat.mode.0 as u16;
+
+    match node.node_type() {
+        VNodeType::Directory => {
+            push_entry(out, KIND_DIR, mode, rel, &[])?;
+            stats.dirs += 1;
+            *count += 1;
+
+            for entry in node.readdir()? {
+                if entry.name == "." || entry.name == ".." {
+                    continue;
+                }
+                let child = node.lookup(&entry.name)?;
+                let child_rel = if rel.is_empty() {
+                    entry.name.clone()
+                } else {
+                    path::join(rel, &entry.name)
+                };
+                serialize(&child, &child_rel, out, count, stats, depth + 1)?;
+            }
+        }
+        VNodeType::File => {
+            let data = read_all(node)?;
+            push_entry(out, KIND_FILE, mode, rel, &data)?;
+            stats.files += 1;
+            stats.data_bytes += data.len();
+            *count += 1;
+        }
+        VNodeType::Symlink => {
+            let target = node.readlink()?;
+            push_entry(out, KIND_SYMLINK, mode, rel, target.as_bytes())?;
+            stats.symlinks += 1;
+            *count += 1;
+        }
+        // FIFO는 내용 없는 통신 지점이라 저장하지 않음 (디바이스 노드는 RamFS에 생기지 않음)
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// 항목 하나 기록
+fn push_entry(out: &mut Vec<u8>, kind: u8, mode: u16, rel: &str, data: &[u8]) -> VfsResult<()> {
+    if rel.len() > u16::MAX as usize {
+        return Err(VfsError::InvalidPath);
+    }
+    out.push(kind);
+    out.extend_from_slice(&mode.to_le_bytes());
+    out.extend_from_slice(&(rel.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    out.extend_from_slice(rel.as_bytes());
+    out.extend_from_slice(data);
+    Ok(())
+}
+
+/// 헤더 검증 후 (항목 수, 본문) 반환
+fn parse_header(archive: &[u8]) -> VfsResult<(u32, &[u8])> {
+    if archive.len() < HEADER_SIZE || &archive[..8] != MAGIC {
+        return Err(VfsError::InvalidFormat);
+    }
+
+    let version = read_u32(archive, 8);
+    let count = read_u32(archive, 12);
+    let body_len = read_u64(archive, 16) as usize;
+    let checksum = read_u32(archive, 24);
+
+    if version != VERSION {
+        return Err(VfsError::NotSupported);
+    }
+    if archive.len() - HEADER_SIZE != body_len {
+        return Err(VfsError::InvalidFormat);
+    }
+
+    let body = &archive[HEADER_SIZE..];
+    if fnv1a(body) != checksum {
+        kprintln!("[ramfs] Archive checksum mismatch");
+        return Err(VfsError::InvalidFormat);
+    }
+
+    Ok((count, body))
+}
+
+/// `offset`의 항목 하나를 `dest` 아래에 복원하고 다음 오프셋 반환
+fn restore_entry(
+    dest: &Arc<dyn VNode>,
+    body: &[u8],
+    offset: usize,
+    stats: &mut ArchiveStats,
+) -> VfsResult<usize> {
+    if body.len() - offset < ENTRY_HEADER_SIZE {
+        return Err(VfsError::InvalidFormat);
+    }
+    let kind = body[offset];
+    let mode = FileMode::new(read_u16(body, offset + 1) as u32);
+    let path_len = read_u16(body, offset + 3) as usize;
+    let data_len = read_u64(body, offset + 5) as usize;
+
+    let path_start = offset + ENTRY_HEADER_SIZE;
+    let data_start = path_start + path_len;
+    if data_len > body.len() || data_start > body.len() - data_len {
+        return Err(VfsError::InvalidFormat);
+    }
+    let rel = core::str::from_utf8(&body[path_start..data_start])
+        .map_err(|_| VfsError::InvalidFormat)?;
+    let data = &body[data_start..data_start + data_len];
+
+    // 하위 트리 루트 항목: 권한만 반영
+    if rel.is_empty() {
+        if kind != KIND_DIR {
+            return Err(VfsError::InvalidFormat);
+        }
+        dest.chmod(mode)?;
+        stats.dirs += 1;
+        return Ok(data_start + data_len);
+    }
+
+    // ".." 등으로 dest 밖을 가리키지 못하게 함
+    if rel.starts_with('/') || rel.split('/').any(|c| c.is_empty() || c == "." || c == "..") {
+        return Err(VfsError::InvalidPath);
+    }
+    let (parent, name) = path::resolve_parent(dest, rel)?;
+
+    match kind {
+        KIND_DIR => {
+            let node = match parent.lookup(&name) {
+                Ok(node) if node.node_type() == VNodeType::Directory => node,
+                Ok(_) => return Err(VfsError::NotADirectory),
+                Err(_) => parent.create(&name, VNodeType::Directory, mode)?,
+            };
+            node.chmod(mode)?;
+            stats.dirs += 1;
+        }
+        KIND_FILE => {
+            let node = match parent.lookup(&name) {
+                Ok(node) if node.node_type() == VNodeType::File => node,
+                Ok(_) => return Err(VfsError::AlreadyExists),
+                Err(_) => parent.create(&name, VNodeType::File, mode)?,
+            };
+            node.truncate(0)?;
+            write_all(&node, data)?;
+            node.chmod(mode)?;
+            stats.files += 1;
+            stats.data_bytes += data.len();
+        }
+        KIND_SYMLINK => {
+            let target = core::str::from_utf8(data).map_err(|_| VfsError::InvalidFormat)?;
+            match parent.lookup(&name) {
+                Ok(node) if node.node_type() == VNodeType::Symlink => {
+                    if node.readlink()? != target {
+                        parent.unlink(&name)?;
+                        parent.symlink(&name, target)?;
+                    }
+                }
+                Ok(_) => return Err(VfsError::AlreadyExists),
+                Err(_) => {
+                    parent.symlink(&name, target)?;
+                }
+            }
+            stats.symlinks += 1;
+        }
+        _ => return Err(VfsError::InvalidFormat),
+    }
+
+    Ok(data_start + data_len)
+}
+
+/// 아카이브 저장 위치
+///
+/// `<device>:<path>` 형식이면 해당 블록 디바이스의 FAT32를 전역 마운트
+/// 테이블에 올리지 않고 직접 열어 사용합니다.
+struct Target {
+    /// 디바이스에서 직접 연 파일시스템 (VFS 경로면 None)
+    fs: Option<Arc<dyn FileSystem>>,
+    /// 파일 경로 (`fs`가 있으면 그 안의 경로)
+    path: String,
+}
+
+impl Target {
+    fn open(spec: &str) -> VfsResult<Self> {
+        let Some((device, file)) = spec.split_once(':') else {
+            return Ok(Self { fs: None, path: path::normalize(spec)? });
+        };
+
+        let name = device.strip_prefix("/dev/").unwrap_or(device);
+        let dev = block::get_device(name).ok_or(VfsError::NotFound)?;
+        let fs = fs::fat32::mount_fat32(dev)?;
+
+        Ok(Self {
+            fs: Some(fs),
+            path: path::normalize(file)?,
+        })
+    }
+
+    /// 대상 파일 검색
+    fn lookup_file(&self) -> VfsResult<Arc<dyn VNode>> {
+        let node = match &self.fs {
+            Some(fs) => path::resolve(&fs.root(), &self.path)?,
+            None => fs::lookup_path(&self.path)?,
+        };
+        if node.node_type() != VNodeType::File {
+            return Err(VfsError::IsADirectory);
+        }
+        Ok(node)
+    }
+
+    /// 대상 파일 검색, 없으면 생성
+    fn create_file(&self) -> VfsResult<Arc<dyn VNode>> {
+        if let Ok(node) = self.lookup_file() {
+            return Ok(node);
+        }
+
+        let (parent, name) = match &self.fs {
+            Some(fs) => path::resolve_parent(&fs.root(), &self.path)?,
+            None => {
+                let (parent_path, name) = path::split(&self.path);
+                (fs::lookup_path(parent_path)?, String::from(name))
+            }
+        };
+        parent.create(&name, VNodeType::File, FileMode::default_file())
+    }
+
+    /// 디바이스에서 직접 연 파일시스템 동기화 및 해제
+    fn close(self) -> VfsResult<()> {
+        if let Some(fs) = self.fs {
+            fs.sync()?;
+            fs.unmount()?;
+        }
+        Ok(())
+    }
+}
+
+/// 노드 전체 읽기
+fn read_all(node: &Arc<dyn VNode>) -> VfsResult<Vec<u8>> {
+    let size = node.stat()?.size as usize;
+    let mut data = alloc::vec![0u8; size];
+    let mut done = 0;
+    while done < size {
+        let n = node.read(done, &mut data[done..])?;
+        if n == 0 {
+            break;
+        }
+        done += n;
+    }
+    data.truncate(done);
+    Ok(data)
+}
+
+/// 노드에 전체 쓰기
+fn write_all(node: &Arc<dyn VNode>, data: &[u8]) -> VfsResult<()> {
+    let mut done = 0;
+    while done < data.len() {
+        let n = node.write(done, &data[done..])?;
+        if n == 0 {
+            return Err(VfsError::NoSpace);
+        }
+        done += n;
+    }
+    Ok(())
+}
+
+/// FNV-1a 32비트 해시
+fn fnv1a(data: &[u8]) -> u32 {
+    let mut hash = 0x811c_9dc5u32;
+    for &b in data {
+        hash ^= b as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+fn read_u16(buf: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes([buf[off], buf[off + 1]])
+}
+
+fn read_u32(buf: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes(buf[off..off + 4].try_into().unwrap())
+}
+
+fn read_u64(buf: &[u8], off: usize) -> u64 {
+    u64::from_le_bytes(buf[off..off + 8].try_into().unwrap())
+}
@@ -2,19 +2,27 @@
 //!
 //! 메모리에 파일과 디렉토리를 저장하는 간단한 파일시스템
 //! 재부팅 시 데이터가 사라짐
+//!
+//! 하드 링크는 여러 디렉토리 엔트리가 같은 `Arc<RamFsFile>`을 가리키는 방식입니다.
+//! 파일의 링크 수는 엔트리가 추가/삭제될 때 `adjust_nlink()`로 관리하고, 데이터는
+//! 마지막 엔트리와 열린 fd가 모두 사라져 `Arc`가 해제될 때 함께 해제됩니다.
 
 use alloc::boxed::Box;
 use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU32, Ordering};
 
+use crate::ipc::pipe::Pipe;
 use crate::sync::RwLock;
 
 use super::{
     DirEntry, FileMode, FileSystem, FsStats, Stat, VfsError, VfsResult, VNode, VNodeType,
 };
 
+pub mod archive;
+
 /// RamFS 파일시스템
 pub struct RamFs {
     /// 루트 디렉토리
@@ -122,6 +130,7 @@ impl VNode for RamFsDir {
         let node: Arc<dyn VNode> = match node_type {
             VNodeType::File => Arc::new(RamFsFile::new(String::from(name), mode)),
             VNodeType::Directory => Arc::new(RamFsDir::new(String::from(name), mode)),
+            VNodeType::Fifo => Arc::new(RamFsFifo::new(mode)),
             _ => return Err(VfsError::NotSupported),
         };
 
@@ -145,7 +154,72 @@ impl VNode for RamFsDir {
             }
         }
 
-        children.remove(index);
+        let (_, node) = children.remove(index);
+        if node.node_type() != VNodeType::Directory {
+            let _ = node.adjust_nlink(-1);
+        }
+        Ok(())
+    }
+
+    fn rename(&self, old_name: &str, new_name: &str) -> VfsResult<()> {
+        if new_name.is_empty() || new_name.contains('/') {
+            return Err(VfsError::InvalidArgument);
+        }
+
+        let mut children = self.children.write();
+
+        let mut index = children.iter().position(|(n, _)| n == old_name).ok_or(VfsError::NotFound)?;
+        if old_name == new_name {
+            return Ok(());
+        }
+        // 같은 노드를 가리키는 하드 링크끼리는 아무것도 하지 않음
+        if children.iter().any(|(n, node)| n == new_name && Arc::ptr_eq(node, &children[index].1)) {
+            return Ok(());
+        }
+
+        // 대상이 있으면 교체 (파일 ↔ 디렉토리 교체 불가, 디렉토리는 비어 있어야 함)
+        if let Some(target) = children.iter().position(|(n, _)| n == new_name) {
+            let is_dir = children[index].1.node_type() == VNodeType::Directory;
+            let target_node = &children[target].1;
+            match (is_dir, target_node.node_type() == VNodeType::Directory) {
+                (false, true) => return Err(VfsError::IsADirectory),
+                (true, false) => return Err(VfsError::NotADirectory),
+                (true, true) if !target_node.readdir()?.is_empty() => {
+                    return Err(VfsError::DirectoryNotEmpty);
+                }
+                _ => {}
+            }
+            let (_, replaced) = children.remove(target);
+            if replaced.node_type() != VNodeType::Directory {
+                let _ = replaced.adjust_nlink(-1);
+            }
+            if target < index {
+                index -= 1;
+            }
+        }
+
+        children[index].0 = String::from(new_name);
+        Ok(())
+    }
+
+    fn link(&self, name: &str, target: &Arc<dyn VNode>) -> VfsResult<()> {
+        if name.is_empty() || name.contains('/') {
+            return Err(VfsError::InvalidArgument);
+        }
+        if target.node_type() == VNodeType::Directory {
+            return Err(VfsError::PermissionDenied);
+        }
+
+        let mut children = self.children.write();
+
+        if children.iter().any(|(n, _)| n == name) {
+            return Err(VfsError::AlreadyExists);
+        }
+
+        // 링크 수를 관리하지 않는 노드(FIFO, 다른 파일시스템 노드)는 거부
+        target.adjust_nlink(1)?;
+        children.push((String::from(name), target.clone()));
+
         Ok(())
     }
 
@@ -180,6 +254,23 @@ impl VNode for RamFsDir {
         *m = mode;
         Ok(())
     }
+
+    fn symlink(&self, name: &str, target: &str) -> VfsResult<Arc<dyn VNode>> {
+        if name.is_empty() || name.contains('/') {
+            return Err(VfsError::InvalidArgument);
+        }
+
+        let mut children = self.children.write();
+
+        if children.iter().any(|(n, _)| n == name) {
+            return Err(VfsError::AlreadyExists);
+        }
+
+        let node: Arc<dyn VNode> = Arc::new(RamFsSymlink::new(String::from(name), String::from(target)));
+        children.push((String::from(name), node.clone()));
+
+        Ok(node)
+    }
 }
 
 /// RamFS 파일
@@ -190,6 +281,8 @@ pub struct RamFsFile {
     mode: RwLock<FileMode>,
     /// 파일 내용
     data: RwLock<Vec<u8>>,
+    /// 하드 링크 수 (이 파일을 가리키는 디렉토리 엔트리 수)
+    nlink: AtomicU32,
 }
 
 impl RamFsFile {
@@ -199,6 +292,7 @@ impl RamFsFile {
             name,
             mode: RwLock::new(mode),
             data: RwLock::new(Vec::new()),
+            nlink: AtomicU32::new(1),
         }
     }
 }
@@ -251,7 +345,7 @@ impl VNode for RamFsFile {
             node_type: VNodeType::File,
             mode: *mode,
             size: data.len() as u64,
-            nlink: 1,
+            nlink: self.nlink.load(Ordering::Relaxed),
             blocks: ((data.len() + 511) / 512) as u64,
             ..Default::default()
         })
@@ -266,6 +360,14 @@ impl VNode for RamFsFile {
     fn sync(&self) -> VfsResult<()> {
         Ok(()) // RAM 기반이므로 동기화 불필요
     }
+
+    fn adjust_nlink(&self, delta: i32) -> VfsResult<u32> {
+        let old = self
+            .nlink
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_add_signed(delta))
+            .map_err(|_| VfsError::InvalidArgument)?;
+        Ok(old.wrapping_add_signed(delta))
+    }
 }
 
 /// RamFS 심볼릭 링크
@@ -309,6 +411,52 @@ impl VNode for RamFsSymlink {
     }
 }
 
+/// RamFS FIFO (이름 있는 파이프)
+///
+/// 노드 자체는 읽거나 쓰지 않고, 열 때 `fifo()`의 파이프 끝으로 바뀝니다.
+/// 같은 FIFO를 연 fd는 모두 하나의 파이프 버퍼를 공유합니다.
+pub struct RamFsFifo {
+    /// 권한
+    mode: RwLock<FileMode>,
+    /// 파이프 버퍼
+    pipe: Arc<Pipe>,
+}
+
+impl RamFsFifo {
+    /// 새 FIFO 생성
+    pub fn new(mode: FileMode) -> Self {
+        Self {
+            mode: RwLock::new(mode),
+            pipe: Pipe::new(),
+        }
+    }
+}
+
+impl VNode for RamFsFifo {
+    fn node_type(&self) -> VNodeType {
+        VNodeType::Fifo
+    }
+
+    fn stat(&self) -> VfsResult<Stat> {
+        Ok(Stat {
+            node_type: VNodeType::Fifo,
+            mode: *self.mode.read(),
+            size: 0,
+            nlink: 1,
+            ..Default::default()
+        })
+    }
+
+    fn chmod(&self, mode: FileMode) -> VfsResult<()> {
+        *self.mode.write() = mode;
+        Ok(())
+    }
+
+    fn fifo(&self) -> Option<Arc<Pipe>> {
+        Some(self.pipe.clone())
+    }
+}
+
 /// RamFS 생성 헬퍼
 pub fn create_ramfs() -> Arc<RamFs> {
     RamFs::new()
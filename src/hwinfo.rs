@@ -0,0 +1,237 @@
+//! 하드웨어 요약
+//!
+//! 부팅이 끝난 뒤 보드, CPU, 메모리, 인터럽트 컨트롤러, UART, 타이머, VirtIO
+//! 디바이스를 한 블록으로 정리해 출력하고, 같은 내용을 `hwinfo` 명령으로 다시
+//! 볼 수 있게 저장합니다. 각 항목은 DTB, 플랫폼 설정(`drivers::config`), 보드
+//! 레지스트리, 아키텍처 ID 레지스터(aarch64 ID_AA64*, riscv64 misa)에서 읽습니다.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::drivers::config::{self, InterruptControllerConfig};
+use crate::kprintln;
+use crate::sync::RwLock;
+
+/// 하드웨어 요약
+#[derive(Debug, Clone)]
+pub struct HwSummary {
+    /// 아키텍처 이름
+    pub arch: &'static str,
+    /// 활성 보드 이름
+    pub board: String,
+    /// DTB root compatible
+    pub compatible: Vec<String>,
+    /// 온라인 CPU 수
+    pub cpus_online: u32,
+    /// 전체 CPU 수
+    pub cpus_total: u32,
+    /// 커널 권한 모드 (riscv64: M-mode 또는 SBI 위의 S-mode)
+    pub mode: Option<String>,
+    /// CPU 모델 (aarch64: MIDR 코어 이름, riscv64: ISA 문자열)
+    pub cpu_model: String,
+    /// CPU 기능 목록
+    pub cpu_features: Vec<&'static str>,
+    /// RAM 시작 주소
+    pub ram_base: u64,
+    /// RAM 크기 (바이트)
+    pub ram_size: u64,
+    /// 인터럽트 컨트롤러 설명
+    pub irq_controller: String,
+    /// UART 설명 (시리얼 포트마다 하나)
+    pub uart: Vec<String>,
+    /// 타이머 설명
+    pub timer: String,
+    /// VirtIO 디바이스 설명
+    pub virtio: Vec<String>,
+}
+
+impl HwSummary {
+    /// 요약 블록 출력
+    pub fn print(&self) {
+        const RULE: &str = "============================================================";
+        kprintln!("{}", RULE);
+        kprintln!(" kerners {} ({}) - hardware summary", env!("CARGO_PKG_VERSION"), self.arch);
+        kprintln!("{}", RULE);
+        if self.compatible.is_empty() {
+            kprintln!(" Board    : {}", self.board);
+        } else {
+            kprintln!(" Board    : {} [{}]", self.board, self.compatible.join(", "));
+        }
+        if let Some(mode) = &self.mode {
+            kprintln!(" Mode     : {}", mode);
+        }
+        kprintln!(" CPU      : {}/{} online, {}", self.cpus_online, self.cpus_total, self.cpu_model);
+        kprintln!(" Features : {}", self.cpu_features.join(" "));
+        kprintln!(" Memory   : {} MB @ {:#x}", self.ram_size / (1024 * 1024), self.ram_base);
+        kprintln!(" IRQ ctrl : {}", self.irq_controller);
+        if self.uart.is_empty() {
+            kprintln!(" UART     : unknown");
+        }
+        for (i, port) in self.uart.iter().enumerate() {
+            kprintln!(" {:<9}: {}", if i == 0 { "UART" } else { "" }, port);
+        }
+        kprintln!(" Timer    : {}", self.timer);
+        if self.virtio.is_empty() {
+            kprintln!(" VirtIO   : none");
+        }
+        for (i, dev) in self.virtio.iter().enumerate() {
+            kprintln!(" {:<9}: {}", if i == 0 { "VirtIO" } else { "" }, dev);
+        }
+        kprintln!("{}", RULE);
+    }
+}
+
+/// 부팅 시 만든 요약
+static SUMMARY: RwLock<Option<HwSummary>> = RwLock::new(None);
+
+/// 현재 하드웨어 정보 수집
+pub fn collect() -> HwSummary {
+    let dt = crate::dtb::get();
+    let platform = config::get_platform_config();
+
+    let board = crate::boards::current_board_info()
+        .map(|b| String::from(b.name))
+        .unwrap_or_else(|| String::from("unknown"));
+    let compatible = dt.map(|dt| dt.get_root_compatible()).unwrap_or_default();
+
+    let (ram_base, ram_size) = match dt.and_then(|dt| dt.get_memory().ok()) {
+        Some(mem) => (mem.base, mem.size),
+        None => (0, crate::mm::page::stats().total_pages as u64 * crate::mm::page::PAGE_SIZE as u64),
+    };
+
+    let (irq_controller, uart, timer) = match &platform {
+        Some(p) => (
+            describe_irq_controller(&p.interrupt_controller),
+            describe_uarts(p),
+            format!("{:?}, {} Hz", p.timer.timer_type, p.timer.frequency),
+        ),
+        None => (String::from("unknown"), Vec::new(), String::from("unknown")),
+    };
+
+    let virtio = crate::virtio::find_virtio_devices()
+        .iter()
+        .map(|d| format!("{:?} @ {:#x} (irq {})", d.device_type, d.mmio_base, d.irq))
+        .collect();
+
+    HwSummary {
+        arch: arch_name(),
+        board,
+        compatible,
+        cpus_online: crate::proc::percpu::online_count(),
+        cpus_total: crate::proc::percpu::total_count(),
+        mode: privilege_mode(),
+        cpu_model: crate::arch::cpu::model(),
+        cpu_features: crate::arch::cpu::features(),
+        ram_base,
+        ram_size,
+        irq_controller,
+        uart,
+        timer,
+        virtio,
+    }
+}
+
+fn arch_name() -> &'static str {
+    #[cfg(target_arch = "aarch64")]
+    {
+        "aarch64"
+    }
+    #[cfg(target_arch = "riscv64")]
+    {
+        "riscv64"
+    }
+}
+
+/// 커널 권한 모드 설명
+fn privilege_mode() -> Option<String> {
+    #[cfg(all(target_arch = "riscv64", not(feature = "riscv_smode")))]
+    {
+        Some(String::from("M-mode (no SBI firmware)"))
+    }
+    #[cfg(all(target_arch = "riscv64", feature = "riscv_smode"))]
+    {
+        use crate::arch::sbi;
+        let (major, minor) = sbi::spec_version();
+        let console = if crate::console::is_enabled("sbi") {
+            ", console via SBI"
+        } else {
+            ""
+        };
+        Some(format!("S-mode, {} SBI v{}.{}{}", sbi::impl_name(), major, minor, console))
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        None
+    }
+}
+
+/// UART 설명 (`/dev/ttyS<n>` 순서, 콘솔 표시)
+fn describe_uarts(platform: &config::PlatformConfig) -> Vec<String> {
+    platform
+        .uarts
+        .iter()
+        .enumerate()
+        .map(|(i, u)| {
+            let console = if u.base == platform.uart.base { " (console)" } else { "" };
+            format!("ttyS{}: {} @ {:#x}, irq {}{}", i, u.compatible, u.base, u.irq, console)
+        })
+        .collect()
+}
+
+/// 인터럽트 컨트롤러 설명 (버전은 하드웨어 레지스터 기준)
+fn describe_irq_controller(ic: &InterruptControllerConfig) -> String {
+    match ic {
+        #[cfg(target_arch = "aarch64")]
+        InterruptControllerConfig::Gic(gic) => {
+            let mut s = format!(
+                "GICv{}, {} IRQs, GICD @ {:#x}",
+                crate::arch::gic::arch_version(),
+                crate::arch::gic::max_irqs(),
+                gic.distributor_base
+            );
+            if crate::arch::gic::is_v3() {
+                use crate::arch::gicv3;
+                if let Some(gicr) = gic.redistributor_base {
+                    s.push_str(&format!(", GICR @ {:#x}", gicr));
+                }
+                if let Some(its) = gic.its_base {
+                    let state = if gicv3::its::is_ready() { "ready" } else { "off" };
+                    s.push_str(&format!(", ITS @ {:#x} ({})", its, state));
+                }
+                if gicv3::lpis_enabled() {
+                    s.push_str(&format!(", {} LPIs", gicv3::MAX_LPIS));
+                }
+            }
+            s
+        }
+        #[cfg(not(target_arch = "aarch64"))]
+        InterruptControllerConfig::Gic(gic) => format!("GIC{:?} @ {:#x}", gic.version, gic.distributor_base),
+        InterruptControllerConfig::Plic(plic) => format!(
+            "PLIC, {} sources, {} contexts @ {:#x}",
+            plic.num_sources, plic.num_contexts, plic.base
+        ),
+        InterruptControllerConfig::None => String::from("none"),
+    }
+}
+
+/// 하드웨어 요약을 만들어 저장하고 출력 (부팅 마지막에 호출)
+pub fn init() {
+    let summary = collect();
+    summary.print();
+    *SUMMARY.write() = Some(summary);
+}
+
+/// 부팅 시 저장한 요약 출력 (`hwinfo` 명령)
+///
+/// 온라인 CPU 수는 현재 값으로 갱신합니다.
+pub fn print() {
+    let summary = SUMMARY.read().clone();
+    match summary {
+        Some(mut summary) => {
+            summary.cpus_online = crate::proc::percpu::online_count();
+            summary.print();
+        }
+        None => collect().print(),
+    }
+}
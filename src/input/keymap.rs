@@ -0,0 +1,276 @@
+//! 키 코드 → 터미널 바이트 변환
+//!
+//! 키보드가 보고한 `EV_KEY` 이벤트(Linux 키 코드, US 배열)를 시리얼 터미널이 보내는 것과 같은
+//! 바이트로 바꿉니다. 콘솔 입력(`console::receive`)에 그대로 넣으면 셸과 TTY 줄 규칙이 UART
+//! 입력과 구분하지 않고 처리합니다.
+//!
+//! | 키 | 바이트 |
+//! |----|--------|
+//! | 글자, 숫자, 기호 | ASCII (Shift, Caps Lock 반영) |
+//! | Enter, 키패드 Enter | CR (`ICRNL`이 LF로 바꿈) |
+//! | Backspace | DEL (0x7F, `VERASE`) |
+//! | Ctrl + `@`..`_`, 글자 | 제어 문자 (`Ctrl-C` = 0x03) |
+//! | Alt + 키 | ESC 접두사 (meta) |
+//! | 화살표, Home/End, Insert/Delete, PgUp/PgDn | VT100/xterm 이스케이프 시퀀스 |
+//! | F1-F12 | `ESC O P`..`ESC [ 24 ~` |
+//!
+//! 키패드는 Num Lock이 켜진 것으로 보고 숫자와 기호를 냅니다.
+
+/// Linux 키 코드 (input-event-codes.h)
+mod key {
+    pub const LEFTCTRL: u16 = 29;
+    pub const LEFTSHIFT: u16 = 42;
+    pub const RIGHTSHIFT: u16 = 54;
+    pub const LEFTALT: u16 = 56;
+    pub const CAPSLOCK: u16 = 58;
+    pub const F1: u16 = 59;
+    pub const F10: u16 = 68;
+    pub const F11: u16 = 87;
+    pub const F12: u16 = 88;
+    pub const KPENTER: u16 = 96;
+    pub const RIGHTCTRL: u16 = 97;
+    pub const KPSLASH: u16 = 98;
+    pub const RIGHTALT: u16 = 100;
+    pub const HOME: u16 = 102;
+    pub const UP: u16 = 103;
+    pub const PAGEUP: u16 = 104;
+    pub const LEFT: u16 = 105;
+    pub const RIGHT: u16 = 106;
+    pub const END: u16 = 107;
+    pub const DOWN: u16 = 108;
+    pub const PAGEDOWN: u16 = 109;
+    pub const INSERT: u16 = 110;
+    pub const DELETE: u16 = 111;
+}
+
+/// 키 코드 0..=83 → (Shift 없음, Shift) ASCII (0 = 글자 없음)
+const KEYMAP: [(u8, u8); 84] = [
+    (0, 0),
+    (0x1B, 0x1B),
+    (b'1', b'!'),
+    (b'2', b'@'),
+    (b'3', b'#'),
+    (b'4', b'$'),
+    (b'5', b'%'),
+    (b'6', b'^'),
+    (b'7', b'&'),
+    (b'8', b'*'),
+    (b'9', b'('),
+    (b'0', b')'),
+    (b'-', b'_'),
+    (b'=', b'+'),
+    (0x7F, 0x7F),
+    (b'\t', b'\t'),
+    (b'q', b'Q'),
+    (b'w', b'W'),
+    (b'e', b'E'),
+    (b'r', b'R'),
+    (b't', b'T'),
+    (b'y', b'Y'),
+    (b'u', b'U'),
+    (b'i', b'I'),
+    (b'o', b'O'),
+    (b'p', b'P'),
+    (b'[', b'{'),
+    (b']', b'}'),
+    (b'\r', b'\r'),
+    (0, 0),
+    (b'a', b'A'),
+    (b's', b'S'),
+    (b'd', b'D'),
+    (b'f', b'F'),
+    (b'g', b'G'),
+    (b'h', b'H'),
+    (b'j', b'J'),
+    (b'k', b'K'),
+    (b'l', b'L'),
+    (b';', b':'),
+    (b'\'', b'"'),
+    (b'`', b'~'),
+    (0, 0),
+    (b'\\', b'|'),
+    (b'z', b'Z'),
+    (b'x', b'X'),
+    (b'c', b'C'),
+    (b'v', b'V'),
+    (b'b', b'B'),
+    (b'n', b'N'),
+    (b'm', b'M'),
+    (b',', b'<'),
+    (b'.', b'>'),
+    (b'/', b'?'),
+    (0, 0),
+    (b'*', b'*'),
+    (0, 0),
+    (b' ', b' '),
+    (0, 0),
+    // F1-F10 (시퀀스로 처리)
+    (0, 0),
+    (0, 0),
+    (0, 0),
+    (0, 0),
+    (0, 0),
+    (0, 0),
+    (0, 0),
+    (0, 0),
+    (0, 0),
+    (0, 0),
+    // Num Lock, Scroll Lock
+    (0, 0),
+    (0, 0),
+    // 키패드 7 8 9 - 4 5 6 + 1 2 3 0 .
+    (b'7', b'7'),
+    (b'8', b'8'),
+    (b'9', b'9'),
+    (b'-', b'-'),
+    (b'4', b'4'),
+    (b'5', b'5'),
+    (b'6', b'6'),
+    (b'+', b'+'),
+    (b'1', b'1'),
+    (b'2', b'2'),
+    (b'3', b'3'),
+    (b'0', b'0'),
+    (b'.', b'.'),
+];
+
+/// 키 하나가 낼 수 있는 최대 바이트 수 (Alt + `ESC [ 24 ~`)
+pub const MAX_BYTES: usize = 6;
+
+/// 수정 키 비트
+const SHIFT_L: u8 = 1 << 0;
+const SHIFT_R: u8 = 1 << 1;
+const CTRL_L: u8 = 1 << 2;
+const CTRL_R: u8 = 1 << 3;
+const ALT_L: u8 = 1 << 4;
+const ALT_R: u8 = 1 << 5;
+
+/// 키보드 하나의 수정 키 상태
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Keyboard {
+    /// 눌려 있는 수정 키 (`SHIFT_L` 등)
+    modifiers: u8,
+    /// Caps Lock (누를 때마다 뒤집힘)
+    caps_lock: bool,
+}
+
+impl Keyboard {
+    pub const fn new() -> Self {
+        Self { modifiers: 0, caps_lock: false }
+    }
+
+    fn shift(&self) -> bool {
+        self.modifiers & (SHIFT_L | SHIFT_R) != 0
+    }
+
+    fn ctrl(&self) -> bool {
+        self.modifiers & (CTRL_L | CTRL_R) != 0
+    }
+
+    fn alt(&self) -> bool {
+        self.modifiers & (ALT_L | ALT_R) != 0
+    }
+
+    /// `EV_KEY` 이벤트 하나를 처리해 터미널 바이트를 `out`에 채움
+    ///
+    /// `value`는 0=뗌, 1=누름, 2=반복입니다. 수정 키와 뗌은 상태만 바꾸고 0을 반환합니다.
+    ///
+    /// # Returns
+    /// `out`에 채운 바이트 수
+    pub fn key(&mut self, code: u16, value: i32, out: &mut [u8; MAX_BYTES]) -> usize {
+        let bit = match code {
+            key::LEFTSHIFT => SHIFT_L,
+            key::RIGHTSHIFT => SHIFT_R,
+            key::LEFTCTRL => CTRL_L,
+            key::RIGHTCTRL => CTRL_R,
+            key::LEFTALT => ALT_L,
+            key::RIGHTALT => ALT_R,
+            _ => 0,
+        };
+        if bit != 0 {
+            if value == 0 {
+                self.modifiers &= !bit;
+            } else {
+                self.modifiers |= bit;
+            }
+            return 0;
+        }
+        if value == 0 {
+            return 0;
+        }
+        if code == key::CAPSLOCK {
+            if value == 1 {
+                self.caps_lock = !self.caps_lock;
+            }
+            return 0;
+        }
+
+        let mut len = 0;
+        if self.alt() {
+            out[0] = 0x1B;
+            len = 1;
+        }
+        let seq = match sequence(code) {
+            Some(seq) => seq,
+            None => {
+                let Some(c) = self.ascii(code) else {
+                    return 0;
+                };
+                out[len] = c;
+                return len + 1;
+            }
+        };
+        out[len..len + seq.len()].copy_from_slice(seq);
+        len + seq.len()
+    }
+
+    /// 글자 키의 ASCII (Shift, Caps Lock, Ctrl 반영)
+    fn ascii(&self, code: u16) -> Option<u8> {
+        let &(normal, shifted) = KEYMAP.get(code as usize).or(match code {
+            key::KPENTER => Some(&(b'\r', b'\r')),
+            key::KPSLASH => Some(&(b'/', b'/')),
+            _ => None,
+        })?;
+        if normal == 0 {
+            return None;
+        }
+        let mut c = if self.shift() { shifted } else { normal };
+        if self.caps_lock && normal.is_ascii_lowercase() {
+            c = if self.shift() { normal } else { shifted };
+        }
+        if self.ctrl() {
+            let upper = c.to_ascii_uppercase();
+            if (b'@'..=b'_').contains(&upper) {
+                return Some(upper & 0x1F);
+            }
+            if c == b' ' {
+                return Some(0);
+            }
+        }
+        Some(c)
+    }
+}
+
+/// 이스케이프 시퀀스를 내는 키
+fn sequence(code: u16) -> Option<&'static [u8]> {
+    const FKEYS: [&[u8]; 10] = [
+        b"\x1bOP", b"\x1bOQ", b"\x1bOR", b"\x1bOS", b"\x1b[15~", b"\x1b[17~", b"\x1b[18~", b"\x1b[19~",
+        b"\x1b[20~", b"\x1b[21~",
+    ];
+    Some(match code {
+        key::UP => b"\x1b[A",
+        key::DOWN => b"\x1b[B",
+        key::RIGHT => b"\x1b[C",
+        key::LEFT => b"\x1b[D",
+        key::HOME => b"\x1b[H",
+        key::END => b"\x1b[F",
+        key::INSERT => b"\x1b[2~",
+        key::DELETE => b"\x1b[3~",
+        key::PAGEUP => b"\x1b[5~",
+        key::PAGEDOWN => b"\x1b[6~",
+        key::F1..=key::F10 => FKEYS[(code - key::F1) as usize],
+        key::F11 => b"\x1b[23~",
+        key::F12 => b"\x1b[24~",
+        _ => return None,
+    })
+}
@@ -0,0 +1,421 @@
+//! 입력 서브시스템
+//!
+//! 키보드/마우스 같은 입력 드라이버가 보고한 이벤트를 디바이스별 링에 모아
+//! `/dev/input/eventN`으로 제공합니다.
+//!
+//! 이벤트 레코드는 Linux evdev의 `struct input_event`(64비트)와 같은
+//! 24바이트 레이아웃이므로 evtest 류의 도구 로직을 그대로 옮겨 쓸 수 있습니다.
+//!
+//! ```text
+//! [0:sec u64][8:usec u64][16:type u16][18:code u16][20:value i32]
+//! ```
+//!
+//! 드라이버는 인터럽트 컨텍스트에서 `report_event`를 호출하므로 링은 고정 크기이며, 가득
+//! 차면 가장 오래된 이벤트를 덮어씁니다. 이벤트에는 순서 번호가 붙고, `/dev/input/eventN`을
+//! 연 파일마다 자기가 읽은 번호를 따로 가지므로 여러 프로그램이 같은 이벤트를 모두 받습니다.
+//! 읽기 전에 덮어쓰인 이벤트가 있으면 Linux처럼 `SYN_DROPPED`를 먼저 돌려줍니다.
+//!
+//! 키 이벤트는 디바이스별 수정 키 상태(`keymap::Keyboard`)로 터미널 바이트로 바꿔 콘솔 입력에도
+//! 넣으므로(`input.console`), QEMU GUI 창의 키보드로 셸을 쓸 수 있습니다.
+
+pub mod keymap;
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::fs::fd::OpenFlags;
+use crate::fs::{FileMode, Stat, VfsError, VfsResult, VNode, VNodeType};
+use crate::sync::{IrqSpinlock, Spinlock};
+use crate::sysctl::{self, Kind, Tunable, Value};
+
+/// 이벤트 타입/코드 (Linux input-event-codes.h와 동일)
+#[allow(dead_code)]
+pub mod codes {
+    pub const EV_SYN: u16 = 0x00;
+    pub const EV_KEY: u16 = 0x01;
+    pub const EV_REL: u16 = 0x02;
+    pub const EV_ABS: u16 = 0x03;
+    pub const EV_MSC: u16 = 0x04;
+    pub const EV_LED: u16 = 0x11;
+    pub const EV_REP: u16 = 0x14;
+
+    /// EV_SYN 코드: 이벤트 묶음 끝
+    pub const SYN_REPORT: u16 = 0;
+    /// EV_SYN 코드: 읽기 전에 이벤트가 버려짐 (다음 `SYN_REPORT`까지 버리고 상태를 다시 읽음)
+    pub const SYN_DROPPED: u16 = 3;
+}
+
+/// evdev ioctl (Linux `input.h`, `_IOR('E', nr, size)`)
+pub mod ioctl {
+    /// 프로토콜 버전 (`int`)
+    pub const EVIOCGVERSION: u32 = 0x8004_4501;
+    /// 디바이스 식별자 (`struct input_id`)
+    pub const EVIOCGID: u32 = 0x8008_4502;
+    /// `EVIOCGNAME(len)`의 길이 필드를 뺀 값
+    pub const EVIOCGNAME_BASE: u32 = 0x8000_4506;
+    /// ioctl 번호에서 길이 필드 (bits 16..30)
+    pub const SIZE_MASK: u32 = 0x3FFF_0000;
+
+    /// `EVIOCGNAME(len)` - 디바이스 이름
+    pub const fn eviocgname(len: u32) -> u32 {
+        EVIOCGNAME_BASE | ((len << 16) & SIZE_MASK)
+    }
+
+    /// evdev 프로토콜 버전 (Linux `EV_VERSION`)
+    pub const EV_VERSION: i32 = 0x01_0001;
+    /// `input_id.bustype` (Linux `BUS_VIRTUAL`)
+    pub const BUS_VIRTUAL: u16 = 0x06;
+}
+
+/// 디바이스별 이벤트 링 크기
+pub const QUEUE_LEN: usize = 256;
+
+/// 입력 이벤트 레코드 (evdev `struct input_event` 호환)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InputEvent {
+    /// 타임스탬프 (초)
+    pub sec: u64,
+    /// 타임스탬프 (마이크로초)
+    pub usec: u64,
+    /// 이벤트 타입 (EV_*)
+    pub type_: u16,
+    /// 이벤트 코드 (KEY_*, REL_* 등)
+    pub code: u16,
+    /// 값 (키: 0=뗌, 1=누름, 2=반복)
+    pub value: i32,
+}
+
+/// 이벤트 레코드 크기 (바이트)
+pub const EVENT_SIZE: usize = core::mem::size_of::<InputEvent>();
+
+/// 입력 디바이스
+struct InputDevice {
+    /// 디바이스 이름 (드라이버가 보고한 이름)
+    name: String,
+    /// 이벤트 링 (`seq % QUEUE_LEN` 자리)
+    events: [InputEvent; QUEUE_LEN],
+    /// 다음 이벤트 번호 (지금까지 보고된 이벤트 수)
+    next_seq: u64,
+    /// 열린 `/dev/input/eventN` 파일 수
+    readers: usize,
+    /// 콘솔 입력 변환용 수정 키 상태
+    keyboard: keymap::Keyboard,
+}
+
+impl InputDevice {
+    /// 링에 남아 있는 가장 오래된 이벤트 번호
+    fn oldest_seq(&self) -> u64 {
+        self.next_seq.saturating_sub(QUEUE_LEN as u64)
+    }
+}
+
+/// 입력 디바이스 정보 (`input` 명령 출력용)
+#[derive(Debug, Clone)]
+pub struct InputDeviceInfo {
+    pub id: usize,
+    pub name: String,
+    /// 링에 남아 있는 이벤트 수
+    pub buffered: usize,
+    /// 지금까지 보고된 이벤트 수
+    pub total: u64,
+    /// 열린 `/dev/input/eventN` 파일 수
+    pub readers: usize,
+}
+
+/// 등록된 입력 디바이스 (인덱스 = eventN 번호)
+static DEVICES: IrqSpinlock<Vec<InputDevice>> = IrqSpinlock::new(Vec::new());
+
+/// 키 입력을 콘솔 입력으로도 넣을지
+static CONSOLE_INPUT: AtomicBool = AtomicBool::new(true);
+
+static SYSCTL: [Tunable; 1] = [Tunable {
+    name: "input.console",
+    desc: "feed keyboard input into the console (shell, /dev/console)",
+    kind: Kind::Bool,
+    get: || Value::Bool(CONSOLE_INPUT.load(Ordering::Relaxed)),
+    set: Some(|v| {
+        CONSOLE_INPUT.store(sysctl::boolean(&v), Ordering::Relaxed);
+        Ok(())
+    }),
+}];
+
+/// 키 입력을 콘솔 입력으로도 넣는지 (`input.console`)
+pub fn console_input() -> bool {
+    CONSOLE_INPUT.load(Ordering::Relaxed)
+}
+
+/// 키 입력의 콘솔 전달 켜기/끄기
+pub fn set_console_input(on: bool) {
+    CONSOLE_INPUT.store(on, Ordering::Relaxed);
+}
+
+/// 입력 디바이스 등록
+///
+/// 인터럽트 컨텍스트에서 호출하면 안 됩니다 (heap 할당).
+///
+/// # Returns
+/// 디바이스 번호 (`/dev/input/event<번호>`)
+pub fn register_device(name: &str) -> usize {
+    let device = InputDevice {
+        name: String::from(name),
+        events: [InputEvent::default(); QUEUE_LEN],
+        next_seq: 0,
+        readers: 0,
+        keyboard: keymap::Keyboard::new(),
+    };
+
+    let mut devices = DEVICES.lock();
+    devices.push(device);
+    devices.len() - 1
+}
+
+/// 이벤트 보고 (드라이버에서 호출, 인터럽트 컨텍스트 가능)
+pub fn report_event(id: usize, type_: u16, code: u16, value: i32) {
+    let (sec, usec) = crate::log::get_timestamp();
+    let event = InputEvent { sec, usec, type_, code, value };
+
+    let mut bytes = [0u8; keymap::MAX_BYTES];
+    let mut len = 0;
+    let mut devices = DEVICES.lock();
+    let Some(dev) = devices.get_mut(id) else {
+        return;
+    };
+
+    if type_ == codes::EV_KEY {
+        // 수정 키 상태는 콘솔 입력을 끈 동안에도 따라감
+        len = dev.keyboard.key(code, value, &mut bytes);
+    }
+
+    // 가득 차면 가장 오래된 이벤트를 덮어씀 (늦은 리더는 SYN_DROPPED를 받음)
+    let slot = (dev.next_seq % QUEUE_LEN as u64) as usize;
+    dev.events[slot] = event;
+    dev.next_seq += 1;
+    drop(devices);
+
+    if CONSOLE_INPUT.load(Ordering::Relaxed) {
+        bytes[..len].iter().for_each(|&b| crate::console::receive(b));
+    }
+}
+
+/// `*seq`번 이벤트부터 `out`에 꺼내고 `*seq`를 다음 번호로 옮김
+///
+/// 읽지 않은 이벤트가 링에서 덮어쓰였으면 `EV_SYN/SYN_DROPPED`를 먼저 넣고 남아 있는 가장
+/// 오래된 이벤트부터 이어 읽습니다.
+///
+/// # Returns
+/// 꺼낸 이벤트 수 (0 = 없음)
+pub fn read_events(id: usize, seq: &mut u64, out: &mut [InputEvent]) -> usize {
+    // IRQ가 연결되지 않은 환경을 위해 드라이버 큐를 먼저 비움
+    crate::virtio::input::poll();
+
+    let devices = DEVICES.lock();
+    let Some(dev) = devices.get(id) else {
+        return 0;
+    };
+    if out.is_empty() {
+        return 0;
+    }
+
+    let mut n = 0;
+    if *seq < dev.oldest_seq() {
+        let (sec, usec) = crate::log::get_timestamp();
+        out[0] = InputEvent { sec, usec, type_: codes::EV_SYN, code: codes::SYN_DROPPED, value: 0 };
+        *seq = dev.oldest_seq();
+        n = 1;
+    }
+    while n < out.len() && *seq < dev.next_seq {
+        out[n] = dev.events[(*seq % QUEUE_LEN as u64) as usize];
+        *seq += 1;
+        n += 1;
+    }
+    n
+}
+
+/// `seq`번 이후로 읽을 이벤트가 있는지
+pub fn has_events(id: usize, seq: u64) -> bool {
+    crate::virtio::input::poll();
+    DEVICES.lock().get(id).is_some_and(|dev| seq < dev.next_seq)
+}
+
+/// 다음에 보고될 이벤트 번호 (지금 연 리더가 읽기 시작할 위치)
+pub fn end_seq(id: usize) -> u64 {
+    DEVICES.lock().get(id).map_or(0, |dev| dev.next_seq)
+}
+
+/// 등록된 입력 디바이스 목록
+pub fn list_devices() -> Vec<InputDeviceInfo> {
+    let devices = DEVICES.lock();
+    devices
+        .iter()
+        .enumerate()
+        .map(|(id, dev)| InputDeviceInfo {
+            id,
+            name: dev.name.clone(),
+            buffered: (dev.next_seq - dev.oldest_seq()) as usize,
+            total: dev.next_seq,
+            readers: dev.readers,
+        })
+        .collect()
+}
+
+/// /dev/input/eventN 노드
+///
+/// DevFS에 등록된 노드는 열릴 때마다(`open_instance`) 읽은 위치와 `O_NONBLOCK`을 따로 가진
+/// 노드를 새로 만듭니다. 새로 연 파일은 연 뒤에 보고된 이벤트부터 받습니다.
+///
+/// - 읽기: 버퍼가 레코드 하나보다 작으면 `InvalidArgument`. 이벤트가 없으면 올 때까지
+///   기다리고(시그널이 오면 `Interrupted`), `O_NONBLOCK`이면 `WouldBlock`(`EAGAIN`)
+/// - poll: 읽을 이벤트가 있으면 `POLLIN`
+/// - ioctl: `EVIOCGVERSION`, `EVIOCGID`, `EVIOCGNAME(len)`
+pub struct EventNode {
+    id: usize,
+    /// 다음에 읽을 이벤트 번호
+    seq: Spinlock<u64>,
+    nonblock: bool,
+    /// 열린 파일의 노드인지 (`readers`에 셈)
+    opened: bool,
+}
+
+impl EventNode {
+    /// 이벤트 레코드를 `buf`에 채움 (없으면 0)
+    fn read_once(&self, buf: &mut [u8]) -> usize {
+        let mut events = [InputEvent::default(); 16];
+        let max = (buf.len() / EVENT_SIZE).min(events.len());
+        let n = read_events(self.id, &mut self.seq.lock(), &mut events[..max]);
+        for (i, ev) in events[..n].iter().enumerate() {
+            // Safety: InputEvent는 repr(C) POD
+            let bytes = unsafe {
+                core::slice::from_raw_parts(ev as *const InputEvent as *const u8, EVENT_SIZE)
+            };
+            buf[i * EVENT_SIZE..(i + 1) * EVENT_SIZE].copy_from_slice(bytes);
+        }
+        n * EVENT_SIZE
+    }
+}
+
+impl Drop for EventNode {
+    fn drop(&mut self) {
+        if self.opened {
+            if let Some(dev) = DEVICES.lock().get_mut(self.id) {
+                dev.readers -= 1;
+            }
+        }
+    }
+}
+
+impl VNode for EventNode {
+    fn node_type(&self) -> VNodeType {
+        VNodeType::CharDevice
+    }
+
+    fn read(&self, _offset: usize, buf: &mut [u8]) -> VfsResult<usize> {
+        if buf.len() < EVENT_SIZE {
+            return Err(VfsError::InvalidArgument);
+        }
+        loop {
+            let n = self.read_once(buf);
+            if n > 0 {
+                return Ok(n);
+            }
+            if self.nonblock {
+                return Err(VfsError::WouldBlock);
+            }
+            if crate::proc::current_tid().is_some_and(crate::proc::signal::has_pending) {
+                return Err(VfsError::Interrupted);
+            }
+            // 이벤트는 virtio IRQ 하반부가 보고하므로 양보하며 다시 확인
+            crate::proc::scheduler::schedule();
+        }
+    }
+
+    fn open_instance(&self, flags: OpenFlags) -> Option<Arc<dyn VNode>> {
+        let mut devices = DEVICES.lock();
+        let dev = devices.get_mut(self.id)?;
+        dev.readers += 1;
+        Some(Arc::new(EventNode {
+            id: self.id,
+            seq: Spinlock::new(dev.next_seq),
+            nonblock: flags.is_nonblock(),
+            opened: true,
+        }))
+    }
+
+    fn ioctl(&self, cmd: u32, arg: usize) -> VfsResult<usize> {
+        if arg == 0 {
+            return Err(VfsError::InvalidArgument);
+        }
+        match cmd {
+            ioctl::EVIOCGVERSION => {
+                unsafe { (arg as *mut i32).write_unaligned(ioctl::EV_VERSION) };
+                Ok(0)
+            }
+            ioctl::EVIOCGID => {
+                // struct input_id { bustype, vendor, product, version }
+                let id = [ioctl::BUS_VIRTUAL, 0, 0, 0];
+                unsafe { (arg as *mut [u16; 4]).write_unaligned(id) };
+                Ok(0)
+            }
+            _ if cmd & !ioctl::SIZE_MASK == ioctl::EVIOCGNAME_BASE => {
+                let len = ((cmd & ioctl::SIZE_MASK) >> 16) as usize;
+                let devices = DEVICES.lock();
+                let name = devices.get(self.id).map_or("", |dev| dev.name.as_str());
+                // NUL까지 넣되 버퍼 크기를 넘지 않음 (Linux와 같이 복사한 길이 반환)
+                let n = (name.len() + 1).min(len);
+                let out = unsafe { core::slice::from_raw_parts_mut(arg as *mut u8, n) };
+                for (i, b) in out.iter_mut().enumerate() {
+                    *b = name.as_bytes().get(i).copied().unwrap_or(0);
+                }
+                Ok(n)
+            }
+            _ => Err(VfsError::NotATty),
+        }
+    }
+
+    fn poll(&self, events: u16, _waiter: Option<crate::proc::Tid>) -> u16 {
+        use crate::fs::poll::POLLIN;
+
+        // 이벤트는 IRQ 하반부에서 들어와 대기자를 깨울 수 없으므로 등록하지 않음 (poll이 주기적으로 재확인)
+        if events & POLLIN != 0 && has_events(self.id, *self.seq.lock()) {
+            POLLIN
+        } else {
+            0
+        }
+    }
+
+    fn stat(&self) -> VfsResult<Stat> {
+        Ok(Stat {
+            node_type: VNodeType::CharDevice,
+            mode: FileMode::new(0o444),
+            nlink: 1,
+            ..Default::default()
+        })
+    }
+}
+
+/// 입력 서브시스템 초기화
+///
+/// 입력 드라이버를 초기화하고 등록된 디바이스를 `/dev/input/eventN`으로
+/// 노출합니다. DevFS 마운트 이후에 호출해야 합니다.
+pub fn init() {
+    crate::virtio::input::init();
+    sysctl::register_all(&SYSCTL);
+
+    let count = DEVICES.lock().len();
+    let Some(devfs) = crate::fs::devfs::get_devfs() else {
+        return;
+    };
+    for id in 0..count {
+        let name = alloc::format!("event{}", id);
+        let node = EventNode {
+            id,
+            seq: Spinlock::new(0),
+            nonblock: true,
+            opened: false,
+        };
+        devfs.register_device_in("input", &name, Arc::new(node));
+        crate::kprintln!("[input] Registered /dev/input/{}", name);
+    }
+}
@@ -7,6 +7,7 @@
 //! - 블로킹/논블로킹 송수신 지원
 //! - 타입 안전 (제네릭)
 //! - 용량 제한 옵션 (BoundedMessageQueue)
+//! - `poll()`로 준비 상태 확인 (상태가 바뀌면 `poll` 대기자를 깨움)
 //!
 //! ## 사용 예시
 //! ```rust
@@ -20,6 +21,8 @@ use alloc::boxed::Box;
 use alloc::vec::Vec;
 use alloc::string::String;
 use core::sync::atomic::{AtomicUsize, AtomicBool, Ordering};
+use crate::fs::poll::{POLLHUP, POLLIN, POLLOUT};
+use crate::proc::{Tid, WaitList};
 use crate::sync::{Mutex, Semaphore};
 
 /// 메시지 우선순위
@@ -98,6 +101,8 @@ pub struct MessageQueue<T> {
     closed: AtomicBool,
     /// 수신 대기용 세마포어
     sem_items: Semaphore,
+    /// `poll` 대기자
+    waiters: WaitList,
 }
 
 unsafe impl<T: Send> Send for MessageQueue<T> {}
@@ -111,6 +116,7 @@ impl<T> MessageQueue<T> {
             count: AtomicUsize::new(0),
             closed: AtomicBool::new(false),
             sem_items: Semaphore::new(0),
+            waiters: WaitList::new(),
         }
     }
 
@@ -137,6 +143,7 @@ impl<T> MessageQueue<T> {
         }
 
         self.count.fetch_add(1, Ordering::Release);
+        self.waiters.wake_all();
         self.sem_items.release();
         Ok(())
     }
@@ -178,6 +185,7 @@ impl<T> MessageQueue<T> {
         if let Some(idx) = Self::find_highest_priority(&queue) {
             if let Some(msg) = queue.remove(idx) {
                 self.count.fetch_sub(1, Ordering::Release);
+                self.waiters.wake_all();
                 return Ok(msg);
             }
         }
@@ -198,6 +206,7 @@ impl<T> MessageQueue<T> {
         if let Some(idx) = Self::find_highest_priority(&queue) {
             if let Some(msg) = queue.remove(idx) {
                 self.count.fetch_sub(1, Ordering::Release);
+                self.waiters.wake_all();
                 return Ok(msg);
             }
         }
@@ -221,6 +230,7 @@ impl<T> MessageQueue<T> {
     /// 더 이상 송신 불가, 남은 메시지는 수신 가능
     pub fn close(&self) {
         self.closed.store(true, Ordering::Release);
+        self.waiters.wake_all();
         // 대기 중인 수신자들 깨우기
         for _ in 0..10 {
             self.sem_items.release();
@@ -233,12 +243,30 @@ impl<T> MessageQueue<T> {
         self.closed.load(Ordering::Relaxed)
     }
 
+    /// 준비된 이벤트 (`poll`, fd로 노출할 때 `VNode::poll`에서 사용)
+    ///
+    /// 메시지가 있으면 `POLLIN`, 열려 있으면 항상 `POLLOUT`, 닫히고 비었으면 `POLLHUP`입니다.
+    pub fn poll(&self, events: u16, waiter: Option<Tid>) -> u16 {
+        if let Some(tid) = waiter {
+            self.waiters.register(tid);
+        }
+        let closed = self.is_closed();
+        let mut revents = if closed { 0 } else { POLLOUT };
+        if !self.is_empty() {
+            revents |= POLLIN;
+        } else if closed {
+            revents |= POLLHUP;
+        }
+        revents & events
+    }
+
     /// 모든 메시지 제거
     pub fn clear(&self) {
         let mut queue = self.queue.lock();
         let count = queue.len();
         queue.clear();
         self.count.store(0, Ordering::Release);
+        self.waiters.wake_all();
         
         // 세마포어 카운트 조정
         for _ in 0..count {
@@ -269,6 +297,8 @@ pub struct BoundedMessageQueue<T> {
     sem_slots: Semaphore,
     /// 사용 가능한 메시지 (수신자용)
     sem_items: Semaphore,
+    /// `poll` 대기자
+    waiters: WaitList,
 }
 
 unsafe impl<T: Send> Send for BoundedMessageQueue<T> {}
@@ -284,6 +314,7 @@ impl<T> BoundedMessageQueue<T> {
             closed: AtomicBool::new(false),
             sem_slots: Semaphore::new(capacity as isize),
             sem_items: Semaphore::new(0),
+            waiters: WaitList::new(),
         }
     }
 
@@ -314,6 +345,7 @@ impl<T> BoundedMessageQueue<T> {
         }
 
         self.count.fetch_add(1, Ordering::Release);
+        self.waiters.wake_all();
         self.sem_items.release();
         Ok(())
     }
@@ -339,6 +371,7 @@ impl<T> BoundedMessageQueue<T> {
         }
 
         self.count.fetch_add(1, Ordering::Release);
+        self.waiters.wake_all();
         self.sem_items.release();
         Ok(())
     }
@@ -362,6 +395,7 @@ impl<T> BoundedMessageQueue<T> {
 
         if let Some(msg) = msg {
             self.count.fetch_sub(1, Ordering::Release);
+            self.waiters.wake_all();
             self.sem_slots.release();
             Ok(msg)
         } else {
@@ -386,6 +420,7 @@ impl<T> BoundedMessageQueue<T> {
 
         if let Some(msg) = msg {
             self.count.fetch_sub(1, Ordering::Release);
+            self.waiters.wake_all();
             self.sem_slots.release();
             Ok(msg)
         } else {
@@ -420,6 +455,7 @@ impl<T> BoundedMessageQueue<T> {
     /// 큐 닫기
     pub fn close(&self) {
         self.closed.store(true, Ordering::Release);
+        self.waiters.wake_all();
         // 대기 중인 스레드들 깨우기
         for _ in 0..self.capacity {
             self.sem_slots.release();
@@ -432,6 +468,23 @@ impl<T> BoundedMessageQueue<T> {
     pub fn is_closed(&self) -> bool {
         self.closed.load(Ordering::Relaxed)
     }
+
+    /// 준비된 이벤트 (`poll`, fd로 노출할 때 `VNode::poll`에서 사용)
+    ///
+    /// 메시지가 있으면 `POLLIN`, 열려 있고 자리가 있으면 `POLLOUT`, 닫히고 비었으면 `POLLHUP`입니다.
+    pub fn poll(&self, events: u16, waiter: Option<Tid>) -> u16 {
+        if let Some(tid) = waiter {
+            self.waiters.register(tid);
+        }
+        let closed = self.is_closed();
+        let mut revents = if closed || self.is_full() { 0 } else { POLLOUT };
+        if !self.is_empty() {
+            revents |= POLLIN;
+        } else if closed {
+            revents |= POLLHUP;
+        }
+        revents & events
+    }
 }
 
 /// 채널 (Go 스타일)
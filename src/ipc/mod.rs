@@ -2,8 +2,11 @@
 //!
 //! 프로세스/스레드 간 통신 메커니즘 제공:
 //! - 메시지 큐: 메시지 기반 통신
-//! - (향후) 공유 메모리, 파이프 등
+//! - 파이프: 바이트 스트림 (VFS에서는 `fs::pipe`로 fd/FIFO 제공)
+//! - 공유 메모리: 이름 있는 페이지 프레임 세그먼트 (VFS에서는 /dev/shm + mmap)
 
 pub mod message_queue;
+pub mod pipe;
+pub mod shm;
 
 pub use message_queue::{MessageQueue, BoundedMessageQueue, Message};
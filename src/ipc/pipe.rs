@@ -0,0 +1,225 @@
+//! 파이프 (Pipe)
+//!
+//! 한쪽에서 쓴 바이트를 다른 쪽에서 순서대로 읽는 단방향 바이트 스트림
+//!
+//! ## 특징
+//! - 고정 크기 링 버퍼 (`PIPE_CAPACITY`, 첫 쓰기 때 할당)
+//! - 읽기/쓰기 끝 수를 세어 EOF와 끊어진 파이프를 판별
+//! - 블로킹 읽기/쓰기는 조건이 풀릴 때까지 `yield_now()`로 양보
+//! - 상태가 바뀌면(읽기, 쓰기, 끝 닫기) `poll` 대기자(`WaitList`)를 깨움
+//!
+//! | 상황 | 읽기 | 쓰기 |
+//! |------|------|------|
+//! | 데이터/공간 있음 | 가능한 만큼 반환 | 가능한 만큼 쓰고, 블로킹이면 전부 쓸 때까지 반복 |
+//! | 비어 있음/가득 참 | 블로킹: 대기, 논블로킹: `WouldBlock` | 블로킹: 대기, 논블로킹: `WouldBlock` |
+//! | 상대 끝이 모두 닫힘 | 0 (EOF) | `BrokenPipe` (이미 쓴 바이트가 있으면 그 수) |
+//!
+//! VFS에서는 `fs::pipe`의 읽기/쓰기 끝 VNode로 감싸 fd나 RamFS FIFO로 사용합니다.
+//!
+//! ## 사용 예시
+//! ```rust
+//! let pipe = Pipe::new();
+//! pipe.open_reader();
+//! pipe.open_writer();
+//! pipe.write(b"hello", false)?;
+//! let n = pipe.read(&mut buf, false)?;
+//! ```
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::fs::poll::{POLLERR, POLLHUP, POLLIN, POLLOUT};
+use crate::proc::{Tid, WaitList};
+use crate::sync::Mutex;
+
+/// 파이프 버퍼 크기
+pub const PIPE_CAPACITY: usize = 4096;
+
+/// 파이프 에러
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipeError {
+    /// 논블로킹 모드에서 지금은 진행할 수 없음
+    WouldBlock,
+    /// 읽는 쪽이 모두 닫힘
+    BrokenPipe,
+}
+
+/// 링 버퍼와 끝 수
+struct PipeInner {
+    /// 링 버퍼 (첫 쓰기 전에는 비어 있음)
+    buf: Vec<u8>,
+    /// 읽을 위치
+    head: usize,
+    /// 쌓인 바이트 수
+    len: usize,
+    /// 열린 읽기 끝 수
+    readers: usize,
+    /// 열린 쓰기 끝 수
+    writers: usize,
+}
+
+impl PipeInner {
+    fn pop(&mut self, out: &mut [u8]) -> usize {
+        let n = out.len().min(self.len);
+        for (i, byte) in out[..n].iter_mut().enumerate() {
+            *byte = self.buf[(self.head + i) % PIPE_CAPACITY];
+        }
+        self.head = (self.head + n) % PIPE_CAPACITY;
+        self.len -= n;
+        n
+    }
+
+    fn push(&mut self, data: &[u8]) -> usize {
+        if self.buf.is_empty() {
+            self.buf.resize(PIPE_CAPACITY, 0);
+        }
+        let n = data.len().min(PIPE_CAPACITY - self.len);
+        let tail = self.head + self.len;
+        for (i, &byte) in data[..n].iter().enumerate() {
+            self.buf[(tail + i) % PIPE_CAPACITY] = byte;
+        }
+        self.len += n;
+        n
+    }
+}
+
+/// 파이프 버퍼
+pub struct Pipe {
+    inner: Mutex<PipeInner>,
+    /// `poll` 대기자
+    waiters: WaitList,
+}
+
+impl Pipe {
+    /// 새 파이프 생성 (열린 끝 없음)
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            inner: Mutex::new(PipeInner {
+                buf: Vec::new(),
+                head: 0,
+                len: 0,
+                readers: 0,
+                writers: 0,
+            }),
+            waiters: WaitList::new(),
+        })
+    }
+
+    /// 읽기 끝 열기
+    pub fn open_reader(&self) {
+        self.inner.lock().readers += 1;
+    }
+
+    /// 읽기 끝 닫기
+    pub fn close_reader(&self) {
+        {
+            let mut inner = self.inner.lock();
+            inner.readers = inner.readers.saturating_sub(1);
+        }
+        self.waiters.wake_all();
+    }
+
+    /// 쓰기 끝 열기
+    pub fn open_writer(&self) {
+        self.inner.lock().writers += 1;
+    }
+
+    /// 쓰기 끝 닫기
+    pub fn close_writer(&self) {
+        {
+            let mut inner = self.inner.lock();
+            inner.writers = inner.writers.saturating_sub(1);
+        }
+        self.waiters.wake_all();
+    }
+
+    /// 쌓인 바이트 수
+    pub fn available(&self) -> usize {
+        self.inner.lock().len
+    }
+
+    /// 읽기
+    ///
+    /// 데이터가 있으면 가능한 만큼 읽고, 쓰기 끝이 모두 닫혔으면 0 (EOF)을 반환합니다.
+    pub fn read(&self, buf: &mut [u8], nonblock: bool) -> Result<usize, PipeError> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            {
+                let mut inner = self.inner.lock();
+                if inner.len > 0 {
+                    let n = inner.pop(buf);
+                    drop(inner);
+                    self.waiters.wake_all();
+                    return Ok(n);
+                }
+                if inner.writers == 0 {
+                    return Ok(0);
+                }
+                if nonblock {
+                    return Err(PipeError::WouldBlock);
+                }
+            }
+            crate::proc::yield_now();
+        }
+    }
+
+    /// 쓰기
+    ///
+    /// 블로킹 모드는 전부 쓸 때까지 기다리고, 논블로킹 모드는 들어가는 만큼만 씁니다.
+    pub fn write(&self, data: &[u8], nonblock: bool) -> Result<usize, PipeError> {
+        let mut written = 0;
+        while written < data.len() {
+            let pushed = {
+                let mut inner = self.inner.lock();
+                if inner.readers == 0 {
+                    return if written > 0 { Ok(written) } else { Err(PipeError::BrokenPipe) };
+                }
+                inner.push(&data[written..])
+            };
+            if pushed > 0 {
+                written += pushed;
+                self.waiters.wake_all();
+            }
+            if written == data.len() {
+                break;
+            }
+            if nonblock {
+                return if written > 0 { Ok(written) } else { Err(PipeError::WouldBlock) };
+            }
+            crate::proc::yield_now();
+        }
+        Ok(written)
+    }
+
+    /// 준비된 이벤트 (`poll`)
+    ///
+    /// 읽기 끝은 데이터가 있으면 `POLLIN`, 쓰기 끝이 모두 닫혔으면 `POLLHUP`,
+    /// 쓰기 끝은 공간이 있으면 `POLLOUT`, 읽기 끝이 모두 닫혔으면 `POLLERR`입니다.
+    /// `waiter`는 상태가 바뀔 때 깨우도록 등록합니다.
+    pub fn poll(&self, readable: bool, writable: bool, events: u16, waiter: Option<Tid>) -> u16 {
+        // 확인 전에 등록해야 그 사이의 상태 변화를 놓치지 않음
+        if let Some(tid) = waiter {
+            self.waiters.register(tid);
+        }
+        let inner = self.inner.lock();
+        let mut revents = 0;
+        if readable {
+            if inner.len > 0 {
+                revents |= POLLIN;
+            }
+            if inner.writers == 0 {
+                revents |= POLLHUP;
+            }
+        }
+        if writable {
+            if inner.readers == 0 {
+                revents |= POLLERR;
+            } else if inner.len < PIPE_CAPACITY {
+                revents |= POLLOUT;
+            }
+        }
+        revents & events
+    }
+}
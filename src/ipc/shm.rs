@@ -0,0 +1,268 @@
+//! 공유 메모리 (Shared Memory)
+//!
+//! 이름으로 찾는 공유 메모리 세그먼트 (POSIX shm_open 스타일)
+//!
+//! ## 특징
+//! - 세그먼트는 물리적으로 연속된 페이지 프레임 (커널은 아이덴티티 매핑이므로
+//!   매핑 주소 = 프레임 주소)
+//! - 크기 0으로 생성한 뒤 `resize()`(ftruncate)로 크기를 정함
+//! - 매핑 중에는 주소가 바뀌면 안 되므로 `resize()`가 `Busy`를 반환
+//! - `shm_unlink()`는 이름만 지우고, 프레임은 마지막 참조(열린 fd, 매핑)가 사라질 때 해제
+//!
+//! 모듈은 `kernel_shm_*` 심볼로, 시스템 콜은 `/dev/shm/<name>`을 열고 `mmap`해서 사용합니다.
+//! `mmap`은 페이지 권한을 바꾸지 않으므로 `prot`는 무시됩니다.
+//!
+//! ## 사용 예시
+//! ```rust
+//! let seg = shm_open("frame", true)?;
+//! seg.resize(8192)?;
+//! let addr = map(&seg, 0, 8192)?;
+//! // ... addr를 다른 스레드/모듈과 공유 ...
+//! unmap(addr)?;
+//! shm_unlink("frame")?;
+//! ```
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::mm::page::{self, PAGE_SIZE};
+use crate::sync::{Mutex, RwLock};
+
+/// 세그먼트 최대 크기 (연속 프레임이어야 하므로 제한)
+pub const SHM_MAX_SIZE: usize = 16 * 1024 * 1024;
+
+/// 이름 최대 길이
+pub const SHM_NAME_MAX: usize = 64;
+
+/// 공유 메모리 에러
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShmError {
+    /// 세그먼트 없음
+    NotFound,
+    /// 이미 존재 (배타적 생성)
+    AlreadyExists,
+    /// 잘못된 이름/크기/범위
+    InvalidArgument,
+    /// 연속 프레임 할당 실패
+    NoMemory,
+    /// 매핑 중이라 크기 변경 불가
+    Busy,
+}
+
+/// 세그먼트 메모리
+struct ShmInner {
+    /// 프레임 시작 주소 (크기 0이면 0)
+    base: usize,
+    /// 할당된 페이지 수
+    pages: usize,
+    /// 논리적 크기 (바이트)
+    size: usize,
+    /// 현재 매핑 수
+    maps: usize,
+}
+
+/// 공유 메모리 세그먼트
+pub struct ShmSegment {
+    /// 이름 (익명 세그먼트는 빈 문자열)
+    name: String,
+    inner: Mutex<ShmInner>,
+}
+
+impl ShmSegment {
+    fn new(name: &str) -> Arc<Self> {
+        Arc::new(Self {
+            name: String::from(name),
+            inner: Mutex::new(ShmInner {
+                base: 0,
+                pages: 0,
+                size: 0,
+                maps: 0,
+            }),
+        })
+    }
+
+    /// 현재 크기 (바이트)
+    pub fn size(&self) -> usize {
+        self.inner.lock().size
+    }
+
+    /// 크기 변경 (ftruncate)
+    ///
+    /// 페이지 수가 바뀌면 새 연속 프레임을 할당해 내용을 복사합니다. 늘어난
+    /// 부분은 0으로 채웁니다. 매핑 중이면 `Busy`입니다.
+    pub fn resize(&self, size: usize) -> Result<(), ShmError> {
+        if size > SHM_MAX_SIZE {
+            return Err(ShmError::InvalidArgument);
+        }
+        let mut inner = self.inner.lock();
+        if size == inner.size {
+            return Ok(());
+        }
+        if inner.maps > 0 {
+            return Err(ShmError::Busy);
+        }
+
+        let pages = size.div_ceil(PAGE_SIZE);
+        if pages != inner.pages {
+            let base = if pages > 0 {
+                let base = page::alloc_frames(pages).ok_or(ShmError::NoMemory)?;
+                unsafe {
+                    core::ptr::write_bytes(base as *mut u8, 0, pages * PAGE_SIZE);
+                    if inner.base != 0 {
+                        let keep = inner.size.min(size);
+                        core::ptr::copy_nonoverlapping(inner.base as *const u8, base as *mut u8, keep);
+                    }
+                }
+                base
+            } else {
+                0
+            };
+            if inner.base != 0 {
+                unsafe { page::free_frames(inner.base, inner.pages) };
+            }
+            inner.base = base;
+            inner.pages = pages;
+        } else if size < inner.size {
+            // 같은 페이지 안에서 줄면 잘린 부분을 지워 다시 늘릴 때 0이 보이게 함
+            unsafe {
+                core::ptr::write_bytes((inner.base + size) as *mut u8, 0, inner.size - size);
+            }
+        }
+        inner.size = size;
+        Ok(())
+    }
+
+    /// `offset`부터 읽기 (크기 밖은 읽지 않음)
+    pub fn read(&self, offset: usize, buf: &mut [u8]) -> usize {
+        let inner = self.inner.lock();
+        if offset >= inner.size {
+            return 0;
+        }
+        let n = buf.len().min(inner.size - offset);
+        unsafe {
+            core::ptr::copy_nonoverlapping((inner.base + offset) as *const u8, buf.as_mut_ptr(), n);
+        }
+        n
+    }
+
+    /// `offset`부터 쓰기 (크기 밖은 쓰지 않음)
+    pub fn write(&self, offset: usize, data: &[u8]) -> usize {
+        let inner = self.inner.lock();
+        if offset >= inner.size {
+            return 0;
+        }
+        let n = data.len().min(inner.size - offset);
+        unsafe {
+            core::ptr::copy_nonoverlapping(data.as_ptr(), (inner.base + offset) as *mut u8, n);
+        }
+        n
+    }
+}
+
+impl Drop for ShmSegment {
+    fn drop(&mut self) {
+        let inner = self.inner.lock();
+        if inner.base != 0 {
+            unsafe { page::free_frames(inner.base, inner.pages) };
+        }
+    }
+}
+
+/// 이름 있는 세그먼트 목록
+static SEGMENTS: RwLock<Vec<Arc<ShmSegment>>> = RwLock::new(Vec::new());
+
+/// 현재 매핑 (주소, 세그먼트) - 매핑이 세그먼트를 살려 둠
+static MAPPINGS: Mutex<Vec<(usize, Arc<ShmSegment>)>> = Mutex::new(Vec::new());
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty() && name.len() <= SHM_NAME_MAX && !name.contains('/')
+}
+
+/// 이름으로 세그먼트 찾기
+pub fn lookup(name: &str) -> Option<Arc<ShmSegment>> {
+    SEGMENTS.read().iter().find(|s| s.name == name).cloned()
+}
+
+/// 세그먼트 생성 (크기 0, 이미 있으면 `AlreadyExists`)
+pub fn shm_create(name: &str) -> Result<Arc<ShmSegment>, ShmError> {
+    if !valid_name(name) {
+        return Err(ShmError::InvalidArgument);
+    }
+    let mut segments = SEGMENTS.write();
+    if segments.iter().any(|s| s.name == name) {
+        return Err(ShmError::AlreadyExists);
+    }
+    let seg = ShmSegment::new(name);
+    segments.push(seg.clone());
+    Ok(seg)
+}
+
+/// 세그먼트 열기/생성 (POSIX shm_open 스타일)
+pub fn shm_open(name: &str, create: bool) -> Result<Arc<ShmSegment>, ShmError> {
+    if let Some(seg) = lookup(name) {
+        return Ok(seg);
+    }
+    if !create {
+        return Err(ShmError::NotFound);
+    }
+    match shm_create(name) {
+        // 그 사이 다른 스레드가 만들었으면 그것을 사용
+        Err(ShmError::AlreadyExists) => lookup(name).ok_or(ShmError::NotFound),
+        result => result,
+    }
+}
+
+/// 세그먼트 이름 삭제 (POSIX shm_unlink 스타일)
+///
+/// 이미 연 fd와 매핑은 계속 유효하며, 마지막 참조가 사라질 때 프레임이 해제됩니다.
+pub fn shm_unlink(name: &str) -> Result<(), ShmError> {
+    let mut segments = SEGMENTS.write();
+    let pos = segments.iter().position(|s| s.name == name).ok_or(ShmError::NotFound)?;
+    segments.remove(pos);
+    Ok(())
+}
+
+/// 이름 있는 세그먼트 이름 목록
+pub fn names() -> Vec<String> {
+    SEGMENTS.read().iter().map(|s| s.name.clone()).collect()
+}
+
+/// 익명 세그먼트 생성 (`mmap(MAP_ANONYMOUS | MAP_SHARED)`)
+pub fn anonymous(size: usize) -> Result<Arc<ShmSegment>, ShmError> {
+    let seg = ShmSegment::new("");
+    seg.resize(size)?;
+    Ok(seg)
+}
+
+/// 세그먼트 매핑 - `offset`부터 `len` 바이트를 가리키는 주소 반환
+///
+/// `offset`은 페이지 정렬이어야 하고, 범위는 세그먼트 크기 안이어야 합니다.
+pub fn map(seg: &Arc<ShmSegment>, offset: usize, len: usize) -> Result<usize, ShmError> {
+    if len == 0 || offset % PAGE_SIZE != 0 {
+        return Err(ShmError::InvalidArgument);
+    }
+    let addr = {
+        let mut inner = seg.inner.lock();
+        match offset.checked_add(len) {
+            Some(end) if end <= inner.size => {}
+            _ => return Err(ShmError::InvalidArgument),
+        }
+        inner.maps += 1;
+        inner.base + offset
+    };
+    MAPPINGS.lock().push((addr, seg.clone()));
+    Ok(addr)
+}
+
+/// 매핑 해제 (`map`이 반환한 주소)
+pub fn unmap(addr: usize) -> Result<(), ShmError> {
+    let seg = {
+        let mut mappings = MAPPINGS.lock();
+        let pos = mappings.iter().position(|(a, _)| *a == addr).ok_or(ShmError::InvalidArgument)?;
+        mappings.swap_remove(pos).1
+    };
+    let mut inner = seg.inner.lock();
+    inner.maps = inner.maps.saturating_sub(1);
+    Ok(())
+}
@@ -0,0 +1,395 @@
+//! IRQ 핸들러 등록과 디스패치
+//!
+//! 드라이버는 `request_irq(irq, handler, name)`로 인터럽트 번호에 핸들러를 걸고,
+//! 인터럽트 컨트롤러(GIC, PLIC)는 ack한 IRQ를 `dispatch()`로 넘깁니다. 같은 IRQ에
+//! 여러 핸들러를 걸 수 있으며(공유 IRQ), 디스패치는 등록 순서대로 모두 부르고 하나라도
+//! 처리했다고 답하면 처리된 것으로 봅니다.
+//!
+//! 첫 핸들러가 붙을 때 컨트롤러에서 그 IRQ를 켜고(우선순위, 부팅 CPU로 라우팅), 마지막
+//! 핸들러가 떨어질 때 끕니다. 드라이버는 GIC/PLIC 레지스터를 직접 만지지 않습니다.
+//!
+//! IRQ마다 CPU별 발생 횟수와 아무 핸들러도 처리하지 않은 횟수를 세며, `interrupts`
+//! 명령이 Linux `/proc/interrupts`처럼 보여 줍니다. 컨트롤러를 거치지 않는 IPI(GIC SGI,
+//! riscv64 소프트웨어 인터럽트)와 riscv64 로컬 타이머는 따로 셉니다.
+//!
+//! 핸들러는 인터럽트가 꺼진 채 실행되므로 잠들거나 `Mutex`를 잡으면 안 됩니다. 표는
+//! `IrqSpinlock`으로 보호하고 핸들러는 락을 놓은 뒤 부르므로, 핸들러가 스케줄러를
+//! 호출하거나(타이머) 다른 CPU가 같은 IRQ를 처리해도 교착되지 않습니다.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+use crate::kprintln;
+use crate::proc::percpu::{self, MAX_CPUS};
+use crate::sync::IrqSpinlock;
+
+/// IRQ 하나에 걸 수 있는 최대 핸들러 수
+const MAX_SHARED: usize = 4;
+
+/// IRQ 핸들러
+#[derive(Debug, Clone, Copy)]
+pub enum Handler {
+    /// 커널 함수 (처리했으면 true)
+    Kernel(fn(u32) -> bool),
+    /// 모듈 함수 (`kernel_request_irq`, 처리했으면 0이 아닌 값)
+    Module(extern "C" fn(u32) -> i32),
+}
+
+impl Handler {
+    fn call(self, irq: u32) -> bool {
+        match self {
+            Handler::Kernel(f) => f(irq),
+            Handler::Module(f) => f(irq) != 0,
+        }
+    }
+
+    /// 핸들러 주소 (소유 모듈 판별, 해제 시 비교용)
+    pub fn addr(self) -> usize {
+        match self {
+            Handler::Kernel(f) => f as usize,
+            Handler::Module(f) => f as usize,
+        }
+    }
+}
+
+/// IRQ 등록 에러
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IrqError {
+    /// IRQ 0 (GIC SGI / PLIC의 "인터럽트 없음")
+    InvalidIrq,
+    /// 같은 핸들러가 이미 걸려 있음
+    AlreadyRegistered,
+    /// 공유 핸들러 수 초과
+    TooManyHandlers,
+}
+
+impl IrqError {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IrqError::InvalidIrq => "invalid irq",
+            IrqError::AlreadyRegistered => "handler already registered",
+            IrqError::TooManyHandlers => "too many shared handlers",
+        }
+    }
+}
+
+struct Action {
+    handler: Handler,
+    name: String,
+}
+
+struct Line {
+    irq: u32,
+    actions: Vec<Action>,
+    /// CPU별 발생 횟수
+    counts: [u64; MAX_CPUS],
+    /// 아무 핸들러도 처리하지 않은 횟수
+    unhandled: u64,
+}
+
+static LINES: IrqSpinlock<Vec<Line>> = IrqSpinlock::new(Vec::new());
+
+/// CPU별 실행 중인 핸들러의 IRQ (0 = 없음, 표 락 안에서 설정)
+static RUNNING: [AtomicU32; MAX_CPUS] = [const { AtomicU32::new(0) }; MAX_CPUS];
+
+/// 핸들러가 없는 IRQ 수신 횟수
+static SPURIOUS: AtomicU64 = AtomicU64::new(0);
+
+/// CPU별 IPI 수신 횟수
+static IPIS: [AtomicU64; MAX_CPUS] = [const { AtomicU64::new(0) }; MAX_CPUS];
+
+/// CPU별 로컬 타이머 인터럽트 횟수 (riscv64, 컨트롤러를 거치지 않음)
+#[cfg(target_arch = "riscv64")]
+static LOCAL_TIMER: [AtomicU64; MAX_CPUS] = [const { AtomicU64::new(0) }; MAX_CPUS];
+
+#[inline]
+fn this_cpu() -> usize {
+    percpu::get_cpu_id() as usize % MAX_CPUS
+}
+
+/// IRQ에 핸들러 등록 (인터럽트 밖에서 호출)
+///
+/// IRQ의 첫 핸들러면 인터럽트 컨트롤러에서 그 IRQ를 켭니다. `name`은 `interrupts`
+/// 명령에 표시됩니다.
+pub fn request_irq(irq: u32, handler: fn(u32) -> bool, name: &str) -> Result<(), IrqError> {
+    request(irq, Handler::Kernel(handler), name)
+}
+
+/// `request_irq`의 일반형 (모듈 핸들러도 받음)
+pub fn request(irq: u32, handler: Handler, name: &str) -> Result<(), IrqError> {
+    if irq == 0 {
+        return Err(IrqError::InvalidIrq);
+    }
+    let action = Action {
+        handler,
+        name: String::from(name),
+    };
+
+    let first = {
+        let mut lines = LINES.lock();
+        match lines.iter_mut().find(|l| l.irq == irq) {
+            Some(line) => {
+                if line.actions.iter().any(|a| a.handler.addr() == handler.addr()) {
+                    return Err(IrqError::AlreadyRegistered);
+                }
+                if line.actions.len() >= MAX_SHARED {
+                    return Err(IrqError::TooManyHandlers);
+                }
+                let first = line.actions.is_empty();
+                line.actions.push(action);
+                first
+            }
+            None => {
+                let mut actions = Vec::with_capacity(1);
+                actions.push(action);
+                lines.push(Line {
+                    irq,
+                    actions,
+                    counts: [0; MAX_CPUS],
+                    unhandled: 0,
+                });
+                true
+            }
+        }
+    };
+
+    if first {
+        unmask(irq);
+    }
+    Ok(())
+}
+
+/// IRQ에서 핸들러 해제
+///
+/// 돌아온 뒤에는 핸들러가 더 이상 불리지 않고 다른 CPU에서 실행 중이지도 않습니다.
+/// 자기 핸들러 안에서 부르면 안 됩니다.
+///
+/// # Returns
+/// 등록되어 있던 핸들러를 해제했으면 true
+pub fn free_irq(irq: u32, handler: fn(u32) -> bool) -> bool {
+    remove(Some(irq), handler as usize) > 0
+}
+
+/// 핸들러 주소로 해제 (`irq`가 None이면 모든 IRQ에서), 해제한 수 반환
+pub fn remove(irq: Option<u32>, handler_addr: usize) -> usize {
+    let mut removed = 0;
+    let mut affected: Vec<u32> = Vec::new();
+    let mut masked: Vec<u32> = Vec::new();
+    {
+        let mut lines = LINES.lock();
+        lines.retain_mut(|line| {
+            if irq.is_some_and(|irq| irq != line.irq) {
+                return true;
+            }
+            let before = line.actions.len();
+            line.actions.retain(|a| a.handler.addr() != handler_addr);
+            if line.actions.len() == before {
+                return true;
+            }
+            removed += before - line.actions.len();
+            affected.push(line.irq);
+            if line.actions.is_empty() {
+                masked.push(line.irq);
+                return false;
+            }
+            true
+        });
+    }
+
+    for &irq in &masked {
+        mask(irq);
+    }
+
+    // 다른 CPU에서 그 IRQ의 핸들러가 실행 중이면 끝날 때까지 대기
+    let me = this_cpu();
+    for (cpu, running) in RUNNING.iter().enumerate() {
+        if cpu == me {
+            continue;
+        }
+        while affected.contains(&running.load(Ordering::Acquire)) {
+            core::hint::spin_loop();
+        }
+    }
+    removed
+}
+
+/// 인터럽트 컨트롤러가 ack한 IRQ 처리 (GIC/PLIC 핸들러에서 호출)
+///
+/// 인터럽트 경로이므로 힙을 쓰지 않습니다.
+///
+/// # Returns
+/// 핸들러 중 하나라도 처리했으면 true
+pub fn dispatch(irq: u32) -> bool {
+    let cpu = this_cpu();
+    let mut handlers: [Option<Handler>; MAX_SHARED] = [None; MAX_SHARED];
+    {
+        let mut lines = LINES.lock();
+        let Some(line) = lines.iter_mut().find(|l| l.irq == irq) else {
+            SPURIOUS.fetch_add(1, Ordering::Relaxed);
+            return false;
+        };
+        line.counts[cpu] += 1;
+        for (slot, action) in handlers.iter_mut().zip(line.actions.iter()) {
+            *slot = Some(action.handler);
+        }
+        // 락 안에서 표시해야 remove()가 핸들러 종료를 기다릴 수 있음
+        RUNNING[cpu].store(irq, Ordering::Release);
+    }
+
+    let mut handled = false;
+    for handler in handlers.iter().flatten() {
+        handled |= handler.call(irq);
+    }
+    RUNNING[cpu].store(0, Ordering::Release);
+
+    if !handled {
+        if let Some(line) = LINES.lock().iter_mut().find(|l| l.irq == irq) {
+            line.unhandled += 1;
+        }
+    }
+    handled
+}
+
+/// IPI 수신 기록 (GIC SGI, riscv64 소프트웨어 인터럽트)
+pub fn count_ipi() {
+    IPIS[this_cpu()].fetch_add(1, Ordering::Relaxed);
+}
+
+/// 로컬 타이머 인터럽트 기록 (riscv64 타이머는 PLIC을 거치지 않음)
+#[cfg(target_arch = "riscv64")]
+pub fn count_local_timer() {
+    LOCAL_TIMER[this_cpu()].fetch_add(1, Ordering::Relaxed);
+}
+
+/// 핸들러가 걸린 IRQ 번호 (PLIC 보조 hart 컨텍스트 초기화용)
+#[cfg(target_arch = "riscv64")]
+pub fn requested() -> Vec<u32> {
+    LINES.lock().iter().map(|l| l.irq).collect()
+}
+
+/// IRQ 통계 (`interrupts` 명령용)
+#[derive(Debug, Clone)]
+pub struct IrqInfo {
+    pub irq: u32,
+    pub names: Vec<String>,
+    pub counts: [u64; MAX_CPUS],
+    pub unhandled: u64,
+}
+
+impl IrqInfo {
+    /// 모든 CPU 합계
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+}
+
+/// 핸들러가 걸린 IRQ 목록 (번호 순)
+pub fn stats() -> Vec<IrqInfo> {
+    let mut out: Vec<IrqInfo> = LINES
+        .lock()
+        .iter()
+        .map(|l| IrqInfo {
+            irq: l.irq,
+            names: l.actions.iter().map(|a| a.name.clone()).collect(),
+            counts: l.counts,
+            unhandled: l.unhandled,
+        })
+        .collect();
+    out.sort_by_key(|i| i.irq);
+    out
+}
+
+/// 핸들러가 없는 IRQ 수신 횟수
+pub fn spurious() -> u64 {
+    SPURIOUS.load(Ordering::Relaxed)
+}
+
+/// 인터럽트 컨트롤러 이름
+fn controller_name() -> &'static str {
+    #[cfg(target_arch = "aarch64")]
+    {
+        if crate::arch::gic::is_v3() { "GICv3" } else { "GICv2" }
+    }
+    #[cfg(target_arch = "riscv64")]
+    {
+        "PLIC"
+    }
+}
+
+/// `interrupts` 명령 출력 (Linux `/proc/interrupts` 형식)
+pub fn dump() {
+    let cpus: Vec<usize> = percpu::online_mask().iter().map(|c| c as usize).collect();
+    let cpus = if cpus.is_empty() { alloc::vec![0] } else { cpus };
+
+    let mut header = String::from("     ");
+    for cpu in &cpus {
+        header.push_str(&alloc::format!(" {:>10}", alloc::format!("CPU{}", cpu)));
+    }
+    kprintln!("{}", header);
+
+    let controller = controller_name();
+    for info in stats() {
+        let mut row = alloc::format!("{:>4}:", info.irq);
+        for &cpu in &cpus {
+            row.push_str(&alloc::format!(" {:>10}", info.counts[cpu]));
+        }
+        row.push_str(&alloc::format!("  {:<6} {}", controller, info.names.join(", ")));
+        if info.unhandled > 0 {
+            row.push_str(&alloc::format!(" ({} unhandled)", info.unhandled));
+        }
+        kprintln!("{}", row);
+    }
+
+    let per_cpu_row = |label: &str, counters: &[AtomicU64; MAX_CPUS], desc: &str| {
+        let mut row = alloc::format!("{:>4}:", label);
+        for &cpu in &cpus {
+            row.push_str(&alloc::format!(" {:>10}", counters[cpu].load(Ordering::Relaxed)));
+        }
+        row.push_str(&alloc::format!("  {}", desc));
+        kprintln!("{}", row);
+    };
+    #[cfg(target_arch = "riscv64")]
+    per_cpu_row("LOC", &LOCAL_TIMER, "Local timer interrupts");
+    per_cpu_row("IPI", &IPIS, "Rescheduling interrupts");
+    kprintln!("{:>4}: {:>10}", "ERR", spurious());
+}
+
+// ============================================================================
+// 인터럽트 컨트롤러 연결
+// ============================================================================
+
+/// IRQ 켜기 (GIC: 기본 우선순위, CPU 0으로 라우팅)
+#[cfg(target_arch = "aarch64")]
+fn unmask(irq: u32) {
+    use crate::arch::gic;
+    unsafe {
+        gic::set_priority(irq, 0xA0);
+        if irq >= 32 {
+            gic::set_target(irq, 1); // CPU 0
+        }
+        gic::enable_irq(irq);
+    }
+}
+
+/// IRQ 끄기
+#[cfg(target_arch = "aarch64")]
+fn mask(irq: u32) {
+    unsafe { crate::arch::gic::disable_irq(irq) };
+}
+
+/// IRQ 켜기 (PLIC: 우선순위 1, 부팅 hart 컨텍스트)
+#[cfg(target_arch = "riscv64")]
+fn unmask(irq: u32) {
+    use crate::arch::plic;
+    unsafe {
+        plic::set_priority(irq, 1);
+        plic::enable_irq(irq);
+    }
+}
+
+/// IRQ 끄기 (우선순위 0 = 모든 컨텍스트에서 전달되지 않음)
+#[cfg(target_arch = "riscv64")]
+fn mask(irq: u32) {
+    unsafe { crate::arch::plic::set_priority(irq, 0) };
+}
@@ -0,0 +1,365 @@
+//! kexec: 실행 중인 커널에서 새 커널 이미지로 웜 리부트
+//!
+//! QEMU를 재시작하지 않고 VFS(마운트된 디스크 등)에 있는 새 커널 ELF로
+//! 바로 부팅합니다.
+//!
+//! 흐름:
+//! 1. `load()`: ELF 파싱, LOAD 세그먼트를 프레임 할당자에서 받은 스테이징
+//!    버퍼에 복사 (이 프레임들은 점프 전까지 예약 상태로 유지)
+//! 2. `execute()`: 부트 CPU(0)에서 다른 CPU 정지, 디바이스 정지, 인터럽트 비활성화
+//! 3. 아키텍처 트램펄린을 별도 페이지로 옮겨 실행: 세그먼트를 링크 주소로
+//!    복사한 뒤 DTB 주소와 함께 새 커널 진입점으로 점프
+//!
+//! 새 커널은 현재 커널과 같은 주소에 링크되므로 복사는 반드시 커널 이미지
+//! 밖에 있는 트램펄린에서 수행해야 합니다.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use crate::kprintln;
+use crate::mm::page::{self, PAGE_SIZE};
+use crate::module::elf::{Elf64, ElfType};
+use crate::proc::percpu;
+use crate::sync::Mutex;
+
+/// 스테이징된 세그먼트
+struct KexecSegment {
+    /// 복사 대상 물리 주소 (p_paddr)
+    dest: usize,
+    /// 스테이징 버퍼 주소
+    staging: usize,
+    /// 메모리 크기 (p_memsz, filesz 이후는 0으로 채워짐)
+    len: usize,
+    /// 목적지 끝 주소 (`dest + len`, 로드 때 overflow 검사)
+    end: usize,
+    /// 스테이징 버퍼 페이지 수
+    pages: usize,
+}
+
+/// 로드된 kexec 이미지
+pub struct KexecImage {
+    /// 이미지 경로
+    pub path: String,
+    /// 새 커널 진입점
+    pub entry: usize,
+    /// 스테이징된 세그먼트들
+    segments: Vec<KexecSegment>,
+}
+
+impl KexecImage {
+    /// 세그먼트 전체 크기 (바이트)
+    pub fn image_size(&self) -> usize {
+        self.segments.iter().map(|s| s.len).sum()
+    }
+}
+
+impl Drop for KexecImage {
+    fn drop(&mut self) {
+        for seg in self.segments.iter() {
+            unsafe { page::free_frames(seg.staging, seg.pages) };
+        }
+    }
+}
+
+/// 복사 목록 한 페이지에 들어가는 최대 세그먼트 수 (항목당 u64 3개)
+const MAX_SEGMENTS: usize = PAGE_SIZE / (3 * 8);
+
+/// 로드된 이미지 (execute 전까지 보관)
+static LOADED: Mutex<Option<KexecImage>> = Mutex::new(None);
+
+/// kexec를 수행하는 CPU (u32::MAX = 진행 중 아님)
+static KEXEC_CPU: AtomicU32 = AtomicU32::new(u32::MAX);
+
+/// 정지 완료한 CPU 수
+static CPUS_STOPPED: AtomicU32 = AtomicU32::new(0);
+
+/// 부트 CPU로 넘긴 kexec 요청이 진행 중인지
+static HANDOFF_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// 새 커널 ELF 이미지 로드
+///
+/// 이전에 로드된 이미지가 있으면 교체합니다.
+pub fn load(path: &str) -> Result<(), &'static str> {
+    let node = crate::fs::lookup_path(path).map_err(|_| "file not found")?;
+    let stat = node.stat().map_err(|_| "failed to stat file")?;
+    if stat.size == 0 {
+        return Err("file is empty");
+    }
+
+    let mut data = Vec::new();
+    data.resize(stat.size as usize, 0u8);
+    let read = node.read(0, &mut data).map_err(|_| "failed to read file")?;
+    if read != data.len() {
+        return Err("partial read");
+    }
+
+    let elf = Elf64::parse(&data).map_err(|_| "invalid ELF image")?;
+    if elf.file_type() != ElfType::Exec {
+        return Err("not an executable ELF");
+    }
+
+    let ram = crate::dtb::get().and_then(|dt| dt.get_memory().ok());
+
+    let mut image = KexecImage {
+        path: String::from(path),
+        entry: elf.entry_point() as usize,
+        segments: Vec::new(),
+    };
+
+    for ph in elf.load_segments() {
+        if ph.p_memsz == 0 {
+            continue;
+        }
+        if image.segments.len() == MAX_SEGMENTS {
+            return Err("too many loadable segments");
+        }
+
+        let dest = ph.p_paddr as usize;
+        let len = ph.p_memsz as usize;
+        let offset = ph.p_offset as usize;
+        let filesz = ph.p_filesz as usize;
+
+        // 헤더 값은 믿을 수 없으므로 더하기가 넘치면 거부
+        let file_end = offset.checked_add(filesz).ok_or("segment out of file bounds")?;
+        if filesz > len || file_end > data.len() {
+            return Err("segment out of file bounds");
+        }
+        let end = dest.checked_add(len).ok_or("segment outside address space")?;
+        if let Some(mem) = ram {
+            let ram_end = mem.base.checked_add(mem.size).ok_or("invalid memory node")?;
+            if dest < mem.base as usize || end > ram_end as usize {
+                return Err("segment outside RAM");
+            }
+        }
+
+        let pages = len.div_ceil(PAGE_SIZE);
+        let staging = page::alloc_frames(pages).ok_or("out of memory for staging")?;
+        image.segments.push(KexecSegment { dest, staging, len, end, pages });
+
+        unsafe {
+            core::ptr::copy_nonoverlapping(data.as_ptr().add(offset), staging as *mut u8, filesz);
+            core::ptr::write_bytes((staging + filesz) as *mut u8, 0, pages * PAGE_SIZE - filesz);
+        }
+    }
+
+    if image.segments.is_empty() {
+        return Err("no loadable segments");
+    }
+    if !image
+        .segments
+        .iter()
+        .any(|s| image.entry >= s.dest && image.entry < s.end)
+    {
+        return Err("entry point outside loaded segments");
+    }
+
+    kprintln!(
+        "[kexec] Loaded {}: {} segments, {} bytes, entry={:#x}",
+        path,
+        image.segments.len(),
+        image.image_size(),
+        image.entry
+    );
+    for seg in image.segments.iter() {
+        kprintln!(
+            "[kexec]   {:#x}-{:#x} (staged at {:#x})",
+            seg.dest,
+            seg.end,
+            seg.staging
+        );
+    }
+
+    *LOADED.lock() = Some(image);
+    Ok(())
+}
+
+/// 로드된 이미지 해제
+pub fn unload() {
+    LOADED.lock().take();
+}
+
+/// 로드된 이미지로 부팅
+///
+/// 성공하면 반환하지 않습니다. 새 커널은 CPU 0에서 시작한다고 가정하므로
+/// 다른 CPU에서 호출되면 CPU 0에 고정된 스레드로 넘기고 결과를 기다립니다.
+pub fn execute() -> Result<(), &'static str> {
+    if LOADED.lock().is_none() {
+        return Err("no image loaded");
+    }
+
+    if percpu::get_cpu_id() == 0 {
+        return boot_loaded_image();
+    }
+
+    HANDOFF_PENDING.store(true, Ordering::SeqCst);
+    crate::proc::spawn_on_cpu("kexec", kexec_thread_entry, 0);
+    while HANDOFF_PENDING.load(Ordering::SeqCst) {
+        crate::proc::yield_now();
+    }
+    Err("kexec on boot CPU failed")
+}
+
+/// CPU 0에서 kexec를 수행하는 스레드
+fn kexec_thread_entry() -> ! {
+    if let Err(e) = boot_loaded_image() {
+        kprintln!("[kexec] Error: {}", e);
+    }
+    HANDOFF_PENDING.store(false, Ordering::SeqCst);
+    crate::proc::exit();
+}
+
+/// 트램펄린 준비, 시스템 정지 후 새 커널로 점프
+fn boot_loaded_image() -> Result<(), &'static str> {
+    let image = LOADED.lock().take().ok_or("no image loaded")?;
+    let dt = crate::dtb::get().ok_or("DTB not available")?;
+    let dtb = dt.base();
+
+    // 트램펄린 코드 페이지와 복사 목록 페이지
+    let tramp_page = page::alloc_frame().ok_or("out of memory for trampoline")?;
+    let list_page = match page::alloc_frame() {
+        Some(addr) => addr,
+        None => {
+            unsafe { page::free_frame(tramp_page) };
+            return Err("out of memory for copy list");
+        }
+    };
+
+    // 트램펄린 실행 중 덮어쓰면 안 되는 영역
+    let mut keep: Vec<(usize, usize)> = Vec::new();
+    keep.push((tramp_page, PAGE_SIZE));
+    keep.push((list_page, PAGE_SIZE));
+    keep.push((dtb, dt.total_size()));
+    for seg in image.segments.iter() {
+        keep.push((seg.staging, seg.pages * PAGE_SIZE));
+    }
+
+    // 끝이 넘치는 영역(DTB 헤더의 크기 등)은 겹치는 것으로 봄
+    let overlaps = image.segments.iter().any(|seg| {
+        keep.iter().any(|&(start, len)| {
+            start.checked_add(len).is_none_or(|end| seg.dest < end && start < seg.end)
+        })
+    });
+    if overlaps {
+        unsafe {
+            page::free_frame(tramp_page);
+            page::free_frame(list_page);
+        }
+        return Err("image overlaps trampoline, staging or DTB");
+    }
+
+    let list = list_page as *mut u64;
+    for (i, seg) in image.segments.iter().enumerate() {
+        unsafe {
+            list.add(i * 3).write(seg.dest as u64);
+            list.add(i * 3 + 1).write(seg.staging as u64);
+            list.add(i * 3 + 2).write(seg.len as u64);
+        }
+    }
+
+    // 남은 로그를 파일 싱크에 내보낸 뒤 마운트된 볼륨을 정상 언마운트해
+    // dirty 플래그를 남기지 않음
+    crate::log::sink::flush_all();
+    crate::fs::unmount_all();
+
+    kprintln!(
+        "[kexec] Booting {} (entry={:#x}, dtb={:#x})",
+        image.path,
+        image.entry,
+        dtb
+    );
+
+    // 트램펄린 페이지가 속한 2MB 블록을 미리 4KB 페이지로 나눠 둠: 정지한 CPU가 프레임
+    // 할당자 락을 쥐고 있을 수 있으므로 arch::kexec::boot가 읽기+실행으로 바꿀 때 할당하지 않게
+    let _ = crate::arch::mmu::protect_page(tramp_page, true, false);
+
+    stop_other_cpus();
+    quiesce_devices();
+    crate::arch::kexec::disable_interrupts();
+
+    // MMU를 끈 트램펄린이 캐시를 거치지 않고 읽으므로 dirty 라인을 미리 내보냄
+    #[cfg(target_arch = "aarch64")]
+    {
+        crate::arch::cache::flush_range(list_page, PAGE_SIZE);
+        for seg in image.segments.iter() {
+            crate::arch::cache::flush_range(seg.staging, seg.len);
+        }
+    }
+
+    let count = image.segments.len();
+    let entry = image.entry;
+    // 스테이징 프레임은 트램펄린이 읽으므로 해제하지 않음
+    core::mem::forget(image);
+
+    unsafe { crate::arch::kexec::boot(tramp_page, list_page, count, entry, dtb) }
+}
+
+/// 다른 CPU들을 정지 (reschedule IPI 경로에서 `stop_this_cpu()` 호출)
+///
+/// kexec와 `power::shutdown()`이 씁니다. 정지한 CPU는 펌웨어로 반환됩니다.
+pub fn stop_other_cpus() {
+    let me = percpu::get_cpu_id();
+    let mut expected = 0;
+
+    CPUS_STOPPED.store(0, Ordering::SeqCst);
+    KEXEC_CPU.store(me, Ordering::SeqCst);
+
+    for cpu in 0..percpu::total_count() {
+        // 오프라인으로 내린 CPU도 idle 루프에서 IPI를 받으므로 함께 정지
+        if cpu == me || !(percpu::get(cpu).is_online() || crate::proc::hotplug::is_parked(cpu)) {
+            continue;
+        }
+        expected += 1;
+
+        #[cfg(target_arch = "aarch64")]
+        crate::arch::gic::send_reschedule_ipi(cpu);
+
+        #[cfg(target_arch = "riscv64")]
+        crate::arch::plic::send_reschedule_ipi(cpu);
+    }
+
+    // 최대 ~100ms 대기 (start_smp와 동일)
+    for _ in 0..1000u32 {
+        if CPUS_STOPPED.load(Ordering::SeqCst) >= expected {
+            break;
+        }
+        for _ in 0..10000u32 {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// 디바이스 정지: DMA와 인터럽트가 새 커널 메모리를 건드리지 않도록 함
+fn quiesce_devices() {
+    for dev in crate::virtio::find_virtio_devices() {
+        crate::virtio::mmio::VirtIOMMIO::new(dev.mmio_base).reset();
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        crate::arch::timer::disable();
+        crate::arch::gic::disable();
+    }
+}
+
+/// 현재 CPU가 kexec로 인해 정지해야 하는지
+///
+/// IPI 핸들러에서 확인합니다. kexec를 수행하는 CPU 자신은 제외됩니다.
+pub fn stop_requested() -> bool {
+    let cpu = KEXEC_CPU.load(Ordering::SeqCst);
+    cpu != u32::MAX && cpu != percpu::get_cpu_id()
+}
+
+/// 현재 CPU 정지 (반환하지 않음)
+///
+/// 새 커널이 PSCI CPU_ON / SBI HSM으로 다시 시작할 수 있도록 펌웨어에 반환합니다.
+pub fn stop_this_cpu() -> ! {
+    crate::arch::kexec::disable_interrupts();
+    CPUS_STOPPED.fetch_add(1, Ordering::SeqCst);
+
+    #[cfg(target_arch = "aarch64")]
+    crate::drivers::psci::cpu_off();
+
+    #[cfg(target_arch = "riscv64")]
+    crate::boards::qemu_virt_riscv64_smp::hart_stop();
+}
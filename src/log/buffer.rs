@@ -1,38 +1,74 @@
 //! 커널 로그 링 버퍼 (dmesg)
 //!
 //! 64KB 정적 배열 기반 순환 버퍼.
-//! 엔트리 포맷: [4:length][1:level][8:timestamp_us][1:cpu_id][N:msg]
+//! 엔트리 포맷: [4:length][1:level][8:timestamp_us][1:cpu_id][8:seq][8:text_pos][N:msg]
+//!
+//! 공간이 부족하면 가장 오래된 엔트리부터 통째로 버리므로 `head`는 항상
+//! 엔트리 경계를 가리킵니다.
+//!
+//! `seq`는 레코드 일련번호, `text_pos`는 /dev/kmsg 텍스트 스트림에서 이 레코드가
+//! 시작하는 위치입니다. 오래된 레코드가 버려져도 뒤 레코드의 위치는 변하지
+//! 않으므로 파일 오프셋으로 읽기 위치를 추적할 수 있습니다.
 
 use crate::sync::Spinlock;
 use super::LogLevel;
 
 const RING_BUFFER_SIZE: usize = 64 * 1024; // 64KB
-const ENTRY_HEADER_SIZE: usize = 14; // 4 + 1 + 8 + 1
+const ENTRY_HEADER_SIZE: usize = 30; // 4 + 1 + 8 + 1 + 8 + 8
+
+/// 메시지 최대 길이 (log::log의 포매팅 버퍼와 동일)
+const MAX_MSG_LEN: usize = 512;
+
+/// dmesg 형식 한 줄 최대 길이 (접두사 + 메시지 + 개행)
+const MAX_LINE_LEN: usize = MAX_MSG_LEN + 64;
 
 struct RingBuffer {
     buffer: [u8; RING_BUFFER_SIZE],
-    write_pos: usize,
-    total_written: usize, // 총 기록 바이트 수 (wrap 감지용)
+    head: usize,   // 가장 오래된 엔트리 시작 위치
+    used: usize,   // 사용 중인 바이트 수
+    next_seq: u64, // 다음 레코드 일련번호
+    text_end: u64, // /dev/kmsg 텍스트 스트림 끝 위치
+}
+
+/// 링 버퍼 엔트리 헤더
+struct EntryHeader {
+    total_len: usize,
+    level: u8,
+    timestamp_us: u64,
+    cpu_id: u8,
+    seq: u64,
+    text_pos: u64,
 }
 
 impl RingBuffer {
     const fn new() -> Self {
         Self {
             buffer: [0u8; RING_BUFFER_SIZE],
-            write_pos: 0,
-            total_written: 0,
+            head: 0,
+            used: 0,
+            next_seq: 0,
+            text_end: 0,
         }
     }
 
     fn append(&mut self, level: LogLevel, seconds: u64, micros: u64, cpu_id: u32, msg: &str) {
-        let msg_bytes = msg.as_bytes();
+        let msg_bytes = &msg.as_bytes()[..msg.len().min(MAX_MSG_LEN)];
         let total_len = ENTRY_HEADER_SIZE + msg_bytes.len();
 
-        // 버퍼 절반보다 큰 메시지는 무시
-        if total_len > RING_BUFFER_SIZE / 2 {
-            return;
+        // 공간이 생길 때까지 가장 오래된 엔트리 제거
+        while self.used + total_len > RING_BUFFER_SIZE {
+            let Some(oldest) = self.header_at(0) else {
+                self.used = 0;
+                break;
+            };
+            self.head = (self.head + oldest.total_len) % RING_BUFFER_SIZE;
+            self.used -= oldest.total_len;
         }
 
+        let timestamp_us = seconds * 1_000_000 + micros;
+        let seq = self.next_seq;
+        let text_pos = self.text_end;
+
         // length (u32 LE)
         let len_bytes = (total_len as u32).to_le_bytes();
         self.write_bytes(&len_bytes);
@@ -41,32 +77,119 @@ impl RingBuffer {
         self.write_bytes(&[level as u8]);
 
         // timestamp_us (u64 LE)
-        let timestamp_us = seconds * 1_000_000 + micros;
         self.write_bytes(&timestamp_us.to_le_bytes());
 
         // cpu_id (u8)
         self.write_bytes(&[cpu_id as u8]);
 
+        // seq, text_pos (u64 LE)
+        self.write_bytes(&seq.to_le_bytes());
+        self.write_bytes(&text_pos.to_le_bytes());
+
         // message
         self.write_bytes(msg_bytes);
+
+        self.next_seq += 1;
+        self.text_end += kmsg_record_len(level as u8, seq, timestamp_us, msg_bytes.len()) as u64;
     }
 
     fn write_bytes(&mut self, bytes: &[u8]) {
+        let mut pos = (self.head + self.used) % RING_BUFFER_SIZE;
         for &byte in bytes {
-            self.buffer[self.write_pos] = byte;
-            self.write_pos += 1;
-            if self.write_pos >= RING_BUFFER_SIZE {
-                self.write_pos = 0;
+            self.buffer[pos] = byte;
+            pos += 1;
+            if pos >= RING_BUFFER_SIZE {
+                pos = 0;
             }
         }
-        self.total_written += bytes.len();
+        self.used += bytes.len();
+    }
+
+    fn read_at(&self, offset: usize, out: &mut [u8]) {
+        for (i, b) in out.iter_mut().enumerate() {
+            *b = self.buffer[(self.head + offset + i) % RING_BUFFER_SIZE];
+        }
     }
 
-    fn has_wrapped(&self) -> bool {
-        self.total_written > RING_BUFFER_SIZE
+    fn read_u64_at(&self, offset: usize) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.read_at(offset, &mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    /// `head` 기준 `offset`의 엔트리 헤더 (범위를 벗어나거나 손상되었으면 None)
+    fn header_at(&self, offset: usize) -> Option<EntryHeader> {
+        if offset + ENTRY_HEADER_SIZE > self.used {
+            return None;
+        }
+
+        let mut len_bytes = [0u8; 4];
+        self.read_at(offset, &mut len_bytes);
+        let total_len = u32::from_le_bytes(len_bytes) as usize;
+
+        // 유효성 검사
+        if total_len < ENTRY_HEADER_SIZE || offset + total_len > self.used {
+            return None;
+        }
+
+        let mut level = [0u8; 1];
+        let mut cpu_id = [0u8; 1];
+        self.read_at(offset + 4, &mut level);
+        self.read_at(offset + 13, &mut cpu_id);
+
+        Some(EntryHeader {
+            total_len,
+            level: level[0],
+            timestamp_us: self.read_u64_at(offset + 5),
+            cpu_id: cpu_id[0],
+            seq: self.read_u64_at(offset + 14),
+            text_pos: self.read_u64_at(offset + 22),
+        })
+    }
+
+    /// `offset`의 엔트리를 dmesg 형식 한 줄로 포매팅
+    ///
+    /// 형식: `[{seconds:>6}.{micros:06}] CPU{id} {LEVEL}: {message}\n`
+    fn format_line(&self, offset: usize, header: &EntryHeader, out: &mut [u8; MAX_LINE_LEN]) -> usize {
+        let seconds = header.timestamp_us / 1_000_000;
+        let micros = header.timestamp_us % 1_000_000;
+        let level_str = LogLevel::from_u8(header.level).as_str();
+
+        let prefix_len = {
+            let mut w = super::BufWriter::new(&mut out[..]);
+            let _ = core::fmt::write(
+                &mut w,
+                format_args!("[{:>6}.{:06}] CPU{} {}: ", seconds, micros, header.cpu_id, level_str),
+            );
+            w.pos
+        };
+
+        let msg_len = (header.total_len - ENTRY_HEADER_SIZE).min(MAX_MSG_LEN);
+        self.read_at(offset + ENTRY_HEADER_SIZE, &mut out[prefix_len..prefix_len + msg_len]);
+        out[prefix_len + msg_len] = b'\n';
+        prefix_len + msg_len + 1
+    }
+
+    /// 보존된 가장 오래된 레코드의 텍스트 위치
+    fn oldest_text_pos(&self) -> u64 {
+        self.header_at(0).map(|h| h.text_pos).unwrap_or(self.text_end)
     }
 }
 
+/// /dev/kmsg 레코드 텍스트 길이: "<level>,<seq>,<timestamp_us>,-;<msg>\n"
+fn kmsg_record_len(level: u8, seq: u64, timestamp_us: u64, msg_len: usize) -> usize {
+    fn digits(mut v: u64) -> usize {
+        let mut n = 1;
+        while v >= 10 {
+            v /= 10;
+            n += 1;
+        }
+        n
+    }
+    // '<' '>' ',' ',' ',' '-' ';' '\n'
+    8 + digits(level as u64) + digits(seq) + digits(timestamp_us) + msg_len
+}
+
 static RING_BUFFER: Spinlock<RingBuffer> = Spinlock::new(RingBuffer::new());
 
 pub fn init() {
@@ -74,85 +197,170 @@ pub fn init() {
 }
 
 pub fn append(level: LogLevel, seconds: u64, micros: u64, cpu_id: u32, msg: &str) {
-    let mut buf = RING_BUFFER.lock();
+    let mut buf = RING_BUFFER.lock_irqsave();
     buf.append(level, seconds, micros, cpu_id, msg);
+    buf.unlock_irqrestore();
 }
 
 pub fn dump_logs() {
-    let buf = RING_BUFFER.lock();
-
-    // 유효한 데이터 영역 결정
-    let (data_start, data_len) = if !buf.has_wrapped() {
-        (0, buf.write_pos)
-    } else {
-        (buf.write_pos, RING_BUFFER_SIZE)
+    let end = {
+        let buf = RING_BUFFER.lock_irqsave();
+        if buf.used == 0 {
+            drop(buf);
+            crate::console::puts("(empty log buffer)\n");
+            return;
+        }
+        buf.next_seq
     };
 
-    if data_len == 0 {
-        crate::console::puts("(empty log buffer)\n");
-        return;
+    // 인터럽트를 끈 채 콘솔로 출력하지 않도록 몇 줄씩 포매팅해 락 밖에서 출력
+    let mut out = [0u8; 2 * MAX_LINE_LEN];
+    let mut seq = 0;
+    while seq < end {
+        let batch = read_lines(seq, &mut out);
+        if batch.len == 0 {
+            break;
+        }
+        if let Ok(text) = core::str::from_utf8(&out[..batch.len]) {
+            crate::console::puts(text);
+        }
+        seq = batch.next_seq;
     }
+}
+
+/// `read_lines` 결과
+#[derive(Debug, Clone, Copy)]
+pub struct LineBatch {
+    /// `out`에 채운 바이트 수
+    pub len: usize,
+    /// 다음에 읽을 레코드 번호
+    pub next_seq: u64,
+    /// `from_seq` 이후 이미 버려져 건너뛴 레코드 수
+    pub lost: u64,
+}
+
+/// 레코드 번호 `from_seq`부터 dmesg 형식 줄로 `out`에 포매팅 (로그 싱크용)
+///
+/// 줄 단위로만 채우므로 `out`은 `MAX_LINE_LEN` 이상이어야 진행이 보장됩니다.
+pub fn read_lines(from_seq: u64, out: &mut [u8]) -> LineBatch {
+    let ring = RING_BUFFER.lock_irqsave();
+    let oldest_seq = ring.header_at(0).map(|h| h.seq).unwrap_or(ring.next_seq);
+    let start = from_seq.max(oldest_seq);
+
+    let mut batch = LineBatch {
+        len: 0,
+        next_seq: start,
+        lost: start - from_seq,
+    };
 
-    // 엔트리 파싱 및 출력
+    let mut line = [0u8; MAX_LINE_LEN];
     let mut offset = 0;
-    while offset + ENTRY_HEADER_SIZE <= data_len {
-        // length 읽기
-        let mut len_bytes = [0u8; 4];
-        for i in 0..4 {
-            len_bytes[i] = buf.buffer[(data_start + offset + i) % RING_BUFFER_SIZE];
+    while let Some(header) = ring.header_at(offset) {
+        if header.seq >= start {
+            let len = ring.format_line(offset, &header, &mut line);
+            if batch.len + len > out.len() {
+                break;
+            }
+            out[batch.len..batch.len + len].copy_from_slice(&line[..len]);
+            batch.len += len;
+            batch.next_seq = header.seq + 1;
         }
-        let total_len = u32::from_le_bytes(len_bytes) as usize;
+        offset += header.total_len;
+    }
 
-        // 유효성 검사
-        if total_len < ENTRY_HEADER_SIZE || total_len > RING_BUFFER_SIZE / 2 {
-            break;
-        }
-        if offset + total_len > data_len {
-            break;
-        }
+    batch
+}
 
-        // level 읽기
-        let level = buf.buffer[(data_start + offset + 4) % RING_BUFFER_SIZE];
+/// 마지막 레코드 `count`개를 dmesg 형식 줄로 하나씩 `f`에 전달 (패닉 출력용)
+///
+/// 락을 기다리지 않습니다. 이 CPU가 기록하던 중에 패닉해 락이 잡혀 있으면 false.
+pub fn tail(count: usize, mut f: impl FnMut(&[u8])) -> bool {
+    let Some(ring) = RING_BUFFER.try_lock() else {
+        return false;
+    };
 
-        // timestamp 읽기
-        let mut ts_bytes = [0u8; 8];
-        for i in 0..8 {
-            ts_bytes[i] = buf.buffer[(data_start + offset + 5 + i) % RING_BUFFER_SIZE];
-        }
-        let timestamp_us = u64::from_le_bytes(ts_bytes);
-        let seconds = timestamp_us / 1_000_000;
-        let micros = timestamp_us % 1_000_000;
-
-        // cpu_id 읽기
-        let cpu_id = buf.buffer[(data_start + offset + 13) % RING_BUFFER_SIZE];
-
-        // message 읽기
-        let msg_len = total_len - ENTRY_HEADER_SIZE;
-        let mut msg_buf = [0u8; 512];
-        let copy_len = msg_len.min(msg_buf.len());
-        for i in 0..copy_len {
-            msg_buf[i] = buf.buffer[(data_start + offset + ENTRY_HEADER_SIZE + i) % RING_BUFFER_SIZE];
+    let mut total: usize = 0;
+    let mut offset = 0;
+    while let Some(header) = ring.header_at(offset) {
+        total += 1;
+        offset += header.total_len;
+    }
+
+    let mut skip = total.saturating_sub(count);
+    let mut line = [0u8; MAX_LINE_LEN];
+    offset = 0;
+    while let Some(header) = ring.header_at(offset) {
+        if skip > 0 {
+            skip -= 1;
+        } else {
+            let len = ring.format_line(offset, &header, &mut line);
+            f(&line[..len]);
         }
+        offset += header.total_len;
+    }
+    true
+}
 
-        // 출력
-        let level_str = LogLevel::from_u8(level).as_str();
-        if let Ok(msg) = core::str::from_utf8(&msg_buf[..copy_len]) {
-            // 접두사 포매팅
-            let mut prefix_buf = [0u8; 40];
-            let prefix_len = {
-                let mut w = super::BufWriter::new(&mut prefix_buf);
-                let _ = core::fmt::write(
-                    &mut w,
-                    format_args!("[{:>6}.{:06}] CPU{} {}: ", seconds, micros, cpu_id, level_str),
-                );
-                w.pos
-            };
-            let prefix = unsafe { core::str::from_utf8_unchecked(&prefix_buf[..prefix_len]) };
-            crate::console::puts(prefix);
-            crate::console::puts(msg);
-            crate::console::puts("\n");
+/// /dev/kmsg 읽기
+///
+/// `offset`은 텍스트 스트림 위치입니다. 그 위치부터 레코드를 이어서 `buf`가
+/// 찰 때까지 복사하며, 이미 버려진 위치면 가장 오래된 레코드부터 읽습니다.
+///
+/// # Returns
+/// 복사한 바이트 수 (0 = 새 레코드 없음)
+pub fn kmsg_read(offset: u64, buf: &mut [u8]) -> usize {
+    let ring = RING_BUFFER.lock_irqsave();
+    let mut pos = offset.max(ring.oldest_text_pos());
+
+    let mut copied = 0;
+    let mut entry_off = 0;
+    while copied < buf.len() {
+        let Some(header) = ring.header_at(entry_off) else {
+            break;
+        };
+        let msg_off = entry_off + ENTRY_HEADER_SIZE;
+        entry_off += header.total_len;
+
+        let msg_len = header.total_len - ENTRY_HEADER_SIZE;
+        let text_len = kmsg_record_len(header.level, header.seq, header.timestamp_us, msg_len);
+        if pos >= header.text_pos + text_len as u64 {
+            continue;
         }
 
-        offset += total_len;
+        // 레코드 텍스트 구성 (메시지는 바이트 그대로 복사해 길이를 맞춤)
+        let mut record = [0u8; MAX_MSG_LEN + 64];
+        let prefix_len = {
+            let mut w = super::BufWriter::new(&mut record);
+            let _ = core::fmt::write(
+                &mut w,
+                format_args!("<{}>,{},{},-;", header.level, header.seq, header.timestamp_us),
+            );
+            w.pos
+        };
+        ring.read_at(msg_off, &mut record[prefix_len..prefix_len + msg_len]);
+        record[prefix_len + msg_len] = b'\n';
+
+        let skip = (pos - header.text_pos) as usize;
+        let n = (text_len - skip).min(buf.len() - copied);
+        buf[copied..copied + n].copy_from_slice(&record[skip..skip + n]);
+        copied += n;
+        pos += n as u64;
     }
+
+    copied
+}
+
+/// /dev/kmsg에서 아직 읽을 수 있는 가장 이른 위치로 보정
+pub fn kmsg_adjust_offset(offset: u64) -> u64 {
+    offset.max(RING_BUFFER.lock_irqsave().oldest_text_pos())
+}
+
+/// 다음에 기록될 레코드 번호
+pub fn next_seq() -> u64 {
+    RING_BUFFER.lock_irqsave().next_seq
+}
+
+/// /dev/kmsg 텍스트 스트림 끝 위치
+pub fn kmsg_end() -> u64 {
+    RING_BUFFER.lock_irqsave().text_end
 }
@@ -1,9 +1,18 @@
 //! 커널 로그 매크로
 //!
 //! log_error!, log_warn!, log_info!, log_debug!, log_trace!
+//!
+//! `tag: "vfs",`를 앞에 붙이면 서브시스템 태그별 레벨로 필터링됩니다.
+//!
+//! ```rust,ignore
+//! log_debug!(tag: "vfs", "lookup {}", path);
+//! ```
 
 #[macro_export]
 macro_rules! log_error {
+    (tag: $tag:expr, $($arg:tt)*) => {
+        $crate::log::log_tagged(Some($tag), $crate::log::LogLevel::Error, core::format_args!($($arg)*))
+    };
     ($($arg:tt)*) => {
         $crate::log::log($crate::log::LogLevel::Error, core::format_args!($($arg)*))
     };
@@ -11,6 +20,9 @@ macro_rules! log_error {
 
 #[macro_export]
 macro_rules! log_warn {
+    (tag: $tag:expr, $($arg:tt)*) => {
+        $crate::log::log_tagged(Some($tag), $crate::log::LogLevel::Warn, core::format_args!($($arg)*))
+    };
     ($($arg:tt)*) => {
         $crate::log::log($crate::log::LogLevel::Warn, core::format_args!($($arg)*))
     };
@@ -18,6 +30,9 @@ macro_rules! log_warn {
 
 #[macro_export]
 macro_rules! log_info {
+    (tag: $tag:expr, $($arg:tt)*) => {
+        $crate::log::log_tagged(Some($tag), $crate::log::LogLevel::Info, core::format_args!($($arg)*))
+    };
     ($($arg:tt)*) => {
         $crate::log::log($crate::log::LogLevel::Info, core::format_args!($($arg)*))
     };
@@ -25,6 +40,9 @@ macro_rules! log_info {
 
 #[macro_export]
 macro_rules! log_debug {
+    (tag: $tag:expr, $($arg:tt)*) => {
+        $crate::log::log_tagged(Some($tag), $crate::log::LogLevel::Debug, core::format_args!($($arg)*))
+    };
     ($($arg:tt)*) => {
         $crate::log::log($crate::log::LogLevel::Debug, core::format_args!($($arg)*))
     };
@@ -32,6 +50,9 @@ macro_rules! log_debug {
 
 #[macro_export]
 macro_rules! log_trace {
+    (tag: $tag:expr, $($arg:tt)*) => {
+        $crate::log::log_tagged(Some($tag), $crate::log::LogLevel::Trace, core::format_args!($($arg)*))
+    };
     ($($arg:tt)*) => {
         $crate::log::log($crate::log::LogLevel::Trace, core::format_args!($($arg)*))
     };
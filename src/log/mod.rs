@@ -2,17 +2,32 @@
 //!
 //! - 로그 레벨: ERROR, WARN, INFO, DEBUG, TRACE
 //! - 타임스탬프 + CPU ID 접두사
-//! - 64KB 링 버퍼 (dmesg)
+//! - 64KB 링 버퍼 (dmesg, /dev/kmsg)
 //! - Per-CPU 재귀 방지
+//! - 서브시스템 태그별 레벨 오버라이드 (`log_debug!(tag: "vfs", ...)`)
+//! - 로그 싱크 (링 버퍼를 파일 등으로 복제, `sink.rs`)
 
 mod buffer;
 mod macros;
+pub mod sink;
 
 use core::fmt;
-use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+
+use crate::sync::Spinlock;
+use crate::sysctl::{self, Kind, Tunable, Value};
 
 const MAX_CPUS: usize = 8;
 
+/// 서브시스템 태그 최대 길이
+pub const MAX_TAG_LEN: usize = 16;
+
+/// 태그별 레벨 오버라이드 최대 개수
+const MAX_TAG_OVERRIDES: usize = 16;
+
+/// `replay()`가 한 번에 포매팅할 버퍼 크기
+const REPLAY_CHUNK: usize = 4096;
+
 // 로깅 시스템 초기화 여부
 static INITIALIZED: AtomicBool = AtomicBool::new(false);
 
@@ -52,6 +67,15 @@ impl LogLevel {
         }
     }
 
+    /// 콘솔 출력 색 (`console.color`가 켜져 있을 때만 적용)
+    pub fn color(&self) -> Option<crate::console::Color> {
+        match self {
+            LogLevel::Error => Some(crate::console::Color::Red),
+            LogLevel::Warn => Some(crate::console::Color::Yellow),
+            _ => None,
+        }
+    }
+
     pub fn from_u8(v: u8) -> Self {
         match v {
             0 => LogLevel::Error,
@@ -88,6 +112,28 @@ impl LogLevel {
     }
 }
 
+/// 태그별 레벨 오버라이드 항목
+#[derive(Clone, Copy)]
+struct TagLevel {
+    name: [u8; MAX_TAG_LEN],
+    len: u8,
+    level: u8,
+}
+
+impl TagLevel {
+    fn name(&self) -> &str {
+        // set_tag_level에서 &str로부터 복사했으므로 항상 유효한 UTF-8
+        core::str::from_utf8(&self.name[..self.len as usize]).unwrap_or("")
+    }
+}
+
+/// 태그 → 레벨 테이블 (할당 없이 고정 크기)
+static TAG_TABLE: Spinlock<[Option<TagLevel>; MAX_TAG_OVERRIDES]> =
+    Spinlock::new([None; MAX_TAG_OVERRIDES]);
+
+/// 등록된 오버라이드 수 (0이면 테이블 조회 생략)
+static TAG_OVERRIDE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
 fn eq_ignore_case(a: &[u8], b: &[u8]) -> bool {
     if a.len() != b.len() {
         return false;
@@ -124,23 +170,110 @@ pub fn get_log_level() -> LogLevel {
     LogLevel::from_u8(CURRENT_LOG_LEVEL.load(Ordering::Relaxed))
 }
 
+/// 태그별 로그 레벨 설정 (전역 레벨보다 우선)
+pub fn set_tag_level(tag: &str, level: LogLevel) -> Result<(), &'static str> {
+    if tag.is_empty() || tag.len() > MAX_TAG_LEN {
+        return Err("tag must be 1-16 bytes");
+    }
+
+    let mut table = TAG_TABLE.lock_irqsave();
+    if let Some(entry) = table.iter_mut().flatten().find(|e| e.name() == tag) {
+        entry.level = level as u8;
+        return Ok(());
+    }
+
+    let slot = table.iter_mut().find(|e| e.is_none()).ok_or("tag table full")?;
+    let mut name = [0u8; MAX_TAG_LEN];
+    name[..tag.len()].copy_from_slice(tag.as_bytes());
+    *slot = Some(TagLevel { name, len: tag.len() as u8, level: level as u8 });
+    TAG_OVERRIDE_COUNT.fetch_add(1, Ordering::Relaxed);
+    Ok(())
+}
+
+/// 태그별 레벨 오버라이드 제거 (전역 레벨을 따르게 됨)
+///
+/// # Returns
+/// 오버라이드가 있었으면 true
+pub fn clear_tag_level(tag: &str) -> bool {
+    let mut table = TAG_TABLE.lock_irqsave();
+    match table.iter_mut().find(|e| matches!(e, Some(t) if t.name() == tag)) {
+        Some(slot) => {
+            *slot = None;
+            TAG_OVERRIDE_COUNT.fetch_sub(1, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// 설정된 태그별 레벨 순회
+pub fn for_each_tag_level<F: FnMut(&str, LogLevel)>(mut f: F) {
+    let table = TAG_TABLE.lock_irqsave();
+    for entry in table.iter().flatten() {
+        f(entry.name(), LogLevel::from_u8(entry.level));
+    }
+}
+
+/// 태그에 적용되는 레벨 (오버라이드가 없으면 전역 레벨)
+fn effective_level(tag: Option<&str>) -> u8 {
+    if let Some(tag) = tag {
+        if TAG_OVERRIDE_COUNT.load(Ordering::Relaxed) != 0 {
+            let table = TAG_TABLE.lock_irqsave();
+            if let Some(entry) = table.iter().flatten().find(|e| e.name() == tag) {
+                return entry.level;
+            }
+        }
+    }
+    CURRENT_LOG_LEVEL.load(Ordering::Relaxed)
+}
+
+static SYSCTL: [Tunable; 1] = [Tunable {
+    name: "log.level",
+    desc: "console log level (error, warn, info, debug, trace or 0-4)",
+    kind: Kind::Str,
+    get: || Value::Str(alloc::string::String::from(get_log_level().as_str().trim()).to_ascii_lowercase()),
+    set: Some(|v| {
+        set_log_level(LogLevel::from_str(sysctl::string(&v)).ok_or("unknown log level")?);
+        Ok(())
+    }),
+}];
+
 /// 로깅 시스템 초기화
 pub fn init() {
     buffer::init();
     INITIALIZED.store(true, Ordering::Release);
+    sysctl::register_all(&SYSCTL);
+}
+
+/// bootargs의 `loglevel=<0-4|name>`(또는 `quiet`) 처리 (`boot::cmdline::init()` 후 호출)
+pub fn init_from_cmdline() {
+    if let Some(level) = crate::boot::cmdline::loglevel() {
+        set_log_level(level);
+        crate::kprintln!("[log] Log level set to {} by command line", level.as_str().trim());
+    }
 }
 
 /// 로그 메시지 출력
 pub fn log(level: LogLevel, args: fmt::Arguments) {
+    log_tagged(None, level, args);
+}
+
+/// 서브시스템 태그가 붙은 로그 메시지 출력
+///
+/// 태그별 레벨 오버라이드로 필터링하며, 메시지 앞에 `[tag] `가 붙습니다.
+pub fn log_tagged(tag: Option<&str>, level: LogLevel, args: fmt::Arguments) {
     // 초기화 전이면 직접 UART 출력 (fallback)
     if !INITIALIZED.load(Ordering::Acquire) {
+        if let Some(tag) = tag {
+            crate::console::kprint(format_args!("[{}] ", tag));
+        }
         crate::console::kprint(args);
         crate::console::puts("\n");
         return;
     }
 
-    // 레벨 필터링
-    if (level as u8) > CURRENT_LOG_LEVEL.load(Ordering::Relaxed) {
+    // 레벨 필터링 (태그 오버라이드 우선)
+    if (level as u8) > effective_level(tag) {
         return;
     }
 
@@ -164,12 +297,14 @@ pub fn log(level: LogLevel, args: fmt::Arguments) {
 
     // 메시지를 스택 버퍼에 포매팅
     let mut msg_buf = [0u8; 512];
-    let msg_len = format_to_buf(&mut msg_buf, args);
+    let msg_len = match tag {
+        Some(tag) => format_to_buf(&mut msg_buf, format_args!("[{}] {}", tag, args)),
+        None => format_to_buf(&mut msg_buf, args),
+    };
     let msg = unsafe { core::str::from_utf8_unchecked(&msg_buf[..msg_len]) };
 
-    // UART 출력
-    crate::console::puts(prefix);
-    crate::console::puts(msg);
+    // 콘솔 출력 (색은 콘솔 계층이 붙이고, 링 버퍼에는 일반 텍스트만 저장)
+    crate::console::puts_colored(level.color(), &[prefix, msg]);
     crate::console::puts("\n");
 
     // 링 버퍼에 저장
@@ -184,8 +319,66 @@ pub fn dump_logs() {
     buffer::dump_logs();
 }
 
-// 타임스탬프 (초, 마이크로초) 계산
-fn get_timestamp() -> (u64, u64) {
+/// 링 버퍼의 모든 레코드를 dmesg 형식 줄 묶음으로 `f`에 전달
+///
+/// 늦게 초기화되는 출력 장치(`fbcon`)가 그 전의 부팅 로그를 보여줄 때 사용합니다.
+pub fn replay(mut f: impl FnMut(&[u8])) {
+    let mut chunk = alloc::vec![0u8; REPLAY_CHUNK];
+    let mut seq = 0;
+    loop {
+        let batch = buffer::read_lines(seq, &mut chunk);
+        if batch.len == 0 {
+            break;
+        }
+        f(&chunk[..batch.len]);
+        seq = batch.next_seq;
+    }
+}
+
+/// 링 버퍼의 마지막 `count`줄을 dmesg 형식으로 `f`에 전달
+///
+/// 락을 기다리지 않으므로 패닉 핸들러에서 씁니다 (락이 잡혀 있으면 false).
+pub fn tail(count: usize, f: impl FnMut(&[u8])) -> bool {
+    buffer::tail(count, f)
+}
+
+/// /dev/kmsg 읽기: 텍스트 스트림 `offset`부터 레코드를 `buf`에 복사
+///
+/// 레코드 형식: `<level>,<seq>,<timestamp_us>,-;<message>\n`
+pub fn kmsg_read(offset: u64, buf: &mut [u8]) -> usize {
+    buffer::kmsg_read(offset, buf)
+}
+
+/// /dev/kmsg 읽기 위치 보정 (버려진 레코드를 가리키면 가장 오래된 레코드로)
+pub fn kmsg_adjust_offset(offset: u64) -> u64 {
+    buffer::kmsg_adjust_offset(offset)
+}
+
+/// /dev/kmsg 텍스트 스트림 끝 위치
+pub fn kmsg_end() -> u64 {
+    buffer::kmsg_end()
+}
+
+/// /dev/kmsg 쓰기: 줄마다 로그 메시지로 기록
+///
+/// 줄 앞의 `<N>` (N = 0~4)으로 레벨을 지정하며, 없으면 INFO입니다.
+pub fn kmsg_write(text: &str) {
+    for line in text.lines() {
+        let (level, msg) = match line.as_bytes() {
+            [b'<', c @ b'0'..=b'4', b'>', ..] => (LogLevel::from_u8(c - b'0'), &line[3..]),
+            _ => (LogLevel::Info, line),
+        };
+        if !msg.is_empty() {
+            log(level, format_args!("{}", msg));
+        }
+    }
+}
+
+/// 부팅 후 경과 시간 (초, 마이크로초)
+///
+/// 로그 타임스탬프와 같은 시계이므로 다른 서브시스템의 이벤트 시각과
+/// dmesg 출력을 맞춰 볼 수 있습니다.
+pub fn get_timestamp() -> (u64, u64) {
     #[cfg(target_arch = "aarch64")]
     {
         let counter = crate::arch::timer::get_counter();
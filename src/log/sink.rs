@@ -0,0 +1,310 @@
+//! 로그 싱크 - 링 버퍼를 다른 저장소로 복제
+//!
+//! 싱크마다 마지막으로 내보낸 레코드 번호를 기억하고, `klogd` 커널 스레드가
+//! 주기적으로 새 레코드를 dmesg 형식 줄로 만들어 넘깁니다. 로그 호출 경로에서
+//! 직접 쓰지 않으므로 인터럽트 컨텍스트나 FS 락을 잡은 채 남긴 로그도 안전합니다.
+//!
+//! 싱크가 아직 쓸 수 없으면(예: FAT32 마운트 전) 커서를 유지한 채 다음 주기에
+//! 다시 시도합니다. 그 사이 링 버퍼에서 밀려난 레코드는 `lost`로 셉니다.
+//!
+//! ```text
+//! kerners> mount
+//! kerners> logsink add /mnt/kernel.log
+//! ```
+//!
+//! 부팅 시 bootargs에 `log.file=/mnt/kernel.log`가 있으면 파일 싱크를 등록합니다.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use crate::fs::{self, FileMode, VNodeType};
+use crate::sync::Mutex;
+
+use super::buffer;
+
+/// bootargs 파일 싱크 옵션
+const BOOTARG_FILE: &str = "log.file";
+
+/// klogd 플러시 주기 (마이크로초)
+const FLUSH_INTERVAL_US: u64 = 1_000_000;
+
+/// 한 번에 포매팅할 버퍼 크기
+const FLUSH_CHUNK: usize = 4096;
+
+/// 최대 싱크 수
+const MAX_SINKS: usize = 4;
+
+/// 로그 싱크
+pub trait LogSink: Send + Sync {
+    /// 싱크 이름 (목록/해제에 사용, 파일 싱크는 경로)
+    fn name(&self) -> &str;
+
+    /// 지금 쓸 수 있는지 (false면 이번 주기는 건너뜀)
+    fn ready(&self) -> bool {
+        true
+    }
+
+    /// dmesg 형식 줄 묶음 기록
+    fn write(&self, data: &[u8]) -> Result<(), &'static str>;
+
+    /// 기록한 내용을 저장소에 반영
+    fn sync(&self) -> Result<(), &'static str> {
+        Ok(())
+    }
+}
+
+/// 등록된 싱크와 진행 상태
+struct SinkSlot {
+    sink: Arc<dyn LogSink>,
+    /// 다음에 내보낼 레코드 번호
+    next_seq: u64,
+    /// 내보내기 전에 링 버퍼에서 밀려난 레코드 수
+    lost: u64,
+    /// 기록한 바이트 수
+    written: u64,
+    /// 쓰기 실패 횟수
+    errors: u64,
+}
+
+/// 싱크 정보 (`logsink` 명령 출력용)
+#[derive(Debug, Clone)]
+pub struct SinkInfo {
+    pub name: String,
+    pub ready: bool,
+    pub next_seq: u64,
+    pub lost: u64,
+    pub written: u64,
+    pub errors: u64,
+}
+
+static SINKS: Mutex<Vec<SinkSlot>> = Mutex::new(Vec::new());
+
+/// klogd 스레드 시작 여부
+static FLUSHER_STARTED: AtomicBool = AtomicBool::new(false);
+/// klogd TID (`NO_THREAD` = 시작 전)
+static FLUSHER_TID: AtomicU64 = AtomicU64::new(crate::proc::percpu::NO_THREAD);
+
+/// 싱크 등록
+///
+/// 링 버퍼에 남아 있는 가장 오래된 레코드부터 내보내며, 처음 등록될 때
+/// klogd 스레드를 시작합니다. 이미 실행 중이면 깨워서 바로 내보냅니다.
+pub fn register(sink: Arc<dyn LogSink>) -> Result<(), &'static str> {
+    {
+        let mut sinks = SINKS.lock();
+        if sinks.iter().any(|s| s.sink.name() == sink.name()) {
+            return Err("sink already registered");
+        }
+        if sinks.len() >= MAX_SINKS {
+            return Err("too many sinks");
+        }
+        sinks.push(SinkSlot {
+            sink,
+            next_seq: 0,
+            lost: 0,
+            written: 0,
+            errors: 0,
+        });
+    }
+
+    if !FLUSHER_STARTED.swap(true, Ordering::AcqRel) {
+        let tid = crate::proc::spawn("klogd", klogd_main);
+        FLUSHER_TID.store(tid, Ordering::Release);
+    } else {
+        let tid = FLUSHER_TID.load(Ordering::Acquire);
+        if tid != crate::proc::percpu::NO_THREAD {
+            crate::proc::unpark(tid);
+        }
+    }
+    Ok(())
+}
+
+/// 싱크 해제 (남은 레코드를 한 번 내보낸 뒤 제거)
+pub fn unregister(name: &str) -> bool {
+    let mut sinks = SINKS.lock();
+    let Some(pos) = sinks.iter().position(|s| s.sink.name() == name) else {
+        return false;
+    };
+    let mut buf = vec![0u8; FLUSH_CHUNK];
+    flush_slot(&mut sinks[pos], &mut buf);
+    sinks.remove(pos);
+    true
+}
+
+/// 등록된 싱크 목록
+pub fn list() -> Vec<SinkInfo> {
+    SINKS
+        .lock()
+        .iter()
+        .map(|s| SinkInfo {
+            name: String::from(s.sink.name()),
+            ready: s.sink.ready(),
+            next_seq: s.next_seq,
+            lost: s.lost,
+            written: s.written,
+            errors: s.errors,
+        })
+        .collect()
+}
+
+/// 모든 싱크에 새 레코드 내보내기
+pub fn flush_all() {
+    let mut sinks = SINKS.lock();
+    if sinks.is_empty() {
+        return;
+    }
+    let mut buf = vec![0u8; FLUSH_CHUNK];
+    for slot in sinks.iter_mut() {
+        flush_slot(slot, &mut buf);
+    }
+}
+
+/// 싱크 하나에 새 레코드 내보내기
+fn flush_slot(slot: &mut SinkSlot, buf: &mut [u8]) {
+    if !slot.sink.ready() {
+        return;
+    }
+
+    // 싱크 쓰기 중 남은 로그까지 쫓아가며 끝나지 않도록 시작 시점까지만 내보냄
+    let end_seq = buffer::next_seq();
+    let mut wrote = false;
+    while slot.next_seq < end_seq {
+        let batch = buffer::read_lines(slot.next_seq, buf);
+
+        if batch.lost > 0 {
+            let note = format!("[klogd] {} messages lost\n", batch.lost);
+            if slot.sink.write(note.as_bytes()).is_err() {
+                slot.errors += 1;
+                break;
+            }
+            slot.next_seq += batch.lost;
+            slot.lost += batch.lost;
+        }
+        if batch.len == 0 {
+            slot.next_seq = batch.next_seq;
+            break;
+        }
+
+        if slot.sink.write(&buf[..batch.len]).is_err() {
+            // 커서를 유지하고 다음 주기에 재시도
+            slot.errors += 1;
+            break;
+        }
+        slot.next_seq = batch.next_seq;
+        slot.written += batch.len as u64;
+        wrote = true;
+    }
+
+    if wrote && slot.sink.sync().is_err() {
+        slot.errors += 1;
+    }
+}
+
+/// klogd: 주기적으로(또는 싱크가 등록되면 바로) 싱크 플러시
+fn klogd_main() -> ! {
+    loop {
+        flush_all();
+        crate::proc::park_timeout(FLUSH_INTERVAL_US);
+        // 시그널로 깨어났으면 여기서 처리
+        crate::proc::signal::deliver_pending();
+    }
+}
+
+/// bootargs의 `log.file=<path>` 처리 (부팅 시 호출)
+pub fn init_from_bootargs() {
+    let Some(path) = crate::boot::cmdline::get(BOOTARG_FILE) else {
+        return;
+    };
+
+    match FileSink::new(&path).and_then(|sink| register(Arc::new(sink))) {
+        Ok(()) => crate::kprintln!("[log] Mirroring kernel log to {} (once mounted)", path),
+        Err(e) => crate::kprintln!("[log] Cannot add log file {}: {}", path, e),
+    }
+}
+
+/// 파일 싱크 - 로그를 파일 끝에 덧붙임
+///
+/// 경로가 루트 RamFS가 아닌 마운트된 파일시스템(FAT32 등)에 있을 때만 쓰므로
+/// `/mnt`가 마운트되기 전에는 기다리고, 언마운트되면 다시 멈춥니다.
+/// 처음 쓸 때 부팅 구분선을 남겨 여러 부팅의 로그를 구별할 수 있습니다.
+pub struct FileSink {
+    path: String,
+    /// 이번 부팅 구분선 기록 여부
+    marked: AtomicBool,
+}
+
+impl FileSink {
+    pub fn new(path: &str) -> Result<Self, &'static str> {
+        let path = fs::path::normalize(path).map_err(|_| "invalid path")?;
+        if path == "/" || path.ends_with('/') {
+            return Err("invalid path");
+        }
+        Ok(Self {
+            path,
+            marked: AtomicBool::new(false),
+        })
+    }
+
+    /// 파일을 찾거나 생성
+    fn open(&self) -> Result<Arc<dyn fs::VNode>, &'static str> {
+        match fs::lookup_path(&self.path) {
+            Ok(node) if node.node_type() == VNodeType::File => Ok(node),
+            Ok(_) => Err("not a file"),
+            Err(fs::VfsError::NotFound) => {
+                let pos = self.path.rfind('/').unwrap_or(0);
+                let parent_path = if pos == 0 { "/" } else { &self.path[..pos] };
+                let parent = fs::lookup_path(parent_path).map_err(|_| "parent directory not found")?;
+                parent
+                    .create(&self.path[pos + 1..], VNodeType::File, FileMode::default_file())
+                    .map_err(|_| "failed to create file")
+            }
+            Err(_) => Err("failed to access file"),
+        }
+    }
+
+    fn append(&self, file: &Arc<dyn fs::VNode>, data: &[u8]) -> Result<(), &'static str> {
+        let offset = file.stat().map(|s| s.size as usize).map_err(|_| "stat failed")?;
+        let written = file.write(offset, data).map_err(|_| "write failed")?;
+        if written != data.len() {
+            return Err("short write");
+        }
+        Ok(())
+    }
+}
+
+impl LogSink for FileSink {
+    fn name(&self) -> &str {
+        &self.path
+    }
+
+    fn ready(&self) -> bool {
+        // 루트 RamFS에 쓰면 재부팅 후 남지 않으므로 별도 마운트를 기다림
+        match (fs::find_mount(&self.path), fs::root_fs()) {
+            (Some((mounted, _)), Some(root)) => {
+                Arc::as_ptr(&mounted) as *const () != Arc::as_ptr(&root) as *const ()
+            }
+            _ => false,
+        }
+    }
+
+    fn write(&self, data: &[u8]) -> Result<(), &'static str> {
+        let file = self.open()?;
+        if !self.marked.load(Ordering::Relaxed) {
+            let (sec, usec) = super::get_timestamp();
+            let mark = format!("---- kerners boot (log attached at {}.{:06}) ----\n", sec, usec);
+            self.append(&file, mark.as_bytes())?;
+            self.marked.store(true, Ordering::Relaxed);
+        }
+        self.append(&file, data)
+    }
+
+    fn sync(&self) -> Result<(), &'static str> {
+        match fs::find_mount(&self.path) {
+            Some((mounted, _)) => mounted.sync().map_err(|_| "sync failed"),
+            None => Ok(()),
+        }
+    }
+}
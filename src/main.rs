@@ -5,20 +5,36 @@ extern crate alloc;
 
 use core::panic::PanicInfo;
 
+mod bench;
 mod block;
 mod boards;
+mod boot;
 mod console;
+mod debug;
 mod drivers;
 mod log;
 mod dtb;
+mod fbcon;
 mod fs;
+mod hwinfo;
+mod input;
 mod ipc;
+mod irq;
+mod kexec;
 mod mm;
 mod module;
+mod panic;
+mod power;
 mod proc;
+mod selftest;
+mod softirq;
 mod sync;
 mod syscall;
+mod sysctl;
+mod time;
+mod trace;
 mod virtio;
+mod watchdog;
 
 #[cfg(feature = "test_runner")]
 mod test_runner;
@@ -67,14 +83,14 @@ real_start:
 "#
 );
 
-#[cfg(target_arch = "riscv64")]
+#[cfg(all(target_arch = "riscv64", not(feature = "riscv_smode")))]
 core::arch::global_asm!(
     r#"
     .section .text.boot
     .global _start
     .type _start, @function
 _start:
-    // a0 = hartid, a1 = DTB 주소 (OpenSBI/QEMU가 전달)
+    // a0 = hartid, a1 = DTB 주소 (QEMU -bios none이 전달, M-mode)
     
     // Hart 0만 부팅 진행, 나머지는 대기
     csrr t0, mhartid
@@ -107,6 +123,61 @@ park:
 "#
 );
 
+#[cfg(all(target_arch = "riscv64", feature = "riscv_smode"))]
+core::arch::global_asm!(
+    r#"
+    .section .text.boot
+    .global _start
+    .type _start, @function
+_start:
+    // a0 = hartid, a1 = DTB 주소 (OpenSBI가 전달, S-mode)
+    // OpenSBI는 임의의 hart 하나로 부팅하고 나머지는 HSM STOPPED 상태로 둠
+
+    // 커널은 hart 0을 boot hart로 가정: 다른 hart면 hart 0을 깨우고 자신은 정지
+    bnez a0, wake_hart0
+
+    // S-mode는 mhartid를 읽을 수 없으므로 sscratch에 보관 (arch::csr::hartid)
+    csrw sscratch, a0
+
+    // Hart 0: DTB 주소 보관
+    mv s0, a1
+    
+    // 스택 포인터 설정
+    la sp, _stack_start
+    
+    // BSS 영역 초기화
+    la t0, _bss
+    la t1, _ebss
+clear_bss:
+    beq t0, t1, bss_done
+    sd zero, 0(t0)
+    addi t0, t0, 8
+    j clear_bss
+
+bss_done:
+    // DTB 주소를 인자로 전달하고 Rust 코드 호출
+    mv a0, s0
+    call _entry
+
+wake_hart0:
+    // SBI HSM hart_start(hartid = 0, start_addr = _start, opaque = DTB)
+    mv a2, a1
+    la a1, _start
+    li a0, 0
+    li a6, 0
+    li a7, 0x48534D
+    ecall
+    // SBI HSM hart_stop (나중에 start_smp가 다시 시작)
+    li a6, 1
+    li a7, 0x48534D
+    ecall
+
+park:
+    wfi
+    j park
+"#
+);
+
 /// 어셈블리 진입점에서 호출되는 Rust 엔트리
 /// dtb_addr: QEMU가 전달한 DTB 주소
 #[unsafe(no_mangle)]
@@ -207,6 +278,13 @@ fn aarch64_start(dtb_addr: usize) -> ! {
             // 로깅 시스템 초기화 (힙 사용 가능 후)
             log::init();
 
+            // 커널 명령줄 파싱 (QEMU -append), loglevel=, console.color=, panic=, module.verify=, module.timeout= 적용
+            boot::cmdline::init();
+            log::init_from_cmdline();
+            console::init_from_cmdline();
+            panic::init_from_cmdline();
+            module::init_from_cmdline();
+
             // 힙 초기화 완료 후 DTB 디바이스 정보 출력 (디버깅용)
             if let Some(dt) = dtb::get() {
                 dt.dump_devices();
@@ -219,6 +297,12 @@ fn aarch64_start(dtb_addr: usize) -> ! {
             let platform_config = drivers::probe::probe_platform();
             drivers::config::init_platform_config(platform_config);
 
+            // PSCI 호출 방식과 idle 상태 (보조 CPU 시작, 재부팅, CPU idle)
+            drivers::psci::init();
+
+            // 시리얼 포트 표 (/dev/ttySn, UART IRQ 라우팅)
+            drivers::serial::init();
+
             // MMU 초기화
             match arch::mmu::init(ram_base, ram_size) {
                 Ok(()) => {
@@ -233,12 +317,24 @@ fn aarch64_start(dtb_addr: usize) -> ! {
                                         enable_irq();
                                     }
 
+                                    // UART RX 인터럽트 (콘솔 입력 링, 시리얼 포트 수신 링)
+                                    drivers::serial::enable_rx_irqs();
+
                                     // 메모리 할당 테스트
                                     test_memory_allocation();
 
                                     // 프로세스 서브시스템 초기화
                                     proc::init();
 
+                                    // bootargs의 watchdog=<초>: 이후 부팅(SMP 포함) 중 멈춤 감지
+                                    watchdog::init_from_cmdline();
+
+                                    // bootargs의 trace[=<events>]: 부팅 중 이벤트부터 추적
+                                    trace::event::init_from_bootargs();
+
+                                    // 벽시계 초기화 (RTC)
+                                    time::clock::init();
+
                                     // VFS 초기화
                                     init_vfs();
 
@@ -251,6 +347,35 @@ fn aarch64_start(dtb_addr: usize) -> ! {
                                     // 블록 디바이스를 DevFS에 등록 (/dev/vda 등)
                                     fs::devfs::register_block_devices_to_devfs();
 
+                                    // bootargs의 root=<dev>이면 FAT32를 /mnt에 마운트
+                                    block::mount_root_from_cmdline();
+
+                                    // bootargs의 crashdump=<dev>[@<블록>]: 패닉 때 덤프를 쓸 영역
+                                    debug::crashdump::init_from_cmdline();
+
+                                    // 입력 디바이스 초기화 (/dev/input/eventN)
+                                    input::init();
+
+                                    // VirtIO 콘솔 (/dev/hvc0), bootargs의 console=hvc0이면 주 콘솔로 전환
+                                    virtio::console::init();
+                                    console::init_from_bootargs();
+
+                                    // bootargs의 gdb=<ttyS<n>|hvc0>: GDB 원격 스텁 포트
+                                    debug::gdbstub::init_from_cmdline();
+
+                                    // VirtIO GPU (/dev/fb0)와 프레임버퍼 콘솔 (부팅 로그를 화면에 표시)
+                                    virtio::gpu::init();
+                                    fbcon::init();
+
+                                    // VirtIO 9P 전송 (mount -t 9p <tag>로 호스트 디렉토리 마운트)
+                                    virtio::p9::init();
+
+                                    // bootargs의 log.file=<path> 로그 파일 싱크 등록
+                                    log::sink::init_from_bootargs();
+
+                                    // bootargs의 ramfs.restore=<target> 스냅샷 복원
+                                    fs::ramfs::archive::restore_from_bootargs();
+
                                     // 테스트: 모듈 로드
                                     #[cfg(feature = "embed_test_module")]
                                     {
@@ -264,6 +389,9 @@ fn aarch64_start(dtb_addr: usize) -> ! {
                                     // SMP 부팅 (secondary CPUs 시작)
                                     start_smp();
 
+                                    // 하드웨어 요약 출력 (hwinfo 명령으로 다시 조회)
+                                    hwinfo::init();
+
                                     kprintln!("\n[boot] Initialization complete!");
 
                                     #[cfg(feature = "test_runner")]
@@ -353,53 +481,74 @@ fn echo_to_file(path: &str, text: &str, append: bool) -> Result<(), &'static str
     Ok(())
 }
 
+/// 파일 옮기기 (`mv`)
+///
+/// `fs::rename()`이 다른 디렉토리/파일시스템이라 `CrossDevice`를 반환하면
+/// 일반 파일에 한해 복사한 뒤 원본을 삭제합니다.
+fn move_path(src: &str, dst: &str) -> fs::VfsResult<()> {
+    use fs::fd::{self, OpenFlags};
+
+    match fs::rename(src, dst) {
+        Err(fs::VfsError::CrossDevice) => {}
+        result => return result,
+    }
+
+    let src = fs::path::normalize(src)?;
+    let node = fs::lookup_path(&src)?;
+    if node.node_type() != fs::VNodeType::File {
+        return Err(fs::VfsError::CrossDevice);
+    }
+
+    let flags = OpenFlags::new(OpenFlags::O_WRONLY | OpenFlags::O_CREAT | OpenFlags::O_TRUNC);
+    let mode = node.stat().map(|st| st.mode).unwrap_or(fs::FileMode::default_file());
+    let out = fd::open(dst, flags, mode)?;
+    let mut buf = alloc::vec![0u8; 4096];
+    let mut offset = 0;
+    loop {
+        let n = node.read(offset, &mut buf)?;
+        if n == 0 {
+            break;
+        }
+        out.write(&buf[..n])?;
+        offset += n;
+    }
+
+    let (parent, name) = fs::path::split(&src);
+    fs::lookup_path(parent)?.unlink(name)
+}
+
 /// 간단한 쉘
 fn simple_shell() -> ! {
     use alloc::string::String;
-    use alloc::vec::Vec;
 
+    let tty = drivers::tty::console();
     let mut line = String::new();
+    let mut buf = [0u8; 64];
 
     loop {
         // 프롬프트 출력
-        console::puts("\nkerners> ");
+        console::puts("\n");
+        console::puts_colored(Some(console::Color::BoldGreen), &["kerners> "]);
 
-        // 명령 입력 받기
+        // 명령 입력 받기 (편집과 에코는 콘솔 TTY 줄 규칙이 처리)
         line.clear();
-        loop {
-            // 폴링 방식으로 입력 받기
-            if let Some(ch) = arch::uart::getc() {
-                if ch == b'\r' || ch == b'\n' {
-                    console::puts("\n");
-                    break;
-                } else if ch == 0x7F || ch == 0x08 {
-                    // Backspace
-                    if !line.is_empty() {
-                        line.pop();
-                        // 백스페이스 에코
-                        console::puts("\x08 \x08");
+        'read: loop {
+            if let Some(n) = tty.read(&mut buf) {
+                for &ch in &buf[..n] {
+                    // raw 모드(stty raw)에서는 CR이 그대로 옴
+                    if ch == b'\n' || ch == b'\r' {
+                        break 'read;
+                    }
+                    if ch.is_ascii() {
+                        line.push(ch as char);
                     }
-                } else if ch >= 32 && ch < 127 {
-                    // 출력 가능한 ASCII 문자
-                    line.push(ch as char);
-                    // 에코
-                    console::putc(ch);
                 }
             } else {
-                // 입력이 없으면 잠시 대기
-                #[cfg(target_arch = "aarch64")]
+                // 입력이 없으면 다음 인터럽트까지 대기 (UART RX IRQ가 수신 링을 채우고 깨움,
+                // SBI 콘솔처럼 RX IRQ가 없는 입력은 타이머 tick마다 다시 확인)
                 unsafe {
-                    // AArch64: WFI는 IRQ로 깨어남
                     core::arch::asm!("wfi");
                 }
-                #[cfg(target_arch = "riscv64")]
-                {
-                    // RISC-V: 폴링 모드에서는 짧은 스핀 루프 사용
-                    // (UART 인터럽트 설정 없이 WFI 사용 시 멈출 수 있음)
-                    for _ in 0..1000 {
-                        core::hint::spin_loop();
-                    }
-                }
             }
         }
 
@@ -409,413 +558,1312 @@ fn simple_shell() -> ! {
             continue;
         }
 
-        let parts: Vec<&str> = cmd.split_whitespace().collect();
-        match parts.get(0).map(|s| *s) {
-            Some("help") => {
-                kprintln!("Available commands:");
-                kprintln!("  help     - Show this help");
-                kprintln!("  meminfo  - Show memory information");
-                kprintln!("  uptime   - Show system uptime");
-                kprintln!("  boardinfo - Show active board information");
-                kprintln!("  lsboards - List registered boards");
-                kprintln!("  threads  - Show thread list");
-                kprintln!("  spawn    - Spawn a test thread");
-                kprintln!("  usertest - Test user mode");
-                kprintln!("  mqtest   - Test message queue");
-                kprintln!("  modtest  - Test module loader");
-                kprintln!("  lsmod    - List loaded modules");
-                kprintln!("  insmod <path> - Load module from path");
-                kprintln!("  rmmod <name> - Unload a module");
-                kprintln!("  ls [path] - List directory contents");
-                kprintln!("  cat <path> - Display file contents");
-                kprintln!("  write <path> <text> - Write text to file");
-                kprintln!("  echo <text> [> file] - Echo text (optionally to file)");
-                kprintln!("  blkinfo  - Show block devices");
-                kprintln!("  blktest  - Test VirtIO block device");
-                kprintln!("  mount    - Mount FAT32 from /dev/vda to /mnt");
-                kprintln!("  mounts   - List mount points");
-                kprintln!("  cpuinfo  - Show CPU/SMP status");
-                kprintln!("  dmesg    - Display kernel ring buffer");
-                kprintln!("  loglevel [level] - Set log level (0-4 or ERROR/WARN/INFO/DEBUG/TRACE)");
-            }
-            Some("meminfo") => {
-                mm::heap::print_stats();
-                mm::page::print_stats();
-            }
-            Some("uptime") => {
-                let ticks = arch::timer::ticks();
-                let seconds = ticks / 100;
-                let minutes = seconds / 60;
-                let hours = minutes / 60;
-                kprintln!(
-                    "Uptime: {}h {}m {}s ({} ticks)",
-                    hours,
-                    minutes % 60,
-                    seconds % 60,
-                    ticks
-                );
+        run_command(cmd);
+    }
+}
+
+/// 셸 스크립트 최대 중첩 깊이 (`run` 안의 `run`)
+const SCRIPT_MAX_DEPTH: usize = 4;
+
+/// 셸 스크립트 최대 크기
+const SCRIPT_MAX_SIZE: usize = 64 * 1024;
+
+/// 현재 스크립트 중첩 깊이
+static SCRIPT_DEPTH: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+/// 셸 스크립트 실행 (`run <path>`)
+///
+/// 파일의 각 줄을 셸 명령으로 실행합니다. 빈 줄과 `#`으로 시작하는 줄은 건너뛰고,
+/// 실행하는 줄은 `+ `를 붙여 출력합니다. 알 수 없는 명령을 만나면 줄 번호를
+/// 출력하고 중단합니다.
+///
+/// # Returns
+/// 모든 줄을 실행했으면 true
+fn run_script(path: &str) -> bool {
+    use core::sync::atomic::Ordering;
+
+    let data = match fs::lookup_path(path) {
+        Ok(node) if node.node_type() == fs::VNodeType::File => {
+            let size = node.stat().map(|s| s.size as usize).unwrap_or(0);
+            if size > SCRIPT_MAX_SIZE {
+                kprintln!("run: {}: script too large ({} bytes, max {})", path, size, SCRIPT_MAX_SIZE);
+                return false;
             }
-            Some("boardinfo") => {
-                if let Some(board) = boards::current_board_info() {
-                    kprintln!("Active board: {}", board.name);
-                    kprintln!("  Compatible: {:?}", board.compatible);
-                    kprintln!("  Timer freq: {} Hz", board.timer_freq);
-                    kprintln!("  UART quirks: {:#x}", board.uart_quirks);
-                    kprintln!("  SMP capable: {}", if board.smp_capable { "yes" } else { "no" });
-                    if board.cpu_count > 0 {
-                        kprintln!("  CPU count: {}", board.cpu_count);
-                    } else {
-                        // DTB에서 CPU 개수 읽기
-                        let cpu_count = dtb::get().map(|dt| dt.count_cpus()).unwrap_or(1);
-                        kprintln!("  CPU count: {} (from DTB)", cpu_count);
+            let mut buf = alloc::vec![0u8; size];
+            match node.read(0, &mut buf) {
+                Ok(n) => {
+                    buf.truncate(n);
+                    buf
+                }
+                Err(e) => {
+                    kprintln!("run: {}: read failed: {:?}", path, e);
+                    return false;
+                }
+            }
+        }
+        Ok(_) => {
+            kprintln!("run: {}: not a file", path);
+            return false;
+        }
+        Err(e) => {
+            kprintln!("run: {}: {:?}", path, e);
+            return false;
+        }
+    };
+    let Ok(text) = core::str::from_utf8(&data) else {
+        kprintln!("run: {}: not a text file", path);
+        return false;
+    };
+
+    if SCRIPT_DEPTH.fetch_add(1, Ordering::AcqRel) >= SCRIPT_MAX_DEPTH {
+        SCRIPT_DEPTH.fetch_sub(1, Ordering::AcqRel);
+        kprintln!("run: {}: scripts nested too deeply (max {})", path, SCRIPT_MAX_DEPTH);
+        return false;
+    }
+
+    let mut ok = true;
+    for (lineno, line) in text.lines().enumerate() {
+        let cmd = line.trim();
+        if cmd.is_empty() || cmd.starts_with('#') {
+            continue;
+        }
+        kprintln!("+ {}", cmd);
+        if !run_command(cmd) {
+            kprintln!("run: {}:{}: aborted", path, lineno + 1);
+            ok = false;
+            break;
+        }
+    }
+
+    SCRIPT_DEPTH.fetch_sub(1, Ordering::AcqRel);
+    ok
+}
+
+/// 셸 명령 한 줄 실행
+///
+/// 알 수 없는 명령이거나 하위 스크립트가 중단되면 false를 반환합니다
+/// (스크립트 실행 중단에 사용).
+fn run_command(cmd: &str) -> bool {
+    use alloc::vec::Vec;
+
+    let parts: Vec<&str> = cmd.split_whitespace().collect();
+    match parts.get(0).map(|s| *s) {
+        Some("help") => {
+            kprintln!("Available commands:");
+            kprintln!("  help     - Show this help");
+            kprintln!("  meminfo [-m] - Show memory information (-m: heap usage by module)");
+            kprintln!("  oom [kill] - Show OOM stats and next victim, or kill it now");
+            kprintln!("  poison [on|off] - Toggle heap poisoning");
+            kprintln!("  memleak [checkpoint|all] - List heap allocations live since checkpoint");
+            kprintln!("  lockstat [reset] - Show lock classes by contention and lock order violations");
+            kprintln!("  failinject [<frame|heap|block> [every n] [prob pct] [times n] [tid n] | <point> off | off | seed n] - Fault injection");
+            kprintln!("  gdb [port <ttyS<n>|hvc0>|off|break] - GDB remote stub status, port, or stop here for GDB");
+            kprintln!("  crashdump [show|clear|dev <dev>[@<block>]|off] - Crash dump region, read back the last panic");
+            kprintln!("  sysctl [-a|-v] [<key>[=<value>]] - Show or set runtime tunables (sched, log, panic, fat32, ...)");
+            kprintln!("  uptime   - Show system uptime");
+            kprintln!("  timers   - List pending kernel timers (owner, expiry, period)");
+            kprintln!("  workqueue - Workqueue workers and pending work items");
+            kprintln!("  watchdog [start [secs]|stop] - Lockup watchdog status, or start with kernel pinging");
+            kprintln!("  interrupts - Per-CPU IRQ counts and handlers (like /proc/interrupts)");
+            kprintln!("  softirqs - Per-CPU tasklet (IRQ bottom half) counts");
+            kprintln!("  date [unix-seconds] - Show or set the wall clock (UTC)");
+            kprintln!("  boardinfo - Show active board information");
+            kprintln!("  hwinfo   - Show hardware summary (CPU, memory, IRQ, UART, VirtIO)");
+            kprintln!("  cmdline  - Show kernel command line (bootargs) and parsed options");
+            kprintln!("  lsboards - List registered boards");
+            kprintln!("  threads  - Show thread list");
+            kprintln!("  top [ms] - Per-thread and per-CPU usage over an interval");
+            kprintln!("  schedstat [cpu|reset] - Per-CPU wakeup latency and context switch histograms");
+            kprintln!("  bench [-n iters] [heap|ctxsw|ipc|vfs|block]... - Micro-benchmarks: ops/sec and latency percentiles");
+            kprintln!("  kill [-SIG] <tid> - Send a signal to a thread (default TERM)");
+            kprintln!("  taskset <tid> [mask] - Show or set a thread's CPU affinity (hex mask)");
+            kprintln!("  spawn    - Spawn a test thread");
+            kprintln!("  usertest - Test user mode");
+            kprintln!("  mqtest   - Test message queue");
+            kprintln!("  vmatest  - Test demand paging / page fault handler");
+            kprintln!("  vmas     - Show VMAs and page fault stats");
+            kprintln!("  selftest [name] - Run a named self test (mm.smp, mm.buddy, sched.policy, signal, futex, join, fd)");
+            kprintln!("  modtest  - Test module loader");
+            kprintln!("  lsmod    - List loaded modules");
+            kprintln!("  insmod <path> - Load module from path");
+            kprintln!("  rmmod <name> - Unload a module");
+            kprintln!("  ksyms [filter] - List kernel symbols exported to modules");
+            kprintln!("  kprobe [add <sym|addr>[+off] [log] | del|on|off <id>] - Dynamic breakpoints");
+            kprintln!("  trace [on [n]|off|clear|dump [n]] - Per-CPU tracepoint event buffers");
+            kprintln!("  drivers [probe] - List DTB drivers and bound devices, or re-run probe");
+            kprintln!("  kexec <path> - Boot a new kernel ELF image from VFS");
+            kprintln!("  panic [msg] - Trigger a kernel panic (backtrace, then panic= halt/reboot)");
+            kprintln!("  reboot   - Sync filesystems and reset the system");
+            kprintln!("  poweroff - Sync filesystems and power off (exits QEMU)");
+            kprintln!("  ls [path] - List directory contents");
+            kprintln!("  cat <path> - Display file contents");
+            kprintln!("  run <path> - Run shell commands from a file (alias: sh)");
+            kprintln!("  write <path> <text> - Write text to file");
+            kprintln!("  mkfifo <path> - Create a named pipe (RamFS)");
+            kprintln!("  mv <src> <dst> - Rename or move a file or directory");
+            kprintln!("  ln <target> <link> - Create a hard link (RamFS)");
+            kprintln!("  echo <text> [> file] - Echo text (optionally to file)");
+            kprintln!("  blkinfo  - Show block devices");
+            kprintln!("  blktest  - Test VirtIO block device");
+            kprintln!("  input    - Show input devices and event counters");
+            kprintln!("  mount [dev] [path] - Mount FAT32 (default: /dev/vda on /mnt)");
+            kprintln!("  mount -t 9p <tag> [path] - Mount host directory over virtio-9p (default: /host)");
+            kprintln!("  mounts   - List mount points");
+            kprintln!("  df       - Show size and free space of mounted filesystems");
+            kprintln!("  locks    - List flock file locks (open file id, type, path)");
+            kprintln!("  fds [tid] - List a thread's file descriptors (default: kernel fd table)");
+            kprintln!("  stty [sane|raw|[-]icanon|[-]echo|[-]icrnl]... - Show or change console tty modes");
+            kprintln!("  console [list|enable <name>|disable <name>|priority <name> <n>|log [clear]|color [on|off]] - Manage output consoles");
+            kprintln!("  ramfs save|load <target> - Snapshot/restore RamFS (e.g. /dev/vda:/backup.img)");
+            kprintln!("  cpuinfo  - Show CPU/SMP status");
+            #[cfg(target_arch = "aarch64")]
+            kprintln!("  psci     - PSCI conduit, version, CPU power states and idle state usage");
+            kprintln!("  cpu offline|online <n> - Take a secondary CPU offline (threads migrate) or bring it back");
+            kprintln!("  dmesg    - Display kernel ring buffer");
+            kprintln!("  loglevel [level] - Set log level (0-4 or ERROR/WARN/INFO/DEBUG/TRACE)");
+            kprintln!("  loglevel <tag>=<level|default> - Per-subsystem level override (e.g. vfs=debug)");
+            kprintln!("  logsink [add|rm <path>|flush] - Mirror kernel log to a file (e.g. /mnt/kernel.log)");
+        }
+        Some("meminfo") => {
+            mm::heap::print_stats();
+            mm::page::print_stats();
+            mm::slab::dump_stats();
+            mm::dma::print_stats();
+            if parts.get(1) == Some(&"-m") {
+                mm::owner::dump_stats();
+            }
+        }
+        Some("oom") => match parts.get(1).map(|s| *s) {
+            Some("kill") => match mm::oom::kill_victim() {
+                Some(v) => kprintln!("[oom] Killed tid={} '{}' ({} pages)", v.tid, v.name(), v.pages),
+                None => kprintln!("[oom] No victim (none killable or previous victim still exiting)"),
+            },
+            None => mm::oom::dump(),
+            Some(_) => kprintln!("Usage: oom [kill]"),
+        },
+        Some("memleak") => {
+            #[cfg(feature = "kmemleak")]
+            match parts.get(1).map(|s| *s) {
+                Some("checkpoint") => {
+                    let generation = mm::memleak::checkpoint();
+                    kprintln!("[memleak] Checkpoint set (generation {})", generation);
+                }
+                Some("all") => mm::memleak::report(0),
+                None => mm::memleak::report(mm::memleak::generation()),
+                Some(_) => kprintln!("Usage: memleak [checkpoint|all]"),
+            }
+            #[cfg(not(feature = "kmemleak"))]
+            kprintln!("memleak: kernel built without 'kmemleak' feature");
+        }
+        Some("lockstat") => {
+            #[cfg(feature = "lockdep")]
+            match parts.get(1).map(|s| *s) {
+                Some("reset") => {
+                    sync::lockdep::reset_stats();
+                    kprintln!("[lockstat] Counters reset");
+                }
+                None => sync::lockdep::report_stats(32),
+                Some(_) => kprintln!("Usage: lockstat [reset]"),
+            }
+            #[cfg(not(feature = "lockdep"))]
+            kprintln!("lockstat: kernel built without 'lockdep' feature");
+        }
+        Some("poison") => {
+            match parts.get(1).map(|s| *s) {
+                Some("on") => mm::heap::set_poison(true),
+                Some("off") => mm::heap::set_poison(false),
+                Some(_) => kprintln!("Usage: poison [on|off]"),
+                None => {}
+            }
+            kprintln!(
+                "Heap poisoning: {} (alloc={:#04x}, free={:#04x})",
+                if mm::heap::poison_enabled() { "on" } else { "off" },
+                mm::heap::ALLOC_POISON,
+                mm::heap::FREE_POISON
+            );
+            #[cfg(feature = "heap_redzone")]
+            kprintln!(
+                "Heap red zones: {} bytes ({:#04x}), {} error(s) reported",
+                mm::redzone::REDZONE_SIZE,
+                mm::redzone::REDZONE_BYTE,
+                mm::redzone::errors()
+            );
+        }
+        Some("failinject") => {
+            use debug::failinject::{self, Point};
+            const USAGE: &str =
+                "Usage: failinject [<frame|heap|block> [every n] [prob pct] [times n] [tid n] | <point> off | off | seed n]";
+            match parts.get(1).map(|s| *s) {
+                None => {}
+                Some("off") => failinject::disable_all(),
+                Some("seed") => match parts.get(2).and_then(|s| s.parse::<u64>().ok()) {
+                    Some(seed) => failinject::set_seed(seed),
+                    None => kprintln!("{}", USAGE),
+                },
+                Some(name) => match Point::from_name(name) {
+                    Some(point) if parts.get(2) == Some(&"off") => failinject::disable(point),
+                    Some(point) => {
+                        let mut config = failinject::Config::default();
+                        let mut valid = true;
+                        for pair in parts[2..].chunks(2) {
+                            let value = pair.get(1).and_then(|s| s.parse::<u64>().ok());
+                            match (pair[0], value) {
+                                ("every", Some(n)) if n > 0 => config.interval = n as usize,
+                                ("prob", Some(n)) if n > 0 && n <= 100 => config.probability = n as u32,
+                                ("times", Some(n)) => config.times = Some(n as usize),
+                                ("tid", Some(n)) => config.tid = Some(n),
+                                _ => valid = false,
+                            }
+                        }
+                        if !valid {
+                            kprintln!("{}", USAGE);
+                        } else if point == Point::Heap && config.tid.is_none() {
+                            // 컬렉션 할당 실패는 패닉이므로 모든 스레드에 걸면 셸부터 멈춤
+                            kprintln!("failinject: heap needs 'tid <n>'");
+                        } else {
+                            failinject::configure(point, config);
+                        }
+                    }
+                    None => kprintln!("{}", USAGE),
+                },
+            }
+            failinject::report();
+        }
+        Some("gdb") => {
+            use debug::gdbstub::{self, Port};
+            match (parts.get(1).copied(), parts.get(2).copied()) {
+                (None, _) => {}
+                (Some("port"), Some(name)) => match Port::parse(name).ok_or("unknown port") {
+                    Ok(port) => {
+                        if let Err(e) = gdbstub::set_port(Some(port)) {
+                            kprintln!("gdb: {}: {}", name, e);
+                        }
+                    }
+                    Err(e) => kprintln!("gdb: {}: {}", name, e),
+                },
+                (Some("off"), None) => {
+                    let _ = gdbstub::set_port(None);
+                }
+                (Some("break"), None) => {
+                    if let Err(e) = gdbstub::breakpoint() {
+                        kprintln!("gdb: {}", e);
                     }
-                } else {
-                    kprintln!("No active board module");
-                    kprintln!("Using compile-time defaults (BoardConfig)");
                 }
+                _ => kprintln!("Usage: gdb [port <ttyS<n>|hvc0>|off|break]"),
             }
-            Some("lsboards") => {
-                kprintln!("Registered board modules:");
-                boards::registry::for_each_board(|name, is_active| {
-                    let marker = if is_active { "*" } else { " " };
-                    kprintln!("  {} {}", marker, name);
-                });
-                kprintln!("");
-                kprintln!("Total: {} board(s) (* = active)", boards::registry::board_count());
-            }
-            Some("threads") => {
-                proc::dump_threads();
-            }
-            Some("cpuinfo") => {
-                let total = proc::percpu::total_count();
-                let online = proc::percpu::online_count();
-                let my_cpu = proc::percpu::get_cpu_id();
-                kprintln!("CPU info:");
-                kprintln!("  Total CPUs: {}", total);
-                kprintln!("  Online CPUs: {}", online);
-                kprintln!("  Current CPU: {}", my_cpu);
-                for cpu in 0..total {
-                    let pc = proc::percpu::get(cpu);
-                    let status = if pc.is_online() { "online" } else { "offline" };
-                    let ticks = pc.tick_count.load(core::sync::atomic::Ordering::Relaxed);
-                    kprintln!("  CPU {}: {} (ticks: {})", cpu, status, ticks);
-                }
-            }
-            Some("spawn") => {
-                static THREAD_COUNT: core::sync::atomic::AtomicU64 = 
-                    core::sync::atomic::AtomicU64::new(1);
-                let n = THREAD_COUNT.fetch_add(1, core::sync::atomic::Ordering::SeqCst);
-                let name = alloc::format!("test-{}", n);
-                let tid = proc::spawn(&name, test_thread_entry);
-                kprintln!("Spawned thread '{}' (tid={})", name, tid);
-            }
-            Some("usertest") => {
-                proc::user::test_user_mode();
-            }
-            Some("mqtest") => {
-                test_message_queue();
-            }
-            Some("modtest") => {
-                test_module_loader();
-            }
-            Some("lsmod") => {
-                let modules = module::ModuleLoader::list();
-                if modules.is_empty() {
-                    kprintln!("No modules loaded");
+            gdbstub::report();
+        }
+        Some("crashdump") => {
+            use debug::crashdump;
+            match (parts.get(1).copied(), parts.get(2).copied()) {
+                (None, _) => crashdump::report(),
+                (Some("show"), None) => match crashdump::read() {
+                    Ok(Some(dump)) => crashdump::show(&dump),
+                    Ok(None) => kprintln!("crashdump: no dump"),
+                    Err(e) => kprintln!("crashdump: {}", e),
+                },
+                (Some("clear"), None) => match crashdump::clear() {
+                    Ok(()) => crashdump::report(),
+                    Err(e) => kprintln!("crashdump: {}", e),
+                },
+                (Some("dev"), Some(spec)) => match crashdump::set_target(Some(spec)) {
+                    Ok(()) => crashdump::report(),
+                    Err(e) => kprintln!("crashdump: {}: {}", spec, e),
+                },
+                (Some("off"), None) => {
+                    let _ = crashdump::set_target(None);
+                    crashdump::report();
+                }
+                _ => kprintln!("Usage: crashdump [show|clear|dev <dev>[@<block>]|off]"),
+            }
+        }
+        Some("sysctl") => {
+            sysctl::command(&parts[1..]);
+        }
+        Some("uptime") => {
+            // 틱 카운터는 모든 CPU의 틱 합계이므로 시간은 단조 시계로 계산
+            let ticks = arch::timer::ticks();
+            let seconds = time::now_us() / 1_000_000;
+            let minutes = seconds / 60;
+            let hours = minutes / 60;
+            kprintln!(
+                "Uptime: {}h {}m {}s ({} ticks)",
+                hours,
+                minutes % 60,
+                seconds % 60,
+                ticks
+            );
+        }
+        Some("workqueue") => {
+            proc::workqueue::dump();
+        }
+        Some("watchdog") => match parts.get(1).copied() {
+            None => watchdog::dump(),
+            Some("start") => {
+                let timeout_s = match parts.get(2) {
+                    Some(s) => s.parse().ok(),
+                    None => Some(watchdog::DEFAULT_TIMEOUT_S),
+                };
+                match timeout_s.map(|t| watchdog::start(t, watchdog::Pinger::Kernel)) {
+                    Some(Ok(())) => watchdog::dump(),
+                    _ => kprintln!("watchdog: timeout must be 1-3600 seconds"),
+                }
+            }
+            Some("stop") => {
+                watchdog::stop();
+                watchdog::dump();
+            }
+            _ => kprintln!("Usage: watchdog [start [secs]|stop]"),
+        },
+        Some("timers") => {
+            time::dump_timers();
+        }
+        Some("interrupts") => {
+            irq::dump();
+        }
+        Some("softirqs") => {
+            softirq::dump();
+        }
+        Some("date") => {
+            use time::clock::{self, DateTime, NSEC_PER_SEC};
+            match parts.get(1).map(|s| s.parse::<u64>()) {
+                Some(Err(_)) => kprintln!("Usage: date [unix-seconds]"),
+                set => {
+                    if let Some(Ok(secs)) = set {
+                        clock::set_realtime(secs.saturating_mul(NSEC_PER_SEC));
+                    }
+                    let secs = clock::realtime_ns() / NSEC_PER_SEC;
+                    kprintln!("{} UTC ({}, source: {})", DateTime::from_unix(secs), secs, clock::source().as_str());
+                }
+            }
+        }
+        Some("hwinfo") => {
+            hwinfo::print();
+        }
+        Some("cmdline") => {
+            kprintln!("{}", boot::cmdline::raw());
+            for (key, value) in boot::cmdline::params() {
+                match value {
+                    Some(value) => kprintln!("  {:<16} = {}", key, value),
+                    None => kprintln!("  {}", key),
+                }
+            }
+        }
+        Some("boardinfo") => {
+            if let Some(board) = boards::current_board_info() {
+                kprintln!("Active board: {}", board.name);
+                kprintln!("  Compatible: {:?}", board.compatible);
+                kprintln!("  Timer freq: {} Hz", board.timer_freq);
+                kprintln!("  UART quirks: {:#x}", board.uart_quirks);
+                kprintln!("  SMP capable: {}", if board.smp_capable { "yes" } else { "no" });
+                if board.cpu_count > 0 {
+                    kprintln!("  CPU count: {}", board.cpu_count);
                 } else {
-                    kprintln!("Loaded modules:");
-                    for name in modules {
-                        kprintln!("  - {}", name);
+                    // DTB에서 CPU 개수 읽기
+                    let cpu_count = dtb::get().map(|dt| dt.count_cpus()).unwrap_or(1);
+                    kprintln!("  CPU count: {} (from DTB)", cpu_count);
+                }
+            } else {
+                kprintln!("No active board module");
+                kprintln!("Using compile-time defaults (BoardConfig)");
+            }
+        }
+        Some("lsboards") => {
+            kprintln!("Registered board modules:");
+            boards::registry::for_each_board(|name, is_active| {
+                let marker = if is_active { "*" } else { " " };
+                kprintln!("  {} {}", marker, name);
+            });
+            kprintln!("");
+            kprintln!("Total: {} board(s) (* = active)", boards::registry::board_count());
+        }
+        Some("threads") => {
+            proc::dump_threads();
+        }
+        Some("bench") => {
+            let mut iterations = bench::DEFAULT_ITERATIONS;
+            let mut names: Vec<&str> = Vec::new();
+            let mut args = parts[1..].iter();
+            let mut usage = false;
+            while let Some(&arg) = args.next() {
+                match arg {
+                    "-n" => match args.next().and_then(|n| n.parse::<usize>().ok()) {
+                        Some(n) if n > 0 && n <= bench::MAX_ITERATIONS => iterations = n,
+                        _ => usage = true,
+                    },
+                    name if bench::BENCHES.contains(&name) => names.push(name),
+                    _ => usage = true,
+                }
+            }
+            if usage {
+                kprintln!("Usage: bench [-n iterations] [{}]...", bench::BENCHES.join("|"));
+                kprintln!("  (1 <= iterations <= {}, default {})", bench::MAX_ITERATIONS, bench::DEFAULT_ITERATIONS);
+            } else {
+                if names.is_empty() {
+                    names.extend(bench::BENCHES);
+                }
+                kprintln!("[bench] {} iterations per benchmark", iterations);
+                bench::print_header();
+                for name in names {
+                    if let Err(e) = bench::run(name, iterations) {
+                        kprintln!("  {:<14} {}", name, e);
                     }
                 }
             }
-            Some("rmmod") => {
-                if parts.len() > 1 {
-                    match module::ModuleLoader::unload(parts[1]) {
-                        Ok(()) => kprintln!("Module '{}' unloaded", parts[1]),
-                        Err(e) => kprintln!("Failed to unload: {:?}", e),
+        }
+        Some("top") => match parts.get(1).map(|s| s.parse::<u64>()) {
+            None => proc::acct::top(1000),
+            Some(Ok(ms)) if ms > 0 => proc::acct::top(ms),
+            Some(_) => kprintln!("Usage: top [interval-ms]"),
+        },
+        Some("schedstat") => match parts.get(1).map(|s| *s) {
+            None => proc::schedstat::report(None),
+            Some("reset") => {
+                proc::schedstat::reset();
+                kprintln!("[schedstat] Histograms reset");
+            }
+            Some(cpu) => match cpu.parse::<u32>() {
+                Ok(cpu) if proc::percpu::online_mask().contains(cpu) => proc::schedstat::report(Some(cpu)),
+                Ok(cpu) => kprintln!("schedstat: CPU {} is not online", cpu),
+                Err(_) => kprintln!("Usage: schedstat [cpu|reset]"),
+            },
+        },
+        Some("kill") => {
+            let (sig, tid) = match (parts.get(1), parts.get(2)) {
+                (Some(s), Some(t)) if s.starts_with('-') => (proc::signal::parse(&s[1..]), t.parse::<proc::Tid>().ok()),
+                (Some(t), None) => (Some(proc::signal::SIGTERM), t.parse::<proc::Tid>().ok()),
+                _ => (None, None),
+            };
+            match (sig, tid) {
+                (Some(sig), Some(tid)) => match proc::signal::send(tid, sig) {
+                    Ok(()) => kprintln!("Sent SIG{} to thread {}", proc::signal::name(sig), tid),
+                    Err(e) => kprintln!("kill: {:?}", e),
+                },
+                _ => kprintln!("Usage: kill [-SIG] <tid>"),
+            }
+        }
+        Some("taskset") => {
+            let tid = parts.get(1).and_then(|t| t.parse::<proc::Tid>().ok());
+            // taskset처럼 마스크는 16진수 (0x 생략 가능)
+            let mask = parts.get(2).map(|m| {
+                let hex = m.strip_prefix("0x").unwrap_or(m);
+                u64::from_str_radix(hex, 16).ok().map(proc::percpu::CpuMask::from_bits)
+            });
+            match (tid, mask) {
+                (Some(tid), None) => match proc::affinity(tid) {
+                    Some(mask) => kprintln!("thread {} affinity mask: {:#x}", tid, mask.bits()),
+                    None => kprintln!("taskset: no thread {}", tid),
+                },
+                (Some(tid), Some(Some(mask))) => match proc::set_affinity(tid, mask) {
+                    Ok(()) => kprintln!("thread {} new affinity mask: {:#x}", tid, mask.bits()),
+                    Err(e) => kprintln!("taskset: {:?}", e),
+                },
+                _ => kprintln!("Usage: taskset <tid> [hex-mask]"),
+            }
+        }
+        #[cfg(target_arch = "aarch64")]
+        Some("psci") => drivers::psci::print_status(),
+        Some("cpuinfo") => {
+            let total = proc::percpu::total_count();
+            let online = proc::percpu::online_count();
+            let my_cpu = proc::percpu::get_cpu_id();
+            kprintln!("CPU info:");
+            kprintln!("  Total CPUs: {}", total);
+            kprintln!("  Online CPUs: {}", online);
+            kprintln!("  Current CPU: {}", my_cpu);
+            for cpu in 0..total {
+                let pc = proc::percpu::get(cpu);
+                let status = if pc.is_online() { "online" } else { "offline" };
+                let ticks = pc.tick_count.load(core::sync::atomic::Ordering::Relaxed);
+                let skipped = pc.ticks_skipped.load(core::sync::atomic::Ordering::Relaxed);
+                let migrated_in = pc.migrations_in.load(core::sync::atomic::Ordering::Relaxed);
+                let migrated_out = pc.migrations_out.load(core::sync::atomic::Ordering::Relaxed);
+                kprintln!(
+                    "  CPU {}: {} (ticks: {}, skipped idle: {}, migrations in/out: {}/{})",
+                    cpu, status, ticks, skipped, migrated_in, migrated_out
+                );
+            }
+        }
+        Some("cpu") => {
+            let cpu = parts.get(2).and_then(|s| s.parse::<u32>().ok());
+            let result = match (parts.get(1).copied(), cpu) {
+                (Some("offline"), Some(cpu)) => Some(proc::hotplug::cpu_down(cpu)),
+                (Some("online"), Some(cpu)) => Some(proc::hotplug::cpu_up(cpu)),
+                _ => None,
+            };
+            match result {
+                Some(Ok(())) => {}
+                Some(Err(e)) => kprintln!("cpu: {:?}", e),
+                None => kprintln!("Usage: cpu offline|online <n>"),
+            }
+        }
+        Some("spawn") => {
+            static THREAD_COUNT: core::sync::atomic::AtomicU64 = 
+                core::sync::atomic::AtomicU64::new(1);
+            let n = THREAD_COUNT.fetch_add(1, core::sync::atomic::Ordering::SeqCst);
+            let name = alloc::format!("test-{}", n);
+            let tid = proc::spawn(&name, test_thread_entry);
+            kprintln!("Spawned thread '{}' (tid={})", name, tid);
+        }
+        Some("usertest") => {
+            proc::user::test_user_mode();
+        }
+        Some("mqtest") => {
+            test_message_queue();
+        }
+        Some("vmatest") => {
+            test_demand_paging();
+        }
+        Some("vmas") => {
+            mm::vma::dump_vmas();
+        }
+        Some("selftest") => selftest::run(parts.get(1).map(|s| *s)),
+        Some("kexec") => {
+            if let Some(path) = parts.get(1) {
+                let result = kexec::load(path).and_then(|_| kexec::execute());
+                if let Err(e) = result {
+                    kexec::unload();
+                    kprintln!("kexec: {}", e);
+                }
+            } else {
+                kprintln!("Usage: kexec <path>");
+            }
+        }
+        Some("modtest") => {
+            test_module_loader();
+        }
+        Some("lsmod") => {
+            let modules = module::ModuleLoader::list();
+            if modules.is_empty() {
+                kprintln!("No modules loaded");
+            } else {
+                kprintln!("Loaded modules:");
+                for name in modules {
+                    let checksum = module::ModuleLoader::info(&name).and_then(|info| info.checksum);
+                    match checksum {
+                        Some(algo) => kprintln!("  - {} ({})", name, algo),
+                        None => kprintln!("  - {} (unverified)", name),
+                    }
+                    for r in module::ledger::resources_of(&name) {
+                        kprintln!("      {} {} ({})", r.kind.as_str(), r.id, r.label);
                     }
-                } else {
-                    kprintln!("Usage: rmmod <module_name>");
                 }
             }
-            Some("insmod") => {
-                if parts.len() > 1 {
-                    match module::ModuleLoader::load_from_path(parts[1]) {
-                        Ok(m) => kprintln!("Module '{}' loaded at 0x{:x}", m.info.name, m.base_addr),
-                        Err(e) => kprintln!("Failed to load module: {:?}", e),
+        }
+        Some("rmmod") => {
+            if parts.len() > 1 {
+                match module::ModuleLoader::unload(parts[1]) {
+                    Ok(()) => kprintln!("Module '{}' unloaded", parts[1]),
+                    Err(e) => kprintln!("Failed to unload: {:?}", e),
+                }
+            } else {
+                kprintln!("Usage: rmmod <module_name>");
+            }
+        }
+        Some("ksyms") => {
+            let filter = parts.get(1).copied().unwrap_or("");
+            let mut symbols: Vec<(alloc::string::String, usize)> = module::symbol::list_symbols()
+                .into_iter()
+                .filter(|(name, _)| name.contains(filter))
+                .collect();
+            symbols.sort_by(|a, b| a.0.cmp(&b.0));
+            for (name, addr) in &symbols {
+                kprintln!("{:#018x} {}", addr, name);
+            }
+            kprintln!("{} of {} symbols", symbols.len(), module::symbol::symbol_count());
+        }
+        Some("kprobe") => {
+            use trace::kprobe;
+            let id = parts.get(2).and_then(|s| s.parse::<usize>().ok());
+            let result = match (parts.get(1).copied(), id) {
+                (None, _) | (Some("list"), _) => {
+                    let probes = kprobe::list();
+                    if probes.is_empty() {
+                        kprintln!("No kprobes");
                     }
-                } else {
-                    kprintln!("Usage: insmod <path>");
-                    kprintln!("Example: insmod /modules/hello_module.ko");
+                    for p in probes {
+                        kprintln!(
+                            "  #{:<2} {:#014x} {:<3} hits={} missed={} {}{}",
+                            p.id,
+                            p.addr,
+                            if p.enabled { "on" } else { "off" },
+                            p.hits,
+                            p.missed,
+                            p.name,
+                            if p.log { " [log]" } else { "" }
+                        );
+                        if p.hits > 0 {
+                            kprintln!(
+                                "        last args: {:#x} {:#x} {:#x} {:#x}",
+                                p.last_args[0], p.last_args[1], p.last_args[2], p.last_args[3]
+                            );
+                        }
+                    }
+                    Ok(())
+                }
+                (Some("add"), _) if parts.len() > 2 => {
+                    kprobe::register(parts[2], None, parts.get(3) == Some(&"log")).map(|_| ())
                 }
+                (Some("del"), Some(id)) => kprobe::unregister(id),
+                (Some("on"), Some(id)) => kprobe::enable(id),
+                (Some("off"), Some(id)) => kprobe::disable(id),
+                _ => {
+                    kprintln!("Usage: kprobe [list]");
+                    kprintln!("       kprobe add <symbol|0xaddr>[+off] [log]");
+                    kprintln!("       kprobe del|on|off <id>");
+                    Ok(())
+                }
+            };
+            if let Err(e) = result {
+                kprintln!("kprobe: {:?}", e);
             }
-            Some("ls") => {
-                let path = if parts.len() > 1 { parts[1] } else { "/" };
-                match fs::lookup_path(path) {
-                    Ok(node) => {
-                        match node.readdir() {
-                            Ok(entries) => {
-                                kprintln!("Directory: {}", path);
-                                for entry in entries {
-                                    let type_char = match entry.node_type {
-                                        fs::VNodeType::File => '-',
-                                        fs::VNodeType::Directory => 'd',
-                                        fs::VNodeType::Symlink => 'l',
-                                        fs::VNodeType::CharDevice => 'c',
-                                        fs::VNodeType::BlockDevice => 'b',
-                                        fs::VNodeType::Fifo => 'p',
-                                        fs::VNodeType::Socket => 's',
-                                    };
-                                    kprintln!("  {} {}", type_char, entry.name);
-                                }
+        }
+        Some("trace") => {
+            use trace::event;
+            match parts.get(1).copied() {
+                None | Some("status") => {
+                    let st = event::status();
+                    kprintln!(
+                        "Tracing {} ({} events/CPU), {} recorded, {} overwritten",
+                        if st.enabled { "on" } else { "off" },
+                        st.capacity,
+                        st.recorded,
+                        st.overwritten
+                    );
+                }
+                Some("on") => {
+                    let events = parts
+                        .get(2)
+                        .and_then(|s| s.parse::<usize>().ok())
+                        .unwrap_or(event::DEFAULT_EVENTS);
+                    if let Err(e) = event::start(events) {
+                        kprintln!("trace: {} (1-{})", e, event::MAX_EVENTS);
+                    }
+                }
+                Some("off") => event::stop(),
+                Some("clear") => event::clear(),
+                Some("dump") => {
+                    let events = event::snapshot();
+                    let skip = parts
+                        .get(2)
+                        .and_then(|s| s.parse::<usize>().ok())
+                        .map_or(0, |n| events.len().saturating_sub(n));
+                    for e in &events[skip..] {
+                        let mut line = alloc::format!(
+                            "[{:>5}.{:06}] CPU{} {}:",
+                            e.ts_us / 1_000_000,
+                            e.ts_us % 1_000_000,
+                            e.cpu,
+                            e.name
+                        );
+                        for (field, value) in e.fields.iter().zip(e.args.iter()) {
+                            line.push_str(&alloc::format!(" {}={}", field, value));
+                        }
+                        kprintln!("{}", line);
+                    }
+                    kprintln!("{} events", events.len() - skip);
+                }
+                _ => {
+                    kprintln!("Usage: trace [status]");
+                    kprintln!("       trace on [events/CPU]");
+                    kprintln!("       trace off|clear");
+                    kprintln!("       trace dump [last-n]");
+                }
+            }
+        }
+        Some("drivers") => match parts.get(1) {
+            None => drivers::list_drivers(),
+            Some(&"probe") => {
+                drivers::probe_all();
+            }
+            Some(_) => kprintln!("Usage: drivers [probe]"),
+        },
+        Some("insmod") => {
+            if parts.len() > 1 {
+                match module::ModuleLoader::load_from_path(parts[1]) {
+                    Ok(m) => kprintln!("Module '{}' loaded at 0x{:x}", m.info.name, m.base_addr),
+                    Err(e) => kprintln!("Failed to load module: {:?}", e),
+                }
+            } else {
+                kprintln!("Usage: insmod <path>");
+                kprintln!("Example: insmod /modules/hello_module.ko");
+            }
+        }
+        Some("ls") => {
+            let path = if parts.len() > 1 { parts[1] } else { "/" };
+            match fs::lookup_path(path) {
+                Ok(node) => {
+                    match node.readdir() {
+                        Ok(entries) => {
+                            kprintln!("Directory: {}", path);
+                            for entry in entries {
+                                let type_char = match entry.node_type {
+                                    fs::VNodeType::File => '-',
+                                    fs::VNodeType::Directory => 'd',
+                                    fs::VNodeType::Symlink => 'l',
+                                    fs::VNodeType::CharDevice => 'c',
+                                    fs::VNodeType::BlockDevice => 'b',
+                                    fs::VNodeType::Fifo => 'p',
+                                    fs::VNodeType::Socket => 's',
+                                };
+                                kprintln!("  {} {}", type_char, entry.name);
                             }
-                            Err(e) => kprintln!("Failed to read directory: {:?}", e),
                         }
+                        Err(e) => kprintln!("Failed to read directory: {:?}", e),
                     }
-                    Err(e) => kprintln!("Path not found: {:?}", e),
-                }
-            }
-            Some("cat") => {
-                if parts.len() > 1 {
-                    match fs::lookup_path(parts[1]) {
-                        Ok(node) => {
-                            let stat = node.stat().unwrap_or_default();
-                            let size = core::cmp::min(stat.size as usize, 4096);
-                            let mut buffer = alloc::vec![0u8; size];
-                            match node.read(0, &mut buffer) {
-                                Ok(n) => {
-                                    if let Ok(s) = core::str::from_utf8(&buffer[..n]) {
-                                        console::puts(s);
-                                        if !s.ends_with('\n') {
-                                            console::puts("\n");
-                                        }
-                                    } else {
-                                        kprintln!("<binary data: {} bytes>", n);
+                }
+                Err(e) => kprintln!("Path not found: {:?}", e),
+            }
+        }
+        Some("run") | Some("sh") => {
+            match parts.get(1) {
+                Some(path) => return run_script(path),
+                None => kprintln!("Usage: run <path>"),
+            }
+        }
+        Some("cat") => {
+            if parts.len() > 1 {
+                match fs::lookup_path(parts[1]) {
+                    Ok(node) => {
+                        let stat = node.stat().unwrap_or_default();
+                        let size = core::cmp::min(stat.size as usize, 4096);
+                        let mut buffer = alloc::vec![0u8; size];
+                        match node.read(0, &mut buffer) {
+                            Ok(n) => {
+                                if let Ok(s) = core::str::from_utf8(&buffer[..n]) {
+                                    console::puts(s);
+                                    if !s.ends_with('\n') {
+                                        console::puts("\n");
                                     }
+                                } else {
+                                    kprintln!("<binary data: {} bytes>", n);
                                 }
-                                Err(e) => kprintln!("Failed to read file: {:?}", e),
                             }
+                            Err(e) => kprintln!("Failed to read file: {:?}", e),
                         }
-                        Err(e) => kprintln!("File not found: {:?}", e),
                     }
-                } else {
-                    kprintln!("Usage: cat <path>");
-                }
-            }
-            Some("echo") => {
-                if parts.len() > 1 {
-                    let args = &parts[1..].join(" ");
-
-                    // 리다이렉션 처리: echo text > file 또는 echo text >> file
-                    if let Some(append_pos) = args.find(">>") {
-                        // Append 모드
-                        let text = args[..append_pos].trim();
-                        let path = args[append_pos + 2..].trim();
-                        if !path.is_empty() {
-                            match echo_to_file(path, text, true) {
-                                Ok(_) => {}
-                                Err(e) => kprintln!("echo: {}: {}", path, e),
-                            }
-                        } else {
-                            kprintln!("echo: missing file path after '>>'");
+                    Err(e) => kprintln!("File not found: {:?}", e),
+                }
+            } else {
+                kprintln!("Usage: cat <path>");
+            }
+        }
+        Some("echo") => {
+            if parts.len() > 1 {
+                let args = &parts[1..].join(" ");
+
+                // 리다이렉션 처리: echo text > file 또는 echo text >> file
+                if let Some(append_pos) = args.find(">>") {
+                    // Append 모드
+                    let text = args[..append_pos].trim();
+                    let path = args[append_pos + 2..].trim();
+                    if !path.is_empty() {
+                        match echo_to_file(path, text, true) {
+                            Ok(_) => {}
+                            Err(e) => kprintln!("echo: {}: {}", path, e),
                         }
-                    } else if let Some(redir_pos) = args.find('>') {
-                        // Overwrite 모드
-                        let text = args[..redir_pos].trim();
-                        let path = args[redir_pos + 1..].trim();
-                        if !path.is_empty() {
-                            match echo_to_file(path, text, false) {
-                                Ok(_) => {}
-                                Err(e) => kprintln!("echo: {}: {}", path, e),
-                            }
-                        } else {
-                            kprintln!("echo: missing file path after '>'");
+                    } else {
+                        kprintln!("echo: missing file path after '>>'");
+                    }
+                } else if let Some(redir_pos) = args.find('>') {
+                    // Overwrite 모드
+                    let text = args[..redir_pos].trim();
+                    let path = args[redir_pos + 1..].trim();
+                    if !path.is_empty() {
+                        match echo_to_file(path, text, false) {
+                            Ok(_) => {}
+                            Err(e) => kprintln!("echo: {}: {}", path, e),
                         }
                     } else {
-                        // 일반 echo (화면 출력)
-                        kprintln!("{}", args);
+                        kprintln!("echo: missing file path after '>'");
                     }
+                } else {
+                    // 일반 echo (화면 출력)
+                    kprintln!("{}", args);
                 }
             }
-            Some("blkinfo") => {
-                let devices = block::list_devices();
-                if devices.is_empty() {
-                    kprintln!("No block devices found");
-                } else {
-                    kprintln!("Block devices:");
-                    for name in &devices {
-                        if let Some(info) = block::device_info(name) {
-                            kprintln!("  {}: {} bytes ({} blocks of {} bytes){}",
-                                info.name,
-                                info.capacity,
-                                info.block_count,
-                                info.block_size,
-                                if info.read_only { " [RO]" } else { "" }
+        }
+        Some("blkinfo") => {
+            let devices = block::list_devices();
+            if devices.is_empty() {
+                kprintln!("No block devices found");
+            } else {
+                kprintln!("Block devices:");
+                for name in &devices {
+                    if let Some(info) = block::device_info(name) {
+                        kprintln!("  {}: {} bytes ({} blocks of {} bytes){}",
+                            info.name,
+                            info.capacity,
+                            info.block_count,
+                            info.block_size,
+                            if info.read_only { " [RO]" } else { "" }
+                        );
+                        if let Some(stats) = info.stats {
+                            kprintln!("    io: dispatched {}, completed {}, merged {}",
+                                stats.dispatched,
+                                stats.completed,
+                                stats.merged
                             );
                         }
                     }
                 }
             }
-            Some("blktest") => {
-                if let Some(device) = block::get_device("vda") {
-                    kprintln!("Testing VirtIO block device 'vda'...");
+        }
+        Some("input") => {
+            let devices = input::list_devices();
+            if devices.is_empty() {
+                kprintln!("No input devices found");
+            } else {
+                kprintln!("Input devices:");
+                for dev in &devices {
+                    kprintln!("  event{}: {} (buffered {}, total {}, readers {})",
+                        dev.id,
+                        dev.name,
+                        dev.buffered,
+                        dev.total,
+                        dev.readers
+                    );
+                }
+            }
+        }
+        Some("blktest") => {
+            if let Some(device) = block::get_device("vda") {
+                kprintln!("Testing VirtIO block device 'vda'...");
+
+                // Read first block
+                let block_size = device.block_size();
+                let mut buf = alloc::vec![0u8; block_size];
+
+                kprintln!("  Reading block 0...");
+                match device.read_block(0, &mut buf) {
+                    Ok(()) => {
+                        kprintln!("  Read successful! First 16 bytes:");
+                        kprintln!("  {:02x?}", &buf[..16.min(block_size)]);
+
+                        // Check if it's a FAT32 disk
+                        if buf.len() >= 3 && (buf[0] == 0xEB || buf[0] == 0xE9) {
+                            kprintln!("  Looks like a FAT boot sector!");
+                        }
+                    }
+                    Err(e) => kprintln!("  Read failed: {:?}", e),
+                }
 
-                    // Read first block
-                    let block_size = device.block_size();
-                    let mut buf = alloc::vec![0u8; block_size];
+                // Write test (write to a high block number to avoid damaging FAT)
+                if !device.is_read_only() {
+                    let test_block = 1000;
+                    let test_data = b"KERNERS_BLK_TEST";
+                    let mut write_buf = alloc::vec![0u8; block_size];
+                    write_buf[..test_data.len()].copy_from_slice(test_data);
 
-                    kprintln!("  Reading block 0...");
-                    match device.read_block(0, &mut buf) {
+                    kprintln!("  Writing test data to block {}...", test_block);
+                    match device.write_block(test_block, &write_buf) {
                         Ok(()) => {
-                            kprintln!("  Read successful! First 16 bytes:");
-                            kprintln!("  {:02x?}", &buf[..16.min(block_size)]);
+                            kprintln!("  Write successful!");
 
-                            // Check if it's a FAT32 disk
-                            if buf.len() >= 3 && (buf[0] == 0xEB || buf[0] == 0xE9) {
-                                kprintln!("  Looks like a FAT boot sector!");
-                            }
-                        }
-                        Err(e) => kprintln!("  Read failed: {:?}", e),
-                    }
-
-                    // Write test (write to a high block number to avoid damaging FAT)
-                    if !device.is_read_only() {
-                        let test_block = 1000;
-                        let test_data = b"KERNERS_BLK_TEST";
-                        let mut write_buf = alloc::vec![0u8; block_size];
-                        write_buf[..test_data.len()].copy_from_slice(test_data);
-
-                        kprintln!("  Writing test data to block {}...", test_block);
-                        match device.write_block(test_block, &write_buf) {
-                            Ok(()) => {
-                                kprintln!("  Write successful!");
-
-                                // Read back
-                                let mut read_buf = alloc::vec![0u8; block_size];
-                                match device.read_block(test_block, &mut read_buf) {
-                                    Ok(()) => {
-                                        if &read_buf[..test_data.len()] == test_data {
-                                            kprintln!("  Verify successful: data matches!");
-                                        } else {
-                                            kprintln!("  Verify failed: data mismatch");
-                                        }
+                            // Read back
+                            let mut read_buf = alloc::vec![0u8; block_size];
+                            match device.read_block(test_block, &mut read_buf) {
+                                Ok(()) => {
+                                    if &read_buf[..test_data.len()] == test_data {
+                                        kprintln!("  Verify successful: data matches!");
+                                    } else {
+                                        kprintln!("  Verify failed: data mismatch");
                                     }
-                                    Err(e) => kprintln!("  Read-back failed: {:?}", e),
                                 }
+                                Err(e) => kprintln!("  Read-back failed: {:?}", e),
                             }
-                            Err(e) => kprintln!("  Write failed: {:?}", e),
                         }
-                    } else {
-                        kprintln!("  Device is read-only, skipping write test");
+                        Err(e) => kprintln!("  Write failed: {:?}", e),
                     }
                 } else {
-                    kprintln!("VirtIO block device 'vda' not found");
+                    kprintln!("  Device is read-only, skipping write test");
                 }
+            } else {
+                kprintln!("VirtIO block device 'vda' not found");
             }
-            Some("mount") => {
-                // FAT32 파일시스템 마운트
-                if let Some(device) = block::get_device("vda") {
-                    kprintln!("Mounting FAT32 from /dev/vda...");
+        }
+        Some("mount") if parts.get(1) == Some(&"-t") => {
+            // 9P 호스트 디렉토리 마운트: mount -t 9p <tag> [path] (기본 /host)
+            if let (Some(&"9p"), Some(tag)) = (parts.get(2), parts.get(3)) {
+                let mount_path = parts.get(4).copied().unwrap_or("/host");
+
+                let dir = fs::path::dirname(mount_path);
+                if let Ok(parent) = fs::lookup_path(dir) {
+                    let _ = parent.create(fs::path::basename(mount_path), fs::VNodeType::Directory, fs::FileMode::default_dir());
+                }
 
-                    // /mnt 디렉토리 생성
-                    if let Ok(root) = fs::lookup_path("/") {
-                        let _ = root.create("mnt", fs::VNodeType::Directory, fs::FileMode::default_dir());
-                    }
+                match fs::p9::mount_9p(tag) {
+                    Ok(p9_fs) => match fs::mount(mount_path, p9_fs) {
+                        Ok(()) => kprintln!("9p '{}' mounted at {}", tag, mount_path),
+                        Err(e) => kprintln!("Mount failed: {:?}", e),
+                    },
+                    Err(fs::VfsError::NotFound) => kprintln!("9p tag '{}' not found", tag),
+                    Err(e) => kprintln!("9p mount failed: {:?}", e),
+                }
+            } else {
+                kprintln!("Usage: mount -t 9p <tag> [path]");
+                let tags = virtio::p9::tags();
+                if !tags.is_empty() {
+                    kprintln!("  available tags: {}", tags.join(", "));
+                }
+            }
+        }
+        Some("mount") => {
+            // FAT32 파일시스템 마운트: mount [dev] [path] (기본 vda → /mnt)
+            let dev_name = parts.get(1).copied().unwrap_or("vda");
+            let mount_path = parts.get(2).copied().unwrap_or("/mnt");
+            if let Some(device) = block::get_device(dev_name) {
+                kprintln!("Mounting FAT32 from /dev/{}...", dev_name);
+
+                // 마운트 포인트 디렉토리 생성 (없어도 마운트는 되지만 ls에서 보이도록)
+                let dir = fs::path::dirname(mount_path);
+                if let Ok(parent) = fs::lookup_path(dir) {
+                    let _ = parent.create(fs::path::basename(mount_path), fs::VNodeType::Directory, fs::FileMode::default_dir());
+                }
 
-                    match fs::fat32::mount_fat32(device) {
-                        Ok(fat32_fs) => {
-                            match fs::mount("/mnt", fat32_fs) {
-                                Ok(()) => kprintln!("FAT32 mounted at /mnt"),
-                                Err(e) => kprintln!("Mount failed: {:?}", e),
-                            }
+                match fs::fat32::mount_fat32(device) {
+                    Ok(fat32_fs) => {
+                        match fs::mount(mount_path, fat32_fs) {
+                            Ok(()) => kprintln!("FAT32 mounted at {}", mount_path),
+                            Err(e) => kprintln!("Mount failed: {:?}", e),
                         }
-                        Err(e) => kprintln!("FAT32 mount failed: {:?}", e),
                     }
-                } else {
-                    kprintln!("Block device 'vda' not found");
+                    Err(e) => kprintln!("FAT32 mount failed: {:?}", e),
+                }
+            } else {
+                kprintln!("Block device '{}' not found", dev_name);
+            }
+        }
+        Some("ramfs") => {
+            // Usage: ramfs save <target> [subtree] / ramfs load <target> [dest]
+            match (parts.get(1).map(|s| *s), parts.get(2)) {
+                (Some("save"), Some(target)) => {
+                    let subtree = parts.get(3).map(|s| *s).unwrap_or("/");
+                    match fs::ramfs::archive::save(target, subtree) {
+                        Ok(stats) => kprintln!(
+                            "Saved {} dirs, {} files, {} symlinks to {} ({} bytes)",
+                            stats.dirs, stats.files, stats.symlinks, target, stats.archive_size
+                        ),
+                        Err(e) => kprintln!("ramfs save: {}", e),
+                    }
+                }
+                (Some("load"), Some(target)) => {
+                    let dest = parts.get(3).map(|s| *s).unwrap_or("/");
+                    match fs::ramfs::archive::load(target, dest) {
+                        Ok(stats) => kprintln!(
+                            "Restored {} dirs, {} files, {} symlinks ({} bytes) to {}",
+                            stats.dirs, stats.files, stats.symlinks, stats.data_bytes, dest
+                        ),
+                        Err(e) => kprintln!("ramfs load: {}", e),
+                    }
+                }
+                _ => {
+                    kprintln!("Usage: ramfs save <target> [subtree]");
+                    kprintln!("       ramfs load <target> [dest]");
+                    kprintln!("  target: <device>:<path> (e.g. /dev/vda:/backup.img) or VFS path");
                 }
             }
-            Some("mounts") => {
-                let mounts = fs::list_mounts();
-                if mounts.is_empty() {
-                    kprintln!("No filesystems mounted");
+        }
+        Some("mounts") => {
+            let mounts = fs::list_mounts();
+            if mounts.is_empty() {
+                kprintln!("No filesystems mounted");
+            } else {
+                kprintln!("Mount points:");
+                for (path, fs_type) in mounts {
+                    kprintln!("  {} -> {}", path, fs_type);
+                }
+            }
+        }
+        Some("df") => {
+            kprintln!("{:<8} {:>10} {:>10} {:>10} {:>5}  MOUNTED ON", "TYPE", "SIZE(KB)", "USED(KB)", "AVAIL(KB)", "USE%");
+            for (path, stats) in fs::list_mount_stats() {
+                match stats {
+                    // 크기 제한 없는 파일시스템(ramfs)과 장치 파일시스템은 크기 없음
+                    Ok(s) if s.total_blocks > 0 => {
+                        let kb = |blocks: u64| blocks * s.block_size / 1024;
+                        let used = s.total_blocks.saturating_sub(s.free_blocks);
+                        kprintln!(
+                            "{:<8} {:>10} {:>10} {:>10} {:>4}%  {}",
+                            s.fs_type,
+                            kb(s.total_blocks),
+                            kb(used),
+                            kb(s.free_blocks),
+                            used * 100 / s.total_blocks,
+                            path
+                        );
+                    }
+                    Ok(s) => kprintln!("{:<8} {:>10} {:>10} {:>10} {:>5}  {}", s.fs_type, "-", "-", "-", "-", path),
+                    Err(e) => kprintln!("{:<8} {:>10} {:>10} {:>10} {:>5}  {} ({})", "?", "-", "-", "-", "-", path, e),
+                }
+            }
+        }
+        Some("fds") => {
+            // 스레드를 지정하면 그 스레드의 테이블 (없으면 커널 FD 테이블을 쓰는 중)
+            let tid = parts.get(1).map(|s| s.parse::<proc::Tid>());
+            let table = match tid {
+                Some(Ok(tid)) => proc::files(tid).map(Ok).unwrap_or_else(fs::fd::kernel_fd_table),
+                Some(Err(_)) => {
+                    kprintln!("Usage: fds [tid]");
+                    return true;
+                }
+                None => fs::fd::kernel_fd_table(),
+            };
+            match table {
+                Ok(table) => {
+                    kprintln!("{:>4}  {:<4}  {:<7}  PATH", "FD", "MODE", "CLOEXEC");
+                    for (fd, file, cloexec) in table.list() {
+                        let mode = match (file.flags.is_readable(), file.flags.is_writable()) {
+                            (true, true) => "rw",
+                            (true, false) => "r",
+                            _ => "w",
+                        };
+                        let path = if file.path.is_empty() {
+                            alloc::format!("({:?})", file.vnode.node_type())
+                        } else {
+                            file.path.clone()
+                        };
+                        kprintln!("{:>4}  {:<4}  {:<7}  {}", fd, mode, if cloexec { "yes" } else { "-" }, path);
+                    }
+                }
+                Err(e) => kprintln!("fds: {}", e),
+            }
+        }
+        Some("locks") => {
+            let locks = fs::flock::list();
+            if locks.is_empty() {
+                kprintln!("No file locks held");
+            } else {
+                kprintln!("{:>6}  {:<9}  PATH", "FILE", "TYPE");
+                for l in locks {
+                    let path = if l.path.is_empty() { "(no path)" } else { l.path.as_str() };
+                    kprintln!("{:>6}  {:<9}  {}", l.owner, l.kind.as_str(), path);
+                }
+            }
+        }
+        Some("console") => {
+            console::command(&parts[1..]);
+        }
+        Some("stty") => {
+            use drivers::tty::{self, Termios, ECHO, ECHOE, ICANON, ICRNL};
+
+            let console_tty = tty::console();
+            let mut termios = console_tty.termios();
+            for arg in &parts[1..] {
+                let (flag, on) = match arg.strip_prefix('-') {
+                    Some(name) => (name, false),
+                    None => (*arg, true),
+                };
+                let (bits, lflag) = match flag {
+                    "sane" if on => {
+                        termios = Termios::cooked();
+                        continue;
+                    }
+                    "raw" => {
+                        if on {
+                            termios.make_raw();
+                        } else {
+                            termios = Termios::cooked();
+                        }
+                        continue;
+                    }
+                    "icanon" => (ICANON, true),
+                    "echo" => (ECHO | ECHOE, true),
+                    "icrnl" => (ICRNL, false),
+                    _ => {
+                        kprintln!("stty: unknown mode '{}'", arg);
+                        return true;
+                    }
+                };
+                let field = if lflag { &mut termios.c_lflag } else { &mut termios.c_iflag };
+                if on {
+                    *field |= bits;
                 } else {
-                    kprintln!("Mount points:");
-                    for (path, fs_type) in mounts {
-                        kprintln!("  {} -> {}", path, fs_type);
+                    *field &= !bits;
+                }
+            }
+            if parts.len() > 1 {
+                console_tty.set_termios(termios, false);
+            }
+            let mode = |name: &str, set: bool| alloc::format!("{}{}", if set { "" } else { "-" }, name);
+            kprintln!(
+                "{} {} {} min={}",
+                mode("icanon", termios.c_lflag & ICANON != 0),
+                mode("echo", termios.c_lflag & ECHO != 0),
+                mode("icrnl", termios.c_iflag & ICRNL != 0),
+                termios.c_cc[tty::VMIN]
+            );
+        }
+        Some("mkfifo") => {
+            match parts.get(1) {
+                Some(path) => {
+                    if let Err(e) = fs::pipe::mkfifo(path, fs::FileMode::new(0o644)) {
+                        kprintln!("mkfifo: {}: {}", path, e);
                     }
                 }
+                None => kprintln!("Usage: mkfifo <path>"),
             }
-            Some("write") => {
-                // Usage: write <path> <content>
-                if parts.len() > 2 {
-                    let path = parts[1];
-                    let content = parts[2..].join(" ");
-
-                    // 파일이 없으면 생성
-                    let node = match fs::lookup_path(path) {
-                        Ok(n) => n,
-                        Err(_) => {
-                            // 부모 디렉토리 찾기
-                            let parent_path = if let Some(pos) = path.rfind('/') {
-                                if pos == 0 { "/" } else { &path[..pos] }
-                            } else {
-                                "/"
-                            };
-                            let file_name = path.rsplit('/').next().unwrap_or(path);
-
-                            match fs::lookup_path(parent_path) {
-                                Ok(parent) => {
-                                    match parent.create(file_name, fs::VNodeType::File, fs::FileMode::default_file()) {
-                                        Ok(n) => n,
-                                        Err(e) => {
-                                            kprintln!("Failed to create file: {:?}", e);
-                                            continue;
-                                        }
+        }
+        Some("mv") => {
+            // Usage: mv <src> <dst> (dst가 디렉토리면 그 안으로)
+            match (parts.get(1), parts.get(2)) {
+                (Some(src), Some(dst)) => {
+                    let dst = match fs::lookup_path(dst) {
+                        Ok(node) if node.node_type() == fs::VNodeType::Directory => {
+                            let dir = fs::path::normalize(dst).unwrap_or_else(|_| alloc::string::String::from(*dst));
+                            fs::path::join(&dir, fs::path::basename(src.trim_end_matches('/')))
+                        }
+                        _ => alloc::string::String::from(*dst),
+                    };
+                    if let Err(e) = move_path(src, &dst) {
+                        kprintln!("mv: {} -> {}: {}", src, dst, e);
+                    }
+                }
+                _ => kprintln!("Usage: mv <src> <dst>"),
+            }
+        }
+        Some("ln") => {
+            match (parts.get(1), parts.get(2)) {
+                (Some(target), Some(link)) => {
+                    if let Err(e) = fs::link(target, link) {
+                        kprintln!("ln: {} -> {}: {}", link, target, e);
+                    }
+                }
+                _ => kprintln!("Usage: ln <target> <link>"),
+            }
+        }
+        Some("write") => {
+            // Usage: write <path> <content>
+            if parts.len() > 2 {
+                let path = parts[1];
+                let content = parts[2..].join(" ");
+
+                // 파일이 없으면 생성
+                let node = match fs::lookup_path(path) {
+                    Ok(n) => n,
+                    Err(_) => {
+                        // 부모 디렉토리 찾기
+                        let parent_path = if let Some(pos) = path.rfind('/') {
+                            if pos == 0 { "/" } else { &path[..pos] }
+                        } else {
+                            "/"
+                        };
+                        let file_name = path.rsplit('/').next().unwrap_or(path);
+
+                        match fs::lookup_path(parent_path) {
+                            Ok(parent) => {
+                                match parent.create(file_name, fs::VNodeType::File, fs::FileMode::default_file()) {
+                                    Ok(n) => n,
+                                    Err(e) => {
+                                        kprintln!("Failed to create file: {:?}", e);
+                                        return true;
                                     }
                                 }
-                                Err(e) => {
-                                    kprintln!("Parent directory not found: {:?}", e);
-                                    continue;
-                                }
+                            }
+                            Err(e) => {
+                                kprintln!("Parent directory not found: {:?}", e);
+                                return true;
                             }
                         }
-                    };
-
-                    // 파일에 쓰기
-                    match node.write(0, content.as_bytes()) {
-                        Ok(n) => kprintln!("Wrote {} bytes to {}", n, path),
-                        Err(e) => kprintln!("Write failed: {:?}", e),
                     }
-                } else {
-                    kprintln!("Usage: write <path> <content>");
-                    kprintln!("Example: write /test.txt Hello World");
+                };
+
+                // 파일에 쓰기
+                match node.write(0, content.as_bytes()) {
+                    Ok(n) => kprintln!("Wrote {} bytes to {}", n, path),
+                    Err(e) => kprintln!("Write failed: {:?}", e),
                 }
+            } else {
+                kprintln!("Usage: write <path> <content>");
+                kprintln!("Example: write /test.txt Hello World");
             }
-            Some("dmesg") => {
-                log::dump_logs();
-            }
-            Some("loglevel") => {
-                if parts.len() == 1 {
-                    let level = log::get_log_level();
-                    kprintln!("Current log level: {} ({})", level as u8, level);
-                } else if let Some(level) = log::LogLevel::from_str(parts[1]) {
-                    log::set_log_level(level);
-                    kprintln!("Log level set to: {} ({})", level as u8, level);
+        }
+        Some("dmesg") => {
+            log::dump_logs();
+        }
+        Some("reboot") => power::shutdown(power::PowerAction::Restart),
+        Some("poweroff") => power::shutdown(power::PowerAction::PowerOff),
+        Some("panic") => {
+            let msg = cmd.trim_start().strip_prefix("panic").unwrap_or("").trim();
+            panic!("{}", if msg.is_empty() { "triggered from shell" } else { msg });
+        }
+        Some("loglevel") => {
+            if parts.len() == 1 {
+                let level = log::get_log_level();
+                kprintln!("Current log level: {} ({})", level as u8, level);
+                log::for_each_tag_level(|tag, level| {
+                    kprintln!("  {}={} ({})", tag, level as u8, level);
+                });
+            } else if let Some((tag, value)) = parts[1].split_once('=') {
+                // 태그별 오버라이드: loglevel vfs=debug / loglevel vfs=default
+                if value.is_empty() || value == "default" {
+                    if log::clear_tag_level(tag) {
+                        kprintln!("Log level for '{}' reset to global", tag);
+                    } else {
+                        kprintln!("No log level override for '{}'", tag);
+                    }
+                } else if let Some(level) = log::LogLevel::from_str(value) {
+                    match log::set_tag_level(tag, level) {
+                        Ok(()) => kprintln!("Log level for '{}' set to: {} ({})", tag, level as u8, level),
+                        Err(e) => kprintln!("loglevel: {}", e),
+                    }
                 } else {
                     kprintln!("Invalid log level. Use: 0-4 or ERROR/WARN/INFO/DEBUG/TRACE");
                 }
+            } else if let Some(level) = log::LogLevel::from_str(parts[1]) {
+                log::set_log_level(level);
+                kprintln!("Log level set to: {} ({})", level as u8, level);
+            } else {
+                kprintln!("Invalid log level. Use: 0-4 or ERROR/WARN/INFO/DEBUG/TRACE");
             }
-            Some(unknown) => {
-                kprintln!("Unknown command: {}", unknown);
-                kprintln!("Type 'help' for available commands.");
+        }
+        Some("logsink") => {
+            match (parts.get(1).map(|s| *s), parts.get(2)) {
+                (None, _) => {
+                    let sinks = log::sink::list();
+                    if sinks.is_empty() {
+                        kprintln!("No log sinks");
+                    }
+                    for s in &sinks {
+                        kprintln!("  {} [{}] next_seq={} written={} lost={} errors={}",
+                            s.name,
+                            if s.ready { "active" } else { "waiting" },
+                            s.next_seq,
+                            s.written,
+                            s.lost,
+                            s.errors
+                        );
+                    }
+                }
+                (Some("add"), Some(path)) => {
+                    let result = log::sink::FileSink::new(path)
+                        .and_then(|sink| log::sink::register(alloc::sync::Arc::new(sink)));
+                    match result {
+                        Ok(()) => kprintln!("Mirroring kernel log to {}", path),
+                        Err(e) => kprintln!("logsink: {}", e),
+                    }
+                }
+                (Some("rm"), Some(path)) => {
+                    let name = fs::path::normalize(path).unwrap_or_else(|_| alloc::string::String::from(*path));
+                    if !log::sink::unregister(&name) {
+                        kprintln!("logsink: no sink '{}'", path);
+                    }
+                }
+                (Some("flush"), _) => log::sink::flush_all(),
+                _ => kprintln!("Usage: logsink [add <path> | rm <path> | flush]"),
             }
-            None => {}
         }
+        Some(unknown) => {
+            kprintln!("Unknown command: {}", unknown);
+            kprintln!("Type 'help' for available commands.");
+            return false;
+        }
+        None => {}
     }
+    true
 }
 
+
 /// 테스트용 스레드 엔트리 함수
 fn test_thread_entry() -> ! {
     let tid = proc::current_tid().unwrap_or(0);
@@ -979,6 +2027,113 @@ fn test_message_queue() {
     kprintln!("\n=== All Message Queue Tests Passed! ===\n");
 }
 
+/// Demand paging 테스트
+fn test_demand_paging() {
+    use mm::vma::{self, VmaFlags};
+    use mm::page::PAGE_SIZE;
+
+    kprintln!("\n=== Demand Paging Tests ===\n");
+
+    // M-mode 커널은 satp 변환을 거치지 않으므로 커널 스레드에서 VMA 접근 불가
+    if cfg!(all(target_arch = "riscv64", not(feature = "riscv_smode"))) {
+        kprintln!("  Skipped tests 1-2: M-mode kernel accesses bypass Sv39 translation");
+        kprintln!("  (page faults are delivered only for U-mode accesses)");
+        test_user_fault_ignored();
+        kprintln!("\n=== Demand Paging Tests Done ===\n");
+        return;
+    }
+
+    // 테스트 1: 첫 접근 시 프레임 할당
+    kprintln!("[Test 1] Lazy allocation on first touch");
+    {
+        const PAGES: usize = 8;
+        let base = match vma::map_anonymous(PAGES * PAGE_SIZE, VmaFlags::RW, "vmatest") {
+            Ok(addr) => addr,
+            Err(e) => {
+                kprintln!("  map_anonymous failed: {}", e);
+                return;
+            }
+        };
+        let faults_before = vma::faults_handled();
+        kprintln!("  Reserved {} pages at {:#x}", PAGES, base);
+
+        for i in 0..PAGES {
+            let ptr = (base + i * PAGE_SIZE) as *mut u64;
+            unsafe {
+                // 새 페이지는 0으로 채워져 있어야 함
+                assert_eq!(core::ptr::read_volatile(ptr), 0);
+                core::ptr::write_volatile(ptr, 0xC0DE_0000 + i as u64);
+            }
+        }
+        for i in 0..PAGES {
+            let ptr = (base + i * PAGE_SIZE) as *const u64;
+            assert_eq!(unsafe { core::ptr::read_volatile(ptr) }, 0xC0DE_0000 + i as u64);
+        }
+
+        let faults = vma::faults_handled() - faults_before;
+        kprintln!("  Touched {} pages, {} faults handled", PAGES, faults);
+        vma::dump_vmas();
+        vma::unmap(base).unwrap();
+        kprintln!("  [PASS]");
+    }
+
+    // 테스트 2: 잘못된 접근은 커널 패닉 대신 스레드 종료
+    kprintln!("\n[Test 2] Invalid access kills offending thread");
+    {
+        let tid = proc::spawn("vma-fault", vma_fault_thread_entry);
+        kprintln!("  Spawned 'vma-fault' (tid={}), expect it to be killed", tid);
+        let expected = proc::signal::exit_status(proc::signal::SIGSEGV);
+        match proc::join(tid) {
+            Ok(status) if status == expected => kprintln!("  [PASS] killed with SIGSEGV (status {})", status),
+            other => kprintln!("  [FAIL] expected status {}, got {:?}", expected, other),
+        }
+    }
+
+    test_user_fault_ignored();
+
+    kprintln!("\n=== Demand Paging Tests Done ===\n");
+}
+
+/// 테스트 3: SIGSEGV를 무시하는 유저 스레드도 처리할 수 없는 폴트에서 종료
+fn test_user_fault_ignored() {
+    kprintln!("\n[Test 3] User thread ignoring SIGSEGV is still killed");
+    let tid = proc::spawn("vma-user-fault", vma_user_fault_thread_entry);
+    kprintln!("  Spawned 'vma-user-fault' (tid={}), expect it to be killed", tid);
+    let expected = proc::signal::exit_status(proc::signal::SIGSEGV);
+    match proc::join(tid) {
+        Ok(status) if status == expected => kprintln!("  [PASS] killed with SIGSEGV (status {})", status),
+        other => kprintln!("  [FAIL] expected status {}, got {:?}", expected, other),
+    }
+}
+
+/// 읽기 전용 VMA에 쓰기를 시도하는 테스트 스레드
+fn vma_fault_thread_entry() -> ! {
+    use mm::vma::{self, VmaFlags};
+
+    if let Ok(addr) = vma::map_anonymous(mm::page::PAGE_SIZE, VmaFlags::READ, "vmatest-ro") {
+        unsafe {
+            core::ptr::write_volatile(addr as *mut u64, 0xBAD);
+        }
+    }
+
+    kprintln!("[vma-fault] ERROR: write to read-only VMA succeeded");
+    proc::exit();
+}
+
+/// SIGSEGV를 무시하도록 설정한 뒤 읽기 전용 VMA에 쓰는 유저 프로그램을 실행하는 스레드
+fn vma_user_fault_thread_entry() -> ! {
+    use mm::vma::{self, VmaFlags};
+    use proc::user::{self, UserProcess};
+
+    let Ok(addr) = vma::map_anonymous(mm::page::PAGE_SIZE, VmaFlags::READ, "vmatest-user-ro") else {
+        kprintln!("[vma-user-fault] ERROR: map_anonymous failed");
+        proc::exit();
+    };
+    let mut process = UserProcess::new(user::ignore_segv_user_program as *const () as usize);
+    process.arg = addr;
+    unsafe { process.run() }
+}
+
 /// IRQ 활성화 (DAIF 레지스터 조작)
 #[cfg(target_arch = "aarch64")]
 unsafe fn enable_irq() {
@@ -1111,6 +2266,9 @@ fn init_vfs() {
         }
     }
 
+    // initramfs(cpio)를 루트 RamFS에 풀기 (임베드 아카이브, 부트로더 initrd)
+    fs::initramfs::init();
+
     // 테스트: /dev 내용 확인
     if let Ok(dev) = fs::lookup_path("/dev") {
         if let Ok(entries) = dev.readdir() {
@@ -1132,7 +2290,14 @@ fn init_vfs() {
 
 /// SMP 부팅: Per-CPU 초기화 + Secondary CPU/hart 시작
 fn start_smp() {
-    let cpu_count = drivers::config::cpu_count();
+    let mut cpu_count = drivers::config::cpu_count();
+    // bootargs의 nosmp / maxcpus=<n> / smp=<n>으로 시작할 CPU 수 제한
+    if let Some(max) = boot::cmdline::max_cpus() {
+        if max.max(1) < cpu_count {
+            kprintln!("\n[smp] Limiting to {} of {} CPUs (command line)", max.max(1), cpu_count);
+            cpu_count = max.max(1);
+        }
+    }
     if cpu_count <= 1 {
         kprintln!("\n[smp] Single CPU detected, skipping SMP boot");
         return;
@@ -1142,6 +2307,7 @@ fn start_smp() {
 
     // 1. Per-CPU 총 CPU 수 업데이트 (proc::init()에서 이미 1로 초기화됨)
     proc::percpu::set_total_cpu_count(cpu_count as u32);
+    time::nohz::init_from_cmdline();
 
     // 2. Secondary CPU 스택 할당
     proc::percpu::stacks::allocate_secondary_stacks(cpu_count as u32);
@@ -1149,13 +2315,12 @@ fn start_smp() {
     // 3. 아키텍처별 secondary CPU/hart 시작
     #[cfg(target_arch = "aarch64")]
     {
-        let entry = boards::qemu_virt_aarch64_smp::secondary_cpu_entry as usize;
-        boards::qemu_virt_aarch64_smp::start_secondary_cpus(cpu_count, entry);
+        arch::smp::start_secondary_cpus(cpu_count);
     }
 
     #[cfg(target_arch = "riscv64")]
     {
-        let entry = boards::qemu_virt_riscv64_smp::secondary_hart_entry as usize;
+        let entry = boards::qemu_virt_riscv64_smp::secondary_hart_start as usize;
         boards::qemu_virt_riscv64_smp::start_secondary_harts(cpu_count, entry);
     }
 
@@ -1238,6 +2403,13 @@ fn riscv64_start(dtb_addr: usize) -> ! {
             // 로깅 시스템 초기화 (힙 사용 가능 후)
             log::init();
 
+            // 커널 명령줄 파싱 (QEMU -append), loglevel=, console.color=, panic=, module.verify=, module.timeout= 적용
+            boot::cmdline::init();
+            log::init_from_cmdline();
+            console::init_from_cmdline();
+            panic::init_from_cmdline();
+            module::init_from_cmdline();
+
             // 보드 모듈 시스템 초기화 (DTB compatible 기반 보드 선택)
             init_board_system();
 
@@ -1245,6 +2417,9 @@ fn riscv64_start(dtb_addr: usize) -> ! {
             let platform_config = drivers::probe::probe_platform();
             drivers::config::init_platform_config(platform_config);
 
+            // 시리얼 포트 표 (/dev/ttySn, UART IRQ 라우팅)
+            drivers::serial::init();
+
             // MMU 초기화
             match arch::mmu::init(ram_base, ram_size) {
                 Ok(()) => {
@@ -1259,12 +2434,24 @@ fn riscv64_start(dtb_addr: usize) -> ! {
                                         enable_irq_riscv();
                                     }
 
+                                    // UART RX 인터럽트 (콘솔 입력 링, 시리얼 포트 수신 링)
+                                    drivers::serial::enable_rx_irqs();
+
                                     // 메모리 할당 테스트
                                     test_memory_allocation();
 
                                     // 프로세스 서브시스템 초기화
                                     proc::init();
 
+                                    // bootargs의 watchdog=<초>: 이후 부팅(SMP 포함) 중 멈춤 감지
+                                    watchdog::init_from_cmdline();
+
+                                    // bootargs의 trace[=<events>]: 부팅 중 이벤트부터 추적
+                                    trace::event::init_from_bootargs();
+
+                                    // 벽시계 초기화 (RTC)
+                                    time::clock::init();
+
                                     // VFS 초기화
                                     init_vfs();
 
@@ -1277,9 +2464,41 @@ fn riscv64_start(dtb_addr: usize) -> ! {
                                     // 블록 디바이스를 DevFS에 등록 (/dev/vda 등)
                                     fs::devfs::register_block_devices_to_devfs();
 
+                                    // bootargs의 root=<dev>이면 FAT32를 /mnt에 마운트
+                                    block::mount_root_from_cmdline();
+
+                                    // bootargs의 crashdump=<dev>[@<블록>]: 패닉 때 덤프를 쓸 영역
+                                    debug::crashdump::init_from_cmdline();
+
+                                    // 입력 디바이스 초기화 (/dev/input/eventN)
+                                    input::init();
+
+                                    // VirtIO 콘솔 (/dev/hvc0), bootargs의 console=hvc0이면 주 콘솔로 전환
+                                    virtio::console::init();
+                                    console::init_from_bootargs();
+
+                                    // bootargs의 gdb=<ttyS<n>|hvc0>: GDB 원격 스텁 포트
+                                    debug::gdbstub::init_from_cmdline();
+
+                                    // VirtIO GPU (/dev/fb0)와 프레임버퍼 콘솔 (부팅 로그를 화면에 표시)
+                                    virtio::gpu::init();
+                                    fbcon::init();
+
+                                    // VirtIO 9P 전송 (mount -t 9p <tag>로 호스트 디렉토리 마운트)
+                                    virtio::p9::init();
+
+                                    // bootargs의 log.file=<path> 로그 파일 싱크 등록
+                                    log::sink::init_from_bootargs();
+
+                                    // bootargs의 ramfs.restore=<target> 스냅샷 복원
+                                    fs::ramfs::archive::restore_from_bootargs();
+
                                     // SMP 부팅 (secondary harts 시작)
                                     start_smp();
 
+                                    // 하드웨어 요약 출력 (hwinfo 명령으로 다시 조회)
+                                    hwinfo::init();
+
                                     kprintln!("\n[boot] Initialization complete!");
 
                                     #[cfg(feature = "test_runner")]
@@ -1321,10 +2540,8 @@ fn riscv64_start(dtb_addr: usize) -> ! {
 #[cfg(target_arch = "riscv64")]
 unsafe fn enable_irq_riscv() {
     kprintln!("[boot] Enabling interrupts...");
-    unsafe {
-        // mstatus.MIE = 1 (Machine Interrupt Enable)
-        core::arch::asm!("li t0, 0x8", "csrs mstatus, t0");
-    }
+    // mstatus.MIE = 1 (M-mode) 또는 sstatus.SIE = 1 (S-mode)
+    arch::csr::set_status(arch::csr::STATUS_IE);
 }
 
 /// 보드 모듈 시스템 초기화
@@ -1349,11 +2566,11 @@ fn init_board_system() {
     // CPU 개수 확인 (SMP 보드 선택에 사용)
     let cpu_count = dtb::get().map(|dt| dt.count_cpus()).unwrap_or(1);
 
-    kprintln!("[board] Detected {} CPU(s)", cpu_count);
+    log_debug!(tag: "board", "Detected {} CPU(s)", cpu_count);
 
     // 보드 레지스트리 초기화
     if let Some(ref compats) = compatibles {
-        kprintln!("[board] DTB compatible: {:?}", compats);
+        log_debug!(tag: "board", "DTB compatible: {:?}", compats);
         boards::init_early(Some(compats.as_slice()));
     } else {
         kprintln!("[board] No DTB compatible found, using defaults");
@@ -1365,12 +2582,9 @@ fn init_board_system() {
         kprintln!("[board] Warning: Board init failed with error {}", e);
     }
 
-    // 선택된 보드 정보 출력
+    // 선택된 보드 정보 (부팅 요약은 hwinfo가 출력)
     if let Some(board) = boards::current_board_info() {
-        kprintln!("[board] Active board: {}", board.name);
-        if board.smp_capable {
-            kprintln!("[board] SMP capable: yes");
-        }
+        log_debug!(tag: "board", "Active board: {} (SMP capable: {})", board.name, board.smp_capable);
     }
 }
 
@@ -1391,13 +2605,6 @@ fn is_valid_dtb(addr: usize) -> bool {
 }
 
 #[panic_handler]
-fn panic(_info: &PanicInfo) -> ! {
-    kprintln!("Kernels panic: {}\n", _info);
-    #[cfg(feature = "test_runner")]
-    {
-        kprintln!("TEST_STATUS: FAIL");
-        test_runner::qemu_exit(1);
-    }
-    #[cfg(not(feature = "test_runner"))]
-    loop {}
+fn panic(info: &PanicInfo) -> ! {
+    panic::handle(info)
 }
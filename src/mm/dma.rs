@@ -0,0 +1,202 @@
+//! DMA 메모리
+//!
+//! 디바이스와 CPU가 함께 읽고 쓰는 버퍼(virtqueue 링 등)를 할당합니다. 버퍼는 프레임
+//! 할당자의 물리적으로 연속인 페이지이고, 커널은 RAM을 identity 매핑하므로 CPU 주소(vaddr)와
+//! 디바이스에 알려 줄 버스 주소(paddr)가 같습니다. 둘을 따로 돌려주는 것은 IOMMU나 오프셋
+//! 매핑이 생겨도 호출자를 고치지 않기 위해서입니다.
+//!
+//! 캐시 일관성은 DTB의 `virtio,mmio` 노드로 부팅 때 한 번 정합니다 (Linux와 같은 규칙).
+//! - aarch64: `dma-coherent`가 있어야 일관적. 없으면 일관성 없는 디바이스로 보고 할당한 페이지를
+//!   Normal Non-cacheable로 다시 매핑합니다 (MAIR Attr2).
+//! - riscv64: `dma-noncoherent`가 없으면 일관적. 지원하는 보드는 모두 일관적이며, 일관성 없는
+//!   디바이스는 페이지 메모리 종류(Svpbmt)가 없어 경고만 남깁니다.
+//!
+//! 드라이버가 힙 버퍼를 그대로 디바이스에 넘기는 경우(스트리밍 DMA)는 `sync_for_device()`,
+//! `sync_for_cpu()`로 캐시를 정리합니다. 일관적인 구성에서는 메모리 순서만 맞춥니다.
+
+use core::sync::atomic::{fence, AtomicBool, AtomicUsize, Ordering};
+
+use super::page::{self, PAGE_SIZE};
+use crate::arch::{cache, mmu};
+use crate::kprintln;
+
+/// 디바이스 DMA가 CPU 캐시와 일관적인지
+static COHERENT: AtomicBool = AtomicBool::new(true);
+
+/// 살아 있는 일관성 버퍼 수
+static BUFFERS: AtomicUsize = AtomicUsize::new(0);
+/// 일관성 버퍼가 차지한 페이지 수
+static PAGES: AtomicUsize = AtomicUsize::new(0);
+/// 할당 실패 횟수
+static FAILURES: AtomicUsize = AtomicUsize::new(0);
+
+/// DTB에서 DMA 캐시 일관성 결정 (`mm::init`에서 힙 초기화 뒤)
+pub fn init() {
+    let Some(dt) = crate::dtb::get() else {
+        return;
+    };
+    // 속성이 없을 때의 아키텍처 기본값
+    let default = cfg!(target_arch = "riscv64");
+    let nodes = dt.find_compatible("virtio,mmio");
+    if nodes.is_empty() {
+        return;
+    }
+    let coherent = nodes.iter().all(|n| n.dma_coherent.unwrap_or(default));
+
+    if !coherent && cfg!(target_arch = "riscv64") {
+        kprintln!("[MM] Warning: non-coherent DMA not supported, treating devices as coherent");
+        return;
+    }
+    COHERENT.store(coherent, Ordering::Relaxed);
+    if !coherent {
+        kprintln!("[MM] DMA: non-coherent devices, coherent buffers mapped uncached");
+    }
+}
+
+/// 디바이스 DMA가 캐시 일관적인지
+pub fn is_coherent() -> bool {
+    COHERENT.load(Ordering::Relaxed)
+}
+
+/// 일관성 DMA 버퍼 할당 (0으로 채움, 페이지 정렬)
+///
+/// # Returns
+/// (CPU 주소, 디바이스에 줄 물리 주소), `size`는 페이지 단위로 올림
+pub fn alloc_coherent(size: usize) -> Option<(usize, usize)> {
+    let pages = size.div_ceil(PAGE_SIZE).max(1);
+    let Some(vaddr) = page::alloc_frames(pages) else {
+        FAILURES.fetch_add(1, Ordering::Relaxed);
+        return None;
+    };
+    let len = pages * PAGE_SIZE;
+    unsafe {
+        core::ptr::write_bytes(vaddr as *mut u8, 0, len);
+    }
+
+    if !is_coherent() {
+        // 0을 메모리까지 내린 뒤 속성을 바꾸고, 그 사이 투기적으로 올라온 라인도 버림
+        // (순서가 바뀌면 이전에 쓰던 dirty 라인이 나중에 디바이스 데이터를 덮어씀)
+        cache::flush_range(vaddr, len);
+        if let Err(e) = mmu::set_cacheable(vaddr, len, false) {
+            crate::log_warn!(tag: "dma", "cannot map {:#x} uncached: {}", vaddr, e);
+            unsafe {
+                page::free_frames(vaddr, pages);
+            }
+            FAILURES.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        cache::flush_range(vaddr, len);
+    }
+
+    BUFFERS.fetch_add(1, Ordering::Relaxed);
+    PAGES.fetch_add(pages, Ordering::Relaxed);
+    Some((vaddr, vaddr))
+}
+
+/// 일관성 DMA 버퍼 해제
+///
+/// # Safety
+/// `alloc_coherent(size)`로 받은 CPU 주소여야 하고, 디바이스가 더 이상 접근하지 않아야 함
+pub unsafe fn free_coherent(vaddr: usize, size: usize) {
+    let pages = size.div_ceil(PAGE_SIZE).max(1);
+    if !is_coherent() {
+        // Non-cacheable 매핑에는 캐시 라인이 없으므로 속성만 되돌림
+        if let Err(e) = mmu::set_cacheable(vaddr, pages * PAGE_SIZE, true) {
+            // 속성이 남은 페이지를 일반 메모리로 돌려주면 안 되므로 버림
+            crate::log_warn!(tag: "dma", "cannot restore {:#x} cacheable, leaking: {}", vaddr, e);
+            return;
+        }
+    }
+    unsafe {
+        page::free_frames(vaddr, pages);
+    }
+    BUFFERS.fetch_sub(1, Ordering::Relaxed);
+    PAGES.fetch_sub(pages, Ordering::Relaxed);
+}
+
+/// CPU가 쓴 `[addr, addr + len)`을 디바이스에 넘기기 전
+pub fn sync_for_device(addr: usize, len: usize) {
+    if is_coherent() {
+        fence(Ordering::SeqCst);
+    } else {
+        cache::clean_range(addr, len);
+    }
+}
+
+/// 디바이스가 쓴 `[addr, addr + len)`을 CPU가 읽기 전
+pub fn sync_for_cpu(addr: usize, len: usize) {
+    if is_coherent() {
+        fence(Ordering::SeqCst);
+    } else {
+        cache::flush_range(addr, len);
+    }
+}
+
+/// 일관성 DMA 버퍼 (drop 때 해제)
+pub struct DmaBuffer {
+    vaddr: usize,
+    paddr: usize,
+    size: usize,
+}
+
+impl DmaBuffer {
+    /// `size` 바이트 (페이지 단위로 올림) 할당
+    pub fn new(size: usize) -> Option<Self> {
+        let (vaddr, paddr) = alloc_coherent(size)?;
+        Some(Self { vaddr, paddr, size })
+    }
+
+    /// CPU 주소
+    pub fn vaddr(&self) -> usize {
+        self.vaddr
+    }
+
+    /// 디바이스에 줄 물리 주소
+    pub fn paddr(&self) -> usize {
+        self.paddr
+    }
+
+    /// 요청한 크기 (바이트)
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl Drop for DmaBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            free_coherent(self.vaddr, self.size);
+        }
+    }
+}
+
+/// DMA 통계
+#[derive(Debug, Clone, Copy)]
+pub struct DmaStats {
+    pub coherent: bool,
+    pub buffers: usize,
+    pub pages: usize,
+    pub failures: usize,
+}
+
+pub fn stats() -> DmaStats {
+    DmaStats {
+        coherent: is_coherent(),
+        buffers: BUFFERS.load(Ordering::Relaxed),
+        pages: PAGES.load(Ordering::Relaxed),
+        failures: FAILURES.load(Ordering::Relaxed),
+    }
+}
+
+/// `meminfo`용 한 줄 요약
+pub fn print_stats() {
+    let s = stats();
+    kprintln!(
+        "DMA: {} coherent buffers, {} pages ({} KB), {} failures, devices {}",
+        s.buffers,
+        s.pages,
+        s.pages * PAGE_SIZE / 1024,
+        s.failures,
+        if s.coherent { "coherent" } else { "non-coherent (uncached buffers)" }
+    );
+}
@@ -1,13 +1,127 @@
 //! Heap Allocator
 //!
 //! linked_list_allocator를 사용한 커널 힙 관리
+//!
+//! 4096바이트 이하의 할당은 먼저 `mm::slab` 크기 클래스 캐시에서 처리하고,
+//! 큰 할당이나 slab이 페이지를 얻지 못한 경우(부팅 초기 등)에만 힙을 사용합니다.
+//! 해제 시에는 주소가 힙 영역 안인지로 출처를 구분합니다.
+//!
+//! 둘 다 실패하면 `oom::out_of_memory()`로 캐시를 회수하고 한 번 더 시도합니다.
+//!
+//! 포이즈닝을 켜면 할당 직후 `ALLOC_POISON`, 해제 직전 `FREE_POISON`으로
+//! 메모리를 채워 초기화되지 않은 읽기와 use-after-free를 드러냅니다.
+//!
+//! `kmemleak` feature로 빌드하면 모든 할당/해제를 `mm::memleak`에 기록합니다.
+//!
+//! `heap_redzone` feature로 빌드하면 할당마다 `mm::redzone`이 앞뒤에 레드존을 두고
+//! 해제할 때 overflow, 두 번 해제를 확인합니다. 해제한 메모리는 항상 포이즈닝됩니다.
 
+use core::alloc::{GlobalAlloc, Layout};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::debug::failinject::{self, Point};
 use crate::kprintln;
 use linked_list_allocator::LockedHeap;
 
+/// 할당 직후 채우는 값 (초기화 안 된 읽기 탐지)
+pub const ALLOC_POISON: u8 = 0xA5;
+
+/// 해제 직전 채우는 값 (use-after-free 탐지)
+pub const FREE_POISON: u8 = 0x6B;
+
+/// 포이즈닝 활성화 여부
+static POISON_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// 포이즈닝을 지원하는 커널 힙
+struct KernelHeap {
+    inner: LockedHeap,
+}
+
+unsafe impl GlobalAlloc for KernelHeap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if failinject::should_fail(Point::Heap) {
+            return core::ptr::null_mut();
+        }
+        #[cfg(feature = "heap_redzone")]
+        let ptr = unsafe { super::redzone::alloc(layout, |block| self.alloc_block(block)) };
+        #[cfg(not(feature = "heap_redzone"))]
+        let ptr = unsafe { self.alloc_block(layout) };
+        if !ptr.is_null() && POISON_ENABLED.load(Ordering::Relaxed) {
+            unsafe { core::ptr::write_bytes(ptr, ALLOC_POISON, layout.size()) };
+        }
+        #[cfg(feature = "kmemleak")]
+        if !ptr.is_null() {
+            super::memleak::record_alloc(ptr as usize, layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        #[cfg(feature = "kmemleak")]
+        super::memleak::record_free(ptr as usize);
+        #[cfg(feature = "heap_redzone")]
+        unsafe {
+            super::redzone::dealloc(ptr, layout, |block, layout| self.dealloc_block(block, layout))
+        };
+        #[cfg(not(feature = "heap_redzone"))]
+        {
+            if POISON_ENABLED.load(Ordering::Relaxed) {
+                unsafe { core::ptr::write_bytes(ptr, FREE_POISON, layout.size()) };
+            }
+            unsafe { self.dealloc_block(ptr, layout) }
+        }
+    }
+}
+
+impl KernelHeap {
+    /// slab 또는 linked_list 힙에서 할당 (실패하면 OOM 처리 후 한 번 더)
+    unsafe fn alloc_block(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { self.try_alloc_block(layout) };
+        if ptr.is_null() && super::oom::out_of_memory(layout.size().div_ceil(super::page::PAGE_SIZE)) {
+            return unsafe { self.try_alloc_block(layout) };
+        }
+        ptr
+    }
+
+    unsafe fn try_alloc_block(&self, layout: Layout) -> *mut u8 {
+        match super::slab::alloc(layout) {
+            Some(ptr) => ptr,
+            None => unsafe { self.inner.alloc(layout) },
+        }
+    }
+
+    /// 주소로 출처를 구분해 반환
+    unsafe fn dealloc_block(&self, ptr: *mut u8, layout: Layout) {
+        if in_heap(ptr as usize) {
+            unsafe { self.inner.dealloc(ptr, layout) }
+        } else {
+            unsafe { super::slab::dealloc(ptr, layout) }
+        }
+    }
+}
+
+/// 주소가 linked_list 힙 영역 안에 있는지 확인
+#[inline]
+fn in_heap(addr: usize) -> bool {
+    let (start, size) = unsafe { (HEAP_START, HEAP_SIZE) };
+    addr >= start && addr < start + size
+}
+
 /// 전역 힙 할당자
 #[global_allocator]
-static HEAP_ALLOCATOR: LockedHeap = LockedHeap::empty();
+static HEAP_ALLOCATOR: KernelHeap = KernelHeap {
+    inner: LockedHeap::empty(),
+};
+
+/// 힙 포이즈닝 설정
+pub fn set_poison(enabled: bool) {
+    POISON_ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// 힙 포이즈닝 활성화 여부
+pub fn poison_enabled() -> bool {
+    POISON_ENABLED.load(Ordering::Relaxed)
+}
 
 /// 힙 초기화 상태
 static mut HEAP_INITIALIZED: bool = false;
@@ -41,7 +155,7 @@ pub fn init(start: usize, size: usize) -> Result<(), &'static str> {
             return Err("Heap already initialized");
         }
 
-        HEAP_ALLOCATOR.lock().init(start as *mut u8, size);
+        HEAP_ALLOCATOR.inner.lock().init(start as *mut u8, size);
 
         HEAP_INITIALIZED = true;
         HEAP_START = start;
@@ -86,7 +200,7 @@ impl HeapStats {
 
 /// 힙 통계 반환
 pub fn stats() -> HeapStats {
-    let allocator = HEAP_ALLOCATOR.lock();
+    let allocator = HEAP_ALLOCATOR.inner.lock();
     let free = allocator.free();
     let (start, size) = unsafe { (HEAP_START, HEAP_SIZE) };
 
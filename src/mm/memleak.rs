@@ -0,0 +1,209 @@
+//! 힙 할당 추적 (kmemleak-lite)
+//!
+//! `kmemleak` feature로 빌드하면 전역 할당자(`mm::heap`)를 거치는 모든 할당의
+//! 주소, 크기, 호출 위치(프레임 포인터 체인), 세대 번호를 기록합니다.
+//!
+//! 체크포인트를 찍으면 세대가 올라가고, `memleak` 명령은 마지막 체크포인트
+//! 이후 할당되어 아직 해제되지 않은 항목을 보여줍니다.
+//!
+//! ```text
+//! kerners> memleak checkpoint
+//! kerners> (의심되는 동작 반복)
+//! kerners> memleak
+//! ```
+//!
+//! 추적 테이블은 할당자 재귀를 피하기 위해 고정 크기 정적 배열(open addressing)
+//! 이며, 가득 차면 이후 할당은 기록하지 않고 `dropped`로만 셉니다.
+//! 호출 위치는 프레임 포인터를 따라가므로(`trace::backtrace`)
+//! `-C force-frame-pointers=yes`로 빌드해야 정확합니다.
+
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{kprint, kprintln};
+use crate::sync::IrqSpinlock;
+
+/// 추적 테이블 크기 (2의 거듭제곱)
+const TABLE_SIZE: usize = 4096;
+
+/// 기록할 호출 스택 깊이
+pub const STACK_DEPTH: usize = 4;
+
+/// 한 번에 출력할 최대 항목 수
+const MAX_REPORT: usize = 64;
+
+/// 추적 중인 할당 하나
+#[derive(Debug, Clone, Copy)]
+pub struct LeakEntry {
+    /// 할당 주소 (0 = 빈 슬롯)
+    pub ptr: usize,
+    /// 요청 크기
+    pub size: usize,
+    /// 할당 시점의 세대 번호
+    pub generation: u64,
+    /// 호출 위치 (복귀 주소, 0 = 없음)
+    pub callers: [usize; STACK_DEPTH],
+}
+
+impl LeakEntry {
+    const EMPTY: Self = Self {
+        ptr: 0,
+        size: 0,
+        generation: 0,
+        callers: [0; STACK_DEPTH],
+    };
+}
+
+/// 주소 → 할당 정보 해시 테이블 (linear probing)
+struct LeakTable {
+    entries: [LeakEntry; TABLE_SIZE],
+    count: usize,
+    dropped: u64,
+}
+
+impl LeakTable {
+    #[inline]
+    fn slot(ptr: usize) -> usize {
+        // 할당 주소는 최소 8바이트 정렬이므로 하위 비트를 버리고 섞음
+        ((ptr >> 3).wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 32) & (TABLE_SIZE - 1)
+    }
+
+    fn insert(&mut self, entry: LeakEntry) {
+        if self.count >= TABLE_SIZE - 1 {
+            self.dropped += 1;
+            return;
+        }
+        let mut i = Self::slot(entry.ptr);
+        while self.entries[i].ptr != 0 {
+            i = (i + 1) & (TABLE_SIZE - 1);
+        }
+        self.entries[i] = entry;
+        self.count += 1;
+    }
+
+    fn remove(&mut self, ptr: usize) {
+        let mut i = Self::slot(ptr);
+        loop {
+            match self.entries[i].ptr {
+                0 => return,
+                p if p == ptr => break,
+                _ => i = (i + 1) & (TABLE_SIZE - 1),
+            }
+        }
+
+        // backward-shift 삭제: 뒤따르는 항목들을 빈 자리로 당겨 탐색 체인 유지
+        self.entries[i] = LeakEntry::EMPTY;
+        self.count -= 1;
+        let mut hole = i;
+        let mut j = (i + 1) & (TABLE_SIZE - 1);
+        while self.entries[j].ptr != 0 {
+            let home = Self::slot(self.entries[j].ptr);
+            // home이 (hole, j] 순환 구간 밖이면 hole로 옮길 수 있음
+            let in_range = if hole <= j {
+                home > hole && home <= j
+            } else {
+                home > hole || home <= j
+            };
+            if !in_range {
+                self.entries[hole] = self.entries[j];
+                self.entries[j] = LeakEntry::EMPTY;
+                hole = j;
+            }
+            j = (j + 1) & (TABLE_SIZE - 1);
+        }
+    }
+}
+
+static TABLE: IrqSpinlock<LeakTable> = IrqSpinlock::new(LeakTable {
+    entries: [LeakEntry::EMPTY; TABLE_SIZE],
+    count: 0,
+    dropped: 0,
+});
+
+/// 현재 세대 (체크포인트마다 증가)
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 할당 기록 (전역 할당자에서 호출)
+#[inline(always)]
+pub fn record_alloc(ptr: usize, size: usize) {
+    let entry = LeakEntry {
+        ptr,
+        size,
+        generation: GENERATION.load(Ordering::Relaxed),
+        callers: crate::trace::backtrace::capture(),
+    };
+    TABLE.lock().insert(entry);
+}
+
+/// 해제 기록 (전역 할당자에서 호출)
+pub fn record_free(ptr: usize) {
+    TABLE.lock().remove(ptr);
+}
+
+/// 체크포인트: 이후 할당은 새 세대로 기록됨
+///
+/// # Returns
+/// 새 세대 번호
+pub fn checkpoint() -> u64 {
+    GENERATION.fetch_add(1, Ordering::SeqCst) + 1
+}
+
+/// 현재 세대 번호
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+/// `since` 세대 이후의 살아있는 할당 목록
+pub fn live_since(since: u64) -> Vec<LeakEntry> {
+    // 락을 잡은 채 할당하면 재귀로 교착되므로 미리 확보
+    let mut result = Vec::with_capacity(TABLE_SIZE);
+    let table = TABLE.lock();
+    for entry in table.entries.iter() {
+        // 결과 Vec 자체는 제외
+        if entry.ptr != 0 && entry.generation >= since && entry.ptr != result.as_ptr() as usize {
+            result.push(*entry);
+        }
+    }
+    drop(table);
+
+    result.sort_unstable_by_key(|e| (e.generation, e.ptr));
+    result
+}
+
+/// 살아있는 할당 출력
+///
+/// # Arguments
+/// * `since` - 이 세대 이후 할당만 출력 (0 = 전체)
+pub fn report(since: u64) {
+    let entries = live_since(since);
+    let (tracked, dropped) = {
+        let table = TABLE.lock();
+        (table.count, table.dropped)
+    };
+
+    kprintln!(
+        "[memleak] Live allocations since generation {} (current {}):",
+        since,
+        generation()
+    );
+    kprintln!("  {:<18} {:>8} {:>4}  CALLERS", "ADDR", "SIZE", "GEN");
+    for entry in entries.iter().take(MAX_REPORT) {
+        kprint!("  {:#018x} {:>8} {:>4} ", entry.ptr, entry.size, entry.generation);
+        for &caller in entry.callers.iter().take_while(|&&c| c != 0) {
+            kprint!(" {:#x}", caller);
+        }
+        kprintln!("");
+    }
+    if entries.len() > MAX_REPORT {
+        kprintln!("  ... {} more", entries.len() - MAX_REPORT);
+    }
+
+    let bytes: usize = entries.iter().map(|e| e.size).sum();
+    kprintln!(
+        "[memleak] {} allocations, {} bytes (tracked {}, dropped {})",
+        entries.len(),
+        bytes,
+        tracked,
+        dropped
+    );
+}
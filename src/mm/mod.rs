@@ -3,21 +3,105 @@
 //! 메모리 관리 모듈
 //! - 페이지 프레임 할당자
 //! - 힙 할당자
-//! - MMU 설정 (추후 구현)
+//! - Slab 할당자 (작은 객체 크기 클래스 캐시)
+//! - VMA 및 demand paging
+//! - DMA 버퍼 (물리적으로 연속, 캐시 일관성)
+//! - 메모리 부족 처리 (캐시 회수, OOM 킬러)
+//! - 힙 할당 추적 (`kmemleak` feature)
+//! - 힙 레드존, double free 탐지 (`heap_redzone` feature)
 
 pub mod page;
+pub mod dma;
 pub mod heap;
+#[cfg(feature = "kmemleak")]
+pub mod memleak;
+pub mod oom;
+pub mod owner;
+#[cfg(feature = "heap_redzone")]
+pub mod redzone;
+pub mod slab;
+pub mod vma;
 
 use crate::kprintln;
+use crate::sync::Spinlock;
 
 /// 메모리 영역 정보
 #[derive(Debug, Clone, Copy)]
-#[allow(dead_code)]
 pub struct MemoryRegion {
     pub base: usize,
     pub size: usize,
 }
 
+impl MemoryRegion {
+    /// 영역 끝 (끝 다음 바이트)
+    pub fn end(&self) -> usize {
+        self.base + self.size
+    }
+
+    /// `[start, end)`와 겹치는지
+    pub fn overlaps(&self, start: usize, end: usize) -> bool {
+        self.base < end && start < self.end()
+    }
+}
+
+/// 영역 목록 최대 개수 (메모리 뱅크, 예약 영역 각각)
+pub const MAX_REGIONS: usize = 16;
+
+/// 메모리 영역 목록
+///
+/// RAM 뱅크와 예약 영역(DTB 자체, 메모리 예약 블록, /reserved-memory, initrd)을
+/// 모읍니다. 힙 초기화 전에 만들므로 고정 크기 배열입니다.
+#[derive(Debug, Clone, Copy)]
+pub struct RegionList {
+    regions: [MemoryRegion; MAX_REGIONS],
+    count: usize,
+}
+
+impl RegionList {
+    pub const fn new() -> Self {
+        Self {
+            regions: [MemoryRegion { base: 0, size: 0 }; MAX_REGIONS],
+            count: 0,
+        }
+    }
+
+    /// 영역 추가
+    fn push(&mut self, region: MemoryRegion) {
+        if region.size == 0 {
+            return;
+        }
+        if self.count == MAX_REGIONS {
+            kprintln!("[MM] Warning: too many memory regions, {:#x} ignored", region.base);
+            return;
+        }
+        self.regions[self.count] = region;
+        self.count += 1;
+    }
+
+    /// 예약 영역 추가 (페이지 경계로 넓힘)
+    fn reserve(&mut self, base: usize, size: usize) {
+        if size == 0 {
+            return;
+        }
+        let start = base & !0xFFF;
+        let end = (base.saturating_add(size) + 0xFFF) & !0xFFF;
+        self.push(MemoryRegion { base: start, size: end - start });
+    }
+
+    pub fn as_slice(&self) -> &[MemoryRegion] {
+        &self.regions[..self.count]
+    }
+
+    /// `[start, end)`와 겹치는 영역 중 시작 주소가 가장 낮은 것
+    fn first_overlap(&self, start: usize, end: usize) -> Option<MemoryRegion> {
+        self.as_slice()
+            .iter()
+            .filter(|r| r.overlaps(start, end))
+            .min_by_key(|r| r.base)
+            .copied()
+    }
+}
+
 /// 커널 메모리 레이아웃
 #[derive(Debug)]
 pub struct KernelMemoryLayout {
@@ -37,6 +121,12 @@ pub struct KernelMemoryLayout {
     pub frame_alloc_start: usize,
     /// 페이지 프레임 할당 영역 크기
     pub frame_alloc_size: usize,
+    /// 부트로더가 올려 둔 initramfs 영역 (힙/프레임 풀에서 제외됨)
+    pub initrd: Option<MemoryRegion>,
+    /// 예약 영역 (프레임 풀 안에 있으면 할당에서 제외됨)
+    pub reserved: RegionList,
+    /// RAM 뱅크 (0번이 커널이 있는 주 뱅크, 나머지는 각각 별도 프레임 풀)
+    pub banks: RegionList,
 }
 
 impl KernelMemoryLayout {
@@ -54,9 +144,51 @@ impl KernelMemoryLayout {
         kprintln!("  Frame Pool:  {:#x} - {:#x} ({} MB)", 
             self.frame_alloc_start, self.frame_alloc_start + self.frame_alloc_size,
             self.frame_alloc_size / (1024 * 1024));
+        if let Some(initrd) = self.initrd {
+            kprintln!("  Initrd:      {:#x} - {:#x} ({} KB)",
+                initrd.base, initrd.end(),
+                initrd.size / 1024);
+        }
+        for bank in self.banks.as_slice().iter().skip(1) {
+            kprintln!("  Extra RAM:   {:#x} - {:#x} ({} MB)",
+                bank.base, bank.end(),
+                bank.size / (1024 * 1024));
+        }
+        for region in self.reserved.as_slice() {
+            kprintln!("  Reserved:    {:#x} - {:#x} ({} KB)",
+                region.base, region.end(),
+                region.size / 1024);
+        }
     }
 }
 
+/// 예약 영역 때문에 힙을 줄일 때 남겨야 하는 최소 크기
+const MIN_HEAP_SIZE: usize = 16 * 1024 * 1024;
+
+/// DTB 위치를 모를 때 RAM 끝에서 비워 두는 크기 (QEMU는 RAM 끝 - 2MB에 DTB 배치)
+const FALLBACK_RESERVED_AT_END: usize = 4 * 1024 * 1024;
+
+/// 예약한 initramfs 영역 (`mm::init`에서 기록)
+static INITRD: Spinlock<Option<MemoryRegion>> = Spinlock::new(None);
+
+/// 부트로더가 올려 둔 initramfs 영역
+///
+/// `mm::init()`이 힙/프레임 풀과 겹치지 않게 예약한 영역만 돌려줍니다.
+pub fn initrd_region() -> Option<MemoryRegion> {
+    *INITRD.lock()
+}
+
+/// RAM 뱅크 목록 (`mm::init`에서 기록)
+static BANKS: Spinlock<RegionList> = Spinlock::new(RegionList::new());
+
+/// RAM 뱅크 목록
+///
+/// 0번은 커널과 힙이 있는 주 뱅크이고, 나머지는 DTB에 추가로 기술된 뱅크입니다.
+/// MMU 초기화 시 모든 뱅크를 identity 매핑합니다.
+pub fn memory_banks() -> RegionList {
+    *BANKS.lock()
+}
+
 /// 메모리 관리 시스템 초기화
 /// 
 /// # Arguments
@@ -87,26 +219,67 @@ pub fn init(ram_start: usize, ram_size: usize) -> Result<KernelMemoryLayout, &'s
     
     // 힙 크기 설정: RAM의 1/4 또는 최대 128MB
     let max_heap_size = 128 * 1024 * 1024; // 128MB
-    let heap_size = core::cmp::min(ram_size / 4, max_heap_size);
+    let mut heap_size = core::cmp::min(ram_size / 4, max_heap_size);
     let heap_start = kernel_end_aligned;
+    let ram_end = ram_start + ram_size;
+
+    // RAM 뱅크 수집 (주 뱅크 + DTB의 나머지 memory 영역)
+    let dt = crate::dtb::get();
+    let mut banks = RegionList::new();
+    banks.push(MemoryRegion { base: ram_start, size: ram_size });
+    if let Some(dt) = dt {
+        dt.for_each_memory(|region| {
+            let bank = MemoryRegion { base: region.base as usize, size: region.size as usize };
+            if banks.as_slice().iter().all(|b| !b.overlaps(bank.base, bank.end())) {
+                banks.push(bank);
+            }
+        });
+    }
+    *BANKS.lock() = banks;
+
+    // 예약 영역 수집 (DTB 자체, 메모리 예약 블록, /reserved-memory)
+    let mut reserved = RegionList::new();
+    let mut frame_alloc_end = ram_end;
+    match dt {
+        Some(dt) => {
+            // DTB는 부팅 내내 제자리에서 읽음
+            reserved.reserve(dt.base(), dt.total_size());
+            dt.for_each_reserved(|name, region| {
+                kprintln!("[MM] DTB reserved {:#x} - {:#x} ({})",
+                    region.base, region.base + region.size, name);
+                reserved.reserve(region.base as usize, region.size as usize);
+            });
+        }
+        None => frame_alloc_end = ram_end.saturating_sub(FALLBACK_RESERVED_AT_END),
+    }
+
+    // initramfs 영역 예약 (DTB /chosen의 linux,initrd-start/end)
+    // QEMU는 커널 뒤 일정 오프셋에 올리므로 힙이나 프레임 풀과 겹칠 수 있음
+    let mut initrd = None;
+    if let Some((start, end)) = dt.and_then(|dt| dt.get_initrd()) {
+        if (start & !0xFFF) < heap_start + MIN_HEAP_SIZE {
+            kprintln!("[MM] Warning: initrd {:#x} - {:#x} too close to kernel image, ignored", start, end);
+        } else {
+            reserved.reserve(start, end - start);
+            initrd = Some(MemoryRegion { base: start, size: end - start });
+        }
+    }
+    *INITRD.lock() = initrd;
+
+    // 힙은 연속 영역이어야 하므로 처음 겹치는 예약 영역 앞에서 끊음
+    if let Some(region) = reserved.first_overlap(heap_start, heap_start + heap_size) {
+        if region.base >= heap_start + MIN_HEAP_SIZE {
+            heap_size = region.base - heap_start;
+        } else {
+            kprintln!("[MM] Warning: reserved region {:#x} - {:#x} overlaps kernel heap",
+                region.base, region.end());
+        }
+    }
     let heap_end = heap_start + heap_size;
-    
-    // 페이지 프레임 할당 영역: 힙 이후 ~ RAM 끝 (DTB 영역 제외)
-    // DTB는 RAM 끝에서 2MB 전에 위치하므로 4MB 여유 확보
+
+    // 페이지 프레임 할당 영역: 힙 이후 ~ RAM 끝 (예약 영역은 page::init()에서 제외)
     let frame_alloc_start = (heap_end + 0xFFF) & !0xFFF;
-    let ram_end = ram_start + ram_size;
-    let reserved_at_end = 4 * 1024 * 1024; // 4MB 예약 (DTB 등)
-    let frame_alloc_end = if ram_end > reserved_at_end {
-        ram_end - reserved_at_end
-    } else {
-        ram_end
-    };
-    
-    let frame_alloc_size = if frame_alloc_end > frame_alloc_start {
-        frame_alloc_end - frame_alloc_start
-    } else {
-        0
-    };
+    let frame_alloc_size = frame_alloc_end.saturating_sub(frame_alloc_start);
     
     let layout = KernelMemoryLayout {
         kernel_start: KERNEL_START,
@@ -117,6 +290,9 @@ pub fn init(ram_start: usize, ram_size: usize) -> Result<KernelMemoryLayout, &'s
         heap_size,
         frame_alloc_start,
         frame_alloc_size,
+        initrd,
+        reserved,
+        banks,
     };
     
     layout.dump();
@@ -124,13 +300,25 @@ pub fn init(ram_start: usize, ram_size: usize) -> Result<KernelMemoryLayout, &'s
     // 힙 초기화
     heap::init(heap_start, heap_size)?;
     
-    // 페이지 프레임 할당자 초기화
+    // 페이지 프레임 할당자 초기화 (주 뱅크)
     if frame_alloc_size > 0 {
-        page::init(frame_alloc_start, frame_alloc_size)?;
+        page::add_pool(frame_alloc_start, frame_alloc_size, reserved.as_slice())?;
     } else {
         kprintln!("[MM] Warning: No memory available for page frame allocator");
     }
+
+    // 추가 뱅크는 각각 별도 프레임 풀 (뱅크 전체, 예약 영역 제외)
+    for bank in banks.as_slice().iter().skip(1) {
+        let start = (bank.base + 0xFFF) & !0xFFF;
+        let end = bank.end() & !0xFFF;
+        if let Err(e) = page::add_pool(start, end.saturating_sub(start), reserved.as_slice()) {
+            kprintln!("[MM] Warning: RAM bank {:#x} - {:#x} not used: {}", bank.base, bank.end(), e);
+        }
+    }
     
+    dma::init();
+    oom::register_sysctl();
+
     kprintln!("[MM] Memory management initialized successfully");
     
     Ok(layout)
@@ -0,0 +1,301 @@
+//! 메모리 부족 처리 (OOM)
+//!
+//! 프레임 할당자나 힙이 실패하면 호출자에게 바로 `None`/null을 돌려주는 대신
+//! `out_of_memory()`를 거칩니다.
+//!
+//! 1. CPU별 프레임 매거진을 전역 할당자로 비움
+//! 2. 등록된 메모리 압박 통지(`Notifier`)를 등록 순서대로 불러 캐시를 줄임
+//!    (FAT32 섹터 캐시의 깨끗한 섹터 등)
+//! 3. 요청한 만큼 회수했으면 호출자가 한 번 더 시도
+//! 4. 모자라면 희생 스레드를 골라 SIGKILL을 보내고 로그를 남김 (`mm.oom_kill`)
+//!
+//! 희생 스레드는 VMA를 소유한 스레드 중 상주 페이지와 커널 스택 페이지의 합이 가장 큰
+//! 것입니다. idle 스레드(셸 포함)와 VMA가 없는 커널 데몬(`kreaperd`, `klogd`, `kworker` 등)은
+//! 필수 스레드로 보고 고르지 않습니다. 희생 스레드의 메모리는 종료한 뒤 `kreaperd`가
+//! 해제하므로, 그 스택이 해제될 때까지는 다른 스레드를 더 죽이지 않고 이번 할당은
+//! 실패합니다.
+//!
+//! 할당자 안에서 불리므로 힙을 쓰지 않고, 호출자가 잡고 있을 수 있는 락(스레드 레지스트리,
+//! VMA 목록, 매거진, 통지 대상의 캐시 락)은 `try_lock`으로만 잡습니다. 한 번에 하나만
+//! 실행하며, 처리 중에 다시 할당이 실패하면(재귀 포함) 바로 실패를 돌려줍니다.
+
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use super::page::{self, PAGE_SIZE};
+use super::vma;
+use crate::kprintln;
+use crate::proc::{self, percpu, signal, ThreadState, Tid};
+use crate::sync::Spinlock;
+use crate::sysctl::{self, Kind, Tunable, Value};
+
+/// 메모리 압박 통지 대상
+pub struct Notifier {
+    /// 이름 (`oom` 명령 출력용)
+    pub name: &'static str,
+    /// 최대 `pages` 페이지를 회수하고 실제로 회수한 페이지 수를 반환
+    ///
+    /// 할당 실패 경로에서 불리므로 잠들지 않고, 락은 `try_lock`으로만 잡습니다.
+    pub reclaim: fn(pages: usize) -> usize,
+}
+
+/// 통지 대상 최대 개수
+const MAX_NOTIFIERS: usize = 8;
+
+/// 등록된 통지 대상 (등록 순서, 할당 경로에서 읽으므로 고정 크기)
+static NOTIFIERS: Spinlock<[Option<&'static Notifier>; MAX_NOTIFIERS]> = Spinlock::new([None; MAX_NOTIFIERS]);
+
+/// OOM 처리 중 (한 번에 하나, 재귀 방지)
+static IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+/// 회수로 모자라면 희생 스레드를 죽일지 (`mm.oom_kill`)
+static KILL_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// 마지막 희생 스레드 (`NO_THREAD` = 없음, 스택이 해제될 때까지 유지)
+static VICTIM: AtomicU64 = AtomicU64::new(percpu::NO_THREAD);
+
+/// `out_of_memory()` 호출 수
+static EVENTS: AtomicU64 = AtomicU64::new(0);
+/// 회수한 페이지 수
+static RECLAIMED: AtomicU64 = AtomicU64::new(0);
+/// 죽인 스레드 수
+static KILLS: AtomicU64 = AtomicU64::new(0);
+/// 죽일 스레드를 찾지 못한 횟수
+static NO_VICTIM: AtomicU64 = AtomicU64::new(0);
+/// 마지막 "희생 스레드 없음" 로그 시각 (부팅 후 마이크로초, 1초에 한 번만 출력)
+static LAST_NO_VICTIM_LOG: AtomicU64 = AtomicU64::new(0);
+
+static SYSCTL: [Tunable; 1] = [Tunable {
+    name: "mm.oom_kill",
+    desc: "kill the largest thread when reclaim cannot satisfy an allocation",
+    kind: Kind::Bool,
+    get: || Value::Bool(KILL_ENABLED.load(Ordering::Relaxed)),
+    set: Some(|v| {
+        KILL_ENABLED.store(sysctl::boolean(&v), Ordering::Relaxed);
+        Ok(())
+    }),
+}];
+
+/// `mm.*` OOM 노브 등록 (`mm::init()`에서 호출)
+pub fn register_sysctl() {
+    sysctl::register_all(&SYSCTL);
+}
+
+/// 메모리 압박 통지 대상 등록
+pub fn register_notifier(notifier: &'static Notifier) -> Result<(), &'static str> {
+    let mut notifiers = NOTIFIERS.lock();
+    if notifiers.iter().flatten().any(|n| core::ptr::eq(*n, notifier)) {
+        return Err("notifier already registered");
+    }
+    let slot = notifiers.iter_mut().find(|n| n.is_none()).ok_or("too many notifiers")?;
+    *slot = Some(notifier);
+    Ok(())
+}
+
+/// 통지 대상 등록 해제 (있었으면 true)
+pub fn unregister_notifier(notifier: &'static Notifier) -> bool {
+    let mut notifiers = NOTIFIERS.lock();
+    match notifiers.iter_mut().find(|n| n.is_some_and(|n| core::ptr::eq(n, notifier))) {
+        Some(slot) => {
+            *slot = None;
+            true
+        }
+        None => false,
+    }
+}
+
+/// 캐시 회수 (CPU별 매거진, 통지 대상 순서, 회수한 페이지 수)
+///
+/// `pages`만큼 회수하면 남은 통지 대상은 부르지 않습니다.
+pub fn reclaim(pages: usize) -> usize {
+    let mut freed = page::reclaim_percpu_caches();
+    // 통지 함수가 힙을 해제하므로 목록을 복사해 락 밖에서 부름
+    let notifiers = *NOTIFIERS.lock();
+    for notifier in notifiers.iter().flatten() {
+        if freed >= pages {
+            break;
+        }
+        freed += (notifier.reclaim)(pages - freed);
+    }
+    RECLAIMED.fetch_add(freed as u64, Ordering::Relaxed);
+    freed
+}
+
+/// 할당 실패 처리: 회수하고, 모자라면 희생 스레드를 죽임
+///
+/// `pages`는 실패한 할당의 크기입니다. 그만큼 회수해 호출자가 다시 시도할 만하면 true,
+/// 아니면 false이며 이번 할당은 실패합니다 (희생 스레드의 메모리는 나중에 돌아옴).
+pub fn out_of_memory(pages: usize) -> bool {
+    if IN_PROGRESS.swap(true, Ordering::Acquire) {
+        return false;
+    }
+    EVENTS.fetch_add(1, Ordering::Relaxed);
+
+    let retry = reclaim(pages) >= pages;
+    if !retry && KILL_ENABLED.load(Ordering::Relaxed) {
+        kill_victim();
+    }
+
+    IN_PROGRESS.store(false, Ordering::Release);
+    retry
+}
+
+/// 희생 스레드 후보
+#[derive(Debug, Clone, Copy)]
+pub struct Victim {
+    pub tid: Tid,
+    /// 소유한 VMA의 상주 페이지 + 커널 스택 페이지
+    pub pages: usize,
+    name: [u8; VICTIM_NAME_LEN],
+    name_len: usize,
+}
+
+/// 로그용으로 복사해 두는 스레드 이름 길이 (힙을 쓰지 않으려고 고정)
+const VICTIM_NAME_LEN: usize = 16;
+
+impl Victim {
+    fn new(tid: Tid, name: &str, pages: usize) -> Self {
+        let mut len = name.len().min(VICTIM_NAME_LEN);
+        while !name.is_char_boundary(len) {
+            len -= 1;
+        }
+        let mut buf = [0u8; VICTIM_NAME_LEN];
+        buf[..len].copy_from_slice(&name.as_bytes()[..len]);
+        Self { tid, pages, name: buf, name_len: len }
+    }
+
+    /// 스레드 이름 (앞 16바이트)
+    pub fn name(&self) -> &str {
+        core::str::from_utf8(&self.name[..self.name_len]).unwrap_or("?")
+    }
+}
+
+/// 희생 스레드 고르기 (레지스트리나 VMA 목록 락을 바로 얻지 못하면 후보에서 빠짐)
+pub fn select_victim() -> Option<Victim> {
+    let mut best: Option<Victim> = None;
+    proc::try_for_each_thread(|t| {
+        if t.state == ThreadState::Terminated || t.tid == 0 || proc::is_idle_thread(t.tid) {
+            return;
+        }
+        // VMA가 없는 스레드는 커널 데몬이므로 필수로 봄
+        let owned = vma::try_owned_pages(t.tid).unwrap_or(0);
+        if owned == 0 {
+            return;
+        }
+        let pages = owned + t.kernel_stack.capacity().div_ceil(PAGE_SIZE);
+        if !best.as_ref().is_some_and(|b| b.pages >= pages) {
+            best = Some(Victim::new(t.tid, &t.name, pages));
+        }
+    });
+    best
+}
+
+/// 지난 희생 스레드가 아직 메모리를 들고 있는지 (커널 스택과 VMA는 함께 해제됨)
+fn victim_pending() -> bool {
+    let tid = VICTIM.load(Ordering::Acquire);
+    if tid == percpu::NO_THREAD {
+        return false;
+    }
+    let mut alive = false;
+    if !proc::try_for_each_thread(|t| alive |= t.tid == tid && t.kernel_stack.capacity() > 0) {
+        // 레지스트리를 볼 수 없으면 아직 있다고 봄
+        return true;
+    }
+    if !alive {
+        let _ = VICTIM.compare_exchange(tid, percpu::NO_THREAD, Ordering::AcqRel, Ordering::Relaxed);
+    }
+    alive
+}
+
+/// 스레드가 OOM 킬러에 선택돼 종료를 기다리는 중인지
+///
+/// 페이지 폴트 처리가 희생 스레드의 폴트를 실패시켜 바로 종료하는 데 씁니다.
+pub fn is_victim(tid: Tid) -> bool {
+    VICTIM.load(Ordering::Acquire) == tid
+}
+
+/// 희생 스레드를 골라 SIGKILL (지난 희생 스레드가 아직 메모리를 들고 있으면 None)
+///
+/// 시그널은 스레드가 다음 전달 지점(양보, 시스템 콜 복귀 등)이나 자기 VMA의 다음
+/// 페이지 폴트에서 처리됩니다.
+pub fn kill_victim() -> Option<Victim> {
+    if victim_pending() {
+        return None;
+    }
+    let Some(victim) = select_victim() else {
+        NO_VICTIM.fetch_add(1, Ordering::Relaxed);
+        let now = crate::time::now_us();
+        let last = LAST_NO_VICTIM_LOG.load(Ordering::Relaxed);
+        if (last == 0 || now.saturating_sub(last) >= 1_000_000)
+            && LAST_NO_VICTIM_LOG.compare_exchange(last, now.max(1), Ordering::Relaxed, Ordering::Relaxed).is_ok()
+        {
+            crate::log_error!(tag: "oom", "Out of memory: no killable thread");
+        }
+        return None;
+    };
+
+    VICTIM.store(victim.tid, Ordering::Release);
+    if let Err(e) = signal::try_send(victim.tid, signal::SIGKILL) {
+        VICTIM.store(percpu::NO_THREAD, Ordering::Release);
+        crate::log_error!(tag: "oom", "Out of memory: failed to kill thread {}: {:?}", victim.tid, e);
+        return None;
+    }
+    KILLS.fetch_add(1, Ordering::Relaxed);
+    crate::log_error!(
+        tag: "oom",
+        "Out of memory: killed thread {} '{}' ({} pages, {} KB)",
+        victim.tid,
+        victim.name(),
+        victim.pages,
+        victim.pages * PAGE_SIZE / 1024
+    );
+    Some(victim)
+}
+
+/// OOM 통계
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OomStats {
+    /// `out_of_memory()` 호출 수
+    pub events: u64,
+    /// 회수한 페이지 수
+    pub reclaimed_pages: u64,
+    /// 죽인 스레드 수
+    pub kills: u64,
+    /// 죽일 스레드를 찾지 못한 횟수
+    pub no_victim: u64,
+}
+
+/// 통계
+pub fn stats() -> OomStats {
+    OomStats {
+        events: EVENTS.load(Ordering::Relaxed),
+        reclaimed_pages: RECLAIMED.load(Ordering::Relaxed),
+        kills: KILLS.load(Ordering::Relaxed),
+        no_victim: NO_VICTIM.load(Ordering::Relaxed),
+    }
+}
+
+/// 통계, 통지 대상, 현재 후보 출력 (`oom` 명령)
+pub fn dump() {
+    let s = stats();
+    kprintln!(
+        "OOM: {} events, {} pages reclaimed, {} threads killed, {} without victim (oom_kill={})",
+        s.events,
+        s.reclaimed_pages,
+        s.kills,
+        s.no_victim,
+        KILL_ENABLED.load(Ordering::Relaxed) as u8
+    );
+    let notifiers = *NOTIFIERS.lock();
+    kprintln!("Pressure notifiers:");
+    for notifier in notifiers.iter().flatten() {
+        kprintln!("  {}", notifier.name);
+    }
+    match select_victim() {
+        Some(v) => kprintln!("Next victim: tid={} '{}' ({} pages)", v.tid, v.name(), v.pages),
+        None => kprintln!("Next victim: none"),
+    }
+    if victim_pending() {
+        let pending = VICTIM.load(Ordering::Relaxed);
+        kprintln!("Waiting for victim tid={} to release memory", pending);
+    }
+}
@@ -0,0 +1,143 @@
+//! 할당 소유자(호출 모듈)별 힙 통계
+//!
+//! 모듈(.ko)은 `kernel_heap_alloc`/`kernel_heap_dealloc` 심볼을 통해서만
+//! 커널 힙을 사용하므로, 해당 래퍼에서 복귀 주소가 속한 모듈을 찾아
+//! 할당을 모듈 단위로 기록합니다.
+//!
+//! 커널 자체 할당은 개별 기록하지 않고, 전체 힙 사용량에서 모듈 몫을
+//! 뺀 값으로 계산합니다.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::kprintln;
+use crate::sync::Mutex;
+
+/// 커널 자신을 나타내는 소유자 이름
+pub const KERNEL_OWNER: &str = "kernel";
+
+/// 소유자별 할당 통계
+#[derive(Debug, Clone)]
+pub struct OwnerStats {
+    /// 소유자 이름 (모듈 이름 또는 "kernel")
+    pub owner: String,
+    /// 현재 살아있는 할당 바이트
+    pub live_bytes: usize,
+    /// 현재 살아있는 할당 개수
+    pub live_allocs: usize,
+    /// 누적 할당 횟수
+    pub total_allocs: usize,
+    /// 누적 해제 횟수
+    pub total_frees: usize,
+}
+
+impl OwnerStats {
+    fn new(owner: &str) -> Self {
+        Self {
+            owner: String::from(owner),
+            live_bytes: 0,
+            live_allocs: 0,
+            total_allocs: 0,
+            total_frees: 0,
+        }
+    }
+}
+
+/// 소유자 추적 상태
+struct OwnerTable {
+    /// 소유자별 통계 (Vec으로 관리)
+    owners: Vec<OwnerStats>,
+    /// 살아있는 모듈 할당 (주소, 크기, owners 인덱스)
+    allocations: Vec<(usize, usize, usize)>,
+}
+
+static OWNER_TABLE: Mutex<OwnerTable> = Mutex::new(OwnerTable {
+    owners: Vec::new(),
+    allocations: Vec::new(),
+});
+
+/// 모듈 할당 기록
+pub fn record_alloc(owner: &str, ptr: usize, size: usize) {
+    let mut table = OWNER_TABLE.lock();
+    let idx = match table.owners.iter().position(|o| o.owner == owner) {
+        Some(idx) => idx,
+        None => {
+            table.owners.push(OwnerStats::new(owner));
+            table.owners.len() - 1
+        }
+    };
+
+    let stats = &mut table.owners[idx];
+    stats.live_bytes += size;
+    stats.live_allocs += 1;
+    stats.total_allocs += 1;
+    table.allocations.push((ptr, size, idx));
+}
+
+/// 모듈 할당 해제 기록
+///
+/// 기록되지 않은 주소(커널 할당)는 무시합니다.
+pub fn record_free(ptr: usize) {
+    let mut table = OWNER_TABLE.lock();
+    if let Some(pos) = table.allocations.iter().position(|&(p, _, _)| p == ptr) {
+        let (_, size, idx) = table.allocations.swap_remove(pos);
+        let stats = &mut table.owners[idx];
+        stats.live_bytes = stats.live_bytes.saturating_sub(size);
+        stats.live_allocs = stats.live_allocs.saturating_sub(1);
+        stats.total_frees += 1;
+    }
+}
+
+/// 모듈 언로드 시 호출: 해제되지 않은 할당 경고
+pub fn module_unloaded(name: &str) {
+    let table = OWNER_TABLE.lock();
+    if let Some(stats) = table.owners.iter().find(|o| o.owner == name) {
+        if stats.live_allocs > 0 {
+            kprintln!(
+                "[MM] Module '{}' unloaded with {} live allocations ({} bytes)",
+                name,
+                stats.live_allocs,
+                stats.live_bytes
+            );
+        }
+    }
+}
+
+/// 소유자별 통계 스냅샷 (첫 항목은 커널)
+pub fn stats() -> Vec<OwnerStats> {
+    let heap_used = super::heap::stats().used + super::slab::used_bytes();
+
+    let table = OWNER_TABLE.lock();
+    let module_bytes: usize = table.owners.iter().map(|o| o.live_bytes).sum();
+
+    let mut kernel = OwnerStats::new(KERNEL_OWNER);
+    kernel.live_bytes = heap_used.saturating_sub(module_bytes);
+
+    let mut result = Vec::with_capacity(table.owners.len() + 1);
+    result.push(kernel);
+    result.extend(table.owners.iter().cloned());
+    result
+}
+
+/// 소유자별 통계 출력
+pub fn dump_stats() {
+    kprintln!("[Heap] Usage by owner:");
+    kprintln!(
+        "  {:<16} {:>10} {:>8} {:>8} {:>8}",
+        "OWNER", "LIVE(B)", "LIVE#", "ALLOCS", "FREES"
+    );
+    for stats in self::stats() {
+        if stats.owner == KERNEL_OWNER {
+            kprintln!("  {:<16} {:>10} {:>8} {:>8} {:>8}", stats.owner, stats.live_bytes, "-", "-", "-");
+        } else {
+            kprintln!(
+                "  {:<16} {:>10} {:>8} {:>8} {:>8}",
+                stats.owner,
+                stats.live_bytes,
+                stats.live_allocs,
+                stats.total_allocs,
+                stats.total_frees
+            );
+        }
+    }
+}
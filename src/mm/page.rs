@@ -1,29 +1,82 @@
 //! Page Frame Allocator
 //!
 //! 물리 페이지 프레임 할당자 구현
-//! 비트맵 기반의 간단한 할당자
+//! Buddy 시스템 기반 할당자 (order-N 연속 할당, 해제 시 병합)
+//!
+//! 단일 프레임 할당/해제는 CPU별 매거진(작은 프레임 캐시)을 먼저 사용하고,
+//! 매거진이 비거나 가득 차면 전역 할당자와 배치 단위로 주고받아
+//! 전역 락 경합을 줄입니다.
+//!
+//! DTB에 RAM 뱅크가 여러 개이면 뱅크마다 독립된 buddy 풀을 두고(`FramePools`),
+//! 할당은 등록 순서(주 뱅크 먼저)대로 시도하며 해제는 주소로 풀을 찾습니다.
+//!
+//! 프레임이 모자라면 `oom::out_of_memory()`가 캐시를 회수하고(필요하면 희생 스레드를 종료)
+//! 회수에 성공했을 때 한 번 더 시도합니다.
+
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
+use super::MemoryRegion;
+use crate::debug::failinject::{self, Point};
 use crate::kprintln;
-use crate::sync::Mutex;
+use crate::proc::percpu::{self, MAX_CPUS};
+use crate::sync::{Mutex, Spinlock};
 
 /// 페이지 크기: 4KB
 pub const PAGE_SIZE: usize = 4096;
 
+/// 최대 order (2^MAX_ORDER 페이지 = 4MB 블록)
+pub const MAX_ORDER: usize = 10;
+
+/// order 개수
+const NR_ORDERS: usize = MAX_ORDER + 1;
+
+/// 리스트 끝 표시
+const NONE: usize = usize::MAX;
+
+/// 페이지 상태: free 블록 내부 (헤드가 아닌 free 페이지)
+const PAGE_NOT_HEAD: u8 = 0xFF;
+
+/// 페이지 상태: 할당됨 (메타데이터/예약 페이지 포함)
+///
+/// 해제 시 블록의 모든 페이지가 이 상태여야 하므로, 해제 후 더 큰 블록으로 병합된
+/// 페이지를 다시 해제해도 double free로 거부됩니다.
+const PAGE_ALLOCATED: u8 = 0xFE;
+
+/// free 블록 헤드 페이지에 저장되는 리스트 노드 (페이지 인덱스)
+#[repr(C)]
+struct FreeBlock {
+    next: usize,
+    prev: usize,
+}
+
 /// 페이지 프레임 할당자
-/// 비트맵 기반으로 구현
+/// Buddy 시스템으로 구현
+///
+/// - order k 블록 = 2^k개의 연속 페이지, 인덱스가 2^k 배수로 정렬
+/// - order별 이중 연결 free list (노드는 free 블록 첫 페이지에 저장)
+/// - 해제 시 buddy(`idx ^ (1 << k)`)가 같은 order의 free 블록이면 병합
 pub struct FrameAllocator {
     /// 관리 영역 시작 주소
     base: usize,
     /// 총 페이지 수
     total_pages: usize,
-    /// 비트맵 시작 주소 (관리 영역 내)
-    bitmap: *mut u64,
-    /// 비트맵 크기 (u64 단위)
-    bitmap_len: usize,
-    /// 다음 검색 시작 위치 (최적화)
-    next_search: usize,
-    /// 할당된 페이지 수
+    /// 페이지별 상태 배열 (관리 영역 앞부분에 배치)
+    /// free 블록 헤드이면 해당 order, free 블록 내부이면 PAGE_NOT_HEAD,
+    /// 할당된 페이지이면 PAGE_ALLOCATED
+    page_state: *mut u8,
+    /// 메타데이터가 차지하는 페이지 수
+    meta_pages: usize,
+    /// order별 free list 헤드 (페이지 인덱스)
+    free_lists: [usize; NR_ORDERS],
+    /// order별 free 블록 수
+    free_blocks: [usize; NR_ORDERS],
+    /// 할당된 페이지 수 (메타데이터/예약 페이지 포함)
     allocated_count: usize,
+    /// 예약 영역이라 free list에 넣지 않은 페이지 수
+    reserved_count: usize,
+    /// `allocated_count`의 최댓값 (high watermark)
+    peak_allocated: usize,
 }
 
 unsafe impl Send for FrameAllocator {}
@@ -38,10 +91,13 @@ impl FrameAllocator {
         Self {
             base: 0,
             total_pages: 0,
-            bitmap: core::ptr::null_mut(),
-            bitmap_len: 0,
-            next_search: 0,
+            page_state: core::ptr::null_mut(),
+            meta_pages: 0,
+            free_lists: [NONE; NR_ORDERS],
+            free_blocks: [0; NR_ORDERS],
             allocated_count: 0,
+            reserved_count: 0,
+            peak_allocated: 0,
         }
     }
 
@@ -50,63 +106,69 @@ impl FrameAllocator {
     /// # Arguments
     /// * `base` - 관리할 메모리 영역 시작 주소
     /// * `size` - 관리할 메모리 영역 크기
-    pub unsafe fn init(&mut self, base: usize, size: usize) {
+    /// * `reserved` - 할당하지 않을 영역 (페이지 정렬, 메타데이터와 겹치지 않아야 함)
+    pub unsafe fn init(&mut self, base: usize, size: usize, reserved: &[MemoryRegion]) {
         // 페이지 정렬 확인
         assert!(base % PAGE_SIZE == 0, "Base address must be page-aligned");
 
         let total_pages = size / PAGE_SIZE;
 
-        // 비트맵 크기 계산 (1비트 = 1페이지, u64 = 64페이지)
-        let bitmap_bits = total_pages;
-        let bitmap_u64s = (bitmap_bits + 63) / 64;
-        let bitmap_bytes = bitmap_u64s * 8;
-        let bitmap_pages = (bitmap_bytes + PAGE_SIZE - 1) / PAGE_SIZE;
-
-        // 비트맵은 관리 영역 시작에 배치
-        let bitmap_ptr = base as *mut u64;
-
-        // 비트맵 초기화 (모든 페이지를 사용 가능으로)
-        for i in 0..bitmap_u64s {
-            unsafe { bitmap_ptr.add(i).write_volatile(0) };
-        }
-
-        // 비트맵 자체가 사용하는 페이지들을 할당됨으로 표시
-        for page_idx in 0..bitmap_pages {
-            let word_idx = page_idx / 64;
-            let bit_idx = page_idx % 64;
-            unsafe {
-                let word = bitmap_ptr.add(word_idx).read_volatile();
-                bitmap_ptr
-                    .add(word_idx)
-                    .write_volatile(word | (1u64 << bit_idx));
-            }
-        }
+        // 페이지 상태 배열 크기 계산 (1바이트 = 1페이지)
+        let meta_pages = (total_pages + PAGE_SIZE - 1) / PAGE_SIZE;
+
+        // 상태 배열은 관리 영역 시작에 배치, 모든 페이지를 할당됨으로 초기화
+        let state_ptr = base as *mut u8;
+        unsafe { core::ptr::write_bytes(state_ptr, PAGE_ALLOCATED, total_pages) };
 
         self.base = base;
         self.total_pages = total_pages;
-        self.bitmap = bitmap_ptr;
-        self.bitmap_len = bitmap_u64s;
-        self.next_search = bitmap_pages; // 비트맵 이후부터 검색 시작
-        self.allocated_count = bitmap_pages;
+        self.page_state = state_ptr;
+        self.meta_pages = meta_pages;
+        self.free_lists = [NONE; NR_ORDERS];
+        self.free_blocks = [0; NR_ORDERS];
+        self.allocated_count = total_pages;
+        self.reserved_count = 0;
+
+        // 메타데이터 이후 영역 중 예약 영역을 뺀 구간들을 free list에 등록
+        let end = base + total_pages * PAGE_SIZE;
+        let page_of = |addr: usize| (addr.clamp(base, end) - base) / PAGE_SIZE;
+        let mut idx = meta_pages;
+        while idx < total_pages {
+            let addr = base + idx * PAGE_SIZE;
+            // 예약 영역 안이면 그 끝으로 건너뜀
+            if let Some(region) = reserved.iter().find(|r| r.overlaps(addr, addr + PAGE_SIZE)) {
+                let next = page_of(region.end()).max(idx + 1);
+                self.reserved_count += next - idx;
+                idx = next;
+                continue;
+            }
+            // 다음 예약 영역 시작까지 해제
+            let next = reserved
+                .iter()
+                .filter(|r| r.base > addr)
+                .map(|r| page_of(r.base))
+                .min()
+                .unwrap_or(total_pages);
+            self.free_range(idx, next - idx);
+            idx = next;
+        }
+        self.peak_allocated = self.allocated_count;
 
         kprintln!(
-            "[PageAlloc] Initialized: {} pages ({} MB), bitmap uses {} pages",
+            "[PageAlloc] Initialized: {} pages ({} MB) at {:#x}, buddy metadata uses {} pages, {} pages reserved",
             total_pages,
             (total_pages * PAGE_SIZE) / (1024 * 1024),
-            bitmap_pages
+            base,
+            meta_pages,
+            self.reserved_count
         );
     }
 
-    /// 단일 페이지 프레임 할당
-    ///
-    /// # Returns
-    /// 할당된 페이지의 물리 주소, 실패 시 None
-    pub fn alloc(&mut self) -> Option<usize> {
-        self.alloc_pages(1)
-    }
-
     /// 연속 페이지 프레임 할당
     ///
+    /// `count`를 담는 최소 order 블록을 할당한 뒤 남는 꼬리 페이지는 즉시 반환하므로
+    /// 정확히 `count`개 페이지만 할당됩니다.
+    ///
     /// # Arguments
     /// * `count` - 할당할 페이지 수
     ///
@@ -117,58 +179,75 @@ impl FrameAllocator {
             return None;
         }
 
-        // First-fit 검색
-        let mut start_page = self.next_search;
-        let mut consecutive = 0;
-        let mut first_page = 0;
+        let order = order_for(count);
+        if order > MAX_ORDER {
+            return None;
+        }
 
-        for _ in 0..self.total_pages {
-            if start_page >= self.total_pages {
-                start_page = 0;
-                consecutive = 0;
-            }
+        let addr = self.take_block(order)?;
+        let idx = (addr - self.base) / PAGE_SIZE;
+        let excess = (1 << order) - count;
+        if excess > 0 {
+            self.free_range(idx + count, excess);
+        }
+        self.peak_allocated = self.peak_allocated.max(self.allocated_count);
+        Some(addr)
+    }
 
-            if self.is_page_free(start_page) {
-                if consecutive == 0 {
-                    first_page = start_page;
-                }
-                consecutive += 1;
+    /// order 블록 (2^order 페이지) 할당
+    pub fn alloc_order(&mut self, order: usize) -> Option<usize> {
+        let addr = self.take_block(order)?;
+        self.peak_allocated = self.peak_allocated.max(self.allocated_count);
+        Some(addr)
+    }
 
-                if consecutive == count {
-                    // 연속 페이지 찾음, 할당
-                    for i in 0..count {
-                        self.mark_allocated(first_page + i);
-                    }
-                    self.allocated_count += count;
-                    self.next_search = first_page + count;
+    /// free list에서 order 블록을 떼어냄 (최댓값 갱신은 호출자가)
+    fn take_block(&mut self, order: usize) -> Option<usize> {
+        if order > MAX_ORDER {
+            return None;
+        }
 
-                    return Some(self.base + first_page * PAGE_SIZE);
-                }
-            } else {
-                consecutive = 0;
-            }
+        // 요청 order 이상에서 가장 작은 free 블록 찾기
+        let mut current = (order..NR_ORDERS).find(|&k| self.free_lists[k] != NONE)?;
+        let idx = self.free_lists[current];
+        self.list_remove(idx, current);
 
-            start_page += 1;
+        // 필요한 크기가 될 때까지 반으로 나누고 뒷쪽 buddy는 free list로
+        while current > order {
+            current -= 1;
+            self.list_push(idx + (1 << current), current);
         }
 
-        None
+        self.set_states(idx, 1 << order, PAGE_ALLOCATED);
+        self.allocated_count += 1 << order;
+        Some(self.base + idx * PAGE_SIZE)
     }
 
-    /// 페이지 프레임 해제
-    ///
-    /// # Arguments
-    /// * `addr` - 해제할 페이지의 물리 주소
+    /// order 블록 해제 (buddy 병합)
     ///
     /// # Safety
-    /// `addr`은 이전에 alloc으로 할당된 주소여야 함
-    pub unsafe fn free(&mut self, addr: usize) {
-        unsafe {
-            self.free_pages(addr, 1);
+    /// `addr`은 같은 order로 할당된 블록이어야 함
+    pub unsafe fn free_order(&mut self, addr: usize, order: usize) {
+        if addr < self.base || order > MAX_ORDER {
+            return;
+        }
+        let idx = (addr - self.base) / PAGE_SIZE;
+        if idx + (1 << order) > self.total_pages {
+            return;
         }
+        if (idx..idx + (1 << order)).any(|i| self.state(i) != PAGE_ALLOCATED) {
+            kprintln!("[PageAlloc] WARNING: double free at {:#x}", addr);
+            return;
+        }
+
+        self.allocated_count = self.allocated_count.saturating_sub(1 << order);
+        self.free_block(idx, order);
     }
 
     /// 연속 페이지 프레임 해제
     ///
+    /// 정렬된 최대 블록 단위로 나누어 해제하며, 각 블록은 buddy와 병합됩니다.
+    ///
     /// # Arguments
     /// * `addr` - 해제할 첫 페이지의 물리 주소
     /// * `count` - 해제할 페이지 수
@@ -177,60 +256,128 @@ impl FrameAllocator {
             return;
         }
 
-        let page_idx = (addr - self.base) / PAGE_SIZE;
-
-        for i in 0..count {
-            if page_idx + i < self.total_pages {
-                self.mark_free(page_idx + i);
-                self.allocated_count = self.allocated_count.saturating_sub(1);
-            }
+        let mut idx = (addr - self.base) / PAGE_SIZE;
+        let end = core::cmp::min(idx + count, self.total_pages);
+        while idx < end {
+            let order = block_order(idx, end - idx);
+            unsafe { self.free_order(self.base + idx * PAGE_SIZE, order) };
+            idx += 1 << order;
         }
+    }
 
-        // 다음 검색을 해제된 위치부터 시작
-        if page_idx < self.next_search {
-            self.next_search = page_idx;
+    /// 페이지 범위를 정렬된 최대 블록 단위로 free list에 추가
+    fn free_range(&mut self, start: usize, count: usize) {
+        let mut idx = start;
+        let end = start + count;
+        while idx < end {
+            let order = block_order(idx, end - idx);
+            self.allocated_count = self.allocated_count.saturating_sub(1 << order);
+            self.free_block(idx, order);
+            idx += 1 << order;
         }
     }
 
-    /// 페이지가 사용 가능한지 확인
-    fn is_page_free(&self, page_idx: usize) -> bool {
-        if page_idx >= self.total_pages {
-            return false;
+    /// 블록을 buddy와 병합하며 free list에 추가
+    fn free_block(&mut self, mut idx: usize, mut order: usize) {
+        self.set_states(idx, 1 << order, PAGE_NOT_HEAD);
+        while order < MAX_ORDER {
+            let buddy = idx ^ (1 << order);
+            if buddy + (1 << order) > self.total_pages || self.state(buddy) != order as u8 {
+                break;
+            }
+            self.list_remove(buddy, order);
+            idx = core::cmp::min(idx, buddy);
+            order += 1;
         }
+        self.list_push(idx, order);
+    }
 
-        let word_idx = page_idx / 64;
-        let bit_idx = page_idx % 64;
+    /// 페이지 상태 읽기
+    #[inline]
+    fn state(&self, idx: usize) -> u8 {
+        unsafe { self.page_state.add(idx).read_volatile() }
+    }
 
-        unsafe {
-            let word = self.bitmap.add(word_idx).read_volatile();
-            (word & (1u64 << bit_idx)) == 0
-        }
+    /// 페이지 상태 쓰기
+    #[inline]
+    fn set_state(&mut self, idx: usize, state: u8) {
+        unsafe { self.page_state.add(idx).write_volatile(state) };
     }
 
-    /// 페이지를 할당됨으로 표시
-    fn mark_allocated(&mut self, page_idx: usize) {
-        let word_idx = page_idx / 64;
-        let bit_idx = page_idx % 64;
+    /// 연속 페이지 상태 쓰기
+    #[inline]
+    fn set_states(&mut self, idx: usize, count: usize, state: u8) {
+        unsafe { core::ptr::write_bytes(self.page_state.add(idx), state, count) };
+    }
 
+    /// free 블록 헤드의 리스트 노드
+    #[inline]
+    fn node(&self, idx: usize) -> *mut FreeBlock {
+        (self.base + idx * PAGE_SIZE) as *mut FreeBlock
+    }
+
+    /// order 리스트 앞에 블록 추가
+    fn list_push(&mut self, idx: usize, order: usize) {
+        let head = self.free_lists[order];
         unsafe {
-            let word = self.bitmap.add(word_idx).read_volatile();
-            self.bitmap
-                .add(word_idx)
-                .write_volatile(word | (1u64 << bit_idx));
+            self.node(idx).write(FreeBlock { next: head, prev: NONE });
+            if head != NONE {
+                (*self.node(head)).prev = idx;
+            }
         }
+        self.free_lists[order] = idx;
+        self.free_blocks[order] += 1;
+        self.set_state(idx, order as u8);
     }
 
-    /// 페이지를 사용 가능으로 표시
-    fn mark_free(&mut self, page_idx: usize) {
-        let word_idx = page_idx / 64;
-        let bit_idx = page_idx % 64;
+    /// order 리스트에서 블록 제거
+    fn list_remove(&mut self, idx: usize, order: usize) {
+        let FreeBlock { next, prev } = unsafe { self.node(idx).read() };
+        if prev != NONE {
+            unsafe { (*self.node(prev)).next = next };
+        } else {
+            self.free_lists[order] = next;
+        }
+        if next != NONE {
+            unsafe { (*self.node(next)).prev = prev };
+        }
+        self.free_blocks[order] -= 1;
+        self.set_state(idx, PAGE_NOT_HEAD);
+    }
 
-        unsafe {
-            let word = self.bitmap.add(word_idx).read_volatile();
-            self.bitmap
-                .add(word_idx)
-                .write_volatile(word & !(1u64 << bit_idx));
+    /// 관리 영역 `[base, end)`
+    pub fn range(&self) -> (usize, usize) {
+        (self.base, self.base + self.total_pages * PAGE_SIZE)
+    }
+
+    /// 관리 영역 안의 주소인지
+    pub fn contains(&self, addr: usize) -> bool {
+        let (start, end) = self.range();
+        addr >= start && addr < end
+    }
+
+    /// 가장 긴 연속 free 페이지 수
+    ///
+    /// buddy가 아니라 병합되지 않은 이웃 free 블록도 이어서 셉니다. 상태 배열을 훑으므로
+    /// 통계 출력용입니다.
+    pub fn largest_free_run(&self) -> usize {
+        let mut best = 0;
+        let mut run = 0;
+        let mut idx = self.meta_pages;
+        while idx < self.total_pages {
+            match self.state(idx) {
+                PAGE_NOT_HEAD | PAGE_ALLOCATED => {
+                    run = 0;
+                    idx += 1;
+                }
+                order => {
+                    run += 1 << order;
+                    best = best.max(run);
+                    idx += 1 << order;
+                }
+            }
         }
+        best
     }
 
     /// 통계 정보 반환
@@ -239,53 +386,423 @@ impl FrameAllocator {
             total_pages: self.total_pages,
             allocated_pages: self.allocated_count,
             free_pages: self.total_pages.saturating_sub(self.allocated_count),
+            reserved_pages: self.reserved_count,
+            cached_pages: 0,
+            free_blocks: self.free_blocks,
+            peak_allocated_pages: self.peak_allocated,
+            largest_free_run: self.largest_free_run(),
+            failed_allocs: [0; NR_ORDERS],
         }
     }
 }
 
+/// 프레임 풀 최대 개수 (RAM 뱅크 수)
+pub const MAX_POOLS: usize = 8;
+
+/// RAM 뱅크별 buddy 풀 묶음
+///
+/// 모든 풀이 전역 락 하나를 공유합니다 (NUMA 노드 구분 없이 뱅크만 나눔).
+pub struct FramePools {
+    pools: [FrameAllocator; MAX_POOLS],
+    count: usize,
+    /// 전체 할당 페이지 수의 최댓값 (풀별 최댓값의 합과 다름)
+    peak_allocated: usize,
+}
+
+impl FramePools {
+    pub const fn new() -> Self {
+        Self {
+            pools: [const { FrameAllocator::new() }; MAX_POOLS],
+            count: 0,
+            peak_allocated: 0,
+        }
+    }
+
+    /// 할당 뒤 전체 최댓값 갱신
+    fn note_alloc(&mut self, addr: Option<usize>) -> Option<usize> {
+        if addr.is_some() {
+            self.update_peak();
+        }
+        addr
+    }
+
+    fn update_peak(&mut self) {
+        let allocated = self.pools[..self.count].iter().map(|p| p.allocated_count).sum();
+        self.peak_allocated = self.peak_allocated.max(allocated);
+    }
+
+    /// 등록된 풀
+    fn active(&mut self) -> &mut [FrameAllocator] {
+        &mut self.pools[..self.count]
+    }
+
+    /// 주소가 속한 풀
+    fn pool_of(&mut self, addr: usize) -> Option<&mut FrameAllocator> {
+        self.pools[..self.count].iter_mut().find(|p| p.contains(addr))
+    }
+
+    /// 단일 페이지 프레임 할당
+    ///
+    /// # Returns
+    /// 할당된 페이지의 물리 주소, 실패 시 None
+    pub fn alloc(&mut self) -> Option<usize> {
+        self.alloc_order(0)
+    }
+
+    /// 연속 페이지 프레임 할당 (한 풀 안에서만 연속)
+    pub fn alloc_pages(&mut self, count: usize) -> Option<usize> {
+        let addr = self.active().iter_mut().find_map(|p| p.alloc_pages(count));
+        self.note_alloc(addr)
+    }
+
+    /// order 블록 할당 (앞 풀부터 시도)
+    pub fn alloc_order(&mut self, order: usize) -> Option<usize> {
+        let addr = self.active().iter_mut().find_map(|p| p.alloc_order(order));
+        self.note_alloc(addr)
+    }
+
+    /// order 블록 해제
+    ///
+    /// # Safety
+    /// `addr`은 같은 order로 할당된 블록이어야 함
+    pub unsafe fn free_order(&mut self, addr: usize, order: usize) {
+        match self.pool_of(addr) {
+            Some(pool) => unsafe { pool.free_order(addr, order) },
+            None => kprintln!("[PageAlloc] WARNING: free of unmanaged frame {:#x}", addr),
+        }
+    }
+
+    /// 페이지 프레임 해제
+    ///
+    /// # Safety
+    /// `addr`은 이전에 alloc으로 할당된 주소여야 함
+    pub unsafe fn free(&mut self, addr: usize) {
+        unsafe { self.free_order(addr, 0) };
+    }
+
+    /// 연속 페이지 프레임 해제
+    ///
+    /// # Safety
+    /// `addr`부터 `count` 페이지는 이전에 할당된 영역이어야 함
+    pub unsafe fn free_pages(&mut self, addr: usize, count: usize) {
+        match self.pool_of(addr) {
+            Some(pool) => unsafe { pool.free_pages(addr, count) },
+            None => kprintln!("[PageAlloc] WARNING: free of unmanaged frames {:#x}", addr),
+        }
+    }
+
+    /// 전체 풀 합계 통계 (연속 free 구간은 풀 사이에 이어지지 않으므로 최댓값)
+    pub fn stats(&self) -> FrameAllocatorStats {
+        let mut total = FrameAllocatorStats {
+            peak_allocated_pages: self.peak_allocated,
+            ..FrameAllocatorStats::default()
+        };
+        for pool in &self.pools[..self.count] {
+            let stats = pool.stats();
+            total.total_pages += stats.total_pages;
+            total.allocated_pages += stats.allocated_pages;
+            total.free_pages += stats.free_pages;
+            total.reserved_pages += stats.reserved_pages;
+            total.largest_free_run = total.largest_free_run.max(stats.largest_free_run);
+            for (sum, n) in total.free_blocks.iter_mut().zip(stats.free_blocks) {
+                *sum += n;
+            }
+        }
+        total
+    }
+}
+
+/// `count` 페이지를 담는 최소 order
+fn order_for(count: usize) -> usize {
+    count.next_power_of_two().trailing_zeros() as usize
+}
+
+/// `idx`에서 시작해 `remaining` 페이지 안에 들어가는 최대 정렬 블록 order
+fn block_order(idx: usize, remaining: usize) -> usize {
+    let align_order = if idx == 0 { MAX_ORDER } else { idx.trailing_zeros() as usize };
+    let size_order = (usize::BITS - 1 - remaining.leading_zeros()) as usize;
+    align_order.min(size_order).min(MAX_ORDER)
+}
+
 /// 프레임 할당자 통계
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct FrameAllocatorStats {
     pub total_pages: usize,
     pub allocated_pages: usize,
     pub free_pages: usize,
+    /// 예약 영역 페이지 수 (allocated_pages에 포함)
+    pub reserved_pages: usize,
+    /// CPU별 매거진에 보관 중인 페이지 수 (allocated_pages에 포함)
+    pub cached_pages: usize,
+    /// order별 free 블록 수
+    pub free_blocks: [usize; MAX_ORDER + 1],
+    /// allocated_pages의 최댓값 (high watermark)
+    pub peak_allocated_pages: usize,
+    /// 가장 긴 연속 free 페이지 수
+    pub largest_free_run: usize,
+    /// 요청 order별로 호출자에게 돌아간 할당 실패 수 (OOM 회수 뒤, 결함 주입 포함,
+    /// `MAX_ORDER`보다 큰 요청은 마지막 칸, 전체 통계에만)
+    pub failed_allocs: [usize; MAX_ORDER + 1],
 }
 
 impl FrameAllocatorStats {
+    /// order 블록 하나로 `1 << order` 페이지를 받을 수 없는 free 페이지 비율 (퍼센트)
+    ///
+    /// order보다 작은 free 블록에 흩어진 페이지의 비율로, Linux의 unusable free space
+    /// index와 같습니다. free 페이지가 없으면 0입니다.
+    pub fn unusable_percent(&self, order: usize) -> usize {
+        let free: usize = self.free_blocks.iter().enumerate().map(|(k, n)| n << k).sum();
+        if free == 0 {
+            return 0;
+        }
+        let unusable: usize = self.free_blocks[..order.min(NR_ORDERS)].iter().enumerate().map(|(k, n)| n << k).sum();
+        unusable * 100 / free
+    }
+
+    /// 전체 할당 실패 수
+    pub fn total_failures(&self) -> usize {
+        self.failed_allocs.iter().sum()
+    }
+
     pub fn dump(&self) {
         kprintln!(
-            "[PageAlloc] Stats: total={}, allocated={}, free={} ({} MB free)",
+            "[PageAlloc] Stats: total={}, allocated={}, free={} ({} MB free), reserved={}, cached={}",
             self.total_pages,
             self.allocated_pages,
             self.free_pages,
-            (self.free_pages * PAGE_SIZE) / (1024 * 1024)
+            (self.free_pages * PAGE_SIZE) / (1024 * 1024),
+            self.reserved_pages,
+            self.cached_pages
         );
+        kprintln!(
+            "[PageAlloc] Peak allocated={} ({} MB), largest free run={} pages ({} KB), failures={}",
+            self.peak_allocated_pages,
+            (self.peak_allocated_pages * PAGE_SIZE) / (1024 * 1024),
+            self.largest_free_run,
+            self.largest_free_run * PAGE_SIZE / 1024,
+            self.total_failures()
+        );
+        kprintln!("[PageAlloc]   order  block   free blocks  free pages  unusable  failures");
+        for order in 0..NR_ORDERS {
+            kprintln!(
+                "[PageAlloc]   {:>5}  {:>5}K  {:>11}  {:>10}  {:>7}%  {:>8}",
+                order,
+                (PAGE_SIZE << order) / 1024,
+                self.free_blocks[order],
+                self.free_blocks[order] << order,
+                self.unusable_percent(order),
+                self.failed_allocs[order]
+            );
+        }
+    }
+}
+
+/// 전역 프레임 할당자 (RAM 뱅크별 풀)
+static FRAME_ALLOCATOR: Mutex<FramePools> = Mutex::new(FramePools::new());
+
+/// 요청 order별 할당 실패 수 (호출자에게 None을 돌려준 횟수)
+static ALLOC_FAILURES: [AtomicUsize; NR_ORDERS] = [const { AtomicUsize::new(0) }; NR_ORDERS];
+
+// ============================================================================
+// CPU별 프레임 매거진
+// ============================================================================
+
+/// 매거진 용량 (프레임 수)
+const MAGAZINE_SIZE: usize = 32;
+
+/// 전역 할당자와 한 번에 주고받는 프레임 수
+const MAGAZINE_BATCH: usize = MAGAZINE_SIZE / 2;
+
+/// CPU별 프레임 캐시
+struct FrameMagazine {
+    frames: [usize; MAGAZINE_SIZE],
+    count: usize,
+}
+
+impl FrameMagazine {
+    const fn new() -> Self {
+        Self {
+            frames: [0; MAGAZINE_SIZE],
+            count: 0,
+        }
+    }
+
+    /// 전역 할당자에서 배치 단위로 채움 (전역 락 1회)
+    fn refill(&mut self) {
+        let mut global = FRAME_ALLOCATOR.lock();
+        while self.count < MAGAZINE_BATCH {
+            match global.alloc() {
+                Some(frame) => {
+                    self.frames[self.count] = frame;
+                    self.count += 1;
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// 배치 단위로 전역 할당자에 반환 (전역 락 1회)
+    fn flush(&mut self, keep: usize) {
+        let mut global = FRAME_ALLOCATOR.lock();
+        while self.count > keep {
+            self.count -= 1;
+            unsafe { global.free(self.frames[self.count]) };
+        }
+    }
+}
+
+/// CPU별 매거진
+///
+/// 같은 CPU의 다른 스레드가 선점 중에 잡고 있을 수 있으므로 락으로 보호하며,
+/// 락을 얻지 못하면 전역 할당자로 바로 폴백합니다.
+static MAGAZINES: [Spinlock<FrameMagazine>; MAX_CPUS] =
+    [const { Spinlock::new(FrameMagazine::new()) }; MAX_CPUS];
+
+/// CPU별 매거진 사용 여부
+static MAGAZINES_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// 현재 CPU의 매거진 인덱스
+#[inline]
+fn magazine_index() -> usize {
+    percpu::get_cpu_id() as usize % MAX_CPUS
+}
+
+/// CPU별 매거진 사용 여부 설정
+///
+/// 비활성화하면 모든 매거진의 프레임을 전역 할당자로 반환합니다.
+pub fn set_percpu_cache(enabled: bool) {
+    MAGAZINES_ENABLED.store(enabled, Ordering::SeqCst);
+    if !enabled {
+        drain_percpu_caches();
     }
 }
 
-/// 전역 프레임 할당자
-static FRAME_ALLOCATOR: Mutex<FrameAllocator> = Mutex::new(FrameAllocator::new());
+/// CPU별 매거진 사용 여부
+pub fn percpu_cache_enabled() -> bool {
+    MAGAZINES_ENABLED.load(Ordering::Relaxed)
+}
+
+/// 모든 매거진의 프레임을 전역 할당자로 반환
+pub fn drain_percpu_caches() {
+    for mag in MAGAZINES.iter() {
+        mag.lock().flush(0);
+    }
+}
 
-/// 프레임 할당자 초기화
-pub fn init(base: usize, size: usize) -> Result<(), &'static str> {
+/// 잡혀 있지 않은 매거진의 프레임을 전역 할당자로 반환 (반환한 프레임 수)
+///
+/// OOM 경로용입니다. 같은 CPU에서 선점된 스레드가 잡고 있을 수 있으므로 기다리지 않습니다.
+pub fn reclaim_percpu_caches() -> usize {
+    let mut freed = 0;
+    for mag in MAGAZINES.iter() {
+        if let Some(mut mag) = mag.try_lock() {
+            freed += mag.count;
+            mag.flush(0);
+        }
+    }
+    freed
+}
+
+/// 매거진에 보관 중인 총 프레임 수
+fn cached_frames() -> usize {
+    MAGAZINES.iter().map(|mag| mag.lock().count).sum()
+}
+
+/// 프레임 풀 추가 (RAM 뱅크마다 한 번, 주 뱅크 먼저)
+///
+/// `reserved`와 겹치는 페이지는 할당하지 않습니다. 관리 영역 앞의 페이지 상태
+/// 배열이 예약 영역과 겹치면 관리 영역 시작을 그 뒤로 옮깁니다.
+pub fn add_pool(base: usize, size: usize, reserved: &[MemoryRegion]) -> Result<(), &'static str> {
+    let end = base + size;
+    let mut base = base;
+    loop {
+        let meta_end = base + (end.saturating_sub(base) / PAGE_SIZE).div_ceil(PAGE_SIZE) * PAGE_SIZE;
+        match reserved.iter().find(|r| r.overlaps(base, meta_end)) {
+            Some(region) => base = region.end(),
+            None => break,
+        }
+    }
+    let size = end.saturating_sub(base);
     if size < PAGE_SIZE * 2 {
         return Err("Not enough memory for frame allocator");
     }
 
     let mut allocator = FRAME_ALLOCATOR.lock();
-    unsafe { allocator.init(base, size) };
+    if allocator.count == MAX_POOLS {
+        return Err("Too many frame pools");
+    }
+    let idx = allocator.count;
+    unsafe { allocator.pools[idx].init(base, size, reserved) };
+    allocator.count += 1;
+    allocator.update_peak();
 
     Ok(())
 }
 
+/// 프레임 할당 (실패하면 `reclaim`일 때 OOM 처리 후 한 번 더 시도)
+///
+/// 결함 주입을 먼저 확인하고, 호출자에게 돌려주는 실패는 요청 order별로 셉니다.
+/// 줄 수 없는 크기(0 페이지, `MAX_ORDER` 초과)는 회수하지 않고 실패합니다.
+fn alloc_counted(order: usize, pages: usize, reclaim: bool, alloc: impl Fn() -> Option<usize>) -> Option<usize> {
+    let result = if failinject::should_fail(Point::Frame) || pages == 0 || order > MAX_ORDER {
+        None
+    } else {
+        alloc().or_else(|| if reclaim && super::oom::out_of_memory(pages) { alloc() } else { None })
+    };
+    if result.is_none() {
+        ALLOC_FAILURES[order.min(MAX_ORDER)].fetch_add(1, Ordering::Relaxed);
+    }
+    result
+}
+
 /// 단일 페이지 할당
+///
+/// 현재 CPU의 매거진에서 먼저 꺼내고, 비어 있으면 배치로 채웁니다.
 pub fn alloc_frame() -> Option<usize> {
+    alloc_counted(0, 1, true, take_frame)
+}
+
+/// 단일 페이지 할당 (실패해도 OOM 처리를 하지 않음)
+///
+/// 회수 중에 다시 잡을 수 있는 락(slab 크기 클래스 등)을 잡은 채 할당하는 곳용입니다.
+pub fn alloc_frame_noreclaim() -> Option<usize> {
+    alloc_counted(0, 1, false, take_frame)
+}
+
+/// 매거진 또는 전역 할당자에서 프레임 하나
+fn take_frame() -> Option<usize> {
+    if MAGAZINES_ENABLED.load(Ordering::Relaxed) {
+        if let Some(mut mag) = MAGAZINES[magazine_index()].try_lock() {
+            if mag.count == 0 {
+                mag.refill();
+            }
+            if mag.count > 0 {
+                mag.count -= 1;
+                return Some(mag.frames[mag.count]);
+            }
+        }
+    }
     FRAME_ALLOCATOR.lock().alloc()
 }
 
 /// 연속 페이지 할당
 pub fn alloc_frames(count: usize) -> Option<usize> {
-    FRAME_ALLOCATOR.lock().alloc_pages(count)
+    alloc_counted(order_for(count), count, true, || FRAME_ALLOCATOR.lock().alloc_pages(count))
+}
+
+/// order 블록 할당 (2^order 연속 페이지)
+pub fn alloc_frames_order(order: usize) -> Option<usize> {
+    alloc_counted(order, 1 << order.min(MAX_ORDER), true, || FRAME_ALLOCATOR.lock().alloc_order(order))
+}
+
+/// order 블록 해제
+///
+/// # Safety
+/// `alloc_frames_order(order)`로 받은 주소여야 함
+pub unsafe fn free_frames_order(addr: usize, order: usize) {
+    unsafe {
+        FRAME_ALLOCATOR.lock().free_order(addr, order);
+    }
 }
 
 /// 단일 페이지 해제
@@ -293,6 +810,17 @@ pub fn alloc_frames(count: usize) -> Option<usize> {
 /// # Safety
 /// 유효한 주소여야 함
 pub unsafe fn free_frame(addr: usize) {
+    if MAGAZINES_ENABLED.load(Ordering::Relaxed) {
+        if let Some(mut mag) = MAGAZINES[magazine_index()].try_lock() {
+            if mag.count == MAGAZINE_SIZE {
+                mag.flush(MAGAZINE_SIZE - MAGAZINE_BATCH);
+            }
+            let idx = mag.count;
+            mag.frames[idx] = addr;
+            mag.count += 1;
+            return;
+        }
+    }
     unsafe {
         FRAME_ALLOCATOR.lock().free(addr);
     }
@@ -310,12 +838,43 @@ pub unsafe fn free_frames(addr: usize, count: usize) {
 
 /// 할당자 통계 반환
 pub fn stats() -> FrameAllocatorStats {
-    FRAME_ALLOCATOR.lock().stats()
+    let mut stats = FRAME_ALLOCATOR.lock().stats();
+    stats.cached_pages = cached_frames();
+    for (n, count) in stats.failed_allocs.iter_mut().zip(ALLOC_FAILURES.iter()) {
+        *n = count.load(Ordering::Relaxed);
+    }
+    stats
+}
+
+/// 풀별 통계 (`[start, end)`, 통계)
+pub fn pool_stats() -> Vec<(usize, usize, FrameAllocatorStats)> {
+    let allocator = FRAME_ALLOCATOR.lock();
+    allocator.pools[..allocator.count]
+        .iter()
+        .map(|pool| {
+            let (start, end) = pool.range();
+            (start, end, pool.stats())
+        })
+        .collect()
 }
 
 /// 할당자 통계 출력
 pub fn dump_stats() {
     stats().dump();
+    for (i, (start, end, pool)) in pool_stats().iter().enumerate() {
+        kprintln!(
+            "[PageAlloc]   pool {}: {:#x} - {:#x}, total={}, free={} ({} MB free), reserved={}, peak={}, largest run={}",
+            i,
+            start,
+            end,
+            pool.total_pages,
+            pool.free_pages,
+            (pool.free_pages * PAGE_SIZE) / (1024 * 1024),
+            pool.reserved_pages,
+            pool.peak_allocated_pages,
+            pool.largest_free_run
+        );
+    }
 }
 
 /// 할당자 통계 출력 (별칭)
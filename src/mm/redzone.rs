@@ -0,0 +1,236 @@
+//! 힙 레드존 (`heap_redzone` feature)
+//!
+//! 전역 할당자(`mm::heap`)를 거치는 할당마다 앞뒤에 카나리 바이트(`REDZONE_BYTE`)를 두고,
+//! 해제할 때 확인해 범위를 넘은 쓰기(overflow/underflow)를 잡습니다. 앞 레드존 바로 앞의
+//! 헤더에 크기, 할당 위치, 상태를 남겨 두 번 해제(double free)와 할당하지 않은 주소의
+//! 해제도 잡습니다. 해제한 데이터는 포이즈닝 설정과 관계없이 항상 `FREE_POISON`으로
+//! 채웁니다.
+//!
+//! ```text
+//! 블록 시작                                      사용자 포인터
+//! |  (정렬 여백)  |  Header  |  앞 레드존 (16B)  |  데이터 (size)  |  뒤 레드존 (16B)  |
+//! ```
+//!
+//! 오류를 찾으면 크기, 소유자(할당 위치가 속한 모듈, 없으면 kernel), 할당 위치를 출력하고
+//! 오류 수(`errors()`)를 올립니다. 두 번 해제와 잘못된 해제는 블록을 반환하지 않습니다
+//! (이미 다른 할당에 쓰이고 있을 수 있음). 해제한 블록이 같은 크기로 다시 할당되면 헤더가
+//! 새로 쓰이므로, 오래된 포인터를 그 뒤에 해제하면 두 번 해제로 잡히지 않습니다.
+//!
+//! `test_runner` feature가 이 feature를 켜고, 테스트 모듈마다 오류 수를 확인합니다.
+//! 호출 위치는 프레임 포인터를 따라가므로(`trace::backtrace`) `-C force-frame-pointers=yes`로
+//! 빌드해야 정확합니다.
+
+use core::alloc::Layout;
+use core::ptr;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use super::heap::FREE_POISON;
+use crate::kprintln;
+
+/// 레드존 크기 (앞뒤 각각)
+pub const REDZONE_SIZE: usize = 16;
+
+/// 레드존을 채우는 값
+pub const REDZONE_BYTE: u8 = 0xCC;
+
+/// 기록할 호출 스택 깊이 (할당자 내부 프레임 포함)
+const STACK_DEPTH: usize = 6;
+
+/// 사용 중인 블록 ("KRNZLIVE")
+const LIVE: usize = 0x4B52_4E5A_4C49_5645;
+
+/// 해제한 블록 ("KRNZFREE")
+const FREED: usize = 0x4B52_4E5A_4652_4545;
+
+/// 앞 레드존 바로 앞의 블록 정보
+///
+/// 해제된 블록은 slab free list와 linked_list 힙이 앞 16바이트를 덮으므로, 해제 뒤에도
+/// 읽는 `callers`와 `state`는 뒤쪽에 둡니다.
+#[repr(C)]
+struct Header {
+    /// 요청 크기
+    size: usize,
+    /// 블록 시작에서 사용자 포인터까지
+    offset: usize,
+    /// 할당 위치 (복귀 주소, 0 = 없음)
+    callers: [usize; STACK_DEPTH],
+    /// `LIVE` / `FREED`
+    state: usize,
+}
+
+const HEADER_SIZE: usize = core::mem::size_of::<Header>();
+
+/// 지금까지 찾은 오류 수
+static ERRORS: AtomicUsize = AtomicUsize::new(0);
+
+/// 찾은 오류 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fault {
+    /// 데이터 뒤 레드존이 바뀜
+    Overflow,
+    /// 데이터 앞 레드존이 바뀜
+    Underflow,
+    /// 이미 해제한 블록
+    DoubleFree,
+    /// 헤더가 없는 주소 (할당하지 않았거나 헤더까지 덮임)
+    InvalidFree,
+}
+
+impl Fault {
+    fn name(self) -> &'static str {
+        match self {
+            Fault::Overflow => "heap overflow",
+            Fault::Underflow => "heap underflow",
+            Fault::DoubleFree => "double free",
+            Fault::InvalidFree => "invalid free",
+        }
+    }
+}
+
+/// 지금까지 찾은 오류 수
+pub fn errors() -> usize {
+    ERRORS.load(Ordering::Relaxed)
+}
+
+/// 사용자 `layout`을 담는 블록의 (레이아웃, 사용자 포인터 오프셋)
+fn block_layout(layout: Layout) -> Option<(Layout, usize)> {
+    let align = layout.align().max(core::mem::align_of::<Header>());
+    let offset = (HEADER_SIZE + REDZONE_SIZE).next_multiple_of(align);
+    let size = offset.checked_add(layout.size())?.checked_add(REDZONE_SIZE)?;
+    Some((Layout::from_size_align(size, align).ok()?, offset))
+}
+
+/// 레드존을 둘러 할당 (`alloc_block`은 실제 할당자)
+///
+/// # Safety
+/// `GlobalAlloc::alloc`과 같음
+#[inline(always)]
+pub unsafe fn alloc(layout: Layout, alloc_block: impl FnOnce(Layout) -> *mut u8) -> *mut u8 {
+    let Some((block_layout, offset)) = block_layout(layout) else {
+        return ptr::null_mut();
+    };
+    let block = alloc_block(block_layout);
+    if block.is_null() {
+        return block;
+    }
+    // SAFETY: block은 block_layout 크기이고 offset/레드존은 그 안에 있음
+    unsafe {
+        let user = block.add(offset);
+        ptr::write_bytes(user.sub(REDZONE_SIZE), REDZONE_BYTE, REDZONE_SIZE);
+        ptr::write_bytes(user.add(layout.size()), REDZONE_BYTE, REDZONE_SIZE);
+        header(user).write(Header {
+            size: layout.size(),
+            offset,
+            callers: crate::trace::backtrace::capture::<STACK_DEPTH>(),
+            state: LIVE,
+        });
+        user
+    }
+}
+
+/// 레드존과 상태를 확인하고 해제 (`dealloc_block`은 실제 할당자)
+///
+/// 두 번 해제와 잘못된 해제는 보고만 하고 블록을 반환하지 않습니다.
+///
+/// # Safety
+/// `ptr`은 `alloc`이 돌려준 포인터여야 함 (아니면 보고됨)
+pub unsafe fn dealloc(ptr: *mut u8, layout: Layout, dealloc_block: impl FnOnce(*mut u8, Layout)) {
+    // SAFETY: 정상 할당이면 헤더가 블록 안에 있음. 잘못된 포인터는 state로 걸러짐
+    let header = unsafe { &mut *header(ptr) };
+    match header.state {
+        LIVE => {}
+        FREED => {
+            report(Fault::DoubleFree, ptr, layout.size(), &header.callers, 0);
+            return;
+        }
+        _ => {
+            report(Fault::InvalidFree, ptr, layout.size(), &[], 0);
+            return;
+        }
+    }
+
+    let size = header.size;
+    let Some((block_layout, offset)) = block_layout(layout) else {
+        report(Fault::InvalidFree, ptr, layout.size(), &header.callers, 0);
+        return;
+    };
+    if size != layout.size() || header.offset != offset {
+        report(Fault::InvalidFree, ptr, layout.size(), &header.callers, 0);
+        return;
+    }
+
+    // SAFETY: 레드존은 블록 안
+    let (front, back) = unsafe {
+        (
+            core::slice::from_raw_parts(ptr.sub(REDZONE_SIZE), REDZONE_SIZE),
+            core::slice::from_raw_parts(ptr.add(size), REDZONE_SIZE),
+        )
+    };
+    let corrupted = |zone: &[u8]| zone.iter().filter(|&&b| b != REDZONE_BYTE).count();
+    let (under, over) = (corrupted(front), corrupted(back));
+    if under != 0 {
+        report(Fault::Underflow, ptr, size, &header.callers, under);
+    }
+    if over != 0 {
+        report(Fault::Overflow, ptr, size, &header.callers, over);
+    }
+
+    // SAFETY: 데이터 영역은 블록 안
+    unsafe { ptr::write_bytes(ptr, FREE_POISON, size) };
+    header.state = FREED;
+    // SAFETY: alloc에서 같은 레이아웃으로 받은 블록
+    dealloc_block(unsafe { ptr.sub(offset) }, block_layout);
+}
+
+/// 사용자 포인터의 헤더 위치
+#[inline(always)]
+unsafe fn header(user: *mut u8) -> *mut Header {
+    unsafe { user.sub(REDZONE_SIZE + HEADER_SIZE) as *mut Header }
+}
+
+/// 오류 보고 (`corrupted`는 바뀐 레드존 바이트 수)
+///
+/// 힙을 쓰지 않습니다 (할당자 안에서 호출됨).
+fn report(fault: Fault, ptr: *mut u8, size: usize, callers: &[usize], corrupted: usize) {
+    ERRORS.fetch_add(1, Ordering::Relaxed);
+    let callers = || callers.iter().copied().take_while(|&c| c != 0);
+
+    // 소유자: 할당 위치 중 처음 나오는 모듈, 없으면 커널
+    let mut owner = [0u8; 64];
+    let mut owner_len = 0;
+    for caller in callers() {
+        crate::module::symbol::symbolize(caller, &mut |_, _, module| {
+            if let Some(module) = module {
+                owner_len = module.len().min(owner.len());
+                owner[..owner_len].copy_from_slice(&module.as_bytes()[..owner_len]);
+            }
+        });
+        if owner_len != 0 {
+            break;
+        }
+    }
+    let owner = match core::str::from_utf8(&owner[..owner_len]) {
+        Ok(name) if !name.is_empty() => name,
+        _ => "kernel",
+    };
+
+    if corrupted != 0 {
+        kprintln!(
+            "[heap] BUG: {} at {:p}: {} of {} redzone bytes changed (size {}, owner {})",
+            fault.name(),
+            ptr,
+            corrupted,
+            REDZONE_SIZE,
+            size,
+            owner
+        );
+    } else {
+        kprintln!("[heap] BUG: {} at {:p} (size {}, owner {})", fault.name(), ptr, size, owner);
+    }
+    if callers().next().is_some() {
+        kprintln!("[heap]   allocated from:");
+        for caller in callers() {
+            kprintln!("[heap]     {}", crate::panic::Symbolized(caller));
+        }
+    }
+}
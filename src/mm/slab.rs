@@ -0,0 +1,191 @@
+//! Slab 할당자
+//!
+//! 크기 클래스(32 ~ 4096 바이트)별 캐시로 작은 커널 객체를 할당합니다.
+//! 각 캐시는 페이지 프레임 할당자에서 4KB 페이지를 받아 같은 크기의
+//! 객체로 나누고, 빈 객체는 객체 자체에 다음 포인터를 저장하는
+//! intrusive free list로 관리합니다.
+//!
+//! `#[global_allocator]`(`mm::heap`)가 작은 할당을 먼저 여기로 보내므로
+//! `Thread`, VNode, `Message` 같은 빈번한 할당이 일반 힙을 거치지 않습니다.
+//!
+//! ```text
+//! SlabCache(64B)
+//!   free_list ─► [obj] ─► [obj] ─► [obj] ─► 0
+//!   page 0: | obj0 | obj1 | ... | obj63 |
+//!   page 1: | obj0 | obj1 | ... | obj63 |
+//! ```
+
+use core::alloc::Layout;
+
+use crate::kprintln;
+use crate::sync::Spinlock;
+use super::page::{self, PAGE_SIZE};
+
+/// 크기 클래스 (바이트)
+pub const SIZE_CLASSES: [usize; 8] = [32, 64, 128, 256, 512, 1024, 2048, 4096];
+
+/// 단일 크기 클래스 캐시
+struct SlabCache {
+    /// 객체 크기
+    object_size: usize,
+    /// 빈 객체 리스트 헤드 (0 = 비어 있음)
+    free_list: usize,
+    /// 캐시가 보유한 페이지 수
+    pages: usize,
+    /// 전체 객체 수
+    total_objects: usize,
+    /// 빈 객체 수
+    free_objects: usize,
+    /// 누적 할당 횟수
+    allocs: u64,
+    /// 누적 해제 횟수
+    frees: u64,
+}
+
+impl SlabCache {
+    const fn new(object_size: usize) -> Self {
+        Self {
+            object_size,
+            free_list: 0,
+            pages: 0,
+            total_objects: 0,
+            free_objects: 0,
+            allocs: 0,
+            frees: 0,
+        }
+    }
+
+    /// 새 페이지를 받아 객체로 나눠 free list에 추가
+    fn grow(&mut self) -> bool {
+        // 크기 클래스 락을 잡고 있으므로 회수하지 않음 (실패하면 힙이 회수 후 다시 시도)
+        let page_addr = match page::alloc_frame_noreclaim() {
+            Some(addr) => addr,
+            None => return false,
+        };
+
+        let count = PAGE_SIZE / self.object_size;
+        // 역순으로 push하여 낮은 주소부터 할당되도록 함
+        for i in (0..count).rev() {
+            let obj = page_addr + i * self.object_size;
+            unsafe { (obj as *mut usize).write(self.free_list) };
+            self.free_list = obj;
+        }
+
+        self.pages += 1;
+        self.total_objects += count;
+        self.free_objects += count;
+        true
+    }
+
+    fn alloc(&mut self) -> Option<usize> {
+        if self.free_list == 0 && !self.grow() {
+            return None;
+        }
+
+        let obj = self.free_list;
+        self.free_list = unsafe { (obj as *const usize).read() };
+        self.free_objects -= 1;
+        self.allocs += 1;
+        Some(obj)
+    }
+
+    fn free(&mut self, obj: usize) {
+        unsafe { (obj as *mut usize).write(self.free_list) };
+        self.free_list = obj;
+        self.free_objects += 1;
+        self.frees += 1;
+    }
+
+    fn stats(&self) -> SlabCacheStats {
+        SlabCacheStats {
+            object_size: self.object_size,
+            pages: self.pages,
+            total_objects: self.total_objects,
+            active_objects: self.total_objects - self.free_objects,
+            allocs: self.allocs,
+            frees: self.frees,
+        }
+    }
+}
+
+/// 캐시 통계
+#[derive(Debug, Clone, Copy)]
+pub struct SlabCacheStats {
+    pub object_size: usize,
+    pub pages: usize,
+    pub total_objects: usize,
+    pub active_objects: usize,
+    pub allocs: u64,
+    pub frees: u64,
+}
+
+/// 크기 클래스별 캐시
+static CACHES: [Spinlock<SlabCache>; SIZE_CLASSES.len()] = [
+    Spinlock::new(SlabCache::new(SIZE_CLASSES[0])),
+    Spinlock::new(SlabCache::new(SIZE_CLASSES[1])),
+    Spinlock::new(SlabCache::new(SIZE_CLASSES[2])),
+    Spinlock::new(SlabCache::new(SIZE_CLASSES[3])),
+    Spinlock::new(SlabCache::new(SIZE_CLASSES[4])),
+    Spinlock::new(SlabCache::new(SIZE_CLASSES[5])),
+    Spinlock::new(SlabCache::new(SIZE_CLASSES[6])),
+    Spinlock::new(SlabCache::new(SIZE_CLASSES[7])),
+];
+
+/// 레이아웃에 맞는 크기 클래스 인덱스
+///
+/// 객체는 크기 클래스 배수 오프셋에 놓이므로 align <= 클래스 크기면 정렬이 보장됩니다.
+fn class_index(layout: &Layout) -> Option<usize> {
+    let size = layout.size().max(layout.align());
+    SIZE_CLASSES.iter().position(|&class| size <= class)
+}
+
+/// Slab에서 객체 할당
+///
+/// 크기 클래스에 맞지 않거나 페이지를 얻지 못하면 None (호출자가 일반 힙 사용)
+pub fn alloc(layout: Layout) -> Option<*mut u8> {
+    let idx = class_index(&layout)?;
+    CACHES[idx].lock().alloc().map(|obj| obj as *mut u8)
+}
+
+/// Slab 객체 해제
+///
+/// # Safety
+/// `ptr`은 같은 `layout`으로 `alloc()`에서 받은 주소여야 함
+pub unsafe fn dealloc(ptr: *mut u8, layout: Layout) {
+    if let Some(idx) = class_index(&layout) {
+        CACHES[idx].lock().free(ptr as usize);
+    }
+}
+
+/// 모든 캐시 통계
+pub fn stats() -> [SlabCacheStats; SIZE_CLASSES.len()] {
+    core::array::from_fn(|i| CACHES[i].lock().stats())
+}
+
+/// 사용 중인 객체가 차지하는 바이트 (크기 클래스 기준)
+pub fn used_bytes() -> usize {
+    stats()
+        .iter()
+        .map(|s| s.active_objects * s.object_size)
+        .sum()
+}
+
+/// 캐시 통계 출력
+pub fn dump_stats() {
+    kprintln!("[Slab] Caches:");
+    kprintln!(
+        "  {:>6} {:>6} {:>8} {:>8} {:>10} {:>10}",
+        "SIZE", "PAGES", "ACTIVE", "TOTAL", "ALLOCS", "FREES"
+    );
+    for s in stats().iter() {
+        kprintln!(
+            "  {:>6} {:>6} {:>8} {:>8} {:>10} {:>10}",
+            s.object_size,
+            s.pages,
+            s.active_objects,
+            s.total_objects,
+            s.allocs,
+            s.frees
+        );
+    }
+}
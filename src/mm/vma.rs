@@ -0,0 +1,383 @@
+//! VMA (Virtual Memory Area) 및 Demand Paging
+//!
+//! 가상 주소 영역을 예약만 해두고, 실제 물리 프레임은 첫 접근 시
+//! 페이지 폴트 핸들러에서 할당합니다 (lazy allocation).
+//!
+//! 흐름:
+//! 1. `map_anonymous()`로 VMA 예약 (프레임 할당 없음)
+//! 2. 첫 접근 시 MMU가 translation fault 발생
+//! 3. 아키텍처 예외 핸들러 → `handle_page_fault()`
+//! 4. VMA 범위/권한 확인 후 프레임 할당, 0으로 초기화, 매핑
+//! 5. 예외 복귀 후 같은 명령어 재실행
+//!
+//! VMA에 속하지 않거나 권한이 없는 접근은 `FaultError`를 반환하며,
+//! 예외 핸들러는 커널 패닉 대신 해당 스레드에 SIGSEGV를 걸고 예외에서 빠져나온 뒤 종료합니다.
+//!
+//! 폴트 핸들러는 인터럽트가 꺼진 예외 경로에서 돌므로 VMA 목록은 양보하지 않는
+//! `IrqSpinlock`으로 보호합니다.
+//!
+//! VMA는 만든 스레드가 소유합니다. 스레드가 종료하면 `kreaperd`가 `release_owner()`로
+//! 남은 VMA를 해제하고, OOM 킬러는 소유한 상주 페이지 수로 희생 스레드를 고릅니다.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use crate::kprintln;
+use crate::proc::Tid;
+use crate::sync::IrqSpinlock;
+use super::page::{self, PAGE_SIZE};
+
+/// Demand paging 영역 가상 주소 시작 (64GB)
+///
+/// RAM identity mapping과 겹치지 않는 위치로, aarch64 48비트 VA와
+/// RISC-V Sv39 하위 절반(256GB) 모두에서 유효합니다.
+pub const VMA_AREA_START: usize = 0x10_0000_0000;
+
+/// Demand paging 영역 크기 (64GB)
+pub const VMA_AREA_SIZE: usize = 0x10_0000_0000;
+
+/// VMA 접근 권한 플래그
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VmaFlags(u32);
+
+impl VmaFlags {
+    pub const READ: Self = Self(1 << 0);
+    pub const WRITE: Self = Self(1 << 1);
+    pub const EXEC: Self = Self(1 << 2);
+    pub const RW: Self = Self(Self::READ.0 | Self::WRITE.0);
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+/// 페이지 폴트 접근 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultAccess {
+    Read,
+    Write,
+    Execute,
+}
+
+impl FaultAccess {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FaultAccess::Read => "read",
+            FaultAccess::Write => "write",
+            FaultAccess::Execute => "execute",
+        }
+    }
+}
+
+/// 페이지 폴트 처리 실패 원인
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultError {
+    /// 주소가 어떤 VMA에도 속하지 않음
+    NoVma,
+    /// VMA 권한 위반 (예: 읽기 전용 VMA에 쓰기)
+    AccessViolation,
+    /// 이미 매핑된 페이지에서 폴트 (권한/속성 폴트)
+    AlreadyMapped,
+    /// 물리 프레임 부족
+    OutOfMemory,
+    /// 페이지 테이블 갱신 실패
+    MapFailed,
+}
+
+impl FaultError {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FaultError::NoVma => "address not in any VMA",
+            FaultError::AccessViolation => "access violates VMA permissions",
+            FaultError::AlreadyMapped => "fault on already mapped page",
+            FaultError::OutOfMemory => "out of physical frames",
+            FaultError::MapFailed => "page table update failed",
+        }
+    }
+}
+
+/// 가상 메모리 영역
+#[derive(Debug)]
+pub struct Vma {
+    /// 시작 주소 (페이지 정렬)
+    pub start: usize,
+    /// 끝 주소 (exclusive, 페이지 정렬)
+    pub end: usize,
+    /// 접근 권한
+    pub flags: VmaFlags,
+    /// 디버깅용 이름
+    pub name: String,
+    /// 만든 스레드 (종료하면 해제, 스레드 문맥 밖에서 만들었으면 None)
+    pub owner: Option<Tid>,
+    /// 폴트로 할당된 페이지들 (가상 주소, 물리 주소)
+    resident: Vec<(usize, usize)>,
+}
+
+impl Vma {
+    fn contains(&self, addr: usize) -> bool {
+        addr >= self.start && addr < self.end
+    }
+
+    /// 현재 물리 프레임이 할당된 페이지 수
+    pub fn resident_pages(&self) -> usize {
+        self.resident.len()
+    }
+
+    fn permits(&self, access: FaultAccess) -> bool {
+        match access {
+            FaultAccess::Read => self.flags.contains(VmaFlags::READ),
+            FaultAccess::Write => self.flags.contains(VmaFlags::WRITE),
+            FaultAccess::Execute => self.flags.contains(VmaFlags::EXEC),
+        }
+    }
+}
+
+/// 전역 VMA 목록 (Vec으로 관리, 예외 경로에서 잡으므로 양보하는 `Mutex`를 쓰지 않음)
+static VMAS: IrqSpinlock<Vec<Vma>> = IrqSpinlock::new(Vec::new());
+
+/// 다음 VMA 예약 위치 (bump 방식)
+static NEXT_VMA_ADDR: AtomicUsize = AtomicUsize::new(VMA_AREA_START);
+
+/// 처리된 페이지 폴트 수
+static FAULTS_HANDLED: AtomicU64 = AtomicU64::new(0);
+
+/// 처리 실패한 페이지 폴트 수
+static FAULTS_FAILED: AtomicU64 = AtomicU64::new(0);
+
+/// 익명 메모리 영역 예약
+///
+/// 가상 주소 범위만 예약하고 물리 프레임은 할당하지 않습니다.
+/// 각 페이지는 첫 접근 시 0으로 채워진 프레임이 할당됩니다.
+///
+/// # Returns
+/// 예약된 영역의 시작 가상 주소
+pub fn map_anonymous(size: usize, flags: VmaFlags, name: &str) -> Result<usize, &'static str> {
+    if size == 0 {
+        return Err("VMA size is zero");
+    }
+    let size = (size + PAGE_SIZE - 1) & !(PAGE_SIZE - 1);
+
+    // 영역 사이에 가드 페이지 1개를 두어 오버런을 폴트로 잡음
+    let start = NEXT_VMA_ADDR.fetch_add(size + PAGE_SIZE, Ordering::SeqCst);
+    if start + size > VMA_AREA_START + VMA_AREA_SIZE {
+        return Err("VMA area exhausted");
+    }
+
+    VMAS.lock().push(Vma {
+        start,
+        end: start + size,
+        flags,
+        name: String::from(name),
+        owner: crate::proc::current_tid(),
+        resident: Vec::new(),
+    });
+
+    Ok(start)
+}
+
+/// VMA 해제
+///
+/// 폴트로 할당된 프레임을 매핑 해제하고 반환합니다.
+pub fn unmap(start: usize) -> Result<(), &'static str> {
+    let vma = {
+        let mut vmas = VMAS.lock();
+        let pos = vmas
+            .iter()
+            .position(|v| v.start == start)
+            .ok_or("VMA not found")?;
+        vmas.remove(pos)
+    };
+    release(vma);
+    Ok(())
+}
+
+/// 스레드가 소유한 VMA를 모두 해제 (`kreaperd`가 종료한 스레드마다 호출)
+///
+/// 해제한 상주 페이지 수를 반환합니다.
+pub fn release_owner(tid: Tid) -> usize {
+    let mut freed = 0;
+    loop {
+        let vma = {
+            let mut vmas = VMAS.lock();
+            match vmas.iter().position(|v| v.owner == Some(tid)) {
+                Some(pos) => vmas.remove(pos),
+                None => return freed,
+            }
+        };
+        freed += vma.resident_pages();
+        release(vma);
+    }
+}
+
+/// 폴트로 할당된 프레임을 매핑 해제하고 반환
+fn release(vma: Vma) {
+    for &(virt, phys) in vma.resident.iter() {
+        crate::arch::mmu::unmap_page(virt);
+        unsafe { page::free_frame(phys) };
+    }
+}
+
+/// 스레드가 소유한 VMA의 상주 페이지 수
+///
+/// OOM 경로에서 부르므로 VMA 목록 락을 기다리지 않으며, 얻지 못하면 None입니다.
+pub fn try_owned_pages(tid: Tid) -> Option<usize> {
+    let vmas = VMAS.try_lock()?;
+    Some(vmas.iter().filter(|v| v.owner == Some(tid)).map(Vma::resident_pages).sum())
+}
+
+/// 페이지 폴트 처리
+///
+/// 아키텍처 예외 핸들러에서 translation fault 발생 시 호출됩니다.
+/// 성공하면 예외 복귀 후 폴트를 일으킨 명령어가 재실행됩니다.
+pub fn handle_page_fault(addr: usize, access: FaultAccess) -> Result<(), FaultError> {
+    let result = handle_page_fault_inner(addr, access);
+    match result {
+        Ok(()) => FAULTS_HANDLED.fetch_add(1, Ordering::Relaxed),
+        Err(_) => FAULTS_FAILED.fetch_add(1, Ordering::Relaxed),
+    };
+    result
+}
+
+/// 폴트 주소의 VMA를 찾아 권한 확인 (이미 채워진 페이지면 `Ok(None)`)
+fn check_fault<'a>(vmas: &'a mut [Vma], addr: usize, access: FaultAccess) -> Result<Option<&'a mut Vma>, FaultError> {
+    let page_addr = addr & !(PAGE_SIZE - 1);
+    let vma = vmas
+        .iter_mut()
+        .find(|v| v.contains(addr))
+        .ok_or(FaultError::NoVma)?;
+
+    if !vma.permits(access) {
+        return Err(FaultError::AccessViolation);
+    }
+
+    // 다른 CPU가 먼저 같은 페이지를 채웠을 수 있음
+    if vma.resident.iter().any(|&(v, _)| v == page_addr) {
+        return if crate::arch::mmu::translate(page_addr).is_some() {
+            Ok(None)
+        } else {
+            Err(FaultError::AlreadyMapped)
+        };
+    }
+    Ok(Some(vma))
+}
+
+fn handle_page_fault_inner(addr: usize, access: FaultAccess) -> Result<(), FaultError> {
+    let page_addr = addr & !(PAGE_SIZE - 1);
+
+    if check_fault(&mut VMAS.lock(), addr, access)?.is_none() {
+        return Ok(());
+    }
+
+    // 프레임이 부족하면 OOM 처리가 VMA 목록을 훑으므로 목록 락 밖에서 할당
+    let frame = page::alloc_frame().ok_or(FaultError::OutOfMemory)?;
+    // OOM 킬러가 이 스레드를 골랐으면 폴트를 실패시켜 바로 종료
+    if crate::proc::current_tid().is_some_and(super::oom::is_victim) {
+        unsafe { page::free_frame(frame) };
+        return Err(FaultError::OutOfMemory);
+    }
+    unsafe {
+        core::ptr::write_bytes(frame as *mut u8, 0, PAGE_SIZE);
+    }
+
+    // 락을 놓은 사이 VMA가 해제됐거나 다른 CPU가 같은 페이지를 채웠을 수 있음
+    let mut vmas = VMAS.lock();
+    let vma = match check_fault(&mut vmas, addr, access) {
+        Ok(Some(vma)) => vma,
+        other => {
+            unsafe { page::free_frame(frame) };
+            return other.map(|_| ());
+        }
+    };
+
+    let write = vma.flags.contains(VmaFlags::WRITE);
+    let exec = vma.flags.contains(VmaFlags::EXEC);
+    if crate::arch::mmu::map_page(page_addr, frame, write, exec).is_err() {
+        unsafe { page::free_frame(frame) };
+        return Err(FaultError::MapFailed);
+    }
+
+    vma.resident.push((page_addr, frame));
+    Ok(())
+}
+
+/// 처리 실패한 폴트에 대한 진단 출력 후 현재 스레드에 SIGSEGV를 걸어 둠
+///
+/// 예외 프레임 안에서 스레드를 끝내지 않습니다. 커널 모드 폴트면 아키텍처 핸들러가 복귀
+/// 주소를 `fault_exit`로 바꿔 예외에서 빠져나온 뒤 종료하고, 유저 모드 폴트는 유저로
+/// 돌아가는 대신 핸들러 끝에서 `fault_exit`를 부릅니다. 어느 쪽이든 같은 명령어로 돌아가
+/// 폴트를 되풀이하지 않습니다. `irqs_enabled`는 폴트 시점의 인터럽트 상태입니다.
+///
+/// 다음 폴트는 스레드만 끝내고 넘어갈 수 없으므로 패닉합니다.
+/// - 스레드 문맥이 아님 (스케줄러 초기화 전)
+/// - idle 스레드 (부트 스레드 tid 0과 셸 포함, 시그널로 종료할 수 없음)
+/// - 하반부(태스크릿) 실행 중 (끼어든 스레드와 무관한 코드)
+/// - 인터럽트를 끈 채 (인터럽트 핸들러이거나 `IrqSpinlock`을 잡고 있을 수 있음)
+pub fn kill_faulting_thread(addr: usize, pc: usize, access: FaultAccess, err: FaultError, irqs_enabled: bool) {
+    kprintln!(
+        "\n[MM] Segmentation fault: {} at {:#x} (pc={:#x}): {}",
+        access.as_str(),
+        addr,
+        pc,
+        err.as_str()
+    );
+
+    let critical = if !irqs_enabled {
+        Some("with interrupts disabled")
+    } else if crate::softirq::in_softirq() {
+        Some("in softirq")
+    } else {
+        None
+    };
+    let tid = match (crate::proc::current_tid(), critical) {
+        (Some(tid), None) if !crate::proc::is_idle_thread(tid) => tid,
+        (Some(tid), None) => panic!("Page fault in idle thread {} at {:#x}", tid, addr),
+        (None, _) => panic!("Page fault outside thread context at {:#x}", addr),
+        (Some(_), Some(why)) => panic!("Page fault {} at {:#x}", why, addr),
+    };
+    kprintln!("[MM] Killing thread {}", tid);
+    // SIG_IGN으로 설정한 스레드도 종료 (무시하면 같은 명령어에서 폴트가 되풀이됨)
+    let _ = crate::proc::signal::force(tid, crate::proc::signal::SIGSEGV);
+}
+
+/// 처리할 수 없는 폴트로 SIGSEGV를 받은 스레드를 끝내는 곳
+///
+/// 커널 모드 폴트는 폴트 난 명령어를 다시 실행하지 않도록 아키텍처 핸들러가 복귀 주소를
+/// 여기로 바꾸고 (스택은 폴트 시점 그대로), 유저 모드 폴트는 핸들러가 유저로 돌아가는 대신
+/// 직접 부릅니다. 시그널 처리 결과와 관계없이 돌아가지 않습니다.
+pub extern "C" fn fault_exit() -> ! {
+    use crate::proc::signal::{self, SIGSEGV};
+
+    signal::deliver_pending();
+    crate::proc::exit_with(signal::exit_status(SIGSEGV));
+}
+
+/// VMA 목록 출력
+pub fn dump_vmas() {
+    let vmas = VMAS.lock();
+    kprintln!("VMAs ({}):", vmas.len());
+    for vma in vmas.iter() {
+        kprintln!(
+            "  {:#014x}-{:#014x} {}{}{} resident={}/{} tid={} {}",
+            vma.start,
+            vma.end,
+            if vma.flags.contains(VmaFlags::READ) { 'r' } else { '-' },
+            if vma.flags.contains(VmaFlags::WRITE) { 'w' } else { '-' },
+            if vma.flags.contains(VmaFlags::EXEC) { 'x' } else { '-' },
+            vma.resident_pages(),
+            (vma.end - vma.start) / PAGE_SIZE,
+            vma.owner.map_or(-1, |tid| tid as i64),
+            vma.name
+        );
+    }
+    kprintln!(
+        "Page faults: {} handled, {} failed",
+        FAULTS_HANDLED.load(Ordering::Relaxed),
+        FAULTS_FAILED.load(Ordering::Relaxed)
+    );
+}
+
+/// 처리된 페이지 폴트 수
+pub fn faults_handled() -> u64 {
+    FAULTS_HANDLED.load(Ordering::Relaxed)
+}
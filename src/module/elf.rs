@@ -148,6 +148,7 @@ pub mod section_flags {
     pub const SHF_WRITE: u64 = 0x1; // 쓰기 가능
     pub const SHF_ALLOC: u64 = 0x2; // 메모리 할당 필요
     pub const SHF_EXECINSTR: u64 = 0x4; // 실행 가능
+    pub const SHF_TLS: u64 = 0x400; // 스레드 로컬 (.tdata/.tbss)
 }
 
 /// ELF64 프로그램 헤더 (56바이트)
@@ -180,6 +181,14 @@ pub mod program_type {
     pub const PT_INTERP: u32 = 3; // 인터프리터 경로
     pub const PT_NOTE: u32 = 4; // 노트
     pub const PT_PHDR: u32 = 6; // 프로그램 헤더 테이블
+    pub const PT_TLS: u32 = 7; // TLS 초기화 이미지
+}
+
+/// 프로그램 헤더 플래그
+pub mod program_flags {
+    pub const PF_X: u32 = 0x1; // 실행 가능
+    pub const PF_W: u32 = 0x2; // 쓰기 가능
+    pub const PF_R: u32 = 0x4; // 읽기 가능
 }
 
 /// ELF64 심볼 테이블 엔트리 (24바이트)
@@ -255,6 +264,29 @@ pub mod reloc_aarch64 {
     pub const R_AARCH64_LDST64_ABS_LO12_NC: u32 = 286; // S + A (하위 12비트, 8바이트 정렬)
     pub const R_AARCH64_PREL32: u32 = 261; // S + A - P
     pub const R_AARCH64_PREL64: u32 = 260; // S + A - P
+    pub const R_AARCH64_MOVW_UABS_G0: u32 = 263; // S + A 비트 [15:0] (MOVZ/MOVK, 범위 검사)
+    pub const R_AARCH64_MOVW_UABS_G0_NC: u32 = 264; // S + A 비트 [15:0]
+    pub const R_AARCH64_MOVW_UABS_G1: u32 = 265; // S + A 비트 [31:16] (범위 검사)
+    pub const R_AARCH64_MOVW_UABS_G1_NC: u32 = 266; // S + A 비트 [31:16]
+    pub const R_AARCH64_MOVW_UABS_G2: u32 = 267; // S + A 비트 [47:32] (범위 검사)
+    pub const R_AARCH64_MOVW_UABS_G2_NC: u32 = 268; // S + A 비트 [47:32]
+    pub const R_AARCH64_MOVW_UABS_G3: u32 = 269; // S + A 비트 [63:48]
+    pub const R_AARCH64_LDST8_ABS_LO12_NC: u32 = 278; // S + A (하위 12비트, 1바이트)
+    pub const R_AARCH64_TSTBR14: u32 = 279; // S + A - P (TBZ/TBNZ, ±32KB)
+    pub const R_AARCH64_CONDBR19: u32 = 280; // S + A - P (B.cond/CBZ/CBNZ, ±1MB)
+    pub const R_AARCH64_LDST16_ABS_LO12_NC: u32 = 284; // S + A (하위 12비트, 2바이트 정렬)
+    pub const R_AARCH64_LDST32_ABS_LO12_NC: u32 = 285; // S + A (하위 12비트, 4바이트 정렬)
+    pub const R_AARCH64_LDST128_ABS_LO12_NC: u32 = 299; // S + A (하위 12비트, 16바이트 정렬)
+    pub const R_AARCH64_ADR_GOT_PAGE: u32 = 311; // Page(G(GDAT(S+A))) - Page(P)
+    pub const R_AARCH64_LD64_GOT_LO12_NC: u32 = 312; // G(GDAT(S+A)) 하위 12비트 (LDR)
+    pub const R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21: u32 = 541; // initial-exec (미지원)
+    pub const R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC: u32 = 542; // initial-exec (미지원)
+    pub const R_AARCH64_TLSLE_ADD_TPREL_HI12: u32 = 549; // local-exec (미지원)
+    pub const R_AARCH64_TLSLE_ADD_TPREL_LO12_NC: u32 = 551; // local-exec (미지원)
+    pub const R_AARCH64_TLSDESC_ADR_PAGE21: u32 = 562; // Page(G(TLSDESC(S+A))) - Page(P)
+    pub const R_AARCH64_TLSDESC_LD64_LO12: u32 = 563; // G(TLSDESC(S+A)) 하위 12비트 (LDR)
+    pub const R_AARCH64_TLSDESC_ADD_LO12: u32 = 564; // G(TLSDESC(S+A)) 하위 12비트 (ADD)
+    pub const R_AARCH64_TLSDESC_CALL: u32 = 569; // resolver 호출 표시 (BLR)
 }
 
 /// RISC-V 재배치 타입
@@ -266,13 +298,37 @@ pub mod reloc_riscv {
     pub const R_RISCV_JAL: u32 = 17; // S + A - P (J-type)
     pub const R_RISCV_CALL: u32 = 18; // S + A - P (auipc+jalr)
     pub const R_RISCV_CALL_PLT: u32 = 19; // S + A - P (auipc+jalr, PLT)
+    pub const R_RISCV_GOT_HI20: u32 = 20; // G(S + A) - P (상위 20비트, LO12는 PCREL_LO12_I)
+    pub const R_RISCV_TLS_GOT_HI20: u32 = 21; // initial-exec (미지원)
+    pub const R_RISCV_TLS_GD_HI20: u32 = 22; // G(TLS_INDEX(S+A)) - P (상위 20비트)
     pub const R_RISCV_PCREL_HI20: u32 = 23; // S + A - P (상위 20비트)
     pub const R_RISCV_PCREL_LO12_I: u32 = 24; // S - P (하위 12비트, I-type) - 주의: 실제로는 auipc를 참조
     pub const R_RISCV_PCREL_LO12_S: u32 = 25; // S - P (하위 12비트, S-type)
     pub const R_RISCV_HI20: u32 = 26; // S + A (상위 20비트)
     pub const R_RISCV_LO12_I: u32 = 27; // S + A (하위 12비트, I-type)
     pub const R_RISCV_LO12_S: u32 = 28; // S + A (하위 12비트, S-type)
+    pub const R_RISCV_TPREL_HI20: u32 = 29; // local-exec (미지원)
+    pub const R_RISCV_TPREL_LO12_I: u32 = 30; // local-exec (미지원)
+    pub const R_RISCV_TPREL_LO12_S: u32 = 31; // local-exec (미지원)
+    pub const R_RISCV_TPREL_ADD: u32 = 32; // local-exec (미지원)
+    pub const R_RISCV_ADD8: u32 = 33; // V + S + A (8비트)
+    pub const R_RISCV_ADD16: u32 = 34; // V + S + A (16비트)
+    pub const R_RISCV_ADD32: u32 = 35; // V + S + A (32비트)
+    pub const R_RISCV_ADD64: u32 = 36; // V + S + A (64비트)
+    pub const R_RISCV_SUB8: u32 = 37; // V - S - A (8비트)
+    pub const R_RISCV_SUB16: u32 = 38; // V - S - A (16비트)
+    pub const R_RISCV_SUB32: u32 = 39; // V - S - A (32비트)
+    pub const R_RISCV_SUB64: u32 = 40; // V - S - A (64비트)
+    pub const R_RISCV_ALIGN: u32 = 43; // 완화용 정렬 패딩 (완화하지 않으므로 무시)
+    pub const R_RISCV_RVC_BRANCH: u32 = 44; // S + A - P (c.beqz/c.bnez, ±256B)
+    pub const R_RISCV_RVC_JUMP: u32 = 45; // S + A - P (c.j, ±2KB)
     pub const R_RISCV_RELAX: u32 = 51; // 링커 최적화 힌트
+    pub const R_RISCV_SUB6: u32 = 52; // V - S - A (하위 6비트)
+    pub const R_RISCV_SET6: u32 = 53; // S + A (하위 6비트)
+    pub const R_RISCV_SET8: u32 = 54; // S + A (8비트)
+    pub const R_RISCV_SET16: u32 = 55; // S + A (16비트)
+    pub const R_RISCV_SET32: u32 = 56; // S + A (32비트)
+    pub const R_RISCV_32_PCREL: u32 = 57; // S + A - P (32비트)
 }
 
 /// ELF64 파서 에러
@@ -551,6 +607,14 @@ impl<'a> Elf64<'a> {
             .filter(|ph| ph.p_type == program_type::PT_LOAD)
     }
 
+    /// TLS 세그먼트 (실행 파일용, 없으면 None)
+    pub fn tls_segment(&self) -> Option<&Elf64ProgramHeader> {
+        self.program_headers
+            .into_iter()
+            .flatten()
+            .find(|ph| ph.p_type == program_type::PT_TLS)
+    }
+
     /// 전체 메모리 요구량 계산 (LOAD 세그먼트 기준)
     pub fn memory_size(&self) -> usize {
         let mut max_addr = 0usize;
@@ -562,20 +626,4 @@ impl<'a> Elf64<'a> {
         }
         max_addr
     }
-
-    /// 전체 메모리 요구량 계산 (섹션 기준, relocatable용)
-    pub fn section_memory_size(&self) -> usize {
-        let mut total = 0usize;
-        for sh in self.section_headers {
-            if sh.sh_flags & section_flags::SHF_ALLOC != 0 {
-                total += sh.sh_size as usize;
-                // 정렬 고려
-                let align = sh.sh_addralign as usize;
-                if align > 0 {
-                    total = (total + align - 1) & !(align - 1);
-                }
-            }
-        }
-        total
-    }
 }
@@ -0,0 +1,190 @@
+//! 모듈별 자원 장부
+//!
+//! 모듈이 커널 API로 만든 자원(스레드, 타이머, 워크 항목, IRQ 핸들러, 드라이버)은 모듈
+//! 코드를 가리키는 콜백을 품고 있습니다. `rmmod`가 모듈 페이지를 해제한 뒤에도
+//! 이런 콜백이 남아 있으면 해제된 메모리로 점프해 CPU가 멈추므로, 자원을 만드는
+//! 서브시스템은 여기에 소유 모듈과 취소 함수를 기록합니다.
+//!
+//! 언로드 순서:
+//! 1. 모듈 exit 함수 (모듈이 직접 정리할 기회)
+//! 2. `reclaim()` - 장부에 남은 자원을 강제 취소하고 항목마다 로그를 남김
+//! 3. 취소에 실패한 자원이 있으면 모듈 페이지를 해제하지 않고 남김 (누수가
+//!    dangling 콜백보다 안전)
+//!
+//! 힙 할당은 별도로 `mm::owner`가 추적합니다.
+//!
+//! 일회성 타이머는 타이머 인터럽트에서 만료되므로 장부를 `IrqSpinlock`으로 보호하고,
+//! 인터럽트 경로의 `release_from_irq()`는 힙을 해제하지 않도록 항목에 표시만 해 둡니다.
+//! 표시한 항목은 다음에 스레드 문맥에서 장부를 만질 때 치웁니다.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::kprintln;
+use crate::sync::{IrqSpinlock, Mutex};
+
+/// 자원 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum ResourceKind {
+    /// 커널 스레드 (id = tid)
+    Thread,
+    /// 타이머 (id = 타이머 핸들)
+    Timer,
+    /// 워크큐 작업 (id = 작업 ID)
+    Work,
+    /// IRQ 핸들러 (id = 핸들러 주소)
+    Irq,
+    /// DTB 드라이버 (id = 드라이버 등록 ID)
+    Driver,
+}
+
+impl ResourceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResourceKind::Thread => "thread",
+            ResourceKind::Timer => "timer",
+            ResourceKind::Work => "work",
+            ResourceKind::Irq => "irq",
+            ResourceKind::Driver => "driver",
+        }
+    }
+}
+
+/// 자원 취소 함수
+///
+/// 자원을 멈추고 그 콜백이 더 이상 실행 중이 아님을 보장하면 true를 반환합니다.
+/// 모듈 목록 쓰기 락을 잡은 채 호출되므로 목록을 바꾸는 `ModuleLoader` API(로드, 언로드,
+/// `export_symbol`)를 사용하면 안 됩니다.
+pub type CancelFn = fn(usize) -> bool;
+
+/// 장부 항목
+struct Entry {
+    module: String,
+    kind: ResourceKind,
+    id: usize,
+    label: String,
+    cancel: CancelFn,
+    /// 인터럽트 경로에서 해제됨 (스레드 문맥에서 치울 때까지 남음)
+    released: bool,
+}
+
+/// 모듈 자원 정보 (조회용)
+#[derive(Debug, Clone)]
+pub struct ResourceInfo {
+    pub kind: ResourceKind,
+    pub id: usize,
+    pub label: String,
+}
+
+/// 언로드 시 회수 결과
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReclaimStats {
+    /// 강제 취소한 자원 수
+    pub reclaimed: usize,
+    /// 취소하지 못한 자원 수
+    pub failed: usize,
+}
+
+static LEDGER: IrqSpinlock<Vec<Entry>> = IrqSpinlock::new(Vec::new());
+
+/// init 함수를 실행 중인 모듈 (아직 모듈 목록에 없어 주소로 찾을 수 없음)
+static INITIALIZING: Mutex<Option<String>> = Mutex::new(None);
+
+/// 모듈 init 실행 구간 표시 (로더에서 호출)
+pub fn set_initializing(module: Option<&str>) {
+    *INITIALIZING.lock() = module.map(String::from);
+}
+
+/// 주소로 자원 소유 모듈 판별
+///
+/// 로드된 모듈의 페이지에 속하면 그 모듈, 아니면 init 실행 중인 모듈로 봅니다.
+pub fn owner_of(addr: usize) -> Option<String> {
+    super::ModuleLoader::module_at(addr).or_else(|| INITIALIZING.lock().clone())
+}
+
+/// 모듈 소유 자원 기록
+pub fn track(module: &str, kind: ResourceKind, id: usize, label: &str, cancel: CancelFn) {
+    let entry = Entry {
+        module: String::from(module),
+        kind,
+        id,
+        label: String::from(label),
+        cancel,
+        released: false,
+    };
+    let mut ledger = LEDGER.lock();
+    ledger.retain(|e| !e.released);
+    ledger.push(entry);
+}
+
+/// 자원이 정상적으로 해제되었을 때 장부에서 제거
+pub fn release(kind: ResourceKind, id: usize) {
+    let mut ledger = LEDGER.lock();
+    if let Some(pos) = ledger.iter().position(|e| e.kind == kind && e.id == id) {
+        ledger.swap_remove(pos);
+    }
+}
+
+/// `release`의 인터럽트 경로용 (일회성 타이머 만료)
+///
+/// 힙을 해제하지 않도록 항목을 해제됨으로 표시만 합니다. 조회와 회수는 표시한 항목을
+/// 건너뜁니다.
+pub fn release_from_irq(kind: ResourceKind, id: usize) {
+    let mut ledger = LEDGER.lock();
+    if let Some(entry) = ledger.iter_mut().find(|e| e.kind == kind && e.id == id && !e.released) {
+        entry.released = true;
+    }
+}
+
+/// 모듈이 소유한 자원 목록
+pub fn resources_of(module: &str) -> Vec<ResourceInfo> {
+    LEDGER
+        .lock()
+        .iter()
+        .filter(|e| e.module == module && !e.released)
+        .map(|e| ResourceInfo {
+            kind: e.kind,
+            id: e.id,
+            label: e.label.clone(),
+        })
+        .collect()
+}
+
+/// 모듈에 남은 자원을 모두 강제 취소 (언로드 중 호출)
+pub fn reclaim(module: &str) -> ReclaimStats {
+    // 취소 함수가 다시 장부를 건드릴 수 있으므로 먼저 꺼냄
+    let entries: Vec<Entry> = {
+        let mut ledger = LEDGER.lock();
+        let (owned, rest) = core::mem::take(&mut *ledger)
+            .into_iter()
+            .filter(|e| !e.released)
+            .partition(|e| e.module == module);
+        *ledger = rest;
+        owned
+    };
+
+    let mut stats = ReclaimStats::default();
+    for entry in entries {
+        if (entry.cancel)(entry.id) {
+            stats.reclaimed += 1;
+            kprintln!(
+                "[module] '{}': reclaimed {} {} ({})",
+                module,
+                entry.kind.as_str(),
+                entry.id,
+                entry.label
+            );
+        } else {
+            stats.failed += 1;
+            kprintln!(
+                "[module] '{}': failed to cancel {} {} ({}), still live",
+                module,
+                entry.kind.as_str(),
+                entry.id,
+                entry.label
+            );
+        }
+    }
+    stats
+}
@@ -4,18 +4,25 @@
 //! - 섹션 로딩 및 메모리 할당
 //! - 재배치 처리 (PLT 스텁 지원)
 //! - 모듈 라이프사이클 관리
+//!
+//! 로드된 모듈 목록은 심볼 조회마다 읽으므로 RCU(`sync::RcuCell`)로 보호합니다. 읽기는
+//! 락 없이 하고, 목록 밖에서 계속 쓸 모듈은 `Arc`를 복제해 둡니다. 로드/언로드/심볼
+//! export는 `MODULES_WRITE`로 직렬화하고 목록 복사본을 교체합니다.
 
-use alloc::boxed::Box;
 use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
-use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
 
 use crate::kprintln;
 use crate::mm::page::{self, PAGE_SIZE};
-use crate::sync::RwLock;
+use crate::proc::tls::TlsImage;
+use crate::sync::{Mutex, RcuCell};
 
 use super::elf::*;
+use super::sandbox::{self, Entry};
 use super::symbol::lookup_symbol;
+use super::verify::{self, Checksum};
 
 // ============================================================================
 // PLT (Procedure Linkage Table) 지원
@@ -28,10 +35,12 @@ const PLT_ENTRY_SIZE: usize = 16;
 #[cfg(target_arch = "riscv64")]
 const PLT_ENTRY_SIZE: usize = 16;
 
-/// 최대 PLT 엔트리 수 (페이지당)
-const MAX_PLT_ENTRIES: usize = PAGE_SIZE / PLT_ENTRY_SIZE;
+/// GOT 엔트리 크기 (바이트)
+const GOT_ENTRY_SIZE: usize = 8;
 
 /// PLT 테이블 관리
+///
+/// PLT 스텁과 TLS 항목은 페이지 앞에서부터, GOT 엔트리는 페이지 끝에서부터 채웁니다.
 struct PltTable {
     /// PLT 메모리 시작 주소
     base: usize,
@@ -39,6 +48,10 @@ struct PltTable {
     count: usize,
     /// 심볼별 PLT 엔트리 매핑 (target_addr, plt_addr) - Vec으로 변경
     entries: Vec<(usize, usize)>,
+    /// TLS 항목 매핑 (항목 내용, 주소) - TLSDESC 디스크립터 / `TlsIndex`
+    tls_entries: Vec<([u64; 2], usize)>,
+    /// GOT 엔트리 매핑 (심볼 주소, GOT 주소)
+    got_entries: Vec<(usize, usize)>,
 }
 
 impl PltTable {
@@ -48,7 +61,52 @@ impl PltTable {
             base,
             count: 0,
             entries: Vec::new(),
+            tls_entries: Vec::new(),
+            got_entries: Vec::new(),
+        }
+    }
+
+    /// 페이지 앞쪽에 16바이트 슬롯 하나를 더 둘 자리가 있는지 (GOT 영역과 겹치지 않음)
+    fn has_slot(&self) -> bool {
+        (self.count + 1) * PLT_ENTRY_SIZE + self.got_entries.len() * GOT_ENTRY_SIZE <= PAGE_SIZE
+    }
+
+    /// GOT 엔트리(심볼 주소를 담은 8바이트) 할당 또는 기존 엔트리 반환
+    fn get_or_create_got(&mut self, target: usize) -> Option<usize> {
+        if let Some(&(_, addr)) = self.got_entries.iter().find(|(t, _)| *t == target) {
+            return Some(addr);
+        }
+        let used = (self.got_entries.len() + 1) * GOT_ENTRY_SIZE;
+        if self.count * PLT_ENTRY_SIZE + used > PAGE_SIZE {
+            return None;
+        }
+
+        let addr = self.base + PAGE_SIZE - used;
+        unsafe {
+            *(addr as *mut u64) = target as u64;
+        }
+        self.got_entries.push((target, addr));
+
+        Some(addr)
+    }
+
+    /// TLS 항목(16바이트 데이터) 할당 또는 기존 항목 반환 (PLT 엔트리와 같은 슬롯 사용)
+    fn get_or_create_tls(&mut self, entry: [u64; 2]) -> Option<usize> {
+        if let Some(&(_, addr)) = self.tls_entries.iter().find(|(e, _)| *e == entry) {
+            return Some(addr);
+        }
+        if !self.has_slot() {
+            return None;
+        }
+
+        let addr = self.base + self.count * PLT_ENTRY_SIZE;
+        unsafe {
+            *(addr as *mut [u64; 2]) = entry;
         }
+        self.tls_entries.push((entry, addr));
+        self.count += 1;
+
+        Some(addr)
     }
 
     /// PLT 엔트리 할당 또는 기존 엔트리 반환
@@ -59,7 +117,7 @@ impl PltTable {
         }
 
         // 새 엔트리 할당
-        if self.count >= MAX_PLT_ENTRIES {
+        if !self.has_slot() {
             return None; // PLT 공간 부족
         }
 
@@ -137,6 +195,14 @@ pub enum ModuleError {
     InvalidFormat,
     /// 모듈이 언로딩 중
     ModuleUnloading,
+    /// 체크섬 트레일러 없음 (`module.verify=enforce`)
+    Unverified,
+    /// 체크섬 불일치 (`module.verify=enforce`)
+    ChecksumMismatch,
+    /// `module_init`이 제한 시간 안에 끝나지 않음 (`module.timeout=`)
+    Timeout,
+    /// 쓰기와 실행이 함께 필요한 섹션 (W^X 위반)
+    WritableExecutable,
 }
 
 impl From<Elf64Error> for ModuleError {
@@ -154,6 +220,19 @@ pub enum ModuleState {
     Live,
     /// 언로딩 중
     Unloading,
+    /// init/exit 실패 또는 제한 시간 초과
+    Failed,
+}
+
+impl ModuleState {
+    fn from_u8(v: u8) -> Self {
+        match v {
+            0 => Self::Loading,
+            1 => Self::Live,
+            2 => Self::Unloading,
+            _ => Self::Failed,
+        }
+    }
 }
 
 /// 모듈 상세 정보 (조회용)
@@ -175,6 +254,8 @@ pub struct ModuleInfo {
     pub is_unloading: bool,
     /// Export된 심볼 수
     pub exported_symbol_count: usize,
+    /// 로드 때 확인한 체크섬 (None = 검증 안 됨)
+    pub checksum: Option<Checksum>,
 }
 
 /// 모듈 메타데이터
@@ -203,12 +284,14 @@ pub struct LoadedModule {
     pub base_addr: usize,
     /// 할당된 총 메모리 크기
     pub size: usize,
-    /// 모듈 상태
-    pub state: ModuleState,
+    /// 모듈 상태 (`ModuleState`)
+    state: AtomicU8,
     /// 참조 카운트
     pub ref_count: AtomicUsize,
     /// 언로딩 중 플래그 (새 참조 획득 차단)
     unloading: AtomicBool,
+    /// 제한 시간을 넘긴 init/exit 스레드가 CPU에서 내려가지 않음 (페이지 해제 금지)
+    stuck: AtomicBool,
     /// 초기화 함수 주소
     init_fn: Option<usize>,
     /// 정리 함수 주소
@@ -217,64 +300,96 @@ pub struct LoadedModule {
     pages: Vec<usize>,
     /// 섹션별 로드 주소 (재배치용)
     section_addrs: Vec<usize>,
-    /// 모듈이 export한 심볼들 - Vec으로 변경
-    pub exported_symbols: Vec<(String, usize)>,
+    /// 모듈이 export한 심볼들 (로드 후 `export_symbol`로 추가하므로 RCU)
+    pub exported_symbols: RcuCell<Vec<(String, usize)>>,
     /// PLT 페이지 주소 (있으면)
     plt_page: Option<usize>,
+    /// 로드 때 확인한 체크섬 (None = 검증 안 됨)
+    pub checksum: Option<Checksum>,
+    /// 모듈 TLS id (`.tdata`/`.tbss`가 있으면, `proc::tls`)
+    tls_id: Option<usize>,
 }
 
 /// 모듈 참조 가드 (RAII)
 /// Drop 시 자동으로 참조 카운트 감소
 pub struct ModuleRef {
-    module_name: String,
+    module: Arc<LoadedModule>,
 }
 
 impl ModuleRef {
     /// 모듈 이름 반환
     pub fn name(&self) -> &str {
-        &self.module_name
+        &self.module.info.name
     }
 
-    /// 모듈에 접근
-    pub fn get(&self) -> Option<&'static LoadedModule> {
-        let modules = LOADED_MODULES.read();
-        modules
-            .iter()
-            .find(|m| m.info.name == self.module_name)
-            .map(|m| unsafe { &*(&**m as *const LoadedModule) })
+    /// 모듈에 접근 (참조를 쥐고 있는 동안 언로드되지 않음)
+    pub fn get(&self) -> &LoadedModule {
+        &self.module
     }
 }
 
 impl Drop for ModuleRef {
     fn drop(&mut self) {
         // 참조 카운트 감소
-        if let Some(module) = self.get() {
-            module.ref_count.fetch_sub(1, Ordering::SeqCst);
-        }
+        self.module.ref_count.fetch_sub(1, Ordering::SeqCst);
     }
 }
 
 impl LoadedModule {
     /// 초기화 함수 호출 (PLT를 통해 extern 함수 사용 가능)
+    ///
+    /// 전용 스레드에서 실행합니다 (`sandbox`). 실패하거나 제한 시간을 넘기면 Failed.
     pub fn init(&self) -> Result<(), ModuleError> {
         if let Some(addr) = self.init_fn {
-            // module_init() -> i32 (PLT 사용으로 인자 없음)
-            type InitFn = extern "C" fn() -> i32;
-            let init: InitFn = unsafe { core::mem::transmute(addr) };
-            let result = init();
-            if result != 0 {
-                return Err(ModuleError::InitFailed(result));
+            match sandbox::call(&self.info.name, Entry::Init(addr)) {
+                Ok(0) => {}
+                Ok(result) => {
+                    self.set_state(ModuleState::Failed);
+                    return Err(ModuleError::InitFailed(result));
+                }
+                Err(e) => {
+                    self.set_state(ModuleState::Failed);
+                    self.report_timeout("module_init", e);
+                    return Err(ModuleError::Timeout);
+                }
             }
         }
+        self.set_state(ModuleState::Live);
         Ok(())
     }
 
-    /// 정리 함수 호출
-    pub fn exit(&self) {
+    /// 정리 함수 호출 (전용 스레드, 제한 시간을 넘기면 Failed)
+    pub fn exit(&self) -> Result<(), ModuleError> {
+        self.set_state(ModuleState::Unloading);
         if let Some(addr) = self.exit_fn {
-            let exit: fn() = unsafe { core::mem::transmute(addr) };
-            exit();
+            if let Err(e) = sandbox::call(&self.info.name, Entry::Exit(addr)) {
+                self.set_state(ModuleState::Failed);
+                self.report_timeout("module_exit", e);
+                return Err(ModuleError::Timeout);
+            }
         }
+        Ok(())
+    }
+
+    fn report_timeout(&self, what: &str, e: sandbox::CallError) {
+        self.stuck.fetch_or(!e.stopped, Ordering::AcqRel);
+        kprintln!(
+            "[module] '{}': {} did not return within {} ms, killed thread {}{}",
+            self.info.name,
+            what,
+            sandbox::timeout_ms(),
+            e.tid,
+            if e.stopped { "" } else { " (still running)" }
+        );
+    }
+
+    /// 모듈 상태
+    pub fn state(&self) -> ModuleState {
+        ModuleState::from_u8(self.state.load(Ordering::Acquire))
+    }
+
+    fn set_state(&self, state: ModuleState) {
+        self.state.store(state as u8, Ordering::Release);
     }
 
     /// 참조 카운트 증가 (deprecated: try_get 사용 권장)
@@ -308,30 +423,155 @@ impl LoadedModule {
 
     /// 모듈 내 심볼 조회
     pub fn lookup_symbol(&self, name: &str) -> Option<usize> {
-        self.exported_symbols.iter().find(|(n, _)| n == name).map(|(_, addr)| *addr)
+        self.exported_symbols.read().iter().find(|(n, _)| n == name).map(|(_, addr)| *addr)
     }
 
     /// 심볼 export (모듈이 자신의 심볼을 등록)
-    pub fn export_symbol(&mut self, name: &str, address: usize) {
-        if let Some(pos) = self.exported_symbols.iter().position(|(n, _)| n == name) {
-            self.exported_symbols[pos] = (String::from(name), address);
+    pub fn export_symbol(&self, name: &str, address: usize) {
+        let _guard = MODULES_WRITE.lock();
+        self.exported_symbols.update(|symbols| {
+            if let Some(pos) = symbols.iter().position(|(n, _)| n == name) {
+                symbols[pos] = (String::from(name), address);
+            } else {
+                symbols.push((String::from(name), address));
+            }
+        });
+    }
+}
+
+/// 로드된 모듈 목록
+static LOADED_MODULES: RcuCell<Vec<Arc<LoadedModule>>> = RcuCell::lazy(Vec::new);
+
+/// 모듈 목록/심볼 쓰기 직렬화 (RCU writer끼리는 직렬화되지 않음)
+static MODULES_WRITE: Mutex<()> = Mutex::new(());
+
+/// 이름으로 모듈 찾기
+fn find_module(name: &str) -> Option<Arc<LoadedModule>> {
+    LOADED_MODULES.read().iter().find(|m| m.info.name == name).cloned()
+}
+
+/// 모듈 목록에 추가
+fn publish(module: &Arc<LoadedModule>) {
+    let _guard = MODULES_WRITE.lock();
+    LOADED_MODULES.update(|modules| modules.push(Arc::clone(module)));
+}
+
+/// 로드한 실행 파일
+pub struct LoadedExecutable {
+    /// 엔트리 포인트
+    pub entry: usize,
+    /// TLS 초기화 이미지 (`PT_TLS`가 있으면)
+    pub tls: Option<TlsImage>,
+}
+
+/// 섹션 메모리 권한 (W^X: 권한마다 페이지를 따로 씀)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SectionPerm {
+    /// 읽기 + 실행 (.text)
+    Text,
+    /// 읽기 전용 (.rodata)
+    ReadOnly,
+    /// 읽기 + 쓰기, 실행 불가 (.data, .bss)
+    Data,
+}
+
+impl SectionPerm {
+    /// 로드하는 섹션의 권한 (ALLOC이 아니거나 TLS 섹션이면 None)
+    fn of(sh: &Elf64SectionHeader) -> Option<Self> {
+        if sh.sh_flags & section_flags::SHF_ALLOC == 0 || sh.sh_flags & section_flags::SHF_TLS != 0 {
+            None
+        } else if sh.sh_flags & section_flags::SHF_EXECINSTR != 0 {
+            Some(Self::Text)
+        } else if sh.sh_flags & section_flags::SHF_WRITE != 0 {
+            Some(Self::Data)
         } else {
-            self.exported_symbols.push((String::from(name), address));
+            Some(Self::ReadOnly)
+        }
+    }
+
+    /// (쓰기 가능, 실행 가능)
+    fn permissions(self) -> (bool, bool) {
+        match self {
+            Self::Text => (false, true),
+            Self::ReadOnly => (false, false),
+            Self::Data => (true, false),
         }
     }
 }
 
-/// 로드된 모듈 목록
-static LOADED_MODULES: RwLock<Vec<Box<LoadedModule>>> = RwLock::new(Vec::new());
+/// 권한별로 페이지를 나눈 섹션 배치 (코드, 읽기 전용, 데이터 순)
+struct SectionLayout {
+    /// 섹션 인덱스별 base 기준 오프셋 (로드하지 않는 섹션은 None)
+    offsets: Vec<Option<usize>>,
+    /// 권한별 범위 (시작 오프셋, 끝 오프셋, 권한) - 페이지 경계에서 시작하고 끝남
+    regions: Vec<(usize, usize, SectionPerm)>,
+    /// 전체 크기 (페이지 단위)
+    size: usize,
+}
+
+impl SectionLayout {
+    /// 섹션 배치 계산, 쓰기와 실행이 함께 필요한 섹션이 있으면 `WritableExecutable`
+    fn new(elf: &Elf64) -> Result<Self, ModuleError> {
+        let sections = elf.sections();
+        let wx = section_flags::SHF_ALLOC | section_flags::SHF_WRITE | section_flags::SHF_EXECINSTR;
+        if let Some(sh) = sections.iter().find(|sh| sh.sh_flags & wx == wx) {
+            kprintln!("[module] Section '{}' is writable and executable (W^X)", elf.section_name(sh));
+            return Err(ModuleError::WritableExecutable);
+        }
+
+        let mut offsets = alloc::vec![None; sections.len()];
+        let mut regions = Vec::new();
+        let mut offset = 0usize;
+        for perm in [SectionPerm::Text, SectionPerm::ReadOnly, SectionPerm::Data] {
+            let start = offset;
+            for (i, sh) in sections.iter().enumerate() {
+                if SectionPerm::of(sh) != Some(perm) {
+                    continue;
+                }
+                offset = offset.next_multiple_of((sh.sh_addralign as usize).max(1));
+                offsets[i] = Some(offset);
+                offset += sh.sh_size as usize;
+            }
+            offset = offset.next_multiple_of(PAGE_SIZE);
+            if offset > start {
+                regions.push((start, offset, perm));
+            }
+        }
+
+        Ok(Self { offsets, regions, size: offset })
+    }
+}
+
+/// 모듈 TLS 배치 (TLS 섹션들을 블록 하나로 모음)
+struct TlsLayout {
+    /// 모듈 TLS id (`proc::tls::register`)
+    id: usize,
+    /// 섹션 인덱스별 블록 안 오프셋 (TLS 섹션이 아니면 None)
+    section_offsets: Vec<Option<usize>>,
+}
+
+impl TlsLayout {
+    /// TLS 섹션에 정의된 심볼이면 블록 안 오프셋
+    fn offset_of(&self, sym: &Elf64Symbol) -> Option<usize> {
+        let offset = (*self.section_offsets.get(sym.st_shndx as usize)?)?;
+        Some(offset + sym.st_value as usize)
+    }
+}
 
 /// 모듈 로더
 pub struct ModuleLoader;
 
 impl ModuleLoader {
     /// Relocatable object (.o) 로드
-    pub fn load_object(data: &[u8], name: &str) -> Result<&'static LoadedModule, ModuleError> {
+    pub fn load_object(data: &[u8], name: &str) -> Result<Arc<LoadedModule>, ModuleError> {
         kprintln!("[module] Loading relocatable object: {}", name);
 
+        // 체크섬 트레일러 확인 후 떼어냄 (재배치/실행 전)
+        let (data, checksum) = verify::verify(data, name)?;
+        if let Some(algo) = checksum {
+            kprintln!("[module] {} checksum verified", algo);
+        }
+
         // ELF 파싱
         let elf = Elf64::parse(data)?;
 
@@ -344,9 +584,10 @@ impl ModuleLoader {
             return Err(ModuleError::InvalidFormat);
         }
 
-        // 필요한 메모리 크기 계산
-        let mem_size = elf.section_memory_size();
-        let num_pages = (mem_size + PAGE_SIZE - 1) / PAGE_SIZE;
+        // 섹션 배치 (권한별 페이지 분리) 및 필요한 메모리 크기 계산
+        let layout = SectionLayout::new(&elf)?;
+        let mem_size = layout.size;
+        let num_pages = mem_size / PAGE_SIZE;
 
         // PLT 페이지 할당 (최대 256개 엔트리, 16바이트씩 = 4KB = 1페이지)
         let plt_page_count = 1;
@@ -359,29 +600,10 @@ impl ModuleLoader {
             plt_page_count
         );
 
-        // 페이지 할당
-        let mut pages = Vec::new();
-        let mut base_addr = 0usize;
-
-        for i in 0..total_pages {
-            match page::alloc_frame() {
-                Some(addr) => {
-                    if i == 0 {
-                        base_addr = addr;
-                    }
-                    pages.push(addr);
-                }
-                None => {
-                    // 할당 실패 - 이미 할당한 페이지 해제
-                    for &page in &pages {
-                        unsafe {
-                            page::free_frame(page);
-                        }
-                    }
-                    return Err(ModuleError::OutOfMemory);
-                }
-            }
-        }
+        // 페이지 할당 (섹션 배치가 base_addr 기준 오프셋이므로 연속 할당 필요)
+        // 단일 프레임 할당은 CPU별 매거진을 거쳐 주소 순서가 보장되지 않음
+        let base_addr = page::alloc_frames(total_pages).ok_or(ModuleError::OutOfMemory)?;
+        let pages: Vec<usize> = (0..total_pages).map(|i| base_addr + i * PAGE_SIZE).collect();
 
         kprintln!("[module] Allocated {} pages at 0x{:x}", total_pages, base_addr);
 
@@ -395,13 +617,19 @@ impl ModuleLoader {
         }
 
         // 섹션 로드 및 주소 매핑
-        let section_addrs = Self::load_sections(&elf, base_addr)?;
+        let section_addrs = Self::load_sections(&elf, &layout, base_addr)?;
+
+        // TLS 섹션은 스레드별 블록의 이미지로 등록
+        let tls = Self::register_tls(&elf)?;
 
         // PLT 테이블 생성
         let mut plt = Some(PltTable::new(plt_base));
 
         // 재배치 적용
-        Self::apply_relocations(&elf, &section_addrs, &mut plt)?;
+        if let Err(e) = Self::apply_relocations(&elf, &section_addrs, tls.as_ref(), &mut plt) {
+            Self::discard(&pages, tls.as_ref());
+            return Err(e);
+        }
 
         // PLT 사용 로깅
         if let Some(ref plt_table) = plt {
@@ -413,6 +641,13 @@ impl ModuleLoader {
         // PLT 영역도 플러시
         Self::flush_icache(plt_base, PAGE_SIZE);
 
+        // W^X: 재배치가 끝났으므로 코드는 읽기+실행, 데이터는 읽기/쓰기로 보호
+        if let Err(e) = Self::protect(&layout, base_addr, plt_base) {
+            kprintln!("[module] Failed to protect module memory: {}", e);
+            Self::discard(&pages, tls.as_ref());
+            return Err(ModuleError::OutOfMemory);
+        }
+
         // init/exit 함수 찾기
         let init_fn = elf
             .find_symbol("module_init")
@@ -454,48 +689,44 @@ impl ModuleLoader {
         kprintln!("[module] Exported {} symbols", exported_symbols.len());
 
         // LoadedModule 생성
-        let module = Box::new(LoadedModule {
+        let module = Arc::new(LoadedModule {
             info: Module::new(name),
             base_addr,
             size: mem_size,
-            state: ModuleState::Live,
+            state: AtomicU8::new(ModuleState::Loading as u8),
             ref_count: AtomicUsize::new(0),
             unloading: AtomicBool::new(false),
+            stuck: AtomicBool::new(false),
             init_fn,
             exit_fn,
             pages,
             section_addrs,
-            exported_symbols,
+            exported_symbols: RcuCell::new(exported_symbols),
             plt_page: Some(plt_base),
+            checksum,
+            tls_id: tls.as_ref().map(|t| t.id),
         });
 
-        // init 함수 호출
-        if let Err(e) = module.init() {
-            // 실패 시 정리
-            for &page in &module.pages {
-                unsafe {
-                    page::free_frame(page);
-                }
-            }
+        // init 함수 호출 (전용 스레드, init 중 만든 자원도 이 모듈 소유로 기록)
+        super::ledger::set_initializing(Some(name));
+        let init_result = module.init();
+        super::ledger::set_initializing(None);
+        if let Err(e) = init_result {
+            // 실패 시 정리 (제한 시간 초과면 init 스레드가 만든 자원도 회수)
+            Self::release_module_memory(&module);
             return Err(e);
         }
 
         // 모듈 목록에 추가
-        let mut modules = LOADED_MODULES.write();
-        modules.push(module);
-
-        // 마지막 추가된 모듈 참조 반환
-        let module_ref = modules.last().unwrap().as_ref();
-        let static_ref: &'static LoadedModule =
-            unsafe { &*(module_ref as *const LoadedModule) };
+        publish(&module);
 
         kprintln!("[module] Module '{}' loaded successfully", name);
 
-        Ok(static_ref)
+        Ok(module)
     }
 
     /// 실행 파일 로드 (ELF executable)
-    pub fn load_executable(data: &[u8]) -> Result<usize, ModuleError> {
+    pub fn load_executable(data: &[u8]) -> Result<LoadedExecutable, ModuleError> {
         kprintln!("[module] Loading executable");
 
         // ELF 파싱
@@ -553,30 +784,47 @@ impl ModuleLoader {
                     return Err(ModuleError::OutOfMemory);
                 }
             }
+
+            // identity 매핑의 RAM은 실행 불가이므로 실행 세그먼트만 권한을 바꿈
+            if ph.p_flags & program_flags::PF_X != 0 {
+                let write = ph.p_flags & program_flags::PF_W != 0;
+                if let Err(e) = crate::arch::mmu::protect_range(vaddr, mem_size, write, true) {
+                    kprintln!("[module] Error: cannot make segment executable: {}", e);
+                    return Err(ModuleError::OutOfMemory);
+                }
+            }
         }
 
+        // TLS 세그먼트 (스레드마다 `proc::tls::StaticTls`로 복제)
+        let tls = match elf.tls_segment() {
+            Some(ph) => {
+                let start = ph.p_offset as usize;
+                let init = data
+                    .get(start..start + ph.p_filesz as usize)
+                    .ok_or(ModuleError::InvalidFormat)?;
+                kprintln!("[module] TLS segment: filesz={}, memsz={}, align={}", ph.p_filesz, ph.p_memsz, ph.p_align);
+                let image = TlsImage::new(init.to_vec(), ph.p_memsz as usize, ph.p_align as usize)
+                    .ok_or(ModuleError::InvalidFormat)?;
+                Some(image)
+            }
+            None => None,
+        };
+
         // 엔트리 포인트 반환
         let entry = elf.entry_point() as usize;
         kprintln!("[module] Entry point: 0x{:x}", entry);
 
-        Ok(entry)
+        Ok(LoadedExecutable { entry, tls })
     }
 
-    /// 섹션들을 메모리에 로드
-    fn load_sections(elf: &Elf64, base_addr: usize) -> Result<Vec<usize>, ModuleError> {
+    /// 섹션들을 `layout` 배치대로 메모리에 로드
+    fn load_sections(elf: &Elf64, layout: &SectionLayout, base_addr: usize) -> Result<Vec<usize>, ModuleError> {
         let mut section_addrs = Vec::new();
-        let mut current_offset = 0usize;
 
-        for sh in elf.sections().iter() {
-            // ALLOC 플래그가 있는 섹션만 로드
-            if sh.sh_flags & section_flags::SHF_ALLOC != 0 {
-                // 정렬
-                let align = sh.sh_addralign as usize;
-                if align > 0 && current_offset % align != 0 {
-                    current_offset = (current_offset + align - 1) & !(align - 1);
-                }
-
-                let load_addr = base_addr + current_offset;
+        for (sh, offset) in elf.sections().iter().zip(&layout.offsets) {
+            // ALLOC 플래그가 있는 섹션만 로드 (TLS 섹션은 `register_tls`)
+            if let Some(offset) = *offset {
+                let load_addr = base_addr + offset;
                 section_addrs.push(load_addr);
 
                 let section_name = elf.section_name(sh);
@@ -594,8 +842,6 @@ impl ModuleLoader {
                         core::ptr::copy_nonoverlapping(data.as_ptr(), load_addr as *mut u8, data.len());
                     }
                 }
-
-                current_offset += sh.sh_size as usize;
             } else {
                 // 로드하지 않는 섹션은 0으로 표시
                 section_addrs.push(0);
@@ -605,8 +851,81 @@ impl ModuleLoader {
         Ok(section_addrs)
     }
 
+    /// 섹션 권한대로 페이지 보호 (W^X), PLT 페이지는 스텁과 GOT/TLS 항목뿐이므로 읽기+실행
+    fn protect(layout: &SectionLayout, base_addr: usize, plt_base: usize) -> Result<(), &'static str> {
+        for &(start, end, perm) in &layout.regions {
+            let (write, execute) = perm.permissions();
+            crate::arch::mmu::protect_range(base_addr + start, end - start, write, execute)?;
+        }
+        crate::arch::mmu::protect_page(plt_base, false, true)
+    }
+
+    /// 모듈 페이지를 identity 매핑 기본 권한(읽기/쓰기, 실행 불가)으로 되돌리고 반환
+    fn free_pages(pages: &[usize]) {
+        for &page in pages {
+            if crate::arch::mmu::page_permissions(page) != Some((true, false)) {
+                let _ = crate::arch::mmu::protect_page(page, true, false);
+            }
+            unsafe {
+                page::free_frame(page);
+            }
+        }
+    }
+
+    /// `LoadedModule`을 만들기 전에 실패한 로드의 페이지와 TLS 이미지 반환
+    fn discard(pages: &[usize], tls: Option<&TlsLayout>) {
+        if let Some(tls) = tls {
+            crate::proc::tls::unregister(tls.id);
+        }
+        Self::free_pages(pages);
+    }
+
+    /// TLS 섹션(.tdata/.tbss)을 이미지 하나로 모아 등록 (없으면 None)
+    fn register_tls(elf: &Elf64) -> Result<Option<TlsLayout>, ModuleError> {
+        let mut section_offsets = Vec::new();
+        let mut init = Vec::new();
+        let mut size = 0usize;
+        let mut align = 1usize;
+
+        for sh in elf.sections().iter() {
+            if sh.sh_flags & section_flags::SHF_TLS == 0 || sh.sh_flags & section_flags::SHF_ALLOC == 0 {
+                section_offsets.push(None);
+                continue;
+            }
+            let sh_align = (sh.sh_addralign as usize).max(1);
+            if !sh_align.is_power_of_two() {
+                return Err(ModuleError::InvalidFormat);
+            }
+            size = (size + sh_align - 1) & !(sh_align - 1);
+            align = align.max(sh_align);
+            section_offsets.push(Some(size));
+
+            if sh.sh_type != section_type::SHT_NOBITS {
+                init.resize(size, 0);
+                init.extend_from_slice(elf.section_data(sh));
+            }
+            size += sh.sh_size as usize;
+        }
+
+        if section_offsets.iter().all(Option::is_none) {
+            return Ok(None);
+        }
+        let image = TlsImage::new(init, size, align).ok_or(ModuleError::InvalidFormat)?;
+        let id = crate::proc::tls::register(image);
+        kprintln!("[module] TLS block: {} bytes, align {} (id {})", size, align, id);
+
+        Ok(Some(TlsLayout { id, section_offsets }))
+    }
+
     /// 재배치 적용
-    fn apply_relocations(elf: &Elf64, section_addrs: &[usize], plt: &mut Option<PltTable>) -> Result<(), ModuleError> {
+    ///
+    /// TLS 섹션에 정의된 심볼의 값은 모듈 TLS 블록 안 오프셋입니다.
+    fn apply_relocations(
+        elf: &Elf64,
+        section_addrs: &[usize],
+        tls: Option<&TlsLayout>,
+        plt: &mut Option<PltTable>,
+    ) -> Result<(), ModuleError> {
         let (_symtab_sh, symbols) = elf.symbol_table().ok_or(ModuleError::SymbolNotFound)?;
 
         // RISC-V: PCREL_HI20 결과를 저장하여 PCREL_LO12에서 사용 - Vec으로 변경
@@ -619,6 +938,11 @@ impl ModuleLoader {
             if target_section_idx >= section_addrs.len() {
                 continue;
             }
+            if elf.sections()[target_section_idx].sh_flags & section_flags::SHF_TLS != 0 && !relas.is_empty() {
+                // TLS 초기값은 스레드마다 그대로 복사하므로 고칠 수 없음
+                kprintln!("[module] Relocations in TLS initializers are not supported");
+                return Err(ModuleError::InvalidFormat);
+            }
             let section_base = section_addrs[target_section_idx];
             if section_base == 0 {
                 continue; // 로드되지 않은 섹션
@@ -651,6 +975,9 @@ impl ModuleLoader {
                 } else if sym.st_shndx == section_index::SHN_ABS {
                     // 절대값
                     sym.st_value as usize
+                } else if let Some(offset) = tls.and_then(|t| t.offset_of(sym)) {
+                    // TLS 심볼
+                    offset
                 } else {
                     // 로컬 심볼
                     let sym_section = sym.st_shndx as usize;
@@ -666,11 +993,13 @@ impl ModuleLoader {
                 let addend = rela.r_addend;
 
                 // 아키텍처별 재배치 처리
+                let tls_id = tls.map(|t| t.id);
+
                 #[cfg(target_arch = "aarch64")]
-                Self::apply_relocation_aarch64(reloc_addr, sym_value, addend, rel_type, plt)?;
+                Self::apply_relocation_aarch64(reloc_addr, sym_value, addend, rel_type, tls_id, plt)?;
 
                 #[cfg(target_arch = "riscv64")]
-                Self::apply_relocation_riscv(reloc_addr, sym_value, addend, rel_type, &mut hi20_results, plt)?;
+                Self::apply_relocation_riscv(reloc_addr, sym_value, addend, rel_type, tls_id, &mut hi20_results, plt)?;
             }
         }
 
@@ -684,6 +1013,7 @@ impl ModuleLoader {
         sym_value: usize,
         addend: i64,
         rel_type: u32,
+        tls_id: Option<usize>,
         plt: &mut Option<PltTable>,
     ) -> Result<(), ModuleError> {
         use super::elf::reloc_aarch64::*;
@@ -758,44 +1088,115 @@ impl ModuleLoader {
 
             R_AARCH64_ADR_PREL_PG_HI21 => {
                 // Page(S+A) - Page(P), ADRP 명령
-                let page_s = (s + a) & !0xfff;
-                let page_p = p & !0xfff;
-                let offset = ((page_s - page_p) >> 12) as i32;
+                Self::patch_adrp(reloc_addr, s + a, rel_type)?;
+            }
 
-                if offset > 0xfffff || offset < -0x100000 {
-                    kprintln!("[module] ADRP offset out of range");
+            R_AARCH64_ADD_ABS_LO12_NC => {
+                // S + A, 하위 12비트 (ADD 명령)
+                Self::patch_lo12(reloc_addr, s + a, 0);
+            }
+
+            R_AARCH64_LDST64_ABS_LO12_NC => {
+                // S + A, 하위 12비트, 8바이트 정렬 (LDR/STR 64비트)
+                Self::patch_lo12(reloc_addr, s + a, 3);
+            }
+
+            R_AARCH64_LDST8_ABS_LO12_NC => Self::patch_lo12(reloc_addr, s + a, 0),
+            R_AARCH64_LDST16_ABS_LO12_NC => Self::patch_lo12(reloc_addr, s + a, 1),
+            R_AARCH64_LDST32_ABS_LO12_NC => Self::patch_lo12(reloc_addr, s + a, 2),
+            R_AARCH64_LDST128_ABS_LO12_NC => Self::patch_lo12(reloc_addr, s + a, 4),
+
+            R_AARCH64_MOVW_UABS_G0
+            | R_AARCH64_MOVW_UABS_G0_NC
+            | R_AARCH64_MOVW_UABS_G1
+            | R_AARCH64_MOVW_UABS_G1_NC
+            | R_AARCH64_MOVW_UABS_G2
+            | R_AARCH64_MOVW_UABS_G2_NC
+            | R_AARCH64_MOVW_UABS_G3 => {
+                // S + A의 16비트 조각 (MOVZ/MOVK imm16), NC가 아니면 남은 상위 비트가 0이어야 함
+                let (group, checked) = match rel_type {
+                    R_AARCH64_MOVW_UABS_G0 => (0, true),
+                    R_AARCH64_MOVW_UABS_G0_NC => (0, false),
+                    R_AARCH64_MOVW_UABS_G1 => (1, true),
+                    R_AARCH64_MOVW_UABS_G1_NC => (1, false),
+                    R_AARCH64_MOVW_UABS_G2 => (2, true),
+                    R_AARCH64_MOVW_UABS_G2_NC => (2, false),
+                    _ => (3, false),
+                };
+                let value = (s + a) as u64;
+                if checked && value >> (16 * (group + 1)) != 0 {
+                    kprintln!("[module] MOVW_UABS_G{} value out of range: 0x{:x}", group, value);
                     return Err(ModuleError::UnsupportedRelocation(rel_type));
                 }
-
+                let imm16 = ((value >> (16 * group)) & 0xffff) as u32;
                 unsafe {
                     let insn = *(reloc_addr as *mut u32);
-                    let immlo = ((offset & 0x3) as u32) << 29;
-                    let immhi = (((offset >> 2) & 0x7ffff) as u32) << 5;
-                    let new_insn = (insn & 0x9f00001f) | immlo | immhi;
-                    *(reloc_addr as *mut u32) = new_insn;
+                    *(reloc_addr as *mut u32) = (insn & !(0xffff << 5)) | (imm16 << 5);
                 }
             }
 
-            R_AARCH64_ADD_ABS_LO12_NC => {
-                // S + A, 하위 12비트 (ADD 명령)
-                let value = ((s + a) & 0xfff) as u32;
+            R_AARCH64_CONDBR19 | R_AARCH64_TSTBR14 => {
+                // S + A - P, B.cond/CBZ/CBNZ는 imm19, TBZ/TBNZ는 imm14 (비트 5부터)
+                let bits = if rel_type == R_AARCH64_CONDBR19 { 19 } else { 14 };
+                let offset = (s + a - p) >> 2;
+                if offset >= 1 << (bits - 1) || offset < -(1 << (bits - 1)) {
+                    kprintln!("[module] Conditional branch offset out of range: {}", offset);
+                    return Err(ModuleError::UnsupportedRelocation(rel_type));
+                }
+                let mask = ((1u32 << bits) - 1) << 5;
                 unsafe {
                     let insn = *(reloc_addr as *mut u32);
-                    let new_insn = (insn & 0xffc003ff) | (value << 10);
-                    *(reloc_addr as *mut u32) = new_insn;
+                    *(reloc_addr as *mut u32) = (insn & !mask) | (((offset as u32) << 5) & mask);
                 }
             }
 
-            R_AARCH64_LDST64_ABS_LO12_NC => {
-                // S + A, 하위 12비트, 8바이트 정렬 (LDR/STR 64비트)
-                let value = (((s + a) & 0xfff) >> 3) as u32;
-                unsafe {
-                    let insn = *(reloc_addr as *mut u32);
-                    let new_insn = (insn & 0xffc003ff) | (value << 10);
-                    *(reloc_addr as *mut u32) = new_insn;
+            R_AARCH64_ADR_GOT_PAGE | R_AARCH64_LD64_GOT_LO12_NC => {
+                // 대상은 PLT 페이지의 GOT 엔트리 (S + A를 담음)
+                let Some(plt_table) = plt.as_mut() else {
+                    kprintln!("[module] GOT relocation without PLT page");
+                    return Err(ModuleError::UnsupportedRelocation(rel_type));
+                };
+                let got = plt_table.get_or_create_got((s + a) as usize).ok_or_else(|| {
+                    kprintln!("[module] PLT table full");
+                    ModuleError::UnsupportedRelocation(rel_type)
+                })? as i64;
+                if rel_type == R_AARCH64_ADR_GOT_PAGE {
+                    Self::patch_adrp(reloc_addr, got, rel_type)?;
+                } else {
+                    Self::patch_lo12(reloc_addr, got, 3);
+                }
+            }
+
+            R_AARCH64_TLSDESC_ADR_PAGE21 | R_AARCH64_TLSDESC_LD64_LO12 | R_AARCH64_TLSDESC_ADD_LO12 => {
+                // S + A = TLS 블록 안 오프셋, 대상은 PLT 페이지의 디스크립터
+                let (Some(id), Some(plt_table)) = (tls_id, plt.as_mut()) else {
+                    kprintln!("[module] TLSDESC relocation without TLS section or PLT");
+                    return Err(ModuleError::UnsupportedRelocation(rel_type));
+                };
+                let entry = crate::proc::tls::module_entry(id, (s + a) as usize);
+                let desc = plt_table.get_or_create_tls(entry).ok_or_else(|| {
+                    kprintln!("[module] PLT table full");
+                    ModuleError::UnsupportedRelocation(rel_type)
+                })? as i64;
+                match rel_type {
+                    R_AARCH64_TLSDESC_ADR_PAGE21 => Self::patch_adrp(reloc_addr, desc, rel_type)?,
+                    R_AARCH64_TLSDESC_LD64_LO12 => Self::patch_lo12(reloc_addr, desc, 3),
+                    _ => Self::patch_lo12(reloc_addr, desc, 0),
                 }
             }
 
+            R_AARCH64_TLSDESC_CALL => {
+                // resolver 호출 표시 (완화하지 않으므로 그대로)
+            }
+
+            R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21
+            | R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC
+            | R_AARCH64_TLSLE_ADD_TPREL_HI12
+            | R_AARCH64_TLSLE_ADD_TPREL_LO12_NC => {
+                kprintln!("[module] Static TLS model not supported in modules (use general-dynamic): {}", rel_type);
+                return Err(ModuleError::UnsupportedRelocation(rel_type));
+            }
+
             _ => {
                 kprintln!("[module] Unsupported AArch64 relocation type: {}", rel_type);
                 return Err(ModuleError::UnsupportedRelocation(rel_type));
@@ -805,6 +1206,39 @@ impl ModuleLoader {
         Ok(())
     }
 
+    /// ADRP 명령에 Page(target) - Page(P) 기록
+    #[cfg(target_arch = "aarch64")]
+    fn patch_adrp(reloc_addr: usize, target: i64, rel_type: u32) -> Result<(), ModuleError> {
+        let page_s = target & !0xfff;
+        let page_p = reloc_addr as i64 & !0xfff;
+        let offset = ((page_s - page_p) >> 12) as i32;
+
+        if offset > 0xfffff || offset < -0x100000 {
+            kprintln!("[module] ADRP offset out of range");
+            return Err(ModuleError::UnsupportedRelocation(rel_type));
+        }
+
+        unsafe {
+            let insn = *(reloc_addr as *mut u32);
+            let immlo = ((offset & 0x3) as u32) << 29;
+            let immhi = (((offset >> 2) & 0x7ffff) as u32) << 5;
+            let new_insn = (insn & 0x9f00001f) | immlo | immhi;
+            *(reloc_addr as *mut u32) = new_insn;
+        }
+        Ok(())
+    }
+
+    /// ADD/LDR/STR 명령의 imm12에 target 하위 12비트 기록 (`shift` = 접근 크기 log2)
+    #[cfg(target_arch = "aarch64")]
+    fn patch_lo12(reloc_addr: usize, target: i64, shift: u32) {
+        let value = ((target & 0xfff) >> shift) as u32;
+        unsafe {
+            let insn = *(reloc_addr as *mut u32);
+            let new_insn = (insn & 0xffc003ff) | (value << 10);
+            *(reloc_addr as *mut u32) = new_insn;
+        }
+    }
+
     /// RISC-V 재배치 적용
     #[cfg(target_arch = "riscv64")]
     fn apply_relocation_riscv(
@@ -812,6 +1246,7 @@ impl ModuleLoader {
         sym_value: usize,
         addend: i64,
         rel_type: u32,
+        tls_id: Option<usize>,
         hi20_results: &mut Vec<(usize, i64)>,
         plt: &mut Option<PltTable>,
     ) -> Result<(), ModuleError> {
@@ -913,14 +1348,123 @@ impl ModuleLoader {
                 let offset = s + a - p;
                 // HI20 결과를 저장 (LO12에서 참조)
                 hi20_results.push((reloc_addr, offset));
-                let value = ((offset + 0x800) >> 12) as i32;
+                Self::patch_auipc(reloc_addr, offset);
+            }
+
+            R_RISCV_GOT_HI20 => {
+                // G(S + A) - P, 대상은 PLT 페이지의 GOT 엔트리 (LO12는 PCREL_LO12_I)
+                let Some(plt_table) = plt.as_mut() else {
+                    kprintln!("[module] GOT relocation without PLT page");
+                    return Err(ModuleError::UnsupportedRelocation(rel_type));
+                };
+                let got = plt_table.get_or_create_got((s + a) as usize).ok_or_else(|| {
+                    kprintln!("[module] PLT table full");
+                    ModuleError::UnsupportedRelocation(rel_type)
+                })?;
+                let offset = got as i64 - p;
+                hi20_results.push((reloc_addr, offset));
+                Self::patch_auipc(reloc_addr, offset);
+            }
+
+            R_RISCV_32_PCREL => {
+                // S + A - P (32비트)
+                let value = (s + a - p) as i32;
                 unsafe {
-                    let insn = *(reloc_addr as *mut u32);
-                    let new_insn = (insn & 0xfff) | ((value as u32) << 12);
-                    *(reloc_addr as *mut u32) = new_insn;
+                    *(reloc_addr as *mut i32) = value;
                 }
             }
 
+            R_RISCV_ADD8 | R_RISCV_ADD16 | R_RISCV_ADD32 | R_RISCV_ADD64 => {
+                // V + S + A (심볼 차이 `a - b`의 앞쪽 절반)
+                let size = 1 << (rel_type - R_RISCV_ADD8);
+                Self::patch_data(reloc_addr, size, |v| v.wrapping_add((s + a) as u64));
+            }
+
+            R_RISCV_SUB8 | R_RISCV_SUB16 | R_RISCV_SUB32 | R_RISCV_SUB64 => {
+                // V - S - A (심볼 차이 `a - b`의 뒤쪽 절반)
+                let size = 1 << (rel_type - R_RISCV_SUB8);
+                Self::patch_data(reloc_addr, size, |v| v.wrapping_sub((s + a) as u64));
+            }
+
+            R_RISCV_SUB6 => {
+                Self::patch_data(reloc_addr, 1, |v| (v & 0xc0) | (v.wrapping_sub((s + a) as u64) & 0x3f));
+            }
+
+            R_RISCV_SET6 => {
+                Self::patch_data(reloc_addr, 1, |v| (v & 0xc0) | ((s + a) as u64 & 0x3f));
+            }
+
+            R_RISCV_SET8 | R_RISCV_SET16 | R_RISCV_SET32 => {
+                let size = 1 << (rel_type - R_RISCV_SET8);
+                Self::patch_data(reloc_addr, size, |_| (s + a) as u64);
+            }
+
+            R_RISCV_ALIGN => {
+                // 완화(명령 삭제)를 하지 않으므로 어셈블러가 넣은 nop을 그대로 둠
+            }
+
+            R_RISCV_RVC_BRANCH => {
+                // S + A - P, c.beqz/c.bnez (imm[8|4:3] 비트 12:10, imm[7:6|2:1|5] 비트 6:2)
+                let offset = s + a - p;
+                if !(-0x100..0x100).contains(&offset) {
+                    kprintln!("[module] RVC_BRANCH offset out of range: {}", offset);
+                    return Err(ModuleError::UnsupportedRelocation(rel_type));
+                }
+                let o = offset as u16;
+                let imm = ((o >> 8) & 0x1) << 12
+                    | ((o >> 3) & 0x3) << 10
+                    | ((o >> 6) & 0x3) << 5
+                    | ((o >> 1) & 0x3) << 3
+                    | ((o >> 5) & 0x1) << 2;
+                unsafe {
+                    let insn = *(reloc_addr as *mut u16);
+                    *(reloc_addr as *mut u16) = (insn & 0xe383) | imm;
+                }
+            }
+
+            R_RISCV_RVC_JUMP => {
+                // S + A - P, c.j (imm[11|4|9:8|10|6|7|3:1|5] 비트 12:2)
+                let offset = s + a - p;
+                if !(-0x800..0x800).contains(&offset) {
+                    kprintln!("[module] RVC_JUMP offset out of range: {}", offset);
+                    return Err(ModuleError::UnsupportedRelocation(rel_type));
+                }
+                let o = offset as u16;
+                let imm = ((o >> 11) & 0x1) << 12
+                    | ((o >> 4) & 0x1) << 11
+                    | ((o >> 8) & 0x3) << 9
+                    | ((o >> 10) & 0x1) << 8
+                    | ((o >> 6) & 0x1) << 7
+                    | ((o >> 7) & 0x1) << 6
+                    | ((o >> 1) & 0x7) << 3
+                    | ((o >> 5) & 0x1) << 2;
+                unsafe {
+                    let insn = *(reloc_addr as *mut u16);
+                    *(reloc_addr as *mut u16) = (insn & 0xe003) | imm;
+                }
+            }
+
+            R_RISCV_TLS_GD_HI20 => {
+                // S + A = TLS 블록 안 오프셋, 대상은 PLT 페이지의 TlsIndex (auipc, LO12는 PCREL_LO12_I)
+                let (Some(id), Some(plt_table)) = (tls_id, plt.as_mut()) else {
+                    kprintln!("[module] TLS_GD relocation without TLS section or PLT");
+                    return Err(ModuleError::UnsupportedRelocation(rel_type));
+                };
+                let entry = crate::proc::tls::module_entry(id, (s + a) as usize);
+                let index = plt_table.get_or_create_tls(entry).ok_or_else(|| {
+                    kprintln!("[module] PLT table full");
+                    ModuleError::UnsupportedRelocation(rel_type)
+                })?;
+                let offset = index as i64 - p;
+                hi20_results.push((reloc_addr, offset));
+                Self::patch_auipc(reloc_addr, offset);
+            }
+
+            R_RISCV_TLS_GOT_HI20 | R_RISCV_TPREL_HI20 | R_RISCV_TPREL_LO12_I | R_RISCV_TPREL_LO12_S | R_RISCV_TPREL_ADD => {
+                kprintln!("[module] Static TLS model not supported in modules (use general-dynamic): {}", rel_type);
+                return Err(ModuleError::UnsupportedRelocation(rel_type));
+            }
+
             R_RISCV_PCREL_LO12_I => {
                 // 하위 12비트 (I-type: load, addi 등)
                 // 심볼은 대응하는 HI20의 주소를 가리킴
@@ -1003,6 +1547,27 @@ impl ModuleLoader {
         Ok(())
     }
 
+    /// auipc 명령에 PC 상대 오프셋의 상위 20비트 기록
+    #[cfg(target_arch = "riscv64")]
+    fn patch_auipc(reloc_addr: usize, offset: i64) {
+        let value = ((offset + 0x800) >> 12) as i32;
+        unsafe {
+            let insn = *(reloc_addr as *mut u32);
+            let new_insn = (insn & 0xfff) | ((value as u32) << 12);
+            *(reloc_addr as *mut u32) = new_insn;
+        }
+    }
+
+    /// `size`바이트 데이터 값(little endian, 정렬 무관)을 `f`로 바꿈
+    #[cfg(target_arch = "riscv64")]
+    fn patch_data(reloc_addr: usize, size: usize, f: impl FnOnce(u64) -> u64) {
+        let bytes = unsafe { core::slice::from_raw_parts_mut(reloc_addr as *mut u8, size) };
+        let mut buf = [0u8; 8];
+        buf[..size].copy_from_slice(bytes);
+        let value = f(u64::from_le_bytes(buf)).to_le_bytes();
+        bytes.copy_from_slice(&value[..size]);
+    }
+
     /// 명령어 캐시 플러시
     fn flush_icache(addr: usize, size: usize) {
         #[cfg(target_arch = "aarch64")]
@@ -1041,63 +1606,80 @@ impl ModuleLoader {
     /// 1. unloading 플래그 설정 (새 참조 획득 차단)
     /// 2. 기존 참조가 모두 해제될 때까지 대기 (또는 즉시 실패)
     /// 3. exit 함수 호출
-    /// 4. 메모리 해제
+    /// 4. 장부에 남은 자원(스레드/타이머/워크/IRQ) 강제 회수
+    /// 5. 메모리 해제 (회수 실패 시 페이지는 남김)
     pub fn unload(name: &str) -> Result<(), ModuleError> {
         // 1. 먼저 unloading 플래그 설정
-        {
-            let modules = LOADED_MODULES.read();
-            let module = modules
-                .iter()
-                .find(|m| m.info.name == name)
-                .ok_or(ModuleError::NotFound)?;
+        let module = find_module(name).ok_or(ModuleError::NotFound)?;
 
-            // 이미 언로딩 중인지 확인
-            if module.unloading.swap(true, Ordering::SeqCst) {
-                return Err(ModuleError::ModuleUnloading);
-            }
+        // 이미 언로딩 중인지 확인
+        if module.unloading.swap(true, Ordering::SeqCst) {
+            return Err(ModuleError::ModuleUnloading);
         }
 
         // 2. 참조 카운트 확인 (즉시 실패 방식)
-        {
-            let modules = LOADED_MODULES.read();
-            let module = modules
-                .iter()
-                .find(|m| m.info.name == name)
-                .ok_or(ModuleError::NotFound)?;
-
-            if module.ref_count.load(Ordering::SeqCst) > 0 {
-                // 플래그 롤백
-                module.unloading.store(false, Ordering::SeqCst);
-                return Err(ModuleError::InUse);
-            }
+        if module.ref_count.load(Ordering::SeqCst) > 0 {
+            // 플래그 롤백
+            module.unloading.store(false, Ordering::SeqCst);
+            return Err(ModuleError::InUse);
         }
 
         // 3. 실제 언로드 수행
-        let mut modules = LOADED_MODULES.write();
+        Self::remove(&module);
 
-        let idx = modules
-            .iter()
-            .position(|m| m.info.name == name)
-            .ok_or(ModuleError::NotFound)?;
+        kprintln!("[module] Module '{}' unloaded", name);
+        crate::mm::owner::module_unloaded(name);
 
-        let module = &modules[idx];
+        Ok(())
+    }
 
-        // exit 함수 호출
-        module.exit();
+    /// exit 호출, 남은 자원 회수와 메모리 해제 후 목록에서 제거
+    ///
+    /// 제거 전까지는 `module_at`/`symbolize`가 exit 중인 모듈 주소도 찾습니다. exit가
+    /// 제한 시간을 넘겨도 (Failed) 자원을 회수하고 목록에서 뺍니다.
+    fn remove(module: &Arc<LoadedModule>) {
+        let _guard = MODULES_WRITE.lock();
 
-        // 메모리 해제
-        for &page in &module.pages {
-            unsafe {
-                page::free_frame(page);
-            }
-        }
+        // exit 함수 호출 (제한 시간 초과는 exit가 로그 출력)
+        let _ = module.exit();
+
+        // 남은 자원 회수 후 메모리 해제
+        Self::release_module_memory(module);
 
         // 목록에서 제거
-        modules.remove(idx);
+        LOADED_MODULES.update(|modules| modules.retain(|m| !Arc::ptr_eq(m, module)));
+    }
 
-        kprintln!("[module] Module '{}' unloaded", name);
+    /// exit 이후: 장부에 남은 자원을 회수하고 모듈 페이지 해제
+    ///
+    /// 취소하지 못한 자원이 있거나 제한 시간을 넘긴 init/exit 스레드가 CPU에서 내려가지
+    /// 않았으면 아직 모듈 코드가 실행될 수 있으므로 페이지를 해제하지 않고 남깁니다.
+    fn release_module_memory(module: &LoadedModule) {
+        let stats = super::ledger::reclaim(&module.info.name);
+        if stats.failed > 0 {
+            kprintln!(
+                "[module] '{}': {} resource(s) still live, leaking {} pages at {:#x}",
+                module.info.name,
+                stats.failed,
+                module.pages.len(),
+                module.base_addr
+            );
+            return;
+        }
+        if module.stuck.load(Ordering::Acquire) {
+            kprintln!(
+                "[module] '{}': init/exit thread still running, leaking {} pages at {:#x}",
+                module.info.name,
+                module.pages.len(),
+                module.base_addr
+            );
+            return;
+        }
 
-        Ok(())
+        Self::free_pages(&module.pages);
+        if let Some(id) = module.tls_id {
+            crate::proc::tls::unregister(id);
+        }
     }
 
     /// 모듈 언로드 (참조 해제 대기)
@@ -1106,31 +1688,18 @@ impl ModuleLoader {
     /// 반환: 성공 시 Ok(()), 타임아웃 시 Err(InUse)
     pub fn unload_wait(name: &str, max_wait_ms: usize) -> Result<(), ModuleError> {
         // 1. unloading 플래그 설정
-        {
-            let modules = LOADED_MODULES.read();
-            let module = modules
-                .iter()
-                .find(|m| m.info.name == name)
-                .ok_or(ModuleError::NotFound)?;
+        let module = find_module(name).ok_or(ModuleError::NotFound)?;
 
-            if module.unloading.swap(true, Ordering::SeqCst) {
-                return Err(ModuleError::ModuleUnloading);
-            }
+        if module.unloading.swap(true, Ordering::SeqCst) {
+            return Err(ModuleError::ModuleUnloading);
         }
 
         // 2. 참조 카운트가 0이 될 때까지 대기
         let _start = 0usize; // TODO: 실제 타이머 사용
         let mut waited = 0usize;
         loop {
-            {
-                let modules = LOADED_MODULES.read();
-                if let Some(module) = modules.iter().find(|m| m.info.name == name) {
-                    if module.ref_count.load(Ordering::SeqCst) == 0 {
-                        break; // 참조 해제됨
-                    }
-                } else {
-                    return Err(ModuleError::NotFound);
-                }
+            if module.ref_count.load(Ordering::SeqCst) == 0 {
+                break; // 참조 해제됨
             }
 
             // 스핀 대기 (TODO: yield 또는 sleep 사용)
@@ -1141,34 +1710,16 @@ impl ModuleLoader {
 
             if max_wait_ms > 0 && waited >= max_wait_ms {
                 // 타임아웃: 플래그 롤백
-                let modules = LOADED_MODULES.read();
-                if let Some(module) = modules.iter().find(|m| m.info.name == name) {
-                    module.unloading.store(false, Ordering::SeqCst);
-                }
+                module.unloading.store(false, Ordering::SeqCst);
                 return Err(ModuleError::InUse);
             }
         }
 
         // 3. 실제 언로드
-        let mut modules = LOADED_MODULES.write();
-
-        let idx = modules
-            .iter()
-            .position(|m| m.info.name == name)
-            .ok_or(ModuleError::NotFound)?;
-
-        let module = &modules[idx];
-        module.exit();
-
-        for &page in &module.pages {
-            unsafe {
-                page::free_frame(page);
-            }
-        }
-
-        modules.remove(idx);
+        Self::remove(&module);
 
         kprintln!("[module] Module '{}' unloaded (waited {} iterations)", name, waited);
+        crate::mm::owner::module_unloaded(name);
 
         Ok(())
     }
@@ -1178,20 +1729,14 @@ impl ModuleLoader {
     /// 모듈이 존재하고 언로딩 중이 아니면 참조를 획득하고 ModuleRef 반환
     /// ModuleRef가 drop되면 자동으로 참조 카운트 감소
     pub fn acquire(name: &str) -> Result<ModuleRef, ModuleError> {
-        let modules = LOADED_MODULES.read();
-        let module = modules
-            .iter()
-            .find(|m| m.info.name == name)
-            .ok_or(ModuleError::NotFound)?;
+        let module = find_module(name).ok_or(ModuleError::NotFound)?;
 
         // 안전한 참조 획득 시도
         if module.try_get().is_none() {
             return Err(ModuleError::ModuleUnloading);
         }
 
-        Ok(ModuleRef {
-            module_name: String::from(name),
-        })
+        Ok(ModuleRef { module })
     }
 
     /// 로드된 모듈 목록
@@ -1208,26 +1753,61 @@ impl ModuleLoader {
             version: m.info.version.clone(),
             base_addr: m.base_addr,
             size: m.size,
-            state: m.state,
+            state: m.state(),
             ref_count: m.ref_count.load(Ordering::SeqCst),
             is_unloading: m.unloading.load(Ordering::SeqCst),
-            exported_symbol_count: m.exported_symbols.len(),
+            exported_symbol_count: m.exported_symbols.read().len(),
+            checksum: m.checksum,
         })
     }
 
-    /// 특정 모듈에서 심볼 검색
+    /// 특정 모듈에서 심볼 검색 (언로딩 중인 모듈 제외)
     pub fn lookup_symbol_in(module_name: &str, symbol_name: &str) -> Option<usize> {
         let modules = LOADED_MODULES.read();
         for module in modules.iter() {
-            if module.info.name == module_name {
+            if module.info.name == module_name && !module.is_unloading() {
                 return module.lookup_symbol(symbol_name);
             }
         }
         None
     }
 
+    /// 주소가 속한 모듈 이름 검색 (코드/데이터/PLT 페이지 기준)
+    pub fn module_at(addr: usize) -> Option<String> {
+        let modules = LOADED_MODULES.read();
+        modules
+            .iter()
+            .find(|m| m.pages.iter().any(|&p| addr >= p && addr < p + PAGE_SIZE))
+            .map(|m| m.info.name.clone())
+    }
+
+    /// 주소가 속한 모듈의 가장 가까운 앞쪽 심볼을 `f(심볼, 오프셋, 모듈)`에 전달
+    ///
+    /// 락을 기다리지 않고 힙을 쓰지 않습니다 (패닉 백트레이스용).
+    ///
+    /// # Returns
+    /// 모듈 주소가 아니거나 아직 모듈을 로드한 적이 없으면 None, 모듈 주소면 심볼을 찾았는지
+    pub fn symbolize(addr: usize, f: &mut dyn FnMut(&str, usize, Option<&str>)) -> Option<bool> {
+        let modules = LOADED_MODULES.try_read()?;
+        let module = modules
+            .iter()
+            .find(|m| m.pages.iter().any(|&p| addr >= p && addr < p + PAGE_SIZE))?;
+        let symbols = module.exported_symbols.read();
+        let nearest = symbols
+            .iter()
+            .filter(|(_, sym)| *sym <= addr)
+            .max_by_key(|(_, sym)| *sym);
+        Some(match nearest {
+            Some((name, sym)) => {
+                f(name, addr - sym, Some(&module.info.name));
+                true
+            }
+            None => false,
+        })
+    }
+
     /// 모든 모듈에서 심볼 검색 (커널 심볼 포함)
-    /// 검색 순서: 커널 → 로드된 모듈들 (로드 순서)
+    /// 검색 순서: 커널 → 로드된 모듈들 (로드 순서, 언로딩 중인 모듈 제외)
     pub fn lookup_symbol_global(name: &str) -> Option<usize> {
         // 1. 커널 심볼 테이블에서 검색
         if let Some(addr) = lookup_symbol(name) {
@@ -1236,7 +1816,7 @@ impl ModuleLoader {
 
         // 2. 로드된 모듈들에서 검색
         let modules = LOADED_MODULES.read();
-        for module in modules.iter() {
+        for module in modules.iter().filter(|m| !m.is_unloading()) {
             if let Some(addr) = module.lookup_symbol(name) {
                 return Some(addr);
             }
@@ -1250,7 +1830,7 @@ impl ModuleLoader {
         let modules = LOADED_MODULES.read();
         for module in modules.iter() {
             if module.info.name == module_name {
-                return module.exported_symbols.clone();
+                return module.exported_symbols.read().clone();
             }
         }
         Vec::new()
@@ -1258,19 +1838,18 @@ impl ModuleLoader {
 
     /// 모듈에 심볼 export (외부에서 호출용)
     pub fn export_symbol(module_name: &str, symbol_name: &str, address: usize) -> bool {
-        let mut modules = LOADED_MODULES.write();
-        for module in modules.iter_mut() {
-            if module.info.name == module_name {
+        match find_module(module_name) {
+            Some(module) => {
                 module.export_symbol(symbol_name, address);
-                return true;
+                true
             }
+            None => false,
         }
-        false
     }
 
     /// VFS 파일 경로에서 모듈 로드
     /// RamFS, DevFS 등에서 모듈 파일을 읽어 로드
-    pub fn load_from_path(path: &str) -> Result<&'static LoadedModule, ModuleError> {
+    pub fn load_from_path(path: &str) -> Result<Arc<LoadedModule>, ModuleError> {
         use alloc::vec::Vec;
         use crate::fs;
 
@@ -1344,27 +1923,29 @@ pub mod builtin {
         kprintln!("[module] Test module at 0x{:x}", base_addr);
 
         // LoadedModule 생성
-        let module = Box::new(LoadedModule {
+        let module = Arc::new(LoadedModule {
             info: Module::new("test_builtin"),
             base_addr,
             size: PAGE_SIZE,
-            state: ModuleState::Live,
+            state: AtomicU8::new(ModuleState::Loading as u8),
             ref_count: AtomicUsize::new(0),
             unloading: AtomicBool::new(false),
+            stuck: AtomicBool::new(false),
             init_fn: Some(test_module_init as usize),
             exit_fn: Some(test_module_exit as usize),
             pages: alloc::vec![base_addr],
             section_addrs: alloc::vec![],
-            exported_symbols: Vec::new(),
+            exported_symbols: RcuCell::new(Vec::new()),
             plt_page: None, // 테스트 모듈은 PLT 불필요
+            checksum: None,
+            tls_id: None,
         });
 
         // init 호출
         module.init()?;
 
         // 목록에 추가
-        let mut modules = LOADED_MODULES.write();
-        modules.push(module);
+        publish(&module);
 
         kprintln!("[module] Builtin test module loaded successfully");
         Ok(())
@@ -5,12 +5,50 @@
 //! - 심볼 테이블 관리
 //! - 재배치 처리
 //! - 모듈 라이프사이클
+//! - 모듈 소유 자원 장부 (언로드 시 강제 회수)
+//! - 체크섬 트레일러 검증 (`module.verify=`)
+//! - init/exit 전용 스레드 실행, 제한 시간 (`module.timeout=`)
 
 pub mod elf;
+pub mod ledger;
 pub mod loader;
+pub mod sandbox;
 pub mod symbol;
 pub mod test_symbols;
+pub mod verify;
+
+use crate::sysctl::{self, Kind, Tunable, Value};
 
 pub use elf::{Elf64, Elf64Error};
-pub use loader::{LoadedModule, Module, ModuleError, ModuleInfo, ModuleLoader, ModuleRef, ModuleState};
+pub use loader::{LoadedExecutable, LoadedModule, Module, ModuleError, ModuleInfo, ModuleLoader, ModuleRef, ModuleState};
 pub use symbol::{lookup_symbol, register_symbol, KernelSymbol};
+
+static SYSCTL: [Tunable; 2] = [
+    Tunable {
+        name: "module.verify",
+        desc: "checksum trailer policy (off, warn, enforce)",
+        kind: Kind::Str,
+        get: || Value::Str(alloc::format!("{}", verify::mode())),
+        set: Some(|v| {
+            verify::set_mode(verify::VerifyMode::from_str(sysctl::string(&v)).ok_or("expected off, warn or enforce")?);
+            Ok(())
+        }),
+    },
+    Tunable {
+        name: "module.timeout_ms",
+        desc: "init/exit time limit in milliseconds (0 = unlimited)",
+        kind: Kind::Int { min: 0, max: 3_600_000 },
+        get: || Value::Int(sandbox::timeout_ms() as i64),
+        set: Some(|v| {
+            sandbox::set_timeout_ms(sysctl::int(&v) as u64);
+            Ok(())
+        }),
+    },
+];
+
+/// 명령줄의 `module.verify=`, `module.timeout=` 적용
+pub fn init_from_cmdline() {
+    verify::init_from_cmdline();
+    sandbox::init_from_cmdline();
+    sysctl::register_all(&SYSCTL);
+}
@@ -0,0 +1,157 @@
+//! 모듈 init/exit 격리 실행
+//!
+//! `module_init`/`module_exit`을 호출자 스택에서 바로 부르지 않고 전용 커널 스레드
+//! (`modinit/<이름>`, `modexit/<이름>`)에서 실행한 뒤 제한 시간까지 기다립니다. init에서
+//! 멈춘 모듈이 셸(`insmod`)이나 테스트 러너를 붙잡지 않습니다.
+//!
+//! 제한 시간을 넘기면 스레드를 `proc::kill`로 종료하고 CPU에서 내려갈 때까지 기다립니다.
+//! 내려가면 모듈 코드가 더 이상 실행되지 않으므로 로더가 페이지를 해제하고, 인터럽트를
+//! 끈 채 멈춰 내려가지 않으면 페이지를 남깁니다 (`CallError::stopped`).
+//!
+//! 제한 시간은 `module.timeout=<초>` 명령줄 옵션으로 정합니다 (기본 5초, 0 = 무제한).
+//! 스케줄러가 시작되기 전에는 호출자 스택에서 바로 실행합니다.
+
+use alloc::format;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, Ordering};
+
+use crate::proc::{self, Tid};
+use crate::sync::Mutex;
+
+/// 기본 제한 시간 (밀리초)
+const DEFAULT_TIMEOUT_MS: u64 = 5000;
+
+/// 종료한 스레드가 CPU에서 내려갈 때까지 기다리는 최대 양보 횟수
+const DRAIN_YIELDS: usize = 100;
+
+/// 제한 시간 (밀리초, 0 = 무제한)
+static TIMEOUT_MS: AtomicU64 = AtomicU64::new(DEFAULT_TIMEOUT_MS);
+
+/// 실행할 모듈 함수
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entry {
+    /// `extern "C" fn() -> i32`
+    Init(usize),
+    /// `fn()`
+    Exit(usize),
+}
+
+/// 제한 시간 초과
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallError {
+    /// 실행하던 스레드
+    pub tid: Tid,
+    /// 스레드가 CPU에서 내려갔는지 (false면 모듈 코드가 아직 실행 중일 수 있음)
+    pub stopped: bool,
+}
+
+/// 호출 하나의 상태 (제한 시간을 넘긴 스레드가 나중에 끝나도 다음 호출과 섞이지 않음)
+struct Call {
+    entry: Entry,
+    waiter: Tid,
+    done: AtomicBool,
+    result: AtomicI32,
+}
+
+/// 새 스레드에 넘길 호출
+static PENDING: Mutex<Option<Arc<Call>>> = Mutex::new(None);
+
+/// 호출 직렬화 (`PENDING` 슬롯 하나)
+static CALL_LOCK: Mutex<()> = Mutex::new(());
+
+/// 명령줄의 `module.timeout=` 적용
+pub fn init_from_cmdline() {
+    if let Some(secs) = crate::boot::cmdline::module_timeout() {
+        set_timeout_ms(secs as u64 * 1000);
+    }
+}
+
+pub fn set_timeout_ms(ms: u64) {
+    TIMEOUT_MS.store(ms, Ordering::Relaxed);
+}
+
+pub fn timeout_ms() -> u64 {
+    TIMEOUT_MS.load(Ordering::Relaxed)
+}
+
+/// 모듈 `name`의 함수를 전용 스레드에서 실행하고 결과 반환 (exit는 0)
+pub fn call(name: &str, entry: Entry) -> Result<i32, CallError> {
+    let Some(waiter) = proc::current_tid() else {
+        return Ok(invoke(entry));
+    };
+
+    let _guard = CALL_LOCK.lock();
+    let call = Arc::new(Call {
+        entry,
+        waiter,
+        done: AtomicBool::new(false),
+        result: AtomicI32::new(0),
+    });
+    *PENDING.lock() = Some(Arc::clone(&call));
+
+    let thread_name = match entry {
+        Entry::Init(_) => format!("modinit/{}", name),
+        Entry::Exit(_) => format!("modexit/{}", name),
+    };
+    let tid = proc::spawn(&thread_name, call_thread);
+
+    let timeout_ms = timeout_ms();
+    let deadline = crate::time::now_us().saturating_add(timeout_ms * 1000);
+    while !call.done.load(Ordering::Acquire) {
+        let now = crate::time::now_us();
+        if timeout_ms == 0 {
+            proc::park();
+        } else if now >= deadline {
+            break;
+        } else {
+            proc::park_timeout(deadline - now);
+        }
+    }
+
+    if call.done.load(Ordering::Acquire) {
+        let _ = proc::join(tid);
+        return Ok(call.result.load(Ordering::Acquire));
+    }
+
+    // 제한 시간 초과: 스레드를 멈추고 모듈 코드에서 내려올 때까지 대기
+    PENDING.lock().take();
+    proc::kill(tid);
+    let stopped = wait_off_cpu(tid);
+    let _ = proc::detach(tid);
+    Err(CallError { tid, stopped })
+}
+
+fn wait_off_cpu(tid: Tid) -> bool {
+    for _ in 0..DRAIN_YIELDS {
+        if !proc::is_on_cpu(tid) {
+            return true;
+        }
+        proc::yield_now();
+    }
+    false
+}
+
+fn call_thread() -> ! {
+    let Some(call) = PENDING.lock().take() else {
+        proc::exit();
+    };
+    call.result.store(invoke(call.entry), Ordering::Release);
+    call.done.store(true, Ordering::Release);
+    proc::unpark(call.waiter);
+    proc::exit();
+}
+
+fn invoke(entry: Entry) -> i32 {
+    match entry {
+        Entry::Init(addr) => {
+            // module_init() -> i32 (PLT 사용으로 인자 없음)
+            let init: extern "C" fn() -> i32 = unsafe { core::mem::transmute(addr) };
+            init()
+        }
+        Entry::Exit(addr) => {
+            let exit: fn() = unsafe { core::mem::transmute(addr) };
+            exit();
+            0
+        }
+    }
+}
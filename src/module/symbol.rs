@@ -1,8 +1,21 @@
 //! 커널 심볼 테이블
 //!
 //! 모듈이 커널 함수를 호출할 수 있도록 심볼 테이블 관리
-//! - 정적 커널 심볼 (런타임 초기화)
+//! - 정적 커널 심볼 (`export_symbol!`로 `.ksymtab` 링크 섹션에 모음)
 //! - 동적 심볼 등록/해제
+//!
+//! 커널 API를 모듈에 노출하려면 함수 정의 옆에 `export_symbol!`만 쓰면 됩니다.
+//! 링커 스크립트가 모든 `.ksymtab` 항목을 `__ksymtab_start`..`__ksymtab_end`
+//! 사이에 모으고, `init()`이 이를 읽어 심볼 테이블을 채웁니다.
+//!
+//! ```rust,ignore
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn kernel_foo() { ... }
+//! crate::export_symbol!(kernel_foo);
+//!
+//! // 다른 이름으로 노출
+//! crate::export_symbol!(crate::console::puts, "console_puts");
+//! ```
 
 use alloc::string::String;
 use alloc::vec::Vec;
@@ -28,6 +41,54 @@ impl KernelSymbol {
     }
 }
 
+/// `.ksymtab` 섹션 항목 (`export_symbol!`이 생성)
+#[repr(C)]
+pub struct KsymEntry {
+    /// 심볼 이름
+    pub name: &'static str,
+    /// 함수 주소
+    pub address: *const (),
+}
+
+// Safety: 주소는 읽기만 하는 상수
+unsafe impl Sync for KsymEntry {}
+
+/// 커널 함수를 모듈용 심볼 테이블에 노출
+///
+/// `export_symbol!(func)`는 함수 이름 그대로, `export_symbol!(path, "name")`은
+/// 지정한 이름으로 노출합니다. 모듈에서 부를 함수는 `extern "C"`여야 합니다.
+#[macro_export]
+macro_rules! export_symbol {
+    ($func:ident) => {
+        $crate::export_symbol!($func, stringify!($func));
+    };
+    ($func:path, $name:expr) => {
+        const _: () = {
+            #[used]
+            #[unsafe(link_section = ".ksymtab")]
+            static KSYM: $crate::module::symbol::KsymEntry = $crate::module::symbol::KsymEntry {
+                name: $name,
+                address: $func as *const (),
+            };
+        };
+    };
+}
+
+/// 링커가 모은 `.ksymtab` 항목들
+pub fn ksymtab() -> &'static [KsymEntry] {
+    unsafe extern "C" {
+        static __ksymtab_start: u8;
+        static __ksymtab_end: u8;
+    }
+    // Safety: 링커 스크립트가 두 심볼 사이에 KsymEntry 배열만 둠
+    unsafe {
+        let start = &raw const __ksymtab_start as usize;
+        let end = &raw const __ksymtab_end as usize;
+        let count = (end - start) / core::mem::size_of::<KsymEntry>();
+        core::slice::from_raw_parts(start as *const KsymEntry, count)
+    }
+}
+
 /// 심볼 테이블 (동적 + 정적 통합) - Vec으로 변경하여 BTreeMap 문제 회피
 static SYMBOLS: RwLock<Option<Vec<(String, usize)>>> = RwLock::new(None);
 static INITIALIZED: AtomicBool = AtomicBool::new(false);
@@ -44,6 +105,7 @@ pub extern "C" fn kernel_print(s: *const u8, len: usize) {
         crate::console::puts(msg);
     }
 }
+crate::export_symbol!(kernel_print);
 
 // 컴파일러 intrinsic: 모듈에서 배열 초기화/복사 시 컴파일러가 자동 호출
 // volatile 연산 사용 — 컴파일러가 이 루프를 memset/memcpy 호출로 최적화하면
@@ -58,6 +120,7 @@ pub unsafe extern "C" fn memset(dest: *mut u8, val: i32, count: usize) -> *mut u
     }
     dest
 }
+crate::export_symbol!(memset);
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn memcpy(dest: *mut u8, src: *const u8, count: usize) -> *mut u8 {
@@ -68,6 +131,7 @@ pub unsafe extern "C" fn memcpy(dest: *mut u8, src: *const u8, count: usize) ->
     }
     dest
 }
+crate::export_symbol!(memcpy);
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn memmove(dest: *mut u8, src: *const u8, count: usize) -> *mut u8 {
@@ -86,6 +150,12 @@ pub unsafe extern "C" fn memmove(dest: *mut u8, src: *const u8, count: usize) ->
     }
     dest
 }
+crate::export_symbol!(memmove);
+
+crate::export_symbol!(crate::console::puts, "console_puts");
+crate::export_symbol!(crate::console::putc, "console_putc");
+crate::export_symbol!(crate::proc::yield_now, "yield_now");
+crate::export_symbol!(crate::proc::current_tid, "current_tid");
 
 /// 심볼 테이블 초기화
 pub fn init() {
@@ -93,28 +163,14 @@ pub fn init() {
         return; // 이미 초기화됨
     }
 
-    let mut symbols = SYMBOLS.write();
-    let mut list = Vec::new();
-
-    // 커널 심볼 등록
-    list.push((String::from("console_puts"), crate::console::puts as usize));
-    list.push((String::from("console_putc"), crate::console::putc as usize));
-    list.push((String::from("yield_now"), crate::proc::yield_now as usize));
-    list.push((String::from("current_tid"), crate::proc::current_tid as usize));
-    // 모듈용 출력 함수
-    list.push((String::from("kernel_print"), kernel_print as usize));
-    // 컴파일러 intrinsic (배열 초기화 등에서 컴파일러가 자동 생성)
-    list.push((String::from("memset"), memset as usize));
-    list.push((String::from("memcpy"), memcpy as usize));
-    list.push((String::from("memmove"), memmove as usize));
-
-    *symbols = Some(list);
-
-    crate::kprintln!("[symbol] Kernel symbol table initialized");
+    let list: Vec<(String, usize)> = ksymtab()
+        .iter()
+        .map(|entry| (String::from(entry.name), entry.address as usize))
+        .collect();
+    let count = list.len();
+    *SYMBOLS.write() = Some(list);
 
-    // 테스트 모듈용 심볼 등록
-    drop(symbols); // 쓰기 락 해제 후 등록 (register_symbol이 다시 잠금)
-    super::test_symbols::register_test_symbols();
+    crate::kprintln!("[symbol] Kernel symbol table initialized ({} symbols)", count);
 }
 
 /// 심볼 조회
@@ -131,6 +187,31 @@ pub fn lookup_symbol(name: &str) -> Option<usize> {
     None
 }
 
+/// 주소를 가장 가까운 앞쪽 심볼로 해석해 `f(심볼, 오프셋, 모듈)`에 전달 (백트레이스용)
+///
+/// 커널 주소는 `.ksymtab`의 익스포트 심볼만 보므로, 익스포트하지 않은 함수는 앞쪽 익스포트
+/// 심볼 + 오프셋으로 나옵니다 (정확한 위치는 `addr2line`). 모듈 주소는 그 모듈이 내보낸
+/// 심볼에서 찾습니다. 락을 기다리거나 힙을 쓰지 않으므로 패닉 중에도 부를 수 있습니다.
+///
+/// # Returns
+/// 심볼을 찾았는지
+pub fn symbolize(addr: usize, f: &mut dyn FnMut(&str, usize, Option<&str>)) -> bool {
+    if let Some(found) = super::ModuleLoader::symbolize(addr, f) {
+        return found;
+    }
+    let nearest = ksymtab()
+        .iter()
+        .filter(|entry| entry.address as usize <= addr)
+        .max_by_key(|entry| entry.address as usize);
+    match nearest {
+        Some(entry) => {
+            f(entry.name, addr - entry.address as usize, None);
+            true
+        }
+        None => false,
+    }
+}
+
 /// 동적 심볼 등록
 pub fn register_symbol(name: &str, address: usize) {
     // 초기화 확인
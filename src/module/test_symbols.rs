@@ -1,18 +1,33 @@
 //! 테스트 모듈용 커널 심볼 래퍼
 //!
 //! 커널 모듈(.ko)은 extern "C" 함수만 호출 가능하므로,
-//! 커널 내부 API를 C-compatible 래퍼로 감싸 심볼 테이블에 등록한다.
+//! 커널 내부 API를 C-compatible 래퍼로 감싸 `export_symbol!`로 노출한다.
 
 // ============================================================
 // MM (메모리 관리)
 // ============================================================
 
+/// 현재 함수의 복귀 주소 (호출한 모듈 판별용, 0 = 모름)
+///
+/// lr/ra 레지스터는 프롤로그 뒤 코드 생성에 따라 덮어써질 수 있으므로, 프롤로그가 저장한
+/// 프레임 레코드(aarch64 `[fp, #8]`, riscv64 `-8(s0)`)에서 읽습니다. 매크로를 쓴 함수의
+/// 프레임 포인터가 필요하므로 `trace::backtrace`와 같이 `force-frame-pointers`에 의존합니다.
+macro_rules! return_address {
+    () => {{
+        use crate::trace::backtrace;
+        let mut ra = 0;
+        backtrace::walk(backtrace::current_fp(), 1, |ret| ra = ret);
+        ra
+    }};
+}
+
 /// 페이지 프레임 할당 (C-ABI 래퍼)
 /// 반환: 할당된 주소 (0 = 실패)
 #[unsafe(no_mangle)]
 pub extern "C" fn alloc_frame() -> usize {
     crate::mm::page::alloc_frame().unwrap_or(0)
 }
+crate::export_symbol!(alloc_frame);
 
 /// 페이지 프레임 해제
 #[unsafe(no_mangle)]
@@ -21,12 +36,14 @@ pub extern "C" fn free_frame(addr: usize) {
         crate::mm::page::free_frame(addr);
     }
 }
+crate::export_symbol!(free_frame);
 
 /// 힙 메모리 할당
 /// 반환: 할당된 주소 (0 = 실패)
 #[unsafe(no_mangle)]
 pub extern "C" fn kernel_heap_alloc(size: usize, align: usize) -> usize {
     use core::alloc::Layout;
+    let caller = return_address!();
     if size == 0 || align == 0 || !align.is_power_of_two() {
         return 0;
     }
@@ -36,11 +53,14 @@ pub extern "C" fn kernel_heap_alloc(size: usize, align: usize) -> usize {
     };
     let ptr = unsafe { alloc::alloc::alloc(layout) };
     if ptr.is_null() {
-        0
-    } else {
-        ptr as usize
+        return 0;
     }
+    if let Some(owner) = crate::module::ModuleLoader::module_at(caller) {
+        crate::mm::owner::record_alloc(&owner, ptr as usize, size);
+    }
+    ptr as usize
 }
+crate::export_symbol!(kernel_heap_alloc);
 
 /// 힙 메모리 해제
 #[unsafe(no_mangle)]
@@ -53,10 +73,12 @@ pub extern "C" fn kernel_heap_dealloc(ptr: usize, size: usize, align: usize) {
         Ok(l) => l,
         Err(_) => return,
     };
+    crate::mm::owner::record_free(ptr);
     unsafe {
         alloc::alloc::dealloc(ptr as *mut u8, layout);
     }
 }
+crate::export_symbol!(kernel_heap_dealloc);
 
 // ============================================================
 // IPC (메시지 큐)
@@ -84,6 +106,7 @@ pub extern "C" fn kernel_mq_open(name: *const u8, name_len: usize, create: bool)
         Err(_) => -1,
     }
 }
+crate::export_symbol!(kernel_mq_open);
 
 /// 메시지 전송
 /// 반환: 0 = 성공, -1 = 실패
@@ -107,6 +130,7 @@ pub extern "C" fn kernel_mq_send(
         Err(_) => -1,
     }
 }
+crate::export_symbol!(kernel_mq_send);
 
 /// 메시지 수신
 /// 반환: 수신 바이트 수, -1 = 실패
@@ -141,6 +165,81 @@ pub extern "C" fn kernel_mq_receive(
         Err(_) => -1,
     }
 }
+crate::export_symbol!(kernel_mq_receive);
+
+// ============================================================
+// IPC (공유 메모리)
+// ============================================================
+
+/// 공유 메모리 세그먼트 열기/생성
+/// 반환: 0 = 성공, -1 = 실패
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_shm_open(name: *const u8, name_len: usize, create: bool) -> i32 {
+    let name = match str_from_raw(name, name_len) {
+        Some(s) => s,
+        None => return -1,
+    };
+    match crate::ipc::shm::shm_open(name, create) {
+        Ok(_) => 0,
+        Err(_) => -1,
+    }
+}
+crate::export_symbol!(kernel_shm_open);
+
+/// 세그먼트 크기 변경 (매핑 중이면 실패)
+/// 반환: 0 = 성공, -1 = 실패
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_shm_resize(name: *const u8, name_len: usize, size: usize) -> i32 {
+    let name = match str_from_raw(name, name_len) {
+        Some(s) => s,
+        None => return -1,
+    };
+    match crate::ipc::shm::shm_open(name, false).and_then(|seg| seg.resize(size)) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+crate::export_symbol!(kernel_shm_resize);
+
+/// 세그먼트 매핑
+/// 반환: 매핑 주소 (0 = 실패)
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_shm_map(name: *const u8, name_len: usize, offset: usize, len: usize) -> usize {
+    let name = match str_from_raw(name, name_len) {
+        Some(s) => s,
+        None => return 0,
+    };
+    crate::ipc::shm::shm_open(name, false)
+        .and_then(|seg| crate::ipc::shm::map(&seg, offset, len))
+        .unwrap_or(0)
+}
+crate::export_symbol!(kernel_shm_map);
+
+/// 매핑 해제
+/// 반환: 0 = 성공, -1 = 실패
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_shm_unmap(addr: usize) -> i32 {
+    match crate::ipc::shm::unmap(addr) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+crate::export_symbol!(kernel_shm_unmap);
+
+/// 세그먼트 이름 삭제 (매핑은 해제될 때까지 유효)
+/// 반환: 0 = 성공, -1 = 실패
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_shm_unlink(name: *const u8, name_len: usize) -> i32 {
+    let name = match str_from_raw(name, name_len) {
+        Some(s) => s,
+        None => return -1,
+    };
+    match crate::ipc::shm::shm_unlink(name) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+crate::export_symbol!(kernel_shm_unlink);
 
 // ============================================================
 // Block (블록 디바이스)
@@ -161,6 +260,7 @@ pub extern "C" fn kernel_ramdisk_create(
     let _ = crate::block::ramdisk::create_ramdisk(name, size);
     0
 }
+crate::export_symbol!(kernel_ramdisk_create);
 
 /// 블록 읽기
 /// 반환: 읽은 바이트 수, -1 = 실패
@@ -193,6 +293,7 @@ pub extern "C" fn kernel_block_read(
         Err(_) => -1,
     }
 }
+crate::export_symbol!(kernel_block_read);
 
 /// 블록 쓰기
 /// 반환: 쓴 바이트 수, -1 = 실패
@@ -225,6 +326,7 @@ pub extern "C" fn kernel_block_write(
         Err(_) => -1,
     }
 }
+crate::export_symbol!(kernel_block_write);
 
 // ============================================================
 // VFS (파일시스템)
@@ -248,6 +350,7 @@ pub extern "C" fn kernel_vfs_mkdir(path: *const u8, path_len: usize) -> i32 {
         Err(_) => -1,
     }
 }
+crate::export_symbol!(kernel_vfs_mkdir);
 
 /// 파일 생성
 /// 반환: 0 = 성공, -1 = 실패
@@ -267,6 +370,7 @@ pub extern "C" fn kernel_vfs_create_file(path: *const u8, path_len: usize) -> i3
         Err(_) => -1,
     }
 }
+crate::export_symbol!(kernel_vfs_create_file);
 
 /// 파일 쓰기
 /// 반환: 쓴 바이트 수, -1 = 실패
@@ -295,6 +399,7 @@ pub extern "C" fn kernel_vfs_write(
         Err(_) => -1,
     }
 }
+crate::export_symbol!(kernel_vfs_write);
 
 /// 파일 읽기
 /// 반환: 읽은 바이트 수, -1 = 실패
@@ -323,6 +428,7 @@ pub extern "C" fn kernel_vfs_read(
         Err(_) => -1,
     }
 }
+crate::export_symbol!(kernel_vfs_read);
 
 /// 파일/디렉토리 삭제
 /// 반환: 0 = 성공, -1 = 실패
@@ -342,6 +448,7 @@ pub extern "C" fn kernel_vfs_unlink(path: *const u8, path_len: usize) -> i32 {
         Err(_) => -1,
     }
 }
+crate::export_symbol!(kernel_vfs_unlink);
 
 // ============================================================
 // Thread (스레드)
@@ -377,14 +484,88 @@ pub extern "C" fn kernel_thread_spawn(
         let arg = THREAD_ARG.load(Ordering::SeqCst);
         let entry: extern "C" fn(usize) = unsafe { core::mem::transmute(entry_addr) };
         entry(arg);
-        loop {
-            crate::proc::yield_now();
+
+        // 모듈 코드를 벗어났으므로 장부에서 제거하고 종료
+        if let Some(tid) = crate::proc::current_tid() {
+            crate::module::ledger::release(crate::module::ledger::ResourceKind::Thread, tid as usize);
         }
+        crate::proc::exit();
     }
 
+    // 엔트리가 모듈 코드면 장부에 기록 (언로드 시 강제 종료)
+    let owner = crate::module::ledger::owner_of(entry as usize);
+
     let tid = crate::proc::spawn(name, thread_wrapper);
+    if let Some(owner) = owner {
+        crate::module::ledger::track(
+            &owner,
+            crate::module::ledger::ResourceKind::Thread,
+            tid as usize,
+            name,
+            cancel_module_thread,
+        );
+    }
     tid as i32
 }
+crate::export_symbol!(kernel_thread_spawn);
+
+/// 스레드 CPU 친화도 설정
+/// tid: `kernel_thread_spawn`이 돌려준 값, mask: 비트 n = CPU n
+/// 반환: 0 = 성공, -1 = 실패 (스레드 없음, 온라인 CPU가 없는 마스크, idle 스레드)
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_thread_set_affinity(tid: i32, mask: u64) -> i32 {
+    if tid < 0 {
+        return -1;
+    }
+    let mask = crate::proc::percpu::CpuMask::from_bits(mask);
+    match crate::proc::set_affinity(tid as crate::proc::Tid, mask) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+crate::export_symbol!(kernel_thread_set_affinity);
+
+/// 스레드 종료까지 대기 (합류 후 TCB 해제)
+/// status: 종료 상태를 받을 주소 (null이면 버림)
+/// 반환: 0 = 성공, -1 = 실패 (스레드 없음, 분리됨/이미 대기 중, 자기 자신, 시그널)
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_thread_join(tid: i32, status: *mut i32) -> i32 {
+    if tid < 0 {
+        return -1;
+    }
+    match crate::proc::join(tid as crate::proc::Tid) {
+        Ok(code) => {
+            if !status.is_null() {
+                unsafe { *status = code };
+            }
+            0
+        }
+        Err(_) => -1,
+    }
+}
+crate::export_symbol!(kernel_thread_join);
+
+/// 모듈 스레드 강제 종료 시 CPU에서 내려가기를 기다리는 최대 yield 횟수
+const MODULE_THREAD_DRAIN_YIELDS: usize = 100;
+
+/// 모듈 스레드 강제 종료 (언로드 시 장부에서 호출)
+///
+/// 다른 CPU에서 모듈 코드를 실행 중이면 내려갈 때까지 잠시 기다립니다.
+fn cancel_module_thread(tid: usize) -> bool {
+    let tid = tid as crate::proc::Tid;
+    if crate::proc::current_tid() == Some(tid) {
+        return false;
+    }
+    crate::proc::kill(tid);
+
+    for _ in 0..MODULE_THREAD_DRAIN_YIELDS {
+        if !crate::proc::is_on_cpu(tid) {
+            return true;
+        }
+        crate::proc::yield_now();
+    }
+    false
+}
 
 /// N tick 대기 (busy-wait)
 #[unsafe(no_mangle)]
@@ -398,58 +579,279 @@ pub extern "C" fn kernel_sleep_ticks(ticks: u32) {
         crate::proc::yield_now();
     }
 }
+crate::export_symbol!(kernel_sleep_ticks);
 
 // ============================================================
-// Logging (로깅)
+// Timer (타이머)
 // ============================================================
 
-/// 로그 메시지 출력
-/// level: 0=ERROR, 1=WARN, 2=INFO, 3=DEBUG, 4=TRACE
+/// 장부 회수용: 취소 후에는 콜백이 대기 중이지도 실행 중이지도 않음
+fn cancel_module_timer(id: usize) -> bool {
+    crate::time::timer::cancel(id);
+    true
+}
+
+/// 콜백 타이머 생성
+/// delay_ms 뒤 callback(arg) 호출, period_ms > 0이면 이후 period_ms마다 반복
+/// 콜백은 타이머 인터럽트에서 실행되므로 잠들거나 오래 걸리면 안 됨
+/// 반환: 타이머 ID (> 0)
 #[unsafe(no_mangle)]
-pub extern "C" fn kernel_log(level: u8, msg: *const u8, msg_len: usize) {
-    let level_enum = crate::log::LogLevel::from_u8(level);
-    if let Some(s) = str_from_raw(msg, msg_len) {
-        crate::log::log(level_enum, core::format_args!("{}", s));
+pub extern "C" fn kernel_timer_create(
+    delay_ms: u64,
+    period_ms: u64,
+    callback: extern "C" fn(usize),
+    arg: usize,
+    name: *const u8,
+    name_len: usize,
+) -> i32 {
+    let name = str_from_raw(name, name_len).unwrap_or("module_timer");
+    let period = if period_ms > 0 { Some(period_ms) } else { None };
+    // 콜백이 돌기 전에 장부에 기록되도록 멈춘 채 만들고 기록한 뒤 시작
+    let id = crate::time::timer::create_disarmed(name, period, crate::time::timer::Callback::Module(callback), arg);
+
+    // 콜백이 모듈 코드면 장부에 기록 (언로드 시 취소, 일회성은 만료되면 타이머가 제거)
+    if let Some(owner) = crate::module::ledger::owner_of(callback as usize) {
+        crate::module::ledger::track(
+            &owner,
+            crate::module::ledger::ResourceKind::Timer,
+            id,
+            name,
+            cancel_module_timer,
+        );
     }
+    crate::time::timer::arm(id, delay_ms);
+    id as i32
 }
+crate::export_symbol!(kernel_timer_create);
+
+/// 콜백 타이머 취소 (반환 후 콜백이 실행 중이지 않음)
+/// 반환: 0 = 대기 중이던 타이머 취소, -1 = 없는 ID 또는 이미 만료된 일회성 타이머
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_timer_cancel(id: i32) -> i32 {
+    if id <= 0 {
+        return -1;
+    }
+    let cancelled = crate::time::timer::cancel(id as usize);
+    crate::module::ledger::release(crate::module::ledger::ResourceKind::Timer, id as usize);
+    if cancelled { 0 } else { -1 }
+}
+crate::export_symbol!(kernel_timer_cancel);
 
 // ============================================================
-// 심볼 등록
+// Workqueue (워크큐)
 // ============================================================
 
-/// 테스트 심볼들을 커널 심볼 테이블에 등록
-pub fn register_test_symbols() {
-    use crate::module::symbol::register_symbol;
+/// 장부 회수용: 대기 중이면 빼고, 실행 중이면 끝날 때까지 대기
+fn cancel_module_work(id: usize) -> bool {
+    crate::proc::workqueue::cancel(id);
+    true
+}
+
+/// 워크큐에 작업 넣기
+/// 전역 워커 스레드(kworker/u)에서 func(arg) 실행, delay_ms > 0이면 그만큼 뒤에 실행
+/// 스레드 문맥이므로 잠들어도 되지만, 같은 큐의 다음 작업은 앞 작업이 끝나야 시작
+/// 반환: 작업 ID (> 0)
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_queue_work(
+    func: extern "C" fn(usize),
+    arg: usize,
+    delay_ms: u64,
+    name: *const u8,
+    name_len: usize,
+) -> i32 {
+    let name = str_from_raw(name, name_len).unwrap_or("module_work");
+    let Some(id) = crate::proc::workqueue::queue(None, name, delay_ms, crate::proc::workqueue::WorkFn::Module(func), arg) else {
+        return -1;
+    };
+
+    // 함수가 모듈 코드면 장부에 기록 (언로드 시 취소, 실행을 마치면 워커가 제거)
+    if let Some(owner) = crate::module::ledger::owner_of(func as usize) {
+        crate::module::ledger::track(
+            &owner,
+            crate::module::ledger::ResourceKind::Work,
+            id,
+            name,
+            cancel_module_work,
+        );
+    }
+    id as i32
+}
+crate::export_symbol!(kernel_queue_work);
 
-    // MM
-    register_symbol("alloc_frame", alloc_frame as usize);
-    register_symbol("free_frame", free_frame as usize);
-    register_symbol("kernel_heap_alloc", kernel_heap_alloc as usize);
-    register_symbol("kernel_heap_dealloc", kernel_heap_dealloc as usize);
+/// 작업 취소 (반환 후 실행 중이지 않음)
+/// 반환: 0 = 대기 중이던 작업 취소, -1 = 없는 ID 또는 이미 실행됨
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_cancel_work(id: i32) -> i32 {
+    if id <= 0 {
+        return -1;
+    }
+    let cancelled = crate::proc::workqueue::cancel(id as usize);
+    crate::module::ledger::release(crate::module::ledger::ResourceKind::Work, id as usize);
+    if cancelled { 0 } else { -1 }
+}
+crate::export_symbol!(kernel_cancel_work);
 
-    // IPC
-    register_symbol("kernel_mq_open", kernel_mq_open as usize);
-    register_symbol("kernel_mq_send", kernel_mq_send as usize);
-    register_symbol("kernel_mq_receive", kernel_mq_receive as usize);
+// ============================================================
+// IRQ (인터럽트 핸들러)
+// ============================================================
 
-    // Block
-    register_symbol("kernel_ramdisk_create", kernel_ramdisk_create as usize);
-    register_symbol("kernel_block_read", kernel_block_read as usize);
-    register_symbol("kernel_block_write", kernel_block_write as usize);
+/// 장부 회수용: 모듈 핸들러를 걸린 모든 IRQ에서 해제 (반환 후 실행 중이지 않음)
+fn cancel_module_irq(handler: usize) -> bool {
+    crate::irq::remove(None, handler);
+    true
+}
 
-    // VFS
-    register_symbol("kernel_vfs_mkdir", kernel_vfs_mkdir as usize);
-    register_symbol("kernel_vfs_create_file", kernel_vfs_create_file as usize);
-    register_symbol("kernel_vfs_write", kernel_vfs_write as usize);
-    register_symbol("kernel_vfs_read", kernel_vfs_read as usize);
-    register_symbol("kernel_vfs_unlink", kernel_vfs_unlink as usize);
+/// IRQ 핸들러 등록 (공유 가능, 첫 핸들러면 인터럽트 컨트롤러에서 IRQ 활성화)
+/// handler(irq)는 인터럽트 컨텍스트에서 실행되며 자기 디바이스의 인터럽트를 처리했으면
+/// 0이 아닌 값을 반환
+/// 반환: 0 = 성공, -1 = 실패 (IRQ 0, 같은 핸들러 중복, 공유 한도 초과)
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_request_irq(
+    irq: u32,
+    handler: extern "C" fn(u32) -> i32,
+    name: *const u8,
+    name_len: usize,
+) -> i32 {
+    let name = str_from_raw(name, name_len).unwrap_or("module_irq");
+    if crate::irq::request(irq, crate::irq::Handler::Module(handler), name).is_err() {
+        return -1;
+    }
 
-    // Thread
-    register_symbol("kernel_thread_spawn", kernel_thread_spawn as usize);
-    register_symbol("kernel_sleep_ticks", kernel_sleep_ticks as usize);
+    // 핸들러가 모듈 코드면 장부에 기록 (언로드 시 해제)
+    if let Some(owner) = crate::module::ledger::owner_of(handler as usize) {
+        crate::module::ledger::track(
+            &owner,
+            crate::module::ledger::ResourceKind::Irq,
+            handler as usize,
+            name,
+            cancel_module_irq,
+        );
+    }
+    0
+}
+crate::export_symbol!(kernel_request_irq);
 
-    // Logging
-    register_symbol("kernel_log", kernel_log as usize);
+/// IRQ 핸들러 해제 (반환 후 핸들러가 실행 중이지 않음, 핸들러 안에서 호출 금지)
+/// 반환: 0 = 성공, -1 = 등록되지 않은 핸들러
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_free_irq(irq: u32, handler: extern "C" fn(u32) -> i32) -> i32 {
+    if crate::irq::remove(Some(irq), handler as usize) == 0 {
+        return -1;
+    }
+    crate::module::ledger::release(crate::module::ledger::ResourceKind::Irq, handler as usize);
+    0
+}
+crate::export_symbol!(kernel_free_irq);
 
-    crate::kprintln!("[symbol] Test symbols registered ({} symbols)", 19);
+// ============================================================
+// Driver (드라이버)
+// ============================================================
+
+/// 모듈 드라이버의 probe 콜백
+/// 인자: MMIO 기본 주소, MMIO 크기, 첫 번째 IRQ (없으면 0)
+/// 반환: 0 = 디바이스를 맡음, 그 외 = 실패
+type ModuleProbeFn = extern "C" fn(usize, usize, u32) -> i32;
+
+/// 모듈이 등록한 드라이버 (C-ABI 콜백을 `Driver` trait으로 감쌈)
+struct ModuleDriver {
+    name: alloc::string::String,
+    /// `compatible`의 힙 버퍼를 가리킴 (String은 바뀌지 않으므로 주소 고정)
+    compatible: [&'static str; 1],
+    _compatible_buf: alloc::string::String,
+    probe: ModuleProbeFn,
+}
+
+impl crate::drivers::Driver for ModuleDriver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn compatible(&self) -> &[&str] {
+        &self.compatible
+    }
+
+    fn probe(&self, info: &crate::dtb::DeviceInfo) -> crate::drivers::DriverResult<()> {
+        let irq = info.interrupts.first().copied().unwrap_or(0);
+        match (self.probe)(info.reg_base as usize, info.reg_size as usize, irq) {
+            0 => Ok(()),
+            _ => Err(crate::drivers::DriverError::InitFailed),
+        }
+    }
+}
+
+/// 드라이버 등록 후 주인 없는 DTB 디바이스에 바로 probe
+/// compatible: 매칭할 DTB compatible 문자열 하나
+/// 반환: 드라이버 ID (> 0), -1 = 실패
+///
+/// 부팅 시 내장 드라이버가 맡은 디바이스는 건너뜁니다. 맡은 디바이스가 없어도
+/// 등록은 유지되며, `kernel_driver_unregister()`나 언로드 시 해제됩니다.
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_driver_register(
+    name: *const u8,
+    name_len: usize,
+    compatible: *const u8,
+    compatible_len: usize,
+    probe: ModuleProbeFn,
+) -> i32 {
+    let (Some(name), Some(compatible)) = (
+        str_from_raw(name, name_len),
+        str_from_raw(compatible, compatible_len),
+    ) else {
+        return -1;
+    };
+
+    let compatible_buf = alloc::string::String::from(compatible);
+    // Safety: 버퍼는 ModuleDriver가 소유하고 수정하지 않으므로 드라이버가 살아 있는 동안 유효
+    let compatible_ref: &'static str =
+        unsafe { core::mem::transmute::<&str, &'static str>(compatible_buf.as_str()) };
+    let driver = ModuleDriver {
+        name: alloc::string::String::from(name),
+        compatible: [compatible_ref],
+        _compatible_buf: compatible_buf,
+        probe,
+    };
+
+    let id = crate::drivers::register_driver(alloc::sync::Arc::new(driver));
+
+    // probe 콜백이 모듈 코드면 장부에 기록 (언로드 시 등록 해제)
+    if let Some(owner) = crate::module::ledger::owner_of(probe as usize) {
+        crate::module::ledger::track(
+            &owner,
+            crate::module::ledger::ResourceKind::Driver,
+            id,
+            name,
+            crate::drivers::unregister_driver,
+        );
+    }
+
+    crate::drivers::probe_all();
+    id as i32
+}
+crate::export_symbol!(kernel_driver_register);
+
+/// 드라이버 등록 해제
+/// 반환: 0 = 성공, -1 = 없는 ID
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_driver_unregister(id: i32) -> i32 {
+    if id <= 0 || !crate::drivers::unregister_driver(id as usize) {
+        return -1;
+    }
+    crate::module::ledger::release(crate::module::ledger::ResourceKind::Driver, id as usize);
+    0
+}
+crate::export_symbol!(kernel_driver_unregister);
+
+// ============================================================
+// Logging (로깅)
+// ============================================================
+
+/// 로그 메시지 출력
+/// level: 0=ERROR, 1=WARN, 2=INFO, 3=DEBUG, 4=TRACE
+#[unsafe(no_mangle)]
+pub extern "C" fn kernel_log(level: u8, msg: *const u8, msg_len: usize) {
+    let level_enum = crate::log::LogLevel::from_u8(level);
+    if let Some(s) = str_from_raw(msg, msg_len) {
+        crate::log::log(level_enum, core::format_args!("{}", s));
+    }
 }
+crate::export_symbol!(kernel_log);
@@ -0,0 +1,279 @@
+//! 모듈 체크섬 검증
+//!
+//! 재배치하고 모듈 코드를 실행하기 전에 `.ko` 끝에 붙은 체크섬 트레일러를 확인해
+//! 손상되었거나 빌드 뒤 바뀐 모듈을 걸러냅니다. 트레일러는 `scripts/sign_module.sh`가
+//! 붙이고, 모듈 빌드 스크립트가 `.ko`를 만들 때 부릅니다.
+//!
+//! # 트레일러 (파일 끝 48 바이트, little endian)
+//!
+//! ```text
+//! 모듈 ELF (체크섬 대상)
+//! digest  [u8; 32]  SHA-256, CRC32면 앞 4바이트에 값, 나머지 0
+//! algo    u32       1 = CRC32, 2 = SHA-256
+//! magic   [u8; 12]  "~KRNMODSUM~\n"
+//! ```
+//!
+//! 키가 없는 체크섬이므로 우연한 손상과 잘못 복사한 파일은 막지만, 의도적으로
+//! 바꾼 뒤 트레일러를 다시 붙인 모듈은 막지 못합니다.
+//!
+//! 검증 정책은 `module.verify=<off|warn|enforce>` 명령줄 옵션으로 정합니다 (기본 warn):
+//! - `off`: 확인하지 않음 (트레일러는 떼고 로드)
+//! - `warn`: 트레일러가 없거나 맞지 않으면 경고만 출력하고 로드
+//! - `enforce`: 트레일러가 없거나 맞지 않으면 로드 거부
+
+use core::fmt;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use super::ModuleError;
+use crate::kprintln;
+
+/// 트레일러 매직
+const MAGIC: &[u8; 12] = b"~KRNMODSUM~\n";
+
+/// 트레일러 크기 (digest + algo + magic)
+pub const TRAILER_SIZE: usize = 32 + 4 + MAGIC.len();
+
+/// 검증 정책
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyMode {
+    /// 확인하지 않음
+    Off = 0,
+    /// 경고만 출력하고 로드
+    Warn = 1,
+    /// 검증하지 못한 모듈은 로드 거부
+    Enforce = 2,
+}
+
+impl VerifyMode {
+    /// `off`, `warn`, `enforce`
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "off" => Some(Self::Off),
+            "warn" => Some(Self::Warn),
+            "enforce" => Some(Self::Enforce),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for VerifyMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Off => write!(f, "off"),
+            Self::Warn => write!(f, "warn"),
+            Self::Enforce => write!(f, "enforce"),
+        }
+    }
+}
+
+/// 트레일러의 체크섬 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checksum {
+    Crc32,
+    Sha256,
+}
+
+impl Checksum {
+    fn from_id(id: u32) -> Option<Self> {
+        match id {
+            1 => Some(Self::Crc32),
+            2 => Some(Self::Sha256),
+            _ => None,
+        }
+    }
+
+    fn id(self) -> u32 {
+        match self {
+            Self::Crc32 => 1,
+            Self::Sha256 => 2,
+        }
+    }
+
+    /// `data`의 체크섬 (트레일러 digest 형식)
+    fn digest(self, data: &[u8]) -> [u8; 32] {
+        match self {
+            Self::Crc32 => {
+                let mut digest = [0u8; 32];
+                digest[..4].copy_from_slice(&crc32(data).to_le_bytes());
+                digest
+            }
+            Self::Sha256 => sha256(data),
+        }
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Crc32 => write!(f, "crc32"),
+            Self::Sha256 => write!(f, "sha256"),
+        }
+    }
+}
+
+static MODE: AtomicU8 = AtomicU8::new(VerifyMode::Warn as u8);
+
+/// 명령줄의 `module.verify=` 적용
+pub fn init_from_cmdline() {
+    if let Some(mode) = crate::boot::cmdline::module_verify() {
+        set_mode(mode);
+    }
+}
+
+pub fn set_mode(mode: VerifyMode) {
+    MODE.store(mode as u8, Ordering::Relaxed);
+}
+
+pub fn mode() -> VerifyMode {
+    match MODE.load(Ordering::Relaxed) {
+        0 => VerifyMode::Off,
+        2 => VerifyMode::Enforce,
+        _ => VerifyMode::Warn,
+    }
+}
+
+/// 트레일러를 확인하고 떼어낸 모듈 ELF와 확인한 체크섬 종류를 반환
+///
+/// 트레일러가 없으면 `data` 전체가 ELF입니다. 검증하지 못했는데 정책이 `enforce`면
+/// `Unverified`(트레일러 없음) 또는 `ChecksumMismatch`.
+pub fn verify<'a>(data: &'a [u8], name: &str) -> Result<(&'a [u8], Option<Checksum>), ModuleError> {
+    let policy = mode();
+    let Some((payload, algo, digest)) = split_trailer(data) else {
+        match policy {
+            VerifyMode::Off => {}
+            VerifyMode::Warn => kprintln!("[module] '{}': no checksum trailer, loading unverified", name),
+            VerifyMode::Enforce => {
+                kprintln!("[module] '{}': no checksum trailer, refusing to load", name);
+                return Err(ModuleError::Unverified);
+            }
+        }
+        return Ok((data, None));
+    };
+    if policy == VerifyMode::Off {
+        return Ok((payload, None));
+    }
+
+    let ok = algo.is_some_and(|algo| algo.digest(payload) == digest);
+    if ok {
+        return Ok((payload, algo));
+    }
+    match policy {
+        VerifyMode::Enforce => {
+            kprintln!("[module] '{}': checksum mismatch, refusing to load", name);
+            Err(ModuleError::ChecksumMismatch)
+        }
+        _ => {
+            kprintln!("[module] '{}': checksum mismatch, loading anyway", name);
+            Ok((payload, None))
+        }
+    }
+}
+
+/// `payload` 뒤에 붙일 트레일러 (셀프테스트용, 호스트에서는 `sign_module.sh`)
+pub fn trailer(payload: &[u8], algo: Checksum) -> [u8; TRAILER_SIZE] {
+    let mut out = [0u8; TRAILER_SIZE];
+    out[..32].copy_from_slice(&algo.digest(payload));
+    out[32..36].copy_from_slice(&algo.id().to_le_bytes());
+    out[36..].copy_from_slice(MAGIC);
+    out
+}
+
+/// (ELF, 체크섬 종류, digest), 매직이 없으면 None. 알 수 없는 종류는 None으로 전달
+fn split_trailer(data: &[u8]) -> Option<(&[u8], Option<Checksum>, [u8; 32])> {
+    if data.len() < TRAILER_SIZE || !data.ends_with(MAGIC) {
+        return None;
+    }
+    let (payload, trailer) = data.split_at(data.len() - TRAILER_SIZE);
+    let mut digest = [0u8; 32];
+    digest.copy_from_slice(&trailer[..32]);
+    let id = u32::from_le_bytes([trailer[32], trailer[33], trailer[34], trailer[35]]);
+    Some((payload, Checksum::from_id(id), digest))
+}
+
+/// CRC-32 (IEEE 802.3, 반사 다항식 0xEDB88320 - gzip/zlib과 같음)
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in data {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// SHA-256 라운드 상수
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256 (FIPS 180-4)
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+
+    let mut chunks = data.chunks_exact(64);
+    for block in &mut chunks {
+        sha256_block(&mut h, block);
+    }
+
+    // 패딩: 0x80, 0들, 비트 길이 (big endian u64)
+    let rest = chunks.remainder();
+    let mut tail = [0u8; 128];
+    tail[..rest.len()].copy_from_slice(rest);
+    tail[rest.len()] = 0x80;
+    let tail_len = if rest.len() < 56 { 64 } else { 128 };
+    let bit_len = (data.len() as u64).wrapping_mul(8);
+    tail[tail_len - 8..tail_len].copy_from_slice(&bit_len.to_be_bytes());
+    for block in tail[..tail_len].chunks_exact(64) {
+        sha256_block(&mut h, block);
+    }
+
+    let mut out = [0u8; 32];
+    for (i, word) in h.iter().enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+fn sha256_block(h: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 64];
+    for i in 0..16 {
+        w[i] = u32::from_be_bytes([block[i * 4], block[i * 4 + 1], block[i * 4 + 2], block[i * 4 + 3]]);
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = *h;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = hh.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        hh = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    for (state, v) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+        *state = state.wrapping_add(v);
+    }
+}
@@ -0,0 +1,388 @@
+//! 커널 패닉 처리
+//!
+//! `#[panic_handler]`가 부르는 `handle()`이 패닉 메시지, 예외 또는 호출자 레지스터, 심볼로
+//! 해석한 백트레이스, (선택) 최근 로그를 출력하고 (설정했으면) 크래시 덤프를 디스크에
+//! 남긴 뒤 명령줄 `panic=`에 따라 멈추거나 재부팅합니다.
+//!
+//! ```text
+//! Kernel panic - not syncing: Unhandled exception
+//!   at src/arch/aarch64/exception.rs:204:5
+//! CPU: 0 TID: 3
+//! Exception at [<0000000040093a10>] kernel_vfs_read+0x1c4
+//! Call trace:
+//!  [<0000000040093a10>] kernel_vfs_read+0x1c4
+//!  [<0000000040081f2c>] kernel_main+0x9e0
+//!  [<0000000041a02154>] test_init+0x34 [test_vfs]
+//! ---[ end Kernel panic ]---
+//! ```
+//!
+//! 패닉은 락을 잡은 채로, 할당자 안에서, 인터럽트 핸들러에서 일어날 수 있으므로 이 경로는
+//! 힙을 쓰지 않고 락은 `try_*`로만 잡습니다. 출력은 로그 계층을 거치지 않고 콘솔로 바로
+//! 씁니다 (로그 재귀 방지 표시가 선 채로 패닉해도 출력되게).
+//!
+//! | 명령줄 | 동작 |
+//! |--------|------|
+//! | `panic=halt`, `panic=0` (기본) | 인터럽트를 끄고 이 CPU를 멈춤 |
+//! | `panic=reboot`, `panic=-1` | 바로 재부팅 (aarch64 PSCI SYSTEM_RESET, riscv64 SBI SRST / sifive_test) |
+//! | `panic=<초>` | 그 시간 뒤 재부팅 (Linux와 같음) |
+//! | `panic_log=<n>` | 로그 버퍼의 마지막 n줄 출력 (기본 0 = 출력 안 함) |
+
+use core::fmt;
+use core::panic::PanicInfo;
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+
+use crate::proc::percpu::{self, MAX_CPUS};
+use crate::sysctl::{self, Kind, Tunable, Value};
+use crate::trace::backtrace;
+
+/// 백트레이스 최대 프레임 수
+const MAX_FRAMES: usize = 32;
+
+/// 패닉 후 동작
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicAction {
+    /// 이 CPU를 멈춤
+    Halt,
+    /// `delay_s`초 뒤 재부팅 (0 = 바로)
+    Reboot { delay_s: u32 },
+}
+
+impl PanicAction {
+    /// `halt`, `reboot`, 또는 Linux `panic=N` 형식의 초 (0 = 멈춤, 음수 = 바로 재부팅)
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "halt" => Some(Self::Halt),
+            "reboot" => Some(Self::Reboot { delay_s: 0 }),
+            _ => match s.parse::<i64>().ok()? {
+                0 => Some(Self::Halt),
+                n if n < 0 => Some(Self::Reboot { delay_s: 0 }),
+                n => Some(Self::Reboot {
+                    delay_s: n.min(u32::MAX as i64) as u32,
+                }),
+            },
+        }
+    }
+}
+
+impl fmt::Display for PanicAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Halt => write!(f, "halt"),
+            Self::Reboot { delay_s: 0 } => write!(f, "reboot"),
+            Self::Reboot { delay_s } => write!(f, "reboot after {}s", delay_s),
+        }
+    }
+}
+
+/// 재부팅할지 (`panic=`)
+static REBOOT: AtomicBool = AtomicBool::new(false);
+/// 재부팅 전 대기 시간 (초)
+static REBOOT_DELAY_S: AtomicU32 = AtomicU32::new(0);
+/// 출력할 최근 로그 줄 수 (`panic_log=`)
+static LOG_LINES: AtomicUsize = AtomicUsize::new(0);
+
+/// 패닉을 처리 중인 CPU + 1 (0 = 없음)
+static PANIC_CPU: AtomicUsize = AtomicUsize::new(0);
+
+/// 처리하지 못한 예외의 pc / 프레임 포인터 (CPU별, 0 = 없음)
+struct ExceptionFrame {
+    pc: AtomicUsize,
+    fp: AtomicUsize,
+}
+
+static EXCEPTION: [ExceptionFrame; MAX_CPUS] = [const {
+    ExceptionFrame {
+        pc: AtomicUsize::new(0),
+        fp: AtomicUsize::new(0),
+    }
+}; MAX_CPUS];
+
+static SYSCTL: [Tunable; 2] = [
+    Tunable {
+        name: "panic.action",
+        desc: "halt, reboot, or seconds before reboot",
+        kind: Kind::Str,
+        get: || {
+            Value::Str(match action() {
+                PanicAction::Halt => alloc::string::String::from("halt"),
+                PanicAction::Reboot { delay_s: 0 } => alloc::string::String::from("reboot"),
+                PanicAction::Reboot { delay_s } => alloc::format!("{}", delay_s),
+            })
+        },
+        set: Some(|v| {
+            set_action(PanicAction::from_str(sysctl::string(&v)).ok_or("expected halt, reboot or seconds")?);
+            Ok(())
+        }),
+    },
+    Tunable {
+        name: "panic.log_lines",
+        desc: "recent log lines printed on panic",
+        kind: Kind::Int { min: 0, max: 1000 },
+        get: || Value::Int(log_lines() as i64),
+        set: Some(|v| {
+            LOG_LINES.store(sysctl::int(&v) as usize, Ordering::Relaxed);
+            Ok(())
+        }),
+    },
+];
+
+/// bootargs의 `panic=`, `panic_log=` 처리 (`boot::cmdline::init()` 후 호출)
+pub fn init_from_cmdline() {
+    if let Some(parsed) = crate::boot::cmdline::panic_action() {
+        set_action(parsed);
+        crate::kprintln!("[panic] On panic: {}", action());
+    }
+    if let Some(lines) = crate::boot::cmdline::panic_log_lines() {
+        LOG_LINES.store(lines, Ordering::Relaxed);
+    }
+    sysctl::register_all(&SYSCTL);
+}
+
+/// 패닉 후 동작 설정
+pub fn set_action(action: PanicAction) {
+    let (reboot, delay_s) = match action {
+        PanicAction::Halt => (false, 0),
+        PanicAction::Reboot { delay_s } => (true, delay_s),
+    };
+    REBOOT.store(reboot, Ordering::Relaxed);
+    REBOOT_DELAY_S.store(delay_s, Ordering::Relaxed);
+}
+
+/// 현재 패닉 후 동작
+pub fn action() -> PanicAction {
+    if REBOOT.load(Ordering::Relaxed) {
+        PanicAction::Reboot {
+            delay_s: REBOOT_DELAY_S.load(Ordering::Relaxed),
+        }
+    } else {
+        PanicAction::Halt
+    }
+}
+
+/// 패닉 때 출력할 최근 로그 줄 수
+pub fn log_lines() -> usize {
+    LOG_LINES.load(Ordering::Relaxed)
+}
+
+/// 처리하지 못한 예외로 `panic!`하기 직전에 예외 시점의 pc와 프레임 포인터 기록
+///
+/// 백트레이스가 예외 핸들러가 아니라 예외가 난 곳에서 시작합니다. 레지스터는 예외
+/// 핸들러가 이미 출력하므로 다시 출력하지 않습니다.
+pub fn set_exception_frame(pc: usize, fp: usize) {
+    let frame = &EXCEPTION[percpu::get_cpu_id() as usize];
+    frame.pc.store(pc, Ordering::Relaxed);
+    frame.fp.store(fp, Ordering::Relaxed);
+}
+
+/// 콘솔로 바로 한 줄 출력 (로그 계층을 거치지 않음)
+macro_rules! pr {
+    ($($arg:tt)*) => {
+        crate::console::kprintln(format_args!($($arg)*))
+    };
+}
+
+/// 백트레이스 한 줄 형식: `[<주소>] 심볼+0x오프셋 [모듈]`
+pub(crate) struct Symbolized(pub usize);
+
+impl fmt::Display for Symbolized {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[<{:016x}>] ", self.0)?;
+        let mut result = Ok(());
+        let found = crate::module::symbol::symbolize(self.0, &mut |name, offset, module| {
+            result = match module {
+                Some(module) => write!(f, "{}+{:#x} [{}]", name, offset, module),
+                None => write!(f, "{}+{:#x}", name, offset),
+            };
+        });
+        if !found {
+            result = write!(f, "?");
+        }
+        result
+    }
+}
+
+/// 패닉 처리 (`#[panic_handler]`에서 호출)
+pub fn handle(info: &PanicInfo) -> ! {
+    crate::sync::disable_interrupts();
+    let cpu = percpu::get_cpu_id() as usize;
+
+    if let Err(owner) = PANIC_CPU.compare_exchange(0, cpu + 1, Ordering::AcqRel, Ordering::Acquire) {
+        if owner == cpu + 1 {
+            // 패닉 출력 중에 다시 패닉: 더 출력하지 않고 멈춤
+            crate::console::puts("\nKernel panic: nested panic, halting\n");
+        }
+        // 다른 CPU가 먼저 패닉했으면 그 CPU의 출력을 방해하지 않고 멈춤
+        halt();
+    }
+
+    pr!("\nKernel panic - not syncing: {}", info.message());
+    if let Some(location) = info.location() {
+        pr!("  at {}:{}:{}", location.file(), location.line(), location.column());
+    }
+    match crate::proc::current_tid() {
+        Some(tid) => pr!("CPU: {} TID: {}", cpu, tid),
+        None => pr!("CPU: {}", cpu),
+    }
+
+    let regs = caller_regs();
+    let pc = EXCEPTION[cpu].pc.swap(0, Ordering::Relaxed);
+    let fp = EXCEPTION[cpu].fp.swap(0, Ordering::Relaxed);
+    if pc != 0 {
+        pr!("Exception at {}", Symbolized(pc));
+        pr!("Call trace:");
+        pr!(" {}", Symbolized(pc));
+        backtrace::walk(fp, MAX_FRAMES - 1, |ret| pr!(" {}", Symbolized(ret)));
+    } else {
+        print_regs(&regs);
+        pr!("Call trace:");
+        backtrace::walk(backtrace::current_fp(), MAX_FRAMES, |ret| pr!(" {}", Symbolized(ret)));
+    }
+
+    let lines = log_lines();
+    if lines > 0 {
+        pr!("Last {} log lines:", lines);
+        if !crate::log::tail(lines, crate::console::write) {
+            pr!("  (log buffer locked)");
+        }
+    }
+
+    let trace_fp = if pc != 0 { fp } else { backtrace::current_fp() };
+    crate::debug::crashdump::save(&info.message(), info.location(), cpu, (pc != 0).then_some(pc), trace_fp, &regs);
+
+    // GDB 스텁 포트가 있으면 GDB가 떨어질 때까지 디버거에서 대기
+    crate::debug::gdbstub::enter_panic();
+
+    #[cfg(feature = "test_runner")]
+    {
+        pr!("TEST_STATUS: FAIL");
+        crate::test_runner::qemu_exit(1);
+    }
+
+    #[cfg(not(feature = "test_runner"))]
+    match action() {
+        PanicAction::Halt => {
+            pr!("---[ end Kernel panic ]---");
+            halt();
+        }
+        PanicAction::Reboot { delay_s } => {
+            if delay_s > 0 {
+                pr!("Rebooting in {} seconds..", delay_s);
+                let (start, _) = crate::log::get_timestamp();
+                while crate::log::get_timestamp().0 < start + delay_s as u64 {
+                    core::hint::spin_loop();
+                }
+            } else {
+                pr!("Rebooting..");
+            }
+            reboot();
+        }
+    }
+}
+
+/// 패닉 핸들러 진입 시점의 callee-saved 레지스터, fp, 복귀 주소, sp
+pub(crate) struct CallerRegs {
+    pub names: &'static [&'static str],
+    /// 앞의 `names.len()`개만 의미 있음
+    pub values: [usize; 16],
+}
+
+impl CallerRegs {
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, usize)> + '_ {
+        self.names.iter().copied().zip(self.values.iter().copied())
+    }
+
+    /// 저장한 sp
+    pub fn sp(&self) -> usize {
+        self.iter().find(|&(name, _)| name == "sp").map_or(0, |(_, value)| value)
+    }
+}
+
+/// 호출한 곳의 callee-saved 레지스터, fp, 복귀 주소, sp
+#[inline(always)]
+pub(crate) fn caller_regs() -> CallerRegs {
+    #[cfg(target_arch = "aarch64")]
+    {
+        const NAMES: [&str; 13] = [
+            "x19", "x20", "x21", "x22", "x23", "x24", "x25", "x26", "x27", "x28", "fp", "lr", "sp",
+        ];
+        let mut regs = [0usize; 16];
+        // Safety: regs에 13개 슬롯을 씀
+        unsafe {
+            core::arch::asm!(
+                "stp x19, x20, [{0}]",
+                "stp x21, x22, [{0}, #16]",
+                "stp x23, x24, [{0}, #32]",
+                "stp x25, x26, [{0}, #48]",
+                "stp x27, x28, [{0}, #64]",
+                "stp x29, x30, [{0}, #80]",
+                "mov {1}, sp",
+                "str {1}, [{0}, #96]",
+                in(reg) regs.as_mut_ptr(),
+                out(reg) _,
+                options(nostack),
+            );
+        }
+        CallerRegs { names: &NAMES, values: regs }
+    }
+    #[cfg(target_arch = "riscv64")]
+    {
+        const NAMES: [&str; 16] = [
+            "ra", "sp", "gp", "tp", "s0", "s1", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10",
+            "s11",
+        ];
+        let mut regs = [0usize; 16];
+        // Safety: regs에 16개 슬롯을 씀
+        unsafe {
+            core::arch::asm!(
+                "sd ra, 0({0})",
+                "sd sp, 8({0})",
+                "sd gp, 16({0})",
+                "sd tp, 24({0})",
+                "sd s0, 32({0})",
+                "sd s1, 40({0})",
+                "sd s2, 48({0})",
+                "sd s3, 56({0})",
+                "sd s4, 64({0})",
+                "sd s5, 72({0})",
+                "sd s6, 80({0})",
+                "sd s7, 88({0})",
+                "sd s8, 96({0})",
+                "sd s9, 104({0})",
+                "sd s10, 112({0})",
+                "sd s11, 120({0})",
+                in(reg) regs.as_mut_ptr(),
+                options(nostack),
+            );
+        }
+        CallerRegs { names: &NAMES, values: regs }
+    }
+}
+
+/// 한 줄에 세 개씩 레지스터 출력
+fn print_regs(regs: &CallerRegs) {
+    pr!("Caller registers:");
+    for (names, regs) in regs.names.chunks(3).zip(regs.values.chunks(3)) {
+        for (name, value) in names.iter().zip(regs) {
+            crate::console::kprint(format_args!("  {:>3}: {:016x}", name, value));
+        }
+        crate::console::puts("\n");
+    }
+}
+
+/// 인터럽트가 꺼진 채 이 CPU를 멈춤
+fn halt() -> ! {
+    loop {
+        unsafe {
+            core::arch::asm!("wfi");
+        }
+    }
+}
+
+/// 시스템 리셋
+#[cfg(not(feature = "test_runner"))]
+fn reboot() -> ! {
+    #[cfg(target_arch = "aarch64")]
+    crate::drivers::psci::system_reset();
+    #[cfg(target_arch = "riscv64")]
+    crate::boards::qemu_virt_riscv64_smp::system_reset();
+}
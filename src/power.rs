@@ -0,0 +1,82 @@
+//! 전원 관리: 재부팅, 전원 끄기, 정지
+//!
+//! `reboot` / `poweroff` 명령과 `SYS_REBOOT`가 `shutdown()`을 부릅니다. kexec와 같은
+//! 순서로 시스템을 정리한 뒤 펌웨어에 리셋이나 종료를 요청합니다.
+//!
+//! 1. `log::sink::flush_all()`: 로그 파일 싱크에 남은 로그 기록
+//! 2. `fs::unmount_all()`: 버퍼 동기화, FAT32 dirty 플래그 해제
+//! 3. 다른 CPU 정지 (`kexec::stop_other_cpus()`)
+//! 4. 인터럽트 비활성화 후 펌웨어 호출
+//!
+//! | 동작 | aarch64 | riscv64 S-mode | riscv64 M-mode |
+//! |------|---------|----------------|----------------|
+//! | 재부팅 | PSCI `SYSTEM_RESET` | SBI SRST (cold reboot) | sifive_test `0x7777` |
+//! | 전원 끄기 | PSCI `SYSTEM_OFF` | SBI SRST (shutdown) | sifive_test `0x5555` |
+//! | 정지 | 이 CPU를 `wfi`로 멈춤 | 〃 | 〃 |
+//!
+//! QEMU는 전원 끄기에서 종료 코드 0으로 끝나므로, `test_runner` feature 없이도 자동
+//! 테스트 스크립트가 마지막에 `poweroff`로 QEMU를 정상 종료할 수 있습니다.
+
+use core::fmt;
+
+use crate::kprintln;
+
+/// 전원 동작
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerAction {
+    /// 시스템 리셋 (QEMU는 같은 커널로 다시 부팅)
+    Restart,
+    /// 전원 끄기 (QEMU 종료)
+    PowerOff,
+    /// 다른 CPU를 멈추고 이 CPU도 멈춤 (QEMU는 계속 실행)
+    Halt,
+}
+
+impl fmt::Display for PowerAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Restart => write!(f, "Restarting system"),
+            Self::PowerOff => write!(f, "Power down"),
+            Self::Halt => write!(f, "System halted"),
+        }
+    }
+}
+
+/// 시스템 정리 후 재부팅/종료/정지 (돌아오지 않음)
+///
+/// 스레드 문맥에서만 부릅니다 (언마운트가 잠들 수 있음).
+pub fn shutdown(action: PowerAction) -> ! {
+    crate::log::sink::flush_all();
+    crate::fs::unmount_all();
+
+    crate::kexec::stop_other_cpus();
+    crate::arch::kexec::disable_interrupts();
+
+    kprintln!("reboot: {}", action);
+
+    match action {
+        PowerAction::Restart => system_reset(),
+        PowerAction::PowerOff => system_off(),
+        PowerAction::Halt => loop {
+            unsafe {
+                core::arch::asm!("wfi");
+            }
+        },
+    }
+}
+
+/// 보드 리셋
+fn system_reset() -> ! {
+    #[cfg(target_arch = "aarch64")]
+    crate::drivers::psci::system_reset();
+    #[cfg(target_arch = "riscv64")]
+    crate::boards::qemu_virt_riscv64_smp::system_reset();
+}
+
+/// 보드 전원 끄기
+fn system_off() -> ! {
+    #[cfg(target_arch = "aarch64")]
+    crate::drivers::psci::system_off();
+    #[cfg(target_arch = "riscv64")]
+    crate::boards::qemu_virt_riscv64_smp::system_off();
+}
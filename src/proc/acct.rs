@@ -0,0 +1,233 @@
+//! 스레드/CPU 실행 시간 회계
+//!
+//! 컨텍스트 스위칭마다(`scheduler::switch_to_next`, 런큐 락 안) 내려가는
+//! 스레드에 실행 시간과 그동안 지난 틱 수를 더합니다. 타이머 인터럽트에서는
+//! 스레드를 건드리지 않고, 스위칭 시점에 per-CPU 틱 카운터의 차이로 틱을 계산합니다.
+//!
+//! 실행 중인 스레드는 마지막 스위칭 이후 시간이 아직 더해지지 않았으므로
+//! `snapshot()`이 그 몫을 더해 돌려줍니다. `top` 명령은 두 스냅샷의 차이로 구간
+//! CPU%를 구합니다.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::sync::atomic::Ordering;
+
+use super::percpu::{self, PerCpuData};
+use super::runqueue;
+use super::{Thread, ThreadState, Tid, THREADS};
+
+/// 스레드별 실행 시간 회계
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadAcct {
+    /// 누적 실행 시간 (마이크로초)
+    pub run_us: u64,
+    /// 실행 중에 지난 타이머 틱 수
+    pub run_ticks: u64,
+    /// CPU에 올라간 횟수
+    pub switches: u64,
+    /// 마지막으로 CPU에 올라가거나 내려간 시각 (부팅 후 마이크로초, 0 = 실행한 적 없음)
+    pub last_run_us: u64,
+    /// 깨어난 시각 (단조 시계 나노초, 0 = CPU를 기다리는 깨어난 스레드 아님, `schedstat`)
+    pub woken_ns: u64,
+}
+
+/// 스레드 사용량 스냅샷
+#[derive(Debug, Clone)]
+pub struct ThreadUsage {
+    pub tid: Tid,
+    pub name: String,
+    pub state: ThreadState,
+    /// 실행 중인 CPU
+    pub cpu: Option<u32>,
+    pub acct: ThreadAcct,
+}
+
+/// CPU 사용량 스냅샷
+#[derive(Debug, Clone, Copy)]
+pub struct CpuUsage {
+    pub cpu: u32,
+    /// idle 스레드가 아닌 스레드가 실행된 시간 (마이크로초)
+    pub busy_us: u64,
+    /// idle 스레드가 실행된 시간 (마이크로초)
+    pub idle_us: u64,
+    /// 컨텍스트 스위칭 횟수
+    pub switches: u64,
+}
+
+/// 사용량 스냅샷
+#[derive(Debug, Clone)]
+pub struct Usage {
+    /// 스냅샷 시각 (부팅 후 마이크로초)
+    pub at_us: u64,
+    pub threads: Vec<ThreadUsage>,
+    pub cpus: Vec<CpuUsage>,
+}
+
+/// CPU가 스케줄링을 시작할 때 회계 기준점 설정
+///
+/// 이 시각 이후부터 그 CPU의 현재 스레드(idle)에 실행 시간이 쌓입니다.
+pub fn start_cpu(pc: &PerCpuData) {
+    pc.run_start_us.store(crate::time::now_us(), Ordering::Relaxed);
+    pc.run_start_tick.store(pc.tick_count.load(Ordering::Relaxed), Ordering::Relaxed);
+}
+
+/// 스위칭 시 회계 (런큐 락을 잡은 채 호출)
+///
+/// 내려가는 스레드에 마지막 스위칭 이후 시간과 틱을 더하고, 올라가는 스레드의
+/// 스위칭 횟수를 셉니다. `prev_idle`은 내려가는 스레드가 이 CPU의 idle 스레드인지입니다.
+pub(super) fn on_switch(prev: &mut Thread, next: &mut Thread, pc: &PerCpuData, prev_idle: bool) {
+    let now = crate::time::now_us();
+    let tick = pc.tick_count.load(Ordering::Relaxed);
+    let ran_us = now.saturating_sub(pc.run_start_us.load(Ordering::Relaxed));
+    let ran_ticks = tick.saturating_sub(pc.run_start_tick.load(Ordering::Relaxed));
+
+    let prev_acct = &mut prev.acct;
+    prev_acct.run_us += ran_us;
+    prev_acct.run_ticks += ran_ticks;
+    prev_acct.last_run_us = now;
+
+    let next_acct = &mut next.acct;
+    next_acct.switches += 1;
+    next_acct.last_run_us = now;
+
+    if prev_idle {
+        pc.idle_us.fetch_add(ran_us, Ordering::Relaxed);
+    } else {
+        pc.busy_us.fetch_add(ran_us, Ordering::Relaxed);
+    }
+    pc.switch_count.fetch_add(1, Ordering::Relaxed);
+    pc.run_start_us.store(now, Ordering::Relaxed);
+    pc.run_start_tick.store(tick, Ordering::Relaxed);
+}
+
+/// 현재 사용량 (실행 중인 스레드의 마지막 스위칭 이후 몫 포함)
+///
+/// 스레드는 런큐를 하나씩 잠가 복사합니다. 그 사이 옮겨진 스레드는 빠지거나 두 번
+/// 잡힐 수 있으므로 TID 기준으로 한 번만 남깁니다.
+pub fn snapshot() -> Usage {
+    let total = THREADS.lock().len();
+    let now = crate::time::now_us();
+    let online = percpu::online_mask();
+
+    let mut cpus = Vec::with_capacity(percpu::online_count() as usize);
+    let mut threads: Vec<ThreadUsage> = Vec::with_capacity(total);
+    for cpu in online.iter() {
+        let pc = percpu::get(cpu);
+        let mut usage = CpuUsage {
+            cpu,
+            busy_us: pc.busy_us.load(Ordering::Relaxed),
+            idle_us: pc.idle_us.load(Ordering::Relaxed),
+            switches: pc.switch_count.load(Ordering::Relaxed),
+        };
+
+        let q = runqueue::queue(cpu).lock();
+        let ran_us = now.saturating_sub(pc.run_start_us.load(Ordering::Relaxed));
+        let ran_ticks = pc
+            .tick_count
+            .load(Ordering::Relaxed)
+            .saturating_sub(pc.run_start_tick.load(Ordering::Relaxed));
+        if q.current().is_some() {
+            if q.current == q.idle {
+                usage.idle_us += ran_us;
+            } else {
+                usage.busy_us += ran_us;
+            }
+        }
+        cpus.push(usage);
+
+        for (idx, thread) in q.threads.iter().enumerate() {
+            // Safety: 이 런큐 락을 잡고 있음
+            let t = unsafe { thread.get_mut() };
+            if threads.iter().any(|u| u.tid == t.tid) {
+                continue;
+            }
+            let mut acct = t.acct;
+            let mut running = None;
+            if idx == q.current {
+                acct.run_us += ran_us;
+                acct.run_ticks += ran_ticks;
+                running = Some(cpu);
+            }
+            threads.push(ThreadUsage {
+                tid: t.tid,
+                name: t.name.clone(),
+                state: t.state,
+                cpu: running,
+                acct,
+            });
+        }
+    }
+    threads.sort_unstable_by_key(|u| u.tid);
+
+    Usage { at_us: now, threads, cpus }
+}
+
+/// 구간 대기 (다른 스레드에 양보하거나 다음 인터럽트까지 쉼)
+fn wait_us(us: u64) {
+    let deadline = crate::time::now_us().saturating_add(us);
+    while crate::time::now_us() < deadline {
+        if super::scheduler::ready_count() > 0 {
+            super::scheduler::schedule();
+        } else {
+            unsafe { core::arch::asm!("wfi") };
+        }
+    }
+}
+
+/// `part / whole`을 0.1% 단위로
+fn permille(part: u64, whole: u64) -> u64 {
+    if whole == 0 { 0 } else { part.saturating_mul(1000) / whole }
+}
+
+/// `interval_ms` 동안의 CPU/스레드별 사용률 출력 (`top` 명령)
+///
+/// 호출한 스레드(셸)도 대기 동안 CPU에서 내려가 있지 않으면 사용률에 잡힙니다.
+pub fn top(interval_ms: u64) {
+    let before = snapshot();
+    wait_us(interval_ms * 1000);
+    let after = snapshot();
+    let elapsed = after.at_us.saturating_sub(before.at_us);
+
+    crate::kprintln!("\nInterval {} ms, {} CPUs online", elapsed / 1000, after.cpus.len());
+    crate::kprintln!("  CPU   BUSY%  SWITCHES");
+    for cpu in &after.cpus {
+        let prev = before.cpus.iter().find(|c| c.cpu == cpu.cpu);
+        let busy = cpu.busy_us.saturating_sub(prev.map_or(0, |c| c.busy_us));
+        let switches = cpu.switches.saturating_sub(prev.map_or(0, |c| c.switches));
+        let pm = permille(busy, elapsed);
+        crate::kprintln!("  {:>3} {:>5}.{} {:>9}", cpu.cpu, pm / 10, pm % 10, switches);
+    }
+
+    // (구간 실행 시간, 구간 스위칭 수, 스레드)
+    let mut rows: Vec<(u64, u64, &ThreadUsage)> = after
+        .threads
+        .iter()
+        .map(|t| {
+            let prev = before.threads.iter().find(|p| p.tid == t.tid).map(|p| p.acct).unwrap_or_default();
+            (t.acct.run_us.saturating_sub(prev.run_us), t.acct.switches.saturating_sub(prev.switches), t)
+        })
+        .collect();
+    rows.sort_by(|a, b| b.0.cmp(&a.0).then(a.2.tid.cmp(&b.2.tid)));
+
+    crate::kprintln!("\n  TID NAME             STATE       CPU  %CPU      TIME  SWITCHES   TICKS");
+    for (run_us, switches, t) in rows {
+        let pm = permille(run_us, elapsed);
+        let cpu = match t.cpu {
+            Some(c) => alloc::format!("{}", c),
+            None => String::from("-"),
+        };
+        crate::kprintln!(
+            "  {:>3} {:<16} {:<10} {:>4} {:>3}.{} {:>5}.{:03}s {:>9} {:>7}",
+            t.tid,
+            t.name,
+            alloc::format!("{:?}", t.state),
+            cpu,
+            pm / 10,
+            pm % 10,
+            t.acct.run_us / 1_000_000,
+            t.acct.run_us / 1000 % 1000,
+            switches,
+            t.acct.run_ticks
+        );
+    }
+}
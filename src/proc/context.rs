@@ -1,6 +1,38 @@
 //! CPU 컨텍스트 구조체
 //!
-//! 컨텍스트 스위칭에 필요한 레지스터 저장/복원을 위한 구조체
+//! 컨텍스트 스위칭에 필요한 레지스터 저장/복원을 위한 구조체.
+//! 스케줄러는 아키텍처별 `Context` 대신 `ArchContext` 트레이트만 사용하므로
+//! 새 아키텍처는 구조체, 트레이트 구현, 스위칭 어셈블리만 추가하면 됩니다.
+
+/// 아키텍처별 스레드 컨텍스트
+pub trait ArchContext: Sized {
+    /// 빈 컨텍스트 (첫 스위칭 때 현재 실행 상태가 저장됨)
+    fn empty() -> Self;
+
+    /// `entry`에서 `stack_top` 스택으로 시작하는 새 스레드 컨텍스트
+    fn new_thread(entry: usize, stack_top: usize) -> Self;
+
+    /// 현재 실행 상태를 `old`에 저장하고 `new`로 전환
+    ///
+    /// # Safety
+    /// - old와 new는 유효한 컨텍스트를 가리켜야 합니다.
+    /// - 호출 후 new의 스레드에서 실행이 계속됩니다.
+    unsafe fn switch(old: *mut Self, new: *const Self);
+}
+
+impl ArchContext for Context {
+    fn empty() -> Self {
+        Context::empty()
+    }
+
+    fn new_thread(entry: usize, stack_top: usize) -> Self {
+        Context::new(entry, stack_top)
+    }
+
+    unsafe fn switch(old: *mut Self, new: *const Self) {
+        unsafe { context_switch(old, new) }
+    }
+}
 
 /// CPU 컨텍스트 - 컨텍스트 스위칭 시 저장되는 레지스터들
 ///
@@ -9,11 +41,13 @@
 /// - x29 (FP): Frame pointer
 /// - x30 (LR): Link register (return address)
 /// - SP: Stack pointer
+/// - TPIDR_EL0: 스레드 포인터 (TLS, `proc::tls`)
 ///
 /// RISC-V에서는:
 /// - s0-s11 (x8-x9, x18-x27): Saved registers
 /// - ra (x1): Return address
 /// - sp (x2): Stack pointer
+/// - tp (x4): 스레드 포인터 (TLS, `proc::tls`)
 #[cfg(target_arch = "aarch64")]
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
@@ -35,6 +69,8 @@ pub struct Context {
     pub lr: u64,
     /// Stack pointer
     pub sp: u64,
+    /// TPIDR_EL0 (스레드 포인터)
+    pub tp: u64,
 }
 
 #[cfg(target_arch = "aarch64")]
@@ -47,6 +83,7 @@ impl Context {
             fp: 0,
             lr: 0,
             sp: 0,
+            tp: 0,
         }
     }
 
@@ -61,6 +98,7 @@ impl Context {
             fp: 0,
             lr: entry as u64,  // 컨텍스트 스위치 후 "ret"이 이 주소로 점프
             sp: stack_top as u64,
+            tp: 0,
         }
     }
 }
@@ -87,6 +125,8 @@ pub struct Context {
     pub s9: u64,  // x25
     pub s10: u64, // x26
     pub s11: u64, // x27
+    /// Thread pointer (x4)
+    pub tp: u64,
 }
 
 #[cfg(target_arch = "riscv64")]
@@ -98,6 +138,7 @@ impl Context {
             sp: 0,
             s0: 0, s1: 0, s2: 0, s3: 0, s4: 0, s5: 0,
             s6: 0, s7: 0, s8: 0, s9: 0, s10: 0, s11: 0,
+            tp: 0,
         }
     }
 
@@ -108,6 +149,7 @@ impl Context {
             sp: stack_top as u64,
             s0: 0, s1: 0, s2: 0, s3: 0, s4: 0, s5: 0,
             s6: 0, s7: 0, s8: 0, s9: 0, s10: 0, s11: 0,
+            tp: 0,
         }
     }
 }
@@ -144,6 +186,9 @@ context_switch:
     // sp 저장
     mov x9, sp
     str x9, [x0, #96]
+    // 스레드 포인터 저장
+    mrs x9, tpidr_el0
+    str x9, [x0, #104]
 
     // 새 컨텍스트 로드 (x1 = new_ctx)
     // callee-saved 레지스터 x19-x28 복원
@@ -157,6 +202,9 @@ context_switch:
     // sp 복원
     ldr x9, [x1, #96]
     mov sp, x9
+    // 스레드 포인터 복원
+    ldr x9, [x1, #104]
+    msr tpidr_el0, x9
 
     // lr로 점프 (ret은 x30으로 점프)
     ret
@@ -186,6 +234,7 @@ context_switch:
     sd s9, 88(a0)
     sd s10, 96(a0)
     sd s11, 104(a0)
+    sd tp, 112(a0)
 
     // 새 컨텍스트 로드 (a1 = new_ctx)
     ld ra, 0(a1)
@@ -202,6 +251,7 @@ context_switch:
     ld s9, 88(a1)
     ld s10, 96(a1)
     ld s11, 104(a1)
+    ld tp, 112(a1)
 
     // ra로 점프
     ret
@@ -0,0 +1,160 @@
+//! Futex (Fast Userspace muTEX)
+//!
+//! 유저 공간 락이 경합할 때만 커널에 들어와 잠들 수 있게 하는 대기 큐입니다.
+//! 주소를 해시해 버킷을 고르고, 각 버킷은 (주소, tid) 대기자 목록을 가집니다.
+//!
+//! - `wait(addr, val)`: 버킷 락을 잡은 채 `*addr == val`을 확인하고 대기자로 등록한 뒤
+//!   Blocked 상태로 잠듦 (확인과 등록 사이에 깨우기를 놓치지 않음)
+//! - `wake(addr, n)`: 대기자를 최대 n개 FIFO 순서로 꺼내 Ready로 바꿈
+//!
+//! 깨어난 스레드는 자기 항목이 버킷에서 빠졌으면 정상 깨움으로 보고, 남아 있으면
+//! 타임아웃/시그널/스퓨리어스 깨움으로 판단합니다. 타임아웃이 있는 대기는 틱마다
+//! 시각을 확인해야 하므로 Blocked 대신 `yield`로 폴링합니다.
+//!
+//! 락 순서: 버킷 락 → `THREADS` → 런큐
+
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use super::{percpu, runqueue, signal, ThreadState, Tid};
+use crate::sync::Mutex;
+
+/// 해시 버킷 수
+const FUTEX_BUCKETS: usize = 64;
+
+/// Futex 에러
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FutexError {
+    /// `*addr != val` (대기하지 않음)
+    WouldBlock,
+    /// 타임아웃
+    TimedOut,
+    /// 시그널로 중단
+    Interrupted,
+    /// 정렬되지 않은 주소 등
+    InvalidArgument,
+}
+
+/// 대기자
+struct Waiter {
+    addr: usize,
+    tid: Tid,
+}
+
+/// 주소 해시 버킷
+static BUCKETS: [Mutex<Vec<Waiter>>; FUTEX_BUCKETS] =
+    [const { Mutex::new(Vec::new()) }; FUTEX_BUCKETS];
+
+fn bucket(addr: usize) -> &'static Mutex<Vec<Waiter>> {
+    // 4바이트 정렬 주소의 하위 비트를 버리고 곱셈 해시
+    let hash = (addr >> 2).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    &BUCKETS[hash >> 58]
+}
+
+/// 현재 스레드를 Blocked로 표시 (schedule()이 다시 고르지 않음)
+fn block_current() {
+    let q = runqueue::queue(percpu::get_cpu_id()).lock();
+    if let Some(thread) = q.current() {
+        // Safety: 자기 런큐 락을 잡고 있음
+        let thread = unsafe { thread.get_mut() };
+        if thread.state == ThreadState::Running {
+            thread.state = ThreadState::Blocked;
+        }
+    }
+}
+
+/// Blocked 스레드를 Ready로 (깨울 대상이 아니면 무시)
+///
+/// # Returns
+/// 깨웠으면 스레드가 있는 런큐의 CPU
+fn unblock(tid: Tid) -> Option<u32> {
+    super::with_thread(tid, |thread| {
+        if thread.state != ThreadState::Blocked {
+            return None;
+        }
+        thread.state = ThreadState::Ready;
+        super::schedstat::on_wakeup(thread);
+        crate::trace_event!(sched_wakeup, tid = tid);
+        Some(thread.cpu.load(Ordering::Relaxed))
+    })
+    .flatten()
+}
+
+/// `addr`의 값이 `expected`인 동안 대기
+///
+/// # Arguments
+/// * `addr` - 4바이트 정렬된 futex 워드 주소
+/// * `expected` - 잠들기 직전에 확인할 값
+/// * `timeout_us` - 상대 타임아웃 (None = 무기한)
+pub fn wait(addr: usize, expected: u32, timeout_us: Option<u64>) -> Result<(), FutexError> {
+    if addr == 0 || addr % 4 != 0 {
+        return Err(FutexError::InvalidArgument);
+    }
+    let tid = super::current_tid().ok_or(FutexError::InvalidArgument)?;
+    let deadline = timeout_us.map(|t| crate::time::now_us().saturating_add(t));
+
+    {
+        let mut waiters = bucket(addr).lock();
+        let word = unsafe { &*(addr as *const AtomicU32) };
+        if word.load(Ordering::SeqCst) != expected {
+            return Err(FutexError::WouldBlock);
+        }
+        waiters.push(Waiter { addr, tid });
+        if deadline.is_none() {
+            block_current();
+        }
+    }
+
+    loop {
+        super::scheduler::schedule();
+
+        let mut waiters = bucket(addr).lock();
+        let Some(pos) = waiters.iter().position(|w| w.addr == addr && w.tid == tid) else {
+            // wake()가 꺼내 갔음
+            return Ok(());
+        };
+        if signal::has_pending(tid) {
+            waiters.remove(pos);
+            return Err(FutexError::Interrupted);
+        }
+        match deadline {
+            Some(deadline) if crate::time::now_us() >= deadline => {
+                waiters.remove(pos);
+                return Err(FutexError::TimedOut);
+            }
+            Some(_) => {}
+            // 실행할 다른 스레드가 없어 그대로 돌아온 경우: 다시 잠듦
+            None => block_current(),
+        }
+    }
+}
+
+/// `addr`에서 대기 중인 스레드를 최대 `count`개 깨움
+///
+/// # Returns
+/// 깨운 스레드 수
+pub fn wake(addr: usize, count: usize) -> usize {
+    if addr == 0 || addr % 4 != 0 || count == 0 {
+        return 0;
+    }
+    let mut woken = 0;
+    {
+        let mut waiters = bucket(addr).lock();
+        while woken < count {
+            let Some(pos) = waiters.iter().position(|w| w.addr == addr) else {
+                break;
+            };
+            let waiter = waiters.remove(pos);
+            if let Some(cpu) = unblock(waiter.tid) {
+                super::kick_idle_cpu(cpu);
+            }
+            woken += 1;
+        }
+    }
+    woken
+}
+
+/// `addr`에서 대기 중인 스레드 수
+pub fn waiters(addr: usize) -> usize {
+    bucket(addr).lock().iter().filter(|w| w.addr == addr).count()
+}
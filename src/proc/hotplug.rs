@@ -0,0 +1,222 @@
+//! CPU 핫플러그: 보조 CPU를 오프라인으로 내리고 다시 올림
+//!
+//! `cpu offline <n>` / `cpu online <n>` 명령이 씁니다. 내린 CPU는 펌웨어로 돌려보내지
+//! (PSCI `CPU_OFF`, SBI `HART_STOP`) 않고 idle 루프에서 틱을 끈 채 `wfi`로 기다립니다.
+//! 다시 올릴 때 보조 CPU 부팅 경로(스택, MMU, GIC/PLIC, idle 스레드)를 다시 거치지 않아도
+//! 되고, 펌웨어가 없는 riscv64 M-mode에서도 같은 방식으로 동작합니다.
+//!
+//! 내리기 (`cpu_down`):
+//! 1. 오프라인 표시 (`percpu::mark_offline`): 새 스레드, 부하 분산, CPU별 작업이 이 CPU를
+//!    고르지 않고, 이 CPU의 스케줄러는 idle 스레드만 실행
+//! 2. 온라인 CPU가 하나도 남지 않는 친화도는 모든 CPU로 풀어 줌 (`kworker/<n>` 등)
+//! 3. CPU에서 내려가 있는 스레드는 허용된 CPU 런큐로 직접 옮기고, 실행 중인 스레드는
+//!    reschedule IPI로 내리게 함 (내려간 뒤 다음 차례에 옮김)
+//! 4. 런큐에 idle 스레드만 남고 그 CPU가 대기(`park_if_offline`)에 들어가면 완료
+//!
+//! 대기 중인 CPU도 인터럽트는 받으므로 IPI와 (riscv64 PLIC이 보내는) 디바이스 인터럽트를
+//! 처리하고, kexec/재부팅 때는 다른 CPU처럼 정지합니다.
+//!
+//! 올리기 (`cpu_up`): 온라인 표시 후 IPI로 깨우면 그 CPU가 틱을 다시 켜고 스케줄링을
+//! 재개합니다. CPU별 워커(`kworker/<n>`)는 다시 그 CPU에 묶습니다.
+//!
+//! 부팅 CPU(CPU 0)는 내릴 수 없습니다.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::percpu::{self, CpuMask, MAX_CPUS};
+use super::{runqueue, ThreadState, THREADS};
+use crate::kprintln;
+use crate::sync::Mutex;
+
+/// 스레드를 모두 옮길 때까지 기다리는 최대 시간 (마이크로초)
+const DOWN_TIMEOUT_US: u64 = 2_000_000;
+
+/// 올린 CPU가 깨어날 때까지 기다리는 최대 시간 (마이크로초)
+const UP_TIMEOUT_US: u64 = 100_000;
+
+/// idle 루프에서 대기 중인 CPU
+static PARKED: [AtomicBool; MAX_CPUS] = [const { AtomicBool::new(false) }; MAX_CPUS];
+
+/// 내리기/올리기 직렬화
+static HOTPLUG_LOCK: Mutex<()> = Mutex::new(());
+
+/// 핫플러그 에러
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotplugError {
+    /// 없는 CPU 번호
+    NoSuchCpu,
+    /// 부팅 CPU는 내릴 수 없음
+    BootCpu,
+    /// 이미 온라인
+    AlreadyOnline,
+    /// 이미 오프라인
+    AlreadyOffline,
+    /// 시작되지 않은 CPU (SMP 부팅 실패)
+    NotPresent,
+    /// 제한 시간 안에 스레드를 모두 옮기지 못함 (다시 온라인으로 되돌림)
+    Busy,
+}
+
+/// `cpu`를 오프라인으로 내림
+///
+/// 스레드 문맥에서만 부릅니다. `cpu`에서 실행 중인 스레드가 불러도 되며, 그 경우 이
+/// 스레드도 다른 CPU로 옮겨진 뒤 돌아옵니다.
+pub fn cpu_down(cpu: u32) -> Result<(), HotplugError> {
+    let _guard = HOTPLUG_LOCK.lock();
+    if cpu >= percpu::total_count() || cpu as usize >= MAX_CPUS {
+        return Err(HotplugError::NoSuchCpu);
+    }
+    if cpu == 0 {
+        return Err(HotplugError::BootCpu);
+    }
+    if !percpu::get(cpu).is_online() {
+        return Err(HotplugError::AlreadyOffline);
+    }
+
+    percpu::mark_offline(cpu);
+    break_affinity(cpu);
+
+    let deadline = crate::time::now_us() + DOWN_TIMEOUT_US;
+    while crate::time::now_us() < deadline {
+        if migrate_from(cpu) && is_parked(cpu) {
+            kprintln!("[hotplug] CPU {} offline", cpu);
+            return Ok(());
+        }
+        if percpu::get_cpu_id() == cpu {
+            // 이 스레드는 스스로 옮길 수 없음: 내려가면 다른 CPU의 부하 분산이 가져감
+            super::yield_now();
+        } else {
+            super::park_timeout(1000);
+        }
+    }
+
+    percpu::mark_online(cpu);
+    super::send_reschedule(cpu);
+    super::workqueue::rebind(cpu);
+    kprintln!("[hotplug] CPU {}: threads did not migrate, staying online", cpu);
+    Err(HotplugError::Busy)
+}
+
+/// 오프라인 `cpu`를 다시 올림
+pub fn cpu_up(cpu: u32) -> Result<(), HotplugError> {
+    let _guard = HOTPLUG_LOCK.lock();
+    if cpu >= percpu::total_count() || cpu as usize >= MAX_CPUS {
+        return Err(HotplugError::NoSuchCpu);
+    }
+    if percpu::get(cpu).is_online() {
+        return Err(HotplugError::AlreadyOnline);
+    }
+    if !is_parked(cpu) {
+        return Err(HotplugError::NotPresent);
+    }
+
+    percpu::mark_online(cpu);
+    super::send_reschedule(cpu);
+
+    let deadline = crate::time::now_us() + UP_TIMEOUT_US;
+    while is_parked(cpu) && crate::time::now_us() < deadline {
+        super::yield_now();
+    }
+    super::workqueue::rebind(cpu);
+    kprintln!("[hotplug] CPU {} online", cpu);
+    Ok(())
+}
+
+/// `cpu`가 오프라인으로 idle 루프에서 대기 중인지
+pub fn is_parked(cpu: u32) -> bool {
+    (cpu as usize) < MAX_CPUS && PARKED[cpu as usize].load(Ordering::Acquire)
+}
+
+/// 보조 CPU idle 루프에서 호출: 오프라인이면 틱을 끄고 다시 온라인이 될 때까지 `wfi`로 대기
+///
+/// 인터럽트는 켜 둔 채 기다리므로 IPI를 처리합니다. 오프라인 CPU의 스케줄러는 idle
+/// 스레드만 고르므로 대기 중에 다른 스레드가 실행되지 않습니다.
+pub fn park_if_offline() {
+    let cpu = percpu::get_cpu_id();
+    if percpu::get(cpu).is_online() {
+        return;
+    }
+
+    crate::arch::timer::disable();
+    PARKED[cpu as usize].store(true, Ordering::Release);
+    while !percpu::get(cpu).is_online() {
+        unsafe {
+            core::arch::asm!("wfi");
+        }
+    }
+    PARKED[cpu as usize].store(false, Ordering::Release);
+    crate::arch::timer::init_secondary();
+}
+
+/// 온라인 CPU가 하나도 남지 않는 친화도를 모든 CPU로 풀어 줌
+fn break_affinity(cpu: u32) {
+    let online = percpu::online_mask();
+    let mut broken: Vec<(super::Tid, String)> = Vec::new();
+    {
+        let threads = THREADS.lock();
+        for &thread in threads.iter() {
+            if super::is_idle_thread(thread.get().tid) {
+                continue;
+            }
+            let _q = runqueue::lock_thread(thread);
+            // Safety: 스레드가 속한 런큐 락을 잡고 있음
+            let t = unsafe { thread.get_mut() };
+            if t.cpu_affinity.intersection(online).is_empty() {
+                t.cpu_affinity = CpuMask::ALL;
+                broken.push((t.tid, t.name.clone()));
+            }
+        }
+    }
+    for (tid, name) in broken {
+        kprintln!("[hotplug] thread {} ({}) no longer affine to CPU {}", tid, name, cpu);
+    }
+}
+
+/// `cpu` 런큐에서 CPU에서 내려가 있는 스레드를 허용된 온라인 CPU로 옮김
+///
+/// 실행 중인 스레드가 남아 있으면 IPI로 내리게 합니다. idle 스레드만 남았으면 true.
+fn migrate_from(cpu: u32) -> bool {
+    let my_cpu = percpu::get_cpu_id();
+    for to in percpu::online_mask().iter() {
+        let mut wake = false;
+        {
+            let (mut src, mut dst) = runqueue::lock_pair(cpu, to);
+            let mut idx = 0;
+            while idx < src.threads.len() {
+                let thread = src.threads[idx];
+                // Safety: 두 런큐 락을 모두 잡고 있음
+                let t = unsafe { thread.get_mut() };
+                let movable = idx != src.idle
+                    && idx != src.current
+                    && !t.on_cpu.load(Ordering::Acquire)
+                    && t.cpu_affinity.contains(to);
+                if !movable {
+                    idx += 1;
+                    continue;
+                }
+                src.remove(idx);
+                t.cpu.store(to, Ordering::Release);
+                dst.push(thread);
+                wake |= t.state == ThreadState::Ready;
+
+                percpu::get(cpu).migrations_out.fetch_add(1, Ordering::Relaxed);
+                percpu::get(to).migrations_in.fetch_add(1, Ordering::Relaxed);
+                crate::trace_event!(sched_migrate, tid = t.tid, from = cpu, to = to);
+            }
+        }
+        if wake && to != my_cpu {
+            super::send_reschedule(to);
+        }
+    }
+
+    let (only_idle, running) = {
+        let q = runqueue::queue(cpu).lock();
+        (q.threads.len() == 1 && q.idle == 0, q.current != q.idle)
+    };
+    if running && cpu != my_cpu {
+        super::send_reschedule(cpu);
+    }
+    only_idle
+}
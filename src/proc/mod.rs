@@ -1,21 +1,38 @@
 //! 프로세스/스레드 관리 모듈
 //!
 //! 커널 스레드 추상화와 컨텍스트 스위칭 구현
-//! SMP 환경에서 각 CPU는 per-CPU 데이터를 통해 자신의 현재 스레드를 추적합니다.
+//! SMP 환경에서 각 CPU는 자기 런큐(`runqueue`)에서 스레드를 고르고, per-CPU 데이터로
+//! 현재 스레드를 추적합니다. 전역 레지스트리 `THREADS`는 TID 조회와 목록용입니다.
 
+pub mod acct;
 pub mod context;
+pub mod futex;
+pub mod hotplug;
+pub mod park;
 pub mod percpu;
+pub mod policy;
+pub mod reaper;
+pub mod runqueue;
 pub mod scheduler;
+pub mod schedstat;
+pub mod signal;
+pub mod tls;
 pub mod user;
+pub mod workqueue;
 
 use alloc::boxed::Box;
 use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
-use core::sync::atomic::{AtomicU64, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use crate::sync::Mutex;
 
 use crate::kprintln;
-use context::Context;
+use context::{ArchContext, Context};
+use percpu::CpuMask;
+use runqueue::ThreadRef;
+
+pub use park::{park, park_timeout, unpark, WaitList};
 
 /// 스레드 ID 타입
 pub type Tid = u64;
@@ -36,7 +53,21 @@ pub enum ThreadState {
     Terminated,
 }
 
+/// 합류(join) 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinState {
+    /// 종료되면 `join()`할 때까지 TCB를 남김
+    Joinable,
+    /// 다른 스레드가 `join()`으로 기다리는 중
+    Joining,
+    /// 종료되면 `kreaperd`가 해제 (`detach()`)
+    Detached,
+}
+
 /// 스레드 제어 블록 (TCB)
+///
+/// `state`, `context`, `cpu_affinity`, `signals`, `acct`, `exit_status`, `join`, `park`,
+/// `park_deadline`, `files`는 스레드가 속한 런큐의 락으로 보호합니다 ([`runqueue`] 참고).
 pub struct Thread {
     /// 스레드 ID
     pub tid: Tid,
@@ -46,10 +77,31 @@ pub struct Thread {
     pub state: ThreadState,
     /// CPU 컨텍스트 (레지스터 상태)
     pub context: Context,
-    /// 커널 스택 (Box로 관리)
+    /// 커널 스택 (Box로 관리, 종료 후 CPU에서 내려가면 `kreaperd`가 해제)
     pub kernel_stack: Vec<u8>,
-    /// CPU 친화도 (None = 모든 CPU에서 실행 가능, Some(id) = 특정 CPU에 고정)
-    pub cpu_affinity: Option<u32>,
+    /// CPU 친화도 (실행될 수 있는 CPU 집합, `set_affinity`로 변경)
+    pub cpu_affinity: CpuMask,
+    /// 속한 런큐의 CPU (두 런큐 락을 모두 잡고 부하 분산이 바꿈)
+    pub cpu: AtomicU32,
+    /// CPU에서 실행 중이거나 내려가는 중 (스위칭이 끝나야 다른 CPU로 옮길 수 있음)
+    pub on_cpu: AtomicBool,
+    /// 대기 시그널과 시그널 동작
+    pub signals: signal::SignalState,
+    /// 실행 시간 회계
+    pub acct: acct::ThreadAcct,
+    /// 종료 상태 (`exit_with` 값, 시그널로 종료되면 128 + 시그널 번호)
+    pub exit_status: i32,
+    /// 종료 표시 (futex 워드, 0 = 실행 중, 1 = 종료)
+    exited: AtomicU32,
+    pub join: JoinState,
+    /// park/unpark 토큰 상태
+    pub park: park::ParkState,
+    /// `park_timeout()` 기한 (부팅 후 마이크로초)
+    pub park_deadline: Option<u64>,
+    /// 자기 FD 테이블 (None = 커널 FD 테이블 사용, 종료 시 닫힘)
+    pub files: Option<Arc<crate::fs::fd::FdTable>>,
+    /// 모듈 TLS 블록 (처음 접근할 때 할당, TCB와 함께 해제)
+    pub tls: tls::ThreadTls,
 }
 
 impl Thread {
@@ -70,7 +122,7 @@ impl Thread {
         let stack_top = stack_top & !0xF;
 
         // 컨텍스트 초기화
-        let context = Context::new(entry as usize, stack_top);
+        let context = Context::new_thread(entry as usize, stack_top);
 
         Thread {
             tid,
@@ -78,7 +130,18 @@ impl Thread {
             state: ThreadState::Ready,
             context,
             kernel_stack,
-            cpu_affinity: None, // 모든 CPU에서 실행 가능
+            cpu_affinity: CpuMask::ALL, // 모든 CPU에서 실행 가능
+            cpu: AtomicU32::new(0),
+            on_cpu: AtomicBool::new(false),
+            signals: signal::SignalState::default(),
+            acct: acct::ThreadAcct::default(),
+            exit_status: 0,
+            exited: AtomicU32::new(0),
+            join: JoinState::Joinable,
+            park: park::ParkState::Empty,
+            park_deadline: None,
+            files: None,
+            tls: tls::ThreadTls::new(),
         }
     }
 
@@ -93,9 +156,20 @@ impl Thread {
             tid,
             name: String::from("idle/0"),
             state: ThreadState::Running,
-            context: Context::empty(),
+            context: <Context as ArchContext>::empty(),
             kernel_stack,
-            cpu_affinity: Some(0),
+            cpu_affinity: CpuMask::single(0),
+            cpu: AtomicU32::new(0),
+            on_cpu: AtomicBool::new(true),
+            signals: signal::SignalState::default(),
+            acct: acct::ThreadAcct::default(),
+            exit_status: 0,
+            exited: AtomicU32::new(0),
+            join: JoinState::Joinable,
+            park: park::ParkState::Empty,
+            park_deadline: None,
+            files: None,
+            tls: tls::ThreadTls::new(),
         }
     }
 
@@ -107,15 +181,109 @@ impl Thread {
             tid,
             name: alloc::format!("idle/{}", cpu_id),
             state: ThreadState::Running,
-            context: Context::empty(),
+            context: <Context as ArchContext>::empty(),
             kernel_stack: Vec::new(), // 스택은 percpu::stacks에서 관리
-            cpu_affinity: Some(cpu_id),
+            cpu_affinity: CpuMask::single(cpu_id),
+            cpu: AtomicU32::new(cpu_id),
+            on_cpu: AtomicBool::new(true),
+            signals: signal::SignalState::default(),
+            acct: acct::ThreadAcct::default(),
+            exit_status: 0,
+            exited: AtomicU32::new(0),
+            join: JoinState::Joinable,
+            park: park::ParkState::Empty,
+            park_deadline: None,
+            files: None,
+            tls: tls::ThreadTls::new(),
         }
     }
+
+    /// 종료 상태 기록 후 합류 대기자가 기다리는 futex 주소 반환 (이미 종료 표시되었으면 None)
+    fn mark_exited(&mut self, status: i32) -> Option<usize> {
+        if self.exited.load(Ordering::Relaxed) != 0 {
+            return None;
+        }
+        self.exit_status = status;
+        self.exited.store(1, Ordering::Release);
+        Some(self.exited_word())
+    }
+
+    fn exited_word(&self) -> usize {
+        &self.exited as *const AtomicU32 as usize
+    }
+}
+
+/// 전역 스레드 레지스트리 (TID 조회, 목록 출력용, 스케줄링 경로에서는 잡지 않음)
+pub(crate) static THREADS: Mutex<Vec<ThreadRef>> = Mutex::new(Vec::new());
+
+/// 스레드를 레지스트리와 `cpu` 런큐에 등록
+///
+/// 모든 런큐의 용량을 전체 스레드 수 이상으로 늘려 두므로, 이후 부하 분산이
+/// 인터럽트 경로에서 스레드를 옮겨도 할당하지 않습니다.
+fn register(thread: Box<Thread>, cpu: u32) -> ThreadRef {
+    thread.cpu.store(cpu, Ordering::Relaxed);
+    let thread = ThreadRef::new(thread);
+
+    let mut threads = THREADS.lock();
+    runqueue::reserve_all(threads.len() + 1);
+    threads.push(thread);
+    runqueue::queue(cpu).lock().push(thread);
+    thread
 }
 
-/// 전역 스레드 리스트 (모든 CPU가 공유)
-pub(crate) static THREADS: Mutex<Vec<Box<Thread>>> = Mutex::new(Vec::new());
+/// idle 스레드를 등록하고 `cpu`의 현재 스레드로 설정 (스케줄링 시작 전)
+fn register_idle(thread: Box<Thread>, cpu: u32) {
+    let tid = thread.tid;
+    let thread = register(thread, cpu);
+
+    let mut q = runqueue::queue(cpu).lock();
+    let idx = q.position(thread).unwrap_or(runqueue::NONE);
+    q.current = idx;
+    q.idle = idx;
+
+    let pc = percpu::get(cpu);
+    pc.current_tid.store(tid, Ordering::Release);
+    pc.idle_tid.store(tid, Ordering::Release);
+    tls::set_current(pc, &thread.get().tls);
+    acct::start_cpu(pc);
+}
+
+/// TID로 스레드를 찾아 그 런큐 락을 잡은 채 `f` 실행
+///
+/// 레지스트리 락도 잡고 있으므로 그동안 스레드가 해제(`join`/`detach`)되지 않습니다.
+pub(crate) fn with_thread<R>(tid: Tid, f: impl FnOnce(&mut Thread) -> R) -> Option<R> {
+    let threads = THREADS.lock();
+    let thread = threads.iter().copied().find(|t| t.get().tid == tid)?;
+    let _q = runqueue::lock_thread(thread);
+    // Safety: 스레드가 속한 런큐 락을 잡고 있음
+    Some(f(unsafe { thread.get_mut() }))
+}
+
+/// `with_thread`와 같지만 레지스트리 락을 바로 얻지 못하면 기다리지 않고 None
+///
+/// 할당 실패 경로(OOM 킬러)처럼 호출자가 이미 레지스트리 락을 잡고 있을 수 있는 곳에서 씁니다.
+pub(crate) fn try_with_thread<R>(tid: Tid, f: impl FnOnce(&mut Thread) -> R) -> Option<R> {
+    let threads = THREADS.try_lock()?;
+    let thread = threads.iter().copied().find(|t| t.get().tid == tid)?;
+    let _q = runqueue::lock_thread(thread);
+    // Safety: 스레드가 속한 런큐 락을 잡고 있음
+    Some(f(unsafe { thread.get_mut() }))
+}
+
+/// 레지스트리 락을 바로 얻을 수 있으면 모든 스레드에 `f` 실행 (각 스레드의 런큐 락을 잡은 채)
+///
+/// 락을 얻지 못하면 false입니다 (`try_with_thread` 참고).
+pub(crate) fn try_for_each_thread(mut f: impl FnMut(&mut Thread)) -> bool {
+    let Some(threads) = THREADS.try_lock() else {
+        return false;
+    };
+    for &thread in threads.iter() {
+        let _q = runqueue::lock_thread(thread);
+        // Safety: 스레드가 속한 런큐 락을 잡고 있음
+        f(unsafe { thread.get_mut() });
+    }
+    true
+}
 
 /// 프로세스 서브시스템 초기화
 pub fn init() {
@@ -125,96 +293,133 @@ pub fn init() {
     percpu::init(1);
 
     // idle 스레드 생성 (tid=0, CPU 0 전용)
-    let idle = Box::new(Thread::idle());
+    register_idle(Box::new(Thread::idle()), percpu::get_cpu_id());
 
-    {
-        let mut threads = THREADS.lock();
-        threads.push(idle);
-    }
+    kprintln!("[proc] Idle thread created (tid=0)");
 
-    // Per-CPU 데이터에 현재/idle 스레드 인덱스 설정
-    let pc = percpu::current();
-    pc.current_thread_idx.store(0, Ordering::Release);
-    pc.idle_thread_idx.store(0, Ordering::Release);
+    // 종료된 스레드 회수 스레드
+    reaper::start();
 
-    kprintln!("[proc] Idle thread created (tid=0)");
+    // 전역 워크큐 워커
+    workqueue::init();
+
+    scheduler::register_sysctl();
 }
 
 /// Secondary CPU에서 호출: idle 스레드를 생성하고 per-CPU 데이터 설정
 pub fn init_on_secondary_cpu(cpu_id: u32) {
-    let idle = Box::new(Thread::idle_for_cpu(cpu_id));
-
-    let mut threads = THREADS.lock();
-    let idx = threads.len();
-    threads.push(idle);
-
-    let pc = percpu::get(cpu_id);
-    pc.current_thread_idx.store(idx as u32, Ordering::Release);
-    pc.idle_thread_idx.store(idx as u32, Ordering::Release);
+    register_idle(Box::new(Thread::idle_for_cpu(cpu_id)), cpu_id);
 }
 
 /// 새 커널 스레드 생성
+///
+/// idle 중인 CPU가 있으면 그 CPU의 런큐에, 없으면 현재 CPU의 런큐에 넣습니다.
+/// 이후 부하는 스케줄러의 부하 분산이 고릅니다.
 pub fn spawn(name: &str, entry: fn() -> !) -> Tid {
     let thread = Box::new(Thread::new(name, entry));
     let tid = thread.tid;
+    let my_cpu = percpu::get_cpu_id();
+    let cpu = find_idle_cpu().unwrap_or(my_cpu);
 
-    kprintln!("[proc] Spawning thread '{}' (tid={})", name, tid);
+    kprintln!("[proc] Spawning thread '{}' (tid={}) on CPU {}", name, tid, cpu);
+    register(thread, cpu);
 
-    {
-        let mut threads = THREADS.lock();
-        threads.push(thread);
+    // SMP: idle 중인 다른 CPU를 깨워서 새 스레드를 실행하도록 IPI 전송
+    if cpu != my_cpu {
+        send_reschedule(cpu);
     }
 
-    // SMP: idle 중인 다른 CPU를 깨워서 새 스레드를 실행하도록 IPI 전송
-    kick_idle_cpu();
+    tid
+}
+
+/// 자기 FD 테이블을 가진 커널 스레드 생성
+///
+/// 자식이 부모의 fd를 이어받게 하려면 `FdTable::fork()`로 복제한 테이블을 넘기고,
+/// 넘기기 전에 `redirect`/`dup2`로 stdout 등을 바꿔 둡니다.
+pub fn spawn_with_files(name: &str, entry: fn() -> !, files: Arc<crate::fs::fd::FdTable>) -> Tid {
+    let mut thread = Box::new(Thread::new(name, entry));
+    thread.files = Some(files);
+    let tid = thread.tid;
+    let my_cpu = percpu::get_cpu_id();
+    let cpu = find_idle_cpu().unwrap_or(my_cpu);
+
+    kprintln!("[proc] Spawning thread '{}' (tid={}) on CPU {}", name, tid, cpu);
+    register(thread, cpu);
+
+    if cpu != my_cpu {
+        send_reschedule(cpu);
+    }
+
+    tid
+}
+
+/// 스레드의 FD 테이블 (자기 테이블이 없으면 None)
+pub fn files(tid: Tid) -> Option<Arc<crate::fs::fd::FdTable>> {
+    with_thread(tid, |t| t.files.clone()).flatten()
+}
+
+/// 특정 CPU에 고정된 커널 스레드 생성
+pub fn spawn_on_cpu(name: &str, entry: fn() -> !, cpu: u32) -> Tid {
+    let mut thread = Box::new(Thread::new(name, entry));
+    thread.cpu_affinity = CpuMask::single(cpu);
+    let tid = thread.tid;
+
+    kprintln!("[proc] Spawning thread '{}' (tid={}) on CPU {}", name, tid, cpu);
+    register(thread, cpu);
+
+    // 대상 CPU가 다른 CPU이면 바로 스케줄링되도록 IPI 전송
+    if cpu != percpu::get_cpu_id() {
+        send_reschedule(cpu);
+    }
 
     tid
 }
 
-/// idle 중인 다른 CPU에 reschedule IPI 전송
-fn kick_idle_cpu() {
+/// `cpu`에 reschedule IPI 전송
+fn send_reschedule(cpu: u32) {
+    #[cfg(target_arch = "aarch64")]
+    crate::arch::gic::send_reschedule_ipi(cpu);
+
+    #[cfg(target_arch = "riscv64")]
+    crate::arch::plic::send_reschedule_ipi(cpu);
+}
+
+/// idle 스레드를 실행 중인 다른 온라인 CPU
+fn find_idle_cpu() -> Option<u32> {
     let my_cpu = percpu::get_cpu_id();
-    let online = percpu::online_count();
+    percpu::online_mask().iter().find(|&cpu| cpu != my_cpu && percpu::get(cpu).is_idle())
+}
 
-    // 온라인 CPU 중 idle 상태인 CPU를 찾아 IPI 전송
-    for cpu in 0..online {
-        if cpu == my_cpu {
-            continue;
-        }
-        let pc = percpu::get(cpu);
-        let current = pc.current_thread_idx.load(Ordering::Relaxed);
-        let idle = pc.idle_thread_idx.load(Ordering::Relaxed);
-        if current == idle {
-            // 이 CPU는 idle → reschedule IPI 전송
-            #[cfg(target_arch = "aarch64")]
-            crate::arch::gic::send_reschedule_ipi(cpu);
-
-            #[cfg(target_arch = "riscv64")]
-            crate::arch::plic::send_reschedule_ipi(cpu);
-
-            break; // 하나만 깨우면 됨
-        }
+/// 깨운 스레드를 실행할 CPU에 reschedule IPI 전송
+///
+/// 스레드가 있는 런큐의 CPU(`cpu`)가 idle이면 그 CPU를 깨웁니다 (틱 없이 잠들어 있을 수
+/// 있음, `time::nohz`). 아니면 idle 중인 다른 CPU 하나를 깨워 스케줄러의 부하 분산으로
+/// 가져가게 합니다.
+fn kick_idle_cpu(cpu: u32) {
+    let target = if cpu != percpu::get_cpu_id() && percpu::get(cpu).is_idle() {
+        Some(cpu)
+    } else {
+        find_idle_cpu()
+    };
+    if let Some(target) = target {
+        send_reschedule(target);
     }
 }
 
 /// 현재 스레드 ID 반환
 pub fn current_tid() -> Option<Tid> {
-    let idx = percpu::current().current_thread_idx.load(Ordering::Acquire);
-    if idx == u32::MAX {
+    let tid = percpu::current().current_tid.load(Ordering::Acquire);
+    if tid == percpu::NO_THREAD {
         return None;
     }
-    let threads = THREADS.lock();
-    threads.get(idx as usize).map(|t| t.tid)
+    Some(tid)
 }
 
 /// 현재 스레드의 컨텍스트 포인터 반환
 pub fn current_context_ptr() -> Option<*mut Context> {
-    let idx = percpu::current().current_thread_idx.load(Ordering::Acquire);
-    if idx == u32::MAX {
-        return None;
-    }
-    let mut threads = THREADS.lock();
-    threads.get_mut(idx as usize).map(|t| &mut t.context as *mut Context)
+    let q = runqueue::queue(percpu::get_cpu_id()).lock();
+    // Safety: 자기 런큐 락을 잡고 있음
+    q.current().map(|t| unsafe { &mut t.get_mut().context as *mut Context })
 }
 
 /// 스레드 상태 출력
@@ -223,45 +428,260 @@ pub fn dump_threads() {
     let online = percpu::online_count();
 
     kprintln!("\n[proc] Thread list ({} threads, {} CPUs online):", threads.len(), online);
-    for (i, thread) in threads.iter().enumerate() {
-        // 이 스레드가 어느 CPU에서 실행 중인지 확인
-        let mut running_on = None;
-        for cpu in 0..online {
-            let pc = percpu::get(cpu);
-            if pc.current_thread_idx.load(Ordering::Relaxed) == i as u32 {
-                running_on = Some(cpu);
-                break;
-            }
+    let mut zombies = 0;
+    for &thread in threads.iter() {
+        let t = thread.get();
+        let state = {
+            let _q = runqueue::lock_thread(thread);
+            // Safety: 스레드가 속한 런큐 락을 잡고 있음
+            unsafe { thread.get_mut().state }
+        };
+        if state == ThreadState::Terminated {
+            zombies += 1;
         }
+        // 이 스레드가 어느 CPU에서 실행 중인지 확인
+        let running_on = (0..percpu::total_count()).find(|&cpu| percpu::get(cpu).current_tid.load(Ordering::Relaxed) == t.tid);
         let cpu_mark = match running_on {
             Some(cpu) => alloc::format!(" [CPU {}]", cpu),
-            None => String::new(),
+            None => alloc::format!(" (rq {})", t.cpu.load(Ordering::Relaxed)),
         };
-        kprintln!(
-            "  tid={}, name='{}', state={:?}{}",
-            thread.tid, thread.name, thread.state, cpu_mark
-        );
+        kprintln!("  tid={}, name='{}', state={:?}{}", t.tid, t.name, state, cpu_mark);
+    }
+
+    let reaped = reaper::stats();
+    kprintln!(
+        "  {} terminated awaiting join/reap, {} reaped, {} stacks freed ({} KB)",
+        zombies,
+        reaped.threads_reaped,
+        reaped.stacks_freed,
+        reaped.stack_bytes_freed / 1024
+    );
+}
+
+/// `kill()`로 종료된 스레드의 종료 상태
+const KILLED_STATUS: i32 = signal::exit_status(signal::SIGKILL);
+
+/// 스레드 강제 종료
+///
+/// Terminated로 표시하면 스케줄러가 다시 선택하지 않습니다. 다른 CPU에서
+/// 실행 중인 스레드는 그 CPU의 다음 틱에 내려가므로, 코드가 더 이상 실행되지
+/// 않아야 하면 `is_on_cpu`가 false가 될 때까지 기다려야 합니다.
+///
+/// # Returns
+/// 스레드를 찾아 종료 표시했으면 true (이미 종료된 경우 포함)
+pub fn kill(tid: Tid) -> bool {
+    let exited = with_thread(tid, |t| {
+        t.state = ThreadState::Terminated;
+        t.mark_exited(KILLED_STATUS)
+    });
+    match exited {
+        Some(Some(addr)) => {
+            futex::wake(addr, usize::MAX);
+            reaper::wake();
+            true
+        }
+        Some(None) => true,
+        None => false,
+    }
+}
+
+/// 스레드가 어떤 CPU에서든 현재 실행 중인지 확인 (내려가는 중인 경우 포함)
+pub fn is_on_cpu(tid: Tid) -> bool {
+    with_thread(tid, |t| t.on_cpu.load(Ordering::Acquire)).unwrap_or(false)
+}
+
+/// idle 스레드인지 (idle은 종료하거나 옮길 수 없음)
+pub(crate) fn is_idle_thread(tid: Tid) -> bool {
+    (0..percpu::total_count()).any(|cpu| percpu::get(cpu).idle_tid.load(Ordering::Relaxed) == tid)
+}
+
+/// CPU 친화도 변경 에러
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AffinityError {
+    /// 해당 TID의 스레드 없음
+    NoSuchThread,
+    /// 온라인 CPU가 하나도 없는 집합
+    InvalidMask,
+    /// idle 스레드는 CPU에 고정됨
+    NotPermitted,
+}
+
+/// 스레드의 CPU 친화도 조회
+pub fn affinity(tid: Tid) -> Option<CpuMask> {
+    with_thread(tid, |t| t.cpu_affinity)
+}
+
+/// 스레드의 CPU 친화도 변경
+///
+/// 스레드가 있는 런큐의 CPU가 새 집합에 없으면 허용된 CPU로 옮깁니다. CPU에서
+/// 내려가 있으면 바로 옮기고, 실행 중이면 그 CPU에 reschedule IPI를 보내 내려가게
+/// 한 뒤 허용된 CPU의 부하 분산이 가져갑니다. 현재 스레드면 양보해서 옮겨지게 합니다.
+pub fn set_affinity(tid: Tid, mask: CpuMask) -> Result<(), AffinityError> {
+    let online = percpu::online_mask();
+    if mask.intersection(online).is_empty() {
+        return Err(AffinityError::InvalidMask);
+    }
+    if is_idle_thread(tid) {
+        return Err(AffinityError::NotPermitted);
+    }
+
+    let threads = THREADS.lock();
+    let thread = threads.iter().copied().find(|t| t.get().tid == tid).ok_or(AffinityError::NoSuchThread)?;
+    let from = {
+        let _q = runqueue::lock_thread(thread);
+        // Safety: 스레드가 속한 런큐 락을 잡고 있음
+        unsafe { thread.get_mut().cpu_affinity = mask };
+        let cpu = thread.get().cpu.load(Ordering::Relaxed);
+        if mask.contains(cpu) {
+            return Ok(());
+        }
+        cpu
+    };
+
+    // idle 중인 허용 CPU 우선
+    let allowed = mask.intersection(online);
+    let to = allowed
+        .iter()
+        .find(|&cpu| percpu::get(cpu).is_idle())
+        .or_else(|| allowed.iter().next())
+        .ok_or(AffinityError::InvalidMask)?;
+
+    let moved = {
+        let (mut src, mut dst) = runqueue::lock_pair(from, to);
+        // 그 사이 옮겨졌거나 실행 중이면 스케줄러에 맡김
+        let t = thread.get();
+        match src.position(thread) {
+            Some(idx) if t.cpu.load(Ordering::Acquire) == from && !t.on_cpu.load(Ordering::Acquire) => {
+                src.remove(idx);
+                t.cpu.store(to, Ordering::Release);
+                dst.push(thread);
+                true
+            }
+            _ => false,
+        }
+    };
+    drop(threads);
+
+    let my_cpu = percpu::get_cpu_id();
+    if moved {
+        if to != my_cpu {
+            send_reschedule(to);
+        }
+    } else if current_tid() == Some(tid) {
+        yield_now();
+    } else if from != my_cpu {
+        send_reschedule(from);
     }
+    Ok(())
 }
 
 /// 스레드 yield (다음 스레드로 전환)
+///
+/// 다시 실행될 때 대기 중인 시그널을 처리합니다 (커널 스레드의 시그널 전달 지점).
 pub fn yield_now() {
     scheduler::schedule();
+    signal::deliver_pending();
 }
 
-/// 스레드 종료
-pub fn exit() -> ! {
-    {
-        let idx = percpu::current().current_thread_idx.load(Ordering::Acquire);
-        let mut threads = THREADS.lock();
+/// 합류 에러
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinError {
+    /// 해당 TID의 스레드 없음 (이미 합류/해제된 경우 포함)
+    NoSuchThread,
+    /// 분리되었거나 다른 스레드가 이미 기다리는 중, 또는 idle 스레드
+    NotJoinable,
+    /// 자기 자신을 기다림
+    Deadlock,
+    /// 기다리는 중 시그널 도착 (대상은 다시 합류 가능)
+    Interrupted,
+}
+
+/// 스레드가 종료될 때까지 기다린 뒤 TCB를 해제하고 종료 상태 반환
+///
+/// 종료 표시는 futex로 기다리고, 종료된 스레드가 CPU에서 완전히 내려갈 때까지
+/// (스택을 더 쓰지 않을 때까지) 양보한 뒤 해제합니다.
+pub fn join(tid: Tid) -> Result<i32, JoinError> {
+    if current_tid() == Some(tid) {
+        return Err(JoinError::Deadlock);
+    }
+    if is_idle_thread(tid) {
+        return Err(JoinError::NotJoinable);
+    }
+    let addr = with_thread(tid, |t| {
+        if t.join != JoinState::Joinable {
+            return Err(JoinError::NotJoinable);
+        }
+        t.join = JoinState::Joining;
+        Ok(t.exited_word())
+    })
+    .ok_or(JoinError::NoSuchThread)??;
+
+    // Safety: 합류권(Joining)을 가진 동안 다른 누구도 이 TCB를 해제하지 않음
+    let exited = unsafe { &*(addr as *const AtomicU32) };
+    while exited.load(Ordering::Acquire) == 0 {
+        if futex::wait(addr, 0, None) == Err(futex::FutexError::Interrupted) {
+            with_thread(tid, |t| t.join = JoinState::Joinable);
+            return Err(JoinError::Interrupted);
+        }
+    }
 
-        if idx != u32::MAX {
-            if let Some(thread) = threads.get_mut(idx as usize) {
-                thread.state = ThreadState::Terminated;
-                kprintln!("[proc] Thread {} terminated", thread.tid);
+    loop {
+        {
+            let mut threads = THREADS.lock();
+            let pos = threads.iter().position(|t| t.get().tid == tid).ok_or(JoinError::NoSuchThread)?;
+            if let Some(status) = reaper::release(&mut threads, pos, JoinState::Joining) {
+                return Ok(status);
             }
         }
+        // 시그널로 합류자가 끝나면 대상이 남으므로 yield_now 대신 전달 없이 양보
+        scheduler::schedule();
+    }
+}
+
+/// 스레드 분리: 종료되면 합류 없이 `kreaperd`가 해제
+///
+/// 이미 종료되었으면 바로 해제합니다.
+pub fn detach(tid: Tid) -> Result<(), JoinError> {
+    with_thread(tid, |t| {
+        if t.join != JoinState::Joinable {
+            return Err(JoinError::NotJoinable);
+        }
+        t.join = JoinState::Detached;
+        Ok(())
+    })
+    .ok_or(JoinError::NoSuchThread)??;
+    reaper::reap_detached();
+    Ok(())
+}
+
+/// 스레드 종료 (종료 상태 0)
+pub fn exit() -> ! {
+    exit_with(0)
+}
+
+/// 종료 상태를 남기고 스레드 종료
+pub fn exit_with(status: i32) -> ! {
+    // fd를 먼저 닫아 파이프 상대 끝이 EOF를 보고, 합류자는 닫힌 뒤의 상태를 봄
+    let files = current_tid().and_then(|tid| with_thread(tid, |t| t.files.take())).flatten();
+    drop(files);
+
+    // 합류 대기자를 먼저 깨움 (Terminated로 바꾼 뒤에는 다시 실행되지 않을 수 있음)
+    let exited = current_tid().and_then(|tid| with_thread(tid, |t| t.mark_exited(status)).flatten());
+    if let Some(addr) = exited {
+        futex::wake(addr, usize::MAX);
+    }
+
+    {
+        let q = runqueue::queue(percpu::get_cpu_id()).lock();
+        if let Some(thread) = q.current() {
+            // Safety: 자기 런큐 락을 잡고 있음
+            let t = unsafe { thread.get_mut() };
+            t.state = ThreadState::Terminated;
+            kprintln!("[proc] Thread {} terminated (status {})", t.tid, t.exit_status);
+        }
     }
+    // CPU에서 내려가면 스택 해제
+    reaper::wake();
 
     // 다른 스레드로 전환
     scheduler::schedule();
@@ -0,0 +1,177 @@
+//! 커널 스레드 park/unpark
+//!
+//! 서비스 스레드(klogd, kreaperd 등)가 폴링하지 않고 일이 생길 때까지 잠들게 합니다.
+//! 스레드마다 unpark 토큰 하나를 두고, `park()`는 토큰이 있으면 소비하고 바로
+//! 돌아오며 없으면 Blocked로 잠듭니다. `unpark(tid)`는 토큰을 올리고 잠든 스레드를
+//! Ready로 바꿉니다.
+//!
+//! 토큰 확인과 Blocked 전환, 토큰 설정과 Ready 전환이 모두 그 스레드의 런큐 락 안에서
+//! 일어나므로 `park()` 직전에 온 `unpark()`를 놓치지 않습니다. 같은 이유로 여러 번의
+//! `unpark()`는 토큰 하나로 합쳐집니다.
+//!
+//! `park()`는 시그널이나 다른 이유로 토큰 없이 돌아올 수 있으므로, 호출자는 깨어난 뒤
+//! 기다리던 조건을 다시 확인해야 합니다.
+//!
+//! `WaitList`는 여러 객체를 한꺼번에 기다리는 스레드(`poll`)를 위해 객체 쪽에 두는
+//! 대기자 목록입니다. 상태가 바뀌면 `wake_all()`이 등록된 스레드를 모두 `unpark()`합니다.
+
+use alloc::vec::Vec;
+use core::sync::atomic::Ordering;
+
+use crate::sync::Spinlock;
+
+use super::runqueue::{self, CpuQueue};
+use super::{percpu, ThreadState, Tid};
+
+/// 스레드의 park 상태 (런큐 락으로 보호)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParkState {
+    /// 토큰 없음
+    Empty,
+    /// `park()`로 잠든 중
+    Parked,
+    /// `unpark()` 토큰 대기 중 (다음 `park()`가 바로 돌아옴)
+    Notified,
+}
+
+/// 토큰이 올 때까지 현재 스레드를 잠재움
+///
+/// # Returns
+/// 토큰을 소비했으면 true, 시그널로 돌아왔으면 false
+pub fn park() -> bool {
+    park_until(None)
+}
+
+/// `timeout_us` 동안만 `park()`
+///
+/// 기한은 스케줄링 때(매 타이머 틱, 틱을 끈 idle CPU는 기한에 맞춘 일회성 타이머) 확인하므로
+/// 최대 한 틱 늦게 깨어납니다.
+///
+/// # Returns
+/// 토큰을 소비했으면 true, 타임아웃/시그널이면 false
+pub fn park_timeout(timeout_us: u64) -> bool {
+    park_until(Some(crate::time::now_us().saturating_add(timeout_us)))
+}
+
+fn park_until(deadline: Option<u64>) -> bool {
+    loop {
+        {
+            let q = runqueue::queue(percpu::get_cpu_id()).lock();
+            let Some(thread) = q.current() else {
+                return false;
+            };
+            // Safety: 자기 런큐 락을 잡고 있음
+            let t = unsafe { thread.get_mut() };
+            if t.park == ParkState::Notified {
+                t.park = ParkState::Empty;
+                t.park_deadline = None;
+                return true;
+            }
+            let expired = deadline.is_some_and(|d| crate::time::now_us() >= d);
+            if expired || t.signals.pending != 0 {
+                t.park = ParkState::Empty;
+                t.park_deadline = None;
+                return false;
+            }
+            t.park = ParkState::Parked;
+            t.park_deadline = deadline;
+            if t.state == ThreadState::Running {
+                t.state = ThreadState::Blocked;
+            }
+        }
+        // 실행할 다른 스레드가 없으면 그대로 돌아오므로 다시 확인
+        super::scheduler::schedule();
+    }
+}
+
+/// 스레드의 토큰을 올리고, `park()`로 잠들어 있으면 깨움
+///
+/// # Returns
+/// 스레드가 있으면 true
+pub fn unpark(tid: Tid) -> bool {
+    let woken = super::with_thread(tid, |t| {
+        let parked = t.park == ParkState::Parked;
+        t.park = ParkState::Notified;
+        t.park_deadline = None;
+        if parked && t.state == ThreadState::Blocked {
+            t.state = ThreadState::Ready;
+            super::schedstat::on_wakeup(t);
+            crate::trace_event!(sched_wakeup, tid = tid);
+            return Some(t.cpu.load(Ordering::Relaxed));
+        }
+        None
+    });
+    if let Some(Some(cpu)) = woken {
+        super::kick_idle_cpu(cpu);
+    }
+    woken.is_some()
+}
+
+/// park 대기자 목록
+///
+/// 등록은 한 번 깨우면 사라집니다. 계속 기다리려면 조건을 확인하기 전에 다시 등록해야
+/// `register()`와 확인 사이에 온 `wake_all()`을 토큰으로 받습니다. 이미 돌아간 대기자에게
+/// 남은 토큰은 다음 `park()`를 한 번 일찍 돌아오게 할 뿐입니다.
+/// `unpark()`와 마찬가지로 인터럽트 핸들러에서는 `wake_all()`을 부를 수 없습니다.
+pub struct WaitList {
+    tids: Spinlock<Vec<Tid>>,
+}
+
+impl WaitList {
+    pub const fn new() -> Self {
+        Self {
+            tids: Spinlock::new(Vec::new()),
+        }
+    }
+
+    /// 대기자 등록 (이미 있으면 무시)
+    pub fn register(&self, tid: Tid) {
+        let mut tids = self.tids.lock();
+        if !tids.contains(&tid) {
+            tids.push(tid);
+        }
+    }
+
+    /// 등록된 대기자를 모두 깨우고 목록 비우기
+    pub fn wake_all(&self) {
+        let tids = core::mem::take(&mut *self.tids.lock());
+        for tid in tids {
+            unpark(tid);
+        }
+    }
+}
+
+/// `cpu` 런큐에서 `park_timeout()`으로 잠든 스레드의 가장 이른 기한 (틱 없는 idle)
+pub(crate) fn next_deadline(cpu: u32) -> Option<u64> {
+    let q = runqueue::queue(cpu).lock();
+    q.threads
+        .iter()
+        .filter_map(|thread| {
+            // Safety: 이 런큐 락을 잡고 있음
+            let t = unsafe { thread.get_mut() };
+            if t.park == ParkState::Parked && t.state == ThreadState::Blocked {
+                t.park_deadline
+            } else {
+                None
+            }
+        })
+        .min()
+}
+
+/// 기한이 지난 `park_timeout()` 스레드를 Ready로 (스케줄러가 자기 런큐 락을 잡고 호출)
+///
+/// 인터럽트 경로에서 불리므로 힙을 쓰지 않습니다.
+pub(super) fn wake_expired(q: &CpuQueue, now_us: u64) {
+    for thread in q.threads.iter() {
+        // Safety: 호출자가 이 런큐 락을 잡고 있음
+        let t = unsafe { thread.get_mut() };
+        if t.park != ParkState::Parked || t.state != ThreadState::Blocked {
+            continue;
+        }
+        if t.park_deadline.is_some_and(|d| now_us >= d) {
+            t.state = ThreadState::Ready;
+            super::schedstat::on_wakeup(t);
+            crate::trace_event!(sched_wakeup, tid = t.tid);
+        }
+    }
+}
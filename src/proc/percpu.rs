@@ -3,43 +3,132 @@
 //! 각 CPU/hart별로 독립적인 데이터를 관리합니다.
 //! SMP 환경에서 CPU별 스케줄링, 인터럽트 처리에 사용됩니다.
 
-use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 
 /// 최대 지원 CPU 수
 pub const MAX_CPUS: usize = 8;
 
+/// 스레드 없음 (`current_tid`, `idle_tid`)
+pub const NO_THREAD: u64 = u64::MAX;
+
+/// CPU 집합 (비트 n = CPU n, `MAX_CPUS`개까지)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuMask(u64);
+
+impl CpuMask {
+    /// 모든 CPU
+    pub const ALL: Self = Self((1 << MAX_CPUS) - 1);
+    pub const EMPTY: Self = Self(0);
+
+    /// 비트마스크에서 생성 (`MAX_CPUS` 이상의 비트는 버림)
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits & Self::ALL.0)
+    }
+
+    /// CPU 하나
+    pub const fn single(cpu: u32) -> Self {
+        if (cpu as usize) < MAX_CPUS { Self(1 << cpu) } else { Self::EMPTY }
+    }
+
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+
+    pub const fn contains(self, cpu: u32) -> bool {
+        (cpu as usize) < MAX_CPUS && self.0 & (1 << cpu) != 0
+    }
+
+    pub const fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// 집합의 CPU 번호 (오름차순)
+    pub fn iter(self) -> impl Iterator<Item = u32> {
+        (0..MAX_CPUS as u32).filter(move |&cpu| self.contains(cpu))
+    }
+}
+
 /// Per-CPU 데이터
 #[repr(C)]
 pub struct PerCpuData {
     /// CPU ID (aarch64: MPIDR 기반, riscv64: hartid)
     pub cpu_id: AtomicU32,
-    /// 현재 실행 중인 스레드 인덱스 (THREADS 배열 내 인덱스)
-    pub current_thread_idx: AtomicU32, // u32::MAX = 없음
-    /// 이 CPU의 idle 스레드 인덱스 (THREADS 배열 내 인덱스)
-    pub idle_thread_idx: AtomicU32,
-    /// 이 CPU가 온라인(부팅 완료)인지 여부
+    /// 현재 실행 중인 스레드 TID (`NO_THREAD` = 없음, 런큐 락 안에서 갱신)
+    pub current_tid: AtomicU64,
+    /// 이 CPU의 idle 스레드 TID
+    pub idle_tid: AtomicU64,
+    /// 현재 스레드의 모듈 TLS 블록 목록 (`tls::ThreadTls` 주소, 0 = 없음, 런큐 락 안에서 갱신)
+    pub tls: AtomicUsize,
+    /// 이 CPU가 온라인(부팅 완료, 오프라인으로 내리지 않음)인지 여부
     pub online: AtomicBool,
-    /// 타이머 틱 카운터
+    /// 타이머 틱 카운터 (틱 없는 idle로 건너뛴 틱 포함)
     pub tick_count: AtomicU64,
+    /// 틱 없는 idle로 건너뛴 틱 수 (`time::nohz`)
+    pub ticks_skipped: AtomicU64,
+    /// 다음 틱 타이머 만료 시각 (부팅 후 마이크로초, `timers` 명령용)
+    pub next_tick_us: AtomicU64,
+    /// 컨텍스트 스위칭 횟수
+    pub switch_count: AtomicU64,
+    /// 현재 스레드가 CPU에 올라간 시각과 그때의 틱 카운터 (`acct`)
+    pub run_start_us: AtomicU64,
+    pub run_start_tick: AtomicU64,
+    /// idle 스레드 / 그 밖의 스레드가 실행된 누적 시간 (마이크로초)
+    pub idle_us: AtomicU64,
+    pub busy_us: AtomicU64,
+    /// 다음 주기적 부하 분산 틱
+    pub next_balance_tick: AtomicU64,
+    /// 현재 스레드의 타임 슬라이스가 시작된 틱 (`scheduler::tick`)
+    pub slice_start_tick: AtomicU64,
+    /// 부하 분산으로 이 CPU로 가져온 / 다른 CPU가 가져간 스레드 수
+    pub migrations_in: AtomicU64,
+    pub migrations_out: AtomicU64,
 }
 
 impl PerCpuData {
     pub const fn new() -> Self {
         Self {
             cpu_id: AtomicU32::new(0),
-            current_thread_idx: AtomicU32::new(u32::MAX),
-            idle_thread_idx: AtomicU32::new(u32::MAX),
+            current_tid: AtomicU64::new(NO_THREAD),
+            idle_tid: AtomicU64::new(NO_THREAD),
+            tls: AtomicUsize::new(0),
             online: AtomicBool::new(false),
             tick_count: AtomicU64::new(0),
+            ticks_skipped: AtomicU64::new(0),
+            next_tick_us: AtomicU64::new(0),
+            switch_count: AtomicU64::new(0),
+            run_start_us: AtomicU64::new(0),
+            run_start_tick: AtomicU64::new(0),
+            idle_us: AtomicU64::new(0),
+            busy_us: AtomicU64::new(0),
+            next_balance_tick: AtomicU64::new(0),
+            slice_start_tick: AtomicU64::new(0),
+            migrations_in: AtomicU64::new(0),
+            migrations_out: AtomicU64::new(0),
         }
     }
 
     pub fn init(&self, cpu_id: u32) {
         self.cpu_id.store(cpu_id, Ordering::Relaxed);
-        self.current_thread_idx.store(u32::MAX, Ordering::Relaxed);
-        self.idle_thread_idx.store(u32::MAX, Ordering::Relaxed);
+        self.current_tid.store(NO_THREAD, Ordering::Relaxed);
+        self.idle_tid.store(NO_THREAD, Ordering::Relaxed);
+        self.tls.store(0, Ordering::Relaxed);
         self.online.store(false, Ordering::Relaxed);
         self.tick_count.store(0, Ordering::Relaxed);
+        self.ticks_skipped.store(0, Ordering::Relaxed);
+        self.next_tick_us.store(0, Ordering::Relaxed);
+        self.switch_count.store(0, Ordering::Relaxed);
+        self.run_start_us.store(0, Ordering::Relaxed);
+        self.run_start_tick.store(0, Ordering::Relaxed);
+        self.idle_us.store(0, Ordering::Relaxed);
+        self.busy_us.store(0, Ordering::Relaxed);
+        self.next_balance_tick.store(0, Ordering::Relaxed);
+        self.slice_start_tick.store(0, Ordering::Relaxed);
+        self.migrations_in.store(0, Ordering::Relaxed);
+        self.migrations_out.store(0, Ordering::Relaxed);
     }
 
     pub fn set_online(&self) {
@@ -49,6 +138,12 @@ impl PerCpuData {
     pub fn is_online(&self) -> bool {
         self.online.load(Ordering::Acquire)
     }
+
+    /// idle 스레드를 실행 중인지
+    pub fn is_idle(&self) -> bool {
+        let current = self.current_tid.load(Ordering::Relaxed);
+        current != NO_THREAD && current == self.idle_tid.load(Ordering::Relaxed)
+    }
 }
 
 /// 전역 Per-CPU 데이터 배열
@@ -86,6 +181,23 @@ pub fn init_secondary(cpu_id: u32) {
     NUM_CPUS_ONLINE.fetch_add(1, Ordering::AcqRel);
 }
 
+/// 부팅을 마친 CPU를 다시 온라인으로 표시 (`hotplug::cpu_up`)
+pub fn mark_online(cpu_id: u32) {
+    if (cpu_id as usize) < MAX_CPUS && !PER_CPU[cpu_id as usize].online.swap(true, Ordering::AcqRel) {
+        NUM_CPUS_ONLINE.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+/// CPU를 오프라인으로 표시 (`hotplug::cpu_down`)
+///
+/// 새 스레드와 부하 분산, CPU별 작업이 이 CPU를 고르지 않게 되고, 이 CPU의 스케줄러는
+/// idle 스레드만 실행합니다.
+pub fn mark_offline(cpu_id: u32) {
+    if (cpu_id as usize) < MAX_CPUS && PER_CPU[cpu_id as usize].online.swap(false, Ordering::AcqRel) {
+        NUM_CPUS_ONLINE.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
 /// 현재 CPU의 Per-CPU 데이터 참조
 pub fn current() -> &'static PerCpuData {
     let cpu_id = get_cpu_id() as usize;
@@ -110,6 +222,17 @@ pub fn online_count() -> u32 {
     NUM_CPUS_ONLINE.load(Ordering::Acquire)
 }
 
+/// 온라인 CPU 집합
+pub fn online_mask() -> CpuMask {
+    let mut bits = 0;
+    for (cpu, pc) in PER_CPU.iter().enumerate() {
+        if pc.is_online() {
+            bits |= 1 << cpu;
+        }
+    }
+    CpuMask::from_bits(bits)
+}
+
 /// 전체 CPU 수 반환
 pub fn total_count() -> u32 {
     TOTAL_CPU_COUNT.load(Ordering::Relaxed)
@@ -134,11 +257,8 @@ pub fn get_cpu_id() -> u32 {
 /// 현재 CPU ID 가져오기
 #[cfg(target_arch = "riscv64")]
 pub fn get_cpu_id() -> u32 {
-    let hartid: u64;
-    unsafe {
-        core::arch::asm!("csrr {}, mhartid", out(reg) hartid);
-    }
-    hartid as u32
+    // M-mode: mhartid, S-mode: 부팅 때 sscratch에 보관한 hart ID
+    crate::arch::csr::hartid()
 }
 
 /// Per-CPU 스택 관리
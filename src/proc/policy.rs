@@ -0,0 +1,197 @@
+//! 스케줄링 정책 코어
+//!
+//! "다음에 무엇을 실행할지"만 결정하는 순수 Rust 코드입니다. 어셈블리, 전역 락,
+//! per-CPU 레지스터에 의존하지 않고 `RunQueue` 트레이트로 스레드 표를 읽기만 하므로
+//! 가짜 스레드 표로 그대로 검증할 수 있습니다 (`selftest sched.policy`).
+//!
+//! 실제 상태 변경과 컨텍스트 스위칭은 `scheduler`가 결정 결과를 받아
+//! `ArchContext`로 수행합니다.
+//!
+//! 정책은 CPU 하나의 런큐만 봅니다. CPU 사이의 부하 분산(`should_pull`,
+//! `pick_movable`)은 한가한 CPU가 바쁜 CPU 런큐의 Ready 스레드를 가져오는 pull
+//! 방식입니다. 친화도가 바뀌어 지금 런큐의 CPU에서 실행할 수 없게 된 스레드
+//! (`pick_stranded`)도 허용된 CPU가 같은 방식으로 가져갑니다.
+
+use super::percpu::CpuMask;
+use super::ThreadState;
+
+/// 정책이 보는 스레드 표
+pub trait RunQueue {
+    /// 스레드 수
+    fn len(&self) -> usize;
+    /// `idx` 스레드 상태
+    fn state(&self, idx: usize) -> ThreadState;
+    /// `idx` 스레드가 실행될 수 있는 CPU 집합
+    fn affinity(&self, idx: usize) -> CpuMask;
+    /// `idx` 스레드가 아직 CPU에서 내려가는 중인지 (다른 CPU로 옮길 수 없음)
+    fn on_cpu(&self, _idx: usize) -> bool {
+        false
+    }
+}
+
+/// 스케줄링 결정
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// 현재 스레드 계속 실행
+    Stay,
+    /// `idx` 스레드로 전환
+    Switch(usize),
+}
+
+/// 스케줄링 정책
+pub trait SchedPolicy {
+    /// 정책 이름
+    fn name(&self) -> &'static str;
+
+    /// `cpu`에서 `current` 다음에 실행할 스레드 선택
+    ///
+    /// `current`는 Running이었다면 이미 Ready로 바뀐 상태로 전달됩니다.
+    /// `idle`은 이 CPU의 idle 스레드 인덱스입니다.
+    fn pick_next(&self, rq: &dyn RunQueue, cpu: u32, current: usize, idle: usize) -> Decision;
+}
+
+/// 라운드-로빈 정책
+///
+/// 현재 스레드 다음 인덱스부터 한 바퀴 돌며 이 CPU에서 실행 가능한 첫 Ready
+/// 스레드를 고릅니다. 없으면 현재 스레드를 계속 실행하고, 현재 스레드가
+/// 종료되었거나 친화도가 바뀌어 이 CPU에서 실행할 수 없으면 idle 스레드로 전환합니다.
+pub struct RoundRobin;
+
+impl SchedPolicy for RoundRobin {
+    fn name(&self) -> &'static str {
+        "round-robin"
+    }
+
+    fn pick_next(&self, rq: &dyn RunQueue, cpu: u32, current: usize, idle: usize) -> Decision {
+        let len = rq.len();
+
+        for offset in 1..=len {
+            let idx = (current + offset) % len;
+            if rq.state(idx) != ThreadState::Ready {
+                continue;
+            }
+            // CPU 친화도 확인: 이 CPU가 집합에 없는 스레드는 건너뜀
+            if !rq.affinity(idx).contains(cpu) {
+                continue;
+            }
+            return if idx == current { Decision::Stay } else { Decision::Switch(idx) };
+        }
+
+        // 실행할 스레드가 없음: 종료되었거나 이 CPU에서 실행할 수 없는 스레드만 idle로 보냄
+        if current >= len || idle >= len || idle == current {
+            return Decision::Stay;
+        }
+        let evict = match rq.state(current) {
+            ThreadState::Terminated => true,
+            ThreadState::Ready => !rq.affinity(current).contains(cpu),
+            _ => false,
+        };
+        if evict { Decision::Switch(idle) } else { Decision::Stay }
+    }
+}
+
+/// 런큐 부하 (Ready/Running 스레드 수, idle 스레드 제외)
+pub fn queue_load(rq: &dyn RunQueue, idle: usize) -> usize {
+    (0..rq.len())
+        .filter(|&idx| idx != idle && matches!(rq.state(idx), ThreadState::Ready | ThreadState::Running))
+        .count()
+}
+
+/// 부하가 `mine`인 CPU가 부하가 `theirs`인 CPU에서 스레드를 가져올지
+///
+/// 실행할 스레드가 없으면 기다리는 스레드를 바로 가져오고, 그렇지 않으면 차이가 2 이상일
+/// 때만 옮깁니다 (하나를 옮겨도 차이가 뒤집히기만 하는 경우 제외).
+pub fn should_pull(mine: usize, theirs: usize) -> bool {
+    theirs > 0 && (mine == 0 || theirs >= mine + 2)
+}
+
+/// `to` CPU로 옮길 수 있는 스레드인지 (Ready, `to`에서 실행 가능, CPU에서 내려가 있음)
+fn movable(rq: &dyn RunQueue, idle: usize, idx: usize, to: u32) -> bool {
+    idx != idle && rq.state(idx) == ThreadState::Ready && rq.affinity(idx).contains(to) && !rq.on_cpu(idx)
+}
+
+/// 부하 분산으로 `to` CPU로 옮길 스레드 선택 (조건을 만족하는 첫 번째)
+pub fn pick_movable(rq: &dyn RunQueue, idle: usize, to: u32) -> Option<usize> {
+    (0..rq.len()).find(|&idx| movable(rq, idle, idx, to))
+}
+
+/// `from` CPU 런큐에 있지만 친화도가 바뀌어 `from`에서 실행할 수 없는 스레드 중
+/// `to`로 옮길 수 있는 것 (부하와 관계없이 옮김)
+pub fn pick_stranded(rq: &dyn RunQueue, idle: usize, from: u32, to: u32) -> Option<usize> {
+    (0..rq.len()).find(|&idx| !rq.affinity(idx).contains(from) && movable(rq, idle, idx, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ThreadState::*;
+
+    /// 가짜 런큐: (상태, 친화도)
+    struct FakeQueue<'a>(&'a [(ThreadState, CpuMask)]);
+
+    impl RunQueue for FakeQueue<'_> {
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+        fn state(&self, idx: usize) -> ThreadState {
+            self.0[idx].0
+        }
+        fn affinity(&self, idx: usize) -> CpuMask {
+            self.0[idx].1
+        }
+    }
+
+    const ANY: CpuMask = CpuMask::ALL;
+
+    #[test]
+    fn test_round_robin_order() {
+        // 0: idle, 1..=3: Ready. 현재 스레드 다음 인덱스부터 차례로 고르고 끝에서 되돌아감
+        let rq = FakeQueue(&[(Ready, ANY), (Ready, ANY), (Ready, ANY), (Ready, ANY)]);
+        assert_eq!(RoundRobin.pick_next(&rq, 0, 1, 0), Decision::Switch(2));
+        assert_eq!(RoundRobin.pick_next(&rq, 0, 2, 0), Decision::Switch(3));
+        assert_eq!(RoundRobin.pick_next(&rq, 0, 3, 0), Decision::Switch(0));
+
+        // Blocked/Terminated와 이 CPU에서 실행할 수 없는 스레드는 건너뜀
+        let rq = FakeQueue(&[
+            (Ready, ANY),
+            (Ready, ANY),
+            (Blocked, ANY),
+            (Terminated, ANY),
+            (Ready, CpuMask::single(1)),
+            (Ready, ANY),
+        ]);
+        assert_eq!(RoundRobin.pick_next(&rq, 0, 1, 0), Decision::Switch(5));
+    }
+
+    #[test]
+    fn test_round_robin_requeue_current() {
+        // Ready로 되돌아온 현재 스레드만 실행 가능하면 계속 실행
+        let rq = FakeQueue(&[(Blocked, ANY), (Ready, ANY), (Blocked, ANY)]);
+        assert_eq!(RoundRobin.pick_next(&rq, 0, 1, 0), Decision::Stay);
+
+        // 다른 Ready 스레드가 있으면 현재 스레드는 한 바퀴 뒤로 밀림
+        let rq = FakeQueue(&[(Blocked, ANY), (Ready, ANY), (Ready, ANY)]);
+        assert_eq!(RoundRobin.pick_next(&rq, 0, 1, 0), Decision::Switch(2));
+        assert_eq!(RoundRobin.pick_next(&rq, 0, 2, 0), Decision::Switch(1));
+
+        // 친화도가 바뀌어 이 CPU에서 실행할 수 없는 현재 스레드는 idle로 전환
+        let rq = FakeQueue(&[(Blocked, ANY), (Ready, CpuMask::single(1))]);
+        assert_eq!(RoundRobin.pick_next(&rq, 0, 1, 0), Decision::Switch(0));
+
+        // 종료된 현재 스레드도 idle로 전환
+        let rq = FakeQueue(&[(Blocked, ANY), (Terminated, ANY)]);
+        assert_eq!(RoundRobin.pick_next(&rq, 0, 1, 0), Decision::Switch(0));
+    }
+
+    #[test]
+    fn test_round_robin_empty_queue() {
+        let rq = FakeQueue(&[]);
+        assert_eq!(RoundRobin.pick_next(&rq, 0, 0, 0), Decision::Stay);
+        assert_eq!(queue_load(&rq, 0), 0);
+        assert_eq!(pick_movable(&rq, 0, 1), None);
+
+        // Ready 스레드가 하나도 없고 현재 스레드가 대기 중이면 그대로 둠
+        let rq = FakeQueue(&[(Blocked, ANY), (Blocked, ANY)]);
+        assert_eq!(RoundRobin.pick_next(&rq, 0, 1, 0), Decision::Stay);
+    }
+}
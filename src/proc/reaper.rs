@@ -0,0 +1,162 @@
+//! 종료된 스레드 회수 (`kreaperd`)
+//!
+//! 종료된 스레드는 합류할 때까지 종료 상태를 TCB에 남기지만, 스택은 CPU에서 내려간
+//! 뒤로 쓰이지 않습니다. `kreaperd` 커널 스레드는 스레드가 종료될 때 `wake()`로 깨어나
+//!
+//! 1. CPU에서 내려간 종료 스레드의 커널 스택과 소유한 VMA를 해제하고 (합류 대기 중인
+//!    스레드 포함)
+//! 2. 분리된(`Detached`) 종료 스레드를 레지스트리와 런큐에서 빼고 TCB를 해제합니다.
+//!
+//! 아직 CPU에서 내려가는 중인 스레드가 있으면 한 틱 뒤에 다시 보고, 없으면
+//! `park()`로 잠듭니다. `join()`은 기다리던 스레드를 직접 해제하며, 그 수도 여기서
+//! 셉니다.
+
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+use super::runqueue::{self, ThreadRef};
+use super::{percpu, JoinState, ThreadState, Tid, THREADS};
+
+/// CPU에서 내려가는 중인 종료 스레드를 다시 볼 때까지 (마이크로초, 한 틱)
+const RETRY_US: u64 = 10_000;
+
+/// kreaperd 스레드 시작 여부
+static STARTED: AtomicBool = AtomicBool::new(false);
+/// kreaperd TID (`NO_THREAD` = 시작 전)
+static REAPER_TID: AtomicU64 = AtomicU64::new(percpu::NO_THREAD);
+/// 해제한 TCB 수 (합류 + 분리)
+static THREADS_REAPED: AtomicU64 = AtomicU64::new(0);
+/// 해제한 커널 스택 수
+static STACKS_FREED: AtomicU64 = AtomicU64::new(0);
+/// 해제한 커널 스택 바이트
+static STACK_BYTES_FREED: AtomicUsize = AtomicUsize::new(0);
+
+/// 회수 통계
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReapStats {
+    /// 해제한 TCB 수
+    pub threads_reaped: u64,
+    /// 해제한 커널 스택 수
+    pub stacks_freed: u64,
+    /// 해제한 커널 스택 바이트
+    pub stack_bytes_freed: usize,
+}
+
+pub fn stats() -> ReapStats {
+    ReapStats {
+        threads_reaped: THREADS_REAPED.load(Ordering::Relaxed),
+        stacks_freed: STACKS_FREED.load(Ordering::Relaxed),
+        stack_bytes_freed: STACK_BYTES_FREED.load(Ordering::Relaxed),
+    }
+}
+
+/// 해제한 스택 집계 (처음 해제할 때 소유한 VMA도 해제)
+fn count_stack(tid: Tid, stack: &Vec<u8>) {
+    if stack.capacity() > 0 {
+        STACKS_FREED.fetch_add(1, Ordering::Relaxed);
+        STACK_BYTES_FREED.fetch_add(stack.capacity(), Ordering::Relaxed);
+        // 스택을 처음 해제할 때 스레드가 남긴 VMA도 해제 (`kill()`로 종료된 스레드 포함)
+        crate::mm::vma::release_owner(tid);
+    }
+}
+
+/// kreaperd 스레드 시작 (프로세스 서브시스템 초기화 후 한 번)
+pub fn start() {
+    if !STARTED.swap(true, Ordering::AcqRel) {
+        let tid = super::spawn("kreaperd", reaper_main);
+        REAPER_TID.store(tid, Ordering::Release);
+    }
+}
+
+/// kreaperd 깨우기 (스레드가 종료될 때)
+pub fn wake() {
+    let tid = REAPER_TID.load(Ordering::Acquire);
+    if tid != percpu::NO_THREAD {
+        super::unpark(tid);
+    }
+}
+
+/// 종료되어 CPU에서 내려간 스레드를 런큐와 레지스트리에서 빼고 해제
+///
+/// `THREADS` 락을 잡은 채 호출합니다. 합류 상태가 `owner`인 스레드만 해제하며,
+/// 해제한 스레드의 종료 상태를 돌려줍니다.
+pub(super) fn release(threads: &mut Vec<ThreadRef>, pos: usize, owner: JoinState) -> Option<i32> {
+    let thread = threads[pos];
+    let status = {
+        let mut q = runqueue::lock_thread(thread);
+        // Safety: 스레드가 속한 런큐 락을 잡고 있음
+        let t = unsafe { thread.get_mut() };
+        if t.join != owner || t.state != ThreadState::Terminated || t.on_cpu.load(Ordering::Acquire) {
+            return None;
+        }
+        let idx = q.position(thread)?;
+        q.remove(idx);
+        t.exit_status
+    };
+    threads.remove(pos);
+    count_stack(thread.get().tid, &thread.get().kernel_stack);
+    THREADS_REAPED.fetch_add(1, Ordering::Relaxed);
+    // Safety: 런큐와 레지스트리에서 빠졌고, 다른 참조는 두 락 중 하나를 잡은 동안만 쓰임
+    unsafe { thread.free() };
+    Some(status)
+}
+
+/// 분리된 스레드 중 종료된 것 해제
+pub(super) fn reap_detached() {
+    let mut threads = THREADS.lock();
+    let mut pos = 0;
+    while pos < threads.len() {
+        if release(&mut threads, pos, JoinState::Detached).is_none() {
+            pos += 1;
+        }
+    }
+}
+
+/// CPU에서 내려간 종료 스레드의 커널 스택 해제 (TCB와 종료 상태는 남김)
+///
+/// 아직 CPU에서 내려가는 중인 종료 스레드가 있으면 true
+fn free_stacks() -> bool {
+    let mut pending = false;
+    let threads = THREADS.lock();
+    for &thread in threads.iter() {
+        let (tid, stack) = {
+            let _q = runqueue::lock_thread(thread);
+            // Safety: 스레드가 속한 런큐 락을 잡고 있음
+            let t = unsafe { thread.get_mut() };
+            if t.state != ThreadState::Terminated {
+                continue;
+            }
+            if t.on_cpu.load(Ordering::Acquire) {
+                pending = true;
+                continue;
+            }
+            (t.tid, core::mem::take(&mut t.kernel_stack))
+        };
+        // 런큐 락(인터럽트 차단)을 놓은 뒤 해제
+        count_stack(tid, &stack);
+        drop(stack);
+    }
+    pending
+}
+
+/// 회수 한 번 실행
+///
+/// 아직 CPU에서 내려가는 중이라 회수하지 못한 종료 스레드가 있으면 true
+pub fn reap() -> bool {
+    let pending = free_stacks();
+    reap_detached();
+    pending
+}
+
+/// kreaperd: 스레드가 종료될 때마다 깨어나 회수
+fn reaper_main() -> ! {
+    loop {
+        if reap() {
+            super::park_timeout(RETRY_US);
+        } else {
+            super::park();
+        }
+        // 시그널로 깨어났으면 여기서 처리
+        super::signal::deliver_pending();
+    }
+}
@@ -0,0 +1,201 @@
+//! CPU별 런큐
+//!
+//! 스레드는 정확히 하나의 CPU 런큐에 들어 있고(`Thread::cpu`), 스케줄러는 자기 CPU
+//! 런큐의 락만 잡고 다음 스레드를 고릅니다. 전역 레지스트리(`THREADS`)는 TID 조회,
+//! 목록 출력, 생성에만 쓰이므로 컨텍스트 스위칭이 모든 CPU를 한 락으로 직렬화하지
+//! 않습니다.
+//!
+//! 스레드의 가변 필드(`state`, `context`, `cpu_affinity`, `signals`, `acct`)는 그 스레드가
+//! 속한 런큐의 락으로 보호합니다. 다른 CPU의 스레드를 다룰 때는 `lock_thread()`로 런큐를
+//! 잡습니다. `tid`, `name`은 생성 후 바뀌지 않으므로 락 없이 읽습니다.
+//!
+//! 종료된 스레드는 `join`/`detach` 뒤에 `THREADS`와 런큐 락을 모두 잡고 목록에서 뺀
+//! 다음 해제합니다. 그러므로 `ThreadRef`는 `THREADS` 락이나 스레드가 속한 런큐 락을
+//! 잡고 얻은 동안만 유효합니다.
+//!
+//! 락 순서: `THREADS` → 런큐, 런큐 두 개는 CPU 번호가 작은 쪽부터 (`lock_pair`)
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
+
+use super::percpu::{CpuMask, MAX_CPUS};
+use super::policy::RunQueue;
+use super::{Thread, ThreadState};
+use crate::sync::{IrqSpinlock, IrqSpinlockGuard};
+
+/// 레지스트리에 등록된 스레드에 대한 참조
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ThreadRef(NonNull<Thread>);
+
+// Safety: 가변 접근은 런큐 락으로 직렬화 (`get_mut`)
+unsafe impl Send for ThreadRef {}
+unsafe impl Sync for ThreadRef {}
+
+impl ThreadRef {
+    /// 스레드를 힙에 고정하고 참조 생성 (`free`로 해제)
+    pub fn new(thread: Box<Thread>) -> Self {
+        Self(NonNull::from(Box::leak(thread)))
+    }
+
+    /// 스레드 해제
+    ///
+    /// # Safety
+    /// 레지스트리와 런큐에서 빠져 다른 참조가 남아 있지 않아야 합니다.
+    pub unsafe fn free(self) {
+        drop(unsafe { Box::from_raw(self.0.as_ptr()) });
+    }
+
+    /// 불변 필드(`tid`, `name`)와 원자 필드 접근용
+    pub fn get(&self) -> &'static Thread {
+        unsafe { self.0.as_ref() }
+    }
+
+    /// 가변 접근
+    ///
+    /// # Safety
+    /// 이 스레드가 속한 런큐의 락을 잡고 있어야 합니다.
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn get_mut(&self) -> &mut Thread {
+        unsafe { &mut *self.0.as_ptr() }
+    }
+}
+
+/// 인덱스 없음
+pub const NONE: usize = usize::MAX;
+
+/// CPU 하나의 런큐
+pub struct CpuQueue {
+    /// 이 CPU에 속한 스레드 (상태 무관)
+    ///
+    /// 용량은 항상 전체 스레드 수 이상으로 유지하므로(`reserve_all`) 부하 분산이
+    /// 인터럽트 경로에서 스레드를 옮겨도 재할당하지 않습니다.
+    pub threads: Vec<ThreadRef>,
+    /// 실행 중인 스레드 인덱스 (`NONE` = 아직 스케줄링 시작 전)
+    pub current: usize,
+    /// idle 스레드 인덱스
+    pub idle: usize,
+    /// 직전에 이 CPU에서 내려간 스레드 (스위칭이 끝날 때까지 `on_cpu` 유지)
+    prev: Option<ThreadRef>,
+}
+
+impl CpuQueue {
+    const fn new() -> Self {
+        Self {
+            threads: Vec::new(),
+            current: NONE,
+            idle: NONE,
+            prev: None,
+        }
+    }
+
+    /// 실행 중인 스레드
+    pub fn current(&self) -> Option<ThreadRef> {
+        self.threads.get(self.current).copied()
+    }
+
+    /// 스레드 인덱스
+    pub fn position(&self, thread: ThreadRef) -> Option<usize> {
+        self.threads.iter().position(|&t| t == thread)
+    }
+
+    /// 스레드 추가 (용량은 미리 확보되어 있어야 함)
+    pub fn push(&mut self, thread: ThreadRef) -> usize {
+        debug_assert!(self.threads.len() < self.threads.capacity());
+        self.threads.push(thread);
+        self.threads.len() - 1
+    }
+
+    /// 스레드 제거 (부하 분산), 뒤쪽 인덱스를 당김
+    pub fn remove(&mut self, idx: usize) -> ThreadRef {
+        let thread = self.threads.remove(idx);
+        for slot in [&mut self.current, &mut self.idle] {
+            if *slot != NONE && *slot > idx {
+                *slot -= 1;
+            }
+        }
+        thread
+    }
+
+    /// 직전 스위칭 마무리: 내려간 스레드가 이제 다른 CPU로 옮겨질 수 있음
+    ///
+    /// 스위칭에서 돌아온 스레드가 호출합니다. 처음 실행되는 스레드는 `schedule()`을
+    /// 거치지 않고 진입점에서 시작하므로, 그 CPU의 다음 스케줄링에서 마무리됩니다.
+    pub fn finish_switch(&mut self) {
+        if let Some(prev) = self.prev.take() {
+            prev.get().on_cpu.store(false, Ordering::Release);
+        }
+    }
+
+    /// `next`로 스위칭 준비 (`prev`는 스위칭이 끝날 때까지 `on_cpu`로 남음)
+    pub fn begin_switch(&mut self, prev: ThreadRef, next: usize) {
+        self.threads[next].get().on_cpu.store(true, Ordering::Release);
+        self.prev = Some(prev);
+        self.current = next;
+    }
+}
+
+/// 정책이 보는 스레드 표 (락을 잡은 런큐)
+impl RunQueue for CpuQueue {
+    fn len(&self) -> usize {
+        self.threads.len()
+    }
+
+    fn state(&self, idx: usize) -> ThreadState {
+        // Safety: &self는 런큐 락을 잡은 상태에서만 얻음
+        unsafe { self.threads[idx].get_mut().state }
+    }
+
+    fn affinity(&self, idx: usize) -> CpuMask {
+        // Safety: &self는 런큐 락을 잡은 상태에서만 얻음
+        unsafe { self.threads[idx].get_mut().cpu_affinity }
+    }
+
+    fn on_cpu(&self, idx: usize) -> bool {
+        self.threads[idx].get().on_cpu.load(Ordering::Acquire)
+    }
+}
+
+/// CPU별 런큐
+pub static QUEUES: [IrqSpinlock<CpuQueue>; MAX_CPUS] = [const { IrqSpinlock::new(CpuQueue::new()) }; MAX_CPUS];
+
+pub fn queue(cpu: u32) -> &'static IrqSpinlock<CpuQueue> {
+    &QUEUES[cpu as usize % MAX_CPUS]
+}
+
+/// 스레드가 속한 런큐 잠금 (잡는 사이 다른 CPU로 옮겨졌으면 다시 시도)
+pub fn lock_thread(thread: ThreadRef) -> IrqSpinlockGuard<'static, CpuQueue> {
+    loop {
+        let cpu = thread.get().cpu.load(Ordering::Acquire);
+        let q = queue(cpu).lock();
+        if thread.get().cpu.load(Ordering::Acquire) == cpu {
+            return q;
+        }
+    }
+}
+
+/// 두 런큐를 CPU 번호 순서로 잠금 (`a != b`)
+pub fn lock_pair(
+    a: u32,
+    b: u32,
+) -> (IrqSpinlockGuard<'static, CpuQueue>, IrqSpinlockGuard<'static, CpuQueue>) {
+    if a < b {
+        let qa = queue(a).lock();
+        let qb = queue(b).lock();
+        (qa, qb)
+    } else {
+        let qb = queue(b).lock();
+        let qa = queue(a).lock();
+        (qa, qb)
+    }
+}
+
+/// 모든 런큐의 용량을 `total`개 이상으로 확보 (스레드 생성 시, 인터럽트 밖에서)
+pub fn reserve_all(total: usize) {
+    for q in QUEUES.iter() {
+        let mut q = q.lock();
+        let additional = total.saturating_sub(q.threads.len());
+        q.threads.reserve(additional);
+    }
+}
@@ -0,0 +1,205 @@
+//! 스케줄러 지연 히스토그램 (`schedstat` 명령)
+//!
+//! CPU마다 두 가지 시간을 log2 히스토그램으로 모읍니다 (ftrace `hist`/bcc `runqlat` 형식).
+//! - 깨움 지연: Blocked 스레드가 Ready가 된 때(`on_wakeup`)부터 그 CPU에 올라갈 때까지.
+//!   park/unpark, `park_timeout` 기한, futex 깨움, 시그널이 대상이고 선점된 스레드와 새
+//!   스레드는 빠집니다.
+//! - 스위칭 시간: `scheduler::switch_to_next`가 다음 스레드를 고른 때부터 그 스레드가
+//!   `Context::switch`에서 돌아올 때까지. 처음 실행되는 스레드는 진입점에서 시작하므로
+//!   빠집니다.
+//!
+//! 버킷 `i`는 `[2^i, 2^(i+1))` 나노초입니다(0번은 0과 1 포함). 선점과 부하 분산을 바꿀 때
+//! 전후를 비교하기 위한 것이므로 항상 켜져 있고, 기록은 원자 변수만 써서 런큐 락 안과
+//! 인터럽트 경로에서도 힙을 쓰지 않습니다. `schedstat reset`으로 0부터 다시 셉니다.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use super::percpu::{self, MAX_CPUS};
+use super::Thread;
+use crate::kprintln;
+use crate::time::clock::{monotonic_ns, Nanos};
+
+/// 히스토그램 버킷 수 (마지막 버킷은 2^39ns ≈ 9분 이상 모두)
+pub const BUCKETS: usize = 40;
+
+/// 분포 막대 최대 길이
+const BAR_WIDTH: usize = 40;
+
+/// log2 히스토그램
+pub struct Histogram {
+    buckets: [AtomicU64; BUCKETS],
+    count: AtomicU64,
+    sum_ns: AtomicU64,
+    min_ns: AtomicU64,
+    max_ns: AtomicU64,
+}
+
+/// 히스토그램 스냅샷
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistogramSnapshot {
+    pub buckets: [u64; BUCKETS],
+    pub count: u64,
+    pub sum_ns: u64,
+    /// 샘플이 없으면 0
+    pub min_ns: u64,
+    pub max_ns: u64,
+}
+
+/// `ns`가 들어갈 버킷
+pub fn bucket(ns: u64) -> usize {
+    ((u64::BITS - ns.leading_zeros()) as usize).saturating_sub(1).min(BUCKETS - 1)
+}
+
+impl Histogram {
+    pub const fn new() -> Self {
+        Self {
+            buckets: [const { AtomicU64::new(0) }; BUCKETS],
+            count: AtomicU64::new(0),
+            sum_ns: AtomicU64::new(0),
+            min_ns: AtomicU64::new(u64::MAX),
+            max_ns: AtomicU64::new(0),
+        }
+    }
+
+    /// 샘플 하나 기록
+    pub fn record(&self, ns: u64) {
+        self.buckets[bucket(ns)].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_ns.fetch_add(ns, Ordering::Relaxed);
+        self.min_ns.fetch_min(ns, Ordering::Relaxed);
+        self.max_ns.fetch_max(ns, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let count = self.count.load(Ordering::Relaxed);
+        HistogramSnapshot {
+            buckets: core::array::from_fn(|i| self.buckets[i].load(Ordering::Relaxed)),
+            count,
+            sum_ns: self.sum_ns.load(Ordering::Relaxed),
+            min_ns: if count == 0 { 0 } else { self.min_ns.load(Ordering::Relaxed) },
+            max_ns: self.max_ns.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn reset(&self) {
+        for bucket in &self.buckets {
+            bucket.store(0, Ordering::Relaxed);
+        }
+        self.count.store(0, Ordering::Relaxed);
+        self.sum_ns.store(0, Ordering::Relaxed);
+        self.min_ns.store(u64::MAX, Ordering::Relaxed);
+        self.max_ns.store(0, Ordering::Relaxed);
+    }
+}
+
+impl HistogramSnapshot {
+    /// 평균 (나노초)
+    pub fn avg_ns(&self) -> u64 {
+        if self.count == 0 { 0 } else { self.sum_ns / self.count }
+    }
+}
+
+/// CPU 하나의 히스토그램
+struct CpuSchedStat {
+    wakeup: Histogram,
+    switch: Histogram,
+    /// 진행 중인 스위칭 시작 시각 (0 = 없음)
+    switch_start_ns: AtomicU64,
+}
+
+static STATS: [CpuSchedStat; MAX_CPUS] = [const {
+    CpuSchedStat {
+        wakeup: Histogram::new(),
+        switch: Histogram::new(),
+        switch_start_ns: AtomicU64::new(0),
+    }
+}; MAX_CPUS];
+
+fn stat(cpu: u32) -> &'static CpuSchedStat {
+    &STATS[cpu as usize % MAX_CPUS]
+}
+
+/// Blocked 스레드를 Ready로 바꿀 때 (스레드 런큐 락 안)
+pub(super) fn on_wakeup(thread: &mut Thread) {
+    thread.acct.woken_ns = monotonic_ns();
+}
+
+/// `thread`가 이 CPU에 올라가거나 그대로 계속 실행할 때 (자기 런큐 락 안)
+///
+/// 깨어난 스레드면 그 뒤 기다린 시간을 기록합니다.
+pub(super) fn on_run(thread: &mut Thread, cpu: u32) {
+    let woken = core::mem::take(&mut thread.acct.woken_ns);
+    if woken != 0 {
+        stat(cpu).wakeup.record(monotonic_ns().saturating_sub(woken));
+    }
+}
+
+/// 다음 스레드를 골라 스위칭을 시작할 때
+pub(super) fn switch_begin(cpu: u32) {
+    stat(cpu).switch_start_ns.store(monotonic_ns(), Ordering::Relaxed);
+}
+
+/// `Context::switch`에서 돌아온 직후 (돌아온 CPU에서)
+pub(super) fn switch_done(cpu: u32) {
+    let stat = stat(cpu);
+    let start = stat.switch_start_ns.swap(0, Ordering::Relaxed);
+    if start != 0 {
+        stat.switch.record(monotonic_ns().saturating_sub(start));
+    }
+}
+
+/// CPU의 (깨움 지연, 스위칭 시간) 히스토그램
+pub fn snapshot(cpu: u32) -> (HistogramSnapshot, HistogramSnapshot) {
+    let stat = stat(cpu);
+    (stat.wakeup.snapshot(), stat.switch.snapshot())
+}
+
+/// 모든 CPU 히스토그램 비우기
+pub fn reset() {
+    for stat in &STATS {
+        stat.wakeup.reset();
+        stat.switch.reset();
+    }
+}
+
+/// `schedstat` 출력 (`cpu`가 None이면 온라인 CPU 전부)
+pub fn report(cpu: Option<u32>) {
+    let cpus = percpu::online_mask();
+    for cpu in cpus.iter().filter(|&c| cpu.is_none_or(|want| want == c)) {
+        let (wakeup, switch) = snapshot(cpu);
+        print_histogram(cpu, "wakeup latency", &wakeup);
+        print_histogram(cpu, "context switch", &switch);
+    }
+}
+
+fn print_histogram(cpu: u32, title: &str, hist: &HistogramSnapshot) {
+    kprintln!(
+        "\nCPU{} {}: {} samples, avg {}, min {}, max {}",
+        cpu,
+        title,
+        hist.count,
+        Nanos(hist.avg_ns()),
+        Nanos(hist.min_ns),
+        Nanos(hist.max_ns)
+    );
+    let Some(first) = hist.buckets.iter().position(|&n| n != 0) else {
+        return;
+    };
+    let last = hist.buckets.iter().rposition(|&n| n != 0).unwrap_or(first);
+    let peak = hist.buckets[first..=last].iter().copied().max().unwrap_or(1);
+
+    kprintln!("  {:>25} : {:<8} distribution", "ns", "count");
+    for (i, &count) in hist.buckets.iter().enumerate().take(last + 1).skip(first) {
+        let low = if i == 0 { 0 } else { 1u64 << i };
+        let high = if i == BUCKETS - 1 { u64::MAX } else { (1u64 << (i + 1)) - 1 };
+        let stars = (count * BAR_WIDTH as u64).div_ceil(peak) as usize;
+        kprintln!(
+            "  {:>12} -> {:<9} : {:<8} |{:<width$}|",
+            low,
+            high,
+            count,
+            "*".repeat(stars),
+            width = BAR_WIDTH
+        );
+    }
+}
@@ -1,124 +1,266 @@
 //! SMP-aware 스케줄러 구현
 //!
-//! 라운드-로빈 스케줄러. 각 CPU는 per-CPU 데이터를 통해 자신의 현재 스레드를 추적하며,
-//! 전역 THREADS 리스트에서 Ready 상태의 스레드를 선택합니다.
-//! CPU 친화도(cpu_affinity)가 설정된 스레드는 지정된 CPU에서만 실행됩니다.
+//! 각 CPU는 자기 런큐(`runqueue::QUEUES`)의 락만 잡고 다음 스레드를 고르므로
+//! 컨텍스트 스위칭이 CPU끼리 직렬화되지 않습니다. 선택은 `policy` 모듈의 정책(현재
+//! 라운드-로빈, CPU 친화도 존중)이 하고, 이 파일은 락/상태 변경과 `ArchContext`
+//! 스위칭만 담당합니다.
+//!
+//! 타이머 틱은 `tick()`을 부르고, 현재 스레드가 타임 슬라이스(`sched.quantum` 틱, 기본 1)를
+//! 다 쓴 경우에만 다른 스레드로 선점합니다. idle 스레드는 슬라이스와 관계없이 매 틱 확인합니다.
+//!
+//! 부하 분산은 스케줄링 때 pull 방식으로 합니다. 각 CPU는 `sched.balance_interval`
+//! 틱마다, 그리고 idle 스레드를 실행 중일 때는 매번 가장 바쁜 CPU 런큐의 Ready
+//! 스레드 하나를 자기 런큐로 옮깁니다 (`policy::should_pull`, `policy::pick_movable`).
+//!
+//! 오프라인으로 내리는 CPU(`hotplug`)는 idle 스레드만 실행하고 부하 분산도 하지 않습니다.
+//! 그 런큐에 남은 Ready 스레드는 다른 CPU가 부하와 관계없이 가져갑니다.
+//!
+//! 스위칭마다 깨움 지연과 스위칭 시간을 CPU별 히스토그램에 남깁니다 (`schedstat`).
 
-use super::{ThreadState, THREADS};
-use super::context::{Context, context_switch};
+use super::context::{ArchContext, Context};
 use super::percpu;
-use core::sync::atomic::Ordering;
+use super::policy::{self, Decision, RoundRobin, RunQueue, SchedPolicy};
+use super::runqueue;
+use super::ThreadState;
+use crate::sysctl::{self, Kind, Tunable, Value};
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// 현재 스케줄링 정책
+static POLICY: RoundRobin = RoundRobin;
+
+/// 타임 슬라이스 (타이머 틱, `sched.quantum`)
+static QUANTUM_TICKS: AtomicU64 = AtomicU64::new(1);
+
+/// 주기적 부하 분산 간격 (타이머 틱, 10ms 틱 기준 100ms, `sched.balance_interval`)
+static BALANCE_INTERVAL_TICKS: AtomicU64 = AtomicU64::new(10);
+
+static SYSCTL: [Tunable; 2] = [
+    Tunable {
+        name: "sched.quantum",
+        desc: "time slice in timer ticks",
+        kind: Kind::Int { min: 1, max: 100 },
+        get: || Value::Int(QUANTUM_TICKS.load(Ordering::Relaxed) as i64),
+        set: Some(|v| {
+            QUANTUM_TICKS.store(sysctl::int(&v) as u64, Ordering::Relaxed);
+            Ok(())
+        }),
+    },
+    Tunable {
+        name: "sched.balance_interval",
+        desc: "periodic load balancing interval in timer ticks",
+        kind: Kind::Int { min: 1, max: 1000 },
+        get: || Value::Int(BALANCE_INTERVAL_TICKS.load(Ordering::Relaxed) as i64),
+        set: Some(|v| {
+            BALANCE_INTERVAL_TICKS.store(sysctl::int(&v) as u64, Ordering::Relaxed);
+            Ok(())
+        }),
+    },
+];
+
+/// `sched.*` 노브 등록 (`proc::init()`에서 호출)
+pub fn register_sysctl() {
+    sysctl::register_all(&SYSCTL);
+}
 
 /// 스케줄러: 현재 CPU에서 다음 실행할 스레드를 선택하고 컨텍스트 스위칭 수행
 pub fn schedule() {
+    switch_to_next(&POLICY);
+}
+
+/// 타이머 틱 선점: 현재 스레드가 타임 슬라이스를 다 썼으면 스케줄
+pub fn tick() {
+    let pc = percpu::current();
+    let ran = pc.tick_count.load(Ordering::Relaxed).wrapping_sub(pc.slice_start_tick.load(Ordering::Relaxed));
+    if pc.is_idle() || ran >= QUANTUM_TICKS.load(Ordering::Relaxed) {
+        schedule();
+    }
+}
+
+/// 정책 결정에 따라 스레드 상태를 바꾸고 컨텍스트 스위칭
+fn switch_to_next(policy: &dyn SchedPolicy) {
     let cpu_id = percpu::get_cpu_id();
     let pc = percpu::current();
 
-    let current_idx = pc.current_thread_idx.load(Ordering::Acquire) as usize;
-    if current_idx == u32::MAX as usize {
+    if pc.current_tid.load(Ordering::Acquire) == percpu::NO_THREAD {
         return; // 아직 초기화되지 않음
     }
 
+    // 하반부(태스크릿) 처리 중에는 선점하지 않음 (`softirq` 모듈 참고)
+    if crate::softirq::in_softirq() {
+        return;
+    }
+
+    // 자기 런큐 락을 잡기 전에 (다른 런큐와 함께 잠그므로)
+    let online = pc.is_online();
+    if online && (pc.is_idle() || balance_due(pc)) {
+        balance(cpu_id);
+    }
+
     let (old_ctx, new_ctx) = {
-        let mut threads = THREADS.lock();
+        let mut q = runqueue::queue(cpu_id).lock();
+        q.finish_switch();
+        super::park::wake_expired(&q, crate::time::now_us());
 
-        if current_idx >= threads.len() {
+        let Some(current) = q.current() else {
             return;
-        }
+        };
+        let current_idx = q.current;
+        let idle_idx = q.idle;
+        // Safety: 자기 런큐 락을 잡고 있음 (아래 get_mut 모두 동일)
+        let cur = unsafe { current.get_mut() };
 
         // 현재 스레드가 Running이면 Ready로 변경
-        if let Some(thread) = threads.get_mut(current_idx) {
-            if thread.state == ThreadState::Running {
-                thread.state = ThreadState::Ready;
-            }
-        }
-
-        // 다음 실행할 스레드 찾기 (라운드-로빈, CPU 친화도 존중)
-        let num_threads = threads.len();
-        let mut next_idx = None;
-
-        for offset in 1..=num_threads {
-            let idx = (current_idx + offset) % num_threads;
-            if let Some(thread) = threads.get(idx) {
-                if thread.state == ThreadState::Ready {
-                    // CPU 친화도 확인: 다른 CPU에 고정된 스레드는 건너뜀
-                    if let Some(affinity) = thread.cpu_affinity {
-                        if affinity != cpu_id {
-                            continue;
-                        }
-                    }
-                    next_idx = Some(idx);
-                    break;
-                }
-            }
+        if cur.state == ThreadState::Running {
+            cur.state = ThreadState::Ready;
         }
 
-        // 실행할 스레드가 없으면 현재 스레드 계속 또는 idle로 전환
-        let next_idx = match next_idx {
-            Some(idx) => idx,
-            None => {
-                if let Some(thread) = threads.get_mut(current_idx) {
-                    if thread.state == ThreadState::Terminated {
-                        // 종료된 스레드 → 이 CPU의 idle 스레드로 전환
-                        let idle_idx = pc.idle_thread_idx.load(Ordering::Relaxed) as usize;
-                        if idle_idx < threads.len() {
-                            idle_idx
-                        } else {
-                            return;
-                        }
-                    } else {
-                        // Ready나 다른 상태 → 그대로 계속
-                        thread.state = ThreadState::Running;
-                        return;
-                    }
-                } else {
-                    return;
+        // 오프라인으로 내리는 중이면 idle 스레드로
+        let decision = if online {
+            policy.pick_next(&*q, cpu_id, current_idx, idle_idx)
+        } else if current_idx == idle_idx {
+            Decision::Stay
+        } else {
+            Decision::Switch(idle_idx)
+        };
+        let next_idx = match decision {
+            Decision::Switch(idx) => idx,
+            Decision::Stay => {
+                // 종료된 스레드가 아니면 그대로 계속
+                if cur.state != ThreadState::Terminated {
+                    cur.state = ThreadState::Running;
+                    super::schedstat::on_run(cur, cpu_id);
                 }
+                return;
             }
         };
 
-        // 같은 스레드면 스위칭 불필요
-        if next_idx == current_idx {
-            if let Some(thread) = threads.get_mut(current_idx) {
-                thread.state = ThreadState::Running;
-            }
-            return;
-        }
-
         // 새 스레드를 Running으로 변경
-        if let Some(thread) = threads.get_mut(next_idx) {
-            thread.state = ThreadState::Running;
-        }
+        let next_ref = q.threads[next_idx];
+        let next = unsafe { next_ref.get_mut() };
+        next.state = ThreadState::Running;
+        crate::trace_event!(sched_switch, prev = cur.tid, next = next.tid);
+        super::acct::on_switch(cur, next, pc, current_idx == idle_idx);
+        super::schedstat::on_run(next, cpu_id);
+        super::schedstat::switch_begin(cpu_id);
 
         // 컨텍스트 포인터 얻기
-        let old_ctx = threads.get_mut(current_idx)
-            .map(|t| &mut t.context as *mut Context);
-        let new_ctx = threads.get(next_idx)
-            .map(|t| &t.context as *const Context);
+        let old_ctx = &mut cur.context as *mut Context;
+        let new_ctx = &next.context as *const Context;
 
-        // Per-CPU 현재 스레드 인덱스 업데이트
-        pc.current_thread_idx.store(next_idx as u32, Ordering::Release);
+        // 내려가는 스레드는 스위칭이 끝날 때까지 on_cpu로 남아 다른 CPU가 가져가지 않음
+        q.begin_switch(current, next_idx);
+        pc.slice_start_tick.store(pc.tick_count.load(Ordering::Relaxed), Ordering::Relaxed);
+        pc.current_tid.store(next.tid, Ordering::Release);
+        super::tls::set_current(pc, &next.tls);
 
-        match (old_ctx, new_ctx) {
-            (Some(old), Some(new)) => (old, new),
-            _ => return,
-        }
+        (old_ctx, new_ctx)
     };
 
     // 락을 해제한 후 컨텍스트 스위칭
     unsafe {
-        context_switch(old_ctx, new_ctx);
+        Context::switch(old_ctx, new_ctx);
+    }
+
+    // 다시 실행됨 (다른 CPU로 옮겨졌을 수 있음): 직전에 내려간 스레드 놓아주기
+    let cpu_id = percpu::get_cpu_id();
+    super::schedstat::switch_done(cpu_id);
+    runqueue::queue(cpu_id).lock().finish_switch();
+}
+
+/// 주기적 부하 분산 시각이 되었는지 (되었으면 다음 시각 예약)
+fn balance_due(pc: &percpu::PerCpuData) -> bool {
+    let tick = pc.tick_count.load(Ordering::Relaxed);
+    if tick < pc.next_balance_tick.load(Ordering::Relaxed) {
+        return false;
+    }
+    pc.next_balance_tick
+        .store(tick + BALANCE_INTERVAL_TICKS.load(Ordering::Relaxed), Ordering::Relaxed);
+    true
+}
+
+/// 가장 바쁜 CPU 런큐의 Ready 스레드 하나를 이 CPU 런큐로 옮김
+///
+/// 친화도가 바뀌어 자기 CPU에서 실행할 수 없게 된 스레드가 있는 런큐와 오프라인 CPU의
+/// 런큐를 먼저 봅니다.
+/// 부하는 런큐를 하나씩 잠깐 잠가 읽고, 옮길 때만 두 런큐를 함께 잠급니다. 런큐
+/// 용량은 미리 확보되어 있으므로(`runqueue::reserve_all`) 인터럽트 경로에서도 힙을
+/// 쓰지 않습니다.
+fn balance(cpu_id: u32) {
+    let cpus = percpu::total_count().min(percpu::MAX_CPUS as u32);
+    let online = percpu::online_mask();
+    let mut loads = [0usize; percpu::MAX_CPUS];
+    let mut stranded = [false; percpu::MAX_CPUS];
+    for cpu in 0..cpus {
+        let q = runqueue::queue(cpu).lock();
+        loads[cpu as usize] = policy::queue_load(&*q, q.idle);
+        stranded[cpu as usize] = cpu != cpu_id && pick_stranded(&*q, online, cpu, cpu_id).is_some();
+    }
+    let mine = loads[cpu_id as usize];
+
+    // 부하가 큰 CPU부터 시도 (가져올 스레드가 모두 친화도로 묶여 있을 수 있음)
+    let mut tried = [false; percpu::MAX_CPUS];
+    tried[cpu_id as usize] = true;
+    loop {
+        let Some(from) = (0..cpus)
+            .filter(|&cpu| {
+                let c = cpu as usize;
+                !tried[c] && (stranded[c] || policy::should_pull(mine, loads[c]))
+            })
+            .max_by_key(|&cpu| (stranded[cpu as usize], loads[cpu as usize]))
+        else {
+            return;
+        };
+        tried[from as usize] = true;
+
+        let (mut src, mut dst) = runqueue::lock_pair(from, cpu_id);
+        let pick = pick_stranded(&*src, online, from, cpu_id).or_else(|| {
+            if policy::should_pull(mine, loads[from as usize]) {
+                policy::pick_movable(&*src, src.idle, cpu_id)
+            } else {
+                None
+            }
+        });
+        let Some(idx) = pick else {
+            continue;
+        };
+        let thread = src.remove(idx);
+        thread.get().cpu.store(cpu_id, Ordering::Release);
+        dst.push(thread);
+        drop((src, dst));
+
+        percpu::get(from).migrations_out.fetch_add(1, Ordering::Relaxed);
+        percpu::current().migrations_in.fetch_add(1, Ordering::Relaxed);
+        crate::trace_event!(sched_migrate, tid = thread.get().tid, from = from, to = cpu_id);
+        return;
+    }
+}
+
+/// `from` CPU 런큐에서 부하와 관계없이 `to`로 옮길 스레드 (오프라인 CPU면 모든 스레드)
+fn pick_stranded(q: &runqueue::CpuQueue, online: percpu::CpuMask, from: u32, to: u32) -> Option<usize> {
+    if online.contains(from) {
+        policy::pick_stranded(q, q.idle, from, to)
+    } else {
+        policy::pick_movable(q, q.idle, to)
     }
 }
 
-/// 실행 가능한 스레드 수 반환
+/// 이 CPU 런큐에서 기다리는 Ready 스레드 수 (현재 스레드 제외)
 pub fn ready_count() -> usize {
-    let threads = THREADS.lock();
-    threads.iter().filter(|t| t.state == ThreadState::Ready).count()
+    let q = runqueue::queue(percpu::get_cpu_id()).lock();
+    (0..q.threads.len())
+        .filter(|&idx| idx != q.current && q.state(idx) == ThreadState::Ready)
+        .count()
 }
 
 /// 활성 스레드 수 반환 (종료되지 않은)
 pub fn active_count() -> usize {
-    let threads = THREADS.lock();
-    threads.iter().filter(|t| t.state != ThreadState::Terminated).count()
+    runqueue::QUEUES
+        .iter()
+        .map(|q| {
+            let q = q.lock();
+            (0..q.threads.len())
+                .filter(|&idx| q.state(idx) != ThreadState::Terminated)
+                .count()
+        })
+        .sum()
 }
@@ -0,0 +1,303 @@
+//! 시그널 (Signal)
+//!
+//! 스레드별 대기 시그널 비트맵과 기본 동작(종료/무시)만 지원하는 최소 구현입니다.
+//! 유저 핸들러(시그널 프레임)는 아직 없으므로 `rt_sigaction`은 `SIG_DFL`/`SIG_IGN`만
+//! 받습니다.
+//!
+//! ## 전달 시점
+//! - 유저 모드로 돌아가기 직전 (시스템 콜/예외/인터럽트 복귀, 아키텍처 핸들러에서 호출)
+//! - 커널 스레드는 `yield_now()`에서 다시 실행될 때 (락을 잡지 않은 안전한 지점)
+//!
+//! 임의의 인터럽트 지점에서 커널 스레드를 끝내면 잡고 있던 락이 풀리지 않으므로,
+//! 커널 모드 인터럽트 복귀에서는 전달하지 않습니다.
+//!
+//! ## 사용 예시
+//! ```rust
+//! signal::send(tid, signal::SIGTERM)?;       // 대기 비트 설정
+//! signal::set_action(tid, SIGINT, SigAction::Ignore)?;
+//! signal::deliver_pending();                  // 현재 스레드의 대기 시그널 처리
+//! ```
+
+use core::sync::atomic::Ordering;
+
+use super::{percpu, runqueue, Thread, ThreadState, Tid};
+use crate::kprintln;
+
+/// 시그널 개수 (1..=63 사용, 0은 존재 확인용)
+pub const NSIG: u32 = 64;
+
+pub const SIGHUP: u32 = 1;
+pub const SIGINT: u32 = 2;
+pub const SIGQUIT: u32 = 3;
+pub const SIGILL: u32 = 4;
+pub const SIGABRT: u32 = 6;
+pub const SIGKILL: u32 = 9;
+pub const SIGUSR1: u32 = 10;
+pub const SIGSEGV: u32 = 11;
+pub const SIGUSR2: u32 = 12;
+pub const SIGPIPE: u32 = 13;
+pub const SIGALRM: u32 = 14;
+pub const SIGTERM: u32 = 15;
+pub const SIGCHLD: u32 = 17;
+pub const SIGCONT: u32 = 18;
+pub const SIGSTOP: u32 = 19;
+pub const SIGTSTP: u32 = 20;
+pub const SIGTTIN: u32 = 21;
+pub const SIGTTOU: u32 = 22;
+pub const SIGURG: u32 = 23;
+pub const SIGWINCH: u32 = 28;
+
+/// 이름 표 (셸 `kill -TERM` 등에 사용)
+const NAMES: &[(u32, &str)] = &[
+    (SIGHUP, "HUP"),
+    (SIGINT, "INT"),
+    (SIGQUIT, "QUIT"),
+    (SIGILL, "ILL"),
+    (SIGABRT, "ABRT"),
+    (SIGKILL, "KILL"),
+    (SIGUSR1, "USR1"),
+    (SIGSEGV, "SEGV"),
+    (SIGUSR2, "USR2"),
+    (SIGPIPE, "PIPE"),
+    (SIGALRM, "ALRM"),
+    (SIGTERM, "TERM"),
+    (SIGCHLD, "CHLD"),
+    (SIGCONT, "CONT"),
+    (SIGSTOP, "STOP"),
+    (SIGTSTP, "TSTP"),
+    (SIGTTIN, "TTIN"),
+    (SIGTTOU, "TTOU"),
+    (SIGURG, "URG"),
+    (SIGWINCH, "WINCH"),
+];
+
+/// 시그널 에러
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalError {
+    /// 잘못된 시그널 번호 또는 바꿀 수 없는 동작
+    InvalidSignal,
+    /// 대상 스레드 없음
+    NoSuchThread,
+    /// idle 스레드 등 시그널을 받을 수 없는 대상
+    NotPermitted,
+}
+
+/// 시그널 동작
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigAction {
+    /// 기본 동작 (`default_action`)
+    Default,
+    /// 무시
+    Ignore,
+}
+
+/// 기본 동작 결과
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefaultAction {
+    /// 스레드 종료
+    Terminate,
+    /// 무시
+    Ignore,
+}
+
+/// 시그널 기본 동작
+///
+/// 정지/재개(SIGSTOP, SIGTSTP 등)는 아직 지원하지 않으므로 무시합니다.
+pub fn default_action(sig: u32) -> DefaultAction {
+    match sig {
+        SIGCHLD | SIGCONT | SIGURG | SIGWINCH => DefaultAction::Ignore,
+        SIGSTOP | SIGTSTP | SIGTTIN | SIGTTOU => DefaultAction::Ignore,
+        _ => DefaultAction::Terminate,
+    }
+}
+
+/// 시그널 이름 ("TERM" 등, 모르면 "?")
+pub fn name(sig: u32) -> &'static str {
+    NAMES.iter().find(|(n, _)| *n == sig).map(|(_, s)| *s).unwrap_or("?")
+}
+
+/// 이름 또는 번호로 시그널 찾기 ("TERM", "SIGTERM", "15")
+pub fn parse(s: &str) -> Option<u32> {
+    if let Ok(n) = s.parse::<u32>() {
+        return (n > 0 && n < NSIG).then_some(n);
+    }
+    let s = s.strip_prefix("SIG").unwrap_or(s);
+    NAMES.iter().find(|(_, name)| name.eq_ignore_ascii_case(s)).map(|(n, _)| *n)
+}
+
+/// 스레드별 시그널 상태
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SignalState {
+    /// 대기 중인 시그널 비트맵 (bit n = 시그널 n)
+    pub pending: u64,
+    /// `SIG_IGN`으로 설정된 시그널 비트맵
+    pub ignored: u64,
+}
+
+impl SignalState {
+    fn action(&self, sig: u32) -> SigAction {
+        if self.ignored & (1 << sig) != 0 {
+            SigAction::Ignore
+        } else {
+            SigAction::Default
+        }
+    }
+
+    /// 전달했을 때 무시되는 시그널인지
+    fn discards(&self, sig: u32) -> bool {
+        self.action(sig) == SigAction::Ignore || default_action(sig) == DefaultAction::Ignore
+    }
+}
+
+/// 시그널로 종료된 스레드의 종료 상태 (셸 관례: 128 + 시그널 번호)
+pub const fn exit_status(sig: u32) -> i32 {
+    128 + sig as i32
+}
+
+fn valid(sig: u32) -> bool {
+    sig > 0 && sig < NSIG
+}
+
+/// 스레드에 시그널 보내기
+///
+/// `sig`가 0이면 대상이 있는지만 확인합니다. 무시되는 시그널은 대기 비트에
+/// 올리지 않고 버립니다. 실제 동작은 대상 스레드의 다음 전달 시점에 수행됩니다.
+pub fn send(tid: Tid, sig: u32) -> Result<(), SignalError> {
+    if sig != 0 && !valid(sig) {
+        return Err(SignalError::InvalidSignal);
+    }
+    // idle은 시그널로 종료할 수 없음
+    if super::is_idle_thread(tid) {
+        return Err(SignalError::NotPermitted);
+    }
+    let woken = super::with_thread(tid, |thread| post(thread, sig)).ok_or(SignalError::NoSuchThread)?;
+    if let Some(cpu) = woken {
+        super::kick_idle_cpu(cpu);
+    }
+    Ok(())
+}
+
+/// `send`와 같지만 스레드 레지스트리 락을 기다리지 않음 (OOM 킬러용)
+///
+/// 락을 바로 얻지 못해도 `NoSuchThread`입니다.
+pub(crate) fn try_send(tid: Tid, sig: u32) -> Result<(), SignalError> {
+    if !valid(sig) {
+        return Err(SignalError::InvalidSignal);
+    }
+    if super::is_idle_thread(tid) {
+        return Err(SignalError::NotPermitted);
+    }
+    let woken = super::try_with_thread(tid, |thread| post(thread, sig)).ok_or(SignalError::NoSuchThread)?;
+    if let Some(cpu) = woken {
+        super::kick_idle_cpu(cpu);
+    }
+    Ok(())
+}
+
+/// 무시 설정과 관계없이 시그널 보내기 (처리할 수 없는 페이지 폴트 등)
+///
+/// 대상이 `SIG_IGN`으로 설정해 두었으면 기본 동작으로 되돌린 뒤 대기 비트에 올립니다
+/// (Linux `force_sig`와 같음). 기본 동작이 무시인 시그널은 여전히 버려집니다.
+pub fn force(tid: Tid, sig: u32) -> Result<(), SignalError> {
+    if !valid(sig) {
+        return Err(SignalError::InvalidSignal);
+    }
+    if super::is_idle_thread(tid) {
+        return Err(SignalError::NotPermitted);
+    }
+    let woken = super::with_thread(tid, |thread| {
+        thread.signals.ignored &= !(1 << sig);
+        post(thread, sig)
+    })
+    .ok_or(SignalError::NoSuchThread)?;
+    if let Some(cpu) = woken {
+        super::kick_idle_cpu(cpu);
+    }
+    Ok(())
+}
+
+/// 대기 시그널로 표시하고 잠든 스레드는 깨움 (런큐 락을 잡은 채, 깨웠으면 그 CPU)
+fn post(thread: &mut Thread, sig: u32) -> Option<u32> {
+    if sig == 0 || thread.signals.discards(sig) {
+        return None;
+    }
+    thread.signals.pending |= 1 << sig;
+    // 잠든 스레드(futex 대기 등)는 깨워서 전달 지점까지 진행시킴
+    if thread.state == ThreadState::Blocked {
+        thread.state = ThreadState::Ready;
+        super::schedstat::on_wakeup(thread);
+        crate::trace_event!(sched_wakeup, tid = thread.tid, sig = sig);
+        return Some(thread.cpu.load(Ordering::Relaxed));
+    }
+    None
+}
+
+/// 스레드에 전달할 시그널이 대기 중인지
+pub fn has_pending(tid: Tid) -> bool {
+    super::with_thread(tid, |t| t.signals.pending != 0).unwrap_or(false)
+}
+
+/// 시그널 동작 설정 (이전 동작 반환)
+///
+/// SIGKILL, SIGSTOP의 동작은 바꿀 수 없습니다.
+pub fn set_action(tid: Tid, sig: u32, action: SigAction) -> Result<SigAction, SignalError> {
+    if !valid(sig) || sig == SIGKILL || sig == SIGSTOP {
+        return Err(SignalError::InvalidSignal);
+    }
+    super::with_thread(tid, |thread| {
+        let state = &mut thread.signals;
+        let old = state.action(sig);
+        match action {
+            SigAction::Ignore => {
+                state.ignored |= 1 << sig;
+                // 이미 대기 중인 것도 버림
+                state.pending &= !(1 << sig);
+            }
+            SigAction::Default => state.ignored &= !(1 << sig),
+        }
+        old
+    })
+    .ok_or(SignalError::NoSuchThread)
+}
+
+/// 시그널 동작 조회
+pub fn action(tid: Tid, sig: u32) -> Result<SigAction, SignalError> {
+    if !valid(sig) {
+        return Err(SignalError::InvalidSignal);
+    }
+    super::with_thread(tid, |t| t.signals.action(sig)).ok_or(SignalError::NoSuchThread)
+}
+
+/// 현재 스레드의 대기 시그널 처리
+///
+/// 종료 동작인 시그널이 있으면 스레드를 종료하고 반환하지 않습니다.
+pub fn deliver_pending() {
+    let fatal = {
+        let q = runqueue::queue(percpu::get_cpu_id()).lock();
+        let Some(thread) = q.current() else {
+            return;
+        };
+        // Safety: 자기 런큐 락을 잡고 있음
+        let thread = unsafe { thread.get_mut() };
+        let state = &mut thread.signals;
+        if state.pending == 0 {
+            return;
+        }
+        // 번호가 낮은 것부터, 전달 사이에 바뀐 동작을 반영
+        let mut fatal = None;
+        while state.pending != 0 {
+            let sig = state.pending.trailing_zeros();
+            state.pending &= !(1 << sig);
+            if !state.discards(sig) {
+                fatal = Some((thread.tid, sig));
+                break;
+            }
+        }
+        fatal
+    };
+
+    if let Some((tid, sig)) = fatal {
+        kprintln!("[signal] Thread {} killed by SIG{} ({})", tid, name(sig), sig);
+        super::exit_with(exit_status(sig));
+    }
+}
@@ -0,0 +1,378 @@
+//! 스레드 로컬 저장소 (TLS)
+//!
+//! 모듈과 유저 실행 파일의 ELF TLS 섹션(`.tdata`/`.tbss`)을 스레드마다 따로 둡니다.
+//! 스레드 포인터(aarch64 `TPIDR_EL0`, riscv64 `tp`)는 컨텍스트의 일부라 스위칭 때
+//! 저장/복원합니다 (`context::Context::tp`). 새 커널 스레드는 0에서 시작합니다.
+//!
+//! # 모듈 (동적 TLS)
+//!
+//! 모듈은 스레드가 이미 실행 중일 때 로드되므로 스레드 포인터 기준 고정 오프셋
+//! (local-exec/initial-exec 모델)을 줄 수 없습니다. 로더는 모듈의 TLS 섹션을 이미지
+//! 하나로 모아 등록하고(`register`), 모듈 코드는 `-C relocation-model=pic`의 기본인
+//! general-dynamic 모델로 접근합니다:
+//! - aarch64: TLSDESC. 로더가 PLT 페이지에 디스크립터 `[tls_desc_resolver, (id << 32) | 오프셋]`를
+//!   만들고, resolver는 변수 주소 - `TPIDR_EL0`를 반환합니다 (x0 외 레지스터 보존).
+//! - riscv64: 로더가 PLT 페이지에 `TlsIndex`를 만들고, `__tls_get_addr`가 변수 주소를 반환합니다.
+//!
+//! 스레드의 모듈별 블록은 처음 접근할 때 할당하고 (초기값 복사, 나머지 0) 스레드가 끝날
+//! 때 해제합니다. 블록 목록은 그 스레드만 인터럽트를 끈 채 고치므로 락이 없고, 할당은
+//! 인터럽트를 끄기 전에 합니다. 메모리가 없거나 id가 잘못됐으면 패닉하지 않고 주소 0을
+//! 돌려주므로 모듈의 접근은 널 포인터 폴트가 됩니다.
+//! 언로드한 모듈의 블록도 스레드가 끝날 때 해제합니다 (모듈 TLS id는 재사용하지 않음).
+//!
+//! # 유저 실행 파일 (정적 TLS)
+//!
+//! 실행 파일의 `PT_TLS` 세그먼트는 링커가 스레드 포인터 기준 오프셋으로 접근하므로
+//! ELF TLS Variant I 배치로 블록 하나를 만들고 유저 모드로 넘어가기 전에 스레드 포인터를
+//! 설정합니다 (`StaticTls`, `user::UserProcess`):
+//! - aarch64: `TPIDR_EL0` → 16바이트 TCB, TLS 블록은 `align_up(16, p_align)`부터
+//! - riscv64: `tp` → TLS 블록 시작
+
+use alloc::alloc::{alloc_zeroed, dealloc, Layout};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+use core::sync::atomic::Ordering;
+
+use super::percpu;
+use crate::sync::{Mutex, RcuCell};
+
+/// 스레드 포인터와 TLS 블록 사이의 TCB 크기 (Variant I)
+#[cfg(target_arch = "aarch64")]
+const TCB_SIZE: usize = 16;
+
+#[cfg(target_arch = "riscv64")]
+const TCB_SIZE: usize = 0;
+
+/// TLS 초기화 이미지 (`.tdata` 내용과 `.tbss`까지의 크기)
+#[derive(Debug, Clone)]
+pub struct TlsImage {
+    /// 초기값 (블록 앞부분에 복사)
+    pub init: Vec<u8>,
+    /// 블록 크기 (`.tbss` 포함)
+    pub size: usize,
+    /// 정렬 (2의 거듭제곱)
+    pub align: usize,
+}
+
+impl TlsImage {
+    /// 잘못된 정렬이거나 초기값이 크기보다 크면 None
+    pub fn new(init: Vec<u8>, size: usize, align: usize) -> Option<Self> {
+        let align = align.max(1);
+        if !align.is_power_of_two() || init.len() > size {
+            return None;
+        }
+        Some(Self { init, size, align })
+    }
+}
+
+/// 할당한 TLS 블록 (drop 시 해제)
+struct Block {
+    addr: usize,
+    layout: Layout,
+}
+
+impl Block {
+    /// 0으로 채운 블록을 할당하고 `offset`부터 초기값 복사
+    fn new(layout: Layout, offset: usize, init: &[u8]) -> Option<Self> {
+        let ptr = unsafe { alloc_zeroed(layout) };
+        if ptr.is_null() {
+            return None;
+        }
+        unsafe {
+            core::ptr::copy_nonoverlapping(init.as_ptr(), ptr.add(offset), init.len());
+        }
+        Some(Self { addr: ptr as usize, layout })
+    }
+}
+
+impl Drop for Block {
+    fn drop(&mut self) {
+        unsafe { dealloc(self.addr as *mut u8, self.layout) }
+    }
+}
+
+// ============================================================================
+// 모듈 TLS
+// ============================================================================
+
+/// 등록된 모듈 TLS 이미지 (모듈 TLS id = 인덱스, 해제하면 None)
+static IMAGES: RcuCell<Vec<Option<Arc<TlsImage>>>> = RcuCell::lazy(Vec::new);
+
+/// 이미지 등록/해제 직렬화
+static IMAGES_WRITE: Mutex<()> = Mutex::new(());
+
+/// 모듈 TLS 이미지 등록 후 모듈 TLS id 반환
+pub fn register(image: TlsImage) -> usize {
+    let _guard = IMAGES_WRITE.lock();
+    let mut id = 0;
+    IMAGES.update(|images| {
+        id = images.len();
+        images.push(Some(Arc::new(image)));
+    });
+    id
+}
+
+/// 모듈 TLS 이미지 해제 (모듈 언로드, 스레드에 남은 블록은 스레드가 끝날 때 해제)
+pub fn unregister(id: usize) {
+    let _guard = IMAGES_WRITE.lock();
+    IMAGES.update(|images| {
+        if let Some(slot) = images.get_mut(id) {
+            *slot = None;
+        }
+    });
+}
+
+fn image(id: usize) -> Option<Arc<TlsImage>> {
+    IMAGES.read().get(id).cloned().flatten()
+}
+
+/// 스레드의 모듈 TLS 블록 목록
+pub struct ThreadTls {
+    /// 모듈 TLS id로 색인 (None = 아직 접근하지 않음)
+    blocks: UnsafeCell<Vec<Option<Block>>>,
+}
+
+// Safety: 블록 목록은 그 스레드만 인터럽트를 끈 채 고침 (부팅 중에는 CPU 0만)
+unsafe impl Sync for ThreadTls {}
+
+impl ThreadTls {
+    pub const fn new() -> Self {
+        Self {
+            blocks: UnsafeCell::new(Vec::new()),
+        }
+    }
+}
+
+/// 스케줄러 시작 전에 쓰는 블록 목록
+static BOOT_TLS: ThreadTls = ThreadTls::new();
+
+/// `tls`를 `cpu`의 현재 스레드 블록 목록으로 설정 (런큐 락 안에서)
+pub(super) fn set_current(pc: &percpu::PerCpuData, tls: &ThreadTls) {
+    pc.tls.store(tls as *const ThreadTls as usize, Ordering::Release);
+}
+
+fn current() -> &'static ThreadTls {
+    let ptr = percpu::current().tls.load(Ordering::Acquire);
+    if ptr == 0 {
+        &BOOT_TLS
+    } else {
+        // Safety: 스레드는 CPU에서 내려간 뒤에 해제되고, 그 전에 다음 스레드로 바뀜
+        unsafe { &*(ptr as *const ThreadTls) }
+    }
+}
+
+/// 현재 스레드 블록 목록을 인터럽트를 끈 채 고침
+fn with_blocks<R>(f: impl FnOnce(&mut Vec<Option<Block>>) -> R) -> R {
+    let irq = crate::sync::interrupts_enabled();
+    crate::sync::disable_interrupts();
+    // Safety: 현재 스레드만 자기 블록 목록을 고치고, 인터럽트를 꺼서 재진입하지 않음
+    let result = f(unsafe { &mut *current().blocks.get() });
+    if irq {
+        crate::sync::enable_interrupts();
+    }
+    result
+}
+
+/// 현재 스레드에서 모듈 `id`의 TLS 블록 + `offset` 주소 (처음 접근하면 블록 할당)
+///
+/// 등록되지 않은 id이거나 블록을 할당하지 못하면 0입니다.
+pub fn module_addr(id: usize, offset: usize) -> usize {
+    if let Some(base) = with_blocks(|blocks| blocks.get(id).and_then(|b| b.as_ref()).map(|b| b.addr)) {
+        return base + offset;
+    }
+
+    // 할당은 인터럽트를 끄기 전에
+    let Some(image) = image(id) else {
+        crate::log_error!(tag: "tls", "unknown module TLS id {}", id);
+        return 0;
+    };
+    let block = Layout::from_size_align(image.size.max(1), image.align)
+        .ok()
+        .and_then(|layout| Block::new(layout, 0, &image.init));
+    let Some(block) = block else {
+        crate::log_error!(tag: "tls", "cannot allocate {} byte block for module TLS id {}", image.size, id);
+        return 0;
+    };
+    // 목록을 늘려야 하면 새 목록도 미리 할당
+    let mut grown = Vec::new();
+    if with_blocks(|blocks| blocks.len()) <= id && grown.try_reserve_exact(id + 1).is_err() {
+        crate::log_error!(tag: "tls", "cannot grow block list for module TLS id {}", id);
+        return 0;
+    }
+
+    let mut spare = None;
+    let base = with_blocks(|blocks| {
+        if blocks.len() <= id {
+            grown.append(blocks);
+            grown.resize_with(id + 1, || None);
+            core::mem::swap(blocks, &mut grown);
+        }
+        // 그 사이 인터럽트 핸들러가 같은 블록을 만들었으면 그것을 씀
+        let slot = &mut blocks[id];
+        if slot.is_some() {
+            spare = Some(block);
+        } else {
+            *slot = Some(block);
+        }
+        slot.as_ref().map_or(0, |b| b.addr)
+    });
+    // 남은 것은 인터럽트를 켠 뒤 해제
+    drop(grown);
+    drop(spare);
+    base + offset
+}
+
+/// 모듈 재배치가 가리킬 16바이트 항목 (로더가 PLT 페이지에 둠)
+///
+/// aarch64는 TLSDESC 디스크립터 `[resolver, (id << 32) | offset]`입니다.
+#[cfg(target_arch = "aarch64")]
+pub fn module_entry(id: usize, offset: usize) -> [u64; 2] {
+    [
+        tls_desc_resolver as *const () as u64,
+        ((id as u64) << 32) | (offset as u64 & 0xffff_ffff),
+    ]
+}
+
+/// 모듈 재배치가 가리킬 16바이트 항목 (로더가 PLT 페이지에 둠)
+///
+/// riscv64는 `__tls_get_addr`에 넘기는 `TlsIndex`입니다.
+#[cfg(target_arch = "riscv64")]
+pub fn module_entry(id: usize, offset: usize) -> [u64; 2] {
+    [id as u64, offset as u64]
+}
+
+#[cfg(target_arch = "aarch64")]
+unsafe extern "C" {
+    /// TLSDESC resolver (x0 = 디스크립터 주소, 반환 x0 = 변수 주소 - `TPIDR_EL0`)
+    pub fn tls_desc_resolver();
+}
+
+/// resolver의 Rust 부분 (디스크립터 인자 → 변수 주소)
+#[cfg(target_arch = "aarch64")]
+#[unsafe(no_mangle)]
+extern "C" fn tls_desc_lookup(arg: u64) -> usize {
+    module_addr((arg >> 32) as usize, (arg & 0xffff_ffff) as usize)
+}
+
+// TLSDESC 호출 규약: x0 외 레지스터와 플래그를 보존해야 하므로 호출자 저장 레지스터를
+// 모두 저장하고 Rust로 넘어감 (커널은 softfloat라 FP/SIMD 레지스터는 쓰지 않음)
+#[cfg(target_arch = "aarch64")]
+core::arch::global_asm!(
+    r#"
+.section .text
+.global tls_desc_resolver
+.type tls_desc_resolver, %function
+tls_desc_resolver:
+    stp x29, x30, [sp, #-176]!
+    mov x29, sp
+    stp x1, x2, [sp, #16]
+    stp x3, x4, [sp, #32]
+    stp x5, x6, [sp, #48]
+    stp x7, x8, [sp, #64]
+    stp x9, x10, [sp, #80]
+    stp x11, x12, [sp, #96]
+    stp x13, x14, [sp, #112]
+    stp x15, x16, [sp, #128]
+    stp x17, x18, [sp, #144]
+    mrs x9, nzcv
+    str x9, [sp, #160]
+
+    // x0 = (id << 32) | offset
+    ldr x0, [x0, #8]
+    bl tls_desc_lookup
+    mrs x1, tpidr_el0
+    sub x0, x0, x1
+
+    ldr x9, [sp, #160]
+    msr nzcv, x9
+    ldp x1, x2, [sp, #16]
+    ldp x3, x4, [sp, #32]
+    ldp x5, x6, [sp, #48]
+    ldp x7, x8, [sp, #64]
+    ldp x9, x10, [sp, #80]
+    ldp x11, x12, [sp, #96]
+    ldp x13, x14, [sp, #112]
+    ldp x15, x16, [sp, #128]
+    ldp x17, x18, [sp, #144]
+    ldp x29, x30, [sp], #176
+    ret
+"#
+);
+
+/// `__tls_get_addr` 인자 (GOT의 TLS 항목)
+#[cfg(target_arch = "riscv64")]
+#[repr(C)]
+pub struct TlsIndex {
+    /// 모듈 TLS id
+    pub module: usize,
+    /// 블록 안 오프셋
+    pub offset: usize,
+}
+
+/// general-dynamic TLS 접근 (모듈의 `call __tls_get_addr`)
+#[cfg(target_arch = "riscv64")]
+#[unsafe(no_mangle)]
+pub extern "C" fn __tls_get_addr(index: &TlsIndex) -> usize {
+    module_addr(index.module, index.offset)
+}
+
+#[cfg(target_arch = "riscv64")]
+crate::export_symbol!(__tls_get_addr);
+
+// ============================================================================
+// 유저 실행 파일 TLS
+// ============================================================================
+
+/// 유저 실행 파일의 정적 TLS 블록 (ELF TLS Variant I)
+pub struct StaticTls {
+    block: Block,
+    /// TLS 블록 시작 - 스레드 포인터
+    offset: usize,
+}
+
+impl StaticTls {
+    /// TCB와 TLS 블록을 할당하고 초기값 복사 (메모리가 없으면 None)
+    pub fn new(image: &TlsImage) -> Option<Self> {
+        let offset = (TCB_SIZE + image.align - 1) & !(image.align - 1);
+        let layout = Layout::from_size_align(offset + image.size.max(1), image.align.max(16)).ok()?;
+        let block = Block::new(layout, offset, &image.init)?;
+        Some(Self { block, offset })
+    }
+
+    /// 스레드 포인터 값 (`TPIDR_EL0`/`tp`)
+    pub fn thread_pointer(&self) -> usize {
+        self.block.addr
+    }
+
+    /// TLS 블록 시작 주소
+    pub fn block_start(&self) -> usize {
+        self.block.addr + self.offset
+    }
+}
+
+/// 현재 스레드 포인터
+pub fn thread_pointer() -> usize {
+    let tp: usize;
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        core::arch::asm!("mrs {}, tpidr_el0", out(reg) tp);
+    }
+    #[cfg(target_arch = "riscv64")]
+    unsafe {
+        core::arch::asm!("mv {}, tp", out(reg) tp);
+    }
+    tp
+}
+
+/// 현재 스레드 포인터 설정 (다음 스위칭 때 컨텍스트에 저장됨)
+pub fn set_thread_pointer(tp: usize) {
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        core::arch::asm!("msr tpidr_el0, {}", in(reg) tp);
+    }
+    #[cfg(target_arch = "riscv64")]
+    unsafe {
+        core::arch::asm!("mv tp, {}", in(reg) tp);
+    }
+}
@@ -4,6 +4,7 @@
 
 use alloc::vec::Vec;
 use crate::kprintln;
+use super::tls::{self, StaticTls, TlsImage};
 
 /// 유저 스택 크기 (64KB)
 pub const USER_STACK_SIZE: usize = 64 * 1024;
@@ -24,6 +25,10 @@ pub struct UserProcess {
     pub stack_top: usize,
     /// 엔트리 포인트
     pub entry: usize,
+    /// 엔트리에 넘길 첫 인자 (x0 / a0)
+    pub arg: usize,
+    /// 정적 TLS 블록 (실행 파일에 `PT_TLS`가 있으면)
+    pub tls: Option<StaticTls>,
 }
 
 impl UserProcess {
@@ -43,8 +48,24 @@ impl UserProcess {
             user_stack,
             stack_top,
             entry,
+            arg: 0,
+            tls: None,
         }
     }
+
+    /// TLS가 있는 유저 프로세스 생성 (`LoadedExecutable::tls`, 블록을 할당하지 못하면 None)
+    pub fn with_tls(entry: usize, image: &TlsImage) -> Option<Self> {
+        let mut process = Self::new(entry);
+        let block = StaticTls::new(image)?;
+        kprintln!("[user] TLS block at {:#x} (tp={:#x})", block.block_start(), block.thread_pointer());
+        process.tls = Some(block);
+        Some(process)
+    }
+
+    /// 유저 모드에서 쓸 스레드 포인터 (TLS가 없으면 0)
+    fn thread_pointer(&self) -> usize {
+        self.tls.as_ref().map_or(0, StaticTls::thread_pointer)
+    }
     
     /// 유저 모드로 전환하여 실행
     /// 
@@ -53,6 +74,9 @@ impl UserProcess {
     #[cfg(target_arch = "aarch64")]
     pub unsafe fn run(&self) -> ! {
         kprintln!("[user] Switching to EL0...");
+
+        // TPIDR_EL0 = 스레드 포인터 (이후 컨텍스트 스위칭이 저장/복원)
+        tls::set_thread_pointer(self.thread_pointer());
         
         // EL0로 전환
         // SPSR_EL1: 0 = EL0t (EL0, SP_EL0 사용)
@@ -73,6 +97,7 @@ impl UserProcess {
                 "eret",
                 entry = in(reg) self.entry,
                 sp = in(reg) self.stack_top,
+                in("x0") self.arg,
                 options(noreturn)
             );
         }
@@ -82,30 +107,37 @@ impl UserProcess {
     #[cfg(target_arch = "riscv64")]
     pub unsafe fn run(&self) -> ! {
         kprintln!("[user] Switching to U-mode...");
+
+        // tp = 스레드 포인터 (트랩과 컨텍스트 스위칭이 저장/복원)
+        tls::set_thread_pointer(self.thread_pointer());
         
-        // M-mode에서 U-mode로 전환
-        // mstatus.MPP = 0 (U-mode)
-        // mepc = 유저 엔트리 포인트
+        // M-mode(또는 S-mode)에서 U-mode로 전환
+        // status.MPP/SPP = 0 (U-mode)
+        // epc = 유저 엔트리 포인트
+        use crate::arch::csr::{self, xret};
         unsafe {
             core::arch::asm!(
-                // mstatus.MPP 클리어 (U-mode로 설정)
-                "li t0, 0x1800",        // MPP 비트 마스크 (bits 11-12)
-                "csrc mstatus, t0",     // MPP = 0 (U-mode)
+                // status.MPP/SPP 클리어 (U-mode로 설정)
+                "csrc {status}, {pp}",
                 
-                // mstatus.MPIE 설정 (mret 후 인터럽트 활성화)
-                "li t0, 0x80",          // MPIE 비트
-                "csrs mstatus, t0",
+                // status.MPIE/SPIE 설정 (복귀 후 인터럽트 활성화)
+                "csrs {status}, {pie}",
                 
-                // mepc = 유저 엔트리
-                "csrw mepc, {entry}",
+                // epc = 유저 엔트리
+                "csrw {epc}, {entry}",
                 
                 // 스택 설정
                 "mv sp, {sp}",
                 
-                // U-mode로 전환
-                "mret",
+                // U-mode로 전환 (mret / sret)
+                xret!(),
                 entry = in(reg) self.entry,
                 sp = in(reg) self.stack_top,
+                pp = in(reg) csr::STATUS_PP,
+                pie = in(reg) csr::STATUS_PIE,
+                status = const csr::STATUS,
+                epc = const csr::EPC,
+                in("a0") self.arg,
                 options(noreturn)
             );
         }
@@ -166,6 +198,86 @@ pub unsafe extern "C" fn simple_user_program() -> ! {
     );
 }
 
+/// SIGSEGV를 `SIG_IGN`으로 설정한 뒤 x0 주소에 쓰는 유저 프로그램
+///
+/// 처리할 수 없는 폴트는 무시 설정과 관계없이 스레드를 끝내야 하므로 돌아오면 안 됩니다.
+/// rt_sigaction이 실패하면 exit(2), 쓰기가 성공하면 exit(1).
+#[cfg(target_arch = "aarch64")]
+#[unsafe(naked)]
+pub unsafe extern "C" fn ignore_segv_user_program() -> ! {
+    core::arch::naked_asm!(
+        "mov x19, x0",          // 쓸 주소
+
+        // rt_sigaction(SIGSEGV, &act, NULL, 8)
+        "mov x0, #11",          // SIGSEGV
+        "adr x1, 3f",           // act (sa_handler = SIG_IGN)
+        "mov x2, #0",           // oldact
+        "mov x3, #8",           // sigsetsize
+        "mov x8, #134",         // syscall: rt_sigaction
+        "svc #0",
+        "cbnz x0, 2f",
+
+        // 폴트가 나야 함
+        "str xzr, [x19]",
+
+        // exit(1)
+        "mov x0, #1",
+        "mov x8, #93",
+        "svc #0",
+
+        // exit(2)
+        "2: mov x0, #2",
+        "mov x8, #93",
+        "svc #0",
+
+        // 도달하면 안 됨
+        "1: wfi",
+        "b 1b",
+
+        // struct sigaction
+        ".balign 8",
+        "3: .quad 1, 0, 0, 0",
+    );
+}
+
+#[cfg(target_arch = "riscv64")]
+#[unsafe(naked)]
+pub unsafe extern "C" fn ignore_segv_user_program() -> ! {
+    core::arch::naked_asm!(
+        "mv s1, a0",            // 쓸 주소
+
+        // rt_sigaction(SIGSEGV, &act, NULL, 8)
+        "li a0, 11",            // SIGSEGV
+        "la a1, 3f",            // act (sa_handler = SIG_IGN)
+        "li a2, 0",             // oldact
+        "li a3, 8",             // sigsetsize
+        "li a7, 134",           // syscall: rt_sigaction
+        "ecall",
+        "bnez a0, 2f",
+
+        // 폴트가 나야 함
+        "sd zero, 0(s1)",
+
+        // exit(1)
+        "li a0, 1",
+        "li a7, 93",
+        "ecall",
+
+        // exit(2)
+        "2: li a0, 2",
+        "li a7, 93",
+        "ecall",
+
+        // 도달하면 안 됨
+        "1: wfi",
+        "j 1b",
+
+        // struct sigaction
+        ".balign 8",
+        "3: .quad 1, 0, 0, 0",
+    );
+}
+
 /// 유저 프로그램을 실행하는 커널 스레드 엔트리
 fn user_thread_entry() -> ! {
     let entry = simple_user_program as usize;
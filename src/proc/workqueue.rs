@@ -0,0 +1,306 @@
+//! 워크큐 (커널 워커 스레드에서 실행하는 작업)
+//!
+//! IRQ 핸들러, 태스크릿, 타이머 콜백에서 할 수 없는 일(잠들기, `Mutex`, 힙, 파일 I/O)을
+//! 스레드 문맥으로 넘깁니다. 서브시스템마다 전용 스레드를 띄우는 대신 공용 워커 스레드에
+//! 작업을 넣습니다.
+//!
+//! - 전역 큐: `kworker/u` 워커가 어느 CPU에서든 처리 (`queue_work`, `delayed_work`)
+//! - CPU별 큐: 그 CPU에 묶인 `kworker/<n>` 워커가 처리 (`queue_work_on`). 워커는 처음 쓸 때 만듦.
+//!   CPU를 오프라인으로 내리면(`hotplug`) 워커가 다른 CPU로 풀려 남은 작업을 마저 처리하고,
+//!   다시 올리면 그 CPU에 다시 묶임
+//!
+//! 큐마다 넣은 순서대로 하나씩 실행합니다. 지연 작업은 기한까지 큐에서 기다리고, 워커는 가장
+//! 이른 기한까지 `park_timeout()`으로 잠들므로 최대 한 틱 늦게 실행됩니다.
+//!
+//! 작업 함수는 워커 스레드에서 실행되므로 잠들 수 있습니다. 다만 같은 큐의 다음 작업은 앞
+//! 작업이 끝나야 시작하므로, 오래 막히는 일은 자기 스레드를 띄우는 편이 낫습니다.
+//!
+//! 넣기와 취소는 스레드 문맥에서만 부릅니다 (워커를 깨우는 `unpark()`가 `THREADS` 락을
+//! 잡음). IRQ 핸들러는 `softirq::Tasklet`을 씁니다.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use super::percpu::{self, CpuMask, MAX_CPUS, NO_THREAD};
+use super::Tid;
+use crate::kprintln;
+use crate::module::ledger::{self, ResourceKind};
+use crate::sync::{Mutex, Spinlock};
+
+/// 작업 ID (0은 쓰지 않음)
+pub type WorkId = usize;
+
+/// 전역(CPU에 묶이지 않은) 큐의 `QUEUES` 인덱스
+const UNBOUND: usize = MAX_CPUS;
+
+/// 작업 함수
+#[derive(Debug, Clone, Copy)]
+pub enum WorkFn {
+    /// 커널 함수
+    Kernel(fn(usize)),
+    /// 모듈 함수 (`kernel_queue_work`)
+    Module(extern "C" fn(usize)),
+}
+
+impl WorkFn {
+    fn call(self, arg: usize) {
+        match self {
+            WorkFn::Kernel(f) => f(arg),
+            WorkFn::Module(f) => f(arg),
+        }
+    }
+
+    /// 함수 주소 (소유 모듈 판별용)
+    pub fn addr(self) -> usize {
+        match self {
+            WorkFn::Kernel(f) => f as usize,
+            WorkFn::Module(f) => f as usize,
+        }
+    }
+}
+
+struct Work {
+    id: WorkId,
+    name: String,
+    /// 소유 모듈 (None = 커널)
+    module: Option<String>,
+    func: WorkFn,
+    arg: usize,
+    /// 이 시각(부팅 후 마이크로초) 이후에 실행 (0 = 바로)
+    due_us: u64,
+}
+
+struct Queue {
+    pending: Vec<Work>,
+    /// 실행 중인 작업 ID (0 = 없음)
+    running: WorkId,
+    /// 워커 TID (`NO_THREAD` = 아직 없음)
+    worker: Tid,
+    /// 실행을 마친 작업 수
+    completed: u64,
+}
+
+impl Queue {
+    const fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+            running: 0,
+            worker: NO_THREAD,
+            completed: 0,
+        }
+    }
+}
+
+/// CPU별 큐 + 전역 큐 (마지막)
+static QUEUES: [Spinlock<Queue>; MAX_CPUS + 1] = [const { Spinlock::new(Queue::new()) }; MAX_CPUS + 1];
+
+/// 워커 생성 직렬화 (같은 큐에 워커가 둘 생기지 않게)
+static SPAWN_LOCK: Mutex<()> = Mutex::new(());
+
+/// 다음 작업 ID
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// 전역 워커 시작 (프로세스 서브시스템 초기화 때)
+pub fn init() {
+    worker_for(UNBOUND);
+}
+
+/// 전역 큐에 작업 넣기
+pub fn queue_work(name: &str, func: fn(usize), arg: usize) -> WorkId {
+    queue(None, name, 0, WorkFn::Kernel(func), arg).unwrap_or(0)
+}
+
+/// `cpu`의 큐에 작업 넣기 (그 CPU에서 실행, 온라인이 아니면 None)
+pub fn queue_work_on(cpu: u32, name: &str, func: fn(usize), arg: usize) -> Option<WorkId> {
+    queue(Some(cpu), name, 0, WorkFn::Kernel(func), arg)
+}
+
+/// `delay_ms` 뒤 전역 큐에서 실행할 작업 넣기
+pub fn delayed_work(name: &str, delay_ms: u64, func: fn(usize), arg: usize) -> WorkId {
+    queue(None, name, delay_ms, WorkFn::Kernel(func), arg).unwrap_or(0)
+}
+
+/// 작업 넣기 (`cpu`가 None이면 전역 큐)
+///
+/// `delay_ms`가 0이면 앞 작업들 다음에 바로, 아니면 그만큼 뒤에 실행합니다. 함수가 모듈
+/// 코드면 소유자가 그 모듈로 표시됩니다 (`workqueue` 명령).
+pub fn queue(cpu: Option<u32>, name: &str, delay_ms: u64, func: WorkFn, arg: usize) -> Option<WorkId> {
+    let index = match cpu {
+        Some(cpu) if percpu::online_mask().contains(cpu) => cpu as usize,
+        Some(_) => return None,
+        None => UNBOUND,
+    };
+    let worker = worker_for(index);
+
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let due_us = if delay_ms == 0 {
+        0
+    } else {
+        crate::time::now_us().saturating_add(delay_ms.saturating_mul(1000))
+    };
+    let work = Work {
+        id,
+        name: String::from(name),
+        module: ledger::owner_of(func.addr()),
+        func,
+        arg,
+        due_us,
+    };
+    QUEUES[index].lock().pending.push(work);
+    super::unpark(worker);
+    Some(id)
+}
+
+/// 작업 취소
+///
+/// 돌아온 뒤에는 작업이 대기 중이지도 실행 중이지도 않습니다 (작업 안에서 자기 자신을
+/// 취소하면 기다리지 않음).
+///
+/// # Returns
+/// 대기 중이던 작업을 뺐으면 true (이미 실행했거나 없는 ID는 false)
+pub fn cancel(id: WorkId) -> bool {
+    let mut found = false;
+    for queue in QUEUES.iter() {
+        let mut q = queue.lock();
+        if let Some(pos) = q.pending.iter().position(|w| w.id == id) {
+            q.pending.remove(pos);
+            found = true;
+        }
+    }
+
+    let me = super::current_tid();
+    for queue in QUEUES.iter() {
+        loop {
+            let (running, worker) = {
+                let q = queue.lock();
+                (q.running, q.worker)
+            };
+            if running != id || me == Some(worker) {
+                break;
+            }
+            super::yield_now();
+        }
+    }
+    found
+}
+
+/// 작업이 실행을 마칠 때까지 대기 (대기 중이 아니면 바로 돌아옴)
+///
+/// 같은 큐의 작업 안에서 부르면 끝나지 않습니다.
+pub fn flush(id: WorkId) {
+    let busy = |q: &Queue| q.running == id || q.pending.iter().any(|w| w.id == id);
+    while QUEUES.iter().any(|q| busy(&q.lock())) {
+        super::yield_now();
+    }
+}
+
+/// 큐의 워커 TID (없으면 만듦)
+fn worker_for(index: usize) -> Tid {
+    let tid = QUEUES[index].lock().worker;
+    if tid != NO_THREAD {
+        return tid;
+    }
+
+    let _guard = SPAWN_LOCK.lock();
+    let tid = QUEUES[index].lock().worker;
+    if tid != NO_THREAD {
+        return tid;
+    }
+    let name = if index == UNBOUND {
+        String::from("kworker/u")
+    } else {
+        format!("kworker/{}", index)
+    };
+    let tid = super::spawn(&name, worker_main);
+    if index != UNBOUND {
+        let _ = super::set_affinity(tid, CpuMask::single(index as u32));
+    }
+    QUEUES[index].lock().worker = tid;
+    tid
+}
+
+/// 다시 온라인이 된 CPU의 워커를 그 CPU에 다시 묶음 (`hotplug::cpu_up`)
+pub(super) fn rebind(cpu: u32) {
+    let tid = QUEUES[cpu as usize % MAX_CPUS].lock().worker;
+    if tid != NO_THREAD {
+        let _ = super::set_affinity(tid, CpuMask::single(cpu));
+    }
+}
+
+/// 워커 스레드: 자기 큐에서 기한이 지난 작업을 넣은 순서대로 실행
+fn worker_main() -> ! {
+    // 생성한 쪽이 TID를 기록할 때까지 기다림
+    let me = super::current_tid().unwrap_or(NO_THREAD);
+    let index = loop {
+        if let Some(index) = QUEUES.iter().position(|q| q.lock().worker == me) {
+            break index;
+        }
+        super::yield_now();
+    };
+    let queue = &QUEUES[index];
+
+    loop {
+        let now = crate::time::now_us();
+        let next = {
+            let mut q = queue.lock();
+            match q.pending.iter().position(|w| w.due_us <= now) {
+                Some(pos) => {
+                    let work = q.pending.remove(pos);
+                    q.running = work.id;
+                    Ok(work)
+                }
+                None => Err(q.pending.iter().map(|w| w.due_us).min()),
+            }
+        };
+
+        match next {
+            Ok(work) => {
+                work.func.call(work.arg);
+                {
+                    let mut q = queue.lock();
+                    q.running = 0;
+                    q.completed += 1;
+                }
+                // 모듈 코드를 벗어났으므로 장부에서 제거
+                if let WorkFn::Module(_) = work.func {
+                    ledger::release(ResourceKind::Work, work.id);
+                }
+            }
+            Err(Some(due)) => {
+                super::park_timeout(due.saturating_sub(now));
+            }
+            Err(None) => {
+                super::park();
+            }
+        }
+    }
+}
+
+/// `workqueue` 명령: 워커와 대기 중인 작업
+pub fn dump() {
+    let now = crate::time::now_us();
+    kprintln!("{:<11} {:>5} {:>8} {:>10}  {}", "WORKER", "TID", "PENDING", "COMPLETED", "RUNNING");
+    for (index, queue) in QUEUES.iter().enumerate() {
+        let q = queue.lock();
+        if q.worker == NO_THREAD {
+            continue;
+        }
+        let name = if index == UNBOUND { String::from("kworker/u") } else { format!("kworker/{}", index) };
+        let running = if q.running == 0 { String::from("-") } else { format!("#{}", q.running) };
+        kprintln!("{:<11} {:>5} {:>8} {:>10}  {}", name, q.worker, q.pending.len(), q.completed, running);
+        for w in q.pending.iter() {
+            let due = if w.due_us > now {
+                format!("in {} ms", (w.due_us - now).div_ceil(1000))
+            } else {
+                String::from("ready")
+            };
+            let owner = match &w.module {
+                Some(m) => format!("module {}", m),
+                None => String::from("kernel"),
+            };
+            kprintln!("  #{:<6} {:<20} {:<12} {}", w.id, w.name, due, owner);
+        }
+    }
+}
@@ -0,0 +1,817 @@
+//! 디버깅 인프라 셀프테스트 (벤치마크, 결함 주입, GDB 스텁, 크래시 덤프, sysctl, 패닉, kprobe, 트레이스)
+
+use super::module::RelocTestObject;
+use super::SelfTest;
+use crate::{arch, bench, block, debug, drivers, fs, log, mm, module, power, proc, syscall, sysctl, time, trace, watchdog};
+use crate::kprintln;
+
+/// 벤치마크 셀프테스트: 백분위수 계산, 측정 횟수, 모듈 API, 모든 벤치마크 짧게 실행
+pub fn bench() {
+    use bench::BenchStats;
+
+    let mut test = SelfTest::new("bench");
+
+
+    // 1..=100을 섞어 넣어도 정렬 후 nearest-rank
+    let mut samples: alloc::vec::Vec<u64> = (1..=100).rev().collect();
+    let stats = BenchStats::from_samples(&mut samples);
+    test.check(
+        "percentiles of 1..=100",
+        stats.ops == 100 && stats.total_ns == 5050 && stats.p50_ns == 50 && stats.p90_ns == 90 && stats.p99_ns == 99 && stats.max_ns == 100,
+    );
+    let mut single = [7u64];
+    let stats = BenchStats::from_samples(&mut single);
+    test.check("single sample", stats.p50_ns == 7 && stats.p99_ns == 7 && stats.max_ns == 7);
+    test.check("empty samples", BenchStats::from_samples(&mut []) == BenchStats::default());
+    test.check(
+        "ops/sec",
+        BenchStats { ops: 1000, total_ns: 2_000_000, ..Default::default() }.ops_per_sec() == 500_000,
+    );
+
+    let mut calls = 0usize;
+    let stats = bench::measure(50, |_| calls += 1);
+    test.check("measure counts warm-up separately", stats.ops == 50 && calls == 55);
+    let stats = bench::measure(20, |_| {
+        let start = time::clock::monotonic_ns();
+        while time::clock::monotonic_ns() - start < 20_000 {}
+    });
+    test.check("20us op measured", stats.p50_ns >= 20_000 && stats.p50_ns < 10_000_000);
+
+    static CALLS: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+    extern "C" fn module_op(arg: usize) {
+        CALLS.fetch_add(arg, core::sync::atomic::Ordering::Relaxed);
+    }
+    let mut out = BenchStats::default();
+    let name = "selftest.op";
+    let ret = bench::kernel_bench_run(name.as_ptr(), name.len(), 100, module_op, 2, &mut out);
+    test.check(
+        "kernel_bench_run fills stats",
+        ret == 0 && out.ops == 100 && CALLS.load(core::sync::atomic::Ordering::Relaxed) == 2 * 110,
+    );
+    test.check(
+        "kernel_bench_run rejects zero iterations",
+        bench::kernel_bench_run(name.as_ptr(), name.len(), 0, module_op, 0, core::ptr::null_mut()) == -1,
+    );
+
+    bench::print_header();
+    for name in bench::BENCHES {
+        let ok = bench::run(name, 100).is_ok();
+        test.check(name, ok);
+    }
+    test.check("/.bench removed", fs::lookup_path("/.bench").is_err());
+
+    test.finish();
+}
+
+/// 결함 주입 셀프테스트: every/prob/times/tid 규칙, 힙 null, FAT32 마운트와 모듈 로드 오류 경로
+pub fn failinject() {
+    use alloc::sync::Arc;
+    use block::ramdisk::RamDisk;
+    use block::{BlockDevice, BlockError};
+    use debug::failinject::{self, Config, Point, Stats};
+
+    let mut test = SelfTest::new("failinject");
+
+    let me = proc::current_tid();
+    let only_me = |config: Config| Config { tid: me, ..config };
+
+    // 셋째 호출마다
+    failinject::configure(Point::Frame, only_me(Config { interval: 3, ..Config::default() }));
+    let frames: [Option<usize>; 6] = core::array::from_fn(|_| mm::page::alloc_frame());
+    failinject::disable(Point::Frame);
+    let failed: alloc::vec::Vec<usize> = (0..6).filter(|&i| frames[i].is_none()).collect();
+    for frame in frames.into_iter().flatten() {
+        unsafe { mm::page::free_frame(frame) };
+    }
+    test.check("frame: every 3rd call fails", failed == [2, 5]);
+    test.check(
+        "frame: stats count calls and failures",
+        failinject::stats(Point::Frame) == Stats { calls: 6, injected: 2 },
+    );
+    test.check("disabled point passes", mm::page::alloc_frame().map(|f| unsafe { mm::page::free_frame(f) }).is_some());
+
+    // 남은 횟수를 다 쓰면 다시 성공
+    failinject::configure(Point::Frame, only_me(Config { times: Some(1), ..Config::default() }));
+    let first = mm::page::alloc_frames(2);
+    let second = mm::page::alloc_frames(2);
+    failinject::disable(Point::Frame);
+    if let Some(addr) = second {
+        unsafe { mm::page::free_frames(addr, 2) };
+    }
+    test.check("frame: times 1 fails once", first.is_none() && second.is_some());
+
+    // 다른 스레드만 대상이면 영향 없음
+    failinject::configure(Point::Frame, Config { tid: Some(me.unwrap_or(0) + 100_000), ..Config::default() });
+    let frame = mm::page::alloc_frame();
+    failinject::disable(Point::Frame);
+    if let Some(frame) = frame {
+        unsafe { mm::page::free_frame(frame) };
+    }
+    test.check(
+        "frame: other tid unaffected",
+        frame.is_some() && failinject::stats(Point::Frame).calls == 0,
+    );
+
+    // 확률: 같은 시드면 같은 결과, 대략 비율대로
+    let run = || {
+        failinject::set_seed(42);
+        failinject::configure(Point::Frame, only_me(Config { probability: 25, ..Config::default() }));
+        let mut pattern = 0u128;
+        for i in 0..128 {
+            if let Some(frame) = mm::page::alloc_frame() {
+                unsafe { mm::page::free_frame(frame) };
+            } else {
+                pattern |= 1 << i;
+            }
+        }
+        failinject::disable(Point::Frame);
+        pattern
+    };
+    let (a, b) = (run(), run());
+    test.check("frame: prob repeatable with same seed", a == b);
+    test.check("frame: prob 25% roughly", (16..=48).contains(&a.count_ones()));
+
+    // 힙: 이 스레드의 다음 할당 하나만 (판정 사이에 출력하지 않음)
+    let layout = core::alloc::Layout::from_size_align(64, 8).unwrap();
+    failinject::configure(Point::Heap, only_me(Config { times: Some(1), ..Config::default() }));
+    let failed = unsafe { alloc::alloc::alloc(layout) };
+    let ok = unsafe { alloc::alloc::alloc(layout) };
+    failinject::disable(Point::Heap);
+    if !ok.is_null() {
+        unsafe { alloc::alloc::dealloc(ok, layout) };
+    }
+    test.check("heap: alloc returns null once", failed.is_null() && !ok.is_null());
+
+    // 블록 읽기: 둘째마다, 쓰기는 영향 없음
+    let disk = Arc::new(RamDisk::new_default("failinject", 64 * 1024));
+    let mut buf = [0u8; 512];
+    failinject::configure(Point::BlockRead, only_me(Config { interval: 2, ..Config::default() }));
+    let reads = [disk.read_block(0, &mut buf), disk.read_block(1, &mut buf)];
+    let write = disk.write_block(0, &buf);
+    failinject::disable(Point::BlockRead);
+    test.check(
+        "block: every 2nd read fails with IoError",
+        reads == [Ok(()), Err(BlockError::IoError)] && write.is_ok(),
+    );
+
+    // FAT32: 부트 섹터 읽기 실패는 IoError (실패하지 않으면 형식 오류)
+    failinject::configure(Point::BlockRead, only_me(Config::default()));
+    let injected = fs::fat32::mount_fat32(disk.clone()).err();
+    failinject::disable(Point::BlockRead);
+    let plain = fs::fat32::mount_fat32(disk).err();
+    test.check(
+        "fat32: mount reports I/O error",
+        injected == Some(fs::VfsError::IoError) && plain == Some(fs::VfsError::InvalidFormat),
+    );
+
+    // 모듈 로더: 페이지 할당 실패 뒤 남는 모듈 없음, 다시 로드 가능
+    let obj = RelocTestObject {
+        text: alloc::vec![0; 8],
+        data: alloc::vec![0; 16],
+        syms: alloc::vec![("fi_fn", 0x12, RelocTestObject::TEXT, 0)],
+        relas: alloc::vec![],
+    };
+    failinject::configure(Point::Frame, only_me(Config::default()));
+    let result = obj.load("fitest");
+    failinject::disable(Point::Frame);
+    test.check(
+        "module: load fails with OutOfMemory",
+        matches!(result, Err(module::ModuleError::OutOfMemory)) && module::ModuleLoader::info("fitest").is_none(),
+    );
+    drop(result);
+    let loaded = obj.load("fitest").is_ok();
+    test.check("module: loads after injection stops", loaded && module::ModuleLoader::unload("fitest").is_ok());
+
+    failinject::disable_all();
+    test.check("all points off", failinject::POINTS.iter().all(|&p| !failinject::is_enabled(p)));
+
+    test.finish();
+}
+
+/// GDB 스텁이 브레이크포인트를 심는 대상 (호출하지 않음)
+#[inline(never)]
+fn gdbstub_selftest_target(x: usize) -> usize {
+    x.wrapping_mul(3) ^ 0x5a
+}
+
+/// GDB 스텁 셀프테스트: 패킷 틀, 레지스터/메모리 패킷, target.xml, 브레이크포인트 패치
+///
+/// 포트에 GDB를 붙이지 않고 `handle_packet`에 패킷을 직접 넣습니다.
+pub fn gdbstub() {
+    use alloc::vec::Vec;
+    use debug::gdbstub::{self, Frame, PacketReader, Port, Resume};
+
+    let mut test = SelfTest::new("gdbstub");
+
+    // 패킷 틀
+    test.check("checksum", gdbstub::checksum(b"OK") == 0x9a && gdbstub::checksum(b"") == 0);
+    test.check("encode", gdbstub::encode_packet(b"OK") == b"$OK#9a");
+    let mut reader = PacketReader::new();
+    let mut feed = |bytes: &[u8]| bytes.iter().filter_map(|&b| reader.feed(b)).collect::<Vec<_>>();
+    test.check("reader: noise before '$' ignored", feed(b"+-\x03$g#67") == [Ok(b"g".to_vec())]);
+    test.check("reader: bad checksum rejected", feed(b"$g#00") == [Err(())]);
+    test.check("reader: restarts on '$'", feed(b"$m1$?#3f") == [Ok(b"?".to_vec())]);
+
+    // 포트 이름
+    test.check("port: parse", Port::parse("ttyS1") == Some(Port::Serial(1)) && Port::parse("hvc0") == Some(Port::Hvc));
+    test.check("port: console rejected", gdbstub::set_port(Some(Port::Serial(drivers::serial::console_index()))).is_err());
+
+    let mut regs = arch::gdb::zeroed_regs();
+    let sp = 0x1234_5678_9abc_def0;
+    let mut frame = Frame { regs: &mut regs, sp };
+    let run = |frame: &mut Frame, packet: &[u8]| {
+        let mut reply = Vec::new();
+        let resume = gdbstub::handle_packet(frame, packet, &mut reply);
+        (resume, reply)
+    };
+
+    test.check("'?' stop reply", run(&mut frame, b"?").1 == b"S05");
+
+    // 레지스터: 1번에 쓰고 p/g로 읽기, sp는 프레임 값
+    let (_, reply) = run(&mut frame, b"P1=efcdab8967452301");
+    test.check(
+        "'P' writes register",
+        reply == b"OK" && arch::gdb::read_reg(frame.regs, sp, 1) == Some(0x0123_4567_89ab_cdef),
+    );
+    test.check("'p' reads register (little endian)", run(&mut frame, b"p1").1 == b"efcdab8967452301");
+    #[cfg(target_arch = "aarch64")]
+    let sp_reg = b"p1f";
+    #[cfg(target_arch = "riscv64")]
+    let sp_reg = b"p2";
+    test.check("'p' sp is the pre-trap sp", run(&mut frame, sp_reg).1 == b"f0debc9a78563412");
+    test.check("'p' unknown register", run(&mut frame, b"p100").1 == b"E01");
+    let (_, all) = run(&mut frame, b"g");
+    let expected_len: usize = (0..arch::gdb::NUM_REGS).map(|n| arch::gdb::reg_size(n).unwrap_or(0) * 2).sum();
+    test.check("'g' covers every register", all.len() == expected_len && all[16..32] == *b"efcdab8967452301");
+    let mut write_all = b"G".to_vec();
+    write_all.extend_from_slice(&all);
+    test.check("'G' writes back unchanged", run(&mut frame, &write_all).1 == b"OK" && run(&mut frame, b"g").1 == all);
+
+    // 메모리
+    let mut buf = [0x11u8, 0x22, 0x33, 0x44];
+    let addr = buf.as_mut_ptr() as usize;
+    let read = alloc::format!("m{:x},4", addr);
+    test.check("'m' reads memory", run(&mut frame, read.as_bytes()).1 == b"11223344");
+    let write = alloc::format!("M{:x},2:abcd", addr + 1);
+    let reply = run(&mut frame, write.as_bytes()).1;
+    test.check(
+        "'M' writes memory",
+        reply == b"OK" && unsafe { core::ptr::read_volatile(&buf) } == [0x11, 0xab, 0xcd, 0x44],
+    );
+    test.check("'M' length mismatch", run(&mut frame, alloc::format!("M{:x},4:ab", addr).as_bytes()).1 == b"E01");
+    test.check("'m' malformed", run(&mut frame, b"mzz").1 == b"E01");
+
+    // 질의
+    let (_, supported) = run(&mut frame, b"qSupported:multiprocess+;swbreak+");
+    test.check("qSupported", supported.windows(20).any(|w| w == b"qXfer:features:read+"));
+    let xml = arch::gdb::target_xml();
+    let (_, first) = run(&mut frame, b"qXfer:features:read:target.xml:0,20");
+    let (_, rest) = run(&mut frame, alloc::format!("qXfer:features:read:target.xml:20,{:x}", xml.len()).as_bytes());
+    test.check(
+        "target.xml in two chunks",
+        first[0] == b'm' && rest[0] == b'l' && [&first[1..], &rest[1..]].concat() == xml.as_bytes(),
+    );
+    test.check("unknown packet -> empty", run(&mut frame, b"vMustReplyEmpty").1.is_empty());
+
+    // 실행 제어
+    test.check("'c' continues", run(&mut frame, b"c").0 == Resume::Continue);
+    let target = gdbstub_selftest_target as fn(usize) -> usize as usize;
+    let (resume, _) = run(&mut frame, alloc::format!("s{:x}", target).as_bytes());
+    test.check("'s addr' steps from addr", resume == Resume::Step && arch::gdb::pc(frame.regs) == target);
+
+    // 브레이크포인트: 명령어를 바꾸고 되돌림
+    let orig = arch::kprobe::read_insn(target);
+    let (_, reply) = run(&mut frame, alloc::format!("Z0,{:x},4", target).as_bytes());
+    test.check(
+        "Z0 patches the instruction",
+        reply == b"OK" && gdbstub::breakpoints() == [target] && arch::kprobe::read_insn(target) != orig,
+    );
+    let (_, reply) = run(&mut frame, alloc::format!("Z0,{:x},4", target).as_bytes());
+    test.check("Z0 again is idempotent", reply == b"OK" && gdbstub::breakpoints().len() == 1);
+    test.check("Z1 (hardware) unsupported", run(&mut frame, alloc::format!("Z1,{:x},4", target).as_bytes()).1.is_empty());
+    let (_, reply) = run(&mut frame, alloc::format!("z0,{:x},4", target).as_bytes());
+    test.check(
+        "z0 restores the instruction",
+        reply == b"OK" && gdbstub::breakpoints().is_empty() && arch::kprobe::read_insn(target) == orig,
+    );
+    test.check("z0 unknown address", run(&mut frame, alloc::format!("z0,{:x},4", target).as_bytes()).1 == b"E01");
+
+    let _ = run(&mut frame, alloc::format!("Z0,{:x},4", target).as_bytes());
+    let (resume, reply) = run(&mut frame, b"D");
+    test.check(
+        "'D' detaches and clears breakpoints",
+        resume == Resume::Detach && reply == b"OK" && gdbstub::breakpoints().is_empty(),
+    );
+    test.check("'D' restores the instruction", arch::kprobe::read_insn(target) == orig);
+    test.check("target still runs", gdbstub_selftest_target(2) == 0x5c);
+
+    test.finish();
+}
+
+pub fn crashdump() {
+    use alloc::sync::Arc;
+    use block::partition::Partition;
+    use block::ramdisk::RamDisk;
+    use block::{BlockDevice, BlockError};
+    use debug::crashdump::{self, DUMP_SIZE};
+
+    let mut test = SelfTest::new("crashdump");
+
+    test.check(
+        "spec parsing",
+        crashdump::parse_spec("vda@2048") == Some(("vda", 2048))
+            && crashdump::parse_spec("/dev/vda2") == Some(("vda2", 0))
+            && crashdump::parse_spec("vda@x").is_none()
+            && crashdump::parse_spec("@8").is_none(),
+    );
+
+    // 만들고 다시 읽기
+    kprintln!("crashdump selftest marker");
+    let cpu = proc::percpu::get_cpu_id() as usize;
+    let regs = crate::panic::caller_regs();
+    let mut buf = alloc::vec![0u8; DUMP_SIZE];
+    let location = core::panic::Location::caller();
+    let fp = trace::backtrace::current_fp();
+    let len = crashdump::build(&mut buf, &"selftest dump", Some(location), cpu, None, fp, &regs);
+    let dump = crashdump::parse(&buf).ok().flatten();
+    test.check("parse: dump found", dump.is_some() && len <= DUMP_SIZE);
+    if let Some(dump) = &dump {
+        test.check(
+            "message and location",
+            dump.message.starts_with("selftest dump") && dump.message.contains(location.file()),
+        );
+        test.check("cpu and tid", dump.cpu as usize == cpu && dump.tid == proc::current_tid());
+        test.check(
+            "caller registers",
+            dump.registers.len() == regs.names.len()
+                && dump.registers.iter().any(|(name, value)| name == "sp" && *value == regs.sp() as u64),
+        );
+        test.check("backtrace recorded", !dump.backtrace.is_empty() && dump.exception_pc.is_none());
+        let me = dump.threads.as_ref().and_then(|t| t.iter().find(|t| Some(t.tid) == proc::current_tid()));
+        test.check("current thread listed", me.is_some_and(|t| t.running == Some(cpu as u32)));
+        test.check("stack from sp", dump.stack.as_ref().is_some_and(|(base, bytes)| *base == regs.sp() as u64 && !bytes.is_empty()));
+        test.check(
+            "log tail has the marker",
+            dump.log.as_ref().is_some_and(|log| log.contains("crashdump selftest marker")),
+        );
+    }
+
+    let mut corrupt = buf.clone();
+    corrupt[len - 1] ^= 0xFF;
+    test.check("checksum mismatch detected", crashdump::parse(&corrupt).is_err());
+    test.check("no magic means no dump", matches!(crashdump::parse(&alloc::vec![0u8; DUMP_SIZE]), Ok(None)));
+
+    // 예외로 난 패닉: 레지스터 대신 예외 pc
+    let pc = crashdump as fn() as usize;
+    crashdump::build(&mut buf, &"exception", None, cpu, Some(pc), fp, &regs);
+    let dump = crashdump::parse(&buf).ok().flatten();
+    test.check(
+        "exception: pc recorded, no caller registers",
+        dump.is_some_and(|d| d.exception_pc == Some(pc as u64) && d.registers.is_empty() && d.backtrace.len() >= 2),
+    );
+
+    // 파티션을 거친 폴링 쓰기
+    let disk = Arc::new(RamDisk::new_default("crashdump", 2 * DUMP_SIZE));
+    let part = Partition::new("crashdump1", disk.clone(), 64, 128);
+    let len = crashdump::build(&mut buf, &"written", None, cpu, None, fp, &regs);
+    let padded = len.div_ceil(512) * 512;
+    test.check("panic_write through partition", part.panic_write(0, &buf[..padded]).is_ok());
+    test.check(
+        "dump lands at the partition offset",
+        disk.read_all()[64 * 512..64 * 512 + 8] == *b"KRSCRASH"
+            && crashdump::read_from(&part, 0).ok().flatten().is_some_and(|d| d.message == "written"),
+    );
+    test.check("read_from: region past end rejected", crashdump::read_from(&part, 1).is_err());
+    test.check("panic_write: out of range", part.panic_write(128, &buf[..512]) == Err(BlockError::InvalidBlock));
+    let mut ro = RamDisk::new_default("crashdump-ro", DUMP_SIZE);
+    ro.set_read_only(true);
+    test.check("panic_write: read-only", ro.panic_write(0, &buf[..512]) == Err(BlockError::ReadOnly));
+
+    // 등록한 디바이스로 save / read / clear
+    let previous = crashdump::target();
+    block::register_device("crashdump", disk.clone());
+    test.check("dev: set region", crashdump::set_target(Some("crashdump@8")).is_ok());
+    test.check("dev: unknown device rejected", crashdump::set_target(Some("nosuchdev")).is_err());
+    crashdump::save(&"saved", None, cpu, None, fp, &regs);
+    test.check("save then read", crashdump::read().ok().flatten().is_some_and(|d| d.message == "saved"));
+    test.check("clear", crashdump::clear().is_ok() && matches!(crashdump::read(), Ok(None)));
+    let _ = crashdump::set_target(previous.as_deref());
+    block::unregister_device("crashdump");
+
+    test.finish();
+}
+
+/// sysctl 셀프테스트: 등록/조회, 값 파싱과 범위, 읽기 전용, `/proc/sys` 경로, 서브시스템 노브
+pub fn sysctl() {
+    use core::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+    use sysctl::{Kind, SysctlError, Tunable, Value};
+
+    static INT: AtomicI64 = AtomicI64::new(7);
+    static FLAG: AtomicBool = AtomicBool::new(false);
+    static TEST: [Tunable; 4] = [
+        Tunable {
+            name: "selftest.int",
+            desc: "test integer",
+            kind: Kind::Int { min: -10, max: 100 },
+            get: || Value::Int(INT.load(Ordering::Relaxed)),
+            set: Some(|v| {
+                INT.store(sysctl::int(&v), Ordering::Relaxed);
+                Ok(())
+            }),
+        },
+        Tunable {
+            name: "selftest.flag",
+            desc: "test bool",
+            kind: Kind::Bool,
+            get: || Value::Bool(FLAG.load(Ordering::Relaxed)),
+            set: Some(|v| {
+                FLAG.store(sysctl::boolean(&v), Ordering::Relaxed);
+                Ok(())
+            }),
+        },
+        Tunable {
+            name: "selftest.choice",
+            desc: "test string",
+            kind: Kind::Str,
+            get: || Value::Str(alloc::string::String::from("a")),
+            set: Some(|v| if sysctl::string(&v) == "a" { Ok(()) } else { Err("expected a") }),
+        },
+        Tunable {
+            name: "selftest.ro",
+            desc: "test read-only",
+            kind: Kind::Int { min: 0, max: 0 },
+            get: || Value::Int(0),
+            set: None,
+        },
+    ];
+
+    let mut test = SelfTest::new("sysctl");
+
+    for t in &TEST {
+        sysctl::unregister(t.name);
+    }
+    sysctl::register_all(&TEST);
+    test.check("register and get", sysctl::get("selftest.int") == Ok(Value::Int(7)));
+    test.check("duplicate rejected", sysctl::register(&TEST[0]) == Err(SysctlError::Exists));
+    test.check("unknown key", sysctl::get("selftest.nope") == Err(SysctlError::NotFound));
+
+    test.check(
+        "int: decimal, negative, hex",
+        sysctl::set("selftest.int", "42").is_ok()
+            && INT.load(Ordering::Relaxed) == 42
+            && sysctl::set("selftest.int", " -3 ").is_ok()
+            && INT.load(Ordering::Relaxed) == -3
+            && sysctl::set("selftest.int", "0x10").is_ok()
+            && INT.load(Ordering::Relaxed) == 16,
+    );
+    test.check(
+        "int: range and format errors leave the value",
+        sysctl::set("selftest.int", "101") == Err(SysctlError::OutOfRange { min: -10, max: 100 })
+            && matches!(sysctl::set("selftest.int", "ten"), Err(SysctlError::Invalid(_)))
+            && INT.load(Ordering::Relaxed) == 16,
+    );
+    test.check(
+        "bool: on/off, 1/0, display 0/1",
+        sysctl::set("selftest.flag", "on").is_ok()
+            && FLAG.load(Ordering::Relaxed)
+            && sysctl::get("selftest.flag").map(|v| alloc::format!("{}", v)).as_deref() == Ok("1")
+            && sysctl::set("selftest.flag", "0").is_ok()
+            && !FLAG.load(Ordering::Relaxed)
+            && sysctl::set("selftest.flag", "maybe").is_err(),
+    );
+    test.check(
+        "string: setter validates",
+        sysctl::set("selftest.choice", "a").is_ok()
+            && sysctl::set("selftest.choice", "b") == Err(SysctlError::Invalid("expected a")),
+    );
+    test.check("read-only", sysctl::set("selftest.ro", "0") == Err(SysctlError::ReadOnly));
+    test.check(
+        "/proc/sys path form",
+        sysctl::get("selftest/int") == Ok(Value::Int(16)) && sysctl::get("/proc/sys/selftest/int") == Ok(Value::Int(16)),
+    );
+    test.check(
+        "list by prefix",
+        sysctl::list("selftest").len() == 4
+            && sysctl::list("selftest.").len() == 4
+            && sysctl::list("selftes").is_empty()
+            && sysctl::list("selftest.int").len() == 1,
+    );
+    for t in &TEST {
+        sysctl::unregister(t.name);
+    }
+    test.check("unregister", sysctl::find("selftest.int").is_none() && sysctl::list("selftest").is_empty());
+
+    // 서브시스템 노브: 바꾸고 되돌림
+    let quantum = sysctl::get("sched.quantum");
+    test.check(
+        "sched.quantum",
+        sysctl::set("sched.quantum", "3").is_ok()
+            && sysctl::get("sched.quantum") == Ok(Value::Int(3))
+            && sysctl::set("sched.quantum", "0").is_err(),
+    );
+    if let Ok(v) = quantum {
+        let _ = sysctl::set("sched.quantum", &alloc::format!("{}", v));
+    }
+    let level = log::get_log_level();
+    test.check(
+        "log.level",
+        sysctl::set("log.level", "debug").is_ok()
+            && log::get_log_level() == log::LogLevel::Debug
+            && sysctl::get("log.level") == Ok(Value::Str(alloc::string::String::from("debug")))
+            && sysctl::set("log.level", "loud").is_err(),
+    );
+    log::set_log_level(level);
+    let action = crate::panic::action();
+    test.check(
+        "panic.action",
+        sysctl::set("panic.action", "5").is_ok()
+            && crate::panic::action() == crate::panic::PanicAction::Reboot { delay_s: 5 }
+            && sysctl::get("panic.action") == Ok(Value::Str(alloc::string::String::from("5"))),
+    );
+    crate::panic::set_action(action);
+    test.check(
+        "other subsystems registered",
+        ["time.nohz", "module.verify", "module.timeout_ms", "fat32.cache_sectors", "sched.balance_interval"]
+            .iter()
+            .all(|name| sysctl::find(name).is_some()),
+    );
+
+    test.finish();
+}
+
+/// 패닉 처리 셀프테스트 (실제로 패닉하지 않고 부품만 확인)
+pub fn panic() {
+    use crate::panic::PanicAction;
+
+    let mut test = SelfTest::new("panic");
+    kprintln!("  on panic: {}, log lines: {}", crate::panic::action(), crate::panic::log_lines());
+
+    test.check(
+        "panic= parsing",
+        PanicAction::from_str("halt") == Some(PanicAction::Halt)
+            && PanicAction::from_str("reboot") == Some(PanicAction::Reboot { delay_s: 0 })
+            && PanicAction::from_str("0") == Some(PanicAction::Halt)
+            && PanicAction::from_str("-1") == Some(PanicAction::Reboot { delay_s: 0 })
+            && PanicAction::from_str("10") == Some(PanicAction::Reboot { delay_s: 10 })
+            && PanicAction::from_str("soon").is_none(),
+    );
+
+    let addr = module::symbol::kernel_print as *const () as usize + 8;
+    let mut resolved = None;
+    module::symbol::symbolize(addr, &mut |name, offset, module| {
+        resolved = Some((name == "kernel_print", offset, module.is_none()));
+    });
+    test.check("exported symbol + offset", resolved == Some((true, 8, true)));
+
+    let mut frames = 0;
+    let mut nonzero = true;
+    trace::backtrace::walk(trace::backtrace::current_fp(), 8, |ret| {
+        frames += 1;
+        nonzero &= ret != 0;
+    });
+    kprintln!("  frames from selftest: {}", frames);
+    test.check("frame-pointer walk reaches callers", frames >= 2 && nonzero);
+
+    kprintln!("selftest panic marker");
+    let mut last = alloc::string::String::new();
+    let locked = !log::tail(1, |line| last = alloc::string::String::from_utf8_lossy(line).into_owned());
+    test.check("log tail", !locked && last.contains("selftest panic marker"));
+
+    test.finish();
+}
+
+/// reboot 셀프테스트: 잘못된 SYS_REBOOT 호출이 시스템을 건드리지 않고 거부되는지 확인
+pub fn reboot() {
+    use power::PowerAction;
+
+    let mut test = SelfTest::new("reboot");
+
+    // LINUX_REBOOT_MAGIC1/2, LINUX_REBOOT_CMD_POWER_OFF
+    let reboot = |magic1: usize, magic2: usize, cmd: usize| {
+        syscall::syscall_handler(syscall::SYS_REBOOT, [magic1, magic2, cmd, 0, 0, 0])
+    };
+    test.check("bad magic1 is EINVAL", reboot(0xdead_beef, 672_274_793, 0x4321_fedc) == syscall::errno::EINVAL);
+    test.check("bad magic2 is EINVAL", reboot(0xfee1_dead, 1, 0x4321_fedc) == syscall::errno::EINVAL);
+    // LINUX_REBOOT_CMD_CAD_ON (지원하지 않음)
+    test.check("unknown cmd is EINVAL", reboot(0xfee1_dead, 85_072_278, 0x89ab_cdef) == syscall::errno::EINVAL);
+
+    test.check(
+        "shutdown messages",
+        alloc::format!("{}", PowerAction::Restart) == "Restarting system"
+            && alloc::format!("{}", PowerAction::PowerOff) == "Power down"
+            && alloc::format!("{}", PowerAction::Halt) == "System halted",
+    );
+
+    test.finish();
+}
+
+/// 워치독 셀프테스트 (만료시키지는 않음)
+pub fn watchdog() {
+    use watchdog::{Pinger, WDIOC_GETTIMELEFT, WDIOC_GETTIMEOUT, WDIOC_KEEPALIVE, WDIOC_SETOPTIONS, WDIOC_SETTIMEOUT};
+
+    let mut test = SelfTest::new("watchdog");
+    // watchdog=로 켜져 있었으면 끝에 되돌림
+    let saved = watchdog::status();
+
+    test.check("start", watchdog::start(1, Pinger::Kernel).is_ok() && watchdog::status().is_some());
+    test.check(
+        "check timer listed",
+        time::list_timers().iter().any(|t| t.name == "watchdog"),
+    );
+    proc::park_timeout(1_500_000);
+    let status = watchdog::status();
+    kprintln!("  after 1.5s: {:?}", status);
+    test.check(
+        "kernel pings past the timeout",
+        status.is_some_and(|s| s.pinger == Pinger::Kernel && s.pets >= 3),
+    );
+    test.check("timeout 0 rejected", watchdog::start(0, Pinger::Kernel).is_err());
+
+    match fs::lookup_path("/dev/watchdog") {
+        Ok(dev) => {
+            // int 인자 ioctl: (결과, 돌려받은 값)
+            let ioctl = |cmd: u32, mut value: i32| {
+                let ret = dev.ioctl(cmd, &mut value as *mut i32 as usize);
+                (ret, value)
+            };
+            test.check("write takes over", dev.write(0, b"1").is_ok() && watchdog::status().is_some_and(|s| s.pinger == Pinger::Device));
+            test.check("WDIOC_GETTIMEOUT", ioctl(WDIOC_GETTIMEOUT, 0) == (Ok(0), 1));
+            test.check("WDIOC_SETTIMEOUT", ioctl(WDIOC_SETTIMEOUT, 2) == (Ok(0), 2));
+            let (ret, left) = ioctl(WDIOC_GETTIMELEFT, 0);
+            test.check("WDIOC_GETTIMELEFT", ret.is_ok() && (1..=2).contains(&left));
+            test.check("WDIOC_KEEPALIVE", dev.ioctl(WDIOC_KEEPALIVE, 0).is_ok());
+            test.check("negative timeout rejected", ioctl(WDIOC_SETTIMEOUT, -1).0 == Err(fs::VfsError::InvalidArgument));
+            test.check("unknown ioctl is ENOTTY", ioctl(0x1234, 0).0 == Err(fs::VfsError::NotATty));
+            test.check("magic V stops", dev.write(0, b"V").is_ok() && watchdog::status().is_none());
+            let enabled = ioctl(WDIOC_SETOPTIONS, watchdog::WDIOS_ENABLECARD).0.is_ok() && watchdog::status().is_some();
+            test.check(
+                "WDIOS_ENABLECARD / DISABLECARD",
+                enabled
+                    && ioctl(WDIOC_SETOPTIONS, watchdog::WDIOS_DISABLECARD).0.is_ok()
+                    && watchdog::status().is_none(),
+            );
+        }
+        Err(e) => test.check(&alloc::format!("/dev/watchdog ({})", e), false),
+    }
+
+    watchdog::stop();
+    test.check(
+        "stop cancels the check timer",
+        watchdog::status().is_none() && !time::list_timers().iter().any(|t| t.name == "watchdog"),
+    );
+
+    if let Some(s) = saved {
+        let _ = watchdog::start(s.timeout_s, s.pinger);
+    }
+
+    test.finish();
+}
+
+/// lockdep selftest: 두 락을 반대 순서로 잡아 순환 보고 확인
+///
+/// 첫 보고 뒤 순서 검사가 꺼지므로 부팅 후 한 번만 의미가 있습니다.
+#[cfg(feature = "lockdep")]
+pub fn lockdep() {
+    use crate::sync::{lockdep, Spinlock};
+
+    static LOCK_A: Spinlock<u32> = Spinlock::new(0);
+    static LOCK_B: Spinlock<u32> = Spinlock::new(0);
+
+    let mut test = SelfTest::new("lockdep");
+    let before = lockdep::summary();
+    if !before.checking {
+        kprintln!("  order checking already off (violations {}), reboot to rerun", before.violations);
+        kprintln!("\n[SKIP] selftest lockdep");
+        return;
+    }
+
+
+    {
+        let _a = LOCK_A.lock();
+        let _b = LOCK_B.lock();
+    }
+    let after_ab = lockdep::summary();
+    test.check("A -> B recorded", after_ab.dependencies > before.dependencies && after_ab.violations == before.violations);
+
+    // 한 스레드에서 순서대로 잡으므로 실제로 교착되지는 않음
+    {
+        let _b = LOCK_B.lock();
+        let _a = LOCK_A.lock();
+    }
+    let after_ba = lockdep::summary();
+    test.check("B -> A reported", after_ba.violations == before.violations + 1);
+    test.check("order checking off", !after_ba.checking);
+
+    let addr = &LOCK_A as *const Spinlock<u32> as usize;
+    let stat = lockdep::stats().into_iter().find(|s| s.addr == addr);
+    test.check("lock A counted", stat.is_some_and(|s| s.acquired >= 2));
+
+    test.finish();
+}
+
+/// kprobe selftest 대상 (인라인되면 probe할 주소가 없어짐)
+#[inline(never)]
+fn kprobe_selftest_target(x: usize) -> usize {
+    core::hint::black_box(x * 3 + 1)
+}
+
+/// kprobe selftest: 적중, 단일 스텝 후 재장착, 비활성화, 해제
+pub fn kprobe() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use trace::kprobe::{self, KprobeError, Regs};
+
+    static SEEN_ARG: AtomicUsize = AtomicUsize::new(0);
+    fn on_hit(_id: usize, regs: &mut Regs) {
+        SEEN_ARG.store(arch::kprobe::arg(regs, 0) as usize, Ordering::Relaxed);
+    }
+
+    let mut test = SelfTest::new("kprobe");
+
+    // black_box로 호출을 막아 매번 실제 함수를 거치게 함
+    let target: fn(usize) -> usize = core::hint::black_box(kprobe_selftest_target);
+    let addr = alloc::format!("{:#x}", target as usize);
+
+    let id = match kprobe::register(&addr, Some(on_hit), false) {
+        Ok(id) => id,
+        Err(e) => {
+            kprintln!("  register failed: {:?}", e);
+            kprintln!("\n[FAIL] selftest kprobe");
+            return;
+        }
+    };
+    test.check("register", true);
+    test.check("duplicate address rejected", kprobe::register(&addr, None, false) == Err(KprobeError::AlreadyProbed));
+
+    let r = target(7);
+    test.check("original instruction runs", r == 22);
+    test.check("handler sees argument", kprobe::hits(id) == 1 && SEEN_ARG.load(Ordering::Relaxed) == 7);
+
+    let r = target(1) + target(2);
+    test.check("re-armed after single-step", kprobe::hits(id) == 3 && r == 4 + 7);
+
+    let _ = kprobe::disable(id);
+    let r = target(5);
+    test.check("disabled probe not hit", kprobe::hits(id) == 3 && r == 16);
+
+    let _ = kprobe::enable(id);
+    target(5);
+    test.check("enable re-arms", kprobe::hits(id) == 4);
+
+    test.check("unregister", kprobe::unregister(id).is_ok());
+    test.check("unregister twice fails", kprobe::unregister(id) == Err(KprobeError::InvalidId));
+    test.check("original code restored", target(9) == 28);
+
+    test.finish();
+}
+
+/// 추적 이벤트 selftest: 작은 버퍼에 표식 이벤트를 넘치게 기록해 순서와 덮어쓰기 확인
+pub fn trace() {
+    use trace::event;
+
+    let mut test = SelfTest::new("trace");
+    let prev = event::status();
+
+    event::stop();
+    crate::trace_event!(selftest_mark, seq = 0);
+    test.check("disabled tracepoint not recorded", event::snapshot().iter().all(|e| e.name != "selftest_mark"));
+
+    test.check("start", event::start(8).is_ok());
+    event::clear();
+    for seq in 1..=20u64 {
+        crate::trace_event!(selftest_mark, seq = seq, half = seq / 2);
+    }
+    event::stop();
+
+    let events = event::snapshot();
+    let marks: alloc::vec::Vec<u64> = events
+        .iter()
+        .filter(|e| e.name == "selftest_mark")
+        .map(|e| e.args[0])
+        .collect();
+    // 인터럽트 이벤트가 섞여 남는 표식 수는 달라질 수 있으므로 순서와 마지막 값만 확인
+    test.check("oldest events overwritten", event::status().overwritten >= 12);
+    test.check(
+        "latest events kept in order",
+        marks.windows(2).all(|w| w[0] < w[1]) && marks.last() == Some(&20),
+    );
+    test.check(
+        "fields recorded",
+        events
+            .iter()
+            .any(|e| e.name == "selftest_mark" && e.fields == ["seq", "half"] && e.args[1] == 10),
+    );
+    test.check("sorted by timestamp", events.windows(2).all(|w| w[0].ts_us <= w[1].ts_us));
+    test.check("out-of-range size rejected", event::start(0).is_err() && event::start(event::MAX_EVENTS + 1).is_err());
+
+    // 이전 상태 복원
+    event::stop();
+    if prev.capacity != 0 {
+        let _ = event::start(prev.capacity);
+        if !prev.enabled {
+            event::stop();
+        }
+    }
+    event::clear();
+
+    test.finish();
+}
@@ -0,0 +1,770 @@
+//! 디바이스 드라이버 셀프테스트 (블록 discard, SDHCI, 콘솔, 입력, 시리얼, 인터럽트)
+
+use super::SelfTest;
+use crate::{block, console, drivers, fs, input, irq, log, proc, softirq, sync, sysctl, time};
+use crate::{kprintln, log_error, log_warn};
+
+/// 블록 discard/write zeroes 셀프테스트: RAM 디스크와 그 위 파티션으로 범위 변환과 에러 확인
+pub fn discard() {
+    use alloc::sync::Arc;
+    use block::partition::Partition;
+    use block::ramdisk::RamDisk;
+    use block::{BlockDevice, BlockError};
+
+    let mut test = SelfTest::new("discard");
+
+    const BLOCK: usize = 512;
+    let disk = Arc::new(RamDisk::new("discard-test", 64 * BLOCK, BLOCK));
+    let _ = disk.write_all(&[0xAA; 64 * BLOCK]);
+    // 블록 `first..end`가 모두 0인지, 그 양옆은 그대로인지
+    let zeroed = |first: usize, end: usize| {
+        let data = disk.read_all();
+        data[first * BLOCK..end * BLOCK].iter().all(|&b| b == 0)
+            && data[(first - 1) * BLOCK..first * BLOCK].iter().all(|&b| b == 0xAA)
+            && data[end * BLOCK..(end + 1) * BLOCK].iter().all(|&b| b == 0xAA)
+    };
+
+    test.check("ramdisk discard reads back zeroes", disk.discard(2, 3).is_ok() && zeroed(2, 5));
+    test.check("ramdisk write_zeroes", disk.write_zeroes(8, 4).is_ok() && zeroed(8, 12));
+    test.check("generic zero_fill", block::zero_fill(&*disk, 14, 2).is_ok() && zeroed(14, 16));
+    test.check(
+        "past the end rejected",
+        disk.discard(60, 8) == Err(BlockError::InvalidBlock)
+            && block::zero_fill(&*disk, 63, 2) == Err(BlockError::InvalidBlock),
+    );
+
+    let part = Partition::new("discard-test1", disk.clone(), 32, 16);
+    test.check("partition discard shifted by start", part.discard(4, 2).is_ok() && zeroed(36, 38));
+    test.check("partition write_zeroes", part.write_zeroes(10, 3).is_ok() && zeroed(42, 45));
+    test.check("partition bounds", part.discard(15, 2) == Err(BlockError::InvalidBlock));
+
+    let mut ro = RamDisk::new("discard-ro", 8 * BLOCK, BLOCK);
+    ro.set_read_only(true);
+    test.check(
+        "read-only rejected",
+        ro.discard(0, 1) == Err(BlockError::ReadOnly) && ro.write_zeroes(0, 1) == Err(BlockError::ReadOnly),
+    );
+
+    test.check("fat32.discard registered", matches!(sysctl::get("fat32.discard"), Ok(sysctl::Value::Bool(_))));
+    if let Some(vda) = block::get_device("vda") {
+        // 실제 디스크에는 쓰지 않고 범위 확인만
+        test.check("vda rejects out-of-range discard", vda.discard(vda.block_count(), 1).is_err());
+    }
+
+    test.finish();
+}
+
+/// SDHCI 셀프테스트: 하드웨어 없이 확인할 수 있는 클럭 분주, 긴 응답 정렬, CSD 용량 계산
+pub fn sdhci() {
+    use drivers::sdhci::{clock_divider, csd_capacity, long_response};
+
+    let mut test = SelfTest::new("sdhci");
+
+    // 분주 N이면 버스 클럭 = base / 2N
+    let hz = |base: u32, n: u32| if n == 0 { base } else { base / (2 * n) };
+    test.check("v3 400kHz from 200MHz", clock_divider(200_000_000, 400_000, true) == 250);
+    test.check("v3 25MHz from 200MHz", clock_divider(200_000_000, 25_000_000, true) == 4);
+    let n = clock_divider(41_666_666, 400_000, true);
+    test.check("v3 rounds down the bus clock", hz(41_666_666, n) <= 400_000 && hz(41_666_666, n - 1) > 400_000);
+    test.check("v3 divider capped at 10 bits", clock_divider(1_000_000_000, 400_000, true) == 0x3FF);
+    test.check("v2 power of two", clock_divider(100_000_000, 400_000, false) == 128);
+    test.check("v2 25MHz from 50MHz", clock_divider(50_000_000, 25_000_000, false) == 1);
+    test.check("base below target: no divider", clock_divider(25_000_000, 25_000_000, true) == 0);
+
+    // 응답 레지스터는 CRC를 뺀 R[127:8]을 0비트부터 담음
+    let r2 = long_response([0x1122_3344, 0x5566_7788, 0x99AA_BBCC, 0x00DD_EEFF]);
+    test.check("R2 shifted into CID/CSD layout", r2 == 0xDDEE_FF99_AABB_CC55_6677_8811_2233_4400);
+
+    // CSD 2.0: C_SIZE 15159 -> 15160 * 512KB (8GB 카드)
+    let csd_v2 = 1u128 << 126 | 15159u128 << 48;
+    test.check("CSD v2 capacity", csd_capacity(csd_v2) == 15160 * 512 * 1024);
+    // CSD 1.0: C_SIZE 3799, C_SIZE_MULT 7, READ_BL_LEN 9 -> 3800 * 512 * 512 (1GB 카드)
+    let csd_v1 = 9u128 << 80 | 3799u128 << 62 | 7u128 << 47;
+    test.check("CSD v1 capacity", csd_capacity(csd_v1) == 3800 * 512 * 512);
+
+    test.finish();
+}
+
+/// fd selftest: open 플래그, fd별 오프셋, dup 오프셋 공유
+pub fn console() {
+    use drivers::tty::{Termios, TCGETS, TCSETS, TCSETSF};
+
+    let mut test = SelfTest::new("console");
+
+    let dev = match fs::lookup_path("/dev/console") {
+        Ok(dev) => dev,
+        Err(e) => {
+            kprintln!("\n[FAIL] selftest console: /dev/console: {}", e);
+            return;
+        }
+    };
+    // UART 수신을 흉내 내어 링에 넣고 /dev/console로 읽음 (에코가 화면에 보임)
+    let feed = |bytes: &[u8]| bytes.iter().for_each(|&b| console::receive(b));
+    let mut buf = [0u8; 64];
+
+    feed(b"ab\x7fc\r");
+    let n = dev.read(0, &mut buf).unwrap_or(usize::MAX);
+    test.check("backspace edits line, CR becomes LF", buf.get(..n) == Some(&b"ac\n"[..]));
+
+    feed(b"hello\n");
+    let first = dev.read(0, &mut buf[..3]).unwrap_or(0);
+    let rest = dev.read(0, &mut buf[3..]).unwrap_or(0);
+    test.check("long line split across reads", first == 3 && &buf[..3 + rest] == b"hello\n");
+
+    feed(b"xy\x15z\n");
+    let n = dev.read(0, &mut buf).unwrap_or(usize::MAX);
+    test.check("Ctrl-U kills line", buf.get(..n) == Some(&b"z\n"[..]));
+
+    feed(b"ab\x04");
+    let n = dev.read(0, &mut buf).unwrap_or(usize::MAX);
+    test.check("Ctrl-D flushes partial line", buf.get(..n) == Some(&b"ab"[..]));
+
+    feed(b"\x04");
+    test.check("Ctrl-D on empty line is EOF", dev.read(0, &mut buf) == Ok(0));
+    console::puts("\n");
+
+    // termios ioctl로 raw 모드 전환 후 복원
+    let mut saved = Termios::cooked();
+    let got = dev.ioctl(TCGETS, &mut saved as *mut Termios as usize);
+    test.check("TCGETS returns cooked defaults", got == Ok(0) && saved == Termios::cooked());
+    let mut raw = saved;
+    raw.make_raw();
+    let set = dev.ioctl(TCSETS, &raw as *const Termios as usize);
+    feed(b"q\r");
+    let n = dev.read(0, &mut buf).unwrap_or(usize::MAX);
+    test.check("raw mode passes CR through without waiting for a line", set == Ok(0) && buf.get(..n) == Some(&b"q\r"[..]));
+    feed(b"stale\n");
+    let _ = dev.ioctl(TCSETSF, &saved as *const Termios as usize);
+    test.check("TCSETSF restores mode and flushes input", drivers::tty::console().read(&mut buf).is_none());
+    let null = fs::lookup_path("/dev/null").and_then(|null| null.ioctl(TCGETS, &mut saved as *mut Termios as usize));
+    test.check("termios ioctl on /dev/null is ENOTTY", null == Err(fs::VfsError::NotATty));
+
+    // 콘솔 레지스트리: 테스트 콘솔을 등록해 출력이 켜진 콘솔 모두에 가는지 확인
+    use console::ConsoleError;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    static TEST_BYTES: AtomicUsize = AtomicUsize::new(0);
+    static TEST_CONSOLE: console::ConsoleDriver = console::ConsoleDriver {
+        name: "test",
+        desc: "selftest console",
+        write: |bytes| {
+            TEST_BYTES.fetch_add(bytes.len(), Ordering::Relaxed);
+            true
+        },
+        getc: None,
+    };
+    let _ = console::unregister("test");
+    test.check("register test console", console::register(&TEST_CONSOLE, 1, true).is_ok());
+    test.check(
+        "duplicate name rejected",
+        console::register(&TEST_CONSOLE, 1, true) == Err(ConsoleError::Exists),
+    );
+    let before = TEST_BYTES.load(Ordering::Relaxed);
+    kprintln!("  (console fan-out probe)");
+    test.check("kprintln reaches every enabled console", TEST_BYTES.load(Ordering::Relaxed) > before);
+    test.check("low priority console is not primary", console::primary() != Some("test"));
+    let _ = console::set_priority("test", u8::MAX);
+    test.check("highest priority console is primary", console::primary() == Some("test"));
+    let _ = console::set_priority("test", 1);
+
+    // 나머지를 잠시 끄고 마지막 콘솔 끄기 거부 확인 (그동안 출력하지 않음)
+    let others: alloc::vec::Vec<&str> =
+        console::list().iter().filter(|c| c.enabled && c.name != "test").map(|c| c.name).collect();
+    for name in &others {
+        let _ = console::disable(name);
+    }
+    let last = console::disable("test");
+    for name in &others {
+        let _ = console::enable(name);
+    }
+    test.check("last enabled console cannot be disabled", last == Err(ConsoleError::LastConsole));
+
+    let _ = console::disable("test");
+    let before = TEST_BYTES.load(Ordering::Relaxed);
+    kprintln!("  (console fan-out probe)");
+    test.check("disabled console gets no output", TEST_BYTES.load(Ordering::Relaxed) == before);
+    test.check("unknown console is NotFound", console::enable("nosuch") == Err(ConsoleError::NotFound));
+    test.check(
+        "unregister removes console",
+        console::unregister("test").is_ok() && !console::list().iter().any(|c| c.name == "test"),
+    );
+
+    // log 콘솔은 로그 시스템을 거치지 않는 raw 출력도 보관
+    let log_enabled = console::is_enabled("log");
+    console::clear_captured();
+    let _ = console::enable("log");
+    console::puts("  (capture probe)\n");
+    if !log_enabled {
+        let _ = console::disable("log");
+    }
+    let captured = console::captured();
+    test.check("log console captures raw output", captured.windows(15).any(|w| w == b"(capture probe)"));
+
+    test.finish();
+}
+
+pub fn color() {
+    use console::Color;
+
+    let mut test = SelfTest::new("color");
+    let contains = |haystack: &[u8], needle: &[u8]| haystack.windows(needle.len()).any(|w| w == needle);
+
+    // log 콘솔로 실제 콘솔에 나간 바이트를 잡아 봄
+    let saved_color = console::color_enabled();
+    let log_enabled = console::is_enabled("log");
+    let _ = console::enable("log");
+
+    console::set_color(true);
+    console::clear_captured();
+    console::puts_colored(Some(Color::Red), &["(red)"]);
+    let on = console::captured();
+    console::set_color(false);
+    console::clear_captured();
+    console::puts_colored(Some(Color::Red), &["(red)"]);
+    let off = console::captured();
+    console::puts("\n");
+
+    console::set_color(true);
+    console::clear_captured();
+    log_warn!("color probe");
+    log_error!("color probe");
+    kprintln!("color probe");
+    let logged = console::captured();
+    console::set_color(saved_color);
+    if !log_enabled {
+        let _ = console::disable("log");
+    }
+
+    test.check("color on wraps text in SGR and reset", on == b"\x1b[31m(red)\x1b[0m");
+    test.check("color off writes plain text", off == b"(red)");
+    test.check("warn is yellow, error is red", contains(&logged, b"\x1b[33m[") && contains(&logged, b"\x1b[31m["));
+    test.check("info is not colored", logged.split(|&b| b == b'\n').any(|line| line.starts_with(b"[") && contains(line, b"INFO: color probe")));
+
+    let mut tail = alloc::vec::Vec::new();
+    log::tail(3, |bytes| tail.extend_from_slice(bytes));
+    test.check("log ring stores plain text", contains(&tail, b"color probe") && !tail.contains(&0x1b));
+
+    let knob = sysctl::set("console.color", "on").is_ok() && console::color_enabled();
+    let knob = knob && sysctl::set("console.color", "off").is_ok() && !console::color_enabled();
+    console::set_color(saved_color);
+    test.check("console.color knob toggles colors", knob);
+
+    test.finish();
+}
+
+pub fn keymap() {
+    use input::codes::{EV_KEY, EV_SYN, SYN_REPORT};
+    use input::keymap::{Keyboard, MAX_BYTES};
+
+    let mut test = SelfTest::new("keymap");
+
+    // (키 코드, 값) 이벤트를 차례로 넣고 나온 바이트를 모음
+    let keys = |kbd: &mut Keyboard, events: &[(u16, i32)]| {
+        let mut out = alloc::vec::Vec::new();
+        for &(code, value) in events {
+            let mut bytes = [0u8; MAX_BYTES];
+            let n = kbd.key(code, value, &mut bytes);
+            out.extend_from_slice(&bytes[..n]);
+        }
+        out
+    };
+    const A: u16 = 30;
+    const C: u16 = 46;
+    const X: u16 = 45;
+    const ONE: u16 = 2;
+    const SHIFT: u16 = 42;
+    const CTRL: u16 = 29;
+    const ALT: u16 = 56;
+    const CAPS: u16 = 58;
+
+    let mut kbd = Keyboard::new();
+    test.check("press gives ASCII, release gives nothing", keys(&mut kbd, &[(A, 1), (A, 0)]) == b"a");
+    test.check("autorepeat repeats the key", keys(&mut kbd, &[(A, 1), (A, 2), (A, 0)]) == b"aa");
+    test.check(
+        "Shift selects the upper row",
+        keys(&mut kbd, &[(SHIFT, 1), (A, 1), (ONE, 1), (SHIFT, 0), (A, 1)]) == b"A!a",
+    );
+    test.check(
+        "Caps Lock affects letters only, Shift inverts it",
+        keys(&mut kbd, &[(CAPS, 1), (CAPS, 0), (A, 1), (ONE, 1), (SHIFT, 1), (A, 1), (SHIFT, 0), (CAPS, 1)]) == b"A1a",
+    );
+    test.check("Ctrl-C is 0x03", keys(&mut kbd, &[(CTRL, 1), (C, 1), (CTRL, 0)]) == [0x03]);
+    test.check("Alt prefixes ESC", keys(&mut kbd, &[(ALT, 1), (X, 1), (ALT, 0)]) == b"\x1bx");
+    test.check(
+        "Enter, Backspace, Tab, Esc",
+        keys(&mut kbd, &[(28, 1), (14, 1), (15, 1), (1, 1)]) == b"\r\x7f\t\x1b",
+    );
+    test.check(
+        "arrows, Home, Delete, F1, F5, F12",
+        keys(&mut kbd, &[(103, 1), (105, 1), (102, 1), (111, 1), (59, 1), (63, 1), (88, 1)])
+            == b"\x1b[A\x1b[D\x1b[H\x1b[3~\x1bOP\x1b[15~\x1b[24~",
+    );
+    test.check("keypad digits and Enter", keys(&mut kbd, &[(79, 1), (82, 1), (96, 1)]) == b"10\r");
+    test.check("unmapped keys are ignored", keys(&mut kbd, &[(0x110, 1), (200, 1)]).is_empty());
+
+    // 실제 디바이스가 있으면 그 디바이스로 보고한 키가 콘솔 TTY까지 가는지 확인
+    match input::list_devices().first() {
+        Some(dev) => {
+            let mut buf = [0u8; 16];
+            let mut seq = input::end_seq(dev.id);
+            for &(code, value) in &[(A, 1), (A, 0), (28, 1), (28, 0)] {
+                input::report_event(dev.id, EV_KEY, code, value);
+                input::report_event(dev.id, EV_SYN, SYN_REPORT, 0);
+            }
+            let mut events = [input::InputEvent::default(); 16];
+            let queued = input::read_events(dev.id, &mut seq, &mut events);
+            console::puts("\n");
+            let n = drivers::tty::console().read(&mut buf).unwrap_or(0);
+            test.check("key events still reach /dev/input", queued >= 8);
+            test.check("keyboard input reaches the console TTY", &buf[..n] == b"a\n");
+        }
+        None => kprintln!("  (no input device, console path skipped)"),
+    }
+
+    test.finish();
+}
+
+pub fn evdev() {
+    use fs::fd::{self, OpenFlags};
+    use fs::poll::POLLIN;
+    use fs::{FileMode, VfsError};
+    use input::codes::{EV_KEY, EV_SYN, SYN_DROPPED, SYN_REPORT};
+    use input::{ioctl, EVENT_SIZE, QUEUE_LEN};
+
+    let mut test = SelfTest::new("evdev");
+    let Some(dev) = input::list_devices().into_iter().next() else {
+        kprintln!("  (no input device, skipped)");
+        kprintln!("\n[PASS] selftest evdev");
+        return;
+    };
+
+    let path = alloc::format!("/dev/input/event{}", dev.id);
+    let flags = OpenFlags::new(OpenFlags::O_RDONLY | OpenFlags::O_NONBLOCK);
+    let (Ok(a), Ok(b)) = (fd::open(&path, flags, FileMode::new(0)), fd::open(&path, flags, FileMode::new(0))) else {
+        kprintln!("  [FAIL] open {}", path);
+        kprintln!("\n[FAIL] selftest evdev");
+        return;
+    };
+    // 콘솔로 키가 새지 않도록 잠시 끔
+    let console_input = input::console_input();
+    input::set_console_input(false);
+
+    let mut buf = [0u8; EVENT_SIZE * 4];
+    test.check("open counts readers", input::list_devices()[dev.id].readers >= 2);
+    test.check("empty O_NONBLOCK read is EAGAIN", matches!(a.read(&mut buf), Err(VfsError::WouldBlock)));
+    test.check("short buffer is rejected", matches!(a.read(&mut buf[..EVENT_SIZE - 1]), Err(VfsError::InvalidArgument)));
+    test.check("poll without events", a.vnode.poll(POLLIN, None) == 0);
+
+    input::report_event(dev.id, EV_KEY, 30, 1);
+    input::report_event(dev.id, EV_SYN, SYN_REPORT, 0);
+    test.check("poll reports POLLIN", a.vnode.poll(POLLIN, None) == POLLIN);
+
+    let record = |buf: &[u8], i: usize| {
+        let r = &buf[i * EVENT_SIZE..(i + 1) * EVENT_SIZE];
+        let type_ = u16::from_ne_bytes([r[16], r[17]]);
+        let code = u16::from_ne_bytes([r[18], r[19]]);
+        let value = i32::from_ne_bytes([r[20], r[21], r[22], r[23]]);
+        (type_, code, value)
+    };
+    let n = a.read(&mut buf).unwrap_or(0);
+    test.check(
+        "24-byte input_event records",
+        n == 2 * EVENT_SIZE && record(&buf, 0) == (EV_KEY, 30, 1) && record(&buf, 1) == (EV_SYN, SYN_REPORT, 0),
+    );
+    test.check("drained reader has no POLLIN", a.vnode.poll(POLLIN, None) == 0);
+    let n = b.read(&mut buf).unwrap_or(0);
+    test.check("each open reads its own copy", n == 2 * EVENT_SIZE && record(&buf, 0) == (EV_KEY, 30, 1));
+
+    // 읽지 않고 링을 넘기면 SYN_DROPPED 다음 남은 이벤트부터
+    for i in 0..QUEUE_LEN + 8 {
+        input::report_event(dev.id, EV_KEY, 30, (i % 2) as i32);
+    }
+    let n = a.read(&mut buf).unwrap_or(0);
+    test.check(
+        "overrun reports SYN_DROPPED",
+        n == buf.len() && record(&buf, 0) == (EV_SYN, SYN_DROPPED, 0) && record(&buf, 1) == (EV_KEY, 30, 0),
+    );
+    let mut total = n / EVENT_SIZE - 1;
+    while let Ok(n) = a.read(&mut buf) {
+        total += n / EVENT_SIZE;
+    }
+    test.check("the newest QUEUE_LEN events survive", total == QUEUE_LEN);
+
+    let mut version = 0i32;
+    let mut id = [0u16; 4];
+    let mut name = [0xFFu8; 64];
+    test.check(
+        "EVIOCGVERSION",
+        a.vnode.ioctl(ioctl::EVIOCGVERSION, &mut version as *mut i32 as usize).is_ok()
+            && version == ioctl::EV_VERSION,
+    );
+    test.check(
+        "EVIOCGID",
+        a.vnode.ioctl(ioctl::EVIOCGID, id.as_mut_ptr() as usize).is_ok() && id[0] == ioctl::BUS_VIRTUAL,
+    );
+    let n = a.vnode.ioctl(ioctl::eviocgname(name.len() as u32), name.as_mut_ptr() as usize).unwrap_or(0);
+    test.check(
+        "EVIOCGNAME is NUL-terminated",
+        n == dev.name.len() + 1 && &name[..n - 1] == dev.name.as_bytes() && name[n - 1] == 0,
+    );
+    let n = a.vnode.ioctl(ioctl::eviocgname(4), name.as_mut_ptr() as usize).unwrap_or(0);
+    test.check("EVIOCGNAME truncates to the buffer", n == 4.min(dev.name.len() + 1));
+    test.check("other ioctls are not a TTY", matches!(a.vnode.ioctl(0x5401, name.as_mut_ptr() as usize), Err(VfsError::NotATty)));
+
+    input::set_console_input(console_input);
+    let readers = input::list_devices()[dev.id].readers;
+    drop(a);
+    drop(b);
+    test.check("close releases readers", input::list_devices()[dev.id].readers == readers - 2);
+
+    test.finish();
+}
+
+pub fn serial() {
+    use drivers::serial;
+    use drivers::tty::{Termios, TCGETS};
+
+    let mut test = SelfTest::new("serial");
+
+    test.check(
+        "console= names parse",
+        serial::parse_port_name("ttyS1") == Some(1)
+            && serial::parse_port_name("ttyAMA0") == Some(0)
+            && serial::parse_port_name("hvc0").is_none()
+            && serial::parse_port_name("ttyS").is_none(),
+    );
+
+    let count = serial::count();
+    let console_index = serial::console_index();
+    test.check("console port is in the port table", console_index < count);
+    for port in serial::ports() {
+        kprintln!(
+            "  ttyS{}: base={:#x} irq={}{}",
+            port.index,
+            port.base,
+            port.irq,
+            if port.is_console { " (console)" } else { "" }
+        );
+    }
+
+    let mut buf = [0u8; 64];
+    match fs::lookup_path(&alloc::format!("/dev/ttyS{}", console_index)) {
+        Ok(dev) => {
+            // 콘솔 포트는 /dev/console과 같은 TTY (에코가 화면에 보임)
+            b"hi\n".iter().for_each(|&b| serial::receive(console_index, b));
+            let n = dev.read(0, &mut buf).unwrap_or(usize::MAX);
+            test.check("console port reads the console TTY", buf.get(..n) == Some(&b"hi\n"[..]));
+        }
+        Err(e) => test.check(&alloc::format!("/dev/ttyS{}: {}", console_index, e), false),
+    }
+    test.check(
+        "no node past the last port",
+        fs::lookup_path(&alloc::format!("/dev/ttyS{}", count)).is_err(),
+    );
+
+    match (0..count).find(|&i| i != console_index) {
+        Some(index) => match fs::lookup_path(&alloc::format!("/dev/ttyS{}", index)) {
+            Ok(dev) => {
+                serial::receive(index, b'o');
+                serial::receive(index, b'k');
+                serial::receive(index, b'\r');
+                let n = dev.read(0, &mut buf).unwrap_or(usize::MAX);
+                test.check("second port has its own line discipline", buf.get(..n) == Some(&b"ok\n"[..]));
+                test.check("second port input does not reach the console", drivers::tty::console().read(&mut buf).is_none());
+                let mut termios = Termios::cooked();
+                termios.c_lflag = 0;
+                let got = dev.ioctl(TCGETS, &mut termios as *mut Termios as usize);
+                test.check("second port answers TCGETS", got == Ok(0) && termios == Termios::cooked());
+                test.check("second port write", dev.write(0, b"kerners ttyS\r\n") == Ok(14));
+            }
+            Err(e) => test.check(&alloc::format!("/dev/ttyS{}: {}", index, e), false),
+        },
+        None => kprintln!("  [SKIP] only one UART (add -serial to QEMU for /dev/ttyS1)"),
+    }
+
+    test.finish();
+}
+
+/// psci selftest: DTB 호출 방식, 버전, 켜진 CPU의 AFFINITY_INFO, idle 상태 고르기, psci.idle
+#[cfg(target_arch = "aarch64")]
+pub fn psci() {
+    use drivers::psci::{self, Conduit};
+    use sysctl::Value;
+
+    let mut test = SelfTest::new("psci");
+
+    let info = crate::dtb::get().and_then(|dt| dt.find_psci());
+    test.check("DTB PSCI node found", info.is_some() && psci::is_present());
+    let expected = match info.as_ref().and_then(|i| i.method.as_deref()) {
+        Some("smc") => Conduit::Smc,
+        _ => Conduit::Hvc,
+    };
+    test.check("conduit matches DTB method", psci::conduit() == expected);
+    test.check("version >= 0.2", psci::version() >= 2);
+    test.check(
+        "unknown function reports NOT_SUPPORTED",
+        psci::version() < 0x1_0000 || psci::features(0x8400_00FF).is_none()
+    );
+
+    // 이 CPU와 온라인인 CPU는 모두 켜져 있어야 함
+    test.check("current CPU is on", psci::affinity_info(crate::arch::smp::current_mpidr(), 0) == 0);
+    let cpus = crate::dtb::get().map(|dt| dt.find_cpus()).unwrap_or_default();
+    test.check("DTB CPU nodes match CPU count", cpus.len() == drivers::config::cpu_count());
+    let online_on = cpus.iter().all(|c| {
+        let id = (c.mpidr & 0xFF) as u32;
+        id >= proc::percpu::total_count() || !proc::percpu::get(id).is_online() || psci::affinity_info(c.mpidr, 0) == 0
+    });
+    test.check("online CPUs report on", online_on);
+    test.check("error names", psci::error::name(psci::error::ALREADY_ON) == "ALREADY_ON");
+
+    // 남은 시간이 없으면 어떤 상태에도 들어가지 않음 (호출자가 wfi)
+    test.check("no idle state for 0 us", !psci::suspend_idle(0));
+    let was = sysctl::get("psci.idle");
+    let off = sysctl::set("psci.idle", "0").is_ok() && !psci::suspend_idle(u64::MAX);
+    let _ = sysctl::set("psci.idle", if was == Ok(Value::Bool(false)) { "0" } else { "1" });
+    test.check("psci.idle=0 disables idle states", off);
+
+    test.finish();
+}
+
+/// gic selftest: 버전 선택, 타이머 PPI, 자기 자신에게 SGI, (GICv3) ITS를 거친 LPI
+#[cfg(target_arch = "aarch64")]
+pub fn gic() {
+    use crate::arch::gic;
+    use crate::arch::gicv3::{self, its};
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    /// 테스트용 SGI (0은 reschedule IPI)
+    const TEST_SGI: u32 = 1;
+    /// 테스트용 ITS DeviceID (실제 장치와 겹치지 않는 마지막 번호)
+    const TEST_DEVICE: u32 = its::MAX_DEVICE_ID - 1;
+
+    static LPI_HITS: AtomicU32 = AtomicU32::new(0);
+
+    fn on_lpi(_intid: u32) {
+        LPI_HITS.fetch_add(1, Ordering::AcqRel);
+    }
+
+    // 인터럽트가 들어올 때까지 양보하며 대기 (최대 `ms`)
+    let wait_for = |ms: u64, done: &dyn Fn() -> bool| {
+        let deadline = time::now_us() + ms * 1000;
+        while !done() && time::now_us() < deadline {
+            proc::yield_now();
+        }
+        done()
+    };
+
+    let mut test = SelfTest::new("gic");
+
+    let v3 = gic::is_v3();
+    kprintln!("  driver: GICv{}, GICD_PIDR2.ArchRev={}", if v3 { 3 } else { 2 }, gic::arch_version());
+    test.check(
+        "driver matches the distributor",
+        if v3 { gic::arch_version() >= 3 } else { gic::arch_version() <= 2 },
+    );
+    test.check("timer PPI enabled", gic::is_enabled(gic::IRQ_PHYS_TIMER));
+
+    let before = gic::sgi_count(TEST_SGI);
+    gic::send_sgi(proc::percpu::get_cpu_id(), TEST_SGI);
+    test.check("SGI to self delivered", wait_for(100, &|| gic::sgi_count(TEST_SGI) > before));
+
+    if !v3 {
+        kprintln!("  [SKIP] LPI/ITS (run QEMU with -machine virt,gic-version=3)");
+    } else if !its::is_ready() {
+        kprintln!("  [SKIP] LPI/ITS not available");
+    } else {
+        LPI_HITS.store(0, Ordering::Release);
+        match gicv3::alloc_lpi(on_lpi) {
+            Some(intid) => {
+                let cpu = proc::percpu::get_cpu_id();
+                test.check("LPI allocated above 8191", intid >= gicv3::LPI_BASE);
+                test.check("ITS maps event to LPI", its::map_event(TEST_DEVICE, 0, intid, cpu).is_ok());
+                test.check(
+                    "ITS INT delivers the LPI",
+                    its::trigger(TEST_DEVICE, 0).is_ok() && wait_for(100, &|| LPI_HITS.load(Ordering::Acquire) == 1),
+                );
+
+                gicv3::set_lpi_enabled(intid, false);
+                let masked = its::invalidate(TEST_DEVICE, 0).is_ok() && its::trigger(TEST_DEVICE, 0).is_ok();
+                test.check(
+                    "disabled LPI is not delivered",
+                    masked && !wait_for(20, &|| LPI_HITS.load(Ordering::Acquire) > 1),
+                );
+
+                // DISCARD가 펜딩 상태도 지우므로 해제 후 늦게 도착하지 않음
+                test.check("ITS unmaps event", its::unmap_event(TEST_DEVICE, 0).is_ok());
+                gicv3::free_lpi(intid);
+            }
+            None => test.check("LPI allocated", false),
+        }
+    }
+
+    test.finish();
+}
+
+/// irq selftest: 핸들러 등록/공유/해제와 디스패치 통계
+///
+/// 장치가 없는 IRQ 번호에 핸들러를 걸고 컨트롤러 대신 `irq::dispatch()`를 직접 부릅니다.
+pub fn irq() {
+    use core::sync::atomic::{AtomicU32, Ordering};
+    use irq::IrqError;
+
+    /// 장치가 연결되지 않은 IRQ (QEMU virt 기준)
+    #[cfg(target_arch = "aarch64")]
+    const TEST_IRQ: u32 = 200;
+    #[cfg(target_arch = "riscv64")]
+    const TEST_IRQ: u32 = 60;
+
+    static FIRST_HITS: AtomicU32 = AtomicU32::new(0);
+    static SECOND_HITS: AtomicU32 = AtomicU32::new(0);
+    /// true면 두 핸들러 모두 "내 인터럽트 아님"으로 답함
+    static DECLINE: AtomicU32 = AtomicU32::new(0);
+
+    fn first(_irq: u32) -> bool {
+        FIRST_HITS.fetch_add(1, Ordering::AcqRel);
+        DECLINE.load(Ordering::Acquire) == 0
+    }
+    fn second(_irq: u32) -> bool {
+        SECOND_HITS.fetch_add(1, Ordering::AcqRel);
+        false
+    }
+
+    let line = || irq::stats().into_iter().find(|i| i.irq == TEST_IRQ);
+    let this_cpu = proc::percpu::get_cpu_id() as usize;
+
+    let mut test = SelfTest::new("irq");
+
+    FIRST_HITS.store(0, Ordering::Release);
+    SECOND_HITS.store(0, Ordering::Release);
+    DECLINE.store(0, Ordering::Release);
+
+    test.check("IRQ 0 is rejected", irq::request_irq(0, first, "test") == Err(IrqError::InvalidIrq));
+    test.check("request_irq", irq::request_irq(TEST_IRQ, first, "test-a").is_ok());
+    test.check(
+        "same handler twice is rejected",
+        irq::request_irq(TEST_IRQ, first, "test-a") == Err(IrqError::AlreadyRegistered),
+    );
+    test.check("second handler shares the IRQ", irq::request_irq(TEST_IRQ, second, "test-b").is_ok());
+    test.check(
+        "both names listed",
+        line().is_some_and(|i| i.names.len() == 2 && i.names[0] == "test-a" && i.names[1] == "test-b"),
+    );
+
+    let handled = irq::dispatch(TEST_IRQ);
+    test.check(
+        "dispatch calls every shared handler",
+        handled && FIRST_HITS.load(Ordering::Acquire) == 1 && SECOND_HITS.load(Ordering::Acquire) == 1,
+    );
+    test.check("count on this CPU", line().is_some_and(|i| i.counts[this_cpu] == 1 && i.total() == 1));
+
+    DECLINE.store(1, Ordering::Release);
+    test.check("nobody handled", !irq::dispatch(TEST_IRQ));
+    test.check("unhandled counted", line().is_some_and(|i| i.unhandled == 1 && i.total() == 2));
+    DECLINE.store(0, Ordering::Release);
+
+    test.check("free_irq removes one handler", irq::free_irq(TEST_IRQ, first));
+    test.check("free_irq of a removed handler fails", !irq::free_irq(TEST_IRQ, first));
+    irq::dispatch(TEST_IRQ);
+    test.check(
+        "freed handler is not called",
+        FIRST_HITS.load(Ordering::Acquire) == 2 && SECOND_HITS.load(Ordering::Acquire) == 3,
+    );
+
+    test.check("last free_irq", irq::free_irq(TEST_IRQ, second));
+    test.check("line removed with its last handler", line().is_none());
+    let spurious = irq::spurious();
+    test.check("IRQ without handlers is spurious", !irq::dispatch(TEST_IRQ) && irq::spurious() == spurious + 1);
+
+    if let Some(port) = drivers::serial::ports().find(|p| p.is_console && p.irq != 0) {
+        test.check(
+            "console UART handler registered",
+            irq::stats().iter().any(|i| i.irq == port.irq && i.names.iter().any(|n| n.starts_with("ttyS"))),
+        );
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        let timer = crate::arch::gic::IRQ_PHYS_TIMER;
+        let ticks = || irq::stats().into_iter().find(|i| i.irq == timer).map_or(0, |i| i.total());
+        let before = ticks();
+        let deadline = time::now_us() + 100_000;
+        while ticks() == before && time::now_us() < deadline {
+            proc::yield_now();
+        }
+        test.check("timer PPI dispatched through irq", ticks() > before);
+    }
+
+    test.finish();
+}
+
+/// softirq 셀프테스트: 태스크릿이 인터럽트 끝에서 인터럽트를 켠 채 실행되는지, 중복 예약 병합,
+/// 실행 중 재예약, kill
+pub fn softirq() {
+    use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+    use softirq::Tasklet;
+
+    static HITS: AtomicU32 = AtomicU32::new(0);
+    /// 태스크릿이 스스로 다시 예약해 이 횟수까지 실행
+    static REPEAT: AtomicU32 = AtomicU32::new(1);
+    static IRQS_ON: AtomicBool = AtomicBool::new(false);
+    static IN_SOFTIRQ: AtomicBool = AtomicBool::new(false);
+    static TASKLET: Tasklet = Tasklet::new(run, 0);
+
+    fn run(_data: usize) {
+        IRQS_ON.store(sync::interrupts_enabled(), Ordering::Release);
+        IN_SOFTIRQ.store(softirq::in_softirq(), Ordering::Release);
+        if HITS.fetch_add(1, Ordering::AcqRel) + 1 < REPEAT.load(Ordering::Acquire) {
+            TASKLET.schedule();
+        }
+    }
+
+    // 스레드 문맥에서 예약한 태스크릿은 다음 타이머 틱 끝에 실행됨
+    let wait_hits = |n: u32| {
+        let deadline = time::now_us() + 100_000;
+        while HITS.load(Ordering::Acquire) < n && time::now_us() < deadline {
+            proc::yield_now();
+        }
+        HITS.load(Ordering::Acquire) == n
+    };
+    let total_runs = || (0..proc::percpu::MAX_CPUS).map(softirq::runs).sum::<u64>();
+
+    let mut test = SelfTest::new("softirq");
+
+    HITS.store(0, Ordering::Release);
+    REPEAT.store(1, Ordering::Release);
+    let runs_before = total_runs();
+    let tasklet_runs = TASKLET.runs();
+
+    test.check("schedule", TASKLET.schedule());
+    test.check("runs after the next interrupt", wait_hits(1));
+    test.check("runs with interrupts enabled", IRQS_ON.load(Ordering::Acquire));
+    test.check("in_softirq inside the tasklet", IN_SOFTIRQ.load(Ordering::Acquire));
+    test.check("not in softirq afterwards", !softirq::in_softirq() && !TASKLET.is_scheduled());
+    test.check("per-CPU run count", total_runs() > runs_before);
+
+    // 인터럽트를 끈 채 두 번 예약하면 한 번만 실행
+    sync::disable_interrupts();
+    let first = TASKLET.schedule();
+    let second = TASKLET.schedule();
+    sync::enable_interrupts();
+    test.check("second schedule coalesced", first && !second);
+    let ran = wait_hits(2);
+    let settle = time::now_us() + 20_000;
+    while time::now_us() < settle {
+        proc::yield_now();
+    }
+    test.check("coalesced tasklet runs once", ran && HITS.load(Ordering::Acquire) == 2);
+
+    HITS.store(0, Ordering::Release);
+    REPEAT.store(3, Ordering::Release);
+    TASKLET.schedule();
+    test.check("rescheduled from inside runs again", wait_hits(3));
+    test.check("tasklet run counter", TASKLET.runs() - tasklet_runs == 5);
+
+    HITS.store(0, Ordering::Release);
+    REPEAT.store(1, Ordering::Release);
+    TASKLET.schedule();
+    TASKLET.kill();
+    test.check("kill waits for the pending run", HITS.load(Ordering::Acquire) == 1 && !TASKLET.is_scheduled());
+
+    test.finish();
+}
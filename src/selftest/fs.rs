@@ -0,0 +1,367 @@
+//! 파일/fd 셀프테스트 (시각, fd 테이블, ioctl, poll)
+
+use super::SelfTest;
+use crate::{block, drivers, fs, ipc, proc, sync, syscall, time};
+use crate::kprintln;
+
+/// time selftest: 단조 시계, 날짜 변환, FAT 타임스탬프, clock_gettime
+pub fn time() {
+    use fs::fat32::dir::{from_fat_time, to_fat_time, DirEntry};
+    use time::clock::{self, DateTime, NSEC_PER_SEC};
+
+    let mut test = SelfTest::new("time");
+
+    let mut prev = clock::monotonic_ns();
+    let mut monotonic = true;
+    for _ in 0..1000 {
+        let now = clock::monotonic_ns();
+        monotonic &= now >= prev;
+        prev = now;
+    }
+    test.check("monotonic never goes backwards", monotonic);
+    let start = clock::monotonic_ns();
+    while clock::monotonic_ns() - start < 20_000_000 {
+        proc::yield_now();
+    }
+    test.check("monotonic advances", clock::monotonic_ns() - start >= 20_000_000);
+
+    let cases: [(u64, DateTime); 3] = [
+        (0, DateTime { year: 1970, month: 1, day: 1, hour: 0, minute: 0, second: 0 }),
+        (951_782_400, DateTime { year: 2000, month: 2, day: 29, hour: 0, minute: 0, second: 0 }),
+        (4_102_444_799, DateTime { year: 2099, month: 12, day: 31, hour: 23, minute: 59, second: 59 }),
+    ];
+    test.check("unix -> date", cases.iter().all(|(secs, dt)| DateTime::from_unix(*secs) == *dt));
+    test.check("date -> unix", cases.iter().all(|(secs, dt)| dt.to_unix() == *secs));
+
+    // 2024-07-15 13:45:31.250 UTC
+    let ns = 1_721_051_131 * NSEC_PER_SEC + 250_000_000;
+    let (date, fat_time, tenth) = to_fat_time(ns);
+    test.check("fat date/time round trip (2s units)", from_fat_time(date, fat_time) == 1_721_051_130);
+    test.check("fat odd second and ms in tenth", tenth == 125);
+    test.check("fat clamps before 1980", from_fat_time(to_fat_time(0).0, to_fat_time(0).1) == 315_532_800);
+    let mut entry = DirEntry::new_file("TIME.TXT", 0, 0);
+    entry.set_created(ns);
+    let times = entry.timestamps();
+    test.check("entry mtime and access date", times.modified == 1_721_051_130 && times.accessed == 1_721_001_600);
+    entry.set_accessed(ns + 86_400 * NSEC_PER_SEC);
+    test.check("access date moves, mtime kept", entry.accessed() == 1_721_088_000 && entry.modified() == 1_721_051_130);
+
+    // CLOCK_MONOTONIC = 1
+    let mut ts = [0u8; 16];
+    let before = clock::monotonic_ns();
+    let ret = syscall::syscall_handler(syscall::SYS_CLOCK_GETTIME, [1, ts.as_mut_ptr() as usize, 0, 0, 0, 0]);
+    let after = clock::monotonic_ns();
+    let sec = u64::from_le_bytes(ts[0..8].try_into().unwrap());
+    let nsec = u64::from_le_bytes(ts[8..16].try_into().unwrap());
+    let got = sec * NSEC_PER_SEC + nsec;
+    test.check("clock_gettime(MONOTONIC)", ret == 0 && nsec < NSEC_PER_SEC && before <= got && got <= after);
+    let args = [usize::MAX, ts.as_mut_ptr() as usize, 0, 0, 0, 0];
+    test.check("unknown clock is EINVAL", syscall::syscall_handler(syscall::SYS_CLOCK_GETTIME, args) == syscall::errno::EINVAL);
+
+    test.finish();
+}
+
+pub fn ioctl() {
+    use block::ioctl::{BLKGETSIZE64, BLKROGET, BLKSSZGET};
+    use drivers::tty::{Termios, TCGETS};
+    use fs::fd::{self, OpenFlags};
+    use fs::FileMode;
+
+    let mut test = SelfTest::new("ioctl");
+
+    // 커널 FD 테이블에 열어 syscall 경로로 호출
+    let ioctl = |fd: i32, cmd: u32, arg: usize| {
+        syscall::syscall_handler(syscall::SYS_IOCTL, [fd as usize, cmd as usize, arg, 0, 0, 0])
+    };
+    let open = |path: &str| {
+        let table = fd::kernel_fd_table()?;
+        let file = fd::open(path, OpenFlags::new(OpenFlags::O_RDWR), FileMode::new(0o644))?;
+        table.insert(alloc::sync::Arc::new(file))
+    };
+
+    match open("/dev/console") {
+        Ok(console_fd) => {
+            let mut termios = Termios::cooked();
+            termios.c_lflag = 0;
+            let ret = ioctl(console_fd, TCGETS, &mut termios as *mut Termios as usize);
+            test.check("TCGETS on /dev/console fd", ret == 0 && termios == drivers::tty::console().termios());
+            test.check("unknown command on /dev/console is ENOTTY", ioctl(console_fd, 0x1234, 0) == syscall::errno::ENOTTY);
+            let _ = fd::kernel_fd_table().map(|table| table.close(console_fd));
+        }
+        Err(e) => test.check(&alloc::format!("open /dev/console ({})", e), false),
+    }
+
+    match open("/dev/null") {
+        Ok(null_fd) => {
+            test.check("TCGETS on /dev/null is ENOTTY", ioctl(null_fd, TCGETS, 0) == syscall::errno::ENOTTY);
+            let _ = fd::kernel_fd_table().map(|table| table.close(null_fd));
+        }
+        Err(e) => test.check(&alloc::format!("open /dev/null ({})", e), false),
+    }
+    test.check("invalid fd is rejected", ioctl(-1, TCGETS, 0) < 0);
+
+    // 블록 디바이스는 디스크가 붙어 있을 때만
+    match block::list_devices().first().and_then(|name| block::get_device(name).map(|dev| (name.clone(), dev))) {
+        Some((name, dev)) => match open(&alloc::format!("/dev/{}", name)) {
+            Ok(blk_fd) => {
+                let mut size = 0u64;
+                let mut sector = 0i32;
+                let mut ro = -1i32;
+                let ret = ioctl(blk_fd, BLKGETSIZE64, &mut size as *mut u64 as usize);
+                test.check("BLKGETSIZE64 matches capacity", ret == 0 && size == dev.capacity());
+                let ret = ioctl(blk_fd, BLKSSZGET, &mut sector as *mut i32 as usize);
+                test.check("BLKSSZGET matches block size", ret == 0 && sector as usize == dev.block_size());
+                let ret = ioctl(blk_fd, BLKROGET, &mut ro as *mut i32 as usize);
+                test.check("BLKROGET reports read-only flag", ret == 0 && (ro != 0) == dev.is_read_only());
+                test.check("TCGETS on block device is ENOTTY", ioctl(blk_fd, TCGETS, 0) == syscall::errno::ENOTTY);
+                let _ = fd::kernel_fd_table().map(|table| table.close(blk_fd));
+            }
+            Err(e) => test.check(&alloc::format!("open /dev/{} ({})", name, e), false),
+        },
+        None => kprintln!("  [SKIP] no block device attached"),
+    }
+
+    test.finish();
+}
+
+pub fn poll() {
+    use alloc::sync::Arc;
+    use fs::fd::{self, OpenFile, OpenFlags};
+    use fs::poll::{self, PollFd, POLLHUP, POLLIN, POLLNVAL, POLLOUT};
+    use fs::VNode;
+    use ipc::{BoundedMessageQueue, MessageQueue};
+
+    // 파이프 쓰기 끝 (쓰는 스레드에 넘김)
+    static WRITER: sync::Mutex<Option<Arc<dyn VNode>>> = sync::Mutex::new(None);
+
+    // 잠시 뒤 한 바이트를 쓰고 쓰기 끝을 닫음
+    fn late_writer() -> ! {
+        proc::park_timeout(20_000);
+        if let Some(writer) = WRITER.lock().take() {
+            let _ = writer.write(0, b"x");
+        }
+        proc::exit();
+    }
+
+    let mut test = SelfTest::new("poll");
+
+    let table = match fd::kernel_fd_table() {
+        Ok(table) => table,
+        Err(e) => {
+            kprintln!("\n[FAIL] selftest poll: fd table: {}", e);
+            return;
+        }
+    };
+    let (reader, writer) = fs::pipe::create_pipe(false);
+    let read_fd = match table.insert(Arc::new(OpenFile::new(reader, OpenFlags::new(OpenFlags::O_RDONLY)))) {
+        Ok(fd) => fd,
+        Err(e) => {
+            kprintln!("\n[FAIL] selftest poll: insert: {}", e);
+            return;
+        }
+    };
+
+    let mut fds = [PollFd { fd: read_fd, events: POLLIN, revents: 0 }];
+    test.check("empty pipe is not readable", poll::poll(&table, &mut fds, Some(0)) == Ok(0) && fds[0].revents == 0);
+    test.check("pipe write end is writable", writer.poll(POLLOUT, None) == POLLOUT);
+
+    let start = time::now_us();
+    let timed_out = poll::poll(&table, &mut fds, Some(20_000)) == Ok(0);
+    test.check("timeout returns 0 after the deadline", timed_out && time::now_us() - start >= 20_000);
+
+    // 다른 스레드가 쓰면 대기자가 깨어남 (SYS_PPOLL, 타임아웃 없음)
+    *WRITER.lock() = Some(writer);
+    proc::spawn("poll-writer", late_writer);
+    fds[0].revents = 0;
+    let ret = syscall::syscall_handler(syscall::SYS_PPOLL, [fds.as_mut_ptr() as usize, 1, 0, 0, 0, 0]);
+    test.check("ppoll wakes when a writer fills the pipe", ret == 1 && fds[0].revents & POLLIN != 0);
+
+    // 쓰기 끝이 닫힌 뒤 남은 데이터는 POLLIN, 비우면 POLLHUP만
+    for _ in 0..100 {
+        if WRITER.lock().is_none() {
+            break;
+        }
+        proc::yield_now();
+    }
+    let _ = poll::poll(&table, &mut fds, Some(0));
+    test.check("closed writer with data is POLLIN|POLLHUP", fds[0].revents == POLLIN | POLLHUP);
+    let mut byte = [0u8; 1];
+    let _ = table.get(read_fd).and_then(|file| file.read(&mut byte));
+    let _ = poll::poll(&table, &mut fds, Some(0));
+    test.check("drained pipe with no writer is POLLHUP", fds[0].revents == POLLHUP);
+    let _ = table.close(read_fd);
+
+    let mut fds = [
+        PollFd { fd: -1, events: POLLIN, revents: POLLIN },
+        PollFd { fd: read_fd, events: POLLIN, revents: 0 },
+    ];
+    let ready = poll::poll(&table, &mut fds, Some(0));
+    test.check("negative fd ignored, closed fd is POLLNVAL", ready == Ok(1) && fds[0].revents == 0 && fds[1].revents == POLLNVAL);
+
+    match fs::lookup_path("/dev/console") {
+        Ok(dev) => test.check("console is writable", dev.poll(POLLOUT, None) == POLLOUT),
+        Err(e) => test.check(&alloc::format!("/dev/console ({})", e), false),
+    }
+
+    let mq: MessageQueue<u32> = MessageQueue::new();
+    let empty = mq.poll(POLLIN | POLLOUT, None) == POLLOUT;
+    let _ = mq.send(1);
+    let full = mq.poll(POLLIN, None) == POLLIN;
+    let _ = mq.try_receive();
+    mq.close();
+    test.check("message queue readiness and POLLHUP after close", empty && full && mq.poll(POLLIN, None) == POLLHUP);
+    let bounded: BoundedMessageQueue<u32> = BoundedMessageQueue::new(1);
+    let _ = bounded.try_send(1);
+    test.check("full bounded queue is not writable", bounded.poll(POLLIN | POLLOUT, None) == POLLIN);
+
+    test.finish();
+}
+
+pub fn fd() {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use fs::fd::{self, FdTable, OpenFlags, SeekFrom};
+    use fs::flock::LockKind;
+    use fs::{FileMode, VfsError};
+
+    const PATH: &str = "/selftest-fd.txt";
+
+    const OUT_PATH: &str = "/selftest-fd-out.txt";
+
+    // 상속받은 테이블의 stdout에 씀
+    fn redirected_child() -> ! {
+        if let Ok(table) = fd::current_fd_table() {
+            let _ = table.get(fd::STDOUT_FD).and_then(|out| out.write(b"from child"));
+        }
+        proc::exit();
+    }
+
+    static ACQUIRED: AtomicBool = AtomicBool::new(false);
+    fn flock_waiter() -> ! {
+        {
+            let file = fd::open(PATH, OpenFlags::new(OpenFlags::O_RDONLY), FileMode::new(0o644));
+            if file.and_then(|f| f.lock(LockKind::Shared, false)).is_ok() {
+                ACQUIRED.store(true, Ordering::Release);
+            }
+        }
+        proc::exit();
+    }
+
+    let mut test = SelfTest::new("fd");
+
+    let _ = fs::lookup_path("/").and_then(|root| root.unlink(&PATH[1..]));
+    let mode = FileMode::new(0o644);
+    let rw_create = OpenFlags::new(OpenFlags::O_RDWR | OpenFlags::O_CREAT);
+    let table = FdTable::new(8);
+
+    test.check("open missing without O_CREAT", matches!(
+        fd::open(PATH, OpenFlags::new(OpenFlags::O_RDONLY), mode),
+        Err(VfsError::NotFound)
+    ));
+    let a = match fd::open(PATH, rw_create, mode) {
+        Ok(file) => table.insert(Arc::new(file)).unwrap_or(-1),
+        Err(_) => -1,
+    };
+    test.check("O_CREAT", a >= 0);
+    if a < 0 {
+        kprintln!("\n[FAIL] selftest fd");
+        return;
+    }
+    let file_a = table.get(a).unwrap();
+    test.check("write advances offset", file_a.write(b"hello").ok() == Some(5) && file_a.tell() == 5);
+
+    test.check("O_CREAT|O_EXCL on existing", matches!(
+        fd::open(PATH, OpenFlags::new(rw_create.0 | OpenFlags::O_EXCL), mode),
+        Err(VfsError::AlreadyExists)
+    ));
+    test.check("O_DIRECTORY on file", matches!(
+        fd::open(PATH, OpenFlags::new(OpenFlags::O_RDONLY | OpenFlags::O_DIRECTORY), mode),
+        Err(VfsError::NotADirectory)
+    ));
+
+    // 따로 연 fd는 오프셋이 독립
+    let b = fd::open(PATH, OpenFlags::new(OpenFlags::O_RDONLY), mode)
+        .map(|file| table.insert(Arc::new(file)).unwrap_or(-1))
+        .unwrap_or(-1);
+    let mut buf = [0u8; 8];
+    let n = table.get(b).and_then(|file| file.read(&mut buf)).unwrap_or(0);
+    test.check("separate open has own offset", n == 5 && &buf[..5] == b"hello" && file_a.tell() == 5);
+
+    // dup은 오프셋 공유
+    let c = table.dup(a).unwrap_or(-1);
+    let _ = table.get(c).and_then(|file| file.seek(SeekFrom::Start(1)));
+    test.check("dup shares offset", file_a.tell() == 1);
+    test.check("dup2 onto same fd", table.dup2(a, a).ok() == Some(a) && table.get(a).is_ok());
+
+    let append = fd::open(PATH, OpenFlags::new(OpenFlags::O_WRONLY | OpenFlags::O_APPEND), mode);
+    let appended = append.and_then(|file| file.write(b"!").map(|_| file.tell())).ok();
+    test.check("O_APPEND writes at end", appended == Some(6));
+
+    let trunc = fd::open(PATH, OpenFlags::new(OpenFlags::O_WRONLY | OpenFlags::O_TRUNC), mode);
+    let size = trunc.and_then(|file| file.vnode.stat()).map(|st| st.size).ok();
+    test.check("O_TRUNC", size == Some(0));
+
+    // flock: 따로 연 파일끼리 충돌, dup한 fd는 공유, 마지막 fd를 닫으면 해제
+    let ro = OpenFlags::new(OpenFlags::O_RDONLY);
+    if let (Ok(x), Ok(y)) = (fd::open(PATH, ro, mode), fd::open(PATH, ro, mode)) {
+        let (x, y) = (Arc::new(x), Arc::new(y));
+        test.check("flock exclusive", x.lock(LockKind::Exclusive, true).is_ok());
+        test.check("flock conflicts across opens", y.lock(LockKind::Shared, true) == Err(VfsError::WouldBlock));
+        test.check("flock downgrade admits shared", x.lock(LockKind::Shared, true).is_ok() && y.lock(LockKind::Shared, true).is_ok());
+        test.check("flock upgrade blocked by shared", y.lock(LockKind::Exclusive, true) == Err(VfsError::WouldBlock));
+        x.unlock();
+        test.check("flock upgrade after unlock", y.lock(LockKind::Exclusive, true).is_ok());
+
+        ACQUIRED.store(false, Ordering::Release);
+        let tid = proc::spawn("flock-waiter", flock_waiter);
+        for _ in 0..20 {
+            proc::yield_now();
+        }
+        test.check("blocking flock waits", !ACQUIRED.load(Ordering::Acquire));
+        y.unlock();
+        test.check("blocking flock acquired after unlock", proc::join(tid) == Ok(0) && ACQUIRED.load(Ordering::Acquire));
+
+        let _ = x.lock(LockKind::Exclusive, true);
+        let fx = table.insert(x).unwrap_or(-1);
+        let fx2 = table.dup(fx).unwrap_or(-1);
+        let _ = table.close(fx);
+        test.check("flock kept while dup open", y.lock(LockKind::Shared, true) == Err(VfsError::WouldBlock));
+        let _ = table.close(fx2);
+        test.check("close releases flock", y.lock(LockKind::Exclusive, true).is_ok());
+    } else {
+        test.check("flock open", false);
+    }
+
+    // fd 테이블 상속: fork한 테이블은 같은 열린 파일을 공유, exec 때 FD_CLOEXEC만 닫힘
+    let child = table.fork();
+    test.check("fork copies fds", child.count() == table.count() && child.count() > 0);
+    let extra = fd::open(PATH, OpenFlags::new(OpenFlags::O_RDONLY), mode)
+        .and_then(|file| child.insert_from(0, Arc::new(file), true))
+        .unwrap_or(-1);
+    test.check("fork shares open file", matches!((table.get(a), child.get(a)), (Ok(p), Ok(c)) if Arc::ptr_eq(&p, &c)));
+    test.check("close_on_exec", child.cloexec(extra) == Ok(true) && child.close_on_exec() == 1 && child.get(extra).is_err());
+    test.check("child close leaves parent", child.close(a).is_ok() && table.get(a).is_ok());
+
+    // 자기 테이블을 가진 스레드의 stdout을 파일로 리다이렉트
+    let _ = fs::lookup_path("/").and_then(|root| root.unlink(&OUT_PATH[1..]));
+    if let Ok(kernel) = fd::kernel_fd_table() {
+        let child = kernel.fork();
+        let out_flags = OpenFlags::new(OpenFlags::O_WRONLY | OpenFlags::O_CREAT | OpenFlags::O_TRUNC);
+        test.check("redirect stdout", child.redirect(fd::STDOUT_FD, OUT_PATH, out_flags, mode).is_ok());
+        let parent_out = kernel.get(fd::STDOUT_FD).map(|f| f.path.is_empty());
+        test.check("parent stdout unchanged", parent_out == Ok(true));
+        let tid = proc::spawn_with_files("fd-child", redirected_child, Arc::new(child));
+        let status = proc::join(tid);
+        let mut out = [0u8; 16];
+        let n = fs::lookup_path(OUT_PATH).and_then(|node| node.read(0, &mut out)).unwrap_or(0);
+        test.check("child stdout written to file", status == Ok(0) && &out[..n] == b"from child");
+    } else {
+        test.check("kernel fd table", false);
+    }
+
+    table.close_all();
+    let _ = fs::lookup_path("/").and_then(|root| root.unlink(&PATH[1..]));
+    let _ = fs::lookup_path("/").and_then(|root| root.unlink(&OUT_PATH[1..]));
+
+    test.finish();
+}
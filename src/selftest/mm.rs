@@ -0,0 +1,468 @@
+//! 메모리 관리 셀프테스트 (프레임/buddy 할당자, 커널 매핑, OOM, DMA, 힙 red zone)
+
+use super::SelfTest;
+use crate::{arch, debug, mm, proc, sysctl};
+use crate::kprintln;
+
+/// mm.smp 셀프테스트: 워커 중지 플래그
+static MM_SMP_STOP: core::sync::atomic::AtomicBool = core::sync::atomic::AtomicBool::new(false);
+
+/// mm.smp 셀프테스트: 종료한 워커 수
+static MM_SMP_DONE: core::sync::atomic::AtomicU32 = core::sync::atomic::AtomicU32::new(0);
+
+/// mm.smp 셀프테스트: CPU별 할당+해제 횟수
+static MM_SMP_OPS: [core::sync::atomic::AtomicU64; proc::percpu::MAX_CPUS] =
+    [const { core::sync::atomic::AtomicU64::new(0) }; proc::percpu::MAX_CPUS];
+
+/// mm.smp 셀프테스트 측정 시간 (틱, 10ms 단위)
+const MM_SMP_DURATION_TICKS: u64 = 100;
+
+/// 프레임 할당자를 반복 호출하는 워커 스레드
+fn mm_smp_worker() -> ! {
+    use core::sync::atomic::Ordering;
+
+    let cpu = proc::percpu::get_cpu_id() as usize;
+    let mut frames = [0usize; 8];
+
+    while !MM_SMP_STOP.load(Ordering::Relaxed) {
+        for frame in frames.iter_mut() {
+            *frame = mm::page::alloc_frame().unwrap_or(0);
+        }
+        for &frame in frames.iter() {
+            if frame != 0 {
+                unsafe { mm::page::free_frame(frame) };
+            }
+        }
+        MM_SMP_OPS[cpu].fetch_add(frames.len() as u64, Ordering::Relaxed);
+    }
+
+    MM_SMP_DONE.fetch_add(1, Ordering::SeqCst);
+    proc::exit();
+}
+
+/// CPU마다 워커 하나씩 실행하고 초당 alloc+free 쌍 수 반환
+fn run_mm_smp_round(cpus: u32) -> u64 {
+    use core::sync::atomic::Ordering;
+
+    MM_SMP_STOP.store(false, Ordering::SeqCst);
+    MM_SMP_DONE.store(0, Ordering::SeqCst);
+    for ops in MM_SMP_OPS.iter() {
+        ops.store(0, Ordering::SeqCst);
+    }
+
+    for cpu in 0..cpus {
+        let name = alloc::format!("mm-hammer/{}", cpu);
+        proc::spawn_on_cpu(&name, mm_smp_worker, cpu);
+    }
+
+    let start = arch::timer::ticks();
+    while arch::timer::ticks() - start < MM_SMP_DURATION_TICKS {
+        proc::yield_now();
+    }
+    MM_SMP_STOP.store(true, Ordering::SeqCst);
+    let elapsed = arch::timer::ticks() - start;
+
+    while MM_SMP_DONE.load(Ordering::SeqCst) < cpus {
+        proc::yield_now();
+    }
+
+    for cpu in 0..cpus {
+        kprintln!("    CPU {}: {} ops", cpu, MM_SMP_OPS[cpu as usize].load(Ordering::Relaxed));
+    }
+    let total: u64 = MM_SMP_OPS.iter().map(|ops| ops.load(Ordering::Relaxed)).sum();
+    total * 100 / elapsed.max(1)
+}
+
+/// 페이지 프레임 할당자 SMP 확장성 셀프테스트
+///
+/// 전역 락만 사용할 때와 CPU별 매거진을 사용할 때의 처리량을 비교합니다.
+pub fn smp() {
+    let cpus = proc::percpu::online_count();
+    let was_enabled = mm::page::percpu_cache_enabled();
+
+    kprintln!("\n=== selftest mm.smp ({} CPUs) ===\n", cpus);
+    let stats = mm::page::stats();
+    let free_before = stats.free_pages + stats.cached_pages;
+
+    kprintln!("[Round 1] Global lock only");
+    mm::page::set_percpu_cache(false);
+    let global_rate = run_mm_smp_round(cpus);
+    kprintln!("  Throughput: {} ops/s", global_rate);
+
+    kprintln!("\n[Round 2] Per-CPU magazines");
+    mm::page::set_percpu_cache(true);
+    let cached_rate = run_mm_smp_round(cpus);
+    kprintln!("  Throughput: {} ops/s", cached_rate);
+
+    mm::page::set_percpu_cache(was_enabled);
+    let stats = mm::page::stats();
+    let free_after = stats.free_pages + stats.cached_pages;
+
+    kprintln!(
+        "\nSpeedup: {}.{:02}x",
+        cached_rate / global_rate.max(1),
+        (cached_rate * 100 / global_rate.max(1)) % 100
+    );
+    if free_before == free_after {
+        kprintln!("[PASS] No frames leaked");
+    } else {
+        kprintln!("[FAIL] Free frames {} -> {}", free_before, free_after);
+    }
+}
+
+/// Buddy 할당자 셀프테스트
+///
+/// order별 할당 후 해제 시 buddy 병합으로 free 블록 분포가 원래대로 돌아오는지 확인하고,
+/// 최댓값, 연속 free 구간, order별 실패 수 통계를 확인합니다.
+pub fn buddy() {
+    let mut test = SelfTest::new("mm.buddy");
+
+    // 매거진에 보관된 프레임이 병합을 막지 않도록 비움
+    mm::page::drain_percpu_caches();
+    let before = mm::page::stats();
+
+    kprintln!("[Test 1] Order-N allocations");
+    let mut blocks = alloc::vec::Vec::new();
+    for order in 0..=4 {
+        match mm::page::alloc_frames_order(order) {
+            Some(addr) => {
+                kprintln!("  order {} ({} pages): {:#x}", order, 1 << order, addr);
+                blocks.push((addr, order));
+            }
+            None => {
+                kprintln!("  [FAIL] order {} allocation failed", order);
+                test.fail();
+            }
+        }
+    }
+
+    kprintln!("[Test 2] Non power-of-two allocation (3 pages)");
+    let triple = mm::page::alloc_frames(3);
+    let used = mm::page::stats().allocated_pages - before.allocated_pages;
+    if triple.is_some() && used == 31 + 3 {
+        kprintln!("  [PASS] exactly {} pages allocated", used);
+    } else {
+        kprintln!("  [FAIL] expected 34 pages allocated, got {}", used);
+        test.fail();
+    }
+
+    kprintln!("[Test 3] Free and coalesce");
+    for &(addr, order) in blocks.iter().rev() {
+        unsafe { mm::page::free_frames_order(addr, order) };
+    }
+    if let Some(addr) = triple {
+        unsafe { mm::page::free_frames(addr, 3) };
+    }
+
+    let after = mm::page::stats();
+    if after.free_pages != before.free_pages {
+        kprintln!("  [FAIL] free pages {} -> {}", before.free_pages, after.free_pages);
+        test.fail();
+    } else if after.free_blocks != before.free_blocks {
+        kprintln!("  [FAIL] free blocks not coalesced");
+        kprintln!("    before: {:?}", before.free_blocks);
+        kprintln!("    after:  {:?}", after.free_blocks);
+        test.fail();
+    } else {
+        kprintln!("  [PASS] free block distribution restored: {:?}", after.free_blocks);
+    }
+
+    kprintln!("[Test 4] Statistics");
+    {
+        use debug::failinject::{self, Config, Point};
+
+        let buddy_free: usize = after.free_blocks.iter().enumerate().map(|(k, n)| n << k).sum();
+        test.check("free blocks add up to free pages", buddy_free == after.free_pages);
+        // Test 3에서 해제되어 더 큰 블록으로 병합된 order 0 블록을 다시 해제
+        if let Some(&(addr, order)) = blocks.first() {
+            unsafe { mm::page::free_frames_order(addr, order) };
+            let again = mm::page::stats();
+            test.check(
+                "double free of merged block rejected",
+                again.free_pages == after.free_pages && again.free_blocks == after.free_blocks,
+            );
+        }
+        test.check("peak covers test allocations", after.peak_allocated_pages >= before.allocated_pages + 34);
+        let top = (0..=mm::page::MAX_ORDER).rev().find(|&k| after.free_blocks[k] > 0).unwrap_or(0);
+        test.check(
+            "largest free run within free pages",
+            after.largest_free_run >= 1 << top && after.largest_free_run <= after.free_pages,
+        );
+        test.check(
+            "unusable index grows with order",
+            after.unusable_percent(0) == 0
+                && (1..=mm::page::MAX_ORDER).all(|k| after.unusable_percent(k) >= after.unusable_percent(k - 1)),
+        );
+
+        failinject::configure(
+            Point::Frame,
+            Config { times: Some(1), tid: proc::current_tid(), ..Config::default() },
+        );
+        let failed = mm::page::alloc_frames_order(2);
+        failinject::disable(Point::Frame);
+        let stats = mm::page::stats();
+        test.check(
+            "failure counted by order",
+            failed.is_none() && stats.failed_allocs[2] == after.failed_allocs[2] + 1,
+        );
+        test.check("oversized request counted", {
+            let before = stats.failed_allocs[mm::page::MAX_ORDER];
+            mm::page::alloc_frames_order(mm::page::MAX_ORDER + 1).is_none()
+                && mm::page::stats().failed_allocs[mm::page::MAX_ORDER] == before + 1
+        });
+    }
+    mm::page::print_stats();
+
+    test.finish();
+}
+
+/// 커널 매핑 셀프테스트: 구간별 권한, 런타임 `map_range`/`protect_range`, kprobe용 임시 쓰기 허용
+pub fn kmap() {
+    use alloc::boxed::Box;
+    use arch::mmu::{self, page_permissions, MapKind};
+    use core::sync::atomic::AtomicUsize;
+
+    const PAGE: usize = mm::page::PAGE_SIZE;
+    /// VMA 영역(64GB~)과 겹치지 않는 빈 가상 주소
+    const ALIAS: usize = 0x30_0000_0000;
+    static RODATA: [u8; 8] = *b"kerners!";
+    static DATA: AtomicUsize = AtomicUsize::new(1);
+
+    let mut test = SelfTest::new("kmap");
+
+    let text = kmap as fn() as usize;
+    let stack = 0u64;
+    let heap = Box::new(0u64);
+    test.check(".text: read + execute", page_permissions(text) == Some((false, true)));
+    test.check(".rodata: read only", page_permissions(RODATA.as_ptr() as usize) == Some((false, false)));
+    test.check(".data: read/write, no execute", page_permissions(DATA.as_ptr() as usize) == Some((true, false)));
+    test.check("stack: read/write, no execute", page_permissions(&stack as *const u64 as usize) == Some((true, false)));
+    test.check("heap: read/write, no execute", page_permissions(&*heap as *const u64 as usize) == Some((true, false)));
+    test.check(
+        "kprobe patch window makes .text writable",
+        mmu::with_text_writable(text, 4, || page_permissions(text)) == Some((true, true))
+            && page_permissions(text) == Some((false, true)),
+    );
+
+    match mm::page::alloc_frames(2) {
+        Some(frames) => {
+            test.check("map_range", mmu::map_range(ALIAS, frames, 2 * PAGE, MapKind::Data).is_ok());
+            test.check("alias translates", mmu::translate(ALIAS + PAGE + 8) == Some(frames + PAGE + 8));
+            test.check("alias: read/write, no execute", page_permissions(ALIAS) == Some((true, false)));
+            test.check("alias unmapped", mmu::unmap_page(ALIAS) == Some(frames) && mmu::unmap_page(ALIAS + PAGE) == Some(frames + PAGE));
+
+            test.check("protect_range read-only", mmu::protect_range(frames, 2 * PAGE, false, false).is_ok());
+            test.check("both pages read-only", page_permissions(frames) == Some((false, false)) && page_permissions(frames + PAGE) == Some((false, false)));
+            test.check("protect_range restore", mmu::protect_range(frames, 2 * PAGE, true, false).is_ok());
+            test.check("both pages read/write", page_permissions(frames) == Some((true, false)) && page_permissions(frames + PAGE) == Some((true, false)));
+            unsafe { mm::page::free_frames(frames, 2) };
+        }
+        None => test.check("alloc frames", false),
+    }
+
+    test.finish();
+}
+
+/// OOM 셀프테스트: 압박 통지 회수와 재시도, VMA를 가장 많이 가진 스레드 종료와 해제
+pub fn oom() {
+    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use mm::oom::{self, Notifier};
+    use mm::page::{self, PAGE_SIZE};
+    use mm::vma::{self, VmaFlags};
+    use proc::signal;
+
+    const HOG_PAGES: usize = 32;
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    static HOG_READY: AtomicBool = AtomicBool::new(false);
+    static NOTIFIER: Notifier = Notifier {
+        name: "selftest",
+        reclaim: |pages| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+            pages.min(4)
+        },
+    };
+
+    /// VMA 페이지를 채우고 양보만 하는 스레드 (SIGKILL을 받으면 양보할 때 종료)
+    fn hog() -> ! {
+        if let Ok(base) = vma::map_anonymous(HOG_PAGES * PAGE_SIZE, VmaFlags::RW, "oom-hog") {
+            for i in 0..HOG_PAGES {
+                unsafe { core::ptr::write_volatile((base + i * PAGE_SIZE) as *mut usize, i) };
+            }
+        }
+        HOG_READY.store(true, Ordering::Release);
+        loop {
+            proc::yield_now();
+        }
+    }
+
+    let mut test = SelfTest::new("oom");
+
+    // 압박 통지
+    CALLS.store(0, Ordering::Relaxed);
+    test.check("notifier registered", oom::register_notifier(&NOTIFIER).is_ok());
+    test.check("duplicate notifier refused", oom::register_notifier(&NOTIFIER).is_err());
+    let frame = page::alloc_frame();
+    if let Some(frame) = frame {
+        unsafe { page::free_frame(frame) };
+    }
+    test.check("reclaim reaches notifier", oom::reclaim(usize::MAX) >= 4 && CALLS.load(Ordering::Relaxed) >= 1);
+    let before = oom::stats();
+    test.check("enough reclaimed: caller retries", oom::out_of_memory(2));
+    test.check("no kill after successful reclaim", oom::stats().kills == before.kills);
+    test.check("notifier unregistered", oom::unregister_notifier(&NOTIFIER) && !oom::unregister_notifier(&NOTIFIER));
+
+    // M-mode 커널은 satp 변환을 거치지 않으므로 커널 스레드에서 VMA 접근 불가
+    if cfg!(all(target_arch = "riscv64", not(feature = "riscv_smode"))) {
+        kprintln!("  Skipped kill tests: M-mode kernel accesses bypass Sv39 translation");
+    } else {
+        HOG_READY.store(false, Ordering::Relaxed);
+        let tid = proc::spawn("oom-hog", hog);
+        for _ in 0..1000 {
+            if HOG_READY.load(Ordering::Acquire) {
+                break;
+            }
+            proc::yield_now();
+        }
+        test.check("hog touched its pages", vma::try_owned_pages(tid) == Some(HOG_PAGES));
+        test.check("hog selected as victim", oom::select_victim().map(|v| v.tid) == Some(tid));
+
+        // 끄면 회수만 하고 죽이지 않음
+        let _ = sysctl::set("mm.oom_kill", "0");
+        let before = oom::stats();
+        test.check("mm.oom_kill=0: allocation fails", !oom::out_of_memory(1 << 20));
+        test.check("mm.oom_kill=0: nothing killed", oom::stats().kills == before.kills && !oom::is_victim(tid));
+        let _ = sysctl::set("mm.oom_kill", "1");
+
+        let free_before = page::stats().free_pages + page::stats().cached_pages;
+        test.check("unreclaimable request fails", !oom::out_of_memory(1 << 20));
+        test.check("hog killed", oom::stats().kills == before.kills + 1 && oom::is_victim(tid));
+        test.check("no second kill while victim exits", oom::kill_victim().is_none());
+        test.check("victim exits with SIGKILL", proc::join(tid) == Ok(signal::exit_status(signal::SIGKILL)));
+        test.check("victim VMA released", vma::try_owned_pages(tid) == Some(0));
+        let free_after = page::stats().free_pages + page::stats().cached_pages;
+        test.check("victim frames returned", free_after >= free_before + HOG_PAGES / 2);
+        test.check("victim no longer a candidate", oom::select_victim().is_none_or(|v| v.tid != tid));
+    }
+
+    test.finish();
+}
+
+/// DMA 버퍼 셀프테스트: 일관성 버퍼 할당/해제, 계수, 캐시 정리, 캐시 안 되는 매핑
+pub fn dma() {
+    use mm::dma::{self, DmaBuffer};
+    use mm::page::PAGE_SIZE;
+
+    let mut test = SelfTest::new("dma");
+
+    kprintln!("  Devices: {}", if dma::is_coherent() { "coherent" } else { "non-coherent" });
+    let before = dma::stats();
+    match dma::alloc_coherent(100) {
+        Some((vaddr, paddr)) => {
+            test.check("page aligned", vaddr % PAGE_SIZE == 0 && paddr % PAGE_SIZE == 0);
+            test.check("identity mapped (paddr == vaddr)", paddr == vaddr);
+            let buf = unsafe { core::slice::from_raw_parts_mut(vaddr as *mut u8, PAGE_SIZE) };
+            test.check("zeroed", buf.iter().all(|&b| b == 0));
+            test.check("counted", dma::stats().buffers == before.buffers + 1 && dma::stats().pages == before.pages + 1);
+
+            for (i, b) in buf.iter_mut().enumerate() {
+                *b = i as u8;
+            }
+            dma::sync_for_device(vaddr, PAGE_SIZE);
+            dma::sync_for_cpu(vaddr, PAGE_SIZE);
+            test.check("data survives sync", buf.iter().enumerate().all(|(i, &b)| b == i as u8));
+
+            unsafe { dma::free_coherent(vaddr, 100) };
+            test.check("freed", dma::stats().buffers == before.buffers && dma::stats().pages == before.pages);
+        }
+        None => test.check("alloc_coherent(100)", false),
+    }
+
+    match DmaBuffer::new(3 * PAGE_SIZE + 1) {
+        Some(buf) => {
+            test.check("DmaBuffer rounds up to 4 pages", dma::stats().pages == before.pages + 4);
+            test.check("DmaBuffer keeps requested size", buf.size() == 3 * PAGE_SIZE + 1);
+            drop(buf);
+            test.check("DmaBuffer freed on drop", dma::stats().pages == before.pages);
+        }
+        None => test.check("DmaBuffer::new", false),
+    }
+
+    // 일관성 없는 디바이스용 Normal Non-cacheable 매핑 (QEMU virt는 일관적이라 직접 확인)
+    #[cfg(target_arch = "aarch64")]
+    if let Some(frame) = mm::page::alloc_frame() {
+        arch::cache::flush_range(frame, PAGE_SIZE);
+        let ok = arch::mmu::set_cacheable(frame, PAGE_SIZE, false).is_ok();
+        test.check("remap uncached", ok);
+        if ok {
+            unsafe { core::ptr::write_volatile(frame as *mut u64, 0x5a5a_a5a5) };
+            test.check("uncached read back", unsafe { core::ptr::read_volatile(frame as *const u64) } == 0x5a5a_a5a5);
+            test.check("restore cacheable", arch::mmu::set_cacheable(frame, PAGE_SIZE, true).is_ok());
+        }
+        unsafe { mm::page::free_frame(frame) };
+    }
+
+    test.finish();
+}
+
+/// redzone selftest: 레드존 밖 쓰기, 두 번 해제 보고와 해제 포이즈닝
+#[cfg(feature = "heap_redzone")]
+pub fn redzone() {
+    use alloc::alloc::{alloc, dealloc};
+    use core::alloc::Layout;
+    use mm::redzone::{errors, REDZONE_BYTE};
+
+    let mut test = SelfTest::new("redzone");
+    kprintln!("  (the [heap] BUG reports below are expected)");
+
+
+    let layout = Layout::from_size_align(24, 8).unwrap();
+
+    // 정상 사용은 보고 없음
+    let before = errors();
+    unsafe {
+        let ptr = alloc(layout);
+        test.check("redzone around allocation", *ptr.sub(1) == REDZONE_BYTE && *ptr.add(24) == REDZONE_BYTE);
+        core::ptr::write_bytes(ptr, 0x11, 24);
+        dealloc(ptr, layout);
+    }
+    let wide = Layout::from_size_align(100, 64).unwrap();
+    unsafe {
+        let ptr = alloc(wide);
+        test.check("alignment kept", ptr as usize % 64 == 0);
+        core::ptr::write_bytes(ptr, 0x22, 100);
+        dealloc(ptr, wide);
+    }
+    test.check("in-bounds use not reported", errors() == before);
+
+    // 끝 바로 뒤 한 바이트
+    let before = errors();
+    unsafe {
+        let ptr = alloc(layout);
+        *ptr.add(24) = 0;
+        dealloc(ptr, layout);
+    }
+    test.check("overflow reported", errors() == before + 1);
+
+    // 시작 바로 앞 한 바이트
+    let before = errors();
+    unsafe {
+        let ptr = alloc(layout);
+        *ptr.sub(1) = 0;
+        dealloc(ptr, layout);
+    }
+    test.check("underflow reported", errors() == before + 1);
+
+    // 해제 뒤 데이터 포이즈닝, 같은 포인터 다시 해제
+    let before = errors();
+    unsafe {
+        let ptr = alloc(layout);
+        core::ptr::write_bytes(ptr, 0x33, 24);
+        dealloc(ptr, layout);
+        let poisoned = (0..24).all(|i| core::ptr::read_volatile(ptr.add(i)) == mm::heap::FREE_POISON);
+        test.check("freed data poisoned", poisoned);
+        dealloc(ptr, layout);
+    }
+    test.check("double free reported", errors() == before + 1);
+
+    test.finish();
+}
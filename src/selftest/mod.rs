@@ -0,0 +1,168 @@
+//! 셸 `selftest` 명령과 공용 도우미
+//!
+//! 셀프테스트는 항목마다 `[PASS]`/`[FAIL]` 줄을 출력하고 마지막에 전체 결과를 한 줄로
+//! 남깁니다. 머리말, 항목 출력, 꼬리말 형식을 `SelfTest` 하나로 맞춥니다.
+//!
+//! ```rust
+//! let mut test = selftest::SelfTest::new("signal");
+//! test.check("ignored signal is dropped", !signal::has_pending(tid));
+//! test.finish(); // "[PASS] selftest signal"
+//! ```
+//!
+//! 테스트 본문은 서브시스템별 하위 모듈에 있고, `run`이 이름으로 골라 실행합니다.
+
+mod debug;
+mod drivers;
+mod fs;
+mod mm;
+mod module;
+mod proc;
+
+use crate::kprintln;
+
+/// `selftest <이름>` 셸 명령
+pub fn run(name: Option<&str>) {
+    match name {
+        Some("mm.smp") => mm::smp(),
+        Some("mm.buddy") => mm::buddy(),
+        Some("sched.policy") => proc::sched_policy(),
+        Some("signal") => proc::signal(),
+        Some("futex") => proc::futex(),
+        Some("join") => proc::join(),
+        Some("park") => proc::park(),
+        Some("rcu") => proc::rcu(),
+        Some("modverify") => module::modverify(),
+        Some("modsandbox") => module::modsandbox(),
+        Some("tls") => proc::tls(),
+        Some("reloc") => module::reloc(),
+        Some("modwx") => module::modwx(),
+        Some("kmap") => mm::kmap(),
+        Some("bench") => debug::bench(),
+        Some("failinject") => debug::failinject(),
+        Some("gdbstub") => debug::gdbstub(),
+        Some("crashdump") => debug::crashdump(),
+        Some("sysctl") => debug::sysctl(),
+        Some("oom") => mm::oom(),
+        Some("dma") => mm::dma(),
+        Some("discard") => drivers::discard(),
+        Some("sdhci") => drivers::sdhci(),
+        Some("hotplug") => proc::hotplug(),
+        Some("schedstat") => proc::schedstat(),
+        Some("nohz") => proc::nohz(),
+        Some("workqueue") => proc::workqueue(),
+        Some("panic") => debug::panic(),
+        Some("reboot") => debug::reboot(),
+        Some("watchdog") => debug::watchdog(),
+        Some("timer") => proc::timer(),
+        Some("time") => fs::time(),
+        Some("fd") => fs::fd(),
+        Some("console") => drivers::console(),
+        Some("color") => drivers::color(),
+        Some("keymap") => drivers::keymap(),
+        Some("evdev") => drivers::evdev(),
+        Some("serial") => drivers::serial(),
+        #[cfg(target_arch = "aarch64")]
+        Some("gic") => drivers::gic(),
+        #[cfg(target_arch = "aarch64")]
+        Some("psci") => drivers::psci(),
+        Some("irq") => drivers::irq(),
+        Some("softirq") => drivers::softirq(),
+        Some("ioctl") => fs::ioctl(),
+        Some("poll") => fs::poll(),
+        Some("kprobe") => debug::kprobe(),
+        Some("trace") => debug::trace(),
+        #[cfg(feature = "lockdep")]
+        Some("lockdep") => debug::lockdep(),
+        #[cfg(feature = "heap_redzone")]
+        Some("redzone") => mm::redzone(),
+        _ => {
+            kprintln!("Usage: selftest <name>");
+            kprintln!("Available tests:");
+            kprintln!("  mm.smp   - Per-CPU frame allocator scalability");
+            kprintln!("  mm.buddy - Buddy allocator order allocation and coalescing, watermark/fragmentation/failure stats");
+            kprintln!("  sched.policy - Scheduling policy decisions on a fake thread table");
+            kprintln!("  signal   - Signal send, ignore and fatal delivery");
+            kprintln!("  futex    - Futex wait/wake and timeout");
+            kprintln!("  join     - Thread join exit status, kill status and detach");
+            kprintln!("  park     - Park/unpark token, timeout and sleeping thread wakeup");
+            kprintln!("  rcu      - RcuCell update waits for readers, old data stays valid, module list lookups");
+            kprintln!("  modverify - CRC32/SHA-256 test vectors, module checksum trailer under off/warn/enforce");
+            kprintln!("  modsandbox - Module init/exit on a dedicated thread, hung init killed after the timeout");
+            kprintln!("  tls      - Thread pointer across switches, per-thread module TLS blocks, resolver, static TLS");
+            kprintln!("  reloc    - Hand-crafted objects: GOT, MOVW/LDST/CONDBR (AArch64), GOT_HI20/ADD/SUB/RVC (RISC-V)");
+            kprintln!("  modwx    - Module W^X: text/PLT read+execute, data no-execute, W+X sections rejected");
+            kprintln!("  kmap     - Kernel mappings: .text RX, .rodata RO, data/stack/heap RW-NX, map_range/protect_range");
+            kprintln!("  bench    - Benchmark percentiles, measure(), module API and a short run of every benchmark");
+            kprintln!("  failinject - every/prob/times/tid rules, heap null, FAT32 mount and module load error paths");
+            kprintln!("  gdbstub  - RSP framing and checksums, register/memory packets, target.xml, Z0/z0 patching");
+            kprintln!("  crashdump - Dump build/parse round trip, checksum, polled write through a partition, save/clear");
+            kprintln!("  sysctl   - Register/lookup, int range and bool parsing, read-only, /proc/sys paths, built-in knobs");
+            kprintln!("  oom      - Pressure notifier reclaim and retry, largest VMA owner killed and its pages freed, mm.oom_kill");
+            kprintln!("  dma      - Coherent buffer alignment, zeroing, accounting, uncached remap (aarch64)");
+            kprintln!("  discard  - Block discard/write_zeroes through a partition, range and read-only checks, fat32.discard");
+            kprintln!("  sdhci    - SD clock divider (v2/v3), R2 response realignment, CSD v1/v2 capacity");
+            kprintln!("  hotplug  - CPU offline/online, thread migration, broken affinity, per-CPU worker rebind");
+            kprintln!("  schedstat - log2 buckets, histogram stats, park/unpark wakeups and switches recorded, reset");
+            kprintln!("  nohz     - Idle CPU skips ticks, tick count catch-up, deadline and IPI wakeups, nohz off");
+            kprintln!("  workqueue - Work runs on worker threads in order, may sleep, per-CPU queues, delay, cancel");
+            kprintln!("  panic    - panic= parsing, address symbolization, frame-pointer walk, log tail");
+            kprintln!("  reboot   - SYS_REBOOT rejects bad magic numbers and unknown commands");
+            kprintln!("  watchdog - Kernel pinging past the timeout, /dev/watchdog takeover, WDIOC ioctls, magic V");
+            kprintln!("  timer    - Callback timer one-shot, periodic and cancel");
+            kprintln!("  time     - Monotonic clock, date conversion, FAT timestamps and clock_gettime");
+            kprintln!("  fd       - Open flags, per-fd offsets, dup sharing, flock, fork/CLOEXEC and redirect");
+            kprintln!("  console  - /dev/console line editing, Ctrl-D EOF, termios raw mode ioctls, console registry fan-out");
+            kprintln!("  color    - ANSI colors on/off, error/warn log colors, plain text in the log ring, console.color");
+            kprintln!("  keymap   - Keycode to ASCII/escape sequences, Shift/Caps/Ctrl/Alt, keyboard events reach the console");
+            kprintln!("  evdev    - /dev/input/eventN records, per-open readers, O_NONBLOCK, poll, SYN_DROPPED, ioctls");
+            kprintln!("  serial   - Serial port table, console= names and /dev/ttySn per-port TTYs");
+            #[cfg(target_arch = "aarch64")]
+            kprintln!("  gic      - GIC version, timer PPI, self-SGI and (GICv3) LPI delivery through the ITS");
+            #[cfg(target_arch = "aarch64")]
+            kprintln!("  psci     - PSCI conduit from the DTB, version, AFFINITY_INFO of online CPUs, idle state selection, psci.idle");
+            kprintln!("  irq      - Shared IRQ handler registration, dispatch, per-CPU counts and free_irq");
+            kprintln!("  softirq  - Tasklets run after the IRQ with interrupts on, coalescing, rescheduling, kill");
+            kprintln!("  ioctl    - SYS_IOCTL routing, console termios, block device size queries and ENOTTY");
+            kprintln!("  poll     - ppoll on pipes and console, waiter wakeup, timeout, POLLHUP/POLLNVAL and queue readiness");
+            kprintln!("  kprobe   - Breakpoint hit, single-step re-arm and disable");
+            kprintln!("  trace    - Trace ring overwrite, ordering and field recording");
+            #[cfg(feature = "lockdep")]
+            kprintln!("  lockdep  - Lock order inversion report (turns order checking off)");
+            #[cfg(feature = "heap_redzone")]
+            kprintln!("  redzone  - Heap red zones: overflow, underflow, double free reports and free poisoning");
+        }
+    }
+}
+
+/// 진행 중인 셀프테스트
+pub struct SelfTest {
+    /// `selftest` 명령에 넘기는 이름
+    name: &'static str,
+    /// 지금까지 모든 항목이 통과했는지
+    passed: bool,
+}
+
+impl SelfTest {
+    /// 머리말(`=== selftest <name> ===`)을 출력하고 시작
+    pub fn new(name: &'static str) -> Self {
+        kprintln!("\n=== selftest {} ===\n", name);
+        Self { name, passed: true }
+    }
+
+    /// 항목 결과 출력 및 기록
+    pub fn check(&mut self, name: &str, ok: bool) {
+        kprintln!("  [{}] {}", if ok { "PASS" } else { "FAIL" }, name);
+        self.passed &= ok;
+    }
+
+    /// 자세한 실패 메시지를 직접 출력한 항목 기록
+    pub fn fail(&mut self) {
+        self.passed = false;
+    }
+
+    /// 꼬리말(`[PASS]`/`[FAIL] selftest <name>`) 출력
+    pub fn finish(self) {
+        let verdict = if self.passed { "PASS" } else { "FAIL" };
+        kprintln!("\n[{}] selftest {}", verdict, self.name);
+    }
+}
@@ -0,0 +1,463 @@
+//! 커널 모듈 로더 셀프테스트 (체크섬, 샌드박스, 재배치, W^X)
+
+use super::SelfTest;
+use crate::{arch, mm, module, proc, time};
+use crate::kprintln;
+
+/// 모듈 체크섬 셀프테스트: CRC32/SHA-256 테스트 벡터, 정책별 트레일러 검증
+pub fn modverify() {
+    use alloc::vec::Vec;
+    use module::verify::{self, Checksum, VerifyMode};
+    use module::ModuleError;
+
+    let mut test = SelfTest::new("modverify");
+
+    test.check("crc32 vector", verify::crc32(b"123456789") == 0xCBF4_3926);
+    const SHA256_ABC: [u8; 32] = [
+        0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae, 0x22, 0x23,
+        0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61, 0xf2, 0x00, 0x15, 0xad,
+    ];
+    // 56바이트: 패딩이 두 블록에 걸침
+    const SHA256_56: [u8; 32] = [
+        0x24, 0x8d, 0x6a, 0x61, 0xd2, 0x06, 0x38, 0xb8, 0xe5, 0xc0, 0x26, 0x93, 0x0c, 0x3e, 0x60, 0x39,
+        0xa3, 0x3c, 0xe4, 0x59, 0x64, 0xff, 0x21, 0x67, 0xf6, 0xec, 0xed, 0xd4, 0x19, 0xdb, 0x06, 0xc1,
+    ];
+    test.check("sha256 vector", verify::sha256(b"abc") == SHA256_ABC);
+    test.check(
+        "sha256 two-block padding",
+        verify::sha256(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq") == SHA256_56,
+    );
+
+    let payload = b"\x7fELF not really a module";
+    let mut signed = Vec::from(&payload[..]);
+    signed.extend_from_slice(&verify::trailer(payload, Checksum::Sha256));
+    let mut crc_signed = Vec::from(&payload[..]);
+    crc_signed.extend_from_slice(&verify::trailer(payload, Checksum::Crc32));
+    let mut corrupt = signed.clone();
+    corrupt[1] ^= 0xff;
+
+    let saved = verify::mode();
+    verify::set_mode(VerifyMode::Enforce);
+    test.check("enforce: sha256 ok", verify::verify(&signed, "t") == Ok((&payload[..], Some(Checksum::Sha256))));
+    test.check("enforce: crc32 ok", verify::verify(&crc_signed, "t") == Ok((&payload[..], Some(Checksum::Crc32))));
+    test.check("enforce: mismatch", verify::verify(&corrupt, "t") == Err(ModuleError::ChecksumMismatch));
+    test.check("enforce: no trailer", verify::verify(payload, "t") == Err(ModuleError::Unverified));
+    test.check(
+        "enforce: insmod refused",
+        matches!(module::ModuleLoader::load_object(&corrupt, "t"), Err(ModuleError::ChecksumMismatch)),
+    );
+
+    verify::set_mode(VerifyMode::Warn);
+    test.check("warn: mismatch loads unverified", verify::verify(&corrupt, "t") == Ok((&corrupt[..payload.len()], None)));
+    test.check("warn: no trailer", verify::verify(payload, "t") == Ok((&payload[..], None)));
+
+    verify::set_mode(VerifyMode::Off);
+    test.check("off: trailer stripped", verify::verify(&corrupt, "t") == Ok((&corrupt[..payload.len()], None)));
+    verify::set_mode(saved);
+
+    test.finish();
+}
+
+/// 모듈 init/exit 격리 셀프테스트: 전용 스레드 실행, 결과 전달, 멈춘 init 종료
+pub fn modsandbox() {
+    use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use module::sandbox::{self, Entry};
+
+    static RAN_ON: AtomicU64 = AtomicU64::new(0);
+    static EXITED: AtomicBool = AtomicBool::new(false);
+
+    extern "C" fn init_ok() -> i32 {
+        RAN_ON.store(proc::current_tid().unwrap_or(0), Ordering::Release);
+        7
+    }
+    extern "C" fn init_hang() -> i32 {
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+    fn exit_ok() {
+        EXITED.store(true, Ordering::Release);
+    }
+
+    let mut test = SelfTest::new("modsandbox");
+
+    RAN_ON.store(0, Ordering::Release);
+    EXITED.store(false, Ordering::Release);
+    test.check("init result", sandbox::call("selftest", Entry::Init(init_ok as *const () as usize)) == Ok(7));
+    let ran_on = RAN_ON.load(Ordering::Acquire);
+    test.check("init on its own thread", ran_on != 0 && Some(ran_on) != proc::current_tid());
+    test.check(
+        "exit runs",
+        sandbox::call("selftest", Entry::Exit(exit_ok as *const () as usize)) == Ok(0) && EXITED.load(Ordering::Acquire),
+    );
+
+    let saved = sandbox::timeout_ms();
+    sandbox::set_timeout_ms(100);
+    let start = time::now_us();
+    let result = sandbox::call("selftest", Entry::Init(init_hang as *const () as usize));
+    let elapsed = time::now_us() - start;
+    sandbox::set_timeout_ms(saved);
+    kprintln!("  hung init returned after {} ms: {:?}", elapsed / 1000, result);
+    test.check("hung init times out", matches!(result, Err(e) if e.stopped));
+    test.check("timeout honoured", (100_000..1_000_000).contains(&elapsed));
+    if let Err(e) = result {
+        test.check("hung thread off CPU", !proc::is_on_cpu(e.tid));
+    }
+
+    test.finish();
+}
+
+/// 재배치 셀프테스트용 relocatable object (.text, .data, 심볼, 재배치만 있는 ELF)
+pub(super) struct RelocTestObject {
+    pub(super) text: alloc::vec::Vec<u8>,
+    pub(super) data: alloc::vec::Vec<u8>,
+    /// (이름, st_info, 섹션 인덱스, 값) - 로컬 심볼이 앞 (인덱스는 1부터)
+    pub(super) syms: alloc::vec::Vec<(&'static str, u8, u16, u64)>,
+    /// (대상 섹션 1=.text 2=.data, 오프셋, 심볼 인덱스, 타입, addend)
+    pub(super) relas: alloc::vec::Vec<(u32, u64, u32, u32, i64)>,
+}
+
+impl RelocTestObject {
+    pub(super) const TEXT: u16 = 1;
+    pub(super) const DATA: u16 = 2;
+
+    /// ELF 파일로 조립 (섹션: null, .text, .data, .symtab, .strtab, .shstrtab, .rela.text, .rela.data)
+    fn build(&self) -> alloc::vec::Vec<u8> {
+        use alloc::vec::Vec;
+
+        #[cfg(target_arch = "aarch64")]
+        const MACHINE: u16 = 183;
+        #[cfg(target_arch = "riscv64")]
+        const MACHINE: u16 = 243;
+
+        fn place(out: &mut Vec<u8>, bytes: &[u8]) -> (u64, u64) {
+            while out.len() % 8 != 0 {
+                out.push(0);
+            }
+            let off = out.len() as u64;
+            out.extend_from_slice(bytes);
+            (off, bytes.len() as u64)
+        }
+
+        let mut strtab = Vec::from(&b"\0"[..]);
+        let mut symtab = Vec::from(&[0u8; 24][..]);
+        for &(name, info, shndx, value) in &self.syms {
+            symtab.extend_from_slice(&(strtab.len() as u32).to_le_bytes());
+            symtab.extend_from_slice(&[info, 0]);
+            symtab.extend_from_slice(&shndx.to_le_bytes());
+            symtab.extend_from_slice(&value.to_le_bytes());
+            symtab.extend_from_slice(&0u64.to_le_bytes());
+            strtab.extend_from_slice(name.as_bytes());
+            strtab.push(0);
+        }
+        let first_global = 1 + self.syms.iter().filter(|s| s.1 >> 4 == 0).count() as u32;
+
+        let mut rela = [Vec::new(), Vec::new()];
+        for &(section, offset, sym, ty, addend) in &self.relas {
+            let out = &mut rela[section as usize - 1];
+            out.extend_from_slice(&offset.to_le_bytes());
+            out.extend_from_slice(&((sym as u64) << 32 | ty as u64).to_le_bytes());
+            out.extend_from_slice(&addend.to_le_bytes());
+        }
+
+        let shstrtab = b"\0.text\0.data\0.symtab\0.strtab\0.shstrtab\0.rela.text\0.rela.data\0";
+        let mut out = Vec::from(&[0u8; 64][..]);
+        let text = place(&mut out, &self.text);
+        let data = place(&mut out, &self.data);
+        let sym = place(&mut out, &symtab);
+        let str_ = place(&mut out, &strtab);
+        let shstr = place(&mut out, shstrtab);
+        let rela_text = place(&mut out, &rela[0]);
+        let rela_data = place(&mut out, &rela[1]);
+        let (shoff, _) = place(&mut out, &[]);
+
+        // (이름, 타입, 플래그, (오프셋, 크기), link, info, 정렬, 엔트리 크기)
+        let sections = [
+            (0, 0, 0, (0, 0), 0, 0, 0, 0),
+            (1, 1, 0x6, text, 0, 0, 4, 0),
+            (7, 1, 0x3, data, 0, 0, 8, 0),
+            (13, 2, 0, sym, 4, first_global, 8, 24),
+            (21, 3, 0, str_, 0, 0, 1, 0),
+            (29, 3, 0, shstr, 0, 0, 1, 0),
+            (39, 4, 0, rela_text, 3, 1, 8, 24),
+            (50, 4, 0, rela_data, 3, 2, 8, 24),
+        ];
+        for (name, ty, flags, (off, size), link, info, align, entsize) in sections {
+            out.extend_from_slice(&(name as u32).to_le_bytes());
+            out.extend_from_slice(&(ty as u32).to_le_bytes());
+            out.extend_from_slice(&(flags as u64).to_le_bytes());
+            out.extend_from_slice(&0u64.to_le_bytes());
+            out.extend_from_slice(&off.to_le_bytes());
+            out.extend_from_slice(&size.to_le_bytes());
+            out.extend_from_slice(&(link as u32).to_le_bytes());
+            out.extend_from_slice(&(info as u32).to_le_bytes());
+            out.extend_from_slice(&(align as u64).to_le_bytes());
+            out.extend_from_slice(&(entsize as u64).to_le_bytes());
+        }
+
+        // ELF 헤더: 64비트, LE, ET_REL
+        out[..8].copy_from_slice(&[0x7f, b'E', b'L', b'F', 2, 1, 1, 0]);
+        out[16..18].copy_from_slice(&1u16.to_le_bytes());
+        out[18..20].copy_from_slice(&MACHINE.to_le_bytes());
+        out[20..24].copy_from_slice(&1u32.to_le_bytes());
+        out[40..48].copy_from_slice(&shoff.to_le_bytes());
+        out[52..54].copy_from_slice(&64u16.to_le_bytes());
+        out[58..60].copy_from_slice(&64u16.to_le_bytes());
+        out[60..62].copy_from_slice(&(sections.len() as u16).to_le_bytes());
+        out[62..64].copy_from_slice(&5u16.to_le_bytes());
+        out
+    }
+
+    /// 체크섬 트레일러를 붙여 모듈로 로드
+    pub(super) fn load(&self, name: &str) -> Result<alloc::sync::Arc<module::LoadedModule>, module::ModuleError> {
+        Self::load_elf(self.build(), name)
+    }
+
+    /// `build()` 결과를 고친 뒤 로드할 때
+    fn load_elf(
+        mut elf: alloc::vec::Vec<u8>,
+        name: &str,
+    ) -> Result<alloc::sync::Arc<module::LoadedModule>, module::ModuleError> {
+        elf.extend_from_slice(&module::verify::trailer(&elf, module::verify::Checksum::Sha256));
+        module::ModuleLoader::load_object(&elf, name)
+    }
+}
+
+/// 모듈 재배치 셀프테스트: 손으로 만든 object로 GOT, MOVW/LDST/조건 분기 (AArch64),
+/// GOT_HI20/ADD/SUB/RVC (RISC-V) 재배치 확인
+pub fn reloc() {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use module::{ModuleError, ModuleLoader};
+
+    const VALUE: u64 = 0x1234_5678_9abc_def0;
+    const NAME: &str = "reloctest";
+
+    fn words(insns: &[u32]) -> Vec<u8> {
+        insns.iter().flat_map(|w| w.to_le_bytes()).collect()
+    }
+    let mut data = Vec::from(&VALUE.to_le_bytes()[..]);
+    data.extend_from_slice(&0x5555_aaaau32.to_le_bytes());
+    data.resize(32, 0);
+
+    let mut test = SelfTest::new("reloc");
+
+    let memcpy = module::lookup_symbol("memcpy");
+    let call = |sym: &str| ModuleLoader::lookup_symbol_in(NAME, sym);
+
+    #[cfg(target_arch = "aarch64")]
+    let (obj, bad, bad_type) = {
+        use module::elf::reloc_aarch64::*;
+        const ADRP: u32 = 0x9000_0000; // adrp x0, 0
+        const LDR_X: u32 = 0xf940_0000; // ldr x0, [x0]
+        const LDR_W: u32 = 0xb940_0000; // ldr w0, [x0]
+        const RET: u32 = 0xd65f_03c0;
+        let obj = RelocTestObject {
+            text: words(&[
+                // rt_got_local (0): GOT에서 rt_value 주소를 읽고 값 반환
+                ADRP, LDR_X, LDR_X, RET,
+                // rt_got_extern (16): GOT에서 커널 심볼 주소 반환
+                ADRP, LDR_X, RET,
+                // rt_movw (28): movz/movk x0로 rt_value 절대 주소
+                0xd2e0_0000, 0xf2c0_0000, 0xf2a0_0000, 0xf280_0000, RET,
+                // rt_ldst32 (48): rt_value+8의 u32
+                ADRP, LDR_W, RET,
+                // rt_condbr (60): cbz x0 → 1, 아니면 2
+                0xb400_0000, 0xd280_0040, RET, 0xd280_0020, RET,
+            ]),
+            data: data.clone(),
+            syms: vec![
+                ("rt_value", 0x11, RelocTestObject::DATA, 0),
+                ("rt_got_local", 0x12, RelocTestObject::TEXT, 0),
+                ("rt_got_extern", 0x12, RelocTestObject::TEXT, 16),
+                ("rt_movw", 0x12, RelocTestObject::TEXT, 28),
+                ("rt_ldst32", 0x12, RelocTestObject::TEXT, 48),
+                ("rt_condbr", 0x12, RelocTestObject::TEXT, 60),
+                ("memcpy", 0x10, 0, 0),
+            ],
+            relas: vec![
+                (1, 0, 1, R_AARCH64_ADR_GOT_PAGE, 0),
+                (1, 4, 1, R_AARCH64_LD64_GOT_LO12_NC, 0),
+                (1, 16, 7, R_AARCH64_ADR_GOT_PAGE, 0),
+                (1, 20, 7, R_AARCH64_LD64_GOT_LO12_NC, 0),
+                (1, 28, 1, R_AARCH64_MOVW_UABS_G3, 0),
+                (1, 32, 1, R_AARCH64_MOVW_UABS_G2_NC, 0),
+                (1, 36, 1, R_AARCH64_MOVW_UABS_G1_NC, 0),
+                (1, 40, 1, R_AARCH64_MOVW_UABS_G0_NC, 0),
+                (1, 48, 1, R_AARCH64_ADR_PREL_PG_HI21, 8),
+                (1, 52, 1, R_AARCH64_LDST32_ABS_LO12_NC, 8),
+                (1, 60, 6, R_AARCH64_CONDBR19, 12),
+            ],
+        };
+        // 범위 검사: 커널 주소는 16비트를 넘으므로 MOVW_UABS_G0 거부
+        let bad = RelocTestObject {
+            text: words(&[0xd280_0000, RET]),
+            data,
+            syms: vec![("rt_value", 0x11, RelocTestObject::DATA, 0)],
+            relas: vec![(1, 0, 1, R_AARCH64_MOVW_UABS_G0, 0)],
+        };
+        (obj, bad, R_AARCH64_MOVW_UABS_G0)
+    };
+
+    #[cfg(target_arch = "riscv64")]
+    let (obj, bad, bad_type) = {
+        use module::elf::reloc_riscv::*;
+        const AUIPC: u32 = 0x0000_0517; // auipc a0, 0
+        const LD: u32 = 0x0005_3503; // ld a0, 0(a0)
+        const RET: u32 = 0x0000_8067;
+        let mut text = words(&[
+            // rt_got_local (0): GOT에서 rt_value 주소를 읽고 값 반환
+            AUIPC, LD, LD, RET,
+            // rt_got_extern (16): GOT에서 커널 심볼 주소 반환
+            AUIPC, LD, RET,
+        ]);
+        // rt_rvc_jump (28): c.j로 li a0, 2를 건너뛰어 1 반환
+        text.extend_from_slice(&0xa001u16.to_le_bytes());
+        text.extend_from_slice(&words(&[0x0020_0513, RET, 0x0010_0513, RET]));
+        let obj = RelocTestObject {
+            text,
+            data: data.clone(),
+            syms: vec![
+                (".Lpcrel_hi0", 0x00, RelocTestObject::TEXT, 0),
+                (".Lpcrel_hi1", 0x00, RelocTestObject::TEXT, 16),
+                ("rt_value", 0x11, RelocTestObject::DATA, 0),
+                ("rt_got_local", 0x12, RelocTestObject::TEXT, 0),
+                ("rt_got_extern", 0x12, RelocTestObject::TEXT, 16),
+                ("rt_rvc_jump", 0x12, RelocTestObject::TEXT, 28),
+                ("rt_diff32", 0x11, RelocTestObject::DATA, 16),
+                ("rt_diff64", 0x11, RelocTestObject::DATA, 24),
+                ("memcpy", 0x10, 0, 0),
+            ],
+            relas: vec![
+                (1, 0, 3, R_RISCV_GOT_HI20, 0),
+                (1, 4, 1, R_RISCV_PCREL_LO12_I, 0),
+                (1, 16, 9, R_RISCV_GOT_HI20, 0),
+                (1, 20, 2, R_RISCV_PCREL_LO12_I, 0),
+                (1, 28, 6, R_RISCV_RVC_JUMP, 10),
+                // rt_diff32 = rt_rvc_jump - rt_got_local, rt_diff64 = rt_got_extern - rt_got_local
+                (2, 16, 6, R_RISCV_ADD32, 0),
+                (2, 16, 4, R_RISCV_SUB32, 0),
+                (2, 24, 5, R_RISCV_ADD64, 0),
+                (2, 24, 4, R_RISCV_SUB64, 0),
+            ],
+        };
+        // 범위 검사: c.j는 ±2KB
+        let bad = RelocTestObject {
+            text: 0xa001u16.to_le_bytes().to_vec(),
+            data,
+            syms: vec![("rt_far", 0x12, RelocTestObject::TEXT, 0)],
+            relas: vec![(1, 0, 1, R_RISCV_RVC_JUMP, 0x1000)],
+        };
+        (obj, bad, R_RISCV_RVC_JUMP)
+    };
+
+    match obj.load(NAME) {
+        Ok(_) => {
+            let fn0 = |sym: &str| {
+                call(sym).map(|addr| unsafe { core::mem::transmute::<usize, extern "C" fn() -> u64>(addr) }())
+            };
+            test.check("GOT, local symbol", fn0("rt_got_local") == Some(VALUE));
+            test.check("GOT, kernel symbol", memcpy.is_some() && fn0("rt_got_extern") == memcpy.map(|a| a as u64));
+
+            #[cfg(target_arch = "aarch64")]
+            {
+                let value = call("rt_value");
+                test.check("MOVW_UABS_G0..G3", value.is_some() && fn0("rt_movw") == value.map(|a| a as u64));
+                let ldst32 = call("rt_ldst32")
+                    .map(|addr| unsafe { core::mem::transmute::<usize, extern "C" fn() -> u32>(addr) }());
+                test.check("LDST32_ABS_LO12_NC", ldst32 == Some(0x5555_aaaa));
+                let condbr = call("rt_condbr")
+                    .map(|addr| unsafe { core::mem::transmute::<usize, extern "C" fn(u64) -> u64>(addr) });
+                test.check("CONDBR19", condbr.is_some_and(|f| f(0) == 1 && f(5) == 2));
+            }
+
+            #[cfg(target_arch = "riscv64")]
+            {
+                test.check("RVC_JUMP", fn0("rt_rvc_jump") == Some(1));
+                let read = |sym: &str| call(sym).map(|addr| unsafe { *(addr as *const u64) });
+                test.check("ADD32/SUB32 symbol difference", read("rt_diff32").map(|v| v as u32) == Some(28));
+                test.check("ADD64/SUB64 symbol difference", read("rt_diff64") == Some(16));
+            }
+
+            test.check("unload", ModuleLoader::unload(NAME).is_ok());
+        }
+        Err(e) => {
+            kprintln!("  load failed: {:?}", e);
+            test.check("load hand-crafted object", false);
+        }
+    }
+
+    test.check(
+        "out-of-range relocation rejected",
+        matches!(bad.load("reloctest_bad"), Err(ModuleError::UnsupportedRelocation(t)) if t == bad_type)
+            && ModuleLoader::info("reloctest_bad").is_none(),
+    );
+
+    test.finish();
+}
+
+/// 모듈 W^X 셀프테스트: 재배치 뒤 코드/PLT는 읽기+실행, 데이터는 읽기/쓰기, W+X 섹션 거부,
+/// 언로드하면 기본 권한으로 복구
+pub fn modwx() {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use arch::mmu::page_permissions;
+    use module::{ModuleError, ModuleLoader};
+
+    const NAME: &str = "wxtest";
+    #[cfg(target_arch = "aarch64")]
+    const RETURN_ONE: [u32; 2] = [0xd280_0020, 0xd65f_03c0]; // mov x0, #1; ret
+    #[cfg(target_arch = "riscv64")]
+    const RETURN_ONE: [u32; 2] = [0x0010_0513, 0x0000_8067]; // li a0, 1; ret
+
+    let mut test = SelfTest::new("modwx");
+
+    let obj = RelocTestObject {
+        text: RETURN_ONE.iter().flat_map(|w| w.to_le_bytes()).collect(),
+        data: vec![0; 16],
+        syms: vec![
+            ("wx_fn", 0x12, RelocTestObject::TEXT, 0),
+            ("wx_data", 0x11, RelocTestObject::DATA, 0),
+        ],
+        relas: vec![],
+    };
+
+    match obj.load(NAME) {
+        Ok(module) => {
+            let text = ModuleLoader::lookup_symbol_in(NAME, "wx_fn").unwrap_or(0);
+            let data = ModuleLoader::lookup_symbol_in(NAME, "wx_data").unwrap_or(0);
+            // PLT 페이지는 섹션 페이지 바로 뒤
+            let plt = module.base_addr + module.size;
+            let pages: Vec<usize> = (module.base_addr..=plt).step_by(mm::page::PAGE_SIZE).collect();
+            drop(module);
+
+            test.check("text: read + execute", page_permissions(text) == Some((false, true)));
+            test.check("data: read/write, no execute", page_permissions(data) == Some((true, false)));
+            test.check("PLT page: read + execute", page_permissions(plt) == Some((false, true)));
+            test.check("text and data on separate pages", text / mm::page::PAGE_SIZE != data / mm::page::PAGE_SIZE);
+            let f: extern "C" fn() -> u64 = unsafe { core::mem::transmute(text) };
+            test.check("text runs", f() == 1);
+            unsafe { core::ptr::write_volatile(data as *mut u64, 0x5a5a) };
+            test.check("data writable", unsafe { core::ptr::read_volatile(data as *const u64) } == 0x5a5a);
+
+            test.check("unload", ModuleLoader::unload(NAME).is_ok());
+            test.check("unload restores RW-NX", pages.iter().all(|&p| page_permissions(p) == Some((true, false))));
+        }
+        Err(e) => {
+            kprintln!("  load failed: {:?}", e);
+            test.check("load", false);
+        }
+    }
+
+    // .text 섹션 헤더(1번)에 SHF_WRITE 추가
+    let mut elf = obj.build();
+    let shoff = u64::from_le_bytes(elf[40..48].try_into().unwrap()) as usize;
+    elf[shoff + 64 + 8] |= 0x1;
+    test.check(
+        "writable+executable section rejected",
+        matches!(RelocTestObject::load_elf(elf, "wxtest_bad"), Err(ModuleError::WritableExecutable))
+            && ModuleLoader::info("wxtest_bad").is_none(),
+    );
+
+    test.finish();
+}
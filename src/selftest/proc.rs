@@ -0,0 +1,836 @@
+//! 프로세스/스케줄러 셀프테스트 (시그널, futex, join, park, RCU, TLS, 핫플러그, 워크큐, 타이머)
+
+use super::SelfTest;
+use crate::{arch, module, proc, sync, time};
+use crate::kprintln;
+
+/// 시그널 selftest: 커널 스레드에 무시/종료 시그널을 보내 yield 지점 전달 확인
+pub fn signal() {
+    use core::sync::atomic::{AtomicU32, Ordering};
+    use proc::signal::{self, SigAction, SignalError};
+
+    static TICKS: AtomicU32 = AtomicU32::new(0);
+
+    fn worker() -> ! {
+        if let Some(tid) = proc::current_tid() {
+            let _ = signal::set_action(tid, signal::SIGUSR1, SigAction::Ignore);
+        }
+        loop {
+            TICKS.fetch_add(1, Ordering::Relaxed);
+            proc::yield_now();
+        }
+    }
+
+    fn settle() -> u32 {
+        for _ in 0..50 {
+            proc::yield_now();
+        }
+        TICKS.load(Ordering::Relaxed)
+    }
+
+    let mut test = SelfTest::new("signal");
+    TICKS.store(0, Ordering::Relaxed);
+    let tid = proc::spawn("sigtest", worker);
+
+
+    let before = settle();
+    test.check("worker running", before > 0);
+    test.check("signal 0 probes thread", signal::send(tid, 0).is_ok());
+    test.check("idle thread refused", signal::send(0, signal::SIGTERM) == Err(SignalError::NotPermitted));
+    test.check("SIGKILL action fixed", signal::set_action(tid, signal::SIGKILL, SigAction::Ignore).is_err());
+
+    test.check("ignored SIGUSR1 sent", signal::send(tid, signal::SIGUSR1).is_ok());
+    test.check("worker survives SIGUSR1", settle() > before);
+
+    test.check("SIGTERM sent", signal::send(tid, signal::SIGTERM).is_ok());
+    let after = settle();
+    test.check("worker stopped by SIGTERM", settle() == after);
+
+    test.finish();
+}
+
+/// futex selftest: 값 불일치/타임아웃 확인 후 커널 스레드를 재우고 깨움
+pub fn futex() {
+    use core::sync::atomic::{AtomicU32, Ordering};
+    use proc::futex::{self, FutexError};
+
+    static WORD: AtomicU32 = AtomicU32::new(0);
+    static DONE: AtomicU32 = AtomicU32::new(0);
+
+    fn waiter() -> ! {
+        let addr = &WORD as *const AtomicU32 as usize;
+        while WORD.load(Ordering::Acquire) == 0 {
+            let _ = futex::wait(addr, 0, None);
+        }
+        DONE.store(1, Ordering::Release);
+        proc::exit();
+    }
+
+    let mut test = SelfTest::new("futex");
+    WORD.store(0, Ordering::Release);
+    DONE.store(0, Ordering::Release);
+    let addr = &WORD as *const AtomicU32 as usize;
+
+
+    test.check("value mismatch", futex::wait(addr, 1, None) == Err(FutexError::WouldBlock));
+    test.check("unaligned address", futex::wait(addr + 1, 0, None) == Err(FutexError::InvalidArgument));
+    test.check("timeout", futex::wait(addr, 0, Some(20_000)) == Err(FutexError::TimedOut));
+    test.check("no waiters to wake", futex::wake(addr, 1) == 0);
+
+    proc::spawn("futex-waiter", waiter);
+    for _ in 0..1000 {
+        if futex::waiters(addr) > 0 {
+            break;
+        }
+        proc::yield_now();
+    }
+    test.check("waiter queued", futex::waiters(addr) == 1);
+    test.check("waiter still sleeping", DONE.load(Ordering::Acquire) == 0);
+
+    WORD.store(1, Ordering::Release);
+    test.check("wake one", futex::wake(addr, 1) == 1);
+    for _ in 0..1000 {
+        if DONE.load(Ordering::Acquire) != 0 {
+            break;
+        }
+        proc::yield_now();
+    }
+    test.check("waiter resumed", DONE.load(Ordering::Acquire) == 1);
+
+    test.finish();
+}
+
+/// `selftest join`: 종료 상태 전달, 강제 종료 상태, 분리된 스레드 해제
+pub fn join() {
+    use core::sync::atomic::Ordering;
+    use proc::{JoinError, ThreadState};
+
+    fn worker() -> ! {
+        proc::exit_with(42);
+    }
+
+    fn spinner() -> ! {
+        loop {
+            proc::yield_now();
+        }
+    }
+
+    fn quick() -> ! {
+        proc::exit();
+    }
+
+    let mut test = SelfTest::new("join");
+
+
+    let tid = proc::spawn("join-worker", worker);
+    test.check("exit status", proc::join(tid) == Ok(42));
+    test.check("joined thread freed", proc::join(tid) == Err(JoinError::NoSuchThread));
+    if let Some(me) = proc::current_tid() {
+        test.check("self join", proc::join(me) == Err(JoinError::Deadlock));
+    }
+
+    let tid = proc::spawn("join-spinner", spinner);
+    proc::kill(tid);
+    let killed = proc::signal::exit_status(proc::signal::SIGKILL);
+    test.check("killed thread status", proc::join(tid) == Ok(killed));
+
+    // 종료되어 CPU에서 내려갈 때까지 대기
+    let wait_off_cpu = |tid| {
+        for _ in 0..1000 {
+            let done = proc::with_thread(tid, |t| t.state == ThreadState::Terminated && !t.on_cpu.load(Ordering::Acquire));
+            if done != Some(false) {
+                break;
+            }
+            proc::yield_now();
+        }
+    };
+
+    let tid = proc::spawn("join-zombie", quick);
+    wait_off_cpu(tid);
+    proc::reaper::reap();
+    test.check("zombie stack freed", proc::with_thread(tid, |t| t.kernel_stack.capacity()) == Some(0));
+    test.check("zombie still joinable", proc::join(tid) == Ok(0));
+
+    let tid = proc::spawn("join-detached", quick);
+    wait_off_cpu(tid);
+    test.check("detach", proc::detach(tid) == Ok(()));
+    test.check("detached thread freed", proc::with_thread(tid, |_| ()).is_none());
+
+    test.finish();
+}
+
+/// `selftest park`: 미리 받은 토큰, 타임아웃, 잠든 스레드 깨우기
+/// RCU 셀프테스트: 읽기 가드가 있는 동안 writer가 grace period를 기다리는지, 모듈 목록 조회
+pub fn rcu() {
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use sync::RcuCell;
+
+    static CELL: RcuCell<alloc::vec::Vec<u32>> = RcuCell::lazy(alloc::vec::Vec::new);
+    static UPDATED: AtomicBool = AtomicBool::new(false);
+
+    fn writer() -> ! {
+        CELL.update(|v| v.push(2));
+        UPDATED.store(true, Ordering::Release);
+        proc::exit();
+    }
+
+    let mut test = SelfTest::new("rcu");
+
+    CELL.replace(alloc::vec![1]);
+    UPDATED.store(false, Ordering::Release);
+    {
+        let guard = CELL.read();
+        let tid = proc::spawn("rcu-writer", writer);
+        proc::park_timeout(30_000);
+        test.check("update waits for reader", !UPDATED.load(Ordering::Acquire));
+        test.check("reader keeps old data", *guard == [1]);
+        drop(guard);
+        let _ = proc::join(tid);
+    }
+    test.check("update completes after reader", UPDATED.load(Ordering::Acquire));
+    test.check("new readers see update", *CELL.read() == [1, 2]);
+    test.check("try_read after init", CELL.try_read().is_some());
+
+    test.check("unknown module", module::ModuleLoader::acquire("no-such-module").is_err());
+    test.check(
+        "module lookup",
+        module::ModuleLoader::list()
+            .iter()
+            .all(|name| module::ModuleLoader::info(name).is_some()),
+    );
+
+    test.finish();
+}
+
+pub fn tls() {
+    use alloc::vec;
+    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use proc::tls::{self, StaticTls, TlsImage};
+
+    const INIT: [u8; 4] = [0x11, 0x22, 0x33, 0x44];
+
+    static ID: AtomicUsize = AtomicUsize::new(0);
+    static OTHER_ADDR: AtomicUsize = AtomicUsize::new(0);
+    static OTHER_FRESH: AtomicBool = AtomicBool::new(false);
+    static OTHER_TP_KEPT: AtomicBool = AtomicBool::new(false);
+
+    // 다른 스레드: 자기 블록이 따로 있고 초기값에서 시작하는지
+    fn block_thread() -> ! {
+        let addr = tls::module_addr(ID.load(Ordering::Acquire), 0);
+        let init = unsafe { core::slice::from_raw_parts(addr as *const u8, 4) };
+        let word = unsafe { *((addr + 8) as *const u32) };
+        OTHER_FRESH.store(init == INIT && word == 0, Ordering::Release);
+        OTHER_ADDR.store(addr, Ordering::Release);
+        proc::exit();
+    }
+
+    // 다른 스레드: 자기 스레드 포인터가 스위칭 뒤에도 남는지
+    fn tp_thread() -> ! {
+        tls::set_thread_pointer(0x5a5a_0000);
+        for _ in 0..10 {
+            proc::yield_now();
+        }
+        OTHER_TP_KEPT.store(tls::thread_pointer() == 0x5a5a_0000, Ordering::Release);
+        proc::exit();
+    }
+
+    let mut test = SelfTest::new("tls");
+
+    // 컨텍스트 스위칭이 스레드 포인터를 저장/복원
+    let saved_tp = tls::thread_pointer();
+    tls::set_thread_pointer(0x1234_0000);
+    OTHER_TP_KEPT.store(false, Ordering::Release);
+    let tid = proc::spawn("tls-tp", tp_thread);
+    let _ = proc::join(tid);
+    test.check("thread pointer kept across switches", tls::thread_pointer() == 0x1234_0000);
+    test.check("other thread's pointer kept", OTHER_TP_KEPT.load(Ordering::Acquire));
+    tls::set_thread_pointer(saved_tp);
+
+    // 모듈 블록: 처음 접근할 때 할당, 스레드마다 따로
+    let image = TlsImage::new(INIT.to_vec(), 64, 16).unwrap();
+    let id = tls::register(image);
+    ID.store(id, Ordering::Release);
+    let addr = tls::module_addr(id, 0);
+    let init = unsafe { core::slice::from_raw_parts(addr as *const u8, 64) };
+    test.check("block initialised", init[..4] == INIT && init[4..].iter().all(|&b| b == 0));
+    test.check("block aligned", addr % 16 == 0);
+    test.check("same block on second access", tls::module_addr(id, 8) == addr + 8);
+    unsafe { *((addr + 8) as *mut u32) = 0xdead_beef };
+
+    OTHER_ADDR.store(0, Ordering::Release);
+    OTHER_FRESH.store(false, Ordering::Release);
+    let tid = proc::spawn("tls-block", block_thread);
+    let _ = proc::join(tid);
+    let other = OTHER_ADDR.load(Ordering::Acquire);
+    test.check("other thread gets its own block", other != 0 && other != addr);
+    test.check("other block starts from the image", OTHER_FRESH.load(Ordering::Acquire));
+    test.check("own block untouched", unsafe { *((addr + 8) as *const u32) } == 0xdead_beef);
+
+    // 모듈 코드가 쓰는 경로 (aarch64 TLSDESC resolver, riscv64 __tls_get_addr)
+    #[cfg(target_arch = "aarch64")]
+    {
+        let desc = tls::module_entry(id, 4);
+        let off: usize;
+        let x9: usize;
+        unsafe {
+            core::arch::asm!(
+                "blr {resolver}",
+                resolver = in(reg) desc[0],
+                inout("x0") desc.as_ptr() as usize => off,
+                inout("x9") 0x9999usize => x9,
+                out("x30") _,
+            );
+        }
+        test.check("TLSDESC resolver", tls::thread_pointer().wrapping_add(off) == addr + 4);
+        test.check("TLSDESC resolver preserves registers", x9 == 0x9999);
+    }
+    #[cfg(target_arch = "riscv64")]
+    {
+        let index = tls::TlsIndex { module: id, offset: 4 };
+        test.check("__tls_get_addr", tls::__tls_get_addr(&index) == addr + 4);
+    }
+
+    // 해제한 id는 재사용하지 않음
+    tls::unregister(id);
+    let next = tls::register(TlsImage::new(vec![], 8, 8).unwrap());
+    test.check("ids not reused", next != id);
+    tls::unregister(next);
+    test.check("unregistered id yields 0 instead of panicking", tls::module_addr(next, 0) == 0);
+
+    // 유저 실행 파일 정적 TLS (Variant I)
+    let image = TlsImage::new(vec![1, 2, 3, 4, 5, 6, 7, 8], 32, 32).unwrap();
+    match StaticTls::new(&image) {
+        Some(block) => {
+            let start = block.block_start();
+            let gap = start - block.thread_pointer();
+            kprintln!("  static TLS: tp={:#x}, block at tp+{}", block.thread_pointer(), gap);
+            #[cfg(target_arch = "aarch64")]
+            test.check("TCB before block", gap == 32);
+            #[cfg(target_arch = "riscv64")]
+            test.check("tp at block start", gap == 0);
+            test.check("static block aligned", start % 32 == 0);
+            let data = unsafe { core::slice::from_raw_parts(start as *const u8, 32) };
+            test.check("static block initialised", data[..8] == [1, 2, 3, 4, 5, 6, 7, 8] && data[8..].iter().all(|&b| b == 0));
+        }
+        None => test.check("static block allocated", false),
+    }
+    test.check("bad alignment rejected", TlsImage::new(vec![], 8, 3).is_none());
+
+    test.finish();
+}
+
+pub fn park() {
+    use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+    use proc::ThreadState;
+
+    static WAKEUPS: AtomicU32 = AtomicU32::new(0);
+    static STOP: AtomicBool = AtomicBool::new(false);
+
+    fn sleeper() -> ! {
+        while !STOP.load(Ordering::Acquire) {
+            WAKEUPS.fetch_add(1, Ordering::AcqRel);
+            proc::park();
+        }
+        proc::exit();
+    }
+
+    let mut test = SelfTest::new("park");
+    WAKEUPS.store(0, Ordering::Release);
+    STOP.store(false, Ordering::Release);
+
+
+    if let Some(me) = proc::current_tid() {
+        test.check("unpark before park", proc::unpark(me) && proc::park());
+    }
+    test.check("timeout", !proc::park_timeout(20_000));
+    test.check("unpark missing thread", !proc::unpark(u64::MAX - 1));
+
+    // 잠들 때까지 대기
+    let wait_parked = |tid, wakeups| {
+        for _ in 0..1000 {
+            let parked = proc::with_thread(tid, |t| t.state == ThreadState::Blocked);
+            if WAKEUPS.load(Ordering::Acquire) >= wakeups && parked == Some(true) {
+                return true;
+            }
+            proc::yield_now();
+        }
+        false
+    };
+
+    let tid = proc::spawn("park-sleeper", sleeper);
+    test.check("sleeper parked", wait_parked(tid, 1));
+    proc::yield_now();
+    test.check("no wakeup without token", WAKEUPS.load(Ordering::Acquire) == 1);
+    test.check("unpark", proc::unpark(tid));
+    test.check("sleeper woken and parked again", wait_parked(tid, 2));
+
+    STOP.store(true, Ordering::Release);
+    proc::unpark(tid);
+    test.check("sleeper exits", proc::join(tid) == Ok(0));
+
+    test.finish();
+}
+
+/// CPU 핫플러그 셀프테스트: 내리기/올리기, 스레드 이전, 친화도 풀기, 틱 정지, CPU별 워커 재결합
+pub fn hotplug() {
+    use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+    use proc::hotplug::{self, HotplugError};
+    use proc::percpu::{self, CpuMask};
+    use proc::workqueue;
+
+    static STOP: AtomicBool = AtomicBool::new(false);
+    static CPU: AtomicU32 = AtomicU32::new(u32::MAX);
+
+    fn spinner() -> ! {
+        while !STOP.load(Ordering::Acquire) {
+            proc::yield_now();
+        }
+        proc::exit();
+    }
+    fn which_cpu(_: usize) {
+        CPU.store(percpu::get_cpu_id(), Ordering::Release);
+    }
+
+    let mut test = SelfTest::new("hotplug");
+
+    test.check("boot CPU refused", hotplug::cpu_down(0) == Err(HotplugError::BootCpu));
+    test.check(
+        "unknown CPU refused",
+        hotplug::cpu_down(percpu::MAX_CPUS as u32) == Err(HotplugError::NoSuchCpu),
+    );
+    test.check("online CPU up refused", hotplug::cpu_up(0) == Err(HotplugError::AlreadyOnline));
+
+    if let Some(cpu) = percpu::online_mask().iter().filter(|&c| c != 0).last() {
+        let ticks = |cpu| percpu::get(cpu).tick_count.load(Ordering::Relaxed);
+        let online = percpu::online_count();
+
+        // CPU에 묶인 스레드와 그 CPU의 워커 준비
+        STOP.store(false, Ordering::Release);
+        let tid = proc::spawn_on_cpu("hotplug-pinned", spinner, cpu);
+        if let Some(id) = workqueue::queue_work_on(cpu, "hp-cpu", which_cpu, 0) {
+            workqueue::flush(id);
+        }
+
+        test.check("offline", hotplug::cpu_down(cpu) == Ok(()));
+        test.check(
+            "removed from online mask",
+            !percpu::online_mask().contains(cpu) && percpu::online_count() == online - 1,
+        );
+        test.check("parked in idle loop", hotplug::is_parked(cpu));
+        test.check("offline twice refused", hotplug::cpu_down(cpu) == Err(HotplugError::AlreadyOffline));
+        let moved = proc::with_thread(tid, |t| {
+            t.cpu.load(Ordering::Acquire) != cpu && t.cpu_affinity == CpuMask::ALL
+        });
+        test.check("pinned thread migrated, affinity broken", moved == Some(true));
+        test.check(
+            "per-CPU work refused",
+            workqueue::queue_work_on(cpu, "hp-cpu", which_cpu, 0).is_none(),
+        );
+        let before = ticks(cpu);
+        proc::park_timeout(50_000);
+        test.check("no ticks while offline", ticks(cpu) == before);
+
+        test.check("online", hotplug::cpu_up(cpu) == Ok(()));
+        test.check(
+            "back in online mask",
+            percpu::online_mask().contains(cpu) && percpu::online_count() == online,
+        );
+        proc::park_timeout(50_000);
+        test.check("ticks resume", ticks(cpu) > before);
+        CPU.store(u32::MAX, Ordering::Release);
+        if let Some(id) = workqueue::queue_work_on(cpu, "hp-cpu", which_cpu, 0) {
+            workqueue::flush(id);
+        }
+        test.check("per-CPU worker rebound", CPU.load(Ordering::Acquire) == cpu);
+
+        STOP.store(true, Ordering::Release);
+        test.check("pinned thread exits", proc::join(tid) == Ok(0));
+    } else {
+        kprintln!("  (1 CPU online: offline/online skipped)");
+    }
+
+    test.finish();
+}
+
+/// 스케줄러 지연 히스토그램 셀프테스트: 버킷 경계, 통계, 깨움/스위칭 기록, 초기화
+pub fn schedstat() {
+    use proc::schedstat::{self, Histogram, BUCKETS};
+
+    let mut test = SelfTest::new("schedstat");
+
+    test.check(
+        "log2 buckets",
+        [0, 1, 2, 3, 4, 1023, 1024, u64::MAX].map(schedstat::bucket) == [0, 0, 1, 1, 2, 9, 10, BUCKETS - 1],
+    );
+
+    let hist = Histogram::new();
+    test.check("empty histogram", hist.snapshot().count == 0 && hist.snapshot().min_ns == 0);
+    for ns in [100, 200, 3000] {
+        hist.record(ns);
+    }
+    let snap = hist.snapshot();
+    test.check(
+        "count/sum/min/max/avg",
+        snap.count == 3 && snap.sum_ns == 3300 && snap.min_ns == 100 && snap.max_ns == 3000 && snap.avg_ns() == 1100,
+    );
+    test.check(
+        "samples land in their buckets",
+        snap.buckets[6] == 1 && snap.buckets[7] == 1 && snap.buckets[11] == 1 && snap.buckets.iter().sum::<u64>() == 3,
+    );
+    hist.reset();
+    test.check("histogram reset", hist.snapshot() == Histogram::new().snapshot());
+
+    let totals = || {
+        proc::percpu::online_mask().iter().fold((0u64, 0u64), |(w, s), cpu| {
+            let (wakeup, switch) = schedstat::snapshot(cpu);
+            (w + wakeup.count, s + switch.count)
+        })
+    };
+
+    // park_timeout 기한과 unpark로 깨어나기
+    schedstat::reset();
+    for _ in 0..10 {
+        proc::park_timeout(1000);
+    }
+    fn parker() -> ! {
+        proc::park();
+        proc::exit();
+    }
+    for _ in 0..5 {
+        let tid = proc::spawn("schedstat", parker);
+        proc::park_timeout(2000);
+        proc::unpark(tid);
+        let _ = proc::join(tid);
+    }
+    let (wakeups, switches) = totals();
+    test.check("wakeups recorded", wakeups >= 10);
+    test.check("context switches recorded", switches >= 10);
+    let max_wakeup = proc::percpu::online_mask().iter().map(|cpu| schedstat::snapshot(cpu).0.max_ns).max().unwrap_or(0);
+    test.check("wakeup latency below 1s", max_wakeup < 1_000_000_000);
+
+    schedstat::report(Some(proc::percpu::get_cpu_id()));
+    schedstat::reset();
+    let (wakeups, switches) = totals();
+    // 초기화 직후 다른 CPU에서 몇 개 들어올 수 있음
+    test.check("reset clears histograms", wakeups < 10 && switches < 10);
+
+    test.finish();
+}
+
+/// 틱 없는 idle 셀프테스트: idle 보조 CPU의 틱 건너뛰기, 틱 수 보정, 기한/IPI 깨우기, 끄기
+pub fn nohz() {
+    use core::sync::atomic::{AtomicU64, Ordering};
+    use proc::percpu;
+    use time::nohz;
+
+    /// `sample`이 대상 CPU에서 읽은 (시각, 틱 수, 건너뛴 틱 수)
+    static AT_US: AtomicU64 = AtomicU64::new(0);
+    static TICKS: AtomicU64 = AtomicU64::new(0);
+    static SKIPPED: AtomicU64 = AtomicU64::new(0);
+    static SLEPT_US: AtomicU64 = AtomicU64::new(0);
+
+    fn sample() -> ! {
+        let pc = percpu::current();
+        TICKS.store(pc.tick_count.load(Ordering::Relaxed), Ordering::Release);
+        SKIPPED.store(pc.ticks_skipped.load(Ordering::Relaxed), Ordering::Release);
+        AT_US.store(time::now_us(), Ordering::Release);
+        proc::exit();
+    }
+    fn sleeper() -> ! {
+        let start = time::now_us();
+        proc::park_timeout(50_000);
+        SLEPT_US.store(time::now_us() - start, Ordering::Release);
+        proc::exit();
+    }
+    // 대상 CPU에서 읽기 (IPI로 깨우므로 건너뛴 틱이 보정된 값)
+    let read_on = |cpu| {
+        let tid = proc::spawn_on_cpu("nohz-sample", sample, cpu);
+        let _ = proc::join(tid);
+        (
+            AT_US.load(Ordering::Acquire),
+            TICKS.load(Ordering::Acquire),
+            SKIPPED.load(Ordering::Acquire),
+        )
+    };
+
+    let mut test = SelfTest::new("nohz");
+
+    let tick_us = arch::timer::TIMER_TICK_MS * 1000;
+    let was_enabled = nohz::is_enabled();
+    nohz::set_enabled(true);
+
+    if let Some(cpu) = percpu::online_mask().iter().filter(|&c| c != 0).last() {
+        let (t0, ticks0, skipped0) = read_on(cpu);
+        proc::park_timeout(300_000);
+        let (t1, ticks1, skipped1) = read_on(cpu);
+        let expected = (t1 - t0) / tick_us;
+        let counted = ticks1 - ticks0;
+        kprintln!(
+            "  CPU {}: {} ms, {} ticks counted ({} expected), {} skipped",
+            cpu,
+            (t1 - t0) / 1000,
+            counted,
+            expected,
+            skipped1 - skipped0
+        );
+        test.check("idle CPU skips ticks", skipped1 - skipped0 >= expected / 2);
+        test.check("tick count caught up", counted + 2 >= expected && counted <= expected + 2);
+
+        SLEPT_US.store(0, Ordering::Release);
+        let tid = proc::spawn_on_cpu("nohz-sleeper", sleeper, cpu);
+        let _ = proc::join(tid);
+        let slept = SLEPT_US.load(Ordering::Acquire);
+        test.check("park deadline wakes on time", slept >= 50_000 && slept < 50_000 + 3 * tick_us);
+
+        nohz::set_enabled(false);
+        let (_, _, skipped0) = read_on(cpu);
+        proc::park_timeout(100_000);
+        let (_, _, skipped1) = read_on(cpu);
+        test.check("nohz off keeps the tick", skipped1 == skipped0);
+    } else {
+        kprintln!("  (1 CPU online: CPU 0 keeps its tick, nothing to test)");
+    }
+
+    nohz::set_enabled(was_enabled);
+    test.finish();
+}
+
+/// workqueue 셀프테스트: 워커 스레드에서 넣은 순서대로 실행, 잠들기, CPU별 큐, 지연 작업, 취소
+pub fn workqueue() {
+    use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+    use proc::workqueue;
+
+    /// 실행한 작업의 `arg`를 4비트씩 이어 붙인 순서
+    static ORDER: AtomicU64 = AtomicU64::new(0);
+    static RAN_ON: AtomicU64 = AtomicU64::new(0);
+    static SLEPT: AtomicU32 = AtomicU32::new(0);
+    static CPU: AtomicU32 = AtomicU32::new(u32::MAX);
+
+    fn record(n: usize) {
+        let _ = ORDER.fetch_update(Ordering::AcqRel, Ordering::Acquire, |o| Some(o << 4 | n as u64));
+        RAN_ON.store(proc::current_tid().unwrap_or(0), Ordering::Release);
+    }
+    fn sleepy(_: usize) {
+        proc::park_timeout(20_000);
+        SLEPT.fetch_add(1, Ordering::AcqRel);
+    }
+    fn which_cpu(_: usize) {
+        CPU.store(proc::percpu::get_cpu_id(), Ordering::Release);
+    }
+
+    let mut test = SelfTest::new("workqueue");
+
+    ORDER.store(0, Ordering::Release);
+    RAN_ON.store(0, Ordering::Release);
+    SLEPT.store(0, Ordering::Release);
+
+    workqueue::queue_work("wq-a", record, 1);
+    workqueue::queue_work("wq-b", record, 2);
+    let last = workqueue::queue_work("wq-c", record, 3);
+    workqueue::flush(last);
+    test.check("runs in queue order", ORDER.load(Ordering::Acquire) == 0x123);
+    let ran_on = RAN_ON.load(Ordering::Acquire);
+    test.check("runs on a worker thread", ran_on != 0 && Some(ran_on) != proc::current_tid());
+
+    let id = workqueue::queue_work("wq-sleep", sleepy, 0);
+    workqueue::flush(id);
+    test.check("work may sleep", SLEPT.load(Ordering::Acquire) == 1);
+
+    let mut on_cpu = true;
+    for cpu in proc::percpu::online_mask().iter() {
+        CPU.store(u32::MAX, Ordering::Release);
+        match workqueue::queue_work_on(cpu, "wq-cpu", which_cpu, 0) {
+            Some(id) => workqueue::flush(id),
+            None => on_cpu = false,
+        }
+        on_cpu &= CPU.load(Ordering::Acquire) == cpu;
+    }
+    test.check("per-CPU work runs on its CPU", on_cpu);
+    test.check(
+        "offline CPU rejected",
+        workqueue::queue_work_on(proc::percpu::MAX_CPUS as u32, "wq-cpu", which_cpu, 0).is_none(),
+    );
+
+    ORDER.store(0, Ordering::Release);
+    let start = time::now_us();
+    let id = workqueue::delayed_work("wq-delay", 50, record, 4);
+    proc::yield_now();
+    test.check("delayed work waits", ORDER.load(Ordering::Acquire) == 0);
+    workqueue::flush(id);
+    test.check(
+        "delayed work runs after the delay",
+        ORDER.load(Ordering::Acquire) == 4 && time::now_us() - start >= 50_000,
+    );
+
+    let id = workqueue::delayed_work("wq-cancel", 1000, record, 5);
+    test.check("cancel pending work", workqueue::cancel(id));
+    test.check("cancel twice fails", !workqueue::cancel(id));
+    workqueue::flush(id);
+    test.check("cancelled work never runs", ORDER.load(Ordering::Acquire) == 4);
+
+    test.finish();
+}
+
+/// timer selftest: 일회성/주기 콜백 타이머 실행과 취소
+pub fn timer() {
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    static ONESHOT: AtomicU64 = AtomicU64::new(0);
+    static PERIODIC: AtomicU64 = AtomicU64::new(0);
+
+    fn on_oneshot(arg: usize) {
+        ONESHOT.fetch_add(arg as u64, Ordering::AcqRel);
+    }
+    fn on_periodic(_arg: usize) {
+        PERIODIC.fetch_add(1, Ordering::AcqRel);
+    }
+
+    // 틱 인터럽트가 돌도록 양보하며 대기
+    let wait_ms = |ms: u64| {
+        let deadline = time::now_us() + ms * 1000;
+        while time::now_us() < deadline {
+            proc::yield_now();
+        }
+    };
+
+    let mut test = SelfTest::new("timer");
+    ONESHOT.store(0, Ordering::Release);
+    PERIODIC.store(0, Ordering::Release);
+
+
+    let one = time::timer::oneshot("selftest-once", 30, on_oneshot, 7);
+    test.check("not fired early", ONESHOT.load(Ordering::Acquire) == 0);
+    test.check("listed", time::list_timers().iter().any(|t| t.name == "selftest-once" && t.period_us.is_none()));
+    wait_ms(60);
+    test.check("one-shot fired once with arg", ONESHOT.load(Ordering::Acquire) == 7);
+    test.check("fired one-shot not cancellable", !time::timer::cancel(one));
+
+    let tick = time::timer::periodic("selftest-tick", 10, on_periodic, 0);
+    wait_ms(100);
+    let count = PERIODIC.load(Ordering::Acquire);
+    test.check("periodic fired repeatedly", count >= 3);
+    test.check("cancel periodic", time::timer::cancel(tick));
+    let after = PERIODIC.load(Ordering::Acquire);
+    wait_ms(50);
+    test.check("no calls after cancel", PERIODIC.load(Ordering::Acquire) == after);
+
+    let late = time::timer::oneshot("selftest-late", 1000, on_oneshot, 1);
+    test.check("cancel pending one-shot", time::timer::cancel(late));
+    wait_ms(20);
+    test.check("cancelled one-shot not listed", !time::list_timers().iter().any(|t| t.name == "selftest-late"));
+
+    test.finish();
+}
+
+/// 스케줄링 정책 selftest: 가짜 스레드 표로 정책 결정만 검증 (스위칭 없음)
+pub fn sched_policy() {
+    use proc::policy::{self, Decision, RoundRobin, RunQueue, SchedPolicy};
+    use proc::percpu::CpuMask;
+    use proc::ThreadState::{self, *};
+
+    const ANY: CpuMask = CpuMask::ALL;
+    const CPU0: CpuMask = CpuMask::single(0);
+    const CPU1: CpuMask = CpuMask::single(1);
+    const CPU01: CpuMask = CpuMask::from_bits(0b11);
+
+    /// (상태, 친화도)
+    struct FakeQueue(&'static [(ThreadState, CpuMask)]);
+    /// (상태, 친화도, CPU에서 내려가는 중)
+    struct SwitchingQueue(&'static [(ThreadState, CpuMask, bool)]);
+
+    impl RunQueue for FakeQueue {
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+        fn state(&self, idx: usize) -> ThreadState {
+            self.0[idx].0
+        }
+        fn affinity(&self, idx: usize) -> CpuMask {
+            self.0[idx].1
+        }
+    }
+
+    impl RunQueue for SwitchingQueue {
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+        fn state(&self, idx: usize) -> ThreadState {
+            self.0[idx].0
+        }
+        fn affinity(&self, idx: usize) -> CpuMask {
+            self.0[idx].1
+        }
+        fn on_cpu(&self, idx: usize) -> bool {
+            self.0[idx].2
+        }
+    }
+
+    let policy = RoundRobin;
+    let mut test = SelfTest::new("sched.policy");
+    kprintln!("  policy: {}", policy.name());
+
+    // (설명, 스레드 표, cpu, current, idle, 기대 결과)
+    let cases: &[(&str, FakeQueue, u32, usize, usize, Decision)] = &[
+        ("next ready thread", FakeQueue(&[(Ready, CPU0), (Ready, ANY), (Ready, ANY)]), 0, 1, 0, Decision::Switch(2)),
+        ("wrap around", FakeQueue(&[(Ready, CPU0), (Blocked, ANY), (Ready, ANY)]), 0, 2, 0, Decision::Switch(0)),
+        ("skip other CPU affinity", FakeQueue(&[(Ready, CPU0), (Ready, CPU1), (Ready, ANY)]), 0, 0, 0, Decision::Switch(2)),
+        ("only current runnable", FakeQueue(&[(Blocked, CPU0), (Ready, ANY)]), 0, 1, 0, Decision::Stay),
+        ("blocked with nothing else", FakeQueue(&[(Blocked, CPU0), (Blocked, ANY)]), 0, 1, 0, Decision::Stay),
+        ("terminated goes to idle", FakeQueue(&[(Blocked, CPU0), (Terminated, ANY)]), 0, 1, 0, Decision::Switch(0)),
+        ("terminated idle stays", FakeQueue(&[(Terminated, CPU0)]), 0, 0, 0, Decision::Stay),
+        ("disallowed current goes to idle", FakeQueue(&[(Blocked, CPU0), (Ready, CPU1)]), 0, 1, 0, Decision::Switch(0)),
+        ("mask allows this CPU", FakeQueue(&[(Ready, CPU0), (Ready, CPU1), (Ready, CPU01)]), 0, 0, 0, Decision::Switch(2)),
+    ];
+
+    for (name, rq, cpu, current, idle, expected) in cases {
+        let got = policy.pick_next(rq, *cpu, *current, *idle);
+        if got == *expected {
+            kprintln!("  [PASS] {}: {:?}", name, got);
+        } else {
+            kprintln!("  [FAIL] {}: expected {:?}, got {:?}", name, expected, got);
+            test.fail();
+        }
+    }
+
+    // 부하 분산: (설명, 내 부하, 상대 부하, 기대 결과)
+    let pulls: &[(&str, usize, usize, bool)] = &[
+        ("idle CPU pulls waiting thread", 0, 1, true),
+        ("nothing to pull", 0, 0, false),
+        ("one-thread difference not moved", 1, 2, false),
+        ("two-thread difference moved", 1, 3, true),
+    ];
+    for (name, mine, theirs, expected) in pulls {
+        let got = policy::should_pull(*mine, *theirs);
+        test.check(&alloc::format!("{}: {}", name, got), got == *expected);
+    }
+
+    // CPU 0 런큐에서 CPU 1로 옮길 스레드: 앞쪽 스레드가 CPU 0의 idle 스레드
+    // (설명, 스레드 표, 기대 결과)
+    let movable: &[(&str, SwitchingQueue, Option<usize>)] = &[
+        ("first ready thread", SwitchingQueue(&[(Ready, CPU0, false), (Running, ANY, true), (Ready, ANY, false)]), Some(2)),
+        ("pinned thread stays", SwitchingQueue(&[(Ready, CPU0, false), (Running, ANY, true), (Ready, CPU0, false)]), None),
+        ("mask includes target", SwitchingQueue(&[(Ready, CPU0, false), (Ready, CPU01, false)]), Some(1)),
+        ("switching-out thread stays", SwitchingQueue(&[(Running, CPU0, true), (Ready, ANY, true), (Blocked, ANY, false)]), None),
+        ("idle thread never moved", SwitchingQueue(&[(Ready, ANY, false)]), None),
+    ];
+    for (name, rq, expected) in movable {
+        let got = policy::pick_movable(rq, 0, 1);
+        if got == *expected {
+            kprintln!("  [PASS] {}: {:?}", name, got);
+        } else {
+            kprintln!("  [FAIL] {}: expected {:?}, got {:?}", name, expected, got);
+            test.fail();
+        }
+    }
+
+    // 친화도가 바뀌어 CPU 0에서 실행할 수 없게 된 스레드는 부하와 관계없이 옮김
+    let stranded = SwitchingQueue(&[(Ready, CPU0, false), (Ready, ANY, false), (Ready, CPU1, false)]);
+    let got = policy::pick_stranded(&stranded, 0, 0, 1);
+    test.check(&alloc::format!("stranded thread moved: {:?}", got), got == Some(2));
+
+    let load = FakeQueue(&[(Running, CPU0), (Ready, ANY), (Blocked, ANY), (Running, ANY), (Terminated, ANY)]);
+    let got = policy::queue_load(&load, 0);
+    test.check(&alloc::format!("queue load excludes idle: {}", got), got == 2);
+
+    test.finish();
+}
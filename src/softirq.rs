@@ -0,0 +1,221 @@
+//! Softirq / 태스크릿 (인터럽트 하반부)
+//!
+//! IRQ 핸들러(상반부)는 장치 인터럽트를 ack하고 FIFO를 비우는 최소한의 일만 하고, 나머지
+//! (virtio used 링 비우기, 수신 바이트 분배 등)는 태스크릿으로 미룹니다. 예약된 태스크릿은
+//! 그 CPU에서 컨트롤러 핸들러가 EOI까지 끝낸 뒤 인터럽트를 켠 채로 실행되므로, 하반부
+//! 작업이 길어도 타이머 틱 같은 다음 인터럽트가 밀리지 않습니다.
+//!
+//! ```text
+//! IRQ ─▶ gic/plic handle_irq ─▶ irq::dispatch ─▶ 핸들러: tasklet.schedule()
+//!        (EOI) ─▶ softirq::run_pending() ─▶ 인터럽트 켬 ─▶ 태스크릿 실행 ─▶ 인터럽트 끔 ─▶ 복귀
+//! ```
+//!
+//! 규칙 (Linux 태스크릿과 같음):
+//! - 태스크릿은 원자적 문맥입니다. 잠들거나(`park`, `Mutex`) 힙을 쓰면 안 되고, IRQ 핸들러와
+//!   공유하는 데이터는 `IrqSpinlock`으로 보호합니다.
+//! - 실행되기 전에 여러 번 예약해도 한 번만 실행됩니다. 실행 중에 다시 예약하면 끝난 뒤 한 번
+//!   더 실행됩니다.
+//! - 같은 태스크릿이 두 CPU에서 동시에 실행되지 않습니다.
+//! - 하반부를 처리하는 동안 이 CPU에서는 스케줄링(타이머 틱 선점)을 하지 않습니다.
+//! - 스레드 문맥에서 예약하면 그 CPU의 다음 인터럽트(최대 한 틱) 끝에 실행됩니다.
+
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicU64, AtomicU8, Ordering};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::kprintln;
+use crate::proc::percpu::{self, MAX_CPUS};
+use crate::sync::{self, IrqSpinlock};
+
+/// 한 번의 `run_pending()`에서 대기열을 다시 비우는 최대 횟수
+///
+/// 태스크릿이 계속 자신을 다시 예약해도 인터럽트 복귀가 끝없이 늦어지지 않게 합니다.
+/// 남은 태스크릿은 다음 인터럽트 끝에 실행됩니다.
+const MAX_RESTART: usize = 10;
+
+/// 예약됨 (대기열에 있음)
+const STATE_SCHED: u8 = 1 << 0;
+/// 실행 중
+const STATE_RUN: u8 = 1 << 1;
+
+/// 태스크릿 (하반부 작업 하나)
+///
+/// 대기열 링크를 자기 안에 두므로 `static`으로 만들며, 예약에 힙을 쓰지 않습니다.
+pub struct Tasklet {
+    func: fn(usize),
+    data: usize,
+    state: AtomicU8,
+    /// CPU 대기열의 다음 태스크릿 (대기열 락으로 보호)
+    next: AtomicPtr<Tasklet>,
+    runs: AtomicU64,
+}
+
+impl Tasklet {
+    /// `func(data)`를 실행하는 태스크릿
+    pub const fn new(func: fn(usize), data: usize) -> Self {
+        Self {
+            func,
+            data,
+            state: AtomicU8::new(0),
+            next: AtomicPtr::new(ptr::null_mut()),
+            runs: AtomicU64::new(0),
+        }
+    }
+
+    /// 현재 CPU의 대기열에 예약 (IRQ 핸들러에서 호출 가능)
+    ///
+    /// # Returns
+    /// 새로 예약했으면 true, 이미 예약돼 있었으면 false
+    pub fn schedule(&'static self) -> bool {
+        if self.state.fetch_or(STATE_SCHED, Ordering::AcqRel) & STATE_SCHED != 0 {
+            return false;
+        }
+        QUEUES[percpu::get_cpu_id() as usize].lock().push(self);
+        true
+    }
+
+    /// 예약된 실행과 진행 중인 실행이 모두 끝날 때까지 기다림 (스레드 문맥)
+    ///
+    /// 새 예약을 막지는 않으므로, 태스크릿을 예약하는 IRQ 핸들러를 먼저 떼어 내고 부릅니다.
+    /// 태스크릿 안에서 부르면 끝나지 않습니다.
+    pub fn kill(&self) {
+        while self.state.load(Ordering::Acquire) != 0 {
+            core::hint::spin_loop();
+        }
+    }
+
+    /// 실행 대기 중인지
+    pub fn is_scheduled(&self) -> bool {
+        self.state.load(Ordering::Acquire) & STATE_SCHED != 0
+    }
+
+    /// 실행 횟수
+    pub fn runs(&self) -> u64 {
+        self.runs.load(Ordering::Relaxed)
+    }
+}
+
+/// CPU별 태스크릿 대기열 (예약 순서대로 실행)
+struct Queue {
+    head: Option<&'static Tasklet>,
+    tail: Option<&'static Tasklet>,
+}
+
+impl Queue {
+    const fn new() -> Self {
+        Self { head: None, tail: None }
+    }
+
+    fn push(&mut self, tasklet: &'static Tasklet) {
+        tasklet.next.store(ptr::null_mut(), Ordering::Relaxed);
+        let link = tasklet as *const Tasklet as *mut Tasklet;
+        match self.tail {
+            Some(tail) => tail.next.store(link, Ordering::Relaxed),
+            None => self.head = Some(tasklet),
+        }
+        self.tail = Some(tasklet);
+    }
+
+    /// 대기열 전체를 떼어 냄 (첫 태스크릿부터 `next`로 이어짐)
+    fn take(&mut self) -> Option<&'static Tasklet> {
+        self.tail = None;
+        self.head.take()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+}
+
+static QUEUES: [IrqSpinlock<Queue>; MAX_CPUS] = [const { IrqSpinlock::new(Queue::new()) }; MAX_CPUS];
+/// CPU가 하반부를 처리 중인지 (중첩된 인터럽트와 스케줄러가 확인)
+static ACTIVE: [AtomicBool; MAX_CPUS] = [const { AtomicBool::new(false) }; MAX_CPUS];
+/// CPU별 태스크릿 실행 횟수
+static RUNS: [AtomicU64; MAX_CPUS] = [const { AtomicU64::new(0) }; MAX_CPUS];
+/// `MAX_RESTART`에 걸려 남은 태스크릿을 다음 인터럽트로 넘긴 횟수
+static DEFERRED: [AtomicU64; MAX_CPUS] = [const { AtomicU64::new(0) }; MAX_CPUS];
+
+/// 현재 CPU가 하반부를 처리 중인지 (스케줄러가 선점하지 않음)
+pub fn in_softirq() -> bool {
+    ACTIVE[percpu::get_cpu_id() as usize].load(Ordering::Relaxed)
+}
+
+/// 인터럽트 끝에서 현재 CPU에 예약된 태스크릿 실행
+///
+/// 아키텍처 예외 핸들러가 컨트롤러 핸들러(EOI 포함) 다음에 인터럽트가 꺼진 채로 부르며,
+/// 인터럽트가 꺼진 채로 돌아옵니다. 태스크릿 실행 중에 들어온 인터럽트에서 불리면 바로
+/// 돌아가고, 그 인터럽트가 예약한 것은 바깥 루프가 이어서 실행합니다.
+pub fn run_pending() {
+    let cpu = percpu::get_cpu_id() as usize;
+    if ACTIVE[cpu].load(Ordering::Relaxed) || QUEUES[cpu].lock().is_empty() {
+        return;
+    }
+    ACTIVE[cpu].store(true, Ordering::Relaxed);
+
+    for _ in 0..MAX_RESTART {
+        let Some(first) = QUEUES[cpu].lock().take() else {
+            break;
+        };
+        sync::enable_interrupts();
+        run_list(cpu, first);
+        sync::disable_interrupts();
+    }
+    if !QUEUES[cpu].lock().is_empty() {
+        DEFERRED[cpu].fetch_add(1, Ordering::Relaxed);
+    }
+
+    ACTIVE[cpu].store(false, Ordering::Relaxed);
+}
+
+/// 떼어 낸 대기열의 태스크릿을 차례로 실행
+fn run_list(cpu: usize, first: &'static Tasklet) {
+    let mut next = Some(first);
+    while let Some(tasklet) = next {
+        // 실행 중에 다시 예약되면 `next`가 바뀌므로 먼저 읽어 둠
+        // Safety: 대기열에는 `&'static Tasklet`만 들어감
+        next = unsafe { tasklet.next.load(Ordering::Relaxed).as_ref() };
+
+        // 다른 CPU에서 실행 중이면 이 CPU 대기열에 되돌려 다음 차례에 실행
+        if tasklet.state.fetch_or(STATE_RUN, Ordering::Acquire) & STATE_RUN != 0 {
+            QUEUES[cpu].lock().push(tasklet);
+            continue;
+        }
+        // 예약 표시를 먼저 지워야 실행 중의 예약이 다음 실행으로 이어짐
+        tasklet.state.fetch_and(!STATE_SCHED, Ordering::AcqRel);
+        crate::trace_event!(softirq_entry, func = tasklet.func as usize);
+        (tasklet.func)(tasklet.data);
+        crate::trace_event!(softirq_exit, func = tasklet.func as usize);
+        tasklet.runs.fetch_add(1, Ordering::Relaxed);
+        RUNS[cpu].fetch_add(1, Ordering::Relaxed);
+        tasklet.state.fetch_and(!STATE_RUN, Ordering::Release);
+    }
+}
+
+/// CPU별 태스크릿 실행 횟수
+pub fn runs(cpu: usize) -> u64 {
+    RUNS.get(cpu).map_or(0, |r| r.load(Ordering::Relaxed))
+}
+
+/// `softirqs` 명령: CPU별 하반부 통계 (Linux `/proc/softirqs`와 비슷)
+pub fn dump() {
+    let cpus: Vec<usize> = percpu::online_mask().iter().map(|c| c as usize).collect();
+    let cpus = if cpus.is_empty() { alloc::vec![0] } else { cpus };
+
+    let mut header = String::from("          ");
+    for cpu in &cpus {
+        header.push_str(&alloc::format!(" {:>10}", alloc::format!("CPU{}", cpu)));
+    }
+    kprintln!("{}", header);
+
+    let row = |label: &str, counters: &[AtomicU64; MAX_CPUS]| {
+        let mut row = alloc::format!("{:>9}:", label);
+        for &cpu in &cpus {
+            row.push_str(&alloc::format!(" {:>10}", counters[cpu].load(Ordering::Relaxed)));
+        }
+        kprintln!("{}", row);
+    };
+    row("TASKLET", &RUNS);
+    row("DEFERRED", &DEFERRED);
+}
@@ -0,0 +1,616 @@
+//! 락 순서 검사 (lockdep-lite)
+//!
+//! `lockdep` feature로 빌드하면 `Spinlock`, `IrqSpinlock`, `Mutex`의 획득/해제를
+//! 기록해 "락 A를 잡은 채 락 B를 잡음" 관계(A → B)를 그래프로 모읍니다. 새 관계를
+//! 추가할 때 이미 B에서 A로 가는 경로가 있으면 두 실행 경로가 서로 반대 순서로 락을
+//! 잡을 수 있다는 뜻이므로(교착 가능성) 두 호출 체인을 함께 보고합니다.
+//!
+//! ```text
+//! kerners> lockstat
+//! kerners> lockstat reset
+//! ```
+//!
+//! - 락 클래스는 락 주소입니다. 대부분의 커널 락이 정적 변수라 주소가 곧 종류이고,
+//!   이름 대신 처음 잡은 위치(`#[track_caller]`)를 보여줍니다. 힙 객체 안의 락은 객체마다
+//!   다른 클래스가 되며, 해제된 주소가 다른 락에 재사용되면 잘못된 보고가 나올 수 있습니다.
+//! - 잡고 있는 락 목록은 스레드(TID)별로 둡니다. 인터럽트 핸들러가 잡는 락은 끼어든
+//!   스레드의 목록에 쌓이므로 "스레드가 A를 잡은 채 인터럽트에서 B" 관계도 기록됩니다.
+//! - `try_lock`은 기다리지 않으므로 순서를 검사하지 않고 목록에만 올립니다.
+//! - 첫 보고 뒤에는 순서 검사를 끕니다 (같은 문제의 반복 보고 방지). 경합 통계는 계속
+//!   셉니다.
+//!
+//! 할당자와 콘솔도 이 락들을 쓰므로 내부 상태는 고정 크기 정적 배열이고, 상태 자체는
+//! 추적하지 않는 원자 변수 스핀으로 보호합니다. 보고를 출력하는 동안에는 그 CPU의
+//! 추적을 멈춥니다. 표가 가득 차면 이후 항목은 기록하지 않고 `dropped`로만 셉니다.
+
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+use core::panic::Location;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::spinlock::{disable_interrupts, enable_interrupts, interrupts_enabled};
+use crate::proc::percpu::{self, MAX_CPUS};
+use crate::{kprint, kprintln};
+
+/// 락 클래스 표 크기 (2의 거듭제곱)
+const MAX_CLASSES: usize = 512;
+/// 순서 관계(간선) 표 크기 (2의 거듭제곱)
+const MAX_EDGES: usize = 1024;
+/// 컨텍스트 하나가 동시에 잡을 수 있는 락 수
+const MAX_HELD: usize = 16;
+/// 락을 잡고 있는 컨텍스트(스레드) 수
+const MAX_CONTEXTS: usize = 64;
+/// 기록할 호출 체인 깊이 (앞쪽 몇 개는 락 구현 자신)
+pub const CHAIN_DEPTH: usize = 6;
+/// 보고에 보여줄 경로 길이
+const MAX_PATH: usize = 8;
+
+/// 빈 칸
+const NONE: u16 = u16::MAX;
+/// 빈 컨텍스트 키 (`NO_THREAD`는 TID로 쓰이지 않음)
+const FREE: u64 = percpu::NO_THREAD;
+
+type Site = Option<&'static Location<'static>>;
+
+/// 락 클래스
+#[derive(Clone, Copy)]
+struct Class {
+    /// 락 주소 (0 = 빈 칸)
+    addr: usize,
+    /// 처음 잡은 위치
+    site: Site,
+    acquired: u64,
+    contended: u64,
+}
+
+impl Class {
+    const EMPTY: Self = Self {
+        addr: 0,
+        site: None,
+        acquired: 0,
+        contended: 0,
+    };
+}
+
+/// 순서 관계 `from` → `to` (`from`을 잡은 채 `to`를 잡음)
+#[derive(Clone, Copy)]
+struct Edge {
+    from: u16,
+    to: u16,
+    /// 처음 기록될 때 `to`를 잡던 호출 체인
+    chain: [usize; CHAIN_DEPTH],
+}
+
+impl Edge {
+    const EMPTY: Self = Self {
+        from: NONE,
+        to: NONE,
+        chain: [0; CHAIN_DEPTH],
+    };
+}
+
+/// 잡고 있는 락
+#[derive(Clone, Copy)]
+struct Held {
+    class: u16,
+    /// 잡은 위치
+    site: Site,
+}
+
+impl Held {
+    const EMPTY: Self = Self { class: NONE, site: None };
+}
+
+/// 스레드(또는 스케줄링 전 CPU)별 잡고 있는 락 목록
+#[derive(Clone, Copy)]
+struct Context {
+    key: u64,
+    depth: usize,
+    held: [Held; MAX_HELD],
+}
+
+impl Context {
+    const EMPTY: Self = Self {
+        key: FREE,
+        depth: 0,
+        held: [Held::EMPTY; MAX_HELD],
+    };
+}
+
+/// 보고할 순서 위반 (그래프 락 밖에서 출력하도록 필요한 값을 복사해 둠)
+#[derive(Clone, Copy)]
+struct Violation {
+    key: u64,
+    /// 같은 락을 다시 잡음
+    recursive: bool,
+    /// 잡으려는 락 (주소, 처음 잡은 위치)과 지금 잡는 위치
+    lock: (usize, Site),
+    site: Site,
+    chain: [usize; CHAIN_DEPTH],
+    /// 이미 있는 경로 `lock` → ... → 잡고 있는 락 (주소)
+    path: [usize; MAX_PATH],
+    path_len: usize,
+    /// 경로 첫 간선이 기록될 때의 호출 체인
+    path_chain: [usize; CHAIN_DEPTH],
+    /// 잡고 있는 락 (주소, 잡은 위치)
+    held: [(usize, Site); MAX_HELD],
+    depth: usize,
+}
+
+struct State {
+    classes: [Class; MAX_CLASSES],
+    nclasses: usize,
+    edges: [Edge; MAX_EDGES],
+    nedges: usize,
+    contexts: [Context; MAX_CONTEXTS],
+    /// 경로 탐색용 (클래스별 도착 간선, 너비 우선 큐)
+    came_from: [u16; MAX_CLASSES],
+    queue: [u16; MAX_CLASSES],
+    dropped: u64,
+    violations: u64,
+}
+
+impl State {
+    fn class_slot(addr: usize) -> usize {
+        ((addr >> 3).wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 32) & (MAX_CLASSES - 1)
+    }
+
+    fn edge_slot(from: u16, to: u16) -> usize {
+        let key = (from as usize) << 16 | to as usize;
+        (key.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 32) & (MAX_EDGES - 1)
+    }
+
+    /// 주소의 클래스 (없으면 `site`를 처음 잡은 위치로 새로 등록, `site`가 None이면 찾기만)
+    fn class_of(&mut self, addr: usize, site: Site) -> Option<u16> {
+        let mut i = Self::class_slot(addr);
+        loop {
+            if self.classes[i].addr == addr {
+                return Some(i as u16);
+            }
+            if self.classes[i].addr == 0 {
+                if site.is_none() || self.nclasses >= MAX_CLASSES - 1 {
+                    return None;
+                }
+                self.classes[i] = Class { addr, site, ..Class::EMPTY };
+                self.nclasses += 1;
+                return Some(i as u16);
+            }
+            i = (i + 1) & (MAX_CLASSES - 1);
+        }
+    }
+
+    fn has_edge(&self, from: u16, to: u16) -> bool {
+        let mut i = Self::edge_slot(from, to);
+        loop {
+            let edge = &self.edges[i];
+            if edge.from == NONE {
+                return false;
+            }
+            if edge.from == from && edge.to == to {
+                return true;
+            }
+            i = (i + 1) & (MAX_EDGES - 1);
+        }
+    }
+
+    fn add_edge(&mut self, from: u16, to: u16, chain: [usize; CHAIN_DEPTH]) {
+        if self.nedges >= MAX_EDGES - 1 {
+            self.dropped += 1;
+            return;
+        }
+        let mut i = Self::edge_slot(from, to);
+        while self.edges[i].from != NONE {
+            i = (i + 1) & (MAX_EDGES - 1);
+        }
+        self.edges[i] = Edge { from, to, chain };
+        self.nedges += 1;
+    }
+
+    /// `from`에서 `to`로 가는 경로가 있는지 (너비 우선, 경로는 `came_from`에 남김)
+    ///
+    /// 새 간선을 추가할 때만 부르므로 간선 표 전체를 훑습니다.
+    fn find_path(&mut self, from: u16, to: u16) -> bool {
+        self.came_from = [NONE; MAX_CLASSES];
+        let (mut head, mut tail) = (0, 1);
+        self.queue[0] = from;
+        while head < tail {
+            let node = self.queue[head];
+            head += 1;
+            for (i, edge) in self.edges.iter().enumerate() {
+                if edge.from != node || edge.to == from || self.came_from[edge.to as usize] != NONE {
+                    continue;
+                }
+                self.came_from[edge.to as usize] = i as u16;
+                if edge.to == to {
+                    return true;
+                }
+                if tail < MAX_CLASSES {
+                    self.queue[tail] = edge.to;
+                    tail += 1;
+                }
+            }
+        }
+        false
+    }
+
+    fn context(&mut self, key: u64, create: bool) -> Option<usize> {
+        if let Some(i) = self.contexts.iter().position(|c| c.key == key) {
+            return Some(i);
+        }
+        if !create {
+            return None;
+        }
+        let i = self.contexts.iter().position(|c| c.key == FREE)?;
+        self.contexts[i].key = key;
+        self.contexts[i].depth = 0;
+        Some(i)
+    }
+
+    /// 락을 기다리기 전 순서 검사 후 잡은 목록에 올림
+    fn acquire(
+        &mut self,
+        key: u64,
+        addr: usize,
+        site: &'static Location<'static>,
+        chain: [usize; CHAIN_DEPTH],
+        check: bool,
+    ) -> Option<Violation> {
+        let (Some(class), Some(ctx)) = (self.class_of(addr, Some(site)), self.context(key, true)) else {
+            self.dropped += 1;
+            return None;
+        };
+        self.classes[class as usize].acquired += 1;
+
+        let mut violation = None;
+        if check && CHECKING.load(Ordering::Relaxed) {
+            for i in 0..self.contexts[ctx].depth {
+                let held = self.contexts[ctx].held[i].class;
+                if held != class && self.has_edge(held, class) {
+                    continue;
+                }
+                let recursive = held == class;
+                if recursive || self.find_path(class, held) {
+                    violation = Some(self.violation(ctx, recursive, class, site, chain, held));
+                    break;
+                }
+                self.add_edge(held, class, chain);
+            }
+        }
+
+        let c = &mut self.contexts[ctx];
+        if c.depth < MAX_HELD {
+            c.held[c.depth] = Held { class, site: Some(site) };
+            c.depth += 1;
+        } else {
+            self.dropped += 1;
+        }
+        violation
+    }
+
+    fn violation(
+        &mut self,
+        ctx: usize,
+        recursive: bool,
+        lock: u16,
+        site: &'static Location<'static>,
+        chain: [usize; CHAIN_DEPTH],
+        held: u16,
+    ) -> Violation {
+        let class = self.classes[lock as usize];
+        let c = self.contexts[ctx];
+        let mut v = Violation {
+            key: c.key,
+            recursive,
+            lock: (class.addr, class.site),
+            site: Some(site),
+            chain,
+            path: [0; MAX_PATH],
+            path_len: 0,
+            path_chain: [0; CHAIN_DEPTH],
+            held: [(0, None); MAX_HELD],
+            depth: c.depth,
+        };
+        for (slot, h) in v.held.iter_mut().zip(c.held[..c.depth].iter()) {
+            *slot = (self.classes[h.class as usize].addr, h.site);
+        }
+
+        if !recursive {
+            // 잡고 있는 락에서 lock까지 거꾸로 따라가며 경로 복원 (너무 길면 앞부분만)
+            let mut rev = [NONE; MAX_PATH];
+            let mut n = 0;
+            let mut node = held;
+            while node != lock && n < MAX_PATH - 1 {
+                rev[n] = node;
+                n += 1;
+                let edge = self.edges[self.came_from[node as usize] as usize];
+                v.path_chain = edge.chain;
+                node = edge.from;
+            }
+            rev[n] = lock;
+            n += 1;
+            for i in 0..n {
+                v.path[i] = self.classes[rev[n - 1 - i] as usize].addr;
+            }
+            v.path_len = n;
+        }
+        self.violations += 1;
+        v
+    }
+
+    fn release(&mut self, key: u64, addr: usize) {
+        let Some(class) = self.class_of(addr, None) else {
+            return;
+        };
+        let Some(ctx) = self.context(key, false) else {
+            return;
+        };
+        let c = &mut self.contexts[ctx];
+        // 보통 마지막에 잡은 락부터 놓음
+        let Some(i) = c.held[..c.depth].iter().rposition(|h| h.class == class) else {
+            return;
+        };
+        c.held.copy_within(i + 1..c.depth, i);
+        c.depth -= 1;
+        if c.depth == 0 {
+            c.key = FREE;
+        }
+    }
+}
+
+/// 추적하지 않는 스핀으로 보호하는 그래프 상태
+struct Graph {
+    locked: AtomicBool,
+    state: UnsafeCell<State>,
+}
+
+unsafe impl Sync for Graph {}
+
+impl Graph {
+    /// 인터럽트를 막고 상태 접근 (안에서 락을 잡거나 할당하면 안 됨)
+    ///
+    /// 이 CPU가 이미 lockdep 안에 있으면(보고 출력 중 등) None
+    fn with<R>(&self, f: impl FnOnce(&mut State) -> R) -> Option<R> {
+        let irq_enabled = interrupts_enabled();
+        disable_interrupts();
+        let result = Busy::enter().map(|busy| {
+            while self
+                .locked
+                .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+            {
+                core::hint::spin_loop();
+            }
+            let result = f(unsafe { &mut *self.state.get() });
+            self.locked.store(false, Ordering::Release);
+            drop(busy);
+            result
+        });
+        if irq_enabled {
+            enable_interrupts();
+        }
+        result
+    }
+}
+
+static GRAPH: Graph = Graph {
+    locked: AtomicBool::new(false),
+    state: UnsafeCell::new(State {
+        classes: [Class::EMPTY; MAX_CLASSES],
+        nclasses: 0,
+        edges: [Edge::EMPTY; MAX_EDGES],
+        nedges: 0,
+        contexts: [Context::EMPTY; MAX_CONTEXTS],
+        came_from: [NONE; MAX_CLASSES],
+        queue: [NONE; MAX_CLASSES],
+        dropped: 0,
+        violations: 0,
+    }),
+};
+
+/// 순서 검사 여부 (첫 보고 뒤 꺼짐)
+static CHECKING: AtomicBool = AtomicBool::new(true);
+
+/// CPU별 추적 중단 표시 (lockdep 안이거나 보고 출력 중)
+static BUSY: [AtomicBool; MAX_CPUS] = [const { AtomicBool::new(false) }; MAX_CPUS];
+
+/// 이 CPU에서 lockdep에 들어가 있는 동안 (그 사이 락 획득/해제는 추적하지 않음)
+struct Busy(usize);
+
+impl Busy {
+    fn enter() -> Option<Self> {
+        let cpu = percpu::get_cpu_id() as usize % MAX_CPUS;
+        if BUSY[cpu].swap(true, Ordering::Acquire) {
+            return None;
+        }
+        Some(Self(cpu))
+    }
+}
+
+impl Drop for Busy {
+    fn drop(&mut self) {
+        BUSY[self.0].store(false, Ordering::Release);
+    }
+}
+
+/// 잡고 있는 락 목록의 키: 현재 스레드, 스케줄링 전이면 CPU
+fn context_key() -> u64 {
+    match crate::proc::current_tid() {
+        Some(tid) => tid,
+        None => FREE - 1 - percpu::get_cpu_id() as u64,
+    }
+}
+
+/// 락을 기다리기 전에 호출: 순서 검사 후 잡은 목록에 올림
+#[inline(always)]
+pub fn acquire(addr: usize, site: &'static Location<'static>) {
+    let chain = crate::trace::backtrace::capture();
+    acquire_inner(addr, site, chain, true);
+}
+
+/// `try_lock` 성공 시 호출: 순서 검사 없이 잡은 목록에 올림
+pub fn acquire_try(addr: usize, site: &'static Location<'static>) {
+    acquire_inner(addr, site, [0; CHAIN_DEPTH], false);
+}
+
+fn acquire_inner(addr: usize, site: &'static Location<'static>, chain: [usize; CHAIN_DEPTH], check: bool) {
+    let violation = GRAPH.with(|s| s.acquire(context_key(), addr, site, chain, check)).flatten();
+    if let Some(v) = violation {
+        CHECKING.store(false, Ordering::Relaxed);
+        // 출력하는 동안 콘솔 락 등은 추적하지 않음
+        if let Some(_busy) = Busy::enter() {
+            report(&v);
+        }
+    }
+}
+
+/// 첫 시도에서 락을 얻지 못함
+pub fn contended(addr: usize) {
+    GRAPH.with(|s| {
+        if let Some(class) = s.class_of(addr, None) {
+            s.classes[class as usize].contended += 1;
+        }
+    });
+}
+
+/// 락 해제 (가드 drop)
+pub fn release(addr: usize) {
+    GRAPH.with(|s| s.release(context_key(), addr));
+}
+
+fn site_name(site: Site) -> (&'static str, u32) {
+    site.map_or(("?", 0), |l| (l.file(), l.line()))
+}
+
+fn print_chain(chain: &[usize; CHAIN_DEPTH]) {
+    kprint!("[lockdep]     call chain:");
+    for &ret in chain.iter().take_while(|&&r| r != 0) {
+        kprint!(" {:#x}", ret);
+    }
+    kprintln!("");
+}
+
+/// 위반 보고
+fn report(v: &Violation) {
+    let (file, line) = site_name(v.lock.1);
+    let (at_file, at_line) = site_name(v.site);
+
+    kprintln!("[lockdep] ======================================================");
+    if v.recursive {
+        kprintln!("[lockdep] possible recursive locking detected (tid {})", v.key);
+    } else {
+        kprintln!("[lockdep] possible circular locking dependency detected (tid {})", v.key);
+    }
+    kprintln!(
+        "[lockdep]   acquiring lock {:#x} ({}:{}) at {}:{}",
+        v.lock.0,
+        file,
+        line,
+        at_file,
+        at_line
+    );
+    print_chain(&v.chain);
+
+    if v.path_len > 0 {
+        kprint!("[lockdep]   but this order already exists:");
+        for (i, addr) in v.path[..v.path_len].iter().enumerate() {
+            kprint!("{}{:#x}", if i == 0 { " " } else { " -> " }, addr);
+        }
+        kprintln!("");
+        kprintln!("[lockdep]   first recorded at:");
+        print_chain(&v.path_chain);
+    }
+
+    kprintln!("[lockdep]   held locks:");
+    for (i, &(addr, site)) in v.held[..v.depth].iter().enumerate() {
+        let (file, line) = site_name(site);
+        kprintln!("[lockdep]     #{} {:#x} at {}:{}", i, addr, file, line);
+    }
+    kprintln!("[lockdep] lock order checking turned off");
+}
+
+/// 락 클래스 통계
+#[derive(Debug, Clone, Copy)]
+pub struct LockStat {
+    pub addr: usize,
+    /// 처음 잡은 위치
+    pub site: Option<&'static Location<'static>>,
+    pub acquired: u64,
+    /// 첫 시도에서 얻지 못한 횟수
+    pub contended: u64,
+}
+
+/// lockdep 요약
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Summary {
+    pub classes: usize,
+    pub dependencies: usize,
+    pub violations: u64,
+    pub dropped: u64,
+    /// 순서 검사 중인지 (첫 보고 뒤 false)
+    pub checking: bool,
+}
+
+pub fn summary() -> Summary {
+    let summary = GRAPH.with(|s| Summary {
+        classes: s.nclasses,
+        dependencies: s.nedges,
+        violations: s.violations,
+        dropped: s.dropped,
+        checking: false,
+    });
+    Summary {
+        checking: CHECKING.load(Ordering::Relaxed),
+        ..summary.unwrap_or_default()
+    }
+}
+
+/// 클래스별 통계 (경합 많은 순)
+pub fn stats() -> Vec<LockStat> {
+    // 상태를 잡은 채 할당하면 할당자의 락이 다시 들어오므로 미리 확보
+    let mut result = Vec::with_capacity(MAX_CLASSES);
+    GRAPH.with(|s| {
+        for class in s.classes.iter().filter(|c| c.addr != 0) {
+            result.push(LockStat {
+                addr: class.addr,
+                site: class.site,
+                acquired: class.acquired,
+                contended: class.contended,
+            });
+        }
+    });
+    result.sort_unstable_by(|a, b| b.contended.cmp(&a.contended).then(b.acquired.cmp(&a.acquired)));
+    result
+}
+
+/// 획득/경합 횟수 초기화 (순서 관계는 유지)
+pub fn reset_stats() {
+    GRAPH.with(|s| {
+        for class in s.classes.iter_mut() {
+            class.acquired = 0;
+            class.contended = 0;
+        }
+    });
+}
+
+/// `lockstat` 명령: 경합 많은 클래스부터 `limit`개 출력
+pub fn report_stats(limit: usize) {
+    let stats = stats();
+    let summary = summary();
+
+    kprintln!(
+        "[lockstat] {} lock classes, {} dependencies, {} violations (dropped {}){}",
+        summary.classes,
+        summary.dependencies,
+        summary.violations,
+        summary.dropped,
+        if summary.checking { "" } else { ", order checking off" }
+    );
+    kprintln!("  {:<18} {:>10} {:>10}  FIRST ACQUIRED AT", "LOCK", "ACQUIRED", "CONTENDED");
+    for stat in stats.iter().take(limit) {
+        let (file, line) = site_name(stat.site);
+        kprintln!("  {:#018x} {:>10} {:>10}  {}:{}", stat.addr, stat.acquired, stat.contended, file, line);
+    }
+    if stats.len() > limit {
+        kprintln!("  ... {} more", stats.len() - limit);
+    }
+}
@@ -6,6 +6,7 @@
 //! - Semaphore: 카운팅 세마포어
 //! - SeqLock: 순차 락 (Writer 우선)
 //! - RCU: Read-Copy-Update (락 프리 읽기)
+//! - lockdep: 락 획득 순서 검사 (`lockdep` feature)
 
 mod spinlock;
 mod mutex;
@@ -13,8 +14,11 @@ mod rwlock;
 mod semaphore;
 mod seqlock;
 mod rcu;
+#[cfg(feature = "lockdep")]
+pub mod lockdep;
 
-pub use spinlock::{Spinlock, SpinlockGuard};
+pub use spinlock::{IrqSpinlock, IrqSpinlockGuard, Spinlock, SpinlockGuard};
+pub(crate) use spinlock::{disable_interrupts, enable_interrupts, interrupts_enabled};
 pub use mutex::{Mutex, MutexGuard};
 pub use rwlock::{RwLock, ReadGuard, WriteGuard};
 pub use semaphore::Semaphore;
@@ -12,8 +12,13 @@
 
 use core::cell::UnsafeCell;
 use core::ops::{Deref, DerefMut};
+#[cfg(feature = "lockdep")]
+use core::panic::Location;
 use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
+#[cfg(feature = "lockdep")]
+use super::lockdep;
+
 /// 스핀 횟수 임계값 - 이 횟수 이상 스핀하면 yield
 const SPIN_LIMIT: u32 = 100;
 
@@ -41,7 +46,21 @@ impl<T> Mutex<T> {
     /// 1. 먼저 짧은 스핀 시도
     /// 2. 스핀 한계 초과 시 yield (스케줄러에 CPU 양보)
     #[inline]
+    #[cfg_attr(feature = "lockdep", track_caller)]
     pub fn lock(&self) -> MutexGuard<'_, T> {
+        #[cfg(feature = "lockdep")]
+        {
+            lockdep::acquire(self as *const Self as usize, Location::caller());
+            if self
+                .locked
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                return MutexGuard { mutex: self };
+            }
+            lockdep::contended(self as *const Self as usize);
+        }
+
         let mut spin_count = 0u32;
 
         loop {
@@ -71,12 +90,15 @@ impl<T> Mutex<T> {
 
     /// 락 시도 (논블로킹)
     #[inline]
+    #[cfg_attr(feature = "lockdep", track_caller)]
     pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
         if self
             .locked
             .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
             .is_ok()
         {
+            #[cfg(feature = "lockdep")]
+            lockdep::acquire_try(self as *const Self as usize, Location::caller());
             Some(MutexGuard { mutex: self })
         } else {
             None
@@ -122,6 +144,8 @@ impl<T> DerefMut for MutexGuard<'_, T> {
 
 impl<T> Drop for MutexGuard<'_, T> {
     fn drop(&mut self) {
+        #[cfg(feature = "lockdep")]
+        lockdep::release(self.mutex as *const Mutex<T> as usize);
         self.mutex.locked.store(false, Ordering::Release);
     }
 }
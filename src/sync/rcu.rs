@@ -11,20 +11,25 @@
 //! 3. Grace period 대기 (모든 reader 종료)
 //! 4. 이전 데이터 해제
 //!
-//! 현재 구현: 단순화된 버전 (선점 비활성화 기반)
+//! 현재 구현: 단순화된 버전. 읽기 구역은 전역 `SrcuDomain`의 reader 카운터로 세고,
+//! grace period는 교체 이전 epoch의 reader가 모두 끝날 때까지 기다립니다. Writer끼리는
+//! 직렬화하지 않으므로 여러 writer가 있으면 호출하는 쪽에서 락을 잡습니다. Writer는
+//! reader를 기다리므로 읽기 가드를 쥔 채, 또는 인터럽트를 끈 채 업데이트하지 않습니다.
 
 use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering, fence};
 use core::marker::PhantomData;
 use alloc::boxed::Box;
 
-/// RCU 글로벌 카운터 - grace period 추적
-static RCU_GLOBAL_EPOCH: AtomicUsize = AtomicUsize::new(0);
+/// 전역 RCU 읽기 구역 카운터 - grace period 추적
+static RCU_READERS: SrcuDomain = SrcuDomain::new();
 
 /// RCU protected cell
 /// 
 /// T 타입의 데이터를 RCU로 보호
 pub struct RcuCell<T> {
     ptr: AtomicPtr<T>,
+    /// 처음 접근할 때 초기값 생성 (`lazy`, `ptr`이 null인 동안)
+    init: Option<fn() -> T>,
     _marker: PhantomData<T>,
 }
 
@@ -37,26 +42,73 @@ impl<T> RcuCell<T> {
         let ptr = Box::into_raw(Box::new(data));
         Self {
             ptr: AtomicPtr::new(ptr),
+            init: None,
             _marker: PhantomData,
         }
     }
 
+    /// 처음 접근할 때 `init()`으로 초기값을 만드는 RcuCell (static용)
+    pub const fn lazy(init: fn() -> T) -> Self {
+        Self {
+            ptr: AtomicPtr::new(core::ptr::null_mut()),
+            init: Some(init),
+            _marker: PhantomData,
+        }
+    }
+
+    /// 현재 데이터 포인터 (`lazy`로 만들었으면 처음에 초기값 설치)
+    fn load(&self) -> *mut T {
+        let ptr = self.ptr.load(Ordering::SeqCst);
+        if !ptr.is_null() {
+            return ptr;
+        }
+        let init = self.init.expect("RcuCell without data");
+        let new_ptr = Box::into_raw(Box::new(init()));
+        match self
+            .ptr
+            .compare_exchange(core::ptr::null_mut(), new_ptr, Ordering::SeqCst, Ordering::SeqCst)
+        {
+            Ok(_) => new_ptr,
+            Err(current) => {
+                // 다른 CPU가 먼저 설치함
+                unsafe {
+                    drop(Box::from_raw(new_ptr));
+                }
+                current
+            }
+        }
+    }
+
     /// RCU 읽기 임계 구역 시작
     /// 
     /// 이 가드가 활성화된 동안에는 데이터가 해제되지 않음
     #[inline]
     pub fn read(&self) -> RcuReadGuard<'_, T> {
-        // 선점 비활성화 (간단한 구현)
-        // 실제 커널에서는 per-CPU 카운터 사용
-        rcu_read_lock();
-        
-        let ptr = self.ptr.load(Ordering::Acquire);
+        let epoch = rcu_read_lock();
+        let ptr = self.load();
         RcuReadGuard {
             cell: self,
             ptr,
+            epoch,
         }
     }
 
+    /// 할당 없는 읽기: `lazy`로 만든 뒤 아직 초기값이 없으면 None (패닉 경로용)
+    #[inline]
+    pub fn try_read(&self) -> Option<RcuReadGuard<'_, T>> {
+        let epoch = rcu_read_lock();
+        let ptr = self.ptr.load(Ordering::SeqCst);
+        if ptr.is_null() {
+            rcu_read_unlock(epoch);
+            return None;
+        }
+        Some(RcuReadGuard {
+            cell: self,
+            ptr,
+            epoch,
+        })
+    }
+
     /// 데이터 업데이트 (Copy-on-Write)
     ///
     /// 1. 현재 데이터 복사
@@ -69,7 +121,7 @@ impl<T> RcuCell<T> {
         F: FnOnce(&mut T),
     {
         // 1. 현재 데이터 복사
-        let old_ptr = self.ptr.load(Ordering::Acquire);
+        let old_ptr = self.load();
         let mut new_data = unsafe { (*old_ptr).clone() };
 
         // 2. 수정 함수 적용
@@ -77,7 +129,7 @@ impl<T> RcuCell<T> {
 
         // 3. 새 데이터로 교체
         let new_ptr = Box::into_raw(Box::new(new_data));
-        let old_ptr = self.ptr.swap(new_ptr, Ordering::AcqRel);
+        let old_ptr = self.ptr.swap(new_ptr, Ordering::SeqCst);
 
         // 4. Grace period 대기 후 해제
         synchronize_rcu();
@@ -89,11 +141,13 @@ impl<T> RcuCell<T> {
     /// 데이터 교체 (새 값으로)
     pub fn replace(&self, new_data: T) {
         let new_ptr = Box::into_raw(Box::new(new_data));
-        let old_ptr = self.ptr.swap(new_ptr, Ordering::AcqRel);
+        let old_ptr = self.ptr.swap(new_ptr, Ordering::SeqCst);
 
         synchronize_rcu();
-        unsafe {
-            drop(Box::from_raw(old_ptr));
+        if !old_ptr.is_null() {
+            unsafe {
+                drop(Box::from_raw(old_ptr));
+            }
         }
     }
 
@@ -102,7 +156,7 @@ impl<T> RcuCell<T> {
     /// Grace period를 비동기적으로 처리 (call_rcu 패턴)
     pub fn replace_async(&self, new_data: T, callback: fn(*mut T)) {
         let new_ptr = Box::into_raw(Box::new(new_data));
-        let old_ptr = self.ptr.swap(new_ptr, Ordering::AcqRel);
+        let old_ptr = self.ptr.swap(new_ptr, Ordering::SeqCst);
 
         // TODO: 비동기 grace period 후 callback 호출
         // 현재는 동기적으로 처리
@@ -128,6 +182,8 @@ impl<T> Drop for RcuCell<T> {
 pub struct RcuReadGuard<'a, T> {
     cell: &'a RcuCell<T>,
     ptr: *const T,
+    /// 읽기 구역을 시작한 epoch (`rcu_read_unlock`에 전달)
+    epoch: usize,
 }
 
 impl<T> core::ops::Deref for RcuReadGuard<'_, T> {
@@ -140,41 +196,35 @@ impl<T> core::ops::Deref for RcuReadGuard<'_, T> {
 
 impl<T> Drop for RcuReadGuard<'_, T> {
     fn drop(&mut self) {
-        rcu_read_unlock();
+        rcu_read_unlock(self.epoch);
     }
 }
 
 // RCU 구현 함수들
 
-/// RCU 읽기 락 (선점 비활성화)
+/// RCU 읽기 락: 현재 epoch의 reader 카운터 증가, epoch 반환
 #[inline]
-fn rcu_read_lock() {
-    // 간단한 구현: 메모리 배리어만 사용
-    // 실제 커널에서는 per-CPU 카운터 증가
-    fence(Ordering::Acquire);
+fn rcu_read_lock() -> usize {
+    let epoch = RCU_READERS.read_lock();
+    // 카운터 증가가 이후의 포인터 읽기보다 먼저 보이도록 (writer의 교체와 짝)
+    fence(Ordering::SeqCst);
+    epoch
 }
 
-/// RCU 읽기 언락 (선점 활성화)
+/// RCU 읽기 언락
 #[inline]
-fn rcu_read_unlock() {
-    fence(Ordering::Release);
+fn rcu_read_unlock(epoch: usize) {
+    RCU_READERS.read_unlock(epoch);
 }
 
 /// Grace period 동기화
 ///
-/// 모든 CPU에서 현재 진행 중인 RCU 읽기 구역이 끝날 때까지 대기
+/// 호출 전에 시작한 RCU 읽기 구역이 모두 끝날 때까지 대기. 이후에 시작한 reader는
+/// 이미 교체된 포인터를 읽으므로 기다리지 않습니다.
 #[inline]
 pub fn synchronize_rcu() {
-    // 간단한 구현: epoch 증가 후 배리어
-    // 실제 커널에서는 복잡한 quiescent state 추적
-    RCU_GLOBAL_EPOCH.fetch_add(1, Ordering::SeqCst);
     fence(Ordering::SeqCst);
-    
-    // 스케줄러가 있다면 모든 CPU에서 컨텍스트 스위치 발생 대기
-    // 현재는 단순 배리어로 대체
-    for _ in 0..100 {
-        core::hint::spin_loop();
-    }
+    RCU_READERS.synchronize();
 }
 
 /// RCU 보호 리스트
@@ -201,9 +251,10 @@ impl<T> RcuList<T> {
 
     /// 리스트 순회 (락 프리)
     pub fn iter(&self) -> RcuListIter<'_, T> {
-        rcu_read_lock();
+        let epoch = rcu_read_lock();
         RcuListIter {
             current: self.head.load(Ordering::Acquire),
+            epoch,
             _marker: PhantomData,
         }
     }
@@ -266,6 +317,7 @@ impl<T> Drop for RcuList<T> {
 
 pub struct RcuListIter<'a, T> {
     current: *const RcuNode<T>,
+    epoch: usize,
     _marker: PhantomData<&'a T>,
 }
 
@@ -285,7 +337,7 @@ impl<'a, T> Iterator for RcuListIter<'a, T> {
 
 impl<T> Drop for RcuListIter<'_, T> {
     fn drop(&mut self) {
-        rcu_read_unlock();
+        rcu_read_unlock(self.epoch);
     }
 }
 
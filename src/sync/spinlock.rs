@@ -4,12 +4,18 @@
 //! - CPU가 락을 얻을 때까지 루프를 돌며 대기 (busy-waiting)
 //! - 짧은 임계 구역에 적합 (수십~수백 사이클)
 //! - 인터럽트 컨텍스트에서 사용 가능
-//! - IRQ-safe 버전 포함 (인터럽트 비활성화)
+//! - IRQ-safe 버전 포함: `Spinlock::lock_irqsave()`, 항상 irqsave로 잡는 `IrqSpinlock`
 
 use core::cell::UnsafeCell;
+use core::mem::ManuallyDrop;
 use core::ops::{Deref, DerefMut};
+#[cfg(feature = "lockdep")]
+use core::panic::Location;
 use core::sync::atomic::{AtomicBool, Ordering};
 
+#[cfg(feature = "lockdep")]
+use super::lockdep;
+
 /// Spinlock - 기본 스핀락
 pub struct Spinlock<T> {
     locked: AtomicBool,
@@ -31,7 +37,21 @@ impl<T> Spinlock<T> {
 
     /// 락 획득 (블로킹)
     #[inline]
+    #[cfg_attr(feature = "lockdep", track_caller)]
     pub fn lock(&self) -> SpinlockGuard<'_, T> {
+        #[cfg(feature = "lockdep")]
+        {
+            lockdep::acquire(self as *const Self as usize, Location::caller());
+            if self
+                .locked
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                return SpinlockGuard { lock: self };
+            }
+            lockdep::contended(self as *const Self as usize);
+        }
+
         while self
             .locked
             .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
@@ -45,14 +65,56 @@ impl<T> Spinlock<T> {
         SpinlockGuard { lock: self }
     }
 
+    /// 로컬 인터럽트를 끄고 락 획득 (irqsave)
+    ///
+    /// 인터럽트 핸들러에서도 잡는 락은 이것으로 잡아야 같은 CPU의 핸들러가 락을 쥔
+    /// 코드에 끼어들어 교착되지 않습니다. 가드를 놓으면(`unlock_irqrestore`) 락을
+    /// 해제한 뒤 이전 인터럽트 상태를 복원합니다.
+    #[inline]
+    #[cfg_attr(feature = "lockdep", track_caller)]
+    pub fn lock_irqsave(&self) -> IrqSpinlockGuard<'_, T> {
+        let irq_enabled = interrupts_enabled();
+        disable_interrupts();
+
+        let guard = self.lock();
+        IrqSpinlockGuard {
+            guard: ManuallyDrop::new(guard),
+            irq_was_enabled: irq_enabled,
+        }
+    }
+
+    /// 로컬 인터럽트를 끄고 락 시도 (논블로킹, 실패하면 인터럽트 상태 복원)
+    #[inline]
+    #[cfg_attr(feature = "lockdep", track_caller)]
+    pub fn try_lock_irqsave(&self) -> Option<IrqSpinlockGuard<'_, T>> {
+        let irq_enabled = interrupts_enabled();
+        disable_interrupts();
+
+        match self.try_lock() {
+            Some(guard) => Some(IrqSpinlockGuard {
+                guard: ManuallyDrop::new(guard),
+                irq_was_enabled: irq_enabled,
+            }),
+            None => {
+                if irq_enabled {
+                    enable_interrupts();
+                }
+                None
+            }
+        }
+    }
+
     /// 락 시도 (논블로킹)
     #[inline]
+    #[cfg_attr(feature = "lockdep", track_caller)]
     pub fn try_lock(&self) -> Option<SpinlockGuard<'_, T>> {
         if self
             .locked
             .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
             .is_ok()
         {
+            #[cfg(feature = "lockdep")]
+            lockdep::acquire_try(self as *const Self as usize, Location::caller());
             Some(SpinlockGuard { lock: self })
         } else {
             None
@@ -94,11 +156,13 @@ impl<T> DerefMut for SpinlockGuard<'_, T> {
 
 impl<T> Drop for SpinlockGuard<'_, T> {
     fn drop(&mut self) {
+        #[cfg(feature = "lockdep")]
+        lockdep::release(self.lock as *const Spinlock<T> as usize);
         self.lock.locked.store(false, Ordering::Release);
     }
 }
 
-/// IRQ-safe Spinlock - 인터럽트 비활성화 포함
+/// IRQ-safe Spinlock - 항상 `lock_irqsave()`로 잡는 스핀락
 pub struct IrqSpinlock<T> {
     inner: Spinlock<T>,
 }
@@ -115,34 +179,44 @@ impl<T> IrqSpinlock<T> {
 
     /// 인터럽트 비활성화 후 락 획득
     #[inline]
+    #[cfg_attr(feature = "lockdep", track_caller)]
     pub fn lock(&self) -> IrqSpinlockGuard<'_, T> {
-        let irq_enabled = interrupts_enabled();
-        disable_interrupts();
+        self.inner.lock_irqsave()
+    }
 
-        let guard = self.inner.lock();
-        IrqSpinlockGuard {
-            guard,
-            irq_was_enabled: irq_enabled,
-        }
+    /// 인터럽트 비활성화 후 락 시도 (논블로킹)
+    #[inline]
+    #[cfg_attr(feature = "lockdep", track_caller)]
+    pub fn try_lock(&self) -> Option<IrqSpinlockGuard<'_, T>> {
+        self.inner.try_lock_irqsave()
     }
 }
 
 pub struct IrqSpinlockGuard<'a, T> {
-    guard: SpinlockGuard<'a, T>,
+    /// 인터럽트를 복원하기 전에 직접 해제하므로 필드 drop에서 다시 해제하지 않음
+    guard: ManuallyDrop<SpinlockGuard<'a, T>>,
     irq_was_enabled: bool,
 }
 
+impl<T> IrqSpinlockGuard<'_, T> {
+    /// 락 해제 후 `lock_irqsave()` 이전의 인터럽트 상태 복원 (가드 drop과 같음)
+    #[inline]
+    pub fn unlock_irqrestore(self) {
+        drop(self);
+    }
+}
+
 impl<T> Deref for IrqSpinlockGuard<'_, T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        &*self.guard
+        &**self.guard
     }
 }
 
 impl<T> DerefMut for IrqSpinlockGuard<'_, T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut *self.guard
+        &mut **self.guard
     }
 }
 
@@ -150,7 +224,7 @@ impl<T> Drop for IrqSpinlockGuard<'_, T> {
     fn drop(&mut self) {
         // guard가 먼저 drop되어 락 해제
         // 그 후 인터럽트 상태 복원
-        drop(unsafe { core::ptr::read(&self.guard) });
+        unsafe { ManuallyDrop::drop(&mut self.guard) };
         if self.irq_was_enabled {
             enable_interrupts();
         }
@@ -159,7 +233,7 @@ impl<T> Drop for IrqSpinlockGuard<'_, T> {
 
 // 아키텍처별 인터럽트 제어
 #[cfg(target_arch = "aarch64")]
-fn interrupts_enabled() -> bool {
+pub(crate) fn interrupts_enabled() -> bool {
     let daif: u64;
     unsafe {
         core::arch::asm!("mrs {}, DAIF", out(reg) daif);
@@ -168,40 +242,35 @@ fn interrupts_enabled() -> bool {
 }
 
 #[cfg(target_arch = "aarch64")]
-fn disable_interrupts() {
+pub(crate) fn disable_interrupts() {
     unsafe {
         core::arch::asm!("msr DAIFSet, #2");
     }
 }
 
 #[cfg(target_arch = "aarch64")]
-fn enable_interrupts() {
+pub(crate) fn enable_interrupts() {
     unsafe {
         core::arch::asm!("msr DAIFClr, #2");
     }
 }
 
 #[cfg(target_arch = "riscv64")]
-fn interrupts_enabled() -> bool {
-    let mstatus: usize;
-    unsafe {
-        core::arch::asm!("csrr {}, mstatus", out(reg) mstatus);
-    }
-    (mstatus & 0x8) != 0 // MIE bit
+pub(crate) fn interrupts_enabled() -> bool {
+    use crate::arch::csr;
+    (csr::read_status() & csr::STATUS_IE) != 0 // MIE / SIE bit
 }
 
 #[cfg(target_arch = "riscv64")]
-fn disable_interrupts() {
-    unsafe {
-        core::arch::asm!("csrc mstatus, {}", in(reg) 0x8usize);
-    }
+pub(crate) fn disable_interrupts() {
+    use crate::arch::csr;
+    csr::clear_status(csr::STATUS_IE);
 }
 
 #[cfg(target_arch = "riscv64")]
-fn enable_interrupts() {
-    unsafe {
-        core::arch::asm!("csrs mstatus, {}", in(reg) 0x8usize);
-    }
+pub(crate) fn enable_interrupts() {
+    use crate::arch::csr;
+    csr::set_status(csr::STATUS_IE);
 }
 
 /// Ticket Spinlock - 공정성 보장 (FIFO)
@@ -11,8 +11,9 @@ use super::errno;
 fn vfs_error_to_errno(e: VfsError) -> isize {
     match e {
         VfsError::NotFound => errno::ENOENT,
+        VfsError::InvalidPath => errno::EINVAL,
         VfsError::PermissionDenied => errno::EACCES,
-        VfsError::AlreadyExists => errno::EBUSY,
+        VfsError::AlreadyExists => errno::EEXIST,
         VfsError::NotADirectory => errno::ENOTDIR,
         VfsError::IsADirectory => errno::EISDIR,
         VfsError::IoError => errno::EIO,
@@ -20,6 +21,13 @@ fn vfs_error_to_errno(e: VfsError) -> isize {
         VfsError::ReadOnly => errno::EACCES,
         VfsError::NotSupported => errno::ENOSYS,
         VfsError::InvalidArgument => errno::EINVAL,
+        VfsError::WouldBlock => errno::EAGAIN,
+        VfsError::BrokenPipe => errno::EPIPE,
+        VfsError::FileBusy => errno::EBUSY,
+        VfsError::DirectoryNotEmpty => errno::ENOTEMPTY,
+        VfsError::CrossDevice => errno::EXDEV,
+        VfsError::Interrupted => errno::EINTR,
+        VfsError::NotATty => errno::ENOTTY,
         _ => errno::EIO,
     }
 }
@@ -41,7 +49,7 @@ pub fn sys_write(fd: usize, buf: *const u8, count: usize) -> isize {
     }
 
     // VFS가 초기화되었으면 FD 테이블 사용
-    if let Ok(fd_table) = fd::kernel_fd_table() {
+    if let Ok(fd_table) = fd::current_fd_table() {
         if let Ok(file) = fd_table.get(fd as i32) {
             let slice = unsafe { core::slice::from_raw_parts(buf, count) };
             match file.write(slice) {
@@ -84,7 +92,7 @@ pub fn sys_read(fd: usize, buf: *mut u8, count: usize) -> isize {
     }
 
     // VFS가 초기화되었으면 FD 테이블 사용
-    if let Ok(fd_table) = fd::kernel_fd_table() {
+    if let Ok(fd_table) = fd::current_fd_table() {
         if let Ok(file) = fd_table.get(fd as i32) {
             let slice = unsafe { core::slice::from_raw_parts_mut(buf, count) };
             match file.read(slice) {
@@ -104,7 +112,7 @@ pub fn sys_read(fd: usize, buf: *mut u8, count: usize) -> isize {
 
             // 폴링 방식으로 한 문자 읽기
             loop {
-                if let Some(c) = crate::arch::uart::getc() {
+                if let Some(c) = crate::console::getc() {
                     unsafe {
                         *buf = c;
                     }
@@ -118,85 +126,88 @@ pub fn sys_read(fd: usize, buf: *mut u8, count: usize) -> isize {
     }
 }
 
-/// sys_open - 파일 열기
-///
-/// # Arguments
-/// * `path` - 경로 (null-terminated)
-/// * `flags` - 열기 플래그
-/// * `mode` - 생성 시 권한
-///
-/// # Returns
-/// * 성공: 파일 디스크립터
-/// * 실패: 음수 에러 코드
-pub fn sys_open(path: *const u8, flags: u32, mode: u32) -> isize {
+/// openat 등에서 "현재 디렉토리 기준"을 뜻하는 dirfd (현재 디렉토리는 항상 `/`)
+const AT_FDCWD: i32 = -100;
+
+/// unlinkat flags: 디렉토리 삭제 (rmdir)
+const AT_REMOVEDIR: u32 = 0x200;
+
+/// 유저 경로 문자열 읽기 (null-terminated, 최대 4096바이트)
+fn user_str<'a>(path: *const u8) -> Result<&'a str, isize> {
     if path.is_null() {
-        return errno::EFAULT;
+        return Err(errno::EFAULT);
     }
-
-    // 경로 문자열 추출
-    let path_str = unsafe {
+    let bytes = unsafe {
         let mut len = 0;
         while *path.add(len) != 0 {
             len += 1;
             if len > 4096 {
-                return errno::EINVAL;
+                return Err(errno::EINVAL);
             }
         }
-        core::str::from_utf8_unchecked(core::slice::from_raw_parts(path, len))
+        core::slice::from_raw_parts(path, len)
     };
+    core::str::from_utf8(bytes).map_err(|_| errno::EINVAL)
+}
 
-    let open_flags = OpenFlags::new(flags);
+/// `*at` 시스템 콜의 (dirfd, path)를 절대 경로로 변환
+///
+/// 절대 경로면 dirfd를 보지 않고, `AT_FDCWD`면 루트 기준, 그 외에는
+/// dirfd로 연 디렉토리의 경로 기준으로 해석합니다.
+fn resolve_at(dirfd: i32, path: *const u8) -> Result<alloc::string::String, isize> {
+    let path = user_str(path)?;
+    if path.is_empty() {
+        return Err(errno::ENOENT);
+    }
+    if fs::path::is_absolute(path) {
+        return Ok(alloc::string::String::from(path));
+    }
+    if dirfd == AT_FDCWD {
+        return Ok(fs::path::join("/", path));
+    }
 
-    // VNode 찾기
-    let vnode = match fs::lookup_path(path_str) {
-        Ok(v) => {
-            // 디렉토리 전용 플래그 체크
-            if open_flags.is_directory() && v.node_type() != VNodeType::Directory {
-                return errno::ENOTDIR;
-            }
-            v
-        }
-        Err(VfsError::NotFound) if open_flags.is_create() => {
-            // 파일 생성
-            let (parent, name) = match fs::path::resolve_parent(
-                &fs::root_fs().unwrap().root(),
-                path_str
-            ) {
-                Ok(p) => p,
-                Err(e) => return vfs_error_to_errno(e),
-            };
+    let dir = fd::current_fd_table()
+        .and_then(|table| table.get(dirfd))
+        .map_err(vfs_error_to_errno)?;
+    if dir.vnode.node_type() != VNodeType::Directory || dir.path.is_empty() {
+        return Err(errno::ENOTDIR);
+    }
+    Ok(fs::path::join(&dir.path, path))
+}
 
-            match parent.create(&name, VNodeType::File, FileMode::new(mode)) {
-                Ok(v) => v,
-                Err(e) => return vfs_error_to_errno(e),
-            }
-        }
-        Err(e) => return vfs_error_to_errno(e),
+/// sys_openat - 파일 열기
+///
+/// # Arguments
+/// * `dirfd` - 상대 경로의 기준 디렉토리 fd (`AT_FDCWD` = 루트)
+/// * `path` - 경로 (null-terminated)
+/// * `flags` - 열기 플래그 (`O_CREAT`, `O_EXCL`, `O_TRUNC`, `O_APPEND`, `O_DIRECTORY`, `O_CLOEXEC`, ...)
+/// * `mode` - 생성 시 권한
+///
+/// # Returns
+/// * 성공: 파일 디스크립터
+/// * 실패: 음수 에러 코드
+pub fn sys_openat(dirfd: i32, path: *const u8, flags: u32, mode: u32) -> isize {
+    let path = match resolve_at(dirfd, path) {
+        Ok(path) => path,
+        Err(e) => return e,
     };
 
-    // 파일 열기
-    let open_file = fd::OpenFile::new(vnode, open_flags);
-
-    // Truncate 처리
-    if open_flags.is_truncate() && open_flags.is_writable() {
-        let _ = open_file.vnode.truncate(0);
-    }
+    // O_CLOEXEC는 fd 플래그이므로 열린 파일 플래그에서 뺌
+    let cloexec = OpenFlags::new(flags).is_cloexec();
+    let open_file = match fd::open(&path, OpenFlags::new(flags & !OpenFlags::O_CLOEXEC), FileMode::new(mode)) {
+        Ok(file) => file,
+        Err(e) => return vfs_error_to_errno(e),
+    };
 
-    // FD 테이블에 추가
-    match fd::kernel_fd_table() {
-        Ok(table) => {
-            match table.insert(alloc::sync::Arc::new(open_file)) {
-                Ok(fd) => fd as isize,
-                Err(e) => vfs_error_to_errno(e),
-            }
-        }
+    match fd::current_fd_table().and_then(|table| table.insert_from(0, alloc::sync::Arc::new(open_file), cloexec)) {
+        Ok(fd) => fd as isize,
         Err(e) => vfs_error_to_errno(e),
     }
 }
 
 /// sys_close - 파일 닫기
 pub fn sys_close(fd: i32) -> isize {
-    match fd::kernel_fd_table() {
+    match fd::current_fd_table() {
         Ok(table) => {
             match table.close(fd) {
                 Ok(()) => 0,
@@ -207,6 +218,28 @@ pub fn sys_close(fd: i32) -> isize {
     }
 }
 
+/// sys_dup - fd 복제 (가장 낮은 빈 번호, 오프셋 공유, 새 fd의 `FD_CLOEXEC`는 꺼짐)
+pub fn sys_dup(fd: i32) -> isize {
+    match fd::current_fd_table().and_then(|table| table.dup(fd)) {
+        Ok(new_fd) => new_fd as isize,
+        Err(e) => vfs_error_to_errno(e),
+    }
+}
+
+/// sys_dup3 - fd를 지정한 번호로 복제 (열려 있던 `new_fd`는 닫힘)
+///
+/// `old_fd == new_fd`는 `EINVAL`이고, flags는 `O_CLOEXEC`(새 fd에 `FD_CLOEXEC`)만 받습니다.
+pub fn sys_dup3(old_fd: i32, new_fd: i32, flags: u32) -> isize {
+    if old_fd == new_fd || flags & !OpenFlags::O_CLOEXEC != 0 {
+        return errno::EINVAL;
+    }
+    let cloexec = flags & OpenFlags::O_CLOEXEC != 0;
+    match fd::current_fd_table().and_then(|table| table.dup3(old_fd, new_fd, cloexec)) {
+        Ok(fd) => fd as isize,
+        Err(e) => vfs_error_to_errno(e),
+    }
+}
+
 /// sys_lseek - 파일 오프셋 이동
 ///
 /// # Arguments
@@ -221,7 +254,7 @@ pub fn sys_lseek(fd: i32, offset: i64, whence: i32) -> isize {
         _ => return errno::EINVAL,
     };
 
-    match fd::kernel_fd_table() {
+    match fd::current_fd_table() {
         Ok(table) => {
             match table.get(fd) {
                 Ok(file) => {
@@ -243,7 +276,7 @@ pub fn sys_fstat(fd: i32, stat_buf: *mut u8) -> isize {
         return errno::EFAULT;
     }
 
-    match fd::kernel_fd_table() {
+    match fd::current_fd_table() {
         Ok(table) => {
             match table.get(fd) {
                 Ok(file) => {
@@ -256,6 +289,8 @@ pub fn sys_fstat(fd: i32, stat_buf: *mut u8) -> isize {
                             out[0..8].copy_from_slice(&stat.size.to_le_bytes());
                             // mode at offset 8
                             out[8..12].copy_from_slice(&stat.mode.0.to_le_bytes());
+                            // mtime (Unix 초) at offset 16
+                            out[16..24].copy_from_slice(&stat.mtime.to_le_bytes());
                             0
                         }
                         Err(e) => vfs_error_to_errno(e),
@@ -268,62 +303,455 @@ pub fn sys_fstat(fd: i32, stat_buf: *mut u8) -> isize {
     }
 }
 
-/// sys_mkdir - 디렉토리 생성
-pub fn sys_mkdir(path: *const u8, mode: u32) -> isize {
-    if path.is_null() {
+/// dirent64 고정 헤더 크기 (d_ino 8 + d_off 8 + d_reclen 2 + d_type 1)
+const DIRENT64_HEADER: usize = 19;
+
+/// dirent64 d_type 값
+const DT_FIFO: u8 = 1;
+const DT_CHR: u8 = 2;
+const DT_DIR: u8 = 4;
+const DT_BLK: u8 = 6;
+const DT_REG: u8 = 8;
+const DT_LNK: u8 = 10;
+const DT_SOCK: u8 = 12;
+
+fn dirent_type(node_type: VNodeType) -> u8 {
+    match node_type {
+        VNodeType::File => DT_REG,
+        VNodeType::Directory => DT_DIR,
+        VNodeType::Symlink => DT_LNK,
+        VNodeType::BlockDevice => DT_BLK,
+        VNodeType::CharDevice => DT_CHR,
+        VNodeType::Fifo => DT_FIFO,
+        VNodeType::Socket => DT_SOCK,
+    }
+}
+
+/// sys_getdents64 - 디렉토리 엔트리 읽기 (Linux `struct linux_dirent64` 형식)
+///
+/// fd의 오프셋을 엔트리 인덱스로 사용해 여러 번 호출하면 이어서 읽습니다.
+/// 인덱스 0, 1은 `.`과 `..`이고 그 뒤는 `VNode::readdir()` 순서입니다.
+/// 아이노드 번호가 없으므로 `d_ino`에는 인덱스 + 1을, `d_off`에는 다음 엔트리
+/// 인덱스를 넣습니다 (`lseek(fd, 0, SEEK_SET)`으로 처음부터 다시 읽기).
+///
+/// # Returns
+/// * 성공: 채운 바이트 수 (0 = 끝)
+/// * 실패: 음수 에러 코드 (첫 엔트리도 못 담는 버퍼는 `EINVAL`)
+pub fn sys_getdents64(fd: i32, buf: *mut u8, count: usize) -> isize {
+    if buf.is_null() {
         return errno::EFAULT;
     }
+    let file = match fd::current_fd_table().and_then(|table| table.get(fd)) {
+        Ok(file) => file,
+        Err(e) => return vfs_error_to_errno(e),
+    };
+    if file.vnode.node_type() != VNodeType::Directory {
+        return errno::ENOTDIR;
+    }
+    let entries = match file.vnode.readdir() {
+        Ok(entries) => entries,
+        Err(e) => return vfs_error_to_errno(e),
+    };
 
-    let path_str = unsafe {
-        let mut len = 0;
-        while *path.add(len) != 0 {
-            len += 1;
+    let out = unsafe { core::slice::from_raw_parts_mut(buf, count) };
+    let mut offset = file.offset.write();
+    let mut index = *offset;
+    let mut pos = 0;
+
+    loop {
+        let (name, d_type) = match index {
+            0 => (".", DT_DIR),
+            1 => ("..", DT_DIR),
+            i => match entries.get(i - 2) {
+                Some(entry) => (entry.name.as_str(), dirent_type(entry.node_type)),
+                None => break,
+            },
+        };
+
+        // 이름 + NUL을 붙이고 8바이트로 정렬
+        let reclen = (DIRENT64_HEADER + name.len() + 1).next_multiple_of(8);
+        if pos + reclen > count {
+            if pos == 0 {
+                return errno::EINVAL;
+            }
+            break;
         }
-        core::str::from_utf8_unchecked(core::slice::from_raw_parts(path, len))
-    };
 
-    let root = match fs::root_fs() {
-        Some(fs) => fs.root(),
-        None => return errno::EIO,
+        let rec = &mut out[pos..pos + reclen];
+        rec.fill(0);
+        rec[0..8].copy_from_slice(&(index as u64 + 1).to_le_bytes());
+        rec[8..16].copy_from_slice(&(index as i64 + 1).to_le_bytes());
+        rec[16..18].copy_from_slice(&(reclen as u16).to_le_bytes());
+        rec[18] = d_type;
+        rec[DIRENT64_HEADER..DIRENT64_HEADER + name.len()].copy_from_slice(name.as_bytes());
+
+        pos += reclen;
+        index += 1;
+    }
+
+    *offset = index;
+    pos as isize
+}
+
+/// sys_mkdirat - 디렉토리 생성
+pub fn sys_mkdirat(dirfd: i32, path: *const u8, mode: u32) -> isize {
+    let path = match resolve_at(dirfd, path) {
+        Ok(path) => path,
+        Err(e) => return e,
     };
+    let result = fs::path::normalize(&path).and_then(|path| {
+        let (parent, name) = fs::path::split(&path);
+        fs::lookup_path(parent)?.create(name, VNodeType::Directory, FileMode::new(mode))
+    });
+    match result {
+        Ok(_) => 0,
+        Err(e) => vfs_error_to_errno(e),
+    }
+}
 
-    match fs::path::resolve_parent(&root, path_str) {
-        Ok((parent, name)) => {
-            match parent.create(&name, VNodeType::Directory, FileMode::new(mode)) {
-                Ok(_) => 0,
-                Err(e) => vfs_error_to_errno(e),
-            }
+/// sys_unlinkat - 파일 삭제 (`AT_REMOVEDIR`이면 빈 디렉토리 삭제)
+pub fn sys_unlinkat(dirfd: i32, path: *const u8, flags: u32) -> isize {
+    if flags & !AT_REMOVEDIR != 0 {
+        return errno::EINVAL;
+    }
+    let path = match resolve_at(dirfd, path) {
+        Ok(path) => path,
+        Err(e) => return e,
+    };
+    let result = fs::path::normalize(&path).and_then(|path| {
+        let (parent, name) = fs::path::split(&path);
+        let parent = fs::lookup_path(parent)?;
+        if flags & AT_REMOVEDIR != 0 {
+            parent.rmdir(name)
+        } else {
+            parent.unlink(name)
         }
+    });
+    match result {
+        Ok(()) => 0,
         Err(e) => vfs_error_to_errno(e),
     }
 }
 
-/// sys_unlink - 파일 삭제
-pub fn sys_unlink(path: *const u8) -> isize {
-    if path.is_null() {
+/// linkat flags: 심볼릭 링크를 따라감 (경로 해석이 링크를 따라가지 않으므로 받기만 함)
+const AT_SYMLINK_FOLLOW: u32 = 0x400;
+
+/// sys_linkat - 하드 링크 생성 (RamFS, 디렉토리는 `EPERM`)
+pub fn sys_linkat(old_dirfd: i32, old_path: *const u8, new_dirfd: i32, new_path: *const u8, flags: u32) -> isize {
+    if flags & !AT_SYMLINK_FOLLOW != 0 {
+        return errno::EINVAL;
+    }
+    let old_path = match resolve_at(old_dirfd, old_path) {
+        Ok(path) => path,
+        Err(e) => return e,
+    };
+    let new_path = match resolve_at(new_dirfd, new_path) {
+        Ok(path) => path,
+        Err(e) => return e,
+    };
+
+    match fs::link(&old_path, &new_path) {
+        Ok(()) => 0,
+        Err(VfsError::PermissionDenied) | Err(VfsError::NotSupported) => errno::EPERM,
+        Err(e) => vfs_error_to_errno(e),
+    }
+}
+
+/// renameat2 flags: 대상이 있으면 `EEXIST`
+const RENAME_NOREPLACE: u32 = 1;
+
+/// sys_renameat2 - 이름 변경 (같은 디렉토리 안에서만, 그 외는 `EXDEV`)
+///
+/// flags는 `RENAME_NOREPLACE`만 지원합니다.
+pub fn sys_renameat2(old_dirfd: i32, old_path: *const u8, new_dirfd: i32, new_path: *const u8, flags: u32) -> isize {
+    if flags & !RENAME_NOREPLACE != 0 {
+        return errno::EINVAL;
+    }
+    let old_path = match resolve_at(old_dirfd, old_path) {
+        Ok(path) => path,
+        Err(e) => return e,
+    };
+    let new_path = match resolve_at(new_dirfd, new_path) {
+        Ok(path) => path,
+        Err(e) => return e,
+    };
+    if flags & RENAME_NOREPLACE != 0 && fs::lookup_path(&new_path).is_ok() {
+        return errno::EEXIST;
+    }
+
+    match fs::rename(&old_path, &new_path) {
+        Ok(()) => 0,
+        Err(e) => vfs_error_to_errno(e),
+    }
+}
+
+/// S_IFMT / S_IFIFO (mknod mode의 파일 타입 비트)
+const S_IFMT: u32 = 0o170000;
+const S_IFIFO: u32 = 0o010000;
+
+/// pipe2(fds, flags)에서 받는 플래그
+const PIPE2_FLAGS: u32 = OpenFlags::O_NONBLOCK | OpenFlags::O_CLOEXEC;
+
+/// sys_pipe2 - 익명 파이프 생성
+///
+/// # Arguments
+/// * `fds` - 결과를 받을 int[2] (fds[0] = 읽기 끝, fds[1] = 쓰기 끝)
+/// * `flags` - O_NONBLOCK, O_CLOEXEC
+pub fn sys_pipe2(fds: *mut i32, flags: u32) -> isize {
+    if fds.is_null() {
         return errno::EFAULT;
     }
+    if flags & !PIPE2_FLAGS != 0 {
+        return errno::EINVAL;
+    }
 
-    let path_str = unsafe {
-        let mut len = 0;
-        while *path.add(len) != 0 {
-            len += 1;
+    let table = match fd::current_fd_table() {
+        Ok(table) => table,
+        Err(e) => return vfs_error_to_errno(e),
+    };
+
+    let nonblock = flags & OpenFlags::O_NONBLOCK;
+    let (reader, writer) = fs::pipe::create_pipe(nonblock != 0);
+    let read_file = fd::OpenFile::new(reader, OpenFlags::new(OpenFlags::O_RDONLY | nonblock));
+    let write_file = fd::OpenFile::new(writer, OpenFlags::new(OpenFlags::O_WRONLY | nonblock));
+
+    let cloexec = flags & OpenFlags::O_CLOEXEC != 0;
+    let read_fd = match table.insert_from(0, alloc::sync::Arc::new(read_file), cloexec) {
+        Ok(fd) => fd,
+        Err(e) => return vfs_error_to_errno(e),
+    };
+    let write_fd = match table.insert_from(0, alloc::sync::Arc::new(write_file), cloexec) {
+        Ok(fd) => fd,
+        Err(e) => {
+            let _ = table.close(read_fd);
+            return vfs_error_to_errno(e);
         }
-        core::str::from_utf8_unchecked(core::slice::from_raw_parts(path, len))
     };
 
-    let root = match fs::root_fs() {
-        Some(fs) => fs.root(),
-        None => return errno::EIO,
+    unsafe {
+        *fds = read_fd;
+        *fds.add(1) = write_fd;
+    }
+    0
+}
+
+/// sys_mknodat - 특수 파일 생성 (FIFO만 지원)
+pub fn sys_mknodat(dirfd: i32, path: *const u8, mode: u32) -> isize {
+    if mode & S_IFMT != S_IFIFO {
+        return errno::EPERM;
+    }
+    let path = match resolve_at(dirfd, path) {
+        Ok(path) => path,
+        Err(e) => return e,
     };
 
-    match fs::path::resolve_parent(&root, path_str) {
-        Ok((parent, name)) => {
-            match parent.unlink(&name) {
-                Ok(()) => 0,
-                Err(e) => vfs_error_to_errno(e),
-            }
+    match fs::pipe::mkfifo(&path, FileMode::new(mode)) {
+        Ok(()) => 0,
+        Err(e) => vfs_error_to_errno(e),
+    }
+}
+
+/// sys_ftruncate - 파일 크기 변경 (shm 세그먼트 크기 지정에 사용)
+pub fn sys_ftruncate(fd: i32, length: i64) -> isize {
+    if length < 0 {
+        return errno::EINVAL;
+    }
+    let file = match fd::current_fd_table().and_then(|table| table.get(fd)) {
+        Ok(file) => file,
+        Err(e) => return vfs_error_to_errno(e),
+    };
+    if !file.flags.is_writable() {
+        return errno::EINVAL;
+    }
+    match file.vnode.truncate(length as u64) {
+        Ok(()) => 0,
+        Err(e) => vfs_error_to_errno(e),
+    }
+}
+
+/// fcntl 명령
+const F_DUPFD: u32 = 0;
+const F_GETFD: u32 = 1;
+const F_SETFD: u32 = 2;
+const F_GETFL: u32 = 3;
+const F_DUPFD_CLOEXEC: u32 = 1030;
+
+/// sys_fcntl - fd 제어
+///
+/// # Arguments
+/// * `cmd` - `F_DUPFD`/`F_DUPFD_CLOEXEC` (`arg` 이상 가장 낮은 번호로 복제),
+///   `F_GETFD`/`F_SETFD` (`FD_CLOEXEC`), `F_GETFL` (열기 플래그)
+///
+/// # Returns
+/// * 성공 시 명령별 값, 모르는 명령은 `EINVAL`
+pub fn sys_fcntl(fd: i32, cmd: u32, arg: usize) -> isize {
+    let table = match fd::current_fd_table() {
+        Ok(table) => table,
+        Err(e) => return vfs_error_to_errno(e),
+    };
+    let result = match cmd {
+        F_DUPFD | F_DUPFD_CLOEXEC => table
+            .get(fd)
+            .and_then(|file| table.insert_from(arg.min(i32::MAX as usize) as i32, file, cmd == F_DUPFD_CLOEXEC))
+            .map(|new_fd| new_fd as isize),
+        F_GETFD => table.cloexec(fd).map(|on| if on { fd::FD_CLOEXEC as isize } else { 0 }),
+        F_SETFD => table.set_cloexec(fd, arg as u32 & fd::FD_CLOEXEC != 0).map(|()| 0),
+        F_GETFL => table.get(fd).map(|file| file.flags.0 as isize),
+        _ => return errno::EINVAL,
+    };
+    result.unwrap_or_else(vfs_error_to_errno)
+}
+
+/// sys_flock - 파일 전체 권고 잠금
+///
+/// # Arguments
+/// * `op` - `LOCK_SH`, `LOCK_EX`, `LOCK_UN` 중 하나, `LOCK_NB`를 더하면 기다리지 않음
+///
+/// # Returns
+/// * 성공 시 0, 충돌(`LOCK_NB`)은 `EAGAIN`, 기다리다 시그널은 `EINTR`, 잘못된 연산은 `EINVAL`
+pub fn sys_flock(fd: i32, op: u32) -> isize {
+    use fs::flock::{LockKind, LOCK_EX, LOCK_NB, LOCK_SH, LOCK_UN};
+
+    let kind = match op & !LOCK_NB {
+        LOCK_SH => Some(LockKind::Shared),
+        LOCK_EX => Some(LockKind::Exclusive),
+        LOCK_UN => None,
+        _ => return errno::EINVAL,
+    };
+    let file = match fd::current_fd_table().and_then(|table| table.get(fd)) {
+        Ok(file) => file,
+        Err(e) => return vfs_error_to_errno(e),
+    };
+    match kind {
+        Some(kind) => match file.lock(kind, op & LOCK_NB != 0) {
+            Ok(()) => 0,
+            Err(e) => vfs_error_to_errno(e),
+        },
+        None => {
+            file.unlock();
+            0
         }
+    }
+}
+
+/// sys_ioctl - 장치 제어
+///
+/// # Arguments
+/// * `cmd` - 장치별 명령 (`/dev/console`은 `TCGETS`/`TCSETS`/`TCSETSW`/`TCSETSF`)
+/// * `arg` - 명령 인자 (termios 명령은 `struct termios` 포인터)
+///
+/// # Returns
+/// * 성공 시 명령의 반환값, 터미널이 아니거나 모르는 명령은 `ENOTTY`
+pub fn sys_ioctl(fd: i32, cmd: u32, arg: usize) -> isize {
+    match fd::current_fd_table()
+        .and_then(|table| table.get(fd))
+        .and_then(|file| file.vnode.ioctl(cmd, arg))
+    {
+        Ok(ret) => ret as isize,
         Err(e) => vfs_error_to_errno(e),
     }
 }
+
+/// ppoll 한 번에 볼 수 있는 최대 fd 수
+const PPOLL_MAX_FDS: usize = 1024;
+
+/// sys_ppoll - 여러 fd의 준비 상태 대기
+///
+/// # Arguments
+/// * `fds` - `struct pollfd` 배열 (`revents`를 채움)
+/// * `nfds` - 배열 길이 (`PPOLL_MAX_FDS` 이하)
+/// * `timeout` - 상대 타임아웃 (`struct timespec`, NULL = 무기한, 0 = 확인만)
+///
+/// 시그널 마스크 인자는 무시합니다 (시그널 마스크 미지원).
+///
+/// # Returns
+/// * 준비된 fd 수 (시간이 다 되면 0), 기다리다 시그널은 `EINTR`
+pub fn sys_ppoll(fds: *mut u8, nfds: usize, timeout: *const u8) -> isize {
+    use fs::poll::{self, PollFd};
+
+    if nfds > PPOLL_MAX_FDS {
+        return errno::EINVAL;
+    }
+    if fds.is_null() && nfds > 0 {
+        return errno::EFAULT;
+    }
+    let timeout_us = if timeout.is_null() {
+        None
+    } else {
+        let (sec, nsec) = unsafe {
+            (
+                core::ptr::read_unaligned(timeout as *const i64),
+                core::ptr::read_unaligned(timeout.add(8) as *const i64),
+            )
+        };
+        if sec < 0 || !(0..1_000_000_000).contains(&nsec) {
+            return errno::EINVAL;
+        }
+        Some((sec as u64).saturating_mul(1_000_000).saturating_add(nsec as u64 / 1000))
+    };
+
+    let table = match fd::current_fd_table() {
+        Ok(table) => table,
+        Err(e) => return vfs_error_to_errno(e),
+    };
+    let user = fds as *mut PollFd;
+    let mut pollfds: alloc::vec::Vec<PollFd> = (0..nfds).map(|i| unsafe { user.add(i).read_unaligned() }).collect();
+    let result = poll::poll(&table, &mut pollfds, timeout_us);
+    for (i, pfd) in pollfds.iter().enumerate() {
+        unsafe { user.add(i).write_unaligned(*pfd) };
+    }
+    match result {
+        Ok(ready) => ready as isize,
+        Err(e) => vfs_error_to_errno(e),
+    }
+}
+
+/// mmap flags
+const MAP_SHARED: u32 = 0x01;
+const MAP_ANONYMOUS: u32 = 0x20;
+
+/// sys_mmap - 공유 메모리 매핑
+///
+/// `MAP_SHARED`만 지원합니다. fd는 `/dev/shm` 세그먼트나 `/dev/fb0`이어야 하고,
+/// `MAP_ANONYMOUS`는 익명 세그먼트를 새로 만들어 매핑합니다.
+/// 커널은 아이덴티티 매핑이므로 `addr` 힌트와 `prot`는 무시합니다.
+pub fn sys_mmap(_addr: usize, len: usize, _prot: u32, flags: u32, fd: i32, offset: usize) -> isize {
+    if len == 0 || flags & MAP_SHARED == 0 {
+        return errno::EINVAL;
+    }
+
+    if flags & MAP_ANONYMOUS != 0 {
+        let seg = match crate::ipc::shm::anonymous(len) {
+            Ok(seg) => seg,
+            Err(_) => return errno::ENOMEM,
+        };
+        return match crate::ipc::shm::map(&seg, 0, len) {
+            Ok(addr) => addr as isize,
+            Err(_) => errno::EINVAL,
+        };
+    }
+
+    let file = match fd::current_fd_table().and_then(|table| table.get(fd)) {
+        Ok(file) => file,
+        Err(e) => return vfs_error_to_errno(e),
+    };
+    match file.vnode.mmap(offset, len) {
+        Ok(addr) => addr as isize,
+        Err(VfsError::NotSupported) => errno::ENODEV,
+        Err(e) => vfs_error_to_errno(e),
+    }
+}
+
+/// sys_munmap - 매핑 해제 (mmap이 반환한 주소 단위)
+///
+/// 프레임버퍼 매핑은 해제할 것이 없으며, 그동안 그린 내용을 화면에 반영합니다.
+pub fn sys_munmap(addr: usize, _len: usize) -> isize {
+    if let Some(fb) = crate::virtio::gpu::framebuffer().filter(|fb| fb.contains(addr)) {
+        crate::virtio::gpu::flush(fb.full_rect());
+        return 0;
+    }
+    match crate::ipc::shm::unmap(addr) {
+        Ok(()) => 0,
+        Err(_) => errno::EINVAL,
+    }
+}
@@ -5,6 +5,7 @@
 
 mod fs;
 mod process;
+mod time;
 
 use crate::kprintln;
 
@@ -15,9 +16,24 @@ use crate::kprintln;
 /// openat(dirfd, path, flags, mode) -> fd
 pub const SYS_OPENAT: usize = 56;
 
+/// dup(fd) -> fd
+pub const SYS_DUP: usize = 23;
+
+/// dup3(old_fd, new_fd, flags) -> fd
+pub const SYS_DUP3: usize = 24;
+
+/// fcntl(fd, cmd, arg) -> int
+pub const SYS_FCNTL: usize = 25;
+
 /// close(fd) -> int
 pub const SYS_CLOSE: usize = 57;
 
+/// pipe2(fds[2], flags) -> int
+pub const SYS_PIPE2: usize = 59;
+
+/// getdents64(fd, dirp, count) -> ssize_t
+pub const SYS_GETDENTS64: usize = 61;
+
 /// lseek(fd, offset, whence) -> off_t
 pub const SYS_LSEEK: usize = 62;
 
@@ -45,18 +61,66 @@ pub const SYS_GETPID: usize = 172;
 /// nanosleep(req, rem) -> int
 pub const SYS_NANOSLEEP: usize = 101;
 
+/// clock_gettime(clockid, tp) -> int
+pub const SYS_CLOCK_GETTIME: usize = 113;
+
+/// gettimeofday(tv, tz) -> int
+pub const SYS_GETTIMEOFDAY: usize = 169;
+
 /// brk(addr) -> void*
 pub const SYS_BRK: usize = 214;
 
+/// mknodat(dirfd, path, mode, dev) -> int
+pub const SYS_MKNODAT: usize = 33;
+
 /// mkdirat(dirfd, path, mode) -> int
 pub const SYS_MKDIRAT: usize = 34;
 
 /// unlinkat(dirfd, path, flags) -> int
 pub const SYS_UNLINKAT: usize = 35;
 
+/// linkat(olddirfd, oldpath, newdirfd, newpath, flags) -> int
+pub const SYS_LINKAT: usize = 37;
+
+/// renameat(olddirfd, oldpath, newdirfd, newpath) -> int (aarch64만, riscv64는 renameat2 사용)
+pub const SYS_RENAMEAT: usize = 38;
+
+/// renameat2(olddirfd, oldpath, newdirfd, newpath, flags) -> int
+pub const SYS_RENAMEAT2: usize = 276;
+
+/// futex(uaddr, op, val, timeout, uaddr2, val3) -> int
+pub const SYS_FUTEX: usize = 98;
+
+/// reboot(magic1, magic2, cmd, arg) -> int
+pub const SYS_REBOOT: usize = 142;
+
+/// kill(pid, sig) -> int
+pub const SYS_KILL: usize = 129;
+
+/// tkill(tid, sig) -> int
+pub const SYS_TKILL: usize = 130;
+
+/// rt_sigaction(sig, act, oldact, sigsetsize) -> int
+pub const SYS_RT_SIGACTION: usize = 134;
+
+/// munmap(addr, len) -> int
+pub const SYS_MUNMAP: usize = 215;
+
 /// mmap(addr, len, prot, flags, fd, offset) -> void*
 pub const SYS_MMAP: usize = 222;
 
+/// ftruncate(fd, length) -> int
+pub const SYS_FTRUNCATE: usize = 46;
+
+/// flock(fd, operation) -> int
+pub const SYS_FLOCK: usize = 32;
+
+/// ioctl(fd, cmd, arg) -> int
+pub const SYS_IOCTL: usize = 29;
+
+/// ppoll(fds, nfds, tmo_p, sigmask, sigsetsize) -> int
+pub const SYS_PPOLL: usize = 73;
+
 // ============================================================================
 // 시스템 콜 디스패처
 // ============================================================================
@@ -72,11 +136,12 @@ pub const SYS_MMAP: usize = 222;
 /// * 실패 시 음수 에러 코드
 pub fn syscall_handler(syscall_num: usize, args: [usize; 6]) -> isize {
     match syscall_num {
-        SYS_OPENAT => {
-            // openat(dirfd, path, flags, mode) - dirfd 무시하고 path만 사용
-            fs::sys_open(args[1] as *const u8, args[2] as u32, args[3] as u32)
-        }
+        SYS_OPENAT => fs::sys_openat(args[0] as i32, args[1] as *const u8, args[2] as u32, args[3] as u32),
         SYS_CLOSE => fs::sys_close(args[0] as i32),
+        SYS_DUP => fs::sys_dup(args[0] as i32),
+        SYS_DUP3 => fs::sys_dup3(args[0] as i32, args[1] as i32, args[2] as u32),
+        SYS_FCNTL => fs::sys_fcntl(args[0] as i32, args[1] as u32, args[2]),
+        SYS_GETDENTS64 => fs::sys_getdents64(args[0] as i32, args[1] as *mut u8, args[2]),
         SYS_LSEEK => fs::sys_lseek(args[0] as i32, args[1] as i64, args[2] as i32),
         SYS_READ => fs::sys_read(args[0], args[1] as *mut u8, args[2]),
         SYS_WRITE => fs::sys_write(args[0], args[1] as *const u8, args[2]),
@@ -85,14 +150,42 @@ pub fn syscall_handler(syscall_num: usize, args: [usize; 6]) -> isize {
         SYS_EXIT_GROUP => process::sys_exit(args[0] as i32),
         SYS_SCHED_YIELD => process::sys_yield(),
         SYS_GETPID => process::sys_getpid(),
-        SYS_MKDIRAT => {
-            // mkdirat(dirfd, path, mode) - dirfd 무시
-            fs::sys_mkdir(args[1] as *const u8, args[2] as u32)
+        SYS_CLOCK_GETTIME => time::sys_clock_gettime(args[0], args[1] as *mut u8),
+        SYS_GETTIMEOFDAY => time::sys_gettimeofday(args[0] as *mut u8, args[1] as *mut u8),
+        SYS_FUTEX => process::sys_futex(args[0], args[1] as u32, args[2] as u32, args[3] as *const u8),
+        SYS_REBOOT => process::sys_reboot(args[0] as u32, args[1] as u32, args[2] as u32),
+        SYS_KILL | SYS_TKILL => process::sys_kill(args[0] as i64, args[1] as u32),
+        SYS_RT_SIGACTION => process::sys_rt_sigaction(
+            args[0] as u32,
+            args[1] as *const u8,
+            args[2] as *mut u8,
+            args[3],
+        ),
+        SYS_PIPE2 => fs::sys_pipe2(args[0] as *mut i32, args[1] as u32),
+        SYS_MKNODAT => {
+            // mknodat(dirfd, path, mode, dev) - FIFO만 지원하므로 dev 무시
+            fs::sys_mknodat(args[0] as i32, args[1] as *const u8, args[2] as u32)
         }
-        SYS_UNLINKAT => {
-            // unlinkat(dirfd, path, flags) - dirfd, flags 무시
-            fs::sys_unlink(args[1] as *const u8)
+        SYS_MKDIRAT => fs::sys_mkdirat(args[0] as i32, args[1] as *const u8, args[2] as u32),
+        SYS_UNLINKAT => fs::sys_unlinkat(args[0] as i32, args[1] as *const u8, args[2] as u32),
+        SYS_LINKAT => fs::sys_linkat(
+            args[0] as i32,
+            args[1] as *const u8,
+            args[2] as i32,
+            args[3] as *const u8,
+            args[4] as u32,
+        ),
+        SYS_RENAMEAT | SYS_RENAMEAT2 => {
+            // renameat은 flags 인자가 없음
+            let flags = if syscall_num == SYS_RENAMEAT2 { args[4] as u32 } else { 0 };
+            fs::sys_renameat2(args[0] as i32, args[1] as *const u8, args[2] as i32, args[3] as *const u8, flags)
         }
+        SYS_FTRUNCATE => fs::sys_ftruncate(args[0] as i32, args[1] as i64),
+        SYS_FLOCK => fs::sys_flock(args[0] as i32, args[1] as u32),
+        SYS_IOCTL => fs::sys_ioctl(args[0] as i32, args[1] as u32, args[2]),
+        SYS_PPOLL => fs::sys_ppoll(args[0] as *mut u8, args[1], args[2] as *const u8),
+        SYS_MMAP => fs::sys_mmap(args[0], args[1], args[2] as u32, args[3] as u32, args[4] as i32, args[5]),
+        SYS_MUNMAP => fs::sys_munmap(args[0], args[1]),
         _ => {
             kprintln!("[syscall] Unknown syscall: {} (args: {:?})", syscall_num, args);
             -1 // EPERM
@@ -108,12 +201,20 @@ pub mod errno {
     pub const ESRCH: isize = -3;
     pub const EINTR: isize = -4;
     pub const EIO: isize = -5;
+    pub const EAGAIN: isize = -11;
     pub const ENOMEM: isize = -12;
     pub const EACCES: isize = -13;
     pub const EFAULT: isize = -14;
     pub const EBUSY: isize = -16;
+    pub const EEXIST: isize = -17;
+    pub const EXDEV: isize = -18;
+    pub const ENODEV: isize = -19;
     pub const ENOTDIR: isize = -20;
     pub const EISDIR: isize = -21;
     pub const EINVAL: isize = -22;
+    pub const ENOTTY: isize = -25;
+    pub const EPIPE: isize = -32;
     pub const ENOSYS: isize = -38;
+    pub const ENOTEMPTY: isize = -39;
+    pub const ETIMEDOUT: isize = -110;
 }
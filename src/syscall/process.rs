@@ -1,9 +1,13 @@
 //! 프로세스 관련 시스템 콜
 //!
-//! exit, yield, getpid 등
+//! exit, yield, getpid, kill, rt_sigaction, futex, reboot 등
 
 use crate::kprintln;
+use crate::power::{self, PowerAction};
 use crate::proc;
+use crate::proc::futex::{self, FutexError};
+use crate::proc::signal::{self, SigAction, SignalError};
+use super::errno;
 
 /// sys_exit - 프로세스 종료
 ///
@@ -36,3 +40,169 @@ pub fn sys_yield() -> isize {
 pub fn sys_getpid() -> isize {
     proc::current_tid().unwrap_or(0) as isize
 }
+
+fn signal_error_to_errno(e: SignalError) -> isize {
+    match e {
+        SignalError::InvalidSignal => errno::EINVAL,
+        SignalError::NoSuchThread => errno::ESRCH,
+        SignalError::NotPermitted => errno::EPERM,
+    }
+}
+
+/// sys_kill - 시그널 보내기
+///
+/// 프로세스 = 스레드이므로 `pid`는 tid입니다. 프로세스 그룹(0, 음수)은 지원하지 않습니다.
+///
+/// # Arguments
+/// * `pid` - 대상 tid
+/// * `sig` - 시그널 번호 (0 = 존재 확인)
+pub fn sys_kill(pid: i64, sig: u32) -> isize {
+    if pid <= 0 {
+        return errno::EINVAL;
+    }
+    match signal::send(pid as proc::Tid, sig) {
+        // 자기 자신에게 보낸 시그널은 syscall 복귀 직전에 전달됨
+        Ok(()) => 0,
+        Err(e) => signal_error_to_errno(e),
+    }
+}
+
+/// 커널 `struct sigaction` 크기 (handler, flags, [restorer], mask)
+#[cfg(target_arch = "aarch64")]
+const SIGACTION_SIZE: usize = 32;
+#[cfg(target_arch = "riscv64")]
+const SIGACTION_SIZE: usize = 24;
+
+/// sa_handler 특수값
+const SIG_DFL: usize = 0;
+const SIG_IGN: usize = 1;
+
+/// sys_rt_sigaction - 시그널 동작 설정/조회
+///
+/// `sa_handler`만 해석하며 `SIG_DFL`/`SIG_IGN`만 받습니다 (유저 핸들러는 `EINVAL`).
+/// `oldact`에는 handler만 채우고 나머지 필드는 0으로 씁니다.
+pub fn sys_rt_sigaction(sig: u32, act: *const u8, oldact: *mut u8, sigsetsize: usize) -> isize {
+    if sigsetsize != 8 {
+        return errno::EINVAL;
+    }
+    let tid = proc::current_tid().unwrap_or(0);
+
+    let old = if act.is_null() {
+        signal::action(tid, sig)
+    } else {
+        let handler = unsafe { core::ptr::read_unaligned(act as *const usize) };
+        let action = match handler {
+            SIG_DFL => SigAction::Default,
+            SIG_IGN => SigAction::Ignore,
+            _ => return errno::EINVAL,
+        };
+        signal::set_action(tid, sig, action)
+    };
+    let old = match old {
+        Ok(old) => old,
+        Err(e) => return signal_error_to_errno(e),
+    };
+
+    if !oldact.is_null() {
+        let handler = match old {
+            SigAction::Default => SIG_DFL,
+            SigAction::Ignore => SIG_IGN,
+        };
+        unsafe {
+            core::ptr::write_bytes(oldact, 0, SIGACTION_SIZE);
+            core::ptr::write_unaligned(oldact as *mut usize, handler);
+        }
+    }
+    0
+}
+
+/// futex op 명령
+const FUTEX_WAIT: u32 = 0;
+const FUTEX_WAKE: u32 = 1;
+/// 무시하는 op 플래그 (프로세스별 주소 공간이 없으므로 PRIVATE도 같은 큐 사용)
+const FUTEX_PRIVATE_FLAG: u32 = 128;
+const FUTEX_CLOCK_REALTIME: u32 = 256;
+
+/// sys_futex - 주소 기반 대기/깨우기
+///
+/// # Arguments
+/// * `uaddr` - futex 워드 주소 (4바이트 정렬)
+/// * `op` - `FUTEX_WAIT` / `FUTEX_WAKE` (+ PRIVATE 플래그)
+/// * `val` - WAIT: 기대 값, WAKE: 깨울 최대 수
+/// * `timeout` - WAIT의 상대 타임아웃 (`struct timespec`, NULL = 무기한)
+///
+/// # Returns
+/// * WAIT: 0 (깨어남), `EAGAIN` (값이 다름), `ETIMEDOUT`, `EINTR`
+/// * WAKE: 깨운 스레드 수
+pub fn sys_futex(uaddr: usize, op: u32, val: u32, timeout: *const u8) -> isize {
+    match op & !(FUTEX_PRIVATE_FLAG | FUTEX_CLOCK_REALTIME) {
+        FUTEX_WAIT => {
+            let timeout_us = if timeout.is_null() {
+                None
+            } else {
+                let (sec, nsec) = unsafe {
+                    (
+                        core::ptr::read_unaligned(timeout as *const i64),
+                        core::ptr::read_unaligned(timeout.add(8) as *const i64),
+                    )
+                };
+                if sec < 0 || !(0..1_000_000_000).contains(&nsec) {
+                    return errno::EINVAL;
+                }
+                Some(sec as u64 * 1_000_000 + nsec as u64 / 1000)
+            };
+            match futex::wait(uaddr, val, timeout_us) {
+                Ok(()) => 0,
+                Err(FutexError::WouldBlock) => errno::EAGAIN,
+                Err(FutexError::TimedOut) => errno::ETIMEDOUT,
+                Err(FutexError::Interrupted) => errno::EINTR,
+                Err(FutexError::InvalidArgument) => errno::EINVAL,
+            }
+        }
+        FUTEX_WAKE => futex::wake(uaddr, val as usize) as isize,
+        _ => errno::ENOSYS,
+    }
+}
+
+/// reboot(2) 매직 넘버 (실수로 부르는 것 방지)
+const LINUX_REBOOT_MAGIC1: u32 = 0xfee1_dead;
+const LINUX_REBOOT_MAGIC2: u32 = 672_274_793;
+const LINUX_REBOOT_MAGIC2A: u32 = 85_072_278;
+const LINUX_REBOOT_MAGIC2B: u32 = 369_367_448;
+const LINUX_REBOOT_MAGIC2C: u32 = 537_993_216;
+
+/// reboot(2) 명령
+const LINUX_REBOOT_CMD_RESTART: u32 = 0x0123_4567;
+const LINUX_REBOOT_CMD_HALT: u32 = 0xcdef_0123;
+const LINUX_REBOOT_CMD_POWER_OFF: u32 = 0x4321_fedc;
+
+/// sys_reboot - 시스템 재부팅/종료
+///
+/// 로그 싱크를 비우고 파일시스템을 언마운트한 뒤 `power::shutdown()`으로 넘어갑니다.
+///
+/// # Arguments
+/// * `magic1`, `magic2` - Linux 매직 넘버 (`0xfee1dead`, `LINUX_REBOOT_MAGIC2*`)
+/// * `cmd` - RESTART, POWER_OFF, HALT
+///
+/// # Returns
+/// * 성공하면 반환하지 않음
+/// * 매직 넘버나 명령이 틀리면 -EINVAL
+pub fn sys_reboot(magic1: u32, magic2: u32, cmd: u32) -> isize {
+    if magic1 != LINUX_REBOOT_MAGIC1
+        || !matches!(
+            magic2,
+            LINUX_REBOOT_MAGIC2 | LINUX_REBOOT_MAGIC2A | LINUX_REBOOT_MAGIC2B | LINUX_REBOOT_MAGIC2C
+        )
+    {
+        return errno::EINVAL;
+    }
+    let action = match cmd {
+        LINUX_REBOOT_CMD_RESTART => PowerAction::Restart,
+        LINUX_REBOOT_CMD_POWER_OFF => PowerAction::PowerOff,
+        LINUX_REBOOT_CMD_HALT => PowerAction::Halt,
+        _ => return errno::EINVAL,
+    };
+    let tid = proc::current_tid().unwrap_or(0);
+    kprintln!("[syscall] Process {} requested reboot ({:#x})", tid, cmd);
+    power::shutdown(action)
+}
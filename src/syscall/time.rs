@@ -0,0 +1,63 @@
+//! 시간 관련 시스템 콜
+//!
+//! clock_gettime, gettimeofday
+
+use super::errno;
+use crate::time::clock::{self, NSEC_PER_SEC};
+
+/// clockid_t 값 (Linux 호환)
+pub const CLOCK_REALTIME: usize = 0;
+pub const CLOCK_MONOTONIC: usize = 1;
+pub const CLOCK_MONOTONIC_RAW: usize = 4;
+pub const CLOCK_REALTIME_COARSE: usize = 5;
+pub const CLOCK_MONOTONIC_COARSE: usize = 6;
+pub const CLOCK_BOOTTIME: usize = 7;
+
+/// 64비트 두 개를 유저 버퍼에 기록 (timespec/timeval 공통 레이아웃)
+fn write_pair(buf: *mut u8, first: u64, second: u64) {
+    let out = unsafe { core::slice::from_raw_parts_mut(buf, 16) };
+    out[0..8].copy_from_slice(&first.to_le_bytes());
+    out[8..16].copy_from_slice(&second.to_le_bytes());
+}
+
+/// sys_clock_gettime - 시계 읽기
+///
+/// # Arguments
+/// * `clock_id` - `CLOCK_REALTIME`(벽시계) 또는 단조 시계 계열
+/// * `tp` - `struct timespec { i64 tv_sec; i64 tv_nsec; }`
+///
+/// # Returns
+/// * 성공 시 0, 모르는 시계는 `EINVAL`, 널 포인터는 `EFAULT`
+pub fn sys_clock_gettime(clock_id: usize, tp: *mut u8) -> isize {
+    let ns = match clock_id {
+        CLOCK_REALTIME | CLOCK_REALTIME_COARSE => clock::realtime_ns(),
+        // 일시 정지가 없으므로 BOOTTIME = MONOTONIC
+        CLOCK_MONOTONIC | CLOCK_MONOTONIC_RAW | CLOCK_MONOTONIC_COARSE | CLOCK_BOOTTIME => clock::monotonic_ns(),
+        _ => return errno::EINVAL,
+    };
+    if tp.is_null() {
+        return errno::EFAULT;
+    }
+    write_pair(tp, ns / NSEC_PER_SEC, ns % NSEC_PER_SEC);
+    0
+}
+
+/// sys_gettimeofday - 벽시계 읽기 (마이크로초)
+///
+/// # Arguments
+/// * `tv` - `struct timeval { i64 tv_sec; i64 tv_usec; }` (널이면 건너뜀)
+/// * `tz` - `struct timezone` (널이 아니면 0으로 채움, 항상 UTC)
+///
+/// # Returns
+/// * 항상 0
+pub fn sys_gettimeofday(tv: *mut u8, tz: *mut u8) -> isize {
+    if !tv.is_null() {
+        let ns = clock::realtime_ns();
+        write_pair(tv, ns / NSEC_PER_SEC, ns % NSEC_PER_SEC / 1000);
+    }
+    if !tz.is_null() {
+        // tz_minuteswest, tz_dsttime
+        unsafe { core::ptr::write_bytes(tz, 0, 8) };
+    }
+    0
+}
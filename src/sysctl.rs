@@ -0,0 +1,283 @@
+//! 런타임 조정 값 레지스트리 (sysctl)
+//!
+//! 서브시스템이 이름 붙은 정수/불리언/문자열 노브(`Tunable`)를 등록하면 `sysctl` 셸 명령으로
+//! 실행 중에 읽고 바꿀 수 있습니다. 이름은 명령줄 옵션처럼 점으로 구분합니다
+//! (`sched.quantum`, `log.level`). 나중에 procfs가 생기면 같은 노브를 `/proc/sys/sched/quantum`
+//! 처럼 점을 `/`로 바꾼 경로에 노출할 예정이며, 지금도 `sysctl`은 두 형식을 모두 받습니다.
+//!
+//! ```text
+//! sysctl -a                   모든 노브
+//! sysctl sched                sched.* 노브
+//! sysctl sched.quantum=5      값 쓰기
+//! ```
+//!
+//! 노브는 값을 직접 들고 있지 않고 서브시스템의 상태(보통 원자 변수)를 읽고 쓰는 함수 쌍을
+//! 가리킵니다. 그래서 명령줄 옵션이나 전용 셸 명령으로 바꾼 값도 그대로 보입니다. 정수 범위와
+//! 형식은 레지스트리가 확인하고, 그 밖의 검증(문자열 선택지 등)은 `set` 함수가 합니다.
+//!
+//! 노브는 `static`으로 만들어 각 서브시스템의 초기화 함수에서 `register_all()`로 등록합니다.
+//! 레지스트리는 `&'static Tunable`을 그대로 들고 있으므로 언로드할 수 있는 모듈은 노브를
+//! 등록하지 않습니다.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::sync::RwLock;
+
+/// 노브 값
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Int(i64),
+    Bool(bool),
+    Str(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            // Linux sysctl처럼 불리언은 0/1
+            Self::Int(v) => write!(f, "{}", v),
+            Self::Bool(v) => write!(f, "{}", *v as u8),
+            Self::Str(s) => f.write_str(s),
+        }
+    }
+}
+
+/// 노브 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// `min..=max` 범위의 정수 (10진수 또는 `0x` 16진수)
+    Int { min: i64, max: i64 },
+    /// `0`/`1`, `on`/`off`, `true`/`false`, `yes`/`no`
+    Bool,
+    Str,
+}
+
+impl Kind {
+    /// 문자열을 이 종류의 값으로 (정수는 범위 확인)
+    pub fn parse(self, s: &str) -> Result<Value, SysctlError> {
+        match self {
+            Kind::Int { min, max } => {
+                let v = parse_int(s).ok_or(SysctlError::Invalid("not an integer"))?;
+                if v < min || v > max {
+                    return Err(SysctlError::OutOfRange { min, max });
+                }
+                Ok(Value::Int(v))
+            }
+            Kind::Bool => match s {
+                "1" | "on" | "true" | "yes" => Ok(Value::Bool(true)),
+                "0" | "off" | "false" | "no" => Ok(Value::Bool(false)),
+                _ => Err(SysctlError::Invalid("expected 0/1, on/off, true/false or yes/no")),
+            },
+            Kind::Str => Ok(Value::Str(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Kind::Int { min, max } => write!(f, "int {}..{}", min, max),
+            Kind::Bool => write!(f, "bool"),
+            Kind::Str => write!(f, "string"),
+        }
+    }
+}
+
+/// 조정 값 하나
+pub struct Tunable {
+    /// 점으로 구분한 이름 (`sched.quantum`)
+    pub name: &'static str,
+    /// 한 줄 설명
+    pub desc: &'static str,
+    pub kind: Kind,
+    /// 현재 값 (`kind`와 같은 종류)
+    pub get: fn() -> Value,
+    /// 값 적용 (`kind`로 파싱하고 범위를 확인한 값을 받음, None = 읽기 전용)
+    pub set: Option<fn(Value) -> Result<(), &'static str>>,
+}
+
+/// sysctl 에러
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SysctlError {
+    /// 없는 이름
+    NotFound,
+    /// 같은 이름이 이미 등록됨
+    Exists,
+    /// 읽기 전용 노브
+    ReadOnly,
+    /// 정수 범위를 벗어남
+    OutOfRange { min: i64, max: i64 },
+    /// 형식이 맞지 않거나 서브시스템이 거부함
+    Invalid(&'static str),
+}
+
+impl fmt::Display for SysctlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "unknown key"),
+            Self::Exists => write!(f, "key already registered"),
+            Self::ReadOnly => write!(f, "read-only key"),
+            Self::OutOfRange { min, max } => write!(f, "out of range ({}..{})", min, max),
+            Self::Invalid(msg) => f.write_str(msg),
+        }
+    }
+}
+
+/// 등록된 노브 (이름순)
+static REGISTRY: RwLock<Vec<&'static Tunable>> = RwLock::new(Vec::new());
+
+/// `/proc/sys` 경로 형식(`sched/quantum`)도 받도록 이름 정규화
+fn normalize(name: &str) -> String {
+    name.trim_start_matches('/').trim_start_matches("proc/sys/").replace('/', ".")
+}
+
+/// 노브 등록 (이름이 겹치면 `Exists`)
+pub fn register(tunable: &'static Tunable) -> Result<(), SysctlError> {
+    let mut registry = REGISTRY.write();
+    match registry.binary_search_by(|t| t.name.cmp(tunable.name)) {
+        Ok(_) => Err(SysctlError::Exists),
+        Err(pos) => {
+            registry.insert(pos, tunable);
+            Ok(())
+        }
+    }
+}
+
+/// 노브 등록 해제 (있었으면 true)
+pub fn unregister(name: &str) -> bool {
+    let mut registry = REGISTRY.write();
+    match registry.binary_search_by(|t| t.name.cmp(name)) {
+        Ok(pos) => {
+            registry.remove(pos);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// 서브시스템의 노브 목록 등록 (겹치는 이름은 경고만 출력)
+pub fn register_all(tunables: &'static [Tunable]) {
+    for tunable in tunables {
+        if let Err(e) = register(tunable) {
+            crate::log_warn!(tag: "sysctl", "{}: {}", tunable.name, e);
+        }
+    }
+}
+
+/// 이름으로 노브 찾기
+pub fn find(name: &str) -> Option<&'static Tunable> {
+    let name = normalize(name);
+    let registry = REGISTRY.read();
+    registry
+        .binary_search_by(|t| t.name.cmp(name.as_str()))
+        .ok()
+        .map(|i| registry[i])
+}
+
+/// 현재 값 읽기
+pub fn get(name: &str) -> Result<Value, SysctlError> {
+    find(name).map(|t| (t.get)()).ok_or(SysctlError::NotFound)
+}
+
+/// 문자열 값을 파싱해 쓰기
+pub fn set(name: &str, value: &str) -> Result<(), SysctlError> {
+    let tunable = find(name).ok_or(SysctlError::NotFound)?;
+    let set = tunable.set.ok_or(SysctlError::ReadOnly)?;
+    let value = tunable.kind.parse(value.trim())?;
+    set(value).map_err(SysctlError::Invalid)
+}
+
+/// 이름이 `prefix`이거나 `prefix.`으로 시작하는 노브들 (빈 문자열이면 전부, 이름순)
+pub fn list(prefix: &str) -> Vec<&'static Tunable> {
+    let prefix = normalize(prefix);
+    let prefix = prefix.trim_end_matches('.');
+    REGISTRY
+        .read()
+        .iter()
+        .copied()
+        .filter(|t| {
+            prefix.is_empty()
+                || t.name == prefix
+                || (t.name.starts_with(prefix) && t.name.as_bytes().get(prefix.len()) == Some(&b'.'))
+        })
+        .collect()
+}
+
+/// 10진수 (음수 포함) 또는 `0x` 16진수
+fn parse_int(s: &str) -> Option<i64> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+/// `Value::Int`의 값 (`set` 함수용, 레지스트리가 종류를 맞춰 넘기므로 다른 종류는 0)
+pub fn int(value: &Value) -> i64 {
+    match value {
+        Value::Int(v) => *v,
+        _ => 0,
+    }
+}
+
+/// `Value::Bool`의 값
+pub fn boolean(value: &Value) -> bool {
+    matches!(value, Value::Bool(true))
+}
+
+/// `Value::Str`의 값
+pub fn string(value: &Value) -> &str {
+    match value {
+        Value::Str(s) => s,
+        _ => "",
+    }
+}
+
+/// `sysctl` 명령: 인자 없거나 `-a`면 전부, `<이름>`이면 그 노브(또는 하위 노브), `<이름>=<값>`이면 쓰기
+pub fn command(args: &[&str]) {
+    let verbose = args.contains(&"-v");
+    let args: Vec<&str> = args.iter().copied().filter(|a| *a != "-v" && *a != "-a").collect();
+    if args.is_empty() {
+        print_list("", verbose);
+        return;
+    }
+
+    // `name=value`와 `name = value` 모두 허용
+    let joined = args.join(" ");
+    if let Some((name, value)) = joined.split_once('=') {
+        let name = name.trim();
+        match set(name, value).and_then(|()| find(name).ok_or(SysctlError::NotFound)) {
+            Ok(tunable) => print_one(tunable, verbose),
+            Err(e) => crate::kprintln!("sysctl: {}: {}", name, e),
+        }
+        return;
+    }
+
+    for name in args {
+        if let Some(tunable) = find(name) {
+            print_one(tunable, verbose);
+        } else if !print_list(name, verbose) {
+            crate::kprintln!("sysctl: {}: {}", name, SysctlError::NotFound);
+        }
+    }
+}
+
+fn print_one(tunable: &Tunable, verbose: bool) {
+    let value = (tunable.get)();
+    if verbose {
+        let ro = if tunable.set.is_none() { ", ro" } else { "" };
+        crate::kprintln!("{} = {}    # {} ({}{})", tunable.name, value, tunable.desc, tunable.kind, ro);
+    } else {
+        crate::kprintln!("{} = {}", tunable.name, value);
+    }
+}
+
+/// `prefix` 아래 노브 출력 (하나도 없으면 false)
+fn print_list(prefix: &str, verbose: bool) -> bool {
+    let tunables = list(prefix);
+    for tunable in &tunables {
+        print_one(tunable, verbose);
+    }
+    !tunables.is_empty()
+}
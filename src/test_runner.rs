@@ -1,13 +1,114 @@
 //! QEMU 테스트 러너
 //!
 //! `--features test_runner`로 빌드 시 활성화.
-//! 셸 대신 FAT32에서 테스트 모듈(.ko)을 로드/실행하고 결과를 리포팅한 뒤
+//! 셸 대신 커널 테스트와 FAT32의 테스트 모듈(.ko)을 차례로 실행하고 결과를 리포팅한 뒤
 //! QEMU를 종료한다.
+//!
+//! # 테스트 등록
+//!
+//! 커널 테스트는 `kernel_test!`로 `.ktests` 링크 섹션에 모으고, 링커 스크립트가
+//! `__ktests_start`..`__ktests_end` 사이에 둔다 (`export_symbol!`의 `.ksymtab`과 같은 방식).
+//! 테스트 모듈은 `/mnt/test_*.ko`이며 이름은 파일 이름(소문자, 확장자 제외)이다.
+//!
+//! ```rust,ignore
+//! fn heap_roundtrip() -> TestResult {
+//!     let v = alloc::vec![1u8; 64];
+//!     if v.iter().all(|&b| b == 1) { Ok(()) } else { Err("bad data".into()) }
+//! }
+//! crate::kernel_test!("kernel.heap.roundtrip", heap_roundtrip);
+//! crate::kernel_test!("kernel.slow", slow_test, 30_000); // 제한 시간 30초
+//! ```
+//!
+//! # 선택과 제한 시간
+//!
+//! - 명령줄 `-t <패턴>`으로 실행할 테스트를 고른다 (여러 번 쓰면 하나라도 맞으면 실행).
+//!   패턴에 `*`, `?`가 있으면 이름 전체와 glob 비교, 없으면 부분 문자열 비교.
+//! - 테스트마다 전용 스레드(`test/<이름>`)에서 실행하고, `time::timer` 일회성 타이머로
+//!   제한 시간을 잰다. 넘기면 스레드를 종료하고 `TIMEOUT`으로 기록한다. 기본 제한 시간은
+//!   `test.timeout=<초>` (기본 10초), `kernel_test!`의 세 번째 인자가 우선한다.
+//!   종료한 스레드가 잡고 있던 락은 풀리지 않으므로 뒤 테스트가 멈출 수 있다.
+//! - `heap_redzone` feature를 함께 켜므로, 테스트 중에 힙 레드존 오류(overflow, double free)가
+//!   새로 보고되면 테스트 자체가 성공해도 실패로 본다.
+//!
+//! # 출력 (CI 파싱용)
+//!
+//! ```text
+//! TEST_RESULT name=test_mm status=PASS duration_ms=12
+//! TEST_RESULT name=kernel.timer.oneshot status=FAIL duration_ms=3 reason="fired 0 times"
+//! TEST_SUMMARY total=9 passed=7 failed=1 timeout=1 skipped=2 duration_ms=1530
+//! RESULT: 7 passed, 2 failed
+//! TEST_STATUS: FAIL
+//! ```
 
+use alloc::format;
 use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::proc::{self, Tid};
+use crate::sync::Mutex;
+use crate::{kprintln, block, fs, mm, module, time};
+
+/// 기본 제한 시간 (밀리초)
+const DEFAULT_TIMEOUT_MS: u64 = 10_000;
+
+/// 종료한 테스트 스레드가 CPU에서 내려갈 때까지 기다리는 최대 양보 횟수
+const DRAIN_YIELDS: usize = 100;
+
+/// 완료/제한 시간 확인 간격 (마이크로초, 틱 하나)
+const POLL_US: u64 = 10_000;
+
+/// 테스트 결과 (`Err`는 실패 이유)
+pub type TestResult = Result<(), String>;
+
+/// `.ktests` 섹션 항목 (`kernel_test!`가 생성)
+#[repr(C)]
+pub struct KernelTest {
+    /// 테스트 이름 (`-t` 패턴과 비교)
+    pub name: &'static str,
+    /// 테스트 함수
+    pub func: fn() -> TestResult,
+    /// 제한 시간 (밀리초, 0 = `test.timeout=` 기본값)
+    pub timeout_ms: u64,
+}
+
+/// 커널 테스트를 `.ktests` 링크 섹션에 등록
+///
+/// `kernel_test!("이름", 함수)` 또는 `kernel_test!("이름", 함수, 제한_시간_ms)`.
+/// 함수는 `fn() -> TestResult`이며 전용 스레드에서 실행됩니다.
+#[macro_export]
+macro_rules! kernel_test {
+    ($name:expr, $func:path) => {
+        $crate::kernel_test!($name, $func, 0);
+    };
+    ($name:expr, $func:path, $timeout_ms:expr) => {
+        const _: () = {
+            #[used]
+            #[unsafe(link_section = ".ktests")]
+            static KTEST: $crate::test_runner::KernelTest = $crate::test_runner::KernelTest {
+                name: $name,
+                func: $func,
+                timeout_ms: $timeout_ms,
+            };
+        };
+    };
+}
 
-use crate::{kprintln, block, fs, module};
+/// 링커가 모은 `.ktests` 항목들
+fn ktests() -> &'static [KernelTest] {
+    unsafe extern "C" {
+        static __ktests_start: u8;
+        static __ktests_end: u8;
+    }
+    // Safety: 링커 스크립트가 두 심볼 사이에 KernelTest 배열만 둠
+    unsafe {
+        let start = &raw const __ktests_start as usize;
+        let end = &raw const __ktests_end as usize;
+        let count = (end - start) / core::mem::size_of::<KernelTest>();
+        core::slice::from_raw_parts(start as *const KernelTest, count)
+    }
+}
 
 /// QEMU 종료
 ///
@@ -110,30 +211,227 @@ fn find_test_modules() -> Vec<String> {
     modules
 }
 
-/// 단일 테스트 모듈 실행
-/// 반환: true = pass, false = fail
-fn run_test_module(path: &str) -> bool {
+/// 실행할 테스트
+#[derive(Clone)]
+enum Test {
+    Kernel(&'static KernelTest),
+    /// 모듈 파일 경로
+    Module(String),
+}
+
+impl Test {
+    /// 결과 줄과 `-t` 패턴에 쓰는 이름
+    fn name(&self) -> String {
+        match self {
+            Test::Kernel(test) => String::from(test.name),
+            Test::Module(path) => module_name(path).to_ascii_lowercase(),
+        }
+    }
+
+    /// 제한 시간 (밀리초, 0 = 무제한)
+    fn timeout_ms(&self) -> u64 {
+        let default = crate::boot::cmdline::test_timeout()
+            .map(|secs| secs as u64 * 1000)
+            .unwrap_or(DEFAULT_TIMEOUT_MS);
+        match self {
+            Test::Kernel(test) if test.timeout_ms != 0 => test.timeout_ms,
+            _ => default,
+        }
+    }
+
+    fn run(&self) -> TestResult {
+        match self {
+            Test::Kernel(test) => (test.func)(),
+            Test::Module(path) => run_test_module(path),
+        }
+    }
+}
+
+/// 테스트 하나의 결과
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    Pass,
+    Fail(String),
+    Timeout,
+    Skip,
+}
+
+impl Outcome {
+    fn status(&self) -> &'static str {
+        match self {
+            Outcome::Pass => "PASS",
+            Outcome::Fail(_) => "FAIL",
+            Outcome::Timeout => "TIMEOUT",
+            Outcome::Skip => "SKIP",
+        }
+    }
+}
+
+/// 실행 중인 테스트 (테스트 스레드에 넘김)
+struct Run {
+    test: Test,
+    waiter: Tid,
+    done: AtomicBool,
+    result: Mutex<Option<TestResult>>,
+}
+
+/// 새 테스트 스레드에 넘길 실행
+static PENDING: Mutex<Option<Arc<Run>>> = Mutex::new(None);
+
+/// 현재 테스트의 제한 시간이 지났는지 (타이머 콜백이 설정)
+static EXPIRED: AtomicBool = AtomicBool::new(false);
+
+/// 제한 시간 타이머 콜백 (인터럽트 문맥이므로 표시만 함)
+fn on_timeout(_: usize) {
+    EXPIRED.store(true, Ordering::Release);
+}
+
+/// `path`의 모듈 이름 (`/mnt/TEST_MM.KO` → `TEST_MM`)
+fn module_name(path: &str) -> &str {
     let name = path.rsplit('/').next().unwrap_or("unknown");
-    let module_name = name.trim_end_matches(".ko").trim_end_matches(".o");
+    name.trim_end_matches(".ko").trim_end_matches(".KO").trim_end_matches(".o")
+}
+
+/// 단일 테스트 모듈 실행: 로드(`module_init`) 후 언로드
+fn run_test_module(path: &str) -> TestResult {
+    let module_name = module_name(path);
 
     kprintln!("[test] Loading {} ...", path);
 
     match module::ModuleLoader::load_from_path(path) {
-        Ok(_module) => {
+        Ok(module) => {
             kprintln!("[test] {}: module_init() returned 0 → OK", module_name);
-            // 언로드
-            let _ = module::ModuleLoader::unload(module_name);
-            true
+            // 언로드 (로더가 붙인 이름, FAT32 8.3이면 확장자 대문자 포함)
+            let name = module.info.name.clone();
+            drop(module);
+            let _ = module::ModuleLoader::unload(&name);
+            Ok(())
         }
-        Err(module::ModuleError::InitFailed(code)) => {
-            kprintln!("[test] {}: module_init() returned {} → FAIL", module_name, code);
-            false
+        Err(module::ModuleError::InitFailed(code)) => Err(format!("module_init() returned {}", code)),
+        Err(e) => Err(format!("load failed: {:?}", e)),
+    }
+}
+
+/// 테스트를 전용 스레드에서 제한 시간 안에 실행
+fn run_one(test: &Test) -> Outcome {
+    let heap_errors = mm::redzone::errors();
+    let outcome = run_isolated(test);
+
+    let new_errors = mm::redzone::errors() - heap_errors;
+    match outcome {
+        Outcome::Pass if new_errors != 0 => Outcome::Fail(format!("{} heap redzone error(s)", new_errors)),
+        outcome => outcome,
+    }
+}
+
+fn run_isolated(test: &Test) -> Outcome {
+    let Some(waiter) = proc::current_tid() else {
+        return match test.run() {
+            Ok(()) => Outcome::Pass,
+            Err(reason) => Outcome::Fail(reason),
+        };
+    };
+
+    let run = Arc::new(Run {
+        test: test.clone(),
+        waiter,
+        done: AtomicBool::new(false),
+        result: Mutex::new(None),
+    });
+    *PENDING.lock() = Some(Arc::clone(&run));
+
+    let timeout_ms = test.timeout_ms();
+    EXPIRED.store(false, Ordering::Release);
+    let timer = (timeout_ms != 0).then(|| time::timer::oneshot("test-timeout", timeout_ms, on_timeout, 0));
+    let tid = proc::spawn(&format!("test/{}", test.name()), test_thread);
+
+    while !run.done.load(Ordering::Acquire) && !EXPIRED.load(Ordering::Acquire) {
+        proc::park_timeout(POLL_US);
+    }
+    if let Some(timer) = timer {
+        time::timer::cancel(timer);
+    }
+
+    if run.done.load(Ordering::Acquire) {
+        let _ = proc::join(tid);
+        return match run.result.lock().take() {
+            Some(Ok(())) => Outcome::Pass,
+            Some(Err(reason)) => Outcome::Fail(reason),
+            None => Outcome::Fail(String::from("no result")),
+        };
+    }
+
+    // 제한 시간 초과: 스레드를 멈추고 CPU에서 내려올 때까지 대기
+    PENDING.lock().take();
+    proc::kill(tid);
+    for _ in 0..DRAIN_YIELDS {
+        if !proc::is_on_cpu(tid) {
+            break;
         }
-        Err(e) => {
-            kprintln!("[test] {}: load failed: {:?} → FAIL", module_name, e);
-            false
+        proc::yield_now();
+    }
+    let _ = proc::detach(tid);
+    Outcome::Timeout
+}
+
+fn test_thread() -> ! {
+    let Some(run) = PENDING.lock().take() else {
+        proc::exit();
+    };
+    let result = run.test.run();
+    *run.result.lock() = Some(result);
+    run.done.store(true, Ordering::Release);
+    proc::unpark(run.waiter);
+    proc::exit();
+}
+
+/// `-t` 패턴과 이름 비교 (`*`, `?`가 있으면 glob, 없으면 부분 문자열)
+fn matches(pattern: &str, name: &str) -> bool {
+    if !pattern.contains(['*', '?']) {
+        return name.contains(pattern);
+    }
+    glob(pattern.as_bytes(), name.as_bytes())
+}
+
+fn glob(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // 마지막 `*`의 위치와 그때 비교하던 이름 위치 (되돌아갈 곳)
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((sp, sn)) => {
+                    star = Some((sp, sn + 1));
+                    p = sp + 1;
+                    n = sn + 1;
+                }
+                None => return false,
+            },
         }
     }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// 결과 한 줄 출력 (`reason`의 큰따옴표는 작은따옴표로)
+fn report(name: &str, outcome: &Outcome, duration_ms: u64) {
+    match outcome {
+        Outcome::Fail(reason) => kprintln!(
+            "TEST_RESULT name={} status={} duration_ms={} reason=\"{}\"",
+            name,
+            outcome.status(),
+            duration_ms,
+            reason.replace('"', "'")
+        ),
+        _ => kprintln!("TEST_RESULT name={} status={} duration_ms={}", name, outcome.status(), duration_ms),
+    }
 }
 
 /// 전체 테스트 스위트 실행 → QEMU 종료
@@ -148,35 +446,82 @@ pub fn run_kernel_tests() -> ! {
         qemu_exit(1);
     }
 
-    // 2. 테스트 모듈 탐색
+    // 2. 테스트 탐색: 커널 테스트(이름순) 다음 테스트 모듈
     let test_modules = find_test_modules();
     if test_modules.is_empty() {
         kprintln!("[test] No test modules found in /mnt/");
         kprintln!("TEST_STATUS: FAIL");
         qemu_exit(1);
     }
+    let mut kernel_tests: Vec<&'static KernelTest> = ktests().iter().collect();
+    kernel_tests.sort_by_key(|test| test.name);
 
-    kprintln!("[test] Found {} test module(s)", test_modules.len());
+    let tests: Vec<Test> = kernel_tests
+        .into_iter()
+        .map(Test::Kernel)
+        .chain(test_modules.into_iter().map(Test::Module))
+        .collect();
+
+    let filters = crate::boot::cmdline::test_filters();
+    let selected = |test: &Test| filters.is_empty() || filters.iter().any(|f| matches(f, &test.name()));
+    let count = tests.iter().filter(|test| selected(test)).count();
+
+    kprintln!("[test] Found {} test(s), {} selected", tests.len(), count);
+    if !filters.is_empty() {
+        kprintln!("[test] Filter: {}", filters.join(" "));
+    }
     kprintln!("");
+    if count == 0 {
+        kprintln!("[test] No tests match the filter");
+        kprintln!("TEST_STATUS: FAIL");
+        qemu_exit(1);
+    }
 
     // 3. 순서대로 실행
-    let mut passed = 0u32;
-    let mut failed = 0u32;
+    let (mut passed, mut failed, mut timeout, mut skipped) = (0u32, 0u32, 0u32, 0u32);
+    let suite_start = time::now_us();
 
-    for path in &test_modules {
-        if run_test_module(path) {
-            passed += 1;
-        } else {
-            failed += 1;
+    for test in &tests {
+        let name = test.name();
+        if !selected(test) {
+            report(&name, &Outcome::Skip, 0);
+            skipped += 1;
+            continue;
+        }
+
+        let start = time::now_us();
+        let outcome = run_one(test);
+        let duration_ms = (time::now_us() - start) / 1000;
+        match &outcome {
+            Outcome::Pass => passed += 1,
+            Outcome::Fail(reason) => {
+                kprintln!("[test] {}: {} → FAIL", name, reason);
+                failed += 1;
+            }
+            Outcome::Timeout => {
+                kprintln!("[test] {}: no result after {} ms → TIMEOUT", name, test.timeout_ms());
+                timeout += 1;
+            }
+            Outcome::Skip => skipped += 1,
         }
+        report(&name, &outcome, duration_ms);
         kprintln!("");
     }
 
     // 4. 결과 리포팅
     kprintln!("=== KERNERS TEST SUITE END ===");
-    kprintln!("RESULT: {} passed, {} failed", passed, failed);
+    kprintln!(
+        "TEST_SUMMARY total={} passed={} failed={} timeout={} skipped={} duration_ms={}",
+        tests.len(),
+        passed,
+        failed,
+        timeout,
+        skipped,
+        (time::now_us() - suite_start) / 1000
+    );
+    kprintln!("RESULT: {} passed, {} failed", passed, failed + timeout);
 
-    if failed == 0 {
+    if failed + timeout == 0 {
         kprintln!("TEST_STATUS: PASS");
         qemu_exit(0);
     } else {
@@ -184,3 +529,77 @@ pub fn run_kernel_tests() -> ! {
         qemu_exit(1);
     }
 }
+
+// ---- 커널 테스트 ----
+
+/// 크기와 정렬이 다른 할당을 쓰고 읽은 뒤 해제 (레드존 오류가 나면 러너가 실패로 봄)
+fn heap_roundtrip() -> TestResult {
+    for (i, size) in [1usize, 24, 100, 4096, 20000].into_iter().enumerate() {
+        let pattern = i as u8 + 1;
+        let data = alloc::vec![pattern; size];
+        if data.iter().any(|&b| b != pattern) {
+            return Err(format!("{} byte allocation read back wrong data", size));
+        }
+    }
+    Ok(())
+}
+crate::kernel_test!("kernel.heap.roundtrip", heap_roundtrip);
+
+/// 일회성 타이머가 한 번만 불림
+fn timer_oneshot() -> TestResult {
+    use core::sync::atomic::AtomicU32;
+
+    static FIRED: AtomicU32 = AtomicU32::new(0);
+    fn fire(_: usize) {
+        FIRED.fetch_add(1, Ordering::Relaxed);
+    }
+
+    FIRED.store(0, Ordering::Relaxed);
+    time::timer::oneshot("ktest-oneshot", 20, fire, 0);
+    let deadline = time::now_us() + 1_000_000;
+    while FIRED.load(Ordering::Relaxed) == 0 && time::now_us() < deadline {
+        proc::park_timeout(POLL_US);
+    }
+    proc::park_timeout(50_000);
+    match FIRED.load(Ordering::Relaxed) {
+        1 => Ok(()),
+        n => Err(format!("fired {} times", n)),
+    }
+}
+crate::kernel_test!("kernel.timer.oneshot", timer_oneshot);
+
+/// 생성한 스레드의 종료 상태를 join으로 받음
+fn proc_spawn_join() -> TestResult {
+    fn worker() -> ! {
+        proc::exit_with(7);
+    }
+
+    let tid = proc::spawn("ktest-worker", worker);
+    match proc::join(tid) {
+        Ok(7) => Ok(()),
+        other => Err(format!("join returned {:?}", other)),
+    }
+}
+crate::kernel_test!("kernel.proc.spawn_join", proc_spawn_join);
+
+/// `-t` 패턴 비교
+fn runner_filter() -> TestResult {
+    let cases = [
+        ("heap", "kernel.heap.roundtrip", true),
+        ("kernel.*", "kernel.timer.oneshot", true),
+        ("kernel.*", "test_mm", false),
+        ("test_?m", "test_mm", true),
+        ("*.oneshot", "kernel.timer.oneshot", true),
+        ("*time*shot", "kernel.timer.oneshot", true),
+        ("test_mm*", "test_mm", true),
+        ("test_m", "test_mm", true),
+        ("test_m?", "test_m", false),
+    ];
+    for (pattern, name, expected) in cases {
+        if matches(pattern, name) != expected {
+            return Err(format!("'{}' vs '{}' should be {}", pattern, name, expected));
+        }
+    }
+    Ok(())
+}
+crate::kernel_test!("kernel.runner.filter", runner_filter);
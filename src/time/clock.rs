@@ -0,0 +1,182 @@
+//! 단조 시계와 벽시계
+//!
+//! - 단조 시계(`monotonic_ns`): 부팅 후 나노초. 아키텍처 카운터(aarch64 `CNTPCT_EL0`,
+//!   riscv64 CLINT `mtime`)에서 계산하며 되돌아가지 않습니다.
+//! - 벽시계(`realtime_ns`): Unix 시각 나노초 = 부팅 시각 + 단조 시계. 부팅 시각은
+//!   `init()`이 RTC(`rtc`)에서 읽고, RTC가 없으면 1970-01-01에서 시작합니다.
+//!   `set_realtime()`(`date` 명령)으로 다시 맞출 수 있습니다.
+//!
+//! 벽시계는 원자 변수 하나(부팅 시각)로만 유지하므로 인터럽트 경로에서도 읽을 수 있습니다.
+
+use core::fmt;
+use core::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+use crate::kprintln;
+
+pub const NSEC_PER_SEC: u64 = 1_000_000_000;
+
+/// 단조 시계 0 시점의 Unix 시각 (나노초)
+static BOOT_EPOCH_NS: AtomicU64 = AtomicU64::new(0);
+
+/// 벽시계 출처
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ClockSource {
+    /// 맞춘 적 없음 (1970-01-01부터)
+    None = 0,
+    /// 부팅 시 RTC에서 읽음
+    Rtc = 1,
+    /// `set_realtime()`으로 설정
+    Manual = 2,
+}
+
+impl ClockSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClockSource::None => "unset",
+            ClockSource::Rtc => "rtc",
+            ClockSource::Manual => "manual",
+        }
+    }
+}
+
+static SOURCE: AtomicU8 = AtomicU8::new(ClockSource::None as u8);
+
+/// 아키텍처 카운터와 주파수 (로그 타임스탬프와 같은 출처)
+fn counter() -> (u64, u64) {
+    #[cfg(target_arch = "aarch64")]
+    {
+        (crate::arch::timer::get_counter(), crate::arch::timer::get_frequency())
+    }
+    #[cfg(target_arch = "riscv64")]
+    {
+        (crate::arch::timer::get_time(), crate::boards::timer_freq())
+    }
+}
+
+/// 부팅 후 경과 시간 (나노초)
+pub fn monotonic_ns() -> u64 {
+    let (counter, freq) = counter();
+    if freq == 0 {
+        return 0;
+    }
+    // 나머지 부분만 곱해 오버플로 방지
+    (counter / freq) * NSEC_PER_SEC + (counter % freq) * NSEC_PER_SEC / freq
+}
+
+/// 현재 Unix 시각 (나노초)
+pub fn realtime_ns() -> u64 {
+    BOOT_EPOCH_NS.load(Ordering::Relaxed) + monotonic_ns()
+}
+
+/// 벽시계 설정 (Unix 시각 나노초, 단조 시계에는 영향 없음)
+pub fn set_realtime(unix_ns: u64) {
+    set_epoch(unix_ns, ClockSource::Manual);
+}
+
+fn set_epoch(unix_ns: u64, source: ClockSource) {
+    BOOT_EPOCH_NS.store(unix_ns.saturating_sub(monotonic_ns()), Ordering::Relaxed);
+    SOURCE.store(source as u8, Ordering::Relaxed);
+}
+
+/// 벽시계 출처
+pub fn source() -> ClockSource {
+    match SOURCE.load(Ordering::Relaxed) {
+        1 => ClockSource::Rtc,
+        2 => ClockSource::Manual,
+        _ => ClockSource::None,
+    }
+}
+
+/// RTC에서 벽시계 초기화 (MMU 초기화 후 한 번)
+pub fn init() {
+    match super::rtc::probe() {
+        Some(rtc) => {
+            let now = rtc.read_ns();
+            set_epoch(now, ClockSource::Rtc);
+            kprintln!("[time] Wall clock from {} @ {:#x}: {} UTC", rtc.name(), rtc.base(), DateTime::from_unix(now / NSEC_PER_SEC));
+        }
+        None => kprintln!("[time] No RTC found, wall clock starts at 1970-01-01 (set with 'date')"),
+    }
+}
+
+/// 나노초 간격을 ns/us/ms 단위로 출력 (`bench`, `schedstat`)
+pub struct Nanos(pub u64);
+
+impl fmt::Display for Nanos {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ns = self.0;
+        let text = if ns < 10_000 {
+            alloc::format!("{}ns", ns)
+        } else if ns < 10_000_000 {
+            alloc::format!("{}.{}us", ns / 1000, ns % 1000 / 100)
+        } else {
+            alloc::format!("{}.{}ms", ns / 1_000_000, ns % 1_000_000 / 100_000)
+        };
+        f.pad(&text)
+    }
+}
+
+/// 달력 날짜/시각 (UTC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTime {
+    pub year: u32,
+    /// 1-12
+    pub month: u8,
+    /// 1-31
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl DateTime {
+    /// Unix 초 → 날짜 (그레고리력, 1970년 이후)
+    pub fn from_unix(secs: u64) -> Self {
+        let days = secs / 86_400;
+        let rem = secs % 86_400;
+
+        // 0000-03-01 기준 400년 주기로 계산 (윤일이 해 끝에 오도록 3월 시작)
+        let z = days + 719_468;
+        let era = z / 146_097;
+        let doe = z % 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        Self {
+            year: year as u32,
+            month: month as u8,
+            day: day as u8,
+            hour: (rem / 3600) as u8,
+            minute: (rem / 60 % 60) as u8,
+            second: (rem % 60) as u8,
+        }
+    }
+
+    /// 날짜 → Unix 초 (1970년 이전은 0)
+    pub fn to_unix(&self) -> u64 {
+        let (month, day) = (self.month as u64, self.day as u64);
+        let year = self.year as u64 - if month <= 2 { 1 } else { 0 };
+        let era = year / 400;
+        let yoe = year % 400;
+        let mp = if month > 2 { month - 3 } else { month + 9 };
+        let doy = (153 * mp + 2) / 5 + day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = (era * 146_097 + doe).saturating_sub(719_468);
+        days * 86_400 + self.hour as u64 * 3600 + self.minute as u64 * 60 + self.second as u64
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
@@ -0,0 +1,105 @@
+//! 커널 타이머
+//!
+//! - timer: 밀리초 단위 일회성/주기 콜백 타이머 (틱 인터럽트에서 실행)
+//! - clock: 단조 시계(부팅 후 나노초)와 벽시계(Unix 시각)
+//! - rtc: 부팅 시 벽시계를 맞추는 RTC (PL031, Goldfish)
+//! - nohz: 보조 CPU가 idle일 때 주기 틱을 끄고 다음 기한까지 잠드는 틱 없는 idle
+//!
+//! 커널에서 대기 중인 타이머를 한곳에서 나열합니다 (`timers` 명령).
+//! 각 항목은 소유자(커널/스레드/모듈), 만료 시각, 주기를 가지므로 모듈이 언로드
+//! 전에 취소하지 않은 주기 타이머 같은 문제를 찾기 쉽습니다.
+//!
+//! 목록에는 CPU별 아키텍처 틱 타이머(aarch64 Generic Timer, riscv64 CLINT
+//! mtimecmp)와 `timer` 모듈의 콜백 타이머가 들어갑니다.
+//!
+//! 모든 시각은 부팅 후 마이크로초이며 로그 타임스탬프와 같은 시계를 씁니다.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::sync::atomic::Ordering;
+
+use crate::kprintln;
+use crate::proc::percpu;
+
+pub mod clock;
+pub mod nohz;
+pub mod rtc;
+pub mod timer;
+
+/// 대기 중인 타이머 정보
+#[derive(Debug, Clone)]
+pub struct TimerInfo {
+    /// 타이머 이름
+    pub name: String,
+    /// 소유자 ("kernel", "thread <tid>", "module <name>")
+    pub owner: String,
+    /// 타이머가 걸린 CPU (None = CPU 무관)
+    pub cpu: Option<u32>,
+    /// 만료 시각 (부팅 후 마이크로초)
+    pub expires_us: u64,
+    /// 주기 (None = 일회성)
+    pub period_us: Option<u64>,
+}
+
+/// 부팅 후 경과 시간 (마이크로초)
+pub fn now_us() -> u64 {
+    clock::monotonic_ns() / 1000
+}
+
+/// 현재 CPU의 틱 타이머가 `period_ms` 뒤로 설정되었음을 기록 (arch 타이머에서 호출)
+#[inline]
+pub fn tick_armed(period_ms: u64) {
+    percpu::current()
+        .next_tick_us
+        .store(now_us() + period_ms * 1000, Ordering::Relaxed);
+}
+
+/// 대기 중인 타이머 목록 (만료 시각 순)
+pub fn list_timers() -> Vec<TimerInfo> {
+    let period_us = crate::arch::timer::TIMER_TICK_MS * 1000;
+    let mut timers = Vec::new();
+
+    // CPU별 틱 타이머
+    for cpu in 0..percpu::total_count() {
+        let pc = percpu::get(cpu);
+        if !pc.is_online() {
+            continue;
+        }
+        timers.push(TimerInfo {
+            name: String::from("tick"),
+            owner: String::from("kernel"),
+            cpu: Some(cpu),
+            expires_us: pc.next_tick_us.load(Ordering::Relaxed),
+            period_us: Some(period_us),
+        });
+    }
+
+    // 콜백 타이머
+    timer::list(&mut timers);
+
+    timers.sort_by_key(|t| t.expires_us);
+    timers
+}
+
+/// 대기 중인 타이머 출력 (`timers` 명령)
+pub fn dump_timers() {
+    let now = now_us();
+    let timers = list_timers();
+
+    kprintln!("now: {}.{:06}", now / 1_000_000, now % 1_000_000);
+    kprintln!("  {:<16} {:<16} {:>4} {:>12} {:>10}", "NAME", "OWNER", "CPU", "EXPIRES(us)", "PERIOD(us)");
+    for t in &timers {
+        let cpu = match t.cpu {
+            Some(cpu) => alloc::format!("{}", cpu),
+            None => String::from("-"),
+        };
+        let period = match t.period_us {
+            Some(p) => alloc::format!("{}", p),
+            None => String::from("oneshot"),
+        };
+        // 만료 시각은 현재 기준 상대값 (음수 = 이미 지남)
+        let expires = t.expires_us as i64 - now as i64;
+        kprintln!("  {:<16} {:<16} {:>4} {:>+12} {:>10}", t.name, t.owner, cpu, expires, period);
+    }
+    kprintln!("{} timer(s), {} callbacks fired", timers.len(), timer::fired());
+}
@@ -0,0 +1,132 @@
+//! 틱 없는 idle (NO_HZ idle)
+//!
+//! 보조 CPU의 idle 루프는 실행할 스레드가 없으면 주기 틱(10ms)을 끄고, 다음 할 일까지
+//! 한 번만 울리는 타이머를 건 뒤 `wfi`로 잠듭니다 (`idle()`). 다음 할 일은 이 CPU
+//! 런큐에서 `park_timeout()`으로 잠든 스레드의 가장 이른 기한이고, 없으면 `MAX_SLEEP_US`
+//! 뒤입니다.
+//!
+//! - CPU 0은 주기 틱을 유지합니다. CPU 0의 idle 스레드는 셸이고(RX 인터럽트가 없는 콘솔은
+//!   틱마다 확인), 전역 콜백 타이머(`time::timer`)는 CPU 0의 틱에서 제때 실행됩니다.
+//! - 다른 CPU가 이 CPU 런큐의 스레드를 깨우거나 스레드를 넣으면 reschedule IPI로 깨웁니다
+//!   (`proc::kick_idle_cpu`, `spawn`).
+//! - 깨어나면 잠든 동안 건너뛴 틱 수를 CPU 틱 카운터(`tick_count`)와 전역 틱 카운터에 더해
+//!   주기 틱을 계속 받은 것과 같게 맞추고, `ticks_skipped`에도 셉니다 (`cpuinfo`).
+//!
+//! 타이머를 걸고 `wfi`에 들어가는 사이에 온 인터럽트를 놓치지 않도록 인터럽트를 끈 채
+//! 잠듭니다 (aarch64와 riscv64 모두 대기 중인 인터럽트는 꺼져 있어도 `wfi`를 깨움).
+//! 깨어났을 때 일회성 타이머 인터럽트가 대기 중이면 그대로 두어 틱 핸들러가 주기 틱을
+//! 다시 설정하게 하고, 다른 인터럽트로 일찍 깼으면 여기서 주기 틱을 다시 설정합니다.
+//!
+//! aarch64에서는 DTB에 쓸 수 있는 PSCI idle 상태가 있으면 다음 타이머까지 남은 시간에 맞는
+//! 가장 깊은 상태로 `wfi` 대신 `CPU_SUSPEND`합니다 (`drivers::psci::suspend_idle`).
+//!
+//! `nohz=off` 명령줄 옵션으로 끌 수 있습니다.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::now_us;
+use crate::proc::percpu;
+use crate::sysctl::{self, Kind, Tunable, Value};
+
+/// 한 번에 잠드는 최대 시간 (마이크로초)
+const MAX_SLEEP_US: u64 = 1_000_000;
+
+/// 틱 주기 (마이크로초)
+const TICK_US: u64 = crate::arch::timer::TIMER_TICK_MS * 1000;
+
+/// 틱 없는 idle 사용 여부
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+static SYSCTL: [Tunable; 1] = [Tunable {
+    name: "time.nohz",
+    desc: "tickless idle on secondary CPUs",
+    kind: Kind::Bool,
+    get: || Value::Bool(is_enabled()),
+    set: Some(|v| {
+        set_enabled(sysctl::boolean(&v));
+        Ok(())
+    }),
+}];
+
+/// 명령줄의 `nohz=` 적용 (보조 CPU 시작 전)
+pub fn init_from_cmdline() {
+    if let Some(on) = crate::boot::cmdline::nohz() {
+        set_enabled(on);
+    }
+    sysctl::register_all(&SYSCTL);
+}
+
+/// 틱 없는 idle 켜기/끄기 (이미 잠든 CPU는 깨어난 뒤부터 적용)
+pub fn set_enabled(on: bool) {
+    ENABLED.store(on, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// idle 루프 한 번: 다음 인터럽트까지 `wfi`로 대기 (할 일이 없으면 그동안 틱을 끔)
+pub fn idle() {
+    crate::sync::disable_interrupts();
+    let stopped = stop_tick();
+    wait_for_interrupt();
+    if let Some(next_tick) = stopped {
+        restart_tick(next_tick);
+    }
+    crate::sync::enable_interrupts();
+}
+
+/// 다음 인터럽트까지 대기 (aarch64는 다음 타이머까지 충분히 길면 PSCI idle 상태로)
+fn wait_for_interrupt() {
+    #[cfg(target_arch = "aarch64")]
+    {
+        let next_timer = percpu::current().next_tick_us.load(Ordering::Relaxed);
+        if crate::drivers::psci::suspend_idle(next_timer.saturating_sub(now_us())) {
+            return;
+        }
+    }
+    unsafe {
+        core::arch::asm!("wfi");
+    }
+}
+
+/// 틱을 끌 수 있으면 다음 할 일까지 일회성 타이머를 걸고, 끄기 전의 다음 틱 시각 반환
+fn stop_tick() -> Option<u64> {
+    let cpu = percpu::get_cpu_id();
+    if cpu == 0 || !is_enabled() || crate::proc::scheduler::ready_count() > 0 {
+        return None;
+    }
+
+    let now = now_us();
+    let pc = percpu::get(cpu);
+    let next_tick = pc.next_tick_us.load(Ordering::Relaxed);
+    let deadline = crate::proc::park::next_deadline(cpu)
+        .unwrap_or(u64::MAX)
+        .min(now + MAX_SLEEP_US);
+    // 틱을 하나도 건너뛰지 못하면 그대로
+    if deadline <= next_tick.saturating_add(TICK_US) {
+        return None;
+    }
+
+    crate::arch::timer::set_oneshot(deadline - now);
+    pc.next_tick_us.store(deadline, Ordering::Relaxed);
+    Some(next_tick)
+}
+
+/// 깨어난 뒤: 건너뛴 틱을 세고, 일회성 타이머 전에 깼으면 주기 틱을 다시 설정
+fn restart_tick(next_tick: u64) {
+    let now = now_us();
+    // 주기 틱이었다면 지금까지 울렸을 틱 수
+    let mut missed = if now >= next_tick { (now - next_tick) / TICK_US + 1 } else { 0 };
+    if crate::arch::timer::is_pending() {
+        // 틱 핸들러가 하나를 세고 주기 틱을 다시 설정
+        missed = missed.saturating_sub(1);
+    } else {
+        crate::arch::timer::set_next_tick();
+    }
+
+    let pc = percpu::current();
+    pc.tick_count.fetch_add(missed, Ordering::Relaxed);
+    pc.ticks_skipped.fetch_add(missed, Ordering::Relaxed);
+    crate::arch::timer::add_ticks(missed);
+}
@@ -0,0 +1,82 @@
+//! 실시간 시계(RTC) 읽기
+//!
+//! DTB에서 찾은 RTC로 부팅 시 벽시계를 맞춥니다 (`clock::init`).
+//!
+//! - aarch64 QEMU virt: PL031 (`arm,pl031`, 0x09010000), DR 레지스터 = Unix 초
+//! - riscv64 QEMU virt: Goldfish RTC (`google,goldfish-rtc`, 0x101000),
+//!   TIME_LOW/TIME_HIGH = Unix 나노초 (LOW를 먼저 읽으면 HIGH가 고정됨)
+
+use core::ptr::read_volatile;
+
+use super::clock::NSEC_PER_SEC;
+
+/// 지원하는 RTC 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtcKind {
+    Pl031,
+    Goldfish,
+}
+
+/// 찾은 RTC
+#[derive(Debug, Clone, Copy)]
+pub struct Rtc {
+    kind: RtcKind,
+    base: usize,
+}
+
+// PL031 레지스터
+const PL031_DR: usize = 0x00;
+
+// Goldfish RTC 레지스터
+const GOLDFISH_TIME_LOW: usize = 0x00;
+const GOLDFISH_TIME_HIGH: usize = 0x04;
+
+impl Rtc {
+    pub fn name(&self) -> &'static str {
+        match self.kind {
+            RtcKind::Pl031 => "pl031",
+            RtcKind::Goldfish => "goldfish-rtc",
+        }
+    }
+
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    fn read32(&self, offset: usize) -> u32 {
+        // Safety: probe()에서 DTB로 찾고 매핑을 확인한 MMIO 레지스터
+        unsafe { read_volatile((self.base + offset) as *const u32) }
+    }
+
+    /// 현재 Unix 시각 (나노초)
+    pub fn read_ns(&self) -> u64 {
+        match self.kind {
+            RtcKind::Pl031 => self.read32(PL031_DR) as u64 * NSEC_PER_SEC,
+            RtcKind::Goldfish => {
+                let low = self.read32(GOLDFISH_TIME_LOW) as u64;
+                let high = self.read32(GOLDFISH_TIME_HIGH) as u64;
+                (high << 32) | low
+            }
+        }
+    }
+}
+
+/// DTB에서 RTC 찾기
+pub fn probe() -> Option<Rtc> {
+    let dt = crate::dtb::get()?;
+    let candidates = [("arm,pl031", RtcKind::Pl031), ("google,goldfish-rtc", RtcKind::Goldfish)];
+    for (compatible, kind) in candidates {
+        let Some(dev) = dt.find_compatible(compatible).into_iter().next() else {
+            continue;
+        };
+        let base = dev.reg_base as usize;
+        // aarch64는 MMU가 켜져 있으므로 매핑되지 않은 주소는 읽지 않음
+        #[cfg(target_arch = "aarch64")]
+        if crate::arch::mmu::translate(base).is_none() {
+            crate::kprintln!("[time] {} @ {:#x} is not mapped, skipping", dev.name, base);
+            continue;
+        }
+        return Some(Rtc { kind, base });
+    }
+    None
+}
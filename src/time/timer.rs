@@ -0,0 +1,261 @@
+//! 소프트웨어 타이머 (콜백)
+//!
+//! 커널 코드와 모듈이 밀리초 단위 지연 뒤 한 번, 또는 일정 주기마다 호출될 콜백을
+//! 등록합니다. 만료 확인은 아키텍처 틱 인터럽트(`run_expired()`)에서 하므로 콜백은
+//! 만료 시각 이후 첫 틱에 불립니다 (최대 한 틱, 10ms 늦음).
+//!
+//! 콜백은 타이머 인터럽트 안에서 인터럽트가 꺼진 채 실행됩니다. 잠들거나 `Mutex`를
+//! 잡거나 오래 걸리면 안 되며, 긴 작업은 원자 변수 등으로 스레드에 넘깁니다.
+//! 타이머 표는 `IrqSpinlock`으로 보호하므로 만료 처리 중인 CPU에서 `create`/`cancel`을
+//! 불러도 교착되지 않습니다.
+//!
+//! 인터럽트 경로에서 힙을 쓰지 않도록 이름과 소유자는 고정 크기로 복사해 둡니다.
+
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use super::now_us;
+use crate::proc::percpu::{self, MAX_CPUS};
+use crate::sync::IrqSpinlock;
+
+/// 타이머 ID (0은 쓰지 않음)
+pub type TimerId = usize;
+
+/// 틱 하나에서 실행할 최대 콜백 수 (나머지는 다음 틱)
+const MAX_PER_TICK: usize = 16;
+
+/// 이름/소유자 최대 길이
+const LABEL_LEN: usize = 24;
+
+/// 타이머 콜백
+#[derive(Debug, Clone, Copy)]
+pub enum Callback {
+    /// 커널 함수
+    Kernel(fn(usize)),
+    /// 모듈 함수 (`kernel_timer_create`)
+    Module(extern "C" fn(usize)),
+}
+
+impl Callback {
+    fn call(self, arg: usize) {
+        match self {
+            Callback::Kernel(f) => f(arg),
+            Callback::Module(f) => f(arg),
+        }
+    }
+
+    /// 콜백 주소 (소유 모듈 판별용)
+    pub fn addr(self) -> usize {
+        match self {
+            Callback::Kernel(f) => f as usize,
+            Callback::Module(f) => f as usize,
+        }
+    }
+}
+
+/// 힙을 쓰지 않는 짧은 문자열 (넘치면 잘림)
+#[derive(Clone, Copy)]
+struct Label {
+    buf: [u8; LABEL_LEN],
+    len: usize,
+}
+
+impl Label {
+    fn new(s: &str) -> Self {
+        let mut len = s.len().min(LABEL_LEN);
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        let mut buf = [0; LABEL_LEN];
+        buf[..len].copy_from_slice(&s.as_bytes()[..len]);
+        Self { buf, len }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or("?")
+    }
+}
+
+struct Timer {
+    id: TimerId,
+    name: Label,
+    /// 소유 모듈 (None = 커널)
+    module: Option<Label>,
+    expires_us: u64,
+    period_us: Option<u64>,
+    callback: Callback,
+    arg: usize,
+}
+
+static TIMERS: IrqSpinlock<Vec<Timer>> = IrqSpinlock::new(Vec::new());
+
+/// 다음 ID
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// 가장 이른 만료 시각 (틱마다 락 없이 비교, `u64::MAX` = 없음)
+static NEXT_EXPIRY: AtomicU64 = AtomicU64::new(u64::MAX);
+
+/// CPU별 실행 중인 콜백의 타이머 ID (0 = 없음, 타이머 표 락 안에서 설정)
+static RUNNING: [AtomicUsize; MAX_CPUS] = [const { AtomicUsize::new(0) }; MAX_CPUS];
+
+/// 누적 콜백 실행 수
+static FIRED: AtomicU64 = AtomicU64::new(0);
+
+fn update_next_expiry(timers: &[Timer]) {
+    let next = timers.iter().map(|t| t.expires_us).min().unwrap_or(u64::MAX);
+    NEXT_EXPIRY.store(next, Ordering::Release);
+}
+
+/// 타이머 등록 (인터럽트 밖에서 호출)
+///
+/// `delay_ms` 뒤 `callback(arg)`을 부르고, `period_ms`가 있으면 이후 그 주기마다 다시
+/// 부릅니다. 콜백이 모듈 코드면 소유자가 그 모듈로 표시됩니다 (`timers` 명령).
+pub fn create(name: &str, delay_ms: u64, period_ms: Option<u64>, callback: Callback, arg: usize) -> TimerId {
+    let id = create_disarmed(name, period_ms, callback, arg);
+    arm(id, delay_ms);
+    id
+}
+
+/// 만료되지 않는 타이머 등록 (`arm()`으로 시작)
+///
+/// 콜백이 실행되기 전에 ID를 기록해야 하는 호출자(모듈 자원 장부)가 씁니다.
+pub fn create_disarmed(name: &str, period_ms: Option<u64>, callback: Callback, arg: usize) -> TimerId {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let module = crate::module::ledger::owner_of(callback.addr()).map(|m| Label::new(&m));
+    let timer = Timer {
+        id,
+        name: Label::new(name),
+        module,
+        expires_us: u64::MAX,
+        // 주기 0은 매 틱으로 취급
+        period_us: period_ms.map(|p| p.max(1) * 1000),
+        callback,
+        arg,
+    };
+
+    TIMERS.lock().push(timer);
+    id
+}
+
+/// `delay_ms` 뒤 만료되도록 시작
+///
+/// # Returns
+/// 타이머가 있으면 true
+pub fn arm(id: TimerId, delay_ms: u64) -> bool {
+    let mut timers = TIMERS.lock();
+    let Some(timer) = timers.iter_mut().find(|t| t.id == id) else {
+        return false;
+    };
+    timer.expires_us = now_us().saturating_add(delay_ms.saturating_mul(1000));
+    update_next_expiry(&timers);
+    true
+}
+
+/// 일회성 타이머 등록
+pub fn oneshot(name: &str, delay_ms: u64, callback: fn(usize), arg: usize) -> TimerId {
+    create(name, delay_ms, None, Callback::Kernel(callback), arg)
+}
+
+/// 주기 타이머 등록 (첫 호출도 `period_ms` 뒤)
+pub fn periodic(name: &str, period_ms: u64, callback: fn(usize), arg: usize) -> TimerId {
+    create(name, period_ms, Some(period_ms), Callback::Kernel(callback), arg)
+}
+
+/// 타이머 취소
+///
+/// 돌아온 뒤에는 콜백이 더 이상 불리지 않고 다른 CPU에서 실행 중이지도 않습니다.
+/// 콜백 안에서 자기 타이머를 취소할 수도 있습니다.
+///
+/// # Returns
+/// 대기 중이던 타이머를 취소했으면 true (이미 만료된 일회성 타이머, 없는 ID는 false)
+pub fn cancel(id: TimerId) -> bool {
+    let found = {
+        let mut timers = TIMERS.lock();
+        let pos = timers.iter().position(|t| t.id == id);
+        if let Some(pos) = pos {
+            timers.swap_remove(pos);
+            update_next_expiry(&timers);
+        }
+        pos.is_some()
+    };
+
+    // 다른 CPU에서 실행 중인 콜백이 끝날 때까지 대기 (자기 CPU면 콜백 안에서 취소한 것)
+    let me = percpu::get_cpu_id() as usize % MAX_CPUS;
+    for (cpu, running) in RUNNING.iter().enumerate() {
+        if cpu == me {
+            continue;
+        }
+        while running.load(Ordering::Acquire) == id {
+            core::hint::spin_loop();
+        }
+    }
+    found
+}
+
+/// 만료된 타이머 콜백 실행 (아키텍처 틱 인터럽트에서 호출)
+///
+/// 인터럽트 경로이므로 힙을 쓰지 않습니다. 여러 CPU가 동시에 불러도 콜백은 한 번만
+/// 실행됩니다. 모듈의 일회성 타이머는 콜백이 끝나면 모듈 자원 장부에서 뺍니다.
+pub fn run_expired() {
+    let now = now_us();
+    if now < NEXT_EXPIRY.load(Ordering::Acquire) {
+        return;
+    }
+    let running = &RUNNING[percpu::get_cpu_id() as usize % MAX_CPUS];
+
+    for _ in 0..MAX_PER_TICK {
+        let (callback, arg, oneshot) = {
+            let mut timers = TIMERS.lock();
+            let Some(pos) = timers.iter().position(|t| t.expires_us <= now) else {
+                break;
+            };
+            let timer = &mut timers[pos];
+            let fire = (timer.callback, timer.arg, timer.period_us.is_none().then_some(timer.id));
+            // 락 안에서 표시해야 cancel()이 콜백 종료를 기다릴 수 있음
+            running.store(timer.id, Ordering::Release);
+            match timer.period_us {
+                Some(period) => {
+                    // 밀린 주기는 몰아서 부르지 않음
+                    timer.expires_us = timer.expires_us.saturating_add(period).max(now + 1);
+                }
+                None => {
+                    timers.swap_remove(pos);
+                }
+            }
+            update_next_expiry(&timers);
+            fire
+        };
+
+        callback.call(arg);
+        running.store(0, Ordering::Release);
+        FIRED.fetch_add(1, Ordering::Relaxed);
+        // 모듈 코드를 벗어났으므로 장부에서 제거 (워크큐 작업과 같음)
+        if let (Callback::Module(_), Some(id)) = (callback, oneshot) {
+            crate::module::ledger::release_from_irq(crate::module::ledger::ResourceKind::Timer, id);
+        }
+    }
+}
+
+/// 누적 콜백 실행 수
+pub fn fired() -> u64 {
+    FIRED.load(Ordering::Relaxed)
+}
+
+/// `timers` 명령용 목록
+pub(super) fn list(out: &mut Vec<super::TimerInfo>) {
+    let timers = TIMERS.lock();
+    out.reserve(timers.len());
+    // 아직 시작하지 않은 타이머는 빼고
+    for t in timers.iter().filter(|t| t.expires_us != u64::MAX) {
+        out.push(super::TimerInfo {
+            name: alloc::string::String::from(t.name.as_str()),
+            owner: match &t.module {
+                Some(m) => alloc::format!("module {}", m.as_str()),
+                None => alloc::string::String::from("kernel"),
+            },
+            cpu: None,
+            expires_us: t.expires_us,
+            period_us: t.period_us,
+        });
+    }
+}
@@ -0,0 +1,67 @@
+//! 프레임 포인터 체인으로 호출 위치 수집
+//!
+//! 디버그 기능(`kmemleak`, `lockdep`, `heap_redzone`)이 할당/락 획득 시점의 호출 위치를
+//! 남기고, 패닉 핸들러가 호출 스택을 출력할 때 씁니다. 힙과 락을 쓰지 않으므로 할당자나
+//! 락 구현 안에서도 부를 수 있습니다. `-C force-frame-pointers=yes`로 빌드해야 정확합니다
+//! (`.cargo/config.toml`에 기본으로 켜 둠).
+
+/// 프레임 사이 최대 간격 (이보다 멀면 체인이 깨진 것으로 판단)
+const MAX_FRAME_GAP: usize = 64 * 1024;
+
+/// 현재 프레임에서 시작해 복귀 주소를 최대 `N`개 수집 (남는 칸은 0)
+#[cfg(any(feature = "kmemleak", feature = "lockdep", feature = "heap_redzone"))]
+#[inline(always)]
+pub fn capture<const N: usize>() -> [usize; N] {
+    let mut callers = [0usize; N];
+    let mut slots = callers.iter_mut();
+    walk(current_fp(), N, |ret| {
+        if let Some(slot) = slots.next() {
+            *slot = ret;
+        }
+    });
+    callers
+}
+
+/// 현재 함수의 프레임 포인터
+#[inline(always)]
+pub fn current_fp() -> usize {
+    let fp: usize;
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        core::arch::asm!("mov {}, x29", out(reg) fp, options(nomem, nostack));
+    }
+    #[cfg(target_arch = "riscv64")]
+    unsafe {
+        core::arch::asm!("mv {}, s0", out(reg) fp, options(nomem, nostack));
+    }
+    fp
+}
+
+/// 프레임 포인터 `fp`에서 시작해 복귀 주소를 바깥쪽 호출자 순서로 최대 `max`개 `f`에 전달
+///
+/// 예외 프레임의 fp(aarch64 x29, riscv64 s0)를 넘기면 예외가 난 함수의 호출자부터 나옵니다.
+pub fn walk(mut fp: usize, max: usize, mut f: impl FnMut(usize)) {
+    for _ in 0..max {
+        if fp == 0 || fp % 8 != 0 {
+            break;
+        }
+
+        // aarch64: [fp] = 이전 fp, [fp + 8] = lr
+        // riscv64: [fp - 16] = 이전 fp, [fp - 8] = ra
+        #[cfg(target_arch = "aarch64")]
+        let (next, ret) = unsafe { (*(fp as *const usize), *((fp + 8) as *const usize)) };
+        #[cfg(target_arch = "riscv64")]
+        let (next, ret) = unsafe { (*((fp - 16) as *const usize), *((fp - 8) as *const usize)) };
+
+        if ret == 0 {
+            break;
+        }
+        f(ret);
+
+        // 스택은 아래로 자라므로 이전 프레임은 더 높은 주소에 있어야 함
+        if next <= fp || next - fp > MAX_FRAME_GAP {
+            break;
+        }
+        fp = next;
+    }
+}
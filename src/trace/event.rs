@@ -0,0 +1,234 @@
+//! 정적 tracepoint와 CPU별 이벤트 링 버퍼
+//!
+//! 커널 곳곳에 `trace_event!`로 심어 둔 tracepoint는 추적이 켜져 있을 때만 현재
+//! CPU의 링 버퍼에 이벤트를 남깁니다. 꺼져 있으면 원자 변수 하나만 읽고 지나갑니다.
+//!
+//! 기록은 인터럽트/예외 컨텍스트에서도 일어나므로 힙을 쓰지 않습니다. 버퍼는
+//! `start()`에서 미리 할당하고, 가득 차면 가장 오래된 이벤트를 덮어씁니다.
+//! `snapshot()`은 모든 CPU의 버퍼를 모아 타임스탬프 순으로 정렬합니다. 타임스탬프는
+//! CPU 간에 공유되는 카운터(aarch64 CNTPCT, riscv64 mtime)에서 읽으므로 CPU가 달라도
+//! 비교할 수 있습니다.
+//!
+//! ```ignore
+//! crate::trace_event!(sched_switch, prev = prev_tid, next = next_tid);
+//! ```
+
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::proc::percpu::{self, MAX_CPUS};
+use crate::sync::IrqSpinlock;
+
+/// 이벤트 하나에 기록할 수 있는 필드 수
+pub const MAX_FIELDS: usize = 4;
+/// CPU별 기본 버퍼 크기 (이벤트 수)
+pub const DEFAULT_EVENTS: usize = 4096;
+/// CPU별 최대 버퍼 크기 (이벤트 수)
+pub const MAX_EVENTS: usize = 65536;
+
+/// bootargs 옵션 (`trace` 또는 `trace=<events>`)
+const BOOTARG: &str = "trace";
+
+/// 기록된 이벤트
+#[derive(Debug, Clone, Copy)]
+pub struct Event {
+    /// 부팅 후 마이크로초
+    pub ts_us: u64,
+    /// 기록한 CPU
+    pub cpu: u32,
+    /// tracepoint 이름
+    pub name: &'static str,
+    /// 필드 이름 (`args`와 같은 순서)
+    pub fields: &'static [&'static str],
+    pub args: [u64; MAX_FIELDS],
+}
+
+/// CPU 하나의 링 버퍼
+///
+/// `buf`의 용량은 `start()`에서 정해지고 기록 중에는 늘어나지 않습니다.
+struct Ring {
+    buf: Vec<Event>,
+    /// 가장 오래된 이벤트 위치 (버퍼가 찬 뒤에만 의미 있음)
+    head: usize,
+    /// 덮어써서 잃은 이벤트 수
+    overwritten: u64,
+}
+
+impl Ring {
+    const fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            head: 0,
+            overwritten: 0,
+        }
+    }
+
+    fn push(&mut self, event: Event) {
+        let cap = self.buf.capacity();
+        if cap == 0 {
+            return;
+        }
+        if self.buf.len() < cap {
+            self.buf.push(event);
+        } else {
+            self.buf[self.head] = event;
+            self.head = (self.head + 1) % cap;
+            self.overwritten += 1;
+        }
+    }
+
+    /// 오래된 순서로 이벤트 복사
+    fn copy_into(&self, out: &mut Vec<Event>) {
+        out.extend_from_slice(&self.buf[self.head..]);
+        out.extend_from_slice(&self.buf[..self.head]);
+    }
+}
+
+static RINGS: [IrqSpinlock<Ring>; MAX_CPUS] = [const { IrqSpinlock::new(Ring::new()) }; MAX_CPUS];
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// 현재 CPU별 버퍼 크기 (0 = 할당 안 됨)
+static CAPACITY: AtomicUsize = AtomicUsize::new(0);
+
+/// 추적 상태
+#[derive(Debug, Clone, Copy)]
+pub struct TraceStatus {
+    pub enabled: bool,
+    /// CPU별 버퍼 크기 (이벤트 수)
+    pub capacity: usize,
+    /// 버퍼에 남아 있는 이벤트 수
+    pub recorded: usize,
+    /// 덮어써서 잃은 이벤트 수
+    pub overwritten: u64,
+}
+
+/// tracepoint가 켜져 있는지 (`trace_event!`가 인자를 평가하기 전에 확인)
+#[inline]
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// 현재 CPU의 버퍼에 이벤트 기록 (`trace_event!`에서 호출)
+///
+/// 인터럽트를 막은 채 CPU 자신의 버퍼만 잡으므로 인터럽트 핸들러에서 불려도
+/// 교착되지 않습니다. `args`가 `MAX_FIELDS`보다 길면 나머지는 버립니다.
+pub fn record(name: &'static str, fields: &'static [&'static str], args: &[u64]) {
+    let cpu = percpu::get_cpu_id();
+    let mut event = Event {
+        ts_us: crate::time::now_us(),
+        cpu,
+        name,
+        fields,
+        args: [0; MAX_FIELDS],
+    };
+    let n = args.len().min(MAX_FIELDS);
+    event.args[..n].copy_from_slice(&args[..n]);
+
+    RINGS[cpu as usize % MAX_CPUS].lock().push(event);
+}
+
+/// 추적 시작
+///
+/// `events`는 CPU별 버퍼 크기입니다. 크기가 바뀌면 버퍼를 새로 할당하므로 이전
+/// 이벤트는 사라집니다. 같은 크기면 기존 이벤트를 이어서 기록합니다.
+pub fn start(events: usize) -> Result<(), &'static str> {
+    if events == 0 || events > MAX_EVENTS {
+        return Err("buffer size out of range");
+    }
+    if CAPACITY.load(Ordering::Relaxed) != events {
+        ENABLED.store(false, Ordering::Relaxed);
+        for ring in RINGS.iter() {
+            let buf = Vec::with_capacity(events);
+            // 이전 버퍼는 락을 놓은 뒤 해제
+            let old = core::mem::replace(&mut *ring.lock(), Ring { buf, head: 0, overwritten: 0 });
+            drop(old);
+        }
+        CAPACITY.store(events, Ordering::Relaxed);
+    }
+    ENABLED.store(true, Ordering::Release);
+    Ok(())
+}
+
+/// 추적 중지 (버퍼는 남겨 두어 `dump`로 볼 수 있음)
+pub fn stop() {
+    ENABLED.store(false, Ordering::Release);
+}
+
+/// 모든 버퍼 비우기 (크기는 유지)
+pub fn clear() {
+    for ring in RINGS.iter() {
+        let mut ring = ring.lock();
+        ring.buf.clear();
+        ring.head = 0;
+        ring.overwritten = 0;
+    }
+}
+
+pub fn status() -> TraceStatus {
+    let mut recorded = 0;
+    let mut overwritten = 0;
+    for ring in RINGS.iter() {
+        let ring = ring.lock();
+        recorded += ring.buf.len();
+        overwritten += ring.overwritten;
+    }
+    TraceStatus {
+        enabled: enabled(),
+        capacity: CAPACITY.load(Ordering::Relaxed),
+        recorded,
+        overwritten,
+    }
+}
+
+/// 모든 CPU의 이벤트를 타임스탬프 순으로 모음
+///
+/// 같은 시각의 이벤트는 CPU 번호, 기록 순서를 유지합니다. 추적이 켜져 있어도
+/// 복사하는 동안만 각 CPU의 버퍼를 잡으므로 기록이 오래 막히지 않습니다.
+pub fn snapshot() -> Vec<Event> {
+    let total = status().recorded;
+    let mut events = Vec::with_capacity(total);
+    for ring in RINGS.iter() {
+        let mut chunk = Vec::with_capacity(CAPACITY.load(Ordering::Relaxed));
+        // 락을 잡은 채 할당하지 않도록 미리 잡아 둔 용량 안에서 복사
+        ring.lock().copy_into(&mut chunk);
+        events.append(&mut chunk);
+    }
+    events.sort_by_key(|e| e.ts_us);
+    events
+}
+
+/// bootargs의 `trace[=<events>]` 처리 (부팅 시 호출)
+pub fn init_from_bootargs() {
+    if !crate::boot::cmdline::has(BOOTARG) {
+        return;
+    }
+    let events = crate::boot::cmdline::get_usize(BOOTARG).unwrap_or(DEFAULT_EVENTS);
+    match start(events) {
+        Ok(()) => crate::kprintln!("[trace] Tracing enabled at boot ({} events/CPU)", events),
+        Err(e) => crate::kprintln!("[trace] Invalid {}={}: {}", BOOTARG, events, e),
+    }
+}
+
+/// 정적 tracepoint
+///
+/// 추적이 꺼져 있으면 값 식을 평가하지 않습니다. 필드는 `MAX_FIELDS`개까지이며
+/// 값은 `u64`로 변환(`as`)할 수 있어야 합니다.
+///
+/// ```ignore
+/// crate::trace_event!(irq_entry, irq = irq_num);
+/// ```
+#[macro_export]
+macro_rules! trace_event {
+    ($name:ident $(, $field:ident = $value:expr)* $(,)?) => {
+        if $crate::trace::event::enabled() {
+            const _: () = assert!(
+                <[&str]>::len(&[$(stringify!($field)),*]) <= $crate::trace::event::MAX_FIELDS,
+                "too many trace_event! fields"
+            );
+            $crate::trace::event::record(
+                stringify!($name),
+                &[$(stringify!($field)),*],
+                &[$($value as u64),*],
+            );
+        }
+    };
+}
@@ -0,0 +1,321 @@
+//! kprobe: 동적 브레이크포인트 훅
+//!
+//! 커널 심볼(`module::symbol`, 로드된 모듈 포함) 또는 주소의 명령어를 아키텍처
+//! 브레이크포인트로 바꿔, 그 명령어가 실행될 때 레지스터 컨텍스트와 함께 핸들러를
+//! 호출합니다. 핸들러가 끝나면 원래 명령어를 되돌려 한 번만 실행(단일 스텝)하고
+//! 브레이크포인트를 다시 심습니다.
+//!
+//! | 아키텍처 | 브레이크포인트 | 단일 스텝 |
+//! |----------|----------------|-----------|
+//! | aarch64 | `brk #0x4` | MDSCR_EL1.SS 하드웨어 스텝 |
+//! | riscv64 | `ebreak` / `c.ebreak` | 다음 명령어에 임시 `ebreak` (분기 명령어는 거부) |
+//!
+//! # 제약
+//!
+//! - 스텝 동안 원래 명령어가 복원되어 있으므로 그 사이 다른 CPU의 실행은 놓칠 수
+//!   있습니다 (`hits`에 잡히지 않음).
+//! - 핸들러는 예외 컨텍스트에서 실행됩니다. 힙 할당, 잠들 수 있는 락, probe한
+//!   함수가 이미 잡고 있을 수 있는 락(`log` 출력은 콘솔 락)을 피해야 합니다.
+//! - 핸들러 안에서 다시 probe에 걸리면 핸들러는 건너뛰고 `missed`로 셉니다.
+//! - kprobe 자신과 예외 진입 경로는 probe하면 안 됩니다.
+//!
+//! # 사용법
+//!
+//! ```rust,ignore
+//! fn on_hit(id: usize, regs: &mut kprobe::Regs) {
+//!     // regs로 인자 확인/수정
+//! }
+//! let id = kprobe::register("kernel_vfs_read", Some(on_hit), false)?;
+//! kprobe::disable(id)?;
+//! kprobe::unregister(id)?;
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+
+use crate::arch::kprobe as arch;
+use crate::kprintln;
+use crate::proc::percpu::{self, MAX_CPUS};
+use crate::sync::Mutex;
+
+/// 동시에 심을 수 있는 최대 probe 수
+pub const MAX_KPROBES: usize = 16;
+
+/// 기록해 두는 인자 수
+pub const RECORDED_ARGS: usize = 4;
+
+/// 적중 시 레지스터 컨텍스트 (아키텍처 예외 프레임)
+pub type Regs = arch::Regs;
+
+/// probe 핸들러 (probe ID, 적중 시점의 레지스터)
+///
+/// 레지스터를 수정하면 복귀 시 반영됩니다. PC를 바꾸면 원래 명령어를 실행하지
+/// 않고 그 주소로 복귀합니다.
+pub type KprobeHandler = fn(usize, &mut Regs);
+
+/// kprobe 에러
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KprobeError {
+    /// 심볼/주소를 해석할 수 없음
+    SymbolNotFound,
+    /// 같은 주소에 이미 probe가 있음
+    AlreadyProbed,
+    /// 빈 슬롯 없음
+    NoSlot,
+    /// 이 명령어에는 probe를 심을 수 없음
+    Unprobeable(&'static str),
+    /// 없는 probe ID
+    InvalidId,
+}
+
+/// probe 슬롯 (예외 경로에서 락 없이 읽으므로 전부 원자 변수)
+struct Slot {
+    /// probe 주소 (0 = 빈 슬롯)
+    addr: AtomicUsize,
+    /// 브레이크포인트로 덮어쓴 원래 명령어
+    orig: AtomicU32,
+    /// 브레이크포인트가 심어져 있음
+    enabled: AtomicBool,
+    /// 적중할 때마다 콘솔에 출력
+    log: AtomicBool,
+    /// 핸들러 (`KprobeHandler`, 0 = 없음)
+    handler: AtomicUsize,
+    /// 적중 횟수
+    hits: AtomicU64,
+    /// 핸들러 안에서 다시 적중해 핸들러를 건너뛴 횟수
+    missed: AtomicU64,
+    /// 마지막 적중 때의 인자 레지스터
+    last_args: [AtomicU64; RECORDED_ARGS],
+}
+
+impl Slot {
+    const fn new() -> Self {
+        Self {
+            addr: AtomicUsize::new(0),
+            orig: AtomicU32::new(0),
+            enabled: AtomicBool::new(false),
+            log: AtomicBool::new(false),
+            handler: AtomicUsize::new(0),
+            hits: AtomicU64::new(0),
+            missed: AtomicU64::new(0),
+            last_args: [const { AtomicU64::new(0) }; RECORDED_ARGS],
+        }
+    }
+}
+
+static SLOTS: [Slot; MAX_KPROBES] = [const { Slot::new() }; MAX_KPROBES];
+
+/// 슬롯별 probe 이름 (조회용, 등록/해제를 직렬화하는 락이기도 함)
+static NAMES: Mutex<[Option<String>; MAX_KPROBES]> = Mutex::new([const { None }; MAX_KPROBES]);
+
+/// CPU별 핸들러 실행 중 표시 (재진입 방지)
+static IN_HANDLER: [AtomicBool; MAX_CPUS] = [const { AtomicBool::new(false) }; MAX_CPUS];
+
+/// probe 정보 (조회용)
+#[derive(Debug, Clone)]
+pub struct KprobeInfo {
+    pub id: usize,
+    pub name: String,
+    pub addr: usize,
+    pub enabled: bool,
+    pub log: bool,
+    pub hits: u64,
+    pub missed: u64,
+    pub last_args: [u64; RECORDED_ARGS],
+}
+
+/// `symbol`, `symbol+off`, `0xaddr` 형식의 대상을 주소로 해석
+pub fn resolve(target: &str) -> Option<usize> {
+    let (base, offset) = match target.split_once('+') {
+        Some((base, off)) => (base, parse_number(off)?),
+        None => (target, 0),
+    };
+    let addr = match base.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok()?,
+        None => crate::module::ModuleLoader::lookup_symbol_global(base)?,
+    };
+    Some(addr + offset)
+}
+
+/// 10진수 또는 `0x` 16진수
+fn parse_number(s: &str) -> Option<usize> {
+    match s.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+/// probe 등록 후 바로 활성화
+///
+/// # Returns
+/// probe ID (`enable`/`disable`/`unregister`에 사용)
+pub fn register(target: &str, handler: Option<KprobeHandler>, log: bool) -> Result<usize, KprobeError> {
+    let addr = resolve(target).ok_or(KprobeError::SymbolNotFound)?;
+    arch::check_probe(addr).map_err(KprobeError::Unprobeable)?;
+
+    let mut names = NAMES.lock();
+    if SLOTS.iter().any(|s| s.addr.load(Ordering::Acquire) == addr) {
+        return Err(KprobeError::AlreadyProbed);
+    }
+    let id = names.iter().position(|n| n.is_none()).ok_or(KprobeError::NoSlot)?;
+
+    let slot = &SLOTS[id];
+    slot.orig.store(arch::read_insn(addr), Ordering::Relaxed);
+    slot.log.store(log, Ordering::Relaxed);
+    slot.handler.store(handler.map_or(0, |h| h as usize), Ordering::Relaxed);
+    slot.hits.store(0, Ordering::Relaxed);
+    slot.missed.store(0, Ordering::Relaxed);
+    for arg in &slot.last_args {
+        arg.store(0, Ordering::Relaxed);
+    }
+    slot.addr.store(addr, Ordering::Release);
+    names[id] = Some(String::from(target));
+
+    slot.enabled.store(true, Ordering::Release);
+    arch::arm(addr);
+
+    kprintln!("[kprobe] #{} {} @ {:#x}", id, target, addr);
+    Ok(id)
+}
+
+/// probe 해제 (원래 명령어 복원)
+pub fn unregister(id: usize) -> Result<(), KprobeError> {
+    let mut names = NAMES.lock();
+    if id >= MAX_KPROBES || names[id].is_none() {
+        return Err(KprobeError::InvalidId);
+    }
+    let slot = &SLOTS[id];
+    disarm(slot);
+    slot.addr.store(0, Ordering::Release);
+    slot.handler.store(0, Ordering::Relaxed);
+    names[id] = None;
+    Ok(())
+}
+
+/// 브레이크포인트 다시 심기
+pub fn enable(id: usize) -> Result<(), KprobeError> {
+    let names = NAMES.lock();
+    if id >= MAX_KPROBES || names[id].is_none() {
+        return Err(KprobeError::InvalidId);
+    }
+    let slot = &SLOTS[id];
+    if !slot.enabled.swap(true, Ordering::AcqRel) {
+        arch::arm(slot.addr.load(Ordering::Acquire));
+    }
+    Ok(())
+}
+
+/// 브레이크포인트 제거 (등록과 통계는 유지)
+pub fn disable(id: usize) -> Result<(), KprobeError> {
+    let names = NAMES.lock();
+    if id >= MAX_KPROBES || names[id].is_none() {
+        return Err(KprobeError::InvalidId);
+    }
+    disarm(&SLOTS[id]);
+    Ok(())
+}
+
+fn disarm(slot: &Slot) {
+    if slot.enabled.swap(false, Ordering::AcqRel) {
+        arch::disarm(slot.addr.load(Ordering::Acquire), slot.orig.load(Ordering::Relaxed));
+    }
+}
+
+/// 등록된 probe 목록
+pub fn list() -> Vec<KprobeInfo> {
+    let names = NAMES.lock();
+    names
+        .iter()
+        .enumerate()
+        .filter_map(|(id, name)| {
+            let name = name.as_ref()?;
+            let slot = &SLOTS[id];
+            Some(KprobeInfo {
+                id,
+                name: name.clone(),
+                addr: slot.addr.load(Ordering::Acquire),
+                enabled: slot.enabled.load(Ordering::Acquire),
+                log: slot.log.load(Ordering::Relaxed),
+                hits: slot.hits.load(Ordering::Relaxed),
+                missed: slot.missed.load(Ordering::Relaxed),
+                last_args: core::array::from_fn(|i| slot.last_args[i].load(Ordering::Relaxed)),
+            })
+        })
+        .collect()
+}
+
+/// probe 적중 횟수
+pub fn hits(id: usize) -> u64 {
+    SLOTS.get(id).map_or(0, |slot| slot.hits.load(Ordering::Relaxed))
+}
+
+/// 브레이크포인트 예외 처리 (아키텍처 예외 핸들러에서 호출)
+///
+/// kprobe의 브레이크포인트면 핸들러를 부르고 원래 명령어의 단일 스텝을 준비한 뒤
+/// true를 반환합니다. 모르는 브레이크포인트면 false (호출자가 기존 처리를 계속).
+pub fn on_breakpoint(regs: &mut Regs) -> bool {
+    let addr = arch::pc(regs);
+    let Some(id) = SLOTS.iter().position(|s| s.addr.load(Ordering::Acquire) == addr) else {
+        // 방금 해제된 probe거나 다른 CPU의 스텝용 임시 브레이크포인트면 다시 실행
+        return !arch::is_break(addr) || arch::is_step_break(addr);
+    };
+    if !arch::is_break(addr) {
+        // 다른 CPU가 스텝 중이거나 비활성화함: 원래 명령어를 그대로 실행
+        return true;
+    }
+
+    let slot = &SLOTS[id];
+    slot.hits.fetch_add(1, Ordering::Relaxed);
+    for (i, arg) in slot.last_args.iter().enumerate() {
+        arg.store(arch::arg(regs, i), Ordering::Relaxed);
+    }
+    crate::trace_event!(kprobe_hit, id = id, addr = addr, arg0 = arch::arg(regs, 0));
+
+    let cpu = percpu::get_cpu_id() as usize % MAX_CPUS;
+    if IN_HANDLER[cpu].swap(true, Ordering::Acquire) {
+        slot.missed.fetch_add(1, Ordering::Relaxed);
+    } else {
+        if slot.log.load(Ordering::Relaxed) {
+            kprintln!(
+                "[kprobe] #{} hit @ {:#x}: args {:#x} {:#x} {:#x} {:#x}, ret {:#x}",
+                id,
+                addr,
+                arch::arg(regs, 0),
+                arch::arg(regs, 1),
+                arch::arg(regs, 2),
+                arch::arg(regs, 3),
+                arch::ret_addr(regs)
+            );
+        }
+        let handler = slot.handler.load(Ordering::Acquire);
+        if handler != 0 {
+            // Safety: register()가 KprobeHandler만 저장
+            let handler: KprobeHandler = unsafe { core::mem::transmute(handler) };
+            handler(id, regs);
+        }
+        IN_HANDLER[cpu].store(false, Ordering::Release);
+    }
+
+    // 핸들러가 PC를 바꿨으면 원래 명령어를 건너뜀 (브레이크포인트는 그대로)
+    if arch::pc(regs) != addr {
+        return true;
+    }
+    arch::begin_step(id, addr, slot.orig.load(Ordering::Relaxed), regs);
+    true
+}
+
+/// 단일 스텝 완료 (아키텍처 스텝 처리에서 호출): 아직 활성이면 브레이크포인트 복원
+pub fn end_step(id: usize) {
+    let slot = &SLOTS[id];
+    let addr = slot.addr.load(Ordering::Acquire);
+    let orig = slot.orig.load(Ordering::Relaxed);
+    if addr == 0 || !slot.enabled.load(Ordering::Acquire) {
+        return;
+    }
+    arch::arm(addr);
+    // 그 사이 다른 CPU가 비활성화/해제했으면 되돌림
+    if !slot.enabled.load(Ordering::Acquire) || slot.addr.load(Ordering::Acquire) != addr {
+        arch::disarm(addr, orig);
+    }
+}
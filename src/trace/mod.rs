@@ -0,0 +1,10 @@
+//! 커널 동적 추적
+//!
+//! 다시 빌드하지 않고 실행 중인 커널을 관찰하기 위한 도구들
+//! - kprobe: 커널 명령어에 브레이크포인트를 심어 핸들러 호출
+//! - event: 정적 tracepoint(`trace_event!`)와 CPU별 이벤트 링 버퍼
+//! - backtrace: 프레임 포인터 체인 수집 (패닉 백트레이스, `kmemleak`, `lockdep` feature)
+
+pub mod backtrace;
+pub mod event;
+pub mod kprobe;
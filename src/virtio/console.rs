@@ -0,0 +1,315 @@
+//! VirtIO 콘솔 드라이버 (hvc0)
+//!
+//! virtio-console 디바이스의 첫 포트(receiveq 0, transmitq 1)만 사용합니다
+//! (MULTIPORT 기능은 협상하지 않음).
+//!
+//! - 수신: receiveq에 버퍼를 미리 넣어두고, 인터럽트 핸들러가 채워진 버퍼를 고정 크기
+//!   수신 링으로 옮긴 뒤 다시 큐에 넣음 (인터럽트 컨텍스트에서 heap 할당 없음)
+//! - 송신: 고정 송신 버퍼에 복사해 transmitq에 넣고 디바이스가 돌려줄 때까지 스핀
+//!   (콘솔 출력은 인터럽트 컨텍스트에서도 호출되므로 잠들지 않음)
+//!
+//! `/dev/hvc0` 문자 디바이스로 노출되며, 콘솔 레지스트리에 `hvc0`으로(꺼진 채) 등록합니다.
+//! bootargs에 `console=hvc0`이 있거나 `console enable hvc0`으로 켜면 커널 콘솔 출력과 셸
+//! 입력도 이 디바이스를 사용합니다.
+//!
+//! QEMU: `-device virtio-serial-device -chardev socket,id=hvc,... -device virtconsole,chardev=hvc`
+
+extern crate alloc;
+
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::sync::atomic::AtomicBool;
+
+use crate::sync::IrqSpinlock;
+use crate::virtio::mmio::VirtIOMMIO;
+use crate::virtio::queue::Virtqueue;
+use crate::virtio::{DeviceType, VirtIODeviceInfo, VirtIOError, VirtIOResult};
+
+/// 포트 0 수신 큐
+const RECEIVEQ: u32 = 0;
+/// 포트 0 송신 큐
+const TRANSMITQ: u32 = 1;
+
+/// receiveq에 미리 넣어둘 버퍼 수
+const RX_BUFFERS: usize = 16;
+/// 수신 버퍼 크기
+const RX_BUF_SIZE: usize = 64;
+/// 송신 버퍼 크기 (더 긴 출력은 나눠서 보냄)
+const TX_BUF_SIZE: usize = 256;
+/// 수신 링 크기 (읽지 않은 입력, 넘치면 버림)
+const RX_RING_SIZE: usize = 1024;
+/// 송신 완료 대기 스핀 한도
+const TX_SPIN_LIMIT: u32 = 1_000_000;
+
+/// receiveq와 수신 버퍼
+struct RxQueue {
+    queue: Virtqueue,
+    /// 디바이스가 쓰는 버퍼 (주소가 고정되도록 생성 후 크기 변경 금지)
+    bufs: Vec<[u8; RX_BUF_SIZE]>,
+    /// descriptor 번호 → 버퍼 번호
+    desc_slot: Vec<u16>,
+}
+
+impl RxQueue {
+    /// `slot` 버퍼를 receiveq에 넣음
+    fn post(&mut self, slot: usize) -> VirtIOResult<()> {
+        let buf = unsafe { core::slice::from_raw_parts(self.bufs[slot].as_ptr(), RX_BUF_SIZE) };
+        let desc = self.queue.add_buffer(buf, true)?;
+        self.desc_slot[desc as usize] = slot as u16;
+        Ok(())
+    }
+}
+
+/// transmitq와 송신 버퍼
+struct TxQueue {
+    queue: Virtqueue,
+    buf: Vec<u8>,
+}
+
+/// 아직 읽지 않은 입력 (고정 크기 원형 버퍼)
+struct RxRing {
+    data: [u8; RX_RING_SIZE],
+    head: usize,
+    len: usize,
+}
+
+impl RxRing {
+    const fn new() -> Self {
+        Self {
+            data: [0; RX_RING_SIZE],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, byte: u8) {
+        if self.len == RX_RING_SIZE {
+            return;
+        }
+        self.data[(self.head + self.len) % RX_RING_SIZE] = byte;
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<u8> {
+        if self.len == 0 {
+            return None;
+        }
+        let byte = self.data[self.head];
+        self.head = (self.head + 1) % RX_RING_SIZE;
+        self.len -= 1;
+        Some(byte)
+    }
+}
+
+/// VirtIO 콘솔 디바이스
+pub struct VirtIOConsole {
+    /// MMIO 핸들
+    mmio: VirtIOMMIO,
+    /// IRQ 번호
+    irq: u32,
+    rx: IrqSpinlock<RxQueue>,
+    tx: IrqSpinlock<TxQueue>,
+    input: IrqSpinlock<RxRing>,
+    /// 인터럽트 플래그 (VirtIO IRQ 디스패치 테이블용)
+    interrupt_flag: AtomicBool,
+}
+
+// Safety: 큐는 IrqSpinlock으로 보호됨
+unsafe impl Send for VirtIOConsole {}
+unsafe impl Sync for VirtIOConsole {}
+
+impl VirtIOConsole {
+    /// 새 VirtIO 콘솔 디바이스 생성
+    pub fn new(info: &VirtIODeviceInfo) -> VirtIOResult<Self> {
+        if info.device_type != DeviceType::Console {
+            return Err(VirtIOError::NoDevice);
+        }
+
+        let mmio = VirtIOMMIO::new(info.mmio_base);
+
+        // 디바이스 초기화, Feature 협상 (SIZE, MULTIPORT 등 디바이스별 기능은 사용하지 않음)
+        mmio.negotiate(0)?;
+
+        // receiveq 설정 및 버퍼 채우기
+        let queue = Virtqueue::new(&mmio, RECEIVEQ)?;
+        let count = RX_BUFFERS.min(queue.size() as usize);
+        let mut rx = RxQueue {
+            desc_slot: vec![0; queue.size() as usize],
+            queue,
+            bufs: vec![[0; RX_BUF_SIZE]; count],
+        };
+        for slot in 0..count {
+            rx.post(slot)?;
+        }
+
+        let tx = TxQueue {
+            queue: Virtqueue::new(&mmio, TRANSMITQ)?,
+            buf: vec![0; TX_BUF_SIZE],
+        };
+
+        // 드라이버 준비 완료 후 디바이스에 수신 버퍼 알림
+        mmio.driver_ok();
+        mmio.notify_queue(RECEIVEQ);
+
+        crate::kprintln!("[VirtIO-console] hvc0 @ {:#x}: {} receive buffers", info.mmio_base, count);
+
+        Ok(Self {
+            mmio,
+            irq: info.irq,
+            rx: IrqSpinlock::new(rx),
+            tx: IrqSpinlock::new(tx),
+            input: IrqSpinlock::new(RxRing::new()),
+            interrupt_flag: AtomicBool::new(false),
+        })
+    }
+
+    /// 채워진 수신 버퍼를 수신 링으로 옮기고 다시 큐에 넣음
+    fn drain(&self) {
+        let mut rx = self.rx.lock();
+        let mut reposted = false;
+
+        while let Some((desc, len)) = rx.queue.poll_used() {
+            let slot = rx.desc_slot[desc as usize] as usize;
+            let len = (len as usize).min(RX_BUF_SIZE);
+            {
+                let mut input = self.input.lock();
+                for i in 0..len {
+                    // Safety: 디바이스가 DMA로 채운 버퍼
+                    input.push(unsafe { core::ptr::read_volatile(&rx.bufs[slot][i]) });
+                }
+            }
+            if rx.post(slot).is_ok() {
+                reposted = true;
+            }
+        }
+
+        if reposted {
+            rx.queue.kick(&self.mmio);
+        }
+    }
+
+    /// 바이트열 송신 (디바이스가 받을 때까지 대기)
+    pub fn write(&self, data: &[u8]) -> VirtIOResult<()> {
+        let mut tx = self.tx.lock();
+        for chunk in data.chunks(TX_BUF_SIZE) {
+            tx.buf[..chunk.len()].copy_from_slice(chunk);
+            let buf = unsafe { core::slice::from_raw_parts(tx.buf.as_ptr(), chunk.len()) };
+            tx.queue.add_buffer(buf, false)?;
+            tx.queue.kick(&self.mmio);
+
+            let mut spins = 0;
+            while tx.queue.poll_used().is_none() {
+                spins += 1;
+                if spins == TX_SPIN_LIMIT {
+                    return Err(VirtIOError::Timeout);
+                }
+                core::hint::spin_loop();
+            }
+        }
+        Ok(())
+    }
+
+    /// 수신한 입력을 `buf`로 읽기 (없으면 0)
+    pub fn read(&self, buf: &mut [u8]) -> usize {
+        // 인터럽트를 놓친 경우 대비
+        self.drain();
+
+        let mut input = self.input.lock();
+        let mut n = 0;
+        while n < buf.len() {
+            let Some(byte) = input.pop() else {
+                break;
+            };
+            buf[n] = byte;
+            n += 1;
+        }
+        n
+    }
+
+    /// 인터럽트 컨트롤러에 IRQ 등록 및 디스패치 테이블에 등록
+    fn register_interrupt(&self) {
+        if self.irq == 0 {
+            return;
+        }
+
+        crate::virtio::irq::register_irq(self.irq, self.mmio.base(), &self.interrupt_flag, "virtio-console");
+        crate::virtio::irq::set_irq_handler(self.irq, handle_irq);
+    }
+}
+
+/// hvc0 (첫 번째 콘솔 디바이스)
+static CONSOLE: IrqSpinlock<Option<Arc<VirtIOConsole>>> = IrqSpinlock::new(None);
+
+/// VirtIO IRQ 하반부(태스크릿)에서 호출되는 핸들러
+fn handle_irq(irq: u32) {
+    if let Some(dev) = CONSOLE.lock().as_ref().filter(|d| d.irq == irq) {
+        dev.drain();
+    }
+}
+
+fn device() -> Option<Arc<VirtIOConsole>> {
+    CONSOLE.lock().clone()
+}
+
+/// hvc0이 있는지
+pub fn is_present() -> bool {
+    CONSOLE.lock().is_some()
+}
+
+/// hvc0으로 송신 (디바이스가 없거나 실패하면 false)
+pub fn write(data: &[u8]) -> bool {
+    device().is_some_and(|dev| dev.write(data).is_ok())
+}
+
+/// hvc0 입력 읽기 (non-blocking, 없으면 0)
+pub fn read(buf: &mut [u8]) -> usize {
+    device().map_or(0, |dev| dev.read(buf))
+}
+
+/// hvc0 입력 한 바이트 (non-blocking)
+pub fn getc() -> Option<u8> {
+    let mut byte = [0u8; 1];
+    (read(&mut byte) == 1).then_some(byte[0])
+}
+
+static HVC_CONSOLE: crate::console::ConsoleDriver = crate::console::ConsoleDriver {
+    name: "hvc0",
+    desc: "virtio console",
+    write,
+    getc: Some(getc),
+};
+
+/// VirtIO 콘솔 초기화
+///
+/// 첫 번째 virtio-console 디바이스를 hvc0으로 사용하고 `/dev/hvc0`을 등록합니다.
+/// DevFS 마운트 이후에 호출해야 합니다.
+///
+/// # Returns
+/// hvc0을 찾았으면 true
+pub fn init() -> bool {
+    let Some(info) = crate::virtio::find_virtio_devices()
+        .into_iter()
+        .find(|info| info.device_type == DeviceType::Console)
+    else {
+        return false;
+    };
+
+    let dev = match VirtIOConsole::new(&info) {
+        Ok(dev) => Arc::new(dev),
+        Err(e) => {
+            crate::kprintln!("[VirtIO-console] Init failed: {:?}", e);
+            return false;
+        }
+    };
+    *CONSOLE.lock() = Some(dev.clone());
+    // 인터럽트 등록 (Arc 생성 후, flag 포인터가 안정적)
+    dev.register_interrupt();
+
+    if let Some(devfs) = crate::fs::devfs::get_devfs() {
+        devfs.register_device("hvc0", Arc::new(crate::fs::devfs::HvcDevice));
+        crate::kprintln!("[VirtIO-console] Registered /dev/hvc0");
+    }
+    let _ = crate::console::register(&HVC_CONSOLE, crate::console::PRIORITY_DEFAULT, false);
+    true
+}
@@ -0,0 +1,465 @@
+//! VirtIO GPU 드라이버 (2D 스캔아웃, /dev/fb0)
+//!
+//! 3D(virgl) 없이 controlq로 2D 리소스 하나를 만들어 스캔아웃 0에 연결합니다.
+//!
+//! ```text
+//! GET_DISPLAY_INFO          → 해상도 (없으면 1024x768)
+//! RESOURCE_CREATE_2D        → 리소스 1 (B8G8R8X8)
+//! RESOURCE_ATTACH_BACKING   → 연속 페이지 프레임버퍼
+//! SET_SCANOUT               → 스캔아웃 0에 표시
+//! TRANSFER_TO_HOST_2D + RESOURCE_FLUSH  → flush()마다 바뀐 영역 반영
+//! ```
+//!
+//! 프레임버퍼는 픽셀당 32비트 (`0x00RRGGBB`)이며, 쓰기만으로는 화면에 나타나지 않고
+//! `flush()`를 호출해야 합니다. 명령은 콘솔 출력(`fbcon`) 경로에서도 보내므로
+//! 인터럽트 없이 완료까지 스핀합니다.
+//!
+//! QEMU: `-device virtio-gpu-device`
+
+extern crate alloc;
+
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem::size_of;
+
+use crate::mm::page::{self, MAX_ORDER, PAGE_SIZE};
+use crate::sync::IrqSpinlock;
+use crate::virtio::mmio::VirtIOMMIO;
+use crate::virtio::queue::Virtqueue;
+use crate::virtio::{DeviceType, VirtIODeviceInfo, VirtIOError, VirtIOResult};
+
+/// 제어 큐
+const CONTROLQ: u32 = 0;
+
+/// 명령 종류
+const CMD_GET_DISPLAY_INFO: u32 = 0x0100;
+const CMD_RESOURCE_CREATE_2D: u32 = 0x0101;
+const CMD_SET_SCANOUT: u32 = 0x0103;
+const CMD_RESOURCE_FLUSH: u32 = 0x0104;
+const CMD_TRANSFER_TO_HOST_2D: u32 = 0x0105;
+const CMD_RESOURCE_ATTACH_BACKING: u32 = 0x0106;
+
+/// 응답 종류
+const RESP_OK_NODATA: u32 = 0x1100;
+const RESP_OK_DISPLAY_INFO: u32 = 0x1101;
+
+/// 픽셀 형식: 메모리 순서 B, G, R, X (리틀 엔디언 u32 `0x00RRGGBB`)
+const FORMAT_B8G8R8X8_UNORM: u32 = 2;
+
+/// 최대 스캔아웃 수
+const MAX_SCANOUTS: usize = 16;
+/// 사용하는 리소스 ID (0은 "없음")
+const RESOURCE_ID: u32 = 1;
+/// 사용하는 스캔아웃
+const SCANOUT_ID: u32 = 0;
+
+/// 디스플레이 정보가 없을 때 해상도
+const DEFAULT_WIDTH: u32 = 1024;
+const DEFAULT_HEIGHT: u32 = 768;
+/// 픽셀당 바이트
+const BYTES_PER_PIXEL: u32 = 4;
+
+/// 명령/응답 버퍼 크기 (가장 큰 구조체인 디스플레이 정보 응답 이상)
+const CMD_BUF_SIZE: usize = 512;
+/// 명령 완료 대기 스핀 한도
+const CMD_SPIN_LIMIT: u32 = 10_000_000;
+
+/// 명령/응답 공통 헤더
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+struct CtrlHeader {
+    kind: u32,
+    flags: u32,
+    fence_id: u64,
+    ctx_id: u32,
+    ring_idx: u8,
+    padding: [u8; 3],
+}
+
+impl CtrlHeader {
+    fn new(kind: u32) -> Self {
+        Self {
+            kind,
+            ..Default::default()
+        }
+    }
+}
+
+/// 화면 영역 (픽셀)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+struct DisplayOne {
+    rect: Rect,
+    enabled: u32,
+    flags: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+struct RespDisplayInfo {
+    hdr: CtrlHeader,
+    pmodes: [DisplayOne; MAX_SCANOUTS],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct ResourceCreate2d {
+    hdr: CtrlHeader,
+    resource_id: u32,
+    format: u32,
+    width: u32,
+    height: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct MemEntry {
+    addr: u64,
+    length: u32,
+    padding: u32,
+}
+
+/// 연속 메모리이므로 엔트리는 하나만 붙임
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct ResourceAttachBacking {
+    hdr: CtrlHeader,
+    resource_id: u32,
+    nr_entries: u32,
+    entry: MemEntry,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct SetScanout {
+    hdr: CtrlHeader,
+    rect: Rect,
+    scanout_id: u32,
+    resource_id: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct TransferToHost2d {
+    hdr: CtrlHeader,
+    rect: Rect,
+    offset: u64,
+    resource_id: u32,
+    padding: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct ResourceFlush {
+    hdr: CtrlHeader,
+    rect: Rect,
+    resource_id: u32,
+    padding: u32,
+}
+
+/// 프레임버퍼 정보
+#[derive(Debug, Clone, Copy)]
+pub struct FbInfo {
+    /// 프레임버퍼 시작 주소 (물리 = 커널 주소)
+    pub addr: usize,
+    /// 너비 (픽셀)
+    pub width: u32,
+    /// 높이 (픽셀)
+    pub height: u32,
+    /// 한 줄 바이트 수
+    pub stride: u32,
+    /// 전체 바이트 수
+    pub size: usize,
+}
+
+impl FbInfo {
+    /// `addr`가 프레임버퍼 안인지
+    pub fn contains(&self, addr: usize) -> bool {
+        addr >= self.addr && addr < self.addr + self.size
+    }
+
+    /// 화면 전체 영역
+    pub fn full_rect(&self) -> Rect {
+        Rect {
+            x: 0,
+            y: 0,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+/// controlq와 명령/응답 버퍼
+struct ControlQueue {
+    queue: Virtqueue,
+    req: Vec<u8>,
+    resp: Vec<u8>,
+}
+
+/// VirtIO GPU 디바이스
+pub struct VirtIOGpu {
+    /// MMIO 핸들
+    mmio: VirtIOMMIO,
+    control: IrqSpinlock<ControlQueue>,
+    /// 스캔아웃에 연결한 프레임버퍼
+    fb: FbInfo,
+}
+
+// Safety: 큐는 IrqSpinlock으로 보호됨
+unsafe impl Send for VirtIOGpu {}
+unsafe impl Sync for VirtIOGpu {}
+
+/// 구조체를 바이트열로 보기 (`repr(C)` 명령 구조체 전용)
+fn as_bytes<T: Copy>(value: &T) -> &[u8] {
+    unsafe { core::slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) }
+}
+
+impl VirtIOGpu {
+    /// 새 VirtIO GPU 디바이스 생성 (프레임버퍼 할당 및 스캔아웃 설정까지)
+    pub fn new(info: &VirtIODeviceInfo) -> VirtIOResult<Self> {
+        if info.device_type != DeviceType::Gpu {
+            return Err(VirtIOError::NoDevice);
+        }
+
+        let mmio = VirtIOMMIO::new(info.mmio_base);
+
+        // 디바이스 초기화, Feature 협상 (VIRGL, EDID 등 디바이스별 기능은 사용하지 않음)
+        mmio.negotiate(0)?;
+
+        let control = ControlQueue {
+            queue: Virtqueue::new(&mmio, CONTROLQ)?,
+            req: vec![0; CMD_BUF_SIZE],
+            resp: vec![0; CMD_BUF_SIZE],
+        };
+        mmio.driver_ok();
+
+        let mut gpu = Self {
+            mmio,
+            control: IrqSpinlock::new(control),
+            fb: FbInfo {
+                addr: 0,
+                width: 0,
+                height: 0,
+                stride: 0,
+                size: 0,
+            },
+        };
+
+        let (width, height) = gpu.display_size()?;
+        gpu.fb = alloc_framebuffer(width, height)?;
+        gpu.setup_scanout()?;
+
+        crate::kprintln!("[VirtIO-gpu] {}x{} framebuffer @ {:#x} ({} KB)",
+            gpu.fb.width,
+            gpu.fb.height,
+            gpu.fb.addr,
+            gpu.fb.size / 1024
+        );
+        Ok(gpu)
+    }
+
+    /// 명령 전송 후 응답 수신
+    fn command<Req: Copy, Resp: Copy + Default>(&self, req: &Req) -> VirtIOResult<Resp> {
+        let req_len = size_of::<Req>();
+        let resp_len = size_of::<Resp>();
+        if req_len > CMD_BUF_SIZE || resp_len > CMD_BUF_SIZE {
+            return Err(VirtIOError::BufferTooSmall);
+        }
+
+        let mut ctrl = self.control.lock();
+        let ctrl = &mut *ctrl;
+        ctrl.req[..req_len].copy_from_slice(as_bytes(req));
+        ctrl.resp[..resp_len].fill(0);
+
+        ctrl.queue
+            .add_buffer_chain(&[&ctrl.req[..req_len]], &[&mut ctrl.resp[..resp_len]])?;
+        ctrl.queue.kick(&self.mmio);
+
+        let mut spins = 0;
+        while ctrl.queue.poll_used().is_none() {
+            spins += 1;
+            if spins == CMD_SPIN_LIMIT {
+                return Err(VirtIOError::Timeout);
+            }
+            core::hint::spin_loop();
+        }
+
+        // Safety: 디바이스가 DMA로 채운 응답, 크기는 위에서 확인
+        Ok(unsafe { core::ptr::read_volatile(ctrl.resp.as_ptr() as *const Resp) })
+    }
+
+    /// 응답 없는 명령 (OK_NODATA 확인)
+    fn command_ok<Req: Copy>(&self, req: &Req) -> VirtIOResult<()> {
+        let resp: CtrlHeader = self.command(req)?;
+        if resp.kind != RESP_OK_NODATA {
+            return Err(VirtIOError::IoError);
+        }
+        Ok(())
+    }
+
+    /// 스캔아웃 0의 해상도
+    fn display_size(&self) -> VirtIOResult<(u32, u32)> {
+        let resp: RespDisplayInfo = self.command(&CtrlHeader::new(CMD_GET_DISPLAY_INFO))?;
+        if resp.hdr.kind != RESP_OK_DISPLAY_INFO {
+            return Err(VirtIOError::IoError);
+        }
+        let mode = resp.pmodes[SCANOUT_ID as usize];
+        if mode.enabled == 0 || mode.rect.width == 0 || mode.rect.height == 0 {
+            return Ok((DEFAULT_WIDTH, DEFAULT_HEIGHT));
+        }
+        Ok((mode.rect.width, mode.rect.height))
+    }
+
+    /// 리소스 생성, 프레임버퍼 연결, 스캔아웃 설정
+    fn setup_scanout(&self) -> VirtIOResult<()> {
+        self.command_ok(&ResourceCreate2d {
+            hdr: CtrlHeader::new(CMD_RESOURCE_CREATE_2D),
+            resource_id: RESOURCE_ID,
+            format: FORMAT_B8G8R8X8_UNORM,
+            width: self.fb.width,
+            height: self.fb.height,
+        })?;
+        self.command_ok(&ResourceAttachBacking {
+            hdr: CtrlHeader::new(CMD_RESOURCE_ATTACH_BACKING),
+            resource_id: RESOURCE_ID,
+            nr_entries: 1,
+            entry: MemEntry {
+                addr: self.fb.addr as u64,
+                length: self.fb.size as u32,
+                padding: 0,
+            },
+        })?;
+        self.command_ok(&SetScanout {
+            hdr: CtrlHeader::new(CMD_SET_SCANOUT),
+            rect: self.fb.full_rect(),
+            scanout_id: SCANOUT_ID,
+            resource_id: RESOURCE_ID,
+        })?;
+        self.flush(self.fb.full_rect())
+    }
+
+    /// 프레임버퍼의 `rect` 영역을 화면에 반영
+    pub fn flush(&self, rect: Rect) -> VirtIOResult<()> {
+        let rect = clip(rect, &self.fb);
+        if rect.width == 0 || rect.height == 0 {
+            return Ok(());
+        }
+        self.command_ok(&TransferToHost2d {
+            hdr: CtrlHeader::new(CMD_TRANSFER_TO_HOST_2D),
+            rect,
+            offset: rect.y as u64 * self.fb.stride as u64 + rect.x as u64 * BYTES_PER_PIXEL as u64,
+            resource_id: RESOURCE_ID,
+            padding: 0,
+        })?;
+        self.command_ok(&ResourceFlush {
+            hdr: CtrlHeader::new(CMD_RESOURCE_FLUSH),
+            rect,
+            resource_id: RESOURCE_ID,
+            padding: 0,
+        })
+    }
+}
+
+/// `rect`를 화면 안으로 자르기
+fn clip(rect: Rect, fb: &FbInfo) -> Rect {
+    let x = rect.x.min(fb.width);
+    let y = rect.y.min(fb.height);
+    Rect {
+        x,
+        y,
+        width: rect.width.min(fb.width - x),
+        height: rect.height.min(fb.height - y),
+    }
+}
+
+/// 연속 페이지로 프레임버퍼 할당 (0으로 채움)
+///
+/// 버디 할당기 최대 블록(`MAX_ORDER`)을 넘는 해상도는 기본 해상도로 줄입니다.
+fn alloc_framebuffer(width: u32, height: u32) -> VirtIOResult<FbInfo> {
+    let max_bytes = PAGE_SIZE << MAX_ORDER;
+    let (width, height) = if width as usize * height as usize * BYTES_PER_PIXEL as usize > max_bytes {
+        crate::kprintln!("[VirtIO-gpu] {}x{} too large, using {}x{}",
+            width,
+            height,
+            DEFAULT_WIDTH,
+            DEFAULT_HEIGHT
+        );
+        (DEFAULT_WIDTH, DEFAULT_HEIGHT)
+    } else {
+        (width, height)
+    };
+
+    let stride = width * BYTES_PER_PIXEL;
+    let size = (stride * height) as usize;
+    let pages = size.div_ceil(PAGE_SIZE);
+    let addr = page::alloc_frames(pages).ok_or(VirtIOError::BufferTooSmall)?;
+    unsafe {
+        core::ptr::write_bytes(addr as *mut u8, 0, pages * PAGE_SIZE);
+    }
+
+    Ok(FbInfo {
+        addr,
+        width,
+        height,
+        stride,
+        size,
+    })
+}
+
+/// 첫 번째 GPU 디바이스
+static GPU: IrqSpinlock<Option<Arc<VirtIOGpu>>> = IrqSpinlock::new(None);
+
+fn device() -> Option<Arc<VirtIOGpu>> {
+    GPU.lock().clone()
+}
+
+/// 프레임버퍼 정보 (GPU가 없으면 None)
+pub fn framebuffer() -> Option<FbInfo> {
+    device().map(|dev| dev.fb)
+}
+
+/// 프레임버퍼의 `rect` 영역을 화면에 반영 (GPU가 없거나 실패하면 false)
+pub fn flush(rect: Rect) -> bool {
+    device().is_some_and(|dev| dev.flush(rect).is_ok())
+}
+
+/// VirtIO GPU 초기화
+///
+/// 첫 번째 virtio-gpu 디바이스의 스캔아웃 0에 프레임버퍼를 연결하고 `/dev/fb0`을
+/// 등록합니다. DevFS 마운트 이후에 호출해야 합니다.
+///
+/// # Returns
+/// GPU를 찾았으면 true
+pub fn init() -> bool {
+    let Some(info) = crate::virtio::find_virtio_devices()
+        .into_iter()
+        .find(|info| info.device_type == DeviceType::Gpu)
+    else {
+        return false;
+    };
+
+    let dev = match VirtIOGpu::new(&info) {
+        Ok(dev) => Arc::new(dev),
+        Err(e) => {
+            crate::kprintln!("[VirtIO-gpu] Init failed: {:?}", e);
+            return false;
+        }
+    };
+    *GPU.lock() = Some(dev);
+
+    if let Some(devfs) = crate::fs::devfs::get_devfs() {
+        devfs.register_device("fb0", Arc::new(crate::fs::devfs::FbDevice));
+        crate::kprintln!("[VirtIO-gpu] Registered /dev/fb0");
+    }
+    true
+}
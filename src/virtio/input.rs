@@ -0,0 +1,251 @@
+//! VirtIO 입력 드라이버
+//!
+//! virtio-input(키보드/마우스/태블릿) 디바이스의 eventq에 이벤트 버퍼를
+//! 미리 넣어두고, 디바이스가 채운 버퍼를 `input` 서브시스템에 보고한 뒤
+//! 다시 큐에 넣습니다. statusq(LED 상태 등)는 사용하지 않습니다.
+//!
+//! 키보드의 키 이벤트는 `input` 서브시스템이 터미널 바이트로 바꿔 콘솔 입력으로도 넣고
+//! (`input::keymap`), 태블릿의 `EV_ABS`/버튼 이벤트는 `/dev/input/eventN`으로만 나갑니다.
+//!
+//! QEMU: `-device virtio-keyboard-device`, `-device virtio-tablet-device`
+//! (virt 머신의 virtio-mmio 버스)
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::sync::atomic::AtomicBool;
+
+use crate::sync::IrqSpinlock;
+use crate::virtio::mmio::VirtIOMMIO;
+use crate::virtio::queue::Virtqueue;
+use crate::virtio::{DeviceType, VirtIODeviceInfo, VirtIOError, VirtIOResult};
+
+/// 설정 공간 오프셋 (struct virtio_input_config)
+mod config {
+    pub const SELECT: usize = 0;
+    pub const SUBSEL: usize = 1;
+    pub const SIZE: usize = 2;
+    pub const DATA: usize = 8;
+
+    /// select 값: 디바이스 이름 문자열
+    pub const ID_NAME: u8 = 0x01;
+}
+
+/// eventq에 미리 넣어둘 버퍼 수 (queue 크기와 작은 쪽)
+const EVENT_BUFFERS: usize = 64;
+
+/// 디바이스가 보내는 이벤트 (struct virtio_input_event, 리틀 엔디언)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+struct VirtIOInputEvent {
+    type_: u16,
+    code: u16,
+    value: u32,
+}
+
+/// eventq와 이벤트 버퍼
+struct EventQueue {
+    queue: Virtqueue,
+    /// 디바이스가 쓰는 이벤트 버퍼 (주소가 고정되도록 생성 후 크기 변경 금지)
+    bufs: Vec<VirtIOInputEvent>,
+    /// descriptor 번호 → 버퍼 번호
+    desc_slot: Vec<u16>,
+}
+
+impl EventQueue {
+    /// `slot` 버퍼를 eventq에 넣음
+    fn post(&mut self, slot: usize) -> VirtIOResult<()> {
+        let buf = unsafe {
+            core::slice::from_raw_parts(
+                &self.bufs[slot] as *const VirtIOInputEvent as *const u8,
+                core::mem::size_of::<VirtIOInputEvent>(),
+            )
+        };
+        let desc = self.queue.add_buffer(buf, true)?;
+        self.desc_slot[desc as usize] = slot as u16;
+        Ok(())
+    }
+}
+
+/// VirtIO 입력 디바이스
+pub struct VirtIOInput {
+    /// 디바이스가 보고한 이름
+    name: String,
+    /// MMIO 핸들
+    mmio: VirtIOMMIO,
+    /// IRQ 번호
+    irq: u32,
+    /// input 서브시스템 디바이스 번호
+    input_id: usize,
+    /// eventq (인터럽트 핸들러에서도 접근)
+    events: IrqSpinlock<EventQueue>,
+    /// 인터럽트 플래그 (VirtIO IRQ 디스패치 테이블용)
+    interrupt_flag: AtomicBool,
+}
+
+// Safety: eventq는 IrqSpinlock으로 보호됨
+unsafe impl Send for VirtIOInput {}
+unsafe impl Sync for VirtIOInput {}
+
+impl VirtIOInput {
+    /// 새 VirtIO 입력 디바이스 생성
+    pub fn new(info: &VirtIODeviceInfo) -> VirtIOResult<Self> {
+        if info.device_type != DeviceType::Input {
+            return Err(VirtIOError::NoDevice);
+        }
+
+        let mmio = VirtIOMMIO::new(info.mmio_base);
+
+        // 디바이스 초기화, Feature 협상 (디바이스별 기능은 없음)
+        mmio.negotiate(0)?;
+
+        let name = read_name(&mmio);
+
+        // eventq 설정 및 버퍼 채우기
+        let queue = Virtqueue::new(&mmio, 0)?;
+        let count = EVENT_BUFFERS.min(queue.size() as usize);
+        let mut events = EventQueue {
+            desc_slot: vec![0; queue.size() as usize],
+            queue,
+            bufs: vec![VirtIOInputEvent::default(); count],
+        };
+        for slot in 0..count {
+            events.post(slot)?;
+        }
+
+        // 드라이버 준비 완료 후 디바이스에 버퍼 알림
+        mmio.driver_ok();
+        mmio.notify_queue(0);
+
+        crate::kprintln!(
+            "[VirtIO-input] '{}' @ {:#x}: {} event buffers",
+            name,
+            info.mmio_base,
+            count
+        );
+
+        Ok(Self {
+            name,
+            mmio,
+            irq: info.irq,
+            input_id: usize::MAX,
+            events: IrqSpinlock::new(events),
+            interrupt_flag: AtomicBool::new(false),
+        })
+    }
+
+    /// 디바이스 이름
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 채워진 이벤트 버퍼를 input 서브시스템에 보고하고 다시 큐에 넣음
+    fn drain(&self) {
+        let mut events = self.events.lock();
+        let mut reposted = false;
+
+        while let Some((desc, _len)) = events.queue.poll_used() {
+            let slot = events.desc_slot[desc as usize] as usize;
+            // Safety: 디바이스가 DMA로 채운 버퍼
+            let ev = unsafe { core::ptr::read_volatile(&events.bufs[slot]) };
+            crate::input::report_event(
+                self.input_id,
+                u16::from_le(ev.type_),
+                u16::from_le(ev.code),
+                u32::from_le(ev.value) as i32,
+            );
+
+            if events.post(slot).is_ok() {
+                reposted = true;
+            }
+        }
+
+        if reposted {
+            events.queue.kick(&self.mmio);
+        }
+    }
+
+    /// 인터럽트 컨트롤러에 IRQ 등록 및 디스패치 테이블에 등록
+    fn register_interrupt(&self) {
+        if self.irq == 0 {
+            return;
+        }
+
+        crate::virtio::irq::register_irq(self.irq, self.mmio.base(), &self.interrupt_flag, "virtio-input");
+        crate::virtio::irq::set_irq_handler(self.irq, handle_irq);
+    }
+}
+
+/// 설정 공간에서 디바이스 이름 읽기
+fn read_name(mmio: &VirtIOMMIO) -> String {
+    mmio.write_config8(config::SELECT, config::ID_NAME);
+    mmio.write_config8(config::SUBSEL, 0);
+
+    let size = (mmio.read_config8(config::SIZE) as usize).min(128);
+    let mut name = String::with_capacity(size);
+    for i in 0..size {
+        let c = mmio.read_config8(config::DATA + i);
+        if c == 0 {
+            break;
+        }
+        name.push(if c.is_ascii_graphic() || c == b' ' { c as char } else { '?' });
+    }
+    if name.is_empty() {
+        name.push_str("virtio-input");
+    }
+    name
+}
+
+/// 초기화된 입력 디바이스
+static DEVICES: IrqSpinlock<Vec<Arc<VirtIOInput>>> = IrqSpinlock::new(Vec::new());
+
+/// VirtIO IRQ 하반부(태스크릿)에서 호출되는 핸들러
+fn handle_irq(irq: u32) {
+    let devices = DEVICES.lock();
+    for dev in devices.iter().filter(|d| d.irq == irq) {
+        dev.drain();
+    }
+}
+
+/// 모든 입력 디바이스의 eventq를 비움 (인터럽트를 놓친 경우 대비)
+pub fn poll() {
+    let devices = DEVICES.lock();
+    for dev in devices.iter() {
+        dev.drain();
+    }
+}
+
+/// VirtIO 입력 디바이스 초기화
+///
+/// 찾은 디바이스마다 input 서브시스템에 디바이스를 등록합니다.
+///
+/// # Returns
+/// 초기화된 디바이스 수
+pub fn init() -> usize {
+    let mut count = 0;
+
+    for info in crate::virtio::find_virtio_devices() {
+        if info.device_type != DeviceType::Input {
+            continue;
+        }
+
+        match VirtIOInput::new(&info) {
+            Ok(mut dev) => {
+                dev.input_id = crate::input::register_device(dev.name());
+                let dev = Arc::new(dev);
+                DEVICES.lock().push(dev.clone());
+                // 인터럽트 등록 (Arc 생성 후, flag 포인터가 안정적)
+                dev.register_interrupt();
+                count += 1;
+            }
+            Err(e) => {
+                crate::kprintln!("[VirtIO-input] Init failed: {:?}", e);
+            }
+        }
+    }
+
+    count
+}
@@ -1,9 +1,12 @@
 //! VirtIO IRQ 디스패치 레지스트리
 //!
-//! PLIC/GIC 핸들러에서 VirtIO 디바이스의 인터럽트를 라우팅합니다.
-//! 인터럽트 컨텍스트에서 호출되므로 고정 크기 배열 사용 (heap 할당 불가).
+//! 디바이스를 등록하면 그 IRQ에 `handle_virtio_irq()`를 걸고(`crate::irq::request_irq`),
+//! 핸들러가 IRQ 번호로 디바이스를 찾아 인터럽트를 ack합니다. used 링을 비우는 디바이스
+//! 핸들러(`set_irq_handler`)는 항목마다 둔 태스크릿으로 미뤄 인터럽트를 켠 채 실행합니다
+//! (`crate::softirq`). 인터럽트 컨텍스트에서 호출되므로 고정 크기 배열 사용 (heap 할당 불가).
 
-use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use crate::softirq::Tasklet;
 use crate::virtio::mmio::VirtIOMMIO;
 
 /// 최대 VirtIO 디바이스 수
@@ -18,15 +21,21 @@ struct IrqEntry {
     /// 디바이스의 interrupt_flag 포인터
     flag: AtomicU32, // *const AtomicBool을 u32로 저장 (주소)
     flag_high: AtomicU32, // 상위 32비트 (64비트 주소 지원)
+    /// 추가 인터럽트 핸들러 (`fn(u32)` 주소, 0 = 없음)
+    handler: AtomicUsize,
+    /// `handler`를 부르는 하반부 (data = 표 인덱스)
+    tasklet: Tasklet,
 }
 
 impl IrqEntry {
-    const fn new() -> Self {
+    const fn new(index: usize) -> Self {
         Self {
             irq_num: AtomicU32::new(0),
             mmio_base: AtomicU32::new(0),
             flag: AtomicU32::new(0),
             flag_high: AtomicU32::new(0),
+            handler: AtomicUsize::new(0),
+            tasklet: Tasklet::new(run_handler, index),
         }
     }
 
@@ -43,15 +52,18 @@ impl IrqEntry {
 
 /// 전역 IRQ 디스패치 테이블 (lock-free)
 static IRQ_TABLE: [IrqEntry; MAX_VIRTIO_DEVICES] = [
-    IrqEntry::new(), IrqEntry::new(), IrqEntry::new(), IrqEntry::new(),
-    IrqEntry::new(), IrqEntry::new(), IrqEntry::new(), IrqEntry::new(),
+    IrqEntry::new(0), IrqEntry::new(1), IrqEntry::new(2), IrqEntry::new(3),
+    IrqEntry::new(4), IrqEntry::new(5), IrqEntry::new(6), IrqEntry::new(7),
 ];
 
 /// VirtIO 디바이스 IRQ 등록
 ///
+/// 디스패치 테이블에 넣은 뒤 IRQ 핸들러를 걸어 인터럽트 컨트롤러에서 IRQ를 켭니다.
+/// `name`은 `interrupts` 명령에 표시됩니다.
+///
 /// # Safety
 /// `flag`는 디바이스가 살아있는 동안 유효해야 합니다 (Arc로 보호되는 경우 안전).
-pub fn register_irq(irq_num: u32, mmio_base: usize, flag: &AtomicBool) {
+pub fn register_irq(irq_num: u32, mmio_base: usize, flag: &AtomicBool, name: &str) {
     if irq_num == 0 {
         return;
     }
@@ -65,6 +77,12 @@ pub fn register_irq(irq_num: u32, mmio_base: usize, flag: &AtomicBool) {
             entry.flag_high.store((flag_addr >> 32) as u32, Ordering::Relaxed);
             // irq_num을 마지막에 저장하여 다른 필드가 먼저 설정되도록 보장
             entry.irq_num.store(irq_num, Ordering::Release);
+
+            // IRQ를 공유하는 디바이스는 같은 핸들러를 씀
+            match crate::irq::request_irq(irq_num, handle_virtio_irq, name) {
+                Ok(()) | Err(crate::irq::IrqError::AlreadyRegistered) => {}
+                Err(e) => crate::kprintln!("[VirtIO-IRQ] IRQ {}: {}", irq_num, e.as_str()),
+            }
             crate::kprintln!("[VirtIO-IRQ] Registered IRQ {} (MMIO {:#x})", irq_num, mmio_base);
             return;
         }
@@ -73,20 +91,44 @@ pub fn register_irq(irq_num: u32, mmio_base: usize, flag: &AtomicBool) {
     crate::kprintln!("[VirtIO-IRQ] Warning: IRQ table full, cannot register IRQ {}", irq_num);
 }
 
+/// 등록된 IRQ에 인터럽트 핸들러 추가
+///
+/// 플래그만으로 부족한 디바이스(예: virtio-input처럼 디바이스가 먼저 이벤트를
+/// 보내는 경우)는 핸들러에서 used 링을 직접 비웁니다. 핸들러는 인터럽트를 ack한 뒤
+/// 태스크릿(하반부)으로 실행되며, heap 할당이나 블로킹 락을 사용하면 안 됩니다.
+/// 같은 핸들러가 두 CPU에서 동시에 실행되지는 않습니다.
+pub fn set_irq_handler(irq_num: u32, handler: fn(u32)) {
+    for entry in IRQ_TABLE.iter() {
+        if entry.irq_num.load(Ordering::Acquire) == irq_num {
+            entry.handler.store(handler as usize, Ordering::Release);
+            return;
+        }
+    }
+}
+
 /// VirtIO 디바이스 IRQ 해제
+///
+/// IRQ를 쓰는 디바이스가 더 없으면 IRQ 핸들러도 해제합니다. 반환 후에는 디바이스
+/// 핸들러가 실행 중이지 않습니다.
 pub fn unregister_irq(irq_num: u32) {
     for entry in IRQ_TABLE.iter() {
         if entry.irq_num.load(Ordering::Relaxed) == irq_num {
+            // 예약된 하반부가 핸들러를 부르지 않게 먼저 떼고, 실행 중이면 끝날 때까지 대기
+            entry.handler.store(0, Ordering::Release);
+            entry.tasklet.kill();
             entry.irq_num.store(0, Ordering::Release);
             entry.mmio_base.store(0, Ordering::Relaxed);
             entry.flag.store(0, Ordering::Relaxed);
             entry.flag_high.store(0, Ordering::Relaxed);
-            return;
+            break;
         }
     }
+    if !IRQ_TABLE.iter().any(|e| e.irq_num.load(Ordering::Relaxed) == irq_num) {
+        crate::irq::free_irq(irq_num, handle_virtio_irq);
+    }
 }
 
-/// VirtIO IRQ 핸들러 — PLIC/GIC에서 호출
+/// VirtIO IRQ 핸들러 — `crate::irq` 디스패치에서 호출
 ///
 /// 해당 IRQ를 처리했으면 true, 아니면 false 반환
 pub fn handle_virtio_irq(irq_num: u32) -> bool {
@@ -105,9 +147,24 @@ pub fn handle_virtio_irq(irq_num: u32) -> bool {
                         (*flag_ptr).store(true, Ordering::SeqCst);
                     }
                 }
+                if entry.handler.load(Ordering::Acquire) != 0 {
+                    entry.tasklet.schedule();
+                }
                 return true;
             }
         }
     }
     false
 }
+
+/// 하반부: 항목의 디바이스 핸들러 실행 (`index` = `IRQ_TABLE` 인덱스)
+fn run_handler(index: usize) {
+    let entry = &IRQ_TABLE[index];
+    let irq_num = entry.irq_num.load(Ordering::Acquire);
+    let handler = entry.handler.load(Ordering::Acquire);
+    if irq_num != 0 && handler != 0 {
+        // Safety: set_irq_handler에서 fn(u32) 주소로만 저장됨
+        let handler: fn(u32) = unsafe { core::mem::transmute(handler) };
+        handler(irq_num);
+    }
+}
@@ -1,6 +1,11 @@
 //! VirtIO MMIO 레지스터 접근
 //!
 //! VirtIO MMIO 디바이스의 레지스터 레이아웃 및 접근 함수
+//!
+//! 버전 1(legacy)과 2(modern, VirtIO 1.0+) 레지스터를 모두 지원합니다. QEMU virt의 virtio-mmio는
+//! 기본이 legacy이고 `-global virtio-mmio.force-legacy=false`면 modern입니다. 드라이버는
+//! `negotiate()`로 Feature를 협상하며, modern 디바이스에는 `VIRTIO_F_VERSION_1`을 함께
+//! 받아들이고 legacy 디바이스에는 하위 32비트만 씁니다.
 
 use core::ptr::{read_volatile, write_volatile};
 use super::{DeviceType, VirtIOError, VirtIOResult};
@@ -61,6 +66,8 @@ mod regs {
     pub const QUEUE_USED_LOW: usize = 0x0a0;
     /// Queue Used 영역 (modern, 상위 32비트)
     pub const QUEUE_USED_HIGH: usize = 0x0a4;
+    /// 설정 공간 세대 번호 (modern, 읽기 전용)
+    pub const CONFIG_GENERATION: usize = 0x0fc;
     /// 디바이스별 설정 (오프셋 0x100부터)
     pub const CONFIG: usize = 0x100;
 }
@@ -82,6 +89,26 @@ pub mod status {
     pub const FAILED: u32 = 128;
 }
 
+/// VirtIO 공통 Feature 비트 (64비트 번호, 24~40번은 transport/링 기능)
+#[allow(dead_code)]
+pub mod features {
+    /// 간접 Descriptor
+    pub const RING_INDIRECT_DESC: u64 = 1 << 28;
+    /// used_event/avail_event로 알림 억제
+    pub const RING_EVENT_IDX: u64 = 1 << 29;
+    /// VirtIO 1.0+ (modern) 디바이스, 버전 2 MMIO에서는 필수
+    pub const VERSION_1: u64 = 1 << 32;
+    /// 디바이스 DMA가 플랫폼 주소 변환(IOMMU)을 거침
+    pub const ACCESS_PLATFORM: u64 = 1 << 33;
+    /// Packed virtqueue
+    pub const RING_PACKED: u64 = 1 << 34;
+    /// Queue Notify에 링 위치까지 씀
+    pub const NOTIFICATION_DATA: u64 = 1 << 38;
+
+    /// 디바이스별 Feature 영역 (0~23, 41~)
+    pub const DEVICE_MASK: u64 = !(((1u64 << 41) - 1) & !((1u64 << 24) - 1));
+}
+
 /// 인터럽트 상태 비트
 #[allow(dead_code)]
 pub mod interrupt {
@@ -186,6 +213,50 @@ impl VirtIOMMIO {
         }
     }
 
+    /// 디바이스 Feature 64비트 (legacy는 하위 32비트만)
+    pub fn device_features64(&self) -> u64 {
+        let lo = self.device_features(0) as u64;
+        if self.is_legacy() {
+            return lo;
+        }
+        lo | (self.device_features(1) as u64) << 32
+    }
+
+    /// 드라이버 Feature 64비트 쓰기 (legacy는 하위 32비트만)
+    pub fn set_driver_features64(&self, features: u64) {
+        self.set_driver_features(0, features as u32);
+        if !self.is_legacy() {
+            self.set_driver_features(1, (features >> 32) as u32);
+        }
+    }
+
+    /// 리셋부터 FEATURES_OK까지 초기화하고 Feature 협상
+    ///
+    /// `wanted`는 드라이버가 쓸 줄 아는 디바이스별 Feature입니다. 디바이스가 제공하는 것만
+    /// 받아들이고, modern 디바이스에는 `VERSION_1`(없으면 실패)과 제공된 경우 `ACCESS_PLATFORM`
+    /// (IOMMU가 없어 물리 주소 그대로)을 더합니다. 받아들인 Feature를 돌려주며, 이후 Queue를
+    /// 설정하고 `driver_ok()`를 부르는 것은 드라이버 몫입니다.
+    pub fn negotiate(&self, wanted: u64) -> VirtIOResult<u64> {
+        self.init_device()?;
+
+        let offered = self.device_features64();
+        let mut accepted = offered & wanted & features::DEVICE_MASK;
+        if !self.is_legacy() {
+            if offered & features::VERSION_1 == 0 {
+                self.set_status(status::FAILED);
+                return Err(VirtIOError::FeatureNegotiationFailed);
+            }
+            accepted |= features::VERSION_1 | (offered & features::ACCESS_PLATFORM);
+        }
+        self.set_driver_features64(accepted);
+
+        // legacy에는 FEATURES_OK 단계가 없음
+        if !self.is_legacy() {
+            self.finish_features()?;
+        }
+        Ok(accepted)
+    }
+
     /// Queue 선택
     pub fn select_queue(&self, queue: u32) {
         unsafe { self.write(regs::QUEUE_SEL, queue) }
@@ -285,14 +356,49 @@ impl VirtIOMMIO {
         unsafe { read_volatile((self.base + regs::CONFIG + offset) as *const u8) }
     }
 
+    /// 디바이스 설정 쓰기 (8비트)
+    pub fn write_config8(&self, offset: usize, value: u8) {
+        unsafe { write_volatile((self.base + regs::CONFIG + offset) as *mut u8, value) }
+    }
+
+    /// 디바이스 설정 읽기 (16비트)
+    pub fn read_config16(&self, offset: usize) -> u16 {
+        unsafe { read_volatile((self.base + regs::CONFIG + offset) as *const u16) }
+    }
+
     /// 디바이스 설정 읽기 (32비트)
     pub fn read_config32(&self, offset: usize) -> u32 {
         unsafe { read_volatile((self.base + regs::CONFIG + offset) as *const u32) }
     }
 
+    /// 설정 공간 세대 번호 (modern, legacy는 0)
+    ///
+    /// 디바이스가 설정을 바꿀 때마다 바뀌므로 여러 번에 나눠 읽는 값의 일관성을 확인합니다.
+    pub fn config_generation(&self) -> u32 {
+        if self.is_legacy() {
+            return 0;
+        }
+        unsafe { self.read(regs::CONFIG_GENERATION) }
+    }
+
     /// 디바이스 설정 읽기 (64비트)
+    ///
+    /// MMIO 설정 공간의 64비트 필드는 32비트 두 번으로 읽어야 하므로, 그 사이 디바이스가
+    /// 값을 바꾸지 않았는지 세대 번호로 확인하고 바뀌었으면 다시 읽습니다.
     pub fn read_config64(&self, offset: usize) -> u64 {
-        unsafe { read_volatile((self.base + regs::CONFIG + offset) as *const u64) }
+        loop {
+            let generation = self.config_generation();
+            let lo = self.read_config32(offset) as u64;
+            let hi = self.read_config32(offset + 4) as u64;
+            if self.config_generation() == generation {
+                return lo | hi << 32;
+            }
+        }
+    }
+
+    /// 버전 이름 (`legacy` / `modern`)
+    pub fn version_name(&self) -> &'static str {
+        if self.is_legacy() { "legacy" } else { "modern" }
     }
 
     /// 디바이스 초기화
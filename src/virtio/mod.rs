@@ -2,14 +2,21 @@
 //!
 //! VirtIO MMIO 기반 디바이스 지원
 //! - virtio-blk: 블록 디바이스
+//! - virtio-input: 키보드/마우스 입력 (input.rs)
 //! - virtio-net: 네트워크 (향후)
-//! - virtio-console: 콘솔 (향후)
+//! - virtio-console: 보조 콘솔 hvc0 (console.rs)
+//! - virtio-gpu: 2D 프레임버퍼 /dev/fb0 (gpu.rs)
+//! - virtio-9p: 호스트 디렉토리 공유 전송 (p9.rs, 프로토콜은 fs::p9)
 
 extern crate alloc;
 
 pub mod mmio;
 pub mod queue;
 pub mod irq;
+pub mod input;
+pub mod console;
+pub mod gpu;
+pub mod p9;
 
 use alloc::vec::Vec;
 use crate::dtb::DeviceInfo;
@@ -52,6 +59,7 @@ impl From<u32> for DeviceType {
             3 => DeviceType::Console,
             4 => DeviceType::Entropy,
             5 => DeviceType::Balloon,
+            9 => DeviceType::Transport9P,
             16 => DeviceType::Gpu,
             18 => DeviceType::Input,
             _ => DeviceType::Invalid,
@@ -94,6 +102,8 @@ pub struct VirtIODeviceInfo {
     pub irq: u32,
     /// 디바이스 타입
     pub device_type: DeviceType,
+    /// MMIO 버전 (1 = legacy, 2 = modern)
+    pub version: u32,
 }
 
 /// DTB에서 VirtIO 디바이스 목록 가져오기
@@ -108,14 +118,12 @@ pub fn find_virtio_devices() -> Vec<VirtIODeviceInfo> {
                 continue;
             }
 
-            // MMIO에서 디바이스 타입 확인
-            let device_type = unsafe {
-                let mmio = mmio::VirtIOMMIO::new(info.reg_base as usize);
-                if mmio.is_valid() {
-                    mmio.device_type()
-                } else {
-                    DeviceType::Invalid
-                }
+            // MMIO에서 디바이스 타입, 버전 확인
+            let mmio = mmio::VirtIOMMIO::new(info.reg_base as usize);
+            let device_type = if mmio.is_valid() {
+                mmio.device_type()
+            } else {
+                DeviceType::Invalid
             };
 
             // Invalid가 아닌 실제 디바이스만 추가
@@ -128,6 +136,7 @@ pub fn find_virtio_devices() -> Vec<VirtIODeviceInfo> {
                     mmio_size: info.reg_size as usize,
                     irq,
                     device_type,
+                    version: mmio.version(),
                 });
             }
         }
@@ -149,10 +158,11 @@ pub fn init() {
 
     for dev in &devices {
         crate::kprintln!(
-            "[VirtIO] Found {:?} @ {:#x} (IRQ {})",
+            "[VirtIO] Found {:?} @ {:#x} (IRQ {}, {})",
             dev.device_type,
             dev.mmio_base,
-            dev.irq
+            dev.irq,
+            if dev.version == 1 { "legacy" } else { "modern" }
         );
     }
 
@@ -0,0 +1,180 @@
+//! VirtIO 9P 전송 (virtio-9p)
+//!
+//! 호스트 디렉토리 공유(QEMU `-virtfs`)의 전송 계층입니다. 요청 큐 하나에
+//! T-메시지(디바이스 읽기)와 R-메시지 버퍼(디바이스 쓰기)를 한 체인으로 넣고 응답을
+//! 기다립니다. 9P 프로토콜 자체는 `fs::p9`가 처리합니다.
+//!
+//! 디바이스마다 config 공간의 마운트 태그로 구분하며, `mount -t 9p <tag>`에서
+//! 태그로 찾습니다.
+//!
+//! QEMU: `-virtfs local,path=<host dir>,mount_tag=host,security_model=none`
+//! (virt 머신은 virtio-mmio이므로 `-fsdev local,id=fs0,path=<dir>,security_model=none
+//! -device virtio-9p-device,fsdev=fs0,mount_tag=host`)
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::sync::atomic::AtomicBool;
+
+use crate::sync::{Mutex, RwLock};
+use crate::virtio::mmio::VirtIOMMIO;
+use crate::virtio::queue::Virtqueue;
+use crate::virtio::{DeviceType, VirtIODeviceInfo, VirtIOError, VirtIOResult};
+
+/// config 공간에 마운트 태그가 있음
+const VIRTIO_9P_MOUNT_TAG: u64 = 1 << 0;
+
+/// 요청 큐
+const REQUESTQ: u32 = 0;
+
+/// 최대 메시지 크기 (Tversion에서 이 값 이하로 협상)
+pub const MAX_MSIZE: usize = 64 * 1024;
+
+/// 요청/응답 버퍼
+struct Channel {
+    queue: Virtqueue,
+    req: Vec<u8>,
+    resp: Vec<u8>,
+}
+
+/// VirtIO 9P 디바이스
+pub struct VirtIO9P {
+    /// MMIO 핸들
+    mmio: VirtIOMMIO,
+    /// IRQ 번호
+    irq: u32,
+    /// 마운트 태그
+    tag: String,
+    /// 요청은 한 번에 하나씩 (응답을 기다리는 동안 다른 스레드는 대기)
+    channel: Mutex<Channel>,
+    /// 인터럽트 플래그 (VirtIO IRQ 디스패치 테이블용)
+    interrupt_flag: AtomicBool,
+}
+
+// Safety: 큐는 Mutex로 보호됨
+unsafe impl Send for VirtIO9P {}
+unsafe impl Sync for VirtIO9P {}
+
+impl VirtIO9P {
+    /// 새 VirtIO 9P 디바이스 생성
+    pub fn new(info: &VirtIODeviceInfo) -> VirtIOResult<Self> {
+        if info.device_type != DeviceType::Transport9P {
+            return Err(VirtIOError::NoDevice);
+        }
+
+        let mmio = VirtIOMMIO::new(info.mmio_base);
+
+        // 디바이스 초기화, Feature 협상 (마운트 태그만 사용)
+        let features = mmio.negotiate(VIRTIO_9P_MOUNT_TAG)?;
+
+        // config: tag_len (u16) + tag
+        let tag = if features & VIRTIO_9P_MOUNT_TAG != 0 {
+            let len = mmio.read_config8(0) as usize | (mmio.read_config8(1) as usize) << 8;
+            let bytes: Vec<u8> = (0..len).map(|i| mmio.read_config8(2 + i)).collect();
+            String::from_utf8_lossy(&bytes).into_owned()
+        } else {
+            String::new()
+        };
+
+        let channel = Channel {
+            queue: Virtqueue::new(&mmio, REQUESTQ)?,
+            req: vec![0; MAX_MSIZE],
+            resp: vec![0; MAX_MSIZE],
+        };
+        mmio.driver_ok();
+
+        crate::kprintln!("[VirtIO-9p] tag '{}' @ {:#x}", tag, info.mmio_base);
+
+        Ok(Self {
+            mmio,
+            irq: info.irq,
+            tag,
+            channel: Mutex::new(channel),
+            interrupt_flag: AtomicBool::new(false),
+        })
+    }
+
+    /// 마운트 태그
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// 요청 메시지를 보내고 응답 메시지를 받음
+    ///
+    /// 응답을 기다리는 동안 `block::request::relax()`로 CPU를 양보하며, 완료
+    /// 인터럽트가 `wfi`를 깨웁니다.
+    pub fn transact(&self, req: &[u8]) -> VirtIOResult<Vec<u8>> {
+        if req.len() > MAX_MSIZE {
+            return Err(VirtIOError::BufferTooSmall);
+        }
+
+        let mut chan = self.channel.lock();
+        let chan = &mut *chan;
+        chan.req[..req.len()].copy_from_slice(req);
+
+        chan.queue.add_buffer_chain(&[&chan.req[..req.len()]], &[&mut chan.resp[..]])?;
+        chan.queue.kick(&self.mmio);
+
+        let len = loop {
+            if let Some((_, len)) = chan.queue.poll_used() {
+                break len as usize;
+            }
+            crate::block::request::relax();
+        };
+
+        // Safety: 디바이스가 DMA로 채운 응답
+        let resp = unsafe { core::slice::from_raw_parts(chan.resp.as_ptr(), len.min(MAX_MSIZE)) };
+        Ok(resp.to_vec())
+    }
+
+    /// 인터럽트 컨트롤러에 IRQ 등록 및 디스패치 테이블에 등록
+    ///
+    /// 핸들러는 없고, 인터럽트는 응답을 기다리는 `wfi`를 깨우는 역할만 합니다.
+    fn register_interrupt(&self) {
+        if self.irq == 0 {
+            return;
+        }
+
+        crate::virtio::irq::register_irq(self.irq, self.mmio.base(), &self.interrupt_flag, "virtio-9p");
+    }
+}
+
+/// 발견한 9P 디바이스
+static DEVICES: RwLock<Vec<Arc<VirtIO9P>>> = RwLock::new(Vec::new());
+
+/// 마운트 태그로 디바이스 찾기
+pub fn find(tag: &str) -> Option<Arc<VirtIO9P>> {
+    DEVICES.read().iter().find(|dev| dev.tag() == tag).cloned()
+}
+
+/// 사용 가능한 마운트 태그 목록
+pub fn tags() -> Vec<String> {
+    DEVICES.read().iter().map(|dev| String::from(dev.tag())).collect()
+}
+
+/// VirtIO 9P 초기화 (모든 virtio-9p 디바이스 등록)
+///
+/// # Returns
+/// 등록한 디바이스 수
+pub fn init() -> usize {
+    let mut count = 0;
+    for info in crate::virtio::find_virtio_devices() {
+        if info.device_type != DeviceType::Transport9P {
+            continue;
+        }
+        match VirtIO9P::new(&info) {
+            Ok(dev) => {
+                let dev = Arc::new(dev);
+                // 인터럽트 등록 (Arc 생성 후, flag 포인터가 안정적)
+                dev.register_interrupt();
+                DEVICES.write().push(dev);
+                count += 1;
+            }
+            Err(e) => crate::kprintln!("[VirtIO-9p] Init failed @ {:#x}: {:?}", info.mmio_base, e),
+        }
+    }
+    count
+}
@@ -1,18 +1,22 @@
 //! VirtIO Virtqueue 구현
 //!
-//! Split Virtqueue (legacy) 구현
+//! Split Virtqueue 구현 (legacy는 `QueuePFN` 한 영역, modern은 세 영역 주소를 따로 알려 줌)
 //! - Descriptor Table: 버퍼 정보
 //! - Available Ring: 드라이버 → 디바이스
 //! - Used Ring: 디바이스 → 드라이버
 
+//!
+//! 링 메모리는 `mm::dma`의 일관성 버퍼이고, 디바이스에 넘기는 데이터 버퍼는 넣을 때와 완료될
+//! 때 `sync_for_device`/`sync_for_cpu`로 캐시를 정리합니다.
+
 extern crate alloc;
 
-use alloc::boxed::Box;
 use alloc::vec::Vec;
 use core::ptr::{read_volatile, write_volatile};
 use core::sync::atomic::{fence, Ordering};
 use super::{VirtIOError, VirtIOResult};
 use super::mmio::VirtIOMMIO;
+use crate::mm::dma::{self, DmaBuffer};
 
 /// Descriptor 플래그
 pub mod desc_flags {
@@ -59,6 +63,9 @@ pub struct VirtqUsedElem {
     pub len: u32,
 }
 
+/// Used Ring 플래그: 디바이스가 알림을 원하지 않음 (폴링 중)
+const VIRTQ_USED_F_NO_NOTIFY: u16 = 1;
+
 /// Used Ring
 #[repr(C)]
 pub struct VirtqUsed {
@@ -88,8 +95,8 @@ pub struct Virtqueue {
     avail_ring: *mut VirtqAvail,
     /// Used Ring 메모리
     used_ring: *mut VirtqUsed,
-    /// 할당된 메모리 (해제용)
-    _memory: Box<[u8]>,
+    /// 링 메모리 (drop 때 해제)
+    _memory: DmaBuffer,
 }
 
 // Safety: Virtqueue는 단일 스레드에서만 사용됨
@@ -114,8 +121,11 @@ impl Virtqueue {
         // Guest 페이지 크기 설정 (legacy 필수)
         mmio.set_guest_page_size(4096);
 
-        // Queue 선택
+        // Queue 선택 (이미 쓰고 있는 Queue면 실패)
         mmio.select_queue(queue_idx);
+        if mmio.queue_pfn() != 0 {
+            return Err(VirtIOError::QueueSetupFailed);
+        }
 
         // Queue 최대 크기 확인
         let max_size = mmio.queue_max_size();
@@ -141,18 +151,10 @@ impl Virtqueue {
         let used_pages = (used_size + LEGACY_QUEUE_ALIGN - 1) / LEGACY_QUEUE_ALIGN;
         let total_size = (desc_avail_pages + used_pages) * LEGACY_QUEUE_ALIGN;
 
-        // 메모리 할당 (페이지 정렬)
-        let layout = core::alloc::Layout::from_size_align(total_size, LEGACY_QUEUE_ALIGN)
-            .map_err(|_| VirtIOError::QueueSetupFailed)?;
-        let memory = unsafe {
-            let ptr = alloc::alloc::alloc_zeroed(layout);
-            if ptr.is_null() {
-                return Err(VirtIOError::QueueSetupFailed);
-            }
-            Box::from_raw(core::slice::from_raw_parts_mut(ptr, total_size))
-        };
+        // 메모리 할당 (페이지 정렬, 0으로 채워짐)
+        let memory = DmaBuffer::new(total_size).ok_or(VirtIOError::QueueSetupFailed)?;
 
-        let base = memory.as_ptr() as usize;
+        let base = memory.vaddr();
         let desc_table = base as *mut VirtqDesc;
         let avail_ring = (base + desc_size) as *mut VirtqAvail;
         let used_ring = (base + used_offset) as *mut VirtqUsed;
@@ -172,7 +174,7 @@ impl Virtqueue {
         // Legacy MMIO 설정
         mmio.set_queue_size(queue_size as u32);
         mmio.set_queue_align(LEGACY_QUEUE_ALIGN as u32);
-        mmio.set_queue_pfn((base / LEGACY_QUEUE_ALIGN) as u32);
+        mmio.set_queue_pfn((memory.paddr() / LEGACY_QUEUE_ALIGN) as u32);
 
         Ok(Self {
             queue_idx,
@@ -189,8 +191,11 @@ impl Virtqueue {
 
     /// Modern (v2) Virtqueue 생성
     fn new_modern(mmio: &VirtIOMMIO, queue_idx: u32) -> VirtIOResult<Self> {
-        // Queue 선택
+        // Queue 선택 (이미 쓰고 있는 Queue면 실패)
         mmio.select_queue(queue_idx);
+        if mmio.queue_ready() {
+            return Err(VirtIOError::QueueSetupFailed);
+        }
 
         // Queue 최대 크기 확인
         let max_size = mmio.queue_max_size();
@@ -215,18 +220,10 @@ impl Virtqueue {
         let used_offset = ((avail_offset + avail_size) + 4095) & !4095; // 페이지 정렬
         let total_size = used_offset + used_size;
 
-        // 메모리 할당 (페이지 정렬)
-        let layout = core::alloc::Layout::from_size_align(total_size, 4096)
-            .map_err(|_| VirtIOError::QueueSetupFailed)?;
-        let memory = unsafe {
-            let ptr = alloc::alloc::alloc_zeroed(layout);
-            if ptr.is_null() {
-                return Err(VirtIOError::QueueSetupFailed);
-            }
-            Box::from_raw(core::slice::from_raw_parts_mut(ptr, total_size))
-        };
+        // 메모리 할당 (페이지 정렬, 0으로 채워짐)
+        let memory = DmaBuffer::new(total_size).ok_or(VirtIOError::QueueSetupFailed)?;
 
-        let base = memory.as_ptr() as usize;
+        let base = memory.vaddr();
         let desc_table = (base + desc_offset) as *mut VirtqDesc;
         let avail_ring = (base + avail_offset) as *mut VirtqAvail;
         let used_ring = (base + used_offset) as *mut VirtqUsed;
@@ -245,9 +242,10 @@ impl Virtqueue {
 
         // Modern MMIO에 주소 설정
         mmio.set_queue_size(queue_size as u32);
-        mmio.set_queue_desc((base + desc_offset) as u64);
-        mmio.set_queue_avail((base + avail_offset) as u64);
-        mmio.set_queue_used((base + used_offset) as u64);
+        let phys = memory.paddr();
+        mmio.set_queue_desc((phys + desc_offset) as u64);
+        mmio.set_queue_avail((phys + avail_offset) as u64);
+        mmio.set_queue_used((phys + used_offset) as u64);
         mmio.set_queue_ready(true);
 
         Ok(Self {
@@ -296,6 +294,7 @@ impl Virtqueue {
             desc.flags = if write { desc_flags::WRITE } else { 0 };
             desc.next = 0;
         }
+        dma::sync_for_device(buf.as_ptr() as usize, buf.len());
 
         // Available Ring에 추가
         unsafe {
@@ -332,6 +331,7 @@ impl Virtqueue {
 
         // 읽기 버퍼들 (디바이스가 읽음)
         for buf in read_bufs {
+            dma::sync_for_device(buf.as_ptr() as usize, buf.len());
             let desc_idx = self.free_head;
             unsafe {
                 let desc = &mut *self.desc_table.add(desc_idx as usize);
@@ -352,6 +352,8 @@ impl Virtqueue {
 
         // 쓰기 버퍼들 (디바이스가 씀)
         for (i, buf) in write_bufs.iter().enumerate() {
+            // 디바이스가 쓰는 동안 dirty 라인이 내려가 덮어쓰지 않도록
+            dma::sync_for_device(buf.as_ptr() as usize, buf.len());
             let desc_idx = self.free_head;
             unsafe {
                 let desc = &mut *self.desc_table.add(desc_idx as usize);
@@ -391,6 +393,19 @@ impl Virtqueue {
         Ok(head)
     }
 
+    /// 넣은 버퍼를 디바이스에 알림 (디바이스가 `NO_NOTIFY`로 알림을 끈 동안은 생략)
+    ///
+    /// MMIO는 PCI의 Queue별 notify 오프셋이 없고 모든 Queue가 `QueueNotify` 레지스터 하나에
+    /// Queue 번호를 씁니다.
+    pub fn kick(&self, mmio: &VirtIOMMIO) {
+        // avail.idx 갱신이 used.flags 읽기보다 먼저 보여야 함
+        fence(Ordering::SeqCst);
+        let flags = unsafe { read_volatile(&(*self.used_ring).flags) };
+        if flags & VIRTQ_USED_F_NO_NOTIFY == 0 {
+            mmio.notify_queue(self.queue_idx);
+        }
+    }
+
     /// 완료된 버퍼 확인
     pub fn poll_used(&mut self) -> Option<(u16, u32)> {
         fence(Ordering::SeqCst);
@@ -417,11 +432,14 @@ impl Virtqueue {
         }
     }
 
-    /// Descriptor 체인 해제
+    /// Descriptor 체인 해제 (디바이스가 쓴 버퍼는 CPU가 읽도록 캐시 정리)
     fn free_descriptor_chain(&mut self, mut head: u16) {
         loop {
             unsafe {
                 let desc = &mut *self.desc_table.add(head as usize);
+                if desc.flags & desc_flags::WRITE != 0 {
+                    dma::sync_for_cpu(desc.addr as usize, desc.len as usize);
+                }
                 let has_next = desc.flags & desc_flags::NEXT != 0;
                 let next = desc.next;
 
@@ -0,0 +1,310 @@
+//! 워치독 (멈춤 감지)
+//!
+//! `start()`로 켜면 `timeout` 안에 `pet()`이 오지 않을 때 CPU별 상태를 출력하고 패닉합니다.
+//! SMP 부팅이나 드라이버 초기화 중에 스케줄러가 멈추는 문제를 조용한 멈춤 대신 패닉
+//! 메시지와 백트레이스로 드러냅니다 (`panic=reboot`과 함께 쓰면 자동 재부팅).
+//!
+//! 누가 pet하는지(`Pinger`):
+//! - `Kernel`: 워크큐 지연 작업이 `timeout / 2`마다 pet. `kworker/u`가 돌지 못할 만큼
+//!   스케줄러가 멈추면 만료 (`watchdog=<초>`, `watchdog start`)
+//! - `Device`: `/dev/watchdog`에 쓰거나 `WDIOC_KEEPALIVE`를 부르는 쪽이 pet. 처음 쓰면
+//!   커널 pet이 멈추고 장치를 연 쪽이 책임짐 (Linux 워치독 코어와 같음)
+//!
+//! 백엔드는 소프트웨어 하나입니다. 만료 확인을 `time::timer` 주기 타이머(틱 인터럽트)에서
+//! 하므로 모든 CPU가 인터럽트를 끈 채 멈추면 잡지 못합니다. QEMU virt(aarch64, riscv64)에는
+//! 하드웨어 워치독이 없고 virtio에도 워치독 장치 종류가 없어 하드웨어 백엔드는 두지 않았습니다.
+
+use core::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+
+use crate::fs::{VfsError, VfsResult};
+use crate::kprintln;
+use crate::proc::percpu;
+use crate::proc::workqueue;
+use crate::sync::Mutex;
+use crate::time::{self, timer};
+
+/// 기본 제한 시간 (초)
+pub const DEFAULT_TIMEOUT_S: u32 = 30;
+/// 제한 시간 상한 (초)
+const MAX_TIMEOUT_S: u32 = 3600;
+/// 만료 확인 주기
+const CHECK_MS: u64 = 100;
+
+/// `/dev/watchdog` ioctl (Linux `<linux/watchdog.h>`)
+pub const WDIOC_SETOPTIONS: u32 = 0x8004_5704;
+pub const WDIOC_KEEPALIVE: u32 = 0x8004_5705;
+pub const WDIOC_SETTIMEOUT: u32 = 0xC004_5706;
+pub const WDIOC_GETTIMEOUT: u32 = 0x8004_5707;
+pub const WDIOC_GETTIMELEFT: u32 = 0x8004_570A;
+/// `WDIOC_SETOPTIONS` 인자
+pub const WDIOS_DISABLECARD: i32 = 0x0001;
+pub const WDIOS_ENABLECARD: i32 = 0x0002;
+
+/// pet하는 쪽
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pinger {
+    /// 워크큐 지연 작업
+    Kernel,
+    /// `/dev/watchdog` 사용자
+    Device,
+}
+
+impl Pinger {
+    fn name(self) -> &'static str {
+        match self {
+            Pinger::Kernel => "kernel",
+            Pinger::Device => "/dev/watchdog",
+        }
+    }
+}
+
+/// 워치독 상태 (`status()`)
+#[derive(Debug, Clone, Copy)]
+pub struct Status {
+    pub timeout_s: u32,
+    /// 만료까지 남은 시간 (밀리초)
+    pub left_ms: u64,
+    pub pinger: Pinger,
+    /// 켠 뒤 pet 횟수
+    pub pets: u64,
+}
+
+/// 제한 시간 (초)
+static TIMEOUT_S: AtomicU64 = AtomicU64::new(DEFAULT_TIMEOUT_S as u64);
+/// 만료 시각 (부팅 후 마이크로초, 0 = 꺼짐)
+static DEADLINE_US: AtomicU64 = AtomicU64::new(0);
+/// 마지막 pet 시각
+static LAST_PET_US: AtomicU64 = AtomicU64::new(0);
+static PETS: AtomicU64 = AtomicU64::new(0);
+/// `Pinger` (0 = Kernel, 1 = Device)
+static PINGER: AtomicU8 = AtomicU8::new(0);
+/// 켤 때마다 바뀜. 이전 세대의 커널 pet 작업은 아무것도 하지 않고 끝남
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+/// 만료 확인 타이머 / 커널 pet 작업 (0 = 없음)
+static CHECK_TIMER: AtomicUsize = AtomicUsize::new(0);
+static PING_WORK: AtomicUsize = AtomicUsize::new(0);
+/// 이미 만료를 보고했는지
+static EXPIRED: AtomicBool = AtomicBool::new(false);
+
+/// 켜기/끄기 직렬화
+static CONTROL: Mutex<()> = Mutex::new(());
+
+/// bootargs의 `watchdog=<초>` 처리 (워크큐와 타이머가 준비된 뒤, `proc::init()` 후 호출)
+pub fn init_from_cmdline() {
+    if let Some(timeout_s) = crate::boot::cmdline::watchdog_timeout() {
+        match start(timeout_s, Pinger::Kernel) {
+            Ok(()) => kprintln!("[watchdog] Started: timeout {}s, kernel pinger", timeout_s),
+            Err(e) => kprintln!("[watchdog] watchdog={}: {:?}", timeout_s, e),
+        }
+    }
+}
+
+/// 워치독 켜기 (이미 켜져 있으면 제한 시간과 pet하는 쪽만 바꾸고 pet)
+///
+/// 스레드 문맥에서 부릅니다. 제한 시간은 1..=3600초입니다.
+pub fn start(timeout_s: u32, pinger: Pinger) -> VfsResult<()> {
+    if !(1..=MAX_TIMEOUT_S).contains(&timeout_s) {
+        return Err(VfsError::InvalidArgument);
+    }
+    let _guard = CONTROL.lock();
+    TIMEOUT_S.store(timeout_s as u64, Ordering::Relaxed);
+    PINGER.store(pinger as u8, Ordering::Relaxed);
+    let generation = GENERATION.fetch_add(1, Ordering::AcqRel) + 1;
+
+    if DEADLINE_US.load(Ordering::Acquire) == 0 {
+        PETS.store(0, Ordering::Relaxed);
+        EXPIRED.store(false, Ordering::Relaxed);
+        pet();
+        CHECK_TIMER.store(timer::periodic("watchdog", CHECK_MS, check, 0), Ordering::Relaxed);
+    } else {
+        pet();
+    }
+
+    // 이전 세대의 pet 작업은 GENERATION이 달라 스스로 끝나므로 취소만 해 둠
+    cancel_ping();
+    if pinger == Pinger::Kernel {
+        ping(generation);
+    }
+    Ok(())
+}
+
+/// 만료 시각을 다시 `timeout` 뒤로 미룸 (어느 문맥에서나)
+pub fn pet() {
+    let now = time::now_us();
+    let deadline = now + TIMEOUT_S.load(Ordering::Relaxed) * 1_000_000;
+    // 꺼져 있으면 그대로 둠
+    if DEADLINE_US
+        .fetch_update(Ordering::AcqRel, Ordering::Acquire, |d| (d != 0).then_some(deadline))
+        .is_ok()
+    {
+        LAST_PET_US.store(now, Ordering::Relaxed);
+        PETS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// 워치독 끄기 (스레드 문맥)
+pub fn stop() {
+    let _guard = CONTROL.lock();
+    if DEADLINE_US.swap(0, Ordering::AcqRel) == 0 {
+        return;
+    }
+    GENERATION.fetch_add(1, Ordering::AcqRel);
+    let id = CHECK_TIMER.swap(0, Ordering::AcqRel);
+    if id != 0 {
+        timer::cancel(id);
+    }
+    cancel_ping();
+}
+
+/// 대기 중인 커널 pet 작업 취소 (0은 "실행 중 아님"과 같아 넘기지 않음)
+fn cancel_ping() {
+    let id = PING_WORK.swap(0, Ordering::AcqRel);
+    if id != 0 {
+        workqueue::cancel(id);
+    }
+}
+
+/// 현재 상태 (꺼져 있으면 None)
+pub fn status() -> Option<Status> {
+    let deadline = DEADLINE_US.load(Ordering::Acquire);
+    if deadline == 0 {
+        return None;
+    }
+    Some(Status {
+        timeout_s: TIMEOUT_S.load(Ordering::Relaxed) as u32,
+        left_ms: deadline.saturating_sub(time::now_us()) / 1000,
+        pinger: pinger(),
+        pets: PETS.load(Ordering::Relaxed),
+    })
+}
+
+fn pinger() -> Pinger {
+    match PINGER.load(Ordering::Relaxed) {
+        0 => Pinger::Kernel,
+        _ => Pinger::Device,
+    }
+}
+
+/// 커널 pet 작업: pet하고 `timeout / 2` 뒤 자신을 다시 넣음
+fn ping(generation: usize) {
+    if generation != GENERATION.load(Ordering::Acquire) {
+        return;
+    }
+    pet();
+    let delay_ms = (TIMEOUT_S.load(Ordering::Relaxed) * 1000 / 2).max(CHECK_MS);
+    PING_WORK.store(workqueue::delayed_work("watchdog-ping", delay_ms, ping, generation), Ordering::Release);
+}
+
+/// 만료 확인 (틱 인터럽트의 타이머 콜백)
+fn check(_: usize) {
+    let deadline = DEADLINE_US.load(Ordering::Acquire);
+    let now = time::now_us();
+    if deadline == 0 || now < deadline || EXPIRED.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    report(now);
+    panic!(
+        "watchdog: no ping for {} ms (timeout {}s, pinger {})",
+        (now - LAST_PET_US.load(Ordering::Relaxed)) / 1000,
+        TIMEOUT_S.load(Ordering::Relaxed),
+        pinger().name()
+    );
+}
+
+/// 만료 진단: CPU별 현재 스레드와 그 스레드가 CPU를 잡고 있던 시간
+///
+/// 멈춘 쪽이 로그 락을 잡고 있을 수 있으므로 콘솔로 바로 씁니다.
+fn report(now: u64) {
+    let pr = |args: core::fmt::Arguments| {
+        crate::console::kprint(args);
+        crate::console::puts("\n");
+    };
+    pr(format_args!("watchdog: timeout, CPU states:"));
+    pr(format_args!("  {:>3} {:>5} {:>10} {:>10} {:>10}", "CPU", "TID", "RUN(ms)", "TICKS", "SWITCHES"));
+    for cpu in 0..percpu::total_count() {
+        let pc = percpu::get(cpu);
+        if !pc.is_online() {
+            continue;
+        }
+        let tid = pc.current_tid.load(Ordering::Relaxed);
+        let run_ms = now.saturating_sub(pc.run_start_us.load(Ordering::Relaxed)) / 1000;
+        let ticks = pc.tick_count.load(Ordering::Relaxed);
+        let switches = pc.switch_count.load(Ordering::Relaxed);
+        if tid == percpu::NO_THREAD {
+            pr(format_args!("  {:>3} {:>5} {:>10} {:>10} {:>10}", cpu, "-", run_ms, ticks, switches));
+        } else {
+            pr(format_args!("  {:>3} {:>5} {:>10} {:>10} {:>10}", cpu, tid, run_ms, ticks, switches));
+        }
+    }
+}
+
+/// `/dev/watchdog` 쓰기: pet (꺼져 있거나 커널이 pet 중이면 장치 쪽으로 켬)
+///
+/// 데이터에 `V`가 있으면 끕니다. Linux는 `V`를 쓴 뒤 닫을 때 끄지만(magic close) VFS에
+/// 닫기 훅이 없어 쓸 때 바로 끕니다.
+pub fn device_write(buf: &[u8]) -> VfsResult<usize> {
+    if buf.contains(&b'V') {
+        stop();
+    } else {
+        claim()?;
+    }
+    Ok(buf.len())
+}
+
+/// 장치 쪽에서 pet (필요하면 장치 쪽으로 켬)
+fn claim() -> VfsResult<()> {
+    match status() {
+        Some(s) if s.pinger == Pinger::Device => {
+            pet();
+            Ok(())
+        }
+        _ => start(TIMEOUT_S.load(Ordering::Relaxed) as u32, Pinger::Device),
+    }
+}
+
+/// `/dev/watchdog` ioctl (`arg`는 호출자 주소 공간의 `int` 포인터)
+pub fn device_ioctl(cmd: u32, arg: usize) -> VfsResult<usize> {
+    if !matches!(
+        cmd,
+        WDIOC_SETOPTIONS | WDIOC_KEEPALIVE | WDIOC_SETTIMEOUT | WDIOC_GETTIMEOUT | WDIOC_GETTIMELEFT
+    ) {
+        return Err(VfsError::NotATty);
+    }
+    if arg == 0 && cmd != WDIOC_KEEPALIVE {
+        return Err(VfsError::InvalidArgument);
+    }
+    let int = arg as *mut i32;
+    match cmd {
+        WDIOC_KEEPALIVE => claim()?,
+        WDIOC_SETTIMEOUT => {
+            let timeout_s = unsafe { int.read_unaligned() };
+            start(u32::try_from(timeout_s).map_err(|_| VfsError::InvalidArgument)?, Pinger::Device)?;
+            unsafe { int.write_unaligned(timeout_s) };
+        }
+        WDIOC_GETTIMEOUT => unsafe { int.write_unaligned(TIMEOUT_S.load(Ordering::Relaxed) as i32) },
+        WDIOC_GETTIMELEFT => {
+            let left_s = status().map_or(0, |s| s.left_ms / 1000);
+            unsafe { int.write_unaligned(left_s as i32) };
+        }
+        _ => match unsafe { int.read_unaligned() } {
+            WDIOS_DISABLECARD => stop(),
+            WDIOS_ENABLECARD => claim()?,
+            _ => return Err(VfsError::InvalidArgument),
+        },
+    }
+    Ok(0)
+}
+
+/// `watchdog` 명령: 상태 출력
+pub fn dump() {
+    match status() {
+        Some(s) => kprintln!(
+            "watchdog: active, timeout {}s, {} ms left, pinger {}, {} pings",
+            s.timeout_s,
+            s.left_ms,
+            s.pinger.name(),
+            s.pets
+        ),
+        None => kprintln!("watchdog: inactive (timeout {}s)", TIMEOUT_S.load(Ordering::Relaxed)),
+    }
+}